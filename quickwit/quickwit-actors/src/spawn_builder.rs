@@ -21,6 +21,7 @@ use std::time::Duration;
 
 use anyhow::Context;
 use quickwit_common::metrics::IntCounter;
+use quickwit_common::runtimes::spawn_named_task_with_panic_handler_on;
 use sync_wrapper::SyncWrapper;
 use tokio::sync::watch;
 use tracing::{debug, error, info};
@@ -181,7 +182,9 @@ impl<A: Actor> SpawnBuilder<A> {
         let ctx_clone = ctx.clone();
         let loop_async_actor_future =
             async move { actor_loop(actor, inbox, no_advance_time_guard, ctx).await };
-        let join_handle = ActorJoinHandle::new(quickwit_common::spawn_named_task_on(
+        // Panics are logged with their backtrace before being reported as `Panicked` exit
+        // statuses, and the actor is not restarted: the supervisor, if any, takes care of it.
+        let join_handle = ActorJoinHandle::new(spawn_named_task_with_panic_handler_on(
             loop_async_actor_future,
             std::any::type_name::<A>(),
             &runtime_handle,
@@ -29,11 +29,13 @@ use aws_sdk_kinesis::operation::{
 use aws_sdk_s3::error::SdkError;
 use aws_sdk_s3::operation::abort_multipart_upload::AbortMultipartUploadError;
 use aws_sdk_s3::operation::complete_multipart_upload::CompleteMultipartUploadError;
+use aws_sdk_s3::operation::copy_object::CopyObjectError;
 use aws_sdk_s3::operation::create_multipart_upload::CreateMultipartUploadError;
 use aws_sdk_s3::operation::delete_object::DeleteObjectError;
 use aws_sdk_s3::operation::delete_objects::DeleteObjectsError;
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::operation::head_object::HeadObjectError;
+use aws_sdk_s3::operation::list_objects_v2::ListObjectsV2Error;
 use aws_sdk_s3::operation::put_object::PutObjectError;
 use aws_sdk_s3::operation::upload_part::UploadPartError;
 
@@ -102,12 +104,24 @@ impl AwsRetryable for PutObjectError {
     }
 }
 
+impl AwsRetryable for CopyObjectError {
+    fn is_retryable(&self) -> bool {
+        false
+    }
+}
+
 impl AwsRetryable for HeadObjectError {
     fn is_retryable(&self) -> bool {
         false
     }
 }
 
+impl AwsRetryable for ListObjectsV2Error {
+    fn is_retryable(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(feature = "kinesis")]
 impl AwsRetryable for GetRecordsError {
     fn is_retryable(&self) -> bool {
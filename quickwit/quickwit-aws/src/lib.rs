@@ -24,9 +24,9 @@ use aws_config::BehaviorVersion;
 pub use aws_smithy_async::rt::sleep::TokioSleep;
 use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
 use aws_types::region::Region;
-use hyper::client::HttpConnector;
 use hyper::Client as HyperClient;
 use hyper_rustls::HttpsConnectorBuilder;
+use quickwit_common::net::{TcpConfig, TcpConfigConnector};
 use tokio::sync::OnceCell;
 
 pub mod error;
@@ -40,9 +40,7 @@ pub async fn get_aws_config() -> &'static aws_config::SdkConfig {
 
     SDK_CONFIG
         .get_or_init(|| async {
-            let mut http_connector = HttpConnector::new();
-            http_connector.enforce_http(false); // Enforced by `HttpsConnector`.
-            http_connector.set_nodelay(true);
+            let http_connector = TcpConfigConnector::new(TcpConfig::default());
 
             let https_connector = HttpsConnectorBuilder::new()
                 .with_native_roots()
@@ -0,0 +1,1749 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use bytes::Bytes;
+use bytesize::ByteSize;
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use itertools::Itertools;
+use quickwit_actors::Universe;
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::rand::append_random_suffix;
+use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::uri::Uri;
+use quickwit_config::service::QuickwitService;
+use quickwit_config::{
+    load_index_config_from_user_config, ConfigFormat, IndexConfig, IndexerConfig, NodeConfig,
+    SourceConfig, SourceInputFormat, SourceParams, VecSourceParams,
+};
+use quickwit_index_management::IndexService;
+use quickwit_indexing::actors::IndexingService;
+use quickwit_indexing::models::{
+    DetachIndexingPipeline, DetachMergePipeline, IndexingStatistics, SpawnPipeline,
+};
+use quickwit_indexing::INDEXER_METRICS;
+use quickwit_ingest::IngesterPool;
+use quickwit_metastore::{
+    IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitState,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::SearchRequest;
+use quickwit_proto::types::{IndexId, PipelineUid};
+use quickwit_search::SingleNodeSearcher;
+use quickwit_serve::{search_request_from_api_request, SearchRequestQueryString};
+use quickwit_storage::{load_file, PutPayload, Storage, StorageResolver, STORAGE_METRICS};
+use serde_json::json;
+use tabled::Tabled;
+use time::OffsetDateTime;
+use tokio::task::JoinSet;
+use tracing::debug;
+
+use crate::checklist::{GREEN_COLOR, RED_COLOR};
+use crate::stats::quantile;
+use crate::tool::create_empty_cluster;
+use crate::{config_cli_arg, get_resolvers, load_node_config, make_table, start_actor_runtimes};
+
+/// Maximum number of queries displayed in the "slowest queries" section of the report.
+const MAX_SLOWEST_QUERIES: usize = 5;
+
+pub fn build_bench_command() -> Command {
+    Command::new("bench")
+        .about("Runs in-process benchmarks. Requires a node config.")
+        .arg(config_cli_arg())
+        .subcommand(
+            Command::new("search")
+                .display_order(1)
+                .about("Benchmarks search queries against an index.")
+                .long_about("Replays concurrently the search requests of an NDJSON query file against an index for a given duration and reports latency and throughput statistics. The search engine is called directly on the configured storage without using a server.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"query-file" <QUERY_FILE> "Location of the NDJSON file containing the search requests to replay, one JSON object per line, following the format of the search REST API.")
+                        .display_order(2)
+                        .required(true),
+                    arg!(--"duration-secs" <DURATION_SECS> "Duration of the benchmark in seconds.")
+                        .default_value("30")
+                        .required(false),
+                    arg!(--concurrency <CONCURRENCY> "Number of search requests executed concurrently.")
+                        .default_value("8")
+                        .required(false),
+                    arg!(--"output-path" <OUTPUT_PATH> "Location of the file the benchmark report is exported to as JSON.")
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("ingest")
+                .display_order(2)
+                .about("Benchmarks the indexing pipeline.")
+                .long_about("Indexes the NDJSON documents of an input file with an in-process indexing pipeline and reports indexing throughput, split commit latency, and the time spent in each stage of the pipeline. The benchmark runs either against an existing index or against a throwaway index created from an index config and deleted afterwards.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index.")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required_unless_present("index-config")
+                        .conflicts_with("index-config"),
+                    arg!(--"index-config" <INDEX_CONFIG> "Location of the config file of the throwaway index created for the benchmark.")
+                        .display_order(2)
+                        .required(false),
+                    arg!(--"input-path" <INPUT_PATH> "Location of the NDJSON input file.")
+                        .visible_alias("input-file")
+                        .display_order(3)
+                        .required(true),
+                    arg!(--"num-documents" <NUM_DOCUMENTS> "Number of documents to index. The documents of the input file are repeated if needed. Defaults to the number of documents of the input file.")
+                        .required(false),
+                    arg!(--"batch-size" <BATCH_SIZE> "Number of documents per batch emitted by the source.")
+                        .default_value("1000")
+                        .required(false),
+                    arg!(--"warmup-secs" <WARMUP_SECS> "Duration in seconds at the beginning of the benchmark excluded from the measurements.")
+                        .default_value("10")
+                        .required(false),
+                    arg!(--"output-path" <OUTPUT_PATH> "Location of the file the benchmark report is exported to as JSON.")
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("storage")
+                .display_order(3)
+                .about("Benchmarks the read and write throughput of a storage.")
+                .long_about("Writes objects of random bytes to a storage concurrently for a given duration, reads them back for the same duration, and reports throughput and latency statistics along with the number of requests issued to the object storage. The objects are deleted at the end of the benchmark.")
+                .args(&[
+                    arg!(--"storage-uri" <STORAGE_URI> "URI of the directory the benchmark objects are written to, for instance `s3://my-bucket/bench/`.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"duration-secs" <DURATION_SECS> "Duration in seconds of each phase of the benchmark (write, then read).")
+                        .default_value("60")
+                        .required(false),
+                    arg!(--concurrency <CONCURRENCY> "Number of requests executed concurrently.")
+                        .default_value("16")
+                        .required(false),
+                    arg!(--"object-size-mb" <OBJECT_SIZE_MB> "Size of the benchmark objects in MB.")
+                        .default_value("10")
+                        .required(false),
+                    arg!(--"output-path" <OUTPUT_PATH> "Location of the file the benchmark report is exported to as JSON.")
+                        .required(false),
+                ])
+            )
+        .arg_required_else_help(true)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SearchBenchArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub query_file_uri: Uri,
+    pub duration: Duration,
+    pub concurrency: usize,
+    pub output_path_opt: Option<PathBuf>,
+}
+
+/// The index the ingest benchmark indexes documents into.
+#[derive(Debug, Eq, PartialEq)]
+pub enum IngestBenchIndex {
+    /// An existing index.
+    Existing(IndexId),
+    /// A throwaway index created from an index config and deleted at the end of the benchmark.
+    Throwaway(Uri),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct IngestBenchArgs {
+    pub config_uri: Uri,
+    pub index: IngestBenchIndex,
+    pub input_path: PathBuf,
+    pub num_documents_opt: Option<usize>,
+    pub batch_size: usize,
+    pub warmup: Duration,
+    pub output_path_opt: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct StorageBenchArgs {
+    pub config_uri: Uri,
+    pub storage_uri: Uri,
+    pub duration: Duration,
+    pub concurrency: usize,
+    pub object_size: ByteSize,
+    pub output_path_opt: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum BenchCliCommand {
+    Search(SearchBenchArgs),
+    Ingest(IngestBenchArgs),
+    Storage(StorageBenchArgs),
+}
+
+impl BenchCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let (subcommand, submatches) = matches
+            .remove_subcommand()
+            .context("failed to parse bench subcommand")?;
+        match subcommand.as_str() {
+            "search" => Self::parse_search_bench_args(submatches),
+            "ingest" => Self::parse_ingest_bench_args(submatches),
+            "storage" => Self::parse_storage_bench_args(submatches),
+            _ => bail!("unknown bench subcommand `{subcommand}`"),
+        }
+    }
+
+    fn parse_search_bench_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let query_file_uri = matches
+            .remove_one::<String>("query-file")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`query-file` should be a required arg.")?;
+        let duration_secs: u64 = matches
+            .remove_one::<String>("duration-secs")
+            .expect("`duration-secs` should have a default value.")
+            .parse()
+            .context("failed to parse `duration-secs`")?;
+        let concurrency: usize = matches
+            .remove_one::<String>("concurrency")
+            .expect("`concurrency` should have a default value.")
+            .parse()
+            .context("failed to parse `concurrency`")?;
+        if concurrency == 0 {
+            bail!("`concurrency` must be strictly positive");
+        }
+        let output_path_opt = matches
+            .remove_one::<String>("output-path")
+            .map(PathBuf::from);
+        Ok(Self::Search(SearchBenchArgs {
+            config_uri,
+            index_id,
+            query_file_uri,
+            duration: Duration::from_secs(duration_secs),
+            concurrency,
+            output_path_opt,
+        }))
+    }
+
+    fn parse_ingest_bench_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index = if let Some(index_config_uri_str) = matches.remove_one::<String>("index-config")
+        {
+            IngestBenchIndex::Throwaway(Uri::from_str(&index_config_uri_str)?)
+        } else {
+            let index_id = matches
+                .remove_one::<String>("index")
+                .expect("`index` should be a required arg when `index-config` is missing.");
+            IngestBenchIndex::Existing(index_id)
+        };
+        let input_path = matches
+            .remove_one::<String>("input-path")
+            .map(PathBuf::from)
+            .expect("`input-path` should be a required arg.");
+        let num_documents_opt = matches
+            .remove_one::<String>("num-documents")
+            .map(|num_documents_str| num_documents_str.parse::<usize>())
+            .transpose()
+            .context("failed to parse `num-documents`")?;
+        if num_documents_opt == Some(0) {
+            bail!("`num-documents` must be strictly positive");
+        }
+        let batch_size: usize = matches
+            .remove_one::<String>("batch-size")
+            .expect("`batch-size` should have a default value.")
+            .parse()
+            .context("failed to parse `batch-size`")?;
+        if batch_size == 0 {
+            bail!("`batch-size` must be strictly positive");
+        }
+        let warmup_secs: u64 = matches
+            .remove_one::<String>("warmup-secs")
+            .expect("`warmup-secs` should have a default value.")
+            .parse()
+            .context("failed to parse `warmup-secs`")?;
+        let output_path_opt = matches
+            .remove_one::<String>("output-path")
+            .map(PathBuf::from);
+        Ok(Self::Ingest(IngestBenchArgs {
+            config_uri,
+            index,
+            input_path,
+            num_documents_opt,
+            batch_size,
+            warmup: Duration::from_secs(warmup_secs),
+            output_path_opt,
+        }))
+    }
+
+    fn parse_storage_bench_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let storage_uri = matches
+            .remove_one::<String>("storage-uri")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`storage-uri` should be a required arg.")?;
+        let duration_secs: u64 = matches
+            .remove_one::<String>("duration-secs")
+            .expect("`duration-secs` should have a default value.")
+            .parse()
+            .context("failed to parse `duration-secs`")?;
+        let concurrency: usize = matches
+            .remove_one::<String>("concurrency")
+            .expect("`concurrency` should have a default value.")
+            .parse()
+            .context("failed to parse `concurrency`")?;
+        if concurrency == 0 {
+            bail!("`concurrency` must be strictly positive");
+        }
+        let object_size_mb: u64 = matches
+            .remove_one::<String>("object-size-mb")
+            .expect("`object-size-mb` should have a default value.")
+            .parse()
+            .context("failed to parse `object-size-mb`")?;
+        if object_size_mb == 0 {
+            bail!("`object-size-mb` must be strictly positive");
+        }
+        let output_path_opt = matches
+            .remove_one::<String>("output-path")
+            .map(PathBuf::from);
+        Ok(Self::Storage(StorageBenchArgs {
+            config_uri,
+            storage_uri,
+            duration: Duration::from_secs(duration_secs),
+            concurrency,
+            object_size: ByteSize::mb(object_size_mb),
+            output_path_opt,
+        }))
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            Self::Search(args) => search_bench_cli(args).await,
+            Self::Ingest(args) => ingest_bench_cli(args).await,
+            Self::Storage(args) => storage_bench_cli(args).await,
+        }
+    }
+}
+
+/// A search request replayed by the search benchmark.
+#[derive(Debug)]
+struct BenchQuery {
+    /// The user query, used to identify the query in the report.
+    query: String,
+    search_request: SearchRequest,
+}
+
+/// Parses the NDJSON query file of the search benchmark.
+fn parse_bench_queries(
+    index_id: &str,
+    query_file_content: &[u8],
+) -> anyhow::Result<Vec<BenchQuery>> {
+    let query_file_str =
+        std::str::from_utf8(query_file_content).context("query file is not valid UTF-8")?;
+    let mut bench_queries = Vec::new();
+
+    for (line_idx, line) in query_file_str.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let search_request_query_string: SearchRequestQueryString = serde_json::from_str(line)
+            .with_context(|| format!("failed to parse search request at line {}", line_idx + 1))?;
+        let query = search_request_query_string.query.clone();
+        let search_request = search_request_from_api_request(
+            vec![index_id.to_string()],
+            search_request_query_string,
+        )
+        .with_context(|| format!("failed to build search request at line {}", line_idx + 1))?;
+        bench_queries.push(BenchQuery {
+            query,
+            search_request,
+        });
+    }
+    if bench_queries.is_empty() {
+        bail!("query file does not contain any search request");
+    }
+    Ok(bench_queries)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QuerySample {
+    query_idx: usize,
+    latency_micros: u64,
+    is_error: bool,
+}
+
+/// Replays `bench_queries` with `concurrency` concurrent tasks until `duration` has elapsed.
+async fn run_search_bench(
+    searcher: SingleNodeSearcher,
+    bench_queries: Vec<BenchQuery>,
+    duration: Duration,
+    concurrency: usize,
+) -> anyhow::Result<SearchBenchReport> {
+    let bench_queries = Arc::new(bench_queries);
+    let next_query_idx = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+    let deadline = start + duration;
+    let mut join_set = JoinSet::new();
+
+    for _ in 0..concurrency {
+        let searcher = searcher.clone();
+        let bench_queries = bench_queries.clone();
+        let next_query_idx = next_query_idx.clone();
+
+        join_set.spawn(async move {
+            let mut samples = Vec::new();
+            let mut first_error_opt: Option<String> = None;
+
+            while Instant::now() < deadline {
+                let query_idx =
+                    next_query_idx.fetch_add(1, Ordering::Relaxed) % bench_queries.len();
+                let search_request = bench_queries[query_idx].search_request.clone();
+                let query_start = Instant::now();
+                let search_result = searcher.search(search_request).await;
+                let latency_micros = query_start.elapsed().as_micros() as u64;
+
+                if let Err(search_error) = &search_result {
+                    first_error_opt.get_or_insert_with(|| search_error.to_string());
+                }
+                samples.push(QuerySample {
+                    query_idx,
+                    latency_micros,
+                    is_error: search_result.is_err(),
+                });
+            }
+            (samples, first_error_opt)
+        });
+    }
+    let mut samples = Vec::new();
+    let mut first_error_opt = None;
+
+    while let Some(join_result) = join_set.join_next().await {
+        let (task_samples, task_first_error_opt) =
+            join_result.context("search benchmark task panicked")?;
+        samples.extend(task_samples);
+
+        if first_error_opt.is_none() {
+            first_error_opt = task_first_error_opt;
+        }
+    }
+    let elapsed = start.elapsed();
+    let mut report = SearchBenchReport::new(&bench_queries, &samples, elapsed);
+    report.first_error_opt = first_error_opt;
+    Ok(report)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LatencyStats {
+    mean_ms: f32,
+    p50_ms: f32,
+    p95_ms: f32,
+    p99_ms: f32,
+    p999_ms: f32,
+}
+
+impl LatencyStats {
+    fn from_sorted_latencies_micros(sorted_latencies_micros: &[u64]) -> Self {
+        if sorted_latencies_micros.is_empty() {
+            return Self::default();
+        }
+        let sum_micros: u64 = sorted_latencies_micros.iter().sum();
+        let mean_micros = sum_micros as f32 / sorted_latencies_micros.len() as f32;
+        Self {
+            mean_ms: mean_micros / 1_000f32,
+            p50_ms: quantile(sorted_latencies_micros, 0.5) / 1_000f32,
+            p95_ms: quantile(sorted_latencies_micros, 0.95) / 1_000f32,
+            p99_ms: quantile(sorted_latencies_micros, 0.99) / 1_000f32,
+            p999_ms: quantile(sorted_latencies_micros, 0.999) / 1_000f32,
+        }
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        json!({
+            "mean_ms": self.mean_ms,
+            "p50_ms": self.p50_ms,
+            "p95_ms": self.p95_ms,
+            "p99_ms": self.p99_ms,
+            "p999_ms": self.p999_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QueryReport {
+    query: String,
+    num_queries: usize,
+    num_errors: usize,
+    latency_stats: LatencyStats,
+}
+
+#[derive(Debug)]
+struct SearchBenchReport {
+    elapsed: Duration,
+    num_queries: usize,
+    num_errors: usize,
+    throughput_qps: f64,
+    latency_stats: LatencyStats,
+    /// Per query reports, sorted by decreasing mean latency.
+    query_reports: Vec<QueryReport>,
+    first_error_opt: Option<String>,
+}
+
+impl SearchBenchReport {
+    fn new(bench_queries: &[BenchQuery], samples: &[QuerySample], elapsed: Duration) -> Self {
+        let num_queries = samples.len();
+        let num_errors = samples.iter().filter(|sample| sample.is_error).count();
+        let throughput_qps = num_queries as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+
+        let sorted_latencies_micros: Vec<u64> = samples
+            .iter()
+            .map(|sample| sample.latency_micros)
+            .sorted()
+            .collect();
+        let latency_stats = LatencyStats::from_sorted_latencies_micros(&sorted_latencies_micros);
+
+        let query_reports: Vec<QueryReport> = samples
+            .iter()
+            .into_group_map_by(|sample| sample.query_idx)
+            .into_iter()
+            .map(|(query_idx, query_samples)| {
+                let sorted_latencies_micros: Vec<u64> = query_samples
+                    .iter()
+                    .map(|sample| sample.latency_micros)
+                    .sorted()
+                    .collect();
+                QueryReport {
+                    query: bench_queries[query_idx].query.clone(),
+                    num_queries: query_samples.len(),
+                    num_errors: query_samples
+                        .iter()
+                        .filter(|sample| sample.is_error)
+                        .count(),
+                    latency_stats: LatencyStats::from_sorted_latencies_micros(
+                        &sorted_latencies_micros,
+                    ),
+                }
+            })
+            .sorted_by(|left, right| {
+                right
+                    .latency_stats
+                    .mean_ms
+                    .total_cmp(&left.latency_stats.mean_ms)
+            })
+            .collect();
+
+        Self {
+            elapsed,
+            num_queries,
+            num_errors,
+            throughput_qps,
+            latency_stats,
+            query_reports,
+            first_error_opt: None,
+        }
+    }
+
+    fn display_as_table(&self) -> String {
+        let stats_rows = [
+            ("Duration", format!("{:.1}s", self.elapsed.as_secs_f32())),
+            ("Queries", self.num_queries.to_string()),
+            ("Errors", self.num_errors.to_string()),
+            (
+                "Throughput",
+                format!("{:.2} queries/s", self.throughput_qps),
+            ),
+            ("Mean latency", format_ms(self.latency_stats.mean_ms)),
+            ("p50 latency", format_ms(self.latency_stats.p50_ms)),
+            ("p95 latency", format_ms(self.latency_stats.p95_ms)),
+            ("p99 latency", format_ms(self.latency_stats.p99_ms)),
+            ("p99.9 latency", format_ms(self.latency_stats.p999_ms)),
+        ]
+        .into_iter()
+        .map(|(stat, value)| BenchStatRow { stat, value });
+        let stats_table = make_table("Search benchmark", stats_rows, false);
+
+        let slowest_query_rows =
+            self.query_reports
+                .iter()
+                .take(MAX_SLOWEST_QUERIES)
+                .map(|query_report| SlowestQueryRow {
+                    query: query_report.query.clone(),
+                    num_queries: query_report.num_queries,
+                    num_errors: query_report.num_errors,
+                    mean_latency: format_ms(query_report.latency_stats.mean_ms),
+                    p99_latency: format_ms(query_report.latency_stats.p99_ms),
+                });
+        let slowest_queries_table = make_table("Slowest queries", slowest_query_rows, false);
+        format!("\n{stats_table}\n\n{slowest_queries_table}\n")
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let query_reports_json: Vec<serde_json::Value> = self
+            .query_reports
+            .iter()
+            .map(|query_report| {
+                json!({
+                    "query": query_report.query,
+                    "num_queries": query_report.num_queries,
+                    "num_errors": query_report.num_errors,
+                    "latency": query_report.latency_stats.to_json(),
+                })
+            })
+            .collect();
+        json!({
+            "duration_secs": self.elapsed.as_secs_f64(),
+            "num_queries": self.num_queries,
+            "num_errors": self.num_errors,
+            "throughput_qps": self.throughput_qps,
+            "latency": self.latency_stats.to_json(),
+            "queries": query_reports_json,
+        })
+    }
+}
+
+fn format_ms(latency_ms: f32) -> String {
+    format!("{latency_ms:.2}ms")
+}
+
+#[derive(Tabled)]
+struct BenchStatRow {
+    #[tabled(rename = "Statistic")]
+    stat: &'static str,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+#[derive(Tabled)]
+struct SlowestQueryRow {
+    #[tabled(rename = "Query")]
+    query: String,
+    #[tabled(rename = "Executions")]
+    num_queries: usize,
+    #[tabled(rename = "Errors")]
+    num_errors: usize,
+    #[tabled(rename = "Mean latency")]
+    mean_latency: String,
+    #[tabled(rename = "p99 latency")]
+    p99_latency: String,
+}
+
+/// Source ID of the indexing pipeline spawned by the ingest benchmark.
+const INGEST_BENCH_SOURCE_ID: &str = "_bench-source";
+
+/// Stages of the indexing pipeline reported by the ingest benchmark. Each stage is identified by
+/// the name of the actor whose backpressure is measured.
+const INDEXING_PIPELINE_STAGES: [(&str, &str); 5] = [
+    ("doc_processor", "Parsing"),
+    ("indexer", "Tokenization & indexing"),
+    ("uploader", "Storage write"),
+    ("sequencer", "Metastore write (sequencing)"),
+    ("publisher", "Metastore write (publishing)"),
+];
+
+/// Splits the NDJSON input file of the ingest benchmark into documents, and repeats them until
+/// `num_documents_opt` documents are produced.
+fn parse_bench_docs(
+    input_content: Bytes,
+    num_documents_opt: Option<usize>,
+) -> anyhow::Result<Vec<Bytes>> {
+    let mut docs = Vec::new();
+    let mut line_start = 0;
+
+    for line in input_content.split(|byte| *byte == b'\n') {
+        let line_end = line_start + line.len();
+
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            docs.push(input_content.slice(line_start..line_end));
+        }
+        line_start = line_end + 1;
+    }
+    if docs.is_empty() {
+        bail!("input file does not contain any document");
+    }
+    if let Some(num_documents) = num_documents_opt {
+        docs = docs.into_iter().cycle().take(num_documents).collect();
+    }
+    Ok(docs)
+}
+
+/// Cumulative counters of the ingest benchmark at a given point in time.
+#[derive(Debug, Clone, Copy, Default)]
+struct IngestBenchSnapshot {
+    elapsed: Duration,
+    num_docs: u64,
+    num_bytes: u64,
+    total_size_splits: u64,
+    backpressure_micros: [u64; INDEXING_PIPELINE_STAGES.len()],
+}
+
+impl IngestBenchSnapshot {
+    fn take(start: Instant, statistics: &IndexingStatistics) -> Self {
+        let backpressure_micros = INDEXING_PIPELINE_STAGES.map(|(actor_name, _)| {
+            INDEXER_METRICS
+                .backpressure_micros
+                .with_label_values([actor_name])
+                .get() as u64
+        });
+        Self {
+            elapsed: start.elapsed(),
+            num_docs: statistics.num_docs,
+            num_bytes: statistics.total_bytes_processed,
+            total_size_splits: statistics.total_size_splits,
+            backpressure_micros,
+        }
+    }
+}
+
+/// Loads the index config of the throwaway index of the ingest benchmark. The index ID is suffixed
+/// with a random string to avoid conflicting with existing indexes.
+async fn load_throwaway_index_config(
+    storage_resolver: &StorageResolver,
+    index_config_uri: &Uri,
+    default_index_root_uri: &Uri,
+) -> anyhow::Result<IndexConfig> {
+    let index_config_content = load_file(storage_resolver, index_config_uri).await?;
+    let config_format = ConfigFormat::sniff_from_uri(index_config_uri)?;
+    let mut index_config = load_index_config_from_user_config(
+        config_format,
+        index_config_content.as_slice(),
+        default_index_root_uri,
+    )?;
+    index_config.index_id = append_random_suffix(&format!("bench-{}", index_config.index_id));
+    index_config.index_uri = default_index_root_uri.join(&index_config.index_id)?;
+    Ok(index_config)
+}
+
+/// Indexes `docs` into `index_id` with an in-process indexing pipeline and measures the pipeline
+/// once `warmup` has elapsed.
+async fn run_ingest_bench(
+    config: &NodeConfig,
+    mut metastore: MetastoreServiceClient,
+    storage_resolver: StorageResolver,
+    index_id: &str,
+    docs: Vec<Bytes>,
+    batch_size: usize,
+    warmup: Duration,
+) -> anyhow::Result<IngestBenchReport> {
+    let index_uid = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    // A new partition is used for every run so that the checkpoint of previous runs on an
+    // existing index does not skip the documents.
+    let source_params = SourceParams::Vec(VecSourceParams {
+        docs,
+        batch_num_docs: batch_size,
+        partition: append_random_suffix("bench"),
+    });
+    let source_config = SourceConfig {
+        source_id: INGEST_BENCH_SOURCE_ID.to_string(),
+        num_pipelines: NonZeroUsize::new(1).expect("1 is always non-zero."),
+        enabled: true,
+        source_params,
+        transform_config: None,
+        input_format: SourceInputFormat::Json,
+    };
+    let cluster = create_empty_cluster(config).await?;
+    let runtimes_config = RuntimesConfig::default();
+    start_actor_runtimes(
+        runtimes_config,
+        &HashSet::from_iter([QuickwitService::Indexer]),
+    )?;
+    let universe = Universe::new();
+    let merge_scheduler_service_mailbox = universe.get_or_spawn_one();
+    let indexing_server = IndexingService::new(
+        config.node_id.clone(),
+        config.data_dir_path.clone(),
+        IndexerConfig::default(),
+        runtimes_config.num_threads_indexing,
+        cluster,
+        metastore.clone(),
+        None,
+        merge_scheduler_service_mailbox,
+        IngesterPool::default(),
+        storage_resolver,
+        EventBroker::default(),
+    )
+    .await?;
+    let (indexing_server_mailbox, indexing_server_handle) =
+        universe.spawn_builder().spawn(indexing_server);
+    let pipeline_id = indexing_server_mailbox
+        .ask_for_res(SpawnPipeline {
+            index_id: index_id.to_string(),
+            source_config,
+            pipeline_uid: PipelineUid::new(),
+        })
+        .await?;
+    let merge_pipeline_handle = indexing_server_mailbox
+        .ask_for_res(DetachMergePipeline {
+            pipeline_id: pipeline_id.merge_pipeline_id(),
+        })
+        .await?;
+    let indexing_pipeline_handle = indexing_server_mailbox
+        .ask_for_res(DetachIndexingPipeline { pipeline_id })
+        .await?;
+
+    let start = Instant::now();
+    let initial_snapshot = IngestBenchSnapshot::take(start, &IndexingStatistics::default());
+    let initial_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    let mut warmup_snapshot_opt: Option<(IngestBenchSnapshot, i64)> = None;
+    let mut check_interval = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        check_interval.tick().await;
+        indexing_pipeline_handle.refresh_observe();
+
+        if warmup_snapshot_opt.is_none() && start.elapsed() >= warmup {
+            let observation = indexing_pipeline_handle.last_observation();
+            let warmup_snapshot = IngestBenchSnapshot::take(start, &observation);
+            let warmup_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+            warmup_snapshot_opt = Some((warmup_snapshot, warmup_timestamp));
+        }
+        if indexing_pipeline_handle.state().is_exit() {
+            break;
+        }
+    }
+    let (pipeline_exit_status, pipeline_statistics) = indexing_pipeline_handle.join().await;
+    let final_snapshot = IngestBenchSnapshot::take(start, &pipeline_statistics);
+    merge_pipeline_handle.quit().await;
+    universe
+        .send_exit_with_success(&indexing_server_mailbox)
+        .await?;
+    indexing_server_handle.join().await;
+    universe.quit().await;
+
+    if !pipeline_exit_status.is_success() {
+        bail!(pipeline_exit_status);
+    }
+    let (measurement_snapshot, measurement_timestamp) = warmup_snapshot_opt.unwrap_or_else(|| {
+        println!(
+            "The indexing pipeline completed before the end of the warmup: the measurements \
+             include the warmup."
+        );
+        (initial_snapshot, initial_timestamp)
+    });
+    // The commit latency of a split is measured from its creation to its publication. Splits
+    // produced by merges are ignored.
+    let list_splits_query = ListSplitsQuery::for_index(index_uid)
+        .with_split_states([SplitState::Published, SplitState::MarkedForDeletion])
+        .with_create_timestamp_gte(measurement_timestamp);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&list_splits_query)?;
+    let splits = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits()
+        .await?;
+    let split_commit_latencies_micros: Vec<u64> = splits
+        .iter()
+        .filter(|split| {
+            split.split_metadata.source_id == INGEST_BENCH_SOURCE_ID
+                && split.split_metadata.num_merge_ops == 0
+        })
+        .filter_map(|split| {
+            let publish_timestamp = split.publish_timestamp?;
+            let commit_latency_secs =
+                (publish_timestamp - split.split_metadata.create_timestamp).max(0) as u64;
+            Some(commit_latency_secs * 1_000_000)
+        })
+        .sorted()
+        .collect();
+    let report = IngestBenchReport::new(
+        &measurement_snapshot,
+        &final_snapshot,
+        &split_commit_latencies_micros,
+    );
+    Ok(report)
+}
+
+#[derive(Debug)]
+struct StageReport {
+    stage: &'static str,
+    actor_name: &'static str,
+    backpressure: Duration,
+}
+
+#[derive(Debug)]
+struct IngestBenchReport {
+    /// Duration of the measurement window, i.e. the duration of the benchmark minus the warmup.
+    elapsed: Duration,
+    num_docs: u64,
+    num_bytes: u64,
+    throughput_docs_per_sec: f64,
+    throughput_mb_per_sec: f64,
+    storage_write_mb_per_sec: f64,
+    num_splits: usize,
+    split_commit_latency_stats: LatencyStats,
+    /// Time spent by the upstream actors waiting for each stage of the pipeline.
+    stage_reports: Vec<StageReport>,
+}
+
+impl IngestBenchReport {
+    fn new(
+        start_snapshot: &IngestBenchSnapshot,
+        end_snapshot: &IngestBenchSnapshot,
+        sorted_split_commit_latencies_micros: &[u64],
+    ) -> Self {
+        let elapsed = end_snapshot.elapsed.saturating_sub(start_snapshot.elapsed);
+        let elapsed_secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let num_docs = end_snapshot
+            .num_docs
+            .saturating_sub(start_snapshot.num_docs);
+        let num_bytes = end_snapshot
+            .num_bytes
+            .saturating_sub(start_snapshot.num_bytes);
+        let num_split_bytes = end_snapshot
+            .total_size_splits
+            .saturating_sub(start_snapshot.total_size_splits);
+
+        let stage_reports = INDEXING_PIPELINE_STAGES
+            .iter()
+            .zip(
+                start_snapshot
+                    .backpressure_micros
+                    .iter()
+                    .zip(end_snapshot.backpressure_micros.iter()),
+            )
+            .map(
+                |(&(actor_name, stage), (&start_micros, &end_micros))| StageReport {
+                    stage,
+                    actor_name,
+                    backpressure: Duration::from_micros(end_micros.saturating_sub(start_micros)),
+                },
+            )
+            .collect();
+
+        Self {
+            elapsed,
+            num_docs,
+            num_bytes,
+            throughput_docs_per_sec: num_docs as f64 / elapsed_secs,
+            throughput_mb_per_sec: num_bytes as f64 / 1_000_000f64 / elapsed_secs,
+            storage_write_mb_per_sec: num_split_bytes as f64 / 1_000_000f64 / elapsed_secs,
+            num_splits: sorted_split_commit_latencies_micros.len(),
+            split_commit_latency_stats: LatencyStats::from_sorted_latencies_micros(
+                sorted_split_commit_latencies_micros,
+            ),
+            stage_reports,
+        }
+    }
+
+    fn display_as_table(&self) -> String {
+        let stats_rows = [
+            ("Duration", format!("{:.1}s", self.elapsed.as_secs_f32())),
+            ("Documents", self.num_docs.to_string()),
+            ("Bytes", self.num_bytes.to_string()),
+            (
+                "Throughput",
+                format!("{:.2} docs/s", self.throughput_docs_per_sec),
+            ),
+            (
+                "Throughput (MB)",
+                format!("{:.2} MB/s", self.throughput_mb_per_sec),
+            ),
+            (
+                "Storage write throughput",
+                format!("{:.2} MB/s", self.storage_write_mb_per_sec),
+            ),
+            ("Splits", self.num_splits.to_string()),
+            (
+                "Mean split commit latency",
+                format_ms(self.split_commit_latency_stats.mean_ms),
+            ),
+            (
+                "p50 split commit latency",
+                format_ms(self.split_commit_latency_stats.p50_ms),
+            ),
+            (
+                "p99 split commit latency",
+                format_ms(self.split_commit_latency_stats.p99_ms),
+            ),
+        ]
+        .into_iter()
+        .map(|(stat, value)| BenchStatRow { stat, value });
+        let stats_table = make_table("Ingest benchmark", stats_rows, false);
+
+        let total_backpressure: Duration = self
+            .stage_reports
+            .iter()
+            .map(|stage_report| stage_report.backpressure)
+            .sum();
+        let stage_rows = self.stage_reports.iter().map(|stage_report| {
+            let share = stage_report.backpressure.as_secs_f64()
+                / total_backpressure.as_secs_f64().max(f64::EPSILON)
+                * 100f64;
+            StageRow {
+                stage: stage_report.stage,
+                actor_name: stage_report.actor_name,
+                backpressure: format!("{:.2}s", stage_report.backpressure.as_secs_f32()),
+                share: format!("{share:.1}%"),
+            }
+        });
+        let stages_table = make_table("Time spent waiting on each stage", stage_rows, false);
+        format!("\n{stats_table}\n\n{stages_table}\n")
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let stages_json: Vec<serde_json::Value> = self
+            .stage_reports
+            .iter()
+            .map(|stage_report| {
+                json!({
+                    "stage": stage_report.stage,
+                    "actor_name": stage_report.actor_name,
+                    "backpressure_secs": stage_report.backpressure.as_secs_f64(),
+                })
+            })
+            .collect();
+        json!({
+            "duration_secs": self.elapsed.as_secs_f64(),
+            "num_docs": self.num_docs,
+            "num_bytes": self.num_bytes,
+            "throughput_docs_per_sec": self.throughput_docs_per_sec,
+            "throughput_mb_per_sec": self.throughput_mb_per_sec,
+            "storage_write_mb_per_sec": self.storage_write_mb_per_sec,
+            "num_splits": self.num_splits,
+            "split_commit_latency": self.split_commit_latency_stats.to_json(),
+            "stages": stages_json,
+        })
+    }
+}
+
+#[derive(Tabled)]
+struct StageRow {
+    #[tabled(rename = "Stage")]
+    stage: &'static str,
+    #[tabled(rename = "Actor")]
+    actor_name: &'static str,
+    #[tabled(rename = "Backpressure")]
+    backpressure: String,
+    #[tabled(rename = "Share")]
+    share: String,
+}
+
+fn export_report(output_path: &Path, report_json: &serde_json::Value) -> anyhow::Result<()> {
+    let report_json_bytes = serde_json::to_vec_pretty(report_json)?;
+    std::fs::write(output_path, report_json_bytes).with_context(|| {
+        format!(
+            "failed to write benchmark report to `{}`",
+            output_path.display()
+        )
+    })?;
+    println!("Benchmark report exported to `{}`.", output_path.display());
+    Ok(())
+}
+
+pub async fn search_bench_cli(args: SearchBenchArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "bench-search");
+    println!(
+        "❯ Benchmarking search queries directly on the index storage (without calling REST API)..."
+    );
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    let query_file_content = load_file(&storage_resolver, &args.query_file_uri).await?;
+    let bench_queries = parse_bench_queries(&args.index_id, query_file_content.as_slice())?;
+    let searcher = SingleNodeSearcher::new(metastore, storage_resolver);
+    let report = run_search_bench(searcher, bench_queries, args.duration, args.concurrency).await?;
+    println!("{}", report.display_as_table());
+
+    if let Some(output_path) = &args.output_path_opt {
+        export_report(output_path, &report.to_json())?;
+    }
+    if report.num_queries > 0 && report.num_errors == report.num_queries {
+        let first_error = report.first_error_opt.unwrap_or_default();
+        bail!("all search requests failed: {first_error}");
+    }
+    if let Some(first_error) = &report.first_error_opt {
+        println!(
+            "{} {} search request(s) failed, first error: {first_error}",
+            "✘".color(RED_COLOR),
+            report.num_errors
+        );
+    } else {
+        println!("{} Search benchmark completed.", "✔".color(GREEN_COLOR));
+    }
+    Ok(())
+}
+
+pub async fn ingest_bench_cli(args: IngestBenchArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "bench-ingest");
+    println!("❯ Benchmarking indexing pipeline locally...");
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    let input_content = tokio::fs::read(&args.input_path)
+        .await
+        .with_context(|| format!("failed to read input file `{}`", args.input_path.display()))?;
+    let docs = parse_bench_docs(Bytes::from(input_content), args.num_documents_opt)?;
+
+    let mut index_service = IndexService::new(metastore.clone(), storage_resolver.clone());
+    let index_id = match &args.index {
+        IngestBenchIndex::Existing(index_id) => index_id.clone(),
+        IngestBenchIndex::Throwaway(index_config_uri) => {
+            let index_config = load_throwaway_index_config(
+                &storage_resolver,
+                index_config_uri,
+                &config.default_index_root_uri,
+            )
+            .await?;
+            let index_id = index_config.index_id.clone();
+            index_service.create_index(index_config, false).await?;
+            println!("Created throwaway index `{index_id}`.");
+            index_id
+        }
+    };
+    let bench_result = run_ingest_bench(
+        &config,
+        metastore,
+        storage_resolver,
+        &index_id,
+        docs,
+        args.batch_size,
+        args.warmup,
+    )
+    .await;
+
+    if let IngestBenchIndex::Throwaway(_) = &args.index {
+        index_service.delete_index(&index_id, false).await?;
+        println!("Deleted throwaway index `{index_id}`.");
+    }
+    let report = bench_result?;
+    println!("{}", report.display_as_table());
+
+    if let Some(output_path) = &args.output_path_opt {
+        export_report(output_path, &report.to_json())?;
+    }
+    println!("{} Ingest benchmark completed.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
+/// A storage request issued by the storage benchmark.
+#[derive(Debug, Clone, Copy)]
+struct StorageOpSample {
+    latency_micros: u64,
+    num_bytes: u64,
+    is_error: bool,
+}
+
+/// Generates `num_bytes` pseudo-random bytes so that the benchmark objects do not compress.
+fn random_payload(num_bytes: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut payload = Vec::with_capacity(num_bytes + 8);
+
+    while payload.len() < num_bytes {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        payload.extend_from_slice(&state.to_le_bytes());
+    }
+    payload.truncate(num_bytes);
+    payload
+}
+
+/// Writes objects of `payload` with `concurrency` concurrent tasks until `duration` has elapsed.
+/// Returns the samples and the paths of the objects successfully written.
+async fn run_storage_write_phase(
+    storage: Arc<dyn Storage>,
+    object_dir: &Path,
+    payload: Vec<u8>,
+    duration: Duration,
+    concurrency: usize,
+) -> anyhow::Result<(Vec<StorageOpSample>, Vec<PathBuf>, Option<String>)> {
+    let payload: Box<dyn PutPayload> = Box::new(payload);
+    let next_object_idx = Arc::new(AtomicUsize::new(0));
+    let deadline = Instant::now() + duration;
+    let mut join_set = JoinSet::new();
+
+    for _ in 0..concurrency {
+        let storage = storage.clone();
+        let object_dir = object_dir.to_path_buf();
+        let payload = payload.clone();
+        let next_object_idx = next_object_idx.clone();
+
+        join_set.spawn(async move {
+            let mut samples = Vec::new();
+            let mut object_paths = Vec::new();
+            let mut first_error_opt: Option<String> = None;
+
+            while Instant::now() < deadline {
+                let object_idx = next_object_idx.fetch_add(1, Ordering::Relaxed);
+                let object_path = object_dir.join(format!("object-{object_idx}"));
+                let num_bytes = payload.len();
+                let payload = payload.clone();
+                let put_start = Instant::now();
+                let put_result = storage.put(&object_path, payload).await;
+                let latency_micros = put_start.elapsed().as_micros() as u64;
+
+                match &put_result {
+                    Ok(()) => object_paths.push(object_path),
+                    Err(put_error) => {
+                        first_error_opt.get_or_insert_with(|| put_error.to_string());
+                    }
+                }
+                samples.push(StorageOpSample {
+                    latency_micros,
+                    num_bytes,
+                    is_error: put_result.is_err(),
+                });
+            }
+            (samples, object_paths, first_error_opt)
+        });
+    }
+    let mut samples = Vec::new();
+    let mut object_paths = Vec::new();
+    let mut first_error_opt = None;
+
+    while let Some(join_result) = join_set.join_next().await {
+        let (task_samples, task_object_paths, task_first_error_opt) =
+            join_result.context("storage benchmark task panicked")?;
+        samples.extend(task_samples);
+        object_paths.extend(task_object_paths);
+
+        if first_error_opt.is_none() {
+            first_error_opt = task_first_error_opt;
+        }
+    }
+    Ok((samples, object_paths, first_error_opt))
+}
+
+/// Reads the objects at `object_paths` in a round-robin fashion with `concurrency` concurrent
+/// tasks until `duration` has elapsed.
+async fn run_storage_read_phase(
+    storage: Arc<dyn Storage>,
+    object_paths: Arc<Vec<PathBuf>>,
+    duration: Duration,
+    concurrency: usize,
+) -> anyhow::Result<(Vec<StorageOpSample>, Option<String>)> {
+    let next_object_idx = Arc::new(AtomicUsize::new(0));
+    let deadline = Instant::now() + duration;
+    let mut join_set = JoinSet::new();
+
+    for _ in 0..concurrency {
+        let storage = storage.clone();
+        let object_paths = object_paths.clone();
+        let next_object_idx = next_object_idx.clone();
+
+        join_set.spawn(async move {
+            let mut samples = Vec::new();
+            let mut first_error_opt: Option<String> = None;
+
+            while Instant::now() < deadline {
+                let object_idx =
+                    next_object_idx.fetch_add(1, Ordering::Relaxed) % object_paths.len();
+                let get_start = Instant::now();
+                let get_result = storage.get_all(&object_paths[object_idx]).await;
+                let latency_micros = get_start.elapsed().as_micros() as u64;
+
+                let num_bytes = match &get_result {
+                    Ok(object_bytes) => object_bytes.len(),
+                    Err(get_error) => {
+                        first_error_opt.get_or_insert_with(|| get_error.to_string());
+                        0
+                    }
+                };
+                samples.push(StorageOpSample {
+                    latency_micros,
+                    num_bytes: num_bytes as u64,
+                    is_error: get_result.is_err(),
+                });
+            }
+            (samples, first_error_opt)
+        });
+    }
+    let mut samples = Vec::new();
+    let mut first_error_opt = None;
+
+    while let Some(join_result) = join_set.join_next().await {
+        let (task_samples, task_first_error_opt) =
+            join_result.context("storage benchmark task panicked")?;
+        samples.extend(task_samples);
+
+        if first_error_opt.is_none() {
+            first_error_opt = task_first_error_opt;
+        }
+    }
+    Ok((samples, first_error_opt))
+}
+
+#[derive(Debug)]
+struct StoragePhaseReport {
+    elapsed: Duration,
+    num_requests: usize,
+    num_errors: usize,
+    num_bytes: u64,
+    throughput_mb_per_sec: f64,
+    latency_stats: LatencyStats,
+}
+
+impl StoragePhaseReport {
+    fn new(samples: &[StorageOpSample], elapsed: Duration) -> Self {
+        let num_bytes: u64 = samples
+            .iter()
+            .filter(|sample| !sample.is_error)
+            .map(|sample| sample.num_bytes)
+            .sum();
+        let sorted_latencies_micros: Vec<u64> = samples
+            .iter()
+            .map(|sample| sample.latency_micros)
+            .sorted()
+            .collect();
+        Self {
+            elapsed,
+            num_requests: samples.len(),
+            num_errors: samples.iter().filter(|sample| sample.is_error).count(),
+            num_bytes,
+            throughput_mb_per_sec: num_bytes as f64
+                / 1_000_000f64
+                / elapsed.as_secs_f64().max(f64::EPSILON),
+            latency_stats: LatencyStats::from_sorted_latencies_micros(&sorted_latencies_micros),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "duration_secs": self.elapsed.as_secs_f64(),
+            "num_requests": self.num_requests,
+            "num_errors": self.num_errors,
+            "num_bytes": self.num_bytes,
+            "throughput_mb_per_sec": self.throughput_mb_per_sec,
+            "latency": self.latency_stats.to_json(),
+        })
+    }
+}
+
+/// Number of requests issued to the object storage during the benchmark, measured with the
+/// storage metrics. They are zero for storages that are not object storages.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+struct ObjectStorageRequests {
+    num_gets: u64,
+    /// Number of uploaded parts: a multipart upload counts as many requests as parts.
+    num_put_parts: u64,
+}
+
+impl ObjectStorageRequests {
+    fn snapshot() -> Self {
+        Self {
+            num_gets: STORAGE_METRICS.object_storage_get_total.get(),
+            num_put_parts: STORAGE_METRICS.object_storage_put_parts.get(),
+        }
+    }
+
+    fn since(self, start_snapshot: Self) -> Self {
+        Self {
+            num_gets: self.num_gets.saturating_sub(start_snapshot.num_gets),
+            num_put_parts: self
+                .num_put_parts
+                .saturating_sub(start_snapshot.num_put_parts),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StorageBenchReport {
+    object_size: ByteSize,
+    concurrency: usize,
+    write_report: StoragePhaseReport,
+    read_report: StoragePhaseReport,
+    num_deleted_objects: usize,
+    object_storage_requests: ObjectStorageRequests,
+    first_error_opt: Option<String>,
+}
+
+impl StorageBenchReport {
+    fn display_as_table(&self) -> String {
+        let mut stats_rows = vec![
+            ("Object size", self.object_size.to_string()),
+            ("Concurrency", self.concurrency.to_string()),
+        ];
+        for (phase, phase_report) in [("Write", &self.write_report), ("Read", &self.read_report)] {
+            stats_rows.extend([
+                (
+                    phase,
+                    format!(
+                        "{} requests ({} errors) in {:.1}s",
+                        phase_report.num_requests,
+                        phase_report.num_errors,
+                        phase_report.elapsed.as_secs_f32()
+                    ),
+                ),
+                (
+                    "  Throughput",
+                    format!("{:.2} MB/s", phase_report.throughput_mb_per_sec),
+                ),
+                (
+                    "  p50 latency",
+                    format_ms(phase_report.latency_stats.p50_ms),
+                ),
+                (
+                    "  p99 latency",
+                    format_ms(phase_report.latency_stats.p99_ms),
+                ),
+                (
+                    "  p99.9 latency",
+                    format_ms(phase_report.latency_stats.p999_ms),
+                ),
+            ]);
+        }
+        stats_rows.extend([
+            ("Deleted objects", self.num_deleted_objects.to_string()),
+            (
+                "Object storage GET requests",
+                self.object_storage_requests.num_gets.to_string(),
+            ),
+            (
+                "Object storage PUT requests",
+                self.object_storage_requests.num_put_parts.to_string(),
+            ),
+        ]);
+        let stats_rows = stats_rows
+            .into_iter()
+            .map(|(stat, value)| BenchStatRow { stat, value });
+        let stats_table = make_table("Storage benchmark", stats_rows, false);
+        format!("\n{stats_table}\n")
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "object_size_bytes": self.object_size.as_u64(),
+            "concurrency": self.concurrency,
+            "write": self.write_report.to_json(),
+            "read": self.read_report.to_json(),
+            "num_deleted_objects": self.num_deleted_objects,
+            "object_storage_requests": {
+                "get": self.object_storage_requests.num_gets,
+                "put": self.object_storage_requests.num_put_parts,
+            },
+        })
+    }
+}
+
+async fn run_storage_bench(
+    storage: Arc<dyn Storage>,
+    duration: Duration,
+    concurrency: usize,
+    object_size: ByteSize,
+) -> anyhow::Result<StorageBenchReport> {
+    let object_dir = PathBuf::from(append_random_suffix("quickwit-storage-bench"));
+    let payload = random_payload(object_size.as_u64() as usize);
+    let start_snapshot = ObjectStorageRequests::snapshot();
+
+    println!("Writing objects for {}s...", duration.as_secs());
+    let write_start = Instant::now();
+    let (write_samples, object_paths, write_first_error_opt) =
+        run_storage_write_phase(storage.clone(), &object_dir, payload, duration, concurrency)
+            .await?;
+    let write_report = StoragePhaseReport::new(&write_samples, write_start.elapsed());
+    let object_paths = Arc::new(object_paths);
+
+    let (read_report, read_first_error_opt) = if object_paths.is_empty() {
+        (StoragePhaseReport::new(&[], Duration::ZERO), None)
+    } else {
+        println!("Reading objects for {}s...", duration.as_secs());
+        let read_start = Instant::now();
+        let (read_samples, read_first_error_opt) =
+            run_storage_read_phase(storage.clone(), object_paths.clone(), duration, concurrency)
+                .await?;
+        let read_report = StoragePhaseReport::new(&read_samples, read_start.elapsed());
+        (read_report, read_first_error_opt)
+    };
+    let object_storage_requests = ObjectStorageRequests::snapshot().since(start_snapshot);
+
+    println!("Deleting {} objects...", object_paths.len());
+    let object_path_refs: Vec<&Path> = object_paths.iter().map(PathBuf::as_path).collect();
+    storage
+        .bulk_delete(&object_path_refs)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to delete the benchmark objects under `{}/{}`",
+                storage.uri(),
+                object_dir.display()
+            )
+        })?;
+
+    Ok(StorageBenchReport {
+        object_size,
+        concurrency,
+        write_report,
+        read_report,
+        num_deleted_objects: object_paths.len(),
+        object_storage_requests,
+        first_error_opt: write_first_error_opt.or(read_first_error_opt),
+    })
+}
+
+pub async fn storage_bench_cli(args: StorageBenchArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "bench-storage");
+    println!("❯ Benchmarking storage `{}`...", args.storage_uri);
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, _metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let storage = storage_resolver.resolve(&args.storage_uri).await?;
+    let report =
+        run_storage_bench(storage, args.duration, args.concurrency, args.object_size).await?;
+    println!("{}", report.display_as_table());
+
+    if let Some(output_path) = &args.output_path_opt {
+        export_report(output_path, &report.to_json())?;
+    }
+    if report.write_report.num_requests > 0
+        && report.write_report.num_errors == report.write_report.num_requests
+    {
+        let first_error = report.first_error_opt.unwrap_or_default();
+        bail!("all write requests failed: {first_error}");
+    }
+    if let Some(first_error) = &report.first_error_opt {
+        println!(
+            "{} {} storage request(s) failed, first error: {first_error}",
+            "✘".color(RED_COLOR),
+            report.write_report.num_errors + report.read_report.num_errors
+        );
+    } else {
+        println!("{} Storage benchmark completed.", "✔".color(GREEN_COLOR));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bench_queries() {
+        let query_file_content = br#"{"query": "severity_text:ERROR", "max_hits": 10}
+
+{"query": "body:quickwit", "start_timestamp": 1000}
+"#;
+        let bench_queries = parse_bench_queries("my-index", query_file_content).unwrap();
+        assert_eq!(bench_queries.len(), 2);
+        assert_eq!(bench_queries[0].query, "severity_text:ERROR");
+        assert_eq!(
+            bench_queries[0].search_request.index_id_patterns,
+            ["my-index"]
+        );
+        assert_eq!(bench_queries[0].search_request.max_hits, 10);
+        assert_eq!(bench_queries[1].query, "body:quickwit");
+        assert_eq!(bench_queries[1].search_request.start_timestamp, Some(1000));
+
+        let error = parse_bench_queries("my-index", b"").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("does not contain any search request"));
+
+        let error = parse_bench_queries("my-index", b"{\"query\": \"foo\"}\n{\"foo\"").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_search_bench_report() {
+        let bench_queries =
+            parse_bench_queries("my-index", b"{\"query\": \"fast\"}\n{\"query\": \"slow\"}")
+                .unwrap();
+        let samples = [
+            QuerySample {
+                query_idx: 0,
+                latency_micros: 1_000,
+                is_error: false,
+            },
+            QuerySample {
+                query_idx: 1,
+                latency_micros: 10_000,
+                is_error: false,
+            },
+            QuerySample {
+                query_idx: 0,
+                latency_micros: 3_000,
+                is_error: true,
+            },
+            QuerySample {
+                query_idx: 1,
+                latency_micros: 20_000,
+                is_error: false,
+            },
+        ];
+        let report = SearchBenchReport::new(&bench_queries, &samples, Duration::from_secs(2));
+        assert_eq!(report.num_queries, 4);
+        assert_eq!(report.num_errors, 1);
+        assert_eq!(report.throughput_qps, 2.0);
+        assert_eq!(report.latency_stats.mean_ms, 8.5);
+        assert_eq!(report.latency_stats.p50_ms, 6.5);
+
+        assert_eq!(report.query_reports.len(), 2);
+        assert_eq!(report.query_reports[0].query, "slow");
+        assert_eq!(report.query_reports[0].num_queries, 2);
+        assert_eq!(report.query_reports[0].num_errors, 0);
+        assert_eq!(report.query_reports[0].latency_stats.mean_ms, 15.0);
+        assert_eq!(report.query_reports[1].query, "fast");
+        assert_eq!(report.query_reports[1].num_errors, 1);
+        assert_eq!(report.query_reports[1].latency_stats.mean_ms, 2.0);
+
+        let report_json = report.to_json();
+        assert_eq!(report_json["num_queries"], 4);
+        assert_eq!(report_json["queries"][0]["query"], "slow");
+    }
+    #[test]
+    fn test_parse_bench_docs() {
+        let input_content = Bytes::from_static(b"{\"body\": \"foo\"}\n\n{\"body\": \"bar\"}\n");
+        let docs = parse_bench_docs(input_content.clone(), None).unwrap();
+        assert_eq!(
+            docs,
+            [&b"{\"body\": \"foo\"}"[..], &b"{\"body\": \"bar\"}"[..]]
+        );
+        let docs = parse_bench_docs(input_content.clone(), Some(3)).unwrap();
+        assert_eq!(docs.len(), 3);
+        assert_eq!(docs[2], &b"{\"body\": \"foo\"}"[..]);
+
+        let docs = parse_bench_docs(input_content, Some(1)).unwrap();
+        assert_eq!(docs.len(), 1);
+
+        let error = parse_bench_docs(Bytes::from_static(b"\n \n"), None).unwrap_err();
+        assert!(error.to_string().contains("does not contain any document"));
+    }
+
+    #[test]
+    fn test_ingest_bench_report() {
+        let start_snapshot = IngestBenchSnapshot {
+            elapsed: Duration::from_secs(10),
+            num_docs: 1_000,
+            num_bytes: 1_000_000,
+            total_size_splits: 0,
+            backpressure_micros: [0, 1_000_000, 0, 0, 0],
+        };
+        let end_snapshot = IngestBenchSnapshot {
+            elapsed: Duration::from_secs(20),
+            num_docs: 11_000,
+            num_bytes: 21_000_000,
+            total_size_splits: 5_000_000,
+            backpressure_micros: [0, 4_000_000, 1_000_000, 0, 0],
+        };
+        let report =
+            IngestBenchReport::new(&start_snapshot, &end_snapshot, &[1_000_000, 3_000_000]);
+        assert_eq!(report.elapsed, Duration::from_secs(10));
+        assert_eq!(report.num_docs, 10_000);
+        assert_eq!(report.throughput_docs_per_sec, 1_000.0);
+        assert_eq!(report.throughput_mb_per_sec, 2.0);
+        assert_eq!(report.storage_write_mb_per_sec, 0.5);
+        assert_eq!(report.num_splits, 2);
+        assert_eq!(report.split_commit_latency_stats.mean_ms, 2_000.0);
+
+        assert_eq!(report.stage_reports.len(), INDEXING_PIPELINE_STAGES.len());
+        assert_eq!(report.stage_reports[1].actor_name, "indexer");
+        assert_eq!(report.stage_reports[1].backpressure, Duration::from_secs(3));
+        assert_eq!(report.stage_reports[2].backpressure, Duration::from_secs(1));
+
+        let report_json = report.to_json();
+        assert_eq!(report_json["num_docs"], 10_000);
+        assert_eq!(report_json["stages"][1]["backpressure_secs"], 3.0);
+    }
+
+    #[test]
+    fn test_random_payload() {
+        assert!(random_payload(0).is_empty());
+
+        let payload = random_payload(1_001);
+        assert_eq!(payload.len(), 1_001);
+        assert_ne!(payload[..8], payload[8..16]);
+    }
+
+    #[test]
+    fn test_storage_phase_report() {
+        let samples = [
+            StorageOpSample {
+                latency_micros: 1_000,
+                num_bytes: 1_000_000,
+                is_error: false,
+            },
+            StorageOpSample {
+                latency_micros: 3_000,
+                num_bytes: 1_000_000,
+                is_error: false,
+            },
+            StorageOpSample {
+                latency_micros: 2_000,
+                num_bytes: 1_000_000,
+                is_error: true,
+            },
+        ];
+        let report = StoragePhaseReport::new(&samples, Duration::from_secs(2));
+        assert_eq!(report.num_requests, 3);
+        assert_eq!(report.num_errors, 1);
+        assert_eq!(report.num_bytes, 2_000_000);
+        assert_eq!(report.throughput_mb_per_sec, 1.0);
+        assert_eq!(report.latency_stats.p50_ms, 2.0);
+
+        let report_json = report.to_json();
+        assert_eq!(report_json["num_requests"], 3);
+        assert_eq!(report_json["latency"]["p50_ms"], 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_run_storage_bench() {
+        let ram_storage = Arc::new(quickwit_storage::RamStorage::default());
+        let report = run_storage_bench(
+            ram_storage.clone(),
+            Duration::from_millis(50),
+            2,
+            ByteSize::b(100),
+        )
+        .await
+        .unwrap();
+
+        assert!(report.write_report.num_requests > 0);
+        assert_eq!(report.write_report.num_errors, 0);
+        assert_eq!(
+            report.write_report.num_bytes,
+            report.write_report.num_requests as u64 * 100
+        );
+        assert!(report.read_report.num_requests > 0);
+        assert_eq!(report.read_report.num_errors, 0);
+        assert_eq!(report.num_deleted_objects, report.write_report.num_requests);
+        assert!(report.first_error_opt.is_none());
+
+        // The benchmark objects are cleaned up.
+        assert!(ram_storage.list_files().await.is_empty());
+
+        let report_json = report.to_json();
+        assert_eq!(report_json["object_size_bytes"], 100);
+        assert_eq!(report_json["concurrency"], 2);
+    }
+}
@@ -22,10 +22,16 @@ use clap::{arg, Arg, ArgAction, ArgMatches, Command};
 use quickwit_serve::EnvFilterReloadFn;
 use tracing::Level;
 
+use crate::bench::{build_bench_command, BenchCliCommand};
 use crate::index::{build_index_command, IndexCliCommand};
+use crate::node::{build_node_command, NodeCliCommand};
+use crate::search::{build_search_command, SearchCliCommand};
+use crate::search_stream::SearchStreamCliCommand;
 use crate::service::{build_run_command, RunCliCommand};
 use crate::source::{build_source_command, SourceCliCommand};
 use crate::split::{build_split_command, SplitCliCommand};
+use crate::storage::{build_storage_command, StorageCliCommand};
+use crate::tail::{build_tail_command, TailCliCommand};
 use crate::tool::{build_tool_command, ToolCliCommand};
 
 pub fn build_cli() -> Command {
@@ -52,6 +58,11 @@ pub fn build_cli() -> Command {
         .subcommand(build_source_command().display_order(3))
         .subcommand(build_split_command().display_order(4))
         .subcommand(build_tool_command().display_order(5))
+        .subcommand(build_bench_command().display_order(6))
+        .subcommand(build_search_command().display_order(7))
+        .subcommand(build_tail_command().display_order(8))
+        .subcommand(build_storage_command().display_order(9))
+        .subcommand(build_node_command().display_order(10))
         .arg_required_else_help(true)
         .disable_help_subcommand(true)
         .subcommand_required(true)
@@ -59,20 +70,32 @@ pub fn build_cli() -> Command {
 
 #[derive(Debug, PartialEq)]
 pub enum CliCommand {
+    Bench(BenchCliCommand),
     Run(RunCliCommand),
     Index(IndexCliCommand),
+    Node(NodeCliCommand),
+    Search(SearchCliCommand),
+    SearchStream(SearchStreamCliCommand),
     Split(SplitCliCommand),
+    Storage(StorageCliCommand),
     Source(SourceCliCommand),
+    Tail(TailCliCommand),
     Tool(ToolCliCommand),
 }
 
 impl CliCommand {
     pub fn default_log_level(&self) -> Level {
         match self {
+            CliCommand::Bench(_) => Level::ERROR,
             CliCommand::Run(_) => Level::INFO,
             CliCommand::Index(subcommand) => subcommand.default_log_level(),
+            CliCommand::Node(_) => Level::ERROR,
+            CliCommand::Search(_) => Level::ERROR,
+            CliCommand::SearchStream(_) => Level::ERROR,
             CliCommand::Source(_) => Level::ERROR,
             CliCommand::Split(_) => Level::ERROR,
+            CliCommand::Storage(_) => Level::ERROR,
+            CliCommand::Tail(_) => Level::ERROR,
             CliCommand::Tool(_) => Level::ERROR,
         }
     }
@@ -82,21 +105,42 @@ impl CliCommand {
             .remove_subcommand()
             .context("failed to parse command")?;
         match subcommand.as_str() {
+            "bench" => BenchCliCommand::parse_cli_args(submatches).map(CliCommand::Bench),
             "index" => IndexCliCommand::parse_cli_args(submatches).map(CliCommand::Index),
+            "node" => NodeCliCommand::parse_cli_args(submatches).map(CliCommand::Node),
             "run" => RunCliCommand::parse_cli_args(submatches).map(CliCommand::Run),
+            "search" => Self::parse_search_args(submatches),
             "source" => SourceCliCommand::parse_cli_args(submatches).map(CliCommand::Source),
             "split" => SplitCliCommand::parse_cli_args(submatches).map(CliCommand::Split),
+            "storage" => StorageCliCommand::parse_cli_args(submatches).map(CliCommand::Storage),
+            "tail" => TailCliCommand::parse_cli_args(submatches).map(CliCommand::Tail),
             "tool" => ToolCliCommand::parse_cli_args(submatches).map(CliCommand::Tool),
             _ => bail!("unknown command `{subcommand}`"),
         }
     }
 
+    fn parse_search_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        match matches.remove_subcommand() {
+            Some((subcommand, submatches)) if subcommand == "stream" => {
+                SearchStreamCliCommand::parse_cli_args(submatches).map(CliCommand::SearchStream)
+            }
+            Some((subcommand, _)) => bail!("unknown search subcommand `{subcommand}`"),
+            None => SearchCliCommand::parse_cli_args(matches).map(CliCommand::Search),
+        }
+    }
+
     pub async fn execute(self, env_filter_reload_fn: EnvFilterReloadFn) -> anyhow::Result<()> {
         match self {
+            CliCommand::Bench(subcommand) => subcommand.execute().await,
             CliCommand::Index(subcommand) => subcommand.execute().await,
+            CliCommand::Node(subcommand) => subcommand.execute().await,
             CliCommand::Run(subcommand) => subcommand.execute(env_filter_reload_fn).await,
+            CliCommand::Search(subcommand) => subcommand.execute().await,
+            CliCommand::SearchStream(subcommand) => subcommand.execute().await,
             CliCommand::Source(subcommand) => subcommand.execute().await,
             CliCommand::Split(subcommand) => subcommand.execute().await,
+            CliCommand::Storage(subcommand) => subcommand.execute().await,
+            CliCommand::Tail(subcommand) => subcommand.execute().await,
             CliCommand::Tool(subcommand) => subcommand.execute().await,
         }
     }
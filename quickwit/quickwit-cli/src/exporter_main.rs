@@ -0,0 +1,53 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use opentelemetry::global;
+use quickwit_cli::exporter::{build_exporter_command, run_exporter, ExporterArgs};
+use quickwit_cli::logger::setup_logging_and_tracing;
+use quickwit_serve::BuildInfo;
+use tracing::{error, Level};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    #[cfg(feature = "openssl-support")]
+    openssl_probe::init_ssl_cert_env_vars();
+
+    let version_text = BuildInfo::get_version_text();
+    let matches = build_exporter_command().version(version_text).get_matches();
+
+    let args = match ExporterArgs::parse_cli_args(matches) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("failed to parse command line arguments: {error:?}");
+            std::process::exit(1);
+        }
+    };
+    let build_info = BuildInfo::get();
+    setup_logging_and_tracing(Level::INFO, true, build_info)?;
+
+    let return_code: i32 = if let Err(error) = run_exporter(args).await {
+        error!(error=?error, "exporter failed");
+        eprintln!("exporter failed: {error:?}");
+        1
+    } else {
+        0
+    };
+
+    global::shutdown_tracer_provider();
+    std::process::exit(return_code)
+}
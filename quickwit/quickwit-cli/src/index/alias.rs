@@ -0,0 +1,488 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::{bail, Context};
+use clap::{arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use itertools::Itertools;
+use quickwit_proto::metastore::IndexAlias;
+use quickwit_proto::types::IndexId;
+use quickwit_serve::IndexAliasUpdates;
+use tabled::{Table, Tabled};
+use tracing::debug;
+
+use crate::checklist::GREEN_COLOR;
+use crate::{make_table, ClientArgs};
+
+pub fn build_index_alias_command() -> Command {
+    Command::new("alias")
+        .about("Manages index aliases: creates, lists, resolves, updates, and switches aliases.")
+        .long_about("Manages index aliases. An index alias is a logical name resolving to one or several indexes. Switching an alias from one index to another is atomic, which allows rotating the indexes behind an alias without downtime.")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("create")
+                .display_order(1)
+                .about("Creates an index alias.")
+                .args(&[
+                    arg!(--alias <ALIAS> "ID of the index alias.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"index-id" <INDEX_ID> "ID of an index the alias resolves to. Can be repeated.")
+                        .display_order(2)
+                        .action(ArgAction::Append)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("list")
+                .alias("ls")
+                .display_order(2)
+                .about("Lists index aliases.")
+            )
+        .subcommand(
+            Command::new("resolve")
+                .display_order(3)
+                .about("Displays the IDs of the indexes an alias resolves to.")
+                .args(&[
+                    arg!(--alias <ALIAS> "ID of the target index alias.")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("update")
+                .display_order(4)
+                .about("Adds and removes indexes from an alias.")
+                .long_about("Adds and removes indexes from an alias. The update is applied atomically: the indexes are removed from the alias before the new ones are added, and the update fails if one of the indexes to remove is not part of the alias.")
+                .args(&[
+                    arg!(--alias <ALIAS> "ID of the target index alias.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--add <INDEX_ID> "ID of an index to add to the alias. Can be repeated.")
+                        .display_order(2)
+                        .action(ArgAction::Append)
+                        .required(false),
+                    arg!(--remove <INDEX_ID> "ID of an index to remove from the alias. Can be repeated.")
+                        .display_order(3)
+                        .action(ArgAction::Append)
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("switch")
+                .display_order(5)
+                .about("Atomically replaces an index with another one in an alias.")
+                .args(&[
+                    arg!(--alias <ALIAS> "ID of the target index alias.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--from <INDEX_ID> "ID of the index currently part of the alias.")
+                        .display_order(2)
+                        .required(true),
+                    arg!(--to <INDEX_ID> "ID of the index replacing it.")
+                        .display_order(3)
+                        .required(true),
+                ])
+            )
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateIndexAliasArgs {
+    pub client_args: ClientArgs,
+    pub alias_id: String,
+    pub index_ids: Vec<IndexId>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ListIndexAliasesArgs {
+    pub client_args: ClientArgs,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ResolveIndexAliasArgs {
+    pub client_args: ClientArgs,
+    pub alias_id: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpdateIndexAliasArgs {
+    pub client_args: ClientArgs,
+    pub alias_id: String,
+    pub add_index_ids: Vec<IndexId>,
+    pub remove_index_ids: Vec<IndexId>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SwitchIndexAliasArgs {
+    pub client_args: ClientArgs,
+    pub alias_id: String,
+    pub from_index_id: IndexId,
+    pub to_index_id: IndexId,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum IndexAliasCliCommand {
+    Create(CreateIndexAliasArgs),
+    List(ListIndexAliasesArgs),
+    Resolve(ResolveIndexAliasArgs),
+    Switch(SwitchIndexAliasArgs),
+    Update(UpdateIndexAliasArgs),
+}
+
+impl IndexAliasCliCommand {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let (subcommand, submatches) = matches
+            .remove_subcommand()
+            .context("failed to parse index alias subcommand")?;
+        match subcommand.as_str() {
+            "create" => Self::parse_create_args(submatches),
+            "list" => Self::parse_list_args(submatches),
+            "resolve" => Self::parse_resolve_args(submatches),
+            "switch" => Self::parse_switch_args(submatches),
+            "update" => Self::parse_update_args(submatches),
+            _ => bail!("unknown index alias subcommand `{subcommand}`"),
+        }
+    }
+
+    fn parse_create_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let alias_id = matches
+            .remove_one::<String>("alias")
+            .expect("`alias` should be a required arg.");
+        let index_ids = matches
+            .remove_many::<String>("index-id")
+            .expect("`index-id` should be a required arg.")
+            .collect();
+        Ok(Self::Create(CreateIndexAliasArgs {
+            client_args,
+            alias_id,
+            index_ids,
+        }))
+    }
+
+    fn parse_list_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        Ok(Self::List(ListIndexAliasesArgs { client_args }))
+    }
+
+    fn parse_resolve_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let alias_id = matches
+            .remove_one::<String>("alias")
+            .expect("`alias` should be a required arg.");
+        Ok(Self::Resolve(ResolveIndexAliasArgs {
+            client_args,
+            alias_id,
+        }))
+    }
+
+    fn parse_switch_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let alias_id = matches
+            .remove_one::<String>("alias")
+            .expect("`alias` should be a required arg.");
+        let from_index_id = matches
+            .remove_one::<String>("from")
+            .expect("`from` should be a required arg.");
+        let to_index_id = matches
+            .remove_one::<String>("to")
+            .expect("`to` should be a required arg.");
+        if from_index_id == to_index_id {
+            bail!("`--from` and `--to` must identify different indexes");
+        }
+        Ok(Self::Switch(SwitchIndexAliasArgs {
+            client_args,
+            alias_id,
+            from_index_id,
+            to_index_id,
+        }))
+    }
+
+    fn parse_update_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let alias_id = matches
+            .remove_one::<String>("alias")
+            .expect("`alias` should be a required arg.");
+        let add_index_ids: Vec<IndexId> = matches
+            .remove_many::<String>("add")
+            .map(Iterator::collect)
+            .unwrap_or_default();
+        let remove_index_ids: Vec<IndexId> = matches
+            .remove_many::<String>("remove")
+            .map(Iterator::collect)
+            .unwrap_or_default();
+        if add_index_ids.is_empty() && remove_index_ids.is_empty() {
+            bail!("at least one of `--add` or `--remove` must be specified");
+        }
+        Ok(Self::Update(UpdateIndexAliasArgs {
+            client_args,
+            alias_id,
+            add_index_ids,
+            remove_index_ids,
+        }))
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            Self::Create(args) => create_index_alias_cli(args).await,
+            Self::List(args) => list_index_aliases_cli(args).await,
+            Self::Resolve(args) => resolve_index_alias_cli(args).await,
+            Self::Switch(args) => switch_index_alias_cli(args).await,
+            Self::Update(args) => update_index_alias_cli(args).await,
+        }
+    }
+}
+
+pub async fn create_index_alias_cli(args: CreateIndexAliasArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "create-index-alias");
+    println!("❯ Creating index alias...");
+    let qw_client = args.client_args.client();
+    qw_client
+        .aliases()
+        .create(&args.alias_id, args.index_ids)
+        .await?;
+    println!(
+        "{} Index alias successfully created.",
+        "✔".color(GREEN_COLOR)
+    );
+    Ok(())
+}
+
+pub async fn list_index_aliases_cli(args: ListIndexAliasesArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "list-index-aliases");
+    let qw_client = args.client_args.client();
+    let index_aliases = qw_client.aliases().list().await?;
+    let index_aliases_table = make_list_index_aliases_table(index_aliases);
+    println!("\n{index_aliases_table}\n");
+    Ok(())
+}
+
+fn make_list_index_aliases_table(index_aliases: Vec<IndexAlias>) -> Table {
+    let rows = index_aliases
+        .into_iter()
+        .map(|index_alias| IndexAliasRow {
+            alias_id: index_alias.alias_id,
+            index_ids: index_alias.index_ids.join(", "),
+        })
+        .sorted_by(|left, right| left.alias_id.cmp(&right.alias_id));
+    make_table("Index aliases", rows, false)
+}
+
+#[derive(Tabled)]
+struct IndexAliasRow {
+    #[tabled(rename = "Alias ID")]
+    alias_id: String,
+    #[tabled(rename = "Index IDs")]
+    index_ids: String,
+}
+
+pub async fn resolve_index_alias_cli(args: ResolveIndexAliasArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "resolve-index-alias");
+    let qw_client = args.client_args.client();
+    let index_alias = qw_client.aliases().get(&args.alias_id).await?;
+
+    for index_id in index_alias.index_ids {
+        println!("{index_id}");
+    }
+    Ok(())
+}
+
+pub async fn update_index_alias_cli(args: UpdateIndexAliasArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "update-index-alias");
+    println!("❯ Updating index alias...");
+    let qw_client = args.client_args.client();
+    let index_alias_updates = IndexAliasUpdates {
+        add: args.add_index_ids,
+        remove: args.remove_index_ids,
+    };
+    let index_alias = qw_client
+        .aliases()
+        .update(&args.alias_id, index_alias_updates)
+        .await?;
+    println!(
+        "{} Index alias successfully updated. It now resolves to `{}`.",
+        "✔".color(GREEN_COLOR),
+        index_alias.index_ids.join("`, `")
+    );
+    Ok(())
+}
+
+pub async fn switch_index_alias_cli(args: SwitchIndexAliasArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "switch-index-alias");
+    println!("❯ Switching index alias...");
+    let qw_client = args.client_args.client();
+    let index_alias = qw_client
+        .aliases()
+        .switch(&args.alias_id, &args.from_index_id, &args.to_index_id)
+        .await?;
+    println!(
+        "{} Index alias successfully switched. It now resolves to `{}`.",
+        "✔".color(GREEN_COLOR),
+        index_alias.index_ids.join("`, `")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{build_cli, CliCommand};
+    use crate::index::IndexCliCommand;
+
+    #[test]
+    fn test_parse_index_alias_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "alias",
+                "create",
+                "--alias",
+                "logs",
+                "--index-id",
+                "logs-2024-01",
+                "--index-id",
+                "logs-2024-02",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Alias(IndexAliasCliCommand::Create(
+                CreateIndexAliasArgs {
+                    alias_id,
+                    index_ids,
+                    ..
+                }
+            ))) if alias_id == "logs" && index_ids == ["logs-2024-01", "logs-2024-02"]
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "alias", "list"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Alias(IndexAliasCliCommand::List(_)))
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "alias",
+                "resolve",
+                "--alias",
+                "logs",
+                "--endpoint",
+                "https://quickwit-cluster.io",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Alias(IndexAliasCliCommand::Resolve(
+                ResolveIndexAliasArgs {
+                    client_args,
+                    alias_id,
+                }
+            ))) if alias_id == "logs" && client_args.cluster_endpoint.as_str() == "https://quickwit-cluster.io/"
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "alias",
+                "update",
+                "--alias",
+                "logs",
+                "--add",
+                "logs-2024-03",
+                "--remove",
+                "logs-2024-01",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Alias(IndexAliasCliCommand::Update(
+                UpdateIndexAliasArgs {
+                    alias_id,
+                    add_index_ids,
+                    remove_index_ids,
+                    ..
+                }
+            ))) if alias_id == "logs" && add_index_ids == ["logs-2024-03"] && remove_index_ids == ["logs-2024-01"]
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "alias", "update", "--alias", "logs"])
+            .unwrap();
+        CliCommand::parse_cli_args(matches).unwrap_err();
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index", "alias", "switch", "--alias", "logs", "--from", "logs-v1", "--to",
+                "logs-v2",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Alias(IndexAliasCliCommand::Switch(
+                SwitchIndexAliasArgs {
+                    alias_id,
+                    from_index_id,
+                    to_index_id,
+                    ..
+                }
+            ))) if alias_id == "logs" && from_index_id == "logs-v1" && to_index_id == "logs-v2"
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index", "alias", "switch", "--alias", "logs", "--from", "logs-v1", "--to",
+                "logs-v1",
+            ])
+            .unwrap();
+        CliCommand::parse_cli_args(matches).unwrap_err();
+    }
+
+    #[test]
+    fn test_make_list_index_aliases_table() {
+        let index_aliases = vec![
+            IndexAlias {
+                alias_id: "traces".to_string(),
+                index_ids: vec!["traces-v1".to_string()],
+            },
+            IndexAlias {
+                alias_id: "logs".to_string(),
+                index_ids: vec!["logs-v1".to_string(), "logs-v2".to_string()],
+            },
+        ];
+        let table = make_list_index_aliases_table(index_aliases).to_string();
+        let logs_line_pos = table.find("logs-v1, logs-v2").unwrap();
+        let traces_line_pos = table.find("traces-v1").unwrap();
+        assert!(logs_line_pos < traces_line_pos);
+    }
+}
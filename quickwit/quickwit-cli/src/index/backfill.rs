@@ -0,0 +1,259 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use chrono::NaiveDate;
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use quickwit_actors::Universe;
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::uri::Uri;
+use quickwit_config::service::QuickwitService;
+use quickwit_config::{
+    BackfillSourceParams, IndexerConfig, SourceConfig, SourceInputFormat, SourceParams,
+    BACKFILL_SOURCE_ID,
+};
+use quickwit_indexing::actors::IndexingService;
+use quickwit_indexing::models::{DetachIndexingPipeline, DetachMergePipeline, SpawnPipeline};
+use quickwit_ingest::IngesterPool;
+use quickwit_metastore::{AddSourceRequestExt, IndexMetadataResponseExt};
+use quickwit_proto::metastore::{AddSourceRequest, IndexMetadataRequest, MetastoreService};
+use quickwit_proto::types::{IndexId, PipelineUid};
+use tracing::debug;
+
+use crate::checklist::GREEN_COLOR;
+use crate::tool::{create_empty_cluster, start_statistics_reporting_loop};
+use crate::{
+    config_cli_arg, get_resolvers, load_node_config, run_index_checklist, start_actor_runtimes,
+};
+
+pub fn build_index_backfill_command() -> Command {
+    Command::new("backfill")
+        .about("Ingests the historical files stored under an object storage prefix.")
+        .long_about("Lists the files stored under an object storage prefix, optionally restricted to a date range for files organized in `YYYY/MM/DD/` directories, and ingests them into an index. The command runs locally against the metastore and the index storage and exits once all the files have been ingested. The progress is checkpointed in the metastore, so an interrupted backfill resumes where it left off when the command is run again.")
+        .arg(config_cli_arg())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--"source-uri" <SOURCE_URI> "URI of the object storage prefix under which the files are stored, e.g. `s3://my-bucket/firehose/`.")
+                .display_order(2)
+                .required(true),
+            arg!(--format <FORMAT> "Format of the files. Gzip-compressed files (`.gz` extension) are decompressed on the fly.")
+                .value_parser(["ndjson", "plain"])
+                .default_value("ndjson")
+                .display_order(3)
+                .required(false),
+            arg!(--"start-date" <START_DATE> "Skips the files stored under a `YYYY/MM/DD/` directory older than this date (YYYY-MM-DD).")
+                .display_order(4)
+                .required(false),
+            arg!(--"end-date" <END_DATE> "Skips the files stored under a `YYYY/MM/DD/` directory more recent than this date (YYYY-MM-DD).")
+                .display_order(5)
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct BackfillIndexArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub source_uri: Uri,
+    pub input_format: SourceInputFormat,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+impl BackfillIndexArgs {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let source_uri = matches
+            .remove_one::<String>("source-uri")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`source-uri` should be a required arg.")?;
+        let input_format = match matches
+            .remove_one::<String>("format")
+            .expect("`format` should have a default value.")
+            .as_str()
+        {
+            "ndjson" => SourceInputFormat::Json,
+            "plain" => SourceInputFormat::PlainText,
+            format => bail!("unknown backfill format `{format}`"),
+        };
+        let start_date = matches
+            .remove_one::<String>("start-date")
+            .map(|date| parse_date(&date))
+            .transpose()
+            .context("failed to parse `start-date`")?;
+        let end_date = matches
+            .remove_one::<String>("end-date")
+            .map(|date| parse_date(&date))
+            .transpose()
+            .context("failed to parse `end-date`")?;
+        if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+            if start_date > end_date {
+                bail!(
+                    "`start-date` ({start_date}) must not be posterior to `end-date` ({end_date})"
+                );
+            }
+        }
+        Ok(Self {
+            config_uri,
+            index_id,
+            source_uri,
+            input_format,
+            start_date,
+            end_date,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        backfill_index_cli(self).await
+    }
+}
+
+fn parse_date(date: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("expected a date formatted as `YYYY-MM-DD`, got `{date}`"))
+}
+
+pub async fn backfill_index_cli(args: BackfillIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "backfill-index");
+    println!("❯ Backfilling index...");
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let mut metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+
+    let source_config = SourceConfig {
+        source_id: BACKFILL_SOURCE_ID.to_string(),
+        num_pipelines: NonZeroUsize::MIN,
+        enabled: true,
+        source_params: SourceParams::Backfill(BackfillSourceParams {
+            source_uri: args.source_uri,
+            start_date: args.start_date,
+            end_date: args.end_date,
+        }),
+        transform_config: None,
+        input_format: args.input_format,
+    };
+    run_index_checklist(
+        &mut metastore,
+        &storage_resolver,
+        &args.index_id,
+        Some(&source_config),
+    )
+    .await?;
+
+    // The checkpoint of the files already ingested is attached to the backfill source, which we
+    // register on the first run.
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(args.index_id.clone()))
+        .await?
+        .deserialize_index_metadata()?;
+    if !index_metadata.sources.contains_key(BACKFILL_SOURCE_ID) {
+        let add_source_request = AddSourceRequest::try_from_source_config(
+            index_metadata.index_uid.clone(),
+            &source_config,
+        )?;
+        metastore.add_source(add_source_request).await?;
+    }
+    // See `local_ingest_docs_cli`.
+    let cluster = create_empty_cluster(&config).await?;
+    let runtimes_config = RuntimesConfig::default();
+    start_actor_runtimes(
+        runtimes_config,
+        &HashSet::from_iter([QuickwitService::Indexer]),
+    )?;
+    let universe = Universe::new();
+    let merge_scheduler_service_mailbox = universe.get_or_spawn_one();
+    let indexing_server = IndexingService::new(
+        config.node_id.clone(),
+        config.data_dir_path.clone(),
+        IndexerConfig::default(),
+        runtimes_config.num_threads_indexing,
+        cluster,
+        metastore,
+        None,
+        merge_scheduler_service_mailbox,
+        IngesterPool::default(),
+        storage_resolver,
+        EventBroker::default(),
+    )
+    .await?;
+    let (indexing_server_mailbox, indexing_server_handle) =
+        universe.spawn_builder().spawn(indexing_server);
+    let pipeline_id = indexing_server_mailbox
+        .ask_for_res(SpawnPipeline {
+            index_id: args.index_id.clone(),
+            source_config,
+            pipeline_uid: PipelineUid::new(),
+        })
+        .await?;
+    let merge_pipeline_handle = indexing_server_mailbox
+        .ask_for_res(DetachMergePipeline {
+            pipeline_id: pipeline_id.merge_pipeline_id(),
+        })
+        .await?;
+    let indexing_pipeline_handle = indexing_server_mailbox
+        .ask_for_res(DetachIndexingPipeline { pipeline_id })
+        .await?;
+    let statistics = start_statistics_reporting_loop(indexing_pipeline_handle, false).await?;
+    merge_pipeline_handle.quit().await;
+    // Shutdown the indexing server.
+    universe
+        .send_exit_with_success(&indexing_server_mailbox)
+        .await?;
+    indexing_server_handle.join().await;
+    universe.quit().await;
+
+    match statistics.num_invalid_docs {
+        0 => {
+            println!("{} Backfill completed.", "✔".color(GREEN_COLOR));
+            Ok(())
+        }
+        _ => bail!("failed to ingest all the documents"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2023-01-31").unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()
+        );
+        parse_date("2023/01/31").unwrap_err();
+        parse_date("2023-02-31").unwrap_err();
+    }
+}
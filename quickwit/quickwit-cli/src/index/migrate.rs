@@ -0,0 +1,692 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use bytesize::ByteSize;
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use futures::{stream, StreamExt, TryStreamExt};
+use humantime::format_duration;
+use quickwit_common::uri::Uri;
+use quickwit_metastore::{
+    IndexMetadataResponseExt, IndexState, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitState, UpdateIndexRequestExt,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, ListSplitsRequest, MetastoreService, UpdateIndexRequest,
+};
+use quickwit_proto::types::{IndexId, SplitId};
+use quickwit_storage::{FilePayload, Storage, StorageErrorKind, StorageResolver};
+use tempfile::TempDir;
+use tracing::{debug, warn};
+
+use crate::checklist::GREEN_COLOR;
+use crate::{
+    config_cli_arg, get_resolvers, load_node_config, prompt_confirmation, run_index_checklist,
+};
+
+/// Minimum interval between two progress reports.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Largest file copied server-side. Larger files are streamed, because S3 rejects `CopyObject`
+/// requests for objects larger than 5GiB.
+const MAX_SERVER_SIDE_COPY_NUM_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+pub fn build_index_migrate_command() -> Command {
+    Command::new("migrate")
+        .about("Moves the split files of an index to a new storage location.")
+        .long_about("Copies the staged and published split files of an index to a new storage location, then points the index to it in the metastore. Split files already present at the destination with the expected size are skipped, so an interrupted migration can be resumed by running the command again. The index must be frozen during the migration, otherwise splits published after the copy started would not be migrated. The command runs locally against the metastore and the storages configured for the node.")
+        .arg(config_cli_arg())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--"dest-storage-uri" <DEST_STORAGE_URI> "URI of the new storage location of the index, for instance `s3://my-bucket/indexes/my-index`.")
+                .display_order(2)
+                .required(true),
+            arg!(--concurrency <CONCURRENCY> "Number of split files copied concurrently.")
+                .default_value("8")
+                .display_order(3)
+                .required(false),
+            arg!(--"dry-run" "Displays the split files that would be copied without copying them.")
+                .required(false),
+            arg!(--"delete-source" "Deletes the split files from the previous storage location once the index has been migrated.")
+                .required(false),
+            arg!(--force "Migrates the index even if it is not frozen. Splits published during the migration are not migrated.")
+                .required(false),
+            arg!(-y --"yes" "Assume \"yes\" as an answer to all prompts and run non-interactively.")
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MigrateIndexArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub dest_storage_uri: Uri,
+    pub concurrency: usize,
+    pub dry_run: bool,
+    pub delete_source: bool,
+    pub force: bool,
+    pub assume_yes: bool,
+}
+
+impl MigrateIndexArgs {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let dest_storage_uri = matches
+            .remove_one::<String>("dest-storage-uri")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`dest-storage-uri` should be a required arg.")?;
+        let concurrency: usize = matches
+            .remove_one::<String>("concurrency")
+            .expect("`concurrency` should have a default value.")
+            .parse()
+            .context("failed to parse `concurrency`")?;
+        if concurrency == 0 {
+            bail!("`concurrency` must be strictly positive");
+        }
+        let dry_run = matches.get_flag("dry-run");
+        let delete_source = matches.get_flag("delete-source");
+        let force = matches.get_flag("force");
+        let assume_yes = matches.get_flag("yes");
+        Ok(Self {
+            config_uri,
+            index_id,
+            dest_storage_uri,
+            concurrency,
+            dry_run,
+            delete_source,
+            force,
+            assume_yes,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        migrate_index_cli(self).await
+    }
+}
+
+/// A split file to migrate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct SplitFile {
+    pub path: PathBuf,
+    pub num_bytes: u64,
+}
+
+/// The split files of an index, partitioned according to whether they still have to be copied.
+#[derive(Debug, Default)]
+pub(crate) struct MigrationPlan {
+    pub files_to_copy: Vec<SplitFile>,
+    pub copied_files: Vec<SplitFile>,
+}
+
+impl MigrationPlan {
+    fn num_bytes_to_copy(&self) -> u64 {
+        self.files_to_copy.iter().map(|file| file.num_bytes).sum()
+    }
+
+    fn source_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files_to_copy
+            .iter()
+            .chain(&self.copied_files)
+            .map(|file| &file.path)
+    }
+}
+
+/// Looks up the split files of `split_ids` in both storages. A split file is considered copied
+/// if it exists in the destination storage with the same size as in the source storage, which
+/// makes the migration resumable.
+pub(crate) async fn plan_migration(
+    source_storage: &dyn Storage,
+    dest_storage: &dyn Storage,
+    split_ids: Vec<SplitId>,
+    concurrency: usize,
+) -> anyhow::Result<MigrationPlan> {
+    let lookups: Vec<(SplitFile, bool)> = stream::iter(split_ids)
+        .map(|split_id| async move {
+            let path = quickwit_common::split_file_path(&split_id);
+            let num_bytes = source_storage
+                .head(&path)
+                .await
+                .with_context(|| format!("failed to look up split file `{}`", path.display()))?
+                .size_bytes;
+            let is_copied = match dest_storage.head(&path).await {
+                Ok(dest_metadata) => dest_metadata.size_bytes == num_bytes,
+                Err(storage_error) if storage_error.kind() == StorageErrorKind::NotFound => false,
+                Err(storage_error) => return Err(anyhow::Error::from(storage_error)),
+            };
+            anyhow::Ok((SplitFile { path, num_bytes }, is_copied))
+        })
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+
+    let mut migration_plan = MigrationPlan::default();
+
+    for (split_file, is_copied) in lookups {
+        if is_copied {
+            migration_plan.copied_files.push(split_file);
+        } else {
+            migration_plan.files_to_copy.push(split_file);
+        }
+    }
+    migration_plan
+        .files_to_copy
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    migration_plan
+        .copied_files
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    Ok(migration_plan)
+}
+
+/// Estimates the remaining duration assuming the bytes are copied at a constant rate.
+fn estimate_remaining_duration(
+    elapsed: Duration,
+    num_copied_bytes: u64,
+    num_remaining_bytes: u64,
+) -> Option<Duration> {
+    if num_copied_bytes == 0 {
+        return None;
+    }
+    let remaining_secs =
+        elapsed.as_secs_f64() * num_remaining_bytes as f64 / num_copied_bytes as f64;
+    Some(Duration::from_secs(remaining_secs.ceil() as u64))
+}
+
+/// A storage containing both the source and the destination storages, for instance the bucket
+/// holding both locations.
+pub(crate) struct SharedStorage {
+    storage: Arc<dyn Storage>,
+    source_prefix: PathBuf,
+    dest_prefix: PathBuf,
+}
+
+impl SharedStorage {
+    /// Resolves the deepest storage containing both `source_uri` and `dest_uri`. Returns `None`
+    /// if the URIs do not share a backend, for instance if they point to different buckets.
+    pub(crate) async fn resolve(
+        storage_resolver: &StorageResolver,
+        source_uri: &Uri,
+        dest_uri: &Uri,
+    ) -> anyhow::Result<Option<Self>> {
+        if source_uri.protocol() != dest_uri.protocol() {
+            return Ok(None);
+        }
+        let source_path = Path::new(source_uri.as_str());
+        let dest_path = Path::new(dest_uri.as_str());
+        let mut ancestor_uri_opt = Some(source_uri.clone());
+
+        while let Some(ancestor_uri) = ancestor_uri_opt {
+            let ancestor_path = Path::new(ancestor_uri.as_str());
+
+            if let (Ok(source_prefix), Ok(dest_prefix)) = (
+                source_path.strip_prefix(ancestor_path),
+                dest_path.strip_prefix(ancestor_path),
+            ) {
+                let storage = storage_resolver.resolve(&ancestor_uri).await?;
+                let shared_storage = Self {
+                    storage,
+                    source_prefix: source_prefix.to_path_buf(),
+                    dest_prefix: dest_prefix.to_path_buf(),
+                };
+                return Ok(Some(shared_storage));
+            }
+            ancestor_uri_opt = ancestor_uri.parent();
+        }
+        Ok(None)
+    }
+}
+
+/// Copies split files between two storages. Files are copied server-side with [`Storage::copy`]
+/// when the storages share a backend. Otherwise, they are streamed through a temporary file, so
+/// that splits are never buffered in memory.
+pub(crate) struct SplitFileCopier {
+    source_storage: Arc<dyn Storage>,
+    dest_storage: Arc<dyn Storage>,
+    shared_storage_opt: Option<SharedStorage>,
+    scratch_dir: TempDir,
+}
+
+impl SplitFileCopier {
+    pub(crate) fn new(
+        source_storage: Arc<dyn Storage>,
+        dest_storage: Arc<dyn Storage>,
+        shared_storage_opt: Option<SharedStorage>,
+    ) -> anyhow::Result<Self> {
+        let scratch_dir = tempfile::tempdir().context("failed to create scratch directory")?;
+        Ok(Self {
+            source_storage,
+            dest_storage,
+            shared_storage_opt,
+            scratch_dir,
+        })
+    }
+
+    async fn copy(&self, split_file: &SplitFile) -> anyhow::Result<()> {
+        if let Some(shared_storage) = &self.shared_storage_opt {
+            if split_file.num_bytes <= MAX_SERVER_SIDE_COPY_NUM_BYTES {
+                shared_storage
+                    .storage
+                    .copy(
+                        &shared_storage.source_prefix.join(&split_file.path),
+                        &shared_storage.dest_prefix.join(&split_file.path),
+                    )
+                    .await?;
+                return Ok(());
+            }
+        }
+        let scratch_path = self.scratch_dir.path().join(&split_file.path);
+        self.source_storage
+            .copy_to_file(&split_file.path, &scratch_path)
+            .await?;
+        let put_result = async {
+            let payload = FilePayload::open(&scratch_path)?;
+            self.dest_storage
+                .put(&split_file.path, Box::new(payload))
+                .await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(io_error) = tokio::fs::remove_file(&scratch_path).await {
+            warn!(error=%io_error, path=%scratch_path.display(), "failed to remove scratch file");
+        }
+        put_result
+    }
+}
+
+/// Copies the split files from the source storage to the destination storage, reporting the
+/// progress of the copy on stdout.
+pub(crate) async fn copy_split_files(
+    split_file_copier: &SplitFileCopier,
+    files_to_copy: Vec<SplitFile>,
+    concurrency: usize,
+) -> anyhow::Result<()> {
+    let num_files = files_to_copy.len();
+    let num_total_bytes: u64 = files_to_copy.iter().map(|file| file.num_bytes).sum();
+
+    let mut copy_stream = stream::iter(files_to_copy)
+        .map(|split_file| async move {
+            split_file_copier.copy(&split_file).await.with_context(|| {
+                format!("failed to copy split file `{}`", split_file.path.display())
+            })?;
+            anyhow::Ok(split_file.num_bytes)
+        })
+        .buffer_unordered(concurrency);
+
+    let start = Instant::now();
+    let mut last_report = start;
+    let mut num_copied_files = 0;
+    let mut num_copied_bytes = 0;
+
+    while let Some(copy_result) = copy_stream.next().await {
+        num_copied_bytes += copy_result?;
+        num_copied_files += 1;
+
+        if num_copied_files < num_files && last_report.elapsed() < PROGRESS_INTERVAL {
+            continue;
+        }
+        last_report = Instant::now();
+        let eta = estimate_remaining_duration(
+            start.elapsed(),
+            num_copied_bytes,
+            num_total_bytes - num_copied_bytes,
+        )
+        .map(|duration| format_duration(duration).to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "{num_copied_files}/{num_files} split files copied, {}/{} transferred, estimated time \
+             remaining: {eta}",
+            ByteSize(num_copied_bytes),
+            ByteSize(num_total_bytes),
+        );
+    }
+    Ok(())
+}
+
+pub async fn migrate_index_cli(args: MigrateIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "migrate-index");
+    println!("❯ Migrating index...");
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let mut metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    run_index_checklist(&mut metastore, &storage_resolver, &args.index_id, None).await?;
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(args.index_id.clone()))
+        .await?
+        .deserialize_index_metadata()?;
+    let source_storage_uri = index_metadata.index_uri().clone();
+
+    if source_storage_uri == args.dest_storage_uri {
+        bail!(
+            "index `{}` is already stored at `{}`",
+            args.index_id,
+            args.dest_storage_uri
+        );
+    }
+    if index_metadata.index_state != IndexState::Frozen {
+        if !args.force {
+            bail!(
+                "index `{}` is not frozen: freeze it with `quickwit index freeze` before \
+                 migrating it, or use `--force` to migrate it anyway",
+                args.index_id
+            );
+        }
+        warn!(
+            "index `{}` is not frozen: splits published during the migration will not be migrated",
+            args.index_id
+        );
+    }
+    let source_storage = storage_resolver.resolve(&source_storage_uri).await?;
+    let dest_storage = storage_resolver.resolve(&args.dest_storage_uri).await?;
+    dest_storage.check_connectivity().await.with_context(|| {
+        format!(
+            "failed to connect to destination storage `{}`",
+            args.dest_storage_uri
+        )
+    })?;
+
+    let query = ListSplitsQuery::for_index(index_metadata.index_uid.clone())
+        .with_split_states([SplitState::Staged, SplitState::Published]);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let split_ids = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_split_ids()
+        .await?;
+    let migration_plan = plan_migration(
+        &*source_storage,
+        &*dest_storage,
+        split_ids,
+        args.concurrency,
+    )
+    .await?;
+
+    println!(
+        "{} split files ({}) will be copied from `{source_storage_uri}` to `{}`, {} split files \
+         were already copied.",
+        migration_plan.files_to_copy.len(),
+        ByteSize(migration_plan.num_bytes_to_copy()),
+        args.dest_storage_uri,
+        migration_plan.copied_files.len()
+    );
+    if args.dry_run {
+        for split_file in &migration_plan.files_to_copy {
+            println!("  - {}", split_file.path.display());
+        }
+        return Ok(());
+    }
+    let shared_storage_opt = SharedStorage::resolve(
+        &storage_resolver,
+        &source_storage_uri,
+        &args.dest_storage_uri,
+    )
+    .await?;
+    let split_file_copier =
+        SplitFileCopier::new(source_storage.clone(), dest_storage, shared_storage_opt)?;
+    copy_split_files(
+        &split_file_copier,
+        migration_plan.files_to_copy.clone(),
+        args.concurrency,
+    )
+    .await?;
+
+    let index_config = &index_metadata.index_config;
+    let mut update_request = UpdateIndexRequest::try_from_updates(
+        index_metadata.index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        None,
+        None,
+        None,
+    )?;
+    update_request.index_uri = Some(args.dest_storage_uri.as_str().to_string());
+    metastore.update_index(update_request).await?;
+
+    println!(
+        "{} Index `{}` successfully migrated to `{}`.",
+        "✔".color(GREEN_COLOR),
+        args.index_id,
+        args.dest_storage_uri
+    );
+    if !args.delete_source {
+        return Ok(());
+    }
+    if !args.assume_yes {
+        let prompt = format!(
+            "This operation will delete the split files of the index `{}` from `{}`. Do you want \
+             to proceed?",
+            args.index_id, source_storage_uri
+        );
+        if !prompt_confirmation(&prompt, false) {
+            return Ok(());
+        }
+    }
+    let source_paths: Vec<_> = migration_plan
+        .source_paths()
+        .map(|path| path.as_path())
+        .collect();
+    source_storage
+        .bulk_delete(&source_paths)
+        .await
+        .with_context(|| format!("failed to delete the split files from `{source_storage_uri}`"))?;
+    println!(
+        "{} Deleted {} split files from `{source_storage_uri}`.",
+        "✔".color(GREEN_COLOR),
+        source_paths.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_storage::{PutPayload, RamStorage};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_plan_and_copy_split_files() {
+        let source_storage: Arc<dyn Storage> = Arc::new(RamStorage::default());
+        let dest_storage: Arc<dyn Storage> = Arc::new(RamStorage::default());
+
+        for split_id in ["split-1", "split-2", "split-3"] {
+            let payload: Box<dyn PutPayload> = Box::new(split_id.as_bytes().repeat(10));
+            source_storage
+                .put(&quickwit_common::split_file_path(split_id), payload)
+                .await
+                .unwrap();
+        }
+        // `split-1` was copied by a previous run, `split-2` was only partially copied.
+        dest_storage
+            .put(
+                &quickwit_common::split_file_path("split-1"),
+                Box::new(b"split-1".repeat(10)),
+            )
+            .await
+            .unwrap();
+        dest_storage
+            .put(
+                &quickwit_common::split_file_path("split-2"),
+                Box::new(b"split-2".to_vec()),
+            )
+            .await
+            .unwrap();
+
+        let split_ids = vec![
+            "split-1".to_string(),
+            "split-2".to_string(),
+            "split-3".to_string(),
+        ];
+        let migration_plan = plan_migration(&*source_storage, &*dest_storage, split_ids, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            migration_plan.files_to_copy,
+            [
+                SplitFile {
+                    path: PathBuf::from("split-2.split"),
+                    num_bytes: 70,
+                },
+                SplitFile {
+                    path: PathBuf::from("split-3.split"),
+                    num_bytes: 70,
+                },
+            ]
+        );
+        assert_eq!(migration_plan.copied_files.len(), 1);
+        assert_eq!(migration_plan.num_bytes_to_copy(), 140);
+
+        let split_file_copier =
+            SplitFileCopier::new(source_storage.clone(), dest_storage.clone(), None).unwrap();
+        copy_split_files(&split_file_copier, migration_plan.files_to_copy, 2)
+            .await
+            .unwrap();
+        // The scratch files are removed once uploaded.
+        assert_eq!(
+            std::fs::read_dir(split_file_copier.scratch_dir.path())
+                .unwrap()
+                .count(),
+            0
+        );
+
+        for split_id in ["split-1", "split-2", "split-3"] {
+            let split_path = quickwit_common::split_file_path(split_id);
+            assert_eq!(
+                dest_storage.get_all(&split_path).await.unwrap(),
+                source_storage.get_all(&split_path).await.unwrap()
+            );
+        }
+        let split_ids = vec![
+            "split-1".to_string(),
+            "split-2".to_string(),
+            "split-3".to_string(),
+        ];
+        let migration_plan = plan_migration(&*source_storage, &*dest_storage, split_ids, 2)
+            .await
+            .unwrap();
+        assert!(migration_plan.files_to_copy.is_empty());
+        assert_eq!(migration_plan.copied_files.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_copy_split_files_server_side() {
+        let storage_resolver = StorageResolver::for_test();
+        let source_uri = Uri::for_test("ram:///indexes/source-index");
+        let dest_uri = Uri::for_test("ram:///indexes/dest-index");
+        let source_storage = storage_resolver.resolve(&source_uri).await.unwrap();
+        let dest_storage = storage_resolver.resolve(&dest_uri).await.unwrap();
+
+        let split_path = quickwit_common::split_file_path("split-1");
+        source_storage
+            .put(&split_path, Box::new(b"split-1".repeat(10)))
+            .await
+            .unwrap();
+
+        let shared_storage = SharedStorage::resolve(&storage_resolver, &source_uri, &dest_uri)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            shared_storage.storage.uri(),
+            &Uri::for_test("ram:///indexes")
+        );
+        assert_eq!(shared_storage.source_prefix, Path::new("source-index"));
+        assert_eq!(shared_storage.dest_prefix, Path::new("dest-index"));
+
+        let split_file_copier = SplitFileCopier::new(
+            source_storage.clone(),
+            dest_storage.clone(),
+            Some(shared_storage),
+        )
+        .unwrap();
+        let files_to_copy = vec![SplitFile {
+            path: split_path.clone(),
+            num_bytes: 70,
+        }];
+        copy_split_files(&split_file_copier, files_to_copy, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            dest_storage.get_all(&split_path).await.unwrap(),
+            source_storage.get_all(&split_path).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shared_storage_resolve_distinct_backends() {
+        let storage_resolver = StorageResolver::for_test();
+
+        let shared_storage_opt = SharedStorage::resolve(
+            &storage_resolver,
+            &Uri::for_test("ram:///indexes/my-index"),
+            &Uri::for_test("file:///indexes/my-index"),
+        )
+        .await
+        .unwrap();
+        assert!(shared_storage_opt.is_none());
+
+        let shared_storage_opt = SharedStorage::resolve(
+            &storage_resolver,
+            &Uri::for_test("s3://my-bucket/indexes/my-index"),
+            &Uri::for_test("s3://my-new-bucket/indexes/my-index"),
+        )
+        .await
+        .unwrap();
+        assert!(shared_storage_opt.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plan_migration_missing_source_file() {
+        let source_storage = RamStorage::default();
+        let dest_storage = RamStorage::default();
+        let split_ids = vec!["split-1".to_string()];
+        plan_migration(&source_storage, &dest_storage, split_ids, 1)
+            .await
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_estimate_remaining_duration() {
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 0, 100),
+            None
+        );
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 50, 100),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 100, 0),
+            Some(Duration::ZERO)
+        );
+    }
+}
@@ -42,14 +42,18 @@ use quickwit_common::uri::Uri;
 use quickwit_config::{ConfigFormat, IndexConfig};
 use quickwit_indexing::models::IndexingStatistics;
 use quickwit_indexing::IndexingPipeline;
-use quickwit_metastore::{IndexMetadata, Split, SplitState};
+use quickwit_metastore::{IndexMetadata, IndexState, Split, SplitState};
 use quickwit_proto::search::{CountHits, SortField, SortOrder};
 use quickwit_proto::types::IndexId;
 use quickwit_rest_client::models::IngestSource;
 use quickwit_rest_client::rest_client::{CommitType, IngestEvent};
 use quickwit_search::SearchResponseRest;
-use quickwit_serve::{ListSplitsQueryParams, SearchRequestQueryString, SortBy};
+use quickwit_serve::{
+    IndexStatsResponse, ListSplitsQueryParams, NdjsonDocErrorKind, NdjsonIngestResponse,
+    SearchRequestQueryString, SortBy,
+};
 use quickwit_storage::{load_file, StorageResolver};
+use serde_json::Value as JsonValue;
 use tabled::settings::object::{FirstRow, Rows, Segment};
 use tabled::settings::panel::Footer;
 use tabled::settings::{Alignment, Disable, Format, Modify, Panel, Rotate, Style};
@@ -57,12 +61,25 @@ use tabled::{Table, Tabled};
 use thousands::Separable;
 use tracing::{debug, Level};
 
-use self::update::{build_index_update_command, IndexUpdateCliCommand};
-use crate::checklist::GREEN_COLOR;
+use self::alias::{build_index_alias_command, IndexAliasCliCommand};
+use self::backfill::{build_index_backfill_command, BackfillIndexArgs};
+use self::migrate::{build_index_migrate_command, MigrateIndexArgs};
+use self::template::{build_index_template_command, IndexTemplateCliCommand};
+use self::update::{
+    build_index_set_retention_command, build_index_update_command, IndexUpdateCliCommand,
+    SetRetentionArgs,
+};
+use self::vacuum::{build_index_vacuum_command, VacuumIndexArgs};
+use crate::checklist::{GREEN_COLOR, RED_COLOR};
 use crate::stats::{mean, percentile, std_deviation};
 use crate::{client_args, make_table, prompt_confirmation, ClientArgs, THROUGHPUT_WINDOW_SIZE};
 
+pub mod alias;
+pub mod backfill;
+pub mod migrate;
+pub mod template;
 pub mod update;
+pub mod vacuum;
 
 pub fn build_index_command() -> Command {
     Command::new("index")
@@ -124,6 +141,16 @@ pub fn build_index_command() -> Command {
                 .alias("ls")
                 .display_order(6)
                 .about("List indexes.")
+                .args(&[
+                    arg!(--filter <FILTER> "Only lists the indexes matching the filter. The only supported filter is `state=<active|frozen>`.")
+                        .required(false),
+                    arg!(--format <FORMAT> "Output format. `json` prints a JSON array of index objects with a stable set of fields to stdout.")
+                        .value_parser(["table", "json"])
+                        .default_value("table")
+                        .required(false),
+                    arg!(--fields <FIELDS> "Comma-separated list of the fields to include in the JSON output, e.g. `id,size_bytes`. Requires `--format json`.")
+                        .required(false),
+                ])
             )
         .subcommand(
             Command::new("ingest")
@@ -195,9 +222,94 @@ pub fn build_index_command() -> Command {
                         .required(false),
                 ])
             )
+        .subcommand(
+            Command::new("analyze-query")
+                .display_order(9)
+                .about("Shows how a query is parsed against an index without executing it.")
+                .long_about("Parses a query against the doc mapping of an index and displays the resulting query AST, the terms searched, the token positions of the phrases, and the number of terms of the index each wildcard expands to. The query is not executed.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--query <QUERY> "Query expressed in natural query language ((barack AND obama) OR \"president of united states\"). Learn more on https://quickwit.io/docs/reference/search-language.")
+                        .display_order(2)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("reprocess-dlq")
+                .display_order(10)
+                .about("Reingests the documents stored in the dead letter queue of an index.")
+                .long_about("Reingests the documents rejected while indexing and stored in the dead letter queue of an index, typically after fixing its doc mapping. Reingested documents are removed from the dead letter queue. Documents that are rejected again are appended back to it.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            build_index_vacuum_command().display_order(11)
+        )
+        .subcommand(
+            build_index_template_command().display_order(12)
+        )
+        .subcommand(
+            build_index_backfill_command().display_order(13)
+        )
+        .subcommand(
+            build_index_alias_command().display_order(14)
+        )
+        .subcommand(
+            Command::new("freeze")
+                .display_order(15)
+                .about("Freezes an index: the index rejects new documents but can still be searched.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("unfreeze")
+                .display_order(16)
+                .about("Unfreezes a frozen index so that it accepts new documents again.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("stats")
+                .display_order(17)
+                .about("Displays operational statistics of an index.")
+                .long_about("Displays operational statistics of an index: number of documents and splits, size, timestamp range, as well as the indexing throughput, search QPS, average search latency, and cache hit rate observed over the last 60 seconds by the node serving the request.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .visible_alias("index-id")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            build_index_set_retention_command().display_order(18)
+        )
+        .subcommand(
+            build_index_migrate_command().display_order(19)
+        )
         .arg_required_else_help(true)
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct AnalyzeQueryArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub query: String,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct ClearIndexArgs {
     pub client_args: ClientArgs,
@@ -251,27 +363,97 @@ pub struct DeleteIndexArgs {
     pub assume_yes: bool,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct FreezeIndexArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UnfreezeIndexArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct IndexStatsArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct ListIndexesArgs {
     pub client_args: ClientArgs,
+    pub index_state_opt: Option<IndexState>,
+    pub format: IndexListOutputFormat,
+    pub fields_opt: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IndexListOutputFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for IndexListOutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format_str: &str) -> anyhow::Result<Self> {
+        match format_str {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => bail!("unknown output format `{format_str}`"),
+        }
+    }
+}
+
+/// Fields of the index objects printed by `quickwit index list --format json`. Scripts rely on
+/// them, so they must not be renamed nor removed.
+pub const INDEX_LIST_JSON_FIELDS: [&str; 9] = [
+    "id",
+    "state",
+    "doc_count",
+    "size_bytes",
+    "created_at",
+    "updated_at",
+    "schema_version",
+    "num_published_splits",
+    "num_staged_splits",
+];
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ReprocessDeadLetterQueueArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum IndexCliCommand {
+    Alias(IndexAliasCliCommand),
+    AnalyzeQuery(AnalyzeQueryArgs),
+    Backfill(BackfillIndexArgs),
     Clear(ClearIndexArgs),
     Create(CreateIndexArgs),
     Delete(DeleteIndexArgs),
     Describe(DescribeIndexArgs),
+    Freeze(FreezeIndexArgs),
     Ingest(IngestDocsArgs),
     List(ListIndexesArgs),
+    Migrate(MigrateIndexArgs),
+    ReprocessDeadLetterQueue(ReprocessDeadLetterQueueArgs),
     Search(SearchIndexArgs),
+    SetRetention(SetRetentionArgs),
+    Stats(IndexStatsArgs),
+    Template(IndexTemplateCliCommand),
+    Unfreeze(UnfreezeIndexArgs),
     Update(IndexUpdateCliCommand),
+    Vacuum(VacuumIndexArgs),
 }
 
 impl IndexCliCommand {
     pub fn default_log_level(&self) -> Level {
         match self {
-            Self::Search(_) => Level::ERROR,
+            Self::AnalyzeQuery(_) | Self::Search(_) => Level::ERROR,
             _ => Level::INFO,
         }
     }
@@ -281,18 +463,48 @@ impl IndexCliCommand {
             .remove_subcommand()
             .context("failed to parse index subcommand")?;
         match subcommand.as_str() {
+            "alias" => Ok(Self::Alias(IndexAliasCliCommand::parse_args(submatches)?)),
+            "analyze-query" => Self::parse_analyze_query_args(submatches),
+            "backfill" => Ok(Self::Backfill(BackfillIndexArgs::parse_args(submatches)?)),
             "clear" => Self::parse_clear_args(submatches),
             "create" => Self::parse_create_args(submatches),
             "delete" => Self::parse_delete_args(submatches),
             "describe" => Self::parse_describe_args(submatches),
+            "freeze" => Self::parse_freeze_args(submatches),
             "ingest" => Self::parse_ingest_args(submatches),
             "list" => Self::parse_list_args(submatches),
+            "migrate" => Ok(Self::Migrate(MigrateIndexArgs::parse_args(submatches)?)),
+            "reprocess-dlq" => Self::parse_reprocess_dead_letter_queue_args(submatches),
             "search" => Self::parse_search_args(submatches),
+            "set-retention" => Ok(Self::SetRetention(SetRetentionArgs::parse_args(
+                submatches,
+            )?)),
+            "stats" => Self::parse_stats_args(submatches),
+            "template" => Ok(Self::Template(IndexTemplateCliCommand::parse_args(
+                submatches,
+            )?)),
+            "unfreeze" => Self::parse_unfreeze_args(submatches),
             "update" => Ok(Self::Update(IndexUpdateCliCommand::parse_args(submatches)?)),
+            "vacuum" => Ok(Self::Vacuum(VacuumIndexArgs::parse_args(submatches)?)),
             _ => bail!("unknown index subcommand `{subcommand}`"),
         }
     }
 
+    fn parse_analyze_query_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let query = matches
+            .remove_one::<String>("query")
+            .expect("`query` should be a required arg.");
+        Ok(Self::AnalyzeQuery(AnalyzeQueryArgs {
+            client_args,
+            index_id,
+            query,
+        }))
+    }
+
     fn parse_clear_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
         let client_args = ClientArgs::parse(&mut matches)?;
         let index_id = matches
@@ -334,9 +546,76 @@ impl IndexCliCommand {
         }))
     }
 
+    fn parse_freeze_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        Ok(Self::Freeze(FreezeIndexArgs {
+            client_args,
+            index_id,
+        }))
+    }
+
+    fn parse_unfreeze_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        Ok(Self::Unfreeze(UnfreezeIndexArgs {
+            client_args,
+            index_id,
+        }))
+    }
+
+    fn parse_stats_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        Ok(Self::Stats(IndexStatsArgs {
+            client_args,
+            index_id,
+        }))
+    }
+
     fn parse_list_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
         let client_args = ClientArgs::parse(&mut matches)?;
-        Ok(Self::List(ListIndexesArgs { client_args }))
+        let index_state_opt = matches
+            .remove_one::<String>("filter")
+            .map(|filter| parse_index_state_filter(&filter))
+            .transpose()?;
+        let format = matches
+            .remove_one::<String>("format")
+            .map(|format| IndexListOutputFormat::from_str(&format))
+            .transpose()?
+            .unwrap_or(IndexListOutputFormat::Table);
+        let fields_opt = matches
+            .remove_one::<String>("fields")
+            .map(|fields| parse_index_list_fields(&fields))
+            .transpose()?;
+        if fields_opt.is_some() && format != IndexListOutputFormat::Json {
+            bail!("`--fields` requires `--format json`");
+        }
+        Ok(Self::List(ListIndexesArgs {
+            client_args,
+            index_state_opt,
+            format,
+            fields_opt,
+        }))
+    }
+
+    fn parse_reprocess_dead_letter_queue_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        Ok(Self::ReprocessDeadLetterQueue(
+            ReprocessDeadLetterQueueArgs {
+                client_args,
+                index_id,
+            },
+        ))
     }
 
     fn parse_ingest_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
@@ -442,18 +721,38 @@ impl IndexCliCommand {
 
     pub async fn execute(self) -> anyhow::Result<()> {
         match self {
+            Self::Alias(args) => args.execute().await,
+            Self::AnalyzeQuery(args) => analyze_query_cli(args).await,
+            Self::Backfill(args) => args.execute().await,
             Self::Clear(args) => clear_index_cli(args).await,
             Self::Create(args) => create_index_cli(args).await,
             Self::Delete(args) => delete_index_cli(args).await,
             Self::Describe(args) => describe_index_cli(args).await,
+            Self::Freeze(args) => freeze_index_cli(args).await,
             Self::Ingest(args) => ingest_docs_cli(args).await,
             Self::List(args) => list_index_cli(args).await,
+            Self::Migrate(args) => args.execute().await,
+            Self::ReprocessDeadLetterQueue(args) => reprocess_dead_letter_queue_cli(args).await,
             Self::Search(args) => search_index_cli(args).await,
+            Self::SetRetention(args) => args.execute().await,
+            Self::Stats(args) => index_stats_cli(args).await,
+            Self::Template(args) => args.execute().await,
+            Self::Unfreeze(args) => unfreeze_index_cli(args).await,
             Self::Update(args) => args.execute().await,
+            Self::Vacuum(args) => args.execute().await,
         }
     }
 }
 
+pub async fn analyze_query_cli(args: AnalyzeQueryArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "analyze-query");
+    let qw_client = args.client_args.client();
+    let analyze_query_response = qw_client.analyze_query(&args.index_id, &args.query).await?;
+    let analyze_query_response_json = serde_json::to_string_pretty(&analyze_query_response)?;
+    println!("{analyze_query_response_json}");
+    Ok(())
+}
+
 pub async fn clear_index_cli(args: ClearIndexArgs) -> anyhow::Result<()> {
     debug!(args=?args, "clear-index");
     if !args.assume_yes {
@@ -501,19 +800,123 @@ pub async fn create_index_cli(args: CreateIndexArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Parses a filter of the form `state=<active|frozen>`.
+fn parse_index_state_filter(filter: &str) -> anyhow::Result<IndexState> {
+    let Some(("state", index_state_str)) = filter.split_once('=') else {
+        bail!("unsupported filter `{filter}`: expected `state=<active|frozen>`");
+    };
+    IndexState::from_str(index_state_str.trim()).map_err(|error| anyhow!(error))
+}
+
+/// Parses a comma-separated list of fields of the index objects printed by `quickwit index list
+/// --format json`.
+fn parse_index_list_fields(fields: &str) -> anyhow::Result<Vec<String>> {
+    let fields: Vec<String> = fields
+        .split(',')
+        .map(|field| field.trim())
+        .filter(|field| !field.is_empty())
+        .map(|field| field.to_string())
+        .collect();
+
+    if fields.is_empty() {
+        bail!("`--fields` must contain at least one field");
+    }
+    for field in &fields {
+        if !INDEX_LIST_JSON_FIELDS.contains(&field.as_str()) {
+            bail!(
+                "unknown field `{field}`: expected one of `{}`",
+                INDEX_LIST_JSON_FIELDS.join("`, `")
+            );
+        }
+    }
+    Ok(fields)
+}
+
 pub async fn list_index_cli(args: ListIndexesArgs) -> anyhow::Result<()> {
     debug!(args=?args, "list-index");
     let qw_client = args.client_args.client();
     let indexes_metadatas = qw_client.indexes().list().await?;
-    let index_table = make_list_indexes_table(
-        indexes_metadatas
-            .into_iter()
-            .map(IndexMetadata::into_index_config),
-    );
-    println!("\n{index_table}\n");
+    let indexes_metadatas = indexes_metadatas.into_iter().filter(|index_metadata| {
+        args.index_state_opt.map_or(true, |index_state| {
+            index_metadata.index_state == index_state
+        })
+    });
+    match args.format {
+        IndexListOutputFormat::Table => {
+            let index_table =
+                make_list_indexes_table(indexes_metadatas.map(IndexMetadata::into_index_config));
+            println!("\n{index_table}\n");
+        }
+        IndexListOutputFormat::Json => {
+            let mut index_objects = Vec::new();
+
+            for index_metadata in
+                indexes_metadatas.sorted_by(|left, right| left.index_id().cmp(right.index_id()))
+            {
+                let list_splits_query_params = ListSplitsQueryParams {
+                    split_states: Some(vec![SplitState::Staged, SplitState::Published]),
+                    ..Default::default()
+                };
+                let splits = qw_client
+                    .splits(index_metadata.index_id())
+                    .list(list_splits_query_params)
+                    .await?;
+                let index_object =
+                    make_index_list_object(&index_metadata, &splits, args.fields_opt.as_deref());
+                index_objects.push(index_object);
+            }
+            // Only the JSON array goes to stdout so that the output can be piped into `jq`.
+            eprintln!("{} index(es) listed", index_objects.len());
+            println!("{}", serde_json::to_string_pretty(&index_objects)?);
+        }
+    }
     Ok(())
 }
 
+/// Builds the JSON object describing an index in the output of `quickwit index list --format
+/// json`. The object has the fields listed in [`INDEX_LIST_JSON_FIELDS`], or only `fields_opt`
+/// if specified.
+fn make_index_list_object(
+    index_metadata: &IndexMetadata,
+    splits: &[Split],
+    fields_opt: Option<&[String]>,
+) -> JsonValue {
+    let mut doc_count = 0;
+    let mut size_bytes = 0;
+    let mut num_published_splits = 0;
+    let mut num_staged_splits = 0;
+    let mut updated_at = index_metadata.create_timestamp;
+
+    for split in splits {
+        updated_at = updated_at.max(split.update_timestamp);
+
+        match split.split_state {
+            SplitState::Published => {
+                doc_count += split.split_metadata.num_docs as u64;
+                size_bytes += split.split_metadata.footer_offsets.end;
+                num_published_splits += 1;
+            }
+            SplitState::Staged => num_staged_splits += 1,
+            SplitState::MarkedForDeletion => {}
+        }
+    }
+    let mut index_object = serde_json::json!({
+        "id": index_metadata.index_id(),
+        "state": index_metadata.index_state.as_str(),
+        "doc_count": doc_count,
+        "size_bytes": size_bytes,
+        "created_at": index_metadata.create_timestamp,
+        "updated_at": updated_at,
+        "schema_version": index_metadata.index_config.schema_version,
+        "num_published_splits": num_published_splits,
+        "num_staged_splits": num_staged_splits,
+    });
+    if let (Some(fields), Some(index_object_map)) = (fields_opt, index_object.as_object_mut()) {
+        index_object_map.retain(|field, _| fields.contains(field));
+    }
+    index_object
+}
+
 fn make_list_indexes_table<I>(indexes: I) -> Table
 where I: IntoIterator<Item = IndexConfig> {
     let rows = indexes
@@ -534,6 +937,101 @@ struct IndexRow {
     index_uri: Uri,
 }
 
+pub async fn freeze_index_cli(args: FreezeIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "freeze-index");
+    let qw_client = args.client_args.client();
+    qw_client.indexes().freeze(&args.index_id).await?;
+    println!("{} Index successfully frozen.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
+pub async fn unfreeze_index_cli(args: UnfreezeIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "unfreeze-index");
+    let qw_client = args.client_args.client();
+    qw_client.indexes().unfreeze(&args.index_id).await?;
+    println!("{} Index successfully unfrozen.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
+pub async fn index_stats_cli(args: IndexStatsArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "index-stats");
+    let qw_client = args.client_args.client();
+    let index_stats = qw_client.indexes().stats(&args.index_id).await?;
+    let index_stats_row = IndexStatsRow {
+        index_id: &args.index_id,
+        index_stats: &index_stats,
+    };
+    println!("{}", create_table(index_stats_row, "Index Stats", true));
+    Ok(())
+}
+
+struct IndexStatsRow<'a> {
+    index_id: &'a str,
+    index_stats: &'a IndexStatsResponse,
+}
+
+impl Tabled for IndexStatsRow<'_> {
+    const LENGTH: usize = 11;
+
+    fn fields(&self) -> Vec<Cow<'_, str>> {
+        let index_stats = self.index_stats;
+        let documents_total = format!(
+            "{} ({})",
+            format_to_si_scale(index_stats.documents_total),
+            separate_thousands(index_stats.documents_total)
+        );
+        let splits_by_state = index_stats
+            .splits_by_state
+            .iter()
+            .sorted_by_key(|(split_state, _)| split_state.as_str())
+            .map(|(split_state, num_splits)| format!("{split_state}: {num_splits}"))
+            .join(", ");
+        let average_search_latency = index_stats
+            .average_search_latency_ms
+            .map(|latency_ms| format!("{latency_ms:.2} ms"))
+            .unwrap_or_else(|| "N/A".to_string());
+        let cache_hit_rate = index_stats
+            .cache_hit_rate
+            .map(|hit_rate| format!("{:.1}%", hit_rate * 100.0))
+            .unwrap_or_else(|| "N/A".to_string());
+        [
+            self.index_id.to_string(),
+            documents_total,
+            separate_thousands(index_stats.splits_total),
+            splits_by_state,
+            ByteSize(index_stats.size_bytes).to_string(),
+            display_timestamp(&index_stats.oldest_document_ts),
+            display_timestamp(&index_stats.newest_document_ts),
+            format!("{:.2}", index_stats.indexing_throughput_docs_per_sec),
+            format!("{:.2}", index_stats.search_qps),
+            average_search_latency,
+            cache_hit_rate,
+        ]
+        .into_iter()
+        .map(|field| field.into())
+        .collect()
+    }
+
+    fn headers() -> Vec<Cow<'static, str>> {
+        [
+            "Index ID",
+            "Number of published documents",
+            "Number of splits",
+            "Splits by state",
+            "Size of published splits",
+            "Oldest document",
+            "Newest document",
+            "Indexing throughput (docs/s)",
+            "Search QPS",
+            "Average search latency",
+            "Cache hit rate",
+        ]
+        .into_iter()
+        .map(|header| header.into())
+        .collect()
+    }
+}
+
 pub async fn describe_index_cli(args: DescribeIndexArgs) -> anyhow::Result<()> {
     debug!(args=?args, "describe-index");
     let qw_client = args.client_args.client();
@@ -977,7 +1475,7 @@ pub async fn ingest_docs_cli(args: IngestDocsArgs) -> anyhow::Result<()> {
     let batch_size_limit_opt = args
         .batch_size_limit_opt
         .map(|batch_size_limit| batch_size_limit.as_u64() as usize);
-    qw_client
+    let ingest_response = qw_client
         .ingest(
             &args.index_id,
             ingest_source,
@@ -987,10 +1485,118 @@ pub async fn ingest_docs_cli(args: IngestDocsArgs) -> anyhow::Result<()> {
         )
         .await?;
     progress_bar.finish();
+
+    if ingest_response.num_errors() == 0 {
+        println!(
+            "Ingested {} documents successfully.",
+            "✔".color(GREEN_COLOR)
+        );
+        return Ok(());
+    }
+    println!("{}", format_ingest_errors_summary(&ingest_response));
+
+    if ingest_response.num_docs_for_processing == 0 {
+        bail!("all documents were rejected");
+    }
+    Ok(())
+}
+
+/// Maximum number of document errors displayed after ingesting documents.
+const MAX_NUM_DISPLAYED_INGEST_ERRORS: usize = 10;
+
+fn format_ingest_errors_summary(ingest_response: &NdjsonIngestResponse) -> String {
+    let mut summary = format!(
+        "{} Ingested {} documents, rejected {} documents ({} parse errors, {} validation errors).",
+        "✖".color(RED_COLOR),
+        ingest_response.num_docs_for_processing,
+        ingest_response.num_errors(),
+        ingest_response.num_parse_errors,
+        ingest_response.num_validation_errors,
+    );
+    for doc_error in ingest_response
+        .errors
+        .iter()
+        .take(MAX_NUM_DISPLAYED_INGEST_ERRORS)
+    {
+        let kind = match doc_error.kind {
+            NdjsonDocErrorKind::Parse => "parse",
+            NdjsonDocErrorKind::Validation => "validation",
+        };
+        summary.push_str(&format!(
+            "\n - line {}: {kind} error: {}",
+            doc_error.line, doc_error.error
+        ));
+    }
+    let num_hidden_errors = ingest_response.num_errors() as usize
+        - ingest_response
+            .errors
+            .len()
+            .min(MAX_NUM_DISPLAYED_INGEST_ERRORS);
+
+    if num_hidden_errors > 0 {
+        summary.push_str(&format!("\n   ... and {num_hidden_errors} more"));
+    }
+    summary
+}
+
+const DEAD_LETTER_QUEUE_PAGE_SIZE: usize = 1_000;
+
+pub async fn reprocess_dead_letter_queue_cli(
+    args: ReprocessDeadLetterQueueArgs,
+) -> anyhow::Result<()> {
+    debug!(args=?args, "reprocess-dead-letter-queue");
+    println!("❯ Reprocessing dead letter queue...");
+    let qw_client = args.client_args.client();
+    let dead_letter_queue_client = qw_client.dead_letter_queue(&args.index_id);
+
+    // Documents rejected again while we reingest are appended to the queue: we only read the
+    // entries present when the command starts.
+    let first_page = dead_letter_queue_client
+        .get(1, DEAD_LETTER_QUEUE_PAGE_SIZE)
+        .await?;
+    let num_entries = first_page.num_entries;
+
+    if num_entries == 0 {
+        println!("{} Dead letter queue is empty.", "✔".color(GREEN_COLOR));
+        return Ok(());
+    }
+    let mut ndjson_docs = String::new();
+    let mut num_read_entries = 0;
+    let mut page = first_page;
+
+    loop {
+        for entry in page.entries.iter().take(num_entries - num_read_entries) {
+            ndjson_docs.push_str(&entry.document_line());
+            ndjson_docs.push('\n');
+            num_read_entries += 1;
+        }
+        if page.entries.is_empty() || num_read_entries >= num_entries {
+            break;
+        }
+        page = dead_letter_queue_client
+            .get(page.page + 1, DEAD_LETTER_QUEUE_PAGE_SIZE)
+            .await?;
+    }
+    qw_client
+        .ingest(
+            &args.index_id,
+            IngestSource::Str(ndjson_docs),
+            None,
+            None,
+            CommitType::Auto,
+        )
+        .await?;
+    let num_removed_entries = dead_letter_queue_client.truncate(num_read_entries).await?;
     println!(
-        "Ingested {} documents successfully.",
+        "{} Reingested {num_read_entries} documents from the dead letter queue.",
         "✔".color(GREEN_COLOR)
     );
+    if num_removed_entries < num_read_entries {
+        println!(
+            "Only {num_removed_entries} entries were removed from the dead letter queue: run the \
+             command again to reingest the remaining ones."
+        );
+    }
     Ok(())
 }
 
@@ -1266,6 +1872,7 @@ mod test {
     use std::ops::RangeInclusive;
 
     use quickwit_metastore::SplitMetadata;
+    use quickwit_serve::NdjsonDocError;
 
     use super::*;
 
@@ -1331,6 +1938,75 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_make_index_list_object() {
+        let mut index_metadata = IndexMetadata::for_test("test-index", "s3://some-test-bucket");
+        index_metadata.create_timestamp = 1_000;
+
+        let splits: Vec<Split> = [
+            (SplitState::Published, 10, 2_000),
+            (SplitState::Published, 20, 3_000),
+            (SplitState::Staged, 30, 4_000),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (split_state, num_docs, update_timestamp))| Split {
+            split_metadata: split_metadata_for_test(
+                &format!("split-{i}"),
+                num_docs,
+                0..=1,
+                (i as u64 + 1) * 100,
+            ),
+            split_state,
+            update_timestamp,
+            publish_timestamp: None,
+        })
+        .collect();
+
+        let index_object = make_index_list_object(&index_metadata, &splits, None);
+        let index_object_map = index_object.as_object().unwrap();
+
+        for field in INDEX_LIST_JSON_FIELDS {
+            assert!(
+                index_object_map.contains_key(field),
+                "missing field `{field}`"
+            );
+        }
+        assert_eq!(index_object_map.len(), INDEX_LIST_JSON_FIELDS.len());
+
+        assert_eq!(index_object["id"].as_str(), Some("test-index"));
+        assert_eq!(index_object["state"].as_str(), Some("active"));
+        assert_eq!(index_object["doc_count"].as_u64(), Some(30));
+        assert_eq!(index_object["size_bytes"].as_u64(), Some(300));
+        assert_eq!(index_object["created_at"].as_i64(), Some(1_000));
+        assert_eq!(index_object["updated_at"].as_i64(), Some(4_000));
+        assert_eq!(index_object["schema_version"].as_u64(), Some(0));
+        assert_eq!(index_object["num_published_splits"].as_u64(), Some(2));
+        assert_eq!(index_object["num_staged_splits"].as_u64(), Some(1));
+
+        // The output must round-trip through the JSON text printed to stdout.
+        let index_objects_json = serde_json::to_string_pretty(&[&index_object]).unwrap();
+        let index_objects: Vec<JsonValue> = serde_json::from_str(&index_objects_json).unwrap();
+        assert_eq!(index_objects, [index_object]);
+
+        let fields = ["id".to_string(), "size_bytes".to_string()];
+        let index_object = make_index_list_object(&index_metadata, &splits, Some(&fields));
+        assert_eq!(
+            index_object,
+            serde_json::json!({"id": "test-index", "size_bytes": 300})
+        );
+    }
+
+    #[test]
+    fn test_parse_index_list_fields() {
+        assert_eq!(
+            parse_index_list_fields("id, size_bytes,").unwrap(),
+            ["id", "size_bytes"]
+        );
+        parse_index_list_fields("").unwrap_err();
+        parse_index_list_fields("id,index_uri").unwrap_err();
+    }
+
     #[test]
     fn test_descriptive_stats() -> anyhow::Result<()> {
         let split_id = "stat-test-split".to_string();
@@ -1395,4 +2071,36 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_format_ingest_errors_summary() {
+        let errors = (1..=12)
+            .map(|line| NdjsonDocError {
+                line,
+                kind: if line % 2 == 0 {
+                    NdjsonDocErrorKind::Parse
+                } else {
+                    NdjsonDocErrorKind::Validation
+                },
+                error: "oops".to_string(),
+                document: serde_json::Value::Null,
+            })
+            .collect();
+        let ingest_response = NdjsonIngestResponse {
+            num_docs_for_processing: 5,
+            num_parse_errors: 10,
+            num_validation_errors: 5,
+            errors,
+        };
+        let summary = format_ingest_errors_summary(&ingest_response);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert!(lines[0].ends_with(
+            "Ingested 5 documents, rejected 15 documents (10 parse errors, 5 validation errors)."
+        ));
+        assert_eq!(lines[1], " - line 1: validation error: oops");
+        assert_eq!(lines[2], " - line 2: parse error: oops");
+        assert_eq!(lines[10], " - line 10: parse error: oops");
+        assert_eq!(lines[11], "   ... and 5 more");
+    }
 }
@@ -0,0 +1,420 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use itertools::Itertools;
+use quickwit_common::uri::Uri;
+use quickwit_config::{ConfigFormat, IndexTemplate, IndexTemplateId};
+use quickwit_metastore::IndexMetadata;
+use quickwit_proto::types::IndexId;
+use quickwit_storage::{load_file, StorageResolver};
+use tabled::{Table, Tabled};
+use tracing::debug;
+
+use crate::checklist::GREEN_COLOR;
+use crate::{make_table, prompt_confirmation, ClientArgs};
+
+pub fn build_index_template_command() -> Command {
+    Command::new("template")
+        .about("Manages index templates: creates, lists, describes, and deletes templates.")
+        .long_about("Manages index templates. An index template defines the configuration applied to the indexes automatically created upon ingestion when their ID matches one of the template's index ID patterns.")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("create")
+                .display_order(1)
+                .about("Creates an index template from a template file.")
+                .args(&[
+                    arg!(--"template-file" <TEMPLATE_FILE> "Location of the index template file.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"dry-run" "Validates the template and displays the existing indexes whose ID matches its index ID patterns without creating it.")
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("list")
+                .alias("ls")
+                .display_order(2)
+                .about("Lists index templates.")
+                .long_about("Lists index templates. With `--ids-only`, prints only the template IDs, one per line, so that bash or zsh completion scripts can complete the `--name` argument of the `get` and `delete` subcommands.")
+                .args(&[
+                    arg!(--"ids-only" "Prints only the template IDs, one per line.")
+                        .required(false),
+                ])
+            )
+        .subcommand(
+            Command::new("get")
+                .display_order(3)
+                .about("Displays an index template.")
+                .args(&[
+                    arg!(--name <TEMPLATE_ID> "ID of the target index template")
+                        .visible_alias("template-id")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+        .subcommand(
+            Command::new("delete")
+                .display_order(4)
+                .alias("del")
+                .about("Deletes an index template.")
+                .long_about("Deletes an index template. Indexes previously created from the template are not affected.")
+                .args(&[
+                    arg!(--name <TEMPLATE_ID> "ID of the target index template")
+                        .visible_alias("template-id")
+                        .display_order(1)
+                        .required(true),
+                ])
+            )
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateIndexTemplateArgs {
+    pub client_args: ClientArgs,
+    pub template_file_uri: Uri,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ListIndexTemplatesArgs {
+    pub client_args: ClientArgs,
+    pub ids_only: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct GetIndexTemplateArgs {
+    pub client_args: ClientArgs,
+    pub template_id: IndexTemplateId,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeleteIndexTemplateArgs {
+    pub client_args: ClientArgs,
+    pub template_id: IndexTemplateId,
+    pub assume_yes: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum IndexTemplateCliCommand {
+    Create(CreateIndexTemplateArgs),
+    Delete(DeleteIndexTemplateArgs),
+    Get(GetIndexTemplateArgs),
+    List(ListIndexTemplatesArgs),
+}
+
+impl IndexTemplateCliCommand {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let (subcommand, submatches) = matches
+            .remove_subcommand()
+            .context("failed to parse index template subcommand")?;
+        match subcommand.as_str() {
+            "create" => Self::parse_create_args(submatches),
+            "delete" => Self::parse_delete_args(submatches),
+            "get" => Self::parse_get_args(submatches),
+            "list" => Self::parse_list_args(submatches),
+            _ => bail!("unknown index template subcommand `{subcommand}`"),
+        }
+    }
+
+    fn parse_create_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let template_file_uri = matches
+            .remove_one::<String>("template-file")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`template-file` should be a required arg.")?;
+        let dry_run = matches.get_flag("dry-run");
+        Ok(Self::Create(CreateIndexTemplateArgs {
+            client_args,
+            template_file_uri,
+            dry_run,
+        }))
+    }
+
+    fn parse_delete_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let template_id = matches
+            .remove_one::<String>("name")
+            .expect("`name` should be a required arg.");
+        let assume_yes = matches.get_flag("yes");
+        Ok(Self::Delete(DeleteIndexTemplateArgs {
+            client_args,
+            template_id,
+            assume_yes,
+        }))
+    }
+
+    fn parse_get_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let template_id = matches
+            .remove_one::<String>("name")
+            .expect("`name` should be a required arg.");
+        Ok(Self::Get(GetIndexTemplateArgs {
+            client_args,
+            template_id,
+        }))
+    }
+
+    fn parse_list_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let ids_only = matches.get_flag("ids-only");
+        Ok(Self::List(ListIndexTemplatesArgs {
+            client_args,
+            ids_only,
+        }))
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            Self::Create(args) => create_index_template_cli(args).await,
+            Self::Delete(args) => delete_index_template_cli(args).await,
+            Self::Get(args) => get_index_template_cli(args).await,
+            Self::List(args) => list_index_templates_cli(args).await,
+        }
+    }
+}
+
+pub async fn create_index_template_cli(args: CreateIndexTemplateArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "create-index-template");
+    let storage_resolver = StorageResolver::unconfigured();
+    let file_content = load_file(&storage_resolver, &args.template_file_uri).await?;
+    let config_format = ConfigFormat::sniff_from_uri(&args.template_file_uri)?;
+    let qw_client = args.client_args.client();
+
+    if args.dry_run {
+        let index_template: IndexTemplate = config_format
+            .parse(&file_content)
+            .context("failed to parse index template file")?;
+        index_template
+            .validate()
+            .context("invalid index template")?;
+        let indexes_metadatas = qw_client
+            .indexes()
+            .list_matching(&index_template.index_id_patterns)
+            .await?;
+        println!(
+            "Index template `{}` is valid. It will apply to the indexes created upon ingestion \
+             with an ID matching `{}`.",
+            index_template.template_id,
+            index_template.index_id_patterns.join("`, `")
+        );
+        if indexes_metadatas.is_empty() {
+            println!("No existing index matches the template.");
+        } else {
+            println!(
+                "The following existing indexes match the template. They will not be modified:"
+            );
+            for index_id in matching_index_ids(indexes_metadatas) {
+                println!(" - {index_id}");
+            }
+        }
+        return Ok(());
+    }
+    println!("❯ Creating index template...");
+    let index_template_str = std::str::from_utf8(&file_content)
+        .with_context(|| format!("Invalid utf8: `{}`", args.template_file_uri))?;
+    qw_client
+        .templates()
+        .create(index_template_str, config_format)
+        .await?;
+    println!(
+        "{} Index template successfully created.",
+        "✔".color(GREEN_COLOR)
+    );
+    Ok(())
+}
+
+fn matching_index_ids(indexes_metadatas: Vec<IndexMetadata>) -> Vec<IndexId> {
+    indexes_metadatas
+        .into_iter()
+        .map(|index_metadata| index_metadata.index_config.index_id)
+        .sorted()
+        .collect()
+}
+
+pub async fn list_index_templates_cli(args: ListIndexTemplatesArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "list-index-templates");
+    let qw_client = args.client_args.client();
+    let index_templates = qw_client.templates().list().await?;
+
+    if args.ids_only {
+        for index_template in index_templates {
+            println!("{}", index_template.template_id);
+        }
+        return Ok(());
+    }
+    let index_templates_table = make_list_index_templates_table(index_templates);
+    println!("\n{index_templates_table}\n");
+    Ok(())
+}
+
+fn make_list_index_templates_table(index_templates: Vec<IndexTemplate>) -> Table {
+    let rows = index_templates
+        .into_iter()
+        .map(|index_template| IndexTemplateRow {
+            template_id: index_template.template_id,
+            index_id_patterns: index_template.index_id_patterns.join(", "),
+            priority: index_template.priority,
+            description: index_template.description.unwrap_or_default(),
+        })
+        .sorted_by(|left, right| left.template_id.cmp(&right.template_id));
+    make_table("Index templates", rows, false)
+}
+
+#[derive(Tabled)]
+struct IndexTemplateRow {
+    #[tabled(rename = "Template ID")]
+    template_id: IndexTemplateId,
+    #[tabled(rename = "Index ID patterns")]
+    index_id_patterns: String,
+    #[tabled(rename = "Priority")]
+    priority: usize,
+    #[tabled(rename = "Description")]
+    description: String,
+}
+
+pub async fn get_index_template_cli(args: GetIndexTemplateArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "get-index-template");
+    let qw_client = args.client_args.client();
+    let index_template = qw_client.templates().get(&args.template_id).await?;
+    let index_template_json = serde_json::to_string_pretty(&index_template)?;
+    println!("{index_template_json}");
+    Ok(())
+}
+
+pub async fn delete_index_template_cli(args: DeleteIndexTemplateArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "delete-index-template");
+    if !args.assume_yes {
+        let prompt = format!(
+            "This operation will delete the index template `{}`. Do you want to proceed?",
+            args.template_id
+        );
+        if !prompt_confirmation(&prompt, false) {
+            return Ok(());
+        }
+    }
+    println!("❯ Deleting index template...");
+    let qw_client = args.client_args.client();
+    qw_client.templates().delete(&args.template_id).await?;
+    println!(
+        "{} Index template successfully deleted.",
+        "✔".color(GREEN_COLOR)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{build_cli, CliCommand};
+    use crate::index::IndexCliCommand;
+
+    #[test]
+    fn test_parse_index_template_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "template",
+                "create",
+                "--template-file",
+                "/templates/logs.yaml",
+                "--dry-run",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_template_file_uri = Uri::from_str("file:///templates/logs.yaml").unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Template(IndexTemplateCliCommand::Create(
+                CreateIndexTemplateArgs {
+                    template_file_uri,
+                    dry_run: true,
+                    ..
+                }
+            ))) if template_file_uri == expected_template_file_uri
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "template", "list", "--ids-only"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Template(IndexTemplateCliCommand::List(
+                ListIndexTemplatesArgs { ids_only: true, .. }
+            )))
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "template", "get", "--name", "logs"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Template(IndexTemplateCliCommand::Get(
+                GetIndexTemplateArgs { template_id, .. }
+            ))) if template_id == "logs"
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "template", "delete", "--template-id", "logs", "-y"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Template(IndexTemplateCliCommand::Delete(
+                DeleteIndexTemplateArgs {
+                    template_id,
+                    assume_yes: true,
+                    ..
+                }
+            ))) if template_id == "logs"
+        ));
+    }
+
+    #[test]
+    fn test_make_list_index_templates_table() {
+        let index_templates = vec![
+            IndexTemplate::for_test("traces", &["traces-*"], 0),
+            IndexTemplate::for_test("logs", &["logs-*", "-logs-internal"], 100),
+        ];
+        let table = make_list_index_templates_table(index_templates).to_string();
+        let logs_line_pos = table.find("logs-*, -logs-internal").unwrap();
+        let traces_line_pos = table.find("traces-*").unwrap();
+        assert!(logs_line_pos < traces_line_pos);
+    }
+
+    #[test]
+    fn test_matching_index_ids() {
+        let indexes_metadatas = vec![
+            IndexMetadata::for_test("logs-foo", "ram:///indexes/logs-foo"),
+            IndexMetadata::for_test("logs-bar", "ram:///indexes/logs-bar"),
+        ];
+        assert_eq!(
+            matching_index_ids(indexes_metadatas),
+            vec!["logs-bar".to_string(), "logs-foo".to_string()]
+        );
+    }
+}
@@ -17,12 +17,20 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::str::FromStr;
+
 use anyhow::{bail, Context};
+use bytesize::ByteSize;
 use clap::{arg, ArgMatches, Command};
 use colored::Colorize;
-use quickwit_config::{RetentionPolicy, SearchSettings};
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    diff_index_configs, load_index_config_from_user_config, ConfigFormat, IndexConfig,
+    RetentionPolicy, SearchSettings,
+};
 use quickwit_proto::types::IndexId;
 use quickwit_serve::IndexUpdates;
+use quickwit_storage::{load_file, StorageResolver};
 use tracing::debug;
 
 use crate::checklist::GREEN_COLOR;
@@ -42,6 +50,9 @@ pub fn build_index_update_command() -> Command {
                         .display_order(2)
                         .num_args(0..)
                         .required(true),
+                    arg!(--"dry-run" "Prints the changes that would be applied to the index config without applying them.")
+                        .display_order(3)
+                        .required(false),
                 ]))
         .subcommand(
             Command::new("retention-policy")
@@ -59,8 +70,166 @@ pub fn build_index_update_command() -> Command {
                     arg!(--"disable" "Disables the retention policy. Old indexed data will not be cleaned up anymore.")
                         .display_order(4)
                         .required(false),
+                    arg!(--"dry-run" "Prints the changes that would be applied to the index config without applying them.")
+                        .display_order(5)
+                        .required(false),
                 ])
         )
+        .subcommand(
+            Command::new("schema")
+                .about("Upgrades the doc mapping of an index. Fields can be added, but cannot be removed and their types can only be widened.")
+                .long_about("Upgrades the doc mapping of an index to the doc mapping of the given index config. Fields can be added, but cannot be removed and their types can only be widened. The splits indexed before the upgrade keep being searched with the doc mapping they were indexed with. Running indexing pipelines only pick up the new doc mapping when they restart.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"index-config" <INDEX_CONFIG> "Location of the index config file containing the new doc mapping.")
+                        .display_order(2)
+                        .required(true),
+                    arg!(--"dry-run" "Prints the changes that would be applied to the index config without applying them.")
+                        .display_order(3)
+                        .required(false),
+                ])
+        )
+        .subcommand(
+            Command::new("indexing-settings")
+                .about("Updates the split targets of an index.")
+                .long_about("Updates the number of documents and the size at which the indexer cuts a split. The options that are not specified keep their current value. Running indexing pipelines only pick up the new settings when they restart.")
+                .args(&[
+                    arg!(--index <INDEX> "ID of the target index")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--"split-num-docs-target" <SPLIT_NUM_DOCS_TARGET> "Target number of documents per split.")
+                        .display_order(2)
+                        .required(false),
+                    arg!(--"split-num-bytes-target" <SPLIT_NUM_BYTES_TARGET> "Target uncompressed size of the documents of a split (`500MB`, `2GB`, ...).")
+                        .display_order(3)
+                        .required(false),
+                    arg!(--"dry-run" "Prints the changes that would be applied to the index config without applying them.")
+                        .display_order(4)
+                        .required(false),
+                ])
+        )
+}
+
+pub fn build_index_set_retention_command() -> Command {
+    Command::new("set-retention")
+        .about("Sets the retention policy of an index.")
+        .long_about("Sets the retention policy of an existing index. The options that are not specified keep their current value, or their default value if the index has no retention policy yet. Use `quickwit index update retention-policy --disable` to remove the retention policy.")
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--"max-age" <MAX_AGE> "Duration after which splits are dropped. Expressed in a human-readable way (`30d`, `2 hours`, `1 week`, ...)")
+                .display_order(2)
+                .required(true),
+            arg!(--"max-bytes" <MAX_BYTES> "Maximum total size of the published splits (`500GB`, `1TiB`, ...). When it is exceeded, the splits holding the oldest documents are dropped first.")
+                .display_order(3)
+                .required(false),
+            arg!(--"schedule" <RETENTION_SCHEDULE> "Frequency at which the retention policy is evaluated and applied. Expressed as a cron expression (0 0 * * * *) or human-readable form (hourly, daily, weekly, ...).")
+                .display_order(4)
+                .required(false),
+            arg!(--"dry-run" "Prints the changes that would be applied to the index config without applying them.")
+                .display_order(5)
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SetRetentionArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub max_age: String,
+    pub max_bytes_opt: Option<ByteSize>,
+    pub schedule_opt: Option<String>,
+    pub dry_run: bool,
+}
+
+impl SetRetentionArgs {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let max_age = matches
+            .remove_one::<String>("max-age")
+            .expect("`max-age` should be a required arg.");
+        let max_bytes_opt = matches
+            .remove_one::<String>("max-bytes")
+            .map(|max_bytes| {
+                ByteSize::from_str(&max_bytes)
+                    .map_err(|error| anyhow::anyhow!("invalid `--max-bytes` value: {error}"))
+            })
+            .transpose()?;
+        let schedule_opt = matches.remove_one::<String>("schedule");
+        let dry_run = matches.get_flag("dry-run");
+        Ok(Self {
+            client_args,
+            index_id,
+            max_age,
+            max_bytes_opt,
+            schedule_opt,
+            dry_run,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        debug!(args=?self, "set-index-retention");
+        println!("❯ Setting index retention policy...");
+        let qw_client = self.client_args.client();
+        let metadata = qw_client.indexes().get(&self.index_id).await?;
+        let new_retention_policy = patch_retention_policy(
+            metadata.index_config.retention_policy_opt.clone(),
+            self.max_age,
+            self.max_bytes_opt,
+            self.schedule_opt,
+        );
+        if self.dry_run {
+            let mut new_index_config = metadata.index_config.clone();
+            new_index_config.retention_policy_opt = Some(new_retention_policy);
+            print_index_config_diff(&metadata.index_config, &new_index_config);
+            return Ok(());
+        }
+        println!(
+            "New retention policy: {}",
+            serde_json::to_string(&new_retention_policy)?
+        );
+        qw_client
+            .indexes()
+            .update(
+                &self.index_id,
+                IndexUpdates {
+                    retention_policy_opt: Some(new_retention_policy),
+                    indexing_settings_opt: None,
+                    search_settings: metadata.index_config.search_settings,
+                },
+            )
+            .await?;
+        println!("{} Index successfully updated.", "✔".color(GREEN_COLOR));
+        Ok(())
+    }
+}
+
+/// Overrides the settings of the current retention policy, if any, with the specified ones.
+fn patch_retention_policy(
+    current_retention_policy_opt: Option<RetentionPolicy>,
+    max_age: String,
+    max_bytes_opt: Option<ByteSize>,
+    schedule_opt: Option<String>,
+) -> RetentionPolicy {
+    let (current_schedule, current_max_size) = match current_retention_policy_opt {
+        Some(retention_policy) => (
+            retention_policy.evaluation_schedule,
+            retention_policy.max_size,
+        ),
+        None => (RetentionPolicy::default_schedule(), None),
+    };
+    RetentionPolicy {
+        retention_period: max_age,
+        evaluation_schedule: schedule_opt.unwrap_or(current_schedule),
+        max_size: max_bytes_opt.or(current_max_size),
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -70,6 +239,7 @@ pub struct RetentionPolicyArgs {
     pub disable: bool,
     pub period: Option<String>,
     pub schedule: Option<String>,
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -77,12 +247,32 @@ pub struct SearchSettingsArgs {
     pub client_args: ClientArgs,
     pub index_id: IndexId,
     pub default_search_fields: Vec<String>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SchemaArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub index_config_uri: Uri,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct IndexingSettingsArgs {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub split_num_docs_target_opt: Option<usize>,
+    pub split_num_bytes_target_opt: Option<ByteSize>,
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum IndexUpdateCliCommand {
+    IndexingSettings(IndexingSettingsArgs),
     RetentionPolicy(RetentionPolicyArgs),
     SearchSettings(SearchSettingsArgs),
+    Schema(SchemaArgs),
 }
 
 impl IndexUpdateCliCommand {
@@ -91,12 +281,47 @@ impl IndexUpdateCliCommand {
             .remove_subcommand()
             .context("failed to parse index update subcommand")?;
         match subcommand.as_str() {
+            "indexing-settings" => Self::parse_update_indexing_settings_args(submatches),
             "retention-policy" => Self::parse_update_retention_policy_args(submatches),
             "search-settings" => Self::parse_update_search_settings_args(submatches),
+            "schema" => Self::parse_update_schema_args(submatches),
             _ => bail!("unknown index update subcommand `{subcommand}`"),
         }
     }
 
+    fn parse_update_indexing_settings_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let split_num_docs_target_opt = matches
+            .remove_one::<String>("split-num-docs-target")
+            .map(|split_num_docs_target| split_num_docs_target.parse())
+            .transpose()
+            .context("failed to parse `split-num-docs-target`")?;
+        let split_num_bytes_target_opt = matches
+            .remove_one::<String>("split-num-bytes-target")
+            .map(|split_num_bytes_target| {
+                ByteSize::from_str(&split_num_bytes_target).map_err(|error| {
+                    anyhow::anyhow!("invalid `--split-num-bytes-target` value: {error}")
+                })
+            })
+            .transpose()?;
+        if split_num_docs_target_opt.is_none() && split_num_bytes_target_opt.is_none() {
+            bail!(
+                "either `--split-num-docs-target` or `--split-num-bytes-target` must be specified"
+            );
+        }
+        let dry_run = matches.get_flag("dry-run");
+        Ok(Self::IndexingSettings(IndexingSettingsArgs {
+            client_args,
+            index_id,
+            split_num_docs_target_opt,
+            split_num_bytes_target_opt,
+            dry_run,
+        }))
+    }
+
     fn parse_update_retention_policy_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
         let client_args = ClientArgs::parse(&mut matches)?;
         let index_id = matches
@@ -105,12 +330,14 @@ impl IndexUpdateCliCommand {
         let disable = matches.get_flag("disable");
         let period = matches.remove_one::<String>("period");
         let schedule = matches.remove_one::<String>("schedule");
+        let dry_run = matches.get_flag("dry-run");
         Ok(Self::RetentionPolicy(RetentionPolicyArgs {
             client_args,
             index_id,
             disable,
             period,
             schedule,
+            dry_run,
         }))
     }
 
@@ -125,21 +352,77 @@ impl IndexUpdateCliCommand {
             // --default-search-fields should be made optional if other fields
             // are added to SearchSettings
             .expect("`default-search-fields` should be a required arg.");
+        let dry_run = matches.get_flag("dry-run");
         Ok(Self::SearchSettings(SearchSettingsArgs {
             client_args,
             index_id,
             default_search_fields,
+            dry_run,
+        }))
+    }
+
+    fn parse_update_schema_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let index_config_uri = matches
+            .remove_one::<String>("index-config")
+            .map(|uri| Uri::from_str(&uri))
+            .expect("`index-config` should be a required arg.")?;
+        let dry_run = matches.get_flag("dry-run");
+        Ok(Self::Schema(SchemaArgs {
+            client_args,
+            index_id,
+            index_config_uri,
+            dry_run,
         }))
     }
 
     pub async fn execute(self) -> anyhow::Result<()> {
         match self {
+            Self::IndexingSettings(args) => update_indexing_settings_cli(args).await,
             Self::RetentionPolicy(args) => update_retention_policy_cli(args).await,
             Self::SearchSettings(args) => update_search_settings_cli(args).await,
+            Self::Schema(args) => update_schema_cli(args).await,
         }
     }
 }
 
+pub async fn update_indexing_settings_cli(args: IndexingSettingsArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "update-index-indexing-settings");
+    println!("❯ Updating index indexing settings...");
+    let qw_client = args.client_args.client();
+    let metadata = qw_client.indexes().get(&args.index_id).await?;
+    let mut indexing_settings = metadata.index_config.indexing_settings.clone();
+
+    if let Some(split_num_docs_target) = args.split_num_docs_target_opt {
+        indexing_settings.split_num_docs_target = split_num_docs_target;
+    }
+    if let Some(split_num_bytes_target) = args.split_num_bytes_target_opt {
+        indexing_settings.split_num_bytes_target = Some(split_num_bytes_target);
+    }
+    if args.dry_run {
+        let mut new_index_config = metadata.index_config.clone();
+        new_index_config.indexing_settings = indexing_settings;
+        print_index_config_diff(&metadata.index_config, &new_index_config);
+        return Ok(());
+    }
+    qw_client
+        .indexes()
+        .update(
+            &args.index_id,
+            IndexUpdates {
+                retention_policy_opt: metadata.index_config.retention_policy_opt,
+                indexing_settings_opt: Some(indexing_settings),
+                search_settings: metadata.index_config.search_settings,
+            },
+        )
+        .await?;
+    println!("{} Index successfully updated.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
 pub async fn update_retention_policy_cli(args: RetentionPolicyArgs) -> anyhow::Result<()> {
     debug!(args=?args, "update-index-retention-policy");
     println!("❯ Updating index retention policy...");
@@ -149,7 +432,7 @@ pub async fn update_retention_policy_cli(args: RetentionPolicyArgs) -> anyhow::R
         args.disable,
         args.period,
         args.schedule,
-        metadata.index_config.retention_policy_opt,
+        metadata.index_config.retention_policy_opt.clone(),
     ) {
         (true, Some(_), Some(_), _) | (true, None, Some(_), _) | (true, Some(_), None, _) => {
             bail!("`--period` and `--schedule` cannot be used together with `--disable`")
@@ -162,16 +445,25 @@ pub async fn update_retention_policy_cli(args: RetentionPolicyArgs) -> anyhow::R
         (false, None, Some(schedule), Some(policy)) => Some(RetentionPolicy {
             retention_period: policy.retention_period,
             evaluation_schedule: schedule,
+            max_size: policy.max_size,
         }),
         (false, Some(period), schedule_opt, None) => Some(RetentionPolicy {
             retention_period: period,
             evaluation_schedule: schedule_opt.unwrap_or(RetentionPolicy::default_schedule()),
+            max_size: None,
         }),
         (false, Some(period), schedule_opt, Some(policy)) => Some(RetentionPolicy {
             retention_period: period,
             evaluation_schedule: schedule_opt.unwrap_or(policy.evaluation_schedule.clone()),
+            max_size: policy.max_size,
         }),
     };
+    if args.dry_run {
+        let mut new_index_config = metadata.index_config.clone();
+        new_index_config.retention_policy_opt = new_retention_policy_opt;
+        print_index_config_diff(&metadata.index_config, &new_index_config);
+        return Ok(());
+    }
     if let Some(new_retention_policy) = new_retention_policy_opt.as_ref() {
         println!(
             "New retention policy: {}",
@@ -186,6 +478,7 @@ pub async fn update_retention_policy_cli(args: RetentionPolicyArgs) -> anyhow::R
             &args.index_id,
             IndexUpdates {
                 retention_policy_opt: new_retention_policy_opt,
+                indexing_settings_opt: None,
                 search_settings: metadata.index_config.search_settings,
             },
         )
@@ -202,6 +495,12 @@ pub async fn update_search_settings_cli(args: SearchSettingsArgs) -> anyhow::Res
     let search_settings = SearchSettings {
         default_search_fields: args.default_search_fields,
     };
+    if args.dry_run {
+        let mut new_index_config = metadata.index_config.clone();
+        new_index_config.search_settings = search_settings;
+        print_index_config_diff(&metadata.index_config, &new_index_config);
+        return Ok(());
+    }
     println!(
         "New search settings: {}",
         serde_json::to_string(&search_settings)?
@@ -212,6 +511,7 @@ pub async fn update_search_settings_cli(args: SearchSettingsArgs) -> anyhow::Res
             &args.index_id,
             IndexUpdates {
                 retention_policy_opt: metadata.index_config.retention_policy_opt,
+                indexing_settings_opt: None,
                 search_settings,
             },
         )
@@ -220,12 +520,100 @@ pub async fn update_search_settings_cli(args: SearchSettingsArgs) -> anyhow::Res
     Ok(())
 }
 
+pub async fn update_schema_cli(args: SchemaArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "update-index-schema");
+    println!("❯ Upgrading index schema...");
+    let storage_resolver = StorageResolver::unconfigured();
+    let file_content = load_file(&storage_resolver, &args.index_config_uri).await?;
+    let index_config_str: String = std::str::from_utf8(&file_content)
+        .with_context(|| format!("Invalid utf8: `{}`", args.index_config_uri))?
+        .to_string();
+    let config_format = ConfigFormat::sniff_from_uri(&args.index_config_uri)?;
+    let qw_client = args.client_args.client();
+
+    if args.dry_run {
+        let metadata = qw_client.indexes().get(&args.index_id).await?;
+        // Only the doc mapping of the new index config is applied, so the index URI it would
+        // otherwise default to does not matter.
+        let index_config = load_index_config_from_user_config(
+            config_format,
+            index_config_str.as_bytes(),
+            &metadata.index_config.index_uri,
+        )?;
+        let mut new_index_config = metadata.index_config.clone();
+        new_index_config.doc_mapping = index_config.doc_mapping;
+        print_index_config_diff(&metadata.index_config, &new_index_config);
+        return Ok(());
+    }
+    let index_metadata = qw_client
+        .indexes()
+        .upgrade_schema(&args.index_id, index_config_str, config_format)
+        .await?;
+    println!(
+        "{} Index schema successfully upgraded to version {}.",
+        "✔".color(GREEN_COLOR),
+        index_metadata.index_config.schema_version
+    );
+    Ok(())
+}
+
+fn print_index_config_diff(old_index_config: &IndexConfig, new_index_config: &IndexConfig) {
+    println!("Dry run, the following changes would be applied to the index config:");
+    print!("{}", diff_index_configs(old_index_config, new_index_config));
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::cli::{build_cli, CliCommand};
     use crate::index::IndexCliCommand;
 
+    #[test]
+    fn test_patch_retention_policy() {
+        let retention_policy = patch_retention_policy(None, "30 days".to_string(), None, None);
+        assert_eq!(
+            retention_policy,
+            RetentionPolicy {
+                retention_period: "30 days".to_string(),
+                evaluation_schedule: "hourly".to_string(),
+                max_size: None,
+            }
+        );
+        let current_retention_policy = RetentionPolicy {
+            retention_period: "1 week".to_string(),
+            evaluation_schedule: "daily".to_string(),
+            max_size: Some(ByteSize::gb(500)),
+        };
+        let retention_policy = patch_retention_policy(
+            Some(current_retention_policy.clone()),
+            "30 days".to_string(),
+            None,
+            None,
+        );
+        assert_eq!(
+            retention_policy,
+            RetentionPolicy {
+                retention_period: "30 days".to_string(),
+                evaluation_schedule: "daily".to_string(),
+                max_size: Some(ByteSize::gb(500)),
+            }
+        );
+        let retention_policy = patch_retention_policy(
+            Some(current_retention_policy),
+            "30 days".to_string(),
+            Some(ByteSize::tb(1)),
+            Some("weekly".to_string()),
+        );
+        assert_eq!(
+            retention_policy,
+            RetentionPolicy {
+                retention_period: "30 days".to_string(),
+                evaluation_schedule: "weekly".to_string(),
+                max_size: Some(ByteSize::tb(1)),
+            }
+        );
+    }
+
     #[test]
     fn test_cmd_update_subsubcommand() {
         let app = build_cli().no_binary_name(true);
@@ -250,8 +638,82 @@ mod test {
                     disable: false,
                     period: Some(period),
                     schedule: None,
+                    dry_run: false,
                 })
             )) if &index_id == "my-index" &&  &period == "1 day"
         ));
     }
+
+    #[test]
+    fn test_cmd_update_indexing_settings_subsubcommand() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "update",
+                "indexing-settings",
+                "--index",
+                "my-index",
+                "--split-num-docs-target",
+                "2000000",
+                "--split-num-bytes-target",
+                "2GB",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Update(
+                IndexUpdateCliCommand::IndexingSettings(IndexingSettingsArgs {
+                    client_args: _,
+                    index_id,
+                    split_num_docs_target_opt: Some(2_000_000),
+                    split_num_bytes_target_opt: Some(split_num_bytes_target),
+                    dry_run: false,
+                })
+            )) if &index_id == "my-index" && split_num_bytes_target == ByteSize::gb(2)
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "update",
+                "indexing-settings",
+                "--index",
+                "my-index",
+            ])
+            .unwrap();
+        let error = CliCommand::parse_cli_args(matches).unwrap_err();
+        assert!(error.to_string().contains("must be specified"));
+    }
+
+    #[test]
+    fn test_cmd_update_schema_subsubcommand() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "update",
+                "schema",
+                "--index",
+                "my-index",
+                "--index-config",
+                "/indexes/my-index.yaml",
+                "--dry-run",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Update(
+                IndexUpdateCliCommand::Schema(SchemaArgs {
+                    client_args: _,
+                    index_id,
+                    index_config_uri,
+                    dry_run: true,
+                })
+            )) if &index_id == "my-index" && index_config_uri == Uri::from_str("file:///indexes/my-index.yaml").unwrap()
+        ));
+    }
 }
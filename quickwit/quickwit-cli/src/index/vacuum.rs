@@ -0,0 +1,524 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashSet};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use bytesize::ByteSize;
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use humantime::format_duration;
+use quickwit_actors::{ActorExitStatus, ActorHandle, Mailbox, Universe};
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::uri::Uri;
+use quickwit_config::service::QuickwitService;
+use quickwit_config::{
+    IndexerConfig, SourceConfig, SourceInputFormat, SourceParams, VecSourceParams,
+};
+use quickwit_indexing::actors::{IndexingService, MergePipeline, MergeSchedulerService};
+use quickwit_indexing::merge_policy::MergeOperation;
+use quickwit_indexing::models::{DetachMergePipeline, RunMergeOperations, SpawnPipeline};
+use quickwit_ingest::IngesterPool;
+use quickwit_metastore::{
+    IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitMetadata, SplitState,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::types::{IndexId, IndexUid, PipelineUid, SourceId, SplitId};
+use tabled::{Table, Tabled};
+use time::OffsetDateTime;
+use tracing::{debug, info};
+
+use crate::checklist::GREEN_COLOR;
+use crate::tool::create_empty_cluster;
+use crate::{
+    config_cli_arg, get_resolvers, load_node_config, make_table, run_index_checklist,
+    start_actor_runtimes,
+};
+
+/// Interval at which the progress of the vacuum is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn build_index_vacuum_command() -> Command {
+    Command::new("vacuum")
+        .about("Compacts the small splits of an index into larger ones.")
+        .long_about("Merges the published splits of an index smaller than a quarter of the target split size into splits no larger than the target split size, then marks the merged splits for deletion. Only mature splits are considered so that the command does not compete with the merge pipelines of the indexers. The command runs locally against the metastore and the index storage and exits once all the merges have completed.")
+        .arg(config_cli_arg())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--"target-split-size-mb" <TARGET_SPLIT_SIZE_MB> "Maximum size of the splits produced by the vacuum, in megabytes.")
+                .default_value("500")
+                .display_order(2)
+                .required(false),
+            arg!(--"dry-run" "Displays the merge plan without executing it.")
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VacuumIndexArgs {
+    pub config_uri: Uri,
+    pub index_id: IndexId,
+    pub target_split_size: ByteSize,
+    pub dry_run: bool,
+}
+
+impl VacuumIndexArgs {
+    pub fn parse_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let target_split_size_mb: u64 = matches
+            .remove_one::<String>("target-split-size-mb")
+            .expect("`target-split-size-mb` should have a default value.")
+            .parse()
+            .context("failed to parse `target-split-size-mb`")?;
+        if target_split_size_mb == 0 {
+            bail!("`target-split-size-mb` must be strictly positive");
+        }
+        let dry_run = matches.get_flag("dry-run");
+        Ok(Self {
+            config_uri,
+            index_id,
+            target_split_size: ByteSize::mb(target_split_size_mb),
+            dry_run,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        vacuum_index_cli(self).await
+    }
+}
+
+/// A group of splits of the same source and partition merged into a single split.
+#[derive(Debug)]
+pub(crate) struct MergeBatch {
+    pub source_id: SourceId,
+    pub partition_id: u64,
+    pub splits: Vec<SplitMetadata>,
+}
+
+impl MergeBatch {
+    fn num_bytes(&self) -> u64 {
+        self.splits.iter().map(split_num_bytes).sum()
+    }
+}
+
+fn split_num_bytes(split: &SplitMetadata) -> u64 {
+    split.footer_offsets.end
+}
+
+/// Groups the splits smaller than a quarter of `target_split_size` into batches of at least two
+/// splits of the same source and partition, whose cumulated size does not exceed
+/// `target_split_size`. Within a group, splits are packed in chronological order so that the
+/// merged splits cover contiguous time ranges.
+pub(crate) fn plan_vacuum(
+    splits: Vec<SplitMetadata>,
+    target_split_size: ByteSize,
+) -> Vec<MergeBatch> {
+    let small_split_threshold = target_split_size.as_u64() / 4;
+    let mut groups: BTreeMap<(SourceId, u64), Vec<SplitMetadata>> = BTreeMap::new();
+
+    for split in splits {
+        if split_num_bytes(&split) < small_split_threshold {
+            groups
+                .entry((split.source_id.clone(), split.partition_id))
+                .or_default()
+                .push(split);
+        }
+    }
+    let mut merge_batches = Vec::new();
+
+    for ((source_id, partition_id), mut group_splits) in groups {
+        group_splits.sort_by_key(|split| {
+            let start_timestamp = split
+                .time_range
+                .as_ref()
+                .map(|time_range| *time_range.start());
+            (start_timestamp, split.create_timestamp)
+        });
+        let mut batch = MergeBatch {
+            source_id: source_id.clone(),
+            partition_id,
+            splits: Vec::new(),
+        };
+        let mut batch_num_bytes = 0;
+
+        for split in group_splits {
+            let num_bytes = split_num_bytes(&split);
+
+            if batch_num_bytes + num_bytes > target_split_size.as_u64() {
+                let full_batch = std::mem::replace(
+                    &mut batch,
+                    MergeBatch {
+                        source_id: source_id.clone(),
+                        partition_id,
+                        splits: Vec::new(),
+                    },
+                );
+                merge_batches.push(full_batch);
+                batch_num_bytes = 0;
+            }
+            batch.splits.push(split);
+            batch_num_bytes += num_bytes;
+        }
+        merge_batches.push(batch);
+    }
+    // Merging a single split would only rewrite it.
+    merge_batches.retain(|batch| batch.splits.len() >= 2);
+    merge_batches
+}
+
+#[derive(Tabled)]
+struct MergeBatchRow {
+    #[tabled(rename = "Source ID")]
+    source_id: SourceId,
+    #[tabled(rename = "Partition ID")]
+    partition_id: u64,
+    #[tabled(rename = "Num splits")]
+    num_splits: usize,
+    #[tabled(rename = "Num docs")]
+    num_docs: usize,
+    #[tabled(rename = "Size")]
+    size: ByteSize,
+}
+
+fn make_merge_plan_table(merge_batches: &[MergeBatch]) -> Table {
+    let rows = merge_batches.iter().map(|batch| MergeBatchRow {
+        source_id: batch.source_id.clone(),
+        partition_id: batch.partition_id,
+        num_splits: batch.splits.len(),
+        num_docs: batch.splits.iter().map(|split| split.num_docs).sum(),
+        size: ByteSize(batch.num_bytes()),
+    });
+    make_table("Merge plan", rows, false)
+}
+
+/// Estimates the remaining duration assuming the splits are merged at a constant rate.
+fn estimate_remaining_duration(
+    elapsed: Duration,
+    num_merged_splits: usize,
+    num_remaining_splits: usize,
+) -> Option<Duration> {
+    if num_merged_splits == 0 {
+        return None;
+    }
+    let remaining_secs =
+        elapsed.as_secs_f64() * num_remaining_splits as f64 / num_merged_splits as f64;
+    Some(Duration::from_secs(remaining_secs.ceil() as u64))
+}
+
+async fn count_remaining_splits(
+    metastore: &mut MetastoreServiceClient,
+    index_uid: &IndexUid,
+    planned_split_ids: &HashSet<SplitId>,
+) -> anyhow::Result<usize> {
+    let query =
+        ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let published_split_ids = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_split_ids()
+        .await?;
+    let num_remaining_splits = published_split_ids
+        .iter()
+        .filter(|split_id| planned_split_ids.contains(*split_id))
+        .count();
+    Ok(num_remaining_splits)
+}
+
+pub async fn vacuum_index_cli(args: VacuumIndexArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "vacuum-index");
+    println!("❯ Vacuuming index...");
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+    let mut metastore = metastore_resolver.resolve(&config.metastore_uri).await?;
+    run_index_checklist(&mut metastore, &storage_resolver, &args.index_id, None).await?;
+
+    let index_uid = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(args.index_id.clone()))
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    // Immature splits are left to the merge pipelines of the indexers.
+    let query = ListSplitsQuery::for_index(index_uid.clone())
+        .with_split_state(SplitState::Published)
+        .retain_mature(OffsetDateTime::now_utc());
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+    let merge_batches = plan_vacuum(splits, args.target_split_size);
+
+    if merge_batches.is_empty() {
+        println!("No small splits to merge.");
+        return Ok(());
+    }
+    let planned_split_ids: HashSet<SplitId> = merge_batches
+        .iter()
+        .flat_map(|batch| batch.splits.iter().map(|split| split.split_id.clone()))
+        .collect();
+    let num_planned_splits = planned_split_ids.len();
+
+    println!("{}", make_merge_plan_table(&merge_batches));
+    println!(
+        "{num_planned_splits} splits will be merged into {} splits.",
+        merge_batches.len()
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+    // See `merge_cli`.
+    let cluster = create_empty_cluster(&config).await?;
+    let runtimes_config = RuntimesConfig::default();
+    start_actor_runtimes(
+        runtimes_config,
+        &HashSet::from_iter([QuickwitService::Indexer]),
+    )?;
+    let universe = Universe::new();
+    let merge_scheduler_service: Mailbox<MergeSchedulerService> = universe.get_or_spawn_one();
+    let indexing_server = IndexingService::new(
+        config.node_id,
+        config.data_dir_path,
+        IndexerConfig::default(),
+        runtimes_config.num_threads_indexing,
+        cluster,
+        metastore.clone(),
+        None,
+        merge_scheduler_service,
+        IngesterPool::default(),
+        storage_resolver,
+        EventBroker::default(),
+    )
+    .await?;
+    let (indexing_service_mailbox, indexing_service_handle) =
+        universe.spawn_builder().spawn(indexing_server);
+
+    let mut merge_operations_per_source: BTreeMap<SourceId, Vec<MergeOperation>> = BTreeMap::new();
+    for batch in merge_batches {
+        merge_operations_per_source
+            .entry(batch.source_id)
+            .or_default()
+            .push(MergeOperation::new_merge_operation(batch.splits));
+    }
+    let mut pipeline_handles: Vec<ActorHandle<MergePipeline>> = Vec::new();
+
+    for (source_id, merge_operations) in merge_operations_per_source {
+        let pipeline_id = indexing_service_mailbox
+            .ask_for_res(SpawnPipeline {
+                index_id: args.index_id.clone(),
+                source_config: SourceConfig {
+                    source_id,
+                    num_pipelines: NonZeroUsize::new(1).unwrap(),
+                    enabled: true,
+                    source_params: SourceParams::Vec(VecSourceParams::default()),
+                    transform_config: None,
+                    input_format: SourceInputFormat::Json,
+                },
+                pipeline_uid: PipelineUid::new(),
+            })
+            .await?;
+        let pipeline_handle: ActorHandle<MergePipeline> = indexing_service_mailbox
+            .ask_for_res(DetachMergePipeline {
+                pipeline_id: pipeline_id.merge_pipeline_id(),
+            })
+            .await?;
+        pipeline_handle
+            .mailbox()
+            .send_message(RunMergeOperations { merge_operations })
+            .await?;
+        pipeline_handles.push(pipeline_handle);
+    }
+    let start = Instant::now();
+    let mut progress_interval = tokio::time::interval(PROGRESS_INTERVAL);
+    // The first tick completes immediately.
+    progress_interval.tick().await;
+
+    loop {
+        progress_interval.tick().await;
+
+        let num_remaining_splits =
+            count_remaining_splits(&mut metastore, &index_uid, &planned_split_ids).await?;
+        let num_merged_splits = num_planned_splits - num_remaining_splits;
+        let eta =
+            estimate_remaining_duration(start.elapsed(), num_merged_splits, num_remaining_splits)
+                .map(|duration| format_duration(duration).to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "{num_merged_splits}/{num_planned_splits} splits merged, {num_remaining_splits} \
+             remaining, estimated time remaining: {eta}"
+        );
+        if num_remaining_splits == 0 {
+            break;
+        }
+        let mut num_ongoing_merges = 0;
+
+        for pipeline_handle in &pipeline_handles {
+            pipeline_handle.refresh_observe();
+            num_ongoing_merges += pipeline_handle.last_observation().num_ongoing_merges;
+        }
+        if num_ongoing_merges == 0 {
+            info!("merge pipelines have no more ongoing merges, exiting");
+            break;
+        }
+        if pipeline_handles
+            .iter()
+            .all(|pipeline_handle| pipeline_handle.state().is_exit())
+        {
+            info!("merge pipelines have exited, exiting");
+            break;
+        }
+    }
+    let mut failed_exit_statuses = Vec::new();
+
+    for pipeline_handle in pipeline_handles {
+        let (pipeline_exit_status, _pipeline_statistics) = pipeline_handle.quit().await;
+
+        if !matches!(
+            pipeline_exit_status,
+            ActorExitStatus::Success | ActorExitStatus::Quit
+        ) {
+            failed_exit_statuses.push(pipeline_exit_status);
+        }
+    }
+    indexing_service_handle.quit().await;
+
+    if let Some(exit_status) = failed_exit_statuses.pop() {
+        bail!(exit_status);
+    }
+    let num_remaining_splits =
+        count_remaining_splits(&mut metastore, &index_uid, &planned_split_ids).await?;
+    if num_remaining_splits > 0 {
+        bail!("failed to merge {num_remaining_splits} out of {num_planned_splits} splits");
+    }
+    println!("{} Vacuum successful.", "✔".color(GREEN_COLOR));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_metadata_for_test(
+        split_id: &str,
+        source_id: &str,
+        partition_id: u64,
+        num_megabytes: u64,
+        start_timestamp: i64,
+    ) -> SplitMetadata {
+        SplitMetadata {
+            split_id: split_id.to_string(),
+            source_id: source_id.to_string(),
+            partition_id,
+            footer_offsets: 0..ByteSize::mb(num_megabytes).as_u64(),
+            time_range: Some(start_timestamp..=start_timestamp + 10),
+            ..Default::default()
+        }
+    }
+
+    fn batch_split_ids(merge_batches: &[MergeBatch]) -> Vec<Vec<&str>> {
+        merge_batches
+            .iter()
+            .map(|batch| batch.splits.iter().map(|split| split.split_id()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_vacuum_ignores_large_splits() {
+        let splits = vec![
+            split_metadata_for_test("a", "source", 0, 125, 0),
+            split_metadata_for_test("b", "source", 0, 400, 10),
+            split_metadata_for_test("c", "source", 0, 124, 20),
+        ];
+        let merge_batches = plan_vacuum(splits, ByteSize::mb(500));
+        assert!(merge_batches.is_empty());
+    }
+
+    #[test]
+    fn test_plan_vacuum_packs_splits_under_target_size() {
+        let splits = vec![
+            split_metadata_for_test("d", "source", 0, 100, 30),
+            split_metadata_for_test("a", "source", 0, 100, 0),
+            split_metadata_for_test("c", "source", 0, 100, 20),
+            split_metadata_for_test("e", "source", 0, 100, 40),
+            split_metadata_for_test("b", "source", 0, 100, 10),
+            split_metadata_for_test("f", "source", 0, 100, 50),
+            split_metadata_for_test("g", "source", 0, 100, 60),
+        ];
+        let merge_batches = plan_vacuum(splits, ByteSize::mb(500));
+        assert_eq!(
+            batch_split_ids(&merge_batches),
+            [vec!["a", "b", "c", "d", "e"], vec!["f", "g"]]
+        );
+        assert_eq!(merge_batches[0].num_bytes(), ByteSize::mb(500).as_u64());
+    }
+
+    #[test]
+    fn test_plan_vacuum_groups_splits_by_source_and_partition() {
+        let splits = vec![
+            split_metadata_for_test("a", "source-1", 0, 10, 0),
+            split_metadata_for_test("b", "source-2", 0, 10, 0),
+            split_metadata_for_test("c", "source-1", 1, 10, 0),
+            split_metadata_for_test("d", "source-1", 0, 10, 10),
+            split_metadata_for_test("e", "source-2", 0, 10, 10),
+            split_metadata_for_test("f", "source-1", 1, 10, 10),
+            split_metadata_for_test("g", "source-2", 1, 10, 10),
+        ];
+        let merge_batches = plan_vacuum(splits, ByteSize::mb(500));
+        assert_eq!(
+            batch_split_ids(&merge_batches),
+            [vec!["a", "d"], vec!["c", "f"], vec!["b", "e"]]
+        );
+        assert_eq!(merge_batches[1].source_id, "source-1");
+        assert_eq!(merge_batches[1].partition_id, 1);
+    }
+
+    #[test]
+    fn test_estimate_remaining_duration() {
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 0, 10),
+            None
+        );
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 5, 10),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            estimate_remaining_duration(Duration::from_secs(10), 10, 0),
+            Some(Duration::ZERO)
+        );
+    }
+}
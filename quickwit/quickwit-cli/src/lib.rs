@@ -48,17 +48,24 @@ use tracing::info;
 
 use crate::checklist::run_checklist;
 
+pub mod bench;
 pub mod checklist;
 pub mod cli;
+pub mod exporter;
 pub mod index;
 #[cfg(feature = "jemalloc")]
 pub mod jemalloc;
 pub mod logger;
 pub mod metrics;
+pub mod node;
+pub mod search;
+pub mod search_stream;
 pub mod service;
 pub mod source;
 pub mod split;
 pub mod stats;
+pub mod storage;
+pub mod tail;
 pub mod tool;
 
 /// Throughput calculation window size.
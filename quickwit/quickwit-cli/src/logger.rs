@@ -104,9 +104,9 @@ pub fn setup_logging_and_tracing(
             .try_init()
             .context("failed to register tracing subscriber")?;
     }
-    Ok(Arc::new(move |env_filter_def: &str| {
-        let new_env_filter = EnvFilter::try_new(env_filter_def)?;
-        reload_handle.reload(new_env_filter)?;
+    quickwit_common::runtimes::set_env_filter_reload_handle(reload_handle)?;
+    Ok(Arc::new(|env_filter_def: &str| {
+        quickwit_common::runtimes::reload_env_filter(env_filter_def)?;
         Ok(())
     }))
 }
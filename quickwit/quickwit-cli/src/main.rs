@@ -123,12 +123,25 @@ mod tests {
     use std::time::Duration;
 
     use bytesize::ByteSize;
+    use chrono::NaiveDate;
+    use quickwit_cli::bench::{
+        BenchCliCommand, IngestBenchArgs, IngestBenchIndex, SearchBenchArgs, StorageBenchArgs,
+    };
     use quickwit_cli::cli::{build_cli, CliCommand};
+    use quickwit_cli::index::backfill::BackfillIndexArgs;
+    use quickwit_cli::index::migrate::MigrateIndexArgs;
+    use quickwit_cli::index::update::SetRetentionArgs;
+    use quickwit_cli::index::vacuum::VacuumIndexArgs;
     use quickwit_cli::index::{
-        ClearIndexArgs, CreateIndexArgs, DeleteIndexArgs, DescribeIndexArgs, IndexCliCommand,
-        IngestDocsArgs, SearchIndexArgs,
+        AnalyzeQueryArgs, ClearIndexArgs, CreateIndexArgs, DeleteIndexArgs, DescribeIndexArgs,
+        FreezeIndexArgs, IndexCliCommand, IndexListOutputFormat, IndexStatsArgs, IngestDocsArgs,
+        ListIndexesArgs, ReprocessDeadLetterQueueArgs, SearchIndexArgs, UnfreezeIndexArgs,
     };
+    use quickwit_cli::search::{SearchCliCommand, SearchOutputFormat};
+    use quickwit_cli::search_stream::{SearchStreamCliCommand, StreamOutputFormat};
     use quickwit_cli::split::{DescribeSplitArgs, SplitCliCommand};
+    use quickwit_cli::storage::{CopyArgs, StorageCliCommand};
+    use quickwit_cli::tail::{LineTemplate, TailCliCommand};
     use quickwit_cli::tool::{
         ExtractSplitArgs, GarbageCollectIndexArgs, LocalIngestDocsArgs, LocalSearchArgs, MergeArgs,
         ToolCliCommand,
@@ -136,6 +149,7 @@ mod tests {
     use quickwit_cli::ClientArgs;
     use quickwit_common::uri::Uri;
     use quickwit_config::SourceInputFormat;
+    use quickwit_metastore::IndexState;
     use quickwit_rest_client::models::Timeout;
     use quickwit_rest_client::rest_client::CommitType;
     use reqwest::Url;
@@ -638,6 +652,412 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_freeze_and_unfreeze_index_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "freeze", "--index-id", "wikipedia"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Freeze(FreezeIndexArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "unfreeze", "--index", "wikipedia"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Unfreeze(UnfreezeIndexArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+        }));
+        assert_eq!(command, expected_cmd);
+    }
+
+    #[test]
+    fn test_parse_index_stats_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "stats", "--index-id", "wikipedia"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Stats(IndexStatsArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+        }));
+        assert_eq!(command, expected_cmd);
+    }
+
+    #[test]
+    fn test_parse_index_set_retention_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "set-retention",
+                "--index",
+                "wikipedia",
+                "--max-age",
+                "30d",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::SetRetention(SetRetentionArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+            max_age: "30d".to_string(),
+            max_bytes_opt: None,
+            schedule_opt: None,
+            dry_run: false,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "set-retention",
+                "--index-id",
+                "wikipedia",
+                "--max-age",
+                "30d",
+                "--max-bytes",
+                "500GB",
+                "--schedule",
+                "daily",
+                "--dry-run",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::SetRetention(SetRetentionArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+            max_age: "30d".to_string(),
+            max_bytes_opt: Some(ByteSize::gb(500)),
+            schedule_opt: Some("daily".to_string()),
+            dry_run: true,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "set-retention",
+                "--index",
+                "wikipedia",
+                "--max-age",
+                "30d",
+                "--max-bytes",
+                "lots",
+            ])
+            .unwrap();
+        CliCommand::parse_cli_args(matches).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_list_index_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(["index", "list"]).unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::List(ListIndexesArgs {
+            client_args: ClientArgs::default(),
+            index_state_opt: None,
+            format: IndexListOutputFormat::Table,
+            fields_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "list", "--filter", "state=frozen"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::List(ListIndexesArgs {
+            client_args: ClientArgs::default(),
+            index_state_opt: Some(IndexState::Frozen),
+            format: IndexListOutputFormat::Table,
+            fields_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "list",
+                "--format",
+                "json",
+                "--fields",
+                "id,size_bytes",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::List(ListIndexesArgs {
+            client_args: ClientArgs::default(),
+            index_state_opt: None,
+            format: IndexListOutputFormat::Json,
+            fields_opt: Some(vec!["id".to_string(), "size_bytes".to_string()]),
+        }));
+        assert_eq!(command, expected_cmd);
+
+        for invalid_args in [
+            &["index", "list", "--fields", "id"][..],
+            &["index", "list", "--format", "json", "--fields", "index_uri"],
+        ] {
+            let app = build_cli().no_binary_name(true);
+            let matches = app.try_get_matches_from(invalid_args).unwrap();
+            CliCommand::parse_cli_args(matches).unwrap_err();
+        }
+
+        for invalid_filter in ["state=melted", "index_id=wikipedia"] {
+            let app = build_cli().no_binary_name(true);
+            let matches = app
+                .try_get_matches_from(["index", "list", "--filter", invalid_filter])
+                .unwrap();
+            CliCommand::parse_cli_args(matches).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_parse_analyze_query_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from([
+                "index",
+                "analyze-query",
+                "--index-id",
+                "wikipedia",
+                "--query",
+                "foo AND bar OR baz",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::AnalyzeQuery(AnalyzeQueryArgs {
+            client_args: ClientArgs::default(),
+            index_id: "wikipedia".to_string(),
+            query: "foo AND bar OR baz".to_string(),
+        }));
+        assert_eq!(command, expected_cmd);
+    }
+
+    #[test]
+    fn test_parse_reprocess_dead_letter_queue_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(["index", "reprocess-dlq", "--index", "wikipedia"])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_cmd = CliCommand::Index(IndexCliCommand::ReprocessDeadLetterQueue(
+            ReprocessDeadLetterQueueArgs {
+                client_args: ClientArgs::default(),
+                index_id: "wikipedia".to_string(),
+            },
+        ));
+        assert_eq!(command, expected_cmd);
+    }
+
+    #[test]
+    fn test_parse_vacuum_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "vacuum",
+            "--index",
+            "wikipedia",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Vacuum(VacuumIndexArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index_id: "wikipedia".to_string(),
+            target_split_size: ByteSize::mb(500),
+            dry_run: false,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "vacuum",
+            "--index",
+            "wikipedia",
+            "--target-split-size-mb",
+            "100",
+            "--dry-run",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Vacuum(VacuumIndexArgs {
+                target_split_size,
+                dry_run: true,
+                ..
+            })) if target_split_size == ByteSize::mb(100)
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "vacuum",
+            "--index",
+            "wikipedia",
+            "--target-split-size-mb",
+            "0",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_migrate_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "migrate",
+            "--index-id",
+            "wikipedia",
+            "--dest-storage-uri",
+            "s3://my-bucket/indexes/wikipedia",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Migrate(MigrateIndexArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index_id: "wikipedia".to_string(),
+            dest_storage_uri: Uri::from_str("s3://my-bucket/indexes/wikipedia").unwrap(),
+            concurrency: 8,
+            dry_run: false,
+            delete_source: false,
+            force: false,
+            assume_yes: false,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "migrate",
+            "--index",
+            "wikipedia",
+            "--dest-storage-uri",
+            "s3://my-bucket/indexes/wikipedia",
+            "--concurrency",
+            "2",
+            "--dry-run",
+            "--delete-source",
+            "--force",
+            "--yes",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Migrate(MigrateIndexArgs {
+                concurrency: 2,
+                dry_run: true,
+                delete_source: true,
+                force: true,
+                assume_yes: true,
+                ..
+            }))
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "migrate",
+            "--index",
+            "wikipedia",
+            "--dest-storage-uri",
+            "s3://my-bucket/indexes/wikipedia",
+            "--concurrency",
+            "0",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_backfill_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "backfill",
+            "--index-id",
+            "hdfs-logs",
+            "--source-uri",
+            "s3://my-bucket/firehose/",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Index(IndexCliCommand::Backfill(BackfillIndexArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index_id: "hdfs-logs".to_string(),
+            source_uri: Uri::from_str("s3://my-bucket/firehose/").unwrap(),
+            input_format: SourceInputFormat::Json,
+            start_date: None,
+            end_date: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "backfill",
+            "--index",
+            "hdfs-logs",
+            "--source-uri",
+            "s3://my-bucket/firehose/",
+            "--format",
+            "plain",
+            "--start-date",
+            "2023-01-01",
+            "--end-date",
+            "2023-01-31",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Backfill(BackfillIndexArgs {
+                input_format: SourceInputFormat::PlainText,
+                start_date,
+                end_date,
+                ..
+            })) if start_date == NaiveDate::from_ymd_opt(2023, 1, 1)
+                && end_date == NaiveDate::from_ymd_opt(2023, 1, 31)
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "backfill",
+            "--index",
+            "hdfs-logs",
+            "--source-uri",
+            "s3://my-bucket/firehose/",
+            "--start-date",
+            "2023-01-31",
+            "--end-date",
+            "2023-01-01",
+            "--config",
+            "/config.yaml",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
     #[test]
     fn test_parse_split_describe_args() -> anyhow::Result<()> {
         let app = build_cli().no_binary_name(true);
@@ -690,6 +1110,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_storage_copy_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "storage",
+            "copy",
+            "--source",
+            "s3://bucket/indexes/wikipedia/ABC.split",
+            "--destination",
+            "s3://bucket/indexes/wikipedia-copy/ABC.split",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Storage(StorageCliCommand::Copy(CopyArgs {
+                config_uri,
+                source_uri,
+                destination_uri,
+            })) if config_uri == Uri::from_str("file:///config.yaml").unwrap()
+                && source_uri == "s3://bucket/indexes/wikipedia/ABC.split"
+                && destination_uri == "s3://bucket/indexes/wikipedia-copy/ABC.split"
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_parse_garbage_collect_args() -> anyhow::Result<()> {
         let app = build_cli().no_binary_name(true);
@@ -763,6 +1210,399 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_search_command_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "search",
+            "--index",
+            "hdfs-logs",
+            "--query",
+            "severity_text:ERROR",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Search(SearchCliCommand {
+            client_args: ClientArgs::default(),
+            index_id: "hdfs-logs".to_string(),
+            query: "severity_text:ERROR".to_string(),
+            max_hits: 20,
+            start_timestamp: None,
+            end_timestamp: None,
+            format: SearchOutputFormat::Table,
+            explain: false,
+        });
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "search",
+            "--endpoint",
+            "http://127.0.0.1:8000",
+            "--index-id",
+            "hdfs-logs",
+            "--query",
+            "severity_text:ERROR",
+            "--max-hits",
+            "5",
+            "--start-timestamp",
+            "0",
+            "--end-timestamp",
+            "1",
+            "--format",
+            "csv",
+            "--explain",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Search(SearchCliCommand {
+                client_args,
+                index_id,
+                max_hits: 5,
+                start_timestamp: Some(0),
+                end_timestamp: Some(1),
+                format: SearchOutputFormat::Csv,
+                explain: true,
+                ..
+            }) if client_args.cluster_endpoint == Url::from_str("http://127.0.0.1:8000").unwrap() && index_id == "hdfs-logs"
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        app.try_get_matches_from([
+            "search",
+            "--index",
+            "hdfs-logs",
+            "--query",
+            "severity_text:ERROR",
+            "--format",
+            "xml",
+        ])
+        .unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tail_command_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(["tail", "--index", "hdfs-logs"])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Tail(TailCliCommand {
+            client_args: ClientArgs::default(),
+            index_id: "hdfs-logs".to_string(),
+            query: "*".to_string(),
+            max_hits: 10,
+            line_template_opt: None,
+            follow: false,
+        });
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "tail",
+            "--endpoint",
+            "http://127.0.0.1:8000",
+            "--index-id",
+            "hdfs-logs",
+            "--query",
+            "severity_text:ERROR",
+            "--max-hits",
+            "5",
+            "--format",
+            "{timestamp} {severity_text} {body}",
+            "--follow",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_line_template: LineTemplate = "{timestamp} {severity_text} {body}".parse()?;
+        assert!(matches!(
+            command,
+            CliCommand::Tail(TailCliCommand {
+                client_args,
+                index_id,
+                query,
+                max_hits: 5,
+                line_template_opt: Some(line_template),
+                follow: true,
+            }) if client_args.cluster_endpoint == Url::from_str("http://127.0.0.1:8000").unwrap()
+                && index_id == "hdfs-logs"
+                && query == "severity_text:ERROR"
+                && line_template == expected_line_template
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches =
+            app.try_get_matches_from(["tail", "--index", "hdfs-logs", "--format", "{timestamp"])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_search_stream_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(["search", "stream", "--index", "hdfs-logs"])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::SearchStream(SearchStreamCliCommand {
+            client_args: ClientArgs::default(),
+            index_id: "hdfs-logs".to_string(),
+            query: "*".to_string(),
+            poll_interval: Duration::from_secs(5),
+            max_docs_opt: None,
+            start_timestamp_opt: None,
+            format: StreamOutputFormat::Ndjson,
+        });
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "search",
+            "stream",
+            "--endpoint",
+            "http://127.0.0.1:8000",
+            "--index-id",
+            "hdfs-logs",
+            "--query",
+            "level:ERROR",
+            "--poll-interval",
+            "500ms",
+            "--max-docs",
+            "100",
+            "--start-timestamp",
+            "1704067200",
+            "--format",
+            "pretty",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::SearchStream(SearchStreamCliCommand {
+                client_args,
+                index_id,
+                query,
+                poll_interval,
+                max_docs_opt: Some(100),
+                start_timestamp_opt: Some(1704067200),
+                format: StreamOutputFormat::Pretty,
+            }) if client_args.cluster_endpoint == Url::from_str("http://127.0.0.1:8000").unwrap()
+                && index_id == "hdfs-logs"
+                && query == "level:ERROR"
+                && poll_interval == Duration::from_millis(500)
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "search",
+            "stream",
+            "--index",
+            "hdfs-logs",
+            "--poll-interval",
+            "often",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bench_search_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "search",
+            "--index",
+            "wikipedia",
+            "--query-file",
+            "/queries.json",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Bench(BenchCliCommand::Search(SearchBenchArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index_id: "wikipedia".to_string(),
+            query_file_uri: Uri::from_str("file:///queries.json").unwrap(),
+            duration: Duration::from_secs(30),
+            concurrency: 8,
+            output_path_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "search",
+            "--index-id",
+            "wikipedia",
+            "--query-file",
+            "/queries.json",
+            "--duration-secs",
+            "10",
+            "--concurrency",
+            "2",
+            "--output-path",
+            "/report.json",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Bench(BenchCliCommand::Search(SearchBenchArgs {
+                duration,
+                concurrency: 2,
+                output_path_opt: Some(output_path),
+                ..
+            })) if duration == Duration::from_secs(10) && output_path == PathBuf::from("/report.json")
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "search",
+            "--index",
+            "wikipedia",
+            "--query-file",
+            "/queries.json",
+            "--concurrency",
+            "0",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bench_ingest_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "ingest",
+            "--index",
+            "wikipedia",
+            "--input-path",
+            "/docs.json",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Bench(BenchCliCommand::Ingest(IngestBenchArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index: IngestBenchIndex::Existing("wikipedia".to_string()),
+            input_path: PathBuf::from("/docs.json"),
+            num_documents_opt: None,
+            batch_size: 1000,
+            warmup: Duration::from_secs(10),
+            output_path_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "ingest",
+            "--index-config",
+            "/index-config.yaml",
+            "--input-file",
+            "/docs.json",
+            "--num-documents",
+            "1000000",
+            "--batch-size",
+            "500",
+            "--warmup-secs",
+            "0",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Bench(BenchCliCommand::Ingest(IngestBenchArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            index: IngestBenchIndex::Throwaway(Uri::from_str("file:///index-config.yaml").unwrap()),
+            input_path: PathBuf::from("/docs.json"),
+            num_documents_opt: Some(1_000_000),
+            batch_size: 500,
+            warmup: Duration::ZERO,
+            output_path_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        app.try_get_matches_from([
+            "bench",
+            "ingest",
+            "--index-id",
+            "wikipedia",
+            "--index-config",
+            "/index-config.yaml",
+            "--input-path",
+            "/docs.json",
+            "--config",
+            "/config.yaml",
+        ])
+        .unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bench_storage_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "storage",
+            "--storage-uri",
+            "s3://bucket/bench/",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Bench(BenchCliCommand::Storage(StorageBenchArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            storage_uri: Uri::from_str("s3://bucket/bench/").unwrap(),
+            duration: Duration::from_secs(60),
+            concurrency: 16,
+            object_size: ByteSize::mb(10),
+            output_path_opt: None,
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "storage",
+            "--storage-uri",
+            "s3://bucket/bench/",
+            "--duration-secs",
+            "5",
+            "--concurrency",
+            "4",
+            "--object-size-mb",
+            "1",
+            "--output-path",
+            "/report.json",
+            "--config",
+            "/config.yaml",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        let expected_cmd = CliCommand::Bench(BenchCliCommand::Storage(StorageBenchArgs {
+            config_uri: Uri::from_str("file:///config.yaml").unwrap(),
+            storage_uri: Uri::from_str("s3://bucket/bench/").unwrap(),
+            duration: Duration::from_secs(5),
+            concurrency: 4,
+            object_size: ByteSize::mb(1),
+            output_path_opt: Some(PathBuf::from("/report.json")),
+        }));
+        assert_eq!(command, expected_cmd);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "bench",
+            "storage",
+            "--storage-uri",
+            "s3://bucket/bench/",
+            "--object-size-mb",
+            "0",
+            "--config",
+            "/config.yaml",
+        ])?;
+        CliCommand::parse_cli_args(matches).unwrap_err();
+        Ok(())
+    }
+
     #[test]
     fn test_parse_no_color() {
         let previous_no_color_res = std::env::var("NO_COLOR");
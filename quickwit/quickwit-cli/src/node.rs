@@ -0,0 +1,382 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use itertools::Itertools;
+use quickwit_cluster::MemberStatus;
+use quickwit_config::service::QuickwitService;
+use quickwit_serve::ClusterMemberInfo;
+use tabled::{Table, Tabled};
+use time::OffsetDateTime;
+use tracing::debug;
+
+use crate::{client_args, make_table, ClientArgs};
+
+pub fn build_node_command() -> Command {
+    Command::new("node")
+        .about("Inspects the nodes of a cluster.")
+        .args(client_args())
+        .subcommand(
+            Command::new("peers")
+                .about("Lists the members of the cluster.")
+                .args(&[
+                    arg!(--filter <FILTER> "Selects the members matching this comma-separated list of filters, for instance `role=indexer` or `status=alive`. Possible keys are `role` and `status`.")
+                        .display_order(1)
+                        .required(false)
+                        .value_delimiter(','),
+                    arg!(--watch "Refreshes the list of members continuously.")
+                        .display_order(2)
+                        .required(false),
+                    arg!(--"watch-interval" <WATCH_INTERVAL> "Interval between two refreshes in watch mode.")
+                        .default_value("2s")
+                        .display_order(3)
+                        .required(false),
+                    arg!(--"output-format" <OUTPUT_FORMAT> "Output format. Possible values are `table`, `json`, and `pretty-json`.")
+                        .alias("format")
+                        .display_order(4)
+                        .required(false),
+                ])
+            )
+        .arg_required_else_help(true)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum OutputFormat {
+    Table, // Default
+    Json,
+    PrettyJson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(output_format_str: &str) -> anyhow::Result<Self> {
+        match output_format_str {
+            "json" => Ok(OutputFormat::Json),
+            "pretty-json" | "pretty_json" => Ok(OutputFormat::PrettyJson),
+            "table" => Ok(OutputFormat::Table),
+            _ => bail!(
+                "unknown output format `{output_format_str}`. supported formats are: `table`, \
+                 `json`, and `pretty-json`"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum PeerFilter {
+    Role(QuickwitService),
+    Status(MemberStatus),
+}
+
+impl PeerFilter {
+    fn matches(&self, member: &ClusterMemberInfo) -> bool {
+        match self {
+            PeerFilter::Role(service) => member.roles.iter().any(|role| role == service.as_str()),
+            PeerFilter::Status(status) => member.status == *status,
+        }
+    }
+}
+
+impl FromStr for PeerFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(filter_str: &str) -> anyhow::Result<Self> {
+        let Some((key, value)) = filter_str.split_once('=') else {
+            bail!("failed to parse filter `{filter_str}`. expected format is `<key>=<value>`");
+        };
+        match key.trim() {
+            "role" => {
+                let service = QuickwitService::from_str(value.trim())?;
+                Ok(PeerFilter::Role(service))
+            }
+            "status" => {
+                let status = match value.trim() {
+                    "alive" => MemberStatus::Alive,
+                    "draining" => MemberStatus::Draining,
+                    "dead" => MemberStatus::Dead,
+                    _ => bail!(
+                        "unknown status `{value}`. possible values are `alive`, `draining`, and \
+                         `dead`"
+                    ),
+                };
+                Ok(PeerFilter::Status(status))
+            }
+            _ => bail!("unknown filter key `{key}`. possible keys are `role` and `status`"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct PeersArgs {
+    pub client_args: ClientArgs,
+    pub filters: Vec<PeerFilter>,
+    /// Refresh interval, set only in watch mode.
+    pub watch_interval_opt: Option<Duration>,
+    output_format: OutputFormat,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum NodeCliCommand {
+    Peers(PeersArgs),
+}
+
+impl NodeCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let (subcommand, submatches) = matches
+            .remove_subcommand()
+            .context("failed to parse node subcommand")?;
+        match subcommand.as_str() {
+            "peers" => Self::parse_peers_args(submatches),
+            _ => bail!("unknown node subcommand `{subcommand}`"),
+        }
+    }
+
+    fn parse_peers_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let client_args = ClientArgs::parse(&mut matches)?;
+        let filters = matches
+            .remove_many::<String>("filter")
+            .map(|values| {
+                values
+                    .map(|filter_str| PeerFilter::from_str(&filter_str))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let watch_interval = matches
+            .remove_one::<String>("watch-interval")
+            .map(|duration_str| humantime::parse_duration(&duration_str))
+            .expect("`watch-interval` should have a default value.")
+            .context("failed to parse watch interval")?;
+        if watch_interval.is_zero() {
+            bail!("watch interval must be strictly positive");
+        }
+        let watch_interval_opt = matches.get_flag("watch").then_some(watch_interval);
+        let output_format = matches
+            .remove_one::<String>("output-format")
+            .map(|s| OutputFormat::from_str(s.as_str()))
+            .transpose()?
+            .unwrap_or(OutputFormat::Table);
+        Ok(Self::Peers(PeersArgs {
+            client_args,
+            filters,
+            watch_interval_opt,
+            output_format,
+        }))
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            Self::Peers(args) => peers_cli(args).await,
+        }
+    }
+}
+
+async fn peers_cli(args: PeersArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "node-peers");
+    let qw_client = args.client_args.client();
+
+    let Some(watch_interval) = args.watch_interval_opt else {
+        let members = qw_client
+            .cluster()
+            .members()
+            .await
+            .context("failed to list cluster members")?;
+        let output = format_peers(members, &args.filters, &args.output_format)?;
+        println!("{output}");
+        return Ok(());
+    };
+    let mut interval = tokio::time::interval(watch_interval);
+
+    loop {
+        interval.tick().await;
+
+        let output = match qw_client.cluster().members().await {
+            Ok(members) => format_peers(members, &args.filters, &args.output_format)?,
+            Err(error) => format!("failed to list cluster members: {error}"),
+        };
+        if args.output_format == OutputFormat::Table {
+            // Clears the terminal and moves the cursor to the top-left corner.
+            print!("\x1B[2J\x1B[1;1H");
+        }
+        println!("{output}");
+    }
+}
+
+fn format_peers(
+    members: Vec<ClusterMemberInfo>,
+    filters: &[PeerFilter],
+    output_format: &OutputFormat,
+) -> anyhow::Result<String> {
+    let members: Vec<ClusterMemberInfo> = members
+        .into_iter()
+        .filter(|member| filters.iter().all(|filter| filter.matches(member)))
+        .collect();
+    let output = match output_format {
+        OutputFormat::Json => serde_json::to_string(&members)?,
+        OutputFormat::PrettyJson => serde_json::to_string_pretty(&members)?,
+        OutputFormat::Table => make_peers_table(&members).to_string(),
+    };
+    Ok(output)
+}
+
+fn make_peers_table(members: &[ClusterMemberInfo]) -> Table {
+    let rows = members.iter().map(|member| {
+        let last_seen = member
+            .last_seen_timestamp
+            .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+            .map(|last_seen| last_seen.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        PeerRow {
+            node_id: member.node_id.clone(),
+            address: member.grpc_advertise_addr.to_string(),
+            roles: member.roles.iter().join(","),
+            status: member.status.as_str(),
+            last_seen,
+            num_splits: member.num_splits,
+        }
+    });
+    make_table("Peers", rows, false)
+}
+
+#[derive(Tabled)]
+struct PeerRow {
+    #[tabled(rename = "Node ID")]
+    node_id: String,
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Roles")]
+    roles: String,
+    #[tabled(rename = "Status")]
+    status: &'static str,
+    #[tabled(rename = "Last seen")]
+    last_seen: String,
+    #[tabled(rename = "Splits")]
+    num_splits: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+    use crate::cli::{build_cli, CliCommand};
+
+    fn member_for_test(
+        node_id: &str,
+        roles: &[&str],
+        status: MemberStatus,
+        num_splits: usize,
+    ) -> ClusterMemberInfo {
+        ClusterMemberInfo {
+            node_id: node_id.to_string(),
+            generation_id: 1,
+            grpc_advertise_addr: "127.0.0.1:7281".parse().unwrap(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            status,
+            is_ready: status == MemberStatus::Alive,
+            last_seen_timestamp: Some(1_700_000_000),
+            num_splits,
+        }
+    }
+
+    #[test]
+    fn test_parse_peers_args() {
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(vec![
+                "node",
+                "peers",
+                "--endpoint",
+                "http://127.0.0.1:8000",
+                "--filter",
+                "role=indexer,status=alive",
+                "--watch",
+                "--format",
+                "json",
+            ])
+            .unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_command = CliCommand::Node(NodeCliCommand::Peers(PeersArgs {
+            client_args: ClientArgs {
+                cluster_endpoint: Url::from_str("http://127.0.0.1:8000").unwrap(),
+                ..Default::default()
+            },
+            filters: vec![
+                PeerFilter::Role(QuickwitService::Indexer),
+                PeerFilter::Status(MemberStatus::Alive),
+            ],
+            watch_interval_opt: Some(Duration::from_secs(2)),
+            output_format: OutputFormat::Json,
+        }));
+        assert_eq!(command, expected_command);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from(vec!["node", "peers"]).unwrap();
+        let command = CliCommand::parse_cli_args(matches).unwrap();
+        let expected_command = CliCommand::Node(NodeCliCommand::Peers(PeersArgs {
+            client_args: ClientArgs::default(),
+            filters: Vec::new(),
+            watch_interval_opt: None,
+            output_format: OutputFormat::Table,
+        }));
+        assert_eq!(command, expected_command);
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app
+            .try_get_matches_from(vec!["node", "peers", "--filter", "region=eu"])
+            .unwrap();
+        CliCommand::parse_cli_args(matches).unwrap_err();
+    }
+
+    #[test]
+    fn test_format_peers() {
+        let members = vec![
+            member_for_test("node-1", &["indexer"], MemberStatus::Alive, 3),
+            member_for_test(
+                "node-2",
+                &["indexer", "searcher"],
+                MemberStatus::Draining,
+                1,
+            ),
+            member_for_test("node-3", &["searcher"], MemberStatus::Dead, 0),
+        ];
+        let filters = vec![PeerFilter::Role(QuickwitService::Indexer)];
+        let output = format_peers(members.clone(), &filters, &OutputFormat::Json).unwrap();
+        let indexers: Vec<ClusterMemberInfo> = serde_json::from_str(&output).unwrap();
+        assert_eq!(indexers, members[..2]);
+
+        let filters = vec![
+            PeerFilter::Role(QuickwitService::Searcher),
+            PeerFilter::Status(MemberStatus::Dead),
+        ];
+        let output = format_peers(members.clone(), &filters, &OutputFormat::Json).unwrap();
+        let dead_searchers: Vec<ClusterMemberInfo> = serde_json::from_str(&output).unwrap();
+        assert_eq!(dead_searchers, members[2..]);
+
+        let table = format_peers(members, &[], &OutputFormat::Table).unwrap();
+        assert!(table.contains("node-1"));
+        assert!(table.contains("indexer,searcher"));
+        assert!(table.contains("draining"));
+        assert!(table.contains("dead"));
+    }
+}
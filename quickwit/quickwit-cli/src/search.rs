@@ -0,0 +1,377 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::query_ast_from_user_text;
+use quickwit_rest_client::rest_client::QuickwitClient;
+use quickwit_search::SearchResponseRest;
+use quickwit_serve::SearchRequestQueryString;
+use serde_json::Value as JsonValue;
+use tabled::builder::Builder;
+use tabled::settings::Style;
+use tracing::debug;
+
+use crate::search_stream::build_search_stream_command;
+use crate::{client_args, ClientArgs};
+
+/// Maximum number of characters displayed in a cell of the `table` output format.
+const MAX_CELL_WIDTH: usize = 64;
+
+pub fn build_search_command() -> Command {
+    Command::new("search")
+        .about("Searches an index of a running Quickwit cluster.")
+        .long_about("Searches an index via the REST API of a running Quickwit cluster and renders the hits as a table, JSON, or CSV. Exits with a non-zero code if the search fails.")
+        .args(client_args())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--query <QUERY> "Query expressed in natural query language ((barack AND obama) OR \"president of united states\"). Learn more on https://quickwit.io/docs/reference/search-language.")
+                .display_order(2)
+                .required(true),
+            arg!(--"max-hits" <MAX_HITS> "Maximum number of hits returned.")
+                .default_value("20")
+                .required(false),
+            arg!(--"start-timestamp" <TIMESTAMP> "Filters out documents before that timestamp (time-series indexes only).")
+                .required(false),
+            arg!(--"end-timestamp" <TIMESTAMP> "Filters out documents after that timestamp (time-series indexes only).")
+                .required(false),
+            arg!(--format <FORMAT> "Output format of the hits.")
+                .value_parser(["table", "json", "csv"])
+                .default_value("table")
+                .required(false),
+            arg!(--explain "Prints the query plan, i.e. the query parsed against the default search fields of the index, before the hits.")
+                .required(false),
+        ])
+        .subcommand(build_search_stream_command())
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SearchOutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for SearchOutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format_str: &str) -> anyhow::Result<Self> {
+        match format_str {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => bail!("unknown output format `{format_str}`"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SearchCliCommand {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub query: String,
+    pub max_hits: usize,
+    pub start_timestamp: Option<i64>,
+    pub end_timestamp: Option<i64>,
+    pub format: SearchOutputFormat,
+    pub explain: bool,
+}
+
+impl SearchCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let query = matches
+            .remove_one::<String>("query")
+            .expect("`query` should be a required arg.");
+        let max_hits = matches
+            .remove_one::<String>("max-hits")
+            .expect("`max-hits` should have a default value.")
+            .parse()
+            .context("failed to parse `max-hits`")?;
+        let start_timestamp = matches
+            .remove_one::<String>("start-timestamp")
+            .map(|ts| ts.parse())
+            .transpose()
+            .context("failed to parse `start-timestamp`")?;
+        let end_timestamp = matches
+            .remove_one::<String>("end-timestamp")
+            .map(|ts| ts.parse())
+            .transpose()
+            .context("failed to parse `end-timestamp`")?;
+        let format = matches
+            .remove_one::<String>("format")
+            .expect("`format` should have a default value.")
+            .parse()?;
+        let explain = matches.get_flag("explain");
+        let client_args = ClientArgs::parse(&mut matches)?;
+        Ok(Self {
+            client_args,
+            index_id,
+            query,
+            max_hits,
+            start_timestamp,
+            end_timestamp,
+            format,
+            explain,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        debug!(args=?self, "search");
+        let qw_client = self.client_args.client();
+
+        if self.explain {
+            let query_plan = explain_query(&qw_client, &self.index_id, &self.query).await?;
+            println!("Query plan:\n{query_plan}\n");
+        }
+        let search_request = SearchRequestQueryString {
+            query: self.query,
+            start_timestamp: self.start_timestamp,
+            end_timestamp: self.end_timestamp,
+            max_hits: self.max_hits as u64,
+            ..Default::default()
+        };
+        let search_response = qw_client.search(&self.index_id, search_request).await?;
+
+        match self.format {
+            SearchOutputFormat::Table => {
+                if !search_response.hits.is_empty() {
+                    println!("{}", render_table(&search_response.hits));
+                }
+                println!(
+                    "{} hit(s) out of {} in {}ms",
+                    search_response.hits.len(),
+                    search_response.num_hits,
+                    search_response.elapsed_time_micros / 1_000
+                );
+            }
+            SearchOutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&search_response)?);
+            }
+            SearchOutputFormat::Csv => {
+                print!("{}", render_csv(&search_response.hits));
+            }
+        }
+        check_search_errors(&search_response)
+    }
+}
+
+/// Returns the user query parsed against the default search fields of the index, serialized as
+/// pretty-printed JSON.
+async fn explain_query(
+    qw_client: &QuickwitClient,
+    index_id: &str,
+    query: &str,
+) -> anyhow::Result<String> {
+    let index_metadata = qw_client.indexes().get(index_id).await?;
+    let default_search_fields = index_metadata
+        .index_config
+        .search_settings
+        .default_search_fields;
+    let query_ast = query_ast_from_user_text(query, None)
+        .parse_user_query(&default_search_fields)
+        .context("failed to parse query")?;
+    let query_plan = serde_json::to_string_pretty(&query_ast)?;
+    Ok(query_plan)
+}
+
+pub(crate) fn check_search_errors(search_response: &SearchResponseRest) -> anyhow::Result<()> {
+    if !search_response.errors.is_empty() {
+        bail!(
+            "search failed on some splits: {}",
+            search_response.errors.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Returns the union of the top-level fields of the hits, in order of first appearance.
+fn collect_columns(hits: &[JsonValue]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+
+    for hit in hits {
+        let Some(hit_object) = hit.as_object() else {
+            continue;
+        };
+        for field_name in hit_object.keys() {
+            if !columns.contains(field_name) {
+                columns.push(field_name.clone());
+            }
+        }
+    }
+    columns
+}
+
+pub(crate) fn format_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(value_str) => value_str.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn truncate_value(value: String, max_width: usize) -> String {
+    if value.chars().count() <= max_width {
+        return value;
+    }
+    let mut truncated_value: String = value.chars().take(max_width - 1).collect();
+    truncated_value.push('…');
+    truncated_value
+}
+
+fn hit_row<'a>(hit: &'a JsonValue, columns: &'a [String]) -> impl Iterator<Item = String> + 'a {
+    columns
+        .iter()
+        .map(move |column| hit.get(column).map(format_value).unwrap_or_default())
+}
+
+fn render_table(hits: &[JsonValue]) -> String {
+    let columns = collect_columns(hits);
+    let mut builder = Builder::default();
+    builder.set_header(columns.clone());
+
+    for hit in hits {
+        let row = hit_row(hit, &columns).map(|value| truncate_value(value, MAX_CELL_WIDTH));
+        builder.push_record(row);
+    }
+    builder.build().with(Style::ascii()).to_string()
+}
+
+fn escape_csv_value(value: String) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn render_csv(hits: &[JsonValue]) -> String {
+    let columns = collect_columns(hits);
+    let mut csv = String::new();
+
+    if columns.is_empty() {
+        return csv;
+    }
+    let header: Vec<String> = columns.iter().cloned().map(escape_csv_value).collect();
+    csv.push_str(&header.join(","));
+    csv.push('\n');
+
+    for hit in hits {
+        let row: Vec<String> = hit_row(hit, &columns).map(escape_csv_value).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_columns() {
+        let hits = vec![
+            json!({"level": "ERROR", "timestamp": 1}),
+            json!({"level": "INFO", "message": "oops", "timestamp": 2}),
+            json!("not an object"),
+        ];
+        assert_eq!(
+            collect_columns(&hits),
+            vec![
+                "level".to_string(),
+                "timestamp".to_string(),
+                "message".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncate_value() {
+        assert_eq!(truncate_value("short".to_string(), 10), "short");
+        assert_eq!(truncate_value("exactly10!".to_string(), 10), "exactly10!");
+        assert_eq!(
+            truncate_value("much too long".to_string(), 10),
+            "much too …"
+        );
+    }
+
+    #[test]
+    fn test_render_table() {
+        let hits = vec![
+            json!({"level": "ERROR", "message": "a".repeat(100)}),
+            json!({"level": "INFO", "payload": {"user": "foo"}}),
+        ];
+        let table = render_table(&hits);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[1].contains("level"));
+        assert!(lines[1].contains("message"));
+        assert!(lines[1].contains("payload"));
+        assert!(table.contains(&format!("{}…", "a".repeat(MAX_CELL_WIDTH - 1))));
+        assert!(!table.contains(&"a".repeat(MAX_CELL_WIDTH)));
+        assert!(table.contains(r#"{"user":"foo"}"#));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let hits = vec![
+            json!({"level": "ERROR", "message": "failed, retrying"}),
+            json!({"count": 3, "level": "INFO", "message": "said \"hello\""}),
+        ];
+        assert_eq!(
+            render_csv(&hits),
+            "level,message,count\nERROR,\"failed, retrying\",\nINFO,\"said \"\"hello\"\"\",3\n"
+        );
+        assert_eq!(render_csv(&[]), "");
+    }
+
+    #[test]
+    fn test_check_search_errors() {
+        let mut search_response = SearchResponseRest {
+            num_hits: 0,
+            hits: Vec::new(),
+            snippets: None,
+            elapsed_time_micros: 0,
+            errors: Vec::new(),
+            aggregations: None,
+            suggestions: None,
+        };
+        check_search_errors(&search_response).unwrap();
+
+        search_response
+            .errors
+            .push("split `foo` is unavailable".to_string());
+        let error = check_search_errors(&search_response).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "search failed on some splits: split `foo` is unavailable"
+        );
+    }
+}
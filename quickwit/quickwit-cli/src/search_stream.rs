@@ -0,0 +1,297 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Write;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use quickwit_proto::types::IndexId;
+use quickwit_rest_client::error::Error as ClientError;
+use quickwit_rest_client::rest_client::QuickwitClient;
+use quickwit_serve::{SearchRequestQueryString, SortBy};
+use reqwest::StatusCode;
+use serde_json::Value as JsonValue;
+use tracing::{debug, warn};
+
+use crate::search::{check_search_errors, format_value};
+use crate::tail::{lookup_field, TailState, MAX_HITS_PER_POLL};
+use crate::{client_args, ClientArgs};
+
+/// Delay before polling the cluster again after losing the connection to it.
+const RECONNECT_BACKOFF: Duration = Duration::from_secs(5);
+
+pub fn build_search_stream_command() -> Command {
+    Command::new("stream")
+        .about("Continuously prints the new documents of an index matching a query.")
+        .long_about("Continuously prints the documents of a time-series index matching a query as they are indexed, similar to `tail -f`. The search API does not push documents, so new documents are discovered by polling the index every `--poll-interval`. The command keeps running until interrupted with Ctrl-C or until `--max-docs` documents are printed, and reconnects to the cluster when the connection is lost.")
+        .args(client_args())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--query <QUERY> "Query expressed in natural query language ((barack AND obama) OR \"president of united states\"). Learn more on https://quickwit.io/docs/reference/search-language.")
+                .default_value("*")
+                .display_order(2)
+                .required(false),
+            arg!(--"poll-interval" <POLL_INTERVAL> "Interval between two polls of the index, expressed in a human-readable way (`500ms`, `5s`, `1m`, ...).")
+                .default_value("5s")
+                .required(false),
+            arg!(--"max-docs" <MAX_DOCS> "Stops after printing this number of documents.")
+                .required(false),
+            arg!(--"start-timestamp" <TIMESTAMP> "Prints the documents indexed with a timestamp greater than or equal to this one, expressed in seconds. Defaults to the current time.")
+                .required(false),
+            arg!(--format <FORMAT> "Output format of the documents.")
+                .value_parser(["ndjson", "pretty"])
+                .default_value("ndjson")
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StreamOutputFormat {
+    Ndjson,
+    Pretty,
+}
+
+impl FromStr for StreamOutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format_str: &str) -> anyhow::Result<Self> {
+        match format_str {
+            "ndjson" => Ok(Self::Ndjson),
+            "pretty" => Ok(Self::Pretty),
+            _ => bail!("unknown output format `{format_str}`"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SearchStreamCliCommand {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub query: String,
+    pub poll_interval: Duration,
+    pub max_docs_opt: Option<usize>,
+    pub start_timestamp_opt: Option<i64>,
+    pub format: StreamOutputFormat,
+}
+
+impl SearchStreamCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let query = matches
+            .remove_one::<String>("query")
+            .expect("`query` should have a default value.");
+        let poll_interval_str = matches
+            .remove_one::<String>("poll-interval")
+            .expect("`poll-interval` should have a default value.");
+        let poll_interval = humantime::parse_duration(&poll_interval_str)
+            .context("failed to parse `poll-interval`")?;
+        let max_docs_opt = matches
+            .remove_one::<String>("max-docs")
+            .map(|max_docs| max_docs.parse())
+            .transpose()
+            .context("failed to parse `max-docs`")?;
+        let start_timestamp_opt = matches
+            .remove_one::<String>("start-timestamp")
+            .map(|ts| ts.parse())
+            .transpose()
+            .context("failed to parse `start-timestamp`")?;
+        let format = matches
+            .remove_one::<String>("format")
+            .expect("`format` should have a default value.")
+            .parse()?;
+        let client_args = ClientArgs::parse(&mut matches)?;
+        Ok(Self {
+            client_args,
+            index_id,
+            query,
+            poll_interval,
+            max_docs_opt,
+            start_timestamp_opt,
+            format,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout();
+
+        tokio::select! {
+            stream_res = self.stream_hits(&mut stdout) => stream_res,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        }
+    }
+
+    /// Polls the index for new documents and writes them to `output` until `--max-docs`
+    /// documents are written. Documents are written in ascending timestamp order.
+    pub async fn stream_hits<W: Write>(&self, output: &mut W) -> anyhow::Result<()> {
+        debug!(args=?self, "search-stream");
+        let qw_client = self.client_args.client();
+        let index_metadata = qw_client.indexes().get(&self.index_id).await?;
+        let timestamp_field = index_metadata
+            .index_config
+            .doc_mapping
+            .timestamp_field
+            .with_context(|| {
+                format!("index `{}` does not have a timestamp field", self.index_id)
+            })?;
+        let start_timestamp = self.start_timestamp_opt.unwrap_or_else(now_timestamp_secs);
+        let mut tail_state = TailState::new(timestamp_field);
+        let mut num_docs_remaining = self.max_docs_opt.unwrap_or(usize::MAX);
+
+        while num_docs_remaining > 0 {
+            let search_request = SearchRequestQueryString {
+                query: self.query.clone(),
+                start_timestamp: Some(tail_state.start_timestamp_secs().unwrap_or(start_timestamp)),
+                max_hits: MAX_HITS_PER_POLL as u64,
+                sort_by: SortBy::from(format!("-{}", tail_state.timestamp_field)),
+                ..Default::default()
+            };
+            let search_response = match qw_client.search(&self.index_id, search_request).await {
+                Ok(search_response) => search_response,
+                Err(error) if is_disconnect_error(&error) => {
+                    warn!(error=%error, "lost connection to the cluster, retrying in {RECONNECT_BACKOFF:?}");
+                    tokio::time::sleep(RECONNECT_BACKOFF).await;
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+            check_search_errors(&search_response)?;
+
+            let is_page_full = search_response.hits.len() == MAX_HITS_PER_POLL;
+            let new_hits = tail_state.retain_new_hits(search_response.hits);
+
+            for hit in new_hits.iter().take(num_docs_remaining) {
+                match self.format {
+                    StreamOutputFormat::Ndjson => writeln!(output, "{hit}")?,
+                    StreamOutputFormat::Pretty => writeln!(
+                        output,
+                        "{}",
+                        render_pretty(hit, &tail_state.timestamp_field)
+                    )?,
+                }
+            }
+            output.flush()?;
+            num_docs_remaining = num_docs_remaining.saturating_sub(new_hits.len());
+
+            // More documents are likely waiting: fetch the next page right away.
+            if !is_page_full && num_docs_remaining > 0 {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the error is caused by the cluster being unreachable, in which case the
+/// request is worth retrying.
+fn is_disconnect_error(error: &ClientError) -> bool {
+    match error {
+        ClientError::Client(client_error) => {
+            client_error.is_connect()
+                || client_error.is_timeout()
+                || client_error.is_request()
+                || client_error.is_body()
+        }
+        ClientError::Api(api_error) => api_error.code == StatusCode::SERVICE_UNAVAILABLE,
+        _ => false,
+    }
+}
+
+/// Renders the document on a single line: the timestamp first, followed by the other fields
+/// formatted as `field=value`.
+fn render_pretty(hit: &JsonValue, timestamp_field: &str) -> String {
+    let mut line = String::new();
+
+    if let Some(timestamp) = lookup_field(hit, timestamp_field) {
+        line.push_str(&format_value(timestamp).cyan().to_string());
+    }
+    if let JsonValue::Object(fields) = hit {
+        for (field_name, field_value) in fields {
+            if field_name == timestamp_field {
+                continue;
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            let value_str = format_value(field_value)
+                .replace('\r', "\\r")
+                .replace('\n', "\\n");
+            line.push_str(&format!("{}={value_str}", field_name.bold()));
+        }
+    }
+    line
+}
+
+fn now_timestamp_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after the UNIX epoch")
+        .as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_rest_client::error::ApiError;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_render_pretty() {
+        colored::control::set_override(false);
+        let hit = json!({
+            "level": "ERROR",
+            "message": "failed to open split\nretrying",
+            "timestamp": "2024-01-01T00:00:00Z"
+        });
+        assert_eq!(
+            render_pretty(&hit, "timestamp"),
+            "2024-01-01T00:00:00Z level=ERROR message=failed to open split\\nretrying"
+        );
+        assert_eq!(
+            render_pretty(&json!({"level": "INFO"}), "timestamp"),
+            "level=INFO"
+        );
+    }
+
+    #[test]
+    fn test_is_disconnect_error() {
+        let unavailable_error = ClientError::Api(ApiError {
+            message: None,
+            code: StatusCode::SERVICE_UNAVAILABLE,
+        });
+        assert!(is_disconnect_error(&unavailable_error));
+
+        let not_found_error = ClientError::Api(ApiError {
+            message: Some("index `foo` not found".to_string()),
+            code: StatusCode::NOT_FOUND,
+        });
+        assert!(!is_disconnect_error(&not_found_error));
+        assert!(!is_disconnect_error(&ClientError::Internal(
+            "foo".to_string()
+        )));
+    }
+}
@@ -19,21 +19,26 @@
 
 use std::collections::HashSet;
 use std::str::FromStr;
+use std::time::Duration;
 
 use clap::{arg, ArgAction, ArgMatches, Command};
 use futures::future::select;
 use itertools::Itertools;
-use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::runtimes::{RuntimesConfig, ShutdownCoordinator};
 use quickwit_common::uri::{Protocol, Uri};
 use quickwit_config::service::QuickwitService;
 use quickwit_config::NodeConfig;
 use quickwit_serve::{serve_quickwit, BuildInfo, EnvFilterReloadFn};
 use quickwit_telemetry::payload::{QuickwitFeature, QuickwitTelemetryInfo, TelemetryEvent};
 use tokio::signal;
-use tracing::{debug, info};
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
 
 use crate::{config_cli_arg, get_resolvers, load_node_config, start_actor_runtimes};
 
+/// Time given to the components of the node to shut down once SIGINT or SIGTERM is received.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
 pub fn build_run_command() -> Command {
     Command::new("run")
         .about("Starts a Quickwit node.")
@@ -43,6 +48,10 @@ pub fn build_run_command() -> Command {
             arg!(--"service" <SERVICE> "Services (`indexer`, `searcher`, `metastore`, `control-plane`, or `janitor`) to run. If unspecified, all the supported services are started.")
                 .action(ArgAction::Append)
                 .required(false),
+            arg!(--"enable-grpc-reflection" "Exposes the gRPC server reflection service, which lets tools such as `grpcurl` discover the gRPC services of the node. Overrides `grpc.enable_reflection` in the node config.")
+                .required(false),
+            arg!(--"consul-token" <CONSUL_TOKEN> "ACL token used to authenticate to Consul when the metastore URI is a `consul://` URI. Overrides `metastore.consul.token` in the node config.")
+                .required(false),
         ])
 }
 
@@ -50,6 +59,8 @@ pub fn build_run_command() -> Command {
 pub struct RunCliCommand {
     pub config_uri: Uri,
     pub services: Option<HashSet<QuickwitService>>,
+    pub enable_grpc_reflection: bool,
+    pub consul_token_opt: Option<String>,
 }
 
 impl RunCliCommand {
@@ -68,9 +79,13 @@ impl RunCliCommand {
                 services
             })
             .transpose()?;
+        let enable_grpc_reflection = matches.get_flag("enable-grpc-reflection");
+        let consul_token_opt = matches.remove_one::<String>("consul-token");
         Ok(RunCliCommand {
             config_uri,
             services,
+            enable_grpc_reflection,
+            consul_token_opt,
         })
     }
 
@@ -79,6 +94,11 @@ impl RunCliCommand {
         let version_text = BuildInfo::get_version_text();
         info!("quickwit version: {version_text}");
         let mut node_config = load_node_config(&self.config_uri).await?;
+        if let Some(consul_token) = &self.consul_token_opt {
+            node_config
+                .metastore_configs
+                .set_consul_token(consul_token.clone());
+        }
         let (storage_resolver, metastore_resolver) =
             get_resolvers(&node_config.storage_configs, &node_config.metastore_configs);
         crate::busy_detector::set_enabled(true);
@@ -87,13 +107,19 @@ impl RunCliCommand {
             info!(services = %services.iter().join(", "), "setting services from override");
             node_config.enabled_services.clone_from(services);
         }
+        if self.enable_grpc_reflection {
+            node_config.grpc_config.enable_reflection = true;
+        }
         let telemetry_handle_opt =
             quickwit_telemetry::start_telemetry_loop(quickwit_telemetry_info(&node_config));
         quickwit_telemetry::send_telemetry_event(TelemetryEvent::RunCommand).await;
         // TODO move in serve quickwit?
         let runtimes_config = RuntimesConfig::default();
         start_actor_runtimes(runtimes_config, &node_config.enabled_services)?;
-        let shutdown_signal = Box::pin(async {
+        let shutdown_coordinator = ShutdownCoordinator::new();
+        let shutdown_coordinator_clone = shutdown_coordinator.clone();
+
+        tokio::spawn(async move {
             select(
                 Box::pin(async {
                     signal::ctrl_c()
@@ -108,16 +134,27 @@ impl RunCliCommand {
                 }),
             )
             .await;
+            shutdown_coordinator_clone.initiate_shutdown(Instant::now() + SHUTDOWN_GRACE_PERIOD);
         });
         let serve_result = serve_quickwit(
             node_config,
             runtimes_config,
             metastore_resolver,
             storage_resolver,
-            shutdown_signal,
+            shutdown_coordinator.clone(),
             env_filter_reload_fn,
         )
         .await;
+
+        // When the node fails to start, some components may never have been registered or stopped,
+        // so there is nothing to wait for.
+        if serve_result.is_ok()
+            && !shutdown_coordinator
+                .wait_for_completion(SHUTDOWN_GRACE_PERIOD)
+                .await
+        {
+            warn!("some components did not shut down gracefully");
+        }
         let return_code = match serve_result {
             Ok(_) => 0,
             Err(_) => 1,
@@ -141,10 +178,11 @@ fn quickwit_telemetry_info(config: &NodeConfig) -> QuickwitTelemetryInfo {
     }
     // The metastore URI is only relevant if the metastore is enabled.
     if config.is_service_enabled(QuickwitService::Metastore) {
-        let feature = if config.metastore_uri.protocol() == Protocol::PostgreSQL {
-            QuickwitFeature::PostgresqMetastore
-        } else {
-            QuickwitFeature::FileBackedMetastore
+        let feature = match config.metastore_uri.protocol() {
+            Protocol::PostgreSQL => QuickwitFeature::PostgresqMetastore,
+            Protocol::Sqlite => QuickwitFeature::SqliteMetastore,
+            Protocol::Consul => QuickwitFeature::ConsulMetastore,
+            _ => QuickwitFeature::FileBackedMetastore,
         };
         features.insert(feature);
     }
@@ -180,6 +218,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_service_run_args_enable_grpc_reflection() -> anyhow::Result<()> {
+        let command = build_cli().no_binary_name(true);
+        let matches = command.try_get_matches_from(vec![
+            "run",
+            "--config",
+            "/config.yaml",
+            "--enable-grpc-reflection",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Run(RunCliCommand {
+                enable_grpc_reflection: true,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_service_run_args_consul_token() -> anyhow::Result<()> {
+        let command = build_cli().no_binary_name(true);
+        let matches = command.try_get_matches_from(vec![
+            "run",
+            "--config",
+            "/config.yaml",
+            "--consul-token",
+            "my-acl-token",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Run(RunCliCommand {
+                consul_token_opt: Some(consul_token),
+                ..
+            })
+            if consul_token == "my-acl-token"
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_parse_service_run_args_indexer_only() -> anyhow::Result<()> {
         let command = build_cli().no_binary_name(true);
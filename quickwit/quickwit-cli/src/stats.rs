@@ -39,16 +39,23 @@ pub(crate) fn std_deviation(values: &[u64]) -> f32 {
 
 /// Return percentile of sorted values using linear interpolation.
 pub(crate) fn percentile(sorted_values: &[u64], percent: usize) -> f32 {
-    assert!(!sorted_values.is_empty());
     assert!(percent <= 100);
+    quantile(sorted_values, percent as f32 / 100f32)
+}
+
+/// Return quantile of sorted values using linear interpolation. `quantile` must belong to
+/// `[0, 1]`.
+pub(crate) fn quantile(sorted_values: &[u64], quantile: f32) -> f32 {
+    assert!(!sorted_values.is_empty());
+    assert!((0f32..=1f32).contains(&quantile));
     if sorted_values.len() == 1 {
         return sorted_values[0] as f32;
     }
-    if percent == 100 {
+    if quantile == 1f32 {
         return sorted_values[sorted_values.len() - 1] as f32;
     }
     let length = (sorted_values.len() - 1) as f32;
-    let rank = (percent as f32 / 100f32) * length;
+    let rank = quantile * length;
     let lrank = rank.floor();
     let d = rank - lrank;
     let n = lrank as usize;
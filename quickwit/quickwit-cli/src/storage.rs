@@ -0,0 +1,202 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use colored::Colorize;
+use quickwit_common::uri::Uri;
+use tracing::debug;
+
+use crate::checklist::GREEN_COLOR;
+use crate::{config_cli_arg, get_resolvers, load_node_config};
+
+pub fn build_storage_command() -> Command {
+    Command::new("storage")
+        .about("Manages files on the storages configured for the node.")
+        .arg(config_cli_arg())
+        .subcommand(
+            Command::new("copy")
+                .about("Copies a file. The copy is performed server-side whenever the storage supports it.")
+                .args(&[
+                    arg!(--source <SOURCE_URI> "URI of the file to copy, for instance `s3://my-bucket/indexes/my-index/my-split.split`.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--destination <DESTINATION_URI> "URI of the copy. The file is overwritten if it already exists.")
+                        .display_order(2)
+                        .required(true),
+                ])
+            )
+        .arg_required_else_help(true)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CopyArgs {
+    pub config_uri: Uri,
+    pub source_uri: Uri,
+    pub destination_uri: Uri,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum StorageCliCommand {
+    Copy(CopyArgs),
+}
+
+impl StorageCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let (subcommand, submatches) = matches
+            .remove_subcommand()
+            .context("failed to parse storage subcommand")?;
+        match subcommand.as_str() {
+            "copy" => Self::parse_copy_args(submatches),
+            _ => bail!("unknown storage subcommand `{subcommand}`"),
+        }
+    }
+
+    fn parse_copy_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let config_uri = matches
+            .remove_one::<String>("config")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`config` should be a required arg.")?;
+        let source_uri = matches
+            .remove_one::<String>("source")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`source` should be a required arg.")?;
+        let destination_uri = matches
+            .remove_one::<String>("destination")
+            .map(|uri_str| Uri::from_str(&uri_str))
+            .expect("`destination` should be a required arg.")?;
+        Ok(Self::Copy(CopyArgs {
+            config_uri,
+            source_uri,
+            destination_uri,
+        }))
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        match self {
+            Self::Copy(args) => copy_cli(args).await,
+        }
+    }
+}
+
+async fn copy_cli(args: CopyArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "storage-copy");
+    println!("❯ Copying file...");
+
+    let config = load_node_config(&args.config_uri).await?;
+    let (storage_resolver, _metastore_resolver) =
+        get_resolvers(&config.storage_configs, &config.metastore_configs);
+
+    if let Some(common_parent_uri) = common_parent_uri(&args.source_uri, &args.destination_uri) {
+        let storage = storage_resolver.resolve(&common_parent_uri).await?;
+        let source_path = relative_path(&common_parent_uri, &args.source_uri);
+        let destination_path = relative_path(&common_parent_uri, &args.destination_uri);
+        storage.copy(source_path, destination_path).await?;
+    } else {
+        // The files live on different storages: we have no choice but to download the file and
+        // upload it back.
+        let (source_storage_uri, source_path) = split_file_uri(&args.source_uri)?;
+        let (destination_storage_uri, destination_path) = split_file_uri(&args.destination_uri)?;
+        let source_storage = storage_resolver.resolve(&source_storage_uri).await?;
+        let destination_storage = storage_resolver.resolve(&destination_storage_uri).await?;
+        let payload = source_storage.get_all(source_path).await?;
+        destination_storage
+            .put(destination_path, Box::new(payload.to_vec()))
+            .await?;
+    }
+    println!(
+        "{} File `{}` successfully copied to `{}`.",
+        "✔".color(GREEN_COLOR),
+        args.source_uri,
+        args.destination_uri
+    );
+    Ok(())
+}
+
+/// Returns the deepest URI that is a parent of both `source_uri` and `destination_uri`, if any.
+fn common_parent_uri(source_uri: &Uri, destination_uri: &Uri) -> Option<Uri> {
+    let mut parent_uri_opt = source_uri.parent();
+
+    while let Some(parent_uri) = parent_uri_opt {
+        let parent_prefix = format!("{}/", parent_uri.as_str().trim_end_matches('/'));
+
+        if destination_uri.as_str().starts_with(&parent_prefix) {
+            return Some(parent_uri);
+        }
+        parent_uri_opt = parent_uri.parent();
+    }
+    None
+}
+
+fn relative_path<'a>(parent_uri: &Uri, uri: &'a Uri) -> &'a Path {
+    let relative_path_str = uri.as_str()[parent_uri.as_str().len()..].trim_start_matches('/');
+    Path::new(relative_path_str)
+}
+
+fn split_file_uri(uri: &Uri) -> anyhow::Result<(Uri, &Path)> {
+    let parent_uri = uri
+        .parent()
+        .with_context(|| format!("URI `{uri}` is not a valid file URI"))?;
+    let file_name = uri
+        .file_name()
+        .with_context(|| format!("URI `{uri}` is not a valid file URI"))?;
+    Ok((parent_uri, file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_parent_uri() {
+        let source_uri = Uri::for_test("s3://bucket/indexes/index-1/split-1.split");
+        let destination_uri = Uri::for_test("s3://bucket/indexes/index-2/split-1.split");
+        let common_parent_uri = common_parent_uri(&source_uri, &destination_uri).unwrap();
+        assert_eq!(common_parent_uri, "s3://bucket/indexes");
+        assert_eq!(
+            relative_path(&common_parent_uri, &source_uri),
+            Path::new("index-1/split-1.split")
+        );
+        assert_eq!(
+            relative_path(&common_parent_uri, &destination_uri),
+            Path::new("index-2/split-1.split")
+        );
+
+        let source_uri = Uri::for_test("s3://bucket/indexes/index-1/split-1.split");
+        let destination_uri = Uri::for_test("s3://bucket/indexes/index-1/split-2.split");
+        let common_parent_uri = common_parent_uri(&source_uri, &destination_uri).unwrap();
+        assert_eq!(common_parent_uri, "s3://bucket/indexes/index-1");
+
+        // The parent of an index must not match a sibling directory sharing its name as prefix.
+        let source_uri = Uri::for_test("s3://bucket/index/split-1.split");
+        let destination_uri = Uri::for_test("s3://bucket/index-2/split-1.split");
+        let common_parent_uri = common_parent_uri(&source_uri, &destination_uri).unwrap();
+        assert_eq!(common_parent_uri, "s3://bucket");
+
+        let source_uri = Uri::for_test("s3://bucket-1/split-1.split");
+        let destination_uri = Uri::for_test("s3://bucket-2/split-1.split");
+        assert!(common_parent_uri(&source_uri, &destination_uri).is_none());
+
+        let source_uri = Uri::for_test("s3://bucket/split-1.split");
+        let destination_uri = Uri::for_test("file:///data/split-1.split");
+        assert!(common_parent_uri(&source_uri, &destination_uri).is_none());
+    }
+}
@@ -0,0 +1,499 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use clap::{arg, ArgMatches, Command};
+use quickwit_datetime::{
+    parse_date_time_str, parse_timestamp, parse_timestamp_float, DateTimeInputFormat,
+};
+use quickwit_proto::types::IndexId;
+use quickwit_rest_client::rest_client::QuickwitClient;
+use quickwit_serve::{SearchRequestQueryString, SortBy};
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use crate::search::{check_search_errors, format_value};
+use crate::{client_args, ClientArgs};
+
+/// Polling interval used as long as new documents keep arriving.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Polling interval reached after repeatedly polling the index without finding new documents.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Maximum number of documents fetched by a single poll in `--follow` mode.
+pub(crate) const MAX_HITS_PER_POLL: usize = 1_000;
+
+pub fn build_tail_command() -> Command {
+    Command::new("tail")
+        .about("Prints the most recent documents of an index and optionally follows new ones.")
+        .long_about("Prints the most recent documents of a time-series index of a running Quickwit cluster, one document per line. With `--follow`, keeps polling the index for new documents until interrupted with Ctrl-C.")
+        .args(client_args())
+        .args(&[
+            arg!(--index <INDEX> "ID of the target index")
+                .visible_alias("index-id")
+                .display_order(1)
+                .required(true),
+            arg!(--query <QUERY> "Query expressed in natural query language ((barack AND obama) OR \"president of united states\"). Learn more on https://quickwit.io/docs/reference/search-language.")
+                .default_value("*")
+                .display_order(2)
+                .required(false),
+            arg!(--"max-hits" <MAX_HITS> "Number of recent documents printed before exiting or following new documents.")
+                .default_value("10")
+                .required(false),
+            arg!(--format <TEMPLATE> "Template used to render each document on a single line, for instance \"{timestamp} {level} {message}\". Placeholders refer to document fields, nested fields are separated by dots. Documents are printed as JSON by default.")
+                .required(false),
+            arg!(-f --follow "Keeps polling the index for new documents until interrupted with Ctrl-C.")
+                .required(false),
+        ])
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum TemplateSegment {
+    Literal(String),
+    Field(String),
+}
+
+/// Single-line template such as `{timestamp} {level} {message}` used to render documents.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LineTemplate {
+    segments: Vec<TemplateSegment>,
+}
+
+impl FromStr for LineTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(template_str: &str) -> anyhow::Result<Self> {
+        let mut segments = Vec::new();
+        let mut remaining = template_str;
+
+        while let Some(open_pos) = remaining.find('{') {
+            if open_pos > 0 {
+                segments.push(TemplateSegment::Literal(remaining[..open_pos].to_string()));
+            }
+            let Some(field_len) = remaining[open_pos + 1..].find('}') else {
+                bail!("unclosed placeholder in template `{template_str}`");
+            };
+            let field_name = remaining[open_pos + 1..open_pos + 1 + field_len].trim();
+
+            if field_name.is_empty() {
+                bail!("empty placeholder in template `{template_str}`");
+            }
+            segments.push(TemplateSegment::Field(field_name.to_string()));
+            remaining = &remaining[open_pos + field_len + 2..];
+        }
+        if !remaining.is_empty() {
+            segments.push(TemplateSegment::Literal(remaining.to_string()));
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl LineTemplate {
+    /// Renders the document. Missing fields are rendered as empty strings and line breaks within
+    /// field values are escaped so that each document fits on a single line.
+    fn render(&self, hit: &JsonValue) -> String {
+        let mut line = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                TemplateSegment::Literal(literal) => line.push_str(literal),
+                TemplateSegment::Field(field_path) => {
+                    if let Some(value) = lookup_field(hit, field_path) {
+                        let value_str = format_value(value)
+                            .replace('\r', "\\r")
+                            .replace('\n', "\\n");
+                        line.push_str(&value_str);
+                    }
+                }
+            }
+        }
+        line
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct TailCliCommand {
+    pub client_args: ClientArgs,
+    pub index_id: IndexId,
+    pub query: String,
+    pub max_hits: usize,
+    pub line_template_opt: Option<LineTemplate>,
+    pub follow: bool,
+}
+
+impl TailCliCommand {
+    pub fn parse_cli_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let index_id = matches
+            .remove_one::<String>("index")
+            .expect("`index` should be a required arg.");
+        let query = matches
+            .remove_one::<String>("query")
+            .expect("`query` should have a default value.");
+        let max_hits = matches
+            .remove_one::<String>("max-hits")
+            .expect("`max-hits` should have a default value.")
+            .parse()
+            .context("failed to parse `max-hits`")?;
+        let line_template_opt = matches
+            .remove_one::<String>("format")
+            .map(|template_str| template_str.parse())
+            .transpose()?;
+        let follow = matches.get_flag("follow");
+        let client_args = ClientArgs::parse(&mut matches)?;
+        Ok(Self {
+            client_args,
+            index_id,
+            query,
+            max_hits,
+            line_template_opt,
+            follow,
+        })
+    }
+
+    pub async fn execute(self) -> anyhow::Result<()> {
+        debug!(args=?self, "tail");
+        let qw_client = self.client_args.client();
+        let index_metadata = qw_client.indexes().get(&self.index_id).await?;
+        let timestamp_field = index_metadata
+            .index_config
+            .doc_mapping
+            .timestamp_field
+            .with_context(|| {
+                format!("index `{}` does not have a timestamp field", self.index_id)
+            })?;
+        let mut tail_state = TailState::new(timestamp_field);
+
+        // The most recent documents are fetched in descending order, then printed in
+        // chronological order.
+        let search_request = SearchRequestQueryString {
+            query: self.query.clone(),
+            max_hits: self.max_hits as u64,
+            sort_by: SortBy::from(tail_state.timestamp_field.clone()),
+            ..Default::default()
+        };
+        let search_response = qw_client.search(&self.index_id, search_request).await?;
+        check_search_errors(&search_response)?;
+
+        let mut hits = search_response.hits;
+        hits.reverse();
+        self.print_hits(&tail_state.retain_new_hits(hits));
+
+        if !self.follow {
+            return Ok(());
+        }
+        tokio::select! {
+            follow_res = self.follow_new_hits(&qw_client, &mut tail_state) => follow_res,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        }
+    }
+
+    /// Polls the index for documents more recent than the ones already printed. The search API
+    /// does not stream documents, so new documents are discovered by polling.
+    async fn follow_new_hits(
+        &self,
+        qw_client: &QuickwitClient,
+        tail_state: &mut TailState,
+    ) -> anyhow::Result<()> {
+        let mut poll_backoff = PollBackoff::default();
+        let mut poll_interval = MIN_POLL_INTERVAL;
+
+        loop {
+            tokio::time::sleep(poll_interval).await;
+
+            // Documents are fetched in ascending order so that no document is skipped when more
+            // than `MAX_HITS_PER_POLL` documents arrived since the last poll.
+            let search_request = SearchRequestQueryString {
+                query: self.query.clone(),
+                start_timestamp: tail_state.start_timestamp_secs(),
+                max_hits: MAX_HITS_PER_POLL as u64,
+                sort_by: SortBy::from(format!("-{}", tail_state.timestamp_field)),
+                ..Default::default()
+            };
+            let search_response = qw_client.search(&self.index_id, search_request).await?;
+            check_search_errors(&search_response)?;
+
+            let is_page_full = search_response.hits.len() == MAX_HITS_PER_POLL;
+            let new_hits = tail_state.retain_new_hits(search_response.hits);
+            self.print_hits(&new_hits);
+            poll_interval = poll_backoff.next_interval(new_hits.len(), is_page_full);
+        }
+    }
+
+    fn print_hits(&self, hits: &[JsonValue]) {
+        for hit in hits {
+            match &self.line_template_opt {
+                Some(line_template) => println!("{}", line_template.render(hit)),
+                None => println!("{hit}"),
+            }
+        }
+    }
+}
+
+/// Keeps track of the most recent timestamp printed so far to discard the documents returned
+/// again by the next poll.
+pub(crate) struct TailState {
+    pub(crate) timestamp_field: String,
+    max_timestamp_nanos_opt: Option<i64>,
+    // Documents sharing the most recent timestamp. The search API filters timestamps with a
+    // one-second granularity, so these documents are returned again by the next poll.
+    hits_at_max_timestamp: HashSet<String>,
+}
+
+impl TailState {
+    pub(crate) fn new(timestamp_field: String) -> Self {
+        Self {
+            timestamp_field,
+            max_timestamp_nanos_opt: None,
+            hits_at_max_timestamp: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn start_timestamp_secs(&self) -> Option<i64> {
+        self.max_timestamp_nanos_opt
+            .map(|max_timestamp_nanos| max_timestamp_nanos.div_euclid(1_000_000_000))
+    }
+
+    /// Retains the hits that have not been printed yet. Hits must be sorted in ascending
+    /// timestamp order. Hits without a valid timestamp are discarded.
+    pub(crate) fn retain_new_hits(&mut self, hits: Vec<JsonValue>) -> Vec<JsonValue> {
+        let mut new_hits = Vec::new();
+
+        for hit in hits {
+            let Some(timestamp_nanos) = extract_timestamp_nanos(&hit, &self.timestamp_field) else {
+                continue;
+            };
+            match self.max_timestamp_nanos_opt {
+                Some(max_timestamp_nanos) if timestamp_nanos < max_timestamp_nanos => continue,
+                Some(max_timestamp_nanos) if timestamp_nanos == max_timestamp_nanos => {
+                    if !self.hits_at_max_timestamp.insert(hit.to_string()) {
+                        continue;
+                    }
+                }
+                _ => {
+                    self.max_timestamp_nanos_opt = Some(timestamp_nanos);
+                    self.hits_at_max_timestamp.clear();
+                    self.hits_at_max_timestamp.insert(hit.to_string());
+                }
+            }
+            new_hits.push(hit);
+        }
+        new_hits
+    }
+}
+
+/// Doubles the polling interval, up to [`MAX_POLL_INTERVAL`], every time a poll does not return
+/// any new documents.
+struct PollBackoff {
+    interval: Duration,
+}
+
+impl Default for PollBackoff {
+    fn default() -> Self {
+        Self {
+            interval: MIN_POLL_INTERVAL,
+        }
+    }
+}
+
+impl PollBackoff {
+    fn next_interval(&mut self, num_new_hits: usize, is_page_full: bool) -> Duration {
+        if num_new_hits == 0 {
+            self.interval = (self.interval * 2).min(MAX_POLL_INTERVAL);
+            return self.interval;
+        }
+        self.interval = MIN_POLL_INTERVAL;
+
+        // More documents are likely waiting: fetch the next page right away.
+        if is_page_full {
+            return Duration::ZERO;
+        }
+        self.interval
+    }
+}
+
+/// Looks up a field by its name, or by its dot-separated path for nested fields.
+pub(crate) fn lookup_field<'a>(hit: &'a JsonValue, field_path: &str) -> Option<&'a JsonValue> {
+    if let Some(value) = hit.get(field_path) {
+        return Some(value);
+    }
+    field_path
+        .split('.')
+        .try_fold(hit, |value, field_name| value.get(field_name))
+}
+
+fn extract_timestamp_nanos(hit: &JsonValue, timestamp_field: &str) -> Option<i64> {
+    let date_time = match lookup_field(hit, timestamp_field)? {
+        JsonValue::String(date_time_str) => parse_date_time_str(
+            date_time_str,
+            &[
+                DateTimeInputFormat::Rfc3339,
+                DateTimeInputFormat::Iso8601,
+                DateTimeInputFormat::Timestamp,
+            ],
+        )
+        .ok()?,
+        JsonValue::Number(timestamp) => {
+            if let Some(timestamp_int) = timestamp.as_i64() {
+                parse_timestamp(timestamp_int).ok()?
+            } else {
+                parse_timestamp_float(timestamp.as_f64()?, &[DateTimeInputFormat::Timestamp])
+                    .ok()?
+            }
+        }
+        _ => return None,
+    };
+    Some(date_time.into_timestamp_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_line_template() {
+        let line_template: LineTemplate = "{timestamp} [{level}] {resource.service}: {message}"
+            .parse()
+            .unwrap();
+        let hit = json!({
+            "level": "ERROR",
+            "message": "failed to open split\nretrying",
+            "resource": {"service": "indexer"},
+            "timestamp": "2024-01-01T00:00:00Z"
+        });
+        assert_eq!(
+            line_template.render(&hit),
+            "2024-01-01T00:00:00Z [ERROR] indexer: failed to open split\\nretrying"
+        );
+        assert_eq!(
+            line_template.render(&json!({"level": "INFO"})),
+            " [INFO] : "
+        );
+
+        let line_template: LineTemplate = "no placeholder".parse().unwrap();
+        assert_eq!(line_template.render(&hit), "no placeholder");
+    }
+
+    #[test]
+    fn test_line_template_invalid() {
+        let error = "{timestamp} {level".parse::<LineTemplate>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unclosed placeholder in template `{timestamp} {level`"
+        );
+        let error = "{timestamp} { }".parse::<LineTemplate>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "empty placeholder in template `{timestamp} { }`"
+        );
+    }
+
+    #[test]
+    fn test_extract_timestamp_nanos() {
+        let hit = json!({"ts": "2024-01-01T00:00:01.5Z"});
+        assert_eq!(
+            extract_timestamp_nanos(&hit, "ts"),
+            Some(1_704_067_201_500_000_000)
+        );
+        let hit = json!({"ts": 1_704_067_201});
+        assert_eq!(
+            extract_timestamp_nanos(&hit, "ts"),
+            Some(1_704_067_201_000_000_000)
+        );
+        let hit = json!({"ts": 1_704_067_201_500i64});
+        assert_eq!(
+            extract_timestamp_nanos(&hit, "ts"),
+            Some(1_704_067_201_500_000_000)
+        );
+        let hit = json!({"attributes": {"ts": "1704067201"}});
+        assert_eq!(
+            extract_timestamp_nanos(&hit, "attributes.ts"),
+            Some(1_704_067_201_000_000_000)
+        );
+        assert_eq!(extract_timestamp_nanos(&json!({"ts": "foo"}), "ts"), None);
+        assert_eq!(extract_timestamp_nanos(&json!({}), "ts"), None);
+    }
+
+    #[test]
+    fn test_tail_state_retain_new_hits() {
+        let mut tail_state = TailState::new("ts".to_string());
+        assert_eq!(tail_state.start_timestamp_secs(), None);
+
+        let hits = vec![
+            json!({"ts": 1_704_067_200, "message": "a"}),
+            json!({"message": "no timestamp"}),
+            json!({"ts": 1_704_067_201, "message": "b"}),
+        ];
+        let new_hits = tail_state.retain_new_hits(hits);
+        assert_eq!(
+            new_hits,
+            vec![
+                json!({"ts": 1_704_067_200, "message": "a"}),
+                json!({"ts": 1_704_067_201, "message": "b"}),
+            ]
+        );
+        assert_eq!(tail_state.start_timestamp_secs(), Some(1_704_067_201));
+
+        // The next poll returns the documents of the last second again.
+        let hits = vec![
+            json!({"ts": 1_704_067_201, "message": "b"}),
+            json!({"ts": 1_704_067_201, "message": "c"}),
+            json!({"ts": 1_704_067_202, "message": "d"}),
+        ];
+        let new_hits = tail_state.retain_new_hits(hits);
+        assert_eq!(
+            new_hits,
+            vec![
+                json!({"ts": 1_704_067_201, "message": "c"}),
+                json!({"ts": 1_704_067_202, "message": "d"}),
+            ]
+        );
+        assert_eq!(tail_state.start_timestamp_secs(), Some(1_704_067_202));
+
+        let hits = vec![json!({"ts": 1_704_067_202, "message": "d"})];
+        assert!(tail_state.retain_new_hits(hits).is_empty());
+    }
+
+    #[test]
+    fn test_poll_backoff() {
+        let mut poll_backoff = PollBackoff::default();
+        assert_eq!(poll_backoff.next_interval(0, false), Duration::from_secs(4));
+        assert_eq!(poll_backoff.next_interval(0, false), Duration::from_secs(8));
+        assert_eq!(
+            poll_backoff.next_interval(0, false),
+            Duration::from_secs(16)
+        );
+        assert_eq!(poll_backoff.next_interval(0, false), MAX_POLL_INTERVAL);
+        assert_eq!(poll_backoff.next_interval(0, false), MAX_POLL_INTERVAL);
+
+        assert_eq!(poll_backoff.next_interval(3, false), MIN_POLL_INTERVAL);
+        assert_eq!(poll_backoff.next_interval(0, false), Duration::from_secs(4));
+
+        assert_eq!(
+            poll_backoff.next_interval(MAX_HITS_PER_POLL, true),
+            Duration::ZERO
+        );
+        assert_eq!(poll_backoff.next_interval(0, false), Duration::from_secs(4));
+    }
+}
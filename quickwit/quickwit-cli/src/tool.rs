@@ -33,6 +33,7 @@ use quickwit_actors::{ActorExitStatus, ActorHandle, Mailbox, Universe};
 use quickwit_cluster::{ChannelTransport, Cluster, ClusterMember, FailureDetectorConfig};
 use quickwit_common::pubsub::EventBroker;
 use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_common::uri::Uri;
 use quickwit_config::service::QuickwitService;
 use quickwit_config::{
@@ -456,7 +457,7 @@ pub async fn local_ingest_docs_cli(args: LocalIngestDocsArgs) -> anyhow::Result<
         config.node_id.clone(),
         config.data_dir_path.clone(),
         indexer_config,
-        runtimes_config.num_threads_blocking,
+        runtimes_config.num_threads_indexing,
         cluster,
         metastore,
         None,
@@ -552,6 +553,7 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
         format: BodyFormat::Json,
         sort_by,
         count_all: CountHits::CountAll,
+        suggest_corrections: false,
     };
     let search_request =
         search_request_from_api_request(vec![args.index_id], search_request_query_string)?;
@@ -588,7 +590,7 @@ pub async fn merge_cli(args: MergeArgs) -> anyhow::Result<()> {
         config.node_id,
         config.data_dir_path,
         indexer_config,
-        runtimes_config.num_threads_blocking,
+        runtimes_config.num_threads_indexing,
         cluster,
         metastore,
         None,
@@ -728,7 +730,7 @@ async fn extract_split_cli(args: ExtractSplitArgs) -> anyhow::Result<()> {
         .await?
         .deserialize_index_metadata()?;
     let index_storage = storage_resolver.resolve(index_metadata.index_uri()).await?;
-    let split_file = PathBuf::from(format!("{}.split", args.split_id));
+    let split_file = quickwit_common::split_file_path(&args.split_id);
     let split_data = index_storage.get_all(split_file.as_path()).await?;
     let (_hotcache_bytes, bundle_storage) = BundleStorage::open_from_split_data_with_owned_bytes(
         index_storage,
@@ -928,7 +930,7 @@ impl ThroughputCalculator {
     }
 }
 
-async fn create_empty_cluster(config: &NodeConfig) -> anyhow::Result<Cluster> {
+pub(crate) async fn create_empty_cluster(config: &NodeConfig) -> anyhow::Result<Cluster> {
     let self_node = ClusterMember {
         node_id: config.node_id.clone(),
         generation_id: quickwit_cluster::GenerationId::now(),
@@ -946,6 +948,7 @@ async fn create_empty_cluster(config: &NodeConfig) -> anyhow::Result<Cluster> {
         Vec::new(),
         config.gossip_interval,
         FailureDetectorConfig::default(),
+        GrpcClientInterceptor::new(&config.cluster_key),
         &ChannelTransport::default(),
     )
     .await?;
@@ -29,22 +29,27 @@ use clap::error::ErrorKind;
 use helpers::{TestEnv, TestStorageType};
 use quickwit_cli::checklist::ChecklistError;
 use quickwit_cli::cli::build_cli;
+use quickwit_cli::exporter::{run_exporter, ExporterArgs};
+use quickwit_cli::index::migrate::{migrate_index_cli, MigrateIndexArgs};
 use quickwit_cli::index::update::{update_retention_policy_cli, RetentionPolicyArgs};
 use quickwit_cli::index::{
     create_index_cli, delete_index_cli, search_index, CreateIndexArgs, DeleteIndexArgs,
     SearchIndexArgs,
 };
+use quickwit_cli::search_stream::{SearchStreamCliCommand, StreamOutputFormat};
 use quickwit_cli::tool::{
     garbage_collect_index_cli, local_ingest_docs_cli, GarbageCollectIndexArgs, LocalIngestDocsArgs,
 };
 use quickwit_cli::ClientArgs;
 use quickwit_common::fs::get_cache_directory_path;
+use quickwit_common::net::find_available_tcp_port;
 use quickwit_common::rand::append_random_suffix;
+use quickwit_common::test_utils::wait_for_server_ready;
 use quickwit_common::uri::Uri;
 use quickwit_config::{RetentionPolicy, SourceInputFormat, CLI_SOURCE_ID};
 use quickwit_metastore::{
-    ListSplitsRequestExt, MetastoreResolver, MetastoreServiceExt, MetastoreServiceStreamSplitsExt,
-    SplitMetadata, SplitState, StageSplitsRequestExt,
+    IndexMetadataResponseExt, ListSplitsRequestExt, MetastoreResolver, MetastoreServiceExt,
+    MetastoreServiceStreamSplitsExt, SplitMetadata, SplitState, StageSplitsRequestExt,
 };
 use quickwit_proto::metastore::{
     DeleteSplitsRequest, EntityKind, IndexMetadataRequest, ListSplitsRequest,
@@ -534,6 +539,56 @@ async fn test_search_index_cli() {
     assert_eq!(search_res.num_hits, 0);
 }
 
+#[tokio::test]
+async fn test_search_stream_cli() {
+    quickwit_common::setup_logging_for_tests();
+    let index_id = append_random_suffix("test-search-stream-cmd");
+    let test_env = create_test_env(index_id.clone(), TestStorageType::LocalFileSystem)
+        .await
+        .unwrap();
+    test_env.start_server().await.unwrap();
+    create_logs_index(&test_env).await.unwrap();
+
+    local_ingest_docs(test_env.resource_files["logs"].as_path(), &test_env)
+        .await
+        .unwrap();
+
+    let create_search_stream_command =
+        |query: &str, start_timestamp: i64, max_docs: usize| SearchStreamCliCommand {
+            client_args: ClientArgs {
+                cluster_endpoint: test_env.cluster_endpoint.clone(),
+                ..Default::default()
+            },
+            index_id: index_id.clone(),
+            query: query.to_string(),
+            poll_interval: Duration::from_millis(100),
+            max_docs_opt: Some(max_docs),
+            start_timestamp_opt: Some(start_timestamp),
+            format: StreamOutputFormat::Ndjson,
+        };
+    let stream_events = |command: SearchStreamCliCommand| async move {
+        let mut output = Vec::new();
+        tokio::time::timeout(Duration::from_secs(30), command.stream_hits(&mut output))
+            .await
+            .unwrap()
+            .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let doc: Value = serde_json::from_str(line).unwrap();
+                doc["event"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+    // Documents are printed in ascending timestamp order.
+    let command = create_search_stream_command("level:info OR level:error", 0, 2);
+    assert_eq!(stream_events(command).await, ["foo", "bar"]);
+
+    let command = create_search_stream_command("city:paris", 72057600, 2);
+    assert_eq!(stream_events(command).await, ["buz", "biz"]);
+}
+
 #[tokio::test]
 async fn test_cmd_update_index() {
     quickwit_common::setup_logging_for_tests();
@@ -544,6 +599,23 @@ async fn test_cmd_update_index() {
     test_env.start_server().await.unwrap();
     create_logs_index(&test_env).await.unwrap();
 
+    // dry run does not apply the policy
+    update_retention_policy_cli(RetentionPolicyArgs {
+        index_id: index_id.clone(),
+        client_args: ClientArgs {
+            cluster_endpoint: test_env.cluster_endpoint.clone(),
+            ..Default::default()
+        },
+        disable: false,
+        period: Some(String::from("1 week")),
+        schedule: Some(String::from("daily")),
+        dry_run: true,
+    })
+    .await
+    .unwrap();
+    let index_metadata = test_env.index_metadata().await.unwrap();
+    assert_eq!(index_metadata.index_config.retention_policy_opt, None);
+
     // add a policy
     update_retention_policy_cli(RetentionPolicyArgs {
         index_id: index_id.clone(),
@@ -554,6 +626,7 @@ async fn test_cmd_update_index() {
         disable: false,
         period: Some(String::from("1 week")),
         schedule: Some(String::from("daily")),
+        dry_run: false,
     })
     .await
     .unwrap();
@@ -562,7 +635,8 @@ async fn test_cmd_update_index() {
         index_metadata.index_config.retention_policy_opt,
         Some(RetentionPolicy {
             retention_period: String::from("1 week"),
-            evaluation_schedule: String::from("daily")
+            evaluation_schedule: String::from("daily"),
+            max_size: None,
         })
     );
 
@@ -576,6 +650,7 @@ async fn test_cmd_update_index() {
         disable: true,
         period: Some(String::from("a week")),
         schedule: Some(String::from("daily")),
+        dry_run: false,
     })
     .await
     .unwrap_err();
@@ -590,6 +665,7 @@ async fn test_cmd_update_index() {
         disable: true,
         period: None,
         schedule: None,
+        dry_run: false,
     })
     .await
     .unwrap();
@@ -949,6 +1025,162 @@ async fn test_garbage_collect_index_cli() {
     assert_eq!(split_path.try_exists().unwrap(), false);
 }
 
+#[tokio::test]
+async fn test_migrate_index_cli() {
+    quickwit_common::setup_logging_for_tests();
+    let index_id = append_random_suffix("test-migrate-cmd");
+    let test_env = create_test_env(index_id.clone(), TestStorageType::LocalFileSystem)
+        .await
+        .unwrap();
+    test_env.start_server().await.unwrap();
+    create_logs_index(&test_env).await.unwrap();
+    local_ingest_docs(test_env.resource_files["logs"].as_path(), &test_env)
+        .await
+        .unwrap();
+
+    let index_uid = test_env.index_metadata().await.unwrap().index_uid;
+    let split_ids = MetastoreResolver::unconfigured()
+        .resolve(&test_env.metastore_uri)
+        .await
+        .unwrap()
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+        .await
+        .unwrap()
+        .collect_split_ids()
+        .await
+        .unwrap();
+    assert_eq!(split_ids.len(), 1);
+    let split_filename = quickwit_common::split_file(&split_ids[0]);
+
+    let source_split_path = test_env
+        .indexes_dir_path
+        .join(&test_env.index_id)
+        .join(&split_filename);
+    let dest_dir_path = test_env.data_dir_path.join("migrated").join(&index_id);
+    let dest_split_path = dest_dir_path.join(&split_filename);
+    let dest_storage_uri = Uri::from_str(&format!("file://{}", dest_dir_path.display())).unwrap();
+
+    // The file-backed metastore caches the index metadata, so we resolve a new instance to read
+    // the updates made by the command.
+    let index_uri = || async {
+        MetastoreResolver::unconfigured()
+            .resolve(&test_env.metastore_uri)
+            .await
+            .unwrap()
+            .index_metadata(IndexMetadataRequest::for_index_id(index_id.clone()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap()
+            .index_uri()
+            .clone()
+    };
+    let create_migrate_args = |dry_run| MigrateIndexArgs {
+        config_uri: test_env.config_uri.clone(),
+        index_id: index_id.clone(),
+        dest_storage_uri: dest_storage_uri.clone(),
+        concurrency: 2,
+        dry_run,
+        delete_source: true,
+        assume_yes: true,
+    };
+    migrate_index_cli(create_migrate_args(true)).await.unwrap();
+
+    assert_eq!(dest_split_path.try_exists().unwrap(), false);
+    assert_eq!(index_uri().await, test_env.index_uri);
+
+    migrate_index_cli(create_migrate_args(false)).await.unwrap();
+
+    assert_eq!(dest_split_path.try_exists().unwrap(), true);
+    assert_eq!(source_split_path.try_exists().unwrap(), false);
+    assert_eq!(index_uri().await, dest_storage_uri);
+
+    // The index already lives in the destination storage.
+    migrate_index_cli(create_migrate_args(false))
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn test_exporter_cli() {
+    quickwit_common::setup_logging_for_tests();
+    let index_id = append_random_suffix("test-exporter");
+    let test_env = create_test_env(index_id.clone(), TestStorageType::LocalFileSystem)
+        .await
+        .unwrap();
+    test_env.start_server().await.unwrap();
+    create_logs_index(&test_env).await.unwrap();
+    local_ingest_docs(test_env.resource_files["logs"].as_path(), &test_env)
+        .await
+        .unwrap();
+
+    let index_uid = test_env.index_metadata().await.unwrap().index_uid;
+    let splits_metadata: Vec<SplitMetadata> = test_env
+        .metastore()
+        .await
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+        .await
+        .unwrap()
+        .collect_splits_metadata()
+        .await
+        .unwrap();
+    assert_eq!(splits_metadata.len(), 1);
+
+    let listen_port = find_available_tcp_port().unwrap();
+    let args = ExporterArgs {
+        metastore_uri: test_env.metastore_uri.clone(),
+        listen_port,
+        poll_interval: Duration::from_millis(100),
+    };
+    tokio::spawn(run_exporter(args));
+    wait_for_server_ready(([127, 0, 0, 1], listen_port).into())
+        .await
+        .unwrap();
+
+    let metrics_url = format!("http://127.0.0.1:{listen_port}/metrics");
+    let documents_total_line =
+        format!("quickwit_exporter_documents_total{{index=\"{index_id}\"}} 5");
+    let mut metrics = String::new();
+
+    // The first poll of the metastore may not have completed yet.
+    for _ in 0..50 {
+        metrics = reqwest::get(&metrics_url)
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        if metrics.contains(&documents_total_line) {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert!(metrics.contains(&documents_total_line), "{metrics}");
+
+    for (split_state, num_splits) in [("Staged", 0), ("Published", 1), ("MarkedForDeletion", 0)] {
+        let splits_total_line = format!(
+            "quickwit_exporter_splits_total{{index=\"{index_id}\",state=\"{split_state}\"}} \
+             {num_splits}"
+        );
+        assert!(metrics.contains(&splits_total_line), "{metrics}");
+    }
+    let index_size_bytes_line = format!(
+        "quickwit_exporter_index_size_bytes{{index=\"{index_id}\"}} {}",
+        splits_metadata[0].size_in_bytes
+    );
+    assert!(metrics.contains(&index_size_bytes_line), "{metrics}");
+
+    let oldest_split_age_prefix =
+        format!("quickwit_exporter_oldest_split_age_seconds{{index=\"{index_id}\"}} ");
+    let oldest_split_age_seconds: i64 = metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(&oldest_split_age_prefix))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((0..60).contains(&oldest_split_age_seconds));
+}
+
 /// testing the api via cli commands
 #[tokio::test]
 async fn test_all_local_index() {
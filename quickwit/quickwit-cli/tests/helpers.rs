@@ -157,6 +157,8 @@ impl TestEnv {
         let run_command = RunCliCommand {
             config_uri: self.config_uri.clone(),
             services: Some(QuickwitService::supported_services()),
+            enable_grpc_reflection: false,
+            consul_token_opt: None,
         };
         tokio::spawn(async move {
             if let Err(error) = run_command
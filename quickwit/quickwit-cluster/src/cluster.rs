@@ -31,9 +31,11 @@ use chitchat::{
     FailureDetectorConfig, KeyChangeEvent, ListenerHandle, NodeState,
 };
 use itertools::Itertools;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_proto::indexing::{IndexingPipelineId, IndexingTask, PipelineMetrics};
 use quickwit_proto::types::{NodeId, NodeIdRef, PipelineUid, ShardId};
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use tokio::sync::{mpsc, watch, Mutex, RwLock};
 use tokio::time::timeout;
 use tokio_stream::wrappers::WatchStream;
@@ -43,9 +45,9 @@ use tracing::{info, warn};
 use crate::change::{compute_cluster_change_events, ClusterChange, ClusterChangeStreamFactory};
 use crate::grpc_gossip::spawn_catchup_callback_task;
 use crate::member::{
-    build_cluster_member, ClusterMember, NodeStateExt, ENABLED_SERVICES_KEY,
-    GRPC_ADVERTISE_ADDR_KEY, PIPELINE_METRICS_PREFIX, READINESS_KEY, READINESS_VALUE_NOT_READY,
-    READINESS_VALUE_READY,
+    build_cluster_member, ClusterMember, MemberStatus, MembershipEntry, NodeStateExt, DRAINING_KEY,
+    ENABLED_SERVICES_KEY, GRPC_ADVERTISE_ADDR_KEY, PIPELINE_METRICS_PREFIX, READINESS_KEY,
+    READINESS_VALUE_NOT_READY, READINESS_VALUE_READY,
 };
 use crate::metrics::spawn_metrics_task;
 use crate::{ClusterChangeStream, ClusterNode};
@@ -67,6 +69,8 @@ pub struct Cluster {
     /// Socket address (UDP) the node listens on for receiving gossip messages.
     pub gossip_listen_addr: SocketAddr,
     gossip_interval: Duration,
+    /// Interceptor applied to the requests sent by the gRPC clients to the other nodes.
+    grpc_client_interceptor: GrpcClientInterceptor,
     inner: Arc<RwLock<InnerCluster>>,
 }
 
@@ -114,6 +118,7 @@ impl Cluster {
         peer_seed_addrs: Vec<String>,
         gossip_interval: Duration,
         failure_detector_config: FailureDetectorConfig,
+        grpc_client_interceptor: GrpcClientInterceptor,
         transport: &dyn Transport,
     ) -> anyhow::Result<Self> {
         info!(
@@ -185,6 +190,7 @@ impl Cluster {
             weak_chitchat,
             live_nodes_rx,
             catchup_callback_rx.clone(),
+            grpc_client_interceptor.clone(),
         )
         .await;
 
@@ -195,18 +201,26 @@ impl Cluster {
             live_nodes: BTreeMap::new(),
             change_stream_subscribers: Vec::new(),
             ready_members_rx,
+            last_seen: HashMap::new(),
         };
         let cluster = Cluster {
             cluster_id,
             self_chitchat_id: self_node.chitchat_id(),
             gossip_listen_addr,
             gossip_interval,
+            grpc_client_interceptor,
             inner: Arc::new(RwLock::new(inner)),
         };
         spawn_change_stream_task(cluster.clone()).await;
         Ok(cluster)
     }
 
+    /// Returns the interceptor to apply to the gRPC clients connecting to the other nodes of the
+    /// cluster.
+    pub fn grpc_client_interceptor(&self) -> GrpcClientInterceptor {
+        self.grpc_client_interceptor.clone()
+    }
+
     /// Deprecated: this is going away soon.
     pub async fn ready_members(&self) -> Vec<ClusterMember> {
         self.inner.read().await.ready_members_rx.borrow().clone()
@@ -270,6 +284,12 @@ impl Cluster {
             .await
     }
 
+    /// Flags the self node as draining. The flag is gossiped to the other members of the cluster
+    /// and cannot be unset.
+    pub async fn set_self_node_draining(&self) {
+        self.set_self_key_value(DRAINING_KEY, true).await
+    }
+
     /// Sets a key-value pair on the cluster node's state.
     pub async fn set_self_key_value(&self, key: impl Display, value: impl Display) {
         self.chitchat()
@@ -376,6 +396,69 @@ impl Cluster {
         }
     }
 
+    /// Returns the live and dead members of the cluster, sorted by node ID and generation ID.
+    /// Members whose state cannot be parsed are omitted.
+    pub async fn members(&self) -> Vec<MembershipEntry> {
+        let chitchat = self.chitchat().await;
+        let chitchat_guard = chitchat.lock().await;
+        let live_chitchat_ids: Vec<ChitchatId> = chitchat_guard.live_nodes().cloned().collect();
+        let dead_chitchat_ids: Vec<ChitchatId> = chitchat_guard.dead_nodes().cloned().collect();
+
+        let mut members = Vec::with_capacity(live_chitchat_ids.len() + dead_chitchat_ids.len());
+
+        for (chitchat_id, is_dead) in live_chitchat_ids
+            .iter()
+            .map(|chitchat_id| (chitchat_id, false))
+            .chain(
+                dead_chitchat_ids
+                    .iter()
+                    .map(|chitchat_id| (chitchat_id, true)),
+            )
+        {
+            let Some(node_state) = chitchat_guard.node_state(chitchat_id) else {
+                continue;
+            };
+            let status = if is_dead {
+                MemberStatus::Dead
+            } else if node_state.is_draining() {
+                MemberStatus::Draining
+            } else {
+                MemberStatus::Alive
+            };
+            let Ok(member) = build_cluster_member(chitchat_id.clone(), node_state) else {
+                continue;
+            };
+            members.push((member, status));
+        }
+        drop(chitchat_guard);
+
+        let now = OffsetDateTime::now_utc();
+        let mut inner = self.inner.write().await;
+
+        // The failure detector considers live nodes as having recently heartbeated.
+        for chitchat_id in &live_chitchat_ids {
+            inner.last_seen.insert(chitchat_id.clone(), now);
+        }
+        inner.last_seen.retain(|chitchat_id, _| {
+            live_chitchat_ids.contains(chitchat_id) || dead_chitchat_ids.contains(chitchat_id)
+        });
+        members
+            .into_iter()
+            .map(|(member, status)| {
+                let last_seen = inner.last_seen.get(&member.chitchat_id()).copied();
+                MembershipEntry {
+                    member,
+                    status,
+                    last_seen,
+                }
+            })
+            .sorted_by(|left, right| {
+                (&left.member.node_id, left.member.generation_id.as_u64())
+                    .cmp(&(&right.member.node_id, right.member.generation_id.as_u64()))
+            })
+            .collect()
+    }
+
     /// Leaves the cluster.
     pub async fn shutdown(self) {
         info!(
@@ -564,6 +647,11 @@ async fn spawn_change_stream_task(cluster: Cluster) {
                 break;
             };
             let mut cluster_guard = cluster.write().await;
+            let now = OffsetDateTime::now_utc();
+
+            for chitchat_id in new_live_node_states.keys() {
+                cluster_guard.last_seen.insert(chitchat_id.clone(), now);
+            }
             let previous_live_nodes = &mut cluster_guard.live_nodes;
 
             let events = compute_cluster_change_events(
@@ -596,6 +684,9 @@ struct InnerCluster {
     live_nodes: BTreeMap<NodeId, ClusterNode>,
     change_stream_subscribers: Vec<mpsc::UnboundedSender<ClusterChange>>,
     ready_members_rx: watch::Receiver<Vec<ClusterMember>>,
+    // Last time each node was observed live. Entries are pruned once Chitchat garbage collects
+    // the corresponding dead node.
+    last_seen: HashMap<ChitchatId, OffsetDateTime>,
 }
 
 // Not used within the code, used for documentation.
@@ -687,6 +778,7 @@ pub async fn create_cluster_for_test_with_id(
         peer_seed_addrs,
         Duration::from_millis(25),
         failure_detector_config,
+        GrpcClientInterceptor::default(),
         transport,
     )
     .await?;
@@ -925,6 +1017,104 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_cluster_members() {
+        let transport = ChannelTransport::default();
+        let node_1 = create_cluster_for_test(Vec::new(), &["indexer"], &transport, true)
+            .await
+            .unwrap();
+        let peer_seeds = vec![node_1.gossip_listen_addr.to_string()];
+        let node_2 = create_cluster_for_test(peer_seeds.clone(), &["searcher"], &transport, true)
+            .await
+            .unwrap();
+        let node_3 = create_cluster_for_test(peer_seeds, &["searcher"], &transport, true)
+            .await
+            .unwrap();
+
+        let wait_secs = Duration::from_secs(10);
+
+        node_1
+            .wait_for_ready_members(|members| members.len() == 3, wait_secs)
+            .await
+            .unwrap();
+
+        let members = node_1.members().await;
+        assert_eq!(members.len(), 3);
+
+        for member in &members {
+            assert_eq!(member.status, MemberStatus::Alive);
+            assert!(member.last_seen.is_some());
+        }
+        let expected_node_ids = [
+            node_1.self_node_id().as_str(),
+            node_2.self_node_id().as_str(),
+            node_3.self_node_id().as_str(),
+        ]
+        .into_iter()
+        .sorted()
+        .collect::<Vec<_>>();
+        let node_ids = members
+            .iter()
+            .map(|member| member.member.node_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(node_ids, expected_node_ids);
+
+        let node_1_member = members
+            .iter()
+            .find(|member| member.member.node_id == node_1.self_node_id())
+            .unwrap();
+        assert_eq!(
+            node_1_member.member.enabled_services,
+            HashSet::from_iter([QuickwitService::Indexer])
+        );
+
+        node_2.set_self_node_draining().await;
+
+        let node_1_clone = node_1.clone();
+        let node_2_id = node_2.self_node_id().as_str().to_string();
+        wait_until_predicate(
+            move || {
+                let node_1 = node_1_clone.clone();
+                let node_2_id = node_2_id.clone();
+                async move {
+                    node_1.members().await.iter().any(|member| {
+                        member.member.node_id == node_2_id
+                            && member.status == MemberStatus::Draining
+                    })
+                }
+            },
+            wait_secs,
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+
+        let node_3_id = node_3.self_node_id().as_str().to_string();
+        node_3.shutdown().await;
+
+        let node_1_clone = node_1.clone();
+        wait_until_predicate(
+            move || {
+                let node_1 = node_1_clone.clone();
+                let node_3_id = node_3_id.clone();
+                async move {
+                    node_1.members().await.iter().any(|member| {
+                        member.member.node_id == node_3_id
+                            && member.status == MemberStatus::Dead
+                            && member.last_seen.is_some()
+                    })
+                }
+            },
+            wait_secs,
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+
+        node_1.shutdown().await;
+        node_2.shutdown().await;
+    }
+
     #[tokio::test]
     async fn test_cluster_members_built_from_chitchat_state() {
         let transport = ChannelTransport::default();
@@ -26,6 +26,7 @@ use std::time::{Duration, Instant};
 use chitchat::{Chitchat, ChitchatId, NodeState, VersionedValue};
 use futures::Future;
 use quickwit_common::pretty::PrettyDisplay;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_proto::cluster::{ClusterService, ClusterServiceClient, FetchClusterStateRequest};
 use rand::seq::IteratorRandom;
 use tokio::sync::{watch, Mutex};
@@ -46,6 +47,7 @@ pub(crate) async fn spawn_catchup_callback_task(
     weak_chitchat: Weak<Mutex<Chitchat>>,
     live_nodes_rx: watch::Receiver<BTreeMap<ChitchatId, NodeState>>,
     mut catchup_callback_rx: watch::Receiver<()>,
+    grpc_client_interceptor: GrpcClientInterceptor,
 ) {
     let catchup_callback_future = async move {
         let mut interval = tokio::time::interval(Duration::from_secs(60));
@@ -55,12 +57,15 @@ pub(crate) async fn spawn_catchup_callback_task(
             let Some(chitchat) = weak_chitchat.upgrade() else {
                 return;
             };
+            let grpc_client_interceptor = grpc_client_interceptor.clone();
             perform_grpc_gossip_rounds(
                 cluster_id.clone(),
                 &self_chitchat_id,
                 chitchat,
                 live_nodes_rx.clone(),
-                cluster_grpc_client,
+                move |socket_addr| {
+                    cluster_grpc_client(socket_addr, grpc_client_interceptor.clone())
+                },
             )
             .await;
 
@@ -22,7 +22,7 @@ use std::net::SocketAddr;
 use bytesize::ByteSize;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use quickwit_common::tower::{make_channel, GrpcMetricsLayer};
+use quickwit_common::tower::{make_channel, GrpcClientInterceptor, GrpcMetricsLayer};
 use quickwit_proto::cluster::cluster_service_grpc_server::ClusterServiceGrpcServer;
 use quickwit_proto::cluster::{
     ChitchatId as ProtoChitchatId, ClusterError, ClusterResult, ClusterService,
@@ -40,12 +40,20 @@ static CLUSTER_GRPC_CLIENT_METRICS_LAYER: Lazy<GrpcMetricsLayer> =
 static CLUSTER_GRPC_SERVER_METRICS_LAYER: Lazy<GrpcMetricsLayer> =
     Lazy::new(|| GrpcMetricsLayer::new("cluster", "server"));
 
-pub(crate) async fn cluster_grpc_client(socket_addr: SocketAddr) -> ClusterServiceClient {
+pub(crate) async fn cluster_grpc_client(
+    socket_addr: SocketAddr,
+    grpc_client_interceptor: GrpcClientInterceptor,
+) -> ClusterServiceClient {
     let channel = make_channel(socket_addr).await;
 
     ClusterServiceClient::tower()
         .stack_layer(CLUSTER_GRPC_CLIENT_METRICS_LAYER.clone())
-        .build_from_channel(socket_addr, channel, MAX_MESSAGE_SIZE)
+        .build_from_channel(
+            socket_addr,
+            channel,
+            MAX_MESSAGE_SIZE,
+            grpc_client_interceptor,
+        )
 }
 
 pub fn cluster_grpc_server(
@@ -37,6 +37,7 @@ use chitchat::{ChitchatMessage, Serializable};
 pub use chitchat::{FailureDetectorConfig, KeyChangeEvent, ListenerHandle};
 pub use grpc_service::cluster_grpc_server;
 use quickwit_common::metrics::IntCounter;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_config::service::QuickwitService;
 use quickwit_config::NodeConfig;
 use quickwit_proto::indexing::CpuCapacity;
@@ -50,7 +51,7 @@ pub use crate::cluster::{
     create_cluster_for_test, create_cluster_for_test_with_id, grpc_addr_from_listen_addr_for_test,
 };
 pub use crate::cluster::{Cluster, ClusterSnapshot, NodeIdSchema};
-pub use crate::member::{ClusterMember, INDEXING_CPU_CAPACITY_KEY};
+pub use crate::member::{ClusterMember, MemberStatus, MembershipEntry, INDEXING_CPU_CAPACITY_KEY};
 pub use crate::node::ClusterNode;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -158,6 +159,7 @@ pub async fn start_cluster_service(node_config: &NodeConfig) -> anyhow::Result<C
         peer_seed_addrs,
         node_config.gossip_interval,
         failure_detector_config,
+        GrpcClientInterceptor::new(&node_config.cluster_key),
         &CountingUdpTransport,
     )
     .await?;
@@ -26,6 +26,8 @@ use anyhow::Context;
 use chitchat::{ChitchatId, NodeState, Version};
 use quickwit_proto::indexing::{CpuCapacity, IndexingTask};
 use quickwit_proto::types::NodeId;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use tracing::{error, warn};
 
 use crate::cluster::parse_indexing_tasks;
@@ -41,6 +43,9 @@ pub(crate) const READINESS_KEY: &str = "readiness";
 pub(crate) const READINESS_VALUE_READY: &str = "READY";
 pub(crate) const READINESS_VALUE_NOT_READY: &str = "NOT_READY";
 
+// Key set by a node once it starts shutting down and draining in-flight requests.
+pub(crate) const DRAINING_KEY: &str = "draining";
+
 pub const INDEXING_CPU_CAPACITY_KEY: &str = "indexing_cpu_capacity";
 
 pub(crate) trait NodeStateExt {
@@ -48,6 +53,8 @@ pub(crate) trait NodeStateExt {
 
     fn is_ready(&self) -> bool;
 
+    fn is_draining(&self) -> bool;
+
     fn size_bytes(&self) -> usize;
 }
 
@@ -70,6 +77,10 @@ impl NodeStateExt for NodeState {
             .unwrap_or(false)
     }
 
+    fn is_draining(&self) -> bool {
+        self.get(DRAINING_KEY).is_some()
+    }
+
     // TODO: Expose more accurate size of the state in Chitchat.
     fn size_bytes(&self) -> usize {
         const SIZE_OF_VERSION: usize = size_of::<Version>();
@@ -118,6 +129,38 @@ impl ClusterMember {
     }
 }
 
+/// Liveness status of a cluster member, as observed through gossip.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberStatus {
+    /// The member is live according to the failure detector.
+    Alive,
+    /// The member is live but shutting down.
+    Draining,
+    /// The member is flagged as dead or faulty by the failure detector.
+    Dead,
+}
+
+impl MemberStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemberStatus::Alive => "alive",
+            MemberStatus::Draining => "draining",
+            MemberStatus::Dead => "dead",
+        }
+    }
+}
+
+/// A cluster member along with its liveness status.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MembershipEntry {
+    pub member: ClusterMember,
+    pub status: MemberStatus,
+    /// Last time the member was observed live by this node. `None` if the member has never been
+    /// observed live since this node joined the cluster.
+    pub last_seen: Option<OffsetDateTime>,
+}
+
 impl From<ClusterMember> for ChitchatId {
     fn from(member: ClusterMember) -> Self {
         member.chitchat_id()
@@ -118,11 +118,15 @@ impl HelloClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = hello_grpc_client::HelloGrpcClient::new(channel)
+        let client = hello_grpc_client::HelloGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = HelloGrpcClientAdapter::new(client, connection_keys_watcher);
@@ -131,9 +135,13 @@ impl HelloClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> HelloClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = hello_grpc_client::HelloGrpcClient::new(balance_channel)
+        let client = hello_grpc_client::HelloGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = HelloGrpcClientAdapter::new(client, connection_keys_watcher);
@@ -522,19 +530,32 @@ impl HelloTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> HelloClient {
         self.build_from_boxed(
-            Box::new(HelloClient::from_channel(addr, channel, max_message_size)),
+            Box::new(
+                HelloClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
+            ),
         )
     }
     pub fn build_from_balance_channel(
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> HelloClient {
         self.build_from_boxed(
             Box::new(
-                HelloClient::from_balance_channel(balance_channel, max_message_size),
+                HelloClient::from_balance_channel(
+                    balance_channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
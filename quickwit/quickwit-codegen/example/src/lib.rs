@@ -164,7 +164,7 @@ mod tests {
 
     use bytesize::ByteSize;
     use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Universe};
-    use quickwit_common::tower::{BalanceChannel, Change};
+    use quickwit_common::tower::{BalanceChannel, Change, GrpcClientInterceptor};
     use tokio::sync::mpsc::error::TrySendError;
     use tokio_stream::StreamExt;
     use tonic::transport::{Endpoint, Server};
@@ -283,7 +283,11 @@ mod tests {
             "127.0.0.1:6666".parse().unwrap(),
             Endpoint::from_static("http://127.0.0.1:6666").connect_lazy(),
         );
-        let mut grpc_client = HelloClient::from_balance_channel(channel, MAX_GRPC_MESSAGE_SIZE);
+        let mut grpc_client = HelloClient::from_balance_channel(
+            channel,
+            MAX_GRPC_MESSAGE_SIZE,
+            GrpcClientInterceptor::default(),
+        );
 
         assert_eq!(
             grpc_client
@@ -342,8 +346,11 @@ mod tests {
 
         // The connectivity check fails if there is no client behind the channel.
         let (balance_channel, _): (BalanceChannel<SocketAddr>, _) = BalanceChannel::new();
-        let mut grpc_client =
-            HelloClient::from_balance_channel(balance_channel, MAX_GRPC_MESSAGE_SIZE);
+        let mut grpc_client = HelloClient::from_balance_channel(
+            balance_channel,
+            MAX_GRPC_MESSAGE_SIZE,
+            GrpcClientInterceptor::default(),
+        );
         assert_eq!(
             grpc_client
                 .check_connectivity()
@@ -647,7 +654,11 @@ mod tests {
             "127.0.0.1:7777".parse().unwrap(),
             Endpoint::from_static("http://127.0.0.1:7777").connect_lazy(),
         );
-        HelloClient::from_balance_channel(balance_channed, MAX_GRPC_MESSAGE_SIZE);
+        HelloClient::from_balance_channel(
+            balance_channed,
+            MAX_GRPC_MESSAGE_SIZE,
+            GrpcClientInterceptor::default(),
+        );
     }
 
     #[tokio::test]
@@ -735,7 +746,12 @@ mod tests {
             .timeout(Duration::from_millis(100))
             .connect_lazy();
         let max_message_size = ByteSize::mib(1);
-        let mut grpc_client = HelloClient::from_channel(addr, channel, max_message_size);
+        let mut grpc_client = HelloClient::from_channel(
+            addr,
+            channel,
+            max_message_size,
+            GrpcClientInterceptor::default(),
+        );
 
         let error = grpc_client
             .hello(HelloRequest {
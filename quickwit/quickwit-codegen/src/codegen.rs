@@ -571,20 +571,20 @@ fn generate_client(context: &CodegenContext) -> TokenStream {
                     .max_encoding_message_size(max_message_size.0 as usize)
             }
 
-            pub fn from_channel(addr: std::net::SocketAddr, channel: tonic::transport::Channel, max_message_size: bytesize::ByteSize) -> Self
+            pub fn from_channel(addr: std::net::SocketAddr, channel: tonic::transport::Channel, max_message_size: bytesize::ByteSize, grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor) -> Self
             {
                 let (_, connection_keys_watcher) = tokio::sync::watch::channel(std::collections::HashSet::from_iter([addr]));
-                let client = #grpc_client_package_name::#grpc_client_name::new(channel)
+                let client = #grpc_client_package_name::#grpc_client_name::with_interceptor(channel, grpc_client_interceptor)
                     .max_decoding_message_size(max_message_size.0 as usize)
                     .max_encoding_message_size(max_message_size.0 as usize);
                 let adapter = #grpc_client_adapter_name::new(client, connection_keys_watcher);
                 Self::new(adapter)
             }
 
-            pub fn from_balance_channel(balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>, max_message_size: bytesize::ByteSize) -> #client_name
+            pub fn from_balance_channel(balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>, max_message_size: bytesize::ByteSize, grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor) -> #client_name
             {
                 let connection_keys_watcher = balance_channel.connection_keys_watcher();
-                let client = #grpc_client_package_name::#grpc_client_name::new(balance_channel)
+                let client = #grpc_client_package_name::#grpc_client_name::with_interceptor(balance_channel, grpc_client_interceptor)
                     .max_decoding_message_size(max_message_size.0 as usize)
                     .max_encoding_message_size(max_message_size.0 as usize);
                 let adapter = #grpc_client_adapter_name::new(client, connection_keys_watcher);
@@ -930,14 +930,14 @@ fn generate_layer_stack_impl(context: &CodegenContext) -> TokenStream {
                 self.build_from_boxed(Box::new(instance))
             }
 
-            pub fn build_from_channel(self, addr: std::net::SocketAddr, channel: tonic::transport::Channel, max_message_size: bytesize::ByteSize) -> #client_name
+            pub fn build_from_channel(self, addr: std::net::SocketAddr, channel: tonic::transport::Channel, max_message_size: bytesize::ByteSize, grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor) -> #client_name
             {
-                self.build_from_boxed(Box::new(#client_name::from_channel(addr, channel, max_message_size)))
+                self.build_from_boxed(Box::new(#client_name::from_channel(addr, channel, max_message_size, grpc_client_interceptor)))
             }
 
-            pub fn build_from_balance_channel(self, balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>, max_message_size: bytesize::ByteSize) -> #client_name
+            pub fn build_from_balance_channel(self, balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>, max_message_size: bytesize::ByteSize, grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor) -> #client_name
             {
-                self.build_from_boxed(Box::new(#client_name::from_balance_channel(balance_channel, max_message_size)))
+                self.build_from_boxed(Box::new(#client_name::from_balance_channel(balance_channel, max_message_size, grpc_client_interceptor)))
             }
 
             pub fn build_from_mailbox<A>(self, mailbox: quickwit_actors::Mailbox<A>) -> #client_name
@@ -17,9 +17,17 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::future::Future;
+use std::io;
 use std::path::{Path, PathBuf};
 
+use anyhow::{bail, Context};
+use bytesize::ByteSize;
+use serde::Serialize;
 use tokio;
+use tokio::io::AsyncWriteExt;
+
+use crate::rand::append_random_suffix;
 
 /// Deletes the contents of a directory.
 pub async fn empty_dir<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
@@ -34,6 +42,152 @@ pub async fn empty_dir<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Returns the number of bytes available to unprivileged users on the file system containing
+/// `path`.
+pub async fn get_disk_free_bytes(path: &Path) -> io::Result<u64> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || disk_free_bytes_blocking(&path))
+        .await
+        .map_err(|join_error| io::Error::new(io::ErrorKind::Other, join_error))?
+}
+
+#[cfg(unix)]
+fn disk_free_bytes_blocking(path: &Path) -> io::Result<u64> {
+    let stats = nix::sys::statvfs::statvfs(path).map_err(io::Error::from)?;
+    // The field types vary across platforms.
+    #[allow(clippy::unnecessary_cast)]
+    let free_bytes = stats.blocks_available() as u64 * stats.fragment_size() as u64;
+    Ok(free_bytes)
+}
+
+#[cfg(windows)]
+fn disk_free_bytes_blocking(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide_path: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut free_bytes_available: u64 = 0;
+    // SAFETY: `wide_path` is a null-terminated UTF-16 string and the output pointers are valid for
+    // the duration of the call. The unused outputs may be null.
+    let success = unsafe {
+        GetDiskFreeSpaceExW(
+            wide_path.as_ptr(),
+            &mut free_bytes_available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if success == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(free_bytes_available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn disk_free_bytes_blocking(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "disk space monitoring is not supported on this platform",
+    ))
+}
+
+/// Checks that the file system containing `path` has at least `required_bytes` available and
+/// returns the number of available bytes.
+pub async fn assert_disk_space_available(path: &Path, required_bytes: u64) -> anyhow::Result<u64> {
+    let free_bytes = get_disk_free_bytes(path).await.with_context(|| {
+        format!(
+            "failed to get the available disk space for `{}`",
+            path.display()
+        )
+    })?;
+    check_disk_space_available(path, free_bytes, required_bytes)?;
+    Ok(free_bytes)
+}
+
+fn check_disk_space_available(
+    path: &Path,
+    free_bytes: u64,
+    required_bytes: u64,
+) -> anyhow::Result<()> {
+    if free_bytes < required_bytes {
+        bail!(
+            "not enough disk space available for `{}`: {} required, {} available",
+            path.display(),
+            ByteSize(required_bytes),
+            ByteSize(free_bytes),
+        );
+    }
+    Ok(())
+}
+
+/// Removes a directory and all its contents, returning `Ok(())` if the directory does not exist.
+pub async fn safe_remove_dir_all(path: &Path) -> io::Result<()> {
+    match tokio::fs::remove_dir_all(path).await {
+        Err(io_error) if io_error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Writes `content` to `path` atomically: readers observe either the previous content of the file
+/// or the new content, never a partial write.
+///
+/// The content is first written and synced to a temporary file located in the same directory, which
+/// is then renamed to `path`. Renaming is atomic on POSIX systems.
+pub async fn atomic_write(path: &Path, content: &[u8]) -> io::Result<()> {
+    atomic_write_with(path, |temp_path| async move {
+        let mut temp_file = tokio::fs::File::create(&temp_path).await?;
+        temp_file.write_all(content).await?;
+        temp_file.sync_all().await
+    })
+    .await
+}
+
+/// Serializes `value` to JSON and writes it to `path` atomically. See [`atomic_write`].
+pub async fn atomic_write_json<T: Serialize>(path: &Path, value: &T) -> anyhow::Result<()> {
+    let content = serde_json::to_vec_pretty(value)?;
+    atomic_write(path, &content).await?;
+    Ok(())
+}
+
+async fn atomic_write_with<F, Fut>(path: &Path, write_temp_file: F) -> io::Result<()>
+where
+    F: FnOnce(PathBuf) -> Fut,
+    Fut: Future<Output = io::Result<()>>,
+{
+    let temp_path = temp_file_path(path)?;
+
+    if let Err(io_error) = write_temp_file(temp_path.clone()).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(io_error);
+    }
+    if let Err(io_error) = tokio::fs::rename(&temp_path, path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(io_error);
+    }
+    Ok(())
+}
+
+/// Returns the path of a unique temporary file located in the same directory as `path`, so that
+/// renaming it to `path` does not cross file systems.
+fn temp_file_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path `{}` does not point to a file", path.display()),
+        )
+    })?;
+    let temp_file_name = format!(
+        "{}.tmp",
+        append_random_suffix(&format!(".{}", file_name.to_string_lossy()))
+    );
+    Ok(path.with_file_name(temp_file_name))
+}
+
 /// Helper function to get the cache path.
 pub fn get_cache_directory_path(data_dir_path: &Path) -> PathBuf {
     data_dir_path.join("indexer-split-cache").join("splits")
@@ -66,4 +220,157 @@ mod tests {
             .is_none());
         Ok(())
     }
+
+    async fn list_dir(dir_path: &Path) -> Vec<String> {
+        let mut entries = tokio::fs::read_dir(dir_path).await.unwrap();
+        let mut file_names = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            file_names.push(entry.file_name().to_string_lossy().to_string());
+        }
+        file_names.sort();
+        file_names
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("file.txt");
+
+        atomic_write(&file_path, b"foo").await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), b"foo");
+
+        atomic_write(&file_path, b"bar").await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), b"bar");
+
+        assert_eq!(list_dir(temp_dir.path()).await, ["file.txt"]);
+
+        let error = atomic_write(
+            temp_dir.path().join("missing-dir/file.txt").as_path(),
+            b"foo",
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        let error = atomic_write(Path::new("/"), b"foo").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write_interrupted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("file.txt");
+        atomic_write(&file_path, b"foo").await.unwrap();
+
+        // Simulates a write interrupted after writing part of the content to the temporary file.
+        let error = atomic_write_with(&file_path, |temp_path| async move {
+            tokio::fs::write(&temp_path, b"ba").await?;
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(error.to_string(), "disk full");
+
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), b"foo");
+        assert_eq!(list_dir(temp_dir.path()).await, ["file.txt"]);
+
+        // Simulates a crash that left a temporary file behind.
+        let stale_temp_path = temp_file_path(&file_path).unwrap();
+        tokio::fs::write(&stale_temp_path, b"ba").await.unwrap();
+
+        atomic_write(&file_path, b"bar").await.unwrap();
+        assert_eq!(tokio::fs::read(&file_path).await.unwrap(), b"bar");
+        assert_eq!(tokio::fs::read(&stale_temp_path).await.unwrap(), b"ba");
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write_json() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("file.json");
+
+        let value = serde_json::json!({"foo": "bar"});
+        atomic_write_json(&file_path, &value).await.unwrap();
+
+        let content = tokio::fs::read(&file_path).await.unwrap();
+        let deserialized_value: serde_json::Value = serde_json::from_slice(&content).unwrap();
+        assert_eq!(deserialized_value, value);
+    }
+
+    #[test]
+    fn test_temp_file_path() {
+        let temp_path = temp_file_path(Path::new("/foo/bar.json")).unwrap();
+        assert_eq!(temp_path.parent().unwrap(), Path::new("/foo"));
+
+        let temp_file_name = temp_path.file_name().unwrap().to_str().unwrap();
+        assert!(temp_file_name.starts_with(".bar.json-"));
+        assert!(temp_file_name.ends_with(".tmp"));
+        assert_ne!(
+            temp_file_path(Path::new("/foo/bar.json")).unwrap(),
+            temp_path
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_disk_free_bytes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let free_bytes = get_disk_free_bytes(temp_dir.path()).await.unwrap();
+        assert!(free_bytes > 0);
+
+        let error = get_disk_free_bytes(&temp_dir.path().join("missing-dir"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_get_disk_free_bytes_on_tmpfs() {
+        // `/dev/shm` is a RAM-backed tmpfs mount on most Linux distributions.
+        let shm_path = Path::new("/dev/shm");
+        if !shm_path.is_dir() {
+            return;
+        }
+        let temp_dir = tempfile::tempdir_in(shm_path).unwrap();
+        let free_bytes = get_disk_free_bytes(temp_dir.path()).await.unwrap();
+
+        assert_disk_space_available(temp_dir.path(), 0)
+            .await
+            .unwrap();
+        assert_disk_space_available(temp_dir.path(), free_bytes / 2)
+            .await
+            .unwrap();
+        assert_disk_space_available(temp_dir.path(), u64::MAX)
+            .await
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_check_disk_space_available() {
+        let path = Path::new("/data/indexing");
+        check_disk_space_available(path, 1_000, 0).unwrap();
+        check_disk_space_available(path, 1_000, 1_000).unwrap();
+
+        let error = check_disk_space_available(path, 999, 2_000_000).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "not enough disk space available for `/data/indexing`: 2.0 MB required, 999 B \
+             available"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_safe_remove_dir_all() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir_path = temp_dir.path().join("dir");
+
+        safe_remove_dir_all(&dir_path).await.unwrap();
+
+        tokio::fs::create_dir(&dir_path).await.unwrap();
+        tokio::fs::write(dir_path.join("file"), b"foo")
+            .await
+            .unwrap();
+        safe_remove_dir_all(&dir_path).await.unwrap();
+        assert!(!dir_path.exists());
+    }
 }
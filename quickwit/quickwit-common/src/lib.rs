@@ -25,6 +25,7 @@ pub mod binary_heap;
 pub mod fs;
 pub mod io;
 mod kill_switch;
+pub mod memory_pressure;
 pub mod metrics;
 pub mod net;
 mod path_hasher;
@@ -44,6 +45,8 @@ pub mod temp_dir;
 #[cfg(any(test, feature = "testsuite"))]
 pub mod test_utils;
 pub mod thread_pool;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod tower;
 pub mod type_map;
 pub mod uri;
@@ -52,6 +55,7 @@ use std::env;
 use std::fmt::{Debug, Display};
 use std::future::Future;
 use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 pub use coolid::new_coolid;
@@ -60,6 +64,7 @@ pub use path_hasher::PathHasher;
 pub use progress::{Progress, ProtectedZoneGuard};
 pub use stream_utils::{BoxStream, ServiceStream};
 use tracing::{error, info};
+use uri::Uri;
 
 pub fn chunk_range(range: Range<usize>, chunk_size: usize) -> impl Iterator<Item = Range<usize>> {
     range.clone().step_by(chunk_size).map(move |block_start| {
@@ -72,6 +77,88 @@ pub fn into_u64_range(range: Range<usize>) -> Range<u64> {
     range.start as u64..range.end as u64
 }
 
+/// Converts a `Range<u64>` into a `Range<usize>`.
+///
+/// # Panics
+///
+/// Panics if one of the bounds of the range does not fit in a `usize`, which can only happen on
+/// platforms where `usize` is narrower than 64 bits.
+pub fn into_usize_range(range: Range<u64>) -> Range<usize> {
+    checked_into_usize_range(range.clone()).unwrap_or_else(|| {
+        panic!(
+            "range `{range:?}` cannot be converted into a `usize` range: bounds exceed \
+             `usize::MAX` ({})",
+            usize::MAX
+        )
+    })
+}
+
+/// Converts a `Range<u64>` into a `Range<usize>`, returning `None` if one of the bounds of the
+/// range does not fit in a `usize`.
+pub fn checked_into_usize_range(range: Range<u64>) -> Option<Range<usize>> {
+    checked_into_usize_range_with_max(range, usize::MAX as u64)
+}
+
+fn checked_into_usize_range_with_max(range: Range<u64>, usize_max: u64) -> Option<Range<usize>> {
+    if range.start > usize_max || range.end > usize_max {
+        return None;
+    }
+    let start = usize::try_from(range.start).ok()?;
+    let end = usize::try_from(range.end).ok()?;
+    Some(start..end)
+}
+
+/// Merges the ranges separated by at most `max_gap_in_bytes` bytes, so that a set of nearby ranges
+/// can be read with fewer requests. Overlapping ranges are always merged and empty ranges are
+/// ignored. The returned ranges are disjoint and sorted.
+pub fn coalesce_ranges(
+    ranges: impl Iterator<Item = Range<u64>>,
+    max_gap_in_bytes: u64,
+) -> impl Iterator<Item = Range<u64>> {
+    coalesce_ranges_aux(ranges, max_gap_in_bytes, u64::MAX).into_iter()
+}
+
+/// Same as [`coalesce_ranges`], except that nearby ranges are only merged as long as the merged
+/// range does not exceed `max_len` bytes. Overlapping ranges are always merged.
+pub fn coalesce_ranges_with_max_len(
+    ranges: &[Range<usize>],
+    max_gap: usize,
+    max_len: usize,
+) -> Vec<Range<usize>> {
+    let ranges = ranges.iter().cloned().map(into_u64_range);
+    coalesce_ranges_aux(ranges, max_gap as u64, max_len as u64)
+        .into_iter()
+        .map(into_usize_range)
+        .collect()
+}
+
+fn coalesce_ranges_aux(
+    ranges: impl Iterator<Item = Range<u64>>,
+    max_gap: u64,
+    max_len: u64,
+) -> Vec<Range<u64>> {
+    let mut sorted_ranges: Vec<Range<u64>> = ranges.filter(|range| !range.is_empty()).collect();
+    sorted_ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut coalesced_ranges: Vec<Range<u64>> = Vec::with_capacity(sorted_ranges.len());
+
+    for range in sorted_ranges {
+        if let Some(last_range) = coalesced_ranges.last_mut() {
+            let merged_end = last_range.end.max(range.end);
+            let overlaps = range.start < last_range.end;
+            let is_nearby = range.start <= last_range.end.saturating_add(max_gap)
+                && merged_end - last_range.start <= max_len;
+
+            if overlaps || is_nearby {
+                last_range.end = merged_end;
+                continue;
+            }
+        }
+        coalesced_ranges.push(range);
+    }
+    coalesced_ranges
+}
+
 pub fn setup_logging_for_tests() {
     let _ = env_logger::builder().format_timestamp(None).try_init();
 }
@@ -80,6 +167,22 @@ pub fn split_file(split_id: impl Display) -> String {
     format!("{split_id}.split")
 }
 
+/// Returns the path of a split file relative to the storage of its index.
+pub fn split_file_path(split_id: impl Display) -> PathBuf {
+    PathBuf::from(split_file(split_id))
+}
+
+/// Returns the URI of a split file located in the storage of its index.
+///
+/// # Panics
+///
+/// Panics if `index_storage_uri` cannot be joined with a path, i.e. it is a database URI.
+pub fn split_storage_path(index_storage_uri: &Uri, split_id: impl Display) -> Uri {
+    index_storage_uri
+        .join(split_file(split_id))
+        .expect("index storage URI should be joinable with a relative path")
+}
+
 pub fn get_from_env<T: FromStr + Debug>(key: &str, default_value: T) -> T {
     if let Ok(value_str) = std::env::var(key) {
         if let Ok(value) = T::from_str(&value_str) {
@@ -93,6 +196,29 @@ pub fn get_from_env<T: FromStr + Debug>(key: &str, default_value: T) -> T {
     default_value
 }
 
+/// Same as [`get_from_env`], but also falls back to `default_value` when the parsed value does
+/// not satisfy `validator`, logging `rejection_message`.
+pub fn get_from_env_with_validation<T: FromStr + Debug, F: Fn(&T) -> bool>(
+    key: &str,
+    default_value: T,
+    validator: F,
+    rejection_message: &str,
+) -> T {
+    if let Ok(value_str) = std::env::var(key) {
+        if let Ok(value) = T::from_str(&value_str) {
+            if validator(&value) {
+                info!(value=?value, "setting `{}` from environment", key);
+                return value;
+            }
+            error!(value=?value, "invalid `{}` from environment: {}", key, rejection_message);
+        } else {
+            error!(value_str=%value_str, "failed to parse `{}` from environment", key);
+        }
+    }
+    info!(value=?default_value, "setting `{}` from default", key);
+    default_value
+}
+
 pub fn get_from_env_opt<T: FromStr + Debug>(key: &str) -> Option<T> {
     let Some(value_str) = std::env::var(key).ok() else {
         info!("{key} is not set");
@@ -151,6 +277,11 @@ pub fn is_false(value: &bool) -> bool {
     !*value
 }
 
+/// For use with the `skip_serializing_if` serde attribute.
+pub fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
 pub fn no_color() -> bool {
     matches!(env::var("NO_COLOR"), Ok(value) if !value.is_empty())
 }
@@ -271,7 +402,9 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
     use std::io::ErrorKind;
+    use std::path::Path;
 
     use super::*;
 
@@ -285,6 +418,262 @@ mod tests {
         assert_eq!(super::get_from_env(TEST_KEY, 10), 10);
     }
 
+    #[test]
+    fn test_get_from_env_with_validation() {
+        const TEST_KEY: &str = "TEST_KEY_WITH_VALIDATION";
+        let is_positive = |value: &i32| *value > 0;
+        assert_eq!(
+            super::get_from_env_with_validation(TEST_KEY, 10, is_positive, "must be positive"),
+            10
+        );
+        std::env::set_var(TEST_KEY, "15");
+        assert_eq!(
+            super::get_from_env_with_validation(TEST_KEY, 10, is_positive, "must be positive"),
+            15
+        );
+        std::env::set_var(TEST_KEY, "-15");
+        assert_eq!(
+            super::get_from_env_with_validation(TEST_KEY, 10, is_positive, "must be positive"),
+            10
+        );
+        std::env::set_var(TEST_KEY, "1invalidnumber");
+        assert_eq!(
+            super::get_from_env_with_validation(TEST_KEY, 10, is_positive, "must be positive"),
+            10
+        );
+    }
+
+    #[test]
+    fn test_into_usize_range() {
+        assert_eq!(into_usize_range(0..0), 0..0);
+        assert_eq!(into_usize_range(3..42), 3..42);
+        assert_eq!(into_usize_range(0..usize::MAX as u64), 0..usize::MAX);
+        assert_eq!(checked_into_usize_range(3..42), Some(3..42));
+    }
+
+    #[test]
+    fn test_checked_into_usize_range_on_32_bit_platform() {
+        let usize_max = u32::MAX as u64;
+        assert_eq!(
+            checked_into_usize_range_with_max(0..usize_max, usize_max),
+            Some(0..u32::MAX as usize)
+        );
+        assert_eq!(
+            checked_into_usize_range_with_max(0..usize_max + 1, usize_max),
+            None
+        );
+        assert_eq!(
+            checked_into_usize_range_with_max(usize_max + 1..usize_max + 2, usize_max),
+            None
+        );
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    #[should_panic(expected = "cannot be converted into a `usize` range")]
+    fn test_into_usize_range_panics_on_overflow() {
+        into_usize_range(0..u32::MAX as u64 + 1);
+    }
+
+    #[test]
+    fn test_coalesce_ranges() {
+        fn coalesce(ranges: &[Range<u64>], max_gap_in_bytes: u64) -> Vec<Range<u64>> {
+            coalesce_ranges(ranges.iter().cloned(), max_gap_in_bytes).collect()
+        }
+        assert!(coalesce(&[], 10).is_empty());
+        assert!(coalesce(&[3..3], 10).is_empty());
+        assert_eq!(coalesce(&[5..10], 0), vec![5..10]);
+        assert_eq!(coalesce(&[0..10, 15..20], 5), vec![0..20]);
+        assert_eq!(coalesce(&[0..10, 16..20], 5), vec![0..10, 16..20]);
+        assert_eq!(coalesce(&[40..50, 0..10, 20..30], 10), vec![0..50]);
+        assert_eq!(coalesce(&[0..10, 5..30, 8..12], 0), vec![0..30]);
+        assert_eq!(coalesce(&[0..10, 10..20], 0), vec![0..20]);
+        assert_eq!(coalesce(&[0..10, 11..20], 0), vec![0..10, 11..20]);
+        assert_eq!(coalesce(&[5..10, 5..10], 0), vec![5..10]);
+        assert_eq!(
+            coalesce(&[0..10, u64::MAX - 10..u64::MAX], u64::MAX),
+            vec![0..u64::MAX]
+        );
+    }
+
+    #[test]
+    fn test_coalesce_ranges_with_max_len() {
+        assert!(coalesce_ranges_with_max_len(&[], 10, 100).is_empty());
+        assert!(coalesce_ranges_with_max_len(&[3..3], 10, 100).is_empty());
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 15..20], 5, 100),
+            vec![0..20]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 16..20], 5, 100),
+            vec![0..10, 16..20]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[40..50, 0..10, 20..30], 10, 100),
+            vec![0..50]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 20..30, 40..50], 10, 30),
+            vec![0..30, 40..50]
+        );
+        // Overlapping ranges are merged regardless of `max_len`.
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 5..30, 8..12], 0, 10),
+            vec![0..30]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 10..20], 0, 100),
+            vec![0..20]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 11..20], 0, 100),
+            vec![0..10, 11..20]
+        );
+        assert_eq!(
+            coalesce_ranges_with_max_len(&[0..10, 20..30], usize::MAX, usize::MAX),
+            vec![0..30]
+        );
+    }
+
+    fn covered_offsets<'a>(ranges: impl IntoIterator<Item = &'a Range<u64>>) -> BTreeSet<u64> {
+        ranges.into_iter().flat_map(|range| range.clone()).collect()
+    }
+
+    fn test_coalesce_ranges_aux(ranges: Vec<Range<u64>>, max_gap_in_bytes: u64) {
+        let coalesced_ranges: Vec<Range<u64>> =
+            coalesce_ranges(ranges.iter().cloned(), max_gap_in_bytes).collect();
+
+        for window in coalesced_ranges.windows(2) {
+            // The ranges are sorted, and separated by more than `max_gap_in_bytes` bytes.
+            assert!(window[0].end + max_gap_in_bytes < window[1].start);
+        }
+        assert!(coalesced_ranges.iter().all(|range| !range.is_empty()));
+
+        let input_offsets = covered_offsets(&ranges);
+        let coalesced_offsets = covered_offsets(&coalesced_ranges);
+        assert!(coalesced_offsets.is_superset(&input_offsets));
+
+        if max_gap_in_bytes == 0 {
+            assert_eq!(coalesced_offsets, input_offsets);
+        }
+        // The extra offsets can only come from the gaps between the input ranges.
+        for range in &coalesced_ranges {
+            let num_input_offsets_in_range = input_offsets.range(range.clone()).count() as u64;
+            let num_gaps_in_range = ranges
+                .iter()
+                .filter(|input_range| {
+                    !input_range.is_empty()
+                        && input_range.start > range.start
+                        && input_range.start < range.end
+                })
+                .count() as u64;
+            assert!(
+                range.end - range.start
+                    <= num_input_offsets_in_range + num_gaps_in_range * max_gap_in_bytes
+            );
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_coalesce_ranges_proptest(
+            ranges in proptest::collection::vec((0..200u64, 0..20u64), 0..20),
+            max_gap_in_bytes in 0..10u64,
+        ) {
+            let ranges = ranges
+                .into_iter()
+                .map(|(start, len)| start..start + len)
+                .collect();
+            test_coalesce_ranges_aux(ranges, max_gap_in_bytes);
+        }
+
+        #[test]
+        fn test_coalesce_ranges_with_max_len_proptest(
+            ranges in proptest::collection::vec((0..200usize, 0..20usize), 0..20),
+            max_gap in 0..10usize,
+            max_len in 0..60usize,
+        ) {
+            let ranges: Vec<Range<usize>> = ranges
+                .into_iter()
+                .map(|(start, len)| start..start + len)
+                .collect();
+            test_coalesce_ranges_with_max_len_aux(&ranges, max_gap, max_len);
+        }
+    }
+
+    fn test_coalesce_ranges_with_max_len_aux(
+        ranges: &[Range<usize>],
+        max_gap: usize,
+        max_len: usize,
+    ) {
+        let coalesced_ranges = coalesce_ranges_with_max_len(ranges, max_gap, max_len);
+
+        for window in coalesced_ranges.windows(2) {
+            // The ranges are sorted and disjoint, and were not merged because they are too far
+            // apart or because the merged range would have been too long.
+            assert!(window[0].end <= window[1].start);
+            assert!(
+                window[0].end + max_gap < window[1].start
+                    || window[1].end - window[0].start > max_len
+            );
+        }
+        assert!(coalesced_ranges.iter().all(|range| !range.is_empty()));
+
+        let input_ranges: Vec<Range<u64>> = ranges.iter().cloned().map(into_u64_range).collect();
+        let input_offsets = covered_offsets(&input_ranges);
+        let coalesced_offsets = covered_offsets(
+            &coalesced_ranges
+                .iter()
+                .cloned()
+                .map(into_u64_range)
+                .collect::<Vec<_>>(),
+        );
+        assert!(coalesced_offsets.is_superset(&input_offsets));
+
+        // Without a length bound, the ranges are coalesced the same way as `coalesce_ranges`.
+        let unbounded_coalesced_ranges: Vec<Range<u64>> =
+            coalesce_ranges_with_max_len(ranges, max_gap, usize::MAX)
+                .into_iter()
+                .map(into_u64_range)
+                .collect();
+        let expected_coalesced_ranges: Vec<Range<u64>> =
+            coalesce_ranges(input_ranges.into_iter(), max_gap as u64).collect();
+        assert_eq!(unbounded_coalesced_ranges, expected_coalesced_ranges);
+    }
+
+    #[test]
+    fn test_split_file_path() {
+        assert_eq!(split_file("my-split"), "my-split.split");
+        assert_eq!(split_file_path("my-split"), PathBuf::from("my-split.split"));
+        assert_eq!(
+            split_file_path("my-split").to_str().unwrap(),
+            split_file("my-split")
+        );
+        assert_eq!(split_file_path("my-split").components().count(), 1);
+    }
+
+    #[test]
+    fn test_split_storage_path() {
+        assert_eq!(
+            split_storage_path(&Uri::for_test("s3://bucket/indexes/my-index"), "my-split"),
+            "s3://bucket/indexes/my-index/my-split.split"
+        );
+        assert_eq!(
+            split_storage_path(&Uri::for_test("s3://bucket/indexes/my-index/"), "my-split"),
+            "s3://bucket/indexes/my-index/my-split.split"
+        );
+        assert_eq!(
+            split_storage_path(&Uri::for_test("ram:///indexes/my-index"), "my-split"),
+            "ram:///indexes/my-index/my-split.split"
+        );
+        let index_storage_uri = Uri::for_test("file:///indexes/my-index");
+        let expected_split_path = Path::new("/indexes/my-index").join(split_file("my-split"));
+        assert_eq!(
+            split_storage_path(&index_storage_uri, "my-split"),
+            format!("file://{}", expected_split_path.display())
+        );
+    }
+
     #[test]
     fn test_truncate_str() {
         assert_eq!(truncate_str("", 0), "");
@@ -0,0 +1,127 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::fmt;
+use std::sync::Arc;
+
+/// Reports the amount of memory available on the host.
+pub trait MemoryReporter: Send + Sync + 'static {
+    /// Returns the number of bytes available for starting new applications without swapping, or
+    /// `None` if this information cannot be obtained.
+    fn available_memory_bytes(&self) -> Option<u64>;
+}
+
+/// Reads the amount of available memory from `/proc/meminfo`. Always reports `None` on platforms
+/// other than Linux.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcMeminfoReporter;
+
+impl MemoryReporter for ProcMeminfoReporter {
+    #[cfg(target_os = "linux")]
+    fn available_memory_bytes(&self) -> Option<u64> {
+        let meminfo = match std::fs::read_to_string("/proc/meminfo") {
+            Ok(meminfo) => meminfo,
+            Err(error) => {
+                tracing::warn!(error=?error, "failed to read `/proc/meminfo`");
+                return None;
+            }
+        };
+        parse_mem_available(&meminfo)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn available_memory_bytes(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Parses the `MemAvailable` line of `/proc/meminfo`, which is expressed in KiB.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let mem_available_line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let mem_available_kib: u64 = mem_available_line
+        .trim_start_matches("MemAvailable:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(mem_available_kib * 1024)
+}
+
+/// Monitors the memory available on the host so that memory-hungry components, such as caches,
+/// can shrink under memory pressure.
+#[derive(Clone)]
+pub struct MemoryPressureMonitor {
+    reporter: Arc<dyn MemoryReporter>,
+}
+
+impl fmt::Debug for MemoryPressureMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryPressureMonitor").finish()
+    }
+}
+
+impl Default for MemoryPressureMonitor {
+    fn default() -> Self {
+        Self::new(ProcMeminfoReporter)
+    }
+}
+
+impl MemoryPressureMonitor {
+    /// Creates a monitor backed by the given memory reporter.
+    pub fn new(reporter: impl MemoryReporter) -> Self {
+        Self {
+            reporter: Arc::new(reporter),
+        }
+    }
+
+    /// Returns the number of bytes currently available on the host, if known.
+    pub fn available_memory_bytes(&self) -> Option<u64> {
+        self.reporter.available_memory_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mem_available() {
+        let meminfo = "MemTotal:       16303428 kB\nMemFree:         1996868 kB\nMemAvailable:    \
+                       8849384 kB\nBuffers:          483640 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(8_849_384 * 1024));
+        assert_eq!(parse_mem_available("MemTotal: 16303428 kB\n"), None);
+        assert_eq!(parse_mem_available("MemAvailable: garbage kB\n"), None);
+    }
+
+    #[test]
+    fn test_memory_pressure_monitor() {
+        struct StaticMemoryReporter(u64);
+
+        impl MemoryReporter for StaticMemoryReporter {
+            fn available_memory_bytes(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+        let monitor = MemoryPressureMonitor::new(StaticMemoryReporter(1_024));
+        assert_eq!(monitor.available_memory_bytes(), Some(1_024));
+    }
+}
@@ -21,10 +21,12 @@ use std::collections::{BTreeMap, HashMap};
 use std::sync::OnceLock;
 
 use once_cell::sync::Lazy;
+use prometheus::core::Collector;
+use prometheus::proto::Metric;
 pub use prometheus::{
-    exponential_buckets, Histogram, HistogramTimer, HistogramVec as PrometheusHistogramVec,
-    IntCounter, IntCounterVec as PrometheusIntCounterVec, IntGauge,
-    IntGaugeVec as PrometheusIntGaugeVec,
+    exponential_buckets, Counter, Histogram, HistogramTimer,
+    HistogramVec as PrometheusHistogramVec, IntCounter, IntCounterVec as PrometheusIntCounterVec,
+    IntGauge, IntGaugeVec as PrometheusIntGaugeVec,
 };
 use prometheus::{Encoder, Gauge, HistogramOpts, Opts, TextEncoder};
 
@@ -37,6 +39,21 @@ impl<const N: usize> HistogramVec<N> {
     pub fn with_label_values(&self, label_values: [&str; N]) -> Histogram {
         self.underlying.with_label_values(&label_values)
     }
+
+    /// Returns the sum and the count of the observations of each histogram of the vector, along
+    /// with its labels.
+    pub fn collect_sums_and_counts(&self) -> Vec<(HashMap<String, String>, f64, u64)> {
+        collect_metrics(&self.underlying)
+            .map(|metric| {
+                let histogram = metric.get_histogram();
+                (
+                    metric_labels(&metric),
+                    histogram.get_sample_sum(),
+                    histogram.get_sample_count(),
+                )
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone)]
@@ -48,6 +65,36 @@ impl<const N: usize> IntCounterVec<N> {
     pub fn with_label_values(&self, label_values: [&str; N]) -> IntCounter {
         self.underlying.with_label_values(&label_values)
     }
+
+    /// Returns the value of each counter of the vector, along with its labels.
+    pub fn collect_values(&self) -> Vec<(HashMap<String, String>, u64)> {
+        collect_metrics(&self.underlying)
+            .map(|metric| {
+                let value = metric.get_counter().get_value() as u64;
+                (metric_labels(&metric), value)
+            })
+            .collect()
+    }
+}
+
+fn collect_metrics(collector: &impl Collector) -> impl Iterator<Item = Metric> {
+    collector
+        .collect()
+        .into_iter()
+        .flat_map(|mut metric_family| metric_family.take_metric().into_iter())
+}
+
+fn metric_labels(metric: &Metric) -> HashMap<String, String> {
+    metric
+        .get_label()
+        .iter()
+        .map(|label_pair| {
+            (
+                label_pair.get_name().to_string(),
+                label_pair.get_value().to_string(),
+            )
+        })
+        .collect()
 }
 
 #[derive(Clone)]
@@ -59,6 +106,11 @@ impl<const N: usize> IntGaugeVec<N> {
     pub fn with_label_values(&self, label_values: [&str; N]) -> IntGauge {
         self.underlying.with_label_values(&label_values)
     }
+
+    /// Removes the gauge associated with the given label values, if any.
+    pub fn remove_label_values(&self, label_values: [&str; N]) {
+        let _ = self.underlying.remove_label_values(&label_values);
+    }
 }
 
 pub fn register_info(name: &'static str, help: &'static str, kvs: BTreeMap<&'static str, String>) {
@@ -114,6 +166,25 @@ pub fn new_counter_vec<const N: usize>(
     IntCounterVec { underlying }
 }
 
+pub fn new_float_counter(
+    name: &str,
+    help: &str,
+    subsystem: &str,
+    const_labels: &[(&str, &str)],
+) -> Counter {
+    let owned_const_labels: HashMap<String, String> = const_labels
+        .iter()
+        .map(|(label_name, label_value)| (label_name.to_string(), label_value.to_string()))
+        .collect();
+    let counter_opts = Opts::new(name, help)
+        .namespace("quickwit")
+        .subsystem(subsystem)
+        .const_labels(owned_const_labels);
+    let counter = Counter::with_opts(counter_opts).expect("failed to create float counter");
+    prometheus::register(Box::new(counter.clone())).expect("failed to register float counter");
+    counter
+}
+
 pub fn new_float_gauge(
     name: &str,
     help: &str,
@@ -286,6 +357,51 @@ impl Drop for OwnedGaugeGuard {
     }
 }
 
+/// Estimates the `quantile` (between 0 and 1) of the observations recorded by `histogram`.
+///
+/// Like Prometheus' `histogram_quantile` function, this assumes the observations are evenly
+/// distributed within each bucket. Returns 0 if the histogram has no observations.
+pub fn histogram_quantile(histogram: &Histogram, quantile: f64) -> f64 {
+    use prometheus::core::Collector;
+
+    let metric_families = histogram.collect();
+    let Some(metric) = metric_families
+        .first()
+        .and_then(|metric_family| metric_family.get_metric().first())
+    else {
+        return 0.0;
+    };
+    let proto_histogram = metric.get_histogram();
+    let sample_count = proto_histogram.get_sample_count();
+
+    if sample_count == 0 {
+        return 0.0;
+    }
+    let rank = quantile.clamp(0.0, 1.0) * sample_count as f64;
+    let mut lower_bound = 0.0;
+    let mut lower_cumulative_count = 0;
+
+    for bucket in proto_histogram.get_bucket() {
+        let upper_bound = bucket.get_upper_bound();
+        let cumulative_count = bucket.get_cumulative_count();
+
+        if cumulative_count as f64 >= rank {
+            let bucket_count = cumulative_count - lower_cumulative_count;
+            if bucket_count == 0 {
+                return upper_bound;
+            }
+            let rank_in_bucket = rank - lower_cumulative_count as f64;
+            return lower_bound
+                + (upper_bound - lower_bound) * rank_in_bucket / bucket_count as f64;
+        }
+        lower_bound = upper_bound;
+        lower_cumulative_count = cumulative_count;
+    }
+    // The quantile falls into the implicit `+Inf` bucket, so the best we can do is to return the
+    // largest finite bound.
+    lower_bound
+}
+
 pub fn metrics_text_payload() -> String {
     let metric_families = prometheus::gather();
     let mut buffer = Vec::new();
@@ -431,3 +547,536 @@ impl InFlightDataGauges {
 }
 
 pub static MEMORY_METRICS: Lazy<MemoryMetrics> = Lazy::new(MemoryMetrics::default);
+
+/// Registry holding the metrics of the storage, cache, search, and indexing components.
+///
+/// The metrics are registered in the default Prometheus registry the first time the registry is
+/// accessed, so they can also be observed directly in tests without scraping the metrics endpoint.
+#[derive(Default)]
+pub struct QuickwitMetrics {
+    pub storage: StorageMetrics,
+    pub cache: CachesMetrics,
+    pub search: SearchMetrics,
+    pub indexer: IndexerMetrics,
+}
+
+static QUICKWIT_METRICS: Lazy<QuickwitMetrics> = Lazy::new(QuickwitMetrics::default);
+
+impl QuickwitMetrics {
+    /// Returns the global metrics registry.
+    pub fn global() -> &'static QuickwitMetrics {
+        &QUICKWIT_METRICS
+    }
+
+    /// Resets the counters and gauges of the global registry. Histograms cannot be reset and are
+    /// left untouched.
+    ///
+    /// Metric vectors are reset by dropping their label values, so handles obtained beforehand
+    /// with `with_label_values` are no longer exported afterwards. The registry is shared by all
+    /// the tests of a process, which should not rely on absolute values when run concurrently.
+    #[cfg(any(test, feature = "testsuite"))]
+    pub fn reset() {
+        let metrics = Self::global();
+        metrics.storage.reset();
+        metrics.cache.reset();
+        metrics.search.reset();
+        metrics.indexer.reset();
+    }
+}
+
+/// Counters associated to storage operations.
+pub struct StorageMetrics {
+    pub object_storage_get_total: IntCounter,
+    pub object_storage_put_total: IntCounter,
+    pub object_storage_put_parts: IntCounter,
+    pub object_storage_download_num_bytes: IntCounter,
+    pub object_storage_upload_num_bytes: IntCounter,
+    /// Number of storage operations per backend, operation (`read`, `write`, `delete`), and
+    /// status (`success`, `error`).
+    pub storage_operations_total: IntCounterVec<3>,
+    pub storage_read_num_bytes: IntCounterVec<1>,
+    pub storage_write_num_bytes: IntCounterVec<1>,
+    pub storage_operation_duration_seconds: HistogramVec<2>,
+    #[cfg(debug_assertions)]
+    pub slices_created: IntCounter,
+}
+
+impl Default for StorageMetrics {
+    fn default() -> Self {
+        StorageMetrics {
+            object_storage_get_total: new_counter(
+                "object_storage_gets_total",
+                "Number of objects fetched.",
+                "storage",
+                &[],
+            ),
+            object_storage_put_total: new_counter(
+                "object_storage_puts_total",
+                "Number of objects uploaded. May differ from object_storage_requests_parts due to \
+                 multipart upload.",
+                "storage",
+                &[],
+            ),
+            object_storage_put_parts: new_counter(
+                "object_storage_puts_parts",
+                "Number of object parts uploaded.",
+                "",
+                &[],
+            ),
+            object_storage_download_num_bytes: new_counter(
+                "object_storage_download_num_bytes",
+                "Amount of data downloaded from an object storage.",
+                "storage",
+                &[],
+            ),
+            object_storage_upload_num_bytes: new_counter(
+                "object_storage_upload_num_bytes",
+                "Amount of data uploaded to an object storage.",
+                "storage",
+                &[],
+            ),
+            storage_operations_total: new_counter_vec(
+                "operations_total",
+                "Number of storage operations, per backend, operation, and status.",
+                "storage",
+                &[],
+                ["backend", "operation", "status"],
+            ),
+            storage_read_num_bytes: new_counter_vec(
+                "read_bytes_total",
+                "Number of bytes read from the storage, per backend.",
+                "storage",
+                &[],
+                ["backend"],
+            ),
+            storage_write_num_bytes: new_counter_vec(
+                "written_bytes_total",
+                "Number of bytes written to the storage, per backend.",
+                "storage",
+                &[],
+                ["backend"],
+            ),
+            storage_operation_duration_seconds: new_histogram_vec(
+                "operation_duration_seconds",
+                "Duration of storage operations, per backend and operation.",
+                "storage",
+                &[],
+                ["backend", "operation"],
+                exponential_buckets(0.001, 2.0, 16).unwrap(),
+            ),
+            #[cfg(debug_assertions)]
+            slices_created: new_counter(
+                "slices_created",
+                "Number of zero-copy slices of `OwnedBytes` created (debug builds only).",
+                "storage",
+                &[],
+            ),
+        }
+    }
+}
+
+impl StorageMetrics {
+    #[cfg(any(test, feature = "testsuite"))]
+    fn reset(&self) {
+        self.object_storage_get_total.reset();
+        self.object_storage_put_total.reset();
+        self.object_storage_put_parts.reset();
+        self.object_storage_download_num_bytes.reset();
+        self.object_storage_upload_num_bytes.reset();
+        self.storage_operations_total.underlying.reset();
+        self.storage_read_num_bytes.underlying.reset();
+        self.storage_write_num_bytes.underlying.reset();
+        self.storage_operation_duration_seconds.underlying.reset();
+        #[cfg(debug_assertions)]
+        self.slices_created.reset();
+    }
+}
+
+/// Metrics of the caches of the storage layer, one [`CacheMetrics`] per cache.
+pub struct CachesMetrics {
+    pub shortlived_cache: CacheMetrics,
+    pub partial_request_cache: CacheMetrics,
+    pub fd_cache_metrics: CacheMetrics,
+    pub fast_field_cache: CacheMetrics,
+    pub split_footer_cache: CacheMetrics,
+    pub searcher_split_cache: CacheMetrics,
+    pub idempotency_cache: CacheMetrics,
+}
+
+impl Default for CachesMetrics {
+    fn default() -> Self {
+        CachesMetrics {
+            fast_field_cache: CacheMetrics::for_component("fastfields"),
+            fd_cache_metrics: CacheMetrics::for_component("fd"),
+            idempotency_cache: CacheMetrics::for_component("idempotency"),
+            partial_request_cache: CacheMetrics::for_component("partial_request"),
+            searcher_split_cache: CacheMetrics::for_component("searcher_split"),
+            shortlived_cache: CacheMetrics::for_component("shortlived"),
+            split_footer_cache: CacheMetrics::for_component("splitfooter"),
+        }
+    }
+}
+
+impl CachesMetrics {
+    #[cfg(any(test, feature = "testsuite"))]
+    fn reset(&self) {
+        self.shortlived_cache.reset();
+        self.partial_request_cache.reset();
+        self.fd_cache_metrics.reset();
+        self.fast_field_cache.reset();
+        self.split_footer_cache.reset();
+        self.searcher_split_cache.reset();
+        self.idempotency_cache.reset();
+    }
+}
+
+/// Counters associated to a cache.
+#[derive(Clone)]
+pub struct CacheMetrics {
+    pub component_name: String,
+    pub in_cache_count: IntGauge,
+    pub in_cache_num_bytes: IntGauge,
+    pub hits_num_items: IntCounter,
+    pub hits_num_bytes: IntCounter,
+    pub misses_num_items: IntCounter,
+}
+
+impl CacheMetrics {
+    pub fn for_component(component_name: &str) -> Self {
+        let namespace = format!("cache_{component_name}");
+        CacheMetrics {
+            component_name: component_name.to_string(),
+            in_cache_count: new_gauge(
+                "in_cache_count",
+                "Count of {component_name} in cache",
+                &namespace,
+                &[],
+            ),
+            in_cache_num_bytes: new_gauge(
+                "in_cache_num_bytes",
+                "Number of {component_name} bytes in cache",
+                &namespace,
+                &[],
+            ),
+            hits_num_items: new_counter(
+                "cache_hits_total",
+                "Number of {component_name} cache hits",
+                &namespace,
+                &[],
+            ),
+            hits_num_bytes: new_counter(
+                "cache_hits_bytes",
+                "Number of {component_name} cache hits in bytes",
+                &namespace,
+                &[],
+            ),
+            misses_num_items: new_counter(
+                "cache_misses_total",
+                "Number of {component_name} cache misses",
+                &namespace,
+                &[],
+            ),
+        }
+    }
+
+    #[cfg(any(test, feature = "testsuite"))]
+    fn reset(&self) {
+        self.in_cache_count.set(0);
+        self.in_cache_num_bytes.set(0);
+        self.hits_num_items.reset();
+        self.hits_num_bytes.reset();
+        self.misses_num_items.reset();
+    }
+}
+
+pub struct SearchMetrics {
+    pub root_search_requests_total: IntCounterVec<1>,
+    pub root_search_request_duration_secs: HistogramVec<1>,
+    pub leaf_searches_splits_total: IntCounter,
+    pub leaf_search_split_duration_secs: Histogram,
+    pub routed_search_jobs_total: IntCounter,
+    pub routed_to_affinity_node_total: IntCounter,
+    pub routed_to_affinity_node_fraction: Gauge,
+}
+
+impl Default for SearchMetrics {
+    fn default() -> Self {
+        SearchMetrics {
+            root_search_requests_total: new_counter_vec(
+                "root_search_requests_total",
+                "Number of root search requests by targeted index.",
+                "search",
+                &[],
+                ["index"],
+            ),
+            root_search_request_duration_secs: new_histogram_vec(
+                "root_search_request_duration_secs",
+                "Number of seconds required to run a root search request, by targeted index.",
+                "search",
+                &[],
+                ["index"],
+                exponential_buckets(0.001, 2.0, 15).unwrap(),
+            ),
+            leaf_searches_splits_total: new_counter(
+                "leaf_searches_splits_total",
+                "Number of leaf searches (count of splits) started.",
+                "search",
+                &[],
+            ),
+            leaf_search_split_duration_secs: new_histogram(
+                "leaf_search_split_duration_secs",
+                "Number of seconds required to run a leaf search over a single split. The timer \
+                 starts after the semaphore is obtained.",
+                "search",
+                exponential_buckets(0.005, 2.0, 10).unwrap(),
+            ),
+            routed_search_jobs_total: new_counter(
+                "routed_search_jobs_total",
+                "Number of per-split search jobs routed to a searcher node.",
+                "search",
+                &[],
+            ),
+            routed_to_affinity_node_total: new_counter(
+                "routed_to_affinity_node_total",
+                "Number of per-split search jobs routed to the searcher node owning the split.",
+                "search",
+                &[],
+            ),
+            routed_to_affinity_node_fraction: new_float_gauge(
+                "routed_to_affinity_node_fraction",
+                "Fraction of the per-split search jobs routed to the searcher node owning the \
+                 split.",
+                "search",
+                &[],
+            ),
+        }
+    }
+}
+
+impl SearchMetrics {
+    #[cfg(any(test, feature = "testsuite"))]
+    fn reset(&self) {
+        self.root_search_requests_total.underlying.reset();
+        self.leaf_searches_splits_total.reset();
+    }
+}
+
+pub struct IndexerMetrics {
+    pub processed_docs_total: IntCounterVec<2>,
+    pub processed_bytes: IntCounterVec<2>,
+    pub document_errors_total: IntCounterVec<2>,
+    pub backpressure_micros: IntCounterVec<1>,
+    pub indexer_backpressure_total: IntCounter,
+    pub indexer_backpressure_duration_seconds: Counter,
+    pub available_concurrent_upload_permits: IntGaugeVec<1>,
+    pub split_builders: IntGauge,
+    pub ongoing_merge_operations: IntGauge,
+    pub pending_merge_operations: IntGauge,
+    pub pending_merge_bytes: IntGauge,
+    pub disk_space_bytes_free: IntGauge,
+}
+
+impl Default for IndexerMetrics {
+    fn default() -> Self {
+        IndexerMetrics {
+            processed_docs_total: new_counter_vec(
+                "processed_docs_total",
+                "Number of processed docs by index, source and processed status in [valid, \
+                 schema_error, parse_error, transform_error]",
+                "indexing",
+                &[],
+                ["index", "docs_processed_status"],
+            ),
+            processed_bytes: new_counter_vec(
+                "processed_bytes",
+                "Number of bytes of processed documents by index, source and processed status in \
+                 [valid, schema_error, parse_error, transform_error]",
+                "indexing",
+                &[],
+                ["index", "docs_processed_status"],
+            ),
+            document_errors_total: new_counter_vec(
+                "document_errors_total",
+                "Number of documents that could not be processed by index and error policy in \
+                 [skip_and_log, fail_fast, dead_letter_queue]",
+                "indexing",
+                &[],
+                ["index", "policy"],
+            ),
+            backpressure_micros: new_counter_vec(
+                "backpressure_micros",
+                "Amount of time spent in backpressure (in micros). This time only includes the \
+                 amount of time spent waiting for a place in the queue of another actor.",
+                "indexing",
+                &[],
+                ["actor_name"],
+            ),
+            indexer_backpressure_total: new_counter(
+                "indexer_backpressure_total",
+                "Number of times the sources of the indexing pipelines were paused because the \
+                 in-memory document buffers exceeded the high water mark.",
+                "indexing",
+                &[],
+            ),
+            indexer_backpressure_duration_seconds: new_float_counter(
+                "indexer_backpressure_duration_seconds",
+                "Amount of time the sources of the indexing pipelines were paused because the \
+                 in-memory document buffers exceeded the high water mark (in seconds).",
+                "indexing",
+                &[],
+            ),
+            available_concurrent_upload_permits: new_gauge_vec(
+                "concurrent_upload_available_permits_num",
+                "Number of available concurrent upload permits by component in [merger, indexer]",
+                "indexing",
+                &[],
+                ["component"],
+            ),
+            split_builders: new_gauge(
+                "split_builders",
+                "Number of existing index writer instances.",
+                "indexing",
+                &[],
+            ),
+            ongoing_merge_operations: new_gauge(
+                "ongoing_merge_operations",
+                "Number of ongoing merge operations",
+                "indexing",
+                &[],
+            ),
+            pending_merge_operations: new_gauge(
+                "pending_merge_operations",
+                "Number of pending merge operations",
+                "indexing",
+                &[],
+            ),
+            pending_merge_bytes: new_gauge(
+                "pending_merge_bytes",
+                "Number of pending merge bytes",
+                "indexing",
+                &[],
+            ),
+            disk_space_bytes_free: new_gauge(
+                "disk_space_bytes_free",
+                "Number of bytes available on the disk of the indexing directory, as of the last \
+                 split commit.",
+                "indexing",
+                &[],
+            ),
+        }
+    }
+}
+
+impl IndexerMetrics {
+    #[cfg(any(test, feature = "testsuite"))]
+    fn reset(&self) {
+        self.processed_docs_total.underlying.reset();
+        self.processed_bytes.underlying.reset();
+        self.document_errors_total.underlying.reset();
+        self.backpressure_micros.underlying.reset();
+        self.indexer_backpressure_total.reset();
+        self.indexer_backpressure_duration_seconds.reset();
+        self.available_concurrent_upload_permits.underlying.reset();
+        self.split_builders.set(0);
+        self.ongoing_merge_operations.set(0);
+        self.pending_merge_operations.set(0);
+        self.pending_merge_bytes.set(0);
+        self.disk_space_bytes_free.set(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quickwit_metrics_reset() {
+        let metrics = QuickwitMetrics::global();
+        metrics.storage.object_storage_get_total.inc_by(3);
+        metrics.cache.fast_field_cache.in_cache_num_bytes.set(1_024);
+        metrics
+            .indexer
+            .processed_docs_total
+            .with_label_values(["test-index", "valid"])
+            .inc();
+        assert_eq!(metrics.storage.object_storage_get_total.get(), 3);
+
+        QuickwitMetrics::reset();
+
+        assert_eq!(metrics.storage.object_storage_get_total.get(), 0);
+        assert_eq!(metrics.cache.fast_field_cache.in_cache_num_bytes.get(), 0);
+        assert_eq!(
+            metrics
+                .indexer
+                .processed_docs_total
+                .with_label_values(["test-index", "valid"])
+                .get(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_histogram_quantile() {
+        let histogram_opts =
+            HistogramOpts::new("test_histogram", "Test histogram.").buckets(vec![1.0, 2.0, 4.0]);
+        let histogram = Histogram::with_opts(histogram_opts).unwrap();
+        assert_eq!(histogram_quantile(&histogram, 0.99), 0.0);
+
+        for _ in 0..50 {
+            histogram.observe(0.5);
+        }
+        for _ in 0..50 {
+            histogram.observe(3.0);
+        }
+        assert_eq!(histogram_quantile(&histogram, 0.5), 1.0);
+        assert_eq!(histogram_quantile(&histogram, 0.25), 0.5);
+        assert!((histogram_quantile(&histogram, 0.99) - 3.96).abs() < 1e-9);
+
+        histogram.observe(10.0);
+        assert_eq!(histogram_quantile(&histogram, 1.0), 4.0);
+    }
+
+    #[test]
+    fn test_collect_metric_vec_values() {
+        let counter_vec = new_counter_vec(
+            "test_collect_values_total",
+            "Test counter vec.",
+            "test",
+            &[],
+            ["index"],
+        );
+        assert!(counter_vec.collect_values().is_empty());
+
+        counter_vec.with_label_values(["index-1"]).inc_by(3);
+        counter_vec.with_label_values(["index-2"]).inc();
+
+        let mut values: Vec<(String, u64)> = counter_vec
+            .collect_values()
+            .into_iter()
+            .map(|(labels, value)| (labels["index"].clone(), value))
+            .collect();
+        values.sort();
+        assert_eq!(
+            values,
+            [("index-1".to_string(), 3), ("index-2".to_string(), 1)]
+        );
+
+        let histogram_vec = new_histogram_vec(
+            "test_collect_sums_and_counts",
+            "Test histogram vec.",
+            "test",
+            &[],
+            ["index"],
+            vec![1.0, 2.0],
+        );
+        histogram_vec.with_label_values(["index-1"]).observe(0.5);
+        histogram_vec.with_label_values(["index-1"]).observe(1.5);
+
+        let sums_and_counts = histogram_vec.collect_sums_and_counts();
+        assert_eq!(sums_and_counts.len(), 1);
+        let (labels, sum, count) = &sums_and_counts[0];
+        assert_eq!(labels["index"], "index-1");
+        assert_eq!(*sum, 2.0);
+        assert_eq!(*count, 2);
+    }
+}
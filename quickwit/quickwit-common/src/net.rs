@@ -22,14 +22,20 @@ use std::fmt::Display;
 use std::io;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
 use std::str::FromStr;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
 
 use anyhow::{bail, Context};
+use futures::future::BoxFuture;
+use hyper::client::HttpConnector;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use pnet::datalink::{self, NetworkInterface};
 use pnet::ipnetwork::IpNetwork;
 use serde::{Deserialize, Serialize, Serializer};
-use tokio::net::{lookup_host, ToSocketAddrs};
+use socket2::{SockRef, TcpKeepalive};
+use tokio::net::{lookup_host, TcpStream, ToSocketAddrs};
+use tower::Service;
 
 /// Represents a host, i.e. an IP address (`127.0.0.1`) or a hostname (`localhost`).
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -376,6 +382,120 @@ pub fn is_valid_hostname(hostname: &str) -> bool {
     true
 }
 
+/// TCP options applied to outbound connections.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcpConfig {
+    /// Idle time before the first keepalive probe is sent, and interval between subsequent probes.
+    /// Keepalive is disabled if `None`.
+    pub keepalive_interval: Option<Duration>,
+    /// Number of unacknowledged keepalive probes after which the connection is considered dead.
+    pub keepalive_retries: Option<u32>,
+    /// Maximum amount of time to establish a connection.
+    pub connect_timeout: Option<Duration>,
+    /// Read timeout of the socket. Only effective for sockets in blocking mode.
+    pub read_timeout: Option<Duration>,
+    /// Write timeout of the socket. Only effective for sockets in blocking mode.
+    pub write_timeout: Option<Duration>,
+}
+
+impl Default for TcpConfig {
+    fn default() -> Self {
+        Self {
+            keepalive_interval: Some(Duration::from_secs(30)),
+            keepalive_retries: Some(3),
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+        }
+    }
+}
+
+/// Applies the socket options of `config` to an established TCP connection. `connect_timeout`
+/// is ignored since the socket is already connected.
+pub fn configure_tcp_socket<'a, S>(socket: &'a S, config: &TcpConfig) -> io::Result<()>
+where SockRef<'a>: From<&'a S> {
+    let sock_ref = SockRef::from(socket);
+
+    if let Some(keepalive_interval) = config.keepalive_interval {
+        let keepalive = TcpKeepalive::new().with_time(keepalive_interval);
+        // The keepalive interval and retries can only be configured on some platforms.
+        #[cfg(any(
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            windows,
+        ))]
+        let keepalive = keepalive.with_interval(keepalive_interval);
+        #[cfg(any(
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+        ))]
+        let keepalive = if let Some(keepalive_retries) = config.keepalive_retries {
+            keepalive.with_retries(keepalive_retries)
+        } else {
+            keepalive
+        };
+        sock_ref.set_tcp_keepalive(&keepalive)?;
+    } else {
+        sock_ref.set_keepalive(false)?;
+    }
+    sock_ref.set_read_timeout(config.read_timeout)?;
+    sock_ref.set_write_timeout(config.write_timeout)?;
+    Ok(())
+}
+
+/// An HTTP connector that applies a [`TcpConfig`] to the connections it establishes. It can be
+/// used by hyper clients and tonic channels alike.
+#[derive(Clone, Debug)]
+pub struct TcpConfigConnector {
+    http_connector: HttpConnector,
+    tcp_config: TcpConfig,
+}
+
+impl TcpConfigConnector {
+    /// Creates a new connector applying `tcp_config` to its connections.
+    pub fn new(tcp_config: TcpConfig) -> Self {
+        let mut http_connector = HttpConnector::new();
+        // Enforced by the `HttpsConnector` wrapping this connector, if any.
+        http_connector.enforce_http(false);
+        http_connector.set_nodelay(true);
+        http_connector.set_connect_timeout(tcp_config.connect_timeout);
+
+        Self {
+            http_connector,
+            tcp_config,
+        }
+    }
+}
+
+impl Service<hyper::Uri> for TcpConfigConnector {
+    type Response = TcpStream;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http_connector.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: hyper::Uri) -> Self::Future {
+        let connect_fut = self.http_connector.call(uri);
+        let tcp_config = self.tcp_config.clone();
+
+        Box::pin(async move {
+            let tcp_stream = connect_fut.await?;
+            configure_tcp_socket(&tcp_stream, &tcp_config)?;
+            Ok(tcp_stream)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::Ipv6Addr;
@@ -600,4 +720,73 @@ mod tests {
         );
         _get_hostname(OsString::from("")).unwrap_err();
     }
+
+    #[tokio::test]
+    async fn test_configure_tcp_socket() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let socket_addr = listener.local_addr().unwrap();
+        let tcp_stream = TcpStream::connect(socket_addr).await.unwrap();
+
+        let tcp_config = TcpConfig {
+            keepalive_interval: Some(Duration::from_secs(17)),
+            keepalive_retries: Some(5),
+            connect_timeout: None,
+            read_timeout: Some(Duration::from_secs(3)),
+            write_timeout: Some(Duration::from_secs(4)),
+        };
+        configure_tcp_socket(&tcp_stream, &tcp_config).unwrap();
+
+        let sock_ref = SockRef::from(&tcp_stream);
+        assert!(sock_ref.keepalive().unwrap());
+        assert_eq!(
+            sock_ref.read_timeout().unwrap(),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            sock_ref.write_timeout().unwrap(),
+            Some(Duration::from_secs(4))
+        );
+
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(sock_ref.keepalive_time().unwrap(), Duration::from_secs(17));
+            assert_eq!(
+                sock_ref.keepalive_interval().unwrap(),
+                Duration::from_secs(17)
+            );
+            assert_eq!(sock_ref.keepalive_retries().unwrap(), 5);
+        }
+        let tcp_config = TcpConfig {
+            keepalive_interval: None,
+            ..Default::default()
+        };
+        configure_tcp_socket(&tcp_stream, &tcp_config).unwrap();
+        assert!(!sock_ref.keepalive().unwrap());
+        assert_eq!(sock_ref.read_timeout().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_config_connector() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let socket_addr = listener.local_addr().unwrap();
+
+        let mut connector = TcpConfigConnector::new(TcpConfig::default());
+        let uri: hyper::Uri = format!("http://{socket_addr}").parse().unwrap();
+        let tcp_stream = connector.call(uri).await.unwrap();
+        let (server_stream, _) = listener.accept().await.unwrap();
+        assert_eq!(
+            server_stream.peer_addr().unwrap(),
+            tcp_stream.local_addr().unwrap()
+        );
+
+        let sock_ref = SockRef::from(&tcp_stream);
+        assert!(sock_ref.keepalive().unwrap());
+        assert!(sock_ref.nodelay().unwrap());
+
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(sock_ref.keepalive_time().unwrap(), Duration::from_secs(30));
+            assert_eq!(sock_ref.keepalive_retries().unwrap(), 3);
+        }
+    }
 }
@@ -18,7 +18,9 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use rand::distributions::Alphanumeric;
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 
 /// Appends a random suffix composed of a hyphen and five random alphanumeric characters.
 pub fn append_random_suffix(string: &str) -> String {
@@ -33,9 +35,48 @@ pub fn append_random_suffix(string: &str) -> String {
     randomized_string
 }
 
+/// Returns a fast, deterministic PRNG seeded with `seed`, for reproducible tests and simulations.
+///
+/// `SmallRng` is not cryptographically secure and its output may differ across platforms and
+/// `rand` versions.
+pub fn seeded_rng(seed: u64) -> SmallRng {
+    SmallRng::seed_from_u64(seed)
+}
+
+/// Shuffles `items` deterministically: the same seed always yields the same permutation.
+pub fn shuffle_seeded<T>(items: &mut [T], seed: u64) {
+    items.shuffle(&mut seeded_rng(seed));
+}
+
+/// Uniformly samples `k` items from a sequence of unknown length using reservoir sampling
+/// (Algorithm R). The sampled items are returned in no particular order.
+///
+/// If the sequence has fewer than `k` items, all of them are returned.
+pub fn reservoir_sample<T>(iter: impl Iterator<Item = T>, k: usize, seed: u64) -> Vec<T> {
+    let mut reservoir = Vec::with_capacity(k);
+
+    if k == 0 {
+        return reservoir;
+    }
+    let mut rng = seeded_rng(seed);
+
+    for (idx, item) in iter.enumerate() {
+        if idx < k {
+            reservoir.push(item);
+            continue;
+        }
+        let replacement_idx = rng.gen_range(0..=idx);
+
+        if replacement_idx < k {
+            reservoir[replacement_idx] = item;
+        }
+    }
+    reservoir
+}
+
 #[cfg(test)]
 mod tests {
-    use super::append_random_suffix;
+    use super::*;
 
     #[test]
     fn test_append_random_suffix() -> anyhow::Result<()> {
@@ -46,4 +87,82 @@ mod tests {
         assert!(chars.all(|ch| ch.is_ascii_alphanumeric()));
         Ok(())
     }
+
+    #[test]
+    fn test_seeded_rng() {
+        let values: Vec<u64> = seeded_rng(42)
+            .sample_iter(rand::distributions::Standard)
+            .take(8)
+            .collect();
+        let same_values: Vec<u64> = seeded_rng(42)
+            .sample_iter(rand::distributions::Standard)
+            .take(8)
+            .collect();
+        let other_values: Vec<u64> = seeded_rng(43)
+            .sample_iter(rand::distributions::Standard)
+            .take(8)
+            .collect();
+        assert_eq!(values, same_values);
+        assert_ne!(values, other_values);
+    }
+
+    #[test]
+    fn test_shuffle_seeded() {
+        let items: Vec<usize> = (0..100).collect();
+
+        let mut shuffled_items = items.clone();
+        shuffle_seeded(&mut shuffled_items, 42);
+
+        let mut same_shuffled_items = items.clone();
+        shuffle_seeded(&mut same_shuffled_items, 42);
+
+        let mut other_shuffled_items = items.clone();
+        shuffle_seeded(&mut other_shuffled_items, 43);
+
+        assert_eq!(shuffled_items, same_shuffled_items);
+        assert_ne!(shuffled_items, other_shuffled_items);
+        assert_ne!(shuffled_items, items);
+
+        shuffled_items.sort_unstable();
+        assert_eq!(shuffled_items, items);
+    }
+
+    #[test]
+    fn test_reservoir_sample() {
+        assert!(reservoir_sample(0..100, 0, 42).is_empty());
+
+        let mut sample = reservoir_sample(0..3, 5, 42);
+        sample.sort_unstable();
+        assert_eq!(sample, [0, 1, 2]);
+
+        let sample = reservoir_sample(0..1_000, 10, 42);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|item| *item < 1_000));
+
+        let mut deduped_sample = sample.clone();
+        deduped_sample.sort_unstable();
+        deduped_sample.dedup();
+        assert_eq!(deduped_sample.len(), 10);
+
+        assert_eq!(reservoir_sample(0..1_000, 10, 42), sample);
+        assert_ne!(reservoir_sample(0..1_000, 10, 43), sample);
+    }
+
+    #[test]
+    fn test_reservoir_sample_is_uniform() {
+        const NUM_ITEMS: usize = 10;
+        const NUM_TRIALS: u64 = 10_000;
+
+        let mut counts = [0usize; NUM_ITEMS];
+
+        for seed in 0..NUM_TRIALS {
+            for item in reservoir_sample(0..NUM_ITEMS, 2, seed) {
+                counts[item] += 1;
+            }
+        }
+        // Each item is expected to be sampled 2,000 times.
+        for count in counts {
+            assert!((1_700..=2_300).contains(&count), "{counts:?}");
+        }
+    }
 }
@@ -17,19 +17,41 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use std::task::{Context as TaskContext, Poll};
 use std::time::Duration;
 
-use once_cell::sync::OnceCell;
+use anyhow::Context;
+use futures::FutureExt;
+use once_cell::sync::{Lazy, OnceCell};
+use pin_project::pin_project;
 use prometheus::{Gauge, IntCounter, IntGauge};
-use tokio::runtime::Runtime;
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tokio_metrics::{RuntimeMetrics, RuntimeMonitor};
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::{reload, EnvFilter, Registry};
 
-use crate::metrics::{new_counter, new_float_gauge, new_gauge};
+use crate::metrics::{new_counter, new_counter_vec, new_float_gauge, new_gauge, IntCounterVec};
 
 static RUNTIMES: OnceCell<HashMap<RuntimeType, tokio::runtime::Runtime>> = OnceCell::new();
 
+/// Handle used to change the log filter of the node at runtime.
+static ENV_FILTER_RELOAD_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Log filter the node was started with.
+static ORIGINAL_ENV_FILTER: OnceCell<String> = OnceCell::new();
+
 /// Describes which runtime an actor should run on.
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
 pub enum RuntimeType {
@@ -48,14 +70,32 @@ pub enum RuntimeType {
     ///
     /// Task are expect to yield within 500 micros.
     NonBlocking,
+
+    /// The indexing runtime runs the CPU-heavy tasks of the split indexing pipeline.
+    ///
+    /// It is isolated from the searching runtime so that long-running indexing tasks do not
+    /// starve the I/O polling of searches.
+    Indexing,
+
+    /// The searching runtime runs the I/O-heavy tasks of searches, such as the coordination of
+    /// root searches.
+    Searching,
 }
 
+/// Share of the cpu intensive threads allocated to the indexing runtime by default. The rest goes
+/// to the blocking runtime, which runs the doc processors, packagers, and merge executors.
+const DEFAULT_INDEXING_THREADS_PERCENT: usize = 75;
+
 #[derive(Debug, Clone, Copy)]
 pub struct RuntimesConfig {
     /// Number of worker threads allocated to the non-blocking runtime.
     pub num_threads_non_blocking: usize,
     /// Number of worker threads allocated to the blocking runtime.
     pub num_threads_blocking: usize,
+    /// Number of worker threads allocated to the indexing runtime.
+    pub num_threads_indexing: usize,
+    /// Number of worker threads allocated to the searching runtime.
+    pub num_threads_searching: usize,
 }
 
 impl RuntimesConfig {
@@ -64,18 +104,40 @@ impl RuntimesConfig {
         RuntimesConfig {
             num_threads_blocking: 1,
             num_threads_non_blocking: 1,
+            num_threads_indexing: 1,
+            num_threads_searching: 1,
         }
     }
 
     pub fn with_num_cpus(num_cpus: usize) -> Self {
+        Self::with_num_cpus_and_indexing_percent(num_cpus, DEFAULT_INDEXING_THREADS_PERCENT)
+    }
+
+    /// Splits `num_cpus` threads between the runtimes, handing `indexing_threads_percent`% of
+    /// the CPU-intensive threads to the indexing runtime and the rest to the blocking runtime.
+    pub fn with_num_cpus_and_indexing_percent(
+        num_cpus: usize,
+        indexing_threads_percent: usize,
+    ) -> Self {
         // Non blocking task are supposed to be io intensive, and not require many threads...
         let num_threads_non_blocking = if num_cpus > 6 { 2 } else { 1 };
-        // On the other hand the blocking actors are cpu intensive. We allocate
-        // almost all of the threads to them.
-        let num_threads_blocking = (num_cpus - num_threads_non_blocking).max(1);
+        // ... and so is searching: its threads spend most of their time waiting.
+        let num_threads_searching = num_threads_non_blocking;
+        // On the other hand the blocking and indexing actors are cpu intensive. We allocate
+        // them the remaining threads, so that a node never runs more worker threads than it has
+        // cpus (save for the one thread minimum per runtime).
+        let num_threads_cpu_intensive = num_cpus
+            .saturating_sub(num_threads_non_blocking + num_threads_searching)
+            .max(2);
+        let num_threads_indexing = (num_threads_cpu_intensive * indexing_threads_percent.min(100)
+            / 100)
+            .clamp(1, num_threads_cpu_intensive - 1);
+        let num_threads_blocking = num_threads_cpu_intensive - num_threads_indexing;
         RuntimesConfig {
             num_threads_non_blocking,
             num_threads_blocking,
+            num_threads_indexing,
+            num_threads_searching,
         }
     }
 }
@@ -83,12 +145,18 @@ impl RuntimesConfig {
 impl Default for RuntimesConfig {
     fn default() -> Self {
         let num_cpus = crate::num_cpus();
-        Self::with_num_cpus(num_cpus)
+        let indexing_threads_percent = crate::get_from_env_with_validation(
+            "QW_INDEXING_THREADS_PERCENT",
+            DEFAULT_INDEXING_THREADS_PERCENT,
+            |percent| (1..=99).contains(percent),
+            "the percentage must be between 1 and 99",
+        );
+        Self::with_num_cpus_and_indexing_percent(num_cpus, indexing_threads_percent)
     }
 }
 
 fn start_runtimes(config: RuntimesConfig) -> HashMap<RuntimeType, Runtime> {
-    let mut runtimes = HashMap::with_capacity(2);
+    let mut runtimes = HashMap::with_capacity(4);
 
     let disable_lifo_slot: bool = crate::get_from_env("QW_DISABLE_TOKIO_LIFO_SLOT", false);
 
@@ -124,6 +192,34 @@ fn start_runtimes(config: RuntimesConfig) -> HashMap<RuntimeType, Runtime> {
     scrape_tokio_runtime_metrics(non_blocking_runtime.handle(), "non_blocking");
     runtimes.insert(RuntimeType::NonBlocking, non_blocking_runtime);
 
+    let indexing_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(config.num_threads_indexing)
+        .thread_name_fn(|| {
+            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
+            let id = ATOMIC_ID.fetch_add(1, Ordering::AcqRel);
+            format!("indexing-{id}")
+        })
+        .enable_all()
+        .build()
+        .unwrap();
+
+    scrape_tokio_runtime_metrics(indexing_runtime.handle(), "indexing");
+    runtimes.insert(RuntimeType::Indexing, indexing_runtime);
+
+    let searching_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(config.num_threads_searching)
+        .thread_name_fn(|| {
+            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
+            let id = ATOMIC_ID.fetch_add(1, Ordering::AcqRel);
+            format!("searching-{id}")
+        })
+        .enable_all()
+        .build()
+        .unwrap();
+
+    scrape_tokio_runtime_metrics(searching_runtime.handle(), "searching");
+    runtimes.insert(RuntimeType::Searching, searching_runtime);
+
     runtimes
 }
 
@@ -151,11 +247,380 @@ impl RuntimeType {
             .handle()
             .clone()
     }
+
+    /// Returns a handle to the runtime if the runtimes were initialized, without initializing
+    /// them.
+    fn try_get_runtime_handle(self) -> Option<Handle> {
+        let runtime = RUNTIMES.get()?.get(&self)?;
+        Some(runtime.handle().clone())
+    }
+}
+
+/// Provides the handles of the runtimes dedicated to indexing and searching.
+pub struct QuickwitRuntimes;
+
+impl QuickwitRuntimes {
+    /// Returns a handle to the indexing runtime. See [`RuntimeType::Indexing`].
+    pub fn indexing() -> tokio::runtime::Handle {
+        RuntimeType::Indexing.get_runtime_handle()
+    }
+
+    /// Returns a handle to the searching runtime. See [`RuntimeType::Searching`].
+    pub fn searching() -> tokio::runtime::Handle {
+        RuntimeType::Searching.get_runtime_handle()
+    }
+
+    /// Returns a handle to the searching runtime if the runtimes were initialized, or to the
+    /// current runtime otherwise. The runtimes are only initialized by the nodes running indexing
+    /// workloads, which searches must be isolated from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtimes were not initialized and this is called outside of a Tokio runtime.
+    pub fn searching_or_current() -> tokio::runtime::Handle {
+        RuntimeType::Searching
+            .try_get_runtime_handle()
+            .unwrap_or_else(Handle::current)
+    }
+}
+
+/// A [`JoinHandle`] that aborts its task when it is dropped, so that cancelling the future
+/// awaiting a spawned task cancels the task too.
+pub struct AbortOnDropJoinHandle<T>(JoinHandle<T>);
+
+impl<T> AbortOnDropJoinHandle<T> {
+    pub fn new(join_handle: JoinHandle<T>) -> Self {
+        Self(join_handle)
+    }
+}
+
+impl<T> Future for AbortOnDropJoinHandle<T> {
+    type Output = Result<T, tokio::task::JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDropJoinHandle<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Delay before a task that panicked is restarted by [`spawn_with_panic_handler`].
+const PANIC_RESTART_DELAY: Duration = Duration::from_secs(1);
+
+static PANICS_TOTAL: Lazy<IntCounterVec<1>> = Lazy::new(|| {
+    new_counter_vec(
+        "panics_total",
+        "Number of panics caught in background tasks.",
+        "runtime",
+        &[],
+        ["task_name"],
+    )
+});
+
+thread_local! {
+    /// Backtrace of the last panic that occurred on the current thread.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+    /// Whether the current thread is polling a task spawned with a panic handler.
+    static IN_PANIC_HANDLED_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs, once, a panic hook that records the backtrace of the panics of the tasks spawned with
+/// a panic handler so that it can be logged. Capturing a backtrace is expensive, so the other
+/// panics are left to the previous panic hook, which is still invoked and honors `RUST_BACKTRACE`.
+fn install_panic_backtrace_hook() {
+    static INSTALL_ONCE: Once = Once::new();
+
+    INSTALL_ONCE.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            if IN_PANIC_HANDLED_TASK.get() {
+                PANIC_BACKTRACE.with(|backtrace| {
+                    *backtrace.borrow_mut() = Some(Backtrace::force_capture());
+                });
+            }
+            previous_hook(panic_info);
+        }));
+    });
+}
+
+/// Flags the current thread as polling a task spawned with a panic handler until dropped.
+struct PanicHandledTaskGuard {
+    was_in_panic_handled_task: bool,
+}
+
+impl PanicHandledTaskGuard {
+    fn enter() -> Self {
+        Self {
+            was_in_panic_handled_task: IN_PANIC_HANDLED_TASK.replace(true),
+        }
+    }
+}
+
+impl Drop for PanicHandledTaskGuard {
+    fn drop(&mut self) {
+        IN_PANIC_HANDLED_TASK.set(self.was_in_panic_handled_task);
+    }
+}
+
+#[pin_project]
+struct PanicHandledFuture<F> {
+    #[pin]
+    future: F,
+}
+
+impl<F: Future> Future for PanicHandledFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let _guard = PanicHandledTaskGuard::enter();
+        self.project().future.poll(cx)
+    }
+}
+
+/// Runs `future`, then logs and counts its panic, if any.
+async fn catch_panic<Fut: Future>(
+    future: Fut,
+    task_name: &str,
+    restart: bool,
+) -> Result<Fut::Output, Box<dyn Any + Send>> {
+    let panic_payload = match AssertUnwindSafe(PanicHandledFuture { future })
+        .catch_unwind()
+        .await
+    {
+        Ok(output) => return Ok(output),
+        Err(panic_payload) => panic_payload,
+    };
+    let backtrace = PANIC_BACKTRACE
+        .with(|backtrace| backtrace.borrow_mut().take())
+        .map(|backtrace| backtrace.to_string())
+        .unwrap_or_default();
+    error!(
+        task_name=%task_name,
+        panic_message=%panic_message(&*panic_payload),
+        backtrace=%backtrace,
+        restart,
+        "background task panicked"
+    );
+    PANICS_TOTAL.with_label_values([task_name]).inc();
+    Err(panic_payload)
+}
+
+fn panic_message(panic_payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic_payload.downcast_ref::<&'static str>() {
+        message
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic payload"
+    }
+}
+
+/// Spawns a long-running background task on `runtime` and logs its panics as structured errors
+/// instead of letting the task silently disappear.
+///
+/// The task is built by calling `task_factory`. When it panics, the panic is logged, the
+/// `quickwit_runtime_panics_total` counter is incremented, and, if `restart` is `true`, a new
+/// task is built and spawned after a 1-second delay. Otherwise, the panic is propagated to the
+/// returned [`JoinHandle`].
+pub fn spawn_with_panic_handler<F, Fut, T>(
+    runtime: &Handle,
+    task_name: &str,
+    task_factory: F,
+    restart: bool,
+) -> JoinHandle<T>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    install_panic_backtrace_hook();
+    let task_name = task_name.to_string();
+
+    runtime.spawn(async move {
+        loop {
+            let panic_payload = match catch_panic(task_factory(), &task_name, restart).await {
+                Ok(output) => return output,
+                Err(panic_payload) => panic_payload,
+            };
+            if !restart {
+                std::panic::resume_unwind(panic_payload);
+            }
+            tokio::time::sleep(PANIC_RESTART_DELAY).await;
+        }
+    })
+}
+
+/// Spawns `future` on `runtime` as a named task and logs its panic like
+/// [`spawn_with_panic_handler`], without restarting it. The panic is propagated to the returned
+/// [`JoinHandle`].
+///
+/// This is meant for the tasks that cannot be rebuilt from a factory, such as actors, which are
+/// restarted by their supervisor.
+pub fn spawn_named_task_with_panic_handler_on<Fut>(
+    future: Fut,
+    task_name: &'static str,
+    runtime: &Handle,
+) -> JoinHandle<Fut::Output>
+where
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    install_panic_backtrace_hook();
+
+    let future = async move {
+        match catch_panic(future, task_name, false).await {
+            Ok(output) => output,
+            Err(panic_payload) => std::panic::resume_unwind(panic_payload),
+        }
+    };
+    crate::spawn_named_task_on(future, task_name, runtime)
+}
+
+/// Signal broadcast to the components of a node when it starts shutting down.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ShutdownSignal {
+    /// Instant by which components should have flushed their state and stopped.
+    pub deadline: Instant,
+}
+
+/// Coordinates the graceful shutdown of the components of a node.
+///
+/// Components obtain a receiver with [`ShutdownCoordinator::subscribe`], check it in their main
+/// loops with `try_recv()`, and call [`ShutdownCoordinator::component_done`] once they have
+/// stopped. [`ShutdownCoordinator::wait_for_completion`] lets the shutdown initiator wait until
+/// every subscribed component is done.
+#[derive(Clone)]
+pub struct ShutdownCoordinator {
+    signal_tx: broadcast::Sender<ShutdownSignal>,
+    num_pending_components_tx: Arc<watch::Sender<usize>>,
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        // A single signal is ever sent, so a capacity of 1 is enough.
+        let (signal_tx, _signal_rx) = broadcast::channel(1);
+        let (num_pending_components_tx, _num_pending_components_rx) = watch::channel(0);
+        Self {
+            signal_tx,
+            num_pending_components_tx: Arc::new(num_pending_components_tx),
+        }
+    }
+
+    /// Registers a new component and returns the receiver on which it will receive the shutdown
+    /// signal. Each subscriber is expected to call [`Self::component_done`] exactly once.
+    pub fn subscribe(&self) -> broadcast::Receiver<ShutdownSignal> {
+        self.num_pending_components_tx
+            .send_modify(|num_pending_components| *num_pending_components += 1);
+        self.signal_tx.subscribe()
+    }
+
+    /// Broadcasts the shutdown signal to all the subscribed components.
+    pub fn initiate_shutdown(&self, deadline: Instant) {
+        info!(
+            num_pending_components = self.num_pending_components(),
+            "initiating graceful shutdown"
+        );
+        let shutdown_signal = ShutdownSignal { deadline };
+
+        if self.signal_tx.send(shutdown_signal).is_err() {
+            debug!("no component subscribed to the shutdown signal");
+        }
+    }
+
+    /// Notifies the coordinator that the component `component_name` has shut down.
+    pub fn component_done(&self, component_name: &str) {
+        info!(component_name, "component shut down");
+        self.num_pending_components_tx
+            .send_modify(|num_pending_components| {
+                *num_pending_components = num_pending_components.saturating_sub(1)
+            });
+    }
+
+    /// Returns the number of subscribed components that have not called
+    /// [`Self::component_done`] yet.
+    pub fn num_pending_components(&self) -> usize {
+        *self.num_pending_components_tx.borrow()
+    }
+
+    /// Waits for all the subscribed components to be done. Returns `false` if some components
+    /// were still pending after `timeout`.
+    pub async fn wait_for_completion(&self, timeout: Duration) -> bool {
+        let mut num_pending_components_rx = self.num_pending_components_tx.subscribe();
+        let wait_fut = num_pending_components_rx
+            .wait_for(|num_pending_components| *num_pending_components == 0);
+
+        if tokio::time::timeout(timeout, wait_fut).await.is_err() {
+            warn!(
+                num_pending_components = self.num_pending_components(),
+                "timed out waiting for components to shut down"
+            );
+            return false;
+        }
+        true
+    }
+}
+
+/// Registers the handle of the reloadable log filter layer. This must be called at most once,
+/// when the tracing subscriber is set up.
+pub fn set_env_filter_reload_handle(
+    reload_handle: reload::Handle<EnvFilter, Registry>,
+) -> anyhow::Result<()> {
+    let original_env_filter = reload_handle.with_current(|env_filter| env_filter.to_string())?;
+    ENV_FILTER_RELOAD_HANDLE
+        .set(reload_handle)
+        .map_err(|_| anyhow::anyhow!("log filter reload handle is already set"))?;
+    ORIGINAL_ENV_FILTER.get_or_init(|| original_env_filter);
+    Ok(())
+}
+
+fn env_filter_reload_handle() -> anyhow::Result<&'static reload::Handle<EnvFilter, Registry>> {
+    ENV_FILTER_RELOAD_HANDLE
+        .get()
+        .context("log filter is not reloadable: tracing subscriber was not set up by Quickwit")
+}
+
+/// Returns the log filter the node was started with.
+pub fn original_env_filter() -> Option<&'static str> {
+    ORIGINAL_ENV_FILTER.get().map(String::as_str)
+}
+
+/// Returns the log filter currently in use.
+pub fn current_env_filter() -> anyhow::Result<String> {
+    let current_env_filter =
+        env_filter_reload_handle()?.with_current(|env_filter| env_filter.to_string())?;
+    Ok(current_env_filter)
+}
+
+/// Replaces the log filter with `env_filter_def` (for instance,
+/// `quickwit_search=debug,quickwit_storage=trace`) and returns the previous one.
+pub fn reload_env_filter(env_filter_def: &str) -> anyhow::Result<String> {
+    let reload_handle = env_filter_reload_handle()?;
+    let new_env_filter = EnvFilter::try_new(env_filter_def)
+        .with_context(|| format!("invalid log filter `{env_filter_def}`"))?;
+    let mut previous_env_filter_opt: Option<EnvFilter> = None;
+    reload_handle.modify(|env_filter| {
+        previous_env_filter_opt = Some(std::mem::replace(env_filter, new_env_filter));
+    })?;
+    let previous_env_filter = previous_env_filter_opt
+        .expect("the log filter should have been replaced")
+        .to_string();
+    Ok(previous_env_filter)
 }
 
 /// Spawns a background task
 pub fn scrape_tokio_runtime_metrics(handle: &tokio::runtime::Handle, label: &'static str) {
     let runtime_monitor = RuntimeMonitor::new(handle);
+    let handle_clone = handle.clone();
     handle.spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(1));
         let mut prometheus_runtime_metrics = PrometheusRuntimeMetrics::new(label);
@@ -163,11 +628,16 @@ pub fn scrape_tokio_runtime_metrics(handle: &tokio::runtime::Handle, label: &'st
         for tokio_runtime_metrics in runtime_monitor.intervals() {
             interval.tick().await;
             prometheus_runtime_metrics.update(&tokio_runtime_metrics);
+            prometheus_runtime_metrics
+                .active_tasks
+                .set(handle_clone.metrics().active_tasks_count() as i64);
         }
     });
 }
 
 struct PrometheusRuntimeMetrics {
+    active_tasks: IntGauge,
+    idle_threads: IntGauge,
     scheduled_tasks: IntGauge,
     worker_busy_duration_milliseconds_total: IntCounter,
     worker_busy_ratio: Gauge,
@@ -177,6 +647,19 @@ struct PrometheusRuntimeMetrics {
 impl PrometheusRuntimeMetrics {
     pub fn new(label: &'static str) -> Self {
         Self {
+            active_tasks: new_gauge(
+                "active_tasks",
+                "The number of tasks currently alive in the runtime.",
+                "runtime",
+                &[("runtime_type", label)],
+            ),
+            idle_threads: new_gauge(
+                "idle_threads",
+                "The estimated number of idle worker threads since the last time runtime metrics \
+                 were collected.",
+                "runtime",
+                &[("runtime_type", label)],
+            ),
             scheduled_tasks: new_gauge(
                 "tokio_scheduled_tasks",
                 "The total number of tasks currently scheduled in workers' local queues.",
@@ -213,6 +696,11 @@ impl PrometheusRuntimeMetrics {
         self.worker_busy_ratio.set(runtime_metrics.busy_ratio());
         self.worker_threads
             .set(runtime_metrics.workers_count as i64);
+
+        let num_busy_threads = runtime_metrics.busy_ratio() * runtime_metrics.workers_count as f64;
+        let num_idle_threads = runtime_metrics.workers_count as f64 - num_busy_threads;
+        self.idle_threads
+            .set(num_idle_threads.round().max(0.0) as i64);
     }
 }
 
@@ -220,24 +708,295 @@ impl PrometheusRuntimeMetrics {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_abort_on_drop_join_handle() {
+        let join_handle = AbortOnDropJoinHandle::new(tokio::spawn(async { 42 }));
+        assert_eq!(join_handle.await.unwrap(), 42);
+
+        let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let join_handle = tokio::spawn(async move {
+            let _ = rx.await;
+        });
+        let abort_handle = join_handle.abort_handle();
+        drop(AbortOnDropJoinHandle::new(join_handle));
+
+        for _ in 0..100 {
+            if abort_handle.is_finished() {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("the task should have been aborted");
+    }
+
+    #[tokio::test]
+    async fn test_searching_or_current_runtime() {
+        // Falls back to the current runtime unless another test initialized the runtimes.
+        let handle = QuickwitRuntimes::searching_or_current();
+        assert_eq!(handle.spawn(async { 42 }).await.unwrap(), 42);
+    }
+
     #[test]
     fn test_runtimes_config_default() {
         let runtime_default = RuntimesConfig::default();
-        assert!(runtime_default.num_threads_non_blocking <= runtime_default.num_threads_blocking);
         assert!(runtime_default.num_threads_non_blocking <= 2);
+        assert!(total_num_threads(&runtime_default) <= crate::num_cpus().max(4));
+    }
+
+    fn total_num_threads(runtime: &RuntimesConfig) -> usize {
+        runtime.num_threads_blocking
+            + runtime.num_threads_non_blocking
+            + runtime.num_threads_indexing
+            + runtime.num_threads_searching
     }
 
     #[test]
     fn test_runtimes_with_given_num_cpus_10() {
         let runtime = RuntimesConfig::with_num_cpus(10);
-        assert_eq!(runtime.num_threads_blocking, 8);
+        assert_eq!(runtime.num_threads_blocking, 2);
         assert_eq!(runtime.num_threads_non_blocking, 2);
+        assert_eq!(runtime.num_threads_indexing, 4);
+        assert_eq!(runtime.num_threads_searching, 2);
+        assert_eq!(total_num_threads(&runtime), 10);
     }
 
     #[test]
     fn test_runtimes_with_given_num_cpus_3() {
         let runtime = RuntimesConfig::with_num_cpus(3);
-        assert_eq!(runtime.num_threads_blocking, 2);
+        assert_eq!(runtime.num_threads_blocking, 1);
+        assert_eq!(runtime.num_threads_non_blocking, 1);
+        assert_eq!(runtime.num_threads_indexing, 1);
+        assert_eq!(runtime.num_threads_searching, 1);
+    }
+
+    #[test]
+    fn test_runtimes_with_given_num_cpus_1() {
+        let runtime = RuntimesConfig::with_num_cpus(1);
+        assert_eq!(runtime.num_threads_blocking, 1);
         assert_eq!(runtime.num_threads_non_blocking, 1);
+        assert_eq!(runtime.num_threads_indexing, 1);
+        assert_eq!(runtime.num_threads_searching, 1);
+    }
+
+    #[test]
+    fn test_runtimes_never_exceed_num_cpus() {
+        for num_cpus in 4..=128 {
+            let runtime = RuntimesConfig::with_num_cpus(num_cpus);
+            assert_eq!(total_num_threads(&runtime), num_cpus);
+        }
+    }
+
+    #[test]
+    fn test_runtimes_with_given_indexing_percent() {
+        let runtime = RuntimesConfig::with_num_cpus_and_indexing_percent(34, 50);
+        assert_eq!(runtime.num_threads_indexing, 15);
+        assert_eq!(runtime.num_threads_blocking, 15);
+        assert_eq!(total_num_threads(&runtime), 34);
+
+        // Both cpu intensive runtimes keep at least one thread.
+        let runtime = RuntimesConfig::with_num_cpus_and_indexing_percent(34, 100);
+        assert_eq!(runtime.num_threads_indexing, 29);
+        assert_eq!(runtime.num_threads_blocking, 1);
+
+        let runtime = RuntimesConfig::with_num_cpus_and_indexing_percent(34, 0);
+        assert_eq!(runtime.num_threads_indexing, 1);
+        assert_eq!(runtime.num_threads_blocking, 29);
+    }
+
+    #[test]
+    fn test_quickwit_runtimes_isolation() {
+        // The runtimes for tests have a single worker thread each, so blocking the indexing
+        // runtime thread blocks the whole indexing runtime.
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let (blocked_tx, blocked_rx) = std::sync::mpsc::channel::<String>();
+
+        let indexing_task = QuickwitRuntimes::indexing().spawn(async move {
+            let thread_name = std::thread::current().name().unwrap().to_string();
+            blocked_tx.send(thread_name).unwrap();
+            release_rx.recv().unwrap();
+        });
+        let indexing_thread_name = blocked_rx.recv().unwrap();
+        assert!(indexing_thread_name.starts_with("indexing-"));
+
+        let searching_task = QuickwitRuntimes::searching()
+            .spawn(async { std::thread::current().name().unwrap().to_string() });
+        let searching_thread_name = futures::executor::block_on(searching_task).unwrap();
+        assert!(searching_thread_name.starts_with("searching-"));
+
+        release_tx.send(()).unwrap();
+        futures::executor::block_on(indexing_task).unwrap();
+    }
+
+    #[test]
+    fn test_reload_env_filter() {
+        assert!(current_env_filter().is_err());
+        assert!(reload_env_filter("debug").is_err());
+
+        // The handle stays valid as long as the layer is alive.
+        let (_env_filter_layer, reload_handle) =
+            reload::Layer::<EnvFilter, Registry>::new(EnvFilter::new("quickwit=info"));
+        set_env_filter_reload_handle(reload_handle.clone()).unwrap();
+        set_env_filter_reload_handle(reload_handle).unwrap_err();
+
+        assert_eq!(original_env_filter(), Some("quickwit=info"));
+        assert_eq!(current_env_filter().unwrap(), "quickwit=info");
+
+        let previous_env_filter = reload_env_filter("quickwit_search=debug").unwrap();
+        assert_eq!(previous_env_filter, "quickwit=info");
+        assert_eq!(current_env_filter().unwrap(), "quickwit_search=debug");
+        reload_env_filter("quickwit=notalevel").unwrap_err();
+
+        // Rolling back to the previous filter.
+        let previous_env_filter = reload_env_filter(&previous_env_filter).unwrap();
+        assert_eq!(previous_env_filter, "quickwit_search=debug");
+        assert_eq!(current_env_filter().unwrap(), "quickwit=info");
+        assert_eq!(original_env_filter(), Some("quickwit=info"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn_with_panic_handler_restarts_task() {
+        let panics_total = PANICS_TOTAL.with_label_values(["test_restart"]);
+        let num_attempts = Arc::new(AtomicUsize::new(0));
+        let num_attempts_clone = num_attempts.clone();
+
+        let join_handle = spawn_with_panic_handler(
+            &Handle::current(),
+            "test_restart",
+            move || {
+                let num_attempts = num_attempts_clone.clone();
+                async move {
+                    if num_attempts.fetch_add(1, Ordering::Relaxed) < 2 {
+                        panic!("transient failure");
+                    }
+                    42
+                }
+            },
+            true,
+        );
+        assert_eq!(join_handle.await.unwrap(), 42);
+        assert_eq!(num_attempts.load(Ordering::Relaxed), 3);
+        assert_eq!(panics_total.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_panic_handler_without_restart() {
+        let panics_total = PANICS_TOTAL.with_label_values(["test_no_restart"]);
+
+        let join_handle = spawn_with_panic_handler(
+            &Handle::current(),
+            "test_no_restart",
+            || async { panic!("fatal failure") },
+            false,
+        );
+        let join_error = join_handle.await.unwrap_err();
+        assert!(join_error.is_panic());
+        assert_eq!(panic_message(&*join_error.into_panic()), "fatal failure");
+        assert_eq!(panics_total.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_named_task_with_panic_handler_on() {
+        let panics_total = PANICS_TOTAL.with_label_values(["test_named_task"]);
+
+        let join_handle = spawn_named_task_with_panic_handler_on(
+            async { panic!("actor failure") },
+            "test_named_task",
+            &Handle::current(),
+        );
+        let join_error = join_handle.await.unwrap_err();
+        assert!(join_error.is_panic());
+        assert_eq!(panic_message(&*join_error.into_panic()), "actor failure");
+        assert_eq!(panics_total.get(), 1);
+
+        let join_handle = spawn_named_task_with_panic_handler_on(
+            async { 42 },
+            "test_named_task",
+            &Handle::current(),
+        );
+        assert_eq!(join_handle.await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_panic_hook_only_captures_backtraces_of_panic_handled_tasks() {
+        install_panic_backtrace_hook();
+
+        // The current-thread runtime polls the task on the thread of the test.
+        let join_error = tokio::spawn(async { panic!("unhandled failure") })
+            .await
+            .unwrap_err();
+        assert!(join_error.is_panic());
+        assert!(PANIC_BACKTRACE.with(|backtrace| backtrace.borrow().is_none()));
+        assert!(!IN_PANIC_HANDLED_TASK.get());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_coordinator_broadcasts_signal_to_all_components() {
+        let coordinator = ShutdownCoordinator::new();
+        let mut receivers: Vec<broadcast::Receiver<ShutdownSignal>> =
+            (0..3).map(|_| coordinator.subscribe()).collect();
+        assert_eq!(coordinator.num_pending_components(), 3);
+
+        for receiver in &mut receivers {
+            assert!(matches!(
+                receiver.try_recv(),
+                Err(broadcast::error::TryRecvError::Empty)
+            ));
+        }
+        let deadline = Instant::now() + Duration::from_secs(30);
+        coordinator.initiate_shutdown(deadline);
+
+        for receiver in &mut receivers {
+            assert_eq!(receiver.try_recv().unwrap(), ShutdownSignal { deadline });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_coordinator_wait_for_completion() {
+        let coordinator = ShutdownCoordinator::new();
+        assert!(coordinator.wait_for_completion(Duration::ZERO).await);
+
+        let mut join_handles = Vec::new();
+
+        for component_id in 0..3 {
+            let coordinator_clone = coordinator.clone();
+            let mut signal_rx = coordinator.subscribe();
+
+            let join_handle = tokio::spawn(async move {
+                let shutdown_signal = signal_rx.recv().await.unwrap();
+                coordinator_clone.component_done(&format!("component-{component_id}"));
+                shutdown_signal
+            });
+            join_handles.push(join_handle);
+        }
+        let deadline = Instant::now() + Duration::from_secs(30);
+        coordinator.initiate_shutdown(deadline);
+
+        assert!(
+            coordinator
+                .wait_for_completion(Duration::from_secs(5))
+                .await
+        );
+        assert_eq!(coordinator.num_pending_components(), 0);
+
+        for join_handle in join_handles {
+            assert_eq!(join_handle.await.unwrap().deadline, deadline);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_coordinator_wait_for_completion_timeout() {
+        let coordinator = ShutdownCoordinator::new();
+        let _signal_rx_0 = coordinator.subscribe();
+        let _signal_rx_1 = coordinator.subscribe();
+
+        coordinator.initiate_shutdown(Instant::now() + Duration::from_secs(1));
+        coordinator.component_done("component-0");
+
+        assert!(
+            !coordinator
+                .wait_for_completion(Duration::from_secs(1))
+                .await
+        );
+        assert_eq!(coordinator.num_pending_components(), 1);
     }
 }
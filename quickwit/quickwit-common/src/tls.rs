@@ -0,0 +1,520 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context};
+use arc_swap::ArcSwap;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Number of days before the expiry of a TLS certificate at which a warning is logged.
+const CERTIFICATE_EXPIRY_WARNING_DAYS: [u64; 3] = [30, 14, 7];
+
+/// Interval at which the expiry date of the TLS certificate is checked.
+const CERTIFICATE_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Delay between the first change detected on the certificate files and the reload. Certificate
+/// renewals usually rewrite both the certificate and the key files, so we wait for both to be
+/// written before reloading.
+const CERTIFICATE_RELOAD_DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Certificate resolver handing out the certificate currently loaded by the
+/// [`TlsCertificateReloader`].
+///
+/// The certificate is picked at handshake time, so swapping it only affects new connections:
+/// established connections keep using the certificate they were negotiated with.
+struct ReloadableCertResolver {
+    certified_key: ArcSwap<CertifiedKey>,
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key.load_full())
+    }
+}
+
+/// A validated certificate chain and its private key.
+struct LoadedCertificate {
+    certified_key: CertifiedKey,
+    not_after_unix_secs: i64,
+}
+
+/// Loads a TLS certificate and its private key from PEM files and reloads them when they change,
+/// so that certificates can be renewed without restarting the node.
+///
+/// Servers must be built from [`TlsCertificateReloader::server_config`]. The certificate files are
+/// only watched once [`TlsCertificateReloader::watch`] is called.
+pub struct TlsCertificateReloader {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    resolver: Arc<ReloadableCertResolver>,
+    server_config: Arc<ServerConfig>,
+    not_after_unix_secs: AtomicI64,
+    // Smallest expiry threshold in `CERTIFICATE_EXPIRY_WARNING_DAYS` for which a warning was
+    // logged, or `u64::MAX` if none was.
+    last_expiry_warning_days: AtomicU64,
+}
+
+impl fmt::Debug for TlsCertificateReloader {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("TlsCertificateReloader")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish()
+    }
+}
+
+impl TlsCertificateReloader {
+    /// Loads the certificate chain stored at `cert_path` and the private key stored at `key_path`.
+    /// Fails if the certificate cannot be parsed or is not currently valid.
+    pub fn load(
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        let cert_path = cert_path.into();
+        let key_path = key_path.into();
+        let loaded_certificate = load_certificate(&cert_path, &key_path)?;
+
+        let resolver = Arc::new(ReloadableCertResolver {
+            certified_key: ArcSwap::from_pointee(loaded_certificate.certified_key),
+        });
+        let mut server_config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_cert_resolver(resolver.clone());
+        server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        let reloader = Self {
+            cert_path,
+            key_path,
+            resolver,
+            server_config: Arc::new(server_config),
+            not_after_unix_secs: AtomicI64::new(loaded_certificate.not_after_unix_secs),
+            last_expiry_warning_days: AtomicU64::new(u64::MAX),
+        };
+        reloader.check_expiry(unix_timestamp_secs());
+        Ok(reloader)
+    }
+
+    /// Returns the server configuration to build TLS servers with. The configuration always
+    /// serves the last certificate successfully loaded.
+    pub fn server_config(&self) -> Arc<ServerConfig> {
+        self.server_config.clone()
+    }
+
+    /// Reloads the certificate and the private key from disk. The new certificate is validated
+    /// before being swapped in: on error, the current certificate is kept.
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let loaded_certificate = load_certificate(&self.cert_path, &self.key_path)?;
+
+        if self.resolver.certified_key.load().cert == loaded_certificate.certified_key.cert {
+            return Ok(());
+        }
+        self.resolver
+            .certified_key
+            .store(Arc::new(loaded_certificate.certified_key));
+        self.not_after_unix_secs
+            .store(loaded_certificate.not_after_unix_secs, Ordering::Relaxed);
+        self.last_expiry_warning_days
+            .store(u64::MAX, Ordering::Relaxed);
+        info!(
+            cert_path=%self.cert_path.display(),
+            "reloaded TLS certificate"
+        );
+        self.check_expiry(unix_timestamp_secs());
+        Ok(())
+    }
+
+    /// Watches the certificate files and reloads them when they change. Also periodically logs a
+    /// warning when the certificate is about to expire. Watching stops when the returned
+    /// [`TlsCertificateWatcher`] is dropped. Must be called from within a tokio runtime.
+    pub fn watch(self: &Arc<Self>) -> anyhow::Result<TlsCertificateWatcher> {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event_res| {
+            // The receiver is only dropped when the watcher is.
+            let _ = event_tx.send(event_res);
+        })
+        .context("failed to create TLS certificate files watcher")?;
+
+        // Certificates are often renewed by moving new files in place or swapping symlinks
+        // (certbot, Kubernetes secrets), so we watch the parent directories rather than the files.
+        let watched_dirs: BTreeSet<&Path> = [&self.cert_path, &self.key_path]
+            .into_iter()
+            .map(|path| path.parent().unwrap_or_else(|| Path::new(".")))
+            .collect();
+        for watched_dir in watched_dirs {
+            watcher
+                .watch(watched_dir, RecursiveMode::NonRecursive)
+                .with_context(|| {
+                    format!("failed to watch directory `{}`", watched_dir.display())
+                })?;
+        }
+        let reloader = self.clone();
+
+        let join_handle = tokio::spawn(async move {
+            let mut expiry_check_interval =
+                tokio::time::interval(CERTIFICATE_EXPIRY_CHECK_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    event_res_opt = event_rx.recv() => {
+                        let Some(event_res) = event_res_opt else {
+                            break;
+                        };
+                        if !is_file_change(event_res) {
+                            continue;
+                        }
+                        tokio::time::sleep(CERTIFICATE_RELOAD_DEBOUNCE_DELAY).await;
+                        // Drain the events triggered by the same renewal.
+                        while event_rx.try_recv().is_ok() {}
+
+                        if let Err(error) = reloader.reload() {
+                            error!(error=?error, "failed to reload TLS certificate");
+                        }
+                    }
+                    _ = expiry_check_interval.tick() => {
+                        reloader.check_expiry(unix_timestamp_secs());
+                    }
+                }
+            }
+        });
+        Ok(TlsCertificateWatcher {
+            _watcher: watcher,
+            join_handle,
+        })
+    }
+
+    fn check_expiry(&self, now_unix_secs: i64) {
+        let not_after_unix_secs = self.not_after_unix_secs.load(Ordering::Relaxed);
+        let days_until_expiry = (not_after_unix_secs - now_unix_secs).div_euclid(SECONDS_PER_DAY);
+
+        if days_until_expiry < 0 {
+            error!(
+                cert_path=%self.cert_path.display(),
+                "TLS certificate has expired"
+            );
+            return;
+        }
+        let Some(warning_days) = expiry_warning_threshold(days_until_expiry as u64) else {
+            return;
+        };
+        let last_warning_days = self
+            .last_expiry_warning_days
+            .fetch_min(warning_days, Ordering::Relaxed);
+
+        if warning_days < last_warning_days {
+            warn!(
+                cert_path=%self.cert_path.display(),
+                days_until_expiry,
+                "TLS certificate expires in less than {warning_days} days"
+            );
+        }
+    }
+}
+
+fn is_file_change(event_res: notify::Result<notify::Event>) -> bool {
+    match event_res {
+        Ok(event) => matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)),
+        Err(error) => {
+            warn!(error=?error, "failed to watch TLS certificate files");
+            false
+        }
+    }
+}
+
+/// Returns the smallest expiry warning threshold greater than or equal to `days_until_expiry`,
+/// if any.
+fn expiry_warning_threshold(days_until_expiry: u64) -> Option<u64> {
+    CERTIFICATE_EXPIRY_WARNING_DAYS
+        .into_iter()
+        .filter(|warning_days| days_until_expiry <= *warning_days)
+        .min()
+}
+
+/// Handle watching the files of a [`TlsCertificateReloader`]. Dropping it stops the watch.
+pub struct TlsCertificateWatcher {
+    _watcher: RecommendedWatcher,
+    join_handle: JoinHandle<()>,
+}
+
+impl Drop for TlsCertificateWatcher {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+fn unix_timestamp_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn load_certificate(cert_path: &Path, key_path: &Path) -> anyhow::Result<LoadedCertificate> {
+    let cert_pem = std::fs::read(cert_path).with_context(|| {
+        format!(
+            "failed to read TLS certificate file `{}`",
+            cert_path.display()
+        )
+    })?;
+    let cert_chain = rustls_pemfile::certs(&mut &cert_pem[..]).with_context(|| {
+        format!(
+            "failed to parse TLS certificate file `{}`",
+            cert_path.display()
+        )
+    })?;
+    let Some(leaf_cert_der) = cert_chain.first() else {
+        bail!(
+            "no certificate found in TLS certificate file `{}`",
+            cert_path.display()
+        );
+    };
+    let (_, leaf_cert) = x509_parser::parse_x509_certificate(leaf_cert_der).map_err(|error| {
+        anyhow!(
+            "failed to parse TLS certificate file `{}`: {error}",
+            cert_path.display()
+        )
+    })?;
+    let not_before_unix_secs = leaf_cert.validity().not_before.timestamp();
+    let not_after_unix_secs = leaf_cert.validity().not_after.timestamp();
+    let now_unix_secs = unix_timestamp_secs();
+
+    if now_unix_secs < not_before_unix_secs {
+        bail!("TLS certificate `{}` is not valid yet", cert_path.display());
+    }
+    if now_unix_secs > not_after_unix_secs {
+        bail!("TLS certificate `{}` has expired", cert_path.display());
+    }
+    let private_key = load_private_key(key_path)?;
+    let signing_key = rustls::sign::any_supported_type(&private_key).map_err(|_| {
+        anyhow!(
+            "unsupported private key type in TLS key file `{}`",
+            key_path.display()
+        )
+    })?;
+    let cert_chain = cert_chain.into_iter().map(Certificate).collect();
+    let loaded_certificate = LoadedCertificate {
+        certified_key: CertifiedKey::new(cert_chain, signing_key),
+        not_after_unix_secs,
+    };
+    Ok(loaded_certificate)
+}
+
+fn load_private_key(key_path: &Path) -> anyhow::Result<PrivateKey> {
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("failed to read TLS key file `{}`", key_path.display()))?;
+    let mut key_pem_reader = &key_pem[..];
+
+    while let Some(item) = rustls_pemfile::read_one(&mut key_pem_reader)
+        .with_context(|| format!("failed to parse TLS key file `{}`", key_path.display()))?
+    {
+        match item {
+            rustls_pemfile::Item::RSAKey(key_der)
+            | rustls_pemfile::Item::PKCS8Key(key_der)
+            | rustls_pemfile::Item::ECKey(key_der) => return Ok(PrivateKey(key_der)),
+            _ => {}
+        }
+    }
+    bail!(
+        "no private key found in TLS key file `{}`",
+        key_path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    fn generate_certificate(not_after_opt: Option<(i32, u8, u8)>) -> (String, String) {
+        let mut certificate_params = rcgen::CertificateParams::new(vec!["localhost".to_string()]);
+        if let Some((year, month, day)) = not_after_opt {
+            certificate_params.not_before = rcgen::date_time_ymd(year - 1, month, day);
+            certificate_params.not_after = rcgen::date_time_ymd(year, month, day);
+        }
+        let certificate = rcgen::Certificate::from_params(certificate_params).unwrap();
+        let cert_pem = certificate.serialize_pem().unwrap();
+        let key_pem = certificate.serialize_private_key_pem();
+        (cert_pem, key_pem)
+    }
+
+    fn write_certificate(dir: &Path, (cert_pem, key_pem): &(String, String)) {
+        std::fs::write(dir.join("key.pem"), key_pem).unwrap();
+        std::fs::write(dir.join("cert.pem"), cert_pem).unwrap();
+    }
+
+    fn cert_der(cert_pem: &str) -> Certificate {
+        let cert_der = rustls_pemfile::certs(&mut cert_pem.as_bytes())
+            .unwrap()
+            .remove(0);
+        Certificate(cert_der)
+    }
+
+    fn current_cert_der(reloader: &TlsCertificateReloader) -> Certificate {
+        reloader.resolver.certified_key.load().cert[0].clone()
+    }
+
+    #[test]
+    fn test_tls_certificate_reloader_reload() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cert_path = temp_dir.path().join("cert.pem");
+        let key_path = temp_dir.path().join("key.pem");
+
+        let error = TlsCertificateReloader::load(&cert_path, &key_path).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("failed to read TLS certificate file"));
+
+        let certificate_a = generate_certificate(None);
+        write_certificate(temp_dir.path(), &certificate_a);
+        let reloader = TlsCertificateReloader::load(&cert_path, &key_path).unwrap();
+        assert_eq!(current_cert_der(&reloader), cert_der(&certificate_a.0));
+
+        let certificate_b = generate_certificate(None);
+        write_certificate(temp_dir.path(), &certificate_b);
+        reloader.reload().unwrap();
+        assert_eq!(current_cert_der(&reloader), cert_der(&certificate_b.0));
+
+        // Invalid or expired certificates are rejected and the current one is kept.
+        std::fs::write(&cert_path, "not a certificate").unwrap();
+        let error = reloader.reload().unwrap_err();
+        assert!(error.to_string().contains("no certificate found"));
+
+        let expired_certificate = generate_certificate(Some((2000, 1, 1)));
+        write_certificate(temp_dir.path(), &expired_certificate);
+        let error = reloader.reload().unwrap_err();
+        assert!(error.to_string().contains("has expired"));
+
+        write_certificate(temp_dir.path(), &certificate_a);
+        std::fs::write(&key_path, "not a key").unwrap();
+        let error = reloader.reload().unwrap_err();
+        assert!(error.to_string().contains("no private key found"));
+
+        assert_eq!(current_cert_der(&reloader), cert_der(&certificate_b.0));
+    }
+
+    #[test]
+    fn test_expiry_warning_threshold() {
+        assert_eq!(expiry_warning_threshold(90), None);
+        assert_eq!(expiry_warning_threshold(31), None);
+        assert_eq!(expiry_warning_threshold(30), Some(30));
+        assert_eq!(expiry_warning_threshold(15), Some(30));
+        assert_eq!(expiry_warning_threshold(14), Some(14));
+        assert_eq!(expiry_warning_threshold(8), Some(14));
+        assert_eq!(expiry_warning_threshold(7), Some(7));
+        assert_eq!(expiry_warning_threshold(0), Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_tls_certificate_reloader_hot_swap_keeps_connections() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cert_path = temp_dir.path().join("cert.pem");
+        let key_path = temp_dir.path().join("key.pem");
+
+        let certificate_a = generate_certificate(None);
+        write_certificate(temp_dir.path(), &certificate_a);
+        let reloader = Arc::new(TlsCertificateReloader::load(&cert_path, &key_path).unwrap());
+        let _watcher = reloader.watch().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listen_addr = listener.local_addr().unwrap();
+        let tls_acceptor = TlsAcceptor::from(reloader.server_config());
+
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                let tls_acceptor = tls_acceptor.clone();
+
+                tokio::spawn(async move {
+                    let mut tls_stream = tls_acceptor.accept(tcp_stream).await.unwrap();
+                    let mut buffer = [0u8; 4];
+
+                    while tls_stream.read_exact(&mut buffer).await.is_ok() {
+                        tls_stream.write_all(&buffer).await.unwrap();
+                    }
+                });
+            }
+        });
+        let certificate_b = generate_certificate(None);
+
+        let mut root_cert_store = rustls::RootCertStore::empty();
+        root_cert_store.add(&cert_der(&certificate_a.0)).unwrap();
+        root_cert_store.add(&cert_der(&certificate_b.0)).unwrap();
+        let client_config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_cert_store)
+            .with_no_client_auth();
+        let tls_connector = TlsConnector::from(Arc::new(client_config));
+        let server_name = rustls::ServerName::try_from("localhost").unwrap();
+
+        let tcp_stream = TcpStream::connect(listen_addr).await.unwrap();
+        let mut tls_stream_a = tls_connector
+            .connect(server_name.clone(), tcp_stream)
+            .await
+            .unwrap();
+        let peer_certificates = tls_stream_a.get_ref().1.peer_certificates().unwrap();
+        assert_eq!(peer_certificates[0], cert_der(&certificate_a.0));
+
+        write_certificate(temp_dir.path(), &certificate_b);
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while current_cert_der(&reloader) != cert_der(&certificate_b.0) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("certificate should have been reloaded");
+
+        // The connection established before the swap is still up.
+        let mut buffer = [0u8; 4];
+        tls_stream_a.write_all(b"ping").await.unwrap();
+        tls_stream_a.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"ping");
+
+        // New connections are served the new certificate.
+        let tcp_stream = TcpStream::connect(listen_addr).await.unwrap();
+        let mut tls_stream_b = tls_connector
+            .connect(server_name, tcp_stream)
+            .await
+            .unwrap();
+        let peer_certificates = tls_stream_b.get_ref().1.peer_certificates().unwrap();
+        assert_eq!(peer_certificates[0], cert_der(&certificate_b.0));
+
+        tls_stream_b.write_all(b"pong").await.unwrap();
+        tls_stream_b.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"pong");
+    }
+}
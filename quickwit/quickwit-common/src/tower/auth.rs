@@ -0,0 +1,220 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+use tonic::metadata::AsciiMetadataValue;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Metadata key of the token authenticating the gRPC requests exchanged by the nodes of a cluster.
+pub const AUTH_TOKEN_METADATA_KEY: &str = "quickwit-auth-token";
+
+/// Derives the auth token from the cluster key. Returns `None` when the cluster key is empty, in
+/// which case authentication is disabled.
+fn derive_auth_token(cluster_key: &str) -> Option<AsciiMetadataValue> {
+    if cluster_key.is_empty() {
+        return None;
+    }
+    let digest = Sha256::new()
+        .chain_update(AUTH_TOKEN_METADATA_KEY)
+        .chain_update(cluster_key)
+        .finalize();
+    let mut auth_token: AsciiMetadataValue = format!("{digest:x}")
+        .parse()
+        .expect("hex string should be a valid metadata value");
+    auth_token.set_sensitive(true);
+    Some(auth_token)
+}
+
+/// [`Interceptor`] which injects the auth token derived from the cluster key into the metadata of
+/// outgoing requests. No token is injected when the cluster key is empty.
+#[derive(Clone, Default)]
+pub struct AuthTokenInjector {
+    auth_token_opt: Option<AsciiMetadataValue>,
+}
+
+impl AuthTokenInjector {
+    pub fn new(cluster_key: &str) -> Self {
+        Self {
+            auth_token_opt: derive_auth_token(cluster_key),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.auth_token_opt.is_some()
+    }
+}
+
+impl fmt::Debug for AuthTokenInjector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthTokenInjector")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl Interceptor for AuthTokenInjector {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(auth_token) = &self.auth_token_opt {
+            request
+                .metadata_mut()
+                .insert(AUTH_TOKEN_METADATA_KEY, auth_token.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// [`Interceptor`] which rejects incoming requests that do not carry the auth token derived from
+/// the cluster key. All requests are accepted when the cluster key is empty.
+#[derive(Clone)]
+pub struct AuthTokenValidator {
+    expected_auth_token_opt: Option<AsciiMetadataValue>,
+}
+
+impl AuthTokenValidator {
+    pub fn new(cluster_key: &str) -> Self {
+        Self {
+            expected_auth_token_opt: derive_auth_token(cluster_key),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.expected_auth_token_opt.is_some()
+    }
+}
+
+impl fmt::Debug for AuthTokenValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthTokenValidator")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl Interceptor for AuthTokenValidator {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(expected_auth_token) = &self.expected_auth_token_opt else {
+            return Ok(request);
+        };
+        let Some(auth_token) = request.metadata().get(AUTH_TOKEN_METADATA_KEY) else {
+            return Err(Status::unauthenticated(format!(
+                "missing `{AUTH_TOKEN_METADATA_KEY}` metadata"
+            )));
+        };
+        if !constant_time_eq(
+            auth_token.as_encoded_bytes(),
+            expected_auth_token.as_encoded_bytes(),
+        ) {
+            return Err(Status::unauthenticated(format!(
+                "invalid `{AUTH_TOKEN_METADATA_KEY}` metadata"
+            )));
+        }
+        Ok(request)
+    }
+}
+
+/// Compares two byte slices in constant time to avoid leaking the expected token via timing.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter()
+        .zip(right)
+        .fold(0u8, |acc, (left_byte, right_byte)| {
+            acc | (left_byte ^ right_byte)
+        })
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Code;
+
+    use super::*;
+
+    #[test]
+    fn test_derive_auth_token() {
+        assert!(derive_auth_token("").is_none());
+
+        let auth_token = derive_auth_token("my-cluster-key").unwrap();
+        assert!(auth_token.is_sensitive());
+        assert_eq!(auth_token.to_str().unwrap().len(), 64);
+        assert_eq!(derive_auth_token("my-cluster-key").unwrap(), auth_token);
+        assert_ne!(
+            derive_auth_token("my-other-cluster-key").unwrap(),
+            auth_token
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"foo", b"foo"));
+        assert!(!constant_time_eq(b"foo", b"bar"));
+        assert!(!constant_time_eq(b"foo", b"foobar"));
+    }
+
+    #[test]
+    fn test_auth_token_validator() {
+        let mut disabled_validator = AuthTokenValidator::new("");
+        assert!(!disabled_validator.is_enabled());
+        disabled_validator.call(Request::new(())).unwrap();
+
+        let mut validator = AuthTokenValidator::new("my-cluster-key");
+        assert!(validator.is_enabled());
+
+        let status = validator.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+
+        let mut request = Request::new(());
+        request.metadata_mut().insert(
+            AUTH_TOKEN_METADATA_KEY,
+            derive_auth_token("my-other-cluster-key").unwrap(),
+        );
+        let status = validator.call(request).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+
+        let mut request = Request::new(());
+        request.metadata_mut().insert(
+            AUTH_TOKEN_METADATA_KEY,
+            derive_auth_token("my-cluster-key").unwrap(),
+        );
+        validator.call(request).unwrap();
+    }
+
+    #[test]
+    fn test_auth_token_injector() {
+        let mut injector = AuthTokenInjector::new("my-cluster-key");
+        assert!(injector.is_enabled());
+        let request = injector.call(Request::new(())).unwrap();
+        AuthTokenValidator::new("my-cluster-key")
+            .call(request)
+            .unwrap();
+
+        let mut disabled_injector = AuthTokenInjector::new("");
+        assert!(!disabled_injector.is_enabled());
+        let request = disabled_injector.call(Request::new(())).unwrap();
+        assert!(request.metadata().get(AUTH_TOKEN_METADATA_KEY).is_none());
+
+        let request = AuthTokenInjector::default().call(Request::new(())).unwrap();
+        assert!(request.metadata().get(AUTH_TOKEN_METADATA_KEY).is_none());
+    }
+}
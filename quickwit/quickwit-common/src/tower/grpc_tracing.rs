@@ -0,0 +1,219 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{ready, Future};
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use pin_project::pin_project;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use tower::{Layer, Service};
+use tracing::instrument::Instrumented;
+use tracing::{info_span, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// [`Interceptor`] which injects the context of the current span into the metadata of outgoing
+/// requests, using the W3C `traceparent` format.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanContextInterceptor;
+
+impl Interceptor for SpanContextInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(
+                &Span::current().context(),
+                &mut MetadataInjector(request.metadata_mut()),
+            )
+        });
+        Ok(request)
+    }
+}
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl<'a> Injector for MetadataInjector<'a> {
+    /// Sets a key-value pair in the metadata map. No-op if the key or value is invalid.
+    fn set(&mut self, key: &str, value: String) {
+        if let Ok(metadata_key) = MetadataKey::from_bytes(key.as_bytes()) {
+            if let Ok(metadata_value) = MetadataValue::try_from(&value) {
+                self.0.insert(metadata_key, metadata_value);
+            }
+        }
+    }
+}
+
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    /// Gets the value of a header. Returns `None` if the value is not valid ASCII.
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Layer that wraps each incoming gRPC request into a span. The span is parented to the span
+/// context propagated by the client via the `traceparent` metadata and carries the `rpc.service`,
+/// `rpc.method`, and `rpc.grpc.status_code` attributes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GrpcTracingLayer;
+
+impl<S> Layer<S> for GrpcTracingLayer {
+    type Service = GrpcTracing<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcTracing { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GrpcTracing<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcTracing<S>
+where S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let (rpc_service, rpc_method) = parse_grpc_path(request.uri().path());
+        let span = info_span!(
+            "grpc_request",
+            otel.kind = "server",
+            rpc.system = "grpc",
+            rpc.service = %rpc_service,
+            rpc.method = %rpc_method,
+            rpc.grpc.status_code = tracing::field::Empty,
+        );
+        let parent_cx = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(request.headers()))
+        });
+        span.set_parent(parent_cx);
+
+        let inner = self.inner.call(request).instrument(span);
+        ResponseFuture { inner }
+    }
+}
+
+#[pin_project]
+pub struct ResponseFuture<F> {
+    #[pin]
+    inner: Instrumented<F>,
+}
+
+impl<F, ResBody, E> Future for ResponseFuture<F>
+where F: Future<Output = Result<http::Response<ResBody>, E>>
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response_res = ready!(this.inner.poll(cx));
+
+        if let Ok(response) = &response_res {
+            this.inner
+                .span()
+                .record("rpc.grpc.status_code", grpc_status_code(response));
+        }
+        Poll::Ready(response_res)
+    }
+}
+
+/// Splits the path of a gRPC request, `/<service>/<method>`, into the service and method names.
+fn parse_grpc_path(path: &str) -> (&str, &str) {
+    path.trim_start_matches('/')
+        .split_once('/')
+        .unwrap_or((path, ""))
+}
+
+/// Returns the gRPC status code of a response. Failed unary calls return a "trailers-only"
+/// response, in which case the status is sent in the headers. Otherwise, the status is only
+/// available in the trailers, which are not inspected, and the call is deemed successful.
+fn grpc_status_code<B>(response: &http::Response<B>) -> i32 {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::ServiceExt;
+    use tracing::info;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_grpc_path() {
+        assert_eq!(
+            parse_grpc_path("/quickwit.search.SearchService/RootSearch"),
+            ("quickwit.search.SearchService", "RootSearch")
+        );
+        assert_eq!(parse_grpc_path("/foo"), ("/foo", ""));
+    }
+
+    #[test]
+    fn test_grpc_status_code() {
+        let response = http::Response::new(());
+        assert_eq!(grpc_status_code(&response), 0);
+
+        let response = http::Response::builder()
+            .header("grpc-status", "16")
+            .body(())
+            .unwrap();
+        assert_eq!(grpc_status_code(&response), 16);
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_grpc_tracing_layer_span_hierarchy() {
+        let service =
+            GrpcTracingLayer.layer(tower::service_fn(|_request: http::Request<()>| async {
+                info_span!("root_search").in_scope(|| info!("handling request"));
+                Ok::<_, Status>(http::Response::new(()))
+            }));
+        let request = http::Request::builder()
+            .uri("/quickwit.search.SearchService/RootSearch")
+            .body(())
+            .unwrap();
+        service.oneshot(request).await.unwrap();
+
+        assert!(logs_contain("grpc_request{"));
+        assert!(logs_contain("rpc.service=quickwit.search.SearchService"));
+        assert!(logs_contain("rpc.method=RootSearch}:root_search: "));
+        assert!(logs_contain("handling request"));
+    }
+}
@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod auth;
 mod box_layer;
 mod box_service;
 mod buffer;
@@ -24,6 +25,7 @@ mod change;
 mod delay;
 mod estimate_rate;
 mod event_listener;
+mod grpc_tracing;
 mod load_shed;
 mod metrics;
 mod one_task_per_call_layer;
@@ -37,6 +39,7 @@ mod transport;
 use std::error;
 use std::pin::Pin;
 
+pub use auth::{AuthTokenInjector, AuthTokenValidator, AUTH_TOKEN_METADATA_KEY};
 pub use box_layer::BoxLayer;
 pub use box_service::BoxService;
 pub use buffer::{Buffer, BufferError, BufferLayer};
@@ -45,6 +48,7 @@ pub use delay::{Delay, DelayLayer};
 pub use estimate_rate::{EstimateRate, EstimateRateLayer};
 pub use event_listener::{EventListener, EventListenerLayer};
 use futures::Future;
+pub use grpc_tracing::{GrpcTracing, GrpcTracingLayer, SpanContextInterceptor};
 pub use load_shed::{LoadShed, LoadShedLayer, MakeLoadShedError};
 pub use metrics::{GrpcMetrics, GrpcMetricsLayer, RpcName};
 pub use one_task_per_call_layer::{OneTaskPerCallLayer, TaskCancelled};
@@ -53,7 +57,7 @@ pub use rate::{ConstantRate, Rate};
 pub use rate_estimator::{RateEstimator, SmaRateEstimator};
 pub use rate_limit::{RateLimit, RateLimitLayer};
 pub use retry::{RetryLayer, RetryPolicy};
-pub use transport::{make_channel, warmup_channel, BalanceChannel};
+pub use transport::{make_channel, warmup_channel, BalanceChannel, GrpcClientInterceptor};
 
 pub type BoxError = Box<dyn error::Error + Send + Sync + 'static>;
 
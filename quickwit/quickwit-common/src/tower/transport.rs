@@ -31,14 +31,17 @@ use futures::{Stream, StreamExt};
 use http::Uri;
 use tokio::sync::{mpsc, watch};
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::service::Interceptor;
 use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
 use tower::balance::p2c::Balance;
 use tower::buffer::Buffer;
 use tower::discover::Change as TowerChange;
 use tower::load::{CompleteOnResponse, PendingRequestsDiscover};
 use tower::{BoxError, Service, ServiceExt};
 
-use super::{BoxFuture, Change};
+use super::{AuthTokenInjector, BoxFuture, Change, SpanContextInterceptor};
+use crate::net::{TcpConfig, TcpConfigConnector};
 use crate::BoxStream;
 
 // Transforms a boxed stream of `Change<K, Channel>` into a stream of `Result<TowerChange<K,
@@ -197,7 +200,31 @@ where K: Hash + Eq + Clone + Send + Sync + 'static
     }
 }
 
-/// Creates a channel from a socket address.
+/// [`Interceptor`] applied to the requests sent by the gRPC clients of the node. It propagates the
+/// context of the current span and injects the auth token derived from the cluster key. The default
+/// interceptor does not inject any auth token.
+#[derive(Clone, Debug, Default)]
+pub struct GrpcClientInterceptor {
+    auth_token_injector: AuthTokenInjector,
+}
+
+impl GrpcClientInterceptor {
+    pub fn new(cluster_key: &str) -> Self {
+        Self {
+            auth_token_injector: AuthTokenInjector::new(cluster_key),
+        }
+    }
+}
+
+impl Interceptor for GrpcClientInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let request = SpanContextInterceptor.call(request)?;
+        self.auth_token_injector.call(request)
+    }
+}
+
+/// Creates a channel from a socket address. The TCP connections of the channel are configured
+/// with the default [`TcpConfig`].
 ///
 /// The function is marked as `async` because it requires an executor (`connect_lazy`).
 pub async fn make_channel(socket_addr: SocketAddr) -> Channel {
@@ -210,7 +237,7 @@ pub async fn make_channel(socket_addr: SocketAddr) -> Channel {
     Endpoint::from(uri)
         .connect_timeout(Duration::from_secs(5))
         .timeout(Duration::from_secs(30))
-        .connect_lazy()
+        .connect_with_connector_lazy(TcpConfigConnector::new(TcpConfig::default()))
 }
 
 /// Forces a channel to initiate the underlying HTTP connection. Calling this function only makes
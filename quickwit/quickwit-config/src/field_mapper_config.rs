@@ -0,0 +1,176 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::{bail, ensure, Context};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Transformation applied to the documents by the indexing pipeline before they are validated
+/// against the doc mapping. Mappers are applied in the order in which they are declared.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FieldMapperConfig {
+    /// Moves the value of the top-level field `from` to the top-level field `to`, overwriting
+    /// its previous value.
+    Rename { from: String, to: String },
+    /// Parses the string value of the top-level field `field` with the first matching `chrono`
+    /// format (`%Y-%m-%d %H:%M:%S`, `%d/%b/%Y:%H:%M:%S %z`, ...) and replaces it with its RFC 3339
+    /// representation. Timestamps without a timezone are assumed to be UTC.
+    NormalizeTimestamp { field: String, formats: Vec<String> },
+    /// Matches the string value of the top-level field `source_field` against `pattern` and
+    /// stores the text captured by each group in the corresponding top-level field of
+    /// `target_fields`.
+    RegexExtract {
+        source_field: String,
+        pattern: String,
+        target_fields: Vec<String>,
+    },
+}
+
+impl FieldMapperConfig {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Rename { from, to } => {
+                ensure!(
+                    !from.is_empty() && !to.is_empty(),
+                    "`rename` field mapper requires non-empty `from` and `to` fields"
+                );
+            }
+            Self::NormalizeTimestamp { field, formats } => {
+                ensure!(
+                    !field.is_empty(),
+                    "`normalize_timestamp` field mapper requires a non-empty `field`"
+                );
+                ensure!(
+                    !formats.is_empty(),
+                    "`normalize_timestamp` field mapper for field `{field}` requires at least one \
+                     format"
+                );
+            }
+            Self::RegexExtract {
+                source_field,
+                pattern,
+                target_fields,
+            } => {
+                ensure!(
+                    !source_field.is_empty(),
+                    "`regex_extract` field mapper requires a non-empty `source_field`"
+                );
+                let regex = Regex::new(pattern).with_context(|| {
+                    format!("`regex_extract` field mapper pattern `{pattern}` is invalid")
+                })?;
+                let num_groups = regex.captures_len() - 1;
+
+                if num_groups != target_fields.len() {
+                    bail!(
+                        "`regex_extract` field mapper pattern `{pattern}` has {num_groups} \
+                         capture group(s) but {} target field(s) are declared",
+                        target_fields.len()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_mapper_config_serde() {
+        let field_mappers_yaml = r#"
+            - type: rename
+              from: msg
+              to: message
+            - type: normalize_timestamp
+              field: timestamp
+              formats:
+                - "%Y-%m-%d %H:%M:%S"
+            - type: regex_extract
+              source_field: message
+              pattern: "^(\\w+) (\\d+)$"
+              target_fields: [method, status]
+        "#;
+        let field_mappers: Vec<FieldMapperConfig> =
+            serde_yaml::from_str(field_mappers_yaml).unwrap();
+        assert_eq!(
+            field_mappers,
+            [
+                FieldMapperConfig::Rename {
+                    from: "msg".to_string(),
+                    to: "message".to_string(),
+                },
+                FieldMapperConfig::NormalizeTimestamp {
+                    field: "timestamp".to_string(),
+                    formats: vec!["%Y-%m-%d %H:%M:%S".to_string()],
+                },
+                FieldMapperConfig::RegexExtract {
+                    source_field: "message".to_string(),
+                    pattern: r"^(\w+) (\d+)$".to_string(),
+                    target_fields: vec!["method".to_string(), "status".to_string()],
+                },
+            ]
+        );
+        for field_mapper in &field_mappers {
+            field_mapper.validate().unwrap();
+        }
+        let unknown_field_yaml = r#"
+            type: rename
+            from: msg
+            to: message
+            into: body
+        "#;
+        serde_yaml::from_str::<FieldMapperConfig>(unknown_field_yaml).unwrap_err();
+    }
+
+    #[test]
+    fn test_field_mapper_config_validate() {
+        let rename = FieldMapperConfig::Rename {
+            from: "msg".to_string(),
+            to: "".to_string(),
+        };
+        rename.validate().unwrap_err();
+
+        let normalize_timestamp = FieldMapperConfig::NormalizeTimestamp {
+            field: "timestamp".to_string(),
+            formats: Vec::new(),
+        };
+        let error = normalize_timestamp.validate().unwrap_err();
+        assert!(error.to_string().contains("at least one format"));
+
+        let invalid_pattern = FieldMapperConfig::RegexExtract {
+            source_field: "message".to_string(),
+            pattern: "(unclosed".to_string(),
+            target_fields: vec!["method".to_string()],
+        };
+        let error = invalid_pattern.validate().unwrap_err();
+        assert!(error.to_string().contains("is invalid"));
+
+        let group_mismatch = FieldMapperConfig::RegexExtract {
+            source_field: "message".to_string(),
+            pattern: r"^(\w+) (\d+)$".to_string(),
+            target_fields: vec!["method".to_string()],
+        };
+        let error = group_mismatch.validate().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("has 2 capture group(s) but 1 target field(s) are declared"));
+    }
+}
@@ -0,0 +1,465 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::BTreeMap;
+use std::fmt;
+
+use colored::Colorize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::IndexConfig;
+
+/// Path of a field of the doc mapping, with the names of the nested fields separated by dots, for
+/// instance `attributes.host`.
+pub type FieldName = String;
+
+/// Path of a setting of the index config, with the keys separated by dots, for instance
+/// `indexing_settings.commit_timeout_secs`.
+pub type SettingPath = String;
+
+/// Change applied to a field present in both doc mappings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    /// The type or the cardinality of the field changed, for instance from `u64` to `array<i64>`.
+    TypeChanged { old_type: String, new_type: String },
+    /// The type of the field is unchanged but some of its options (tokenizer, fast, stored, ...)
+    /// changed. A missing option is represented by `null`.
+    OptionsChanged {
+        changed_options: Vec<(String, JsonValue, JsonValue)>,
+    },
+}
+
+/// Human-readable summary of the changes between two index configs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexConfigDiff {
+    pub added_fields: Vec<FieldName>,
+    pub removed_fields: Vec<FieldName>,
+    pub modified_fields: Vec<(FieldName, FieldChange)>,
+    /// Changes of the settings of the index config, doc mapping settings (timestamp field, mode,
+    /// ...) included. A missing setting is represented by `null`.
+    pub changed_settings: Vec<(SettingPath, JsonValue, JsonValue)>,
+}
+
+impl IndexConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.modified_fields.is_empty()
+            && self.changed_settings.is_empty()
+    }
+}
+
+/// Computes the changes required to go from the `old` index config to the `new` one.
+///
+/// The comparison is performed on the serialized form of the configs, so options set to their
+/// default value explicitly are not reported as changes.
+pub fn diff_index_configs(old: &IndexConfig, new: &IndexConfig) -> IndexConfigDiff {
+    let mut old_json = serialize_index_config(old);
+    let mut new_json = serialize_index_config(new);
+
+    let mut diff = IndexConfigDiff::default();
+    let old_field_mappings = take_field_mappings(old_json.get_mut("doc_mapping"));
+    let new_field_mappings = take_field_mappings(new_json.get_mut("doc_mapping"));
+    diff_field_mappings("", &old_field_mappings, &new_field_mappings, &mut diff);
+
+    // The version of the config format is not a setting.
+    old_json.remove("version");
+    new_json.remove("version");
+
+    let mut old_settings = BTreeMap::new();
+    flatten_json_map("", old_json, &mut old_settings);
+    let mut new_settings = BTreeMap::new();
+    flatten_json_map("", new_json, &mut new_settings);
+    diff.changed_settings = diff_flattened_maps(old_settings, new_settings);
+    diff
+}
+
+fn serialize_index_config(index_config: &IndexConfig) -> JsonMap<String, JsonValue> {
+    match serde_json::to_value(index_config) {
+        Ok(JsonValue::Object(index_config_json)) => index_config_json,
+        _ => panic!("index config should serialize to a JSON object"),
+    }
+}
+
+fn take_field_mappings(json_value_opt: Option<&mut JsonValue>) -> Vec<JsonValue> {
+    match json_value_opt.and_then(|json_value| json_value.get_mut("field_mappings")) {
+        Some(field_mappings_json) => match field_mappings_json.take() {
+            JsonValue::Array(field_mappings) => field_mappings,
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    }
+}
+
+fn field_mapping_name(field_mapping: &JsonValue) -> &str {
+    field_mapping
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+}
+
+fn diff_field_mappings(
+    path: &str,
+    old_field_mappings: &[JsonValue],
+    new_field_mappings: &[JsonValue],
+    diff: &mut IndexConfigDiff,
+) {
+    for old_field_mapping in old_field_mappings {
+        let name = field_mapping_name(old_field_mapping);
+        let field_path = format!("{path}{name}");
+
+        let Some(new_field_mapping) = new_field_mappings
+            .iter()
+            .find(|new_field_mapping| field_mapping_name(new_field_mapping) == name)
+        else {
+            diff.removed_fields.push(field_path);
+            continue;
+        };
+        diff_field_mapping(
+            field_path,
+            old_field_mapping.clone(),
+            new_field_mapping.clone(),
+            diff,
+        );
+    }
+    for new_field_mapping in new_field_mappings {
+        let name = field_mapping_name(new_field_mapping);
+
+        if !old_field_mappings
+            .iter()
+            .any(|old_field_mapping| field_mapping_name(old_field_mapping) == name)
+        {
+            diff.added_fields.push(format!("{path}{name}"));
+        }
+    }
+}
+
+fn diff_field_mapping(
+    field_path: FieldName,
+    mut old_field_mapping: JsonValue,
+    mut new_field_mapping: JsonValue,
+    diff: &mut IndexConfigDiff,
+) {
+    let old_type = old_field_mapping["type"].take();
+    let new_type = new_field_mapping["type"].take();
+
+    if old_type != new_type {
+        let field_change = FieldChange::TypeChanged {
+            old_type: old_type.as_str().unwrap_or_default().to_string(),
+            new_type: new_type.as_str().unwrap_or_default().to_string(),
+        };
+        diff.modified_fields.push((field_path, field_change));
+        return;
+    }
+    // The subfields of object fields are diffed as fields of their own.
+    let old_subfield_mappings = take_field_mappings(Some(&mut old_field_mapping));
+    let new_subfield_mappings = take_field_mappings(Some(&mut new_field_mapping));
+
+    let mut old_options = BTreeMap::new();
+    if let JsonValue::Object(old_options_json) = old_field_mapping {
+        flatten_json_map("", old_options_json, &mut old_options);
+    }
+    let mut new_options = BTreeMap::new();
+    if let JsonValue::Object(new_options_json) = new_field_mapping {
+        flatten_json_map("", new_options_json, &mut new_options);
+    }
+    let changed_options = diff_flattened_maps(old_options, new_options);
+
+    if !changed_options.is_empty() {
+        let field_change = FieldChange::OptionsChanged { changed_options };
+        diff.modified_fields
+            .push((field_path.clone(), field_change));
+    }
+    diff_field_mappings(
+        &format!("{field_path}."),
+        &old_subfield_mappings,
+        &new_subfield_mappings,
+        diff,
+    );
+}
+
+/// Flattens nested JSON objects into a map keyed by the dot-separated paths of their leaves.
+/// Arrays are considered as leaves.
+fn flatten_json_map(
+    path: &str,
+    json_map: JsonMap<String, JsonValue>,
+    flattened: &mut BTreeMap<String, JsonValue>,
+) {
+    for (key, json_value) in json_map {
+        let key_path = format!("{path}{key}");
+
+        match json_value {
+            JsonValue::Object(nested_json_map) if !nested_json_map.is_empty() => {
+                flatten_json_map(&format!("{key_path}."), nested_json_map, flattened);
+            }
+            _ => {
+                flattened.insert(key_path, json_value);
+            }
+        }
+    }
+}
+
+fn diff_flattened_maps(
+    mut old_map: BTreeMap<String, JsonValue>,
+    new_map: BTreeMap<String, JsonValue>,
+) -> Vec<(String, JsonValue, JsonValue)> {
+    let mut changes = Vec::new();
+
+    for (key, new_value) in new_map {
+        let old_value = old_map.remove(&key).unwrap_or(JsonValue::Null);
+
+        if old_value != new_value {
+            changes.push((key, old_value, new_value));
+        }
+    }
+    for (key, old_value) in old_map {
+        if !old_value.is_null() {
+            changes.push((key, old_value, JsonValue::Null));
+        }
+    }
+    changes.sort_by(|(left_key, _, _), (right_key, _, _)| left_key.cmp(right_key));
+    changes
+}
+
+impl fmt::Display for IndexConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes.");
+        }
+        if !self.added_fields.is_empty()
+            || !self.removed_fields.is_empty()
+            || !self.modified_fields.is_empty()
+        {
+            writeln!(f, "Fields:")?;
+        }
+        for field_name in &self.added_fields {
+            writeln!(f, "  {}", format!("+ {field_name}").green())?;
+        }
+        for field_name in &self.removed_fields {
+            writeln!(f, "  {}", format!("- {field_name}").red())?;
+        }
+        for (field_name, field_change) in &self.modified_fields {
+            match field_change {
+                FieldChange::TypeChanged { old_type, new_type } => {
+                    let line = format!("~ {field_name}: type `{old_type}` -> `{new_type}`");
+                    writeln!(f, "  {}", line.yellow())?;
+                }
+                FieldChange::OptionsChanged { changed_options } => {
+                    for (option, old_value, new_value) in changed_options {
+                        let line = format!("~ {field_name}: {option} {old_value} -> {new_value}");
+                        writeln!(f, "  {}", line.yellow())?;
+                    }
+                }
+            }
+        }
+        if !self.changed_settings.is_empty() {
+            writeln!(f, "Settings:")?;
+        }
+        for (setting_path, old_value, new_value) in &self.changed_settings {
+            let line = format!("~ {setting_path}: {old_value} -> {new_value}");
+
+            let colored_line = if old_value.is_null() {
+                line.green()
+            } else if new_value.is_null() {
+                line.red()
+            } else {
+                line.yellow()
+            };
+            writeln!(f, "  {colored_line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ConfigFormat, RetentionPolicy};
+
+    const OLD_INDEX_CONFIG_YAML: &str = r#"
+        version: 0.8
+        index_id: hdfs-logs
+        index_uri: s3://quickwit-indexes/hdfs-logs
+        doc_mapping:
+          timestamp_field: timestamp
+          field_mappings:
+            - name: timestamp
+              type: datetime
+              fast: true
+            - name: body
+              type: text
+            - name: status
+              type: u64
+              fast: true
+            - name: attributes
+              type: object
+              field_mappings:
+                - name: host
+                  type: text
+                  tokenizer: raw
+    "#;
+
+    fn index_config_from_yaml(index_config_yaml: &str) -> IndexConfig {
+        ConfigFormat::Yaml
+            .parse(index_config_yaml.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff_index_configs_no_changes() {
+        let index_config = index_config_from_yaml(OLD_INDEX_CONFIG_YAML);
+        let diff = diff_index_configs(&index_config, &index_config);
+        assert!(diff.is_empty());
+        assert_eq!(diff, IndexConfigDiff::default());
+    }
+
+    #[test]
+    fn test_diff_index_configs_field_additions() {
+        let old_index_config = index_config_from_yaml(OLD_INDEX_CONFIG_YAML);
+        let new_index_config = index_config_from_yaml(&OLD_INDEX_CONFIG_YAML.replace(
+            "                  tokenizer: raw\n",
+            "                  tokenizer: raw\n                - name: region\n                  \
+             type: text\n            - name: trace_id\n              type: text\n",
+        ));
+        let diff = diff_index_configs(&old_index_config, &new_index_config);
+        assert_eq!(diff.added_fields, ["attributes.region", "trace_id"]);
+        assert!(diff.removed_fields.is_empty());
+        assert!(diff.modified_fields.is_empty());
+        assert!(diff.changed_settings.is_empty());
+    }
+
+    #[test]
+    fn test_diff_index_configs_field_removals() {
+        let old_index_config = index_config_from_yaml(OLD_INDEX_CONFIG_YAML);
+        let mut new_index_config = old_index_config.clone();
+        new_index_config.doc_mapping.field_mappings.remove(1);
+
+        let diff = diff_index_configs(&old_index_config, &new_index_config);
+        assert!(diff.added_fields.is_empty());
+        assert_eq!(diff.removed_fields, ["body"]);
+        assert!(diff.modified_fields.is_empty());
+        assert!(diff.changed_settings.is_empty());
+    }
+
+    #[test]
+    fn test_diff_index_configs_field_type_changes() {
+        let old_index_config = index_config_from_yaml(OLD_INDEX_CONFIG_YAML);
+        let new_index_config = index_config_from_yaml(
+            &OLD_INDEX_CONFIG_YAML
+                .replace("type: u64", "type: array<i64>")
+                .replace("tokenizer: raw", "tokenizer: default"),
+        );
+        let diff = diff_index_configs(&old_index_config, &new_index_config);
+        assert!(diff.added_fields.is_empty());
+        assert!(diff.removed_fields.is_empty());
+        assert_eq!(
+            diff.modified_fields,
+            [
+                (
+                    "status".to_string(),
+                    FieldChange::TypeChanged {
+                        old_type: "u64".to_string(),
+                        new_type: "array<i64>".to_string(),
+                    }
+                ),
+                (
+                    "attributes.host".to_string(),
+                    FieldChange::OptionsChanged {
+                        changed_options: vec![(
+                            "tokenizer".to_string(),
+                            json!("raw"),
+                            json!("default")
+                        )],
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_index_configs_changed_settings() {
+        let old_index_config = index_config_from_yaml(OLD_INDEX_CONFIG_YAML);
+        let mut new_index_config = old_index_config.clone();
+        new_index_config.indexing_settings.commit_timeout_secs = 5;
+        new_index_config.search_settings.default_search_fields = vec!["body".to_string()];
+        new_index_config.retention_policy_opt = Some(RetentionPolicy {
+            retention_period: "30 days".to_string(),
+            evaluation_schedule: "daily".to_string(),
+            max_size: None,
+        });
+        let diff = diff_index_configs(&old_index_config, &new_index_config);
+        assert!(diff.added_fields.is_empty());
+        assert!(diff.removed_fields.is_empty());
+        assert!(diff.modified_fields.is_empty());
+        assert_eq!(
+            diff.changed_settings,
+            [
+                (
+                    "indexing_settings.commit_timeout_secs".to_string(),
+                    json!(old_index_config.indexing_settings.commit_timeout_secs),
+                    json!(5)
+                ),
+                (
+                    "retention.period".to_string(),
+                    JsonValue::Null,
+                    json!("30 days")
+                ),
+                (
+                    "retention.schedule".to_string(),
+                    JsonValue::Null,
+                    json!("daily")
+                ),
+                (
+                    "search_settings.default_search_fields".to_string(),
+                    json!([]),
+                    json!(["body"])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_config_diff_display() {
+        colored::control::set_override(false);
+
+        let diff = IndexConfigDiff {
+            added_fields: vec!["trace_id".to_string()],
+            removed_fields: vec!["body".to_string()],
+            modified_fields: vec![(
+                "status".to_string(),
+                FieldChange::TypeChanged {
+                    old_type: "u64".to_string(),
+                    new_type: "i64".to_string(),
+                },
+            )],
+            changed_settings: vec![(
+                "indexing_settings.commit_timeout_secs".to_string(),
+                json!(60),
+                json!(5),
+            )],
+        };
+        assert_eq!(
+            diff.to_string(),
+            "Fields:\n  + trace_id\n  - body\n  ~ status: type `u64` -> `i64`\nSettings:\n  ~ \
+             indexing_settings.commit_timeout_secs: 60 -> 5\n"
+        );
+        assert_eq!(IndexConfigDiff::default().to_string(), "No changes.\n");
+    }
+}
@@ -17,6 +17,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod diff;
+mod schema_evolution;
 pub(crate) mod serialize;
 
 use std::collections::BTreeSet;
@@ -29,6 +31,7 @@ use anyhow::{ensure, Context};
 use bytesize::ByteSize;
 use chrono::Utc;
 use cron::Schedule;
+pub use diff::{diff_index_configs, FieldChange, FieldName, IndexConfigDiff, SettingPath};
 use humantime::parse_duration;
 use quickwit_common::uri::Uri;
 use quickwit_doc_mapper::{
@@ -36,14 +39,23 @@ use quickwit_doc_mapper::{
     QuickwitJsonOptions, TokenizerEntry,
 };
 use quickwit_proto::types::IndexId;
+pub use schema_evolution::validate_schema_evolution;
 use serde::{Deserialize, Serialize};
 pub use serialize::load_index_config_from_user_config;
 use tracing::warn;
 
+use crate::field_mapper_config::FieldMapperConfig;
 use crate::index_config::serialize::VersionedIndexConfig;
 use crate::merge_policy_config::{MergePolicyConfig, StableLogMergePolicyConfig};
 use crate::TestableForRegression;
 
+/// Lower bound of `split_num_docs_target`. Smaller targets produce so many splits that the
+/// metastore and the searchers become the bottleneck.
+pub const MIN_SPLIT_NUM_DOCS_TARGET: usize = 10_000;
+
+/// Lower bound of `split_num_bytes_target`.
+pub const MIN_SPLIT_NUM_BYTES_TARGET: ByteSize = ByteSize::mb(1);
+
 // Note(fmassot): `DocMapping` is a struct only used for
 // serialization/deserialization of `DocMapper` parameters.
 // This is partly a duplicate of the `DefaultDocMapper` and
@@ -166,10 +178,28 @@ pub struct IndexingSettings {
     /// `split_num_docs_target` are considered mature and never merged.
     #[serde(default = "IndexingSettings::default_split_num_docs_target")]
     pub split_num_docs_target: usize,
+    /// When set, the indexer also cuts a split as soon as the uncompressed size of the documents
+    /// it holds reaches this target.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_num_bytes_target: Option<ByteSize>,
     #[serde(default)]
     pub merge_policy: MergePolicyConfig,
     #[serde(default)]
     pub resources: IndexingResources,
+    /// What happens to the documents rejected by the doc processor (invalid JSON, doc mapping
+    /// errors, ...).
+    #[schema(value_type = Object)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "IndexingErrorPolicy::is_default")]
+    pub on_document_error: IndexingErrorPolicy,
+    /// Transformations applied in order to the documents before they are validated against the
+    /// doc mapping.
+    #[schema(value_type = Vec<Object>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_mappers: Vec<FieldMapperConfig>,
 }
 
 impl IndexingSettings {
@@ -200,6 +230,29 @@ impl IndexingSettings {
             ..Default::default()
         }
     }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.split_num_docs_target >= MIN_SPLIT_NUM_DOCS_TARGET,
+            "`split_num_docs_target` must be greater than or equal to \
+             {MIN_SPLIT_NUM_DOCS_TARGET}, got {}",
+            self.split_num_docs_target
+        );
+        if let Some(split_num_bytes_target) = self.split_num_bytes_target {
+            ensure!(
+                split_num_bytes_target >= MIN_SPLIT_NUM_BYTES_TARGET,
+                "`split_num_bytes_target` must be greater than or equal to \
+                 {MIN_SPLIT_NUM_BYTES_TARGET}, got {split_num_bytes_target}"
+            );
+        }
+        self.merge_policy.validate()?;
+        self.resources.validate()?;
+
+        for field_mapper in &self.field_mappers {
+            field_mapper.validate()?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for IndexingSettings {
@@ -209,12 +262,41 @@ impl Default for IndexingSettings {
             docstore_blocksize: Self::default_docstore_blocksize(),
             docstore_compression_level: Self::default_docstore_compression_level(),
             split_num_docs_target: Self::default_split_num_docs_target(),
+            split_num_bytes_target: None,
             merge_policy: MergePolicyConfig::default(),
             resources: IndexingResources::default(),
+            on_document_error: IndexingErrorPolicy::default(),
+            field_mappers: Vec::new(),
         }
     }
 }
 
+/// Policy applied to the documents that cannot be indexed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum IndexingErrorPolicy {
+    /// The document is dropped and the error is logged.
+    #[default]
+    SkipAndLog,
+    /// The indexing pipeline fails before the batch containing the document is committed, so
+    /// the source restarts from the last published checkpoint.
+    FailFast,
+    /// The document is stored in a dead letter queue for later inspection and reprocessing.
+    DeadLetterQueue(DlqConfig),
+}
+
+impl IndexingErrorPolicy {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DlqConfig {
+    /// Storage URI where the rejected documents are stored.
+    pub uri: Uri,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SearchSettings {
@@ -236,6 +318,13 @@ pub struct RetentionPolicy {
     #[serde(default = "RetentionPolicy::default_schedule")]
     #[serde(rename = "schedule")]
     pub evaluation_schedule: String,
+
+    /// Maximum total size of the published splits. When it is exceeded, the splits holding the
+    /// oldest documents are dropped until the index fits again.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<ByteSize>,
 }
 
 impl RetentionPolicy {
@@ -278,6 +367,11 @@ impl RetentionPolicy {
     pub(super) fn validate(&self) -> anyhow::Result<()> {
         self.retention_period()?;
         self.evaluation_schedule()?;
+
+        ensure!(
+            self.max_size != Some(ByteSize(0)),
+            "retention max size must be strictly positive"
+        );
         Ok(())
     }
 }
@@ -304,12 +398,24 @@ pub struct IndexConfig {
     pub index_id: IndexId,
     pub index_uri: Uri,
     pub doc_mapping: DocMapping,
+    /// Version of the doc mapping, bumped every time the schema of the index is upgraded.
+    pub schema_version: u32,
     pub indexing_settings: IndexingSettings,
     pub search_settings: SearchSettings,
     pub retention_policy_opt: Option<RetentionPolicy>,
 }
 
 impl IndexConfig {
+    /// Validates the index config, for instance after some of its settings were updated.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_index_config(
+            &self.doc_mapping,
+            &self.indexing_settings,
+            &self.search_settings,
+            &self.retention_policy_opt,
+        )
+    }
+
     #[cfg(any(test, feature = "testsuite"))]
     pub fn for_test(index_id: &str, index_uri: &str) -> Self {
         let index_uri = Uri::from_str(index_uri).unwrap();
@@ -389,6 +495,7 @@ impl IndexConfig {
             index_id: index_id.to_string(),
             index_uri,
             doc_mapping,
+            schema_version: 0,
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
@@ -462,6 +569,7 @@ impl TestableForRegression for IndexConfig {
         let retention_policy = Some(RetentionPolicy {
             retention_period: "90 days".to_string(),
             evaluation_schedule: "daily".to_string(),
+            max_size: None,
         });
         let stable_log_config = StableLogMergePolicyConfig {
             merge_factor: 9,
@@ -487,6 +595,7 @@ impl TestableForRegression for IndexConfig {
             index_id: "my-index".to_string(),
             index_uri: Uri::for_test("s3://quickwit-indexes/my-index"),
             doc_mapping,
+            schema_version: 0,
             indexing_settings,
             retention_policy_opt: retention_policy,
             search_settings,
@@ -553,8 +662,7 @@ pub(super) fn validate_index_config(
     // TODO see if we should store the byproducton the IndexConfig.
     build_doc_mapper(doc_mapping, search_settings)?;
 
-    indexing_settings.merge_policy.validate()?;
-    indexing_settings.resources.validate()?;
+    indexing_settings.validate()?;
 
     if let Some(retention_policy) = retention_policy_opt {
         retention_policy.validate()?;
@@ -618,6 +726,7 @@ mod tests {
         let expected_retention_policy = RetentionPolicy {
             retention_period: "90 days".to_string(),
             evaluation_schedule: "daily".to_string(),
+            max_size: None,
         };
         assert_eq!(
             index_config.retention_policy_opt.unwrap(),
@@ -761,6 +870,89 @@ mod tests {
             minimal_config.doc_mapping.mode.mode_type(),
             ModeType::Dynamic
         );
+        assert_eq!(
+            minimal_config.indexing_settings.on_document_error,
+            IndexingErrorPolicy::SkipAndLog
+        );
+    }
+
+    #[test]
+    fn test_index_config_with_on_document_error() {
+        let load_on_document_error = |policy_yaml: &str| {
+            let config_yaml = format!(
+                r#"
+                version: 0.8
+                index_id: hdfs-logs
+                index_uri: "s3://my-index"
+                doc_mapping: {{}}
+                indexing_settings:
+                  on_document_error:
+                    {policy_yaml}
+            "#
+            );
+            load_index_config_from_user_config(
+                ConfigFormat::Yaml,
+                config_yaml.as_bytes(),
+                &Uri::for_test("s3://my-index"),
+            )
+            .map(|index_config| index_config.indexing_settings.on_document_error)
+        };
+        assert_eq!(
+            load_on_document_error("policy: skip_and_log").unwrap(),
+            IndexingErrorPolicy::SkipAndLog
+        );
+        assert_eq!(
+            load_on_document_error("policy: fail_fast").unwrap(),
+            IndexingErrorPolicy::FailFast
+        );
+        assert_eq!(
+            load_on_document_error(
+                r#"policy: dead_letter_queue
+                    uri: "s3://my-index-dlq""#
+            )
+            .unwrap(),
+            IndexingErrorPolicy::DeadLetterQueue(DlqConfig {
+                uri: Uri::for_test("s3://my-index-dlq")
+            })
+        );
+        load_on_document_error("policy: retry").unwrap_err();
+        load_on_document_error("policy: dead_letter_queue").unwrap_err();
+    }
+
+    #[test]
+    fn test_index_config_with_field_mappers() {
+        let load_field_mappers = |field_mappers_yaml: &str| {
+            let config_yaml = format!(
+                r#"
+                version: 0.8
+                index_id: hdfs-logs
+                index_uri: "s3://my-index"
+                doc_mapping: {{}}
+                indexing_settings:
+                  field_mappers:
+                    {field_mappers_yaml}
+            "#
+            );
+            load_index_config_from_user_config(
+                ConfigFormat::Yaml,
+                config_yaml.as_bytes(),
+                &Uri::for_test("s3://my-index"),
+            )
+            .map(|index_config| index_config.indexing_settings.field_mappers)
+        };
+        assert_eq!(
+            load_field_mappers("- {type: rename, from: msg, to: message}").unwrap(),
+            [FieldMapperConfig::Rename {
+                from: "msg".to_string(),
+                to: "message".to_string(),
+            }]
+        );
+        let error = load_field_mappers(
+            r#"- {type: regex_extract, source_field: message, pattern: "(\w+)", target_fields: []}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("capture group"));
+        load_field_mappers("- {type: uppercase, field: message}").unwrap_err();
     }
 
     #[test]
@@ -793,6 +985,7 @@ mod tests {
         let retention_policy = RetentionPolicy {
             retention_period: "90 days".to_string(),
             evaluation_schedule: "hourly".to_string(),
+            max_size: None,
         };
         let retention_policy_yaml = serde_yaml::to_string(&retention_policy).unwrap();
         assert_eq!(
@@ -813,6 +1006,7 @@ mod tests {
             let expected_retention_policy = RetentionPolicy {
                 retention_period: "90 days".to_string(),
                 evaluation_schedule: "hourly".to_string(),
+                max_size: None,
             };
             assert_eq!(retention_policy, expected_retention_policy);
         }
@@ -827,6 +1021,22 @@ mod tests {
             let expected_retention_policy = RetentionPolicy {
                 retention_period: "90 days".to_string(),
                 evaluation_schedule: "daily".to_string(),
+                max_size: None,
+            };
+            assert_eq!(retention_policy, expected_retention_policy);
+        }
+        {
+            let retention_policy_yaml = r#"
+            period: 30 days
+            max_size: 500 GB
+        "#;
+            let retention_policy =
+                serde_yaml::from_str::<RetentionPolicy>(retention_policy_yaml).unwrap();
+
+            let expected_retention_policy = RetentionPolicy {
+                retention_period: "30 days".to_string(),
+                evaluation_schedule: "hourly".to_string(),
+                max_size: Some(ByteSize::gb(500)),
             };
             assert_eq!(retention_policy, expected_retention_policy);
         }
@@ -838,6 +1048,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "hourly".to_string(),
+                max_size: None,
             };
             assert_eq!(
                 retention_policy.retention_period().unwrap(),
@@ -847,6 +1058,7 @@ mod tests {
                 let retention_policy = RetentionPolicy {
                     retention_period: "foo".to_string(),
                     evaluation_schedule: "hourly".to_string(),
+                    max_size: None,
                 };
                 assert_eq!(
                     retention_policy.retention_period().unwrap_err().to_string(),
@@ -871,6 +1083,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "@hourly".to_string(),
+                max_size: None,
             };
             assert_eq!(
                 retention_policy.evaluation_schedule().unwrap(),
@@ -881,6 +1094,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "hourly".to_string(),
+                max_size: None,
             };
             assert_eq!(
                 retention_policy.evaluation_schedule().unwrap(),
@@ -891,6 +1105,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "0 * * * * *".to_string(),
+                max_size: None,
             };
             let evaluation_schedule = retention_policy.evaluation_schedule().unwrap();
             assert_eq!(evaluation_schedule.seconds().count(), 1);
@@ -904,6 +1119,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "hourly".to_string(),
+                max_size: None,
             };
             retention_policy.validate().unwrap();
         }
@@ -911,6 +1127,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "foo".to_string(),
                 evaluation_schedule: "hourly".to_string(),
+                max_size: None,
             };
             retention_policy.validate().unwrap_err();
         }
@@ -918,9 +1135,68 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: "foo".to_string(),
+                max_size: None,
             };
             retention_policy.validate().unwrap_err();
         }
+        {
+            let retention_policy = RetentionPolicy {
+                retention_period: "1 hour".to_string(),
+                evaluation_schedule: "hourly".to_string(),
+                max_size: Some(ByteSize::gb(1)),
+            };
+            retention_policy.validate().unwrap();
+        }
+        {
+            let retention_policy = RetentionPolicy {
+                retention_period: "1 hour".to_string(),
+                evaluation_schedule: "hourly".to_string(),
+                max_size: Some(ByteSize(0)),
+            };
+            let error = retention_policy.validate().unwrap_err();
+            assert!(error.to_string().contains("strictly positive"));
+        }
+    }
+
+    #[test]
+    fn test_indexing_settings_validate() {
+        IndexingSettings::default().validate().unwrap();
+
+        let indexing_settings = IndexingSettings {
+            split_num_docs_target: 2_000_000,
+            split_num_bytes_target: Some(ByteSize::gb(5)),
+            ..Default::default()
+        };
+        indexing_settings.validate().unwrap();
+
+        let indexing_settings = IndexingSettings {
+            split_num_docs_target: MIN_SPLIT_NUM_DOCS_TARGET - 1,
+            ..Default::default()
+        };
+        let error = indexing_settings.validate().unwrap_err();
+        assert!(error.to_string().contains("split_num_docs_target"));
+
+        let indexing_settings = IndexingSettings {
+            split_num_bytes_target: Some(ByteSize::kb(10)),
+            ..Default::default()
+        };
+        let error = indexing_settings.validate().unwrap_err();
+        assert!(error.to_string().contains("split_num_bytes_target"));
+    }
+
+    #[test]
+    fn test_indexing_settings_split_num_bytes_target_serde() {
+        let indexing_settings: IndexingSettings =
+            serde_yaml::from_str("split_num_bytes_target: 2 GB").unwrap();
+        assert_eq!(
+            indexing_settings.split_num_bytes_target,
+            Some(ByteSize::gb(2))
+        );
+
+        let indexing_settings_json = serde_json::to_value(IndexingSettings::default()).unwrap();
+        assert!(indexing_settings_json
+            .get("split_num_bytes_target")
+            .is_none());
     }
 
     #[test]
@@ -930,6 +1206,7 @@ mod tests {
             let retention_policy = RetentionPolicy {
                 retention_period: "1 hour".to_string(),
                 evaluation_schedule: schedule_str.to_string(),
+                max_size: None,
             };
 
             let next_evaluation_duration = chrono::Duration::nanoseconds(
@@ -0,0 +1,296 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{bail, ensure};
+use quickwit_doc_mapper::{Cardinality, FieldMappingEntry, FieldMappingType};
+
+use super::DocMapping;
+
+/// Validates that the doc mapping of an index can be upgraded from `old_doc_mapping` to
+/// `new_doc_mapping` while keeping the splits indexed with the old doc mapping searchable.
+///
+/// The allowed changes are:
+/// - adding new fields (all the fields but the timestamp field are optional);
+/// - widening the type of a field: `u64` to `i64` or `f64`, `i64` to `f64`, and single-valued to
+///   multivalued.
+///
+/// Removing or renaming a field, changing its indexing options, or changing the timestamp field is
+/// rejected.
+pub fn validate_schema_evolution(
+    old_doc_mapping: &DocMapping,
+    new_doc_mapping: &DocMapping,
+) -> anyhow::Result<()> {
+    ensure!(
+        old_doc_mapping.timestamp_field == new_doc_mapping.timestamp_field,
+        "the timestamp field cannot be changed (`{}` -> `{}`)",
+        old_doc_mapping.timestamp_field.as_deref().unwrap_or("none"),
+        new_doc_mapping.timestamp_field.as_deref().unwrap_or("none"),
+    );
+    validate_field_mappings_evolution(
+        "",
+        &old_doc_mapping.field_mappings,
+        &new_doc_mapping.field_mappings,
+    )
+}
+
+fn validate_field_mappings_evolution(
+    path: &str,
+    old_field_mappings: &[FieldMappingEntry],
+    new_field_mappings: &[FieldMappingEntry],
+) -> anyhow::Result<()> {
+    for old_field_mapping in old_field_mappings {
+        let field_path = format!("{path}{}", old_field_mapping.name);
+        let Some(new_field_mapping) = new_field_mappings
+            .iter()
+            .find(|new_field_mapping| new_field_mapping.name == old_field_mapping.name)
+        else {
+            bail!("field `{field_path}` cannot be removed from the doc mapping");
+        };
+        validate_field_mapping_type_evolution(
+            &field_path,
+            &old_field_mapping.mapping_type,
+            &new_field_mapping.mapping_type,
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_field_mapping_type_evolution(
+    field_path: &str,
+    old_mapping_type: &FieldMappingType,
+    new_mapping_type: &FieldMappingType,
+) -> anyhow::Result<()> {
+    use FieldMappingType::*;
+
+    if old_mapping_type == new_mapping_type {
+        return Ok(());
+    }
+    let (old_cardinality, new_cardinality) = match (old_mapping_type, new_mapping_type) {
+        (Object(old_options), Object(new_options)) => {
+            return validate_field_mappings_evolution(
+                &format!("{field_path}."),
+                &old_options.field_mappings,
+                &new_options.field_mappings,
+            );
+        }
+        (I64(old_options, old_cardinality), F64(new_options, new_cardinality))
+        | (U64(old_options, old_cardinality), I64(new_options, new_cardinality))
+        | (U64(old_options, old_cardinality), F64(new_options, new_cardinality))
+        | (I64(old_options, old_cardinality), I64(new_options, new_cardinality))
+        | (U64(old_options, old_cardinality), U64(new_options, new_cardinality))
+        | (F64(old_options, old_cardinality), F64(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (Text(old_options, old_cardinality), Text(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (Bool(old_options, old_cardinality), Bool(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (IpAddr(old_options, old_cardinality), IpAddr(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (DateTime(old_options, old_cardinality), DateTime(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (Bytes(old_options, old_cardinality), Bytes(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        (Json(old_options, old_cardinality), Json(new_options, new_cardinality)) => {
+            ensure_same_options(field_path, old_options, new_options)?;
+            (old_cardinality, new_cardinality)
+        }
+        _ => bail!(
+            "the type of field `{field_path}` cannot be changed from `{}` to `{}`",
+            mapping_type_name(old_mapping_type),
+            mapping_type_name(new_mapping_type),
+        ),
+    };
+    ensure!(
+        !(*old_cardinality == Cardinality::MultiValues
+            && *new_cardinality == Cardinality::SingleValue),
+        "field `{field_path}` cannot be changed from multivalued to single-valued"
+    );
+    Ok(())
+}
+
+fn ensure_same_options<T: PartialEq>(
+    field_path: &str,
+    old_options: &T,
+    new_options: &T,
+) -> anyhow::Result<()> {
+    ensure!(
+        old_options == new_options,
+        "the options of field `{field_path}` cannot be changed"
+    );
+    Ok(())
+}
+
+fn mapping_type_name(mapping_type: &FieldMappingType) -> &'static str {
+    match mapping_type {
+        FieldMappingType::Text(..) => "text",
+        FieldMappingType::I64(..) => "i64",
+        FieldMappingType::U64(..) => "u64",
+        FieldMappingType::F64(..) => "f64",
+        FieldMappingType::Bool(..) => "bool",
+        FieldMappingType::IpAddr(..) => "ip",
+        FieldMappingType::DateTime(..) => "datetime",
+        FieldMappingType::Bytes(..) => "bytes",
+        FieldMappingType::Json(..) => "json",
+        FieldMappingType::Object(..) => "object",
+        FieldMappingType::Concatenate(..) => "concatenate",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc_mapping_from_yaml(doc_mapping_yaml: &str) -> DocMapping {
+        serde_yaml::from_str(doc_mapping_yaml).unwrap()
+    }
+
+    const OLD_DOC_MAPPING_YAML: &str = r#"
+        timestamp_field: timestamp
+        field_mappings:
+          - name: timestamp
+            type: datetime
+            fast: true
+          - name: body
+            type: text
+          - name: status
+            type: u64
+            fast: true
+          - name: latency
+            type: i64
+          - name: attributes
+            type: object
+            field_mappings:
+              - name: host
+                type: text
+                tokenizer: raw
+    "#;
+
+    #[test]
+    fn test_validate_schema_evolution_allowed_changes() {
+        let old_doc_mapping = doc_mapping_from_yaml(OLD_DOC_MAPPING_YAML);
+        validate_schema_evolution(&old_doc_mapping, &old_doc_mapping).unwrap();
+
+        let new_doc_mapping = doc_mapping_from_yaml(
+            r#"
+            timestamp_field: timestamp
+            field_mappings:
+              - name: timestamp
+                type: datetime
+                fast: true
+              - name: body
+                type: array<text>
+              - name: status
+                type: i64
+                fast: true
+              - name: latency
+                type: f64
+              - name: attributes
+                type: object
+                field_mappings:
+                  - name: host
+                    type: text
+                    tokenizer: raw
+                  - name: region
+                    type: text
+              - name: trace_id
+                type: text
+                tokenizer: raw
+        "#,
+        );
+        validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap();
+    }
+
+    #[test]
+    fn test_validate_schema_evolution_rejected_changes() {
+        let old_doc_mapping = doc_mapping_from_yaml(OLD_DOC_MAPPING_YAML);
+        {
+            let mut new_doc_mapping = old_doc_mapping.clone();
+            new_doc_mapping.field_mappings.remove(1);
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "field `body` cannot be removed from the doc mapping"
+            );
+        }
+        {
+            let mut new_doc_mapping = old_doc_mapping.clone();
+            new_doc_mapping.timestamp_field = None;
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the timestamp field cannot be changed (`timestamp` -> `none`)"
+            );
+        }
+        {
+            let new_doc_mapping = doc_mapping_from_yaml(&OLD_DOC_MAPPING_YAML.replace(
+                "name: latency\n            type: i64",
+                "name: latency\n            type: text",
+            ));
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the type of field `latency` cannot be changed from `i64` to `text`"
+            );
+        }
+        {
+            let new_doc_mapping = doc_mapping_from_yaml(&OLD_DOC_MAPPING_YAML.replace(
+                "name: status\n            type: u64\n            fast: true",
+                "name: status\n            type: u64\n            fast: false",
+            ));
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the options of field `status` cannot be changed"
+            );
+        }
+        {
+            let new_doc_mapping = doc_mapping_from_yaml(
+                &OLD_DOC_MAPPING_YAML.replace("tokenizer: raw", "tokenizer: default"),
+            );
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the options of field `attributes.host` cannot be changed"
+            );
+        }
+        {
+            let new_doc_mapping = doc_mapping_from_yaml(&OLD_DOC_MAPPING_YAML.replace(
+                "name: latency\n            type: i64",
+                "name: latency\n            type: u64",
+            ));
+            let error = validate_schema_evolution(&old_doc_mapping, &new_doc_mapping).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the type of field `latency` cannot be changed from `i64` to `u64`"
+            );
+        }
+    }
+}
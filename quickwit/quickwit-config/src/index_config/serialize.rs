@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use anyhow::Context;
+use quickwit_common::is_zero;
 use quickwit_common::uri::Uri;
 use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
@@ -95,6 +96,7 @@ impl IndexConfigForSerialization {
             index_id: self.index_id,
             index_uri,
             doc_mapping: self.doc_mapping,
+            schema_version: self.schema_version,
             indexing_settings: self.indexing_settings,
             search_settings: self.search_settings,
             retention_policy_opt: self.retention_policy_opt,
@@ -135,6 +137,9 @@ pub struct IndexConfigV0_8 {
     pub index_uri: Option<Uri>,
     pub doc_mapping: DocMapping,
     #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub schema_version: u32,
+    #[serde(default)]
     pub indexing_settings: IndexingSettings,
     #[serde(default)]
     pub search_settings: SearchSettings,
@@ -149,6 +154,7 @@ impl From<IndexConfig> for IndexConfigV0_8 {
             index_id: index_config.index_id,
             index_uri: Some(index_config.index_uri),
             doc_mapping: index_config.doc_mapping,
+            schema_version: index_config.schema_version,
             indexing_settings: index_config.indexing_settings,
             search_settings: index_config.search_settings,
             retention_policy_opt: index_config.retention_policy_opt,
@@ -211,6 +217,7 @@ mod test {
         invalid_index_config.retention_policy_opt = Some(RetentionPolicy {
             retention_period: "90 days".to_string(),
             evaluation_schedule: "hourly".to_string(),
+            max_size: None,
         });
         let validation_err = invalid_index_config
             .build_and_validate(None)
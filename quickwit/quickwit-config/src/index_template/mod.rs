@@ -72,6 +72,7 @@ impl IndexTemplate {
             index_id,
             index_uri,
             doc_mapping: self.doc_mapping.clone(),
+            schema_version: 0,
             indexing_settings: self.indexing_settings.clone(),
             search_settings: self.search_settings.clone(),
             retention_policy_opt: self.retention_policy_opt.clone(),
@@ -171,6 +172,7 @@ impl TestableForRegression for IndexTemplate {
             retention_policy_opt: Some(RetentionPolicy {
                 retention_period: "42 days".to_string(),
                 evaluation_schedule: "daily".to_string(),
+                max_size: None,
             }),
         }
     }
@@ -230,6 +232,7 @@ mod tests {
         index_template.retention_policy_opt = Some(RetentionPolicy {
             retention_period: "42 days".to_string(),
             evaluation_schedule: "hourly".to_string(),
+            max_size: None,
         });
         let default_index_root_uri = Uri::for_test("s3://test-bucket/indexes");
 
@@ -281,6 +284,7 @@ mod tests {
         index_template.retention_policy_opt = Some(RetentionPolicy {
             retention_period: "".to_string(),
             evaluation_schedule: "".to_string(),
+            max_size: None,
         });
         let error = index_template.validate().unwrap_err();
         assert!(error
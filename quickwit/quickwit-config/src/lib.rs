@@ -32,6 +32,7 @@ use regex::Regex;
 
 mod cluster_config;
 mod config_value;
+mod field_mapper_config;
 mod index_config;
 mod index_template;
 pub mod merge_policy_config;
@@ -48,31 +49,36 @@ pub use cluster_config::ClusterConfig;
 // See #2048
 use index_config::serialize::{IndexConfigV0_8, VersionedIndexConfig};
 pub use index_config::{
-    build_doc_mapper, load_index_config_from_user_config, DocMapping, IndexConfig,
-    IndexingResources, IndexingSettings, RetentionPolicy, SearchSettings,
+    build_doc_mapper, diff_index_configs, load_index_config_from_user_config,
+    validate_schema_evolution, DlqConfig, DocMapping, FieldChange, FieldName, IndexConfig,
+    IndexConfigDiff, IndexingErrorPolicy, IndexingResources, IndexingSettings, RetentionPolicy,
+    SearchSettings, SettingPath,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 pub use source_config::{
-    load_source_config_from_user_config, FileSourceParams, KafkaSourceParams, KinesisSourceParams,
-    PubSubSourceParams, PulsarSourceAuth, PulsarSourceParams, RegionOrEndpoint, SourceConfig,
-    SourceInputFormat, SourceParams, TransformConfig, VecSourceParams, VoidSourceParams,
-    CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID,
+    load_source_config_from_user_config, BackfillSourceParams, FileSourceParams, KafkaSourceParams,
+    KinesisSourceParams, PubSubSourceParams, PulsarSourceAuth, PulsarSourceParams,
+    RegionOrEndpoint, SourceConfig, SourceInputFormat, SourceParams, TransformConfig,
+    VecSourceParams, VoidSourceParams, BACKFILL_SOURCE_ID, CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
+    INGEST_V2_SOURCE_ID,
 };
 use tracing::warn;
 
+pub use crate::field_mapper_config::FieldMapperConfig;
 use crate::index_template::IndexTemplateV0_8;
 pub use crate::index_template::{IndexTemplate, IndexTemplateId, VersionedIndexTemplate};
 use crate::merge_policy_config::{
     ConstWriteAmplificationMergePolicyConfig, MergePolicyConfig, StableLogMergePolicyConfig,
 };
 pub use crate::metastore_config::{
-    MetastoreBackend, MetastoreConfig, MetastoreConfigs, PostgresMetastoreConfig,
+    ConsulMetastoreConfig, MemoryMetastoreConfig, MetastoreBackend, MetastoreConfig,
+    MetastoreConfigs, PostgresMetastoreConfig, SqliteMetastoreConfig,
 };
 pub use crate::node_config::{
-    IndexerConfig, IngestApiConfig, JaegerConfig, NodeConfig, SearcherConfig, SplitCacheLimits,
-    DEFAULT_QW_CONFIG_PATH,
+    AuditLogConfig, CorsConfig, IndexerConfig, IngestApiConfig, JaegerConfig, NodeConfig,
+    SearcherConfig, SplitCacheLimits, DEFAULT_QW_CONFIG_PATH,
 };
 use crate::source_config::serialize::{SourceConfigV0_7, SourceConfigV0_8, VersionedSourceConfig};
 pub use crate::storage_config::{
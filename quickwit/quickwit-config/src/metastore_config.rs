@@ -20,6 +20,7 @@
 use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::time::Duration;
+use std::{env, fmt};
 
 use anyhow::{ensure, Context};
 use humantime::parse_duration;
@@ -33,6 +34,9 @@ pub enum MetastoreBackend {
     File,
     #[serde(alias = "pg", alias = "postgres")]
     PostgreSQL,
+    Sqlite,
+    Memory,
+    Consul,
 }
 
 /// Holds the metastore configurations defined in the `metastore` section of node config files.
@@ -44,6 +48,12 @@ pub enum MetastoreBackend {
 ///
 ///   postgres:
 ///     max_connections: 12
+///
+///   sqlite:
+///     enable_wal_mode: true
+///
+///   consul:
+///     token: ${CONSUL_ACL_TOKEN}
 /// ```
 #[serde_as]
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -95,6 +105,49 @@ impl MetastoreConfigs {
                 _ => None,
             })
     }
+
+    pub fn find_sqlite(&self) -> Option<&SqliteMetastoreConfig> {
+        self.0
+            .iter()
+            .find_map(|metastore_config| match metastore_config {
+                MetastoreConfig::Sqlite(sqlite_metastore_config) => Some(sqlite_metastore_config),
+                _ => None,
+            })
+    }
+
+    pub fn find_memory(&self) -> Option<&MemoryMetastoreConfig> {
+        self.0
+            .iter()
+            .find_map(|metastore_config| match metastore_config {
+                MetastoreConfig::Memory(memory_metastore_config) => Some(memory_metastore_config),
+                _ => None,
+            })
+    }
+
+    pub fn find_consul(&self) -> Option<&ConsulMetastoreConfig> {
+        self.0
+            .iter()
+            .find_map(|metastore_config| match metastore_config {
+                MetastoreConfig::Consul(consul_metastore_config) => Some(consul_metastore_config),
+                _ => None,
+            })
+    }
+
+    /// Sets the ACL token of the Consul metastore config, adding a default Consul metastore
+    /// config if none is defined.
+    pub fn set_consul_token(&mut self, token: String) {
+        for metastore_config in &mut self.0 {
+            if let MetastoreConfig::Consul(consul_metastore_config) = metastore_config {
+                consul_metastore_config.token = Some(token);
+                return;
+            }
+        }
+        let consul_metastore_config = ConsulMetastoreConfig {
+            token: Some(token),
+            ..Default::default()
+        };
+        self.0.push(consul_metastore_config.into());
+    }
 }
 
 impl Deref for MetastoreConfigs {
@@ -111,6 +164,9 @@ pub enum MetastoreConfig {
     File(FileMetastoreConfig),
     #[serde(alias = "pg", alias = "postgres")]
     PostgreSQL(PostgresMetastoreConfig),
+    Sqlite(SqliteMetastoreConfig),
+    Memory(MemoryMetastoreConfig),
+    Consul(ConsulMetastoreConfig),
 }
 
 impl MetastoreConfig {
@@ -118,6 +174,9 @@ impl MetastoreConfig {
         match self {
             Self::File(_) => MetastoreBackend::File,
             Self::PostgreSQL(_) => MetastoreBackend::PostgreSQL,
+            Self::Sqlite(_) => MetastoreBackend::Sqlite,
+            Self::Memory(_) => MetastoreBackend::Memory,
+            Self::Consul(_) => MetastoreBackend::Consul,
         }
     }
 
@@ -135,15 +194,33 @@ impl MetastoreConfig {
         }
     }
 
+    pub fn as_sqlite(&self) -> Option<&SqliteMetastoreConfig> {
+        match self {
+            Self::Sqlite(sqlite_metastore_config) => Some(sqlite_metastore_config),
+            _ => None,
+        }
+    }
+
+    pub fn as_consul(&self) -> Option<&ConsulMetastoreConfig> {
+        match self {
+            Self::Consul(consul_metastore_config) => Some(consul_metastore_config),
+            _ => None,
+        }
+    }
+
     pub fn redact(&mut self) {
-        // TODO: Implement this method when we end up storing secrets in the
-        // metastore config.
+        if let Self::Consul(consul_metastore_config) = self {
+            consul_metastore_config.redact();
+        }
     }
 
     pub fn validate(&self) -> anyhow::Result<()> {
         match self {
             Self::File(file_metastore_config) => file_metastore_config.validate()?,
             Self::PostgreSQL(postgres_metastore_config) => postgres_metastore_config.validate()?,
+            Self::Sqlite(sqlite_metastore_config) => sqlite_metastore_config.validate()?,
+            Self::Memory(memory_metastore_config) => memory_metastore_config.validate()?,
+            Self::Consul(consul_metastore_config) => consul_metastore_config.validate()?,
         }
         Ok(())
     }
@@ -161,6 +238,24 @@ impl From<PostgresMetastoreConfig> for MetastoreConfig {
     }
 }
 
+impl From<SqliteMetastoreConfig> for MetastoreConfig {
+    fn from(sqlite_metastore_config: SqliteMetastoreConfig) -> Self {
+        Self::Sqlite(sqlite_metastore_config)
+    }
+}
+
+impl From<MemoryMetastoreConfig> for MetastoreConfig {
+    fn from(memory_metastore_config: MemoryMetastoreConfig) -> Self {
+        Self::Memory(memory_metastore_config)
+    }
+}
+
+impl From<ConsulMetastoreConfig> for MetastoreConfig {
+    fn from(consul_metastore_config: ConsulMetastoreConfig) -> Self {
+        Self::Consul(consul_metastore_config)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct PostgresMetastoreConfig {
@@ -269,6 +364,68 @@ impl PostgresMetastoreConfig {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteMetastoreConfig {
+    /// Enables the write-ahead log journal mode, which lets readers proceed concurrently with the
+    /// writer.
+    #[serde(default = "SqliteMetastoreConfig::default_enable_wal_mode")]
+    pub enable_wal_mode: bool,
+    #[serde(default = "SqliteMetastoreConfig::default_acquire_connection_timeout")]
+    pub acquire_connection_timeout: String,
+    /// How long to wait for a lock held by another process before giving up.
+    #[serde(default = "SqliteMetastoreConfig::default_busy_timeout")]
+    pub busy_timeout: String,
+}
+
+impl Default for SqliteMetastoreConfig {
+    fn default() -> Self {
+        Self {
+            enable_wal_mode: Self::default_enable_wal_mode(),
+            acquire_connection_timeout: Self::default_acquire_connection_timeout(),
+            busy_timeout: Self::default_busy_timeout(),
+        }
+    }
+}
+
+impl SqliteMetastoreConfig {
+    pub fn default_enable_wal_mode() -> bool {
+        true
+    }
+
+    pub fn default_acquire_connection_timeout() -> String {
+        "10s".to_string()
+    }
+
+    pub fn default_busy_timeout() -> String {
+        "5s".to_string()
+    }
+
+    pub fn acquire_connection_timeout(&self) -> anyhow::Result<Duration> {
+        parse_duration(&self.acquire_connection_timeout).with_context(|| {
+            format!(
+                "failed to parse `acquire_connection_timeout` value `{}`",
+                self.acquire_connection_timeout
+            )
+        })
+    }
+
+    pub fn busy_timeout(&self) -> anyhow::Result<Duration> {
+        parse_duration(&self.busy_timeout).with_context(|| {
+            format!(
+                "failed to parse `busy_timeout` value `{}`",
+                self.busy_timeout
+            )
+        })
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.acquire_connection_timeout()?;
+        self.busy_timeout()?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct FileMetastoreConfig;
@@ -279,6 +436,97 @@ impl FileMetastoreConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryMetastoreConfig;
+
+impl MemoryMetastoreConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsulMetastoreConfig {
+    /// ACL token sent to the Consul agent with each request. Defaults to the value of the
+    /// `CONSUL_HTTP_TOKEN` environment variable.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// How long a blocking query waits for the watched key to change before returning.
+    #[serde(default = "ConsulMetastoreConfig::default_watch_timeout")]
+    pub watch_timeout: String,
+    #[serde(default = "ConsulMetastoreConfig::default_request_timeout")]
+    pub request_timeout: String,
+}
+
+impl Default for ConsulMetastoreConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            watch_timeout: Self::default_watch_timeout(),
+            request_timeout: Self::default_request_timeout(),
+        }
+    }
+}
+
+impl ConsulMetastoreConfig {
+    pub fn default_watch_timeout() -> String {
+        "60s".to_string()
+    }
+
+    pub fn default_request_timeout() -> String {
+        "10s".to_string()
+    }
+
+    pub fn token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| env::var("CONSUL_HTTP_TOKEN").ok())
+    }
+
+    pub fn watch_timeout(&self) -> anyhow::Result<Duration> {
+        parse_duration(&self.watch_timeout).with_context(|| {
+            format!(
+                "failed to parse `watch_timeout` value `{}`",
+                self.watch_timeout
+            )
+        })
+    }
+
+    pub fn request_timeout(&self) -> anyhow::Result<Duration> {
+        parse_duration(&self.request_timeout).with_context(|| {
+            format!(
+                "failed to parse `request_timeout` value `{}`",
+                self.request_timeout
+            )
+        })
+    }
+
+    pub fn redact(&mut self) {
+        if let Some(token) = self.token.as_mut() {
+            *token = "***redacted***".to_string();
+        }
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.watch_timeout()?;
+        self.request_timeout()?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ConsulMetastoreConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsulMetastoreConfig")
+            .field("token", &self.token.as_ref().map(|_| "***redacted***"))
+            .field("watch_timeout", &self.watch_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,4 +665,119 @@ mod tests {
                 .is_none(),);
         }
     }
+
+    #[test]
+    fn test_sqlite_metastore_config_serde() {
+        {
+            let sqlite_metastore_config: SqliteMetastoreConfig = serde_yaml::from_str("").unwrap();
+            assert_eq!(sqlite_metastore_config, SqliteMetastoreConfig::default());
+            assert!(sqlite_metastore_config.enable_wal_mode);
+        }
+        {
+            let sqlite_metastore_config_yaml = r#"
+                enable_wal_mode: false
+                acquire_connection_timeout: 30s
+                busy_timeout: 500ms
+            "#;
+            let sqlite_metastore_config: SqliteMetastoreConfig =
+                serde_yaml::from_str(sqlite_metastore_config_yaml).unwrap();
+
+            let expected_sqlite_metastore_config = SqliteMetastoreConfig {
+                enable_wal_mode: false,
+                acquire_connection_timeout: "30s".to_string(),
+                busy_timeout: "500ms".to_string(),
+            };
+            assert_eq!(sqlite_metastore_config, expected_sqlite_metastore_config);
+            assert_eq!(
+                sqlite_metastore_config
+                    .acquire_connection_timeout()
+                    .unwrap(),
+                Duration::from_secs(30)
+            );
+            assert_eq!(
+                sqlite_metastore_config.busy_timeout().unwrap(),
+                Duration::from_millis(500)
+            );
+        }
+        {
+            let metastore_configs_yaml = r#"
+                sqlite:
+                    busy_timeout: 1s
+            "#;
+            let metastore_configs: MetastoreConfigs =
+                serde_yaml::from_str(metastore_configs_yaml).unwrap();
+            let sqlite_metastore_config = metastore_configs.find_sqlite().unwrap();
+            assert_eq!(sqlite_metastore_config.busy_timeout, "1s");
+        }
+        {
+            let sqlite_metastore_config = SqliteMetastoreConfig {
+                busy_timeout: "5".to_string(),
+                ..Default::default()
+            };
+            let error = sqlite_metastore_config.validate().unwrap_err();
+            assert!(error.to_string().contains("`busy_timeout`"));
+        }
+    }
+
+    #[test]
+    fn test_consul_metastore_config_serde() {
+        {
+            let consul_metastore_config: ConsulMetastoreConfig = serde_yaml::from_str("").unwrap();
+            assert_eq!(consul_metastore_config, ConsulMetastoreConfig::default());
+            assert_eq!(
+                consul_metastore_config.watch_timeout().unwrap(),
+                Duration::from_secs(60)
+            );
+        }
+        {
+            let metastore_configs_yaml = r#"
+                consul:
+                    token: my-acl-token
+                    watch_timeout: 5m
+            "#;
+            let mut metastore_configs: MetastoreConfigs =
+                serde_yaml::from_str(metastore_configs_yaml).unwrap();
+            let consul_metastore_config = metastore_configs.find_consul().unwrap();
+            assert_eq!(
+                consul_metastore_config.token.as_deref(),
+                Some("my-acl-token")
+            );
+            assert_eq!(
+                consul_metastore_config.watch_timeout().unwrap(),
+                Duration::from_secs(300)
+            );
+            assert!(!format!("{consul_metastore_config:?}").contains("my-acl-token"));
+
+            metastore_configs.redact();
+            let consul_metastore_config = metastore_configs.find_consul().unwrap();
+            assert_eq!(
+                consul_metastore_config.token.as_deref(),
+                Some("***redacted***")
+            );
+        }
+        {
+            let consul_metastore_config = ConsulMetastoreConfig {
+                watch_timeout: "60".to_string(),
+                ..Default::default()
+            };
+            let error = consul_metastore_config.validate().unwrap_err();
+            assert!(error.to_string().contains("`watch_timeout`"));
+        }
+    }
+
+    #[test]
+    fn test_metastore_configs_set_consul_token() {
+        let mut metastore_configs = MetastoreConfigs::default();
+        metastore_configs.set_consul_token("token-1".to_string());
+        assert_eq!(
+            metastore_configs.find_consul().unwrap().token.as_deref(),
+            Some("token-1")
+        );
+        metastore_configs.set_consul_token("token-2".to_string());
+        assert_eq!(metastore_configs.len(), 1);
+        assert_eq!(
+            metastore_configs.find_consul().unwrap().token.as_deref(),
+            Some("token-2")
+        );
+    }
 }
@@ -28,7 +28,7 @@ use std::time::Duration;
 
 use anyhow::{bail, ensure};
 use bytesize::ByteSize;
-use http::HeaderMap;
+use http::{HeaderMap, Method};
 use quickwit_common::net::HostAddr;
 use quickwit_common::uri::Uri;
 use quickwit_proto::indexing::CpuCapacity;
@@ -47,9 +47,62 @@ pub const DEFAULT_QW_CONFIG_PATH: &str = "config/quickwit.yaml";
 #[serde(deny_unknown_fields)]
 pub struct RestConfig {
     pub listen_addr: SocketAddr,
-    pub cors_allow_origins: Vec<String>,
+    pub cors: CorsConfig,
     #[serde(with = "http_serde::header_map")]
     pub extra_headers: HeaderMap,
+    /// Key that must be provided as a bearer token to call the admin endpoints of the REST API.
+    /// Admin endpoints are disabled when unset.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin_api_key: Option<String>,
+}
+
+/// Cross-Origin Resource Sharing (CORS) policy of the REST API, configured with the
+/// `cors_*` properties of the `rest` section of the node config.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins allowed to call the REST API. `*` allows any origin. CORS is disabled when empty.
+    pub allowed_origins: Vec<String>,
+    #[serde_as(as = "Vec<serde_with::DisplayFromStr>")]
+    pub allowed_methods: Vec<Method>,
+    /// Whether browsers may send credentials (cookies, authorization headers) along with the
+    /// requests. Ignored when any origin is allowed because the CORS spec forbids it.
+    pub allow_credentials: bool,
+    /// How long browsers may cache the responses to preflight requests.
+    pub max_age_secs: Option<u64>,
+    /// Whether to add a `Vary: Origin` header to the responses when several origins are
+    /// allowed, so that caches do not serve a response issued for one origin to another one.
+    pub vary_on_origin: bool,
+}
+
+impl CorsConfig {
+    pub fn default_allowed_methods() -> Vec<Method> {
+        vec![
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ]
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: Self::default_allowed_methods(),
+            allow_credentials: false,
+            max_age_secs: None,
+            vary_on_origin: true,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -57,6 +110,10 @@ pub struct RestConfig {
 pub struct GrpcConfig {
     #[serde(default = "GrpcConfig::default_max_message_size")]
     pub max_message_size: ByteSize,
+    /// Exposes the gRPC server reflection service, which lets tools such as `grpcurl` list and
+    /// call the gRPC services of the node without the `.proto` files.
+    #[serde(default)]
+    pub enable_reflection: bool,
 }
 
 impl GrpcConfig {
@@ -78,6 +135,7 @@ impl Default for GrpcConfig {
     fn default() -> Self {
         Self {
             max_message_size: Self::default_max_message_size(),
+            enable_reflection: false,
         }
     }
 }
@@ -108,6 +166,14 @@ pub struct IndexerConfig {
     pub enable_cooperative_indexing: bool,
     #[serde(default = "IndexerConfig::default_cpu_capacity")]
     pub cpu_capacity: CpuCapacity,
+    /// When the in-memory document buffers of the indexing pipelines of the node exceed this
+    /// size, the sources of the pipelines are paused. Backpressure is disabled if not set.
+    #[serde(default)]
+    pub high_water_mark_bytes: Option<ByteSize>,
+    /// Size the in-memory document buffers must drop below for paused sources to resume.
+    /// Defaults to 80% of `high_water_mark_bytes`.
+    #[serde(default)]
+    pub low_water_mark_bytes: Option<ByteSize>,
 }
 
 impl IndexerConfig {
@@ -147,6 +213,33 @@ impl IndexerConfig {
         CpuCapacity::one_cpu_thread() * (quickwit_common::num_cpus() as u32)
     }
 
+    /// Returns the high and low water marks of the indexing backpressure, or `None` if
+    /// backpressure is disabled.
+    pub fn backpressure_water_marks(&self) -> Option<(ByteSize, ByteSize)> {
+        let high_water_mark_bytes = self.high_water_mark_bytes?;
+        let low_water_mark_bytes = self
+            .low_water_mark_bytes
+            .unwrap_or_else(|| ByteSize(high_water_mark_bytes.as_u64() * 4 / 5));
+        Some((high_water_mark_bytes, low_water_mark_bytes))
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(low_water_mark_bytes) = self.low_water_mark_bytes {
+            let Some(high_water_mark_bytes) = self.high_water_mark_bytes else {
+                bail!(
+                    "`indexer.low_water_mark_bytes` requires `indexer.high_water_mark_bytes` to \
+                     be set"
+                );
+            };
+            ensure!(
+                low_water_mark_bytes <= high_water_mark_bytes,
+                "`indexer.low_water_mark_bytes` ({low_water_mark_bytes}) must be lower or equal \
+                 to `indexer.high_water_mark_bytes` ({high_water_mark_bytes})"
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "testsuite"))]
     pub fn for_test() -> anyhow::Result<Self> {
         use quickwit_proto::indexing::PIPELINE_FULL_CAPACITY;
@@ -159,6 +252,8 @@ impl IndexerConfig {
             cpu_capacity: PIPELINE_FULL_CAPACITY * 4u32,
             max_merge_write_throughput: None,
             merge_concurrency: NonZeroUsize::new(3).unwrap(),
+            high_water_mark_bytes: None,
+            low_water_mark_bytes: None,
         };
         Ok(indexer_config)
     }
@@ -175,6 +270,8 @@ impl Default for IndexerConfig {
             cpu_capacity: Self::default_cpu_capacity(),
             merge_concurrency: Self::default_merge_concurrency(),
             max_merge_write_throughput: None,
+            high_water_mark_bytes: None,
+            low_water_mark_bytes: None,
         }
     }
 }
@@ -215,6 +312,13 @@ pub struct SearcherConfig {
     pub partial_request_cache_capacity: ByteSize,
     pub max_num_concurrent_split_searches: usize,
     pub max_num_concurrent_split_streams: usize,
+    /// Maximum number of split footers prefetched concurrently during a leaf search. `0`
+    /// disables prefetching.
+    pub prefetch_concurrency: usize,
+    /// When enabled, the split cache downloads splits with presigned URLs issued by the storage
+    /// instead of going through the storage client. Storages that cannot presign URLs fall back
+    /// to regular downloads.
+    pub direct_download: bool,
     // Strangely, if None, this will also have the effect of not forwarding
     // to searcher.
     // TODO document and fix if necessary.
@@ -230,6 +334,8 @@ impl Default for SearcherConfig {
             partial_request_cache_capacity: ByteSize::mb(64),
             max_num_concurrent_split_streams: 100,
             max_num_concurrent_split_searches: 100,
+            prefetch_concurrency: 8,
+            direct_download: false,
             aggregation_memory_limit: ByteSize::mb(500),
             aggregation_bucket_limit: 65000,
             split_cache: None,
@@ -272,6 +378,9 @@ pub struct IngestApiConfig {
     pub max_queue_disk_usage: ByteSize,
     pub replication_factor: usize,
     pub content_length_limit: ByteSize,
+    /// How long the response to an ingest request carrying an `Idempotency-Key` header is
+    /// replayed to the requests sent with the same key.
+    pub idempotency_ttl_secs: NonZeroU64,
 }
 
 impl Default for IngestApiConfig {
@@ -281,11 +390,16 @@ impl Default for IngestApiConfig {
             max_queue_disk_usage: ByteSize::gib(4),   // TODO maybe we want more?
             replication_factor: 1,
             content_length_limit: ByteSize::mib(10),
+            idempotency_ttl_secs: NonZeroU64::new(5 * 60).unwrap(), // 5 minutes
         }
     }
 }
 
 impl IngestApiConfig {
+    pub fn idempotency_ttl(&self) -> Duration {
+        Duration::from_secs(self.idempotency_ttl_secs.get())
+    }
+
     pub fn replication_factor(&self) -> anyhow::Result<NonZeroUsize> {
         if let Ok(replication_factor_str) = env::var("QW_INGEST_REPLICATION_FACTOR") {
             let replication_factor = match replication_factor_str.trim() {
@@ -382,6 +496,24 @@ impl JaegerConfig {
     }
 }
 
+/// Destination of the audit log recording the mutations applied to the metastore.
+///
+/// ```yaml
+/// audit_log:
+///   destination: remote_syslog
+///   address: syslog.example.com:514
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "destination", rename_all = "snake_case")]
+pub enum AuditLogConfig {
+    /// Appends the events to a local file, one JSON object per line.
+    File { path: PathBuf },
+    /// Sends the events to the local syslog daemon via `/dev/log`.
+    Syslog,
+    /// Sends the events to a remote syslog server over UDP.
+    RemoteSyslog { address: String },
+}
+
 impl Default for JaegerConfig {
     fn default() -> Self {
         Self {
@@ -396,6 +528,9 @@ impl Default for JaegerConfig {
 #[derive(Clone, Debug, Serialize)]
 pub struct NodeConfig {
     pub cluster_id: String,
+    /// Shared secret from which the token authenticating gRPC requests between the nodes of the
+    /// cluster is derived. Authentication is disabled when the key is empty.
+    pub cluster_key: String,
     pub node_id: NodeId,
     pub enabled_services: HashSet<QuickwitService>,
     pub gossip_listen_addr: SocketAddr,
@@ -415,6 +550,7 @@ pub struct NodeConfig {
     pub searcher_config: SearcherConfig,
     pub ingest_api_config: IngestApiConfig,
     pub jaeger_config: JaegerConfig,
+    pub audit_log_config: Option<AuditLogConfig>,
 }
 
 impl NodeConfig {
@@ -463,6 +599,13 @@ impl NodeConfig {
         self.metastore_configs.redact();
         self.metastore_uri.redact();
         self.storage_configs.redact();
+
+        if let Some(admin_api_key) = self.rest_config.admin_api_key.as_mut() {
+            *admin_api_key = "***redacted***".to_string();
+        }
+        if !self.cluster_key.is_empty() {
+            self.cluster_key = "***redacted***".to_string();
+        }
     }
 
     #[cfg(any(test, feature = "testsuite"))]
@@ -538,6 +681,52 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_indexer_config_backpressure_water_marks() {
+        assert!(IndexerConfig::default()
+            .backpressure_water_marks()
+            .is_none());
+        {
+            let indexer_config: IndexerConfig =
+                serde_yaml::from_str(r#"high_water_mark_bytes: 1GB"#).unwrap();
+            indexer_config.validate().unwrap();
+            assert_eq!(
+                indexer_config.backpressure_water_marks(),
+                Some((ByteSize::gb(1), ByteSize::mb(800)))
+            );
+        }
+        {
+            let indexer_config: IndexerConfig = serde_yaml::from_str(
+                r#"
+                    high_water_mark_bytes: 1GB
+                    low_water_mark_bytes: 500MB
+                "#,
+            )
+            .unwrap();
+            indexer_config.validate().unwrap();
+            assert_eq!(
+                indexer_config.backpressure_water_marks(),
+                Some((ByteSize::gb(1), ByteSize::mb(500)))
+            );
+        }
+        {
+            let indexer_config: IndexerConfig =
+                serde_yaml::from_str(r#"low_water_mark_bytes: 500MB"#).unwrap();
+            indexer_config.validate().unwrap_err();
+        }
+        {
+            let indexer_config: IndexerConfig = serde_yaml::from_str(
+                r#"
+                    high_water_mark_bytes: 500MB
+                    low_water_mark_bytes: 1GB
+                "#,
+            )
+            .unwrap();
+            indexer_config.validate().unwrap_err();
+        }
+    }
+
     #[test]
     fn test_validate_ingest_api_config() {
         {
@@ -583,17 +772,28 @@ mod tests {
         )
         .unwrap();
         assert_eq!(grpc_config.max_message_size, ByteSize::mib(4));
+        assert!(!grpc_config.enable_reflection);
+
+        let grpc_config: GrpcConfig = serde_yaml::from_str(
+            r#"
+                enable_reflection: true
+            "#,
+        )
+        .unwrap();
+        assert!(grpc_config.enable_reflection);
     }
 
     #[test]
     fn test_grpc_config_validate() {
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::mb(1),
+            ..Default::default()
         };
         assert!(grpc_config.validate().is_ok());
 
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::kb(1),
+            ..Default::default()
         };
         assert!(grpc_config.validate().is_err());
     }
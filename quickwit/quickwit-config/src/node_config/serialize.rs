@@ -23,7 +23,7 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::{bail, Context};
-use http::HeaderMap;
+use http::{HeaderMap, HeaderValue, Method};
 use quickwit_common::net::{find_private_ip, get_short_hostname, Host};
 use quickwit_common::new_coolid;
 use quickwit_common::uri::Uri;
@@ -31,15 +31,15 @@ use quickwit_proto::types::NodeId;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use super::{GrpcConfig, RestConfig};
+use super::{CorsConfig, GrpcConfig, RestConfig};
 use crate::config_value::ConfigValue;
 use crate::qw_env_vars::*;
 use crate::service::QuickwitService;
 use crate::storage_config::StorageConfigs;
 use crate::templating::render_config;
 use crate::{
-    validate_identifier, validate_node_id, ConfigFormat, IndexerConfig, IngestApiConfig,
-    JaegerConfig, MetastoreConfigs, NodeConfig, SearcherConfig,
+    validate_identifier, validate_node_id, AuditLogConfig, ConfigFormat, IndexerConfig,
+    IngestApiConfig, JaegerConfig, MetastoreConfigs, NodeConfig, SearcherConfig,
 };
 
 pub const DEFAULT_CLUSTER_ID: &str = "quickwit-default-cluster";
@@ -173,6 +173,8 @@ impl From<VersionedNodeConfig> for NodeConfigBuilder {
 struct NodeConfigBuilder {
     #[serde(default = "default_cluster_id")]
     cluster_id: ConfigValue<String, QW_CLUSTER_ID>,
+    #[serde(default)]
+    cluster_key: ConfigValue<String, QW_CLUSTER_KEY>,
     #[serde(default = "default_node_id")]
     node_id: ConfigValue<String, QW_NODE_ID>,
     #[serde(default = "default_enabled_services")]
@@ -216,6 +218,9 @@ struct NodeConfigBuilder {
     #[serde(rename = "jaeger")]
     #[serde(default)]
     jaeger_config: JaegerConfig,
+    #[serde(rename = "audit_log")]
+    #[serde(default)]
+    audit_log_config: Option<AuditLogConfig>,
 }
 
 impl NodeConfigBuilder {
@@ -301,6 +306,7 @@ impl NodeConfigBuilder {
         self.storage_configs.apply_flavors();
         self.ingest_api_config.validate()?;
         self.searcher_config.validate()?;
+        self.indexer_config.validate()?;
 
         let gossip_interval = self
             .gossip_interval_ms
@@ -310,6 +316,7 @@ impl NodeConfigBuilder {
 
         let node_config = NodeConfig {
             cluster_id: self.cluster_id.resolve(env_vars)?,
+            cluster_key: self.cluster_key.resolve(env_vars)?,
             node_id,
             enabled_services,
             gossip_listen_addr,
@@ -329,6 +336,7 @@ impl NodeConfigBuilder {
             searcher_config: self.searcher_config,
             ingest_api_config: self.ingest_api_config,
             jaeger_config: self.jaeger_config,
+            audit_log_config: self.audit_log_config,
         };
 
         validate(&node_config)?;
@@ -354,6 +362,7 @@ impl Default for NodeConfigBuilder {
     fn default() -> Self {
         Self {
             cluster_id: default_cluster_id(),
+            cluster_key: ConfigValue::default(),
             node_id: default_node_id(),
             enabled_services: default_enabled_services(),
             listen_address: default_listen_address(),
@@ -374,6 +383,7 @@ impl Default for NodeConfigBuilder {
             searcher_config: SearcherConfig::default(),
             ingest_api_config: IngestApiConfig::default(),
             jaeger_config: JaegerConfig::default(),
+            audit_log_config: None,
         }
     }
 }
@@ -387,9 +397,20 @@ struct RestConfigBuilder {
     #[serde(default)]
     #[serde_as(deserialize_as = "serde_with::OneOrMany<_>")]
     pub cors_allow_origins: Vec<String>,
+    #[serde(default)]
+    #[serde_as(deserialize_as = "Option<serde_with::OneOrMany<_>>")]
+    pub cors_allow_methods: Option<Vec<String>>,
+    #[serde(default)]
+    pub cors_allow_credentials: bool,
+    #[serde(default)]
+    pub cors_max_age_secs: Option<u64>,
+    #[serde(default)]
+    pub cors_vary_on_origin: Option<bool>,
     #[serde(with = "http_serde::header_map")]
     #[serde(default)]
     pub extra_headers: HeaderMap,
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
 }
 
 impl RestConfigBuilder {
@@ -404,10 +425,33 @@ impl RestConfigBuilder {
             listen_port_from_config_or_default,
         )
         .resolve(env_vars)?;
+        for origin in &self.cors_allow_origins {
+            HeaderValue::from_str(origin)
+                .with_context(|| format!("invalid CORS origin `{origin}`"))?;
+        }
+        let cors_allow_methods = if let Some(cors_allow_methods) = self.cors_allow_methods {
+            cors_allow_methods
+                .iter()
+                .map(|method| {
+                    Method::from_str(&method.to_ascii_uppercase())
+                        .with_context(|| format!("invalid CORS method `{method}`"))
+                })
+                .collect::<anyhow::Result<Vec<Method>>>()?
+        } else {
+            CorsConfig::default_allowed_methods()
+        };
+        let cors_config = CorsConfig {
+            allowed_origins: self.cors_allow_origins,
+            allowed_methods: cors_allow_methods,
+            allow_credentials: self.cors_allow_credentials,
+            max_age_secs: self.cors_max_age_secs,
+            vary_on_origin: self.cors_vary_on_origin.unwrap_or(true),
+        };
         let rest_config = RestConfig {
             listen_addr: SocketAddr::new(listen_ip, listen_port),
-            cors_allow_origins: self.cors_allow_origins,
+            cors: cors_config,
             extra_headers: self.extra_headers,
+            admin_api_key: self.admin_api_key,
         };
         Ok(rest_config)
     }
@@ -444,11 +488,13 @@ pub fn node_config_for_test() -> NodeConfig {
     let default_index_root_uri = default_index_root_uri(&data_dir_uri);
     let rest_config = RestConfig {
         listen_addr: rest_listen_addr,
-        cors_allow_origins: Vec::new(),
+        cors: CorsConfig::default(),
         extra_headers: HeaderMap::new(),
+        admin_api_key: None,
     };
     NodeConfig {
         cluster_id: default_cluster_id().unwrap(),
+        cluster_key: String::new(),
         node_id,
         enabled_services,
         gossip_advertise_addr: gossip_listen_addr,
@@ -468,6 +514,7 @@ pub fn node_config_for_test() -> NodeConfig {
         searcher_config: SearcherConfig::default(),
         ingest_api_config: IngestApiConfig::default(),
         jaeger_config: JaegerConfig::default(),
+        audit_log_config: None,
     }
 }
 
@@ -476,7 +523,7 @@ mod tests {
     use std::env;
     use std::net::Ipv4Addr;
     use std::num::{NonZeroU64, NonZeroUsize};
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
 
     use bytesize::ByteSize;
     use itertools::Itertools;
@@ -595,6 +642,8 @@ mod tests {
                 cpu_capacity: IndexerConfig::default_cpu_capacity(),
                 enable_cooperative_indexing: false,
                 max_merge_write_throughput: Some(ByteSize::mb(100)),
+                high_water_mark_bytes: None,
+                low_water_mark_bytes: None,
             }
         );
         assert_eq!(
@@ -614,6 +663,8 @@ mod tests {
                 partial_request_cache_capacity: ByteSize::mb(64),
                 max_num_concurrent_split_searches: 150,
                 max_num_concurrent_split_streams: 120,
+                prefetch_concurrency: 16,
+                direct_download: true,
                 split_cache: None,
             }
         );
@@ -626,6 +677,12 @@ mod tests {
                 max_fetch_spans: NonZeroU64::new(1_000).unwrap(),
             }
         );
+        assert_eq!(
+            config.audit_log_config,
+            Some(AuditLogConfig::File {
+                path: PathBuf::from("/var/log/quickwit/audit.ndjson"),
+            })
+        );
         Ok(())
     }
 
@@ -839,6 +896,7 @@ mod tests {
         assert_eq!(config.searcher_config, SearcherConfig::default());
         assert_eq!(config.ingest_api_config, IngestApiConfig::default());
         assert_eq!(config.jaeger_config, JaegerConfig::default());
+        assert!(config.audit_log_config.is_none());
     }
 
     #[tokio::test]
@@ -1118,7 +1176,150 @@ mod tests {
         )
         .await
         .expect("Deserialize rest config");
-        assert_eq!(config.rest_config.cors_allow_origins, ["*"]);
+        assert_eq!(config.rest_config.cors.allowed_origins, ["*"]);
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_cors() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins: https://www.my-domain.com
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .expect("Deserialize rest config");
+        assert_eq!(
+            config.rest_config.cors,
+            CorsConfig {
+                allowed_origins: vec!["https://www.my-domain.com".to_string()],
+                ..Default::default()
+            }
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins:
+                - https://www.my-domain.com
+                - https://www.my-other-domain.com
+              cors_allow_methods: [get, POST]
+              cors_allow_credentials: true
+              cors_max_age_secs: 3600
+              cors_vary_on_origin: false
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .expect("Deserialize rest config");
+        assert_eq!(
+            config.rest_config.cors,
+            CorsConfig {
+                allowed_origins: vec![
+                    "https://www.my-domain.com".to_string(),
+                    "https://www.my-other-domain.com".to_string()
+                ],
+                allowed_methods: vec![Method::GET, Method::POST],
+                allow_credentials: true,
+                max_age_secs: Some(3600),
+                vary_on_origin: false,
+            }
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins: "https://www.my-domain.com\n"
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("invalid CORS origin"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              cors_allow_origins: '*'
+              cors_allow_methods: "GET POST"
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("invalid CORS method"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_admin_api_key() {
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              admin_api_key: my-admin-api-key
+        "#;
+        let mut config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .expect("Deserialize rest config");
+        assert_eq!(
+            config.rest_config.admin_api_key.as_deref(),
+            Some("my-admin-api-key")
+        );
+        config.redact();
+        assert_eq!(
+            config.rest_config.admin_api_key.as_deref(),
+            Some("***redacted***")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_node_config_cluster_key() {
+        let config_yaml = r#"
+            version: 0.8
+            cluster_key: my-cluster-key
+        "#;
+        let mut config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(config.cluster_key, "my-cluster-key");
+        config.redact();
+        assert_eq!(config.cluster_key, "***redacted***");
+
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            "QW_CLUSTER_KEY".to_string(),
+            "my-env-cluster-key".to_string(),
+        );
+        let config = load_node_config_with_env(ConfigFormat::Yaml, b"version: 0.8", &env_vars)
+            .await
+            .unwrap();
+        assert_eq!(config.cluster_key, "my-env-cluster-key");
+
+        let config =
+            load_node_config_with_env(ConfigFormat::Yaml, b"version: 0.8", &Default::default())
+                .await
+                .unwrap();
+        assert!(config.cluster_key.is_empty());
     }
 
     #[tokio::test]
@@ -1137,7 +1338,7 @@ mod tests {
         .await
         .expect("Deserialize rest config");
         assert_eq!(
-            config.rest_config.cors_allow_origins,
+            config.rest_config.cors.allowed_origins,
             ["https://www.my-domain.com"]
         );
 
@@ -1154,7 +1355,7 @@ mod tests {
         .await
         .expect("Deserialize rest config");
         assert_eq!(
-            config.rest_config.cors_allow_origins,
+            config.rest_config.cors.allowed_origins,
             ["http://192.168.0.108:7280"]
         );
     }
@@ -1175,7 +1376,7 @@ mod tests {
         .await
         .expect("Deserialize rest config");
         assert_eq!(
-            config.rest_config.cors_allow_origins,
+            config.rest_config.cors.allowed_origins,
             ["https://www.my-domain.com"]
         );
 
@@ -1194,7 +1395,7 @@ mod tests {
         .await
         .expect("Deserialize rest config");
         assert_eq!(
-            config.rest_config.cors_allow_origins,
+            config.rest_config.cors.allowed_origins,
             [
                 "https://www.my-domain.com",
                 "https://www.my-other-domain.com"
@@ -24,12 +24,13 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use bytes::Bytes;
+use chrono::NaiveDate;
 use quickwit_common::is_false;
 use quickwit_common::uri::Uri;
 use quickwit_proto::metastore::SourceType;
 use quickwit_proto::types::SourceId;
 use serde::de::Error;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 pub use serialize::load_source_config_from_user_config;
 // For backward compatibility.
@@ -40,6 +41,9 @@ use crate::{disable_ingest_v1, enable_ingest_v2, TestableForRegression};
 /// Reserved source ID for the `quickwit index ingest` CLI command.
 pub const CLI_SOURCE_ID: &str = "_ingest-cli-source";
 
+/// Reserved source ID for the `quickwit index backfill` CLI command.
+pub const BACKFILL_SOURCE_ID: &str = "_backfill-source";
+
 /// Reserved source ID used for Quickwit ingest API.
 pub const INGEST_API_SOURCE_ID: &str = "_ingest-api-source";
 
@@ -47,8 +51,12 @@ pub const INGEST_API_SOURCE_ID: &str = "_ingest-api-source";
 /// (this is for ingest v2)
 pub const INGEST_V2_SOURCE_ID: &str = "_ingest-source";
 
-pub const RESERVED_SOURCE_IDS: &[&str] =
-    &[CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID];
+pub const RESERVED_SOURCE_IDS: &[&str] = &[
+    BACKFILL_SOURCE_ID,
+    CLI_SOURCE_ID,
+    INGEST_API_SOURCE_ID,
+    INGEST_V2_SOURCE_ID,
+];
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(into = "VersionedSourceConfig")]
@@ -74,6 +82,7 @@ pub struct SourceConfig {
 impl SourceConfig {
     pub fn source_type(&self) -> SourceType {
         match self.source_params {
+            SourceParams::Backfill(_) => SourceType::Backfill,
             SourceParams::File(_) => SourceType::File,
             SourceParams::Ingest => SourceType::IngestV2,
             SourceParams::IngestApi => SourceType::IngestV1,
@@ -90,6 +99,7 @@ impl SourceConfig {
     // TODO: Remove after source factory refactor.
     pub fn params(&self) -> JsonValue {
         match &self.source_params {
+            SourceParams::Backfill(params) => serde_json::to_value(params),
             SourceParams::File(params) => serde_json::to_value(params),
             SourceParams::PubSub(params) => serde_json::to_value(params),
             SourceParams::Ingest => serde_json::to_value(()),
@@ -213,6 +223,7 @@ impl FromStr for SourceInputFormat {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "source_type", content = "params", rename_all = "snake_case")]
 pub enum SourceParams {
+    Backfill(BackfillSourceParams),
     File(FileSourceParams),
     Ingest,
     #[serde(rename = "ingest-api")]
@@ -280,6 +291,66 @@ impl FileSourceParams {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BackfillSourceParams {
+    /// URI of the object storage prefix under which the files to backfill are stored.
+    #[schema(value_type = String)]
+    pub source_uri: Uri,
+    /// Files stored under a `YYYY/MM/DD/` path older than this date (inclusive) are skipped.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "date_opt_from_str")]
+    #[serde(serialize_with = "date_opt_to_str")]
+    pub start_date: Option<NaiveDate>,
+    /// Files stored under a `YYYY/MM/DD/` path more recent than this date (inclusive) are
+    /// skipped.
+    #[schema(value_type = Option<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "date_opt_from_str")]
+    #[serde(serialize_with = "date_opt_to_str")]
+    pub end_date: Option<NaiveDate>,
+}
+
+const BACKFILL_DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn date_opt_from_str<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where D: Deserializer<'de> {
+    let date_opt: Option<String> = Deserialize::deserialize(deserializer)?;
+    date_opt
+        .map(|date| {
+            NaiveDate::parse_from_str(&date, BACKFILL_DATE_FORMAT).map_err(|error| {
+                D::Error::custom(format!(
+                    "failed to parse date `{date}`, expected format `YYYY-MM-DD`: {error}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn date_opt_to_str<S>(date_opt: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+where S: Serializer {
+    date_opt
+        .map(|date| date.format(BACKFILL_DATE_FORMAT).to_string())
+        .serialize(serializer)
+}
+
+impl BackfillSourceParams {
+    /// Returns whether a date falls within the `[start_date, end_date]` range.
+    pub fn contains_date(&self, date: NaiveDate) -> bool {
+        self.start_date
+            .map_or(true, |start_date| start_date <= date)
+            && self.end_date.map_or(true, |end_date| date <= end_date)
+    }
+
+    /// Returns whether a date range was specified.
+    pub fn has_date_range(&self) -> bool {
+        self.start_date.is_some() || self.end_date.is_some()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct KafkaSourceParams {
@@ -815,6 +886,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_backfill_source_params_serialization() {
+        {
+            let yaml = r#"
+                source_uri: s3://my-bucket/firehose/
+                start_date: 2023-01-01
+                end_date: 2023-01-31
+            "#;
+            let params = serde_yaml::from_str::<BackfillSourceParams>(yaml).unwrap();
+            assert_eq!(params.source_uri, Uri::for_test("s3://my-bucket/firehose/"));
+            assert_eq!(params.start_date, NaiveDate::from_ymd_opt(2023, 1, 1));
+            assert_eq!(params.end_date, NaiveDate::from_ymd_opt(2023, 1, 31));
+
+            assert!(params.has_date_range());
+            assert!(params.contains_date(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()));
+            assert!(params.contains_date(NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()));
+            assert!(!params.contains_date(NaiveDate::from_ymd_opt(2022, 12, 31).unwrap()));
+            assert!(!params.contains_date(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()));
+
+            let params_yaml = serde_yaml::to_string(&params).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<BackfillSourceParams>(&params_yaml).unwrap(),
+                params,
+            );
+        }
+        {
+            let yaml = r#"
+                source_uri: s3://my-bucket/firehose/
+            "#;
+            let params = serde_yaml::from_str::<BackfillSourceParams>(yaml).unwrap();
+            assert!(!params.has_date_range());
+            assert!(params.contains_date(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()));
+        }
+        {
+            let yaml = r#"
+                source_uri: s3://my-bucket/firehose/
+                start_date: 01/01/2023
+            "#;
+            let error = serde_yaml::from_str::<BackfillSourceParams>(yaml).unwrap_err();
+            assert!(error.to_string().contains("expected format `YYYY-MM-DD`"));
+        }
+        {
+            let content = r#"
+                {
+                    "version": "0.8",
+                    "source_id": "my-backfill-source",
+                    "source_type": "backfill",
+                    "params": {
+                        "source_uri": "s3://my-bucket/firehose/",
+                        "start_date": "2023-01-31",
+                        "end_date": "2023-01-01"
+                    }
+                }
+            "#;
+            let error = load_source_config_from_user_config(ConfigFormat::Json, content.as_bytes())
+                .unwrap_err();
+            assert!(error.to_string().contains("posterior to its end date"));
+        }
+    }
+
     #[test]
     fn test_kinesis_source_params_serialization() {
         {
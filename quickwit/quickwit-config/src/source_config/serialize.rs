@@ -80,6 +80,19 @@ impl SourceConfigForSerialization {
         let num_pipelines = NonZeroUsize::new(self.num_pipelines)
             .ok_or_else(|| anyhow::anyhow!("`desired_num_pipelines` must be strictly positive"))?;
         match &self.source_params {
+            SourceParams::Backfill(backfill_params) => {
+                if let (Some(start_date), Some(end_date)) =
+                    (backfill_params.start_date, backfill_params.end_date)
+                {
+                    if start_date > end_date {
+                        bail!(
+                            "source `{}` of type `backfill` has a start date ({start_date}) \
+                             posterior to its end date ({end_date})",
+                            self.source_id
+                        )
+                    }
+                }
+            }
             // We want to forbid source_config with no filepath
             SourceParams::File(file_params) => {
                 if file_params.filepath.is_none() {
@@ -39,7 +39,7 @@ use quickwit_common::Progress;
 use quickwit_config::service::QuickwitService;
 use quickwit_config::{ClusterConfig, IndexConfig, IndexTemplate, SourceConfig};
 use quickwit_ingest::{IngesterPool, LocalShardsUpdate};
-use quickwit_metastore::{CreateIndexRequestExt, CreateIndexResponseExt};
+use quickwit_metastore::{CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadataResponseExt};
 use quickwit_proto::control_plane::{
     AdviseResetShardsRequest, AdviseResetShardsResponse, ControlPlaneError, ControlPlaneResult,
     GetOrCreateOpenShardsRequest, GetOrCreateOpenShardsResponse, GetOrCreateOpenShardsSubrequest,
@@ -596,6 +596,17 @@ impl Handler<UpdateIndexRequest> for ControlPlane {
         };
         // TODO: Handle doc mapping and/or indexing settings update here.
         info!(%index_uid, "updated index");
+
+        let index_metadata = match response.deserialize_index_metadata() {
+            Ok(index_metadata) => index_metadata,
+            Err(metastore_error) => {
+                return Ok(Err(ControlPlaneError::from(metastore_error)));
+            }
+        };
+        // Freezing or unfreezing an index starts or stops the pipelines of its sources.
+        if self.model.update_index(index_metadata) {
+            let _rebuild_plan_waiter = self.rebuild_plan_debounced(ctx);
+        }
         Ok(Ok(response))
     }
 }
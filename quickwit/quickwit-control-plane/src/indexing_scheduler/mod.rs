@@ -128,7 +128,8 @@ fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
             continue;
         }
         match source_config.source_type() {
-            SourceType::Cli
+            SourceType::Backfill
+            | SourceType::Cli
             | SourceType::File
             | SourceType::Vec
             | SourceType::Void
@@ -170,6 +171,12 @@ fn get_sources_to_schedule(model: &ControlPlaneModel) -> Vec<SourceToSchedule> {
             | SourceType::PubSub
             | SourceType::Nats
             | SourceType::Pulsar => {
+                // Frozen indexes do not accept new documents, so we stop consuming their external
+                // sources. The ingest sources keep running to index the documents accepted before
+                // the index was frozen.
+                if model.is_index_frozen(&source_uid.index_uid) {
+                    continue;
+                }
                 sources.push(SourceToSchedule {
                     source_uid,
                     source_type: SourceToScheduleType::NonSharded {
@@ -539,7 +546,7 @@ mod tests {
 
     use proptest::{prop_compose, proptest};
     use quickwit_config::{IndexConfig, KafkaSourceParams, SourceConfig, SourceParams};
-    use quickwit_metastore::IndexMetadata;
+    use quickwit_metastore::{IndexMetadata, IndexState};
     use quickwit_proto::types::{IndexUid, PipelineUid, SourceUid};
 
     use super::*;
@@ -801,6 +808,22 @@ mod tests {
         model.insert_shards(&index_uid, &"ingest_v2".to_string(), vec![shard]);
         let shards: Vec<SourceToSchedule> = get_sources_to_schedule(&model);
         assert_eq!(shards.len(), 3);
+
+        let mut frozen_index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        frozen_index_metadata.index_uid = index_uid.clone();
+        frozen_index_metadata.sources = model
+            .source_configs()
+            .map(|(source_uid, source_config)| (source_uid.source_id, source_config.clone()))
+            .collect();
+        frozen_index_metadata.set_index_state(IndexState::Frozen);
+        assert!(model.update_index(frozen_index_metadata));
+
+        // The Kafka source of the frozen index is no longer scheduled.
+        let sources: Vec<SourceToSchedule> = get_sources_to_schedule(&model);
+        assert_eq!(sources.len(), 2);
+        assert!(sources
+            .iter()
+            .all(|source| source.source_uid.source_id != "source_enabled"));
     }
 
     #[test]
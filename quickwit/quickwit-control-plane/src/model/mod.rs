@@ -31,7 +31,7 @@ use quickwit_common::pretty::PrettyDisplay;
 use quickwit_common::Progress;
 use quickwit_config::SourceConfig;
 use quickwit_ingest::ShardInfos;
-use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt};
+use quickwit_metastore::{IndexMetadata, IndexState, ListIndexesMetadataResponseExt};
 use quickwit_proto::control_plane::ControlPlaneResult;
 use quickwit_proto::ingest::Shard;
 use quickwit_proto::metastore::{
@@ -194,6 +194,26 @@ impl ControlPlaneModel {
         self.update_metrics();
     }
 
+    /// Replaces the metadata of an index after it was updated in the metastore. Returns `true` if
+    /// the state of the index has changed.
+    pub(crate) fn update_index(&mut self, index_metadata: IndexMetadata) -> bool {
+        let Some(index_model) = self.index_table.get_mut(&index_metadata.index_uid) else {
+            warn!(index_uid=%index_metadata.index_uid, "update index: index not found");
+            return false;
+        };
+        let has_changed = index_model.index_state != index_metadata.index_state;
+        *index_model = index_metadata;
+        has_changed
+    }
+
+    /// Returns `true` if the index is frozen and should not accept new documents.
+    pub(crate) fn is_index_frozen(&self, index_uid: &IndexUid) -> bool {
+        self.index_table
+            .get(index_uid)
+            .map(|index_metadata| index_metadata.index_state == IndexState::Frozen)
+            .unwrap_or(false)
+    }
+
     pub(crate) fn delete_index(&mut self, index_uid: &IndexUid) {
         self.index_table.remove(index_uid);
         self.index_uid_table.remove(&index_uid.index_id);
@@ -45,7 +45,7 @@ impl CachingDirectory {
         CachingDirectory {
             underlying,
             cache: Arc::new(ByteRangeCache::with_infinite_capacity(
-                &quickwit_storage::STORAGE_METRICS.shortlived_cache,
+                &quickwit_storage::CACHE_METRICS.shortlived_cache,
             )),
         }
     }
@@ -41,7 +41,9 @@ pub(crate) use self::field_mapping_entry::{
 #[cfg(all(test, feature = "multilang"))]
 pub(crate) use self::field_mapping_entry::{QuickwitTextOptions, TextIndexingOptions};
 pub use self::field_mapping_type::FieldMappingType;
-pub use self::tokenizer_entry::{analyze_text, TokenizerConfig, TokenizerEntry};
+pub use self::tokenizer_entry::{
+    analyze_field_text, analyze_text, analyze_text_with_tokenizer, TokenizerConfig, TokenizerEntry,
+};
 pub(crate) use self::tokenizer_entry::{
     NgramTokenizerOption, RegexTokenizerOption, TokenFilterType, TokenizerType,
 };
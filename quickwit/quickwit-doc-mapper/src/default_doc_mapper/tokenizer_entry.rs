@@ -17,14 +17,18 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Context;
-use quickwit_query::{CodeTokenizer, DEFAULT_REMOVE_TOKEN_LENGTH};
+use anyhow::{bail, Context};
+use quickwit_query::tokenizers::TokenizerManager;
+use quickwit_query::{find_field_or_hit_dynamic, CodeTokenizer, DEFAULT_REMOVE_TOKEN_LENGTH};
 use serde::{Deserialize, Serialize};
+use tantivy::schema::FieldType;
 use tantivy::tokenizer::{
     AsciiFoldingFilter, LowerCaser, NgramTokenizer, RegexTokenizer, RemoveLongFilter,
     SimpleTokenizer, TextAnalyzer, Token,
 };
 
+use crate::DocMapper;
+
 /// A `TokenizerEntry` defines a custom tokenizer with its name and configuration.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
 pub struct TokenizerEntry {
@@ -85,13 +89,53 @@ impl TokenizerConfig {
 
 /// Helper function to analyze a text with a given `TokenizerConfig`.
 pub fn analyze_text(text: &str, tokenizer: &TokenizerConfig) -> anyhow::Result<Vec<Token>> {
-    let mut text_analyzer = tokenizer.text_analyzer()?;
+    let text_analyzer = tokenizer.text_analyzer()?;
+    Ok(collect_tokens(text_analyzer, text))
+}
+
+/// Helper function to analyze a text with the tokenizer registered under `tokenizer_name` in the
+/// tokenizer manager.
+pub fn analyze_text_with_tokenizer(
+    text: &str,
+    tokenizer_name: &str,
+    tokenizer_manager: &TokenizerManager,
+) -> anyhow::Result<Vec<Token>> {
+    let text_analyzer = tokenizer_manager
+        .get_tokenizer(tokenizer_name)
+        .with_context(|| format!("no tokenizer named `{tokenizer_name}` is registered"))?;
+    Ok(collect_tokens(text_analyzer, text))
+}
+
+/// Helper function to analyze a text with the tokenizer of the field `field_path` of a doc mapper,
+/// custom tokenizers included.
+pub fn analyze_field_text(
+    text: &str,
+    field_path: &str,
+    doc_mapper: &dyn DocMapper,
+) -> anyhow::Result<Vec<Token>> {
+    let schema = doc_mapper.schema();
+    let (_field, field_entry, _json_path) = find_field_or_hit_dynamic(field_path, &schema)?;
+    let text_field_indexing_opt = match field_entry.field_type() {
+        FieldType::Str(text_options) => text_options.get_indexing_options(),
+        FieldType::JsonObject(json_options) => json_options.get_text_indexing_options(),
+        _ => bail!("field `{field_path}` is not a text field"),
+    };
+    let text_field_indexing =
+        text_field_indexing_opt.with_context(|| format!("field `{field_path}` is not indexed"))?;
+    analyze_text_with_tokenizer(
+        text,
+        text_field_indexing.tokenizer(),
+        doc_mapper.tokenizer_manager(),
+    )
+}
+
+fn collect_tokens(mut text_analyzer: TextAnalyzer, text: &str) -> Vec<Token> {
     let mut token_stream = text_analyzer.token_stream(text);
     let mut tokens = Vec::new();
     token_stream.process(&mut |token| {
         tokens.push(token.clone());
     });
-    Ok(tokens)
+    tokens
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -150,9 +194,17 @@ pub struct RegexTokenizerOption {
 
 #[cfg(test)]
 mod tests {
-    use super::{NgramTokenizerOption, TokenizerType};
+    use quickwit_query::create_default_quickwit_tokenizer_manager;
+
+    use super::{
+        analyze_field_text, analyze_text_with_tokenizer, NgramTokenizerOption, TokenizerType,
+    };
     use crate::default_doc_mapper::RegexTokenizerOption;
-    use crate::TokenizerEntry;
+    use crate::{DefaultDocMapper, TokenizerEntry};
+
+    fn token_texts(tokens: &[tantivy::tokenizer::Token]) -> Vec<&str> {
+        tokens.iter().map(|token| token.text.as_str()).collect()
+    }
 
     #[test]
     fn test_deserialize_tokenizer_entry() {
@@ -243,4 +295,94 @@ mod tests {
             _ => panic!("Unexpected tokenizer type"),
         }
     }
+
+    #[test]
+    fn test_analyze_text_with_builtin_tokenizers() {
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let analyze = |text: &str, tokenizer_name: &str| {
+            analyze_text_with_tokenizer(text, tokenizer_name, &tokenizer_manager).unwrap()
+        };
+        let text = "The Quick brown-fox jumped,";
+
+        let tokens = analyze(text, "raw");
+        assert_eq!(token_texts(&tokens), [text]);
+
+        let tokens = analyze(text, "lowercase");
+        assert_eq!(token_texts(&tokens), ["the quick brown-fox jumped,"]);
+
+        let tokens = analyze(text, "default");
+        assert_eq!(
+            token_texts(&tokens),
+            ["the", "quick", "brown", "fox", "jumped"]
+        );
+        assert_eq!(tokens[1].offset_from, 4);
+        assert_eq!(tokens[1].offset_to, 9);
+        assert_eq!(tokens[1].position, 1);
+
+        let tokens = analyze(text, "en_stem");
+        assert_eq!(
+            token_texts(&tokens),
+            ["the", "quick", "brown", "fox", "jump"]
+        );
+        let tokens = analyze(text, "whitespace");
+        assert_eq!(
+            token_texts(&tokens),
+            ["The", "Quick", "brown-fox", "jumped,"]
+        );
+        let tokens = analyze(text, "chinese_compatible");
+        assert_eq!(
+            token_texts(&tokens),
+            ["the", "quick", "brown", "fox", "jumped"]
+        );
+        let tokens = analyze("PigCafe_FACTORY", "source_code_default");
+        assert_eq!(token_texts(&tokens), ["pig", "cafe", "factory"]);
+
+        let error = analyze_text_with_tokenizer(text, "unknown", &tokenizer_manager).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no tokenizer named `unknown` is registered"
+        );
+    }
+
+    #[test]
+    fn test_analyze_field_text() {
+        let doc_mapper: DefaultDocMapper = serde_json::from_str(
+            r#"{
+                "tokenizers": [
+                    {
+                        "name": "my_ngram",
+                        "type": "ngram",
+                        "min_gram": 2,
+                        "max_gram": 2,
+                        "filters": ["lower_caser"]
+                    }
+                ],
+                "field_mappings": [
+                    {"name": "title", "type": "text", "tokenizer": "en_stem"},
+                    {"name": "code", "type": "text", "tokenizer": "my_ngram"},
+                    {"name": "not_indexed", "type": "text", "indexed": false},
+                    {"name": "count", "type": "u64"},
+                    {"name": "attributes", "type": "json", "tokenizer": "raw"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tokens = analyze_field_text("Jumping Foxes", "title", &doc_mapper).unwrap();
+        assert_eq!(token_texts(&tokens), ["jump", "fox"]);
+
+        let tokens = analyze_field_text("AbC", "code", &doc_mapper).unwrap();
+        assert_eq!(token_texts(&tokens), ["ab", "bc"]);
+
+        let tokens = analyze_field_text("Foo Bar", "attributes.key", &doc_mapper).unwrap();
+        assert_eq!(token_texts(&tokens), ["Foo Bar"]);
+
+        let error = analyze_field_text("foo", "not_indexed", &doc_mapper).unwrap_err();
+        assert_eq!(error.to_string(), "field `not_indexed` is not indexed");
+
+        let error = analyze_field_text("foo", "count", &doc_mapper).unwrap_err();
+        assert_eq!(error.to_string(), "field `count` is not a text field");
+
+        analyze_field_text("foo", "unknown", &doc_mapper).unwrap_err();
+    }
 }
@@ -0,0 +1,227 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tantivy::schema::Schema;
+
+use crate::{DocMapper, DocParsingError, JsonObject};
+
+/// Default name of the timestamp field, used when the builder is not bound to a doc mapper or
+/// when the doc mapper does not define a timestamp field.
+const DEFAULT_TIMESTAMP_FIELD_NAME: &str = "timestamp";
+
+/// Trait implemented by types that can be indexed as documents, usually via
+/// `#[derive(QuickwitDocument)]`.
+///
+/// The document is serialized with `serde`, so the usual `serde` attributes (`rename`,
+/// `skip_serializing_if`, ...) apply.
+pub trait QuickwitDocument: Serialize {
+    /// Serializes the document into a JSON object.
+    fn to_json_obj(&self) -> Result<JsonObject, DocParsingError> {
+        match serde_json::to_value(self) {
+            Ok(JsonValue::Object(json_obj)) => Ok(json_obj),
+            Ok(json_value) => Err(DocParsingError::NotJsonObject(json_value.to_string())),
+            Err(error) => Err(DocParsingError::NotJsonObject(error.to_string())),
+        }
+    }
+}
+
+/// Builds a JSON document field by field.
+///
+/// ```
+/// use quickwit_doc_mapper::IndexedDocumentBuilder;
+///
+/// let json_obj = IndexedDocumentBuilder::new()
+///     .timestamp(1_234_567_890i64)
+///     .field("level", "ERROR")
+///     .field("message", "oops")
+///     .build()
+///     .unwrap();
+/// assert_eq!(json_obj["level"], "ERROR");
+/// ```
+///
+/// When bound to a doc mapper with [`IndexedDocumentBuilder::for_doc_mapper`] and strict mode
+/// is enabled, [`IndexedDocumentBuilder::build`] rejects the fields that are not declared in
+/// the doc mapper schema.
+#[derive(Default)]
+pub struct IndexedDocumentBuilder<'a> {
+    doc_mapper_opt: Option<&'a dyn DocMapper>,
+    strict: bool,
+    json_obj: JsonObject,
+}
+
+impl<'a> IndexedDocumentBuilder<'a> {
+    /// Creates a builder that is not bound to any doc mapper.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder bound to `doc_mapper`, with strict mode enabled.
+    pub fn for_doc_mapper(doc_mapper: &'a dyn DocMapper) -> Self {
+        Self {
+            doc_mapper_opt: Some(doc_mapper),
+            strict: true,
+            json_obj: JsonObject::new(),
+        }
+    }
+
+    /// Enables or disables strict mode. Strict mode has no effect if the builder is not bound
+    /// to a doc mapper.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets the timestamp field of the doc mapper, or `timestamp` if the builder is not bound
+    /// to a doc mapper that defines one.
+    pub fn timestamp(self, timestamp: impl Into<JsonValue>) -> Self {
+        let timestamp_field_name = self
+            .doc_mapper_opt
+            .and_then(|doc_mapper| doc_mapper.timestamp_field_name())
+            .unwrap_or(DEFAULT_TIMESTAMP_FIELD_NAME)
+            .to_string();
+        self.field(timestamp_field_name, timestamp)
+    }
+
+    /// Sets a top-level field, replacing any previous value.
+    pub fn field(mut self, field_name: impl Into<String>, value: impl Into<JsonValue>) -> Self {
+        self.json_obj.insert(field_name.into(), value.into());
+        self
+    }
+
+    /// Sets the fields of `document`, replacing the previous values of the fields it defines.
+    pub fn document<D: QuickwitDocument>(mut self, document: &D) -> Result<Self, DocParsingError> {
+        self.json_obj.extend(document.to_json_obj()?);
+        Ok(self)
+    }
+
+    /// Returns the JSON document.
+    pub fn build(self) -> Result<JsonObject, DocParsingError> {
+        if let Some(doc_mapper) = self.doc_mapper_opt {
+            if self.strict {
+                let schema = doc_mapper.schema();
+
+                for field_name in self.json_obj.keys() {
+                    if !is_declared_in_schema(&schema, field_name) {
+                        return Err(DocParsingError::NoSuchFieldInSchema(field_name.clone()));
+                    }
+                }
+            }
+        }
+        Ok(self.json_obj)
+    }
+}
+
+/// Returns whether the top-level field `field_name` is declared in the schema, either as a
+/// leaf field or as an object containing leaf fields.
+fn is_declared_in_schema(schema: &Schema, field_name: &str) -> bool {
+    let escaped_field_name = field_name.replace('.', "\\.");
+    let object_prefix = format!("{escaped_field_name}.");
+
+    schema.fields().any(|(_, field_entry)| {
+        let schema_field_name = field_entry.name();
+        schema_field_name == escaped_field_name || schema_field_name.starts_with(&object_prefix)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::default_doc_mapper_for_test;
+
+    #[test]
+    fn test_indexed_document_builder() {
+        let json_obj = IndexedDocumentBuilder::new()
+            .timestamp(1_234_567_890i64)
+            .field("level", "ERROR")
+            .field("message", "oops")
+            .build()
+            .unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "timestamp": 1_234_567_890i64,
+                "level": "ERROR",
+                "message": "oops",
+            })
+        );
+    }
+
+    #[test]
+    fn test_indexed_document_builder_strict_mode() {
+        let doc_mapper = default_doc_mapper_for_test();
+
+        let json_obj = IndexedDocumentBuilder::for_doc_mapper(&doc_mapper)
+            .timestamp(1_234_567_890i64)
+            .field("body", "foo")
+            .field("attributes", json!({"server": "bar"}))
+            .build()
+            .unwrap();
+        assert_eq!(json_obj["timestamp"], 1_234_567_890i64);
+        assert_eq!(json_obj["attributes"]["server"], "bar");
+
+        let error = IndexedDocumentBuilder::for_doc_mapper(&doc_mapper)
+            .field("body", "foo")
+            .field("unknown", "bar")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            DocParsingError::NoSuchFieldInSchema("unknown".to_string())
+        );
+
+        IndexedDocumentBuilder::for_doc_mapper(&doc_mapper)
+            .strict(false)
+            .field("unknown", "bar")
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_indexed_document_builder_derive() {
+        #[derive(Serialize, crate::QuickwitDocument)]
+        struct LogDocument {
+            timestamp: i64,
+            body: String,
+            #[serde(rename = "owner")]
+            owner_name: String,
+        }
+        let doc_mapper = default_doc_mapper_for_test();
+        let document = LogDocument {
+            timestamp: 1_234_567_890,
+            body: "foo".to_string(),
+            owner_name: "bar".to_string(),
+        };
+        let json_obj = IndexedDocumentBuilder::for_doc_mapper(&doc_mapper)
+            .document(&document)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            JsonValue::Object(json_obj),
+            json!({
+                "timestamp": 1_234_567_890i64,
+                "body": "foo",
+                "owner": "bar",
+            })
+        );
+    }
+}
@@ -25,8 +25,12 @@
 //! to convert a json like documents to a document indexable by tantivy
 //! engine, aka tantivy::Document.
 
+// Allows `#[derive(QuickwitDocument)]` to be used within this crate.
+extern crate self as quickwit_doc_mapper;
+
 mod default_doc_mapper;
 mod doc_mapper;
+mod document_builder;
 mod error;
 mod query_builder;
 mod routing_expression;
@@ -35,9 +39,9 @@ mod routing_expression;
 pub mod tag_pruning;
 
 pub use default_doc_mapper::{
-    analyze_text, BinaryFormat, DefaultDocMapper, DefaultDocMapperBuilder, FieldMappingEntry,
-    FieldMappingType, Mode, ModeType, QuickwitBytesOptions, QuickwitJsonOptions, TokenizerConfig,
-    TokenizerEntry,
+    analyze_field_text, analyze_text, analyze_text_with_tokenizer, BinaryFormat, DefaultDocMapper,
+    DefaultDocMapperBuilder, FieldMappingEntry, FieldMappingType, Mode, ModeType,
+    QuickwitBytesOptions, QuickwitJsonOptions, TokenizerConfig, TokenizerEntry,
 };
 use default_doc_mapper::{
     FastFieldOptions, FieldMappingEntryForSerialization, IndexRecordOptionSchema,
@@ -45,8 +49,11 @@ use default_doc_mapper::{
     TokenFilterType, TokenizerType,
 };
 pub use doc_mapper::{DocMapper, JsonObject, NamedField, TermRange, WarmupInfo};
+pub use document_builder::{IndexedDocumentBuilder, QuickwitDocument};
 pub use error::{DocParsingError, QueryParserError};
 use quickwit_common::shared_consts::FIELD_PRESENCE_FIELD_NAME;
+/// Derives [`QuickwitDocument`] for a `serde::Serialize` struct.
+pub use quickwit_macros::QuickwitDocument;
 pub use routing_expression::RoutingExpr;
 
 /// Field name reserved for storing the source document.
@@ -26,12 +26,12 @@ use futures::Future;
 use quickwit_common::pretty::PrettySample;
 use quickwit_common::{Progress, ServiceStream};
 use quickwit_metastore::{
-    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, SplitInfo,
-    SplitMetadata, SplitState,
+    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceExt, MetastoreServiceStreamSplitsExt,
+    SplitInfo, SplitMetadata, SplitState,
 };
 use quickwit_proto::metastore::{
-    DeleteSplitsRequest, ListSplitsRequest, ListSplitsResponse, MarkSplitsForDeletionRequest,
-    MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
+    ListSplitsRequest, ListSplitsResponse, MarkSplitsForDeletionRequest, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_storage::{BulkDeleteError, Storage};
@@ -231,6 +231,7 @@ async fn delete_splits_marked_for_deletion(
         match delete_splits_result {
             Ok(entries) => removed_splits.extend(entries),
             Err(delete_splits_error) => {
+                removed_splits.extend(delete_splits_error.successes);
                 failed_splits.extend(delete_splits_error.storage_failures);
                 failed_splits.extend(delete_splits_error.metastore_failures);
                 break;
@@ -310,26 +311,34 @@ pub async fn delete_splits_from_storage_and_metastore(
             .iter()
             .map(|split_info| split_info.split_id.to_string())
             .collect();
-        let delete_splits_request = DeleteSplitsRequest {
-            index_uid: Some(index_uid.clone()),
-            split_ids: split_ids.clone(),
-        };
-        let metastore_result =
-            protect_future(progress_opt, metastore.delete_splits(delete_splits_request)).await;
+        let outcome = protect_future(
+            progress_opt,
+            metastore.delete_splits_bulk(index_uid.clone(), split_ids),
+        )
+        .await;
 
-        if let Err(metastore_error) = metastore_result {
+        if let Some(metastore_error) = outcome.error_opt {
             error!(
                 error=?metastore_error,
                 index_id=index_uid.index_id,
                 "failed to delete split(s) {:?} from metastore",
-                PrettySample::new(&split_ids, 5),
+                PrettySample::new(&outcome.failed_split_ids, 5),
             );
+            let failed_split_ids: HashSet<&str> = outcome
+                .failed_split_ids
+                .iter()
+                .map(|split_id| split_id.as_str())
+                .collect();
+            let (metastore_failures, metastore_successes): (Vec<SplitInfo>, Vec<SplitInfo>) =
+                successes.into_iter().partition(|split_info| {
+                    failed_split_ids.contains(split_info.split_id.as_str())
+                });
             let delete_splits_error = DeleteSplitsError {
-                successes: Vec::new(),
+                successes: metastore_successes,
                 storage_error,
                 storage_failures,
                 metastore_error: Some(metastore_error),
-                metastore_failures: successes,
+                metastore_failures,
             };
             return Err(delete_splits_error);
         }
@@ -359,7 +368,7 @@ mod tests {
         MetastoreServiceStreamSplitsExt, SplitMetadata, SplitState, StageSplitsRequestExt,
     };
     use quickwit_proto::metastore::{
-        CreateIndexRequest, EntityKind, MockMetastoreService, StageSplitsRequest,
+        CreateIndexRequest, EmptyResponse, EntityKind, MockMetastoreService, StageSplitsRequest,
     };
     use quickwit_proto::types::IndexUid;
     use quickwit_storage::{
@@ -830,4 +839,53 @@ mod tests {
         assert_eq!(error.storage_failures.len(), 1);
         assert_eq!(error.metastore_failures.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_delete_splits_from_storage_and_metastore_partial_metastore_error() {
+        let mut mock_storage = MockStorage::new();
+        mock_storage
+            .expect_bulk_delete()
+            .return_once(|_split_paths| Ok(()));
+        let storage = Arc::new(mock_storage);
+
+        let index_uid = IndexUid::new_with_random_ulid("test-delete-splits-partial-error--index");
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_delete_splits()
+            .times(2)
+            .returning(|delete_splits_request| {
+                if delete_splits_request.split_ids.len() == 1_000 {
+                    return Ok(EmptyResponse {});
+                }
+                Err(MetastoreError::Db {
+                    message: "failed to delete splits".to_string(),
+                })
+            });
+        let splits_metadata: Vec<SplitMetadata> = (0..1_500)
+            .map(|split_ord| SplitMetadata {
+                split_id: format!("split-{split_ord}"),
+                index_uid: index_uid.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let error = delete_splits_from_storage_and_metastore(
+            index_uid,
+            storage,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            splits_metadata,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        // The splits of the successful batch are reported as deleted.
+        assert_eq!(error.successes.len(), 1_000);
+        assert!(error.storage_failures.is_empty());
+        assert_eq!(error.metastore_failures.len(), 500);
+        assert!(matches!(
+            error.metastore_error,
+            Some(MetastoreError::Db { .. })
+        ));
+    }
 }
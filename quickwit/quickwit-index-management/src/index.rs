@@ -101,6 +101,10 @@ impl IndexService {
         self.metastore.clone()
     }
 
+    pub fn storage_resolver(&self) -> StorageResolver {
+        self.storage_resolver.clone()
+    }
+
     /// Creates an index from `IndexConfig`.
     pub async fn create_index(
         &mut self,
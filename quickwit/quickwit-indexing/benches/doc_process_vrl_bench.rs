@@ -5,7 +5,7 @@ use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use quickwit_actors::{ActorHandle, Mailbox, Universe};
 use quickwit_config::{SourceInputFormat, TransformConfig};
 use quickwit_doc_mapper::DefaultDocMapper;
-use quickwit_indexing::actors::DocProcessor;
+use quickwit_indexing::actors::{DocProcessor, DocumentErrorPolicy};
 use quickwit_indexing::models::RawDocBatch;
 use quickwit_metastore::checkpoint::SourceCheckpointDelta;
 
@@ -128,7 +128,9 @@ fn create_doc_processor(
         doc_mapper,
         indexer_mailbox,
         transform_config_opt,
+        &[],
         SourceInputFormat::Json,
+        DocumentErrorPolicy::SkipAndLog,
     )
     .unwrap();
     let (mailbox, handle) = universe.spawn_builder().spawn(doc_processor);
@@ -25,9 +25,9 @@ use anyhow::{bail, Context};
 use async_trait::async_trait;
 use bytes::Bytes;
 use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, QueueCapacity};
-use quickwit_common::rate_limited_tracing::rate_limited_warn;
+use quickwit_common::rate_limited_tracing::{rate_limited_error, rate_limited_warn};
 use quickwit_common::runtimes::RuntimeType;
-use quickwit_config::{SourceInputFormat, TransformConfig};
+use quickwit_config::{FieldMapperConfig, SourceInputFormat, TransformConfig};
 use quickwit_doc_mapper::{DocMapper, DocParsingError, JsonObject};
 use quickwit_opentelemetry::otlp::{
     parse_otlp_logs_json, parse_otlp_logs_protobuf, parse_otlp_spans_json,
@@ -41,9 +41,11 @@ use tantivy::{DateTime, TantivyDocument};
 use thiserror::Error;
 use tokio::runtime::Handle;
 
+use super::field_mapper::{build_field_mappers, FieldMapper};
 #[cfg(feature = "vrl")]
 use super::vrl_processing::*;
 use crate::actors::Indexer;
+use crate::dead_letter_queue::{DeadLetterEntry, DeadLetterQueue};
 use crate::models::{
     NewPublishLock, NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock, RawDocBatch,
 };
@@ -96,6 +98,8 @@ pub enum DocProcessorError {
     #[cfg(feature = "vrl")]
     #[error("VRL transform error: {0}")]
     Transform(VrlTerminate),
+    #[error("field mapper error: {0}")]
+    FieldMapping(String),
 }
 
 impl From<OtlpLogsError> for DocProcessorError {
@@ -356,6 +360,10 @@ impl DocProcessorCounters {
                 self.num_transform_errors.fetch_add(1, Ordering::Relaxed);
                 "transform_error"
             }
+            DocProcessorError::FieldMapping(_) => {
+                self.num_transform_errors.fetch_add(1, Ordering::Relaxed);
+                "field_mapper_error"
+            }
         };
         crate::metrics::INDEXER_METRICS
             .processed_docs_total
@@ -371,6 +379,28 @@ impl DocProcessorCounters {
     }
 }
 
+/// What the doc processor does with the documents it fails to process. This is the runtime
+/// counterpart of [`quickwit_config::IndexingErrorPolicy`].
+pub enum DocumentErrorPolicy {
+    /// Drops the document and logs the error.
+    SkipAndLog,
+    /// Fails the doc processor before the batch is sent to the indexer, so that the checkpoint
+    /// of the batch is never published.
+    FailFast,
+    /// Stores the document in the dead letter queue.
+    DeadLetterQueue(DeadLetterQueue),
+}
+
+impl DocumentErrorPolicy {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::SkipAndLog => "skip_and_log",
+            Self::FailFast => "fail_fast",
+            Self::DeadLetterQueue(_) => "dead_letter_queue",
+        }
+    }
+}
+
 pub struct DocProcessor {
     doc_mapper: Arc<dyn DocMapper>,
     indexer_mailbox: Mailbox<Indexer>,
@@ -379,7 +409,9 @@ pub struct DocProcessor {
     publish_lock: PublishLock,
     #[cfg(feature = "vrl")]
     transform_opt: Option<VrlProgram>,
+    field_mappers: Vec<Box<dyn FieldMapper>>,
     input_format: SourceInputFormat,
+    document_error_policy: DocumentErrorPolicy,
 }
 
 impl DocProcessor {
@@ -389,7 +421,9 @@ impl DocProcessor {
         doc_mapper: Arc<dyn DocMapper>,
         indexer_mailbox: Mailbox<Indexer>,
         transform_config_opt: Option<TransformConfig>,
+        field_mapper_configs: &[FieldMapperConfig],
         input_format: SourceInputFormat,
+        document_error_policy: DocumentErrorPolicy,
     ) -> anyhow::Result<Self> {
         let timestamp_field_opt = extract_timestamp_field(&*doc_mapper)?;
         if cfg!(not(feature = "vrl")) && transform_config_opt.is_some() {
@@ -405,7 +439,9 @@ impl DocProcessor {
             transform_opt: transform_config_opt
                 .map(VrlProgram::try_from_transform_config)
                 .transpose()?,
+            field_mappers: build_field_mappers(field_mapper_configs)?,
             input_format,
+            document_error_policy,
         };
         Ok(doc_processor)
     }
@@ -430,8 +466,15 @@ impl DocProcessor {
         Ok(Some(timestamp))
     }
 
-    fn process_raw_doc(&mut self, raw_doc: Bytes, processed_docs: &mut Vec<ProcessedDoc>) {
+    /// Processes a raw doc and returns the first error it produced, if any. A raw doc can hold
+    /// several documents (OTLP payloads for instance).
+    fn process_raw_doc(
+        &mut self,
+        raw_doc: Bytes,
+        processed_docs: &mut Vec<ProcessedDoc>,
+    ) -> Option<String> {
         let num_bytes = raw_doc.len();
+        let mut first_error_opt: Option<String> = None;
 
         #[cfg(feature = "vrl")]
         let transform_opt = self.transform_opt.as_mut();
@@ -454,13 +497,18 @@ impl DocProcessor {
                         source_id = self.counters.source_id,
                         "{error}",
                     );
+                    if first_error_opt.is_none() {
+                        first_error_opt = Some(error.to_string());
+                    }
                     self.counters.record_error(error, num_bytes as u64);
                 }
             }
         }
+        first_error_opt
     }
 
     fn process_json_doc(&self, json_doc: JsonDoc) -> Result<ProcessedDoc, DocProcessorError> {
+        let json_doc = self.map_json_doc(json_doc)?;
         let num_bytes = json_doc.num_bytes;
 
         let (partition, doc) = self
@@ -474,6 +522,23 @@ impl DocProcessor {
             num_bytes,
         })
     }
+
+    /// Applies the field mappers of the index in order, before the document is validated
+    /// against the doc mapping.
+    fn map_json_doc(&self, json_doc: JsonDoc) -> Result<JsonDoc, DocProcessorError> {
+        if self.field_mappers.is_empty() {
+            return Ok(json_doc);
+        }
+        let num_bytes = json_doc.num_bytes;
+        let mut json_value = JsonValue::Object(json_doc.json_obj);
+
+        for field_mapper in &self.field_mappers {
+            field_mapper
+                .map_document(&mut json_value)
+                .map_err(|error| DocProcessorError::FieldMapping(error.to_string()))?;
+        }
+        JsonDoc::try_from_json_value(json_value, num_bytes)
+    }
 }
 
 fn extract_timestamp_field(doc_mapper: &dyn DocMapper) -> anyhow::Result<Option<Field>> {
@@ -542,11 +607,47 @@ impl Handler<RawDocBatch> for DocProcessor {
             return Ok(());
         }
         let mut processed_docs: Vec<ProcessedDoc> = Vec::with_capacity(raw_doc_batch.docs.len());
+        let mut dead_letter_entries: Vec<DeadLetterEntry> = Vec::new();
 
         for raw_doc in raw_doc_batch.docs {
             let _protected_zone_guard = ctx.protect_zone();
-            self.process_raw_doc(raw_doc, &mut processed_docs);
+            let error_opt = self.process_raw_doc(raw_doc.clone(), &mut processed_docs);
             ctx.record_progress();
+
+            let Some(error) = error_opt else {
+                continue;
+            };
+            crate::metrics::INDEXER_METRICS
+                .document_errors_total
+                .with_label_values([&self.counters.index_id, self.document_error_policy.name()])
+                .inc();
+            match &self.document_error_policy {
+                DocumentErrorPolicy::SkipAndLog => {}
+                DocumentErrorPolicy::FailFast => {
+                    // The batch is not forwarded to the indexer, so its checkpoint is never
+                    // published and the pipeline restarts from the last published checkpoint.
+                    return Err(anyhow::anyhow!("failed to process document: {error}").into());
+                }
+                DocumentErrorPolicy::DeadLetterQueue(_) => {
+                    dead_letter_entries.push(DeadLetterEntry::new(error, &raw_doc));
+                }
+            }
+        }
+        if let DocumentErrorPolicy::DeadLetterQueue(dead_letter_queue) = &self.document_error_policy
+        {
+            // Failing to write to the dead letter queue must not stall indexing.
+            if let Err(error) = ctx
+                .protect_future(dead_letter_queue.append(&dead_letter_entries))
+                .await
+            {
+                rate_limited_error!(
+                    limit_per_min = 6,
+                    index_id = self.counters.index_id,
+                    source_id = self.counters.source_id,
+                    "failed to append {} documents to the dead letter queue: {error:#}",
+                    dead_letter_entries.len()
+                );
+            }
         }
         let processed_doc_batch = ProcessedDocBatch::new(
             processed_docs,
@@ -606,6 +707,7 @@ mod tests {
     use quickwit_proto::opentelemetry::proto::common::v1::AnyValue as OtlpAnyValue;
     use quickwit_proto::opentelemetry::proto::logs::v1::{LogRecord, ResourceLogs, ScopeLogs};
     use quickwit_proto::opentelemetry::proto::trace::v1::{ResourceSpans, ScopeSpans, Span};
+    use quickwit_storage::RamStorage;
     use serde_json::Value as JsonValue;
     use tantivy::schema::NamedFieldDocument;
     use tantivy::Document;
@@ -626,7 +728,9 @@ mod tests {
             doc_mapper.clone(),
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -690,6 +794,90 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_doc_processor_dead_letter_queue() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let dead_letter_queue = DeadLetterQueue::new(Arc::new(RamStorage::default()));
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            &[],
+            SourceInputFormat::Json,
+            DocumentErrorPolicy::DeadLetterQueue(dead_letter_queue.clone()),
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    br#"{"body": "happy", "timestamp": 1628837062, "response_date": "2021-12-19T16:39:59+00:00", "response_time": 2, "response_payload": "YWJj"}"#, // ok
+                    br#"{"body": "sad"}"#, // missing timestamp
+                    b"{", // invalid json
+                ],
+                0..3,
+            ))
+            .await
+            .unwrap();
+        doc_processor_handle.process_pending_and_observe().await;
+
+        let batch = indexer_inbox
+            .drain_for_test_typed::<ProcessedDocBatch>()
+            .pop()
+            .unwrap();
+        assert_eq!(batch.docs.len(), 1);
+
+        let page = dead_letter_queue.read_page(1, 10).await.unwrap();
+        assert_eq!(page.num_entries, 2);
+        assert_eq!(page.entries[0].document, serde_json::json!({"body": "sad"}));
+        assert!(page.entries[0].error.starts_with("doc mapper parse error"));
+        assert_eq!(page.entries[1].document, serde_json::json!("{"));
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_doc_processor_fail_fast() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            &[],
+            SourceInputFormat::Json,
+            DocumentErrorPolicy::FailFast,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    br#"{"body": "happy", "timestamp": 1628837062, "response_date": "2021-12-19T16:39:59+00:00", "response_time": 2, "response_payload": "YWJj"}"#, // ok
+                    b"{", // invalid json
+                    br#"{"body": "happy2", "timestamp": 1628837062, "response_date": "2021-12-19T16:40:57+00:00", "response_time": 13, "response_payload": "YWJj"}"#, // ok
+                ],
+                0..3,
+            ))
+            .await
+            .unwrap();
+        let (exit_status, counters) = doc_processor_handle.join().await;
+        assert!(matches!(exit_status, ActorExitStatus::Failure(_)));
+        // The batch is aborted at the first invalid document and never reaches the indexer.
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_doc_parse_errors.load(Ordering::Relaxed), 1);
+        assert!(indexer_inbox.drain_for_test().is_empty());
+        universe.assert_quit().await;
+    }
+
     const DOCMAPPER_WITH_PARTITION_JSON: &str = r#"
         {
             "tag_fields": ["tenant"],
@@ -700,6 +888,66 @@ mod tests {
             ]
         }"#;
 
+    #[tokio::test]
+    async fn test_doc_processor_with_field_mappers() {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let field_mapper_configs = [
+            FieldMapperConfig::Rename {
+                from: "message".to_string(),
+                to: "body".to_string(),
+            },
+            FieldMapperConfig::NormalizeTimestamp {
+                field: "response_date".to_string(),
+                formats: vec!["%d/%m/%Y %H:%M:%S".to_string()],
+            },
+        ];
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper.clone(),
+            indexer_mailbox,
+            None,
+            &field_mapper_configs,
+            SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    br#"{"message": "happy", "timestamp": 1628837062, "response_date": "19/12/2021 16:39:59", "response_time": 2, "response_payload": "YWJj"}"#, // ok
+                    br#"{"message": "sad", "timestamp": 1628837062, "response_date": "2021-12-19", "response_time": 2, "response_payload": "YWJj"}"#, // unparsable timestamp
+                ],
+                0..2,
+            ))
+            .await
+            .unwrap();
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_transform_errors.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.num_doc_parse_errors.load(Ordering::Relaxed), 0);
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 1);
+
+        let batch = indexer_inbox
+            .drain_for_test_typed::<ProcessedDocBatch>()
+            .pop()
+            .unwrap();
+        assert_eq!(batch.docs.len(), 1);
+
+        let schema = doc_mapper.schema();
+        let NamedFieldDocument(named_field_doc_map) = batch.docs[0].doc.to_named_doc(&schema);
+        let doc_json = doc_mapper.doc_to_json(named_field_doc_map).unwrap();
+        assert_eq!(doc_json["body"], "happy");
+        assert_eq!(doc_json["response_date"], "2021-12-19T16:39:59Z");
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_doc_processor_partitioning() {
         let doc_mapper: Arc<dyn DocMapper> = Arc::new(
@@ -713,7 +961,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -761,7 +1011,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -793,7 +1045,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -839,7 +1093,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::OtlpLogsJson,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
 
@@ -916,7 +1172,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::OtlpLogsProtobuf,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
 
@@ -995,7 +1253,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::OtlpTracesJson,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
 
@@ -1068,7 +1328,9 @@ mod tests {
             doc_mapper,
             indexer_mailbox,
             None,
+            &[],
             SourceInputFormat::OtlpTracesProtobuf,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
 
@@ -1154,7 +1416,9 @@ mod tests_vrl {
             doc_mapper.clone(),
             indexer_mailbox,
             Some(transform_config),
+            &[],
             SourceInputFormat::Json,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
@@ -1244,7 +1508,9 @@ mod tests_vrl {
             doc_mapper.clone(),
             indexer_mailbox,
             Some(transform_config),
+            &[],
             SourceInputFormat::PlainText,
+            DocumentErrorPolicy::SkipAndLog,
         )
         .unwrap();
         let (doc_processor_mailbox, doc_processor_handle) =
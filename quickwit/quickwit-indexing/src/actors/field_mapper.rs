@@ -0,0 +1,324 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use anyhow::{bail, Context};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use quickwit_config::FieldMapperConfig;
+use quickwit_doc_mapper::JsonObject;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+/// Transforms the fields of a document before it is validated against the doc mapping.
+pub trait FieldMapper: Send + Sync + 'static {
+    fn map_document(&self, doc: &mut JsonValue) -> anyhow::Result<()>;
+}
+
+/// Builds the field mappers declared in the indexing settings of an index, in order.
+pub fn build_field_mappers(
+    field_mapper_configs: &[FieldMapperConfig],
+) -> anyhow::Result<Vec<Box<dyn FieldMapper>>> {
+    field_mapper_configs
+        .iter()
+        .map(build_field_mapper)
+        .collect()
+}
+
+fn build_field_mapper(
+    field_mapper_config: &FieldMapperConfig,
+) -> anyhow::Result<Box<dyn FieldMapper>> {
+    let field_mapper: Box<dyn FieldMapper> = match field_mapper_config {
+        FieldMapperConfig::Rename { from, to } => Box::new(RenameFieldMapper {
+            from: from.clone(),
+            to: to.clone(),
+        }),
+        FieldMapperConfig::NormalizeTimestamp { field, formats } => {
+            Box::new(TimestampNormalizerMapper {
+                field: field.clone(),
+                formats: formats.clone(),
+            })
+        }
+        FieldMapperConfig::RegexExtract {
+            source_field,
+            pattern,
+            target_fields,
+        } => {
+            let pattern = Regex::new(pattern)
+                .with_context(|| format!("failed to compile regex pattern `{pattern}`"))?;
+            Box::new(RegexExtractMapper {
+                source_field: source_field.clone(),
+                pattern,
+                target_fields: target_fields.clone(),
+            })
+        }
+    };
+    Ok(field_mapper)
+}
+
+fn as_json_object(doc: &mut JsonValue) -> anyhow::Result<&mut JsonObject> {
+    doc.as_object_mut().context("document is not an object")
+}
+
+/// Moves the value of the field `from` to the field `to`. Documents without a `from` field are
+/// left untouched.
+pub struct RenameFieldMapper {
+    pub from: String,
+    pub to: String,
+}
+
+impl FieldMapper for RenameFieldMapper {
+    fn map_document(&self, doc: &mut JsonValue) -> anyhow::Result<()> {
+        let json_obj = as_json_object(doc)?;
+
+        if let Some(value) = json_obj.remove(&self.from) {
+            json_obj.insert(self.to.clone(), value);
+        }
+        Ok(())
+    }
+}
+
+/// Parses the timestamp stored in the field `field` with the first matching format and replaces
+/// it with its RFC 3339 representation. Documents without a `field` field are left untouched.
+pub struct TimestampNormalizerMapper {
+    pub field: String,
+    /// `chrono` format strings, tried in order.
+    pub formats: Vec<String>,
+}
+
+impl TimestampNormalizerMapper {
+    fn parse_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+        self.formats.iter().find_map(|format| {
+            if let Ok(datetime) = DateTime::parse_from_str(timestamp_str, format) {
+                return Some(datetime.with_timezone(&Utc));
+            }
+            // Timestamps without a timezone are assumed to be UTC.
+            if let Ok(naive_datetime) = NaiveDateTime::parse_from_str(timestamp_str, format) {
+                return Some(naive_datetime.and_utc());
+            }
+            NaiveDate::parse_from_str(timestamp_str, format)
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        })
+    }
+}
+
+impl FieldMapper for TimestampNormalizerMapper {
+    fn map_document(&self, doc: &mut JsonValue) -> anyhow::Result<()> {
+        let json_obj = as_json_object(doc)?;
+
+        let Some(value) = json_obj.get_mut(&self.field) else {
+            return Ok(());
+        };
+        let Some(timestamp_str) = value.as_str() else {
+            bail!("timestamp field `{}` is not a string", self.field);
+        };
+        let Some(datetime) = self.parse_timestamp(timestamp_str) else {
+            bail!(
+                "failed to parse timestamp `{timestamp_str}` of field `{}` with formats {:?}",
+                self.field,
+                self.formats
+            );
+        };
+        *value = JsonValue::String(datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true));
+        Ok(())
+    }
+}
+
+/// Matches the value of the field `source_field` against `pattern` and stores the text captured
+/// by the i-th group of the pattern in the i-th field of `target_fields`. Documents without a
+/// `source_field` field or whose value does not match the pattern are left untouched.
+pub struct RegexExtractMapper {
+    pub source_field: String,
+    pub pattern: Regex,
+    pub target_fields: Vec<String>,
+}
+
+impl FieldMapper for RegexExtractMapper {
+    fn map_document(&self, doc: &mut JsonValue) -> anyhow::Result<()> {
+        let json_obj = as_json_object(doc)?;
+
+        let Some(value) = json_obj.get(&self.source_field) else {
+            return Ok(());
+        };
+        let Some(value_str) = value.as_str() else {
+            bail!("source field `{}` is not a string", self.source_field);
+        };
+        let Some(captures) = self.pattern.captures(value_str) else {
+            return Ok(());
+        };
+        let extracted_values: Vec<(String, JsonValue)> = self
+            .target_fields
+            .iter()
+            .zip(captures.iter().skip(1))
+            .filter_map(|(target_field, capture_opt)| {
+                let capture = capture_opt?;
+                Some((
+                    target_field.clone(),
+                    JsonValue::String(capture.as_str().to_string()),
+                ))
+            })
+            .collect();
+        json_obj.extend(extracted_values);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_rename_field_mapper() {
+        let field_mapper = RenameFieldMapper {
+            from: "msg".to_string(),
+            to: "message".to_string(),
+        };
+        let mut doc = json!({"msg": "hello", "level": "INFO"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"message": "hello", "level": "INFO"}));
+
+        // The `to` field is overwritten.
+        let mut doc = json!({"msg": "hello", "message": "world"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"message": "hello"}));
+
+        let mut doc = json!({"level": "INFO"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"level": "INFO"}));
+
+        let mut doc = json!(["msg"]);
+        let error = field_mapper.map_document(&mut doc).unwrap_err();
+        assert_eq!(error.to_string(), "document is not an object");
+    }
+
+    #[test]
+    fn test_timestamp_normalizer_mapper() {
+        let field_mapper = TimestampNormalizerMapper {
+            field: "timestamp".to_string(),
+            formats: vec![
+                "%d/%b/%Y:%H:%M:%S %z".to_string(),
+                "%Y-%m-%d %H:%M:%S%.f".to_string(),
+                "%Y-%m-%d".to_string(),
+            ],
+        };
+        let mut doc = json!({"timestamp": "10/Oct/2023:13:55:36 -0700"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"timestamp": "2023-10-10T20:55:36Z"}));
+
+        let mut doc = json!({"timestamp": "2023-10-10 13:55:36.123"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"timestamp": "2023-10-10T13:55:36.123Z"}));
+
+        let mut doc = json!({"timestamp": "2023-10-10"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"timestamp": "2023-10-10T00:00:00Z"}));
+
+        let mut doc = json!({"message": "hello"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"message": "hello"}));
+
+        let mut doc = json!({"timestamp": "yesterday"});
+        let error = field_mapper.map_document(&mut doc).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("failed to parse timestamp `yesterday` of field `timestamp`"));
+
+        let mut doc = json!({"timestamp": 1696946136});
+        let error = field_mapper.map_document(&mut doc).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "timestamp field `timestamp` is not a string"
+        );
+    }
+
+    #[test]
+    fn test_regex_extract_mapper() {
+        let field_mapper = RegexExtractMapper {
+            source_field: "request".to_string(),
+            pattern: Regex::new(r"^(GET|POST) (\S+)(?: (HTTP/\d\.\d))?$").unwrap(),
+            target_fields: vec![
+                "method".to_string(),
+                "path".to_string(),
+                "protocol".to_string(),
+            ],
+        };
+        let mut doc = json!({"request": "GET /index.html HTTP/1.1"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(
+            doc,
+            json!({
+                "request": "GET /index.html HTTP/1.1",
+                "method": "GET",
+                "path": "/index.html",
+                "protocol": "HTTP/1.1",
+            })
+        );
+        // Groups that do not participate in the match are skipped.
+        let mut doc = json!({"request": "POST /login"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(
+            doc,
+            json!({"request": "POST /login", "method": "POST", "path": "/login"})
+        );
+
+        let mut doc = json!({"request": "DELETE /index.html"});
+        field_mapper.map_document(&mut doc).unwrap();
+        assert_eq!(doc, json!({"request": "DELETE /index.html"}));
+
+        let mut doc = json!({"request": ["GET", "/index.html"]});
+        let error = field_mapper.map_document(&mut doc).unwrap_err();
+        assert_eq!(error.to_string(), "source field `request` is not a string");
+    }
+
+    #[test]
+    fn test_build_field_mappers() {
+        let field_mapper_configs = [
+            FieldMapperConfig::Rename {
+                from: "ts".to_string(),
+                to: "timestamp".to_string(),
+            },
+            FieldMapperConfig::NormalizeTimestamp {
+                field: "timestamp".to_string(),
+                formats: vec!["%Y-%m-%d %H:%M:%S".to_string()],
+            },
+            FieldMapperConfig::RegexExtract {
+                source_field: "timestamp".to_string(),
+                pattern: r"^(\d{4})-".to_string(),
+                target_fields: vec!["year".to_string()],
+            },
+        ];
+        let field_mappers = build_field_mappers(&field_mapper_configs).unwrap();
+        let mut doc = json!({"ts": "2023-10-10 13:55:36"});
+
+        for field_mapper in &field_mappers {
+            field_mapper.map_document(&mut doc).unwrap();
+        }
+        assert_eq!(
+            doc,
+            json!({"timestamp": "2023-10-10T13:55:36Z", "year": "2023"})
+        );
+
+        let invalid_field_mapper_configs = [FieldMapperConfig::RegexExtract {
+            source_field: "message".to_string(),
+            pattern: "(unclosed".to_string(),
+            target_fields: vec!["group".to_string()],
+        }];
+        build_field_mappers(&invalid_field_mapper_configs).unwrap_err();
+    }
+}
@@ -19,12 +19,14 @@
 
 use async_trait::async_trait;
 use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, QueueCapacity};
+use quickwit_common::fs::assert_disk_space_available;
 use quickwit_common::io::IoControls;
-use quickwit_common::runtimes::RuntimeType;
+use quickwit_common::runtimes::QuickwitRuntimes;
 use tokio::runtime::Handle;
 use tracing::instrument;
 
 use crate::actors::Packager;
+use crate::metrics::INDEXER_METRICS;
 use crate::models::{EmptySplit, IndexedSplit, IndexedSplitBatch, IndexedSplitBatchBuilder};
 
 /// The index serializer takes a non-serialized split,
@@ -56,7 +58,7 @@ impl Actor for IndexSerializer {
     }
 
     fn runtime_handle(&self) -> Handle {
-        RuntimeType::Blocking.get_runtime_handle()
+        QuickwitRuntimes::indexing()
     }
 }
 
@@ -87,6 +89,13 @@ impl Handler<IndexedSplitBatchBuilder> for IndexSerializer {
                     .set_component("index_serializer");
                 controlled_directory.set_io_controls(io_controls);
             }
+            // Serializing the split writes the in-memory segment to the scratch directory. Fail
+            // early with a descriptive error rather than with a cryptic `ENOSPC`.
+            let required_bytes = split_builder.index_writer.mem_usage() as u64;
+            let free_bytes =
+                assert_disk_space_available(split_builder.path(), required_bytes).await?;
+            INDEXER_METRICS.disk_space_bytes_free.set(free_bytes as i64);
+
             let split = split_builder.finalize()?;
             splits.push(split);
         }
@@ -123,3 +132,24 @@ impl Handler<EmptySplit> for IndexSerializer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_index_serializer_runs_on_indexing_runtime() {
+        let universe = Universe::with_accelerated_time();
+        let (packager_mailbox, _packager_inbox) = universe.create_test_mailbox();
+        let index_serializer = IndexSerializer::new(packager_mailbox);
+        let serializer_thread_name = index_serializer
+            .runtime_handle()
+            .spawn(async { std::thread::current().name().unwrap().to_string() })
+            .await
+            .unwrap();
+        assert!(serializer_thread_name.starts_with("indexing-"));
+        universe.assert_quit().await;
+    }
+}
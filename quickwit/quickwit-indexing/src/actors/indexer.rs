@@ -33,7 +33,7 @@ use quickwit_actors::{
 };
 use quickwit_common::io::IoControls;
 use quickwit_common::metrics::GaugeGuard;
-use quickwit_common::runtimes::RuntimeType;
+use quickwit_common::runtimes::QuickwitRuntimes;
 use quickwit_common::temp_dir::TempDirectory;
 use quickwit_config::IndexingSettings;
 use quickwit_doc_mapper::DocMapper;
@@ -55,7 +55,7 @@ use tracing::{info, info_span, warn, Span};
 use ulid::Ulid;
 
 use crate::actors::cooperative_indexing::{CooperativeIndexingCycle, CooperativeIndexingPeriod};
-use crate::actors::IndexSerializer;
+use crate::actors::{BackpressureMemoryGuard, IndexSerializer, IndexingPipelineBackpressure};
 use crate::models::{
     CommitTrigger, EmptySplit, IndexedSplitBatchBuilder, IndexedSplitBuilder, NewPublishLock,
     NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock,
@@ -92,6 +92,7 @@ pub struct IndexerCounters {
 
 struct IndexerState {
     pipeline_id: IndexingPipelineId,
+    schema_version: u32,
     metastore: MetastoreServiceClient,
     indexing_directory: TempDirectory,
     indexing_settings: IndexingSettings,
@@ -102,6 +103,7 @@ struct IndexerState {
     max_num_partitions: NonZeroU32,
     index_settings: IndexSettings,
     cooperative_indexing_opt: Option<CooperativeIndexingCycle>,
+    indexing_backpressure_opt: Option<IndexingPipelineBackpressure>,
 }
 
 impl IndexerState {
@@ -129,6 +131,7 @@ impl IndexerState {
         let indexed_split = IndexedSplitBuilder::new_in_dir(
             self.pipeline_id.clone(),
             partition_id,
+            self.schema_version,
             last_delete_opstamp,
             self.indexing_directory.clone(),
             index_builder,
@@ -226,6 +229,11 @@ impl IndexerState {
             GaugeGuard::from_gauge(&crate::metrics::INDEXER_METRICS.split_builders);
         split_builders_guard.add(1);
 
+        let backpressure_memory_guard_opt = self
+            .indexing_backpressure_opt
+            .as_ref()
+            .map(|indexing_backpressure| indexing_backpressure.memory_usage_guard());
+
         let workbench = IndexingWorkbench {
             workbench_id,
             batch_parent_span,
@@ -241,6 +249,7 @@ impl IndexerState {
                     .in_flight
                     .index_writer,
             ),
+            backpressure_memory_guard_opt,
             cooperative_indexing_period,
             split_builders_guard,
         };
@@ -287,6 +296,7 @@ impl IndexerState {
             publish_lock,
             last_delete_opstamp,
             memory_usage,
+            backpressure_memory_guard_opt,
             ..
         } = self
             .get_or_create_workbench(indexing_workbench_opt, ctx)
@@ -339,6 +349,10 @@ impl IndexerState {
             ctx.record_progress();
         }
         memory_usage.add(memory_usage_delta);
+
+        if let Some(backpressure_memory_guard) = backpressure_memory_guard_opt {
+            backpressure_memory_guard.add(memory_usage_delta);
+        }
         Ok(())
     }
 }
@@ -362,6 +376,8 @@ struct IndexingWorkbench {
     last_delete_opstamp: u64,
     // Number of bytes declared as used by tantivy.
     memory_usage: GaugeGuard<'static>,
+    // Same as `memory_usage`, declared to the node-wide indexing backpressure, if enabled.
+    backpressure_memory_guard_opt: Option<BackpressureMemoryGuard>,
     split_builders_guard: GaugeGuard<'static>,
     cooperative_indexing_period: Option<CooperativeIndexingPeriod>,
 }
@@ -390,7 +406,7 @@ impl Actor for Indexer {
     }
 
     fn runtime_handle(&self) -> Handle {
-        RuntimeType::Blocking.get_runtime_handle()
+        QuickwitRuntimes::indexing()
     }
 
     #[inline]
@@ -530,10 +546,12 @@ impl Indexer {
     pub fn new(
         pipeline_id: IndexingPipelineId,
         doc_mapper: Arc<dyn DocMapper>,
+        schema_version: u32,
         metastore: MetastoreServiceClient,
         indexing_directory: TempDirectory,
         indexing_settings: IndexingSettings,
         cooperative_indexing_permits_opt: Option<Arc<Semaphore>>,
+        indexing_backpressure_opt: Option<IndexingPipelineBackpressure>,
         index_serializer_mailbox: Mailbox<IndexSerializer>,
     ) -> Self {
         let schema = doc_mapper.schema();
@@ -558,6 +576,7 @@ impl Indexer {
         Self {
             indexer_state: IndexerState {
                 pipeline_id,
+                schema_version,
                 metastore: metastore.clone(),
                 indexing_directory,
                 indexing_settings,
@@ -568,6 +587,7 @@ impl Indexer {
                 index_settings,
                 max_num_partitions: doc_mapper.max_num_partitions(),
                 cooperative_indexing_opt,
+                indexing_backpressure_opt,
             },
             index_serializer_mailbox,
             indexing_workbench_opt: None,
@@ -583,6 +603,19 @@ impl Indexer {
         }
     }
 
+    /// Returns the uncompressed size of the documents held by the current workbench.
+    fn num_bytes_in_workbench(&self) -> u64 {
+        let Some(workbench) = &self.indexing_workbench_opt else {
+            return 0;
+        };
+        workbench
+            .indexed_splits
+            .values()
+            .chain(workbench.other_indexed_split_opt.iter())
+            .map(|split| split.split_attrs.uncompressed_docs_size_in_bytes)
+            .sum()
+    }
+
     async fn index_batch(
         &mut self,
         batch: ProcessedDocBatch,
@@ -609,6 +642,14 @@ impl Indexer {
             self.send_to_serializer(CommitTrigger::NumDocsLimit, ctx)
                 .await?;
         }
+        if let Some(split_num_bytes_target) =
+            self.indexer_state.indexing_settings.split_num_bytes_target
+        {
+            if self.num_bytes_in_workbench() >= split_num_bytes_target.as_u64() {
+                self.send_to_serializer(CommitTrigger::NumBytesLimit, ctx)
+                    .await?;
+            }
+        }
         if force_commit {
             self.send_to_serializer(CommitTrigger::ForceCommit, ctx)
                 .await?;
@@ -631,6 +672,7 @@ impl Indexer {
             publish_token_opt,
             batch_parent_span,
             memory_usage,
+            backpressure_memory_guard_opt,
             split_builders_guard,
             ..
         }) = self.indexing_workbench_opt.take()
@@ -682,6 +724,7 @@ impl Indexer {
                 commit_trigger,
                 batch_parent_span,
                 memory_usage,
+                _backpressure_memory_guard_opt: backpressure_memory_guard_opt,
                 _split_builders_guard: split_builders_guard,
             },
         )
@@ -773,10 +816,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -878,6 +923,107 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_indexer_triggers_commit_on_target_num_bytes() -> anyhow::Result<()> {
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let pipeline_id = IndexingPipelineId {
+            index_uid: index_uid.clone(),
+            source_id: "test-source".to_string(),
+            node_id: NodeId::from("test-node"),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let last_delete_opstamp = 10;
+        let schema = doc_mapper.schema();
+        let body_field = schema.get_field("body").unwrap();
+        let timestamp_field = schema.get_field("timestamp").unwrap();
+        let indexing_directory = TempDirectory::for_test();
+        let mut indexing_settings = IndexingSettings::for_test();
+        indexing_settings.split_num_bytes_target = Some(ByteSize(100));
+        let universe = Universe::with_accelerated_time();
+        let (index_serializer_mailbox, index_serializer_inbox) = universe.create_test_mailbox();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_last_delete_opstamp()
+            .times(2)
+            .returning(move |delete_opstamp_request| {
+                assert_eq!(delete_opstamp_request.index_uid(), &index_uid);
+                Ok(LastDeleteOpstampResponse::new(last_delete_opstamp))
+            });
+        mock_metastore.expect_publish_splits().never();
+        let indexer = Indexer::new(
+            pipeline_id,
+            doc_mapper,
+            0,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            indexing_directory,
+            indexing_settings,
+            None,
+            None,
+            index_serializer_mailbox,
+        );
+        let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+        let processed_doc = |body: &str| ProcessedDoc {
+            doc: doc!(
+                body_field=>body.to_string(),
+                timestamp_field=>DateTime::from_timestamp_secs(1_662_529_435)
+            ),
+            timestamp_opt: Some(DateTime::from_timestamp_secs(1_662_529_435)),
+            partition: 1,
+            num_bytes: 30,
+        };
+        // 60 bytes: below the target.
+        indexer_mailbox
+            .send_message(ProcessedDocBatch::new(
+                vec![processed_doc("document 1"), processed_doc("document 2")],
+                SourceCheckpointDelta::from_range(4..6),
+                false,
+            ))
+            .await?;
+        // 120 bytes: the split is cut.
+        indexer_mailbox
+            .send_message(ProcessedDocBatch::new(
+                vec![processed_doc("document 3"), processed_doc("document 4")],
+                SourceCheckpointDelta::from_range(6..8),
+                false,
+            ))
+            .await?;
+        indexer_mailbox
+            .send_message(ProcessedDocBatch::new(
+                vec![processed_doc("document 5")],
+                SourceCheckpointDelta::from_range(8..9),
+                false,
+            ))
+            .await?;
+        let indexer_counters = indexer_handle.process_pending_and_observe().await.state;
+        assert_eq!(
+            indexer_counters,
+            IndexerCounters {
+                num_splits_emitted: 1,
+                num_split_batches_emitted: 1,
+                num_docs_in_workbench: 1,
+                num_doc_batches_in_workbench: 1,
+                pipeline_metrics_opt: None,
+            }
+        );
+        let messages: Vec<IndexedSplitBatchBuilder> = index_serializer_inbox.drain_for_test_typed();
+        assert_eq!(messages.len(), 1);
+        let batch = messages.into_iter().next().unwrap();
+        assert_eq!(batch.commit_trigger, CommitTrigger::NumBytesLimit);
+        assert_eq!(batch.splits.len(), 1);
+        assert_eq!(batch.splits[0].split_attrs.num_docs, 4);
+        assert_eq!(
+            batch.splits[0].split_attrs.uncompressed_docs_size_in_bytes,
+            120
+        );
+        assert_eq!(
+            batch.checkpoint_delta_opt.unwrap().source_delta,
+            SourceCheckpointDelta::from_range(4..8)
+        );
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_indexer_triggers_commit_on_memory_limit() -> anyhow::Result<()> {
         let universe = Universe::new();
@@ -909,10 +1055,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, _indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -985,10 +1133,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1067,10 +1217,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             Some(Arc::new(Semaphore::new(1))),
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1154,10 +1306,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1238,10 +1392,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1334,10 +1490,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1380,6 +1538,63 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_indexer_runs_on_indexing_runtime() {
+        let universe = Universe::with_accelerated_time();
+        let pipeline_id = IndexingPipelineId {
+            index_uid: IndexUid::new_with_random_ulid("test-index"),
+            source_id: "test-source".to_string(),
+            node_id: NodeId::from("test-node"),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let doc_mapper: Arc<dyn DocMapper> =
+            Arc::new(serde_json::from_str::<DefaultDocMapper>(DOCMAPPER_SIMPLE_JSON).unwrap());
+        let body_field = doc_mapper.schema().get_field("body").unwrap();
+        let indexing_directory = TempDirectory::for_test();
+        let indexing_settings = IndexingSettings::for_test();
+        let (thread_name_tx, thread_name_rx) = std::sync::mpsc::channel();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_last_delete_opstamp()
+            .times(1)
+            .returning(move |_last_delete_opstamp_request| {
+                let thread_name = std::thread::current().name().map(str::to_string);
+                thread_name_tx.send(thread_name).unwrap();
+                Ok(LastDeleteOpstampResponse::new(10))
+            });
+        let (index_serializer_mailbox, _index_serializer_inbox) = universe.create_test_mailbox();
+        let indexer = Indexer::new(
+            pipeline_id,
+            doc_mapper,
+            0,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            indexing_directory,
+            indexing_settings,
+            None,
+            None,
+            index_serializer_mailbox,
+        );
+        let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+        indexer_mailbox
+            .send_message(ProcessedDocBatch::new(
+                vec![ProcessedDoc {
+                    doc: doc!(body_field=>"this is a test document"),
+                    timestamp_opt: None,
+                    partition: 1,
+                    num_bytes: 30,
+                }],
+                SourceCheckpointDelta::from_range(0..1),
+                false,
+            ))
+            .await
+            .unwrap();
+        indexer_handle.process_pending_and_observe().await;
+
+        let indexer_thread_name = thread_name_rx.recv().unwrap().unwrap();
+        assert!(indexer_thread_name.starts_with("indexing-"));
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_indexer_propagates_publish_lock() {
         let universe = Universe::with_accelerated_time();
@@ -1405,10 +1620,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1477,10 +1694,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1541,10 +1760,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
@@ -1609,10 +1830,12 @@ mod tests {
         let indexer = Indexer::new(
             pipeline_id,
             doc_mapper,
+            0,
             MetastoreServiceClient::from_mock(mock_metastore),
             indexing_directory,
             indexing_settings,
             None,
+            None,
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
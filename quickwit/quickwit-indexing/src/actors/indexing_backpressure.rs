@@ -0,0 +1,255 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytesize::ByteSize;
+use quickwit_config::IndexerConfig;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+use crate::metrics::INDEXER_METRICS;
+
+/// Node-wide backpressure applied to the sources of the indexing pipelines to bound the amount of
+/// memory used by the in-memory document buffers of the indexers.
+///
+/// When the memory usage exceeds the high water mark, the sources are paused. They are resumed
+/// once the memory usage drops below the low water mark. Pausing and resuming is implemented with
+/// a semaphore holding a single permit: the backpressure holds the permit while the sources are
+/// paused, and the sources acquire and immediately release the permit before emitting a batch.
+#[derive(Clone)]
+pub struct IndexingPipelineBackpressure {
+    inner: Arc<InnerIndexingPipelineBackpressure>,
+}
+
+struct InnerIndexingPipelineBackpressure {
+    high_water_mark_bytes: u64,
+    low_water_mark_bytes: u64,
+    resume_semaphore: Arc<Semaphore>,
+    state: Mutex<BackpressureState>,
+}
+
+#[derive(Default)]
+struct BackpressureState {
+    memory_usage_bytes: i64,
+    // Held while the sources are paused.
+    pause_permit_opt: Option<OwnedSemaphorePermit>,
+    paused_since_opt: Option<Instant>,
+}
+
+impl fmt::Debug for IndexingPipelineBackpressure {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("IndexingPipelineBackpressure")
+            .field(
+                "high_water_mark",
+                &ByteSize(self.inner.high_water_mark_bytes),
+            )
+            .field("low_water_mark", &ByteSize(self.inner.low_water_mark_bytes))
+            .field("is_paused", &self.is_paused())
+            .finish()
+    }
+}
+
+impl IndexingPipelineBackpressure {
+    pub fn new(high_water_mark: ByteSize, low_water_mark: ByteSize) -> Self {
+        let inner = InnerIndexingPipelineBackpressure {
+            high_water_mark_bytes: high_water_mark.as_u64(),
+            low_water_mark_bytes: low_water_mark.as_u64(),
+            resume_semaphore: Arc::new(Semaphore::new(1)),
+            state: Mutex::new(BackpressureState::default()),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Creates the backpressure configured in the indexer config, if any.
+    pub fn from_indexer_config(indexer_config: &IndexerConfig) -> Option<Self> {
+        let (high_water_mark, low_water_mark) = indexer_config.backpressure_water_marks()?;
+        Some(Self::new(high_water_mark, low_water_mark))
+    }
+
+    /// Returns a guard tracking the memory used by an in-memory document buffer. The memory
+    /// declared through the guard is released when the guard is dropped.
+    pub fn memory_usage_guard(&self) -> BackpressureMemoryGuard {
+        BackpressureMemoryGuard {
+            backpressure: self.clone(),
+            memory_usage_bytes: 0,
+        }
+    }
+
+    pub fn memory_usage(&self) -> ByteSize {
+        let state = self.inner.state.lock().unwrap();
+        ByteSize(state.memory_usage_bytes.max(0) as u64)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        let state = self.inner.state.lock().unwrap();
+        state.pause_permit_opt.is_some()
+    }
+
+    /// Waits until the sources are allowed to emit batches.
+    pub async fn wait_until_resumed(&self) {
+        let _permit = self
+            .inner
+            .resume_semaphore
+            .acquire()
+            .await
+            .expect("the semaphore should never be closed");
+    }
+
+    fn add_memory_usage(&self, delta: i64) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.memory_usage_bytes += delta;
+
+        if state.pause_permit_opt.is_none()
+            && state.memory_usage_bytes > self.inner.high_water_mark_bytes as i64
+        {
+            // The permit may be transiently held by a source. In that case, the sources will be
+            // paused on the next memory usage update.
+            if let Ok(pause_permit) = self.inner.resume_semaphore.clone().try_acquire_owned() {
+                warn!(
+                    memory_usage=%ByteSize(state.memory_usage_bytes as u64),
+                    high_water_mark=%ByteSize(self.inner.high_water_mark_bytes),
+                    "pausing indexing pipeline sources"
+                );
+                state.pause_permit_opt = Some(pause_permit);
+                state.paused_since_opt = Some(Instant::now());
+                INDEXER_METRICS.indexer_backpressure_total.inc();
+            }
+        } else if state.pause_permit_opt.is_some()
+            && state.memory_usage_bytes <= self.inner.low_water_mark_bytes as i64
+        {
+            let pause_duration = state
+                .paused_since_opt
+                .take()
+                .map(|paused_since| paused_since.elapsed())
+                .unwrap_or(Duration::ZERO);
+            info!(
+                memory_usage=%ByteSize(state.memory_usage_bytes.max(0) as u64),
+                pause_duration_ms=pause_duration.as_millis(),
+                "resuming indexing pipeline sources"
+            );
+            state.pause_permit_opt = None;
+            INDEXER_METRICS
+                .indexer_backpressure_duration_seconds
+                .inc_by(pause_duration.as_secs_f64());
+        }
+    }
+}
+
+/// Tracks the memory used by an in-memory document buffer on behalf of the
+/// [`IndexingPipelineBackpressure`].
+pub struct BackpressureMemoryGuard {
+    backpressure: IndexingPipelineBackpressure,
+    memory_usage_bytes: i64,
+}
+
+impl fmt::Debug for BackpressureMemoryGuard {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("BackpressureMemoryGuard")
+            .field("memory_usage_bytes", &self.memory_usage_bytes)
+            .finish()
+    }
+}
+
+impl BackpressureMemoryGuard {
+    pub fn add(&mut self, delta: i64) {
+        self.memory_usage_bytes += delta;
+        self.backpressure.add_memory_usage(delta);
+    }
+}
+
+impl Drop for BackpressureMemoryGuard {
+    fn drop(&mut self) {
+        self.backpressure.add_memory_usage(-self.memory_usage_bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_indexing_pipeline_backpressure() {
+        let backpressure = IndexingPipelineBackpressure::new(ByteSize(1_000), ByteSize(500));
+        let backpressure_total_before = INDEXER_METRICS.indexer_backpressure_total.get();
+        let mut first_guard = backpressure.memory_usage_guard();
+        let mut second_guard = backpressure.memory_usage_guard();
+
+        first_guard.add(600);
+        assert!(!backpressure.is_paused());
+        backpressure.wait_until_resumed().await;
+
+        second_guard.add(600);
+        assert!(backpressure.is_paused());
+        assert_eq!(backpressure.memory_usage(), ByteSize(1_200));
+        assert!(INDEXER_METRICS.indexer_backpressure_total.get() > backpressure_total_before);
+
+        tokio::time::timeout(Duration::from_millis(50), backpressure.wait_until_resumed())
+            .await
+            .unwrap_err();
+
+        // Dropping below the high water mark is not enough to resume the sources.
+        first_guard.add(-300);
+        assert!(backpressure.is_paused());
+
+        drop(second_guard);
+        assert!(!backpressure.is_paused());
+        assert_eq!(backpressure.memory_usage(), ByteSize(300));
+        backpressure.wait_until_resumed().await;
+
+        drop(first_guard);
+        assert_eq!(backpressure.memory_usage(), ByteSize(0));
+    }
+
+    #[tokio::test]
+    async fn test_indexing_pipeline_backpressure_resumes_waiting_sources() {
+        let backpressure = IndexingPipelineBackpressure::new(ByteSize(1_000), ByteSize(500));
+        // Simulates splits accumulating in memory because the storage is slow to write them.
+        let mut slow_split_guard = backpressure.memory_usage_guard();
+        slow_split_guard.add(2_000);
+        assert!(backpressure.is_paused());
+
+        let waiting_sources: Vec<_> = (0..3)
+            .map(|_| {
+                let backpressure = backpressure.clone();
+                tokio::spawn(async move { backpressure.wait_until_resumed().await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(waiting_sources
+            .iter()
+            .all(|waiting_source| !waiting_source.is_finished()));
+
+        // The split is finally written to the storage.
+        drop(slow_split_guard);
+
+        for waiting_source in waiting_sources {
+            tokio::time::timeout(Duration::from_secs(1), waiting_source)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+}
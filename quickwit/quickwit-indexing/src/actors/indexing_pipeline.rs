@@ -30,7 +30,7 @@ use quickwit_actors::{
 use quickwit_common::pubsub::EventBroker;
 use quickwit_common::temp_dir::TempDirectory;
 use quickwit_common::KillSwitch;
-use quickwit_config::{IndexingSettings, SourceConfig};
+use quickwit_config::{IndexingErrorPolicy, IndexingSettings, SourceConfig};
 use quickwit_doc_mapper::DocMapper;
 use quickwit_ingest::IngesterPool;
 use quickwit_proto::indexing::IndexingPipelineId;
@@ -41,12 +41,13 @@ use tokio::sync::Semaphore;
 use tracing::{debug, error, info, instrument};
 
 use super::MergePlanner;
-use crate::actors::doc_processor::DocProcessor;
+use crate::actors::doc_processor::{DocProcessor, DocumentErrorPolicy};
 use crate::actors::index_serializer::IndexSerializer;
 use crate::actors::publisher::PublisherType;
 use crate::actors::sequencer::Sequencer;
 use crate::actors::uploader::UploaderType;
-use crate::actors::{Indexer, Packager, Publisher, Uploader};
+use crate::actors::{Indexer, IndexingPipelineBackpressure, Packager, Publisher, Uploader};
+use crate::dead_letter_queue::DeadLetterQueue;
 use crate::merge_policy::MergePolicy;
 use crate::models::IndexingStatistics;
 use crate::source::{
@@ -395,10 +396,12 @@ impl IndexingPipeline {
         let indexer = Indexer::new(
             self.params.pipeline_id.clone(),
             self.params.doc_mapper.clone(),
+            self.params.schema_version,
             self.params.metastore.clone(),
             self.params.indexing_directory.clone(),
             self.params.indexing_settings.clone(),
             self.params.cooperative_indexing_permits.clone(),
+            self.params.indexing_backpressure_opt.clone(),
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = ctx
@@ -411,13 +414,31 @@ impl IndexingPipeline {
             .set_kill_switch(self.kill_switch.clone())
             .spawn(indexer);
 
+        let document_error_policy = match &self.params.indexing_settings.on_document_error {
+            IndexingErrorPolicy::SkipAndLog => DocumentErrorPolicy::SkipAndLog,
+            IndexingErrorPolicy::FailFast => DocumentErrorPolicy::FailFast,
+            IndexingErrorPolicy::DeadLetterQueue(dlq_config) => {
+                let dead_letter_queue_storage = ctx
+                    .protect_future(self.params.source_storage_resolver.resolve(&dlq_config.uri))
+                    .await?;
+                let writer_id = format!(
+                    "{}/{}",
+                    self.params.pipeline_id.node_id, self.params.pipeline_id.pipeline_uid
+                );
+                DocumentErrorPolicy::DeadLetterQueue(
+                    DeadLetterQueue::new(dead_letter_queue_storage).with_writer_id(writer_id),
+                )
+            }
+        };
         let doc_processor = DocProcessor::try_new(
             index_id.to_string(),
             source_id.to_string(),
             self.params.doc_mapper.clone(),
             indexer_mailbox,
             self.params.source_config.transform_config.clone(),
+            &self.params.indexing_settings.field_mappers,
             self.params.source_config.input_format,
+            document_error_policy,
         )?;
         let (doc_processor_mailbox, doc_processor_handle) = ctx
             .spawn_actor()
@@ -443,6 +464,7 @@ impl IndexingPipeline {
         let actor_source = SourceActor {
             source,
             doc_processor_mailbox,
+            indexing_backpressure_opt: self.params.indexing_backpressure_opt.clone(),
         };
         let (source_mailbox, source_handle) = ctx
             .spawn_actor()
@@ -570,11 +592,13 @@ pub struct IndexingPipelineParams {
 
     // Indexing-related parameters
     pub doc_mapper: Arc<dyn DocMapper>,
+    pub schema_version: u32,
     pub indexing_directory: TempDirectory,
     pub indexing_settings: IndexingSettings,
     pub split_store: IndexingSplitStore,
     pub max_concurrent_split_uploads_index: usize,
     pub cooperative_indexing_permits: Option<Arc<Semaphore>>,
+    pub indexing_backpressure_opt: Option<IndexingPipelineBackpressure>,
 
     // Merge-related parameters
     pub merge_policy: Arc<dyn MergePolicy>,
@@ -700,6 +724,7 @@ mod tests {
         let pipeline_params = IndexingPipelineParams {
             pipeline_id,
             doc_mapper: Arc::new(default_doc_mapper_for_test()),
+            schema_version: 0,
             source_config,
             source_storage_resolver: StorageResolver::for_test(),
             indexing_directory: TempDirectory::for_test(),
@@ -713,6 +738,7 @@ mod tests {
             max_concurrent_split_uploads_index: 4,
             max_concurrent_split_uploads_merge: 5,
             cooperative_indexing_permits: None,
+            indexing_backpressure_opt: None,
             merge_planner_mailbox,
             event_broker: EventBroker::default(),
         };
@@ -812,6 +838,7 @@ mod tests {
         let pipeline_params = IndexingPipelineParams {
             pipeline_id,
             doc_mapper: Arc::new(default_doc_mapper_for_test()),
+            schema_version: 0,
             source_config,
             source_storage_resolver: StorageResolver::for_test(),
             indexing_directory: TempDirectory::for_test(),
@@ -825,6 +852,7 @@ mod tests {
             max_concurrent_split_uploads_index: 4,
             max_concurrent_split_uploads_merge: 5,
             cooperative_indexing_permits: None,
+            indexing_backpressure_opt: None,
             merge_planner_mailbox,
             event_broker: Default::default(),
         };
@@ -910,6 +938,7 @@ mod tests {
         let indexing_pipeline_params = IndexingPipelineParams {
             pipeline_id,
             doc_mapper,
+            schema_version: 0,
             source_config,
             source_storage_resolver: StorageResolver::for_test(),
             indexing_directory: TempDirectory::for_test(),
@@ -923,6 +952,7 @@ mod tests {
             max_concurrent_split_uploads_index: 4,
             max_concurrent_split_uploads_merge: 5,
             cooperative_indexing_permits: None,
+            indexing_backpressure_opt: None,
             merge_planner_mailbox: merge_planner_mailbox.clone(),
             event_broker: Default::default(),
         };
@@ -1036,6 +1066,7 @@ mod tests {
         let pipeline_params = IndexingPipelineParams {
             pipeline_id,
             doc_mapper: Arc::new(broken_mapper),
+            schema_version: 0,
             source_config,
             source_storage_resolver: StorageResolver::for_test(),
             indexing_directory: TempDirectory::for_test(),
@@ -1049,6 +1080,7 @@ mod tests {
             max_concurrent_split_uploads_index: 4,
             max_concurrent_split_uploads_merge: 5,
             cooperative_indexing_permits: None,
+            indexing_backpressure_opt: None,
             merge_planner_mailbox,
             event_broker: Default::default(),
         };
@@ -64,8 +64,11 @@ use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn};
 
 use super::merge_pipeline::{MergePipeline, MergePipelineParams};
-use super::{MergePlanner, MergeSchedulerService};
-use crate::models::{DetachIndexingPipeline, DetachMergePipeline, ObservePipeline, SpawnPipeline};
+use super::{IndexingPipelineBackpressure, MergePlanner, MergeSchedulerService};
+use crate::models::{
+    DetachIndexingPipeline, DetachMergePipeline, ListStalledPipelines, ObservePipeline,
+    SpawnPipeline,
+};
 use crate::source::{AssignShards, Assignment};
 use crate::split_store::{LocalSplitStore, SplitStoreQuota};
 use crate::{IndexingPipeline, IndexingPipelineParams, IndexingSplitStore, IndexingStatistics};
@@ -116,6 +119,7 @@ pub struct IndexingService {
     max_concurrent_split_uploads: usize,
     merge_pipeline_handles: HashMap<MergePipelineId, MergePipelineHandle>,
     cooperative_indexing_permits: Option<Arc<Semaphore>>,
+    indexing_backpressure_opt: Option<IndexingPipelineBackpressure>,
     merge_io_throughput_limiter_opt: Option<Limiter>,
     event_broker: EventBroker,
 }
@@ -137,7 +141,7 @@ impl IndexingService {
         node_id: NodeId,
         data_dir_path: PathBuf,
         indexer_config: IndexerConfig,
-        num_blocking_threads: usize,
+        num_indexing_threads: usize,
         cluster: Cluster,
         metastore: MetastoreServiceClient,
         ingest_api_service_opt: Option<Mailbox<IngestApiService>>,
@@ -159,10 +163,12 @@ impl IndexingService {
             temp_dir::create_or_purge_directory(&data_dir_path.join(INDEXING_DIR_NAME)).await?;
         let queue_dir_path = data_dir_path.join(QUEUES_DIR_NAME);
         let cooperative_indexing_permits = if indexer_config.enable_cooperative_indexing {
-            Some(Arc::new(Semaphore::new(num_blocking_threads)))
+            Some(Arc::new(Semaphore::new(num_indexing_threads)))
         } else {
             None
         };
+        let indexing_backpressure_opt =
+            IndexingPipelineBackpressure::from_indexer_config(&indexer_config);
         Ok(IndexingService {
             node_id,
             indexing_root_directory,
@@ -180,6 +186,7 @@ impl IndexingService {
             merge_pipeline_handles: HashMap::new(),
             merge_io_throughput_limiter_opt,
             cooperative_indexing_permits,
+            indexing_backpressure_opt,
             event_broker,
         })
     }
@@ -318,11 +325,13 @@ impl IndexingService {
             storage,
             // Indexing-related parameters
             doc_mapper,
+            schema_version: index_config.schema_version,
             indexing_directory,
             indexing_settings: index_config.indexing_settings.clone(),
             split_store,
             max_concurrent_split_uploads_index,
             cooperative_indexing_permits: self.cooperative_indexing_permits.clone(),
+            indexing_backpressure_opt: self.indexing_backpressure_opt.clone(),
             // Merge-related parameters
             merge_policy,
             max_concurrent_split_uploads_merge,
@@ -854,6 +863,29 @@ impl Handler<ObservePipeline> for IndexingService {
     }
 }
 
+#[async_trait]
+impl Handler<ListStalledPipelines> for IndexingService {
+    type Reply = Vec<IndexingPipelineId>;
+
+    async fn handle(
+        &mut self,
+        _msg: ListStalledPipelines,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        let stalled_pipeline_ids = self
+            .indexing_pipelines
+            .values()
+            .filter(|pipeline_handle| {
+                // The generation is only incremented when a spawn attempt succeeds.
+                let statistics = pipeline_handle.handle.last_observation();
+                statistics.num_spawn_attempts > statistics.generation
+            })
+            .map(|pipeline_handle| pipeline_handle.indexing_pipeline_id.clone())
+            .collect();
+        Ok(stalled_pipeline_ids)
+    }
+}
+
 #[async_trait]
 impl Handler<DetachIndexingPipeline> for IndexingService {
     type Reply = Result<ActorHandle<IndexingPipeline>, IndexingError>;
@@ -1004,7 +1036,7 @@ mod tests {
         cluster: Cluster,
     ) -> (Mailbox<IndexingService>, ActorHandle<IndexingService>) {
         let indexer_config = IndexerConfig::for_test().unwrap();
-        let num_blocking_threads = 1;
+        let num_indexing_threads = 1;
         let storage_resolver = StorageResolver::unconfigured();
         let queues_dir_path = data_dir_path.join(QUEUES_DIR_NAME);
         let ingest_api_service =
@@ -1016,7 +1048,7 @@ mod tests {
             NodeId::from("test-node"),
             data_dir_path.to_path_buf(),
             indexer_config,
-            num_blocking_threads,
+            num_indexing_threads,
             cluster,
             metastore,
             Some(ingest_api_service),
@@ -1456,7 +1488,7 @@ mod tests {
         let temp_dir = tempfile::tempdir().unwrap();
         let data_dir_path = temp_dir.path().to_path_buf();
         let indexer_config = IndexerConfig::for_test().unwrap();
-        let num_blocking_threads = 1;
+        let num_indexing_threads = 1;
         let storage_resolver = StorageResolver::unconfigured();
         let universe = Universe::with_accelerated_time();
         let queues_dir_path = data_dir_path.join(QUEUES_DIR_NAME);
@@ -1469,7 +1501,7 @@ mod tests {
             NodeId::from("test-node"),
             data_dir_path,
             indexer_config,
-            num_blocking_threads,
+            num_indexing_threads,
             cluster.clone(),
             metastore.clone(),
             Some(ingest_api_service),
@@ -1664,14 +1696,14 @@ mod tests {
         // Setup `IndexingService`
         let data_dir_path = temp_dir.path().to_path_buf();
         let indexer_config = IndexerConfig::for_test().unwrap();
-        let num_blocking_threads = 1;
+        let num_indexing_threads = 1;
         let storage_resolver = StorageResolver::unconfigured();
         let merge_scheduler_service: Mailbox<MergeSchedulerService> = universe.get_or_spawn_one();
         let mut indexing_server = IndexingService::new(
             NodeId::from("test-ingest-api-gc-node"),
             data_dir_path,
             indexer_config,
-            num_blocking_threads,
+            num_indexing_threads,
             cluster.clone(),
             metastore.clone(),
             Some(ingest_api_service.clone()),
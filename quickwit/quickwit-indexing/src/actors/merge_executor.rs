@@ -262,6 +262,11 @@ pub fn merge_split_attrs(
         uncompressed_docs_size_in_bytes,
         delete_opstamp,
         num_merge_ops: max_merge_ops(splits) + 1,
+        // The merge planner only merges splits sharing the same schema version.
+        schema_version: splits
+            .first()
+            .map(|split| split.schema_version)
+            .unwrap_or_default(),
     }
 }
 
@@ -444,6 +449,7 @@ impl MergeExecutor {
                 uncompressed_docs_size_in_bytes,
                 delete_opstamp: last_delete_opstamp,
                 num_merge_ops: split.num_merge_ops,
+                schema_version: split.schema_version,
             },
             index: merged_index,
             split_scratch_directory: merge_scratch_directory,
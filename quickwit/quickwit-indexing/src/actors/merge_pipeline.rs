@@ -48,7 +48,7 @@ use crate::actors::merge_split_downloader::MergeSplitDownloader;
 use crate::actors::publisher::PublisherType;
 use crate::actors::{MergeExecutor, MergePlanner, Packager, Publisher, Uploader, UploaderType};
 use crate::merge_policy::MergePolicy;
-use crate::models::MergeStatistics;
+use crate::models::{MergeStatistics, RunMergeOperations};
 use crate::split_store::IndexingSplitStore;
 
 /// Spawning a merge pipeline puts a lot of pressure on the metastore so
@@ -500,6 +500,22 @@ impl Handler<Spawn> for MergePipeline {
     }
 }
 
+#[async_trait]
+impl Handler<RunMergeOperations> for MergePipeline {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        run_merge_operations: RunMergeOperations,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        // The merge planner mailbox outlives the successive incarnations of the pipeline.
+        ctx.send_message(&self.merge_planner_mailbox, run_merge_operations)
+            .await?;
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct MergePipelineParams {
     pub pipeline_id: MergePipelineId,
@@ -34,14 +34,17 @@ use super::MergeSchedulerService;
 use crate::actors::merge_scheduler_service::schedule_merge;
 use crate::actors::MergeSplitDownloader;
 use crate::merge_policy::MergeOperation;
-use crate::models::NewSplits;
+use crate::models::{NewSplits, RunMergeOperations};
 use crate::MergePolicy;
 
 /// The merge planner decides when to start a merge task.
 pub struct MergePlanner {
     /// A young split is a split that has not reached maturity
     /// yet and can be candidate to merge operations.
-    partitioned_young_splits: HashMap<u64, Vec<SplitMetadata>>,
+    ///
+    /// Young splits are grouped by partition ID and schema version: splits indexed with
+    /// different doc mappings are never merged together.
+    partitioned_young_splits: HashMap<(u64, u32), Vec<SplitMetadata>>,
 
     /// This set contains all of the split ids that we "acknowledged".
     /// The point of this set is to rapidly dismiss redundant `NewSplit` message.
@@ -145,6 +148,47 @@ impl Handler<NewSplits> for MergePlanner {
     }
 }
 
+#[async_trait]
+impl Handler<RunMergeOperations> for MergePlanner {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        run_merge_operations: RunMergeOperations,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        for merge_operation in run_merge_operations.merge_operations {
+            let schema_versions: HashSet<u32> = merge_operation
+                .splits
+                .iter()
+                .map(|split| split.schema_version)
+                .collect();
+            if schema_versions.len() > 1 {
+                warn!(
+                    merge_operation=?merge_operation,
+                    "ignoring requested merge operation over splits with different schema versions"
+                );
+                continue;
+            }
+            // The splits are acknowledged so that they are not recorded again as young splits.
+            for split in &merge_operation.splits {
+                self.acknownledge_split(split.split_id());
+            }
+            info!(merge_operation=?merge_operation, "schedule requested merge operation");
+            let tracked_merge_operation = self
+                .ongoing_merge_operations_inventory
+                .track(merge_operation);
+            schedule_merge(
+                &self.merge_scheduler_service,
+                tracked_merge_operation,
+                self.merge_split_downloader_mailbox.clone(),
+            )
+            .await?
+        }
+        Ok(())
+    }
+}
+
 impl MergePlanner {
     pub fn queue_capacity() -> QueueCapacity {
         // We cannot have a Queue capacity of 0 here because `try_send_self`
@@ -228,7 +272,7 @@ impl MergePlanner {
     fn record_split(&mut self, new_split: SplitMetadata) {
         let splits_for_partition: &mut Vec<SplitMetadata> = self
             .partitioned_young_splits
-            .entry(new_split.partition_id)
+            .entry((new_split.partition_id, new_split.schema_version))
             .or_default();
         splits_for_partition.push(new_split);
     }
@@ -331,9 +375,9 @@ mod tests {
 
     use crate::actors::MergePlanner;
     use crate::merge_policy::{
-        merge_policy_from_settings, MergePolicy, MergeTask, StableLogMergePolicy,
+        merge_policy_from_settings, MergeOperation, MergePolicy, MergeTask, StableLogMergePolicy,
     };
-    use crate::models::NewSplits;
+    use crate::models::{NewSplits, RunMergeOperations};
 
     fn split_metadata_for_test(
         index_uid: &IndexUid,
@@ -667,4 +711,162 @@ mod tests {
         universe.assert_quit().await;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_merge_planner_does_not_merge_splits_with_different_schema_versions(
+    ) -> anyhow::Result<()> {
+        let node_id = NodeId::from("test-node");
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let pipeline_id = MergePipelineId {
+            node_id,
+            index_uid: index_uid.clone(),
+            source_id: "test-source".to_string(),
+        };
+        let merge_policy = Arc::new(StableLogMergePolicy::new(
+            StableLogMergePolicyConfig {
+                min_level_num_docs: 10_000,
+                merge_factor: 3,
+                max_merge_factor: 5,
+                maturation_period: Duration::from_secs(3600),
+            },
+            50_000,
+        ));
+        let universe = Universe::with_accelerated_time();
+        let (merge_split_downloader_mailbox, merge_split_downloader_inbox) =
+            universe.create_test_mailbox();
+
+        let merge_planner = MergePlanner::new(
+            &pipeline_id,
+            Vec::new(),
+            merge_policy,
+            merge_split_downloader_mailbox,
+            universe.get_or_spawn_one(),
+        );
+        let (merge_planner_mailbox, merge_planner_handle) =
+            universe.spawn_builder().spawn(merge_planner);
+
+        let split_with_schema_version = |split_id: &str, schema_version: u32| SplitMetadata {
+            schema_version,
+            ..split_metadata_for_test(&index_uid, split_id, 1, 1_000, 0)
+        };
+        let message = NewSplits {
+            new_splits: vec![
+                split_with_schema_version("1", 0),
+                split_with_schema_version("2", 0),
+                split_with_schema_version("3", 1),
+            ],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+        assert!(merge_split_downloader_inbox
+            .drain_for_test_typed::<MergeTask>()
+            .is_empty());
+
+        let message = NewSplits {
+            new_splits: vec![split_with_schema_version("4", 0)],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+
+        let operations = merge_split_downloader_inbox.drain_for_test_typed::<MergeTask>();
+        assert_eq!(operations.len(), 1);
+
+        let mut merged_split_ids: Vec<&str> = operations[0]
+            .splits
+            .iter()
+            .map(|split| split.split_id())
+            .collect();
+        merged_split_ids.sort_unstable();
+        assert_eq!(merged_split_ids, ["1", "2", "4"]);
+
+        // Requested merge operations mixing schema versions are ignored.
+        let message = RunMergeOperations {
+            merge_operations: vec![MergeOperation::new_merge_operation(vec![
+                split_with_schema_version("5", 0),
+                split_with_schema_version("6", 1),
+            ])],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+        assert!(merge_split_downloader_inbox
+            .drain_for_test_typed::<MergeTask>()
+            .is_empty());
+
+        universe.assert_quit().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_planner_run_merge_operations() -> anyhow::Result<()> {
+        let node_id = NodeId::from("test-node");
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let pipeline_id = MergePipelineId {
+            node_id,
+            index_uid: index_uid.clone(),
+            source_id: "test-source".to_string(),
+        };
+        let merge_policy = Arc::new(StableLogMergePolicy::new(
+            StableLogMergePolicyConfig {
+                min_level_num_docs: 10_000,
+                merge_factor: 3,
+                max_merge_factor: 5,
+                maturation_period: Duration::from_secs(3600),
+            },
+            50_000,
+        ));
+        let universe = Universe::with_accelerated_time();
+        let (merge_split_downloader_mailbox, merge_split_downloader_inbox) =
+            universe.create_test_mailbox();
+
+        let merge_planner = MergePlanner::new(
+            &pipeline_id,
+            Vec::new(),
+            merge_policy,
+            merge_split_downloader_mailbox,
+            universe.get_or_spawn_one(),
+        );
+        let (merge_planner_mailbox, merge_planner_handle) =
+            universe.spawn_builder().spawn(merge_planner);
+
+        // Mature splits are ignored by the merge policy but can be merged on demand.
+        let mature_split = |split_id: &str| SplitMetadata {
+            maturity: SplitMaturity::Mature,
+            ..split_metadata_for_test(&index_uid, split_id, 1, 1_000, 0)
+        };
+        let message = RunMergeOperations {
+            merge_operations: vec![
+                MergeOperation::new_merge_operation(vec![mature_split("1"), mature_split("2")]),
+                MergeOperation::new_merge_operation(vec![mature_split("3"), mature_split("4")]),
+            ],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+
+        let operations = merge_split_downloader_inbox.drain_for_test_typed::<MergeTask>();
+        assert_eq!(operations.len(), 2);
+
+        let mut merged_split_ids: Vec<&str> = operations
+            .iter()
+            .flat_map(|operation| operation.splits.iter().map(|split| split.split_id()))
+            .collect();
+        merged_split_ids.sort_unstable();
+        assert_eq!(merged_split_ids, ["1", "2", "3", "4"]);
+
+        // The splits of the requested merge operations are not recorded again.
+        let message = NewSplits {
+            new_splits: vec![
+                split_metadata_for_test(&index_uid, "1", 1, 1_000, 0),
+                split_metadata_for_test(&index_uid, "2", 1, 1_000, 0),
+                split_metadata_for_test(&index_uid, "3", 1, 1_000, 0),
+            ],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+        assert!(merge_split_downloader_inbox
+            .drain_for_test_typed::<MergeTask>()
+            .is_empty());
+
+        universe.assert_quit().await;
+        Ok(())
+    }
 }
@@ -19,8 +19,10 @@
 
 mod cooperative_indexing;
 mod doc_processor;
+mod field_mapper;
 mod index_serializer;
 mod indexer;
+mod indexing_backpressure;
 mod indexing_pipeline;
 mod indexing_service;
 mod merge_executor;
@@ -35,9 +37,14 @@ mod uploader;
 #[cfg(feature = "vrl")]
 mod vrl_processing;
 
-pub use doc_processor::{DocProcessor, DocProcessorCounters};
+pub use doc_processor::{DocProcessor, DocProcessorCounters, DocumentErrorPolicy};
+pub use field_mapper::{
+    build_field_mappers, FieldMapper, RegexExtractMapper, RenameFieldMapper,
+    TimestampNormalizerMapper,
+};
 pub use index_serializer::IndexSerializer;
 pub use indexer::{Indexer, IndexerCounters};
+pub use indexing_backpressure::{BackpressureMemoryGuard, IndexingPipelineBackpressure};
 pub use indexing_pipeline::{IndexingPipeline, IndexingPipelineParams};
 pub use indexing_service::{IndexingService, IndexingServiceCounters, INDEXING_DIR_NAME};
 pub use merge_executor::{combine_partition_ids, merge_split_attrs, MergeExecutor};
@@ -320,12 +320,17 @@ fn create_packaged_split(
     ctx.record_progress();
 
     let serialized_split_fields = serialize_field_metadata(&fields_metadata);
+    let field_names = fields_metadata
+        .iter()
+        .map(|field_metadata| field_metadata.field_name.clone())
+        .collect();
 
     let packaged_split = PackagedSplit {
         serialized_split_fields,
         split_attrs: split.split_attrs,
         split_scratch_directory: split.split_scratch_directory,
         tags,
+        field_names,
         split_files,
         hotcache_bytes,
     };
@@ -527,6 +532,7 @@ mod tests {
                 replaced_split_ids: Vec::new(),
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                schema_version: 0,
             },
             index,
             split_scratch_directory,
@@ -602,6 +608,23 @@ mod tests {
                 "tag_u64:42"
             ]
         );
+        assert_eq!(
+            &split
+                .field_names
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<&str>>(),
+            &[
+                "tag_bool",
+                "tag_f64",
+                "tag_i64",
+                "tag_many",
+                "tag_str",
+                "tag_u64",
+                "text",
+                "timestamp"
+            ]
+        );
         assert_eq!(
             split.split_attrs.time_range,
             Some(
@@ -36,7 +36,7 @@ use quickwit_metastore::{SplitMetadata, StageSplitsRequestExt};
 use quickwit_proto::metastore::{MetastoreService, MetastoreServiceClient, StageSplitsRequest};
 use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
 use quickwit_proto::types::{IndexUid, PublishToken};
-use quickwit_storage::SplitPayloadBuilder;
+use quickwit_storage::{PutPayload, SplitPayloadBuilder};
 use serde::Serialize;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Semaphore, SemaphorePermit};
@@ -325,7 +325,9 @@ impl Handler<PackagedSplitBatch> for Uploader {
                         &merge_policy,
                         &packaged_split.split_attrs,
                         packaged_split.tags.clone(),
+                        packaged_split.field_names.clone(),
                         split_streamer.footer_range.start..split_streamer.footer_range.end,
+                        split_streamer.len(),
                     );
 
                     report_splits.push(ReportSplit {
@@ -523,6 +525,7 @@ mod tests {
                 index_uid.index_id == "test-index"
                     && split_metadata.split_id() == "test-split"
                     && split_metadata.time_range == Some(1628203589..=1628203640)
+                    && split_metadata.size_in_bytes == split_metadata.footer_offsets.end
             })
             .times(1)
             .returning(|_| Ok(EmptyResponse {}));
@@ -563,10 +566,12 @@ mod tests {
                         split_id: "test-split".to_string(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        schema_version: 0,
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_names: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
@@ -675,10 +680,12 @@ mod tests {
                 ],
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                schema_version: 0,
             },
             serialized_split_fields: Vec::new(),
             split_scratch_directory: split_scratch_directory_1,
             tags: Default::default(),
+            field_names: Default::default(),
             split_files: Vec::new(),
             hotcache_bytes: Vec::new(),
         };
@@ -701,10 +708,12 @@ mod tests {
                 ],
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                schema_version: 0,
             },
             serialized_split_fields: Vec::new(),
             split_scratch_directory: split_scratch_directory_2,
             tags: Default::default(),
+            field_names: Default::default(),
             split_files: Vec::new(),
             hotcache_bytes: Vec::new(),
         };
@@ -819,10 +828,12 @@ mod tests {
                         replaced_split_ids: Vec::new(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        schema_version: 0,
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_names: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
@@ -998,10 +1009,12 @@ mod tests {
                         split_id: SPLIT_ULID_STR.to_string(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        schema_version: 0,
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_names: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
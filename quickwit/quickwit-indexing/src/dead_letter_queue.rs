@@ -0,0 +1,410 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! The dead letter queue stores the documents rejected by the doc processor so that users can
+//! inspect them and reingest them once the doc mapping has been fixed.
+//!
+//! Entries are appended in batches: each batch is written as a new NDJSON segment file named
+//! `<writer_id>/<ulid>-<num_entries>.ndjson`. Segments are never modified once written and the
+//! queue is rebuilt by listing the storage, so any number of writers can append to the same queue
+//! concurrently without coordinating. Segments are ordered by their ULID, i.e. by creation time.
+//!
+//! The storage must support [`Storage::list_objects`].
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use futures::TryStreamExt;
+use quickwit_storage::Storage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use ulid::{Generator, Ulid};
+
+const SEGMENT_FILE_EXTENSION: &str = ".ndjson";
+
+/// A document rejected by the doc processor.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterEntry {
+    /// The error returned while processing the document.
+    pub error: String,
+    /// The document as it was received. Documents that are not valid JSON are stored as strings.
+    #[schema(value_type = Object)]
+    pub document: JsonValue,
+    /// The moment the document was rejected, formatted as RFC 3339.
+    pub timestamp: String,
+}
+
+impl DeadLetterEntry {
+    pub fn new(error: String, raw_doc: &[u8]) -> Self {
+        let document = serde_json::from_slice(raw_doc)
+            .unwrap_or_else(|_| JsonValue::String(String::from_utf8_lossy(raw_doc).into_owned()));
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .expect("formatting the current time as RFC 3339 should not fail");
+        Self {
+            error,
+            document,
+            timestamp,
+        }
+    }
+
+    /// Returns the document as a line that can be fed back to the ingest API.
+    pub fn document_line(&self) -> String {
+        match &self.document {
+            JsonValue::String(raw_doc) => raw_doc.clone(),
+            document => document.to_string(),
+        }
+    }
+}
+
+/// A page of dead letter queue entries, oldest entries first.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterQueuePage {
+    pub entries: Vec<DeadLetterEntry>,
+    pub page: usize,
+    pub page_size: usize,
+    /// Total number of entries in the dead letter queue.
+    pub num_entries: usize,
+}
+
+#[derive(Debug, Clone)]
+struct DeadLetterQueueSegment {
+    path: PathBuf,
+    ulid: Ulid,
+    num_entries: usize,
+}
+
+impl DeadLetterQueueSegment {
+    /// Parses the path of a segment file. Returns `None` for files that are not segments.
+    fn parse(path: PathBuf) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        let file_stem = file_name.strip_suffix(SEGMENT_FILE_EXTENSION)?;
+        let (ulid_str, num_entries_str) = file_stem.rsplit_once('-')?;
+        let ulid = Ulid::from_string(ulid_str).ok()?;
+        let num_entries = num_entries_str.parse().ok()?;
+        Some(Self {
+            path,
+            ulid,
+            num_entries,
+        })
+    }
+}
+
+fn num_entries(segments: &[DeadLetterQueueSegment]) -> usize {
+    segments.iter().map(|segment| segment.num_entries).sum()
+}
+
+/// Dead letter queue backed by a storage.
+///
+/// Each writer appends segments under its own prefix, so the pipelines of several nodes can share
+/// the same queue.
+#[derive(Clone)]
+pub struct DeadLetterQueue {
+    storage: Arc<dyn Storage>,
+    writer_id: String,
+    // Keeps the segments of a writer ordered even when they are appended within the same
+    // millisecond.
+    ulid_generator: Arc<Mutex<Generator>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            writer_id: "default".to_string(),
+            ulid_generator: Arc::new(Mutex::new(Generator::new())),
+        }
+    }
+
+    /// Sets the prefix under which the segments appended by this queue are written. Writers
+    /// sharing a queue should use distinct IDs, for instance `<node_id>/<pipeline_uid>`.
+    pub fn with_writer_id(mut self, writer_id: impl Into<String>) -> Self {
+        self.writer_id = writer_id.into();
+        self
+    }
+
+    /// Appends a batch of entries to the queue as a new segment.
+    pub async fn append(&self, entries: &[DeadLetterEntry]) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut payload: Vec<u8> = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut payload, entry)?;
+            payload.push(b'\n');
+        }
+        let segment_ulid = self
+            .ulid_generator
+            .lock()
+            .expect("the lock should not be poisoned")
+            .generate()
+            .context("failed to generate dead letter queue segment ULID")?;
+        let segment_path = format!(
+            "{}/{segment_ulid}-{}{SEGMENT_FILE_EXTENSION}",
+            self.writer_id,
+            entries.len()
+        );
+        self.storage
+            .put(Path::new(&segment_path), Box::new(payload))
+            .await
+            .with_context(|| {
+                format!("failed to write dead letter queue segment `{segment_path}`")
+            })?;
+        Ok(())
+    }
+
+    /// Returns the total number of entries in the queue.
+    pub async fn num_entries(&self) -> anyhow::Result<usize> {
+        let segments = self.list_segments().await?;
+        Ok(num_entries(&segments))
+    }
+
+    /// Reads a page of entries. Pages are numbered from 1.
+    pub async fn read_page(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> anyhow::Result<DeadLetterQueuePage> {
+        anyhow::ensure!(page > 0, "page numbers start at 1");
+        anyhow::ensure!(page_size > 0, "page size must be strictly positive");
+
+        let segments = self.list_segments().await?;
+        let page_start = (page - 1).saturating_mul(page_size);
+        let page_end = page_start.saturating_add(page_size);
+
+        let mut entries = Vec::new();
+        let mut segment_start = 0;
+
+        for segment in &segments {
+            let segment_end = segment_start + segment.num_entries;
+
+            if segment_end > page_start && segment_start < page_end {
+                let skip = page_start.saturating_sub(segment_start);
+                let take = page_end.min(segment_end) - segment_start - skip;
+                let segment_entries = self.read_segment(&segment.path).await?;
+                entries.extend(segment_entries.into_iter().skip(skip).take(take));
+            }
+            if segment_end >= page_end {
+                break;
+            }
+            segment_start = segment_end;
+        }
+        Ok(DeadLetterQueuePage {
+            entries,
+            page,
+            page_size,
+            num_entries: num_entries(&segments),
+        })
+    }
+
+    /// Removes the oldest segments as long as they hold no more than `num_entries` entries in
+    /// total, and returns the number of entries removed. Segments are never split, so entries
+    /// appended after a read are never removed by mistake, unless a writer's clock lags behind.
+    pub async fn truncate(&self, num_entries: usize) -> anyhow::Result<usize> {
+        let segments = self.list_segments().await?;
+
+        let mut num_removed_entries = 0;
+        let mut num_removed_segments = 0;
+
+        for segment in &segments {
+            if num_removed_entries + segment.num_entries > num_entries {
+                break;
+            }
+            num_removed_entries += segment.num_entries;
+            num_removed_segments += 1;
+        }
+        if num_removed_segments == 0 {
+            return Ok(0);
+        }
+        let removed_segment_paths: Vec<&Path> = segments[..num_removed_segments]
+            .iter()
+            .map(|segment| segment.path.as_path())
+            .collect();
+        self.storage
+            .bulk_delete(&removed_segment_paths)
+            .await
+            .context("failed to delete dead letter queue segments")?;
+        Ok(num_removed_entries)
+    }
+
+    /// Lists the segments of the queue, oldest first.
+    async fn list_segments(&self) -> anyhow::Result<Vec<DeadLetterQueueSegment>> {
+        let mut segments: Vec<DeadLetterQueueSegment> = self
+            .storage
+            .list_objects(Path::new(""))
+            .await
+            .try_filter_map(|object_metadata| {
+                futures::future::ok(DeadLetterQueueSegment::parse(object_metadata.path))
+            })
+            .try_collect()
+            .await
+            .context("failed to list dead letter queue segments")?;
+        segments.sort_by(|left, right| (left.ulid, &left.path).cmp(&(right.ulid, &right.path)));
+        Ok(segments)
+    }
+
+    async fn read_segment(&self, path: &Path) -> anyhow::Result<Vec<DeadLetterEntry>> {
+        let segment_bytes = self.storage.get_all(path).await.with_context(|| {
+            format!(
+                "failed to read dead letter queue segment `{}`",
+                path.display()
+            )
+        })?;
+        segment_bytes
+            .as_slice()
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                serde_json::from_slice(line).with_context(|| {
+                    format!(
+                        "failed to parse dead letter queue segment `{}`",
+                        path.display()
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_storage::RamStorage;
+    use serde_json::json;
+
+    use super::*;
+
+    fn entries_for_test(start: usize, end: usize) -> Vec<DeadLetterEntry> {
+        (start..end)
+            .map(|doc_id| {
+                let raw_doc = format!(r#"{{"doc_id": {doc_id}}}"#);
+                DeadLetterEntry::new("invalid doc".to_string(), raw_doc.as_bytes())
+            })
+            .collect()
+    }
+
+    fn doc_ids(page: &DeadLetterQueuePage) -> Vec<u64> {
+        page.entries
+            .iter()
+            .map(|entry| entry.document["doc_id"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_dead_letter_entry() {
+        let entry = DeadLetterEntry::new("invalid doc".to_string(), br#"{"body": "foo"}"#);
+        assert_eq!(entry.error, "invalid doc");
+        assert_eq!(entry.document, json!({"body": "foo"}));
+        assert_eq!(entry.document_line(), r#"{"body":"foo"}"#);
+        assert!(entry.timestamp.ends_with('Z'));
+
+        let entry = DeadLetterEntry::new("invalid doc".to_string(), b"not json");
+        assert_eq!(entry.document, json!("not json"));
+        assert_eq!(entry.document_line(), "not json");
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_queue() {
+        let storage = Arc::new(RamStorage::default());
+        let dead_letter_queue = DeadLetterQueue::new(storage);
+
+        let page = dead_letter_queue.read_page(1, 10).await.unwrap();
+        assert!(page.entries.is_empty());
+        assert_eq!(page.num_entries, 0);
+
+        dead_letter_queue.append(&[]).await.unwrap();
+        dead_letter_queue
+            .append(&entries_for_test(0, 3))
+            .await
+            .unwrap();
+        dead_letter_queue
+            .append(&entries_for_test(3, 5))
+            .await
+            .unwrap();
+        dead_letter_queue
+            .append(&entries_for_test(5, 9))
+            .await
+            .unwrap();
+        assert_eq!(dead_letter_queue.num_entries().await.unwrap(), 9);
+
+        let page = dead_letter_queue.read_page(1, 4).await.unwrap();
+        assert_eq!(doc_ids(&page), [0, 1, 2, 3]);
+        assert_eq!(page.num_entries, 9);
+
+        let page = dead_letter_queue.read_page(2, 4).await.unwrap();
+        assert_eq!(doc_ids(&page), [4, 5, 6, 7]);
+
+        let page = dead_letter_queue.read_page(3, 4).await.unwrap();
+        assert_eq!(doc_ids(&page), [8]);
+
+        let page = dead_letter_queue.read_page(4, 4).await.unwrap();
+        assert!(page.entries.is_empty());
+
+        dead_letter_queue.read_page(0, 4).await.unwrap_err();
+
+        // Only whole segments are removed.
+        assert_eq!(dead_letter_queue.truncate(4).await.unwrap(), 3);
+        assert_eq!(dead_letter_queue.truncate(1).await.unwrap(), 0);
+
+        let page = dead_letter_queue.read_page(1, 10).await.unwrap();
+        assert_eq!(doc_ids(&page), [3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(dead_letter_queue.truncate(usize::MAX).await.unwrap(), 6);
+        assert_eq!(dead_letter_queue.num_entries().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_queue_concurrent_writers() {
+        let storage = Arc::new(RamStorage::default());
+        storage
+            .put(Path::new("not-a-segment.json"), Box::new(b"{}".to_vec()))
+            .await
+            .unwrap();
+
+        let append_futures = (0..10).map(|writer_ord| {
+            let dead_letter_queue = DeadLetterQueue::new(storage.clone())
+                .with_writer_id(format!("test-node/pipeline-{writer_ord}"));
+            async move {
+                let start = writer_ord * 3;
+                dead_letter_queue
+                    .append(&entries_for_test(start, start + 3))
+                    .await
+            }
+        });
+        for result in futures::future::join_all(append_futures).await {
+            result.unwrap();
+        }
+        let dead_letter_queue = DeadLetterQueue::new(storage.clone());
+        assert_eq!(dead_letter_queue.num_entries().await.unwrap(), 30);
+
+        let page = dead_letter_queue.read_page(1, 100).await.unwrap();
+        let mut doc_ids = doc_ids(&page);
+        doc_ids.sort_unstable();
+        assert_eq!(doc_ids, (0..30).collect::<Vec<u64>>());
+
+        assert_eq!(dead_letter_queue.truncate(usize::MAX).await.unwrap(), 30);
+        assert_eq!(dead_letter_queue.num_entries().await.unwrap(), 0);
+        assert!(storage
+            .exists(Path::new("not-a-segment.json"))
+            .await
+            .unwrap());
+    }
+}
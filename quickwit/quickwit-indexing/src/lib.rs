@@ -35,11 +35,14 @@ pub use crate::actors::{
     Sequencer, SplitsUpdateMailbox,
 };
 pub use crate::controlled_directory::ControlledDirectory;
+pub use crate::dead_letter_queue::{DeadLetterEntry, DeadLetterQueue, DeadLetterQueuePage};
+pub use crate::metrics::INDEXER_METRICS;
 use crate::models::IndexingStatistics;
 pub use crate::split_store::{get_tantivy_directory_from_split_bundle, IndexingSplitStore};
 
 pub mod actors;
 mod controlled_directory;
+mod dead_letter_queue;
 pub mod merge_policy;
 mod metrics;
 pub mod models;
@@ -68,7 +71,7 @@ pub fn new_split_id() -> String {
 pub async fn start_indexing_service(
     universe: &Universe,
     config: &NodeConfig,
-    num_blocking_threads: usize,
+    num_indexing_threads: usize,
     cluster: Cluster,
     metastore: MetastoreServiceClient,
     ingester_pool: IngesterPool,
@@ -85,7 +88,7 @@ pub async fn start_indexing_service(
         config.node_id.clone(),
         config.data_dir_path.to_path_buf(),
         config.indexer_config.clone(),
-        num_blocking_threads,
+        num_indexing_threads,
         cluster,
         metastore.clone(),
         ingest_api_service_mailbox,
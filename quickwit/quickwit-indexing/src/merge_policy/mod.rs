@@ -380,7 +380,7 @@ pub mod tests {
             source_id: "test_source".to_string(),
         };
         let split_attrs = merge_split_attrs(pipeline_id, merged_split_id, splits);
-        create_split_metadata(merge_policy, &split_attrs, tags, 0..0)
+        create_split_metadata(merge_policy, &split_attrs, tags, BTreeSet::new(), 0..0, 0)
     }
 
     fn apply_merge(
@@ -30,6 +30,7 @@ use tantivy::directory::MmapDirectory;
 use tantivy::IndexBuilder;
 use tracing::{instrument, Span};
 
+use crate::actors::BackpressureMemoryGuard;
 use crate::controlled_directory::ControlledDirectory;
 use crate::merge_policy::MergeTask;
 use crate::models::{PublishLock, SplitAttrs};
@@ -81,6 +82,7 @@ impl IndexedSplitBuilder {
     pub fn new_in_dir(
         pipeline_id: IndexingPipelineId,
         partition_id: u64,
+        schema_version: u32,
         last_delete_opstamp: u64,
         scratch_directory: TempDirectory,
         index_builder: IndexBuilder,
@@ -113,6 +115,7 @@ impl IndexedSplitBuilder {
                 time_range: None,
                 delete_opstamp: last_delete_opstamp,
                 num_merge_ops: 0,
+                schema_version,
             },
             index_writer,
             split_scratch_directory,
@@ -173,6 +176,7 @@ pub enum CommitTrigger {
     ForceCommit,
     MemoryLimit,
     NoMoreDocs,
+    NumBytesLimit,
     NumDocsLimit,
     Timeout,
 }
@@ -186,6 +190,7 @@ pub struct IndexedSplitBatchBuilder {
     pub commit_trigger: CommitTrigger,
     pub batch_parent_span: Span,
     pub memory_usage: GaugeGuard<'static>,
+    pub _backpressure_memory_guard_opt: Option<BackpressureMemoryGuard>,
     pub _split_builders_guard: GaugeGuard<'static>,
 }
 
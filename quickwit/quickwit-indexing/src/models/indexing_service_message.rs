@@ -48,3 +48,8 @@ pub struct DetachMergePipeline {
 pub struct ObservePipeline {
     pub pipeline_id: IndexingPipelineId,
 }
+
+/// Lists the indexing pipelines whose last spawn attempt failed, for instance because their source
+/// could not be reached. Those pipelines are not indexing until a retry succeeds.
+#[derive(Debug)]
+pub struct ListStalledPipelines;
@@ -19,7 +19,16 @@
 
 use quickwit_metastore::SplitMetadata;
 
+use crate::merge_policy::MergeOperation;
+
 #[derive(Clone, Debug)]
 pub struct NewSplits {
     pub new_splits: Vec<SplitMetadata>,
 }
+
+/// Asks the merge planner to schedule the given merge operations, bypassing its merge policy.
+/// The splits of the operations need not be immature nor belong to the pipeline.
+#[derive(Debug)]
+pub struct RunMergeOperations {
+    pub merge_operations: Vec<MergeOperation>,
+}
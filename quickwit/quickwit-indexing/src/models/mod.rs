@@ -38,10 +38,11 @@ pub use indexed_split::{
     IndexedSplitBuilder,
 };
 pub use indexing_service_message::{
-    DetachIndexingPipeline, DetachMergePipeline, ObservePipeline, SpawnPipeline,
+    DetachIndexingPipeline, DetachMergePipeline, ListStalledPipelines, ObservePipeline,
+    SpawnPipeline,
 };
 pub use indexing_statistics::IndexingStatistics;
-pub use merge_planner_message::NewSplits;
+pub use merge_planner_message::{NewSplits, RunMergeOperations};
 pub use merge_scratch::MergeScratch;
 pub use merge_statistics::MergeStatistics;
 pub use packaged_split::{PackagedSplit, PackagedSplitBatch};
@@ -35,6 +35,8 @@ pub struct PackagedSplit {
     pub split_attrs: SplitAttrs,
     pub split_scratch_directory: TempDirectory,
     pub tags: BTreeSet<String>,
+    /// Names of the fields, including the dynamic and JSON subfields, present in the split.
+    pub field_names: BTreeSet<String>,
     pub split_files: Vec<PathBuf>,
     pub hotcache_bytes: Vec<u8>,
 }
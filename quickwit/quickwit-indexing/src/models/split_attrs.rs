@@ -68,6 +68,9 @@ pub struct SplitAttrs {
 
     // Number of merge operation the split has been through so far.
     pub num_merge_ops: usize,
+
+    /// Schema version of the index the split is indexed with.
+    pub schema_version: u32,
 }
 
 impl fmt::Debug for SplitAttrs {
@@ -91,7 +94,9 @@ pub fn create_split_metadata(
     merge_policy: &Arc<dyn MergePolicy>,
     split_attrs: &SplitAttrs,
     tags: BTreeSet<String>,
+    field_names: BTreeSet<String>,
     footer_offsets: Range<u64>,
+    size_in_bytes: u64,
 ) -> SplitMetadata {
     let create_timestamp = OffsetDateTime::now_utc().unix_timestamp();
     let maturity =
@@ -111,8 +116,11 @@ pub fn create_split_metadata(
         create_timestamp,
         maturity,
         tags,
+        field_names,
         footer_offsets,
         delete_opstamp: split_attrs.delete_opstamp,
         num_merge_ops: split_attrs.num_merge_ops,
+        schema_version: split_attrs.schema_version,
+        size_in_bytes,
     }
 }
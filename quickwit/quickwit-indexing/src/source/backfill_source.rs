@@ -0,0 +1,686 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_compression::tokio::bufread::GzipDecoder;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::NaiveDate;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use quickwit_actors::{ActorExitStatus, Mailbox};
+use quickwit_common::uri::Uri;
+use quickwit_config::BackfillSourceParams;
+use quickwit_metastore::checkpoint::{PartitionId, SourceCheckpoint};
+use quickwit_proto::metastore::SourceType;
+use quickwit_proto::types::{Position, SourceId};
+use quickwit_storage::{OwnedBytes, Storage};
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
+use tracing::info;
+
+use super::file_source::BATCH_NUM_BYTES_LIMIT;
+use super::BatchBuilder;
+use crate::actors::DocProcessor;
+use crate::source::{Source, SourceContext, SourceRuntime, TypedSourceFactory};
+
+/// Maximum number of files downloaded concurrently, ahead of the file currently being read.
+const MAX_CONCURRENT_FILE_DOWNLOADS: usize = 4;
+
+#[derive(Default, Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BackfillSourceCounters {
+    /// Number of files matching the prefix and the date range.
+    pub num_files_total: u64,
+    /// Number of files fully ingested, including the ones ingested before a restart.
+    pub num_files_processed: u64,
+    pub num_lines_processed: u64,
+    pub num_bytes_processed: u64,
+}
+
+/// A source that ingests the historical files stored under an object storage prefix, one after
+/// the other. The source records the position reached in each file in the checkpoint, so an
+/// interrupted backfill resumes where it left off. It exits once all the files have been read.
+pub struct BackfillSource {
+    source_id: SourceId,
+    counters: BackfillSourceCounters,
+    file_stream: BoxStream<'static, anyhow::Result<BackfillFile>>,
+    current_file_opt: Option<BackfillFile>,
+}
+
+impl fmt::Debug for BackfillSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BackfillSource {{ source_id: {} }}", self.source_id)
+    }
+}
+
+#[async_trait]
+impl Source for BackfillSource {
+    async fn emit_batches(
+        &mut self,
+        doc_processor_mailbox: &Mailbox<DocProcessor>,
+        ctx: &SourceContext,
+    ) -> Result<Duration, ActorExitStatus> {
+        let mut batch_builder = BatchBuilder::new(SourceType::Backfill);
+        let mut reached_end = false;
+
+        while batch_builder.num_bytes < BATCH_NUM_BYTES_LIMIT {
+            if self.current_file_opt.is_none() {
+                // Guard the zone while the next file is being downloaded.
+                match ctx.protect_future(self.file_stream.next()).await {
+                    Some(file_res) => self.current_file_opt = Some(file_res?),
+                    None => {
+                        reached_end = true;
+                        break;
+                    }
+                }
+            }
+            let current_file = self
+                .current_file_opt
+                .as_mut()
+                .expect("current file should be set");
+            let limit_num_bytes = BATCH_NUM_BYTES_LIMIT - batch_builder.num_bytes;
+
+            while let Some(line) = current_file.next_line(limit_num_bytes) {
+                self.counters.num_lines_processed += 1;
+                self.counters.num_bytes_processed += line.len() as u64;
+                batch_builder.add_doc(Bytes::copy_from_slice(line));
+
+                if batch_builder.num_bytes >= BATCH_NUM_BYTES_LIMIT {
+                    break;
+                }
+            }
+            let (from_position, to_position) = current_file.consume_position_delta();
+
+            if from_position < to_position {
+                batch_builder
+                    .checkpoint_delta
+                    .record_partition_delta(
+                        current_file.partition_id.clone(),
+                        from_position,
+                        to_position,
+                    )
+                    .context("failed to record partition delta")?;
+            }
+            if current_file.is_exhausted() {
+                self.counters.num_files_processed += 1;
+                info!(
+                    partition_id=%current_file.partition_id,
+                    "reached end of file ({}/{} files processed)",
+                    self.counters.num_files_processed,
+                    self.counters.num_files_total
+                );
+                self.current_file_opt = None;
+            }
+        }
+        if !batch_builder.checkpoint_delta.is_empty() {
+            ctx.send_message(doc_processor_mailbox, batch_builder.build())
+                .await?;
+        }
+        if reached_end {
+            info!(
+                num_files = self.counters.num_files_processed,
+                "backfill completed"
+            );
+            ctx.send_exit_with_success(doc_processor_mailbox).await?;
+            return Err(ActorExitStatus::Success);
+        }
+        Ok(Duration::default())
+    }
+
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn observable_state(&self) -> serde_json::Value {
+        serde_json::to_value(&self.counters).unwrap()
+    }
+}
+
+pub struct BackfillSourceFactory;
+
+#[async_trait]
+impl TypedSourceFactory for BackfillSourceFactory {
+    type Source = BackfillSource;
+    type Params = BackfillSourceParams;
+
+    async fn typed_create_source(
+        source_runtime: SourceRuntime,
+        params: BackfillSourceParams,
+    ) -> anyhow::Result<BackfillSource> {
+        let checkpoint = source_runtime.fetch_checkpoint().await?;
+        let storage = source_runtime
+            .storage_resolver
+            .resolve(&params.source_uri)
+            .await?;
+        let file_paths = list_files_to_backfill(&*storage, &params).await?;
+
+        let mut counters = BackfillSourceCounters {
+            num_files_total: file_paths.len() as u64,
+            ..Default::default()
+        };
+        let mut pending_files = Vec::with_capacity(file_paths.len());
+
+        for file_path in file_paths {
+            let partition_id = file_partition_id(storage.uri(), &file_path)?;
+            let position = checkpoint_position(&checkpoint, &partition_id);
+
+            if position.is_eof() {
+                counters.num_files_processed += 1;
+                continue;
+            }
+            pending_files.push(PendingFile {
+                path: file_path,
+                partition_id,
+                position,
+            });
+        }
+        info!(
+            source_uri=%params.source_uri,
+            num_files_total=counters.num_files_total,
+            num_files_pending=pending_files.len(),
+            "starting backfill"
+        );
+        let file_stream = futures::stream::iter(pending_files)
+            .map(move |pending_file| download_file(storage.clone(), pending_file))
+            .buffered(MAX_CONCURRENT_FILE_DOWNLOADS)
+            .boxed();
+
+        let backfill_source = BackfillSource {
+            source_id: source_runtime.source_id().to_string(),
+            counters,
+            file_stream,
+            current_file_opt: None,
+        };
+        Ok(backfill_source)
+    }
+}
+
+/// Lists the files stored under the prefix and within the date range of the source, in
+/// lexicographical order, which is also the chronological order for `YYYY/MM/DD/` layouts.
+async fn list_files_to_backfill(
+    storage: &dyn Storage,
+    params: &BackfillSourceParams,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let objects: Vec<_> = storage
+        .list_objects(Path::new(""))
+        .await
+        .try_collect()
+        .await
+        .with_context(|| format!("failed to list files under `{}`", params.source_uri))?;
+
+    let mut file_paths: Vec<PathBuf> = objects
+        .into_iter()
+        .map(|object| object.path)
+        .filter(|path| match extract_date_from_path(path) {
+            Some(date) => params.contains_date(date),
+            None => !params.has_date_range(),
+        })
+        .collect();
+    file_paths.sort_unstable();
+    Ok(file_paths)
+}
+
+/// Extracts the date of the first `YYYY/MM/DD/` sequence of directories, starting from the
+/// file, found in a path. For instance, `2023/01/31` for `2023/01/31/12/part-0.json.gz`.
+fn extract_date_from_path(path: &Path) -> Option<NaiveDate> {
+    let dir_names: Vec<&str> = path
+        .parent()?
+        .iter()
+        .filter_map(|dir_name| dir_name.to_str())
+        .collect();
+    dir_names.windows(3).rev().find_map(|window| {
+        let [year, month, day] = window else {
+            return None;
+        };
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    })
+}
+
+fn file_partition_id(storage_uri: &Uri, file_path: &Path) -> anyhow::Result<PartitionId> {
+    let file_uri = storage_uri.join(file_path)?;
+    Ok(PartitionId::from(file_uri.as_str()))
+}
+
+fn checkpoint_position(checkpoint: &SourceCheckpoint, partition_id: &PartitionId) -> Position {
+    checkpoint
+        .position_for_partition(partition_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+struct PendingFile {
+    path: PathBuf,
+    partition_id: PartitionId,
+    position: Position,
+}
+
+async fn download_file(
+    storage: Arc<dyn Storage>,
+    pending_file: PendingFile,
+) -> anyhow::Result<BackfillFile> {
+    let payload = storage
+        .get_all(&pending_file.path)
+        .await
+        .with_context(|| format!("failed to download file `{}`", pending_file.partition_id))?;
+
+    // Gzip files cannot be seeked into, so we download and decompress them entirely, and skip
+    // the bytes that were already ingested.
+    let content = if pending_file.path.extension() == Some(OsStr::new("gz")) {
+        let mut decoder = GzipDecoder::new(payload.as_slice());
+        let mut decompressed_payload = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed_payload)
+            .await
+            .with_context(|| {
+                format!("failed to decompress file `{}`", pending_file.partition_id)
+            })?;
+        OwnedBytes::new(decompressed_payload)
+    } else {
+        payload
+    };
+    let offset = pending_file
+        .position
+        .as_usize()
+        .unwrap_or(0)
+        .min(content.len());
+
+    let backfill_file = BackfillFile {
+        partition_id: pending_file.partition_id,
+        content,
+        previous_position: pending_file.position,
+        offset,
+    };
+    Ok(backfill_file)
+}
+
+struct BackfillFile {
+    partition_id: PartitionId,
+    content: OwnedBytes,
+    /// Position up to which the file has been recorded in a checkpoint delta.
+    previous_position: Position,
+    /// Offset of the next line to read.
+    offset: usize,
+}
+
+impl BackfillFile {
+    /// Returns the next line, trailing newline included, unless the file is exhausted or
+    /// `limit_num_bytes` is zero.
+    fn next_line(&mut self, limit_num_bytes: u64) -> Option<&[u8]> {
+        if limit_num_bytes == 0 || self.is_exhausted() {
+            return None;
+        }
+        let remaining_content = &self.content.as_slice()[self.offset..];
+        let line_len = remaining_content
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map(|newline_pos| newline_pos + 1)
+            .unwrap_or(remaining_content.len());
+        self.offset += line_len;
+        Some(&remaining_content[..line_len])
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.offset >= self.content.len()
+    }
+
+    /// Returns the positions between which the file has been read since the last call.
+    fn consume_position_delta(&mut self) -> (Position, Position) {
+        let to_position = if self.is_exhausted() {
+            Position::eof(self.offset as u64)
+        } else {
+            Position::offset(self.offset as u64)
+        };
+        let from_position = std::mem::replace(&mut self.previous_position, to_position.clone());
+        (from_position, to_position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use async_compression::tokio::write::GzipEncoder;
+    use quickwit_actors::{Command, Universe};
+    use quickwit_config::{SourceConfig, SourceInputFormat, SourceParams};
+    use quickwit_metastore::checkpoint::SourceCheckpointDelta;
+    use quickwit_proto::types::IndexUid;
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::models::RawDocBatch;
+    use crate::source::tests::SourceRuntimeBuilder;
+    use crate::source::SourceActor;
+
+    fn backfill_source_config(params: BackfillSourceParams) -> SourceConfig {
+        SourceConfig {
+            source_id: "test-backfill-source".to_string(),
+            num_pipelines: NonZeroUsize::MIN,
+            enabled: true,
+            source_params: SourceParams::Backfill(params),
+            transform_config: None,
+            input_format: SourceInputFormat::Json,
+        }
+    }
+
+    async fn gzip_bytes(bytes: &[u8]) -> Vec<u8> {
+        let mut gzip_documents = Vec::new();
+        let mut encoder = GzipEncoder::new(&mut gzip_documents);
+        encoder.write_all(bytes).await.unwrap();
+        encoder.shutdown().await.unwrap();
+        gzip_documents
+    }
+
+    /// Writes the test files to the RAM storage resolved for `source_uri` by the runtime, which
+    /// stands in for a bucket filled by Firehose.
+    async fn put_test_files(source_runtime: &SourceRuntime, source_uri: &Uri) {
+        let storage = source_runtime
+            .storage_resolver
+            .resolve(source_uri)
+            .await
+            .unwrap();
+        let files: [(&str, Vec<u8>); 5] = [
+            ("2022/12/31/23/part-0.json", b"{\"day\": 0}\n".to_vec()),
+            (
+                "2023/01/01/00/part-0.json",
+                b"{\"day\": 1, \"line\": 0}\n{\"day\": 1, \"line\": 1}\n".to_vec(),
+            ),
+            (
+                "2023/01/02/00/part-0.json.gz",
+                gzip_bytes(b"{\"day\": 2, \"line\": 0}\n{\"day\": 2, \"line\": 1}").await,
+            ),
+            ("2023/01/03/00/part-0.json", Vec::new()),
+            ("2023/02/01/00/part-0.json", b"{\"day\": 32}\n".to_vec()),
+        ];
+        for (path, payload) in files {
+            storage
+                .put(Path::new(path), Box::new(payload))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_extract_date_from_path() {
+        assert_eq!(
+            extract_date_from_path(Path::new("2023/01/31/part-0.json")),
+            NaiveDate::from_ymd_opt(2023, 1, 31)
+        );
+        assert_eq!(
+            extract_date_from_path(Path::new("firehose/2023/01/31/12/part-0.json.gz")),
+            NaiveDate::from_ymd_opt(2023, 1, 31)
+        );
+        assert_eq!(extract_date_from_path(Path::new("2023/01/31")), None);
+        assert_eq!(
+            extract_date_from_path(Path::new("2023/02/31/part-0.json")),
+            None
+        );
+        assert_eq!(
+            extract_date_from_path(Path::new("2023/1/31/part-0.json")),
+            None
+        );
+        assert_eq!(extract_date_from_path(Path::new("part-0.json")), None);
+    }
+
+    #[test]
+    fn test_backfill_file_next_line() {
+        let mut backfill_file = BackfillFile {
+            partition_id: PartitionId::from("ram:///backfill/part-0.json"),
+            content: OwnedBytes::new(b"a\nbc\n\nd".to_vec()),
+            previous_position: Position::Beginning,
+            offset: 0,
+        };
+        assert_eq!(backfill_file.next_line(0), None);
+        assert_eq!(backfill_file.next_line(100), Some(&b"a\n"[..]));
+        assert_eq!(backfill_file.next_line(100), Some(&b"bc\n"[..]));
+        assert_eq!(
+            backfill_file.consume_position_delta(),
+            (Position::Beginning, Position::offset(5u64))
+        );
+        assert_eq!(backfill_file.next_line(100), Some(&b"\n"[..]));
+        assert_eq!(backfill_file.next_line(100), Some(&b"d"[..]));
+        assert_eq!(backfill_file.next_line(100), None);
+        assert!(backfill_file.is_exhausted());
+        assert_eq!(
+            backfill_file.consume_position_delta(),
+            (Position::offset(5u64), Position::eof(7u64))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backfill_source() {
+        let universe = Universe::with_accelerated_time();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_uri = Uri::for_test("ram:///firehose");
+        let params = BackfillSourceParams {
+            source_uri: source_uri.clone(),
+            start_date: NaiveDate::from_ymd_opt(2023, 1, 1),
+            end_date: NaiveDate::from_ymd_opt(2023, 1, 31),
+        };
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let source_config = backfill_source_config(params.clone());
+        let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
+        put_test_files(&source_runtime, &source_uri).await;
+
+        let backfill_source = BackfillSourceFactory::typed_create_source(source_runtime, params)
+            .await
+            .unwrap();
+        let backfill_source_actor = SourceActor {
+            source: Box::new(backfill_source),
+            doc_processor_mailbox,
+            indexing_backpressure_opt: None,
+        };
+        let (_backfill_source_mailbox, backfill_source_handle) =
+            universe.spawn_builder().spawn(backfill_source_actor);
+        let (actor_termination, counters) = backfill_source_handle.join().await;
+        assert!(actor_termination.is_success());
+        assert_eq!(
+            counters,
+            serde_json::json!({
+                "num_files_total": 3u64,
+                "num_files_processed": 3u64,
+                "num_lines_processed": 4u64,
+                "num_bytes_processed": 87u64,
+            })
+        );
+        let messages = doc_processor_inbox.drain_for_test();
+        assert_eq!(messages.len(), 2);
+
+        let batch = messages[0].downcast_ref::<RawDocBatch>().unwrap();
+        assert_eq!(batch.docs.len(), 4);
+        assert_eq!(batch.docs[0], r#"{"day": 1, "line": 0}"#.to_string() + "\n");
+        assert_eq!(batch.docs[3], r#"{"day": 2, "line": 1}"#);
+
+        let mut expected_checkpoint_delta = SourceCheckpointDelta::default();
+        for (partition_id, to_position) in [
+            (
+                "ram:///firehose/2023/01/01/00/part-0.json",
+                Position::eof(44u64),
+            ),
+            (
+                "ram:///firehose/2023/01/02/00/part-0.json.gz",
+                Position::eof(43u64),
+            ),
+            (
+                "ram:///firehose/2023/01/03/00/part-0.json",
+                Position::eof(0u64),
+            ),
+        ] {
+            expected_checkpoint_delta
+                .record_partition_delta(
+                    PartitionId::from(partition_id),
+                    Position::Beginning,
+                    to_position,
+                )
+                .unwrap();
+        }
+        assert_eq!(batch.checkpoint_delta, expected_checkpoint_delta);
+
+        assert!(matches!(
+            messages[1].downcast_ref::<Command>().unwrap(),
+            Command::ExitWithSuccess
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_source_resume_from_checkpoint() {
+        let universe = Universe::with_accelerated_time();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_uri = Uri::for_test("ram:///firehose");
+        let params = BackfillSourceParams {
+            source_uri: source_uri.clone(),
+            start_date: None,
+            end_date: None,
+        };
+        let mut source_checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+            PartitionId::from("ram:///firehose/2022/12/31/23/part-0.json"),
+            Position::Beginning,
+            Position::eof(11u64),
+        )
+        .unwrap();
+        source_checkpoint_delta
+            .record_partition_delta(
+                PartitionId::from("ram:///firehose/2023/01/01/00/part-0.json"),
+                Position::Beginning,
+                Position::offset(22u64),
+            )
+            .unwrap();
+
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let source_config = backfill_source_config(params.clone());
+        let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config)
+            .with_mock_metastore(Some(source_checkpoint_delta))
+            .build();
+        put_test_files(&source_runtime, &source_uri).await;
+
+        let backfill_source = BackfillSourceFactory::typed_create_source(source_runtime, params)
+            .await
+            .unwrap();
+        let backfill_source_actor = SourceActor {
+            source: Box::new(backfill_source),
+            doc_processor_mailbox,
+            indexing_backpressure_opt: None,
+        };
+        let (_backfill_source_mailbox, backfill_source_handle) =
+            universe.spawn_builder().spawn(backfill_source_actor);
+        let (actor_termination, counters) = backfill_source_handle.join().await;
+        assert!(actor_termination.is_success());
+        assert_eq!(
+            counters,
+            serde_json::json!({
+                "num_files_total": 5u64,
+                "num_files_processed": 5u64,
+                "num_lines_processed": 4u64,
+                "num_bytes_processed": 77u64,
+            })
+        );
+        let batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].docs.len(), 4);
+        assert_eq!(
+            batches[0].docs[0],
+            r#"{"day": 1, "line": 1}"#.to_string() + "\n"
+        );
+        assert_eq!(batches[0].docs[3], r#"{"day": 32}"#.to_string() + "\n");
+    }
+
+    #[tokio::test]
+    async fn test_backfill_source_several_batches() {
+        let universe = Universe::with_accelerated_time();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_uri = Uri::for_test("ram:///firehose");
+        let params = BackfillSourceParams {
+            source_uri: source_uri.clone(),
+            start_date: None,
+            end_date: None,
+        };
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let source_config = backfill_source_config(params.clone());
+        let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
+
+        let storage = source_runtime
+            .storage_resolver
+            .resolve(&source_uri)
+            .await
+            .unwrap();
+        let line = r#"{"body": "hello happy tax payer!"}"#.to_string() + "\n";
+        for file_idx in 0..2 {
+            let payload = line.repeat(10_000).into_bytes();
+            let path = format!("2023/01/0{}/part-0.json", file_idx + 1);
+            storage
+                .put(Path::new(&path), Box::new(payload))
+                .await
+                .unwrap();
+        }
+        let backfill_source = BackfillSourceFactory::typed_create_source(source_runtime, params)
+            .await
+            .unwrap();
+        let backfill_source_actor = SourceActor {
+            source: Box::new(backfill_source),
+            doc_processor_mailbox,
+            indexing_backpressure_opt: None,
+        };
+        let (_backfill_source_mailbox, backfill_source_handle) =
+            universe.spawn_builder().spawn(backfill_source_actor);
+        let (actor_termination, counters) = backfill_source_handle.join().await;
+        assert!(actor_termination.is_success());
+        assert_eq!(
+            counters,
+            serde_json::json!({
+                "num_files_total": 2u64,
+                "num_files_processed": 2u64,
+                "num_lines_processed": 20_000u64,
+                "num_bytes_processed": 700_000u64,
+            })
+        );
+        let batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(batches.len(), 2);
+
+        // The first batch ends in the middle of the second file.
+        let mut expected_checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+            PartitionId::from("ram:///firehose/2023/01/01/part-0.json"),
+            Position::Beginning,
+            Position::eof(350_000u64),
+        )
+        .unwrap();
+        expected_checkpoint_delta
+            .record_partition_delta(
+                PartitionId::from("ram:///firehose/2023/01/02/part-0.json"),
+                Position::Beginning,
+                Position::offset(150_010u64),
+            )
+            .unwrap();
+        assert_eq!(batches[0].checkpoint_delta, expected_checkpoint_delta);
+
+        let expected_checkpoint_delta = SourceCheckpointDelta::from_partition_delta(
+            PartitionId::from("ram:///firehose/2023/01/02/part-0.json"),
+            Position::offset(150_010u64),
+            Position::eof(350_000u64),
+        )
+        .unwrap();
+        assert_eq!(batches[1].checkpoint_delta, expected_checkpoint_delta);
+    }
+}
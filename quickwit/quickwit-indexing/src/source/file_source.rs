@@ -281,6 +281,7 @@ mod tests {
         let file_source_actor = SourceActor {
             source: Box::new(file_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_file_source_mailbox, file_source_handle) =
             universe.spawn_builder().spawn(file_source_actor);
@@ -355,6 +356,7 @@ mod tests {
         let file_source_actor = SourceActor {
             source: Box::new(file_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_file_source_mailbox, file_source_handle) =
             universe.spawn_builder().spawn(file_source_actor);
@@ -457,6 +459,7 @@ mod tests {
         let file_source_actor = SourceActor {
             source: Box::new(file_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_file_source_mailbox, file_source_handle) =
             universe.spawn_builder().spawn(file_source_actor);
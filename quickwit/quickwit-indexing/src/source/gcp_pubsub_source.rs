@@ -398,6 +398,7 @@ mod gcp_pubsub_emulator_tests {
         let source_actor = SourceActor {
             source,
             doc_processor_mailbox: doc_processor_mailbox.clone(),
+            indexing_backpressure_opt: None,
         };
         let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
         let (exit_status, exit_state) = source_handle.join().await;
@@ -1330,6 +1330,7 @@ mod kafka_broker_tests {
             let source_actor = SourceActor {
                 source,
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
             let (exit_status, exit_state) = source_handle.join().await;
@@ -1382,6 +1383,7 @@ mod kafka_broker_tests {
             let source_actor = SourceActor {
                 source,
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
             let (exit_status, exit_state) = source_handle.join().await;
@@ -1445,6 +1447,7 @@ mod kafka_broker_tests {
             let source_actor = SourceActor {
                 source,
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
             let (exit_status, exit_state) = source_handle.join().await;
@@ -1498,6 +1501,7 @@ mod kafka_broker_tests {
             let source_actor = SourceActor {
                 source,
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
             let (exit_status, exit_state) = source_handle.join().await;
@@ -415,6 +415,7 @@ mod tests {
             let actor = SourceActor {
                 source: Box::new(kinesis_source),
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_mailbox, handle) = universe.spawn_builder().spawn(actor);
             let (exit_status, exit_state) = handle.join().await;
@@ -468,6 +469,7 @@ mod tests {
             let actor = SourceActor {
                 source: Box::new(kinesis_source),
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_mailbox, handle) = universe.spawn_builder().spawn(actor);
             let (exit_status, exit_state) = handle.join().await;
@@ -538,6 +540,7 @@ mod tests {
             let actor = SourceActor {
                 source: Box::new(kinesis_source),
                 doc_processor_mailbox: doc_processor_mailbox.clone(),
+                indexing_backpressure_opt: None,
             };
             let (_mailbox, handle) = universe.spawn_builder().spawn(actor);
             let (exit_status, exit_state) = handle.join().await;
@@ -57,6 +57,7 @@
 //!   that file.
 //! - the kafka source: the partition id is a kafka topic partition id, and the position is a kafka
 //!   offset.
+mod backfill_source;
 mod file_source;
 #[cfg(feature = "gcp-pubsub")]
 mod gcp_pubsub_source;
@@ -77,6 +78,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use async_trait::async_trait;
+pub use backfill_source::{BackfillSource, BackfillSourceCounters, BackfillSourceFactory};
 use bytes::Bytes;
 use bytesize::ByteSize;
 pub use file_source::{FileSource, FileSourceFactory};
@@ -112,7 +114,7 @@ pub use vec_source::{VecSource, VecSourceFactory};
 pub use void_source::{VoidSource, VoidSourceFactory};
 
 use self::file_source::dir_and_filename;
-use crate::actors::DocProcessor;
+use crate::actors::{DocProcessor, IndexingPipelineBackpressure};
 use crate::models::RawDocBatch;
 use crate::source::ingest::IngestSourceFactory;
 use crate::source::ingest_api_source::IngestApiSourceFactory;
@@ -132,6 +134,11 @@ const BATCH_NUM_BYTES_LIMIT: u64 = ByteSize::mib(5).as_u64();
 
 const EMIT_BATCHES_TIMEOUT: Duration = Duration::from_millis(if cfg!(test) { 100 } else { 1_000 });
 
+/// Maximum amount of time a source waits for the indexing backpressure to be released before
+/// processing its other messages.
+const BACKPRESSURE_WAIT_TIMEOUT: Duration =
+    Duration::from_millis(if cfg!(test) { 100 } else { 1_000 });
+
 /// Runtime configuration used during execution of a source actor.
 #[derive(Clone)]
 pub struct SourceRuntime {
@@ -297,6 +304,7 @@ pub trait Source: Send + 'static {
 pub struct SourceActor {
     pub source: Box<dyn Source>,
     pub doc_processor_mailbox: Mailbox<DocProcessor>,
+    pub indexing_backpressure_opt: Option<IndexingPipelineBackpressure>,
 }
 
 #[derive(Debug)]
@@ -353,6 +361,20 @@ impl Handler<Loop> for SourceActor {
     type Reply = ();
 
     async fn handle(&mut self, _message: Loop, ctx: &SourceContext) -> Result<(), ActorExitStatus> {
+        if let Some(indexing_backpressure) = &self.indexing_backpressure_opt {
+            let wait_result = ctx
+                .protect_future(tokio::time::timeout(
+                    BACKPRESSURE_WAIT_TIMEOUT,
+                    indexing_backpressure.wait_until_resumed(),
+                ))
+                .await;
+            if wait_result.is_err() {
+                // The sources are still paused: we requeue the loop message to give the actor a
+                // chance to process its other messages.
+                ctx.send_self_message(Loop).await?;
+                return Ok(());
+            }
+        }
         let wait_for = self
             .source
             .emit_batches(&self.doc_processor_mailbox, ctx)
@@ -388,6 +410,7 @@ pub fn quickwit_supported_sources() -> &'static SourceLoader {
     static SOURCE_LOADER: OnceCell<SourceLoader> = OnceCell::new();
     SOURCE_LOADER.get_or_init(|| {
         let mut source_factory = SourceLoader::default();
+        source_factory.add_source(SourceType::Backfill, BackfillSourceFactory);
         source_factory.add_source(SourceType::File, FileSourceFactory);
         #[cfg(feature = "gcp-pubsub")]
         source_factory.add_source(SourceType::PubSub, GcpPubSubSourceFactory);
@@ -410,6 +433,11 @@ pub async fn check_source_connectivity(
     source_config: &SourceConfig,
 ) -> anyhow::Result<()> {
     match &source_config.source_params {
+        SourceParams::Backfill(params) => {
+            let storage = storage_resolver.resolve(&params.source_uri).await?;
+            storage.check_connectivity().await?;
+            Ok(())
+        }
         SourceParams::File(params) => {
             if let Some(filepath) = &params.filepath {
                 let (dir_uri, file_name) = dir_and_filename(filepath)?;
@@ -717,6 +717,7 @@ mod pulsar_broker_tests {
         let source_actor = SourceActor {
             source,
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_source_mailbox, source_handle) = universe.spawn_builder().spawn(source_actor);
 
@@ -137,12 +137,14 @@ mod tests {
     use std::num::NonZeroUsize;
 
     use bytes::Bytes;
+    use bytesize::ByteSize;
     use quickwit_actors::{Actor, Command, Universe};
     use quickwit_config::{SourceConfig, SourceInputFormat, SourceParams};
     use quickwit_proto::types::IndexUid;
     use serde_json::json;
 
     use super::*;
+    use crate::actors::IndexingPipelineBackpressure;
     use crate::models::RawDocBatch;
     use crate::source::tests::SourceRuntimeBuilder;
     use crate::source::SourceActor;
@@ -173,6 +175,7 @@ mod tests {
         let vec_source_actor = SourceActor {
             source: Box::new(vec_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         assert_eq!(
             vec_source_actor.name(),
@@ -197,6 +200,58 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_vec_source_honors_indexing_backpressure() -> anyhow::Result<()> {
+        let universe = Universe::new();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let docs = std::iter::repeat_with(|| Bytes::from_static(b"{}"))
+            .take(10)
+            .collect();
+        let params = VecSourceParams {
+            docs,
+            batch_num_docs: 3,
+            partition: "partition".to_string(),
+        };
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let source_config = SourceConfig {
+            source_id: "test-vec-source".to_string(),
+            num_pipelines: NonZeroUsize::new(1).unwrap(),
+            enabled: true,
+            source_params: SourceParams::Vec(params.clone()),
+            transform_config: None,
+            input_format: SourceInputFormat::Json,
+        };
+        let source_runtime = SourceRuntimeBuilder::new(index_uid, source_config).build();
+        let vec_source = VecSourceFactory::typed_create_source(source_runtime, params).await?;
+
+        let indexing_backpressure =
+            IndexingPipelineBackpressure::new(ByteSize::kb(1), ByteSize::b(500));
+        // Simulates a split held in memory by a slow storage write.
+        let mut memory_usage_guard = indexing_backpressure.memory_usage_guard();
+        memory_usage_guard.add(2_000);
+        assert!(indexing_backpressure.is_paused());
+
+        let vec_source_actor = SourceActor {
+            source: Box::new(vec_source),
+            doc_processor_mailbox,
+            indexing_backpressure_opt: Some(indexing_backpressure.clone()),
+        };
+        let (_vec_source_mailbox, vec_source_handle) =
+            universe.spawn_builder().spawn(vec_source_actor);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(doc_processor_inbox.drain_for_test().is_empty());
+
+        drop(memory_usage_guard);
+        assert!(!indexing_backpressure.is_paused());
+
+        let (actor_termination, last_observation) = vec_source_handle.join().await;
+        assert!(actor_termination.is_success());
+        assert_eq!(last_observation, json!({"next_item_idx": 10u64}));
+        let batches = doc_processor_inbox.drain_for_test();
+        assert_eq!(batches.len(), 5);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_vec_source_from_checkpoint() -> anyhow::Result<()> {
         let universe = Universe::with_accelerated_time();
@@ -224,6 +279,7 @@ mod tests {
         let vec_source_actor = SourceActor {
             source: Box::new(vec_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_vec_source_mailbox, vec_source_handle) =
             universe.spawn_builder().spawn(vec_source_actor);
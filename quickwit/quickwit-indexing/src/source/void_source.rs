@@ -117,6 +117,7 @@ mod tests {
         let void_source_actor = SourceActor {
             source: Box::new(void_source),
             doc_processor_mailbox,
+            indexing_backpressure_opt: None,
         };
         let (_, void_source_handle) = universe.spawn_builder().spawn(void_source_actor);
         matches!(void_source_handle.check_health(true), Health::Healthy);
@@ -99,7 +99,7 @@ impl IndexingSplitStore {
     }
 
     fn split_path(&self, split_id: &str) -> PathBuf {
-        PathBuf::from(quickwit_common::split_file(split_id))
+        quickwit_common::split_file_path(split_id)
     }
 
     /// Stores a split.
@@ -187,7 +187,7 @@ impl IndexingSplitStore {
         output_dir_path: &Path,
         io_controls: &IoControls,
     ) -> StorageResult<Box<dyn Directory>> {
-        let path = PathBuf::from(quickwit_common::split_file(split_id));
+        let path = quickwit_common::split_file_path(split_id);
         if let Some(split_path) = self
             .inner
             .local_split_store
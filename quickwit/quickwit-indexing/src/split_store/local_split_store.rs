@@ -58,7 +58,7 @@ use std::time::{Duration, SystemTime};
 
 use anyhow::Context;
 use bytesize::ByteSize;
-use quickwit_common::split_file;
+use quickwit_common::split_file_path;
 use quickwit_directories::BundleDirectory;
 use quickwit_storage::StorageResult;
 use tantivy::directory::MmapDirectory;
@@ -183,8 +183,7 @@ impl InnerLocalSplitStore {
 
     /// Returns the directory filepath of a split in cache.
     fn split_path(&self, split_id: Ulid) -> PathBuf {
-        let split_file = split_file(split_id);
-        self.split_store_folder.join(split_file)
+        self.split_store_folder.join(split_file_path(split_id))
     }
 
     /// Remove one split from the cache to make some room.
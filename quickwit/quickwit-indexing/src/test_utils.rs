@@ -112,7 +112,7 @@ impl TestSandbox {
             build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)?;
         let temp_dir = tempfile::tempdir()?;
         let indexer_config = IndexerConfig::for_test()?;
-        let num_blocking_threads = 1;
+        let num_indexing_threads = 1;
         let storage = storage_resolver.resolve(&index_uri).await?;
         let universe = Universe::with_accelerated_time();
         let merge_scheduler_mailbox = universe.get_or_spawn_one();
@@ -123,7 +123,7 @@ impl TestSandbox {
             node_id.clone(),
             temp_dir.path().to_path_buf(),
             indexer_config,
-            num_blocking_threads,
+            num_indexing_threads,
             cluster,
             metastore.clone(),
             Some(ingest_api_service),
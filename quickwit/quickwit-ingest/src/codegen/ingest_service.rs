@@ -248,11 +248,15 @@ impl IngestServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = ingest_service_grpc_client::IngestServiceGrpcClient::new(channel)
+        let client = ingest_service_grpc_client::IngestServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngestServiceGrpcClientAdapter::new(
@@ -264,11 +268,13 @@ impl IngestServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = ingest_service_grpc_client::IngestServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = ingest_service_grpc_client::IngestServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngestServiceGrpcClientAdapter::new(
@@ -621,21 +627,31 @@ impl IngestServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestServiceClient {
         self.build_from_boxed(
-            Box::new(IngestServiceClient::from_channel(addr, channel, max_message_size)),
+            Box::new(
+                IngestServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
+            ),
         )
     }
     pub fn build_from_balance_channel(
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestServiceClient {
         self.build_from_boxed(
             Box::new(
                 IngestServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
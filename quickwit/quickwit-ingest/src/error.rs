@@ -34,6 +34,8 @@ pub enum IngestServiceError {
     Corruption(String),
     #[error("index `{index_id}` already exists")]
     IndexAlreadyExists { index_id: IndexId },
+    #[error("index `{index_id}` is frozen")]
+    IndexFrozen { index_id: IndexId },
     #[error("index `{index_id}` not found")]
     IndexNotFound { index_id: IndexId },
     #[error("an internal error occurred: {0}")]
@@ -98,6 +100,7 @@ impl ServiceError for IngestServiceError {
         match self {
             Self::Corruption { .. } => ServiceErrorCode::Internal,
             Self::IndexAlreadyExists { .. } => ServiceErrorCode::AlreadyExists,
+            Self::IndexFrozen { .. } => ServiceErrorCode::Locked,
             Self::IndexNotFound { .. } => ServiceErrorCode::NotFound,
             Self::Internal(_) => ServiceErrorCode::Internal,
             Self::InvalidPosition(_) => ServiceErrorCode::BadRequest,
@@ -141,6 +144,7 @@ impl From<IngestServiceError> for tonic::Status {
         let code = match &error {
             IngestServiceError::Corruption { .. } => tonic::Code::DataLoss,
             IngestServiceError::IndexAlreadyExists { .. } => tonic::Code::AlreadyExists,
+            IngestServiceError::IndexFrozen { .. } => tonic::Code::FailedPrecondition,
             IngestServiceError::IndexNotFound { .. } => tonic::Code::NotFound,
             IngestServiceError::Internal(_) => tonic::Code::Internal,
             IngestServiceError::InvalidPosition(_) => tonic::Code::InvalidArgument,
@@ -104,7 +104,12 @@ const DEFAULT_BATCH_NUM_BYTES: usize = 1024 * 1024; // 1 MiB
 fn get_batch_num_bytes() -> usize {
     static BATCH_NUM_BYTES_CELL: OnceCell<usize> = OnceCell::new();
     *BATCH_NUM_BYTES_CELL.get_or_init(|| {
-        quickwit_common::get_from_env("QW_INGEST_BATCH_NUM_BYTES", DEFAULT_BATCH_NUM_BYTES)
+        quickwit_common::get_from_env_with_validation(
+            "QW_INGEST_BATCH_NUM_BYTES",
+            DEFAULT_BATCH_NUM_BYTES,
+            |batch_num_bytes| *batch_num_bytes > 0,
+            "batch size must be strictly positive",
+        )
     })
 }
 
@@ -1248,7 +1253,7 @@ mod tests {
     use bytes::Bytes;
     use quickwit_cluster::{create_cluster_for_test_with_id, ChannelTransport};
     use quickwit_common::shared_consts::INGESTER_PRIMARY_SHARDS_PREFIX;
-    use quickwit_common::tower::ConstantRate;
+    use quickwit_common::tower::{ConstantRate, GrpcClientInterceptor};
     use quickwit_config::service::QuickwitService;
     use quickwit_proto::control_plane::{AdviseResetShardsResponse, MockControlPlaneService};
     use quickwit_proto::ingest::ingester::{
@@ -2095,6 +2100,7 @@ mod tests {
             "127.0.0.1:7777".parse().unwrap(),
             follower_channel,
             MAX_GRPC_MESSAGE_SIZE,
+            GrpcClientInterceptor::default(),
         );
 
         leader_ctx
@@ -27,9 +27,8 @@ use futures_util::future;
 use itertools::Itertools;
 use quickwit_actors::ActorExitStatus;
 use quickwit_common::new_coolid;
-use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::runtimes::{RuntimesConfig, ShutdownCoordinator};
 use quickwit_common::test_utils::{wait_for_server_ready, wait_until_predicate};
-use quickwit_common::tower::BoxFutureInfaillible;
 use quickwit_common::uri::Uri as QuickwitUri;
 use quickwit_config::service::QuickwitService;
 use quickwit_config::NodeConfig;
@@ -44,11 +43,12 @@ use quickwit_serve::{serve_quickwit, ListSplitsQueryParams};
 use quickwit_storage::StorageResolver;
 use reqwest::Url;
 use tempfile::TempDir;
-use tokio::sync::watch::{self, Receiver, Sender};
 use tokio::task::JoinHandle;
 use tonic::transport::channel;
 use tracing::debug;
 
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
 /// Configuration of a node made of a [`NodeConfig`] and a
 /// set of services.
 #[derive(Clone)]
@@ -57,29 +57,6 @@ pub struct TestNodeConfig {
     pub services: HashSet<QuickwitService>,
 }
 
-struct ClusterShutdownTrigger {
-    sender: Sender<()>,
-    receiver: Receiver<()>,
-}
-
-impl ClusterShutdownTrigger {
-    fn new() -> Self {
-        let (sender, receiver) = watch::channel(());
-        Self { sender, receiver }
-    }
-
-    fn shutdown_signal(&self) -> BoxFutureInfaillible<()> {
-        let receiver = self.receiver.clone();
-        Box::pin(async move {
-            receiver.clone().changed().await.unwrap();
-        })
-    }
-
-    fn shutdown(self) {
-        self.sender.send(()).unwrap();
-    }
-}
-
 /// Creates a Cluster Test environment.
 ///
 /// The goal is to start several nodes and use the gRPC or REST clients to
@@ -95,7 +72,7 @@ pub struct ClusterSandbox {
     pub trace_client: TraceServiceClient<tonic::transport::Channel>,
     _temp_dir: TempDir,
     join_handles: Vec<JoinHandle<Result<HashMap<String, ActorExitStatus>, anyhow::Error>>>,
-    shutdown_trigger: ClusterShutdownTrigger,
+    shutdown_coordinator: ShutdownCoordinator,
 }
 
 fn transport_url(addr: SocketAddr) -> Url {
@@ -151,20 +128,20 @@ impl ClusterSandbox {
         let storage_resolver = StorageResolver::unconfigured();
         let metastore_resolver = MetastoreResolver::unconfigured();
         let mut join_handles = Vec::new();
-        let shutdown_trigger = ClusterShutdownTrigger::new();
+        let shutdown_coordinator = ShutdownCoordinator::new();
         for node_config in node_configs.iter() {
             join_handles.push(tokio::spawn({
                 let node_config = node_config.node_config.clone();
                 let metastore_resolver = metastore_resolver.clone();
                 let storage_resolver = storage_resolver.clone();
-                let shutdown_signal = shutdown_trigger.shutdown_signal();
+                let shutdown_coordinator = shutdown_coordinator.clone();
                 async move {
                     let result = serve_quickwit(
                         node_config,
                         runtimes_config,
                         metastore_resolver,
                         storage_resolver,
-                        shutdown_signal,
+                        shutdown_coordinator,
                         quickwit_serve::do_nothing_env_filter_reload_fn(),
                     )
                     .await?;
@@ -209,7 +186,7 @@ impl ClusterSandbox {
             trace_client: TraceServiceClient::new(channel),
             _temp_dir: temp_dir,
             join_handles,
-            shutdown_trigger,
+            shutdown_coordinator,
         })
     }
 
@@ -220,9 +197,17 @@ impl ClusterSandbox {
 
     // Starts one node that runs all the services.
     pub async fn start_standalone_node() -> anyhow::Result<Self> {
+        Self::start_standalone_node_with_config(|_| {}).await
+    }
+
+    // Starts one node that runs all the services, with a node config tweaked by `configure`.
+    pub async fn start_standalone_node_with_config(
+        configure: impl FnOnce(&mut NodeConfig),
+    ) -> anyhow::Result<Self> {
         let temp_dir = tempfile::tempdir()?;
         let services = QuickwitService::supported_services();
-        let node_configs = build_node_configs(temp_dir.path().to_path_buf(), &[services]);
+        let mut node_configs = build_node_configs(temp_dir.path().to_path_buf(), &[services]);
+        configure(&mut node_configs[0].node_config);
         let sandbox = Self::start_cluster_with_configs(temp_dir, node_configs).await?;
 
         let now = Instant::now();
@@ -383,7 +368,8 @@ impl ClusterSandbox {
     pub async fn shutdown(self) -> Result<Vec<HashMap<String, ActorExitStatus>>, anyhow::Error> {
         // We need to drop rest clients first because reqwest can hold connections open
         // preventing rest server's graceful shutdown.
-        self.shutdown_trigger.shutdown();
+        self.shutdown_coordinator
+            .initiate_shutdown(tokio::time::Instant::now() + SHUTDOWN_GRACE_PERIOD);
         let result = future::join_all(self.join_handles).await;
         let mut statuses = Vec::new();
         for node in result {
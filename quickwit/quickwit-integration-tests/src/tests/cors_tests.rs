@@ -0,0 +1,84 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use quickwit_config::CorsConfig;
+use reqwest::header::{ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN};
+use reqwest::{Method, StatusCode};
+
+use crate::test_utils::ClusterSandbox;
+
+#[tokio::test]
+async fn test_cors_allow_origin_header() {
+    quickwit_common::setup_logging_for_tests();
+    let sandbox = ClusterSandbox::start_standalone_node_with_config(|node_config| {
+        node_config.rest_config.cors = CorsConfig {
+            allowed_origins: vec![
+                "https://my-dashboard.com".to_string(),
+                "http://localhost:3000".to_string(),
+            ],
+            ..Default::default()
+        };
+    })
+    .await
+    .unwrap();
+    let rest_listen_addr = sandbox.node_configs[0].node_config.rest_config.listen_addr;
+    let version_url = format!("http://{rest_listen_addr}/api/v1/version");
+    let client = reqwest::Client::new();
+
+    for origin in ["https://my-dashboard.com", "http://localhost:3000"] {
+        let response = client
+            .get(&version_url)
+            .header(ORIGIN, origin)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            origin
+        );
+        let preflight_response = client
+            .request(Method::OPTIONS, &version_url)
+            .header(ORIGIN, origin)
+            .header("access-control-request-method", "POST")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(preflight_response.status(), StatusCode::OK);
+        assert_eq!(
+            preflight_response
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            origin
+        );
+    }
+    let response = client
+        .get(&version_url)
+        .header(ORIGIN, "https://evil.com")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+        .is_none());
+
+    sandbox.shutdown().await.unwrap();
+}
@@ -0,0 +1,76 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use tonic_reflection::pb::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::ServerReflectionRequest;
+
+use crate::test_utils::ClusterSandbox;
+
+#[tokio::test]
+async fn test_grpc_reflection_lists_services() {
+    quickwit_common::setup_logging_for_tests();
+    let sandbox = ClusterSandbox::start_standalone_node_with_config(|node_config| {
+        node_config.grpc_config.enable_reflection = true;
+    })
+    .await
+    .unwrap();
+    let grpc_listen_addr = sandbox.node_configs[0].node_config.grpc_listen_addr;
+    let mut reflection_client =
+        ServerReflectionClient::connect(format!("http://{grpc_listen_addr}"))
+            .await
+            .unwrap();
+    let list_services_request = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(MessageRequest::ListServices(String::new())),
+    };
+    let mut reflection_response_stream = reflection_client
+        .server_reflection_info(futures_util::stream::iter([list_services_request]))
+        .await
+        .unwrap()
+        .into_inner();
+    let reflection_response = reflection_response_stream.message().await.unwrap().unwrap();
+    let Some(MessageResponse::ListServicesResponse(list_services_response)) =
+        reflection_response.message_response
+    else {
+        panic!("expected a list services response");
+    };
+    let service_names: Vec<String> = list_services_response
+        .service
+        .into_iter()
+        .map(|service| service.name)
+        .collect();
+
+    for expected_service_name in [
+        "grpc.health.v1.Health",
+        "grpc.reflection.v1alpha.ServerReflection",
+        "quickwit.cluster.ClusterService",
+        "quickwit.metastore.MetastoreService",
+        "quickwit.search.SearchService",
+    ] {
+        assert!(
+            service_names
+                .iter()
+                .any(|name| name == expected_service_name),
+            "service `{expected_service_name}` is missing from {service_names:?}"
+        );
+    }
+    sandbox.shutdown().await.unwrap();
+}
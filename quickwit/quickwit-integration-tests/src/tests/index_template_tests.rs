@@ -0,0 +1,160 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use quickwit_config::ConfigFormat;
+use quickwit_metastore::SplitState;
+use quickwit_rest_client::rest_client::CommitType;
+use quickwit_serve::SearchRequestQueryString;
+use serde_json::json;
+
+use crate::ingest_json;
+use crate::test_utils::{ingest_with_retry, ClusterSandbox};
+
+const TEST_INDEX_TEMPLATE: &str = r#"
+    version: 0.8
+    template_id: test-logs-template
+    index_id_patterns:
+      - test-logs-*
+    description: Template for the test logs indexes.
+    doc_mapping:
+      field_mappings:
+        - name: timestamp
+          type: datetime
+          input_formats: [unix_timestamp]
+          fast: true
+        - name: message
+          type: text
+      timestamp_field: timestamp
+    indexing_settings:
+      commit_timeout_secs: 1
+    search_settings:
+      default_search_fields: [message]
+"#;
+
+#[tokio::test]
+async fn test_index_template_applied_on_index_auto_creation() {
+    quickwit_common::setup_logging_for_tests();
+    let mut sandbox = ClusterSandbox::start_standalone_node().await.unwrap();
+    sandbox.enable_ingest_v2();
+
+    let index_template = sandbox
+        .indexer_rest_client
+        .templates()
+        .create(TEST_INDEX_TEMPLATE, ConfigFormat::Yaml)
+        .await
+        .unwrap();
+    assert_eq!(index_template.template_id, "test-logs-template");
+
+    let index_templates = sandbox
+        .indexer_rest_client
+        .templates()
+        .list()
+        .await
+        .unwrap();
+    assert_eq!(index_templates, vec![index_template.clone()]);
+
+    // No index matches the template yet.
+    let matching_indexes = sandbox
+        .indexer_rest_client
+        .indexes()
+        .list_matching(&index_template.index_id_patterns)
+        .await
+        .unwrap();
+    assert!(matching_indexes.is_empty());
+
+    // Ingesting into a missing index matching the template creates the index.
+    ingest_with_retry(
+        &sandbox.indexer_rest_client,
+        "test-logs-foo",
+        ingest_json!({"timestamp": 1704067200, "message": "hello template"}),
+        CommitType::Auto,
+    )
+    .await
+    .unwrap();
+
+    let index_metadata = sandbox
+        .indexer_rest_client
+        .indexes()
+        .get("test-logs-foo")
+        .await
+        .unwrap();
+    let index_config = index_metadata.index_config;
+    assert_eq!(
+        index_config.doc_mapping.timestamp_field.as_deref(),
+        Some("timestamp")
+    );
+    assert_eq!(index_config.indexing_settings.commit_timeout_secs, 1);
+    assert_eq!(
+        index_config.search_settings.default_search_fields,
+        vec!["message".to_string()]
+    );
+
+    let matching_indexes = sandbox
+        .indexer_rest_client
+        .indexes()
+        .list_matching(&index_template.index_id_patterns)
+        .await
+        .unwrap();
+    assert_eq!(matching_indexes.len(), 1);
+
+    // The default search fields of the template apply to the created index.
+    sandbox
+        .indexer_rest_client
+        .sources("test-logs-foo")
+        .toggle("_ingest-source", true)
+        .await
+        .unwrap();
+    sandbox
+        .wait_for_splits("test-logs-foo", Some(vec![SplitState::Published]), 1)
+        .await
+        .unwrap();
+    let search_response = sandbox
+        .indexer_rest_client
+        .search(
+            "test-logs-foo",
+            SearchRequestQueryString {
+                query: "template".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(search_response.num_hits, 1);
+
+    // Deleting the template does not affect the indexes created from it.
+    sandbox
+        .indexer_rest_client
+        .templates()
+        .delete("test-logs-template")
+        .await
+        .unwrap();
+    sandbox
+        .indexer_rest_client
+        .templates()
+        .get("test-logs-template")
+        .await
+        .unwrap_err();
+    sandbox
+        .indexer_rest_client
+        .indexes()
+        .get("test-logs-foo")
+        .await
+        .unwrap();
+
+    sandbox.shutdown().await.unwrap();
+}
@@ -126,6 +126,7 @@ async fn test_update_on_multi_nodes_cluster() {
                     default_search_fields: vec!["title".to_string(), "body".to_string()],
                 },
                 retention_policy_opt: None,
+                indexing_settings_opt: None,
             },
         )
         .await
@@ -18,5 +18,8 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 mod basic_tests;
+mod cors_tests;
+mod grpc_reflection_tests;
+mod index_template_tests;
 mod index_tests;
 mod index_update_tests;
@@ -322,6 +322,7 @@ impl DeleteTaskPlanner {
                 delete_query.index_uid().clone(),
                 IndexMetasForLeafSearch {
                     doc_mapper_str: doc_mapper_str.to_string(),
+                    previous_doc_mapper_strs: HashMap::new(),
                     index_uri,
                 },
             );
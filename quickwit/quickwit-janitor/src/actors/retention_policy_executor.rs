@@ -257,6 +257,7 @@ fn compute_deleted_indexes<'a>(
 mod tests {
     use std::ops::RangeInclusive;
 
+    use bytesize::ByteSize;
     use mockall::Sequence;
     use quickwit_actors::Universe;
     use quickwit_common::ServiceStream;
@@ -313,6 +314,7 @@ mod tests {
             index.retention_policy_opt = Some(RetentionPolicy {
                 retention_period: retention_period.to_string(),
                 evaluation_schedule: EVALUATION_SCHEDULE.to_string(),
+                max_size: None,
             })
         }
         index
@@ -346,6 +348,7 @@ mod tests {
         let scheduler = RetentionPolicy {
             retention_period: "".to_string(),
             evaluation_schedule: EVALUATION_SCHEDULE.to_string(),
+            max_size: None,
         };
 
         scheduler.duration_until_next_evaluation().unwrap() + Duration::from_secs(1)
@@ -504,4 +507,63 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_retention_policy_execution_enforces_max_size() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .times(..)
+            .returning(|_list_indexes_request| {
+                let mut index_config = make_index("index-1", Some("1 hour"));
+                index_config.retention_policy_opt.as_mut().unwrap().max_size = Some(ByteSize(50));
+                let index_metadata = IndexMetadata::new(index_config);
+                Ok(ListIndexesMetadataResponse::for_test(vec![index_metadata]))
+            });
+
+        mock_metastore
+            .expect_list_splits()
+            .times(2)
+            .returning(|list_splits_request| {
+                let query = list_splits_request.deserialize_list_splits_query().unwrap();
+                assert_eq!(query.split_states, &[SplitState::Published]);
+
+                let splits = if query.time_range.is_unbounded() {
+                    // Each split weighs 20 bytes.
+                    vec![
+                        make_split("split-1", Some(1000..=5000)),
+                        make_split("split-2", Some(2000..=6000)),
+                        make_split("split-3", Some(3000..=7000)),
+                        make_split("split-4", None),
+                    ]
+                } else {
+                    vec![make_split("split-1", Some(1000..=5000))]
+                };
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+
+        mock_metastore
+            .expect_mark_splits_for_deletion()
+            .times(1)
+            .returning(|mark_splits_for_deletion_request| {
+                assert_eq!(
+                    mark_splits_for_deletion_request.split_ids,
+                    ["split-1", "split-4"]
+                );
+                Ok(EmptyResponse {})
+            });
+
+        let retention_policy_executor =
+            RetentionPolicyExecutor::new(MetastoreServiceClient::from_mock(mock_metastore));
+        let universe = Universe::with_accelerated_time();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(retention_policy_executor);
+        handle.process_pending_and_observe().await;
+
+        universe.sleep(shift_time_by()).await;
+        let counters = handle.process_pending_and_observe().await.state;
+        assert_eq!(counters.num_execution_passes, 1);
+        assert_eq!(counters.num_expired_splits, 2);
+        universe.assert_quit().await;
+    }
 }
@@ -17,6 +17,9 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
 use quickwit_actors::ActorContext;
 use quickwit_common::pretty::PrettySample;
 use quickwit_config::RetentionPolicy;
@@ -37,6 +40,9 @@ use crate::actors::RetentionPolicyExecutor;
 /// only mark them as `MarkedForDeletion`. Actual split deletion
 /// is taken care of by the garbage collector.
 ///
+/// Splits expire when their documents are older than the retention period or, if the policy sets
+/// a max size, when the splits holding newer documents already fill it.
+///
 /// * `index_id` - The target index id.
 /// * `metastore` - The metastore managing the target index.
 /// * `retention_policy` - The retention policy to used to evaluate the splits.
@@ -56,7 +62,7 @@ pub async fn run_execute_retention_policy(
         .with_time_range_end_lte(max_retention_timestamp);
 
     let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
-    let (expired_splits, ignored_splits): (Vec<SplitMetadata>, Vec<SplitMetadata>) = ctx
+    let (mut expired_splits, ignored_splits): (Vec<SplitMetadata>, Vec<SplitMetadata>) = ctx
         .protect_future(metastore.list_splits(list_splits_request))
         .await?
         .collect_splits_metadata()
@@ -76,6 +82,17 @@ pub async fn run_execute_retention_policy(
             ignored_split_ids.len()
         );
     }
+    if let Some(max_size) = retention_policy.max_size {
+        let oversized_splits = ctx
+            .protect_future(list_oversized_splits(
+                &index_uid,
+                &mut metastore,
+                max_size.as_u64(),
+                &expired_splits,
+            ))
+            .await?;
+        expired_splits.extend(oversized_splits);
+    }
     if expired_splits.is_empty() {
         return Ok(expired_splits);
     }
@@ -96,3 +113,48 @@ pub async fn run_execute_retention_policy(
         .await?;
     Ok(expired_splits)
 }
+
+/// Returns the published splits that must be dropped, oldest documents first, for the index to
+/// fit within `max_size_bytes` once the `expired_splits` are gone.
+async fn list_oversized_splits(
+    index_uid: &IndexUid,
+    metastore: &mut MetastoreServiceClient,
+    max_size_bytes: u64,
+    expired_splits: &[SplitMetadata],
+) -> anyhow::Result<Vec<SplitMetadata>> {
+    let expired_split_ids: HashSet<&str> = expired_splits
+        .iter()
+        .map(|split_metadata| split_metadata.split_id())
+        .collect();
+    let query =
+        ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let mut retained_splits: Vec<SplitMetadata> = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?
+        .into_iter()
+        .filter(|split_metadata| !expired_split_ids.contains(split_metadata.split_id()))
+        .collect();
+    // The splits without a time range are ordered by creation date.
+    retained_splits.sort_by_key(|split_metadata| {
+        let newest_timestamp = split_metadata
+            .time_range
+            .as_ref()
+            .map(|time_range| *time_range.end())
+            .unwrap_or(split_metadata.create_timestamp);
+        Reverse((newest_timestamp, split_metadata.create_timestamp))
+    });
+    let mut num_retained_bytes = 0;
+
+    let Some(num_kept_splits) = retained_splits.iter().position(|split_metadata| {
+        // The footer is located at the end of the split file.
+        num_retained_bytes += split_metadata.footer_offsets.end;
+        num_retained_bytes > max_size_bytes
+    }) else {
+        return Ok(Vec::new());
+    };
+    let oversized_splits = retained_splits.split_off(num_kept_splits);
+    Ok(oversized_splits)
+}
@@ -28,6 +28,7 @@ use quickwit_actors::{ActorHandle, Mailbox, Universe};
 use quickwit_cluster::{Cluster, ClusterMember};
 use quickwit_common::pubsub::EventBroker;
 use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_common::uri::Uri;
 use quickwit_config::merge_policy_config::MergePolicyConfig;
 use quickwit_config::service::QuickwitService;
@@ -86,6 +87,7 @@ pub(super) async fn create_empty_cluster(
         Vec::new(),
         config.gossip_interval,
         FailureDetectorConfig::default(),
+        GrpcClientInterceptor::new(&config.cluster_key),
         &ChannelTransport::default(),
     )
     .await?;
@@ -226,7 +228,7 @@ pub(super) async fn spawn_services(
         node_config.node_id.clone(),
         node_config.data_dir_path.clone(),
         node_config.indexer_config.clone(),
-        runtime_config.num_threads_blocking,
+        runtime_config.num_threads_indexing,
         cluster,
         metastore.clone(),
         None,
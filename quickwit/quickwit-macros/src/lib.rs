@@ -25,10 +25,24 @@ use quote::quote;
 use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::{
-    parenthesized, Attribute, Error, Field, Fields, FieldsNamed, Ident, ItemStruct, Meta, Path,
-    Token, Visibility,
+    parenthesized, Attribute, DeriveInput, Error, Field, Fields, FieldsNamed, Ident, ItemStruct,
+    Meta, Path, Token, Visibility,
 };
 
+/// Implements `quickwit_doc_mapper::QuickwitDocument` for a type that implements
+/// `serde::Serialize`.
+#[proc_macro_derive(QuickwitDocument)]
+pub fn derive_quickwit_document(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics ::quickwit_doc_mapper::QuickwitDocument for #name #ty_generics #where_clause {}
+    )
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn serde_multikey(attr: TokenStream, item: TokenStream) -> TokenStream {
     match serde_multikey_inner(attr, item) {
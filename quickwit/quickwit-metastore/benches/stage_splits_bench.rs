@@ -0,0 +1,160 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use quickwit_config::IndexConfig;
+use quickwit_metastore::{
+    CreateIndexRequestExt, FileBackedMetastore, IndexMetadataResponseExt, SplitMetadata,
+    StageSplitsRequestExt,
+};
+use quickwit_proto::metastore::{
+    CreateIndexRequest, MetastoreService, MetastoreServiceClient, PublishSplitsRequest,
+    StageSplitsRequest,
+};
+use quickwit_proto::types::IndexUid;
+use quickwit_storage::RamStorage;
+
+/// Staging splits one at a time rewrites the whole index metadata file on every call, so it is
+/// only benchmarked for the smaller batch size.
+const MAX_NUM_SPLITS_ONE_BY_ONE: usize = 1_000;
+
+async fn create_metastore_and_index() -> (MetastoreServiceClient, IndexUid) {
+    let storage = Arc::new(RamStorage::default());
+    let metastore = FileBackedMetastore::try_new(storage, None).await.unwrap();
+    let mut metastore = MetastoreServiceClient::new(metastore);
+
+    let index_config = IndexConfig::for_test("stage-splits-bench", "ram:///stage-splits-bench");
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+    (metastore, index_uid)
+}
+
+fn make_splits_metadata(index_uid: &IndexUid, num_splits: usize) -> Vec<SplitMetadata> {
+    (0..num_splits)
+        .map(|split_ord| SplitMetadata {
+            split_id: format!("split-{split_ord:05}"),
+            index_uid: index_uid.clone(),
+            num_docs: 1_000,
+            ..Default::default()
+        })
+        .collect()
+}
+
+async fn stage_and_publish_in_bulk(num_iters: u64, num_splits: usize) -> Duration {
+    let mut elapsed = Duration::ZERO;
+
+    for _ in 0..num_iters {
+        let (mut metastore, index_uid) = create_metastore_and_index().await;
+        let splits_metadata = make_splits_metadata(&index_uid, num_splits);
+        let split_ids: Vec<String> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+
+        let start = Instant::now();
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: Some(index_uid),
+            staged_split_ids: split_ids,
+            ..Default::default()
+        };
+        metastore
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+        elapsed += start.elapsed();
+    }
+    elapsed
+}
+
+async fn stage_and_publish_one_by_one(num_iters: u64, num_splits: usize) -> Duration {
+    let mut elapsed = Duration::ZERO;
+
+    for _ in 0..num_iters {
+        let (mut metastore, index_uid) = create_metastore_and_index().await;
+        let splits_metadata = make_splits_metadata(&index_uid, num_splits);
+
+        let start = Instant::now();
+        for split_metadata in splits_metadata {
+            let split_id = split_metadata.split_id.clone();
+            let stage_splits_request =
+                StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata)
+                    .unwrap();
+            metastore.stage_splits(stage_splits_request).await.unwrap();
+
+            let publish_splits_request = PublishSplitsRequest {
+                index_uid: Some(index_uid.clone()),
+                staged_split_ids: vec![split_id],
+                ..Default::default()
+            };
+            metastore
+                .publish_splits(publish_splits_request)
+                .await
+                .unwrap();
+        }
+        elapsed += start.elapsed();
+    }
+    elapsed
+}
+
+fn stage_splits_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("file-backed-stage-and-publish-splits");
+    group.sample_size(10);
+
+    for num_splits in [1_000, 10_000] {
+        group.throughput(Throughput::Elements(num_splits as u64));
+        group.bench_with_input(
+            BenchmarkId::new("bulk", num_splits),
+            &num_splits,
+            |b, &num_splits| {
+                b.to_async(&runtime)
+                    .iter_custom(|num_iters| stage_and_publish_in_bulk(num_iters, num_splits))
+            },
+        );
+        if num_splits <= MAX_NUM_SPLITS_ONE_BY_ONE {
+            group.bench_with_input(
+                BenchmarkId::new("one-by-one", num_splits),
+                &num_splits,
+                |b, &num_splits| {
+                    b.to_async(&runtime).iter_custom(|num_iters| {
+                        stage_and_publish_one_by_one(num_iters, num_splits)
+                    })
+                },
+            );
+        }
+    }
+}
+
+criterion_group!(benches, stage_splits_benchmark);
+criterion_main!(benches);
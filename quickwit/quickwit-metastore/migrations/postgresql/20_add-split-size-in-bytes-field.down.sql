@@ -0,0 +1,2 @@
+ALTER TABLE splits
+    DROP COLUMN IF EXISTS size_in_bytes;
@@ -0,0 +1,2 @@
+ALTER TABLE splits
+    ADD COLUMN IF NOT EXISTS size_in_bytes BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS index_aliases (
+    alias_id VARCHAR(255) NOT NULL,
+    index_ids VARCHAR(255)[] NOT NULL,
+    PRIMARY KEY (alias_id)
+);
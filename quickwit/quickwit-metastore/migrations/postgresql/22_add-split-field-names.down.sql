@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS splits_field_names_idx;
+ALTER TABLE splits
+    DROP COLUMN IF EXISTS field_names;
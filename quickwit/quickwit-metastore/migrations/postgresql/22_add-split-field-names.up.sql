@@ -0,0 +1,3 @@
+ALTER TABLE splits
+    ADD COLUMN IF NOT EXISTS field_names TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS splits_field_names_idx ON splits USING GIN (field_names);
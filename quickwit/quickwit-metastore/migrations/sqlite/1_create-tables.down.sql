@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS index_templates;
+DROP TABLE IF EXISTS shards;
+DROP TABLE IF EXISTS delete_tasks;
+DROP TABLE IF EXISTS splits;
+DROP TABLE IF EXISTS indexes;
@@ -0,0 +1,66 @@
+-- This schema mirrors the one of the PostgreSQL metastore, adapted to the SQLite type system:
+-- - timestamps are stored as Unix timestamps in seconds;
+-- - arrays (split tags, index template patterns) are stored as JSON arrays;
+-- - the shard state enum is enforced with a `CHECK` constraint.
+
+CREATE TABLE IF NOT EXISTS indexes (
+    index_uid VARCHAR(282) PRIMARY KEY,
+    index_id VARCHAR(255) NOT NULL UNIQUE,
+    index_metadata_json TEXT NOT NULL,
+    create_timestamp INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+CREATE TABLE IF NOT EXISTS splits (
+    split_id VARCHAR(50) PRIMARY KEY,
+    split_state VARCHAR(30) NOT NULL,
+    time_range_start BIGINT,
+    time_range_end BIGINT,
+    tags TEXT NOT NULL DEFAULT '[]',
+    split_metadata_json TEXT NOT NULL,
+    index_uid VARCHAR(282) NOT NULL,
+    node_id VARCHAR(253) NOT NULL,
+    delete_opstamp BIGINT NOT NULL DEFAULT 0 CHECK (delete_opstamp >= 0),
+    create_timestamp INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    update_timestamp INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    publish_timestamp INTEGER DEFAULT NULL,
+    maturity_timestamp INTEGER NOT NULL DEFAULT 0,
+
+    FOREIGN KEY (index_uid) REFERENCES indexes (index_uid) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS splits_index_uid_idx ON splits (index_uid);
+CREATE INDEX IF NOT EXISTS splits_time_range_start_idx ON splits (time_range_start);
+CREATE INDEX IF NOT EXISTS splits_time_range_end_idx ON splits (time_range_end);
+CREATE INDEX IF NOT EXISTS splits_node_id_idx ON splits (node_id);
+
+CREATE TABLE IF NOT EXISTS delete_tasks (
+    opstamp INTEGER PRIMARY KEY AUTOINCREMENT,
+    create_timestamp INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    index_uid VARCHAR(282) NOT NULL,
+    delete_query_json TEXT NOT NULL,
+
+    FOREIGN KEY (index_uid) REFERENCES indexes (index_uid) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shards (
+    index_uid VARCHAR(282) NOT NULL,
+    source_id VARCHAR(255) NOT NULL,
+    shard_id VARCHAR(255) NOT NULL,
+    leader_id VARCHAR(255) NOT NULL,
+    follower_id VARCHAR(255),
+    shard_state VARCHAR(30) NOT NULL DEFAULT 'open'
+        CHECK (shard_state IN ('unspecified', 'open', 'unavailable', 'closed')),
+    publish_position_inclusive VARCHAR(255) NOT NULL DEFAULT '',
+    publish_token VARCHAR(255),
+
+    PRIMARY KEY (index_uid, source_id, shard_id),
+    FOREIGN KEY (index_uid) REFERENCES indexes (index_uid) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS index_templates (
+    template_id VARCHAR(255) PRIMARY KEY,
+    positive_index_id_patterns TEXT NOT NULL,
+    negative_index_id_patterns TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    index_template_json TEXT NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE splits DROP COLUMN size_in_bytes;
@@ -0,0 +1 @@
+ALTER TABLE splits ADD COLUMN size_in_bytes INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS index_aliases (
+    alias_id VARCHAR(255) PRIMARY KEY,
+    index_ids TEXT NOT NULL
+);
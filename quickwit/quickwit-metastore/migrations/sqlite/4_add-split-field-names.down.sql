@@ -0,0 +1 @@
+ALTER TABLE splits DROP COLUMN field_names;
@@ -0,0 +1 @@
+ALTER TABLE splits ADD COLUMN field_names TEXT NOT NULL DEFAULT '[]';
@@ -139,6 +139,21 @@ impl IndexCheckpoint {
         self.per_source.remove(source_id).is_some()
     }
 
+    /// Overwrites the checkpoint of the source identified by `source_id`. Returns whether a
+    /// mutation occurred.
+    pub(crate) fn set_source_checkpoint(
+        &mut self,
+        source_id: &str,
+        source_checkpoint: SourceCheckpoint,
+    ) -> bool {
+        if self.per_source.get(source_id) == Some(&source_checkpoint) {
+            return false;
+        }
+        self.per_source
+            .insert(source_id.to_string(), source_checkpoint);
+        true
+    }
+
     /// Returns the checkpoint associated with a given source.
     ///
     /// All registered source have an associated checkpoint (that is possibly empty).
@@ -238,6 +253,27 @@ impl<'de> Deserialize<'de> for SourceCheckpoint {
     }
 }
 
+impl From<SourceCheckpoint> for quickwit_proto::metastore::SourceCheckpoint {
+    fn from(source_checkpoint: SourceCheckpoint) -> Self {
+        let per_partition = source_checkpoint
+            .per_partition
+            .into_iter()
+            .map(|(partition_id, position)| (partition_id.as_str().to_string(), position))
+            .collect();
+        Self { per_partition }
+    }
+}
+
+impl From<quickwit_proto::metastore::SourceCheckpoint> for SourceCheckpoint {
+    fn from(source_checkpoint_proto: quickwit_proto::metastore::SourceCheckpoint) -> Self {
+        source_checkpoint_proto
+            .per_partition
+            .into_iter()
+            .map(|(partition_id, position)| (PartitionId::from(partition_id), position))
+            .collect()
+    }
+}
+
 /// Error returned when trying to apply a checkpoint delta to a checkpoint that is not
 /// compatible. ie: the checkpoint delta starts from a point anterior to
 /// the checkpoint.
@@ -817,4 +853,51 @@ mod tests {
             &Position::offset(43u64)
         );
     }
+
+    #[test]
+    fn test_source_checkpoint_proto_roundtrip() {
+        let source_checkpoint: SourceCheckpoint = [
+            (PartitionId::from("a"), Position::offset(42u64)),
+            (PartitionId::from("b"), Position::eof(1337u64)),
+            (PartitionId::from("c"), Position::Beginning),
+        ]
+        .into_iter()
+        .collect();
+        let source_checkpoint_proto =
+            quickwit_proto::metastore::SourceCheckpoint::from(source_checkpoint.clone());
+        assert_eq!(source_checkpoint_proto.per_partition.len(), 3);
+        assert_eq!(
+            source_checkpoint_proto.per_partition["a"],
+            Position::offset(42u64)
+        );
+        assert_eq!(
+            SourceCheckpoint::from(source_checkpoint_proto),
+            source_checkpoint
+        );
+
+        let empty_checkpoint_proto =
+            quickwit_proto::metastore::SourceCheckpoint::from(SourceCheckpoint::default());
+        assert!(empty_checkpoint_proto.per_partition.is_empty());
+        assert!(SourceCheckpoint::from(empty_checkpoint_proto).is_empty());
+    }
+
+    #[test]
+    fn test_index_checkpoint_set_source_checkpoint() {
+        let mut index_checkpoint = IndexCheckpoint::default();
+        let source_checkpoint: SourceCheckpoint =
+            [(PartitionId::from("a"), Position::offset(42u64))]
+                .into_iter()
+                .collect();
+        assert!(index_checkpoint.set_source_checkpoint("test-source", source_checkpoint.clone()));
+        assert!(!index_checkpoint.set_source_checkpoint("test-source", source_checkpoint.clone()));
+        assert_eq!(
+            index_checkpoint.source_checkpoint("test-source"),
+            Some(&source_checkpoint)
+        );
+        assert!(index_checkpoint.set_source_checkpoint("test-source", SourceCheckpoint::default()));
+        assert!(index_checkpoint
+            .source_checkpoint("test-source")
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -33,38 +33,58 @@ pub mod checkpoint;
 mod error;
 mod metastore;
 mod metastore_factory;
+mod metastore_middleware;
 mod metastore_resolver;
 mod split_metadata;
 mod split_metadata_version;
 #[cfg(test)]
 pub(crate) mod tests;
+mod validated_index_id;
+mod validated_split_id;
 
 use std::ops::Range;
 
 pub use error::MetastoreResolverError;
+pub use metastore::audit_log::{AuditContext, AuditEvent, AuditLog, AuditLogWriter, AuditResult};
+pub use metastore::cached_metastore::CachedMetastore;
+#[cfg(feature = "consul")]
+pub use metastore::consul::{ConsulMetastore, SplitsWatchUpdate};
 pub use metastore::control_plane_metastore::ControlPlaneMetastore;
 pub use metastore::file_backed::FileBackedMetastore;
 pub(crate) use metastore::index_metadata::serialize::{IndexMetadataV0_8, VersionedIndexMetadata};
+#[cfg(any(test, feature = "testsuite"))]
+pub use metastore::memory::SharedMemoryMetastore;
 #[cfg(feature = "postgres")]
 pub use metastore::postgres::PostgresqlMetastore;
+#[cfg(feature = "sqlite")]
+pub use metastore::sqlite::SqliteMetastore;
 pub use metastore::{
-    file_backed, AddSourceRequestExt, CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata,
-    IndexMetadataResponseExt, IndexesMetadataResponseExt, ListIndexesMetadataResponseExt,
-    ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
+    file_backed, AddSourceRequestExt, CreateIndexRequestExt, CreateIndexResponseExt,
+    DeleteSplitsBulkOutcome, IndexMetadata, IndexMetadataResponseExt, IndexState,
+    IndexesMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsQuery,
+    ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
     MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt, StageSplitsRequestExt,
     UpdateIndexRequestExt,
 };
 pub use metastore_factory::{MetastoreFactory, UnsupportedMetastore};
-pub use metastore_resolver::MetastoreResolver;
+pub use metastore_middleware::{
+    InstrumentingMetastoreMiddleware, MetastoreMiddleware, RetryingMetastoreMiddleware,
+};
+pub use metastore_resolver::{MetastoreResolver, MetastoreResolverBuilder};
 use quickwit_common::is_disjoint;
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
 pub use split_metadata::{Split, SplitInfo, SplitMaturity, SplitMetadata, SplitState};
 pub(crate) use split_metadata_version::{SplitMetadataV0_8, VersionedSplitMetadata};
+pub use validated_index_id::{
+    InvalidIndexIdError, ValidatedIndexId, MAX_INDEX_ID_LEN, MIN_INDEX_ID_LEN,
+};
+pub use validated_split_id::{InvalidSplitIdError, ValidatedSplitId};
 
 #[derive(utoipa::OpenApi)]
 #[openapi(components(schemas(
     Split,
     SplitState,
+    IndexState,
     VersionedIndexMetadata,
     IndexMetadataV0_8,
     VersionedSplitMetadata,
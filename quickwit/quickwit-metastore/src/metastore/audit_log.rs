@@ -0,0 +1,1243 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Audit log recording every mutation applied to the metastore.
+//!
+//! Events are serialized as NDJSON and handed over to a dedicated writer thread so that metastore
+//! calls never wait on the audit log destination.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{self, Write};
+use std::net::{IpAddr, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use quickwit_common::net::get_short_hostname;
+use quickwit_common::uri::Uri;
+use quickwit_config::AuditLogConfig;
+use quickwit_proto::metastore::{
+    AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexAliasRequest,
+    CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
+    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
+    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
+    GetIndexTemplateResponse, GetSourceCheckpointRequest, GetSourceCheckpointResponse,
+    IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataRequest, IndexesMetadataResponse,
+    LastDeleteOpstampRequest, LastDeleteOpstampResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexAliasesRequest, ListIndexAliasesResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse, ListSplitsRequest,
+    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreResult,
+    MetastoreService, MetastoreServiceClient, MetastoreServiceStream, OpenShardsRequest,
+    OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
+    ToggleSourceRequest, UpdateIndexAliasRequest, UpdateIndexAliasResponse, UpdateIndexRequest,
+    UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
+};
+use quickwit_proto::types::{IndexUid, ShardId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+use crate::{AddSourceRequestExt, CreateIndexRequestExt, StageSplitsRequestExt};
+
+/// Actor recorded for the mutations issued outside of an [`AuditContext`] scope, typically by the
+/// Quickwit services themselves (indexing pipelines, janitor, control plane...).
+const SYSTEM_ACTOR: &str = "system";
+
+/// Path of the socket the local syslog daemon listens on.
+const SYSLOG_SOCKET_PATH: &str = "/dev/log";
+
+/// Syslog priority of audit events: facility `log audit` (13) and severity `informational` (6).
+const SYSLOG_PRIORITY: u8 = 13 * 8 + 6;
+
+tokio::task_local! {
+    static AUDIT_CONTEXT: AuditContext;
+}
+
+/// Identifies who issued the metastore mutations executed within [`AuditContext::scope`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AuditContext {
+    /// ID of the API key that authenticated the request.
+    pub actor: Option<String>,
+    /// IP address of the client that issued the request.
+    pub request_ip: Option<IpAddr>,
+}
+
+impl AuditContext {
+    /// Runs `future` with `self` as the audit context of the metastore mutations it issues.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        AUDIT_CONTEXT.scope(self, future).await
+    }
+
+    fn current() -> Self {
+        AUDIT_CONTEXT
+            .try_with(|audit_context| audit_context.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+/// A mutation applied to the metastore.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// RFC 3339 timestamp of the completion of the operation.
+    pub timestamp: String,
+    /// Name of the metastore RPC, for instance `publish_splits`.
+    pub operation: String,
+    pub actor: String,
+    pub index_id: Option<String>,
+    /// IDs of the splits, sources, shards, or templates affected by the operation.
+    pub affected_entity_ids: Vec<String>,
+    pub result: AuditResult,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub request_ip: Option<IpAddr>,
+}
+
+trait AuditSink: Send + 'static {
+    fn write_event(&mut self, event_json: &str) -> io::Result<()>;
+}
+
+struct FileSink {
+    file: File,
+}
+
+impl FileSink {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+}
+
+impl AuditSink for FileSink {
+    fn write_event(&mut self, event_json: &str) -> io::Result<()> {
+        let mut line = Vec::with_capacity(event_json.len() + 1);
+        line.extend_from_slice(event_json.as_bytes());
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        // The event must survive a crash of the node once the write returns.
+        self.file.sync_data()
+    }
+}
+
+enum SyslogSocket {
+    Local(UnixDatagram),
+    Remote(UdpSocket),
+}
+
+struct SyslogSink {
+    socket: SyslogSocket,
+    hostname: String,
+}
+
+impl SyslogSink {
+    fn new(socket: SyslogSocket) -> Self {
+        let hostname = get_short_hostname().unwrap_or_else(|_| "-".to_string());
+        Self { socket, hostname }
+    }
+}
+
+impl AuditSink for SyslogSink {
+    fn write_event(&mut self, event_json: &str) -> io::Result<()> {
+        let message = format_syslog_message(&self.hostname, OffsetDateTime::now_utc(), event_json);
+
+        match &self.socket {
+            SyslogSocket::Local(socket) => socket.send(message.as_bytes())?,
+            SyslogSocket::Remote(socket) => socket.send(message.as_bytes())?,
+        };
+        Ok(())
+    }
+}
+
+/// Formats an RFC 5424 syslog message.
+fn format_syslog_message(hostname: &str, now: OffsetDateTime, event_json: &str) -> String {
+    let timestamp = now.format(&Rfc3339).unwrap_or_else(|_| "-".to_string());
+    let process_id = std::process::id();
+    format!(
+        "<{SYSLOG_PRIORITY}>1 {timestamp} {hostname} quickwit {process_id} audit - {event_json}"
+    )
+}
+
+enum AuditLogCommand {
+    Write(AuditEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Handle to the thread writing audit events to their destination. Events are written in the
+/// order they are recorded.
+#[derive(Clone)]
+pub struct AuditLogWriter {
+    command_tx: mpsc::UnboundedSender<AuditLogCommand>,
+}
+
+impl fmt::Debug for AuditLogWriter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AuditLogWriter").finish()
+    }
+}
+
+impl AuditLogWriter {
+    /// Opens the audit log destination and spawns the writer thread.
+    pub fn open(audit_log_config: &AuditLogConfig) -> anyhow::Result<Self> {
+        let sink: Box<dyn AuditSink> = match audit_log_config {
+            AuditLogConfig::File { path } => {
+                let sink = FileSink::open(path).with_context(|| {
+                    format!("failed to open audit log file `{}`", path.display())
+                })?;
+                Box::new(sink)
+            }
+            AuditLogConfig::Syslog => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(SYSLOG_SOCKET_PATH).with_context(|| {
+                    format!("failed to connect to syslog socket `{SYSLOG_SOCKET_PATH}`")
+                })?;
+                Box::new(SyslogSink::new(SyslogSocket::Local(socket)))
+            }
+            AuditLogConfig::RemoteSyslog { address } => {
+                let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+                socket.connect(address.as_str()).with_context(|| {
+                    format!("failed to connect to remote syslog server `{address}`")
+                })?;
+                Box::new(SyslogSink::new(SyslogSocket::Remote(socket)))
+            }
+        };
+        Self::spawn(sink)
+    }
+
+    fn spawn(mut sink: Box<dyn AuditSink>) -> anyhow::Result<Self> {
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+
+        std::thread::Builder::new()
+            .name("audit_log_writer".to_string())
+            .spawn(move || {
+                while let Some(command) = command_rx.blocking_recv() {
+                    match command {
+                        AuditLogCommand::Write(event) => write_event(&mut *sink, &event),
+                        AuditLogCommand::Flush(flushed_tx) => {
+                            let _ = flushed_tx.send(());
+                        }
+                    }
+                }
+            })
+            .context("failed to spawn audit log writer thread")?;
+        Ok(Self { command_tx })
+    }
+
+    /// Enqueues an event. This method never blocks.
+    pub fn record(&self, event: AuditEvent) {
+        if self.command_tx.send(AuditLogCommand::Write(event)).is_err() {
+            error!("audit log writer thread exited, dropping audit event");
+        }
+    }
+
+    /// Waits until all the events recorded so far are written.
+    pub async fn flush(&self) {
+        let (flushed_tx, flushed_rx) = oneshot::channel();
+
+        if self
+            .command_tx
+            .send(AuditLogCommand::Flush(flushed_tx))
+            .is_ok()
+        {
+            let _ = flushed_rx.await;
+        }
+    }
+}
+
+fn write_event(sink: &mut dyn AuditSink, event: &AuditEvent) {
+    let event_json = match serde_json::to_string(event) {
+        Ok(event_json) => event_json,
+        Err(serde_error) => {
+            error!(error=%serde_error, "failed to serialize audit event");
+            return;
+        }
+    };
+    if let Err(io_error) = sink.write_event(&event_json) {
+        error!(error=%io_error, operation=%event.operation, "failed to write audit event");
+    }
+}
+
+/// A [`MetastoreService`] implementation that records every mutation it forwards to the
+/// underlying metastore in an audit log. Read-only calls are forwarded as is.
+#[derive(Clone)]
+pub struct AuditLog {
+    metastore: MetastoreServiceClient,
+    writer: AuditLogWriter,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AuditLog").finish()
+    }
+}
+
+impl AuditLog {
+    /// Creates a new [`AuditLog`] wrapping `metastore`.
+    pub fn new(metastore: MetastoreServiceClient, writer: AuditLogWriter) -> Self {
+        Self { metastore, writer }
+    }
+
+    fn record<T>(
+        &self,
+        operation: &str,
+        index_id: Option<String>,
+        affected_entity_ids: Vec<String>,
+        result: &MetastoreResult<T>,
+    ) {
+        let audit_context = AuditContext::current();
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .expect("RFC 3339 formatting of the current time should never fail");
+        let (result, error) = match result {
+            Ok(_) => (AuditResult::Success, None),
+            Err(metastore_error) => (AuditResult::Failure, Some(metastore_error.to_string())),
+        };
+        let event = AuditEvent {
+            timestamp,
+            operation: operation.to_string(),
+            actor: audit_context
+                .actor
+                .unwrap_or_else(|| SYSTEM_ACTOR.to_string()),
+            index_id,
+            affected_entity_ids,
+            result,
+            error,
+            request_ip: audit_context.request_ip,
+        };
+        self.writer.record(event);
+    }
+}
+
+fn index_id_opt(index_uid_opt: &Option<IndexUid>) -> Option<String> {
+    index_uid_opt
+        .as_ref()
+        .map(|index_uid| index_uid.index_id.clone())
+}
+
+fn shard_ids_to_strings(shard_ids: &[ShardId]) -> Vec<String> {
+    shard_ids
+        .iter()
+        .map(|shard_id| shard_id.to_string())
+        .collect()
+}
+
+#[async_trait]
+impl MetastoreService for AuditLog {
+    fn endpoints(&self) -> Vec<Uri> {
+        self.metastore.endpoints()
+    }
+
+    async fn check_connectivity(&mut self) -> anyhow::Result<()> {
+        self.metastore.check_connectivity().await
+    }
+
+    // Audited metastore API calls.
+
+    async fn create_index(
+        &mut self,
+        request: CreateIndexRequest,
+    ) -> MetastoreResult<CreateIndexResponse> {
+        let index_id = request
+            .deserialize_index_config()
+            .ok()
+            .map(|index_config| index_config.index_id);
+        let result = self.metastore.create_index(request).await;
+        let affected_entity_ids = result
+            .as_ref()
+            .ok()
+            .and_then(|response| response.index_uid.as_ref())
+            .map(|index_uid| vec![index_uid.to_string()])
+            .unwrap_or_default();
+        self.record("create_index", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request.index_uid.iter().map(ToString::to_string).collect();
+        let result = self.metastore.update_index(request).await;
+        self.record("update_index", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn delete_index(
+        &mut self,
+        request: DeleteIndexRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request.index_uid.iter().map(ToString::to_string).collect();
+        let result = self.metastore.delete_index(request).await;
+        self.record("delete_index", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request
+            .deserialize_source_config()
+            .map(|source_config| vec![source_config.source_id])
+            .unwrap_or_default();
+        let result = self.metastore.add_source(request).await;
+        self.record("add_source", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn toggle_source(
+        &mut self,
+        request: ToggleSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = vec![request.source_id.clone()];
+        let result = self.metastore.toggle_source(request).await;
+        self.record("toggle_source", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn delete_source(
+        &mut self,
+        request: DeleteSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = vec![request.source_id.clone()];
+        let result = self.metastore.delete_source(request).await;
+        self.record("delete_source", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn stage_splits(
+        &mut self,
+        request: StageSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request
+            .deserialize_splits_metadata()
+            .map(|splits_metadata| {
+                splits_metadata
+                    .into_iter()
+                    .map(|split_metadata| split_metadata.split_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let result = self.metastore.stage_splits(request).await;
+        self.record("stage_splits", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn publish_splits(
+        &mut self,
+        request: PublishSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request
+            .staged_split_ids
+            .iter()
+            .chain(&request.replaced_split_ids)
+            .cloned()
+            .collect();
+        let result = self.metastore.publish_splits(request).await;
+        self.record("publish_splits", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn mark_splits_for_deletion(
+        &mut self,
+        request: MarkSplitsForDeletionRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request.split_ids.clone();
+        let result = self.metastore.mark_splits_for_deletion(request).await;
+        self.record(
+            "mark_splits_for_deletion",
+            index_id,
+            affected_entity_ids,
+            &result,
+        );
+        result
+    }
+
+    async fn delete_splits(
+        &mut self,
+        request: DeleteSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request.split_ids.clone();
+        let result = self.metastore.delete_splits(request).await;
+        self.record("delete_splits", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn reset_source_checkpoint(
+        &mut self,
+        request: ResetSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = vec![request.source_id.clone()];
+        let result = self.metastore.reset_source_checkpoint(request).await;
+        self.record(
+            "reset_source_checkpoint",
+            index_id,
+            affected_entity_ids,
+            &result,
+        );
+        result
+    }
+
+    async fn create_delete_task(
+        &mut self,
+        delete_query: DeleteQuery,
+    ) -> MetastoreResult<DeleteTask> {
+        let index_id = index_id_opt(&delete_query.index_uid);
+        let result = self.metastore.create_delete_task(delete_query).await;
+        let affected_entity_ids = result
+            .as_ref()
+            .map(|delete_task| vec![delete_task.opstamp.to_string()])
+            .unwrap_or_default();
+        self.record("create_delete_task", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn update_splits_delete_opstamp(
+        &mut self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = request.split_ids.clone();
+        let result = self.metastore.update_splits_delete_opstamp(request).await;
+        self.record(
+            "update_splits_delete_opstamp",
+            index_id,
+            affected_entity_ids,
+            &result,
+        );
+        result
+    }
+
+    async fn open_shards(
+        &mut self,
+        request: OpenShardsRequest,
+    ) -> MetastoreResult<OpenShardsResponse> {
+        // The subrequests may target several indexes, so the shards are qualified with their
+        // index and source IDs.
+        let affected_entity_ids = request
+            .subrequests
+            .iter()
+            .map(|subrequest| {
+                let index_id = index_id_opt(&subrequest.index_uid).unwrap_or_default();
+                let shard_id = subrequest
+                    .shard_id
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                format!("{index_id}/{}/{shard_id}", subrequest.source_id)
+            })
+            .collect();
+        let result = self.metastore.open_shards(request).await;
+        self.record("open_shards", None, affected_entity_ids, &result);
+        result
+    }
+
+    async fn acquire_shards(
+        &mut self,
+        request: AcquireShardsRequest,
+    ) -> MetastoreResult<AcquireShardsResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = shard_ids_to_strings(&request.shard_ids);
+        let result = self.metastore.acquire_shards(request).await;
+        self.record("acquire_shards", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn delete_shards(
+        &mut self,
+        request: DeleteShardsRequest,
+    ) -> MetastoreResult<DeleteShardsResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = shard_ids_to_strings(&request.shard_ids);
+        let result = self.metastore.delete_shards(request).await;
+        self.record("delete_shards", index_id, affected_entity_ids, &result);
+        result
+    }
+
+    async fn create_index_template(
+        &mut self,
+        request: CreateIndexTemplateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let affected_entity_ids = serde_json::from_str::<JsonValue>(&request.index_template_json)
+            .ok()
+            .and_then(|index_template| {
+                index_template
+                    .get("template_id")
+                    .and_then(JsonValue::as_str)
+                    .map(|template_id| vec![template_id.to_string()])
+            })
+            .unwrap_or_default();
+        let result = self.metastore.create_index_template(request).await;
+        self.record("create_index_template", None, affected_entity_ids, &result);
+        result
+    }
+
+    async fn delete_index_templates(
+        &mut self,
+        request: DeleteIndexTemplatesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let affected_entity_ids = request.template_ids.clone();
+        let result = self.metastore.delete_index_templates(request).await;
+        self.record("delete_index_templates", None, affected_entity_ids, &result);
+        result
+    }
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let affected_entity_ids = vec![request.alias_id.clone()];
+        let result = self.metastore.create_index_alias(request).await;
+        self.record("create_index_alias", None, affected_entity_ids, &result);
+        result
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        let affected_entity_ids = vec![request.alias_id.clone()];
+        let result = self.metastore.update_index_alias(request).await;
+        self.record("update_index_alias", None, affected_entity_ids, &result);
+        result
+    }
+
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_id = index_id_opt(&request.index_uid);
+        let affected_entity_ids = vec![request.source_id.clone()];
+        let result = self.metastore.update_source_checkpoint(request).await;
+        self.record(
+            "update_source_checkpoint",
+            index_id,
+            affected_entity_ids,
+            &result,
+        );
+        result
+    }
+
+    // Read-only metastore API calls.
+
+    async fn index_metadata(
+        &mut self,
+        request: IndexMetadataRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        self.metastore.index_metadata(request).await
+    }
+
+    async fn indexes_metadata(
+        &mut self,
+        request: IndexesMetadataRequest,
+    ) -> MetastoreResult<IndexesMetadataResponse> {
+        self.metastore.indexes_metadata(request).await
+    }
+
+    async fn list_indexes_metadata(
+        &mut self,
+        request: ListIndexesMetadataRequest,
+    ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        self.metastore.list_indexes_metadata(request).await
+    }
+
+    async fn list_splits(
+        &mut self,
+        request: ListSplitsRequest,
+    ) -> MetastoreResult<MetastoreServiceStream<ListSplitsResponse>> {
+        self.metastore.list_splits(request).await
+    }
+
+    async fn list_stale_splits(
+        &mut self,
+        request: ListStaleSplitsRequest,
+    ) -> MetastoreResult<ListSplitsResponse> {
+        self.metastore.list_stale_splits(request).await
+    }
+
+    async fn last_delete_opstamp(
+        &mut self,
+        request: LastDeleteOpstampRequest,
+    ) -> MetastoreResult<LastDeleteOpstampResponse> {
+        self.metastore.last_delete_opstamp(request).await
+    }
+
+    async fn list_delete_tasks(
+        &mut self,
+        request: ListDeleteTasksRequest,
+    ) -> MetastoreResult<ListDeleteTasksResponse> {
+        self.metastore.list_delete_tasks(request).await
+    }
+
+    async fn list_shards(
+        &mut self,
+        request: ListShardsRequest,
+    ) -> MetastoreResult<ListShardsResponse> {
+        self.metastore.list_shards(request).await
+    }
+
+    async fn get_index_template(
+        &mut self,
+        request: GetIndexTemplateRequest,
+    ) -> MetastoreResult<GetIndexTemplateResponse> {
+        self.metastore.get_index_template(request).await
+    }
+
+    async fn find_index_template_matches(
+        &mut self,
+        request: FindIndexTemplateMatchesRequest,
+    ) -> MetastoreResult<FindIndexTemplateMatchesResponse> {
+        self.metastore.find_index_template_matches(request).await
+    }
+
+    async fn list_index_templates(
+        &mut self,
+        request: ListIndexTemplatesRequest,
+    ) -> MetastoreResult<ListIndexTemplatesResponse> {
+        self.metastore.list_index_templates(request).await
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        self.metastore.list_index_aliases(request).await
+    }
+
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        self.metastore.get_source_checkpoint(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::path::PathBuf;
+
+    use quickwit_config::{IndexConfig, SourceConfig};
+    use quickwit_proto::metastore::{
+        EntityKind, MetastoreError, MockMetastoreService, OpenShardSubrequest,
+    };
+
+    use super::*;
+    use crate::SplitMetadata;
+
+    fn setup_audit_log(mock_metastore: MockMetastoreService) -> (AuditLog, tempfile::TempDir) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let audit_log_config = AuditLogConfig::File {
+            path: audit_log_path(&temp_dir),
+        };
+        let writer = AuditLogWriter::open(&audit_log_config).unwrap();
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        (AuditLog::new(metastore, writer), temp_dir)
+    }
+
+    fn audit_log_path(temp_dir: &tempfile::TempDir) -> PathBuf {
+        temp_dir.path().join("audit.ndjson")
+    }
+
+    async fn read_audit_events(
+        audit_log: &AuditLog,
+        temp_dir: &tempfile::TempDir,
+    ) -> Vec<AuditEvent> {
+        audit_log.writer.flush().await;
+
+        std::fs::read_to_string(audit_log_path(temp_dir))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_all_mutations() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_create_index().returning(|_| {
+            Ok(CreateIndexResponse {
+                index_uid: Some(IndexUid::for_test("test-index", 0)),
+                ..Default::default()
+            })
+        });
+        mock_metastore
+            .expect_update_index()
+            .returning(|_| Ok(IndexMetadataResponse::default()));
+        mock_metastore
+            .expect_delete_index()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_add_source()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_toggle_source()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_delete_source()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_stage_splits()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_publish_splits()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_mark_splits_for_deletion()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_delete_splits()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_reset_source_checkpoint()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore.expect_create_delete_task().returning(|_| {
+            Ok(DeleteTask {
+                opstamp: 42,
+                ..Default::default()
+            })
+        });
+        mock_metastore
+            .expect_update_splits_delete_opstamp()
+            .returning(|_| Ok(UpdateSplitsDeleteOpstampResponse::default()));
+        mock_metastore
+            .expect_open_shards()
+            .returning(|_| Ok(OpenShardsResponse::default()));
+        mock_metastore
+            .expect_acquire_shards()
+            .returning(|_| Ok(AcquireShardsResponse::default()));
+        mock_metastore
+            .expect_delete_shards()
+            .returning(|_| Ok(DeleteShardsResponse::default()));
+        mock_metastore
+            .expect_create_index_template()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_delete_index_templates()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_create_index_alias()
+            .returning(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_update_index_alias()
+            .returning(|_| Ok(UpdateIndexAliasResponse::default()));
+
+        let (mut audit_log, temp_dir) = setup_audit_log(mock_metastore);
+        let index_uid = IndexUid::for_test("test-index", 0);
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        audit_log.create_index(create_index_request).await.unwrap();
+
+        let update_index_request = UpdateIndexRequest {
+            index_uid: Some(index_uid.clone()),
+            ..Default::default()
+        };
+        audit_log.update_index(update_index_request).await.unwrap();
+
+        let delete_index_request = DeleteIndexRequest {
+            index_uid: Some(index_uid.clone()),
+        };
+        audit_log.delete_index(delete_index_request).await.unwrap();
+
+        let source_config = SourceConfig::ingest_v2();
+        let add_source_request =
+            AddSourceRequest::try_from_source_config(index_uid.clone(), &source_config).unwrap();
+        audit_log.add_source(add_source_request).await.unwrap();
+
+        let toggle_source_request = ToggleSourceRequest {
+            index_uid: Some(index_uid.clone()),
+            source_id: "test-source".to_string(),
+            enable: false,
+        };
+        audit_log
+            .toggle_source(toggle_source_request)
+            .await
+            .unwrap();
+
+        let delete_source_request = DeleteSourceRequest {
+            index_uid: Some(index_uid.clone()),
+            source_id: "test-source".to_string(),
+        };
+        audit_log
+            .delete_source(delete_source_request)
+            .await
+            .unwrap();
+
+        let splits_metadata = vec![
+            SplitMetadata::for_test("split-1".to_string()),
+            SplitMetadata::for_test("split-2".to_string()),
+        ];
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        audit_log.stage_splits(stage_splits_request).await.unwrap();
+
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: Some(index_uid.clone()),
+            staged_split_ids: vec!["split-3".to_string()],
+            replaced_split_ids: vec!["split-1".to_string(), "split-2".to_string()],
+            ..Default::default()
+        };
+        audit_log
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+
+        let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest {
+            index_uid: Some(index_uid.clone()),
+            split_ids: vec!["split-1".to_string()],
+        };
+        audit_log
+            .mark_splits_for_deletion(mark_splits_for_deletion_request)
+            .await
+            .unwrap();
+
+        let delete_splits_request = DeleteSplitsRequest {
+            index_uid: Some(index_uid.clone()),
+            split_ids: vec!["split-1".to_string()],
+        };
+        audit_log
+            .delete_splits(delete_splits_request)
+            .await
+            .unwrap();
+
+        let reset_source_checkpoint_request = ResetSourceCheckpointRequest {
+            index_uid: Some(index_uid.clone()),
+            source_id: "test-source".to_string(),
+        };
+        audit_log
+            .reset_source_checkpoint(reset_source_checkpoint_request)
+            .await
+            .unwrap();
+
+        let delete_query = DeleteQuery {
+            index_uid: Some(index_uid.clone()),
+            ..Default::default()
+        };
+        audit_log.create_delete_task(delete_query).await.unwrap();
+
+        let update_splits_delete_opstamp_request = UpdateSplitsDeleteOpstampRequest {
+            index_uid: Some(index_uid.clone()),
+            split_ids: vec!["split-3".to_string()],
+            delete_opstamp: 42,
+        };
+        audit_log
+            .update_splits_delete_opstamp(update_splits_delete_opstamp_request)
+            .await
+            .unwrap();
+
+        let open_shards_request = OpenShardsRequest {
+            subrequests: vec![OpenShardSubrequest {
+                index_uid: Some(index_uid.clone()),
+                source_id: "test-source".to_string(),
+                shard_id: Some(ShardId::from(1)),
+                ..Default::default()
+            }],
+        };
+        audit_log.open_shards(open_shards_request).await.unwrap();
+
+        let acquire_shards_request = AcquireShardsRequest {
+            index_uid: Some(index_uid.clone()),
+            source_id: "test-source".to_string(),
+            shard_ids: vec![ShardId::from(1)],
+            ..Default::default()
+        };
+        audit_log
+            .acquire_shards(acquire_shards_request)
+            .await
+            .unwrap();
+
+        let delete_shards_request = DeleteShardsRequest {
+            index_uid: Some(index_uid.clone()),
+            source_id: "test-source".to_string(),
+            shard_ids: vec![ShardId::from(1)],
+            force: false,
+        };
+        audit_log
+            .delete_shards(delete_shards_request)
+            .await
+            .unwrap();
+
+        let create_index_template_request = CreateIndexTemplateRequest {
+            index_template_json: r#"{"template_id": "test-template"}"#.to_string(),
+            overwrite: false,
+        };
+        audit_log
+            .create_index_template(create_index_template_request)
+            .await
+            .unwrap();
+
+        let delete_index_templates_request = DeleteIndexTemplatesRequest {
+            template_ids: vec!["test-template".to_string()],
+        };
+        audit_log
+            .delete_index_templates(delete_index_templates_request)
+            .await
+            .unwrap();
+
+        let create_index_alias_request = CreateIndexAliasRequest {
+            alias_id: "test-alias".to_string(),
+            index_ids: vec!["test-index".to_string()],
+        };
+        audit_log
+            .create_index_alias(create_index_alias_request)
+            .await
+            .unwrap();
+
+        let update_index_alias_request = UpdateIndexAliasRequest {
+            alias_id: "test-alias".to_string(),
+            add_index_ids: vec!["test-index-2".to_string()],
+            remove_index_ids: vec!["test-index".to_string()],
+        };
+        audit_log
+            .update_index_alias(update_index_alias_request)
+            .await
+            .unwrap();
+
+        let audit_events = read_audit_events(&audit_log, &temp_dir).await;
+
+        let operations: Vec<&str> = audit_events
+            .iter()
+            .map(|audit_event| audit_event.operation.as_str())
+            .collect();
+        assert_eq!(
+            operations,
+            [
+                "create_index",
+                "update_index",
+                "delete_index",
+                "add_source",
+                "toggle_source",
+                "delete_source",
+                "stage_splits",
+                "publish_splits",
+                "mark_splits_for_deletion",
+                "delete_splits",
+                "reset_source_checkpoint",
+                "create_delete_task",
+                "update_splits_delete_opstamp",
+                "open_shards",
+                "acquire_shards",
+                "delete_shards",
+                "create_index_template",
+                "delete_index_templates",
+                "create_index_alias",
+                "update_index_alias",
+            ]
+        );
+        for audit_event in &audit_events {
+            assert_eq!(audit_event.actor, "system");
+            assert_eq!(audit_event.result, AuditResult::Success);
+            assert!(audit_event.error.is_none());
+            assert!(audit_event.request_ip.is_none());
+            OffsetDateTime::parse(&audit_event.timestamp, &Rfc3339).unwrap();
+        }
+        let affected_entity_ids: Vec<Vec<&str>> = audit_events
+            .iter()
+            .map(|audit_event| {
+                audit_event
+                    .affected_entity_ids
+                    .iter()
+                    .map(String::as_str)
+                    .collect()
+            })
+            .collect();
+        let index_uid_str = index_uid.to_string();
+        assert_eq!(
+            affected_entity_ids,
+            [
+                vec![index_uid_str.as_str()],
+                vec![index_uid_str.as_str()],
+                vec![index_uid_str.as_str()],
+                vec![source_config.source_id.as_str()],
+                vec!["test-source"],
+                vec!["test-source"],
+                vec!["split-1", "split-2"],
+                vec!["split-3", "split-1", "split-2"],
+                vec!["split-1"],
+                vec!["split-1"],
+                vec!["test-source"],
+                vec!["42"],
+                vec!["split-3"],
+                vec!["test-index/test-source/00000000000000000001"],
+                vec!["00000000000000000001"],
+                vec!["00000000000000000001"],
+                vec!["test-template"],
+                vec!["test-template"],
+                vec!["test-alias"],
+                vec!["test-alias"],
+            ]
+        );
+        assert_eq!(audit_events[0].index_id.as_deref(), Some("test-index"));
+        assert_eq!(audit_events[7].index_id.as_deref(), Some("test-index"));
+        assert!(audit_events[13].index_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_failures() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_delete_index().returning(|_| {
+            Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: "test-index".to_string(),
+            }))
+        });
+        let (mut audit_log, temp_dir) = setup_audit_log(mock_metastore);
+
+        let delete_index_request = DeleteIndexRequest {
+            index_uid: Some(IndexUid::for_test("test-index", 0)),
+        };
+        audit_log
+            .delete_index(delete_index_request)
+            .await
+            .unwrap_err();
+
+        let audit_events = read_audit_events(&audit_log, &temp_dir).await;
+        assert_eq!(audit_events.len(), 1);
+        assert_eq!(audit_events[0].operation, "delete_index");
+        assert_eq!(audit_events[0].result, AuditResult::Failure);
+        assert!(audit_events[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("test-index"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_ignores_reads() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_index_metadata()
+            .returning(|_| Ok(IndexMetadataResponse::default()));
+        mock_metastore
+            .expect_delete_splits()
+            .returning(|_| Ok(EmptyResponse {}));
+        let (mut audit_log, temp_dir) = setup_audit_log(mock_metastore);
+
+        audit_log
+            .index_metadata(IndexMetadataRequest::for_index_id("test-index".to_string()))
+            .await
+            .unwrap();
+        audit_log
+            .delete_splits(DeleteSplitsRequest::default())
+            .await
+            .unwrap();
+
+        let audit_events = read_audit_events(&audit_log, &temp_dir).await;
+        assert_eq!(audit_events.len(), 1);
+        assert_eq!(audit_events[0].operation, "delete_splits");
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_audit_context() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_delete_splits()
+            .returning(|_| Ok(EmptyResponse {}));
+        let (audit_log, temp_dir) = setup_audit_log(mock_metastore);
+
+        let audit_context = AuditContext {
+            actor: Some("api-key-1".to_string()),
+            request_ip: Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        };
+        let mut audit_log_clone = audit_log.clone();
+        audit_context
+            .scope(async move {
+                audit_log_clone
+                    .delete_splits(DeleteSplitsRequest::default())
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        let audit_events = read_audit_events(&audit_log, &temp_dir).await;
+        assert_eq!(audit_events.len(), 1);
+        assert_eq!(audit_events[0].actor, "api-key-1");
+        assert_eq!(
+            audit_events[0].request_ip,
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_writer_remote_syslog() {
+        let syslog_server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let audit_log_config = AuditLogConfig::RemoteSyslog {
+            address: syslog_server.local_addr().unwrap().to_string(),
+        };
+        let writer = AuditLogWriter::open(&audit_log_config).unwrap();
+
+        let audit_event = AuditEvent {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            operation: "delete_index".to_string(),
+            actor: "system".to_string(),
+            index_id: Some("test-index".to_string()),
+            affected_entity_ids: Vec::new(),
+            result: AuditResult::Success,
+            error: None,
+            request_ip: None,
+        };
+        writer.record(audit_event.clone());
+        writer.flush().await;
+
+        let mut buffer = [0u8; 1024];
+        let num_bytes = syslog_server.recv(&mut buffer).unwrap();
+        let message = std::str::from_utf8(&buffer[..num_bytes]).unwrap();
+        assert!(message.starts_with("<110>1 "));
+
+        let (header, event_json) = message.split_once(" audit - ").unwrap();
+        assert!(header.contains(" quickwit "));
+        let received_audit_event: AuditEvent = serde_json::from_str(event_json).unwrap();
+        assert_eq!(received_audit_event, audit_event);
+    }
+
+    #[test]
+    fn test_format_syslog_message() {
+        let now = OffsetDateTime::from_unix_timestamp(1_704_067_200).unwrap();
+        let message = format_syslog_message("my-host", now, "{}");
+        let process_id = std::process::id();
+        assert_eq!(
+            message,
+            format!("<110>1 2024-01-01T00:00:00Z my-host quickwit {process_id} audit - {{}}")
+        );
+    }
+}
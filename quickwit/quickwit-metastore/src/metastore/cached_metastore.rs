@@ -0,0 +1,641 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Read-through cache for the `index_metadata` and `list_splits` metastore calls.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use quickwit_common::uri::Uri;
+use quickwit_common::ServiceStream;
+use quickwit_proto::metastore::{
+    AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexAliasRequest,
+    CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
+    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
+    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
+    GetIndexTemplateResponse, GetSourceCheckpointRequest, GetSourceCheckpointResponse,
+    IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataRequest, IndexesMetadataResponse,
+    LastDeleteOpstampRequest, LastDeleteOpstampResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexAliasesRequest, ListIndexAliasesResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse, ListSplitsRequest,
+    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreResult,
+    MetastoreService, MetastoreServiceClient, MetastoreServiceStream, OpenShardsRequest,
+    OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
+    ToggleSourceRequest, UpdateIndexAliasRequest, UpdateIndexAliasResponse, UpdateIndexRequest,
+    UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
+};
+use quickwit_proto::types::{IndexId, IndexUid};
+use tokio::time::Instant;
+
+use crate::{CreateIndexRequestExt, ListSplitsRequestExt};
+
+/// `index_metadata` requests target an index either by ID or by UID.
+type IndexMetadataKey = (Option<IndexId>, Option<IndexUid>);
+
+struct CachedResponse<T> {
+    response: T,
+    inserted_at: Instant,
+}
+
+/// Returns the cached response associated with `key` unless it has expired, in which case it is
+/// removed.
+fn get_fresh<K: Eq + Hash, T: Clone>(
+    responses: &mut HashMap<K, CachedResponse<T>>,
+    key: &K,
+    ttl: Duration,
+) -> Option<T> {
+    let cached_response = responses.get(key)?;
+
+    if cached_response.inserted_at.elapsed() < ttl {
+        return Some(cached_response.response.clone());
+    }
+    responses.remove(key);
+    None
+}
+
+#[derive(Default)]
+struct IndexCache {
+    index_metadata: HashMap<IndexMetadataKey, CachedResponse<IndexMetadataResponse>>,
+    // Keyed by the serialized `ListSplitsQuery`.
+    list_splits: HashMap<String, CachedResponse<Vec<ListSplitsResponse>>>,
+}
+
+#[derive(Default)]
+struct MetastoreCache {
+    per_index_caches: HashMap<IndexId, IndexCache>,
+    // Incremented on every eviction so that the responses fetched before an eviction, which may
+    // predate the mutation that triggered it, are not cached.
+    generation: u64,
+}
+
+impl MetastoreCache {
+    fn evict(&mut self, index_id: &str) {
+        self.per_index_caches.remove(index_id);
+        self.generation += 1;
+    }
+
+    fn index_cache_mut(&mut self, generation: u64, index_id: IndexId) -> Option<&mut IndexCache> {
+        if generation != self.generation {
+            return None;
+        }
+        Some(self.per_index_caches.entry(index_id).or_default())
+    }
+}
+
+/// A [`MetastoreService`] implementation caching the responses of the `index_metadata` and
+/// `list_splits` calls of the underlying metastore for a fixed duration.
+///
+/// The cached responses of an index are evicted as soon as a mutation of the index goes through
+/// the [`CachedMetastore`]. The mutations applied by other nodes are only observed once the
+/// responses expire. `list_splits` responses are cached only when the query targets a single
+/// index. Other calls are forwarded as is.
+#[derive(Clone)]
+pub struct CachedMetastore {
+    metastore: MetastoreServiceClient,
+    cache: Arc<Mutex<MetastoreCache>>,
+    ttl: Duration,
+}
+
+impl fmt::Debug for CachedMetastore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CachedMetastore")
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl CachedMetastore {
+    /// Creates a new [`CachedMetastore`] wrapping `metastore`, whose responses are cached for
+    /// `ttl`.
+    pub fn new(metastore: MetastoreServiceClient, ttl: Duration) -> Self {
+        Self {
+            metastore,
+            cache: Default::default(),
+            ttl,
+        }
+    }
+
+    fn evict(&self, index_id: &str) {
+        self.cache.lock().unwrap().evict(index_id);
+    }
+
+    fn evict_index_uid(&self, index_uid_opt: &Option<IndexUid>) {
+        if let Some(index_uid) = index_uid_opt {
+            self.evict(&index_uid.index_id);
+        }
+    }
+}
+
+#[async_trait]
+impl MetastoreService for CachedMetastore {
+    fn endpoints(&self) -> Vec<Uri> {
+        self.metastore.endpoints()
+    }
+
+    async fn check_connectivity(&mut self) -> anyhow::Result<()> {
+        self.metastore.check_connectivity().await
+    }
+
+    // Cached metastore API calls.
+
+    async fn index_metadata(
+        &mut self,
+        request: IndexMetadataRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_id_opt = request
+            .index_uid
+            .as_ref()
+            .map(|index_uid| index_uid.index_id.clone())
+            .or_else(|| request.index_id.clone());
+        let Some(index_id) = index_id_opt else {
+            return self.metastore.index_metadata(request).await;
+        };
+        let key = (request.index_id.clone(), request.index_uid.clone());
+        let generation = {
+            let mut cache = self.cache.lock().unwrap();
+
+            if let Some(index_cache) = cache.per_index_caches.get_mut(&index_id) {
+                if let Some(response) = get_fresh(&mut index_cache.index_metadata, &key, self.ttl) {
+                    return Ok(response);
+                }
+            }
+            cache.generation
+        };
+        let response = self.metastore.index_metadata(request).await?;
+
+        if let Some(index_cache) = self
+            .cache
+            .lock()
+            .unwrap()
+            .index_cache_mut(generation, index_id)
+        {
+            let cached_response = CachedResponse {
+                response: response.clone(),
+                inserted_at: Instant::now(),
+            };
+            index_cache.index_metadata.insert(key, cached_response);
+        }
+        Ok(response)
+    }
+
+    async fn list_splits(
+        &mut self,
+        request: ListSplitsRequest,
+    ) -> MetastoreResult<MetastoreServiceStream<ListSplitsResponse>> {
+        let index_id = match request.deserialize_list_splits_query() {
+            Ok(query) if query.index_uids.len() == 1 => query.index_uids[0].index_id.clone(),
+            _ => return self.metastore.list_splits(request).await,
+        };
+        let key = request.query_json.clone();
+        let generation = {
+            let mut cache = self.cache.lock().unwrap();
+
+            if let Some(index_cache) = cache.per_index_caches.get_mut(&index_id) {
+                if let Some(responses) = get_fresh(&mut index_cache.list_splits, &key, self.ttl) {
+                    return Ok(ServiceStream::from(
+                        responses.into_iter().map(Ok).collect::<Vec<_>>(),
+                    ));
+                }
+            }
+            cache.generation
+        };
+        let responses: Vec<ListSplitsResponse> = self
+            .metastore
+            .list_splits(request)
+            .await?
+            .try_collect()
+            .await?;
+
+        if let Some(index_cache) = self
+            .cache
+            .lock()
+            .unwrap()
+            .index_cache_mut(generation, index_id)
+        {
+            let cached_response = CachedResponse {
+                response: responses.clone(),
+                inserted_at: Instant::now(),
+            };
+            index_cache.list_splits.insert(key, cached_response);
+        }
+        Ok(ServiceStream::from(
+            responses.into_iter().map(Ok).collect::<Vec<_>>(),
+        ))
+    }
+
+    // Metastore API calls evicting the cached responses of the index they mutate.
+
+    async fn create_index(
+        &mut self,
+        request: CreateIndexRequest,
+    ) -> MetastoreResult<CreateIndexResponse> {
+        let index_id_opt = request
+            .deserialize_index_config()
+            .ok()
+            .map(|index_config| index_config.index_id);
+        let result = self.metastore.create_index(request).await;
+
+        if let Some(index_id) = index_id_opt {
+            self.evict(&index_id);
+        }
+        result
+    }
+
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.update_index(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn delete_index(
+        &mut self,
+        request: DeleteIndexRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.delete_index(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.add_source(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn toggle_source(
+        &mut self,
+        request: ToggleSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.toggle_source(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn delete_source(
+        &mut self,
+        request: DeleteSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.delete_source(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn stage_splits(
+        &mut self,
+        request: StageSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.stage_splits(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn publish_splits(
+        &mut self,
+        request: PublishSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.publish_splits(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn mark_splits_for_deletion(
+        &mut self,
+        request: MarkSplitsForDeletionRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.mark_splits_for_deletion(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn delete_splits(
+        &mut self,
+        request: DeleteSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.delete_splits(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn reset_source_checkpoint(
+        &mut self,
+        request: ResetSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.reset_source_checkpoint(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn update_splits_delete_opstamp(
+        &mut self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.update_splits_delete_opstamp(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid_opt = request.index_uid.clone();
+        let result = self.metastore.update_source_checkpoint(request).await;
+        self.evict_index_uid(&index_uid_opt);
+        result
+    }
+
+    // Forwarded metastore API calls.
+
+    async fn indexes_metadata(
+        &mut self,
+        request: IndexesMetadataRequest,
+    ) -> MetastoreResult<IndexesMetadataResponse> {
+        self.metastore.indexes_metadata(request).await
+    }
+
+    async fn list_indexes_metadata(
+        &mut self,
+        request: ListIndexesMetadataRequest,
+    ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        self.metastore.list_indexes_metadata(request).await
+    }
+
+    async fn list_stale_splits(
+        &mut self,
+        request: ListStaleSplitsRequest,
+    ) -> MetastoreResult<ListSplitsResponse> {
+        self.metastore.list_stale_splits(request).await
+    }
+
+    async fn create_delete_task(
+        &mut self,
+        delete_query: DeleteQuery,
+    ) -> MetastoreResult<DeleteTask> {
+        self.metastore.create_delete_task(delete_query).await
+    }
+
+    async fn last_delete_opstamp(
+        &mut self,
+        request: LastDeleteOpstampRequest,
+    ) -> MetastoreResult<LastDeleteOpstampResponse> {
+        self.metastore.last_delete_opstamp(request).await
+    }
+
+    async fn list_delete_tasks(
+        &mut self,
+        request: ListDeleteTasksRequest,
+    ) -> MetastoreResult<ListDeleteTasksResponse> {
+        self.metastore.list_delete_tasks(request).await
+    }
+
+    async fn open_shards(
+        &mut self,
+        request: OpenShardsRequest,
+    ) -> MetastoreResult<OpenShardsResponse> {
+        self.metastore.open_shards(request).await
+    }
+
+    async fn acquire_shards(
+        &mut self,
+        request: AcquireShardsRequest,
+    ) -> MetastoreResult<AcquireShardsResponse> {
+        self.metastore.acquire_shards(request).await
+    }
+
+    async fn delete_shards(
+        &mut self,
+        request: DeleteShardsRequest,
+    ) -> MetastoreResult<DeleteShardsResponse> {
+        self.metastore.delete_shards(request).await
+    }
+
+    async fn list_shards(
+        &mut self,
+        request: ListShardsRequest,
+    ) -> MetastoreResult<ListShardsResponse> {
+        self.metastore.list_shards(request).await
+    }
+
+    async fn create_index_template(
+        &mut self,
+        request: CreateIndexTemplateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_index_template(request).await
+    }
+
+    async fn get_index_template(
+        &mut self,
+        request: GetIndexTemplateRequest,
+    ) -> MetastoreResult<GetIndexTemplateResponse> {
+        self.metastore.get_index_template(request).await
+    }
+
+    async fn find_index_template_matches(
+        &mut self,
+        request: FindIndexTemplateMatchesRequest,
+    ) -> MetastoreResult<FindIndexTemplateMatchesResponse> {
+        self.metastore.find_index_template_matches(request).await
+    }
+
+    async fn list_index_templates(
+        &mut self,
+        request: ListIndexTemplatesRequest,
+    ) -> MetastoreResult<ListIndexTemplatesResponse> {
+        self.metastore.list_index_templates(request).await
+    }
+
+    async fn delete_index_templates(
+        &mut self,
+        request: DeleteIndexTemplatesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_index_templates(request).await
+    }
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_index_alias(request).await
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        self.metastore.update_index_alias(request).await
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        self.metastore.list_index_aliases(request).await
+    }
+
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        self.metastore.get_source_checkpoint(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::metastore::MockMetastoreService;
+
+    use super::*;
+    use crate::{
+        IndexMetadata, IndexMetadataResponseExt, ListSplitsQuery, ListSplitsResponseExt,
+        MetastoreServiceStreamSplitsExt, Split, SplitMetadata, SplitState,
+    };
+
+    const TTL: Duration = Duration::from_secs(10);
+
+    fn index_metadata_response() -> IndexMetadataResponse {
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+        IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap()
+    }
+
+    fn list_splits_request(index_uid: &IndexUid) -> ListSplitsRequest {
+        let query = ListSplitsQuery::for_index(index_uid.clone());
+        ListSplitsRequest::try_from_list_splits_query(&query).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cached_metastore_index_metadata() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_index_metadata()
+            .times(1)
+            .returning(|_| Ok(index_metadata_response()));
+        let mut cached_metastore =
+            CachedMetastore::new(MetastoreServiceClient::from_mock(mock_metastore), TTL);
+
+        for _ in 0..2 {
+            let index_metadata = cached_metastore
+                .index_metadata(IndexMetadataRequest::for_index_id("test-index".to_string()))
+                .await
+                .unwrap()
+                .deserialize_index_metadata()
+                .unwrap();
+            assert_eq!(index_metadata.index_id(), "test-index");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_metastore_index_metadata_expires() {
+        tokio::time::pause();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_index_metadata()
+            .times(2)
+            .returning(|_| Ok(index_metadata_response()));
+        let mut cached_metastore =
+            CachedMetastore::new(MetastoreServiceClient::from_mock(mock_metastore), TTL);
+
+        let request = IndexMetadataRequest::for_index_id("test-index".to_string());
+        cached_metastore
+            .index_metadata(request.clone())
+            .await
+            .unwrap();
+
+        tokio::time::advance(TTL / 2).await;
+        cached_metastore
+            .index_metadata(request.clone())
+            .await
+            .unwrap();
+
+        tokio::time::advance(TTL).await;
+        cached_metastore.index_metadata(request).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cached_metastore_publish_splits_evicts_list_splits() {
+        let index_uid = IndexUid::for_test("test-index", 0);
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_splits().times(2).returning(|_| {
+            let split = Split {
+                split_state: SplitState::Published,
+                update_timestamp: 0,
+                publish_timestamp: None,
+                split_metadata: SplitMetadata::for_test("split-1".to_string()),
+            };
+            let response = ListSplitsResponse::try_from_splits([split])?;
+            Ok(ServiceStream::from(vec![Ok(response)]))
+        });
+        mock_metastore
+            .expect_publish_splits()
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let mut cached_metastore =
+            CachedMetastore::new(MetastoreServiceClient::from_mock(mock_metastore), TTL);
+
+        for _ in 0..2 {
+            let splits_metadata = cached_metastore
+                .list_splits(list_splits_request(&index_uid))
+                .await
+                .unwrap()
+                .collect_splits_metadata()
+                .await
+                .unwrap();
+            assert_eq!(splits_metadata.len(), 1);
+        }
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: Some(index_uid.clone()),
+            staged_split_ids: vec!["split-1".to_string()],
+            ..Default::default()
+        };
+        cached_metastore
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+
+        let splits_metadata = cached_metastore
+            .list_splits(list_splits_request(&index_uid))
+            .await
+            .unwrap()
+            .collect_splits_metadata()
+            .await
+            .unwrap();
+        assert_eq!(splits_metadata.len(), 1);
+    }
+}
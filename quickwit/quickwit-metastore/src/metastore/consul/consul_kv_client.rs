@@ -0,0 +1,426 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use quickwit_common::uri::Uri;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::MetastoreResolverError;
+
+/// Header carrying the ACL token of Consul requests.
+const CONSUL_TOKEN_HEADER: &str = "X-Consul-Token";
+
+/// Header carrying the Raft index of the response to a Consul query.
+const CONSUL_INDEX_HEADER: &str = "X-Consul-Index";
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ConsulError {
+    #[error("key `{key}` was modified concurrently")]
+    CasFailed { key: String },
+    #[error("the ACL token does not allow for this operation: {message}")]
+    PermissionDenied { message: String },
+    #[error("Consul request failed: {message}")]
+    Request { message: String },
+}
+
+impl From<reqwest::Error> for ConsulError {
+    fn from(error: reqwest::Error) -> Self {
+        ConsulError::Request {
+            message: error.to_string(),
+        }
+    }
+}
+
+pub(crate) type ConsulResult<T> = Result<T, ConsulError>;
+
+/// A key-value pair stored in Consul KV.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct KvPair {
+    pub key: String,
+    pub value: Vec<u8>,
+    /// Raft index of the last modification of the key, used for compare-and-swap operations.
+    pub modify_index: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KvEntry {
+    key: String,
+    #[serde(default)]
+    value: Option<String>,
+    modify_index: u64,
+}
+
+impl KvEntry {
+    fn into_kv_pair(self) -> ConsulResult<KvPair> {
+        let value = match self.value {
+            Some(value_base64) => {
+                BASE64_STANDARD
+                    .decode(value_base64)
+                    .map_err(|error| ConsulError::Request {
+                        message: format!("failed to decode value of key `{}`: {error}", self.key),
+                    })?
+            }
+            None => Vec::new(),
+        };
+        Ok(KvPair {
+            key: self.key,
+            value,
+            modify_index: self.modify_index,
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct TxnKvOp<'a> {
+    verb: &'static str,
+    key: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    index: u64,
+}
+
+#[derive(Serialize)]
+struct TxnOp<'a> {
+    #[serde(rename = "KV")]
+    kv: TxnKvOp<'a>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TxnResponse {
+    #[serde(default)]
+    results: Option<Vec<TxnResult>>,
+}
+
+#[derive(Deserialize)]
+struct TxnResult {
+    #[serde(rename = "KV")]
+    kv: KvEntry,
+}
+
+/// A thin client for the KV store and transaction endpoints of the Consul HTTP API.
+#[derive(Clone)]
+pub(crate) struct ConsulKvClient {
+    http_client: reqwest::Client,
+    base_url: String,
+    token_opt: Option<String>,
+}
+
+impl ConsulKvClient {
+    /// Creates a client for the Consul agent designated by a URI of the form
+    /// `consul://{host}:{port}[/{key_prefix}]`.
+    pub fn new(
+        uri: &Uri,
+        token_opt: Option<String>,
+        request_timeout: Duration,
+    ) -> Result<Self, MetastoreResolverError> {
+        let (authority, _) = split_consul_uri(uri)?;
+        let http_client = reqwest::Client::builder()
+            .timeout(request_timeout)
+            .build()
+            .map_err(|error| {
+                MetastoreResolverError::InvalidConfig(format!(
+                    "failed to build Consul HTTP client: {error}"
+                ))
+            })?;
+        Ok(Self {
+            http_client,
+            base_url: format!("http://{authority}"),
+            token_opt,
+        })
+    }
+
+    fn with_token(&self, request_builder: RequestBuilder) -> RequestBuilder {
+        if let Some(token) = &self.token_opt {
+            request_builder.header(CONSUL_TOKEN_HEADER, token)
+        } else {
+            request_builder
+        }
+    }
+
+    fn kv_url(&self, key: &str) -> String {
+        format!("{}/v1/kv/{key}", self.base_url)
+    }
+
+    pub async fn check_connectivity(&self) -> anyhow::Result<()> {
+        let url = format!("{}/v1/status/leader", self.base_url);
+        let response = self
+            .with_token(self.http_client.get(url))
+            .send()
+            .await
+            .context("failed to connect to Consul agent")?;
+        let leader: String = check_status(response)
+            .await?
+            .json()
+            .await
+            .context("failed to parse Consul leader")?;
+        anyhow::ensure!(!leader.is_empty(), "Consul cluster has no leader");
+        Ok(())
+    }
+
+    /// Returns the key-value pair stored under `key`, if any.
+    pub async fn get(&self, key: &str) -> ConsulResult<Option<KvPair>> {
+        let response = self
+            .with_token(self.http_client.get(self.kv_url(key)))
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let entries: Vec<KvEntry> = check_status(response).await?.json().await?;
+        entries
+            .into_iter()
+            .next()
+            .map(KvEntry::into_kv_pair)
+            .transpose()
+    }
+
+    /// Performs a blocking query: waits until the Raft index of `key` exceeds `last_index` or
+    /// `wait` elapses, then returns the key-value pair stored under `key` along with the new
+    /// Raft index to pass to the next call.
+    pub async fn watch(
+        &self,
+        key: &str,
+        last_index: u64,
+        wait: Duration,
+    ) -> ConsulResult<(Option<KvPair>, u64)> {
+        let wait_param = format!("{}s", wait.as_secs().max(1));
+        let request_builder = self
+            .http_client
+            .get(self.kv_url(key))
+            .query(&[("index", last_index.to_string()), ("wait", wait_param)])
+            // Consul adds up to `wait / 16` of jitter to blocking queries.
+            .timeout(wait + wait / 16 + Duration::from_secs(10));
+        let response = self.with_token(request_builder).send().await?;
+        let index = parse_consul_index(&response).unwrap_or(last_index);
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok((None, index));
+        }
+        let entries: Vec<KvEntry> = check_status(response).await?.json().await?;
+        let kv_pair_opt = entries
+            .into_iter()
+            .next()
+            .map(KvEntry::into_kv_pair)
+            .transpose()?;
+        Ok((kv_pair_opt, index))
+    }
+
+    /// Stores `value` under `key` if the key was not modified since `modify_index`, and returns
+    /// the new modify index of the key. A `modify_index` of 0 only succeeds if the key does not
+    /// exist.
+    pub async fn cas_put(&self, key: &str, value: &[u8], modify_index: u64) -> ConsulResult<u64> {
+        let txn_op = TxnOp {
+            kv: TxnKvOp {
+                verb: "cas",
+                key,
+                value: Some(BASE64_STANDARD.encode(value)),
+                index: modify_index,
+            },
+        };
+        let txn_response = self.execute_txn(key, txn_op).await?;
+        let new_modify_index = txn_response
+            .results
+            .and_then(|results| results.into_iter().next())
+            .map(|result| result.kv.modify_index)
+            .ok_or_else(|| ConsulError::Request {
+                message: "transaction response is missing results".to_string(),
+            })?;
+        Ok(new_modify_index)
+    }
+
+    /// Deletes `key` if the key was not modified since `modify_index`.
+    pub async fn cas_delete(&self, key: &str, modify_index: u64) -> ConsulResult<()> {
+        let txn_op = TxnOp {
+            kv: TxnKvOp {
+                verb: "delete-cas",
+                key,
+                value: None,
+                index: modify_index,
+            },
+        };
+        self.execute_txn(key, txn_op).await?;
+        Ok(())
+    }
+
+    /// Deletes `key` unconditionally. Deleting a key that does not exist is not an error.
+    pub async fn delete(&self, key: &str) -> ConsulResult<()> {
+        let response = self
+            .with_token(self.http_client.delete(self.kv_url(key)))
+            .send()
+            .await?;
+        check_status(response).await?;
+        Ok(())
+    }
+
+    async fn execute_txn(&self, key: &str, txn_op: TxnOp<'_>) -> ConsulResult<TxnResponse> {
+        let url = format!("{}/v1/txn", self.base_url);
+        let response = self
+            .with_token(self.http_client.put(url))
+            .json(&[txn_op])
+            .send()
+            .await?;
+        // Consul rolls back transactions whose operations fail with a `409 Conflict`.
+        if response.status() == StatusCode::CONFLICT {
+            return Err(ConsulError::CasFailed {
+                key: key.to_string(),
+            });
+        }
+        let txn_response = check_status(response).await?.json().await?;
+        Ok(txn_response)
+    }
+}
+
+async fn check_status(response: Response) -> ConsulResult<Response> {
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(response);
+    }
+    let message = response.text().await.unwrap_or_default();
+
+    if status == StatusCode::FORBIDDEN {
+        return Err(ConsulError::PermissionDenied { message });
+    }
+    Err(ConsulError::Request {
+        message: format!("unexpected status code {status}: {message}"),
+    })
+}
+
+fn parse_consul_index(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONSUL_INDEX_HEADER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Splits a Consul URI of the form `consul://{host}:{port}[/{key_prefix}]` into its authority
+/// and its key prefix, which defaults to `quickwit`.
+pub(crate) fn split_consul_uri(uri: &Uri) -> Result<(&str, &str), MetastoreResolverError> {
+    let Some(authority_and_path) = uri.as_str().strip_prefix("consul://") else {
+        return Err(MetastoreResolverError::InvalidUri(format!(
+            "expected Consul URI, got `{uri}`"
+        )));
+    };
+    let (authority, key_prefix) = authority_and_path
+        .split_once('/')
+        .unwrap_or((authority_and_path, ""));
+
+    if authority.is_empty() {
+        return Err(MetastoreResolverError::InvalidUri(format!(
+            "Consul URI `{uri}` is missing the address of the Consul agent"
+        )));
+    }
+    let key_prefix = key_prefix.trim_matches('/');
+
+    if key_prefix.is_empty() {
+        Ok((authority, "quickwit"))
+    } else {
+        Ok((authority, key_prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_consul_uri() {
+        let uri = Uri::for_test("consul://localhost:8500");
+        assert_eq!(
+            split_consul_uri(&uri).unwrap(),
+            ("localhost:8500", "quickwit")
+        );
+
+        let uri = Uri::for_test("consul://localhost:8500/");
+        assert_eq!(
+            split_consul_uri(&uri).unwrap(),
+            ("localhost:8500", "quickwit")
+        );
+
+        let uri = Uri::for_test("consul://consul.service:8500/clusters/prod/");
+        assert_eq!(
+            split_consul_uri(&uri).unwrap(),
+            ("consul.service:8500", "clusters/prod")
+        );
+        let uri = Uri::for_test("consul:///quickwit");
+        split_consul_uri(&uri).unwrap_err();
+    }
+
+    #[test]
+    fn test_kv_entry_into_kv_pair() {
+        let entries_json = r#"[
+            {
+                "LockIndex": 0,
+                "Key": "quickwit/indices/my-index",
+                "Flags": 0,
+                "Value": "eyJmb28iOiJiYXIifQ==",
+                "CreateIndex": 12,
+                "ModifyIndex": 42
+            }
+        ]"#;
+        let entries: Vec<KvEntry> = serde_json::from_str(entries_json).unwrap();
+        let kv_pair = entries.into_iter().next().unwrap().into_kv_pair().unwrap();
+        assert_eq!(
+            kv_pair,
+            KvPair {
+                key: "quickwit/indices/my-index".to_string(),
+                value: br#"{"foo":"bar"}"#.to_vec(),
+                modify_index: 42,
+            }
+        );
+
+        let entries_json = r#"[{"Key": "empty", "Value": null, "ModifyIndex": 7}]"#;
+        let entries: Vec<KvEntry> = serde_json::from_str(entries_json).unwrap();
+        let kv_pair = entries.into_iter().next().unwrap().into_kv_pair().unwrap();
+        assert!(kv_pair.value.is_empty());
+        assert_eq!(kv_pair.modify_index, 7);
+    }
+
+    #[test]
+    fn test_txn_op_serialization() {
+        let txn_op = TxnOp {
+            kv: TxnKvOp {
+                verb: "cas",
+                key: "quickwit/manifest.json",
+                value: Some(BASE64_STANDARD.encode(b"{}")),
+                index: 0,
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&[txn_op]).unwrap(),
+            r#"[{"KV":{"Verb":"cas","Key":"quickwit/manifest.json","Value":"e30=","Index":0}}]"#
+        );
+        let txn_response_json = r#"{
+            "Results": [{"KV": {"Key": "quickwit/manifest.json", "ModifyIndex": 43}}],
+            "Errors": null
+        }"#;
+        let txn_response: TxnResponse = serde_json::from_str(txn_response_json).unwrap();
+        assert_eq!(txn_response.results.unwrap()[0].kv.modify_index, 43);
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use quickwit_storage::{
+    BulkDeleteError, OwnedBytes, PutPayload, SendableAsync, Storage, StorageError,
+    StorageErrorKind, StorageResult,
+};
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+use super::consul_kv_client::{ConsulError, ConsulKvClient};
+use crate::metastore::file_backed::METASTORE_FILE_NAME;
+
+/// A [`Storage`] backed by Consul KV, on top of which the Consul metastore runs a
+/// [`FileBackedMetastore`](crate::FileBackedMetastore).
+///
+/// The metadata of the index `{index_id}` is stored under the key
+/// `{key_prefix}/indices/{index_id}`, every other file under `{key_prefix}/{path}`.
+pub(crate) struct ConsulKvStorage {
+    uri: Uri,
+    client: ConsulKvClient,
+    key_prefix: String,
+    // Modify indexes of the keys last read or written by this storage. Writes are
+    // compare-and-swap operations against these indexes so that a write fails, instead of
+    // silently overwriting the key, if another node modified the key in the meantime.
+    modify_indexes: Mutex<HashMap<String, u64>>,
+}
+
+impl fmt::Debug for ConsulKvStorage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConsulKvStorage")
+            .field("uri", &self.uri)
+            .field("key_prefix", &self.key_prefix)
+            .finish()
+    }
+}
+
+impl ConsulKvStorage {
+    pub fn new(uri: Uri, client: ConsulKvClient, key_prefix: String) -> Self {
+        Self {
+            uri,
+            client,
+            key_prefix,
+            modify_indexes: Mutex::default(),
+        }
+    }
+
+    /// Returns the key under which the metadata of the index `index_id` is stored.
+    pub fn index_key(&self, index_id: &str) -> String {
+        format!("{}/indices/{index_id}", self.key_prefix)
+    }
+
+    fn key(&self, path: &Path) -> String {
+        let mut components = path.iter();
+
+        if let (Some(index_id), Some(file_name), None) =
+            (components.next(), components.next(), components.next())
+        {
+            if file_name == METASTORE_FILE_NAME {
+                return self.index_key(&index_id.to_string_lossy());
+            }
+        }
+        format!("{}/{}", self.key_prefix, path.display())
+    }
+
+    fn modify_index(&self, key: &str) -> u64 {
+        self.modify_indexes
+            .lock()
+            .unwrap()
+            .get(key)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn set_modify_index(&self, key: String, modify_index: u64) {
+        self.modify_indexes
+            .lock()
+            .unwrap()
+            .insert(key, modify_index);
+    }
+
+    fn remove_modify_index(&self, key: &str) -> Option<u64> {
+        self.modify_indexes.lock().unwrap().remove(key)
+    }
+
+    async fn get_value(&self, path: &Path) -> StorageResult<Vec<u8>> {
+        let key = self.key(path);
+        let kv_pair = self
+            .client
+            .get(&key)
+            .await
+            .map_err(convert_error)?
+            .ok_or_else(|| {
+                StorageErrorKind::NotFound.with_error(anyhow::anyhow!("key `{key}` not found"))
+            })?;
+        self.set_modify_index(key, kv_pair.modify_index);
+        Ok(kv_pair.value)
+    }
+
+    async fn delete_key(&self, path: &Path) -> StorageResult<()> {
+        let key = self.key(path);
+
+        if let Some(modify_index) = self.remove_modify_index(&key) {
+            self.client.cas_delete(&key, modify_index).await
+        } else {
+            self.client.delete(&key).await
+        }
+        .map_err(convert_error)
+    }
+}
+
+fn convert_error(consul_error: ConsulError) -> StorageError {
+    match consul_error {
+        ConsulError::CasFailed { .. } => StorageErrorKind::Internal.with_error(consul_error),
+        ConsulError::PermissionDenied { .. } => {
+            StorageErrorKind::Unauthorized.with_error(consul_error)
+        }
+        ConsulError::Request { .. } => StorageErrorKind::Service.with_error(consul_error),
+    }
+}
+
+#[async_trait]
+impl Storage for ConsulKvStorage {
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.client.check_connectivity().await
+    }
+
+    async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()> {
+        let value = payload.read_all().await?;
+        let key = self.key(path);
+        let modify_index = self.modify_index(&key);
+        let new_modify_index = self
+            .client
+            .cas_put(&key, &value, modify_index)
+            .await
+            .map_err(|consul_error| {
+                // The key was modified by another node: we forget its modify index so that the
+                // next write only succeeds after the key has been read again.
+                if matches!(consul_error, ConsulError::CasFailed { .. }) {
+                    self.remove_modify_index(&key);
+                }
+                convert_error(consul_error)
+            })?;
+        self.set_modify_index(key, new_modify_index);
+        Ok(())
+    }
+
+    async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
+        let value = self.get_value(path).await?;
+        output.write_all(&value).await?;
+        output.flush().await?;
+        Ok(())
+    }
+
+    async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
+        let value = self.get_value(path).await?;
+
+        if range.start > range.end || range.end > value.len() {
+            return Err(StorageErrorKind::Internal.with_error(anyhow::anyhow!(
+                "range {range:?} is out of bounds for file `{}` of {} bytes",
+                path.display(),
+                value.len()
+            )));
+        }
+        Ok(OwnedBytes::new(value).slice(range))
+    }
+
+    async fn get_slice_stream(
+        &self,
+        path: &Path,
+        range: Range<usize>,
+    ) -> StorageResult<Box<dyn AsyncRead + Send + Unpin>> {
+        let bytes = self.get_slice(path, range).await?;
+        Ok(Box::new(Cursor::new(bytes)))
+    }
+
+    async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
+        let value = self.get_value(path).await?;
+        Ok(OwnedBytes::new(value))
+    }
+
+    async fn delete(&self, path: &Path) -> StorageResult<()> {
+        self.delete_key(path).await
+    }
+
+    async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
+        let mut successes = Vec::with_capacity(paths.len());
+
+        for (path_idx, path) in paths.iter().enumerate() {
+            if let Err(storage_error) = self.delete_key(path).await {
+                let unattempted = paths[path_idx..]
+                    .iter()
+                    .map(|path| path.to_path_buf())
+                    .collect();
+                return Err(BulkDeleteError {
+                    error: Some(storage_error),
+                    successes,
+                    unattempted,
+                    ..Default::default()
+                });
+            }
+            successes.push(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
+        let value = self.get_value(path).await?;
+        Ok(value.len() as u64)
+    }
+
+    fn uri(&self) -> &Uri {
+        &self.uri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+
+    fn consul_kv_storage_for_test(uri: &str, key_prefix: &str) -> ConsulKvStorage {
+        let uri = Uri::for_test(uri);
+        let client = ConsulKvClient::new(&uri, None, Duration::from_secs(1)).unwrap();
+        ConsulKvStorage::new(uri, client, key_prefix.to_string())
+    }
+
+    #[test]
+    fn test_consul_kv_storage_key() {
+        let storage = consul_kv_storage_for_test("consul://localhost:8500", "quickwit");
+        assert_eq!(
+            storage.key(&PathBuf::from("my-index").join(METASTORE_FILE_NAME)),
+            "quickwit/indices/my-index"
+        );
+        assert_eq!(storage.index_key("my-index"), "quickwit/indices/my-index");
+        assert_eq!(
+            storage.key(Path::new("manifest.json")),
+            "quickwit/manifest.json"
+        );
+        assert_eq!(
+            storage.key(Path::new("my-index/other.json")),
+            "quickwit/my-index/other.json"
+        );
+    }
+
+    #[test]
+    fn test_consul_kv_storage_modify_indexes() {
+        let storage = consul_kv_storage_for_test("consul://localhost:8500", "quickwit");
+        assert_eq!(storage.modify_index("quickwit/manifest.json"), 0);
+
+        storage.set_modify_index("quickwit/manifest.json".to_string(), 42);
+        assert_eq!(storage.modify_index("quickwit/manifest.json"), 42);
+
+        assert_eq!(
+            storage.remove_modify_index("quickwit/manifest.json"),
+            Some(42)
+        );
+        assert_eq!(storage.modify_index("quickwit/manifest.json"), 0);
+    }
+}
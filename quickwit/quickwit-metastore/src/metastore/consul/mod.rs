@@ -0,0 +1,547 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod consul_kv_client;
+mod consul_kv_storage;
+mod factory;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use quickwit_config::ConsulMetastoreConfig;
+use quickwit_proto::metastore::{
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateIndexAliasRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
+    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    EntityKind, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
+    GetIndexTemplateRequest, GetIndexTemplateResponse, GetSourceCheckpointRequest,
+    GetSourceCheckpointResponse, IndexMetadataRequest, IndexMetadataResponse,
+    IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListDeleteTasksRequest, ListDeleteTasksResponse,
+    ListIndexAliasesRequest, ListIndexAliasesResponse, ListIndexTemplatesRequest,
+    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
+    ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
+    ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
+    MetastoreService, MetastoreServiceStream, OpenShardsRequest, OpenShardsResponse,
+    PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest,
+    UpdateIndexAliasRequest, UpdateIndexAliasResponse, UpdateIndexRequest,
+    UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
+};
+
+use self::consul_kv_client::{split_consul_uri, ConsulKvClient};
+use self::consul_kv_storage::ConsulKvStorage;
+pub use self::factory::ConsulMetastoreFactory;
+use crate::metastore::file_backed::file_backed_index::FileBackedIndex;
+use crate::{
+    FileBackedMetastore, ListSplitsQuery, MetastoreResolverError, MetastoreServiceExt, Split,
+};
+
+/// The splits of an index returned by [`ConsulMetastore::watch_splits`].
+#[derive(Debug)]
+pub struct SplitsWatchUpdate {
+    /// The splits of the index.
+    pub splits: Vec<Split>,
+    /// The Raft index of the Consul cluster at the time the splits were read. It must be passed
+    /// to the next call to [`ConsulMetastore::watch_splits`].
+    pub consul_index: u64,
+}
+
+/// A metastore that stores the index metadata in HashiCorp Consul KV, for instance
+/// `consul://localhost:8500`.
+///
+/// The metadata of each index is stored as a JSON document under the key
+/// `quickwit/indices/{index_id}`. The key prefix defaults to `quickwit` and can be set with the
+/// path of the URI: `consul://localhost:8500/my-cluster`. Every write is a compare-and-swap
+/// operation, so a write fails instead of overwriting the changes made concurrently by another
+/// node.
+///
+/// Note that Consul limits the size of KV values to 512KiB by default (see the
+/// `kv_max_value_size` option of the Consul agent), which caps the number of splits per index.
+#[derive(Clone)]
+pub struct ConsulMetastore {
+    uri: Uri,
+    metastore: FileBackedMetastore,
+    storage: Arc<ConsulKvStorage>,
+    client: ConsulKvClient,
+    watch_timeout: Duration,
+}
+
+impl fmt::Debug for ConsulMetastore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConsulMetastore")
+            .field("uri", &self.uri)
+            .finish()
+    }
+}
+
+impl ConsulMetastore {
+    /// Creates a metastore backed by the Consul agent designated by `uri`.
+    pub async fn new(
+        consul_metastore_config: &ConsulMetastoreConfig,
+        uri: &Uri,
+    ) -> Result<Self, MetastoreResolverError> {
+        let (_, key_prefix) = split_consul_uri(uri)?;
+        let request_timeout = consul_metastore_config
+            .request_timeout()
+            .map_err(|error| MetastoreResolverError::InvalidConfig(error.to_string()))?;
+        let watch_timeout = consul_metastore_config
+            .watch_timeout()
+            .map_err(|error| MetastoreResolverError::InvalidConfig(error.to_string()))?;
+        let client = ConsulKvClient::new(uri, consul_metastore_config.token(), request_timeout)?;
+        let storage = Arc::new(ConsulKvStorage::new(
+            uri.clone(),
+            client.clone(),
+            key_prefix.to_string(),
+        ));
+        let metastore = FileBackedMetastore::try_new(storage.clone(), None).await?;
+        Ok(Self {
+            uri: uri.clone(),
+            metastore,
+            storage,
+            client,
+            watch_timeout,
+        })
+    }
+
+    /// Waits until the metadata of the index `index_id` changes, or the watch timeout of the
+    /// metastore config elapses, then returns the splits of the index.
+    ///
+    /// Pass 0 as `last_consul_index` to return the splits immediately, and then the
+    /// [`SplitsWatchUpdate::consul_index`] of the previous call to wait for the next change.
+    pub async fn watch_splits(
+        &self,
+        index_id: &str,
+        last_consul_index: u64,
+    ) -> MetastoreResult<SplitsWatchUpdate> {
+        let index_key = self.storage.index_key(index_id);
+        let (kv_pair_opt, consul_index) = self
+            .client
+            .watch(&index_key, last_consul_index, self.watch_timeout)
+            .await
+            .map_err(|consul_error| MetastoreError::Connection {
+                message: consul_error.to_string(),
+            })?;
+        let Some(kv_pair) = kv_pair_opt else {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_id.to_string(),
+            }));
+        };
+        let index: FileBackedIndex = serde_utils::from_json_bytes(&kv_pair.value)?;
+        let list_splits_query = ListSplitsQuery::for_index(index.index_uid().clone());
+        let splits = index.list_splits(&list_splits_query)?;
+        Ok(SplitsWatchUpdate {
+            splits,
+            consul_index,
+        })
+    }
+}
+
+#[async_trait]
+impl MetastoreService for ConsulMetastore {
+    fn endpoints(&self) -> Vec<Uri> {
+        vec![self.uri.clone()]
+    }
+
+    async fn check_connectivity(&mut self) -> anyhow::Result<()> {
+        self.metastore.check_connectivity().await
+    }
+
+    async fn create_index(
+        &mut self,
+        request: CreateIndexRequest,
+    ) -> MetastoreResult<CreateIndexResponse> {
+        self.metastore.create_index(request).await
+    }
+
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        self.metastore.update_index(request).await
+    }
+
+    async fn delete_index(
+        &mut self,
+        request: DeleteIndexRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_index(request).await
+    }
+
+    async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
+        self.metastore.add_source(request).await
+    }
+
+    async fn toggle_source(
+        &mut self,
+        request: ToggleSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.toggle_source(request).await
+    }
+
+    async fn delete_source(
+        &mut self,
+        request: DeleteSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_source(request).await
+    }
+
+    async fn index_metadata(
+        &mut self,
+        request: IndexMetadataRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        self.metastore.index_metadata(request).await
+    }
+
+    async fn indexes_metadata(
+        &mut self,
+        request: IndexesMetadataRequest,
+    ) -> MetastoreResult<IndexesMetadataResponse> {
+        self.metastore.indexes_metadata(request).await
+    }
+
+    async fn list_indexes_metadata(
+        &mut self,
+        request: ListIndexesMetadataRequest,
+    ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        self.metastore.list_indexes_metadata(request).await
+    }
+
+    async fn stage_splits(
+        &mut self,
+        request: StageSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.stage_splits(request).await
+    }
+
+    async fn publish_splits(
+        &mut self,
+        request: PublishSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.publish_splits(request).await
+    }
+
+    async fn list_splits(
+        &mut self,
+        request: ListSplitsRequest,
+    ) -> MetastoreResult<MetastoreServiceStream<ListSplitsResponse>> {
+        self.metastore.list_splits(request).await
+    }
+
+    async fn list_stale_splits(
+        &mut self,
+        request: ListStaleSplitsRequest,
+    ) -> MetastoreResult<ListSplitsResponse> {
+        self.metastore.list_stale_splits(request).await
+    }
+
+    async fn mark_splits_for_deletion(
+        &mut self,
+        request: MarkSplitsForDeletionRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.mark_splits_for_deletion(request).await
+    }
+
+    async fn delete_splits(
+        &mut self,
+        request: DeleteSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_splits(request).await
+    }
+
+    async fn reset_source_checkpoint(
+        &mut self,
+        request: ResetSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.reset_source_checkpoint(request).await
+    }
+
+    // Delete tasks API
+
+    async fn create_delete_task(
+        &mut self,
+        delete_query: DeleteQuery,
+    ) -> MetastoreResult<DeleteTask> {
+        self.metastore.create_delete_task(delete_query).await
+    }
+
+    async fn last_delete_opstamp(
+        &mut self,
+        request: LastDeleteOpstampRequest,
+    ) -> MetastoreResult<LastDeleteOpstampResponse> {
+        self.metastore.last_delete_opstamp(request).await
+    }
+
+    async fn update_splits_delete_opstamp(
+        &mut self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        self.metastore.update_splits_delete_opstamp(request).await
+    }
+
+    async fn list_delete_tasks(
+        &mut self,
+        request: ListDeleteTasksRequest,
+    ) -> MetastoreResult<ListDeleteTasksResponse> {
+        self.metastore.list_delete_tasks(request).await
+    }
+
+    // Shard API
+
+    async fn open_shards(
+        &mut self,
+        request: OpenShardsRequest,
+    ) -> MetastoreResult<OpenShardsResponse> {
+        self.metastore.open_shards(request).await
+    }
+
+    async fn acquire_shards(
+        &mut self,
+        request: AcquireShardsRequest,
+    ) -> MetastoreResult<AcquireShardsResponse> {
+        self.metastore.acquire_shards(request).await
+    }
+
+    async fn list_shards(
+        &mut self,
+        request: ListShardsRequest,
+    ) -> MetastoreResult<ListShardsResponse> {
+        self.metastore.list_shards(request).await
+    }
+
+    async fn delete_shards(
+        &mut self,
+        request: DeleteShardsRequest,
+    ) -> MetastoreResult<DeleteShardsResponse> {
+        self.metastore.delete_shards(request).await
+    }
+
+    // Index Template API
+
+    async fn create_index_template(
+        &mut self,
+        request: CreateIndexTemplateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_index_template(request).await
+    }
+
+    async fn get_index_template(
+        &mut self,
+        request: GetIndexTemplateRequest,
+    ) -> MetastoreResult<GetIndexTemplateResponse> {
+        self.metastore.get_index_template(request).await
+    }
+
+    async fn find_index_template_matches(
+        &mut self,
+        request: FindIndexTemplateMatchesRequest,
+    ) -> MetastoreResult<FindIndexTemplateMatchesResponse> {
+        self.metastore.find_index_template_matches(request).await
+    }
+
+    async fn list_index_templates(
+        &mut self,
+        request: ListIndexTemplatesRequest,
+    ) -> MetastoreResult<ListIndexTemplatesResponse> {
+        self.metastore.list_index_templates(request).await
+    }
+
+    async fn delete_index_templates(
+        &mut self,
+        request: DeleteIndexTemplatesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_index_templates(request).await
+    }
+
+    // Index Alias API
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_index_alias(request).await
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        self.metastore.list_index_aliases(request).await
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        self.metastore.update_index_alias(request).await
+    }
+
+    // Source checkpoint API
+
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        self.metastore.get_source_checkpoint(request).await
+    }
+
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.update_source_checkpoint(request).await
+    }
+}
+
+impl MetastoreServiceExt for ConsulMetastore {}
+
+#[cfg(test)]
+#[async_trait]
+impl crate::tests::DefaultForTest for ConsulMetastore {
+    async fn default_for_test() -> Self {
+        use std::str::FromStr;
+
+        use quickwit_common::rand::append_random_suffix;
+
+        dotenv::dotenv().ok();
+        let consul_uri = std::env::var("QW_TEST_CONSUL_URI")
+            .expect("environment variable `QW_TEST_CONSUL_URI` should be set");
+        // Each test writes under its own key prefix so that tests running concurrently do not
+        // conflict on the manifest key.
+        let key_prefix = append_random_suffix("quickwit-test");
+        let uri = Uri::from_str(&format!(
+            "{}/{key_prefix}",
+            consul_uri.trim_end_matches('/')
+        ))
+        .expect("environment variable `QW_TEST_CONSUL_URI` should be a valid URI");
+        ConsulMetastore::new(&ConsulMetastoreConfig::default(), &uri)
+            .await
+            .expect("failed to initialize Consul metastore test")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::IndexConfig;
+    use quickwit_proto::ingest::Shard;
+    use quickwit_proto::types::{IndexUid, SourceId};
+
+    use super::*;
+    use crate::tests::shard::ReadWriteShardsForTest;
+    use crate::tests::DefaultForTest;
+    use crate::{
+        metastore_test_suite, CreateIndexRequestExt, SplitMetadata, StageSplitsRequestExt,
+    };
+
+    #[async_trait]
+    impl ReadWriteShardsForTest for ConsulMetastore {
+        async fn insert_shards(
+            &mut self,
+            index_uid: &IndexUid,
+            source_id: &SourceId,
+            shards: Vec<Shard>,
+        ) {
+            self.metastore
+                .insert_shards(index_uid, source_id, shards)
+                .await;
+        }
+
+        async fn list_all_shards(&self, index_uid: &IndexUid, source_id: &SourceId) -> Vec<Shard> {
+            self.metastore.list_all_shards(index_uid, source_id).await
+        }
+    }
+
+    metastore_test_suite!(crate::ConsulMetastore);
+
+    #[tokio::test]
+    async fn test_consul_metastore_watch_splits() {
+        let mut metastore = ConsulMetastore::default_for_test().await;
+
+        let index_id = "test-consul-watch-splits";
+        let index_uri = format!("ram:///indexes/{index_id}");
+        let index_config = IndexConfig::for_test(index_id, &index_uri);
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+
+        let watch_update = metastore.watch_splits(index_id, 0).await.unwrap();
+        assert!(watch_update.splits.is_empty());
+        assert!(watch_update.consul_index > 0);
+
+        let watch_metastore = metastore.clone();
+        let watch_handle = tokio::spawn(async move {
+            watch_metastore
+                .watch_splits(index_id, watch_update.consul_index)
+                .await
+        });
+        let split_metadata = SplitMetadata {
+            split_id: "split-1".to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request =
+            StageSplitsRequest::try_from_split_metadata(index_uid, &split_metadata).unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let watch_update = watch_handle.await.unwrap().unwrap();
+        assert_eq!(watch_update.splits.len(), 1);
+        assert_eq!(watch_update.splits[0].split_id(), "split-1");
+
+        let error = metastore
+            .watch_splits("test-consul-watch-splits-missing", 0)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, MetastoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_consul_metastore_concurrent_writers() {
+        let mut metastore = ConsulMetastore::default_for_test().await;
+        // A second metastore instance sharing the same key prefix, as if run by another node.
+        let mut other_metastore =
+            ConsulMetastore::new(&ConsulMetastoreConfig::default(), &metastore.uri)
+                .await
+                .unwrap();
+
+        let index_config =
+            IndexConfig::for_test("test-consul-cas", "ram:///indexes/test-consul-cas");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        metastore
+            .create_index(create_index_request.clone())
+            .await
+            .unwrap();
+
+        // The other instance read the manifest before the index was created, so its
+        // compare-and-swap write of the manifest must fail.
+        other_metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap_err();
+    }
+}
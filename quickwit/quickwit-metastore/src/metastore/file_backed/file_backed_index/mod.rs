@@ -30,7 +30,11 @@ use std::ops::Bound;
 
 use itertools::Itertools;
 use quickwit_common::pretty::PrettySample;
-use quickwit_config::{RetentionPolicy, SearchSettings, SourceConfig, INGEST_V2_SOURCE_ID};
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    DocMapping, IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig,
+    INGEST_V2_SOURCE_ID,
+};
 use quickwit_proto::metastore::{
     AcquireShardsRequest, AcquireShardsResponse, DeleteQuery, DeleteShardsRequest,
     DeleteShardsResponse, DeleteTask, EntityKind, ListShardsSubrequest, ListShardsSubresponse,
@@ -44,8 +48,10 @@ use time::OffsetDateTime;
 use tracing::{info, warn};
 
 use super::MutationOccurred;
-use crate::checkpoint::IndexCheckpointDelta;
-use crate::{split_tag_filter, IndexMetadata, ListSplitsQuery, Split, SplitMetadata, SplitState};
+use crate::checkpoint::{IndexCheckpointDelta, SourceCheckpoint};
+use crate::{
+    split_tag_filter, IndexMetadata, IndexState, ListSplitsQuery, Split, SplitMetadata, SplitState,
+};
 
 /// A `FileBackedIndex` object carries an index metadata and its split metadata.
 // This struct is meant to be used only within the [`FileBackedMetastore`]. The public visibility is
@@ -223,6 +229,26 @@ impl FileBackedIndex {
         self.metadata.set_search_settings(search_settings)
     }
 
+    /// Upgrades the doc mapping in the index config, returning whether a mutation occurred.
+    pub fn set_doc_mapping(&mut self, doc_mapping: DocMapping) -> MetastoreResult<bool> {
+        self.metadata.set_doc_mapping(doc_mapping)
+    }
+
+    /// Freezes or unfreezes the index, returning whether a mutation occurred.
+    pub fn set_index_state(&mut self, index_state: IndexState) -> bool {
+        self.metadata.set_index_state(index_state)
+    }
+
+    /// Replaces the indexing settings in the index config, returning whether a mutation occurred.
+    pub fn set_indexing_settings(&mut self, indexing_settings: IndexingSettings) -> bool {
+        self.metadata.set_indexing_settings(indexing_settings)
+    }
+
+    /// Points the index to a new storage URI, returning whether a mutation occurred.
+    pub fn set_index_uri(&mut self, index_uri: Uri) -> bool {
+        self.metadata.set_index_uri(index_uri)
+    }
+
     /// Stages a single split.
     ///
     /// If a split already exists and is in the [SplitState::Staged] state,
@@ -511,6 +537,27 @@ impl FileBackedIndex {
         Ok(self.metadata.checkpoint.reset_source(source_id))
     }
 
+    /// Returns the checkpoint of a source. Sources without a checkpoint yield an empty one.
+    pub(crate) fn source_checkpoint(&self, source_id: &str) -> SourceCheckpoint {
+        self.metadata
+            .checkpoint
+            .source_checkpoint(source_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Overwrites the checkpoint of a source. Returns whether a mutation occurred.
+    pub(crate) fn update_source_checkpoint(
+        &mut self,
+        source_id: &str,
+        source_checkpoint: SourceCheckpoint,
+    ) -> MetastoreResult<bool> {
+        Ok(self
+            .metadata
+            .checkpoint
+            .set_source_checkpoint(source_id, source_checkpoint))
+    }
+
     /// Creates [`DeleteTask`] from a [`DeleteQuery`].
     pub(crate) fn create_delete_task(
         &mut self,
@@ -677,6 +724,12 @@ fn split_query_predicate(split: &&Split, query: &ListSplitsQuery) -> bool {
         return false;
     }
 
+    if let Some(field_name) = &query.field_name {
+        if !split.split_metadata.field_names.contains(field_name) {
+            return false;
+        }
+    }
+
     if !query
         .delete_opstamp
         .contains(&split.split_metadata.delete_opstamp)
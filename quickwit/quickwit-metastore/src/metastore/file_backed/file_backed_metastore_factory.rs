@@ -18,20 +18,27 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
 use quickwit_common::uri::Uri;
 use quickwit_config::{MetastoreBackend, MetastoreConfig};
 use quickwit_proto::metastore::{MetastoreError, MetastoreServiceClient};
-use quickwit_storage::{StorageResolver, StorageResolverError};
+use quickwit_storage::{
+    Storage, StorageError, StorageErrorKind, StorageResolver, StorageResolverError,
+};
 use regex::Regex;
 use tokio::sync::Mutex;
 use tracing::debug;
 
+use super::manifest::{load_manifest_schema_version, MANIFEST_FILE_NAME, MANIFEST_SCHEMA_VERSION};
+use crate::metastore_factory::check_schema_version;
 use crate::{FileBackedMetastore, MetastoreFactory, MetastoreResolverError};
 
 /// A file-backed metastore factory.
@@ -67,6 +74,47 @@ fn extract_polling_interval_from_uri(uri: &str) -> (String, Option<Duration>) {
     }
 }
 
+/// Returns whether the storage error was caused by a refused connection, which can happen when
+/// the metastore is located on a network filesystem.
+fn is_connection_refused(storage_error: &StorageError) -> bool {
+    let mut source_opt: Option<&(dyn Error + 'static)> = storage_error.source();
+
+    while let Some(source) = source_opt {
+        if let Some(io_error) = source.downcast_ref::<io::Error>() {
+            if io_error.kind() == io::ErrorKind::ConnectionRefused {
+                return true;
+            }
+        }
+        source_opt = source.source();
+    }
+    false
+}
+
+/// Converts an error that occurred while probing the metastore storage into a
+/// [`MetastoreResolverError`].
+fn convert_storage_err(
+    uri: &Uri,
+    elapsed: Duration,
+    storage_error: StorageError,
+) -> MetastoreResolverError {
+    if is_connection_refused(&storage_error) {
+        return MetastoreResolverError::ConnectionRefused(uri.clone());
+    }
+    match storage_error.kind() {
+        StorageErrorKind::Unauthorized | StorageErrorKind::PermissionDenied => {
+            MetastoreResolverError::InsufficientPermissions(storage_error.to_string())
+        }
+        StorageErrorKind::Timeout => MetastoreResolverError::Timeout {
+            uri: uri.clone(),
+            elapsed,
+        },
+        _ => MetastoreResolverError::Initialization(MetastoreError::Internal {
+            message: format!("failed to access metastore storage `{uri}`"),
+            cause: storage_error.to_string(),
+        }),
+    }
+}
+
 impl FileBackedMetastoreFactory {
     /// Creates a new [`FileBackedMetastoreFactory`].
     pub fn new(storage_resolver: StorageResolver) -> Self {
@@ -76,6 +124,29 @@ impl FileBackedMetastoreFactory {
         }
     }
 
+    async fn resolve_storage(&self, uri: &Uri) -> Result<Arc<dyn Storage>, MetastoreResolverError> {
+        self.storage_resolver
+            .resolve(uri)
+            .await
+            .map_err(|err| match err {
+                StorageResolverError::InvalidConfig(message) => {
+                    MetastoreResolverError::InvalidConfig(message)
+                }
+                StorageResolverError::InvalidUri(message) => {
+                    MetastoreResolverError::InvalidUri(message)
+                }
+                StorageResolverError::UnsupportedBackend(message) => {
+                    MetastoreResolverError::UnsupportedBackend(message)
+                }
+                StorageResolverError::FailedToOpenStorage { kind, message } => {
+                    MetastoreResolverError::Initialization(MetastoreError::Internal {
+                        message: format!("failed to open metastore file `{uri}`"),
+                        cause: format!("StorageError {kind:?}: {message}"),
+                    })
+                }
+            })
+    }
+
     async fn get_from_cache(&self, uri: &Uri) -> Option<MetastoreServiceClient> {
         self.cache.lock().await.get(uri).cloned()
     }
@@ -110,36 +181,20 @@ impl MetastoreFactory for FileBackedMetastoreFactory {
         _metastore_config: &MetastoreConfig,
         uri: &Uri,
     ) -> Result<MetastoreServiceClient, MetastoreResolverError> {
-        let (uri_stripped, polling_interval_opt) = extract_polling_interval_from_uri(uri.as_str());
-        let uri = Uri::from_str(&uri_stripped).map_err(|_| {
-            MetastoreResolverError::InvalidConfig(format!("invalid URI: `{uri_stripped}`"))
-        })?;
+        let (uri, polling_interval_opt) = parse_metastore_uri(uri)?;
         if let Some(metastore) = self.get_from_cache(&uri).await {
             debug!("using metastore from cache");
             return Ok(metastore);
         }
         debug!("metastore not found in cache");
-        let storage = self
-            .storage_resolver
-            .resolve(&uri)
+        let storage = self.resolve_storage(&uri).await?;
+        // Probe the storage first to surface connectivity and permission issues with a
+        // meaningful error rather than a generic initialization failure.
+        let now = Instant::now();
+        storage
+            .exists(Path::new(MANIFEST_FILE_NAME))
             .await
-            .map_err(|err| match err {
-                StorageResolverError::InvalidConfig(message) => {
-                    MetastoreResolverError::InvalidConfig(message)
-                }
-                StorageResolverError::InvalidUri(message) => {
-                    MetastoreResolverError::InvalidUri(message)
-                }
-                StorageResolverError::UnsupportedBackend(message) => {
-                    MetastoreResolverError::UnsupportedBackend(message)
-                }
-                StorageResolverError::FailedToOpenStorage { kind, message } => {
-                    MetastoreResolverError::Initialization(MetastoreError::Internal {
-                        message: format!("failed to open metastore file `{uri}`"),
-                        cause: format!("StorageError {kind:?}: {message}"),
-                    })
-                }
-            })?;
+            .map_err(|storage_error| convert_storage_err(&uri, now.elapsed(), storage_error))?;
         let file_backed_metastore = FileBackedMetastore::try_new(storage, polling_interval_opt)
             .await
             .map(MetastoreServiceClient::new)
@@ -147,13 +202,75 @@ impl MetastoreFactory for FileBackedMetastoreFactory {
         let unique_metastore_for_uri = self.cache_metastore(uri, file_backed_metastore).await;
         Ok(unique_metastore_for_uri)
     }
+
+    /// Checks the schema version recorded in the manifest of the metastore.
+    async fn version_check(&self, uri: &Uri) -> Result<(), MetastoreResolverError> {
+        let (uri, _polling_interval_opt) = parse_metastore_uri(uri)?;
+        let storage = self.resolve_storage(&uri).await?;
+        let Some(schema_version) = load_manifest_schema_version(&*storage)
+            .await
+            .map_err(MetastoreResolverError::Initialization)?
+        else {
+            return Ok(());
+        };
+        check_schema_version(MANIFEST_SCHEMA_VERSION, schema_version)
+    }
+}
+
+/// Strips the polling interval fragment from the metastore URI.
+fn parse_metastore_uri(uri: &Uri) -> Result<(Uri, Option<Duration>), MetastoreResolverError> {
+    let (uri_stripped, polling_interval_opt) = extract_polling_interval_from_uri(uri.as_str());
+    let uri = Uri::from_str(&uri_stripped).map_err(|_| {
+        MetastoreResolverError::InvalidConfig(format!("invalid URI: `{uri_stripped}`"))
+    })?;
+    Ok((uri, polling_interval_opt))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io;
     use std::time::Duration;
 
-    use crate::metastore::file_backed::file_backed_metastore_factory::extract_polling_interval_from_uri;
+    use quickwit_common::uri::Uri;
+    use quickwit_storage::StorageErrorKind;
+
+    use crate::metastore::file_backed::file_backed_metastore_factory::{
+        convert_storage_err, extract_polling_interval_from_uri,
+    };
+    use crate::MetastoreResolverError;
+
+    #[test]
+    fn test_convert_storage_err() {
+        let uri = Uri::for_test("file:///mnt/nfs/metastore");
+
+        let io_error = io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused");
+        let storage_error = StorageErrorKind::Io.with_error(io_error);
+        let resolver_error = convert_storage_err(&uri, Duration::from_secs(1), storage_error);
+        assert!(
+            matches!(resolver_error, MetastoreResolverError::ConnectionRefused(refused_uri) if refused_uri == uri)
+        );
+
+        let storage_error = StorageErrorKind::Unauthorized.with_error(anyhow::anyhow!("denied"));
+        let resolver_error = convert_storage_err(&uri, Duration::from_secs(1), storage_error);
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::InsufficientPermissions(_)
+        ));
+
+        let storage_error = StorageErrorKind::Timeout.with_error(anyhow::anyhow!("timed out"));
+        let resolver_error = convert_storage_err(&uri, Duration::from_secs(3), storage_error);
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Timeout { elapsed, .. } if elapsed == Duration::from_secs(3)
+        ));
+
+        let storage_error = StorageErrorKind::Internal.with_error(anyhow::anyhow!("oops"));
+        let resolver_error = convert_storage_err(&uri, Duration::from_secs(1), storage_error);
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Initialization(_)
+        ));
+    }
 
     #[test]
     fn test_extract_polling_interval_from_uri() {
@@ -182,4 +299,78 @@ mod tests {
             )
         );
     }
+
+    #[tokio::test]
+    async fn test_file_backed_metastore_factory_version_check() {
+        use std::path::Path;
+
+        use quickwit_config::MetastoreConfig;
+        use quickwit_storage::StorageResolver;
+
+        use crate::metastore::file_backed::manifest::{
+            MANIFEST_FILE_NAME, MANIFEST_SCHEMA_VERSION,
+        };
+        use crate::{FileBackedMetastoreFactory, MetastoreFactory};
+
+        let storage_resolver = StorageResolver::for_test();
+        let metastore_factory = FileBackedMetastoreFactory::new(storage_resolver.clone());
+        let uri = Uri::for_test("ram:///metastore#polling_interval=10s");
+
+        // The metastore does not exist yet.
+        metastore_factory.version_check(&uri).await.unwrap();
+
+        metastore_factory
+            .resolve(&MetastoreConfig::File(Default::default()), &uri)
+            .await
+            .unwrap();
+        metastore_factory.version_check(&uri).await.unwrap();
+
+        let storage = storage_resolver
+            .resolve(&Uri::for_test("ram:///metastore"))
+            .await
+            .unwrap();
+
+        for (schema_version, expected_error_opt) in [
+            (
+                MANIFEST_SCHEMA_VERSION - 1,
+                Some(MetastoreResolverError::SchemaVersionMismatch {
+                    expected: MANIFEST_SCHEMA_VERSION,
+                    found: MANIFEST_SCHEMA_VERSION - 1,
+                }),
+            ),
+            (MANIFEST_SCHEMA_VERSION, None),
+            (
+                MANIFEST_SCHEMA_VERSION + 1,
+                Some(MetastoreResolverError::IncompatibleSchemaVersion {
+                    supported: MANIFEST_SCHEMA_VERSION,
+                    found: MANIFEST_SCHEMA_VERSION + 1,
+                }),
+            ),
+        ] {
+            let manifest_json = serde_json::json!({
+                "version": "0.8",
+                "schema_version": schema_version,
+                "indexes": {},
+                "templates": [],
+            });
+            storage
+                .put(
+                    Path::new(MANIFEST_FILE_NAME),
+                    Box::new(serde_json::to_vec(&manifest_json).unwrap()),
+                )
+                .await
+                .unwrap();
+            let version_check_res = metastore_factory.version_check(&uri).await;
+
+            match expected_error_opt {
+                Some(expected_error) => {
+                    assert_eq!(
+                        version_check_res.unwrap_err().to_string(),
+                        expected_error.to_string()
+                    );
+                }
+                None => version_check_res.unwrap(),
+            }
+        }
+    }
 }
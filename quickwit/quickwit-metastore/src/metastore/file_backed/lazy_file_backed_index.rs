@@ -20,9 +20,11 @@
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 
+use quickwit_common::runtimes::spawn_with_panic_handler;
 use quickwit_proto::metastore::{EntityKind, MetastoreError, MetastoreResult};
 use quickwit_proto::types::IndexId;
 use quickwit_storage::Storage;
+use tokio::runtime::Handle;
 use tokio::sync::{Mutex, OnceCell};
 use tracing::error;
 
@@ -128,14 +130,24 @@ fn spawn_index_metadata_polling_task(
     metastore_weak: Weak<Mutex<FileBackedIndex>>,
     polling_interval: Duration,
 ) {
-    tokio::task::spawn(async move {
-        let mut interval = tokio::time::interval(polling_interval);
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-        interval.tick().await; //< this is to prevent fetch right after the first population of the data.
+    spawn_with_panic_handler(
+        &Handle::current(),
+        "index_metadata_polling",
+        move || {
+            let storage = storage.clone();
+            let index_id = index_id.clone();
+            let metastore_weak = metastore_weak.clone();
+            async move {
+                let mut interval = tokio::time::interval(polling_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                interval.tick().await; //< this is to prevent fetch right after the first population of the data.
 
-        while let Some(metadata_mutex) = metastore_weak.upgrade() {
-            interval.tick().await;
-            poll_index_metadata_once(&*storage, &index_id, &metadata_mutex).await;
-        }
-    });
+                while let Some(metadata_mutex) = metastore_weak.upgrade() {
+                    interval.tick().await;
+                    poll_index_metadata_once(&*storage, &index_id, &metadata_mutex).await;
+                }
+            }
+        },
+        true,
+    );
 }
@@ -27,10 +27,18 @@ use quickwit_proto::metastore::{serde_utils, MetastoreError, MetastoreResult};
 use quickwit_proto::types::IndexId;
 use quickwit_storage::{OwnedBytes, Storage, StorageError, StorageErrorKind, StorageResult};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use tracing::error;
 
 pub(super) const MANIFEST_FILE_NAME: &str = "manifest.json";
 
+/// Version of the schema of the file-backed metastore. It must be incremented whenever the layout
+/// of the metastore files changes in a way that previous versions of Quickwit cannot handle.
+pub(super) const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Schema version of the manifests written before the schema version was recorded.
+const LEGACY_MANIFEST_SCHEMA_VERSION: u32 = 1;
+
 // The legacy manifest file was deprecated in 0.8.0, we can drop support for it in 0.10.0 or 0.11.0.
 const LEGACY_MANIFEST_FILE_NAME: &str = "indexes_states.json";
 
@@ -45,6 +53,7 @@ impl LegacyManifest {
         Manifest {
             indexes: self.indexes,
             templates: HashMap::new(),
+            aliases: BTreeMap::new(),
         }
     }
 }
@@ -69,6 +78,8 @@ pub(crate) struct Manifest {
     // The templates are serialized as a sorted `Vec<IndexTemplate>` so the btree map is
     // unnecessary here and we can pass the hash map as is to the `MetastoreState`
     pub templates: HashMap<IndexTemplateId, IndexTemplate>,
+    // Index aliases and the sorted IDs of the indexes they resolve to.
+    pub aliases: BTreeMap<String, Vec<IndexId>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -95,8 +106,18 @@ impl From<VersionedManifest> for Manifest {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct ManifestV0_8 {
+    // Only used by `load_manifest_schema_version`.
+    #[serde(default = "legacy_manifest_schema_version")]
+    schema_version: u32,
     indexes: BTreeMap<IndexId, IndexStatus>,
     templates: Vec<IndexTemplate>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: BTreeMap<String, Vec<IndexId>>,
+}
+
+fn legacy_manifest_schema_version() -> u32 {
+    LEGACY_MANIFEST_SCHEMA_VERSION
 }
 
 impl From<Manifest> for ManifestV0_8 {
@@ -107,8 +128,10 @@ impl From<Manifest> for ManifestV0_8 {
             .sorted_unstable_by(|left, right| left.template_id.cmp(&right.template_id))
             .collect();
         ManifestV0_8 {
+            schema_version: MANIFEST_SCHEMA_VERSION,
             indexes: manifest.indexes,
             templates,
+            aliases: manifest.aliases,
         }
     }
 }
@@ -121,7 +144,11 @@ impl From<ManifestV0_8> for Manifest {
             .into_iter()
             .map(|template| (template.template_id.clone(), template))
             .collect();
-        Manifest { indexes, templates }
+        Manifest {
+            indexes,
+            templates,
+            aliases: manifest.aliases,
+        }
     }
 }
 
@@ -137,12 +164,22 @@ impl TestableForRegression for Manifest {
             "test-template-1".to_string(),
             IndexTemplate::sample_for_regression(),
         );
-        Manifest { indexes, templates }
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            "test-alias-1".to_string(),
+            vec!["test-index-1".to_string(), "test-index-2".to_string()],
+        );
+        Manifest {
+            indexes,
+            templates,
+            aliases,
+        }
     }
 
     fn assert_equality(&self, other: &Self) {
         assert_eq!(self.indexes, other.indexes);
         assert_eq!(self.templates, other.templates);
+        assert_eq!(self.aliases, other.aliases);
     }
 }
 
@@ -171,6 +208,29 @@ pub(super) async fn load_or_create_manifest(storage: &dyn Storage) -> MetastoreR
     Ok(manifest)
 }
 
+/// Returns the schema version recorded in the manifest, or `None` if the metastore does not have
+/// a manifest yet.
+///
+/// The manifest is parsed as raw JSON so that manifests written by more recent versions of
+/// Quickwit can be inspected as well.
+pub(super) async fn load_manifest_schema_version(
+    storage: &dyn Storage,
+) -> MetastoreResult<Option<u32>> {
+    if !file_exists(storage, MANIFEST_FILE_NAME).await? {
+        // New metastores and metastores still using the legacy manifest file get a manifest with
+        // the current schema version when they are loaded.
+        return Ok(None);
+    }
+    let manifest_json = get_bytes(storage, MANIFEST_FILE_NAME).await?;
+    let manifest_json_value: JsonValue = serde_utils::from_json_bytes(&manifest_json)?;
+    let schema_version = manifest_json_value
+        .get("schema_version")
+        .and_then(JsonValue::as_u64)
+        .map(|schema_version| schema_version as u32)
+        .unwrap_or(LEGACY_MANIFEST_SCHEMA_VERSION);
+    Ok(Some(schema_version))
+}
+
 pub(super) async fn save_manifest(
     storage: &dyn Storage,
     manifest: &Manifest,
@@ -219,12 +279,14 @@ fn into_metastore_error(
     operation_name: &str,
 ) -> MetastoreError {
     match storage_error.kind() {
-        StorageErrorKind::Unauthorized => MetastoreError::Forbidden {
-            message: format!(
-                "failed to access manifest file located at `{uri}/{}`: unauthorized",
-                path.display()
-            ),
-        },
+        StorageErrorKind::Unauthorized | StorageErrorKind::PermissionDenied => {
+            MetastoreError::Forbidden {
+                message: format!(
+                    "failed to access manifest file located at `{uri}/{}`: unauthorized",
+                    path.display()
+                ),
+            }
+        }
         _ => MetastoreError::Internal {
             message: format!(
                 "failed to {operation_name} manifest file located at `{uri}/{}`",
@@ -313,7 +375,15 @@ mod tests {
                 IndexTemplate::for_test("test-template-2", &["test-index-bar*"], 200),
             ),
         ]);
-        let manifest = Manifest { indexes, templates };
+        let aliases = BTreeMap::from_iter([(
+            "test-alias".to_string(),
+            vec!["test-index-1".to_string(), "test-index-2".to_string()],
+        )]);
+        let manifest = Manifest {
+            indexes,
+            templates,
+            aliases,
+        };
         let manifest_json = serde_json::to_string_pretty(&manifest).unwrap();
         let manifest_deserialized: Manifest = serde_json::from_str(&manifest_json).unwrap();
         assert_eq!(manifest, manifest_deserialized);
@@ -411,4 +481,41 @@ mod tests {
         let manifest_exists = file_exists(&*storage, MANIFEST_FILE_NAME).await.unwrap();
         assert!(manifest_exists);
     }
+
+    #[tokio::test]
+    async fn test_load_manifest_schema_version() {
+        let storage = quickwit_storage::storage_for_test();
+        let schema_version_opt = load_manifest_schema_version(&*storage).await.unwrap();
+        assert!(schema_version_opt.is_none());
+
+        load_or_create_manifest(&*storage).await.unwrap();
+        let schema_version_opt = load_manifest_schema_version(&*storage).await.unwrap();
+        assert_eq!(schema_version_opt, Some(MANIFEST_SCHEMA_VERSION));
+
+        let manifest_json = json!({
+            "version": "0.8",
+            "indexes": {},
+            "templates": [],
+        });
+        let manifest_json_bytes = serde_json::to_vec(&manifest_json).unwrap();
+        put_bytes(&*storage, MANIFEST_FILE_NAME, manifest_json_bytes)
+            .await
+            .unwrap();
+        let schema_version_opt = load_manifest_schema_version(&*storage).await.unwrap();
+        assert_eq!(schema_version_opt, Some(LEGACY_MANIFEST_SCHEMA_VERSION));
+
+        // Manifests written by future versions of Quickwit may not be deserializable as a
+        // `Manifest`.
+        let manifest_json = json!({
+            "version": "0.10",
+            "schema_version": 42,
+            "indexes": [],
+        });
+        let manifest_json_bytes = serde_json::to_vec(&manifest_json).unwrap();
+        put_bytes(&*storage, MANIFEST_FILE_NAME, manifest_json_bytes)
+            .await
+            .unwrap();
+        let schema_version_opt = load_manifest_schema_version(&*storage).await.unwrap();
+        assert_eq!(schema_version_opt, Some(42));
+    }
 }
@@ -35,7 +35,7 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use futures::future::try_join_all;
@@ -45,27 +45,31 @@ use itertools::Itertools;
 use quickwit_common::ServiceStream;
 use quickwit_config::IndexTemplate;
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
-    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateIndexAliasRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
+    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    EntityKind, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
+    GetIndexTemplateRequest, GetIndexTemplateResponse, GetSourceCheckpointRequest,
+    GetSourceCheckpointResponse, IndexAlias, IndexMetadataFailure, IndexMetadataFailureReason,
     IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
     IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
-    ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
-    MetastoreService, MetastoreServiceStream, OpenShardSubrequest, OpenShardsRequest,
-    OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
-    ToggleSourceRequest, UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
+    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexAliasesRequest,
+    ListIndexAliasesResponse, ListIndexTemplatesRequest, ListIndexTemplatesResponse,
+    ListIndexesMetadataRequest, ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse,
+    ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
+    MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
+    OpenShardsRequest, OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest,
+    StageSplitsRequest, ToggleSourceRequest, UpdateIndexAliasRequest, UpdateIndexAliasResponse,
+    UpdateIndexRequest, UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
     UpdateSplitsDeleteOpstampResponse,
 };
-use quickwit_proto::types::{IndexId, IndexUid};
+use quickwit_proto::types::{IndexId, IndexUid, SplitId};
 use quickwit_storage::Storage;
 use time::OffsetDateTime;
 use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+use tracing::field::Empty;
+use tracing::{instrument, Span};
 
 use self::file_backed_index::FileBackedIndex;
 pub use self::file_backed_metastore_factory::FileBackedMetastoreFactory;
@@ -73,15 +77,20 @@ use self::index_id_matcher::IndexIdMatcher;
 use self::lazy_file_backed_index::LazyFileBackedIndex;
 use self::manifest::{load_or_create_manifest, save_manifest, MANIFEST_FILE_NAME};
 use self::state::MetastoreState;
+pub(crate) use self::store_operations::METASTORE_FILE_NAME;
 use self::store_operations::{delete_index, index_exists, load_index, put_index};
 use super::{
+    apply_index_alias_update, check_index_alias_indexes_exist, record_duration_span_field,
+    record_list_span_fields, validate_create_index_alias_request, validate_split_ids,
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
     IndexesMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
     ListSplitsResponseExt, PublishSplitsRequestExt, StageSplitsRequestExt, UpdateIndexRequestExt,
     STREAM_SPLITS_CHUNK_SIZE,
 };
-use crate::checkpoint::IndexCheckpointDelta;
-use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, Split, SplitState};
+use crate::checkpoint::{IndexCheckpointDelta, SourceCheckpoint};
+use crate::{
+    DeleteSplitsBulkOutcome, IndexMetadata, ListSplitsQuery, MetastoreServiceExt, Split, SplitState,
+};
 
 /// Status of an index tracked by the metastore.
 pub(crate) enum LazyIndexStatus {
@@ -418,6 +427,7 @@ impl MetastoreService for FileBackedMetastore {
 
     /// -------------------------------------------------------------------------------
     /// Mutations over the high-level index.
+    #[instrument(skip_all, fields(index_id = Empty))]
     async fn create_index(
         &mut self,
         request: CreateIndexRequest,
@@ -432,6 +442,7 @@ impl MetastoreService for FileBackedMetastore {
         }
         let index_uid = index_metadata.index_uid.clone();
         let index_id = &index_uid.index_id;
+        Span::current().record("index_id", index_id.as_str());
 
         let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
         let index = FileBackedIndex::from(index_metadata);
@@ -498,18 +509,35 @@ impl MetastoreService for FileBackedMetastore {
         Ok(response)
     }
 
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
     async fn update_index(
         &mut self,
         request: UpdateIndexRequest,
     ) -> MetastoreResult<IndexMetadataResponse> {
         let retention_policy_opt = request.deserialize_retention_policy()?;
         let search_settings = request.deserialize_search_settings()?;
+        let doc_mapping_opt = request.deserialize_doc_mapping()?;
+        let index_state_opt = request.deserialize_index_state()?;
+        let indexing_settings_opt = request.deserialize_indexing_settings()?;
+        let index_uri_opt = request.deserialize_index_uri()?;
         let index_uid = request.index_uid();
 
         let index_metadata = self
             .mutate(index_uid, |index| {
                 let mut mutation_occurred = index.set_retention_policy(retention_policy_opt);
                 mutation_occurred |= index.set_search_settings(search_settings);
+                if let Some(doc_mapping) = doc_mapping_opt {
+                    mutation_occurred |= index.set_doc_mapping(doc_mapping)?;
+                }
+                if let Some(index_state) = index_state_opt {
+                    mutation_occurred |= index.set_index_state(index_state);
+                }
+                if let Some(indexing_settings) = indexing_settings_opt {
+                    mutation_occurred |= index.set_indexing_settings(indexing_settings);
+                }
+                if let Some(index_uri) = index_uri_opt {
+                    mutation_occurred |= index.set_index_uri(index_uri);
+                }
 
                 let index_metadata = index.metadata().clone();
 
@@ -523,6 +551,7 @@ impl MetastoreService for FileBackedMetastore {
         IndexMetadataResponse::try_from_index_metadata(&index_metadata)
     }
 
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
     async fn delete_index(
         &mut self,
         request: DeleteIndexRequest,
@@ -580,11 +609,18 @@ impl MetastoreService for FileBackedMetastore {
     /// -------------------------------------------------------------------------------
     /// Mutations over a single index
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = Empty,
+        duration_ms = Empty,
+    ))]
     async fn stage_splits(
         &mut self,
         request: StageSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
         let splits_metadata = request.deserialize_splits_metadata()?;
+        Span::current().record("split_ids_count", splits_metadata.len());
         let index_uid = request.index_uid();
 
         self.mutate(index_uid, |index| {
@@ -613,13 +649,26 @@ impl MetastoreService for FileBackedMetastore {
             }
         })
         .await?;
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.staged_split_ids.len(),
+        duration_ms = Empty,
+    ))]
     async fn publish_splits(
         &mut self,
         request: PublishSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(
+            request
+                .staged_split_ids
+                .iter()
+                .chain(&request.replaced_split_ids),
+        )?;
         let index_checkpoint_delta: Option<IndexCheckpointDelta> =
             request.deserialize_index_checkpoint()?;
         let index_uid = request.index_uid().clone();
@@ -633,13 +682,21 @@ impl MetastoreService for FileBackedMetastore {
             Ok(MutationOccurred::Yes(()))
         })
         .await?;
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
     async fn mark_splits_for_deletion(
         &mut self,
         request: MarkSplitsForDeletionRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
         let index_uid = request.index_uid().clone();
 
         self.mutate(&index_uid, |index| {
@@ -656,13 +713,21 @@ impl MetastoreService for FileBackedMetastore {
                 .map(MutationOccurred::from)
         })
         .await?;
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
     async fn delete_splits(
         &mut self,
         request: DeleteSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
         let index_uid = request.index_uid().clone();
 
         self.mutate(&index_uid, |index| {
@@ -670,9 +735,11 @@ impl MetastoreService for FileBackedMetastore {
             Ok(MutationOccurred::Yes(EmptyResponse {}))
         })
         .await?;
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
     async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
         let source_config = request.deserialize_source_config()?;
         let index_uid = request.index_uid();
@@ -685,6 +752,10 @@ impl MetastoreService for FileBackedMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        source_id = %request.source_id,
+    ))]
     async fn toggle_source(
         &mut self,
         request: ToggleSourceRequest,
@@ -700,6 +771,10 @@ impl MetastoreService for FileBackedMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        source_id = %request.source_id,
+    ))]
     async fn delete_source(
         &mut self,
         request: DeleteSourceRequest,
@@ -714,6 +789,10 @@ impl MetastoreService for FileBackedMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        source_id = %request.source_id,
+    ))]
     async fn reset_source_checkpoint(
         &mut self,
         request: ResetSourceCheckpointRequest,
@@ -729,16 +808,40 @@ impl MetastoreService for FileBackedMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        source_id = %request.source_id,
+    ))]
+    async fn update_source_checkpoint(
+        &mut self,
+        mut request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let source_checkpoint =
+            SourceCheckpoint::from(request.source_checkpoint.take().unwrap_or_default());
+        let index_uid = request.index_uid();
+
+        self.mutate(index_uid, |index| {
+            index
+                .update_source_checkpoint(&request.source_id, source_checkpoint)
+                .map(MutationOccurred::from)
+        })
+        .await?;
+        Ok(EmptyResponse {})
+    }
+
     /// -------------------------------------------------------------------------------
     /// Read-only accessors
 
     /// Streams of splits for the given request.
     /// No error is returned if any of the requested `index_uid` does not exist.
+    #[instrument(skip_all, fields(num_results = Empty, duration_ms = Empty))]
     async fn list_splits(
         &mut self,
         request: ListSplitsRequest,
     ) -> MetastoreResult<MetastoreServiceStream<ListSplitsResponse>> {
+        let start = Instant::now();
         let splits = self.list_splits_inner(request).await?;
+        record_list_span_fields(splits.len(), start);
         let splits_responses: Vec<MetastoreResult<ListSplitsResponse>> = splits
             .chunks(STREAM_SPLITS_CHUNK_SIZE)
             .map(|chunk| ListSplitsResponse::try_from_splits(chunk.to_vec()))
@@ -747,10 +850,16 @@ impl MetastoreService for FileBackedMetastore {
         Ok(ServiceStream::new(splits_responses_stream))
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        num_results = Empty,
+        duration_ms = Empty,
+    ))]
     async fn list_stale_splits(
         &mut self,
         request: ListStaleSplitsRequest,
     ) -> MetastoreResult<ListSplitsResponse> {
+        let start = Instant::now();
         let list_splits_query = ListSplitsQuery::for_index(request.index_uid().clone())
             .with_delete_opstamp_lt(request.delete_opstamp)
             .with_split_state(SplitState::Published)
@@ -760,9 +869,11 @@ impl MetastoreService for FileBackedMetastore {
         let list_splits_request =
             ListSplitsRequest::try_from_list_splits_query(&list_splits_query)?;
         let splits = self.list_splits_inner(list_splits_request).await?;
+        record_list_span_fields(splits.len(), start);
         ListSplitsResponse::try_from_splits(splits)
     }
 
+    #[instrument(skip(self))]
     async fn index_metadata(
         &mut self,
         request: IndexMetadataRequest,
@@ -775,10 +886,12 @@ impl MetastoreService for FileBackedMetastore {
         Ok(response)
     }
 
+    #[instrument(skip_all, fields(num_results = Empty, duration_ms = Empty))]
     async fn indexes_metadata(
         &mut self,
         request: IndexesMetadataRequest,
     ) -> MetastoreResult<IndexesMetadataResponse> {
+        let start = Instant::now();
         let mut indexes_metadata: Vec<IndexMetadata> =
             Vec::with_capacity(request.subrequests.len());
         let mut failures: Vec<IndexMetadataFailure> = Vec::new();
@@ -816,15 +929,22 @@ impl MetastoreService for FileBackedMetastore {
                 }
             }
         }
+        record_list_span_fields(indexes_metadata.len(), start);
         let response =
             IndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata, failures).await?;
         Ok(response)
     }
 
+    #[instrument(skip_all, fields(
+        index_id_patterns = ?request.index_id_patterns,
+        num_results = Empty,
+        duration_ms = Empty,
+    ))]
     async fn list_indexes_metadata(
         &mut self,
         request: ListIndexesMetadataRequest,
     ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        let start = Instant::now();
         // Done in two steps:
         // 1) Get index IDs and release the lock on `per_index_metastores`.
         // 2) Get each index metadata. Note that each get will take a read lock on
@@ -854,6 +974,7 @@ impl MetastoreService for FileBackedMetastore {
         .into_iter()
         .flatten()
         .collect();
+        record_list_span_fields(indexes_metadata.len(), start);
         let response =
             ListIndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata).await?;
         Ok(response)
@@ -926,6 +1047,7 @@ impl MetastoreService for FileBackedMetastore {
     /// -------------------------------------------------------------------------------
     /// Delete tasks
 
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
     async fn last_delete_opstamp(
         &mut self,
         request: LastDeleteOpstampRequest,
@@ -936,6 +1058,22 @@ impl MetastoreService for FileBackedMetastore {
         Ok(LastDeleteOpstampResponse::new(last_delete_opstamp))
     }
 
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        let source_checkpoint = self
+            .read(request.index_uid(), |index| {
+                Ok(index.source_checkpoint(&request.source_id))
+            })
+            .await?;
+        let response = GetSourceCheckpointResponse {
+            source_checkpoint: Some(source_checkpoint.into()),
+        };
+        Ok(response)
+    }
+
+    #[instrument(skip_all, fields(index_id = %delete_query.index_uid().index_id))]
     async fn create_delete_task(
         &mut self,
         delete_query: DeleteQuery,
@@ -951,6 +1089,10 @@ impl MetastoreService for FileBackedMetastore {
         Ok(delete_task)
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+    ))]
     async fn update_splits_delete_opstamp(
         &mut self,
         request: UpdateSplitsDeleteOpstampRequest,
@@ -971,10 +1113,16 @@ impl MetastoreService for FileBackedMetastore {
         Ok(UpdateSplitsDeleteOpstampResponse {})
     }
 
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        num_results = Empty,
+        duration_ms = Empty,
+    ))]
     async fn list_delete_tasks(
         &mut self,
         request: ListDeleteTasksRequest,
     ) -> MetastoreResult<ListDeleteTasksResponse> {
+        let start = Instant::now();
         let index_uid = request.index_uid();
 
         let delete_tasks = self
@@ -982,6 +1130,7 @@ impl MetastoreService for FileBackedMetastore {
                 Ok(index.list_delete_tasks(request.opstamp_start))
             })
             .await??;
+        record_list_span_fields(delete_tasks.len(), start);
         let response = ListDeleteTasksResponse { delete_tasks };
         Ok(response)
     }
@@ -1144,9 +1293,140 @@ impl MetastoreService for FileBackedMetastore {
         }
         Ok(EmptyResponse {})
     }
+
+    // Index Alias API
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_ids = validate_create_index_alias_request(&request)?;
+        let alias_id = request.alias_id;
+
+        let mut state_wlock_guard = self.state.write().await;
+
+        if state_wlock_guard.aliases.contains_key(&alias_id) {
+            return Err(MetastoreError::AlreadyExists(EntityKind::IndexAlias {
+                alias_id,
+            }));
+        }
+        check_index_alias_indexes_exist(&index_ids, |index_id| {
+            matches!(
+                state_wlock_guard.indexes.get(index_id),
+                Some(LazyIndexStatus::Active(_))
+            )
+        })?;
+        state_wlock_guard
+            .aliases
+            .insert(alias_id.clone(), index_ids);
+
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard.aliases.remove(&alias_id);
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        let inner_rlock_guard = self.state.read().await;
+
+        let index_aliases = inner_rlock_guard
+            .aliases
+            .iter()
+            .filter(|(alias_id, _)| {
+                request.alias_ids.is_empty() || request.alias_ids.contains(alias_id)
+            })
+            .map(|(alias_id, index_ids)| IndexAlias {
+                alias_id: alias_id.clone(),
+                index_ids: index_ids.clone(),
+            })
+            .collect();
+        let response = ListIndexAliasesResponse { index_aliases };
+        Ok(response)
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let Some(index_ids) = state_wlock_guard.aliases.get(&request.alias_id) else {
+            return Err(MetastoreError::NotFound(EntityKind::IndexAlias {
+                alias_id: request.alias_id,
+            }));
+        };
+        let mut new_index_ids = index_ids.clone();
+        check_index_alias_indexes_exist(&request.add_index_ids, |index_id| {
+            matches!(
+                state_wlock_guard.indexes.get(index_id),
+                Some(LazyIndexStatus::Active(_))
+            )
+        })?;
+        apply_index_alias_update(&request, &mut new_index_ids)?;
+
+        let previous_index_ids_opt = state_wlock_guard
+            .aliases
+            .insert(request.alias_id.clone(), new_index_ids.clone());
+
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            if let Some(previous_index_ids) = previous_index_ids_opt {
+                state_wlock_guard
+                    .aliases
+                    .insert(request.alias_id, previous_index_ids);
+            }
+            return Err(error);
+        }
+        let index_alias = IndexAlias {
+            alias_id: request.alias_id,
+            index_ids: new_index_ids,
+        };
+        let response = UpdateIndexAliasResponse {
+            index_alias: Some(index_alias),
+        };
+        Ok(response)
+    }
 }
 
-impl MetastoreServiceExt for FileBackedMetastore {}
+#[async_trait]
+impl MetastoreServiceExt for FileBackedMetastore {
+    /// Deletes all the splits in a single mutation of the index: the index is locked and its
+    /// manifest rewritten once, so the splits are either all deleted or none of them is.
+    async fn delete_splits_bulk(
+        &mut self,
+        index_uid: IndexUid,
+        split_ids: Vec<SplitId>,
+    ) -> DeleteSplitsBulkOutcome
+    where
+        Self: Clone + Sized,
+    {
+        let delete_splits_request = DeleteSplitsRequest {
+            index_uid: Some(index_uid),
+            split_ids: split_ids.clone(),
+        };
+        let mut outcome = DeleteSplitsBulkOutcome::default();
+
+        match self.delete_splits(delete_splits_request).await {
+            Ok(_) => outcome.deleted_split_ids = split_ids,
+            Err(metastore_error) => {
+                outcome.failed_split_ids = split_ids;
+                outcome.error_opt = Some(metastore_error);
+            }
+        }
+        outcome
+    }
+}
 
 async fn get_index_mutex(
     index_id: &str,
@@ -1479,6 +1759,123 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_file_backed_metastore_delete_splits_bulk() {
+        let mut metastore = FileBackedMetastore::default_for_test().await;
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+
+        let splits_metadata: Vec<SplitMetadata> = (0..5_000)
+            .map(|split_ord| SplitMetadata {
+                split_id: format!("split-{split_ord}"),
+                index_uid: index_uid.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let split_ids: Vec<SplitId> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest {
+            index_uid: Some(index_uid.clone()),
+            split_ids: split_ids.clone(),
+        };
+        metastore
+            .mark_splits_for_deletion(mark_splits_for_deletion_request)
+            .await
+            .unwrap();
+
+        let outcome = metastore
+            .delete_splits_bulk(index_uid.clone(), split_ids.clone())
+            .await;
+        assert!(outcome.error_opt.is_none());
+        let mut deleted_split_ids = outcome.deleted_split_ids;
+        deleted_split_ids.sort_by_key(|split_id| split_id[6..].parse::<usize>().unwrap());
+        assert_eq!(deleted_split_ids, split_ids);
+
+        let splits = metastore
+            .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert!(splits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_backed_metastore_delete_splits_bulk_is_atomic() {
+        let mut metastore = FileBackedMetastore::default_for_test().await;
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+
+        let splits_metadata: Vec<SplitMetadata> = (0..2_000)
+            .map(|split_ord| SplitMetadata {
+                split_id: format!("split-{split_ord}"),
+                index_uid: index_uid.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let split_ids: Vec<SplitId> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        // The last split is not marked for deletion, so it cannot be deleted.
+        let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest {
+            index_uid: Some(index_uid.clone()),
+            split_ids: split_ids[..1_999].to_vec(),
+        };
+        metastore
+            .mark_splits_for_deletion(mark_splits_for_deletion_request)
+            .await
+            .unwrap();
+
+        let outcome = metastore
+            .delete_splits_bulk(index_uid.clone(), split_ids.clone())
+            .await;
+        assert!(matches!(
+            outcome.error_opt,
+            Some(MetastoreError::FailedPrecondition { .. })
+        ));
+        assert!(outcome.deleted_split_ids.is_empty());
+        assert_eq!(outcome.failed_split_ids, split_ids);
+
+        let splits = metastore
+            .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert_eq!(splits.len(), 2_000);
+    }
+
     #[tokio::test]
     async fn test_file_backed_metastore_write_directly_visible() -> MetastoreResult<()> {
         let mut metastore = FileBackedMetastore::default_for_test().await;
@@ -1522,6 +1919,106 @@ mod tests {
         Ok(())
     }
 
+    /// Records the fields of the spans created while it is installed, keyed by span name.
+    #[derive(Clone, Default)]
+    struct SpanFieldsRecorder {
+        spans: Arc<std::sync::Mutex<HashMap<String, HashMap<String, String>>>>,
+    }
+
+    struct SpanFieldsVisitor<'a>(&'a mut HashMap<String, String>);
+
+    impl tracing::field::Visit for SpanFieldsVisitor<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for SpanFieldsRecorder
+    where S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).unwrap();
+            let mut spans = self.spans.lock().unwrap();
+            let fields = spans.entry(span.name().to_string()).or_default();
+            attrs.record(&mut SpanFieldsVisitor(fields));
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).unwrap();
+            let mut spans = self.spans.lock().unwrap();
+            let fields = spans.entry(span.name().to_string()).or_default();
+            values.record(&mut SpanFieldsVisitor(fields));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_backed_metastore_list_splits_span_fields() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = SpanFieldsRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut metastore = FileBackedMetastore::default_for_test().await;
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let create_index_response = metastore.create_index(create_index_request).await.unwrap();
+        let index_uid: IndexUid = create_index_response.index_uid().clone();
+
+        let split_metadata_1 = SplitMetadata {
+            split_id: "split-1".to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let split_metadata_2 = SplitMetadata {
+            split_id: "split-2".to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+            index_uid.clone(),
+            [split_metadata_1, split_metadata_2],
+        )
+        .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let splits = metastore
+            .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert_eq!(splits.len(), 2);
+
+        let spans = recorder.spans.lock().unwrap();
+
+        let create_index_fields = &spans["create_index"];
+        assert_eq!(create_index_fields["index_id"], "\"test-index\"");
+
+        let stage_splits_fields = &spans["stage_splits"];
+        assert_eq!(stage_splits_fields["index_id"], "test-index");
+        assert_eq!(stage_splits_fields["split_ids_count"], "2");
+        assert!(stage_splits_fields.contains_key("duration_ms"));
+
+        let list_splits_fields = &spans["list_splits"];
+        assert_eq!(list_splits_fields["num_results"], "2");
+        assert!(list_splits_fields.contains_key("duration_ms"));
+    }
+
     #[tokio::test]
     async fn test_file_backed_metastore_polling() -> MetastoreResult<()> {
         let storage = Arc::new(RamStorage::default());
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -36,6 +36,7 @@ pub(super) struct MetastoreState {
     pub indexes: HashMap<IndexId, LazyIndexStatus>,
     pub templates: HashMap<IndexTemplateId, IndexTemplate>,
     pub template_matcher: IndexTemplateMatcher,
+    pub aliases: BTreeMap<String, Vec<IndexId>>,
 }
 
 impl MetastoreState {
@@ -69,6 +70,7 @@ impl MetastoreState {
             indexes,
             templates: manifest.templates,
             template_matcher,
+            aliases: manifest.aliases,
         };
         Ok(state)
     }
@@ -87,6 +89,11 @@ impl MetastoreState {
             })
             .collect();
         let templates = self.templates.clone();
-        Manifest { indexes, templates }
+        let aliases = self.aliases.clone();
+        Manifest {
+            indexes,
+            templates,
+            aliases,
+        }
     }
 }
@@ -25,7 +25,7 @@ use quickwit_storage::{Storage, StorageError, StorageErrorKind};
 use crate::metastore::file_backed::file_backed_index::FileBackedIndex;
 
 /// Index metastore file managed by [`FileBackedMetastore`](crate::FileBackedMetastore).
-pub(super) const METASTORE_FILE_NAME: &str = "metastore.json";
+pub(crate) const METASTORE_FILE_NAME: &str = "metastore.json";
 
 /// Path to the metadata file from the given index ID.
 pub(super) fn metastore_filepath(index_id: &str) -> PathBuf {
@@ -37,9 +37,11 @@ fn convert_error(index_id: &str, storage_error: StorageError) -> MetastoreError
         StorageErrorKind::NotFound => MetastoreError::NotFound(EntityKind::Index {
             index_id: index_id.to_string(),
         }),
-        StorageErrorKind::Unauthorized => MetastoreError::Forbidden {
-            message: "the request credentials do not allow for this operation".to_string(),
-        },
+        StorageErrorKind::Unauthorized | StorageErrorKind::PermissionDenied => {
+            MetastoreError::Forbidden {
+                message: "the request credentials do not allow for this operation".to_string(),
+            }
+        }
         _ => MetastoreError::Internal {
             message: "failed to get index files".to_string(),
             cause: storage_error.to_string(),
@@ -129,9 +131,11 @@ pub(super) async fn delete_index(storage: &dyn Storage, index_id: &str) -> Metas
         .delete(&metastore_filepath)
         .await
         .map_err(|storage_error| match storage_error.kind() {
-            StorageErrorKind::Unauthorized => MetastoreError::Forbidden {
-                message: "the request credentials do not allow for this operation".to_string(),
-            },
+            StorageErrorKind::Unauthorized | StorageErrorKind::PermissionDenied => {
+                MetastoreError::Forbidden {
+                    message: "the request credentials do not allow for this operation".to_string(),
+                }
+            }
             _ => MetastoreError::Internal {
                 message: format!(
                     "failed to delete metastore file located at `{}/{}`",
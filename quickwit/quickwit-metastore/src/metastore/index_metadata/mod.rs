@@ -21,10 +21,13 @@ pub(crate) mod serialize;
 
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
 use quickwit_common::uri::Uri;
 use quickwit_config::{
-    IndexConfig, RetentionPolicy, SearchSettings, SourceConfig, TestableForRegression,
+    validate_schema_evolution, DocMapping, IndexConfig, IndexingSettings, RetentionPolicy,
+    SearchSettings, SourceConfig, TestableForRegression,
 };
 use quickwit_proto::metastore::{EntityKind, MetastoreError, MetastoreResult};
 use quickwit_proto::types::{IndexUid, Position, SourceId};
@@ -35,6 +38,52 @@ use ulid::Ulid;
 
 use crate::checkpoint::{IndexCheckpoint, PartitionId, SourceCheckpoint, SourceCheckpointDelta};
 
+/// An index state.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexState {
+    /// The index accepts new documents.
+    #[default]
+    Active,
+
+    /// The index is read-only: it rejects the ingest requests but can still be searched.
+    Frozen,
+}
+
+impl fmt::Display for IndexState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl IndexState {
+    /// Returns a string representation of the given enum.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexState::Active => "active",
+            IndexState::Frozen => "frozen",
+        }
+    }
+
+    /// Returns whether the index accepts new documents.
+    pub fn is_active(&self) -> bool {
+        *self == IndexState::Active
+    }
+}
+
+impl FromStr for IndexState {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<IndexState, Self::Err> {
+        let index_state = match input {
+            "active" => IndexState::Active,
+            "frozen" => IndexState::Frozen,
+            _ => return Err(format!("unknown index state `{input}`")),
+        };
+        Ok(index_state)
+    }
+}
+
 /// An index metadata carries all meta data about an index.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(into = "VersionedIndexMetadata")]
@@ -50,6 +99,11 @@ pub struct IndexMetadata {
     pub create_timestamp: i64,
     /// Sources
     pub sources: HashMap<SourceId, SourceConfig>,
+    /// Doc mappings of the previous schema versions of the index, required to search the splits
+    /// indexed before the last schema upgrades.
+    pub doc_mapping_history: BTreeMap<u32, DocMapping>,
+    /// State of the index.
+    pub index_state: IndexState,
 }
 
 impl IndexMetadata {
@@ -67,6 +121,8 @@ impl IndexMetadata {
             checkpoint: Default::default(),
             create_timestamp: OffsetDateTime::now_utc().unix_timestamp(),
             sources: HashMap::default(),
+            doc_mapping_history: BTreeMap::default(),
+            index_state: IndexState::default(),
         }
     }
 
@@ -121,6 +177,71 @@ impl IndexMetadata {
         }
     }
 
+    /// Replaces the indexing settings, returning whether a mutation occurred. The new settings
+    /// are picked up by the indexing pipelines of the index the next time they are spawned.
+    pub fn set_indexing_settings(&mut self, indexing_settings: IndexingSettings) -> bool {
+        if self.index_config.indexing_settings != indexing_settings {
+            self.index_config.indexing_settings = indexing_settings;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Points the index to a new storage URI, returning whether a mutation occurred. The caller is
+    /// responsible for copying the split files to the new location first.
+    pub fn set_index_uri(&mut self, index_uri: Uri) -> bool {
+        if self.index_config.index_uri != index_uri {
+            self.index_config.index_uri = index_uri;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Freezes or unfreezes the index, returning whether a mutation occurred.
+    pub fn set_index_state(&mut self, index_state: IndexState) -> bool {
+        if self.index_state != index_state {
+            self.index_state = index_state;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the doc mapping the splits of the given schema version were indexed with.
+    pub fn doc_mapping_for_schema_version(&self, schema_version: u32) -> Option<&DocMapping> {
+        if schema_version == self.index_config.schema_version {
+            Some(&self.index_config.doc_mapping)
+        } else {
+            self.doc_mapping_history.get(&schema_version)
+        }
+    }
+
+    /// Upgrades the doc mapping of the index, returning whether a mutation occurred.
+    ///
+    /// The current doc mapping is kept in the doc mapping history and the schema version is bumped.
+    /// Returns an error if the new doc mapping is not a valid evolution of the current one.
+    pub fn set_doc_mapping(&mut self, doc_mapping: DocMapping) -> MetastoreResult<bool> {
+        if self.index_config.doc_mapping == doc_mapping {
+            return Ok(false);
+        }
+        validate_schema_evolution(&self.index_config.doc_mapping, &doc_mapping).map_err(
+            |error| MetastoreError::InvalidArgument {
+                message: format!(
+                    "invalid schema upgrade for index `{}`: {error}",
+                    self.index_id()
+                ),
+            },
+        )?;
+        let previous_doc_mapping =
+            std::mem::replace(&mut self.index_config.doc_mapping, doc_mapping);
+        self.doc_mapping_history
+            .insert(self.index_config.schema_version, previous_doc_mapping);
+        self.index_config.schema_version += 1;
+        Ok(true)
+    }
+
     /// Adds a source to the index. Returns an error if the source already exists.
     pub fn add_source(&mut self, source_config: SourceConfig) -> MetastoreResult<()> {
         match self.sources.entry(source_config.source_id.clone()) {
@@ -181,6 +302,8 @@ impl TestableForRegression for IndexMetadata {
             checkpoint,
             create_timestamp: 1789,
             sources: Default::default(),
+            doc_mapping_history: Default::default(),
+            index_state: IndexState::Frozen,
         };
         index_metadata
             .add_source(SourceConfig::sample_for_regression())
@@ -193,5 +316,7 @@ impl TestableForRegression for IndexMetadata {
         assert_eq!(self.checkpoint, other.checkpoint);
         assert_eq!(self.create_timestamp, other.create_timestamp);
         assert_eq!(self.sources, other.sources);
+        assert_eq!(self.doc_mapping_history, other.doc_mapping_history);
+        assert_eq!(self.index_state, other.index_state);
     }
 }
@@ -17,15 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use quickwit_config::{IndexConfig, SourceConfig};
+use quickwit_config::{DocMapping, IndexConfig, SourceConfig};
 use quickwit_proto::types::IndexUid;
 use serde::{self, Deserialize, Serialize};
 
 use crate::checkpoint::IndexCheckpoint;
 use crate::split_metadata::utc_now_timestamp;
-use crate::IndexMetadata;
+use crate::{IndexMetadata, IndexState};
 
 #[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "version")]
@@ -66,6 +66,8 @@ impl From<IndexMetadata> for IndexMetadataV0_8 {
             checkpoint: index_metadata.checkpoint,
             create_timestamp: index_metadata.create_timestamp,
             sources,
+            doc_mapping_history: index_metadata.doc_mapping_history,
+            index_state: index_metadata.index_state,
         }
     }
 }
@@ -84,6 +86,13 @@ pub(crate) struct IndexMetadataV0_8 {
     pub create_timestamp: i64,
     #[schema(value_type = Vec<VersionedSourceConfig>)]
     pub sources: Vec<SourceConfig>,
+    #[schema(value_type = Object)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub doc_mapping_history: BTreeMap<u32, DocMapping>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "IndexState::is_active")]
+    pub index_state: IndexState,
 }
 
 impl TryFrom<IndexMetadataV0_8> for IndexMetadata {
@@ -107,6 +116,8 @@ impl TryFrom<IndexMetadataV0_8> for IndexMetadata {
             checkpoint: v0_8.checkpoint,
             create_timestamp: v0_8.create_timestamp,
             sources,
+            doc_mapping_history: v0_8.doc_mapping_history,
+            index_state: v0_8.index_state,
         })
     }
 }
@@ -0,0 +1,160 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use quickwit_common::uri::Uri;
+use quickwit_config::{MetastoreBackend, MetastoreConfig};
+use quickwit_proto::metastore::MetastoreServiceClient;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use super::{SharedMemoryMetastore, SharedMemoryNamespace};
+use crate::{MetastoreFactory, MetastoreResolverError};
+
+/// The live `memory://` namespaces of the process, keyed by URI.
+///
+/// The map only holds weak references so that a namespace is released as soon as the last
+/// metastore instance referencing it is dropped.
+static SHARED_MEMORY_NAMESPACES: Lazy<Mutex<HashMap<Uri, Weak<SharedMemoryNamespace>>>> =
+    Lazy::new(Default::default);
+
+/// A factory for [`SharedMemoryMetastore`] instances.
+///
+/// All the metastores resolved from the same `memory://` URI share the same in-memory state,
+/// regardless of the factory instance that resolved them.
+#[derive(Clone, Default)]
+pub struct MemoryMetastoreFactory;
+
+#[async_trait]
+impl MetastoreFactory for MemoryMetastoreFactory {
+    fn backend(&self) -> MetastoreBackend {
+        MetastoreBackend::Memory
+    }
+
+    async fn resolve(
+        &self,
+        _metastore_config: &MetastoreConfig,
+        uri: &Uri,
+    ) -> Result<MetastoreServiceClient, MetastoreResolverError> {
+        let mut namespaces_guard = SHARED_MEMORY_NAMESPACES.lock().await;
+
+        // Clear the namespaces that are no longer referenced by any metastore.
+        namespaces_guard.retain(|_, namespace| namespace.strong_count() > 0);
+
+        let namespace = if let Some(namespace) = namespaces_guard.get(uri).and_then(Weak::upgrade) {
+            debug!(uri=%uri, "using existing memory metastore namespace");
+            namespace
+        } else {
+            debug!(uri=%uri, "creating memory metastore namespace");
+            let namespace = Arc::new(SharedMemoryNamespace::try_new().await?);
+            namespaces_guard.insert(uri.clone(), Arc::downgrade(&namespace));
+            namespace
+        };
+        let metastore = SharedMemoryMetastore::new(uri.clone(), namespace);
+        Ok(MetastoreServiceClient::new(metastore))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::IndexConfig;
+    use quickwit_proto::metastore::{
+        CreateIndexRequest, ListIndexesMetadataRequest, MetastoreService,
+    };
+
+    use super::*;
+    use crate::{CreateIndexRequestExt, ListIndexesMetadataResponseExt};
+
+    async fn resolve(uri: &str) -> MetastoreServiceClient {
+        MemoryMetastoreFactory
+            .resolve(
+                &MetastoreConfig::Memory(Default::default()),
+                &Uri::for_test(uri),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn list_index_ids(metastore: &mut MetastoreServiceClient) -> Vec<String> {
+        metastore
+            .list_indexes_metadata(ListIndexesMetadataRequest::all())
+            .await
+            .unwrap()
+            .deserialize_indexes_metadata()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|index_metadata| index_metadata.index_id().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_memory_metastore_factory_shares_namespace() {
+        let mut metastore_foo = resolve("memory://test-shares-namespace-foo").await;
+        let mut metastore_foo_bis = resolve("memory://test-shares-namespace-foo").await;
+        let mut metastore_bar = resolve("memory://test-shares-namespace-bar").await;
+
+        assert_eq!(
+            metastore_foo.endpoints(),
+            vec![Uri::for_test("memory://test-shares-namespace-foo")]
+        );
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        metastore_foo
+            .create_index(create_index_request)
+            .await
+            .unwrap();
+
+        assert_eq!(list_index_ids(&mut metastore_foo).await, ["test-index"]);
+        assert_eq!(list_index_ids(&mut metastore_foo_bis).await, ["test-index"]);
+        assert!(list_index_ids(&mut metastore_bar).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_metastore_factory_clears_unreferenced_namespace() {
+        let uri = Uri::for_test("memory://test-clears-unreferenced-namespace");
+        let mut metastore = resolve(uri.as_str()).await;
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        metastore.create_index(create_index_request).await.unwrap();
+
+        let metastore_clone = metastore.clone();
+        drop(metastore);
+
+        // The namespace is still referenced by the clone.
+        let mut metastore = resolve(uri.as_str()).await;
+        assert_eq!(list_index_ids(&mut metastore).await, ["test-index"]);
+
+        drop(metastore);
+        drop(metastore_clone);
+
+        let mut metastore = resolve(uri.as_str()).await;
+        assert!(list_index_ids(&mut metastore).await.is_empty());
+
+        let namespaces_guard = SHARED_MEMORY_NAMESPACES.lock().await;
+        assert_eq!(namespaces_guard.get(&uri).unwrap().strong_count(), 1);
+    }
+}
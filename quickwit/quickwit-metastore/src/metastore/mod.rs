@@ -17,39 +17,218 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+pub mod audit_log;
+pub mod cached_metastore;
+#[cfg(feature = "consul")]
+pub mod consul;
 pub mod file_backed;
 pub(crate) mod index_metadata;
+#[cfg(any(test, feature = "testsuite"))]
+pub mod memory;
 #[cfg(feature = "postgres")]
 pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 pub mod control_plane_metastore;
 
+use std::collections::BTreeSet;
 use std::ops::{Bound, RangeInclusive};
+use std::str::FromStr;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::TryStreamExt;
-pub use index_metadata::IndexMetadata;
+use futures::{stream, StreamExt, TryStreamExt};
+pub use index_metadata::{IndexMetadata, IndexState};
 use itertools::Itertools;
 use quickwit_common::thread_pool::run_cpu_intensive;
-use quickwit_config::{IndexConfig, RetentionPolicy, SearchSettings, SourceConfig};
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    validate_identifier, DocMapping, IndexConfig, IndexingSettings, RetentionPolicy,
+    SearchSettings, SourceConfig,
+};
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteTask,
-    IndexMetadataFailure, IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataResponse,
+    serde_utils, AddSourceRequest, CreateIndexAliasRequest, CreateIndexRequest,
+    CreateIndexResponse, DeleteSplitsRequest, DeleteTask, EntityKind, IndexMetadataFailure,
+    IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataResponse,
     ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse, MetastoreError,
     MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
-    PublishSplitsRequest, StageSplitsRequest, UpdateIndexRequest,
+    PublishSplitsRequest, StageSplitsRequest, UpdateIndexAliasRequest, UpdateIndexRequest,
 };
-use quickwit_proto::types::{IndexUid, NodeId, SplitId};
+use quickwit_proto::types::{IndexId, IndexUid, NodeId, SplitId};
 use time::OffsetDateTime;
 
 use crate::checkpoint::IndexCheckpointDelta;
-use crate::{Split, SplitMetadata, SplitState};
+use crate::{Split, SplitMetadata, SplitState, ValidatedIndexId, ValidatedSplitId};
 
 /// Splits batch size returned by the stream splits API
 pub(crate) const STREAM_SPLITS_CHUNK_SIZE: usize = 100;
 
+/// Maximum number of splits deleted by a single delete splits request issued by
+/// [`MetastoreServiceExt::delete_splits_bulk`].
+pub(crate) const DELETE_BATCH_SIZE: usize = 1_000;
+
+/// Maximum number of delete splits requests issued concurrently by
+/// [`MetastoreServiceExt::delete_splits_bulk`].
+pub(crate) const MAX_DELETE_PARALLELISM: usize = 10;
+
+/// Outcome of [`MetastoreServiceExt::delete_splits_bulk`].
+#[derive(Debug, Default)]
+pub struct DeleteSplitsBulkOutcome {
+    /// IDs of the splits deleted from the metastore.
+    pub deleted_split_ids: Vec<SplitId>,
+    /// IDs of the splits whose batch failed to be deleted. Those splits are still in the
+    /// metastore.
+    pub failed_split_ids: Vec<SplitId>,
+    /// The first error returned by a failed batch, if any.
+    pub error_opt: Option<MetastoreError>,
+}
+
+/// Records the number of results returned by a list operation and its duration on the current
+/// span. The span must declare the `num_results` and `duration_ms` fields.
+pub(crate) fn record_list_span_fields(num_results: usize, start: Instant) {
+    let span = tracing::Span::current();
+    span.record("num_results", num_results);
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+}
+
+/// Records the duration of an operation on the current span. The span must declare the
+/// `duration_ms` field.
+pub(crate) fn record_duration_span_field(start: Instant) {
+    tracing::Span::current().record("duration_ms", start.elapsed().as_millis() as u64);
+}
+
+/// Returns the unix timestamp at which the split becomes mature.
+/// If the split is mature (`SplitMaturity::Mature`), we return 0
+/// as we don't want the maturity to depend on datetime.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub(crate) fn split_maturity_timestamp(split_metadata: &SplitMetadata) -> i64 {
+    match split_metadata.maturity {
+        crate::SplitMaturity::Mature => 0,
+        crate::SplitMaturity::Immature { maturation_period } => {
+            split_metadata.create_timestamp + maturation_period.as_secs() as i64
+        }
+    }
+}
+
+/// Checks that the split IDs of a request are valid [`ValidatedSplitId`]s. Split IDs are plain
+/// strings in the gRPC definitions, so every metastore backend calls this function on the split IDs
+/// it receives.
+pub(crate) fn validate_split_ids<'a>(
+    split_ids: impl IntoIterator<Item = &'a SplitId>,
+) -> MetastoreResult<()> {
+    for split_id in split_ids {
+        ValidatedSplitId::new_validated(split_id.clone()).map_err(|error| {
+            MetastoreError::InvalidArgument {
+                message: error.to_string(),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Checks that the IDs of the indexes targeted by an index alias are valid index IDs.
+fn validate_index_alias_index_ids<'a>(
+    index_ids: impl IntoIterator<Item = &'a IndexId>,
+) -> MetastoreResult<()> {
+    for index_id in index_ids {
+        ValidatedIndexId::new_validated(index_id.clone()).map_err(|error| {
+            MetastoreError::InvalidArgument {
+                message: error.to_string(),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns a `NotFound` error if one of the indexes an alias resolves to does not exist.
+pub(crate) fn check_index_alias_indexes_exist<'a>(
+    index_ids: impl IntoIterator<Item = &'a IndexId>,
+    index_exists: impl Fn(&str) -> bool,
+) -> MetastoreResult<()> {
+    let missing_index_ids: Vec<IndexId> = index_ids
+        .into_iter()
+        .filter(|index_id| !index_exists(index_id))
+        .cloned()
+        .collect();
+
+    if !missing_index_ids.is_empty() {
+        return Err(MetastoreError::NotFound(EntityKind::Indexes {
+            index_ids: missing_index_ids,
+        }));
+    }
+    Ok(())
+}
+
+/// Validates a [`CreateIndexAliasRequest`] and returns the sorted and deduplicated IDs of the
+/// indexes the alias resolves to.
+pub(crate) fn validate_create_index_alias_request(
+    request: &CreateIndexAliasRequest,
+) -> MetastoreResult<Vec<IndexId>> {
+    validate_identifier("index alias ID", &request.alias_id).map_err(|error| {
+        MetastoreError::InvalidArgument {
+            message: error.to_string(),
+        }
+    })?;
+    if request.index_ids.is_empty() {
+        return Err(MetastoreError::InvalidArgument {
+            message: format!(
+                "index alias `{}` must resolve to at least one index",
+                request.alias_id
+            ),
+        });
+    }
+    validate_index_alias_index_ids(&request.index_ids)?;
+
+    let index_ids = request
+        .index_ids
+        .iter()
+        .cloned()
+        .sorted_unstable()
+        .dedup()
+        .collect();
+    Ok(index_ids)
+}
+
+/// Applies an [`UpdateIndexAliasRequest`] to the sorted IDs of the indexes an alias resolves to.
+///
+/// The indexes are removed before the new ones are added, so moving an alias from one index to
+/// another is a single update.
+pub(crate) fn apply_index_alias_update(
+    request: &UpdateIndexAliasRequest,
+    index_ids: &mut Vec<IndexId>,
+) -> MetastoreResult<()> {
+    validate_index_alias_index_ids(&request.add_index_ids)?;
+
+    for index_id in &request.remove_index_ids {
+        let Ok(position) = index_ids.binary_search(index_id) else {
+            return Err(MetastoreError::FailedPrecondition {
+                entity: EntityKind::IndexAlias {
+                    alias_id: request.alias_id.clone(),
+                },
+                message: format!("index `{index_id}` is not part of the alias"),
+            });
+        };
+        index_ids.remove(position);
+    }
+    for index_id in &request.add_index_ids {
+        if let Err(position) = index_ids.binary_search(index_id) {
+            index_ids.insert(position, index_id.clone());
+        }
+    }
+    if index_ids.is_empty() {
+        return Err(MetastoreError::FailedPrecondition {
+            entity: EntityKind::IndexAlias {
+                alias_id: request.alias_id.clone(),
+            },
+            message: "index alias must resolve to at least one index".to_string(),
+        });
+    }
+    Ok(())
+}
+
 /// An extended trait for [`MetastoreService`].
 #[async_trait]
 pub trait MetastoreServiceExt: MetastoreService {
@@ -62,6 +241,107 @@ pub trait MetastoreServiceExt: MetastoreService {
             Err(error) => Err(error),
         }
     }
+
+    /// Deletes the splits `split_ids` of the index `index_uid` and reports which splits were
+    /// deleted and which were not.
+    ///
+    /// The splits are deleted in batches of 1,000 splits, with up to 10 batches deleted
+    /// concurrently, so that the size of a single request remains bounded. Each batch is deleted
+    /// atomically. A failed batch does not roll back nor interrupt the other batches. Backends
+    /// that can delete all the splits at once atomically override this behavior.
+    async fn delete_splits_bulk(
+        &mut self,
+        index_uid: IndexUid,
+        split_ids: Vec<SplitId>,
+    ) -> DeleteSplitsBulkOutcome
+    where
+        Self: Clone + Sized,
+    {
+        let batches: Vec<Vec<SplitId>> = split_ids
+            .into_iter()
+            .chunks(DELETE_BATCH_SIZE)
+            .into_iter()
+            .map(|batch| batch.collect())
+            .collect();
+        let mut delete_batch_futures = stream::iter(batches)
+            .map(|split_ids_batch| {
+                let mut metastore = self.clone();
+                let delete_splits_request = DeleteSplitsRequest {
+                    index_uid: Some(index_uid.clone()),
+                    split_ids: split_ids_batch.clone(),
+                };
+                async move {
+                    let delete_result = metastore.delete_splits(delete_splits_request).await;
+                    (split_ids_batch, delete_result)
+                }
+            })
+            .buffer_unordered(MAX_DELETE_PARALLELISM);
+
+        let mut outcome = DeleteSplitsBulkOutcome::default();
+
+        while let Some((split_ids_batch, delete_result)) = delete_batch_futures.next().await {
+            match delete_result {
+                Ok(_) => outcome.deleted_split_ids.extend(split_ids_batch),
+                Err(metastore_error) => {
+                    outcome.failed_split_ids.extend(split_ids_batch);
+
+                    if outcome.error_opt.is_none() {
+                        outcome.error_opt = Some(metastore_error);
+                    }
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Returns the total size (in bytes) of the published splits of the index `index_uid`.
+    async fn get_index_size_bytes(&mut self, index_uid: IndexUid) -> MetastoreResult<u64> {
+        let query = ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        let splits_metadata = self
+            .list_splits(list_splits_request)
+            .await?
+            .collect_splits_metadata()
+            .await?;
+        let index_size_bytes = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.size_in_bytes)
+            .sum();
+        Ok(index_size_bytes)
+    }
+
+    /// Returns the published splits of the index `index_uid` containing at least one document
+    /// with the field `field_name`.
+    async fn list_splits_with_field(
+        &mut self,
+        index_uid: IndexUid,
+        field_name: &str,
+    ) -> MetastoreResult<Vec<SplitMetadata>> {
+        let query = ListSplitsQuery::for_index(index_uid)
+            .with_split_state(SplitState::Published)
+            .with_field_name(field_name);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        self.list_splits(list_splits_request)
+            .await?
+            .collect_splits_metadata()
+            .await
+    }
+
+    /// Returns the union of the field names of the published splits of the index `index_uid`.
+    async fn list_field_names(&mut self, index_uid: IndexUid) -> MetastoreResult<BTreeSet<String>> {
+        let query = ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        let splits_metadata = self
+            .list_splits(list_splits_request)
+            .await?
+            .collect_splits_metadata()
+            .await?;
+        let field_names = splits_metadata
+            .into_iter()
+            .flat_map(|split_metadata| split_metadata.field_names)
+            .collect();
+        Ok(field_names)
+    }
 }
 
 impl MetastoreServiceExt for MetastoreServiceClient {}
@@ -188,6 +468,9 @@ pub trait UpdateIndexRequestExt {
         index_uid: impl Into<IndexUid>,
         search_settings: &SearchSettings,
         retention_policy_opt: &Option<RetentionPolicy>,
+        doc_mapping_opt: Option<&DocMapping>,
+        index_state_opt: Option<IndexState>,
+        indexing_settings_opt: Option<&IndexingSettings>,
     ) -> MetastoreResult<UpdateIndexRequest>;
 
     /// Deserializes the `search_settings_json` field of an [`UpdateIndexRequest`] into a
@@ -197,6 +480,21 @@ pub trait UpdateIndexRequestExt {
     /// Deserializes the `retention_policy_json` field of an [`UpdateIndexRequest`] into a
     /// [`RetentionPolicy`] object.
     fn deserialize_retention_policy(&self) -> MetastoreResult<Option<RetentionPolicy>>;
+
+    /// Deserializes the `doc_mapping_json` field of an [`UpdateIndexRequest`] into a
+    /// [`DocMapping`] object.
+    fn deserialize_doc_mapping(&self) -> MetastoreResult<Option<DocMapping>>;
+
+    /// Deserializes the `index_state_json` field of an [`UpdateIndexRequest`] into an
+    /// [`IndexState`] object.
+    fn deserialize_index_state(&self) -> MetastoreResult<Option<IndexState>>;
+
+    /// Deserializes the `indexing_settings_json` field of an [`UpdateIndexRequest`] into an
+    /// [`IndexingSettings`] object.
+    fn deserialize_indexing_settings(&self) -> MetastoreResult<Option<IndexingSettings>>;
+
+    /// Parses the `index_uri` field of an [`UpdateIndexRequest`] into a [`Uri`].
+    fn deserialize_index_uri(&self) -> MetastoreResult<Option<Uri>>;
 }
 
 impl UpdateIndexRequestExt for UpdateIndexRequest {
@@ -204,17 +502,32 @@ impl UpdateIndexRequestExt for UpdateIndexRequest {
         index_uid: impl Into<IndexUid>,
         search_settings: &SearchSettings,
         retention_policy_opt: &Option<RetentionPolicy>,
+        doc_mapping_opt: Option<&DocMapping>,
+        index_state_opt: Option<IndexState>,
+        indexing_settings_opt: Option<&IndexingSettings>,
     ) -> MetastoreResult<UpdateIndexRequest> {
         let search_settings_json = serde_utils::to_json_str(&search_settings)?;
         let retention_policy_json = retention_policy_opt
             .as_ref()
             .map(serde_utils::to_json_str)
             .transpose()?;
+        let doc_mapping_json = doc_mapping_opt.map(serde_utils::to_json_str).transpose()?;
+        let index_state_json = index_state_opt
+            .as_ref()
+            .map(serde_utils::to_json_str)
+            .transpose()?;
+        let indexing_settings_json = indexing_settings_opt
+            .map(serde_utils::to_json_str)
+            .transpose()?;
 
         let update_request = UpdateIndexRequest {
             index_uid: Some(index_uid.into()),
             search_settings_json,
             retention_policy_json,
+            doc_mapping_json,
+            index_state_json,
+            indexing_settings_json,
+            index_uri: None,
         };
         Ok(update_request)
     }
@@ -229,6 +542,38 @@ impl UpdateIndexRequestExt for UpdateIndexRequest {
             .map(|policy| serde_utils::from_json_str(policy))
             .transpose()
     }
+
+    fn deserialize_doc_mapping(&self) -> MetastoreResult<Option<DocMapping>> {
+        self.doc_mapping_json
+            .as_ref()
+            .map(|doc_mapping| serde_utils::from_json_str(doc_mapping))
+            .transpose()
+    }
+
+    fn deserialize_index_state(&self) -> MetastoreResult<Option<IndexState>> {
+        self.index_state_json
+            .as_ref()
+            .map(|index_state| serde_utils::from_json_str(index_state))
+            .transpose()
+    }
+
+    fn deserialize_indexing_settings(&self) -> MetastoreResult<Option<IndexingSettings>> {
+        self.indexing_settings_json
+            .as_ref()
+            .map(|indexing_settings| serde_utils::from_json_str(indexing_settings))
+            .transpose()
+    }
+
+    fn deserialize_index_uri(&self) -> MetastoreResult<Option<Uri>> {
+        self.index_uri
+            .as_deref()
+            .map(|index_uri| {
+                Uri::from_str(index_uri).map_err(|error| MetastoreError::InvalidArgument {
+                    message: format!("invalid index URI `{index_uri}`: {error}"),
+                })
+            })
+            .transpose()
+    }
 }
 
 /// Helper trait to build a [`IndexMetadataResponse`] and deserialize its payload.
@@ -444,7 +789,15 @@ impl StageSplitsRequestExt for StageSplitsRequest {
     }
 
     fn deserialize_splits_metadata(&self) -> MetastoreResult<Vec<SplitMetadata>> {
-        serde_utils::from_json_str(&self.split_metadata_list_serialized_json)
+        let splits_metadata: Vec<SplitMetadata> =
+            serde_utils::from_json_str(&self.split_metadata_list_serialized_json)?;
+
+        validate_split_ids(
+            splits_metadata
+                .iter()
+                .map(|split_metadata| &split_metadata.split_id),
+        )?;
+        Ok(splits_metadata)
     }
 }
 
@@ -574,6 +927,9 @@ pub struct ListSplitsQuery {
     /// A specific set of tag(s) to filter by.
     pub tags: Option<TagFilterAst>,
 
+    /// A field that the splits must contain.
+    pub field_name: Option<String>,
+
     /// The time range to filter by.
     pub time_range: FilterRange<i64>,
 
@@ -605,6 +961,7 @@ impl ListSplitsQuery {
             offset: None,
             split_states: Vec::new(),
             tags: None,
+            field_name: None,
             time_range: Default::default(),
             delete_opstamp: Default::default(),
             update_timestamp: Default::default(),
@@ -630,6 +987,7 @@ impl ListSplitsQuery {
             offset: None,
             split_states: Vec::new(),
             tags: None,
+            field_name: None,
             time_range: Default::default(),
             delete_opstamp: Default::default(),
             update_timestamp: Default::default(),
@@ -675,6 +1033,12 @@ impl ListSplitsQuery {
         self
     }
 
+    /// Selects splits containing at least one document with the given field.
+    pub fn with_field_name(mut self, field_name: impl Into<String>) -> Self {
+        self.field_name = Some(field_name.into());
+        self
+    }
+
     /// Sets the field's lower bound to match values that are
     /// *less than or equal to* the provided value.
     pub fn with_time_range_end_lte(mut self, v: i64) -> Self {
@@ -879,8 +1243,78 @@ impl<T> Default for FilterRange<T> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use quickwit_proto::metastore::{EmptyResponse, MockMetastoreService};
+
     use super::*;
 
+    #[tokio::test]
+    async fn test_delete_splits_bulk() {
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let num_requests_clone = num_requests.clone();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_delete_splits()
+            .times(5)
+            .returning(move |request| {
+                assert_eq!(request.split_ids.len(), DELETE_BATCH_SIZE);
+                num_requests_clone.fetch_add(1, Ordering::Relaxed);
+                Ok(EmptyResponse {})
+            });
+        let mut metastore = MetastoreServiceClient::from_mock(mock_metastore);
+
+        let index_uid = IndexUid::for_test("test-index", 0);
+        let split_ids: Vec<SplitId> = (0..5_000)
+            .map(|split_ord| format!("split-{split_ord}"))
+            .collect();
+        let mut outcome = metastore
+            .delete_splits_bulk(index_uid, split_ids.clone())
+            .await;
+        outcome
+            .deleted_split_ids
+            .sort_by_key(|split_id| split_id[6..].parse::<usize>().unwrap());
+        assert_eq!(outcome.deleted_split_ids, split_ids);
+        assert!(outcome.failed_split_ids.is_empty());
+        assert!(outcome.error_opt.is_none());
+        assert_eq!(num_requests.load(Ordering::Relaxed), 5);
+    }
+
+    #[tokio::test]
+    async fn test_delete_splits_bulk_error() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_delete_splits().returning(|request| {
+            if request.split_ids[0] == "split-1000" {
+                return Err(MetastoreError::Internal {
+                    message: "failed to delete splits".to_string(),
+                    cause: "".to_string(),
+                });
+            }
+            Ok(EmptyResponse {})
+        });
+        let mut metastore = MetastoreServiceClient::from_mock(mock_metastore);
+
+        let index_uid = IndexUid::for_test("test-index", 0);
+        let split_ids: Vec<SplitId> = (0..2_500)
+            .map(|split_ord| format!("split-{split_ord}"))
+            .collect();
+        let mut outcome = metastore
+            .delete_splits_bulk(index_uid, split_ids.clone())
+            .await;
+        // The failed batch does not prevent the other batches from being deleted.
+        outcome
+            .deleted_split_ids
+            .sort_by_key(|split_id| split_id[6..].parse::<usize>().unwrap());
+        assert_eq!(outcome.deleted_split_ids[..1_000], split_ids[..1_000]);
+        assert_eq!(outcome.deleted_split_ids[1_000..], split_ids[2_000..]);
+        assert_eq!(outcome.failed_split_ids, split_ids[1_000..2_000]);
+        assert!(matches!(
+            outcome.error_opt,
+            Some(MetastoreError::Internal { .. })
+        ));
+    }
+
     #[test]
     fn test_filter_contains() {
         let filter = FilterRange {
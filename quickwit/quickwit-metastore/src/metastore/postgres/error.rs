@@ -17,14 +17,102 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::io;
+use std::time::Duration;
+
+use quickwit_common::uri::Uri;
 use quickwit_proto::metastore::{EntityKind, MetastoreError};
+use sqlx::migrate::MigrateError;
 use sqlx::postgres::PgDatabaseError;
 use tracing::error;
 
+use crate::MetastoreResolverError;
+
 // https://www.postgresql.org/docs/current/errcodes-appendix.html
-mod pg_error_codes {
+pub(super) mod pg_error_codes {
     pub const FOREIGN_KEY_VIOLATION: &str = "23503";
     pub const UNIQUE_VIOLATION: &str = "23505";
+    pub const INSUFFICIENT_PRIVILEGE: &str = "42501";
+    pub const UNDEFINED_TABLE: &str = "42P01";
+    pub const INVALID_AUTHORIZATION_SPECIFICATION: &str = "28000";
+    pub const INVALID_PASSWORD: &str = "28P01";
+}
+
+/// Converts an error that occurred while connecting to the database or initializing its schema
+/// into a [`MetastoreResolverError`] carrying as much diagnostic information as possible.
+pub(super) fn convert_sqlx_connection_err(
+    connection_uri: &Uri,
+    elapsed: Duration,
+    sqlx_error: sqlx::Error,
+) -> MetastoreResolverError {
+    error!(connection_uri=%connection_uri, error=?sqlx_error, "failed to establish connection to database");
+
+    match sqlx_error {
+        sqlx::Error::PoolTimedOut => MetastoreResolverError::Timeout {
+            uri: connection_uri.clone(),
+            elapsed,
+        },
+        sqlx::Error::Io(io_error) if io_error.kind() == io::ErrorKind::ConnectionRefused => {
+            MetastoreResolverError::ConnectionRefused(connection_uri.clone())
+        }
+        sqlx::Error::Io(io_error) if io_error.kind() == io::ErrorKind::TimedOut => {
+            MetastoreResolverError::Timeout {
+                uri: connection_uri.clone(),
+                elapsed,
+            }
+        }
+        sqlx::Error::Configuration(error) => {
+            MetastoreResolverError::InvalidConfig(error.to_string())
+        }
+        sqlx::Error::Database(boxed_db_error) => {
+            let pg_db_error = boxed_db_error.downcast_ref::<PgDatabaseError>();
+
+            match pg_db_error.code() {
+                pg_error_codes::INVALID_PASSWORD
+                | pg_error_codes::INVALID_AUTHORIZATION_SPECIFICATION => {
+                    MetastoreResolverError::AuthenticationFailed(pg_db_error.message().to_string())
+                }
+                pg_error_codes::INSUFFICIENT_PRIVILEGE => {
+                    MetastoreResolverError::InsufficientPermissions(
+                        pg_db_error.message().to_string(),
+                    )
+                }
+                _ => MetastoreResolverError::Initialization(MetastoreError::Connection {
+                    message: boxed_db_error.to_string(),
+                }),
+            }
+        }
+        _ => MetastoreResolverError::Initialization(MetastoreError::Connection {
+            message: sqlx_error.to_string(),
+        }),
+    }
+}
+
+/// Converts an error that occurred while running the schema migrations into a
+/// [`MetastoreResolverError`]. `latest_version` is the version of the most recent migration known
+/// to this version of Quickwit.
+pub(super) fn convert_migrate_err(
+    latest_version: u32,
+    migrate_error: MigrateError,
+) -> MetastoreResolverError {
+    error!(error=%migrate_error, "failed to run PostgreSQL migrations");
+
+    match migrate_error {
+        // The database has been migrated by a more recent version of Quickwit.
+        MigrateError::VersionMissing(found) => MetastoreResolverError::IncompatibleSchemaVersion {
+            supported: latest_version,
+            found: found as u32,
+        },
+        MigrateError::Execute(sqlx::Error::Database(boxed_db_error))
+            if boxed_db_error.code().as_deref() == Some(pg_error_codes::INSUFFICIENT_PRIVILEGE) =>
+        {
+            MetastoreResolverError::InsufficientPermissions(boxed_db_error.message().to_string())
+        }
+        _ => MetastoreResolverError::Initialization(MetastoreError::Internal {
+            message: "failed to run PostgreSQL migrations".to_string(),
+            cause: migrate_error.to_string(),
+        }),
+    }
 }
 
 pub(super) fn convert_sqlx_err(index_id: &str, sqlx_error: sqlx::Error) -> MetastoreError {
@@ -68,3 +156,50 @@ pub(super) fn convert_sqlx_err(index_id: &str, sqlx_error: sqlx::Error) -> Metas
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_sqlx_connection_err() {
+        let uri = Uri::for_test("postgresql://localhost:5432/metastore");
+
+        let resolver_error =
+            convert_sqlx_connection_err(&uri, Duration::from_secs(10), sqlx::Error::PoolTimedOut);
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Timeout { elapsed, .. } if elapsed == Duration::from_secs(10)
+        ));
+
+        let io_error = io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused");
+        let resolver_error =
+            convert_sqlx_connection_err(&uri, Duration::from_secs(1), sqlx::Error::Io(io_error));
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::ConnectionRefused(refused_uri) if refused_uri == uri
+        ));
+
+        let resolver_error = convert_sqlx_connection_err(
+            &uri,
+            Duration::from_secs(1),
+            sqlx::Error::Protocol("unexpected message".to_string()),
+        );
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Initialization(MetastoreError::Connection { .. })
+        ));
+    }
+
+    #[test]
+    fn test_convert_migrate_err() {
+        let resolver_error = convert_migrate_err(20, MigrateError::VersionMissing(21));
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::IncompatibleSchemaVersion {
+                supported: 20,
+                found: 21
+            }
+        ));
+    }
+}
@@ -19,6 +19,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use quickwit_common::uri::Uri;
@@ -27,8 +28,13 @@ use quickwit_proto::metastore::MetastoreServiceClient;
 use tokio::sync::Mutex;
 use tracing::debug;
 
+use super::migrator::check_migration_version;
+use super::utils::establish_connection;
 use crate::{MetastoreFactory, MetastoreResolverError, PostgresqlMetastore};
 
+/// Maximum amount of time to wait for a connection when checking the schema version.
+const VERSION_CHECK_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Clone, Default)]
 pub struct PostgresqlMetastoreFactory {
     // Under normal conditions of use, this cache will contain a single `Metastore`.
@@ -89,11 +95,17 @@ impl MetastoreFactory for PostgresqlMetastoreFactory {
         })?;
         let postgresql_metastore = PostgresqlMetastore::new(postgresql_metastore_config, uri)
             .await
-            .map(MetastoreServiceClient::new)
-            .map_err(MetastoreResolverError::Initialization)?;
+            .map(MetastoreServiceClient::new)?;
         let unique_metastore_for_uri = self
             .cache_metastore(uri.clone(), postgresql_metastore)
             .await;
         Ok(unique_metastore_for_uri)
     }
+
+    /// Checks the version of the most recent migration applied to the database.
+    async fn version_check(&self, uri: &Uri) -> Result<(), MetastoreResolverError> {
+        let connection_pool =
+            establish_connection(uri, 0, 1, VERSION_CHECK_ACQUIRE_TIMEOUT, None, None).await?;
+        check_migration_version(&connection_pool).await
+    }
 }
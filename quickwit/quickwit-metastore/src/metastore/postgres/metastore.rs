@@ -19,6 +19,7 @@
 
 use std::collections::HashMap;
 use std::fmt::{self, Write};
+use std::time::Instant;
 
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -32,28 +33,32 @@ use quickwit_config::{
 };
 use quickwit_proto::ingest::{Shard, ShardState};
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
-    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateIndexAliasRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
+    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    EntityKind, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
+    GetIndexTemplateRequest, GetIndexTemplateResponse, GetSourceCheckpointRequest,
+    GetSourceCheckpointResponse, IndexAlias, IndexMetadataFailure, IndexMetadataFailureReason,
     IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
     IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListShardsSubresponse, ListSplitsRequest,
-    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
-    OpenShardSubresponse, OpenShardsRequest, OpenShardsResponse, PublishSplitsRequest,
-    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest, UpdateIndexRequest,
-    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
+    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexAliasesRequest,
+    ListIndexAliasesResponse, ListIndexTemplatesRequest, ListIndexTemplatesResponse,
+    ListIndexesMetadataRequest, ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse,
+    ListShardsSubresponse, ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest,
+    MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult, MetastoreService,
+    MetastoreServiceStream, OpenShardSubrequest, OpenShardSubresponse, OpenShardsRequest,
+    OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
+    ToggleSourceRequest, UpdateIndexAliasRequest, UpdateIndexAliasResponse, UpdateIndexRequest,
+    UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid, Position, PublishToken, ShardId, SourceId};
 use sea_query::{Alias, Asterisk, Expr, Func, PostgresQueryBuilder, Query, UnionType};
 use sea_query_binder::SqlxBinder;
 use sqlx::{Acquire, Executor, Postgres, Transaction};
-use tracing::{debug, info, instrument, warn};
+use tracing::field::Empty;
+use tracing::{debug, info, instrument, warn, Span};
 
 use super::error::convert_sqlx_err;
 use super::migrator::run_migrations;
@@ -66,14 +71,17 @@ use crate::checkpoint::{
 };
 use crate::file_backed::MutationOccurred;
 use crate::metastore::postgres::model::Shards;
-use crate::metastore::postgres::utils::split_maturity_timestamp;
 use crate::metastore::{
-    IndexesMetadataResponseExt, PublishSplitsRequestExt, STREAM_SPLITS_CHUNK_SIZE,
+    apply_index_alias_update, check_index_alias_indexes_exist, record_duration_span_field,
+    record_list_span_fields, split_maturity_timestamp, validate_create_index_alias_request,
+    validate_split_ids, IndexesMetadataResponseExt, PublishSplitsRequestExt,
+    STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt, UpdateIndexRequestExt,
+    MetastoreResolverError, MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt,
+    UpdateIndexRequestExt,
 };
 
 /// PostgreSQL metastore implementation.
@@ -96,7 +104,7 @@ impl PostgresqlMetastore {
     pub async fn new(
         postgres_metastore_config: &PostgresMetastoreConfig,
         connection_uri: &Uri,
-    ) -> MetastoreResult<Self> {
+    ) -> Result<Self, MetastoreResolverError> {
         let min_connections = postgres_metastore_config.min_connections;
         let max_connections = postgres_metastore_config.max_connections.get();
         let acquire_timeout = postgres_metastore_config
@@ -398,12 +406,28 @@ impl MetastoreService for PostgresqlMetastore {
     ) -> MetastoreResult<IndexMetadataResponse> {
         let retention_policy_opt = request.deserialize_retention_policy()?;
         let search_settings = request.deserialize_search_settings()?;
+        let doc_mapping_opt = request.deserialize_doc_mapping()?;
+        let index_state_opt = request.deserialize_index_state()?;
+        let indexing_settings_opt = request.deserialize_indexing_settings()?;
+        let index_uri_opt = request.deserialize_index_uri()?;
         let index_uid: IndexUid = request.index_uid().clone();
         let updated_index_metadata = run_with_tx!(self.connection_pool, tx, {
             mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
                 let mut mutation_occurred =
                     index_metadata.set_retention_policy(retention_policy_opt);
                 mutation_occurred |= index_metadata.set_search_settings(search_settings);
+                if let Some(doc_mapping) = doc_mapping_opt {
+                    mutation_occurred |= index_metadata.set_doc_mapping(doc_mapping)?;
+                }
+                if let Some(index_state) = index_state_opt {
+                    mutation_occurred |= index_metadata.set_index_state(index_state);
+                }
+                if let Some(indexing_settings) = indexing_settings_opt {
+                    mutation_occurred |= index_metadata.set_indexing_settings(indexing_settings);
+                }
+                if let Some(index_uri) = index_uri_opt {
+                    mutation_occurred |= index_metadata.set_index_uri(index_uri);
+                }
                 Ok(MutationOccurred::from(mutation_occurred))
             })
             .await
@@ -438,12 +462,13 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(response)
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
     async fn indexes_metadata(
         &mut self,
         request: IndexesMetadataRequest,
     ) -> MetastoreResult<IndexesMetadataResponse> {
         const INDEXES_METADATA_QUERY: &str = include_str!("queries/indexes_metadata.sql");
+        let start = Instant::now();
 
         let num_subrequests = request.subrequests.len();
 
@@ -507,16 +532,18 @@ impl MetastoreService for PostgresqlMetastore {
                 }
             }
         }
+        record_list_span_fields(indexes_metadata.len(), start);
         let response =
             IndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata, failures).await?;
         Ok(response)
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
     async fn list_indexes_metadata(
         &mut self,
         request: ListIndexesMetadataRequest,
     ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        let start = Instant::now();
         let sql =
             build_index_id_patterns_sql_query(&request.index_id_patterns).map_err(|error| {
                 MetastoreError::Internal {
@@ -531,12 +558,13 @@ impl MetastoreService for PostgresqlMetastore {
             .into_iter()
             .map(|pg_index| pg_index.index_metadata())
             .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(indexes_metadata.len(), start);
         let response =
             ListIndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata).await?;
         Ok(response)
     }
 
-    #[instrument(skip_all, fields(index_id=%request.index_uid()))]
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
     async fn delete_index(
         &mut self,
         request: DeleteIndexRequest,
@@ -556,12 +584,17 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(EmptyResponse {})
     }
 
-    #[instrument(skip_all, fields(split_ids))]
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids,
+        split_ids_count = Empty,
+    ))]
     async fn stage_splits(
         &mut self,
         request: StageSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
         let splits_metadata = request.deserialize_splits_metadata()?;
+        Span::current().record("split_ids_count", splits_metadata.len());
 
         if splits_metadata.is_empty() {
             return Ok(Default::default());
@@ -575,6 +608,8 @@ impl MetastoreService for PostgresqlMetastore {
         let mut delete_opstamps = Vec::with_capacity(splits_metadata.len());
         let mut maturity_timestamps = Vec::with_capacity(splits_metadata.len());
         let mut node_ids = Vec::with_capacity(splits_metadata.len());
+        let mut sizes_in_bytes = Vec::with_capacity(splits_metadata.len());
+        let mut field_names_list = Vec::with_capacity(splits_metadata.len());
 
         for split_metadata in splits_metadata {
             let split_metadata_json = serde_utils::to_json_str(&split_metadata)?;
@@ -595,14 +630,17 @@ impl MetastoreService for PostgresqlMetastore {
             split_ids.push(split_metadata.split_id);
             delete_opstamps.push(split_metadata.delete_opstamp as i64);
             node_ids.push(split_metadata.node_id);
+            sizes_in_bytes.push(split_metadata.size_in_bytes as i64);
+            let field_names: Vec<String> = split_metadata.field_names.into_iter().collect();
+            field_names_list.push(sqlx::types::Json(field_names));
         }
-        tracing::Span::current().record("split_ids", format!("{split_ids:?}"));
+        Span::current().record("split_ids", format!("{split_ids:?}"));
 
         // TODO: Remove transaction.
         run_with_tx!(self.connection_pool, tx, {
             let upserted_split_ids: Vec<String> = sqlx::query_scalar(r#"
                 INSERT INTO splits
-                    (split_id, time_range_start, time_range_end, tags, split_metadata_json, delete_opstamp, maturity_timestamp, split_state, index_uid, node_id)
+                    (split_id, time_range_start, time_range_end, tags, split_metadata_json, delete_opstamp, maturity_timestamp, split_state, index_uid, node_id, size_in_bytes, field_names)
                 SELECT
                     split_id,
                     time_range_start,
@@ -611,12 +649,14 @@ impl MetastoreService for PostgresqlMetastore {
                     split_metadata_json,
                     delete_opstamp,
                     to_timestamp(maturity_timestamp),
-                    $9 as split_state,
-                    $10 as index_uid,
-                    node_id
+                    $11 as split_state,
+                    $12 as index_uid,
+                    node_id,
+                    size_in_bytes,
+                    ARRAY(SELECT json_array_elements_text(field_names_json::json)) as field_names
                 FROM
-                    UNNEST($1, $2, $3, $4, $5, $6, $7, $8)
-                    AS staged_splits (split_id, time_range_start, time_range_end, tags_json, split_metadata_json, delete_opstamp, maturity_timestamp, node_id)
+                    UNNEST($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                    AS staged_splits (split_id, time_range_start, time_range_end, tags_json, split_metadata_json, delete_opstamp, maturity_timestamp, node_id, size_in_bytes, field_names_json)
                 ON CONFLICT(split_id) DO UPDATE
                     SET
                         time_range_start = excluded.time_range_start,
@@ -627,6 +667,8 @@ impl MetastoreService for PostgresqlMetastore {
                         maturity_timestamp = excluded.maturity_timestamp,
                         index_uid = excluded.index_uid,
                         node_id = excluded.node_id,
+                        size_in_bytes = excluded.size_in_bytes,
+                        field_names = excluded.field_names,
                         update_timestamp = CURRENT_TIMESTAMP,
                         create_timestamp = CURRENT_TIMESTAMP
                     WHERE splits.split_id = excluded.split_id AND splits.split_state = 'Staged'
@@ -640,6 +682,8 @@ impl MetastoreService for PostgresqlMetastore {
                 .bind(delete_opstamps)
                 .bind(maturity_timestamps)
                 .bind(&node_ids)
+                .bind(sizes_in_bytes)
+                .bind(field_names_list)
                 .bind(SplitState::Staged.as_str())
                 .bind(&index_uid)
                 .fetch_all(tx.as_mut())
@@ -665,11 +709,20 @@ impl MetastoreService for PostgresqlMetastore {
         })
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.staged_split_ids.len(),
+    ))]
     async fn publish_splits(
         &mut self,
         request: PublishSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        validate_split_ids(
+            request
+                .staged_split_ids
+                .iter()
+                .chain(&request.replaced_split_ids),
+        )?;
         let checkpoint_delta_opt: Option<IndexCheckpointDelta> =
             request.deserialize_index_checkpoint()?;
         let index_uid: IndexUid = request.index_uid().clone();
@@ -883,11 +936,17 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(service_stream)
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
     async fn mark_splits_for_deletion(
         &mut self,
         request: MarkSplitsForDeletionRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
         let index_uid: IndexUid = request.index_uid().clone();
         let split_ids = request.split_ids;
         const MARK_SPLITS_FOR_DELETION_QUERY: &str = r#"
@@ -956,14 +1015,21 @@ impl MetastoreService for PostgresqlMetastore {
                 not_found_split_ids.len()
             );
         }
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
     async fn delete_splits(
         &mut self,
         request: DeleteSplitsRequest,
     ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
         let index_uid: IndexUid = request.index_uid().clone();
         let split_ids = request.split_ids;
         const DELETE_SPLITS_QUERY: &str = r#"
@@ -1045,6 +1111,7 @@ impl MetastoreService for PostgresqlMetastore {
                 not_found_split_ids.len()
             );
         }
+        record_duration_span_field(start);
         Ok(EmptyResponse {})
     }
 
@@ -1133,6 +1200,31 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip(self))]
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let source_checkpoint =
+            SourceCheckpoint::from(request.source_checkpoint.unwrap_or_default());
+        run_with_tx!(self.connection_pool, tx, {
+            mutate_index_metadata(tx, index_uid, |index_metadata| {
+                if index_metadata
+                    .checkpoint
+                    .set_source_checkpoint(&request.source_id, source_checkpoint)
+                {
+                    Ok::<_, MetastoreError>(MutationOccurred::Yes(()))
+                } else {
+                    Ok::<_, MetastoreError>(MutationOccurred::No(()))
+                }
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
     /// Retrieves the last delete opstamp for a given `index_id`.
     #[instrument(skip(self))]
     async fn last_delete_opstamp(
@@ -1156,6 +1248,31 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(LastDeleteOpstampResponse::new(max_opstamp as u64))
     }
 
+    #[instrument(skip(self))]
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let index_metadata = index_opt_for_uid(&self.connection_pool, index_uid.clone())
+            .await?
+            .ok_or_else(|| {
+                MetastoreError::NotFound(EntityKind::Index {
+                    index_id: index_uid.index_id.clone(),
+                })
+            })?
+            .index_metadata()?;
+        let source_checkpoint = index_metadata
+            .checkpoint
+            .source_checkpoint(&request.source_id)
+            .cloned()
+            .unwrap_or_default();
+        let response = GetSourceCheckpointResponse {
+            source_checkpoint: Some(source_checkpoint.into()),
+        };
+        Ok(response)
+    }
+
     /// Creates a delete task from a delete query.
     #[instrument(skip(self))]
     async fn create_delete_task(
@@ -1189,7 +1306,11 @@ impl MetastoreService for PostgresqlMetastore {
     }
 
     /// Update splits delete opstamps.
-    #[instrument(skip(self))]
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        delete_opstamp = request.delete_opstamp,
+    ))]
     async fn update_splits_delete_opstamp(
         &mut self,
         request: UpdateSplitsDeleteOpstampRequest,
@@ -1234,11 +1355,12 @@ impl MetastoreService for PostgresqlMetastore {
     }
 
     /// Lists the delete tasks with opstamp > `opstamp_start`.
-    #[instrument(skip(self))]
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
     async fn list_delete_tasks(
         &mut self,
         request: ListDeleteTasksRequest,
     ) -> MetastoreResult<ListDeleteTasksResponse> {
+        let start = Instant::now();
         let index_uid: IndexUid = request.index_uid().clone();
         let pg_delete_tasks: Vec<PgDeleteTask> = sqlx::query_as::<_, PgDeleteTask>(
             r#"
@@ -1256,17 +1378,19 @@ impl MetastoreService for PostgresqlMetastore {
             .into_iter()
             .map(|pg_delete_task| pg_delete_task.try_into())
             .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(delete_tasks.len(), start);
         Ok(ListDeleteTasksResponse { delete_tasks })
     }
 
     /// Returns `num_splits` published splits with `split.delete_opstamp` < `delete_opstamp`.
     /// Results are ordered by ascending `split.delete_opstamp` and `split.publish_timestamp`
     /// values.
-    #[instrument(skip(self))]
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
     async fn list_stale_splits(
         &mut self,
         request: ListStaleSplitsRequest,
     ) -> MetastoreResult<ListSplitsResponse> {
+        let start = Instant::now();
         let index_uid: IndexUid = request.index_uid().clone();
         let stale_pg_splits: Vec<PgSplit> = sqlx::query_as::<_, PgSplit>(
             r#"
@@ -1292,6 +1416,7 @@ impl MetastoreService for PostgresqlMetastore {
             .into_iter()
             .map(|pg_split| pg_split.try_into())
             .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(stale_splits.len(), start);
         let response = ListSplitsResponse::try_from_splits(stale_splits)?;
         Ok(response)
     }
@@ -1626,6 +1751,127 @@ impl MetastoreService for PostgresqlMetastore {
             .await?;
         Ok(EmptyResponse {})
     }
+
+    // Index Alias API
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_ids = validate_create_index_alias_request(&request)?;
+
+        run_with_tx!(self.connection_pool, tx, {
+            check_pg_index_alias_indexes_exist(tx, &index_ids).await?;
+
+            let pg_query_result = sqlx::query(
+                r#"
+                INSERT INTO index_aliases(alias_id, index_ids)
+                    VALUES ($1, $2)
+                ON CONFLICT (alias_id)
+                    DO NOTHING
+                "#,
+            )
+            .bind(&request.alias_id)
+            .bind(&index_ids)
+            .execute(tx.as_mut())
+            .await?;
+
+            if pg_query_result.rows_affected() == 0 {
+                return Err(MetastoreError::AlreadyExists(EntityKind::IndexAlias {
+                    alias_id: request.alias_id,
+                }));
+            }
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        let pg_index_aliases: Vec<(String, Vec<IndexId>)> = if request.alias_ids.is_empty() {
+            sqlx::query_as("SELECT alias_id, index_ids FROM index_aliases ORDER BY alias_id ASC")
+                .fetch_all(&self.connection_pool)
+                .await?
+        } else {
+            sqlx::query_as(
+                "SELECT alias_id, index_ids FROM index_aliases WHERE alias_id = ANY($1) ORDER BY \
+                 alias_id ASC",
+            )
+            .bind(&request.alias_ids)
+            .fetch_all(&self.connection_pool)
+            .await?
+        };
+        let index_aliases = pg_index_aliases
+            .into_iter()
+            .map(|(alias_id, index_ids)| IndexAlias {
+                alias_id,
+                index_ids,
+            })
+            .collect();
+        let response = ListIndexAliasesResponse { index_aliases };
+        Ok(response)
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        let index_alias = run_with_tx!(self.connection_pool, tx, {
+            // Locks the row until the end of the transaction so that concurrent updates of the
+            // same alias are serialized.
+            let mut index_ids: Vec<IndexId> = sqlx::query_scalar(
+                "SELECT index_ids FROM index_aliases WHERE alias_id = $1 FOR UPDATE",
+            )
+            .bind(&request.alias_id)
+            .fetch_optional(tx.as_mut())
+            .await?
+            .ok_or_else(|| {
+                MetastoreError::NotFound(EntityKind::IndexAlias {
+                    alias_id: request.alias_id.clone(),
+                })
+            })?;
+            check_pg_index_alias_indexes_exist(tx, &request.add_index_ids).await?;
+            apply_index_alias_update(&request, &mut index_ids)?;
+
+            sqlx::query("UPDATE index_aliases SET index_ids = $1 WHERE alias_id = $2")
+                .bind(&index_ids)
+                .bind(&request.alias_id)
+                .execute(tx.as_mut())
+                .await?;
+            Ok(IndexAlias {
+                alias_id: request.alias_id,
+                index_ids,
+            })
+        })?;
+        let response = UpdateIndexAliasResponse {
+            index_alias: Some(index_alias),
+        };
+        Ok(response)
+    }
+}
+
+/// Returns a `NotFound` error if one of the indexes an alias resolves to does not exist. The
+/// indexes are locked until the end of the transaction so that they cannot be deleted in the
+/// meantime.
+async fn check_pg_index_alias_indexes_exist(
+    tx: &mut Transaction<'_, Postgres>,
+    index_ids: &[IndexId],
+) -> MetastoreResult<()> {
+    if index_ids.is_empty() {
+        return Ok(());
+    }
+    let existing_index_ids: Vec<IndexId> =
+        sqlx::query_scalar("SELECT index_id FROM indexes WHERE index_id = ANY($1) FOR SHARE")
+            .bind(index_ids)
+            .fetch_all(tx.as_mut())
+            .await?;
+    check_index_alias_indexes_exist(index_ids, |index_id| {
+        existing_index_ids
+            .iter()
+            .any(|existing_index_id| existing_index_id == index_id)
+    })
 }
 
 async fn open_or_fetch_shard<'e>(
@@ -1836,6 +2082,11 @@ mod tests {
 
     metastore_test_suite!(crate::PostgresqlMetastore);
 
+    #[tokio::test]
+    async fn test_metastore_delete_splits_bulk() {
+        crate::tests::split::test_metastore_delete_splits_bulk::<PostgresqlMetastore>().await;
+    }
+
     #[tokio::test]
     async fn test_metastore_connectivity_and_endpoints() {
         let mut metastore = PostgresqlMetastore::default_for_test().await;
@@ -1995,6 +2246,20 @@ mod tests {
         let mut select_statement = Query::select();
         let sql = select_statement.column(Asterisk).from(Splits::Table);
 
+        let query =
+            ListSplitsQuery::for_index(index_uid.clone()).with_field_name("attributes.host");
+        append_query_filters(sql, &query);
+
+        assert_eq!(
+            sql.to_string(PostgresQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND field_names @> ARRAY[$$attributes.host$$]"#
+            )
+        );
+
+        let mut select_statement = Query::select();
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+
         let query = ListSplitsQuery::for_index(index_uid.clone()).with_offset(4);
         append_query_filters(sql, &query);
 
@@ -17,34 +17,77 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use quickwit_proto::metastore::{MetastoreError, MetastoreResult};
+use quickwit_proto::metastore::MetastoreError;
 use sqlx::migrate::Migrator;
 use sqlx::{Acquire, Postgres};
-use tracing::{error, instrument};
+use tracing::instrument;
 
+use super::error::{convert_migrate_err, pg_error_codes};
 use super::pool::TrackedPool;
+use crate::metastore_factory::check_schema_version;
+use crate::MetastoreResolverError;
 
 static MIGRATOR: Migrator = sqlx::migrate!("migrations/postgresql");
 
 /// Initializes the database and runs the SQL migrations stored in the
 /// `quickwit-metastore/migrations` directory.
 #[instrument(skip_all)]
-pub(super) async fn run_migrations(pool: &TrackedPool<Postgres>) -> MetastoreResult<()> {
-    let mut tx = pool.begin().await?;
-    let conn = tx.acquire().await?;
+pub(super) async fn run_migrations(
+    pool: &TrackedPool<Postgres>,
+) -> Result<(), MetastoreResolverError> {
+    let mut tx = pool.begin().await.map_err(MetastoreError::from)?;
+    let conn = tx.acquire().await.map_err(MetastoreError::from)?;
     // this is an hidden function, made to get "around the annoying "implementation of `Acquire`
     // is not general enough" error", which is the error we get otherwise.
     let migrate_result = MIGRATOR.run_direct(conn).await;
 
     let Err(migrate_error) = migrate_result else {
-        tx.commit().await?;
+        tx.commit().await.map_err(MetastoreError::from)?;
         return Ok(());
     };
-    tx.rollback().await?;
-    error!(error=%migrate_error, "failed to run PostgreSQL migrations");
+    tx.rollback().await.map_err(MetastoreError::from)?;
+    Err(convert_migrate_err(
+        latest_migration_version(),
+        migrate_error,
+    ))
+}
+
+/// Returns the version of the most recent migration embedded in the binary.
+fn latest_migration_version() -> u32 {
+    MIGRATOR
+        .iter()
+        .map(|migration| migration.version as u32)
+        .max()
+        .unwrap_or_default()
+}
 
-    Err(MetastoreError::Internal {
-        message: "failed to run PostgreSQL migrations".to_string(),
-        cause: migrate_error.to_string(),
-    })
+/// Returns the schema version of the database, i.e. the version of the most recent migration
+/// applied to it, or `0` if the database has not been initialized yet.
+async fn applied_migration_version(
+    pool: &TrackedPool<Postgres>,
+) -> Result<u32, MetastoreResolverError> {
+    // The applied migrations are recorded by sqlx in the `_sqlx_migrations` table.
+    let version_res: Result<Option<i64>, sqlx::Error> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(pool)
+            .await;
+    match version_res {
+        Ok(version_opt) => Ok(version_opt.unwrap_or_default() as u32),
+        Err(sqlx::Error::Database(boxed_db_error))
+            if boxed_db_error.code().as_deref() == Some(pg_error_codes::UNDEFINED_TABLE) =>
+        {
+            Ok(0)
+        }
+        Err(sqlx_error) => Err(MetastoreError::from(sqlx_error).into()),
+    }
+}
+
+/// Checks that the migrations applied to the database match the migrations embedded in the
+/// binary.
+#[instrument(skip_all)]
+pub(super) async fn check_migration_version(
+    pool: &TrackedPool<Postgres>,
+) -> Result<(), MetastoreResolverError> {
+    let applied_version = applied_migration_version(pool).await?;
+    check_schema_version(latest_migration_version(), applied_version)
 }
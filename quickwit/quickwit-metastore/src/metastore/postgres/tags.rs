@@ -25,7 +25,7 @@ use sea_query::{all, Cond, Expr};
 // In order to ensure that we do not risk SQL injection,
 // we need to generate a string that does not appear in
 // the literal we want to dollar quote.
-fn generate_dollar_guard(tag: &str) -> String {
+pub(super) fn generate_dollar_guard(tag: &str) -> String {
     if !tag.contains('$') {
         // That's our happy path here.
         return String::new();
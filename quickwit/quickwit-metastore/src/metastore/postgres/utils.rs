@@ -20,21 +20,20 @@
 use std::fmt::Display;
 use std::ops::Bound;
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use quickwit_common::uri::Uri;
-use quickwit_proto::metastore::{MetastoreError, MetastoreResult};
 use sea_query::{any, Expr, Func, Order, SelectStatement};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{ConnectOptions, Postgres};
-use tracing::error;
 use tracing::log::LevelFilter;
 
+use super::error::convert_sqlx_connection_err;
 use super::model::{Splits, ToTimestampFunc};
 use super::pool::TrackedPool;
-use super::tags::generate_sql_condition;
+use super::tags::{generate_dollar_guard, generate_sql_condition};
 use crate::metastore::FilterRange;
-use crate::{ListSplitsQuery, SplitMaturity, SplitMetadata};
+use crate::{ListSplitsQuery, MetastoreResolverError};
 
 /// Establishes a connection to the given database URI.
 pub(super) async fn establish_connection(
@@ -44,25 +43,22 @@ pub(super) async fn establish_connection(
     acquire_timeout: Duration,
     idle_timeout_opt: Option<Duration>,
     max_lifetime_opt: Option<Duration>,
-) -> MetastoreResult<TrackedPool<Postgres>> {
+) -> Result<TrackedPool<Postgres>, MetastoreResolverError> {
     let pool_options = PgPoolOptions::new()
         .min_connections(min_connections as u32)
         .max_connections(max_connections as u32)
         .acquire_timeout(acquire_timeout)
         .idle_timeout(idle_timeout_opt)
         .max_lifetime(max_lifetime_opt);
-    let connect_options: PgConnectOptions = PgConnectOptions::from_str(connection_uri.as_str())?
+    let connect_options: PgConnectOptions = PgConnectOptions::from_str(connection_uri.as_str())
+        .map_err(|error| MetastoreResolverError::InvalidUri(error.to_string()))?
         .application_name("quickwit-metastore")
         .log_statements(LevelFilter::Info);
+    let now = Instant::now();
     let sqlx_pool = pool_options
         .connect_with(connect_options)
         .await
-        .map_err(|error| {
-            error!(connection_uri=%connection_uri, error=?error, "failed to establish connection to database");
-            MetastoreError::Connection {
-                message: error.to_string(),
-            }
-        })?;
+        .map_err(|error| convert_sqlx_connection_err(connection_uri, now.elapsed(), error))?;
     let tracked_pool = TrackedPool::new(sqlx_pool);
     Ok(tracked_pool)
 }
@@ -113,6 +109,14 @@ pub(super) fn append_query_filters(sql: &mut SelectStatement, query: &ListSplits
         sql.cond_where(generate_sql_condition(tags));
     };
 
+    if let Some(field_name) = &query.field_name {
+        // The containment operator, unlike `ANY`, is backed by the GIN index on `field_names`.
+        let dollar_guard = generate_dollar_guard(field_name);
+        sql.cond_where(Expr::cust(format!(
+            "field_names @> ARRAY[${dollar_guard}${field_name}${dollar_guard}$]"
+        )));
+    };
+
     match query.time_range.start {
         Bound::Included(v) => {
             sql.cond_where(any![
@@ -188,15 +192,3 @@ pub(super) fn append_query_filters(sql: &mut SelectStatement, query: &ListSplits
             .offset(offset as u64);
     }
 }
-
-/// Returns the unix timestamp at which the split becomes mature.
-/// If the split is mature (`SplitMaturity::Mature`), we return 0
-/// as we don't want the maturity to depend on datetime.
-pub(super) fn split_maturity_timestamp(split_metadata: &SplitMetadata) -> i64 {
-    match split_metadata.maturity {
-        SplitMaturity::Mature => 0,
-        SplitMaturity::Immature { maturation_period } => {
-            split_metadata.create_timestamp + maturation_period.as_secs() as i64
-        }
-    }
-}
@@ -0,0 +1,184 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use quickwit_common::uri::Uri;
+use quickwit_proto::metastore::{EntityKind, MetastoreError};
+use sqlx::migrate::MigrateError;
+use tracing::error;
+
+use crate::MetastoreResolverError;
+
+// https://www.sqlite.org/rescode.html
+mod sqlite_error_codes {
+    pub const PERM: &str = "3";
+    pub const READONLY: &str = "8";
+    pub const CANTOPEN: &str = "14";
+    pub const CONSTRAINT_PRIMARYKEY: &str = "1555";
+    pub const CONSTRAINT_UNIQUE: &str = "2067";
+    pub const CONSTRAINT_FOREIGNKEY: &str = "787";
+}
+
+/// Converts an error that occurred while opening the database or initializing its schema into a
+/// [`MetastoreResolverError`].
+pub(super) fn convert_sqlx_connection_err(
+    connection_uri: &Uri,
+    elapsed: Duration,
+    sqlx_error: sqlx::Error,
+) -> MetastoreResolverError {
+    error!(connection_uri=%connection_uri, error=?sqlx_error, "failed to open SQLite database");
+
+    match sqlx_error {
+        sqlx::Error::PoolTimedOut => MetastoreResolverError::Timeout {
+            uri: connection_uri.clone(),
+            elapsed,
+        },
+        sqlx::Error::Configuration(error) => {
+            MetastoreResolverError::InvalidConfig(error.to_string())
+        }
+        sqlx::Error::Database(boxed_db_error) => match boxed_db_error.code().as_deref() {
+            Some(sqlite_error_codes::PERM | sqlite_error_codes::READONLY) => {
+                MetastoreResolverError::InsufficientPermissions(
+                    boxed_db_error.message().to_string(),
+                )
+            }
+            Some(sqlite_error_codes::CANTOPEN) => MetastoreResolverError::InvalidUri(format!(
+                "failed to open SQLite database `{connection_uri}`: {}",
+                boxed_db_error.message()
+            )),
+            _ => MetastoreResolverError::Initialization(MetastoreError::Connection {
+                message: boxed_db_error.to_string(),
+            }),
+        },
+        _ => MetastoreResolverError::Initialization(MetastoreError::Connection {
+            message: sqlx_error.to_string(),
+        }),
+    }
+}
+
+/// Converts an error that occurred while running the schema migrations into a
+/// [`MetastoreResolverError`]. `latest_version` is the version of the most recent migration known
+/// to this version of Quickwit.
+pub(super) fn convert_migrate_err(
+    latest_version: u32,
+    migrate_error: MigrateError,
+) -> MetastoreResolverError {
+    error!(error=%migrate_error, "failed to run SQLite migrations");
+
+    match migrate_error {
+        // The database has been migrated by a more recent version of Quickwit.
+        MigrateError::VersionMissing(found) => MetastoreResolverError::IncompatibleSchemaVersion {
+            supported: latest_version,
+            found: found as u32,
+        },
+        _ => MetastoreResolverError::Initialization(MetastoreError::Internal {
+            message: "failed to run SQLite migrations".to_string(),
+            cause: migrate_error.to_string(),
+        }),
+    }
+}
+
+pub(super) fn convert_sqlx_err(index_id: &str, sqlx_error: sqlx::Error) -> MetastoreError {
+    match &sqlx_error {
+        sqlx::Error::Database(boxed_db_error) => {
+            let sqlite_error_code = boxed_db_error.code();
+            // SQLite does not expose the table on which a constraint failed, only a message such
+            // as "UNIQUE constraint failed: indexes.index_id".
+            let sqlite_error_message = boxed_db_error.message();
+
+            match sqlite_error_code.as_deref() {
+                Some(sqlite_error_codes::CONSTRAINT_FOREIGNKEY) => {
+                    MetastoreError::NotFound(EntityKind::Index {
+                        index_id: index_id.to_string(),
+                    })
+                }
+                Some(
+                    sqlite_error_codes::CONSTRAINT_PRIMARYKEY
+                    | sqlite_error_codes::CONSTRAINT_UNIQUE,
+                ) if sqlite_error_message.contains("indexes.") => {
+                    MetastoreError::AlreadyExists(EntityKind::Index {
+                        index_id: index_id.to_string(),
+                    })
+                }
+                Some(
+                    sqlite_error_codes::CONSTRAINT_PRIMARYKEY
+                    | sqlite_error_codes::CONSTRAINT_UNIQUE,
+                ) => {
+                    error!(error=?boxed_db_error, "sqlite-error");
+                    MetastoreError::Internal {
+                        message: "unique key violation".to_string(),
+                        cause: format!("DB error {boxed_db_error:?}"),
+                    }
+                }
+                _ => {
+                    error!(error=?boxed_db_error, "sqlite-error");
+                    MetastoreError::Db {
+                        message: boxed_db_error.to_string(),
+                    }
+                }
+            }
+        }
+        _ => {
+            error!(error=?sqlx_error, "an error has occurred in the database operation");
+            MetastoreError::Db {
+                message: sqlx_error.to_string(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_sqlx_connection_err() {
+        let uri = Uri::for_test("sqlite:///var/lib/quickwit/metastore.db");
+
+        let resolver_error =
+            convert_sqlx_connection_err(&uri, Duration::from_secs(10), sqlx::Error::PoolTimedOut);
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Timeout { elapsed, .. } if elapsed == Duration::from_secs(10)
+        ));
+
+        let resolver_error = convert_sqlx_connection_err(
+            &uri,
+            Duration::from_secs(1),
+            sqlx::Error::Protocol("unexpected message".to_string()),
+        );
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::Initialization(MetastoreError::Connection { .. })
+        ));
+    }
+
+    #[test]
+    fn test_convert_migrate_err() {
+        let resolver_error = convert_migrate_err(1, MigrateError::VersionMissing(2));
+        assert!(matches!(
+            resolver_error,
+            MetastoreResolverError::IncompatibleSchemaVersion {
+                supported: 1,
+                found: 2
+            }
+        ));
+    }
+}
@@ -0,0 +1,2257 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use quickwit_common::pretty::PrettySample;
+use quickwit_common::uri::Uri;
+use quickwit_common::ServiceStream;
+use quickwit_config::{
+    validate_index_id_pattern, IndexTemplate, IndexTemplateId, SqliteMetastoreConfig,
+    INGEST_V2_SOURCE_ID,
+};
+use quickwit_proto::ingest::{Shard, ShardState};
+use quickwit_proto::metastore::{
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateIndexAliasRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
+    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
+    EntityKind, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
+    GetIndexTemplateRequest, GetIndexTemplateResponse, GetSourceCheckpointRequest,
+    GetSourceCheckpointResponse, IndexAlias, IndexMetadataFailure, IndexMetadataFailureReason,
+    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
+    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
+    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexAliasesRequest,
+    ListIndexAliasesResponse, ListIndexTemplatesRequest, ListIndexTemplatesResponse,
+    ListIndexesMetadataRequest, ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse,
+    ListShardsSubresponse, ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest,
+    MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult, MetastoreService,
+    MetastoreServiceStream, OpenShardSubrequest, OpenShardSubresponse, OpenShardsRequest,
+    OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
+    ToggleSourceRequest, UpdateIndexAliasRequest, UpdateIndexAliasResponse, UpdateIndexRequest,
+    UpdateSourceCheckpointRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
+};
+use quickwit_proto::types::{IndexId, IndexUid, Position, PublishToken, ShardId, SourceId};
+use sea_query::{all, Asterisk, Cond, Expr, Query, SqliteQueryBuilder};
+use sea_query_binder::SqlxBinder;
+use sqlx::sqlite::SqliteJournalMode;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tracing::field::Empty;
+use tracing::{debug, info, instrument, warn, Span};
+
+use super::error::convert_sqlx_err;
+use super::migrator::run_migrations;
+use super::model::{
+    Shards, Splits, SqliteDeleteTask, SqliteIndex, SqliteIndexTemplate, SqliteShard, SqliteSplit,
+};
+use super::utils::{append_query_filters, establish_connection, json_array};
+use crate::checkpoint::{
+    IndexCheckpointDelta, PartitionId, SourceCheckpoint, SourceCheckpointDelta,
+};
+use crate::file_backed::MutationOccurred;
+use crate::metastore::{
+    apply_index_alias_update, check_index_alias_indexes_exist, record_duration_span_field,
+    record_list_span_fields, split_maturity_timestamp, validate_create_index_alias_request,
+    validate_split_ids, IndexesMetadataResponseExt, PublishSplitsRequestExt,
+    STREAM_SPLITS_CHUNK_SIZE,
+};
+use crate::{
+    AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
+    ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
+    MetastoreResolverError, MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt,
+    UpdateIndexRequestExt,
+};
+
+/// Maximum number of connections of the read-only connection pool.
+const MAX_NUM_READ_CONNECTIONS: u32 = 4;
+
+/// SQLite metastore implementation.
+///
+/// SQLite allows a single writer at a time, so all the write operations go through a connection
+/// pool holding a single connection, which serializes transactions within the process instead
+/// of letting them fail on lock upgrades. Plain reads are served by a separate read-only pool.
+#[derive(Clone)]
+pub struct SqliteMetastore {
+    uri: Uri,
+    read_pool: SqlitePool,
+    write_pool: SqlitePool,
+}
+
+impl fmt::Debug for SqliteMetastore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteMetastore")
+            .field("uri", &self.uri)
+            .finish()
+    }
+}
+
+impl SqliteMetastore {
+    /// Creates a metastore given a database URI.
+    pub async fn new(
+        sqlite_metastore_config: &SqliteMetastoreConfig,
+        connection_uri: &Uri,
+    ) -> Result<Self, MetastoreResolverError> {
+        let acquire_timeout = sqlite_metastore_config
+            .acquire_connection_timeout()
+            .expect("SQLite metastore config should have been validated");
+        let busy_timeout = sqlite_metastore_config
+            .busy_timeout()
+            .expect("SQLite metastore config should have been validated");
+        let journal_mode = if sqlite_metastore_config.enable_wal_mode {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+        let write_pool = establish_connection(
+            connection_uri,
+            1,
+            acquire_timeout,
+            busy_timeout,
+            Some(journal_mode),
+        )
+        .await?;
+
+        run_migrations(&write_pool).await?;
+
+        // The read-only pool must be opened after the migrations have run because it cannot
+        // create the database file.
+        let read_pool = establish_connection(
+            connection_uri,
+            MAX_NUM_READ_CONNECTIONS,
+            acquire_timeout,
+            busy_timeout,
+            None,
+        )
+        .await?;
+
+        let metastore = SqliteMetastore {
+            uri: connection_uri.clone(),
+            read_pool,
+            write_pool,
+        };
+        Ok(metastore)
+    }
+}
+
+/// Returns an Index object given an index_id or None if it does not exist.
+async fn index_opt<'a, E>(executor: E, index_id: &str) -> MetastoreResult<Option<SqliteIndex>>
+where E: sqlx::Executor<'a, Database = Sqlite> {
+    let index_opt: Option<SqliteIndex> = sqlx::query_as::<_, SqliteIndex>(
+        r#"
+        SELECT *
+        FROM indexes
+        WHERE index_id = $1
+        "#,
+    )
+    .bind(index_id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(index_opt)
+}
+
+/// Returns an Index object given an index_uid or None if it does not exist.
+async fn index_opt_for_uid<'a, E>(
+    executor: E,
+    index_uid: IndexUid,
+) -> MetastoreResult<Option<SqliteIndex>>
+where
+    E: sqlx::Executor<'a, Database = Sqlite>,
+{
+    let index_opt: Option<SqliteIndex> = sqlx::query_as::<_, SqliteIndex>(
+        r#"
+        SELECT *
+        FROM indexes
+        WHERE index_uid = $1
+        "#,
+    )
+    .bind(&index_uid)
+    .fetch_optional(executor)
+    .await?;
+    Ok(index_opt)
+}
+
+async fn index_metadata(
+    tx: &mut Transaction<'_, Sqlite>,
+    index_id: &str,
+) -> MetastoreResult<IndexMetadata> {
+    index_opt(tx.as_mut(), index_id)
+        .await?
+        .ok_or_else(|| {
+            MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_id.to_string(),
+            })
+        })?
+        .index_metadata()
+}
+
+/// Returns a `NotFound` error if one of the indexes an alias resolves to does not exist.
+async fn check_sqlite_index_alias_indexes_exist(
+    tx: &mut Transaction<'_, Sqlite>,
+    index_ids: &[IndexId],
+) -> MetastoreResult<()> {
+    if index_ids.is_empty() {
+        return Ok(());
+    }
+    let existing_index_ids: Vec<IndexId> = sqlx::query_scalar(
+        "SELECT index_id FROM indexes WHERE index_id IN (SELECT value FROM json_each($1))",
+    )
+    .bind(json_array(index_ids))
+    .fetch_all(tx.as_mut())
+    .await?;
+    check_index_alias_indexes_exist(index_ids, |index_id| {
+        existing_index_ids
+            .iter()
+            .any(|existing_index_id| existing_index_id == index_id)
+    })
+}
+
+async fn try_apply_delta_v2(
+    tx: &mut Transaction<'_, Sqlite>,
+    index_uid: &IndexUid,
+    source_id: &SourceId,
+    checkpoint_delta: SourceCheckpointDelta,
+    publish_token: PublishToken,
+) -> MetastoreResult<()> {
+    let num_partitions = checkpoint_delta.num_partitions();
+    let shard_ids_json = json_array(checkpoint_delta.partitions());
+
+    let shards: Vec<(String, String, Option<PublishToken>)> = sqlx::query_as(
+        r#"
+        SELECT
+            shard_id, publish_position_inclusive, publish_token
+        FROM
+            shards
+        WHERE
+            index_uid = $1
+            AND source_id = $2
+            AND shard_id IN (SELECT value FROM json_each($3))
+        "#,
+    )
+    .bind(index_uid)
+    .bind(source_id)
+    .bind(shard_ids_json)
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    if shards.len() != num_partitions {
+        let queue_id = format!("{index_uid}/{source_id}"); // FIXME
+        let entity_kind = EntityKind::Shard { queue_id };
+        return Err(MetastoreError::NotFound(entity_kind));
+    }
+    let mut current_checkpoint = SourceCheckpoint::default();
+
+    for (shard_id, current_position, current_publish_token_opt) in shards {
+        if current_publish_token_opt.is_none()
+            || current_publish_token_opt.unwrap() != publish_token
+        {
+            let message = "failed to apply checkpoint delta: invalid publish token".to_string();
+            return Err(MetastoreError::InvalidArgument { message });
+        }
+        let partition_id = PartitionId::from(shard_id);
+        let current_position = Position::from(current_position);
+        current_checkpoint.add_partition(partition_id, current_position);
+    }
+    current_checkpoint
+        .try_apply_delta(checkpoint_delta)
+        .map_err(|error| MetastoreError::InvalidArgument {
+            message: error.to_string(),
+        })?;
+
+    // SQLite has no `UNNEST` equivalent for multiple arrays, so we update the shards one by one.
+    // This remains cheap since the statement is prepared once and cached by the connection.
+    for (partition_id, new_position) in current_checkpoint.iter() {
+        sqlx::query(
+            r#"
+            UPDATE
+                shards
+            SET
+                publish_position_inclusive = $4,
+                shard_state = CASE WHEN $4 LIKE '~%' THEN 'closed' ELSE shard_state END
+            WHERE
+                index_uid = $1
+                AND source_id = $2
+                AND shard_id = $3
+            "#,
+        )
+        .bind(index_uid)
+        .bind(source_id)
+        .bind(partition_id.to_string())
+        .bind(new_position.to_string())
+        .execute(tx.as_mut())
+        .await?;
+    }
+    Ok(())
+}
+
+/// This macro is used to systematically wrap the metastore
+/// into transaction, commit them on Result::Ok and rollback on Error.
+///
+/// Transactions always run on the write pool, which holds a single connection, so they never
+/// contend for the database lock within the process.
+macro_rules! run_with_tx {
+    ($connection_pool:expr, $tx_refmut:ident, $x:block) => {{
+        let mut tx: Transaction<'_, Sqlite> = $connection_pool.begin().await?;
+        let $tx_refmut = &mut tx;
+        let op_fut = move || async move { $x };
+        let op_result: MetastoreResult<_> = op_fut().await;
+        if op_result.is_ok() {
+            debug!("committing transaction");
+            tx.commit().await?;
+        } else {
+            warn!("rolling transaction back");
+            tx.rollback().await?;
+        }
+        op_result
+    }};
+}
+
+async fn mutate_index_metadata<E, M>(
+    tx: &mut Transaction<'_, Sqlite>,
+    index_uid: IndexUid,
+    mutate_fn: M,
+) -> MetastoreResult<IndexMetadata>
+where
+    MetastoreError: From<E>,
+    M: FnOnce(&mut IndexMetadata) -> Result<MutationOccurred<()>, E>,
+{
+    let index_id = &index_uid.index_id;
+    let mut index_metadata = index_metadata(tx, index_id).await?;
+    if index_metadata.index_uid != index_uid {
+        return Err(MetastoreError::NotFound(EntityKind::Index {
+            index_id: index_id.to_string(),
+        }));
+    }
+
+    if let MutationOccurred::No(()) = mutate_fn(&mut index_metadata)? {
+        return Ok(index_metadata);
+    }
+
+    let index_metadata_json = serde_json::to_string(&index_metadata).map_err(|error| {
+        MetastoreError::JsonSerializeError {
+            struct_name: "IndexMetadata".to_string(),
+            message: error.to_string(),
+        }
+    })?;
+    let update_index_res = sqlx::query(
+        r#"
+        UPDATE indexes
+        SET index_metadata_json = $1
+        WHERE index_uid = $2
+        "#,
+    )
+    .bind(index_metadata_json)
+    .bind(&index_uid)
+    .execute(tx.as_mut())
+    .await?;
+    if update_index_res.rows_affected() == 0 {
+        return Err(MetastoreError::NotFound(EntityKind::Index {
+            index_id: index_id.to_string(),
+        }));
+    }
+    Ok(index_metadata)
+}
+
+/// Returns the states of the splits among `split_ids` that exist in the index.
+async fn split_states(
+    tx: &mut Transaction<'_, Sqlite>,
+    index_uid: &IndexUid,
+    split_ids: &[String],
+) -> MetastoreResult<HashMap<String, String>> {
+    let split_states: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT split_id, split_state
+        FROM splits
+        WHERE
+            index_uid = $1
+            AND split_id IN (SELECT value FROM json_each($2))
+        "#,
+    )
+    .bind(index_uid)
+    .bind(json_array(split_ids))
+    .fetch_all(tx.as_mut())
+    .await
+    .map_err(|sqlx_error| convert_sqlx_err(&index_uid.index_id, sqlx_error))?;
+    Ok(split_states.into_iter().collect())
+}
+
+#[async_trait]
+impl MetastoreService for SqliteMetastore {
+    async fn check_connectivity(&mut self) -> anyhow::Result<()> {
+        self.read_pool.acquire().await?;
+        self.write_pool.acquire().await?;
+        Ok(())
+    }
+
+    fn endpoints(&self) -> Vec<quickwit_common::uri::Uri> {
+        vec![self.uri.clone()]
+    }
+
+    // Index API:
+    // - `create_index`
+    // - `update_index`
+    // - `index_metadata`
+    // - `indexes_metadata`
+    // - `list_indexes_metadata`
+
+    #[instrument(skip(self))]
+    async fn create_index(
+        &mut self,
+        request: CreateIndexRequest,
+    ) -> MetastoreResult<CreateIndexResponse> {
+        let index_config = request.deserialize_index_config()?;
+        let mut index_metadata = IndexMetadata::new(index_config);
+
+        let source_configs = request.deserialize_source_configs()?;
+
+        for source_config in source_configs {
+            index_metadata.add_source(source_config)?;
+        }
+        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO indexes (index_uid, index_id, index_metadata_json, create_timestamp)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(index_metadata.index_uid.to_string())
+        .bind(&index_metadata.index_uid.index_id)
+        .bind(&index_metadata_json)
+        .bind(index_metadata.create_timestamp)
+        .execute(&self.write_pool)
+        .await
+        .map_err(|sqlx_error| convert_sqlx_err(index_metadata.index_id(), sqlx_error))?;
+
+        let response = CreateIndexResponse {
+            index_uid: index_metadata.index_uid.into(),
+            index_metadata_json,
+        };
+        Ok(response)
+    }
+
+    async fn update_index(
+        &mut self,
+        request: UpdateIndexRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let retention_policy_opt = request.deserialize_retention_policy()?;
+        let search_settings = request.deserialize_search_settings()?;
+        let doc_mapping_opt = request.deserialize_doc_mapping()?;
+        let index_state_opt = request.deserialize_index_state()?;
+        let indexing_settings_opt = request.deserialize_indexing_settings()?;
+        let index_uri_opt = request.deserialize_index_uri()?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        let updated_index_metadata = run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
+                let mut mutation_occurred =
+                    index_metadata.set_retention_policy(retention_policy_opt);
+                mutation_occurred |= index_metadata.set_search_settings(search_settings);
+                if let Some(doc_mapping) = doc_mapping_opt {
+                    mutation_occurred |= index_metadata.set_doc_mapping(doc_mapping)?;
+                }
+                if let Some(index_state) = index_state_opt {
+                    mutation_occurred |= index_metadata.set_index_state(index_state);
+                }
+                if let Some(indexing_settings) = indexing_settings_opt {
+                    mutation_occurred |= index_metadata.set_indexing_settings(indexing_settings);
+                }
+                if let Some(index_uri) = index_uri_opt {
+                    mutation_occurred |= index_metadata.set_index_uri(index_uri);
+                }
+                Ok(MutationOccurred::from(mutation_occurred))
+            })
+            .await
+        })?;
+        IndexMetadataResponse::try_from_index_metadata(&updated_index_metadata)
+    }
+
+    #[instrument(skip(self))]
+    async fn index_metadata(
+        &mut self,
+        request: IndexMetadataRequest,
+    ) -> MetastoreResult<IndexMetadataResponse> {
+        let sqlite_index_opt = if let Some(index_uid) = &request.index_uid {
+            index_opt_for_uid(&self.read_pool, index_uid.clone()).await?
+        } else if let Some(index_id) = &request.index_id {
+            index_opt(&self.read_pool, index_id).await?
+        } else {
+            let message = "invalid request: neither `index_id` nor `index_uid` is set".to_string();
+            return Err(MetastoreError::Internal {
+                message,
+                cause: "".to_string(),
+            });
+        };
+        let index_metadata = sqlite_index_opt
+            .ok_or(MetastoreError::NotFound(EntityKind::Index {
+                index_id: request
+                    .into_index_id()
+                    .expect("`index_id` or `index_uid` should be set"),
+            }))?
+            .index_metadata()?;
+        let response = IndexMetadataResponse::try_from_index_metadata(&index_metadata)?;
+        Ok(response)
+    }
+
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
+    async fn indexes_metadata(
+        &mut self,
+        request: IndexesMetadataRequest,
+    ) -> MetastoreResult<IndexesMetadataResponse> {
+        let start = Instant::now();
+
+        let num_subrequests = request.subrequests.len();
+
+        if num_subrequests == 0 {
+            return Ok(Default::default());
+        }
+        let mut index_ids: Vec<IndexId> = Vec::new();
+        let mut index_uids: Vec<IndexUid> = Vec::with_capacity(num_subrequests);
+        let mut failures: Vec<IndexMetadataFailure> = Vec::new();
+
+        for subrequest in request.subrequests {
+            if let Some(index_id) = subrequest.index_id {
+                index_ids.push(index_id);
+            } else if let Some(index_uid) = subrequest.index_uid {
+                index_uids.push(index_uid);
+            } else {
+                let failure = IndexMetadataFailure {
+                    index_id: subrequest.index_id,
+                    index_uid: subrequest.index_uid,
+                    reason: IndexMetadataFailureReason::Internal as i32,
+                };
+                failures.push(failure);
+            }
+        }
+        let sqlite_indexes: Vec<SqliteIndex> = sqlx::query_as::<_, SqliteIndex>(
+            r#"
+            SELECT *
+            FROM indexes
+            WHERE
+                index_id IN (SELECT value FROM json_each($1))
+                OR index_uid IN (SELECT value FROM json_each($2))
+            "#,
+        )
+        .bind(json_array(&index_ids))
+        .bind(json_array(&index_uids))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let indexes_metadata: Vec<IndexMetadata> = sqlite_indexes
+            .iter()
+            .map(|sqlite_index| sqlite_index.index_metadata())
+            .collect::<MetastoreResult<_>>()?;
+
+        if sqlite_indexes.len() + failures.len() < num_subrequests {
+            for index_id in index_ids {
+                if sqlite_indexes
+                    .iter()
+                    .all(|sqlite_index| sqlite_index.index_id != index_id)
+                {
+                    let failure = IndexMetadataFailure {
+                        index_id: Some(index_id),
+                        index_uid: None,
+                        reason: IndexMetadataFailureReason::NotFound as i32,
+                    };
+                    failures.push(failure);
+                }
+            }
+            for index_uid in index_uids {
+                if sqlite_indexes
+                    .iter()
+                    .all(|sqlite_index| sqlite_index.index_uid != index_uid)
+                {
+                    let failure = IndexMetadataFailure {
+                        index_id: None,
+                        index_uid: Some(index_uid),
+                        reason: IndexMetadataFailureReason::NotFound as i32,
+                    };
+                    failures.push(failure);
+                }
+            }
+        }
+        record_list_span_fields(indexes_metadata.len(), start);
+        let response =
+            IndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata, failures).await?;
+        Ok(response)
+    }
+
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
+    async fn list_indexes_metadata(
+        &mut self,
+        request: ListIndexesMetadataRequest,
+    ) -> MetastoreResult<ListIndexesMetadataResponse> {
+        let start = Instant::now();
+        let sql =
+            build_index_id_patterns_sql_query(&request.index_id_patterns).map_err(|error| {
+                MetastoreError::Internal {
+                    message: "failed to build `list_indexes_metadata` SQL query".to_string(),
+                    cause: error.to_string(),
+                }
+            })?;
+        let sqlite_indexes = sqlx::query_as::<_, SqliteIndex>(&sql)
+            .fetch_all(&self.read_pool)
+            .await?;
+        let indexes_metadata: Vec<IndexMetadata> = sqlite_indexes
+            .into_iter()
+            .map(|sqlite_index| sqlite_index.index_metadata())
+            .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(indexes_metadata.len(), start);
+        let response =
+            ListIndexesMetadataResponse::try_from_indexes_metadata(indexes_metadata).await?;
+        Ok(response)
+    }
+
+    #[instrument(skip_all, fields(index_id = %request.index_uid().index_id))]
+    async fn delete_index(
+        &mut self,
+        request: DeleteIndexRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        // The splits, delete tasks, and shards of the index are deleted in cascade.
+        let delete_result = sqlx::query("DELETE FROM indexes WHERE index_uid = $1")
+            .bind(&index_uid)
+            .execute(&self.write_pool)
+            .await?;
+        // FIXME: This is not idempotent.
+        if delete_result.rows_affected() == 0 {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_uid.index_id,
+            }));
+        }
+        info!(index_id = index_uid.index_id, "deleted index successfully");
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids,
+        split_ids_count = Empty,
+    ))]
+    async fn stage_splits(
+        &mut self,
+        request: StageSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let splits_metadata = request.deserialize_splits_metadata()?;
+        Span::current().record("split_ids_count", splits_metadata.len());
+
+        if splits_metadata.is_empty() {
+            return Ok(Default::default());
+        }
+        let index_uid: IndexUid = request.index_uid().clone();
+        let split_ids: Vec<String> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+        Span::current().record("split_ids", format!("{split_ids:?}"));
+
+        run_with_tx!(self.write_pool, tx, {
+            let mut failed_split_ids = Vec::new();
+
+            for split_metadata in splits_metadata {
+                let split_metadata_json = serde_utils::to_json_str(&split_metadata)?;
+                let tags_json = serde_utils::to_json_str(&split_metadata.tags)?;
+                let field_names_json = serde_utils::to_json_str(&split_metadata.field_names)?;
+                let maturity_timestamp = split_maturity_timestamp(&split_metadata);
+                let time_range_start = split_metadata
+                    .time_range
+                    .as_ref()
+                    .map(|range| *range.start());
+                let time_range_end = split_metadata.time_range.as_ref().map(|range| *range.end());
+
+                let upserted_split_id_opt: Option<String> = sqlx::query_scalar(
+                    r#"
+                    INSERT INTO splits
+                        (split_id, time_range_start, time_range_end, tags, split_metadata_json, delete_opstamp, maturity_timestamp, split_state, index_uid, node_id, size_in_bytes, field_names)
+                    VALUES
+                        ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    ON CONFLICT(split_id) DO UPDATE
+                        SET
+                            time_range_start = excluded.time_range_start,
+                            time_range_end = excluded.time_range_end,
+                            tags = excluded.tags,
+                            split_metadata_json = excluded.split_metadata_json,
+                            delete_opstamp = excluded.delete_opstamp,
+                            maturity_timestamp = excluded.maturity_timestamp,
+                            index_uid = excluded.index_uid,
+                            node_id = excluded.node_id,
+                            size_in_bytes = excluded.size_in_bytes,
+                            field_names = excluded.field_names,
+                            update_timestamp = CAST(strftime('%s', 'now') AS INTEGER),
+                            create_timestamp = CAST(strftime('%s', 'now') AS INTEGER)
+                        WHERE splits.split_state = 'Staged'
+                    RETURNING split_id
+                    "#,
+                )
+                .bind(&split_metadata.split_id)
+                .bind(time_range_start)
+                .bind(time_range_end)
+                .bind(tags_json)
+                .bind(split_metadata_json)
+                .bind(split_metadata.delete_opstamp as i64)
+                .bind(maturity_timestamp)
+                .bind(SplitState::Staged.as_str())
+                .bind(&index_uid)
+                .bind(split_metadata.node_id.as_str())
+                .bind(split_metadata.size_in_bytes as i64)
+                .bind(field_names_json)
+                .fetch_optional(tx.as_mut())
+                .await
+                .map_err(|sqlx_error| convert_sqlx_err(&index_uid.index_id, sqlx_error))?;
+
+                if upserted_split_id_opt.is_none() {
+                    failed_split_ids.push(split_metadata.split_id);
+                }
+            }
+            if !failed_split_ids.is_empty() {
+                let entity = EntityKind::Splits {
+                    split_ids: failed_split_ids,
+                };
+                let message = "splits are not staged".to_string();
+                return Err(MetastoreError::FailedPrecondition { entity, message });
+            }
+            info!(
+                %index_uid,
+                "staged `{}` splits successfully", split_ids.len()
+            );
+            Ok(EmptyResponse {})
+        })
+    }
+
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.staged_split_ids.len(),
+    ))]
+    async fn publish_splits(
+        &mut self,
+        request: PublishSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        validate_split_ids(
+            request
+                .staged_split_ids
+                .iter()
+                .chain(&request.replaced_split_ids),
+        )?;
+        let checkpoint_delta_opt: Option<IndexCheckpointDelta> =
+            request.deserialize_index_checkpoint()?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        let staged_split_ids = request.staged_split_ids;
+        let replaced_split_ids = request.replaced_split_ids;
+
+        run_with_tx!(self.write_pool, tx, {
+            let mut index_metadata = index_metadata(tx, &index_uid.index_id).await?;
+            if index_metadata.index_uid != index_uid {
+                return Err(MetastoreError::NotFound(EntityKind::Index {
+                    index_id: index_uid.index_id,
+                }));
+            }
+            if let Some(checkpoint_delta) = checkpoint_delta_opt {
+                let source_id = checkpoint_delta.source_id.clone();
+
+                if source_id == INGEST_V2_SOURCE_ID {
+                    let publish_token = request.publish_token_opt.ok_or_else(|| {
+                        let message = format!(
+                            "publish token is required for publishing splits for source \
+                             `{source_id}`"
+                        );
+                        MetastoreError::InvalidArgument { message }
+                    })?;
+                    try_apply_delta_v2(
+                        tx,
+                        &index_uid,
+                        &source_id,
+                        checkpoint_delta.source_delta,
+                        publish_token,
+                    )
+                    .await?;
+                } else {
+                    index_metadata
+                        .checkpoint
+                        .try_apply_delta(checkpoint_delta)
+                        .map_err(|error| {
+                            let entity = EntityKind::CheckpointDelta {
+                                index_id: index_uid.index_id.to_string(),
+                                source_id,
+                            };
+                            let message = error.to_string();
+                            MetastoreError::FailedPrecondition { entity, message }
+                        })?;
+                }
+            }
+            let index_metadata_json = serde_json::to_string(&index_metadata).map_err(|error| {
+                MetastoreError::JsonSerializeError {
+                    struct_name: "IndexMetadata".to_string(),
+                    message: error.to_string(),
+                }
+            })?;
+            let input_split_ids: Vec<String> = staged_split_ids
+                .iter()
+                .chain(replaced_split_ids.iter())
+                .cloned()
+                .collect();
+            let split_states = split_states(tx, &index_uid, &input_split_ids).await?;
+
+            let not_found_split_ids: Vec<String> = staged_split_ids
+                .iter()
+                .chain(replaced_split_ids.iter())
+                .filter(|split_id| !split_states.contains_key(*split_id))
+                .unique()
+                .cloned()
+                .collect();
+
+            if !not_found_split_ids.is_empty() {
+                return Err(MetastoreError::NotFound(EntityKind::Splits {
+                    split_ids: not_found_split_ids,
+                }));
+            }
+            let not_staged_split_ids: Vec<String> = staged_split_ids
+                .iter()
+                .filter(|split_id| split_states[*split_id] != SplitState::Staged.as_str())
+                .cloned()
+                .collect();
+
+            if !not_staged_split_ids.is_empty() {
+                let entity = EntityKind::Splits {
+                    split_ids: not_staged_split_ids,
+                };
+                let message = "splits are not staged".to_string();
+                return Err(MetastoreError::FailedPrecondition { entity, message });
+            }
+            let not_marked_split_ids: Vec<String> = replaced_split_ids
+                .iter()
+                .filter(|split_id| split_states[*split_id] != SplitState::Published.as_str())
+                .cloned()
+                .collect();
+
+            if !not_marked_split_ids.is_empty() {
+                let entity = EntityKind::Splits {
+                    split_ids: not_marked_split_ids,
+                };
+                let message = "splits are not marked for deletion".to_string();
+                return Err(MetastoreError::FailedPrecondition { entity, message });
+            }
+            sqlx::query(
+                r#"
+                UPDATE indexes
+                SET index_metadata_json = $1
+                WHERE index_uid = $2
+                "#,
+            )
+            .bind(index_metadata_json)
+            .bind(&index_uid)
+            .execute(tx.as_mut())
+            .await?;
+
+            let num_published_splits = sqlx::query(
+                r#"
+                UPDATE splits
+                SET
+                    split_state = 'Published',
+                    update_timestamp = CAST(strftime('%s', 'now') AS INTEGER),
+                    publish_timestamp = CAST(strftime('%s', 'now') AS INTEGER)
+                WHERE
+                    index_uid = $1
+                    AND split_id IN (SELECT value FROM json_each($2))
+                "#,
+            )
+            .bind(&index_uid)
+            .bind(json_array(&staged_split_ids))
+            .execute(tx.as_mut())
+            .await?
+            .rows_affected();
+
+            let num_marked_splits = sqlx::query(
+                r#"
+                UPDATE splits
+                SET
+                    split_state = 'MarkedForDeletion',
+                    update_timestamp = CAST(strftime('%s', 'now') AS INTEGER)
+                WHERE
+                    index_uid = $1
+                    AND split_id IN (SELECT value FROM json_each($2))
+                "#,
+            )
+            .bind(&index_uid)
+            .bind(json_array(&replaced_split_ids))
+            .execute(tx.as_mut())
+            .await?
+            .rows_affected();
+
+            info!(
+                %index_uid,
+                "published {} splits and marked {} for deletion successfully",
+                num_published_splits, num_marked_splits
+            );
+            Ok(EmptyResponse {})
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn list_splits(
+        &mut self,
+        request: ListSplitsRequest,
+    ) -> MetastoreResult<MetastoreServiceStream<ListSplitsResponse>> {
+        let list_splits_query = request.deserialize_list_splits_query()?;
+        let mut sql_query_builder = Query::select();
+        sql_query_builder.column(Asterisk).from(Splits::Table);
+        append_query_filters(&mut sql_query_builder, &list_splits_query);
+
+        let (sql_query, values) = sql_query_builder.build_sqlx(SqliteQueryBuilder);
+
+        // Unlike the PostgreSQL metastore, the splits are fetched eagerly: a streaming query
+        // would hold a read connection for as long as the client consumes the stream.
+        let sqlite_splits: Vec<SqliteSplit> =
+            sqlx::query_as_with::<_, SqliteSplit, _>(&sql_query, values)
+                .fetch_all(&self.read_pool)
+                .await
+                .map_err(|error| MetastoreError::Internal {
+                    message: "failed to fetch splits".to_string(),
+                    cause: error.to_string(),
+                })?;
+        let splits: Vec<Split> = sqlite_splits
+            .into_iter()
+            .map(|sqlite_split| sqlite_split.try_into())
+            .collect::<MetastoreResult<_>>()?;
+        let list_splits_responses: Vec<MetastoreResult<ListSplitsResponse>> = splits
+            .chunks(STREAM_SPLITS_CHUNK_SIZE)
+            .map(|splits_chunk| ListSplitsResponse::try_from_splits(splits_chunk.to_vec()))
+            .collect();
+        let service_stream = ServiceStream::from(list_splits_responses);
+        Ok(service_stream)
+    }
+
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
+    async fn mark_splits_for_deletion(
+        &mut self,
+        request: MarkSplitsForDeletionRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        let split_ids = request.split_ids;
+
+        // The transaction is rolled back if it is dropped before being committed.
+        let mut tx = self.write_pool.begin().await?;
+        let split_states = split_states(&mut tx, &index_uid, &split_ids).await?;
+
+        let num_marked_splits = sqlx::query(
+            r#"
+            UPDATE splits
+            SET
+                split_state = 'MarkedForDeletion',
+                update_timestamp = CAST(strftime('%s', 'now') AS INTEGER)
+            WHERE
+                index_uid = $1
+                AND split_id IN (SELECT value FROM json_each($2))
+                AND split_state IN ('Staged', 'Published')
+            "#,
+        )
+        .bind(&index_uid)
+        .bind(json_array(&split_ids))
+        .execute(tx.as_mut())
+        .await
+        .map_err(|sqlx_error| convert_sqlx_err(&index_uid.index_id, sqlx_error))?
+        .rows_affected();
+        tx.commit().await?;
+
+        let num_found_splits = split_states.len();
+        let not_found_split_ids: Vec<String> = split_ids
+            .iter()
+            .filter(|split_id| !split_states.contains_key(*split_id))
+            .cloned()
+            .collect();
+
+        if num_found_splits == 0
+            && index_opt(&self.read_pool, &index_uid.index_id)
+                .await?
+                .is_none()
+        {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_uid.index_id,
+            }));
+        }
+        info!(
+            %index_uid,
+            "Marked {} splits for deletion, among which {} were newly marked.",
+            split_ids.len() - not_found_split_ids.len(),
+            num_marked_splits
+        );
+        if !not_found_split_ids.is_empty() {
+            warn!(
+                %index_uid,
+                split_ids=?PrettySample::new(&not_found_split_ids, 5),
+                "{} splits were not found and could not be marked for deletion.",
+                not_found_split_ids.len()
+            );
+        }
+        record_duration_span_field(start);
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        duration_ms = Empty,
+    ))]
+    async fn delete_splits(
+        &mut self,
+        request: DeleteSplitsRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let start = Instant::now();
+        validate_split_ids(&request.split_ids)?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        let split_ids = request.split_ids;
+
+        // The transaction is rolled back if it is dropped before being committed.
+        let mut tx = self.write_pool.begin().await?;
+        let split_states = split_states(&mut tx, &index_uid, &split_ids).await?;
+
+        let mut not_deletable_split_ids = Vec::new();
+        let mut not_found_split_ids = Vec::new();
+
+        for split_id in &split_ids {
+            match split_states.get(split_id).map(String::as_str) {
+                Some("Staged") | Some("Published") => {
+                    not_deletable_split_ids.push(split_id.clone())
+                }
+                Some(_) => {}
+                None => not_found_split_ids.push(split_id.clone()),
+            }
+        }
+        // Delete the splits if and only if all the splits are marked for deletion.
+        let num_deleted_splits = if not_deletable_split_ids.is_empty() {
+            sqlx::query(
+                r#"
+                DELETE FROM splits
+                WHERE
+                    index_uid = $1
+                    AND split_id IN (SELECT value FROM json_each($2))
+                "#,
+            )
+            .bind(&index_uid)
+            .bind(json_array(&split_ids))
+            .execute(tx.as_mut())
+            .await
+            .map_err(|sqlx_error| convert_sqlx_err(&index_uid.index_id, sqlx_error))?
+            .rows_affected()
+        } else {
+            0
+        };
+        tx.commit().await?;
+        let num_found_splits = split_states.len();
+
+        if num_found_splits == 0
+            && index_opt_for_uid(&self.read_pool, index_uid.clone())
+                .await?
+                .is_none()
+        {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_uid.index_id,
+            }));
+        }
+        if !not_deletable_split_ids.is_empty() {
+            let message = format!(
+                "splits `{}` are not deletable",
+                not_deletable_split_ids.join(", ")
+            );
+            let entity = EntityKind::Splits {
+                split_ids: not_deletable_split_ids,
+            };
+            return Err(MetastoreError::FailedPrecondition { entity, message });
+        }
+        info!(%index_uid, "deleted {} splits from index", num_deleted_splits);
+
+        if !not_found_split_ids.is_empty() {
+            warn!(
+                %index_uid,
+                split_ids=?PrettySample::new(&not_found_split_ids, 5),
+                "{} splits were not found and could not be deleted.",
+                not_found_split_ids.len()
+            );
+        }
+        record_duration_span_field(start);
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip(self))]
+    async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
+        let source_config = request.deserialize_source_config()?;
+        let index_uid: IndexUid = request.index_uid().clone();
+        run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata::<MetastoreError, _>(tx, index_uid, |index_metadata| {
+                index_metadata.add_source(source_config)?;
+                Ok(MutationOccurred::Yes(()))
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip(self))]
+    async fn toggle_source(
+        &mut self,
+        request: ToggleSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata(tx, index_uid, |index_metadata| {
+                if index_metadata.toggle_source(&request.source_id, request.enable)? {
+                    Ok::<_, MetastoreError>(MutationOccurred::Yes(()))
+                } else {
+                    Ok::<_, MetastoreError>(MutationOccurred::No(()))
+                }
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_source(
+        &mut self,
+        request: DeleteSourceRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let source_id = request.source_id.clone();
+        run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata(tx, index_uid.clone(), |index_metadata| {
+                index_metadata.delete_source(&source_id)?;
+                Ok::<_, MetastoreError>(MutationOccurred::Yes(()))
+            })
+            .await?;
+            sqlx::query(
+                r#"
+                    DELETE FROM shards
+                    WHERE
+                        index_uid = $1
+                        AND source_id = $2
+                "#,
+            )
+            .bind(&index_uid)
+            .bind(source_id)
+            .execute(tx.as_mut())
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip(self))]
+    async fn reset_source_checkpoint(
+        &mut self,
+        request: ResetSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata(tx, index_uid, |index_metadata| {
+                if index_metadata.checkpoint.reset_source(&request.source_id) {
+                    Ok::<_, MetastoreError>(MutationOccurred::Yes(()))
+                } else {
+                    Ok::<_, MetastoreError>(MutationOccurred::No(()))
+                }
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    #[instrument(skip(self))]
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let source_checkpoint =
+            SourceCheckpoint::from(request.source_checkpoint.unwrap_or_default());
+        run_with_tx!(self.write_pool, tx, {
+            mutate_index_metadata(tx, index_uid, |index_metadata| {
+                if index_metadata
+                    .checkpoint
+                    .set_source_checkpoint(&request.source_id, source_checkpoint)
+                {
+                    Ok::<_, MetastoreError>(MutationOccurred::Yes(()))
+                } else {
+                    Ok::<_, MetastoreError>(MutationOccurred::No(()))
+                }
+            })
+            .await?;
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    /// Retrieves the last delete opstamp for a given `index_id`.
+    #[instrument(skip(self))]
+    async fn last_delete_opstamp(
+        &mut self,
+        request: LastDeleteOpstampRequest,
+    ) -> MetastoreResult<LastDeleteOpstampResponse> {
+        let max_opstamp: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(MAX(opstamp), 0)
+            FROM delete_tasks
+            WHERE index_uid = $1
+        "#,
+        )
+        .bind(request.index_uid())
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|error| MetastoreError::Db {
+            message: error.to_string(),
+        })?;
+
+        Ok(LastDeleteOpstampResponse::new(max_opstamp as u64))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> MetastoreResult<GetSourceCheckpointResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let index_metadata = index_opt_for_uid(&self.read_pool, index_uid.clone())
+            .await?
+            .ok_or_else(|| {
+                MetastoreError::NotFound(EntityKind::Index {
+                    index_id: index_uid.index_id.clone(),
+                })
+            })?
+            .index_metadata()?;
+        let source_checkpoint = index_metadata
+            .checkpoint
+            .source_checkpoint(&request.source_id)
+            .cloned()
+            .unwrap_or_default();
+        let response = GetSourceCheckpointResponse {
+            source_checkpoint: Some(source_checkpoint.into()),
+        };
+        Ok(response)
+    }
+
+    /// Creates a delete task from a delete query.
+    #[instrument(skip(self))]
+    async fn create_delete_task(
+        &mut self,
+        delete_query: DeleteQuery,
+    ) -> MetastoreResult<DeleteTask> {
+        let delete_query_json = serde_json::to_string(&delete_query).map_err(|error| {
+            MetastoreError::JsonSerializeError {
+                struct_name: "DeleteQuery".to_string(),
+                message: error.to_string(),
+            }
+        })?;
+        let (create_timestamp, opstamp): (i64, i64) = sqlx::query_as(
+            r#"
+                INSERT INTO delete_tasks (index_uid, delete_query_json) VALUES ($1, $2)
+                RETURNING create_timestamp, opstamp
+            "#,
+        )
+        .bind(delete_query.index_uid().to_string())
+        .bind(&delete_query_json)
+        .fetch_one(&self.write_pool)
+        .await
+        .map_err(|error| convert_sqlx_err(&delete_query.index_uid().index_id, error))?;
+
+        Ok(DeleteTask {
+            create_timestamp,
+            opstamp: opstamp as u64,
+            delete_query: Some(delete_query),
+        })
+    }
+
+    /// Update splits delete opstamps.
+    #[instrument(skip_all, fields(
+        index_id = %request.index_uid().index_id,
+        split_ids_count = request.split_ids.len(),
+        delete_opstamp = request.delete_opstamp,
+    ))]
+    async fn update_splits_delete_opstamp(
+        &mut self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        let index_uid: IndexUid = request.index_uid().clone();
+        let split_ids = request.split_ids;
+        if split_ids.is_empty() {
+            return Ok(UpdateSplitsDeleteOpstampResponse {});
+        }
+        let update_result = sqlx::query(
+            r#"
+            UPDATE splits
+            SET
+                delete_opstamp = $1,
+                -- The values we compare with are *before* the modification:
+                update_timestamp = CASE
+                    WHEN delete_opstamp != $1 THEN CAST(strftime('%s', 'now') AS INTEGER)
+                    ELSE update_timestamp
+                END
+            WHERE
+                index_uid = $2
+                AND split_id IN (SELECT value FROM json_each($3))
+        "#,
+        )
+        .bind(request.delete_opstamp as i64)
+        .bind(&index_uid)
+        .bind(json_array(&split_ids))
+        .execute(&self.write_pool)
+        .await?;
+
+        // If no splits were updated, maybe the index does not exist in the first place?
+        if update_result.rows_affected() == 0
+            && index_opt_for_uid(&self.read_pool, index_uid.clone())
+                .await?
+                .is_none()
+        {
+            return Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: index_uid.index_id,
+            }));
+        }
+        Ok(UpdateSplitsDeleteOpstampResponse {})
+    }
+
+    /// Lists the delete tasks with opstamp > `opstamp_start`.
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
+    async fn list_delete_tasks(
+        &mut self,
+        request: ListDeleteTasksRequest,
+    ) -> MetastoreResult<ListDeleteTasksResponse> {
+        let start = Instant::now();
+        let index_uid: IndexUid = request.index_uid().clone();
+        let sqlite_delete_tasks: Vec<SqliteDeleteTask> = sqlx::query_as::<_, SqliteDeleteTask>(
+            r#"
+                SELECT * FROM delete_tasks
+                WHERE
+                    index_uid = $1
+                    AND opstamp > $2
+                ORDER BY opstamp ASC
+                "#,
+        )
+        .bind(&index_uid)
+        .bind(request.opstamp_start as i64)
+        .fetch_all(&self.read_pool)
+        .await?;
+        let delete_tasks: Vec<DeleteTask> = sqlite_delete_tasks
+            .into_iter()
+            .map(|sqlite_delete_task| sqlite_delete_task.try_into())
+            .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(delete_tasks.len(), start);
+        Ok(ListDeleteTasksResponse { delete_tasks })
+    }
+
+    /// Returns `num_splits` published splits with `split.delete_opstamp` < `delete_opstamp`.
+    /// Results are ordered by ascending `split.delete_opstamp` and `split.publish_timestamp`
+    /// values.
+    #[instrument(skip(self), fields(num_results = Empty, duration_ms = Empty))]
+    async fn list_stale_splits(
+        &mut self,
+        request: ListStaleSplitsRequest,
+    ) -> MetastoreResult<ListSplitsResponse> {
+        let start = Instant::now();
+        let index_uid: IndexUid = request.index_uid().clone();
+        let stale_sqlite_splits: Vec<SqliteSplit> = sqlx::query_as::<_, SqliteSplit>(
+            r#"
+                SELECT *
+                FROM splits
+                WHERE
+                    index_uid = $1
+                    AND delete_opstamp < $2
+                    AND split_state = $3
+                    AND (maturity_timestamp = 0 OR CAST(strftime('%s', 'now') AS INTEGER) >= maturity_timestamp)
+                ORDER BY delete_opstamp ASC, publish_timestamp ASC
+                LIMIT $4
+            "#,
+        )
+        .bind(&index_uid)
+        .bind(request.delete_opstamp as i64)
+        .bind(SplitState::Published.as_str())
+        .bind(request.num_splits as i64)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let stale_splits: Vec<Split> = stale_sqlite_splits
+            .into_iter()
+            .map(|sqlite_split| sqlite_split.try_into())
+            .collect::<MetastoreResult<_>>()?;
+        record_list_span_fields(stale_splits.len(), start);
+        let response = ListSplitsResponse::try_from_splits(stale_splits)?;
+        Ok(response)
+    }
+
+    async fn open_shards(
+        &mut self,
+        request: OpenShardsRequest,
+    ) -> MetastoreResult<OpenShardsResponse> {
+        let mut subresponses = Vec::with_capacity(request.subrequests.len());
+
+        for subrequest in request.subrequests {
+            let open_shard: Shard = open_or_fetch_shard(&self.write_pool, &subrequest).await?;
+            let subresponse = OpenShardSubresponse {
+                subrequest_id: subrequest.subrequest_id,
+                open_shard: Some(open_shard),
+            };
+            subresponses.push(subresponse);
+        }
+        Ok(OpenShardsResponse { subresponses })
+    }
+
+    async fn acquire_shards(
+        &mut self,
+        request: AcquireShardsRequest,
+    ) -> MetastoreResult<AcquireShardsResponse> {
+        if request.shard_ids.is_empty() {
+            return Ok(Default::default());
+        }
+        let sqlite_shards: Vec<SqliteShard> = sqlx::query_as(
+            r#"
+            UPDATE
+                shards
+            SET
+                publish_token = $4
+            WHERE
+                index_uid = $1
+                AND source_id = $2
+                AND shard_id IN (SELECT value FROM json_each($3))
+            RETURNING
+                *
+            "#,
+        )
+        .bind(request.index_uid())
+        .bind(&request.source_id)
+        .bind(json_array(&request.shard_ids))
+        .bind(&request.publish_token)
+        .fetch_all(&self.write_pool)
+        .await?;
+        let acquired_shards = sqlite_shards
+            .into_iter()
+            .map(|sqlite_shard| sqlite_shard.into())
+            .collect();
+        let response = AcquireShardsResponse { acquired_shards };
+        Ok(response)
+    }
+
+    async fn list_shards(
+        &mut self,
+        request: ListShardsRequest,
+    ) -> MetastoreResult<ListShardsResponse> {
+        if request.subrequests.is_empty() {
+            return Ok(Default::default());
+        }
+        // SQLite does not accept parenthesized compound `SELECT` statements, so the subrequests
+        // are combined into a single disjunction instead of a union of subqueries.
+        let mut subrequests_cond = Cond::any();
+
+        for subrequest in &request.subrequests {
+            let mut subrequest_cond = all![
+                Expr::col(Shards::IndexUid).eq(subrequest.index_uid()),
+                Expr::col(Shards::SourceId).eq(&subrequest.source_id)
+            ];
+            let shard_state = subrequest.shard_state();
+
+            if shard_state != ShardState::Unspecified {
+                subrequest_cond = subrequest_cond
+                    .add(Expr::col(Shards::ShardState).eq(shard_state.as_json_str_name()));
+            }
+            subrequests_cond = subrequests_cond.add(subrequest_cond);
+        }
+        let (sql_query, values) = Query::select()
+            .column(Asterisk)
+            .from(Shards::Table)
+            .cond_where(subrequests_cond)
+            .build_sqlx(SqliteQueryBuilder);
+
+        let sqlite_shards: Vec<SqliteShard> =
+            sqlx::query_as_with::<_, SqliteShard, _>(&sql_query, values)
+                .fetch_all(&self.read_pool)
+                .await?;
+
+        let mut per_source_subresponses: HashMap<(IndexUid, SourceId), ListShardsSubresponse> =
+            request
+                .subrequests
+                .into_iter()
+                .map(|subrequest| {
+                    let index_uid = subrequest.index_uid().clone();
+                    let source_id = subrequest.source_id.clone();
+                    (
+                        (index_uid, source_id),
+                        ListShardsSubresponse {
+                            index_uid: subrequest.index_uid,
+                            source_id: subrequest.source_id,
+                            shards: Vec::new(),
+                        },
+                    )
+                })
+                .collect();
+
+        for sqlite_shard in sqlite_shards {
+            let shard: Shard = sqlite_shard.into();
+            let source_key = (shard.index_uid().clone(), shard.source_id.clone());
+
+            let Some(subresponse) = per_source_subresponses.get_mut(&source_key) else {
+                warn!(
+                    index_uid=%shard.index_uid(),
+                    source_id=%shard.source_id,
+                    "could not find source in subresponses: this should never happen, please report"
+                );
+                continue;
+            };
+            subresponse.shards.push(shard);
+        }
+        let subresponses = per_source_subresponses.into_values().collect();
+        let response = ListShardsResponse { subresponses };
+        Ok(response)
+    }
+
+    async fn delete_shards(
+        &mut self,
+        request: DeleteShardsRequest,
+    ) -> MetastoreResult<DeleteShardsResponse> {
+        if request.shard_ids.is_empty() {
+            return Ok(Default::default());
+        }
+        let shard_ids_json = json_array(&request.shard_ids);
+
+        let query_result = sqlx::query(
+            r#"
+            DELETE FROM shards
+            WHERE
+                index_uid = $1
+                AND source_id = $2
+                AND shard_id IN (SELECT value FROM json_each($3))
+                AND ($4 OR publish_position_inclusive LIKE '~%')
+            "#,
+        )
+        .bind(request.index_uid())
+        .bind(&request.source_id)
+        .bind(&shard_ids_json)
+        .bind(request.force)
+        .execute(&self.write_pool)
+        .await?;
+
+        // Happy path: all shards were deleted.
+        if request.force || query_result.rows_affected() == request.shard_ids.len() as u64 {
+            let response = DeleteShardsResponse {
+                index_uid: request.index_uid,
+                source_id: request.source_id,
+                successes: request.shard_ids,
+                failures: Vec::new(),
+            };
+            return Ok(response);
+        }
+        // Unhappy path: some shards were not deleted because they do not exist or are not fully
+        // indexed.
+        let not_deletable_sqlite_shards: Vec<SqliteShard> = sqlx::query_as(
+            r#"
+            SELECT
+                *
+            FROM
+                shards
+            WHERE
+                index_uid = $1
+                AND source_id = $2
+                AND shard_id IN (SELECT value FROM json_each($3))
+                AND publish_position_inclusive NOT LIKE '~%'
+            "#,
+        )
+        .bind(request.index_uid())
+        .bind(&request.source_id)
+        .bind(&shard_ids_json)
+        .fetch_all(&self.write_pool)
+        .await?;
+
+        if not_deletable_sqlite_shards.is_empty() {
+            let response = DeleteShardsResponse {
+                index_uid: request.index_uid,
+                source_id: request.source_id,
+                successes: request.shard_ids,
+                failures: Vec::new(),
+            };
+            return Ok(response);
+        }
+        let failures: Vec<ShardId> = not_deletable_sqlite_shards
+            .into_iter()
+            .map(|sqlite_shard| sqlite_shard.shard_id)
+            .collect();
+        warn!(
+            index_uid=%request.index_uid(),
+            source_id=%request.source_id,
+            "failed to delete shards `{}`: shards are not fully indexed",
+            failures.iter().join(", ")
+        );
+        let successes: Vec<ShardId> = request
+            .shard_ids
+            .into_iter()
+            .filter(|shard_id| !failures.contains(shard_id))
+            .collect();
+        let response = DeleteShardsResponse {
+            index_uid: request.index_uid,
+            source_id: request.source_id,
+            successes,
+            failures,
+        };
+        Ok(response)
+    }
+
+    // Index Template API
+
+    async fn create_index_template(
+        &mut self,
+        request: CreateIndexTemplateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_template: IndexTemplate =
+            serde_utils::from_json_str(&request.index_template_json)?;
+
+        index_template
+            .validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!(
+                    "invalid index template `{}`: `{error}`",
+                    index_template.template_id
+                ),
+            })?;
+
+        // The patterns are matched with `GLOB`, which natively supports the `*` wildcard.
+        let mut positive_patterns = Vec::new();
+        let mut negative_patterns = Vec::new();
+
+        for pattern in &index_template.index_id_patterns {
+            if let Some(negative_pattern) = pattern.strip_prefix('-') {
+                negative_patterns.push(negative_pattern);
+            } else {
+                positive_patterns.push(pattern.as_str());
+            }
+        }
+        if request.overwrite {
+            sqlx::query(
+                r#"
+                INSERT INTO index_templates(template_id, positive_index_id_patterns, negative_index_id_patterns, priority, index_template_json)
+                    VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (template_id)
+                    DO UPDATE SET
+                        positive_index_id_patterns = $2, negative_index_id_patterns = $3, priority = $4, index_template_json = $5
+                "#,
+            )
+            .bind(&index_template.template_id)
+            .bind(json_array(positive_patterns))
+            .bind(json_array(negative_patterns))
+            .bind(index_template.priority as i32)
+            .bind(&request.index_template_json)
+            .execute(&self.write_pool)
+            .await?;
+
+            return Ok(EmptyResponse {});
+        }
+        let sqlite_query_result = sqlx::query(
+            r#"
+            INSERT INTO index_templates(template_id, positive_index_id_patterns, negative_index_id_patterns, priority, index_template_json)
+                VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (template_id)
+                DO NOTHING
+            "#,
+        )
+        .bind(&index_template.template_id)
+        .bind(json_array(positive_patterns))
+        .bind(json_array(negative_patterns))
+        .bind(index_template.priority as i32)
+        .bind(&request.index_template_json)
+        .execute(&self.write_pool)
+        .await?;
+
+        if sqlite_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::AlreadyExists(EntityKind::IndexTemplate {
+                template_id: index_template.template_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn get_index_template(
+        &mut self,
+        request: GetIndexTemplateRequest,
+    ) -> MetastoreResult<GetIndexTemplateResponse> {
+        let sqlite_index_template_json: SqliteIndexTemplate =
+            sqlx::query_as("SELECT * FROM index_templates WHERE template_id = $1")
+                .bind(&request.template_id)
+                .fetch_optional(&self.read_pool)
+                .await?
+                .ok_or({
+                    MetastoreError::NotFound(EntityKind::IndexTemplate {
+                        template_id: request.template_id,
+                    })
+                })?;
+        let response = GetIndexTemplateResponse {
+            index_template_json: sqlite_index_template_json.index_template_json,
+        };
+        Ok(response)
+    }
+
+    async fn find_index_template_matches(
+        &mut self,
+        request: FindIndexTemplateMatchesRequest,
+    ) -> MetastoreResult<FindIndexTemplateMatchesResponse> {
+        if request.index_ids.is_empty() {
+            return Ok(Default::default());
+        }
+        // SQLite has no `DISTINCT ON` clause: the rows are sorted so that the best match comes
+        // first for each index ID and the others are filtered out afterwards.
+        let sql_matches: Vec<(IndexId, IndexTemplateId, String)> = sqlx::query_as(
+            r#"
+            SELECT
+                index_ids.value AS index_id,
+                template_id,
+                index_template_json
+            FROM
+                json_each($1) AS index_ids
+                JOIN index_templates ON EXISTS (
+                    SELECT 1
+                    FROM json_each(index_templates.positive_index_id_patterns) AS patterns
+                    WHERE index_ids.value GLOB patterns.value
+                )
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM json_each(index_templates.negative_index_id_patterns) AS patterns
+                    WHERE index_ids.value GLOB patterns.value
+                )
+            ORDER BY
+                index_id,
+                priority DESC,
+                template_id ASC
+            "#,
+        )
+        .bind(json_array(&request.index_ids))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let matches = sql_matches
+            .into_iter()
+            .dedup_by(|(left_index_id, _, _), (right_index_id, _, _)| {
+                left_index_id == right_index_id
+            })
+            .map(
+                |(index_id, template_id, index_template_json)| IndexTemplateMatch {
+                    index_id,
+                    template_id,
+                    index_template_json,
+                },
+            )
+            .collect();
+        let response = FindIndexTemplateMatchesResponse { matches };
+        Ok(response)
+    }
+
+    async fn list_index_templates(
+        &mut self,
+        _request: ListIndexTemplatesRequest,
+    ) -> MetastoreResult<ListIndexTemplatesResponse> {
+        let sqlite_index_templates_json: Vec<(String,)> = sqlx::query_as(
+            "SELECT index_template_json FROM index_templates ORDER BY template_id ASC",
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+        let index_templates_json: Vec<String> = sqlite_index_templates_json
+            .into_iter()
+            .map(|(index_template_json,)| index_template_json)
+            .collect();
+        let response = ListIndexTemplatesResponse {
+            index_templates_json,
+        };
+        Ok(response)
+    }
+
+    async fn delete_index_templates(
+        &mut self,
+        request: DeleteIndexTemplatesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        sqlx::query(
+            "DELETE FROM index_templates WHERE template_id IN (SELECT value FROM json_each($1))",
+        )
+        .bind(json_array(&request.template_ids))
+        .execute(&self.write_pool)
+        .await?;
+        Ok(EmptyResponse {})
+    }
+
+    // Index Alias API
+
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let index_ids = validate_create_index_alias_request(&request)?;
+
+        run_with_tx!(self.write_pool, tx, {
+            check_sqlite_index_alias_indexes_exist(tx, &index_ids).await?;
+
+            let sqlite_query_result = sqlx::query(
+                r#"
+                INSERT INTO index_aliases(alias_id, index_ids)
+                    VALUES ($1, $2)
+                ON CONFLICT (alias_id)
+                    DO NOTHING
+                "#,
+            )
+            .bind(&request.alias_id)
+            .bind(json_array(&index_ids))
+            .execute(tx.as_mut())
+            .await?;
+
+            if sqlite_query_result.rows_affected() == 0 {
+                return Err(MetastoreError::AlreadyExists(EntityKind::IndexAlias {
+                    alias_id: request.alias_id,
+                }));
+            }
+            Ok(())
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        let sqlite_index_aliases: Vec<(String, String)> = if request.alias_ids.is_empty() {
+            sqlx::query_as("SELECT alias_id, index_ids FROM index_aliases ORDER BY alias_id ASC")
+                .fetch_all(&self.read_pool)
+                .await?
+        } else {
+            sqlx::query_as(
+                "SELECT alias_id, index_ids FROM index_aliases WHERE alias_id IN (SELECT value \
+                 FROM json_each($1)) ORDER BY alias_id ASC",
+            )
+            .bind(json_array(&request.alias_ids))
+            .fetch_all(&self.read_pool)
+            .await?
+        };
+        let index_aliases = sqlite_index_aliases
+            .into_iter()
+            .map(|(alias_id, index_ids_json)| {
+                let index_ids: Vec<IndexId> = serde_utils::from_json_str(&index_ids_json)?;
+                Ok(IndexAlias {
+                    alias_id,
+                    index_ids,
+                })
+            })
+            .collect::<MetastoreResult<_>>()?;
+        let response = ListIndexAliasesResponse { index_aliases };
+        Ok(response)
+    }
+
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> MetastoreResult<UpdateIndexAliasResponse> {
+        let index_alias = run_with_tx!(self.write_pool, tx, {
+            let index_ids_json: String =
+                sqlx::query_scalar("SELECT index_ids FROM index_aliases WHERE alias_id = $1")
+                    .bind(&request.alias_id)
+                    .fetch_optional(tx.as_mut())
+                    .await?
+                    .ok_or_else(|| {
+                        MetastoreError::NotFound(EntityKind::IndexAlias {
+                            alias_id: request.alias_id.clone(),
+                        })
+                    })?;
+            let mut index_ids: Vec<IndexId> = serde_utils::from_json_str(&index_ids_json)?;
+            check_sqlite_index_alias_indexes_exist(tx, &request.add_index_ids).await?;
+            apply_index_alias_update(&request, &mut index_ids)?;
+
+            sqlx::query("UPDATE index_aliases SET index_ids = $1 WHERE alias_id = $2")
+                .bind(json_array(&index_ids))
+                .bind(&request.alias_id)
+                .execute(tx.as_mut())
+                .await?;
+            Ok(IndexAlias {
+                alias_id: request.alias_id,
+                index_ids,
+            })
+        })?;
+        let response = UpdateIndexAliasResponse {
+            index_alias: Some(index_alias),
+        };
+        Ok(response)
+    }
+}
+
+async fn open_or_fetch_shard(
+    connection_pool: &SqlitePool,
+    subrequest: &OpenShardSubrequest,
+) -> MetastoreResult<Shard> {
+    let sqlite_shard_opt: Option<SqliteShard> = sqlx::query_as(
+        r#"
+        INSERT INTO shards(index_uid, source_id, shard_id, leader_id, follower_id)
+            VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT
+            DO NOTHING
+        RETURNING
+            *
+        "#,
+    )
+    .bind(subrequest.index_uid())
+    .bind(&subrequest.source_id)
+    .bind(subrequest.shard_id().as_str())
+    .bind(&subrequest.leader_id)
+    .bind(&subrequest.follower_id)
+    .fetch_optional(connection_pool)
+    .await?;
+
+    if let Some(sqlite_shard) = sqlite_shard_opt {
+        let shard: Shard = sqlite_shard.into();
+        info!(
+            index_uid=%shard.index_uid(),
+            source_id=%shard.source_id,
+            shard_id=%shard.shard_id(),
+            leader_id=%shard.leader_id,
+            follower_id=?shard.follower_id,
+            "opened shard"
+        );
+        return Ok(shard);
+    }
+    let sqlite_shard_opt: Option<SqliteShard> = sqlx::query_as(
+        r#"
+        SELECT
+            *
+        FROM
+            shards
+        WHERE
+            index_uid = $1
+            AND source_id = $2
+            AND shard_id = $3
+        "#,
+    )
+    .bind(subrequest.index_uid())
+    .bind(&subrequest.source_id)
+    .bind(subrequest.shard_id().as_str())
+    .fetch_optional(connection_pool)
+    .await?;
+
+    if let Some(sqlite_shard) = sqlite_shard_opt {
+        return Ok(sqlite_shard.into());
+    }
+    Err(MetastoreError::NotFound(EntityKind::Source {
+        index_id: subrequest.index_uid().to_string(),
+        source_id: subrequest.source_id.clone(),
+    }))
+}
+
+impl MetastoreServiceExt for SqliteMetastore {}
+
+/// Builds the SQL query that returns indexes matching at least one pattern in
+/// `index_id_patterns`, and none of the patterns starting with '-'
+///
+/// For each pattern, we check whether the pattern is valid and build a SQL `GLOB` query, which,
+/// unlike `LIKE` in SQLite, is case-sensitive and natively supports the `*` wildcard.
+fn build_index_id_patterns_sql_query(index_id_patterns: &[String]) -> anyhow::Result<String> {
+    let mut positive_patterns = Vec::new();
+    let mut negative_patterns = Vec::new();
+    for pattern in index_id_patterns {
+        if let Some(negative_pattern) = pattern.strip_prefix('-') {
+            negative_patterns.push(negative_pattern.to_string());
+        } else {
+            positive_patterns.push(pattern);
+        }
+    }
+
+    if positive_patterns.is_empty() {
+        anyhow::bail!("The list of index id patterns may not be empty.");
+    }
+
+    if index_id_patterns.iter().any(|pattern| pattern == "*") && negative_patterns.is_empty() {
+        return Ok("SELECT * FROM indexes".to_string());
+    }
+
+    let mut where_glob_query = String::new();
+    for (index_id_pattern_idx, index_id_pattern) in positive_patterns.iter().enumerate() {
+        validate_index_id_pattern(index_id_pattern, false).map_err(|error| {
+            MetastoreError::Internal {
+                message: "failed to build list indexes query".to_string(),
+                cause: error.to_string(),
+            }
+        })?;
+        if index_id_pattern_idx != 0 {
+            where_glob_query.push_str(" OR ");
+        }
+        if index_id_pattern.contains('*') {
+            let _ = write!(where_glob_query, "index_id GLOB '{index_id_pattern}'");
+        } else {
+            let _ = write!(where_glob_query, "index_id = '{index_id_pattern}'");
+        }
+    }
+    let mut negative_glob_query = String::new();
+    for index_id_pattern in negative_patterns.iter() {
+        validate_index_id_pattern(index_id_pattern, false).map_err(|error| {
+            MetastoreError::Internal {
+                message: "failed to build list indexes query".to_string(),
+                cause: error.to_string(),
+            }
+        })?;
+        negative_glob_query.push_str(" AND ");
+        if index_id_pattern.contains('*') {
+            let _ = write!(
+                negative_glob_query,
+                "index_id NOT GLOB '{index_id_pattern}'"
+            );
+        } else {
+            let _ = write!(negative_glob_query, "index_id <> '{index_id_pattern}'");
+        }
+    }
+
+    Ok(format!(
+        "SELECT * FROM indexes WHERE ({where_glob_query}){negative_glob_query}"
+    ))
+}
+
+#[cfg(test)]
+#[async_trait]
+impl crate::tests::DefaultForTest for SqliteMetastore {
+    async fn default_for_test() -> Self {
+        // Each test gets its own database file. The temporary directory is deliberately not
+        // cleaned up on drop because the metastore, which may be cloned and moved around by the
+        // test, must outlive it.
+        let temp_dir = tempfile::tempdir()
+            .expect("failed to create temporary directory")
+            .into_path();
+        let uri: Uri = format!("sqlite://{}/metastore.db", temp_dir.display())
+            .parse()
+            .expect("SQLite metastore URI should be valid");
+        SqliteMetastore::new(&SqliteMetastoreConfig::default(), &uri)
+            .await
+            .expect("failed to initialize SQLite metastore test")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use quickwit_common::uri::Protocol;
+    use quickwit_proto::ingest::Shard;
+    use quickwit_proto::metastore::MetastoreService;
+    use quickwit_proto::types::{IndexUid, SourceId};
+    use sea_query::{Asterisk, Query, SqliteQueryBuilder};
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::metastore::sqlite::model::{Splits, SqliteShard};
+    use crate::tests::shard::ReadWriteShardsForTest;
+    use crate::tests::DefaultForTest;
+    use crate::{metastore_test_suite, ListSplitsQuery, SplitState};
+
+    #[async_trait]
+    impl ReadWriteShardsForTest for SqliteMetastore {
+        async fn insert_shards(
+            &mut self,
+            index_uid: &IndexUid,
+            source_id: &SourceId,
+            shards: Vec<Shard>,
+        ) {
+            for shard in shards {
+                sqlx::query(
+                    r#"
+                    INSERT INTO shards(index_uid, source_id, shard_id, shard_state, leader_id, follower_id, publish_position_inclusive, publish_token)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                    "#,
+                )
+                .bind(index_uid)
+                .bind(source_id)
+                .bind(shard.shard_id().as_str())
+                .bind(shard.shard_state().as_json_str_name())
+                .bind(&shard.leader_id)
+                .bind(&shard.follower_id)
+                .bind(shard.publish_position_inclusive().to_string())
+                .bind(&shard.publish_token)
+                .execute(&self.write_pool)
+                .await
+                .unwrap();
+            }
+        }
+
+        async fn list_all_shards(&self, index_uid: &IndexUid, source_id: &SourceId) -> Vec<Shard> {
+            let sqlite_shards: Vec<SqliteShard> = sqlx::query_as(
+                r#"
+                SELECT *
+                FROM shards
+                WHERE
+                    index_uid = $1
+                    AND source_id = $2
+                "#,
+            )
+            .bind(index_uid)
+            .bind(source_id.as_str())
+            .fetch_all(&self.read_pool)
+            .await
+            .unwrap();
+
+            sqlite_shards
+                .into_iter()
+                .map(|sqlite_shard| sqlite_shard.into())
+                .collect()
+        }
+    }
+
+    metastore_test_suite!(crate::SqliteMetastore);
+
+    #[tokio::test]
+    async fn test_metastore_delete_splits_bulk() {
+        crate::tests::split::test_metastore_delete_splits_bulk::<SqliteMetastore>().await;
+    }
+
+    #[tokio::test]
+    async fn test_metastore_connectivity_and_endpoints() {
+        let mut metastore = SqliteMetastore::default_for_test().await;
+        metastore.check_connectivity().await.unwrap();
+        assert_eq!(metastore.endpoints()[0].protocol(), Protocol::Sqlite);
+    }
+
+    #[tokio::test]
+    async fn test_metastore_journal_mode() {
+        let metastore = SqliteMetastore::default_for_test().await;
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&metastore.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let uri: Uri = format!("sqlite://{}/metastore.db", temp_dir.path().display())
+            .parse()
+            .unwrap();
+        let sqlite_metastore_config = SqliteMetastoreConfig {
+            enable_wal_mode: false,
+            ..Default::default()
+        };
+        let metastore = SqliteMetastore::new(&sqlite_metastore_config, &uri)
+            .await
+            .unwrap();
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&metastore.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "delete");
+    }
+
+    #[test]
+    fn test_single_sql_query_builder() {
+        let mut select_statement = Query::select();
+
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let query =
+            ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Staged);
+        append_query_filters(sql, &query);
+
+        assert_eq!(
+            sql.to_string(SqliteQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND "split_state" IN ('Staged')"#
+            )
+        );
+
+        let mut select_statement = Query::select();
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+
+        let query = ListSplitsQuery::for_index(index_uid.clone()).with_update_timestamp_lt(51);
+        append_query_filters(sql, &query);
+        assert_eq!(
+            sql.to_string(SqliteQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND "update_timestamp" < 51"#
+            )
+        );
+
+        let mut select_statement = Query::select();
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+
+        let maturity_evaluation_datetime = OffsetDateTime::from_unix_timestamp(55).unwrap();
+        let query = ListSplitsQuery::for_index(index_uid.clone())
+            .retain_mature(maturity_evaluation_datetime);
+        append_query_filters(sql, &query);
+
+        assert_eq!(
+            sql.to_string(SqliteQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND ("maturity_timestamp" = 0 OR "maturity_timestamp" <= 55)"#
+            )
+        );
+
+        let mut select_statement = Query::select();
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+
+        let query = ListSplitsQuery::for_index(index_uid.clone())
+            .retain_immature(maturity_evaluation_datetime);
+        append_query_filters(sql, &query);
+        assert_eq!(
+            sql.to_string(SqliteQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND "maturity_timestamp" > 55"#
+            )
+        );
+
+        let mut select_statement = Query::select();
+        let sql = select_statement.column(Asterisk).from(Splits::Table);
+
+        let query = ListSplitsQuery::for_index(index_uid.clone()).with_field_name("it's");
+        append_query_filters(sql, &query);
+        assert_eq!(
+            sql.to_string(SqliteQueryBuilder),
+            format!(
+                r#"SELECT * FROM "splits" WHERE "index_uid" IN ('{index_uid}') AND EXISTS (SELECT 1 FROM json_each(field_names) WHERE json_each.value = 'it''s')"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_index_id_pattern_like_query() {
+        assert_eq!(
+            &build_index_id_patterns_sql_query(&["*".to_string()]).unwrap(),
+            "SELECT * FROM indexes"
+        );
+        assert_eq!(
+            &build_index_id_patterns_sql_query(&["index-1".to_string()]).unwrap(),
+            "SELECT * FROM indexes WHERE (index_id = 'index-1')"
+        );
+        assert_eq!(
+            &build_index_id_patterns_sql_query(&["index-1*".to_string(), "index-2".to_string()])
+                .unwrap(),
+            "SELECT * FROM indexes WHERE (index_id GLOB 'index-1*' OR index_id = 'index-2')"
+        );
+        assert_eq!(
+            &build_index_id_patterns_sql_query(&["*".to_string(), "-index-1*".to_string()])
+                .unwrap(),
+            "SELECT * FROM indexes WHERE (index_id GLOB '*') AND index_id NOT GLOB 'index-1*'"
+        );
+        assert_eq!(
+            &build_index_id_patterns_sql_query(&["index-*".to_string(), "-index-2".to_string()])
+                .unwrap(),
+            "SELECT * FROM indexes WHERE (index_id GLOB 'index-*') AND index_id <> 'index-2'"
+        );
+        assert_eq!(
+            build_index_id_patterns_sql_query(&["-index-2".to_string()])
+                .unwrap_err()
+                .to_string(),
+            "The list of index id patterns may not be empty."
+        );
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_proto::metastore::MetastoreError;
+use sqlx::migrate::Migrator;
+use sqlx::{Acquire, SqlitePool};
+use tracing::instrument;
+
+use super::error::convert_migrate_err;
+use crate::MetastoreResolverError;
+
+static MIGRATOR: Migrator = sqlx::migrate!("migrations/sqlite");
+
+/// Initializes the database and runs the SQL migrations stored in the
+/// `quickwit-metastore/migrations/sqlite` directory.
+#[instrument(skip_all)]
+pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), MetastoreResolverError> {
+    let mut tx = pool.begin().await.map_err(MetastoreError::from)?;
+    let conn = tx.acquire().await.map_err(MetastoreError::from)?;
+    let migrate_result = MIGRATOR.run_direct(conn).await;
+
+    let Err(migrate_error) = migrate_result else {
+        tx.commit().await.map_err(MetastoreError::from)?;
+        return Ok(());
+    };
+    tx.rollback().await.map_err(MetastoreError::from)?;
+    Err(convert_migrate_err(
+        latest_migration_version(),
+        migrate_error,
+    ))
+}
+
+/// Returns the version of the most recent migration embedded in the binary.
+fn latest_migration_version() -> u32 {
+    MIGRATOR
+        .iter()
+        .map(|migration| migration.version as u32)
+        .max()
+        .unwrap_or_default()
+}
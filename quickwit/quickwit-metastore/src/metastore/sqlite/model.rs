@@ -0,0 +1,245 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::convert::TryInto;
+use std::str::FromStr;
+
+use quickwit_proto::ingest::{Shard, ShardState};
+use quickwit_proto::metastore::{DeleteQuery, DeleteTask, MetastoreError, MetastoreResult};
+use quickwit_proto::types::{IndexId, IndexUid, ShardId, SourceId, SplitId};
+use sea_query::Iden;
+use tracing::error;
+
+use crate::{IndexMetadata, Split, SplitMetadata, SplitState};
+
+/// A model structure for handling index metadata in a database.
+#[derive(sqlx::FromRow)]
+pub(super) struct SqliteIndex {
+    /// Index UID. The index UID identifies the index when querying the metastore from the
+    /// application.
+    #[sqlx(try_from = "String")]
+    pub index_uid: IndexUid,
+    /// Index ID. The index ID is used to resolve user queries.
+    pub index_id: IndexId,
+    // A JSON string containing all of the IndexMetadata.
+    pub index_metadata_json: String,
+    /// Unix timestamp for tracking when the index was created.
+    pub create_timestamp: i64,
+}
+
+impl SqliteIndex {
+    /// Deserializes index metadata from JSON string stored in column and sets appropriate
+    /// timestamps.
+    pub fn index_metadata(&self) -> MetastoreResult<IndexMetadata> {
+        let mut index_metadata = serde_json::from_str::<IndexMetadata>(&self.index_metadata_json)
+            .map_err(|error| {
+            error!(index_id=%self.index_id, error=?error, "failed to deserialize index metadata");
+
+            MetastoreError::JsonDeserializeError {
+                struct_name: "IndexMetadata".to_string(),
+                message: error.to_string(),
+            }
+        })?;
+        // `create_timestamp` is stored in a dedicated column but is also duplicated in
+        // [`IndexMetadata`]. We must override the duplicate with the authentic value upon
+        // deserialization.
+        index_metadata.create_timestamp = self.create_timestamp;
+        Ok(index_metadata)
+    }
+}
+
+#[derive(Iden, Clone, Copy)]
+#[allow(dead_code)]
+pub enum Splits {
+    Table,
+    SplitId,
+    SplitState,
+    TimeRangeStart,
+    TimeRangeEnd,
+    CreateTimestamp,
+    UpdateTimestamp,
+    PublishTimestamp,
+    MaturityTimestamp,
+    Tags,
+    SplitMetadataJson,
+    IndexUid,
+    NodeId,
+    DeleteOpstamp,
+}
+
+/// A model structure for handling split metadata in a database.
+#[derive(sqlx::FromRow)]
+pub(super) struct SqliteSplit {
+    /// Split ID.
+    pub split_id: SplitId,
+    /// The state of the split. With `update_timestamp`, this is the only mutable attribute of the
+    /// split.
+    pub split_state: String,
+    /// Unix timestamp for tracking when the split was created.
+    pub create_timestamp: i64,
+    /// Unix timestamp for tracking when the split was last updated.
+    pub update_timestamp: i64,
+    /// Unix timestamp for tracking when the split was published.
+    pub publish_timestamp: Option<i64>,
+    // The split's metadata serialized as a JSON string.
+    pub split_metadata_json: String,
+    /// Index UID. It is used as a foreign key in the database.
+    #[sqlx(try_from = "String")]
+    pub index_uid: IndexUid,
+    /// Delete opstamp.
+    pub delete_opstamp: i64,
+}
+
+impl SqliteSplit {
+    /// Deserializes and returns the split's metadata.
+    fn split_metadata(&self) -> MetastoreResult<SplitMetadata> {
+        serde_json::from_str::<SplitMetadata>(&self.split_metadata_json).map_err(|error| {
+            error!(index_id=%self.index_uid.index_id, split_id=%self.split_id, error=?error, "failed to deserialize split metadata");
+
+            MetastoreError::JsonDeserializeError {
+                struct_name: "SplitMetadata".to_string(),
+                message: error.to_string(),
+            }
+        })
+    }
+
+    /// Deserializes and returns the split's state.
+    fn split_state(&self) -> MetastoreResult<SplitState> {
+        SplitState::from_str(&self.split_state).map_err(|error| {
+            error!(index_id=%self.index_uid.index_id, split_id=%self.split_id, split_state=?self.split_state, error=?error, "failed to deserialize split state");
+            MetastoreError::JsonDeserializeError {
+                struct_name: "SplitState".to_string(),
+                message: error,
+            }
+        })
+    }
+}
+
+impl TryInto<Split> for SqliteSplit {
+    type Error = MetastoreError;
+
+    fn try_into(self) -> Result<Split, Self::Error> {
+        let mut split_metadata = self.split_metadata()?;
+        // `create_timestamp` and `delete_opstamp` are duplicated in `SplitMetadata` and needs to be
+        // overridden with the "true" value stored in a column.
+        split_metadata.create_timestamp = self.create_timestamp;
+        let split_state = self.split_state()?;
+        split_metadata.index_uid = self.index_uid;
+        split_metadata.delete_opstamp = self.delete_opstamp as u64;
+        Ok(Split {
+            split_metadata,
+            split_state,
+            update_timestamp: self.update_timestamp,
+            publish_timestamp: self.publish_timestamp,
+        })
+    }
+}
+
+/// A model structure for handling delete tasks in a database.
+#[derive(sqlx::FromRow)]
+pub(super) struct SqliteDeleteTask {
+    /// Unix timestamp for tracking when the delete task was created.
+    pub create_timestamp: i64,
+    /// Monotonic increasing unique opstamp.
+    pub opstamp: i64,
+    /// Index uid.
+    #[sqlx(try_from = "String")]
+    pub index_uid: IndexUid,
+    /// Query serialized as a JSON string.
+    pub delete_query_json: String,
+}
+
+impl SqliteDeleteTask {
+    /// Deserializes and returns the delete query.
+    fn delete_query(&self) -> MetastoreResult<DeleteQuery> {
+        serde_json::from_str::<DeleteQuery>(&self.delete_query_json).map_err(|error| {
+            error!(index_id=%self.index_uid.index_id, opstamp=%self.opstamp, error=?error, "failed to deserialize delete query");
+
+            MetastoreError::JsonDeserializeError {
+                struct_name: "DeleteQuery".to_string(),
+                message: error.to_string(),
+            }
+        })
+    }
+}
+
+impl TryInto<DeleteTask> for SqliteDeleteTask {
+    type Error = MetastoreError;
+
+    fn try_into(self) -> Result<DeleteTask, Self::Error> {
+        let delete_query = self.delete_query()?;
+        Ok(DeleteTask {
+            create_timestamp: self.create_timestamp,
+            opstamp: self.opstamp as u64,
+            delete_query: Some(delete_query),
+        })
+    }
+}
+
+#[derive(Iden, Clone, Copy)]
+#[allow(dead_code)]
+pub(super) enum Shards {
+    Table,
+    IndexUid,
+    SourceId,
+    ShardId,
+    ShardState,
+    LeaderId,
+    FollowerId,
+    PublishPositionInclusive,
+    PublishToken,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+pub(super) struct SqliteShard {
+    #[sqlx(try_from = "String")]
+    pub index_uid: IndexUid,
+    pub source_id: SourceId,
+    #[sqlx(try_from = "String")]
+    pub shard_id: ShardId,
+    pub leader_id: String,
+    pub follower_id: Option<String>,
+    /// The state of the shard, enforced by a `CHECK` constraint to be one of the JSON names of
+    /// [`ShardState`].
+    pub shard_state: String,
+    pub publish_position_inclusive: String,
+    pub publish_token: Option<String>,
+}
+
+impl From<SqliteShard> for Shard {
+    fn from(sqlite_shard: SqliteShard) -> Self {
+        let shard_state = ShardState::from_json_str_name(&sqlite_shard.shard_state)
+            .unwrap_or(ShardState::Unspecified);
+        Shard {
+            index_uid: Some(sqlite_shard.index_uid),
+            source_id: sqlite_shard.source_id,
+            shard_id: Some(sqlite_shard.shard_id),
+            shard_state: shard_state as i32,
+            leader_id: sqlite_shard.leader_id,
+            follower_id: sqlite_shard.follower_id,
+            publish_position_inclusive: Some(sqlite_shard.publish_position_inclusive.into()),
+            publish_token: sqlite_shard.publish_token,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug)]
+pub(super) struct SqliteIndexTemplate {
+    pub index_template_json: String,
+}
@@ -0,0 +1,121 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_doc_mapper::tag_pruning::TagFilterAst;
+use sea_query::{all, Cond, Expr};
+
+/// Quotes `tag` as an SQL string literal.
+///
+/// SQLite has no dollar-quoted strings, so in order to ensure that we do not risk SQL injection,
+/// we double the single quotes appearing in the tag, which is the only escape sequence of string
+/// literals.
+pub(super) fn quote_tag(tag: &str) -> String {
+    format!("'{}'", tag.replace('\'', "''"))
+}
+
+/// Takes a tag filter AST and returns a SQL expression that can be used as
+/// a filter.
+///
+/// Tags are stored as a JSON array, which we expand with the `json_each` table-valued function.
+pub(super) fn generate_sql_condition(tag_ast: &TagFilterAst) -> Cond {
+    match tag_ast {
+        TagFilterAst::And(child_asts) => {
+            if child_asts.is_empty() {
+                return all![Expr::cust("TRUE")];
+            }
+            child_asts
+                .iter()
+                .map(generate_sql_condition)
+                .fold(Cond::all(), |cond, child_cond| cond.add(child_cond))
+        }
+        TagFilterAst::Or(child_asts) => {
+            if child_asts.is_empty() {
+                return all![Expr::cust("TRUE")];
+            }
+            child_asts
+                .iter()
+                .map(generate_sql_condition)
+                .fold(Cond::any(), |cond, child_cond| cond.add(child_cond))
+        }
+        TagFilterAst::Tag { tag, is_present } => {
+            let expr_str = format!(
+                "EXISTS (SELECT 1 FROM json_each(tags) WHERE json_each.value = {})",
+                quote_tag(tag)
+            );
+            let expr = if *is_present {
+                Expr::cust(expr_str)
+            } else {
+                Expr::cust(expr_str).not()
+            };
+            all![expr]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_doc_mapper::tag_pruning::{no_tag, tag};
+    use sea_query::any;
+
+    use super::*;
+
+    fn test_tags_filter_expression_helper(tags_ast: TagFilterAst, expected: Cond) {
+        assert_eq!(generate_sql_condition(&tags_ast), expected);
+    }
+
+    fn tag_expr(tag: &str) -> Expr {
+        Expr::cust(format!(
+            "EXISTS (SELECT 1 FROM json_each(tags) WHERE json_each.value = '{tag}')"
+        ))
+    }
+
+    #[test]
+    fn test_tags_filter_expression_single_tag() {
+        let expected = all![tag_expr("my_field:titi")];
+        test_tags_filter_expression_helper(tag("my_field:titi"), expected);
+    }
+
+    #[test]
+    fn test_tags_filter_expression_not_tag() {
+        let expected = all![tag_expr("my_field:titi").not()];
+        test_tags_filter_expression_helper(no_tag("my_field:titi"), expected);
+    }
+
+    #[test]
+    fn test_tags_filter_expression_and_or() {
+        let tags_ast = TagFilterAst::Or(vec![
+            TagFilterAst::And(vec![tag("tag:val1"), tag("tag:val2")]),
+            tag("tag:val3"),
+        ]);
+
+        let expected = any![
+            all![tag_expr("tag:val1"), tag_expr("tag:val2")],
+            tag_expr("tag:val3"),
+        ];
+
+        test_tags_filter_expression_helper(tags_ast, expected);
+    }
+
+    #[test]
+    fn test_tags_sql_injection_attempt() {
+        let tags_ast = tag("tag:';DELETE FROM something_evil");
+        let expected = all![tag_expr("tag:'';DELETE FROM something_evil")];
+        test_tags_filter_expression_helper(tags_ast, expected);
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::Display;
+use std::ops::Bound;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use quickwit_common::uri::Uri;
+use sea_query::{any, Expr, Order, SelectStatement};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::{ConnectOptions, SqlitePool};
+use tracing::log::LevelFilter;
+
+use super::error::convert_sqlx_connection_err;
+use super::model::Splits;
+use super::tags::{generate_sql_condition, quote_tag};
+use crate::metastore::FilterRange;
+use crate::{ListSplitsQuery, MetastoreResolverError};
+
+/// Opens a connection pool to the SQLite database located at the given URI.
+///
+/// The write pool creates the database file if it does not exist and sets the journal mode of
+/// the database, which is persistent. The read pool opens the database in read-only mode.
+pub(super) async fn establish_connection(
+    connection_uri: &Uri,
+    max_connections: u32,
+    acquire_timeout: Duration,
+    busy_timeout: Duration,
+    journal_mode_opt: Option<SqliteJournalMode>,
+) -> Result<SqlitePool, MetastoreResolverError> {
+    let read_only = journal_mode_opt.is_none();
+    let pool_options = SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .acquire_timeout(acquire_timeout);
+    let mut connect_options = SqliteConnectOptions::from_str(connection_uri.as_str())
+        .map_err(|error| MetastoreResolverError::InvalidUri(error.to_string()))?
+        .create_if_missing(!read_only)
+        .read_only(read_only)
+        .foreign_keys(true)
+        .busy_timeout(busy_timeout)
+        .log_statements(LevelFilter::Info);
+
+    if let Some(journal_mode) = journal_mode_opt {
+        connect_options = connect_options.journal_mode(journal_mode);
+
+        if matches!(journal_mode, SqliteJournalMode::Wal) {
+            // In WAL mode, `NORMAL` is safe from corruption and only gives up durability of the
+            // last transactions on power loss.
+            connect_options = connect_options.synchronous(SqliteSynchronous::Normal);
+        }
+    }
+    let now = Instant::now();
+    let sqlx_pool = pool_options
+        .connect_with(connect_options)
+        .await
+        .map_err(|error| convert_sqlx_connection_err(connection_uri, now.elapsed(), error))?;
+    Ok(sqlx_pool)
+}
+
+/// Serializes a list of values as a JSON array, which can be bound as a single query parameter
+/// and expanded with the `json_each` table-valued function, since SQLite does not support arrays.
+pub(super) fn json_array<T: ToString>(values: impl IntoIterator<Item = T>) -> String {
+    let values: Vec<String> = values.into_iter().map(|value| value.to_string()).collect();
+    serde_json::to_string(&values).expect("a list of strings should be JSON serializable")
+}
+
+/// Extends an existing SQL string with the generated filter range appended to the query.
+///
+/// This method is **not** SQL injection proof and should not be used with user-defined values.
+pub(super) fn append_range_filters<V: Display>(
+    sql: &mut SelectStatement,
+    field_name: Splits,
+    filter_range: &FilterRange<V>,
+    value_formatter: impl Fn(&V) -> Expr,
+) {
+    if let Bound::Included(value) = &filter_range.start {
+        sql.cond_where(Expr::col(field_name).gte((value_formatter)(value)));
+    };
+
+    if let Bound::Excluded(value) = &filter_range.start {
+        sql.cond_where(Expr::col(field_name).gt((value_formatter)(value)));
+    };
+
+    if let Bound::Included(value) = &filter_range.end {
+        sql.cond_where(Expr::col(field_name).lte((value_formatter)(value)));
+    };
+
+    if let Bound::Excluded(value) = &filter_range.end {
+        sql.cond_where(Expr::col(field_name).lt((value_formatter)(value)));
+    };
+}
+
+pub(super) fn append_query_filters(sql: &mut SelectStatement, query: &ListSplitsQuery) {
+    // Note: `ListSplitsQuery` builder enforces a non empty `index_uids` list.
+
+    sql.cond_where(Expr::col(Splits::IndexUid).is_in(&query.index_uids));
+
+    if let Some(node_id) = &query.node_id {
+        sql.cond_where(Expr::col(Splits::NodeId).eq(node_id));
+    };
+
+    if !query.split_states.is_empty() {
+        sql.cond_where(
+            Expr::col(Splits::SplitState)
+                .is_in(query.split_states.iter().map(|val| val.to_string())),
+        );
+    };
+
+    if let Some(tags) = &query.tags {
+        sql.cond_where(generate_sql_condition(tags));
+    };
+
+    if let Some(field_name) = &query.field_name {
+        // Field names are stored as a JSON array, like the tags.
+        sql.cond_where(Expr::cust(format!(
+            "EXISTS (SELECT 1 FROM json_each(field_names) WHERE json_each.value = {})",
+            quote_tag(field_name)
+        )));
+    };
+
+    match query.time_range.start {
+        Bound::Included(v) => {
+            sql.cond_where(any![
+                Expr::col(Splits::TimeRangeEnd).gte(v),
+                Expr::col(Splits::TimeRangeEnd).is_null()
+            ]);
+        }
+        Bound::Excluded(v) => {
+            sql.cond_where(any![
+                Expr::col(Splits::TimeRangeEnd).gt(v),
+                Expr::col(Splits::TimeRangeEnd).is_null()
+            ]);
+        }
+        Bound::Unbounded => {}
+    };
+
+    match query.time_range.end {
+        Bound::Included(v) => {
+            sql.cond_where(any![
+                Expr::col(Splits::TimeRangeStart).lte(v),
+                Expr::col(Splits::TimeRangeStart).is_null()
+            ]);
+        }
+        Bound::Excluded(v) => {
+            sql.cond_where(any![
+                Expr::col(Splits::TimeRangeStart).lt(v),
+                Expr::col(Splits::TimeRangeStart).is_null()
+            ]);
+        }
+        Bound::Unbounded => {}
+    };
+
+    // Timestamps are stored as Unix timestamps, so they can be compared directly.
+    match &query.mature {
+        Bound::Included(evaluation_datetime) => {
+            sql.cond_where(any![
+                Expr::col(Splits::MaturityTimestamp).eq(0),
+                Expr::col(Splits::MaturityTimestamp).lte(evaluation_datetime.unix_timestamp())
+            ]);
+        }
+        Bound::Excluded(evaluation_datetime) => {
+            sql.cond_where(
+                Expr::col(Splits::MaturityTimestamp).gt(evaluation_datetime.unix_timestamp()),
+            );
+        }
+        Bound::Unbounded => {}
+    };
+    append_range_filters(
+        sql,
+        Splits::UpdateTimestamp,
+        &query.update_timestamp,
+        |&val| Expr::val(val),
+    );
+    append_range_filters(
+        sql,
+        Splits::CreateTimestamp,
+        &query.create_timestamp,
+        |&val| Expr::val(val),
+    );
+    append_range_filters(sql, Splits::DeleteOpstamp, &query.delete_opstamp, |&val| {
+        Expr::expr(val)
+    });
+
+    if let Some(limit) = query.limit {
+        sql.limit(limit as u64);
+    }
+
+    if let Some(offset) = query.offset {
+        sql.order_by(Splits::SplitId, Order::Asc)
+            .offset(offset as u64);
+    }
+}
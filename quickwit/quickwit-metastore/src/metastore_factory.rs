@@ -38,6 +38,30 @@ pub trait MetastoreFactory: Send + Sync + 'static {
         metastore_config: &MetastoreConfig,
         uri: &Uri,
     ) -> Result<MetastoreServiceClient, MetastoreResolverError>;
+
+    /// Checks that the schema version of the metastore located at `uri` is the one expected by
+    /// this version of Quickwit. Backends that do not version their schema always succeed.
+    async fn version_check(&self, _uri: &Uri) -> Result<(), MetastoreResolverError> {
+        Ok(())
+    }
+}
+
+/// Compares the schema version `found` in a metastore with the `latest` schema version supported
+/// by this version of Quickwit.
+pub(crate) fn check_schema_version(latest: u32, found: u32) -> Result<(), MetastoreResolverError> {
+    if found < latest {
+        return Err(MetastoreResolverError::SchemaVersionMismatch {
+            expected: latest,
+            found,
+        });
+    }
+    if found > latest {
+        return Err(MetastoreResolverError::IncompatibleSchemaVersion {
+            supported: latest,
+            found,
+        });
+    }
+    Ok(())
 }
 
 /// A metastore factory for handling unsupported or unavailable metastore backends.
@@ -70,3 +94,30 @@ impl MetastoreFactory for UnsupportedMetastore {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_schema_version() {
+        check_schema_version(3, 3).unwrap();
+
+        let error = check_schema_version(3, 2).unwrap_err();
+        assert!(matches!(
+            error,
+            MetastoreResolverError::SchemaVersionMismatch {
+                expected: 3,
+                found: 2
+            }
+        ));
+        let error = check_schema_version(3, 4).unwrap_err();
+        assert!(matches!(
+            error,
+            MetastoreResolverError::IncompatibleSchemaVersion {
+                supported: 3,
+                found: 4
+            }
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use quickwit_common::metrics::{new_counter_vec, IntCounterVec};
+use quickwit_common::retry::{retry, RetryParams};
+use quickwit_common::uri::Uri;
+use quickwit_config::{MetastoreBackend, MetastoreConfig};
+use quickwit_proto::metastore::MetastoreServiceClient;
+use tracing::{info_span, Instrument};
+
+use crate::{MetastoreFactory, MetastoreResolverError};
+
+/// A metastore middleware decorates the [`MetastoreFactory`] objects registered in a
+/// [`MetastoreResolverBuilder`](crate::MetastoreResolverBuilder) to implement cross-cutting
+/// concerns (retries, instrumentation, caching, ...) uniformly for all the metastore backends.
+pub trait MetastoreMiddleware: Send + Sync + 'static {
+    /// Returns a factory wrapping `factory`.
+    fn wrap(&self, factory: Arc<dyn MetastoreFactory>) -> Arc<dyn MetastoreFactory>;
+}
+
+/// Retries the calls to [`MetastoreFactory::resolve`] and [`MetastoreFactory::version_check`]
+/// that fail with a transient error (connection refused, timeout, ...).
+#[derive(Debug, Clone, Copy)]
+pub struct RetryingMetastoreMiddleware {
+    /// Maximum number of attempts, first attempt included.
+    pub max_attempts: usize,
+}
+
+impl MetastoreMiddleware for RetryingMetastoreMiddleware {
+    fn wrap(&self, factory: Arc<dyn MetastoreFactory>) -> Arc<dyn MetastoreFactory> {
+        let retry_params = RetryParams {
+            max_attempts: self.max_attempts,
+            ..RetryParams::aggressive()
+        };
+        Arc::new(RetryingMetastoreFactory {
+            factory,
+            retry_params,
+        })
+    }
+}
+
+struct RetryingMetastoreFactory {
+    factory: Arc<dyn MetastoreFactory>,
+    retry_params: RetryParams,
+}
+
+#[async_trait]
+impl MetastoreFactory for RetryingMetastoreFactory {
+    fn backend(&self) -> MetastoreBackend {
+        self.factory.backend()
+    }
+
+    async fn resolve(
+        &self,
+        metastore_config: &MetastoreConfig,
+        uri: &Uri,
+    ) -> Result<MetastoreServiceClient, MetastoreResolverError> {
+        retry(&self.retry_params, || {
+            self.factory.resolve(metastore_config, uri)
+        })
+        .await
+    }
+
+    async fn version_check(&self, uri: &Uri) -> Result<(), MetastoreResolverError> {
+        retry(&self.retry_params, || self.factory.version_check(uri)).await
+    }
+}
+
+/// Wraps the calls to [`MetastoreFactory::resolve`] and [`MetastoreFactory::version_check`]
+/// with tracing spans and counts them, per backend and outcome, in the
+/// `quickwit_metastore_factory_calls_total` counter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstrumentingMetastoreMiddleware;
+
+impl MetastoreMiddleware for InstrumentingMetastoreMiddleware {
+    fn wrap(&self, factory: Arc<dyn MetastoreFactory>) -> Arc<dyn MetastoreFactory> {
+        Arc::new(InstrumentingMetastoreFactory { factory })
+    }
+}
+
+struct InstrumentingMetastoreFactory {
+    factory: Arc<dyn MetastoreFactory>,
+}
+
+impl InstrumentingMetastoreFactory {
+    fn record<T>(&self, operation: &str, result: &Result<T, MetastoreResolverError>) {
+        let status = if result.is_ok() { "success" } else { "error" };
+        METASTORE_FACTORY_METRICS
+            .factory_calls_total
+            .with_label_values([operation, backend_label(self.backend()), status])
+            .inc();
+    }
+}
+
+#[async_trait]
+impl MetastoreFactory for InstrumentingMetastoreFactory {
+    fn backend(&self) -> MetastoreBackend {
+        self.factory.backend()
+    }
+
+    async fn resolve(
+        &self,
+        metastore_config: &MetastoreConfig,
+        uri: &Uri,
+    ) -> Result<MetastoreServiceClient, MetastoreResolverError> {
+        let span = info_span!("metastore_factory_resolve", backend=?self.backend(), uri=%uri);
+        let result = self
+            .factory
+            .resolve(metastore_config, uri)
+            .instrument(span)
+            .await;
+        self.record("resolve", &result);
+        result
+    }
+
+    async fn version_check(&self, uri: &Uri) -> Result<(), MetastoreResolverError> {
+        let span = info_span!("metastore_factory_version_check", backend=?self.backend(), uri=%uri);
+        let result = self.factory.version_check(uri).instrument(span).await;
+        self.record("version_check", &result);
+        result
+    }
+}
+
+fn backend_label(backend: MetastoreBackend) -> &'static str {
+    match backend {
+        MetastoreBackend::File => "file",
+        MetastoreBackend::PostgreSQL => "postgresql",
+        MetastoreBackend::Sqlite => "sqlite",
+        MetastoreBackend::Memory => "memory",
+        MetastoreBackend::Consul => "consul",
+    }
+}
+
+struct MetastoreFactoryMetrics {
+    factory_calls_total: IntCounterVec<3>,
+}
+
+impl Default for MetastoreFactoryMetrics {
+    fn default() -> Self {
+        Self {
+            factory_calls_total: new_counter_vec(
+                "factory_calls_total",
+                "Number of calls to the metastore factories.",
+                "metastore",
+                &[],
+                ["operation", "backend", "status"],
+            ),
+        }
+    }
+}
+
+static METASTORE_FACTORY_METRICS: Lazy<MetastoreFactoryMetrics> =
+    Lazy::new(MetastoreFactoryMetrics::default);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use quickwit_config::PostgresMetastoreConfig;
+    use quickwit_proto::metastore::MockMetastoreService;
+
+    use super::*;
+    use crate::metastore_factory::MockMetastoreFactory;
+    use crate::MetastoreResolver;
+
+    fn postgres_uri() -> Uri {
+        Uri::for_test("postgresql://localhost:5432/metastore")
+    }
+
+    fn postgres_config() -> MetastoreConfig {
+        PostgresMetastoreConfig::default().into()
+    }
+
+    fn mock_factory_failing(
+        num_failures: usize,
+        error_fn: fn() -> MetastoreResolverError,
+    ) -> (MockMetastoreFactory, Arc<AtomicUsize>) {
+        let num_calls = Arc::new(AtomicUsize::new(0));
+        let num_calls_clone = num_calls.clone();
+
+        let mut mock_factory = MockMetastoreFactory::new();
+        mock_factory
+            .expect_backend()
+            .return_const(MetastoreBackend::PostgreSQL);
+        mock_factory.expect_resolve().returning(move |_, _| {
+            if num_calls_clone.fetch_add(1, Ordering::Relaxed) < num_failures {
+                return Err(error_fn());
+            }
+            Ok(MetastoreServiceClient::from_mock(
+                MockMetastoreService::new(),
+            ))
+        });
+        (mock_factory, num_calls)
+    }
+
+    fn connection_refused() -> MetastoreResolverError {
+        MetastoreResolverError::ConnectionRefused(postgres_uri())
+    }
+
+    fn metastore_resolver(
+        mock_factory: MockMetastoreFactory,
+        max_attempts: usize,
+    ) -> MetastoreResolver {
+        MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .with_resolver_middleware(RetryingMetastoreMiddleware { max_attempts })
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retrying_middleware_retries_up_to_max_attempts() {
+        let (mock_factory, num_calls) = mock_factory_failing(usize::MAX, connection_refused);
+        let metastore_resolver = metastore_resolver(mock_factory, 3);
+
+        let error = metastore_resolver
+            .resolve(&postgres_uri())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            MetastoreResolverError::ConnectionRefused(_)
+        ));
+        assert_eq!(num_calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retrying_middleware_succeeds_after_transient_failures() {
+        let (mock_factory, num_calls) = mock_factory_failing(2, connection_refused);
+        let metastore_resolver = metastore_resolver(mock_factory, 3);
+
+        metastore_resolver.resolve(&postgres_uri()).await.unwrap();
+        assert_eq!(num_calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retrying_middleware_does_not_retry_permanent_failures() {
+        let (mock_factory, num_calls) = mock_factory_failing(usize::MAX, || {
+            MetastoreResolverError::AuthenticationFailed("invalid password".to_string())
+        });
+        let metastore_resolver = metastore_resolver(mock_factory, 3);
+
+        let error = metastore_resolver
+            .resolve(&postgres_uri())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            MetastoreResolverError::AuthenticationFailed(_)
+        ));
+        assert_eq!(num_calls.load(Ordering::Relaxed), 1);
+    }
+
+    struct RecordingMetastoreMiddleware {
+        name: &'static str,
+        wrapped: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    impl MetastoreMiddleware for RecordingMetastoreMiddleware {
+        fn wrap(&self, factory: Arc<dyn MetastoreFactory>) -> Arc<dyn MetastoreFactory> {
+            self.wrapped.lock().unwrap().push(self.name);
+            factory
+        }
+    }
+
+    #[test]
+    fn test_middlewares_are_applied_in_registration_order() {
+        let wrapped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (mock_factory, _num_calls) = mock_factory_failing(0, connection_refused);
+
+        MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .with_resolver_middleware(RecordingMetastoreMiddleware {
+                name: "first",
+                wrapped: wrapped.clone(),
+            })
+            .with_resolver_middleware(RecordingMetastoreMiddleware {
+                name: "second",
+                wrapped: wrapped.clone(),
+            })
+            .build()
+            .unwrap();
+        assert_eq!(*wrapped.lock().unwrap(), ["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_instrumenting_middleware() {
+        let (mock_factory, num_calls) = mock_factory_failing(1, connection_refused);
+        let metastore_resolver = MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .with_resolver_middleware(InstrumentingMetastoreMiddleware)
+            .build()
+            .unwrap();
+        let factory_calls_total = &METASTORE_FACTORY_METRICS.factory_calls_total;
+        let num_errors_before = factory_calls_total
+            .with_label_values(["resolve", "postgresql", "error"])
+            .get();
+        let num_successes_before = factory_calls_total
+            .with_label_values(["resolve", "postgresql", "success"])
+            .get();
+
+        metastore_resolver
+            .resolve(&postgres_uri())
+            .await
+            .unwrap_err();
+        metastore_resolver.resolve(&postgres_uri()).await.unwrap();
+        assert_eq!(num_calls.load(Ordering::Relaxed), 2);
+
+        let num_errors_after = factory_calls_total
+            .with_label_values(["resolve", "postgresql", "error"])
+            .get();
+        let num_successes_after = factory_calls_total
+            .with_label_values(["resolve", "postgresql", "success"])
+            .get();
+        assert_eq!(num_errors_after - num_errors_before, 1);
+        assert_eq!(num_successes_after - num_successes_before, 1);
+    }
+}
@@ -22,18 +22,25 @@ use std::fmt;
 use std::sync::Arc;
 
 use anyhow::ensure;
+use futures::future;
 use once_cell::sync::Lazy;
 use quickwit_common::uri::{Protocol, Uri};
 use quickwit_config::{MetastoreBackend, MetastoreConfig, MetastoreConfigs};
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_storage::StorageResolver;
 
+#[cfg(feature = "consul")]
+use crate::metastore::consul::ConsulMetastoreFactory;
 use crate::metastore::file_backed::FileBackedMetastoreFactory;
+#[cfg(any(test, feature = "testsuite"))]
+use crate::metastore::memory::MemoryMetastoreFactory;
 #[cfg(feature = "postgres")]
 use crate::metastore::postgres::PostgresqlMetastoreFactory;
-use crate::{MetastoreFactory, MetastoreResolverError};
+#[cfg(feature = "sqlite")]
+use crate::metastore::sqlite::SqliteMetastoreFactory;
+use crate::{MetastoreFactory, MetastoreMiddleware, MetastoreResolverError};
 
-type FactoryAndConfig = (Box<dyn MetastoreFactory>, MetastoreConfig);
+type FactoryAndConfig = (Arc<dyn MetastoreFactory>, MetastoreConfig);
 
 /// Returns the [`MetastoreServiceClient`] instance associated with the protocol of a URI. The
 /// actual creation of metastore objects is delegated to pre-registered [`MetastoreFactory`]. The
@@ -60,6 +67,50 @@ impl MetastoreResolver {
         &self,
         uri: &Uri,
     ) -> Result<MetastoreServiceClient, MetastoreResolverError> {
+        let (metastore_factory, metastore_config) = self.factory_and_config(uri)?;
+        let metastore = metastore_factory.resolve(metastore_config, uri).await?;
+        Ok(metastore)
+    }
+
+    /// Resolves the given `uris` concurrently, so that connecting to several backends does not
+    /// add up their connection delays. Equal URIs are resolved once and share the same metastore
+    /// client. The results are returned in the order of `uris`, and the failure to resolve a URI
+    /// does not affect the others.
+    pub async fn resolve_many(
+        &self,
+        uris: &[Uri],
+    ) -> Vec<Result<MetastoreServiceClient, MetastoreResolverError>> {
+        let mut unique_uris: Vec<&Uri> = Vec::new();
+        let mut unique_uri_ordinals: HashMap<&str, usize> = HashMap::new();
+
+        let uri_ordinals: Vec<usize> = uris
+            .iter()
+            .map(|uri| {
+                *unique_uri_ordinals.entry(uri.as_str()).or_insert_with(|| {
+                    unique_uris.push(uri);
+                    unique_uris.len() - 1
+                })
+            })
+            .collect();
+        let results = future::join_all(unique_uris.into_iter().map(|uri| self.resolve(uri))).await;
+
+        uri_ordinals
+            .into_iter()
+            .map(|ordinal| results[ordinal].clone())
+            .collect()
+    }
+
+    /// Checks that the schema version of the metastore located at `uri` is supported by this
+    /// version of Quickwit. See [`MetastoreFactory::version_check`].
+    pub async fn version_check(&self, uri: &Uri) -> Result<(), MetastoreResolverError> {
+        let (metastore_factory, _metastore_config) = self.factory_and_config(uri)?;
+        metastore_factory.version_check(uri).await
+    }
+
+    fn factory_and_config(
+        &self,
+        uri: &Uri,
+    ) -> Result<(&dyn MetastoreFactory, &MetastoreConfig), MetastoreResolverError> {
         let backend = match uri.protocol() {
             Protocol::Azure => MetastoreBackend::File,
             Protocol::Google => MetastoreBackend::File,
@@ -67,6 +118,9 @@ impl MetastoreResolver {
             Protocol::Ram => MetastoreBackend::File,
             Protocol::S3 => MetastoreBackend::File,
             Protocol::PostgreSQL => MetastoreBackend::PostgreSQL,
+            Protocol::Sqlite => MetastoreBackend::Sqlite,
+            Protocol::Memory => MetastoreBackend::Memory,
+            Protocol::Consul => MetastoreBackend::Consul,
             _ => {
                 return Err(MetastoreResolverError::UnsupportedBackend(
                     "no implementation exists for this backend".to_string(),
@@ -79,8 +133,7 @@ impl MetastoreResolver {
             .ok_or(MetastoreResolverError::UnsupportedBackend(
                 "no metastore factory is registered for this backend".to_string(),
             ))?;
-        let metastore = metastore_factory.resolve(metastore_config, uri).await?;
-        Ok(metastore)
+        Ok((metastore_factory.as_ref(), metastore_config))
     }
 
     /// Creates and returns a [`MetastoreResolver`] holding the default configuration for each
@@ -110,6 +163,32 @@ impl MetastoreResolver {
                 .unwrap_or_default()
                 .into(),
         );
+        // The shared in-memory metastore is meant for tests only.
+        #[cfg(any(test, feature = "testsuite"))]
+        {
+            builder = builder.register(
+                MemoryMetastoreFactory,
+                metastore_configs
+                    .find_memory()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into(),
+            );
+        }
+        #[cfg(not(any(test, feature = "testsuite")))]
+        {
+            use quickwit_config::MemoryMetastoreConfig;
+
+            use crate::UnsupportedMetastore;
+
+            builder = builder.register(
+                UnsupportedMetastore::new(
+                    MetastoreBackend::Memory,
+                    "the `memory` metastore is only available in test builds",
+                ),
+                MemoryMetastoreConfig::default().into(),
+            );
+        }
         #[cfg(feature = "postgres")]
         {
             builder = builder.register(
@@ -135,30 +214,101 @@ impl MetastoreResolver {
                 PostgresMetastoreConfig::default().into(),
             );
         }
+        #[cfg(feature = "sqlite")]
+        {
+            builder = builder.register(
+                SqliteMetastoreFactory::default(),
+                metastore_configs
+                    .find_sqlite()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into(),
+            );
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            use quickwit_config::SqliteMetastoreConfig;
+
+            use crate::UnsupportedMetastore;
+
+            builder = builder.register(
+                UnsupportedMetastore::new(
+                    MetastoreBackend::Sqlite,
+                    "Quickwit was compiled without the `sqlite` feature",
+                ),
+                SqliteMetastoreConfig::default().into(),
+            );
+        }
+        #[cfg(feature = "consul")]
+        {
+            builder = builder.register(
+                ConsulMetastoreFactory::default(),
+                metastore_configs
+                    .find_consul()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into(),
+            );
+        }
+        #[cfg(not(feature = "consul"))]
+        {
+            use quickwit_config::ConsulMetastoreConfig;
+
+            use crate::UnsupportedMetastore;
+
+            builder = builder.register(
+                UnsupportedMetastore::new(
+                    MetastoreBackend::Consul,
+                    "Quickwit was compiled without the `consul` feature",
+                ),
+                ConsulMetastoreConfig::default().into(),
+            );
+        }
         builder
             .build()
             .expect("metastore factory and config backends should match")
     }
 }
 
-#[derive(Default)]
+/// Builds a [`MetastoreResolver`]. Cloning a builder allows creating several resolvers sharing
+/// the same factories and middlewares, each with its own set of overrides.
+#[derive(Clone, Default)]
 pub struct MetastoreResolverBuilder {
-    per_protocol_factories: HashMap<MetastoreBackend, (Box<dyn MetastoreFactory>, MetastoreConfig)>,
+    per_protocol_factories: HashMap<MetastoreBackend, FactoryAndConfig>,
+    middlewares: Vec<Arc<dyn MetastoreMiddleware>>,
 }
 
 impl MetastoreResolverBuilder {
+    /// Registers a [`MetastoreFactory`], replacing the factory previously registered for the same
+    /// backend, if any.
     pub fn register<S: MetastoreFactory>(
-        mut self,
+        self,
         metastore_factory: S,
         metastore_config: MetastoreConfig,
+    ) -> Self {
+        self.register_arc(Arc::new(metastore_factory), metastore_config)
+    }
+
+    /// Same as [`MetastoreResolverBuilder::register`] for a factory that is already shared.
+    pub fn register_arc(
+        mut self,
+        metastore_factory: Arc<dyn MetastoreFactory>,
+        metastore_config: MetastoreConfig,
     ) -> Self {
         self.per_protocol_factories.insert(
             metastore_factory.backend(),
-            (Box::new(metastore_factory), metastore_config),
+            (metastore_factory, metastore_config),
         );
         self
     }
 
+    /// Registers a middleware wrapping every metastore factory of the resolver. Middlewares are
+    /// applied in registration order: the first one registered wraps the factories directly.
+    pub fn with_resolver_middleware<M: MetastoreMiddleware>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
     pub fn build(self) -> anyhow::Result<MetastoreResolver> {
         for (metastore_factory, metastore_config) in self.per_protocol_factories.values() {
             ensure!(
@@ -168,8 +318,21 @@ impl MetastoreResolverBuilder {
                 metastore_config.backend(),
             );
         }
+        let per_backend_factories = self
+            .per_protocol_factories
+            .into_iter()
+            .map(|(backend, (metastore_factory, metastore_config))| {
+                let metastore_factory = self
+                    .middlewares
+                    .iter()
+                    .fold(metastore_factory, |metastore_factory, middleware| {
+                        middleware.wrap(metastore_factory)
+                    });
+                (backend, (metastore_factory, metastore_config))
+            })
+            .collect();
         let metastore_resolver = MetastoreResolver {
-            per_backend_factories: Arc::new(self.per_protocol_factories),
+            per_backend_factories: Arc::new(per_backend_factories),
         };
         Ok(metastore_resolver)
     }
@@ -178,8 +341,168 @@ impl MetastoreResolverBuilder {
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use quickwit_config::PostgresMetastoreConfig;
+    use quickwit_proto::metastore::{MetastoreService, MockMetastoreService};
 
     use super::*;
+    use crate::metastore_factory::MockMetastoreFactory;
+
+    fn postgres_uri() -> Uri {
+        Uri::for_test("postgresql://localhost:5432/metastore")
+    }
+
+    fn postgres_config() -> MetastoreConfig {
+        PostgresMetastoreConfig::default().into()
+    }
+
+    fn counting_mock_factory() -> (MockMetastoreFactory, Arc<AtomicUsize>) {
+        let num_calls = Arc::new(AtomicUsize::new(0));
+        let num_calls_clone = num_calls.clone();
+
+        let mut mock_factory = MockMetastoreFactory::new();
+        mock_factory
+            .expect_backend()
+            .return_const(MetastoreBackend::PostgreSQL);
+        mock_factory.expect_resolve().returning(move |_, _| {
+            let call_ordinal = num_calls_clone.fetch_add(1, Ordering::Relaxed);
+            // Each metastore reports a distinct endpoint to tell the resolved clients apart.
+            let endpoint = Uri::for_test(&format!(
+                "postgresql://localhost:5432/metastore-{call_ordinal}"
+            ));
+            let mut mock_metastore = MockMetastoreService::new();
+            mock_metastore
+                .expect_endpoints()
+                .return_const(vec![endpoint]);
+            Ok(MetastoreServiceClient::from_mock(mock_metastore))
+        });
+        (mock_factory, num_calls)
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolvers_built_independently_do_not_share_factories() {
+        let (mock_factory_a, num_calls_a) = counting_mock_factory();
+        let (mock_factory_b, num_calls_b) = counting_mock_factory();
+
+        let metastore_resolver_a = MetastoreResolver::builder()
+            .register(mock_factory_a, postgres_config())
+            .build()
+            .unwrap();
+        let metastore_resolver_b = MetastoreResolver::builder()
+            .register_arc(Arc::new(mock_factory_b), postgres_config())
+            .build()
+            .unwrap();
+
+        metastore_resolver_a.resolve(&postgres_uri()).await.unwrap();
+        assert_eq!(num_calls_a.load(Ordering::Relaxed), 1);
+        assert_eq!(num_calls_b.load(Ordering::Relaxed), 0);
+
+        metastore_resolver_b.resolve(&postgres_uri()).await.unwrap();
+        metastore_resolver_b
+            .clone()
+            .resolve(&postgres_uri())
+            .await
+            .unwrap();
+        assert_eq!(num_calls_a.load(Ordering::Relaxed), 1);
+        assert_eq!(num_calls_b.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolver_resolve_many() {
+        let (mock_factory, num_calls) = counting_mock_factory();
+        let metastore_resolver = MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .build()
+            .unwrap();
+
+        let results = metastore_resolver.resolve_many(&[]).await;
+        assert!(results.is_empty());
+        assert_eq!(num_calls.load(Ordering::Relaxed), 0);
+
+        let uris = [
+            Uri::for_test("postgresql://localhost:5432/metastore-1"),
+            Uri::for_test("postgresql://localhost:5432/metastore-2"),
+        ];
+        let results = metastore_resolver.resolve_many(&uris).await;
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(num_calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolver_resolve_many_partial_failure() {
+        let (mock_factory, num_calls) = counting_mock_factory();
+        // No factory is registered for the file-backed metastore.
+        let metastore_resolver = MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .build()
+            .unwrap();
+
+        let uris = [
+            Uri::for_test("ram:///metastore"),
+            postgres_uri(),
+            Uri::for_test("ram:///metastore"),
+        ];
+        let results = metastore_resolver.resolve_many(&uris).await;
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[0],
+            Err(MetastoreResolverError::UnsupportedBackend(_))
+        ));
+        assert!(results[1].is_ok());
+        assert!(matches!(
+            results[2],
+            Err(MetastoreResolverError::UnsupportedBackend(_))
+        ));
+        assert_eq!(num_calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolver_resolve_many_deduplicates_uris() {
+        let (mock_factory, num_calls) = counting_mock_factory();
+        let metastore_resolver = MetastoreResolver::builder()
+            .register(mock_factory, postgres_config())
+            .build()
+            .unwrap();
+
+        let uris = [postgres_uri(), postgres_uri(), postgres_uri()];
+        let results = metastore_resolver.resolve_many(&uris).await;
+        assert_eq!(results.len(), 3);
+        // The factory is called once, so the three clients share the same metastore.
+        assert_eq!(num_calls.load(Ordering::Relaxed), 1);
+
+        let endpoints: Vec<Vec<Uri>> = results
+            .into_iter()
+            .map(|result| result.unwrap().endpoints())
+            .collect();
+        let expected_endpoints = vec![Uri::for_test("postgresql://localhost:5432/metastore-0")];
+        assert!(endpoints
+            .iter()
+            .all(|client_endpoints| *client_endpoints == expected_endpoints));
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolver_builder_clone() {
+        let (mock_factory_a, num_calls_a) = counting_mock_factory();
+        let (mock_factory_b, num_calls_b) = counting_mock_factory();
+
+        let builder = MetastoreResolver::builder().register(mock_factory_a, postgres_config());
+        let metastore_resolver_b = builder
+            .clone()
+            .register(mock_factory_b, postgres_config())
+            .build()
+            .unwrap();
+        let metastore_resolver_a = builder.build().unwrap();
+
+        metastore_resolver_b.resolve(&postgres_uri()).await.unwrap();
+        assert_eq!(num_calls_a.load(Ordering::Relaxed), 0);
+        assert_eq!(num_calls_b.load(Ordering::Relaxed), 1);
+
+        metastore_resolver_a.resolve(&postgres_uri()).await.unwrap();
+        assert_eq!(num_calls_a.load(Ordering::Relaxed), 1);
+        assert_eq!(num_calls_b.load(Ordering::Relaxed), 1);
+    }
 
     #[tokio::test]
     async fn test_metastore_resolver_should_not_raise_errors_on_file() {
@@ -190,6 +513,24 @@ mod tests {
         metastore_resolver.resolve(&metastore_uri).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_metastore_resolver_should_not_raise_errors_on_memory() {
+        let metastore_resolver = MetastoreResolver::unconfigured();
+        let metastore_uri = Uri::for_test("memory://test-metastore-resolver");
+        metastore_resolver.resolve(&metastore_uri).await.unwrap();
+    }
+
+    #[test]
+    fn test_metastore_resolver_dispatches_google_cloud_storage_to_file_backed_factory() {
+        let metastore_resolver = MetastoreResolver::unconfigured();
+        let metastore_uri = Uri::for_test("gs://bucket/prefix");
+        let (metastore_factory, metastore_config) = metastore_resolver
+            .factory_and_config(&metastore_uri)
+            .unwrap();
+        assert_eq!(metastore_factory.backend(), MetastoreBackend::File);
+        assert_eq!(metastore_config.backend(), MetastoreBackend::File);
+    }
+
     #[cfg(feature = "postgres")]
     #[tokio::test]
     async fn test_postgres_and_postgresql_protocol_accepted() {
@@ -206,4 +547,14 @@ mod tests {
             metastore_resolver.resolve(&postgres_uri).await.unwrap();
         }
     }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_metastore_resolver_should_not_raise_errors_on_sqlite() {
+        let metastore_resolver = MetastoreResolver::unconfigured();
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let metastore_filepath = format!("sqlite://{}/metastore.db", tmp_dir.path().display());
+        let metastore_uri = Uri::from_str(&metastore_filepath).unwrap();
+        metastore_resolver.resolve(&metastore_uri).await.unwrap();
+    }
 }
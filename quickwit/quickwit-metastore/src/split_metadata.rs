@@ -133,6 +133,18 @@ pub struct SplitMetadata {
     /// Number of merge operations that was involved to create
     /// this split.
     pub num_merge_ops: usize,
+
+    /// Schema version of the index the split was indexed with. Splits with different schema
+    /// versions are searched with their own doc mapping and never merged together.
+    pub schema_version: u32,
+
+    /// Size (in bytes) of the split file uploaded to the storage. It is 0 for the splits staged
+    /// before this field was introduced.
+    pub size_in_bytes: u64,
+
+    /// Names of the fields present in at least one document of the split, including the dynamic
+    /// and JSON subfields. It is empty for the splits staged before this field was introduced.
+    pub field_names: BTreeSet<String>,
 }
 
 impl fmt::Debug for SplitMetadata {
@@ -177,6 +189,11 @@ impl fmt::Debug for SplitMetadata {
         debug_struct.field("footer_offsets", &self.footer_offsets);
         debug_struct.field("delete_opstamp", &self.delete_opstamp);
         debug_struct.field("num_merge_ops", &self.num_merge_ops);
+        debug_struct.field("schema_version", &self.schema_version);
+        debug_struct.field("size_in_bytes", &self.size_in_bytes);
+        if !self.field_names.is_empty() {
+            debug_struct.field("num_field_names", &self.field_names.len());
+        }
         debug_struct.finish()
     }
 }
@@ -230,11 +247,11 @@ impl SplitMetadata {
 
     /// Converts the split metadata into a [`SplitInfo`].
     pub fn as_split_info(&self) -> SplitInfo {
-        let file_name = quickwit_common::split_file(self.split_id());
+        let file_name = quickwit_common::split_file_path(self.split_id());
 
         SplitInfo {
             uncompressed_docs_size_bytes: ByteSize(self.uncompressed_docs_size_in_bytes),
-            file_name: PathBuf::from(file_name),
+            file_name,
             file_size_bytes: ByteSize(self.footer_offsets.end),
             split_id: self.split_id.clone(),
             num_docs: self.num_docs,
@@ -283,6 +300,9 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
             tags: ["234".to_string(), "aaa".to_string()].into_iter().collect(),
             footer_offsets: 1000..2000,
             num_merge_ops: 3,
+            schema_version: 0,
+            size_in_bytes: 0,
+            field_names: BTreeSet::new(),
         }
     }
 
@@ -292,7 +312,7 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
 }
 
 /// A split state.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, utoipa::ToSchema)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash, utoipa::ToSchema)]
 pub enum SplitState {
     /// The split is almost ready. Some of its files may have been uploaded in the storage.
     Staged,
@@ -425,16 +445,19 @@ mod tests {
             footer_offsets: 0..1024,
             delete_opstamp: 0,
             num_merge_ops: 0,
+            schema_version: 0,
+            size_in_bytes: 2048,
+            field_names: BTreeSet::new(),
         };
 
-        let expected_output = "SplitMetadata { split_id: \"split-1\", index_uid: IndexUid { \
-                               index_id: \"00000000-0000-0000-0000-000000000000\", \
-                               incarnation_id: Ulid(0) }, partition_id: 0, source_id: \
-                               \"source-1\", node_id: \"node-1\", num_docs: 100, \
-                               uncompressed_docs_size_in_bytes: 1024, time_range: Some(0..=100), \
-                               create_timestamp: 1629867600, maturity: Mature, tags: \
-                               \"{\\\"🐱\\\", \\\"😻\\\", \\\"😼\\\", \\\"😿\\\", and 1 more}\", \
-                               footer_offsets: 0..1024, delete_opstamp: 0, num_merge_ops: 0 }";
+        let expected_output =
+            "SplitMetadata { split_id: \"split-1\", index_uid: IndexUid { index_id: \
+             \"00000000-0000-0000-0000-000000000000\", incarnation_id: Ulid(0) }, partition_id: \
+             0, source_id: \"source-1\", node_id: \"node-1\", num_docs: 100, \
+             uncompressed_docs_size_in_bytes: 1024, time_range: Some(0..=100), create_timestamp: \
+             1629867600, maturity: Mature, tags: \"{\\\"🐱\\\", \\\"😻\\\", \\\"😼\\\", \
+             \\\"😿\\\", and 1 more}\", footer_offsets: 0..1024, delete_opstamp: 0, \
+             num_merge_ops: 0, schema_version: 0, size_in_bytes: 2048 }";
 
         assert_eq!(format!("{:?}", split_metadata), expected_output);
     }
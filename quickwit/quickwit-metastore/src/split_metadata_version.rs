@@ -20,6 +20,7 @@
 use std::collections::BTreeSet;
 use std::ops::{Range, RangeInclusive};
 
+use quickwit_common::is_zero;
 use quickwit_proto::types::{IndexUid, SplitId};
 use serde::{Deserialize, Serialize};
 
@@ -92,6 +93,21 @@ pub(crate) struct SplitMetadataV0_8 {
 
     #[serde(default)]
     num_merge_ops: usize,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub schema_version: u32,
+
+    /// Size of the split file in bytes.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub size_in_bytes: u64,
+
+    /// Names of the fields present in the split.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    #[schema(value_type = Vec<String>)]
+    pub field_names: BTreeSet<String>,
 }
 
 impl From<SplitMetadataV0_8> for SplitMetadata {
@@ -127,6 +143,9 @@ impl From<SplitMetadataV0_8> for SplitMetadata {
             tags: v8.tags,
             footer_offsets: v8.footer_offsets,
             num_merge_ops: v8.num_merge_ops,
+            schema_version: v8.schema_version,
+            size_in_bytes: v8.size_in_bytes,
+            field_names: v8.field_names,
         }
     }
 }
@@ -148,6 +167,9 @@ impl From<SplitMetadata> for SplitMetadataV0_8 {
             tags: split.tags,
             footer_offsets: split.footer_offsets,
             num_merge_ops: split.num_merge_ops,
+            schema_version: split.schema_version,
+            size_in_bytes: split.size_in_bytes,
+            field_names: split.field_names,
         }
     }
 }
@@ -0,0 +1,394 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use quickwit_common::rand::append_random_suffix;
+use quickwit_config::IndexConfig;
+use quickwit_proto::metastore::{
+    CreateIndexAliasRequest, CreateIndexRequest, EntityKind, IndexAlias, ListIndexAliasesRequest,
+    MetastoreError, MetastoreService, UpdateIndexAliasRequest,
+};
+use quickwit_proto::types::{IndexId, IndexUid};
+
+use super::DefaultForTest;
+use crate::tests::cleanup_index;
+use crate::{CreateIndexRequestExt, MetastoreServiceExt};
+
+/// Creates an index for each of the `index_id_prefixes` and returns their IDs and UIDs. The IDs
+/// keep the order of the prefixes.
+async fn create_indexes(
+    metastore: &mut dyn MetastoreService,
+    index_id_prefixes: &[&str],
+) -> (Vec<IndexId>, Vec<IndexUid>) {
+    let mut index_ids = Vec::with_capacity(index_id_prefixes.len());
+    let mut index_uids = Vec::with_capacity(index_id_prefixes.len());
+
+    for index_id_prefix in index_id_prefixes {
+        let index_id = append_random_suffix(index_id_prefix);
+        let index_uri = format!("ram:///indexes/{index_id}");
+        let index_config = IndexConfig::for_test(&index_id, &index_uri);
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+        index_ids.push(index_id);
+        index_uids.push(index_uid);
+    }
+    (index_ids, index_uids)
+}
+
+async fn list_index_aliases(
+    metastore: &mut dyn MetastoreService,
+    alias_ids: &[&str],
+) -> Vec<IndexAlias> {
+    let list_index_aliases_request = ListIndexAliasesRequest {
+        alias_ids: alias_ids
+            .iter()
+            .map(|alias_id| alias_id.to_string())
+            .collect(),
+    };
+    metastore
+        .list_index_aliases(list_index_aliases_request)
+        .await
+        .unwrap()
+        .index_aliases
+}
+
+pub async fn test_metastore_create_index_alias<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+
+    let (index_ids, index_uids) = create_indexes(
+        &mut metastore,
+        &["test-create-alias-index-1", "test-create-alias-index-2"],
+    )
+    .await;
+
+    let alias_id = append_random_suffix("test-create-alias");
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        index_ids: vec![
+            index_ids[1].clone(),
+            index_ids[0].clone(),
+            index_ids[1].clone(),
+        ],
+    };
+    metastore
+        .create_index_alias(create_index_alias_request.clone())
+        .await
+        .unwrap();
+
+    let index_aliases = list_index_aliases(&mut metastore, &[&alias_id]).await;
+    assert_eq!(index_aliases.len(), 1);
+    assert_eq!(index_aliases[0].alias_id, alias_id);
+    assert_eq!(index_aliases[0].index_ids, index_ids);
+
+    let error = metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, MetastoreError::AlreadyExists(EntityKind::IndexAlias { alias_id }) if alias_id.starts_with("test-create-alias"))
+    );
+
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: append_random_suffix("test-create-alias"),
+        index_ids: Vec::new(),
+    };
+    let error = metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: "-invalid-alias".to_string(),
+        index_ids: vec![index_ids[0].clone()],
+    };
+    let error = metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: append_random_suffix("test-create-alias"),
+        index_ids: vec!["invalid/index".to_string()],
+    };
+    let error = metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    // An alias cannot resolve to an index that does not exist.
+    let missing_alias_id = append_random_suffix("test-create-alias");
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: missing_alias_id.clone(),
+        index_ids: vec![
+            index_ids[0].clone(),
+            "test-create-alias-index-does-not-exist".to_string(),
+        ],
+    };
+    let error = metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, MetastoreError::NotFound(EntityKind::Indexes { index_ids }) if index_ids == ["test-create-alias-index-does-not-exist"])
+    );
+    assert!(list_index_aliases(&mut metastore, &[&missing_alias_id])
+        .await
+        .is_empty());
+
+    for index_uid in index_uids {
+        cleanup_index(&mut metastore, index_uid).await;
+    }
+}
+
+pub async fn test_metastore_list_index_aliases<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+
+    let (index_ids, index_uids) = create_indexes(&mut metastore, &["test-list-alias-index"]).await;
+
+    let alias_id_1 = append_random_suffix("test-list-alias-1");
+    let alias_id_2 = append_random_suffix("test-list-alias-2");
+
+    for alias_id in [&alias_id_1, &alias_id_2] {
+        let create_index_alias_request = CreateIndexAliasRequest {
+            alias_id: alias_id.clone(),
+            index_ids: index_ids.clone(),
+        };
+        metastore
+            .create_index_alias(create_index_alias_request)
+            .await
+            .unwrap();
+    }
+    let index_aliases = list_index_aliases(&mut metastore, &[&alias_id_2]).await;
+    assert_eq!(index_aliases.len(), 1);
+    assert_eq!(index_aliases[0].alias_id, alias_id_2);
+
+    let index_aliases = list_index_aliases(
+        &mut metastore,
+        &[&alias_id_2, &alias_id_1, "test-list-alias-does-not-exist"],
+    )
+    .await;
+    assert_eq!(index_aliases.len(), 2);
+    assert_eq!(index_aliases[0].alias_id, alias_id_1);
+    assert_eq!(index_aliases[1].alias_id, alias_id_2);
+
+    let index_aliases = list_index_aliases(&mut metastore, &[]).await;
+    assert!(index_aliases
+        .iter()
+        .any(|index_alias| index_alias.alias_id == alias_id_1));
+    assert!(index_aliases
+        .iter()
+        .any(|index_alias| index_alias.alias_id == alias_id_2));
+
+    for index_uid in index_uids {
+        cleanup_index(&mut metastore, index_uid).await;
+    }
+}
+
+pub async fn test_metastore_update_index_alias<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+
+    let (index_ids, index_uids) = create_indexes(
+        &mut metastore,
+        &[
+            "test-update-alias-index-1",
+            "test-update-alias-index-2",
+            "test-update-alias-index-3",
+            "test-update-alias-index-4",
+        ],
+    )
+    .await;
+
+    let alias_id = append_random_suffix("test-update-alias");
+
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: vec![index_ids[0].clone()],
+        remove_index_ids: Vec::new(),
+    };
+    let error = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, MetastoreError::NotFound(EntityKind::IndexAlias { alias_id }) if alias_id.starts_with("test-update-alias"))
+    );
+
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        index_ids: vec![index_ids[0].clone()],
+    };
+    metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap();
+
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: vec![index_ids[2].clone(), index_ids[1].clone()],
+        remove_index_ids: Vec::new(),
+    };
+    let index_alias = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap()
+        .index_alias
+        .unwrap();
+    assert_eq!(index_alias.index_ids, index_ids[..3]);
+
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: Vec::new(),
+        remove_index_ids: vec![index_ids[1].clone()],
+    };
+    let index_alias = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap()
+        .index_alias
+        .unwrap();
+    assert_eq!(
+        index_alias.index_ids,
+        [index_ids[0].clone(), index_ids[2].clone()]
+    );
+
+    // Removing an index that is not part of the alias fails and leaves the alias unchanged.
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: vec![index_ids[3].clone()],
+        remove_index_ids: vec![index_ids[1].clone()],
+    };
+    let error = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::FailedPrecondition {
+            entity: EntityKind::IndexAlias { .. },
+            ..
+        }
+    ));
+    let index_aliases = list_index_aliases(&mut metastore, &[&alias_id]).await;
+    assert_eq!(
+        index_aliases[0].index_ids,
+        [index_ids[0].clone(), index_ids[2].clone()]
+    );
+
+    // Adding indexes that do not exist fails and leaves the alias unchanged.
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: vec![
+            "test-update-alias-index-does-not-exist-1".to_string(),
+            index_ids[3].clone(),
+            "test-update-alias-index-does-not-exist-2".to_string(),
+        ],
+        remove_index_ids: Vec::new(),
+    };
+    let error = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, MetastoreError::NotFound(EntityKind::Indexes { index_ids }) if index_ids == ["test-update-alias-index-does-not-exist-1", "test-update-alias-index-does-not-exist-2"])
+    );
+    let index_aliases = list_index_aliases(&mut metastore, &[&alias_id]).await;
+    assert_eq!(
+        index_aliases[0].index_ids,
+        [index_ids[0].clone(), index_ids[2].clone()]
+    );
+
+    // An alias cannot be left empty.
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: Vec::new(),
+        remove_index_ids: vec![index_ids[0].clone(), index_ids[2].clone()],
+    };
+    let error = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::FailedPrecondition { .. }));
+
+    for index_uid in index_uids {
+        cleanup_index(&mut metastore, index_uid).await;
+    }
+}
+
+pub async fn test_metastore_switch_index_alias<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+
+    let (index_ids, index_uids) = create_indexes(
+        &mut metastore,
+        &["test-switch-alias-index-v1", "test-switch-alias-index-v2"],
+    )
+    .await;
+
+    let alias_id = append_random_suffix("test-switch-alias");
+
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        index_ids: vec![index_ids[0].clone()],
+    };
+    metastore
+        .create_index_alias(create_index_alias_request)
+        .await
+        .unwrap();
+
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: vec![index_ids[1].clone()],
+        remove_index_ids: vec![index_ids[0].clone()],
+    };
+    let index_alias = metastore
+        .update_index_alias(update_index_alias_request.clone())
+        .await
+        .unwrap()
+        .index_alias
+        .unwrap();
+    assert_eq!(index_alias.index_ids, [index_ids[1].clone()]);
+
+    // Replaying the switch fails because the alias no longer points to the old index.
+    let error = metastore
+        .update_index_alias(update_index_alias_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::FailedPrecondition { .. }));
+
+    let index_aliases = list_index_aliases(&mut metastore, &[&alias_id]).await;
+    assert_eq!(index_aliases[0].index_ids, [index_ids[1].clone()]);
+
+    for index_uid in index_uids {
+        cleanup_index(&mut metastore, index_uid).await;
+    }
+}
@@ -27,9 +27,11 @@
 
 use std::collections::BTreeSet;
 
+use bytesize::ByteSize;
 use quickwit_common::rand::append_random_suffix;
 use quickwit_config::{
-    IndexConfig, RetentionPolicy, SearchSettings, SourceConfig, CLI_SOURCE_ID, INGEST_V2_SOURCE_ID,
+    IndexConfig, IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig, CLI_SOURCE_ID,
+    INGEST_V2_SOURCE_ID,
 };
 use quickwit_doc_mapper::FieldMappingType;
 use quickwit_proto::metastore::{
@@ -43,7 +45,7 @@ use quickwit_proto::types::IndexUid;
 use super::DefaultForTest;
 use crate::tests::cleanup_index;
 use crate::{
-    CreateIndexRequestExt, IndexMetadataResponseExt, IndexesMetadataResponseExt,
+    CreateIndexRequestExt, IndexMetadataResponseExt, IndexState, IndexesMetadataResponseExt,
     ListIndexesMetadataResponseExt, MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt,
     UpdateIndexRequestExt,
 };
@@ -132,6 +134,7 @@ pub async fn test_metastore_update_index<
     let new_retention_policy_opt = Some(RetentionPolicy {
         retention_period: String::from("3 days"),
         evaluation_schedule: String::from("daily"),
+        max_size: None,
     });
     assert_ne!(
         index_metadata.index_config.retention_policy_opt, new_retention_policy_opt,
@@ -148,6 +151,9 @@ pub async fn test_metastore_update_index<
             index_uid.clone(),
             &new_search_setting,
             &loop_retention_policy_opt,
+            None,
+            None,
+            None,
         )
         .unwrap();
         let response_metadata = metastore
@@ -177,6 +183,296 @@ pub async fn test_metastore_update_index<
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_update_index_doc_mapping<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-index-doc-mapping");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let mut new_doc_mapping = index_config.doc_mapping.clone();
+    new_doc_mapping.field_mappings.push(
+        serde_json::from_str(r#"{"name": "trace_id", "type": "text", "tokenizer": "raw"}"#)
+            .unwrap(),
+    );
+    // Run the same update twice to check idempotence.
+    for _ in 0..2 {
+        let index_update = UpdateIndexRequest::try_from_updates(
+            index_uid.clone(),
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            Some(&new_doc_mapping),
+            None,
+            None,
+        )
+        .unwrap();
+        let response_metadata = metastore
+            .update_index(index_update)
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(response_metadata.index_config.doc_mapping, new_doc_mapping);
+        assert_eq!(response_metadata.index_config.schema_version, 1);
+        assert_eq!(
+            response_metadata.doc_mapping_for_schema_version(0),
+            Some(&index_config.doc_mapping)
+        );
+        assert_eq!(
+            response_metadata.doc_mapping_for_schema_version(1),
+            Some(&new_doc_mapping)
+        );
+        let updated_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(response_metadata, updated_metadata);
+    }
+
+    let mut invalid_doc_mapping = new_doc_mapping.clone();
+    invalid_doc_mapping.field_mappings.remove(1);
+
+    let index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        Some(&invalid_doc_mapping),
+        None,
+        None,
+    )
+    .unwrap();
+    let error = metastore.update_index(index_update).await.unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_config.doc_mapping, new_doc_mapping);
+    assert_eq!(index_metadata.index_config.schema_version, 1);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_index_state<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-index-state");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    // Run the same update twice to check idempotence, then unfreeze the index.
+    for index_state in [IndexState::Frozen, IndexState::Frozen, IndexState::Active] {
+        let index_update = UpdateIndexRequest::try_from_updates(
+            index_uid.clone(),
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            None,
+            Some(index_state),
+            None,
+        )
+        .unwrap();
+        let response_metadata = metastore
+            .update_index(index_update)
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(response_metadata.index_state, index_state);
+
+        let updated_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(response_metadata, updated_metadata);
+    }
+
+    // Updating the other settings does not unfreeze the index.
+    let index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        None,
+        Some(IndexState::Frozen),
+        None,
+    )
+    .unwrap();
+    metastore.update_index(index_update).await.unwrap();
+
+    let index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let response_metadata = metastore
+        .update_index(index_update)
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(response_metadata.index_state, IndexState::Frozen);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_index_indexing_settings<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-index-indexing-settings");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let new_indexing_settings = IndexingSettings {
+        split_num_docs_target: 2_000_000,
+        split_num_bytes_target: Some(ByteSize::gb(5)),
+        ..index_config.indexing_settings.clone()
+    };
+    // Run the same update twice to check idempotence.
+    for _ in 0..2 {
+        let index_update = UpdateIndexRequest::try_from_updates(
+            index_uid.clone(),
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            None,
+            None,
+            Some(&new_indexing_settings),
+        )
+        .unwrap();
+        let response_metadata = metastore
+            .update_index(index_update)
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(
+            response_metadata.index_config.indexing_settings,
+            new_indexing_settings
+        );
+        let updated_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(response_metadata, updated_metadata);
+    }
+
+    // Omitting the indexing settings leaves them unchanged.
+    let index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let response_metadata = metastore
+        .update_index(index_update)
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        response_metadata.index_config.indexing_settings,
+        new_indexing_settings
+    );
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_index_uri<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-index-uri");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let new_index_uri = format!("ram:///migrated-indexes/{index_id}");
+    let mut index_update = UpdateIndexRequest::try_from_updates(
+        index_uid.clone(),
+        &index_config.search_settings,
+        &index_config.retention_policy_opt,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    index_update.index_uri = Some(new_index_uri.clone());
+
+    let response_metadata = metastore
+        .update_index(index_update.clone())
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(response_metadata.index_uri().as_str(), new_index_uri);
+
+    let updated_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(response_metadata, updated_metadata);
+
+    index_update.index_uri = Some("".to_string());
+    let error = metastore.update_index(index_update).await.unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_create_index_with_sources<
     MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
 >() {
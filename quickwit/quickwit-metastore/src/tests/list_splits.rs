@@ -960,6 +960,104 @@ pub async fn test_metastore_list_splits_by_node_id<
     assert_eq!(splits[0].split_metadata.node_id, "test-node-1");
 }
 
+pub async fn test_metastore_list_splits_by_field_name<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-list-splits-by-field-name");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid
+        .unwrap();
+
+    let split_id_1 = format!("{index_id}--split-1");
+    let split_metadata_1 = SplitMetadata {
+        split_id: split_id_1.clone(),
+        index_uid: index_uid.clone(),
+        field_names: to_btree_set(&["body", "attributes.host"]),
+        ..Default::default()
+    };
+    let split_id_2 = format!("{index_id}--split-2");
+    let split_metadata_2 = SplitMetadata {
+        split_id: split_id_2.clone(),
+        index_uid: index_uid.clone(),
+        field_names: to_btree_set(&["body", "attributes.it's"]),
+        ..Default::default()
+    };
+    // The third split remains staged.
+    let split_id_3 = format!("{index_id}--split-3");
+    let split_metadata_3 = SplitMetadata {
+        split_id: split_id_3.clone(),
+        index_uid: index_uid.clone(),
+        field_names: to_btree_set(&["body", "attributes.host", "severity"]),
+        ..Default::default()
+    };
+    let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+        index_uid.clone(),
+        vec![split_metadata_1, split_metadata_2, split_metadata_3],
+    )
+    .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![split_id_1.clone(), split_id_2.clone()],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let list_splits_query =
+        ListSplitsQuery::for_index(index_uid.clone()).with_field_name("attributes.host");
+    let list_splits_request =
+        ListSplitsRequest::try_from_list_splits_query(&list_splits_query).unwrap();
+    let mut split_ids = metastore
+        .list_splits(list_splits_request)
+        .await
+        .unwrap()
+        .collect_split_ids()
+        .await
+        .unwrap();
+    split_ids.sort();
+    assert_eq!(split_ids, [split_id_1.clone(), split_id_3]);
+
+    let splits_metadata = metastore
+        .list_splits_with_field(index_uid.clone(), "attributes.host")
+        .await
+        .unwrap();
+    assert_eq!(splits_metadata.len(), 1);
+    assert_eq!(splits_metadata[0].split_id, split_id_1);
+
+    let splits_metadata = metastore
+        .list_splits_with_field(index_uid.clone(), "attributes.it's")
+        .await
+        .unwrap();
+    assert_eq!(splits_metadata.len(), 1);
+    assert_eq!(splits_metadata[0].split_id, split_id_2);
+
+    let splits_metadata = metastore
+        .list_splits_with_field(index_uid.clone(), "attributes")
+        .await
+        .unwrap();
+    assert!(splits_metadata.is_empty());
+
+    let field_names = metastore.list_field_names(index_uid.clone()).await.unwrap();
+    assert_eq!(
+        field_names,
+        to_btree_set(&["attributes.host", "attributes.it's", "body"])
+    );
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_list_stale_splits<
     MetastoreToTest: MetastoreServiceExt + DefaultForTest,
 >() {
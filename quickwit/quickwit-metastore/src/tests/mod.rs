@@ -30,6 +30,7 @@ use quickwit_proto::metastore::{
 use quickwit_proto::tonic::transport::Channel;
 use quickwit_proto::types::IndexUid;
 
+pub(crate) mod alias;
 pub(crate) mod delete_task;
 pub(crate) mod index;
 pub(crate) mod list_splits;
@@ -200,6 +201,30 @@ macro_rules! metastore_test_suite {
                 $crate::tests::index::test_metastore_update_index::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_update_index_doc_mapping() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_index_doc_mapping::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            async fn test_metastore_update_index_state() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_index_state::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            async fn test_metastore_update_index_indexing_settings() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_index_indexing_settings::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            async fn test_metastore_update_index_uri() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_update_index_uri::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_create_index_enforces_index_id_maximum_length() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -281,6 +306,15 @@ macro_rules! metastore_test_suite {
                 $crate::tests::split::test_metastore_replace_splits::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_replace_splits_is_atomic_for_concurrent_readers() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::split::test_metastore_replace_splits_is_atomic_for_concurrent_readers::<
+                    $metastore_type,
+                >()
+                .await;
+            }
+
             #[tokio::test]
             async fn test_metastore_mark_splits_for_deletion() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -288,6 +322,12 @@ macro_rules! metastore_test_suite {
                     .await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_get_index_size_bytes() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::split::test_metastore_get_index_size_bytes::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_delete_splits() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -318,6 +358,12 @@ macro_rules! metastore_test_suite {
                 $crate::tests::list_splits::test_metastore_list_splits_by_node_id::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_list_splits_by_field_name() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::list_splits::test_metastore_list_splits_by_field_name::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_split_update_timestamp() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -349,6 +395,12 @@ macro_rules! metastore_test_suite {
                 $crate::tests::source::test_metastore_reset_checkpoint::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_get_and_update_source_checkpoint() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::source::test_metastore_get_and_update_source_checkpoint::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_create_delete_task() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -392,6 +444,15 @@ macro_rules! metastore_test_suite {
                 $crate::tests::split::test_metastore_stage_splits::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_stage_and_publish_splits_in_bulk_is_atomic() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::split::test_metastore_stage_and_publish_splits_in_bulk_is_atomic::<
+                    $metastore_type,
+                >()
+                .await;
+            }
+
             /// Shard API tests
 
             #[tokio::test]
@@ -455,6 +516,32 @@ macro_rules! metastore_test_suite {
             async fn test_metastore_delete_index_templates() {
                 $crate::tests::template::test_metastore_delete_index_templates::<$metastore_type>().await;
             }
+
+            /// Index Alias API tests
+
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn test_metastore_create_index_alias() {
+                $crate::tests::alias::test_metastore_create_index_alias::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn test_metastore_list_index_aliases() {
+                $crate::tests::alias::test_metastore_list_index_aliases::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn test_metastore_update_index_alias() {
+                $crate::tests::alias::test_metastore_update_index_alias::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn test_metastore_switch_index_alias() {
+                $crate::tests::alias::test_metastore_switch_index_alias::<$metastore_type>().await;
+            }
         }
     };
 }
@@ -22,14 +22,15 @@ use std::num::NonZeroUsize;
 use quickwit_common::rand::append_random_suffix;
 use quickwit_config::{IndexConfig, SourceConfig, SourceInputFormat, SourceParams};
 use quickwit_proto::metastore::{
-    AddSourceRequest, CreateIndexRequest, DeleteSourceRequest, EntityKind, IndexMetadataRequest,
-    MetastoreError, PublishSplitsRequest, ResetSourceCheckpointRequest, SourceType,
-    StageSplitsRequest, ToggleSourceRequest,
+    AddSourceRequest, CreateIndexRequest, DeleteSourceRequest, EntityKind,
+    GetSourceCheckpointRequest, IndexMetadataRequest, MetastoreError, PublishSplitsRequest,
+    ResetSourceCheckpointRequest, SourceType, StageSplitsRequest, ToggleSourceRequest,
+    UpdateSourceCheckpointRequest,
 };
-use quickwit_proto::types::IndexUid;
+use quickwit_proto::types::{IndexUid, Position};
 
 use super::DefaultForTest;
-use crate::checkpoint::SourceCheckpoint;
+use crate::checkpoint::{PartitionId, SourceCheckpoint};
 use crate::tests::cleanup_index;
 use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt, MetastoreServiceExt,
@@ -447,3 +448,100 @@ pub async fn test_metastore_reset_checkpoint<
 
     cleanup_index(&mut metastore, index_uid).await;
 }
+
+pub async fn test_metastore_get_and_update_source_checkpoint<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-checkpoint");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let source_id = format!("{index_id}--source");
+
+    let source_checkpoint = metastore
+        .get_source_checkpoint(GetSourceCheckpointRequest {
+            index_uid: index_uid.clone().into(),
+            source_id: source_id.clone(),
+        })
+        .await
+        .unwrap()
+        .source_checkpoint
+        .unwrap();
+    assert!(source_checkpoint.per_partition.is_empty());
+
+    let expected_source_checkpoint: SourceCheckpoint = [
+        (PartitionId::from("partition-0"), Position::offset(42u64)),
+        (PartitionId::from("partition-1"), Position::eof(1337u64)),
+    ]
+    .into_iter()
+    .collect();
+
+    metastore
+        .update_source_checkpoint(UpdateSourceCheckpointRequest {
+            index_uid: index_uid.clone().into(),
+            source_id: source_id.clone(),
+            source_checkpoint: Some(expected_source_checkpoint.clone().into()),
+        })
+        .await
+        .unwrap();
+
+    let source_checkpoint = metastore
+        .get_source_checkpoint(GetSourceCheckpointRequest {
+            index_uid: index_uid.clone().into(),
+            source_id: source_id.clone(),
+        })
+        .await
+        .unwrap()
+        .source_checkpoint
+        .unwrap();
+    assert_eq!(
+        SourceCheckpoint::from(source_checkpoint),
+        expected_source_checkpoint
+    );
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(
+        index_metadata.checkpoint.source_checkpoint(&source_id),
+        Some(&expected_source_checkpoint)
+    );
+
+    assert!(matches!(
+        metastore
+            .update_source_checkpoint(UpdateSourceCheckpointRequest {
+                index_uid: Some(IndexUid::new_with_random_ulid("index-not-found")),
+                source_id: source_id.clone(),
+                source_checkpoint: Some(expected_source_checkpoint.clone().into()),
+            })
+            .await
+            .unwrap_err(),
+        MetastoreError::NotFound(EntityKind::Index { .. })
+    ));
+
+    assert!(matches!(
+        metastore
+            .get_source_checkpoint(GetSourceCheckpointRequest {
+                index_uid: Some(IndexUid::new_with_random_ulid(&index_id)),
+                source_id: source_id.clone(),
+            })
+            .await
+            .unwrap_err(),
+        MetastoreError::NotFound(EntityKind::Index { .. })
+    ));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
@@ -17,6 +17,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures::future::try_join_all;
@@ -759,6 +761,110 @@ pub async fn test_metastore_publish_splits_concurrency<
     cleanup_index(&mut metastore, index_uid).await
 }
 
+pub async fn test_metastore_replace_splits_is_atomic_for_concurrent_readers<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest + Clone,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-replace-splits-atomic");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let split_ids: Vec<String> = (1..=3)
+        .map(|split_ord| format!("{index_id}--split-{split_ord}"))
+        .collect();
+    let split_metadatas: Vec<SplitMetadata> = split_ids
+        .iter()
+        .map(|split_id| SplitMetadata {
+            split_id: split_id.clone(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        })
+        .collect();
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), split_metadatas).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: split_ids[..2].to_vec(),
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    // The reader keeps listing the published splits, as a search would, while the merged split
+    // replaces the source splits.
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader_handle = tokio::spawn({
+        let mut metastore = metastore.clone();
+        let index_uid = index_uid.clone();
+        let stop = stop.clone();
+        async move {
+            let mut observed_split_id_sets: Vec<Vec<String>> = Vec::new();
+
+            loop {
+                let is_last_iteration = stop.load(Ordering::Relaxed);
+                let query = ListSplitsQuery::for_index(index_uid.clone())
+                    .with_split_state(SplitState::Published);
+                let list_splits_request =
+                    ListSplitsRequest::try_from_list_splits_query(&query).unwrap();
+                let mut published_split_ids: Vec<String> = metastore
+                    .list_splits(list_splits_request)
+                    .await
+                    .unwrap()
+                    .collect_split_ids()
+                    .await
+                    .unwrap();
+                published_split_ids.sort();
+                observed_split_id_sets.push(published_split_ids);
+
+                if is_last_iteration {
+                    return observed_split_id_sets;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+    sleep(Duration::from_millis(10)).await;
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![split_ids[2].clone()],
+        replaced_split_ids: split_ids[..2].to_vec(),
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+    stop.store(true, Ordering::Relaxed);
+
+    let observed_split_id_sets = reader_handle.await.unwrap();
+    let source_split_ids = split_ids[..2].to_vec();
+    let merged_split_ids = split_ids[2..].to_vec();
+
+    for observed_split_ids in &observed_split_id_sets {
+        assert!(
+            *observed_split_ids == source_split_ids || *observed_split_ids == merged_split_ids,
+            "the reader observed a partial swap: {observed_split_ids:?}"
+        );
+    }
+    assert_eq!(observed_split_id_sets.last().unwrap(), &merged_split_ids);
+
+    cleanup_index(&mut metastore, index_uid).await
+}
+
 pub async fn test_metastore_replace_splits<
     MetastoreToTest: MetastoreServiceExt + DefaultForTest,
 >() {
@@ -1268,6 +1374,154 @@ pub async fn test_metastore_mark_splits_for_deletion<
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_get_index_size_bytes<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-get-index-size-bytes");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let index_size_bytes = metastore
+        .get_index_size_bytes(index_uid.clone())
+        .await
+        .unwrap();
+    assert_eq!(index_size_bytes, 0);
+
+    let split_id_1 = format!("{index_id}--split-1");
+    let split_metadata_1 = SplitMetadata {
+        split_id: split_id_1.clone(),
+        index_uid: index_uid.clone(),
+        size_in_bytes: 1_000,
+        ..Default::default()
+    };
+    let split_id_2 = format!("{index_id}--split-2");
+    let split_metadata_2 = SplitMetadata {
+        split_id: split_id_2.clone(),
+        index_uid: index_uid.clone(),
+        size_in_bytes: 2_000,
+        ..Default::default()
+    };
+    let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+        index_uid.clone(),
+        [split_metadata_1, split_metadata_2],
+    )
+    .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    // Staged splits are not accounted for.
+    let index_size_bytes = metastore
+        .get_index_size_bytes(index_uid.clone())
+        .await
+        .unwrap();
+    assert_eq!(index_size_bytes, 0);
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![split_id_1.clone(), split_id_2.clone()],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let index_size_bytes = metastore
+        .get_index_size_bytes(index_uid.clone())
+        .await
+        .unwrap();
+    assert_eq!(index_size_bytes, 3_000);
+
+    let mark_splits_for_deletion_request =
+        MarkSplitsForDeletionRequest::new(index_uid.clone(), vec![split_id_1.clone()]);
+    metastore
+        .mark_splits_for_deletion(mark_splits_for_deletion_request)
+        .await
+        .unwrap();
+
+    let index_size_bytes = metastore
+        .get_index_size_bytes(index_uid.clone())
+        .await
+        .unwrap();
+    assert_eq!(index_size_bytes, 2_000);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+/// Deletes more splits than fit in a single batch of [`MetastoreServiceExt::delete_splits_bulk`].
+pub async fn test_metastore_delete_splits_bulk<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest + Clone,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-delete-splits-bulk");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let splits_metadata: Vec<SplitMetadata> = (0..5_000)
+        .map(|split_ord| SplitMetadata {
+            split_id: format!("{index_id}--split-{split_ord}"),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        })
+        .collect();
+    let mut split_ids: Vec<String> = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.split_id.clone())
+        .collect();
+    split_ids.sort();
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest {
+        index_uid: Some(index_uid.clone()),
+        split_ids: split_ids.clone(),
+    };
+    metastore
+        .mark_splits_for_deletion(mark_splits_for_deletion_request)
+        .await
+        .unwrap();
+
+    let outcome = metastore
+        .delete_splits_bulk(index_uid.clone(), split_ids.clone())
+        .await;
+    assert!(outcome.error_opt.is_none());
+    assert!(outcome.failed_split_ids.is_empty());
+
+    let mut deleted_split_ids = outcome.deleted_split_ids;
+    deleted_split_ids.sort();
+    assert_eq!(deleted_split_ids, split_ids);
+
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid.clone()).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert!(splits.is_empty());
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_delete_splits<MetastoreToTest: MetastoreServiceExt + DefaultForTest>() {
     let mut metastore = MetastoreToTest::default_for_test().await;
 
@@ -1572,6 +1826,53 @@ pub async fn test_metastore_stage_splits<MetastoreToTest: MetastoreServiceExt +
         .index_uid()
         .clone();
 
+    // Stage a split with a path-unsafe split ID
+    let invalid_split_metadata = SplitMetadata {
+        split_id: "../split".to_string(),
+        index_uid: index_uid.clone(),
+        ..Default::default()
+    };
+    let stage_splits_request =
+        StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &invalid_split_metadata)
+            .unwrap();
+    let error = metastore
+        .stage_splits(stage_splits_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    // Publish, mark for deletion, and delete splits with path-unsafe split IDs
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec!["split/1".to_string()],
+        ..Default::default()
+    };
+    let error = metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest {
+        index_uid: Some(index_uid.clone()),
+        split_ids: vec!["..".to_string()],
+    };
+    let error = metastore
+        .mark_splits_for_deletion(mark_splits_for_deletion_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let delete_splits_request = DeleteSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        split_ids: vec!["".to_string()],
+    };
+    let error = metastore
+        .delete_splits(delete_splits_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
     // Stage a split on an index
     let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
         index_uid.clone(),
@@ -1655,6 +1956,158 @@ pub async fn test_metastore_stage_splits<MetastoreToTest: MetastoreServiceExt +
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_stage_and_publish_splits_in_bulk_is_atomic<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    const NUM_SPLITS: usize = 1_000;
+
+    let mut metastore = MetastoreToTest::default_for_test().await;
+    let current_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    let index_id = append_random_suffix("test-stage-splits-bulk");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid()
+        .clone();
+
+    let published_split_id = format!("{index_id}--split-published");
+    let published_split_metadata = SplitMetadata {
+        split_id: published_split_id.clone(),
+        index_uid: index_uid.clone(),
+        create_timestamp: current_timestamp,
+        ..Default::default()
+    };
+    let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+        index_uid.clone(),
+        [published_split_metadata.clone()],
+    )
+    .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: vec![published_split_id.clone()],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let splits_metadata: Vec<SplitMetadata> = (0..NUM_SPLITS)
+        .map(|split_ord| SplitMetadata {
+            split_id: format!("{index_id}--split-{split_ord:04}"),
+            index_uid: index_uid.clone(),
+            create_timestamp: current_timestamp,
+            ..Default::default()
+        })
+        .collect();
+    let split_ids: Vec<String> = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.split_id.clone())
+        .collect();
+
+    // Staging a batch containing a split that is already published must not stage any split.
+    let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+        index_uid.clone(),
+        splits_metadata
+            .iter()
+            .cloned()
+            .chain([published_split_metadata]),
+    )
+    .unwrap();
+    let error = metastore
+        .stage_splits(stage_splits_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::FailedPrecondition {
+            entity: EntityKind::Splits { .. },
+            ..
+        }
+    ));
+    let query = ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Staged);
+    let staged_splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert!(staged_splits.is_empty());
+
+    // Staging a valid batch stages all the splits at once.
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let staged_splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert_eq!(staged_splits.len(), NUM_SPLITS);
+
+    // Publishing a batch containing a split that does not exist must not publish any split.
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: split_ids
+            .iter()
+            .cloned()
+            .chain([format!("{index_id}--split-does-not-exist")])
+            .collect(),
+        ..Default::default()
+    };
+    let error = metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::NotFound(EntityKind::Splits { .. })
+    ));
+    let staged_splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert_eq!(staged_splits.len(), NUM_SPLITS);
+
+    // Publishing a valid batch publishes all the splits at once.
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: Some(index_uid.clone()),
+        staged_split_ids: split_ids,
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    let query =
+        ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Published);
+    let published_splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert_eq!(published_splits.len(), NUM_SPLITS + 1);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_update_splits_delete_opstamp<
     MetastoreToTest: MetastoreServiceExt + DefaultForTest,
 >() {
@@ -0,0 +1,285 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use quickwit_config::validate_identifier;
+use serde::{Deserialize, Serialize};
+
+/// Minimum length of an index ID.
+pub const MIN_INDEX_ID_LEN: usize = 3;
+
+/// Maximum length of an index ID.
+pub const MAX_INDEX_ID_LEN: usize = 255;
+
+/// Error returned when a string is not a valid index ID.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct InvalidIndexIdError {
+    /// The rejected index ID.
+    pub index_id: String,
+    message: String,
+}
+
+/// An index ID validated with [`validate_identifier`], the rules enforced when an index is
+/// created.
+///
+/// Unlike [`quickwit_proto::types::IndexId`], which is a plain `String` alias, holding a
+/// `ValidatedIndexId` guarantees that the ID could belong to an existing index. Literals can be
+/// validated at compile time with the [`index_id!`](crate::index_id) macro.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ValidatedIndexId(String);
+
+impl ValidatedIndexId {
+    /// Creates a new [`ValidatedIndexId`], returning an error if `index_id` is not a valid index
+    /// ID.
+    pub fn new_validated(index_id: String) -> Result<Self, InvalidIndexIdError> {
+        if let Err(error) = validate_identifier("index", &index_id) {
+            let message = error.to_string();
+            return Err(InvalidIndexIdError { index_id, message });
+        }
+        debug_assert!(Self::is_valid(&index_id));
+        Ok(Self(index_id))
+    }
+
+    /// Returns whether `index_id` is a valid index ID. This is the `const` equivalent of
+    /// [`validate_identifier`], used by the [`index_id!`](crate::index_id) macro.
+    pub const fn is_valid(index_id: &str) -> bool {
+        let bytes = index_id.as_bytes();
+
+        if bytes.len() < MIN_INDEX_ID_LEN || bytes.len() > MAX_INDEX_ID_LEN {
+            return false;
+        }
+        if !bytes[0].is_ascii_alphabetic() {
+            return false;
+        }
+        let mut i = 1;
+
+        while i < bytes.len() {
+            let byte = bytes[i];
+            if !(byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || byte == b'.') {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Creates a [`ValidatedIndexId`] from a literal already validated at compile time by the
+    /// [`index_id!`](crate::index_id) macro.
+    #[doc(hidden)]
+    pub fn from_validated_literal(index_id: &'static str) -> Self {
+        debug_assert!(Self::is_valid(index_id));
+        Self(index_id.to_string())
+    }
+
+    /// Returns the index ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the index ID and returns the underlying string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+/// Creates a [`ValidatedIndexId`] from a string literal, validated at compile time.
+///
+/// ```
+/// use quickwit_metastore::{index_id, ValidatedIndexId};
+///
+/// let index_id: ValidatedIndexId = index_id!("my-index");
+/// assert_eq!(index_id, "my-index");
+/// ```
+///
+/// ```compile_fail
+/// let index_id = quickwit_metastore::index_id!("my/index");
+/// ```
+#[macro_export]
+macro_rules! index_id {
+    ($index_id:literal) => {{
+        const _: () = assert!(
+            $crate::ValidatedIndexId::is_valid($index_id),
+            concat!("invalid index ID `", $index_id, "`")
+        );
+        $crate::ValidatedIndexId::from_validated_literal($index_id)
+    }};
+}
+
+impl Deref for ValidatedIndexId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ValidatedIndexId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ValidatedIndexId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl FromStr for ValidatedIndexId {
+    type Err = InvalidIndexIdError;
+
+    fn from_str(index_id: &str) -> Result<Self, Self::Err> {
+        Self::new_validated(index_id.to_string())
+    }
+}
+
+impl TryFrom<String> for ValidatedIndexId {
+    type Error = InvalidIndexIdError;
+
+    fn try_from(index_id: String) -> Result<Self, Self::Error> {
+        Self::new_validated(index_id)
+    }
+}
+
+impl TryFrom<&str> for ValidatedIndexId {
+    type Error = InvalidIndexIdError;
+
+    fn try_from(index_id: &str) -> Result<Self, Self::Error> {
+        Self::new_validated(index_id.to_string())
+    }
+}
+
+impl From<ValidatedIndexId> for String {
+    fn from(index_id: ValidatedIndexId) -> Self {
+        index_id.0
+    }
+}
+
+impl PartialEq<str> for ValidatedIndexId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ValidatedIndexId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validated_index_id_new_validated() {
+        ValidatedIndexId::new_validated("my-index_01".to_string()).unwrap();
+        ValidatedIndexId::new_validated("my.index".to_string()).unwrap();
+        ValidatedIndexId::new_validated("abc".to_string()).unwrap();
+        ValidatedIndexId::new_validated("a".repeat(MAX_INDEX_ID_LEN)).unwrap();
+
+        let error = ValidatedIndexId::new_validated("a".repeat(MAX_INDEX_ID_LEN + 1)).unwrap_err();
+        assert_eq!(error.index_id.len(), 256);
+
+        for index_id in [
+            "",
+            "ab",
+            "1index",
+            "-index",
+            ".index",
+            "my/index",
+            "my\\index",
+            "my index",
+            "my%20index",
+            "my?index",
+            "my#index",
+            "my*index",
+            "..",
+            "indéx",
+        ] {
+            let error = ValidatedIndexId::new_validated(index_id.to_string()).unwrap_err();
+            assert_eq!(error.index_id, index_id);
+            assert!(error.to_string().contains("is invalid"));
+        }
+    }
+
+    #[test]
+    fn test_validated_index_id_is_valid_matches_validate_identifier() {
+        let index_ids = [
+            "", "a", "ab", "abc", "a.b", "a-b", "a_b", "aB9", "9ab", "_ab", "-ab", ".ab", "ab/",
+            "ab*", "ab c", "abé", "ab\n",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .chain(["a".repeat(254), "a".repeat(255), "a".repeat(256)]);
+
+        for index_id in index_ids {
+            assert_eq!(
+                ValidatedIndexId::is_valid(&index_id),
+                validate_identifier("index", &index_id).is_ok(),
+                "`{index_id}`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validated_index_id_is_valid_in_const_context() {
+        const IS_VALID: bool = ValidatedIndexId::is_valid("my-index");
+        const IS_INVALID: bool = ValidatedIndexId::is_valid("my/index");
+        assert!(IS_VALID);
+        assert!(!IS_INVALID);
+    }
+
+    #[test]
+    fn test_index_id_macro() {
+        let index_id = crate::index_id!("my-index");
+        assert_eq!(index_id, "my-index");
+        assert_eq!(
+            index_id,
+            ValidatedIndexId::new_validated("my-index".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validated_index_id_from_str_and_display() {
+        let index_id: ValidatedIndexId = "my-index".parse().unwrap();
+        assert_eq!(index_id.to_string(), "my-index");
+        assert_eq!(index_id.len(), 8);
+        assert_eq!(String::from(index_id), "my-index");
+
+        ValidatedIndexId::try_from("my/index").unwrap_err();
+    }
+
+    #[test]
+    fn test_validated_index_id_serde() {
+        let index_id = ValidatedIndexId::new_validated("my-index".to_string()).unwrap();
+        let index_id_json = serde_json::to_string(&index_id).unwrap();
+        assert_eq!(index_id_json, r#""my-index""#);
+
+        let deserialized_index_id: ValidatedIndexId = serde_json::from_str(&index_id_json).unwrap();
+        assert_eq!(deserialized_index_id, index_id);
+
+        let error = serde_json::from_str::<ValidatedIndexId>(r#""my/index""#).unwrap_err();
+        assert!(error.to_string().contains("index ID `my/index` is invalid"));
+    }
+}
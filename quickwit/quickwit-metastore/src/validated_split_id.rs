@@ -0,0 +1,180 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Error returned when a string is not a valid split ID.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid split ID `{split_id}`: {reason}")]
+pub struct InvalidSplitIdError {
+    /// The rejected split ID.
+    pub split_id: String,
+    /// Why the split ID was rejected.
+    pub reason: &'static str,
+}
+
+/// A validated split ID.
+///
+/// Unlike [`quickwit_proto::types::SplitId`], which is a plain `String` alias, holding a
+/// `ValidatedSplitId` guarantees that the ID is safe to use in a path. Split IDs are used to build
+/// the file names of the splits in the index storage, so they must not be empty and must not
+/// contain path separators or `..`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ValidatedSplitId(String);
+
+impl ValidatedSplitId {
+    /// Creates a new [`ValidatedSplitId`], returning an error if `split_id` is empty or contains
+    /// `/`, `\` or `..`.
+    pub fn new_validated(split_id: String) -> Result<Self, InvalidSplitIdError> {
+        let reason_opt = if split_id.is_empty() {
+            Some("split ID must not be empty")
+        } else if split_id.contains(['/', '\\']) {
+            Some("split ID must not contain path separators")
+        } else if split_id.contains("..") {
+            Some("split ID must not contain `..`")
+        } else {
+            None
+        };
+        if let Some(reason) = reason_opt {
+            return Err(InvalidSplitIdError { split_id, reason });
+        }
+        Ok(Self(split_id))
+    }
+
+    /// Returns the split ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the split ID and returns the underlying string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for ValidatedSplitId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ValidatedSplitId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ValidatedSplitId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl FromStr for ValidatedSplitId {
+    type Err = InvalidSplitIdError;
+
+    fn from_str(split_id: &str) -> Result<Self, Self::Err> {
+        Self::new_validated(split_id.to_string())
+    }
+}
+
+impl TryFrom<String> for ValidatedSplitId {
+    type Error = InvalidSplitIdError;
+
+    fn try_from(split_id: String) -> Result<Self, Self::Error> {
+        Self::new_validated(split_id)
+    }
+}
+
+impl From<ValidatedSplitId> for String {
+    fn from(split_id: ValidatedSplitId) -> Self {
+        split_id.0
+    }
+}
+
+impl PartialEq<str> for ValidatedSplitId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ValidatedSplitId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validated_split_id_new_validated() {
+        let split_id = ulid::Ulid::new().to_string();
+        assert_eq!(
+            ValidatedSplitId::new_validated(split_id.clone()).unwrap(),
+            *split_id
+        );
+
+        ValidatedSplitId::new_validated("split-1_a.b".to_string()).unwrap();
+
+        let error = ValidatedSplitId::new_validated("".to_string()).unwrap_err();
+        assert_eq!(error.reason, "split ID must not be empty");
+
+        for split_id in ["foo/bar", "/foo", "foo\\bar", "foo\\"] {
+            let error = ValidatedSplitId::new_validated(split_id.to_string()).unwrap_err();
+            assert_eq!(error.split_id, split_id);
+            assert_eq!(error.reason, "split ID must not contain path separators");
+        }
+        for split_id in ["..", "foo..bar", "..foo"] {
+            let error = ValidatedSplitId::new_validated(split_id.to_string()).unwrap_err();
+            assert_eq!(error.reason, "split ID must not contain `..`");
+        }
+    }
+
+    #[test]
+    fn test_validated_split_id_from_str_and_display() {
+        let split_id: ValidatedSplitId = "split-1".parse().unwrap();
+        assert_eq!(split_id.to_string(), "split-1");
+        assert_eq!(split_id.len(), 7);
+        assert_eq!(split_id, "split-1");
+        assert_eq!(String::from(split_id), "split-1");
+
+        "../split".parse::<ValidatedSplitId>().unwrap_err();
+    }
+
+    #[test]
+    fn test_validated_split_id_serde() {
+        let split_id = ValidatedSplitId::new_validated("split-1".to_string()).unwrap();
+        let split_id_json = serde_json::to_string(&split_id).unwrap();
+        assert_eq!(split_id_json, r#""split-1""#);
+
+        let deserialized_split_id: ValidatedSplitId = serde_json::from_str(&split_id_json).unwrap();
+        assert_eq!(deserialized_split_id, split_id);
+
+        let error = serde_json::from_str::<ValidatedSplitId>(r#""foo/bar""#).unwrap_err();
+        assert!(error.to_string().contains("invalid split ID `foo/bar`"));
+    }
+}
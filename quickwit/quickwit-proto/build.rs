@@ -91,6 +91,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "IndexesMetadataResponse.indexes_metadata_json_zstd",
             "ListIndexesMetadataResponse.indexes_metadata_json_zstd",
         ])
+        .extern_path(".quickwit.ingest.Position", "crate::types::Position")
         .extern_path(".quickwit.ingest.ShardId", "crate::types::ShardId")
         .extern_path(".quickwit.common.IndexUid", "crate::types::IndexUid")
         .field_attribute("DeleteQuery.index_uid", "#[serde(alias = \"index_id\")]")
@@ -103,6 +104,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .field_attribute(
             "DeleteQuery.end_timestamp",
             "#[serde(skip_serializing_if = \"Option::is_none\")]",
+        )
+        .field_attribute(
+            "SourceCheckpoint.per_partition",
+            "#[schema(value_type = Object)]",
         );
 
     Codegen::builder()
@@ -200,6 +205,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .type_attribute("StatusCode", r#"#[serde(rename_all = "snake_case")]"#)
         .out_dir("src/codegen/opentelemetry")
         .compile_with_config(prost_config, &protos, &["protos/third-party"])?;
+
+    // File descriptor set of all the gRPC services, exposed by the gRPC reflection service. The
+    // Rust code generated along the way is discarded.
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+
+    let mut protos = find_protos("protos/quickwit");
+    protos.push(PathBuf::from("protos/third-party/jaeger/storage.proto"));
+    protos.extend(find_protos(
+        "protos/third-party/opentelemetry/proto/collector",
+    ));
+
+    let mut prost_config = prost_build::Config::default();
+    prost_config
+        .protoc_arg("--experimental_allow_proto3_optional")
+        .file_descriptor_set_path(out_dir.join("file_descriptor_set.bin"))
+        .out_dir(&out_dir);
+    prost_config.compile_protos(
+        &protos,
+        &["protos/third-party/jaeger", "protos/third-party", "protos"],
+    )?;
     Ok(())
 }
 
@@ -139,11 +139,15 @@ impl ClusterServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = cluster_service_grpc_client::ClusterServiceGrpcClient::new(channel)
+        let client = cluster_service_grpc_client::ClusterServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = ClusterServiceGrpcClientAdapter::new(
@@ -155,11 +159,13 @@ impl ClusterServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ClusterServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = cluster_service_grpc_client::ClusterServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = cluster_service_grpc_client::ClusterServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = ClusterServiceGrpcClientAdapter::new(
@@ -340,21 +346,31 @@ impl ClusterServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ClusterServiceClient {
         self.build_from_boxed(
-            Box::new(ClusterServiceClient::from_channel(addr, channel, max_message_size)),
+            Box::new(
+                ClusterServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
+            ),
         )
     }
     pub fn build_from_balance_channel(
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ClusterServiceClient {
         self.build_from_boxed(
             Box::new(
                 ClusterServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
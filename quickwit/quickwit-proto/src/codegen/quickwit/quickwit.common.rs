@@ -66,13 +66,15 @@ impl DiagnosticServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = diagnostic_service_grpc_client::DiagnosticServiceGrpcClient::new(
-                channel,
-            )
+        let client = diagnostic_service_grpc_client::DiagnosticServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = DiagnosticServiceGrpcClientAdapter::new(
@@ -84,11 +86,13 @@ impl DiagnosticServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DiagnosticServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = diagnostic_service_grpc_client::DiagnosticServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = diagnostic_service_grpc_client::DiagnosticServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = DiagnosticServiceGrpcClientAdapter::new(
@@ -269,10 +273,16 @@ impl DiagnosticServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DiagnosticServiceClient {
         self.build_from_boxed(
             Box::new(
-                DiagnosticServiceClient::from_channel(addr, channel, max_message_size),
+                DiagnosticServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -280,12 +290,14 @@ impl DiagnosticServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DiagnosticServiceClient {
         self.build_from_boxed(
             Box::new(
                 DiagnosticServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
@@ -215,13 +215,15 @@ impl ControlPlaneServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = control_plane_service_grpc_client::ControlPlaneServiceGrpcClient::new(
-                channel,
-            )
+        let client = control_plane_service_grpc_client::ControlPlaneServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = ControlPlaneServiceGrpcClientAdapter::new(
@@ -233,11 +235,13 @@ impl ControlPlaneServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ControlPlaneServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = control_plane_service_grpc_client::ControlPlaneServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = control_plane_service_grpc_client::ControlPlaneServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = ControlPlaneServiceGrpcClientAdapter::new(
@@ -1154,10 +1158,16 @@ impl ControlPlaneServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ControlPlaneServiceClient {
         self.build_from_boxed(
             Box::new(
-                ControlPlaneServiceClient::from_channel(addr, channel, max_message_size),
+                ControlPlaneServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -1165,12 +1175,14 @@ impl ControlPlaneServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> ControlPlaneServiceClient {
         self.build_from_boxed(
             Box::new(
                 ControlPlaneServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
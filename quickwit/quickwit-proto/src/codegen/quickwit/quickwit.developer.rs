@@ -70,13 +70,15 @@ impl DeveloperServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = developer_service_grpc_client::DeveloperServiceGrpcClient::new(
-                channel,
-            )
+        let client = developer_service_grpc_client::DeveloperServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = DeveloperServiceGrpcClientAdapter::new(
@@ -88,11 +90,13 @@ impl DeveloperServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DeveloperServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = developer_service_grpc_client::DeveloperServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = developer_service_grpc_client::DeveloperServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = DeveloperServiceGrpcClientAdapter::new(
@@ -272,10 +276,16 @@ impl DeveloperServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DeveloperServiceClient {
         self.build_from_boxed(
             Box::new(
-                DeveloperServiceClient::from_channel(addr, channel, max_message_size),
+                DeveloperServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -283,12 +293,14 @@ impl DeveloperServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> DeveloperServiceClient {
         self.build_from_boxed(
             Box::new(
                 DeveloperServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
@@ -78,13 +78,15 @@ impl IndexingServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = indexing_service_grpc_client::IndexingServiceGrpcClient::new(
-                channel,
-            )
+        let client = indexing_service_grpc_client::IndexingServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IndexingServiceGrpcClientAdapter::new(
@@ -96,11 +98,13 @@ impl IndexingServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IndexingServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = indexing_service_grpc_client::IndexingServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = indexing_service_grpc_client::IndexingServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IndexingServiceGrpcClientAdapter::new(
@@ -281,10 +285,16 @@ impl IndexingServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IndexingServiceClient {
         self.build_from_boxed(
             Box::new(
-                IndexingServiceClient::from_channel(addr, channel, max_message_size),
+                IndexingServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -292,12 +302,14 @@ impl IndexingServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IndexingServiceClient {
         self.build_from_boxed(
             Box::new(
                 IndexingServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
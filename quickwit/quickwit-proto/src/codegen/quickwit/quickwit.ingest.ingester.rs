@@ -699,13 +699,15 @@ impl IngesterServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = ingester_service_grpc_client::IngesterServiceGrpcClient::new(
-                channel,
-            )
+        let client = ingester_service_grpc_client::IngesterServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngesterServiceGrpcClientAdapter::new(
@@ -717,11 +719,13 @@ impl IngesterServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngesterServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = ingester_service_grpc_client::IngesterServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = ingester_service_grpc_client::IngesterServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngesterServiceGrpcClientAdapter::new(
@@ -1694,10 +1698,16 @@ impl IngesterServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngesterServiceClient {
         self.build_from_boxed(
             Box::new(
-                IngesterServiceClient::from_channel(addr, channel, max_message_size),
+                IngesterServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -1705,12 +1715,14 @@ impl IngesterServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngesterServiceClient {
         self.build_from_boxed(
             Box::new(
                 IngesterServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
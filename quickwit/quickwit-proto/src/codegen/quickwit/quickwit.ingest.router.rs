@@ -74,6 +74,7 @@ pub enum IngestFailureReason {
     RateLimited = 5,
     ResourceExhausted = 6,
     Timeout = 7,
+    IndexFrozen = 8,
 }
 impl IngestFailureReason {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -96,6 +97,7 @@ impl IngestFailureReason {
                 "INGEST_FAILURE_REASON_RESOURCE_EXHAUSTED"
             }
             IngestFailureReason::Timeout => "INGEST_FAILURE_REASON_TIMEOUT",
+            IngestFailureReason::IndexFrozen => "INGEST_FAILURE_REASON_INDEX_FROZEN",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -109,6 +111,7 @@ impl IngestFailureReason {
             "INGEST_FAILURE_REASON_RATE_LIMITED" => Some(Self::RateLimited),
             "INGEST_FAILURE_REASON_RESOURCE_EXHAUSTED" => Some(Self::ResourceExhausted),
             "INGEST_FAILURE_REASON_TIMEOUT" => Some(Self::Timeout),
+            "INGEST_FAILURE_REASON_INDEX_FROZEN" => Some(Self::IndexFrozen),
             _ => None,
         }
     }
@@ -172,13 +175,15 @@ impl IngestRouterServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = ingest_router_service_grpc_client::IngestRouterServiceGrpcClient::new(
-                channel,
-            )
+        let client = ingest_router_service_grpc_client::IngestRouterServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngestRouterServiceGrpcClientAdapter::new(
@@ -190,11 +195,13 @@ impl IngestRouterServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestRouterServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = ingest_router_service_grpc_client::IngestRouterServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = ingest_router_service_grpc_client::IngestRouterServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = IngestRouterServiceGrpcClientAdapter::new(
@@ -373,10 +380,16 @@ impl IngestRouterServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestRouterServiceClient {
         self.build_from_boxed(
             Box::new(
-                IngestRouterServiceClient::from_channel(addr, channel, max_message_size),
+                IngestRouterServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -384,12 +397,14 @@ impl IngestRouterServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> IngestRouterServiceClient {
         self.build_from_boxed(
             Box::new(
                 IngestRouterServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
@@ -30,6 +30,19 @@ pub struct UpdateIndexRequest {
     pub search_settings_json: ::prost::alloc::string::String,
     #[prost(string, optional, tag = "3")]
     pub retention_policy_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// When set, upgrades the doc mapping of the index and bumps its schema version.
+    #[prost(string, optional, tag = "4")]
+    pub doc_mapping_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// When set, freezes or unfreezes the index.
+    #[prost(string, optional, tag = "5")]
+    pub index_state_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// When set, replaces the indexing settings of the index.
+    #[prost(string, optional, tag = "6")]
+    pub indexing_settings_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// When set, points the index to a new storage URI. The split files must have been copied to
+    /// the new location beforehand.
+    #[prost(string, optional, tag = "7")]
+    pub index_uri: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -504,6 +517,98 @@ pub struct DeleteIndexTemplatesRequest {
     pub template_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexAlias {
+    #[prost(string, tag = "1")]
+    pub alias_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateIndexAliasRequest {
+    #[prost(string, tag = "1")]
+    pub alias_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexAliasesRequest {
+    /// The IDs of the aliases to return. If empty, all the aliases are returned.
+    #[prost(string, repeated, tag = "1")]
+    pub alias_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexAliasesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub index_aliases: ::prost::alloc::vec::Vec<IndexAlias>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateIndexAliasRequest {
+    #[prost(string, tag = "1")]
+    pub alias_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub add_index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The indexes to remove from the alias. The update fails if any of them is not part of
+    /// the alias.
+    #[prost(string, repeated, tag = "3")]
+    pub remove_index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateIndexAliasResponse {
+    #[prost(message, optional, tag = "1")]
+    pub index_alias: ::core::option::Option<IndexAlias>,
+}
+/// The positions reached by a source for each of its partitions.
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SourceCheckpoint {
+    #[prost(map = "string, message", tag = "1")]
+    #[schema(value_type = Object)]
+    pub per_partition: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        crate::types::Position,
+    >,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSourceCheckpointRequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+    #[prost(string, tag = "2")]
+    pub source_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSourceCheckpointResponse {
+    #[prost(message, optional, tag = "1")]
+    pub source_checkpoint: ::core::option::Option<SourceCheckpoint>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSourceCheckpointRequest {
+    #[prost(message, optional, tag = "1")]
+    pub index_uid: ::core::option::Option<crate::types::IndexUid>,
+    #[prost(string, tag = "2")]
+    pub source_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub source_checkpoint: ::core::option::Option<SourceCheckpoint>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -524,6 +629,8 @@ pub enum SourceType {
     Pulsar = 9,
     Vec = 10,
     Void = 11,
+    /// Historical files stored on object storage
+    Backfill = 12,
 }
 impl SourceType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -544,6 +651,7 @@ impl SourceType {
             SourceType::Pulsar => "SOURCE_TYPE_PULSAR",
             SourceType::Vec => "SOURCE_TYPE_VEC",
             SourceType::Void => "SOURCE_TYPE_VOID",
+            SourceType::Backfill => "SOURCE_TYPE_BACKFILL",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -561,6 +669,7 @@ impl SourceType {
             "SOURCE_TYPE_PULSAR" => Some(Self::Pulsar),
             "SOURCE_TYPE_VEC" => Some(Self::Vec),
             "SOURCE_TYPE_VOID" => Some(Self::Void),
+            "SOURCE_TYPE_BACKFILL" => Some(Self::Backfill),
             _ => None,
         }
     }
@@ -752,6 +861,31 @@ impl RpcName for DeleteIndexTemplatesRequest {
         "delete_index_templates"
     }
 }
+impl RpcName for CreateIndexAliasRequest {
+    fn rpc_name() -> &'static str {
+        "create_index_alias"
+    }
+}
+impl RpcName for ListIndexAliasesRequest {
+    fn rpc_name() -> &'static str {
+        "list_index_aliases"
+    }
+}
+impl RpcName for UpdateIndexAliasRequest {
+    fn rpc_name() -> &'static str {
+        "update_index_alias"
+    }
+}
+impl RpcName for GetSourceCheckpointRequest {
+    fn rpc_name() -> &'static str {
+        "get_source_checkpoint"
+    }
+}
+impl RpcName for UpdateSourceCheckpointRequest {
+    fn rpc_name() -> &'static str {
+        "update_source_checkpoint"
+    }
+}
 pub type MetastoreServiceStream<T> = quickwit_common::ServiceStream<
     crate::metastore::MetastoreResult<T>,
 >;
@@ -802,6 +936,10 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
         request: StageSplitsRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse>;
     /// Publishes split.
+    ///
+    /// The staged splits are published and the replaced splits are marked for deletion in a single
+    /// transaction, so that merges never expose a state where neither the source splits nor the
+    /// merged split are visible.
     async fn publish_splits(
         &mut self,
         request: PublishSplitsRequest,
@@ -919,6 +1057,31 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
         &mut self,
         request: DeleteIndexTemplatesRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Creates an index alias.
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns the index aliases matching the request.
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse>;
+    /// Atomically adds and removes indexes from an index alias.
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateIndexAliasResponse>;
+    /// Returns the checkpoint of a source.
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<GetSourceCheckpointResponse>;
+    /// Overwrites the checkpoint of a source.
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
     async fn check_connectivity(&mut self) -> anyhow::Result<()>;
     fn endpoints(&self) -> Vec<quickwit_common::uri::Uri>;
 }
@@ -961,13 +1124,15 @@ impl MetastoreServiceClient {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> Self {
         let (_, connection_keys_watcher) = tokio::sync::watch::channel(
             std::collections::HashSet::from_iter([addr]),
         );
-        let client = metastore_service_grpc_client::MetastoreServiceGrpcClient::new(
-                channel,
-            )
+        let client = metastore_service_grpc_client::MetastoreServiceGrpcClient::with_interceptor(
+            channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = MetastoreServiceGrpcClientAdapter::new(
@@ -979,11 +1144,13 @@ impl MetastoreServiceClient {
     pub fn from_balance_channel(
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> MetastoreServiceClient {
         let connection_keys_watcher = balance_channel.connection_keys_watcher();
-        let client = metastore_service_grpc_client::MetastoreServiceGrpcClient::new(
-                balance_channel,
-            )
+        let client = metastore_service_grpc_client::MetastoreServiceGrpcClient::with_interceptor(
+            balance_channel,
+            grpc_client_interceptor,
+        )
             .max_decoding_message_size(max_message_size.0 as usize)
             .max_encoding_message_size(max_message_size.0 as usize);
         let adapter = MetastoreServiceGrpcClientAdapter::new(
@@ -1190,6 +1357,36 @@ impl MetastoreService for MetastoreServiceClient {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner.delete_index_templates(request).await
     }
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.create_index_alias(request).await
+    }
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.inner.list_index_aliases(request).await
+    }
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateIndexAliasResponse> {
+        self.inner.update_index_alias(request).await
+    }
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<GetSourceCheckpointResponse> {
+        self.inner.get_source_checkpoint(request).await
+    }
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.update_source_checkpoint(request).await
+    }
     async fn check_connectivity(&mut self) -> anyhow::Result<()> {
         self.inner.check_connectivity().await
     }
@@ -1384,6 +1581,36 @@ pub mod mock_metastore_service {
         ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
             self.inner.lock().await.delete_index_templates(request).await
         }
+        async fn create_index_alias(
+            &mut self,
+            request: super::CreateIndexAliasRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.create_index_alias(request).await
+        }
+        async fn list_index_aliases(
+            &mut self,
+            request: super::ListIndexAliasesRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListIndexAliasesResponse> {
+            self.inner.lock().await.list_index_aliases(request).await
+        }
+        async fn update_index_alias(
+            &mut self,
+            request: super::UpdateIndexAliasRequest,
+        ) -> crate::metastore::MetastoreResult<super::UpdateIndexAliasResponse> {
+            self.inner.lock().await.update_index_alias(request).await
+        }
+        async fn get_source_checkpoint(
+            &mut self,
+            request: super::GetSourceCheckpointRequest,
+        ) -> crate::metastore::MetastoreResult<super::GetSourceCheckpointResponse> {
+            self.inner.lock().await.get_source_checkpoint(request).await
+        }
+        async fn update_source_checkpoint(
+            &mut self,
+            request: super::UpdateSourceCheckpointRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.update_source_checkpoint(request).await
+        }
         async fn check_connectivity(&mut self) -> anyhow::Result<()> {
             self.inner.lock().await.check_connectivity().await
         }
@@ -1859,6 +2086,86 @@ impl tower::Service<DeleteIndexTemplatesRequest> for Box<dyn MetastoreService> {
         Box::pin(fut)
     }
 }
+impl tower::Service<CreateIndexAliasRequest> for Box<dyn MetastoreService> {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: CreateIndexAliasRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.create_index_alias(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListIndexAliasesRequest> for Box<dyn MetastoreService> {
+    type Response = ListIndexAliasesResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListIndexAliasesRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.list_index_aliases(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<UpdateIndexAliasRequest> for Box<dyn MetastoreService> {
+    type Response = UpdateIndexAliasResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UpdateIndexAliasRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.update_index_alias(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<GetSourceCheckpointRequest> for Box<dyn MetastoreService> {
+    type Response = GetSourceCheckpointResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: GetSourceCheckpointRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.get_source_checkpoint(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<UpdateSourceCheckpointRequest> for Box<dyn MetastoreService> {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UpdateSourceCheckpointRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.update_source_checkpoint(request).await };
+        Box::pin(fut)
+    }
+}
 /// A tower service stack is a set of tower services.
 #[derive(Debug)]
 struct MetastoreServiceTowerServiceStack {
@@ -2008,6 +2315,31 @@ struct MetastoreServiceTowerServiceStack {
         EmptyResponse,
         crate::metastore::MetastoreError,
     >,
+    create_index_alias_svc: quickwit_common::tower::BoxService<
+        CreateIndexAliasRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_index_aliases_svc: quickwit_common::tower::BoxService<
+        ListIndexAliasesRequest,
+        ListIndexAliasesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    update_index_alias_svc: quickwit_common::tower::BoxService<
+        UpdateIndexAliasRequest,
+        UpdateIndexAliasResponse,
+        crate::metastore::MetastoreError,
+    >,
+    get_source_checkpoint_svc: quickwit_common::tower::BoxService<
+        GetSourceCheckpointRequest,
+        GetSourceCheckpointResponse,
+        crate::metastore::MetastoreError,
+    >,
+    update_source_checkpoint_svc: quickwit_common::tower::BoxService<
+        UpdateSourceCheckpointRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
 }
 impl Clone for MetastoreServiceTowerServiceStack {
     fn clone(&self) -> Self {
@@ -2046,6 +2378,11 @@ impl Clone for MetastoreServiceTowerServiceStack {
                 .clone(),
             list_index_templates_svc: self.list_index_templates_svc.clone(),
             delete_index_templates_svc: self.delete_index_templates_svc.clone(),
+            create_index_alias_svc: self.create_index_alias_svc.clone(),
+            list_index_aliases_svc: self.list_index_aliases_svc.clone(),
+            update_index_alias_svc: self.update_index_alias_svc.clone(),
+            get_source_checkpoint_svc: self.get_source_checkpoint_svc.clone(),
+            update_source_checkpoint_svc: self.update_source_checkpoint_svc.clone(),
         }
     }
 }
@@ -2225,6 +2562,36 @@ impl MetastoreService for MetastoreServiceTowerServiceStack {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.delete_index_templates_svc.ready().await?.call(request).await
     }
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.create_index_alias_svc.ready().await?.call(request).await
+    }
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.list_index_aliases_svc.ready().await?.call(request).await
+    }
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateIndexAliasResponse> {
+        self.update_index_alias_svc.ready().await?.call(request).await
+    }
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<GetSourceCheckpointResponse> {
+        self.get_source_checkpoint_svc.ready().await?.call(request).await
+    }
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.update_source_checkpoint_svc.ready().await?.call(request).await
+    }
     async fn check_connectivity(&mut self) -> anyhow::Result<()> {
         self.inner.check_connectivity().await
     }
@@ -2522,6 +2889,56 @@ type DeleteIndexTemplatesLayer = quickwit_common::tower::BoxLayer<
     EmptyResponse,
     crate::metastore::MetastoreError,
 >;
+type CreateIndexAliasLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreateIndexAliasRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreateIndexAliasRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListIndexAliasesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListIndexAliasesRequest,
+        ListIndexAliasesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListIndexAliasesRequest,
+    ListIndexAliasesResponse,
+    crate::metastore::MetastoreError,
+>;
+type UpdateIndexAliasLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UpdateIndexAliasRequest,
+        UpdateIndexAliasResponse,
+        crate::metastore::MetastoreError,
+    >,
+    UpdateIndexAliasRequest,
+    UpdateIndexAliasResponse,
+    crate::metastore::MetastoreError,
+>;
+type GetSourceCheckpointLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        GetSourceCheckpointRequest,
+        GetSourceCheckpointResponse,
+        crate::metastore::MetastoreError,
+    >,
+    GetSourceCheckpointRequest,
+    GetSourceCheckpointResponse,
+    crate::metastore::MetastoreError,
+>;
+type UpdateSourceCheckpointLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UpdateSourceCheckpointRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    UpdateSourceCheckpointRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
 #[derive(Debug, Default)]
 pub struct MetastoreServiceTowerLayerStack {
     create_index_layers: Vec<CreateIndexLayer>,
@@ -2553,6 +2970,11 @@ pub struct MetastoreServiceTowerLayerStack {
     find_index_template_matches_layers: Vec<FindIndexTemplateMatchesLayer>,
     list_index_templates_layers: Vec<ListIndexTemplatesLayer>,
     delete_index_templates_layers: Vec<DeleteIndexTemplatesLayer>,
+    create_index_alias_layers: Vec<CreateIndexAliasLayer>,
+    list_index_aliases_layers: Vec<ListIndexAliasesLayer>,
+    update_index_alias_layers: Vec<UpdateIndexAliasLayer>,
+    get_source_checkpoint_layers: Vec<GetSourceCheckpointLayer>,
+    update_source_checkpoint_layers: Vec<UpdateSourceCheckpointLayer>,
 }
 impl MetastoreServiceTowerLayerStack {
     pub fn stack_layer<L>(mut self, layer: L) -> Self
@@ -3298,31 +3720,166 @@ impl MetastoreServiceTowerLayerStack {
         >>::Service as tower::Service<
             DeleteIndexTemplatesRequest,
         >>::Future: Send + 'static,
-    {
-        self.create_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.update_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.index_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.stage_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.publish_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.mark_splits_for_deletion_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.add_source_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateIndexAliasRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateIndexAliasRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                CreateIndexAliasRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateIndexAliasRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            CreateIndexAliasRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListIndexAliasesRequest,
+                    ListIndexAliasesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListIndexAliasesRequest,
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListIndexAliasesRequest,
+                Response = ListIndexAliasesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListIndexAliasesRequest,
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListIndexAliasesRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateIndexAliasRequest,
+                    UpdateIndexAliasResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateIndexAliasRequest,
+                UpdateIndexAliasResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                UpdateIndexAliasRequest,
+                Response = UpdateIndexAliasResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateIndexAliasRequest,
+                UpdateIndexAliasResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            UpdateIndexAliasRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    GetSourceCheckpointRequest,
+                    GetSourceCheckpointResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                GetSourceCheckpointRequest,
+                GetSourceCheckpointResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                GetSourceCheckpointRequest,
+                Response = GetSourceCheckpointResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                GetSourceCheckpointRequest,
+                GetSourceCheckpointResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            GetSourceCheckpointRequest,
+        >>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateSourceCheckpointRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateSourceCheckpointRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                UpdateSourceCheckpointRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateSourceCheckpointRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            UpdateSourceCheckpointRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.index_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.stage_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.publish_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.mark_splits_for_deletion_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.add_source_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.toggle_source_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.delete_source_layers
@@ -3357,6 +3914,16 @@ impl MetastoreServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.delete_index_templates_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_index_alias_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_index_alias_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.get_source_checkpoint_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_source_checkpoint_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self
     }
     pub fn stack_create_index_layer<L>(mut self, layer: L) -> Self
@@ -3937,6 +4504,116 @@ impl MetastoreServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn stack_create_index_alias_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateIndexAliasRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreateIndexAliasRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreateIndexAliasRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_index_alias_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_index_aliases_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListIndexAliasesRequest,
+                    ListIndexAliasesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListIndexAliasesRequest,
+                Response = ListIndexAliasesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListIndexAliasesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_update_index_alias_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateIndexAliasRequest,
+                    UpdateIndexAliasResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateIndexAliasRequest,
+                Response = UpdateIndexAliasResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            UpdateIndexAliasRequest,
+        >>::Future: Send + 'static,
+    {
+        self.update_index_alias_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_get_source_checkpoint_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    GetSourceCheckpointRequest,
+                    GetSourceCheckpointResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                GetSourceCheckpointRequest,
+                Response = GetSourceCheckpointResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            GetSourceCheckpointRequest,
+        >>::Future: Send + 'static,
+    {
+        self.get_source_checkpoint_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_update_source_checkpoint_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateSourceCheckpointRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateSourceCheckpointRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            UpdateSourceCheckpointRequest,
+        >>::Future: Send + 'static,
+    {
+        self.update_source_checkpoint_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn build<T>(self, instance: T) -> MetastoreServiceClient
     where
         T: MetastoreService,
@@ -3948,10 +4625,16 @@ impl MetastoreServiceTowerLayerStack {
         addr: std::net::SocketAddr,
         channel: tonic::transport::Channel,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> MetastoreServiceClient {
         self.build_from_boxed(
             Box::new(
-                MetastoreServiceClient::from_channel(addr, channel, max_message_size),
+                MetastoreServiceClient::from_channel(
+                    addr,
+                    channel,
+                    max_message_size,
+                    grpc_client_interceptor,
+                ),
             ),
         )
     }
@@ -3959,12 +4642,14 @@ impl MetastoreServiceTowerLayerStack {
         self,
         balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
         max_message_size: bytesize::ByteSize,
+        grpc_client_interceptor: quickwit_common::tower::GrpcClientInterceptor,
     ) -> MetastoreServiceClient {
         self.build_from_boxed(
             Box::new(
                 MetastoreServiceClient::from_balance_channel(
                     balance_channel,
                     max_message_size,
+                    grpc_client_interceptor,
                 ),
             ),
         )
@@ -4219,6 +4904,46 @@ impl MetastoreServiceTowerLayerStack {
                 quickwit_common::tower::BoxService::new(boxed_instance.clone()),
                 |svc, layer| layer.layer(svc),
             );
+        let create_index_alias_svc = self
+            .create_index_alias_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_index_aliases_svc = self
+            .list_index_aliases_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let update_index_alias_svc = self
+            .update_index_alias_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let get_source_checkpoint_svc = self
+            .get_source_checkpoint_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let update_source_checkpoint_svc = self
+            .update_source_checkpoint_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
         let tower_svc_stack = MetastoreServiceTowerServiceStack {
             inner: boxed_instance.clone(),
             create_index_svc,
@@ -4250,6 +4975,11 @@ impl MetastoreServiceTowerLayerStack {
             find_index_template_matches_svc,
             list_index_templates_svc,
             delete_index_templates_svc,
+            create_index_alias_svc,
+            list_index_aliases_svc,
+            update_index_alias_svc,
+            get_source_checkpoint_svc,
+            update_source_checkpoint_svc,
         };
         MetastoreServiceClient::new(tower_svc_stack)
     }
@@ -4520,6 +5250,48 @@ where
             Response = EmptyResponse,
             Error = crate::metastore::MetastoreError,
             Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            CreateIndexAliasRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListIndexAliasesRequest,
+            Response = ListIndexAliasesResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            UpdateIndexAliasRequest,
+            Response = UpdateIndexAliasResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                UpdateIndexAliasResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            GetSourceCheckpointRequest,
+            Response = GetSourceCheckpointResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                GetSourceCheckpointResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            UpdateSourceCheckpointRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
         >,
 {
     async fn create_index(
@@ -4696,6 +5468,36 @@ where
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.call(request).await
     }
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.call(request).await
+    }
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.call(request).await
+    }
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateIndexAliasResponse> {
+        self.call(request).await
+    }
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<GetSourceCheckpointResponse> {
+        self.call(request).await
+    }
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.call(request).await
+    }
     async fn check_connectivity(&mut self) -> anyhow::Result<()> {
         if self.inner.is_disconnected() {
             anyhow::bail!("actor `{}` is disconnected", self.inner.actor_instance_id())
@@ -5128,6 +5930,71 @@ where
                 DeleteIndexTemplatesRequest::rpc_name(),
             ))
     }
+    async fn create_index_alias(
+        &mut self,
+        request: CreateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .create_index_alias(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                CreateIndexAliasRequest::rpc_name(),
+            ))
+    }
+    async fn list_index_aliases(
+        &mut self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.inner
+            .list_index_aliases(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ListIndexAliasesRequest::rpc_name(),
+            ))
+    }
+    async fn update_index_alias(
+        &mut self,
+        request: UpdateIndexAliasRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateIndexAliasResponse> {
+        self.inner
+            .update_index_alias(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                UpdateIndexAliasRequest::rpc_name(),
+            ))
+    }
+    async fn get_source_checkpoint(
+        &mut self,
+        request: GetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<GetSourceCheckpointResponse> {
+        self.inner
+            .get_source_checkpoint(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                GetSourceCheckpointRequest::rpc_name(),
+            ))
+    }
+    async fn update_source_checkpoint(
+        &mut self,
+        request: UpdateSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .update_source_checkpoint(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                UpdateSourceCheckpointRequest::rpc_name(),
+            ))
+    }
     async fn check_connectivity(&mut self) -> anyhow::Result<()> {
         if self.connection_addrs_rx.borrow().len() == 0 {
             anyhow::bail!("no server currently available")
@@ -5483,6 +6350,61 @@ for MetastoreServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(crate::error::grpc_error_to_grpc_status)
     }
+    async fn create_index_alias(
+        &self,
+        request: tonic::Request<CreateIndexAliasRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .create_index_alias(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_index_aliases(
+        &self,
+        request: tonic::Request<ListIndexAliasesRequest>,
+    ) -> Result<tonic::Response<ListIndexAliasesResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .list_index_aliases(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn update_index_alias(
+        &self,
+        request: tonic::Request<UpdateIndexAliasRequest>,
+    ) -> Result<tonic::Response<UpdateIndexAliasResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .update_index_alias(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn get_source_checkpoint(
+        &self,
+        request: tonic::Request<GetSourceCheckpointRequest>,
+    ) -> Result<tonic::Response<GetSourceCheckpointResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .get_source_checkpoint(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn update_source_checkpoint(
+        &self,
+        request: tonic::Request<UpdateSourceCheckpointRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .update_source_checkpoint(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
 }
 /// Generated client implementations.
 pub mod metastore_service_grpc_client {
@@ -5846,6 +6768,10 @@ pub mod metastore_service_grpc_client {
             self.inner.unary(req, path, codec).await
         }
         /// Publishes split.
+        ///
+        /// The staged splits are published and the replaced splits are marked for deletion in a single
+        /// transaction, so that merges never expose a state where neither the source splits nor the
+        /// merged split are visible.
         pub async fn publish_splits(
             &mut self,
             request: impl tonic::IntoRequest<super::PublishSplitsRequest>,
@@ -6312,19 +7238,168 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListShards",
+                "/quickwit.metastore.MetastoreService/ListShards",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Creates an index template.
+        pub async fn create_index_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "CreateIndexTemplate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Fetches an index template.
+        pub async fn get_index_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexTemplateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "GetIndexTemplate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Finds matching index templates.
+        pub async fn find_index_template_matches(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FindIndexTemplateMatchesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "FindIndexTemplateMatches",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Returns all the index templates.
+        pub async fn list_index_templates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexTemplatesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "ListIndexTemplates",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Deletes index templates.
+        pub async fn delete_index_templates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteIndexTemplates",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Creates an index template.
-        pub async fn create_index_template(
+        /// Creates an index alias.
+        pub async fn create_index_alias(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
+            request: impl tonic::IntoRequest<super::CreateIndexAliasRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -6337,24 +7412,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
+                "/quickwit.metastore.MetastoreService/CreateIndexAlias",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "CreateIndexTemplate",
+                        "CreateIndexAlias",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Fetches an index template.
-        pub async fn get_index_template(
+        /// Returns the index aliases matching the request.
+        pub async fn list_index_aliases(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
+            request: impl tonic::IntoRequest<super::ListIndexAliasesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIndexTemplateResponse>,
+            tonic::Response<super::ListIndexAliasesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6368,24 +7443,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
+                "/quickwit.metastore.MetastoreService/ListIndexAliases",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "GetIndexTemplate",
+                        "ListIndexAliases",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Finds matching index templates.
-        pub async fn find_index_template_matches(
+        /// Atomically adds and removes indexes from an index alias.
+        pub async fn update_index_alias(
             &mut self,
-            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
+            request: impl tonic::IntoRequest<super::UpdateIndexAliasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::FindIndexTemplateMatchesResponse>,
+            tonic::Response<super::UpdateIndexAliasResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6399,24 +7474,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
+                "/quickwit.metastore.MetastoreService/UpdateIndexAlias",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "FindIndexTemplateMatches",
+                        "UpdateIndexAlias",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Returns all the index templates.
-        pub async fn list_index_templates(
+        /// Returns the checkpoint of a source.
+        pub async fn get_source_checkpoint(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
+            request: impl tonic::IntoRequest<super::GetSourceCheckpointRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexTemplatesResponse>,
+            tonic::Response<super::GetSourceCheckpointResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6430,23 +7505,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
+                "/quickwit.metastore.MetastoreService/GetSourceCheckpoint",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListIndexTemplates",
+                        "GetSourceCheckpoint",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes index templates.
-        pub async fn delete_index_templates(
+        /// Overwrites the checkpoint of a source.
+        pub async fn update_source_checkpoint(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::UpdateSourceCheckpointRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::EmptyResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6458,14 +7536,14 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
+                "/quickwit.metastore.MetastoreService/UpdateSourceCheckpoint",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteIndexTemplates",
+                        "UpdateSourceCheckpoint",
                     ),
                 );
             self.inner.unary(req, path, codec).await
@@ -6544,6 +7622,10 @@ pub mod metastore_service_grpc_server {
             request: tonic::Request<super::StageSplitsRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
         /// Publishes split.
+        ///
+        /// The staged splits are published and the replaced splits are marked for deletion in a single
+        /// transaction, so that merges never expose a state where neither the source splits nor the
+        /// merged split are visible.
         async fn publish_splits(
             &self,
             request: tonic::Request<super::PublishSplitsRequest>,
@@ -6694,6 +7776,43 @@ pub mod metastore_service_grpc_server {
             &self,
             request: tonic::Request<super::DeleteIndexTemplatesRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Creates an index alias.
+        async fn create_index_alias(
+            &self,
+            request: tonic::Request<super::CreateIndexAliasRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns the index aliases matching the request.
+        async fn list_index_aliases(
+            &self,
+            request: tonic::Request<super::ListIndexAliasesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexAliasesResponse>,
+            tonic::Status,
+        >;
+        /// Atomically adds and removes indexes from an index alias.
+        async fn update_index_alias(
+            &self,
+            request: tonic::Request<super::UpdateIndexAliasRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateIndexAliasResponse>,
+            tonic::Status,
+        >;
+        /// Returns the checkpoint of a source.
+        async fn get_source_checkpoint(
+            &self,
+            request: tonic::Request<super::GetSourceCheckpointRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSourceCheckpointResponse>,
+            tonic::Status,
+        >;
+        /// Overwrites the checkpoint of a source.
+        async fn update_source_checkpoint(
+            &self,
+            request: tonic::Request<super::UpdateSourceCheckpointRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::EmptyResponse>,
+            tonic::Status,
+        >;
     }
     /// Metastore meant to manage Quickwit's indexes, their splits and delete tasks.
     ///
@@ -8157,6 +9276,236 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.metastore.MetastoreService/CreateIndexAlias" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateIndexAliasSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::CreateIndexAliasRequest>
+                    for CreateIndexAliasSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateIndexAliasRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).create_index_alias(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateIndexAliasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/ListIndexAliases" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIndexAliasesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::ListIndexAliasesRequest>
+                    for ListIndexAliasesSvc<T> {
+                        type Response = super::ListIndexAliasesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListIndexAliasesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).list_index_aliases(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListIndexAliasesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/UpdateIndexAlias" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateIndexAliasSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UpdateIndexAliasRequest>
+                    for UpdateIndexAliasSvc<T> {
+                        type Response = super::UpdateIndexAliasResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateIndexAliasRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_index_alias(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateIndexAliasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/GetSourceCheckpoint" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSourceCheckpointSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::GetSourceCheckpointRequest>
+                    for GetSourceCheckpointSvc<T> {
+                        type Response = super::GetSourceCheckpointResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSourceCheckpointRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).get_source_checkpoint(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSourceCheckpointSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/UpdateSourceCheckpoint" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSourceCheckpointSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UpdateSourceCheckpointRequest>
+                    for UpdateSourceCheckpointSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSourceCheckpointRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_source_checkpoint(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSourceCheckpointSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -191,6 +191,10 @@ pub struct SearchRequest {
     pub search_after: ::core::option::Option<PartialHit>,
     #[prost(enumeration = "CountHits", tag = "17")]
     pub count_hits: i32,
+    /// If set and the query matches no documents, the response will contain
+    /// spelling suggestions for the query terms.
+    #[prost(bool, tag = "18")]
+    pub suggest_corrections: bool,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[derive(Eq, Hash)]
@@ -230,6 +234,25 @@ pub struct SearchResponse {
     /// Scroll Id (only set if scroll_secs was set in the request)
     #[prost(string, optional, tag = "6")]
     pub scroll_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Spelling suggestions for the query terms (only set if suggest_corrections
+    /// was set in the request and the query matched no documents).
+    #[prost(string, repeated, tag = "7")]
+    pub suggestions: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Indexes that could not be searched when the search fanned out to several
+    /// indexes. The response only contains the hits of the other indexes.
+    #[prost(message, repeated, tag = "8")]
+    pub partial_failures: ::prost::alloc::vec::Vec<IndexSearchFailure>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexSearchFailure {
+    /// ID of the index that could not be searched.
+    #[prost(string, tag = "1")]
+    pub index_id: ::prost::alloc::string::String,
+    /// The search error that occurred formatted as string.
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -429,6 +452,24 @@ pub struct LeafSearchResponse {
     pub intermediate_aggregation_result: ::core::option::Option<
         ::prost::alloc::vec::Vec<u8>,
     >,
+    /// Terms from the split dictionaries close to the query terms.
+    /// Only computed if suggest_corrections was set and no document matched.
+    #[prost(message, repeated, tag = "7")]
+    pub suggestions: ::prost::alloc::vec::Vec<TermSuggestion>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TermSuggestion {
+    /// Suggested term.
+    #[prost(string, tag = "1")]
+    pub term: ::prost::alloc::string::String,
+    /// Levenshtein distance between the suggested term and the query term.
+    #[prost(uint32, tag = "2")]
+    pub distance: u32,
+    /// Number of documents containing the suggested term.
+    #[prost(uint64, tag = "3")]
+    pub doc_freq: u64,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -42,6 +42,8 @@ pub enum ServiceErrorCode {
     // Use `Unauthenticated` if the caller cannot be identified.
     Forbidden,
     Internal,
+    // The target resource is locked, e.g. a frozen index.
+    Locked,
     NotFound,
     Timeout,
     TooManyRequests,
@@ -50,12 +52,13 @@ pub enum ServiceErrorCode {
 }
 
 impl ServiceErrorCode {
-    fn grpc_status_code(&self) -> tonic::Code {
+    pub fn grpc_status_code(&self) -> tonic::Code {
         match self {
             Self::AlreadyExists => tonic::Code::AlreadyExists,
             Self::BadRequest => tonic::Code::InvalidArgument,
             Self::Forbidden => tonic::Code::PermissionDenied,
             Self::Internal => tonic::Code::Internal,
+            Self::Locked => tonic::Code::FailedPrecondition,
             Self::NotFound => tonic::Code::NotFound,
             Self::Timeout => tonic::Code::DeadlineExceeded,
             Self::TooManyRequests => tonic::Code::ResourceExhausted,
@@ -70,6 +73,7 @@ impl ServiceErrorCode {
             Self::BadRequest => http::StatusCode::BAD_REQUEST,
             Self::Forbidden => http::StatusCode::FORBIDDEN,
             Self::Internal => http::StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Locked => http::StatusCode::LOCKED,
             Self::NotFound => http::StatusCode::NOT_FOUND,
             Self::Timeout => http::StatusCode::REQUEST_TIMEOUT,
             Self::TooManyRequests => http::StatusCode::TOO_MANY_REQUESTS,
@@ -58,6 +58,7 @@ generate_getters! {
     DeleteShardsResponse,
     DeleteSourceRequest,
     DeleteSplitsRequest,
+    GetSourceCheckpointRequest,
     LastDeleteOpstampRequest,
     ListDeleteTasksRequest,
     ListShardsSubrequest,
@@ -70,6 +71,7 @@ generate_getters! {
     StageSplitsRequest,
     ToggleSourceRequest,
     UpdateIndexRequest,
+    UpdateSourceCheckpointRequest,
     UpdateSplitsDeleteOpstampRequest
 }
 
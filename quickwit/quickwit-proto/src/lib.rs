@@ -24,15 +24,14 @@
 use std::cmp::Ordering;
 
 use ::opentelemetry::global;
-use ::opentelemetry::propagation::{Extractor, Injector};
-use tonic::service::Interceptor;
-use tonic::Status;
+use ::opentelemetry::propagation::Extractor;
 use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 pub mod cluster;
 pub mod control_plane;
-pub use {bytes, tonic};
+pub use bytes;
+pub use tonic;
 pub mod developer;
 pub mod error;
 mod getters;
@@ -43,9 +42,15 @@ pub mod search;
 pub mod types;
 
 pub use error::{GrpcServiceError, ServiceError, ServiceErrorCode};
+pub use quickwit_common::tower::SpanContextInterceptor;
 
 use crate::search::ReportSplitsRequest;
 
+/// Encoded file descriptor set of the gRPC services exposed by Quickwit nodes, served by the gRPC
+/// reflection service.
+pub const GRPC_FILE_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin"));
+
 pub mod jaeger {
     pub mod api_v2 {
         include!("codegen/jaeger/jaeger.api_v2.rs");
@@ -142,56 +147,6 @@ impl TryFrom<metastore::DeleteQuery> for search::SearchRequest {
     }
 }
 
-/// `MutMetadataMap` used to extract [`tonic::metadata::MetadataMap`] from a request.
-pub struct MutMetadataMap<'a>(&'a mut tonic::metadata::MetadataMap);
-
-impl<'a> Injector for MutMetadataMap<'a> {
-    /// Sets a key-value pair in the [`MetadataMap`]. No-op if the key or value is invalid.
-    fn set(&mut self, key: &str, value: String) {
-        if let Ok(metadata_key) = tonic::metadata::MetadataKey::from_bytes(key.as_bytes()) {
-            if let Ok(metadata_value) = tonic::metadata::MetadataValue::try_from(&value) {
-                self.0.insert(metadata_key, metadata_value);
-            }
-        }
-    }
-}
-
-impl<'a> Extractor for MutMetadataMap<'a> {
-    /// Gets a value for a key from the MetadataMap.  If the value can't be converted to &str,
-    /// returns None.
-    fn get(&self, key: &str) -> Option<&str> {
-        self.0.get(key).and_then(|metadata| metadata.to_str().ok())
-    }
-
-    /// Collect all the keys from the MetadataMap.
-    fn keys(&self) -> Vec<&str> {
-        self.0
-            .keys()
-            .map(|key| match key {
-                tonic::metadata::KeyRef::Ascii(v) => v.as_str(),
-                tonic::metadata::KeyRef::Binary(v) => v.as_str(),
-            })
-            .collect::<Vec<_>>()
-    }
-}
-
-/// [`tonic::service::interceptor::Interceptor`] which injects the span context into
-/// [`tonic::metadata::MetadataMap`].
-#[derive(Clone, Debug)]
-pub struct SpanContextInterceptor;
-
-impl Interceptor for SpanContextInterceptor {
-    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
-        global::get_text_map_propagator(|propagator| {
-            propagator.inject_context(
-                &tracing::Span::current().context(),
-                &mut MutMetadataMap(request.metadata_mut()),
-            )
-        });
-        Ok(request)
-    }
-}
-
 /// `MetadataMap` extracts OpenTelemetry
 /// tracing keys from request's headers.
 struct MetadataMap<'a>(&'a tonic::metadata::MetadataMap);
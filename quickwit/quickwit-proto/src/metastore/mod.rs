@@ -80,6 +80,11 @@ pub enum EntityKind {
         /// Index template ID.
         template_id: String,
     },
+    /// An index alias.
+    IndexAlias {
+        /// Index alias ID.
+        alias_id: String,
+    },
 }
 
 impl fmt::Display for EntityKind {
@@ -101,6 +106,7 @@ impl fmt::Display for EntityKind {
             EntityKind::IndexTemplate { template_id } => {
                 write!(f, "index template `{}`", template_id)
             }
+            EntityKind::IndexAlias { alias_id } => write!(f, "index alias `{alias_id}`"),
         }
     }
 }
@@ -156,7 +162,7 @@ pub enum MetastoreError {
     Unavailable(String),
 }
 
-#[cfg(feature = "postgres")]
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
 impl From<sqlx::Error> for MetastoreError {
     fn from(error: sqlx::Error) -> Self {
         MetastoreError::Db {
@@ -225,6 +231,7 @@ impl MakeLoadShedError for MetastoreError {
 impl SourceType {
     pub fn as_str(&self) -> &'static str {
         match self {
+            SourceType::Backfill => "backfill",
             SourceType::Cli => "ingest-cli",
             SourceType::File => "file",
             SourceType::IngestV1 => "ingest-api",
@@ -244,6 +251,7 @@ impl SourceType {
 impl fmt::Display for SourceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let source_type_str = match self {
+            SourceType::Backfill => "backfill",
             SourceType::Cli => "CLI ingest",
             SourceType::File => "file",
             SourceType::IngestV1 => "ingest API v1",
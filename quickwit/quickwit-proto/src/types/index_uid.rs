@@ -194,7 +194,7 @@ pub struct InvalidIndexUid {
     pub invalid_index_uid_str: String,
 }
 
-#[cfg(feature = "postgres")]
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
 impl TryFrom<String> for IndexUid {
     type Error = InvalidIndexUid;
 
@@ -226,20 +226,37 @@ impl sqlx::postgres::PgHasArrayType for IndexUid {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl sqlx::Type<sqlx::Sqlite> for IndexUid {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for IndexUid {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> sqlx::encode::IsNull {
+        sqlx::Encode::<sqlx::Sqlite>::encode(self.to_string(), buf)
+    }
+}
+
 impl PartialEq<(&'static str, u128)> for IndexUid {
     fn eq(&self, (index_id, incarnation_id): &(&str, u128)) -> bool {
         self.index_id == *index_id && self.incarnation_id == Ulid::from(*incarnation_id)
     }
 }
 
-#[cfg(feature = "postgres")]
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
 impl From<IndexUid> for sea_query::Value {
     fn from(index_uid: IndexUid) -> Self {
         index_uid.to_string().into()
     }
 }
 
-#[cfg(feature = "postgres")]
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
 impl From<&IndexUid> for sea_query::Value {
     fn from(index_uid: &IndexUid) -> Self {
         index_uid.to_string().into()
@@ -160,6 +160,23 @@ impl sqlx::postgres::PgHasArrayType for ShardId {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl sqlx::Type<sqlx::Sqlite> for ShardId {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for ShardId {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> sqlx::encode::IsNull {
+        sqlx::Encode::<sqlx::Sqlite>::encode(self.as_str().to_string(), buf)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -247,6 +247,47 @@ impl BuildTantivyAst for FullTextQuery {
 }
 
 impl FullTextQuery {
+    /// Tokenizes the text of the query and returns the resulting terms along with their
+    /// positions, assuming the query is targetting a string or a Json field.
+    pub fn get_terms(
+        &self,
+        schema: &TantivySchema,
+        tokenizer_manager: &TokenizerManager,
+    ) -> Result<(Field, Vec<(usize, Term)>), InvalidQuery> {
+        let (field, field_entry, json_path) = find_field_or_hit_dynamic(&self.field, schema)?;
+        let field_type: &FieldType = field_entry.field_type();
+        match field_type {
+            FieldType::Str(text_options) => {
+                let text_field_indexing = text_options.get_indexing_options().ok_or_else(|| {
+                    InvalidQuery::SchemaError(format!(
+                        "field {} is not full-text searchable",
+                        field_entry.name()
+                    ))
+                })?;
+                let terms = self.params.tokenize_text_into_terms(
+                    field,
+                    &self.text,
+                    text_field_indexing,
+                    tokenizer_manager,
+                )?;
+                Ok((field, terms))
+            }
+            FieldType::JsonObject(json_options) => {
+                let terms = self.params.tokenize_text_into_terms_json(
+                    field,
+                    json_path,
+                    &self.text,
+                    json_options,
+                    tokenizer_manager,
+                )?;
+                Ok((field, terms))
+            }
+            _ => Err(InvalidQuery::SchemaError(
+                "trying to tokenize a full-text query on a non-text field".to_string(),
+            )),
+        }
+    }
+
     /// Returns the last term of the query assuming the query is targetting a string or a Json
     /// field.
     ///
@@ -300,7 +341,7 @@ impl FullTextQuery {
 
 #[cfg(test)]
 mod tests {
-    use tantivy::schema::{Schema, TEXT};
+    use tantivy::schema::{Schema, INDEXED, TEXT};
 
     use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
     use crate::query_ast::{BuildTantivyAst, FullTextMode, FullTextQuery};
@@ -415,4 +456,44 @@ mod tests {
         let bool_query = ast.as_bool_query().unwrap();
         assert_eq!(bool_query.must.len(), 2);
     }
+
+    #[test]
+    fn test_full_text_get_terms() {
+        let full_text_query = FullTextQuery {
+            field: "body".to_string(),
+            text: "Hello, big World!".to_string(),
+            params: super::FullTextParams {
+                tokenizer: None,
+                mode: FullTextMode::Phrase { slop: 0 },
+                zero_terms_query: crate::MatchAllOrNone::MatchNone,
+            },
+        };
+        let mut schema_builder = Schema::builder();
+        let body_field = schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_u64_field("count", INDEXED);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+
+        let (field, terms) = full_text_query
+            .get_terms(&schema, &tokenizer_manager)
+            .unwrap();
+        assert_eq!(field, body_field);
+
+        let positions_and_texts: Vec<(usize, &str)> = terms
+            .iter()
+            .map(|(position, term)| (*position, term.value().as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            positions_and_texts,
+            [(0, "hello"), (1, "big"), (2, "world")]
+        );
+
+        let full_text_query = FullTextQuery {
+            field: "count".to_string(),
+            ..full_text_query
+        };
+        full_text_query
+            .get_terms(&schema, &tokenizer_manager)
+            .unwrap_err();
+    }
 }
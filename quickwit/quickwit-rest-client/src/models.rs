@@ -20,6 +20,7 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use quickwit_serve::NdjsonIngestResponse;
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 
@@ -49,12 +50,7 @@ impl ApiResponse {
 
     async fn extract_error_message(self) -> Option<String> {
         let error_body_bytes = self.inner.bytes().await.ok()?;
-        let error_body_text = std::str::from_utf8(&error_body_bytes).ok()?;
-        if let Ok(error_payload) = serde_json::from_str::<ErrorResponsePayload>(error_body_text) {
-            Some(error_payload.message)
-        } else {
-            Some(error_body_text.to_string())
-        }
+        error_message_from_body(&error_body_bytes)
     }
 
     async fn api_error(self) -> Error {
@@ -74,6 +70,44 @@ impl ApiResponse {
             Ok(object)
         }
     }
+
+    /// Deserializes the response of an NDJSON ingest request. When all the documents of the
+    /// request are rejected, the server responds with `400 Bad Request` along with the
+    /// per-document errors. An empty body deserializes into an empty response.
+    pub async fn deserialize_ndjson_ingest_response(self) -> Result<NdjsonIngestResponse, Error> {
+        let code = self.inner.status();
+
+        if code != StatusCode::BAD_REQUEST && (code.is_client_error() || code.is_server_error()) {
+            return Err(self.api_error().await);
+        }
+        let body_bytes = self.inner.bytes().await?;
+
+        if code.is_success() {
+            if body_bytes.is_empty() {
+                return Ok(NdjsonIngestResponse::default());
+            }
+            let ingest_response = serde_json::from_slice(&body_bytes)?;
+            return Ok(ingest_response);
+        }
+        // Other bad requests carry a regular error payload, which also deserializes into an
+        // `NdjsonIngestResponse` since all its fields are optional.
+        match serde_json::from_slice::<NdjsonIngestResponse>(&body_bytes) {
+            Ok(ingest_response) if ingest_response.num_errors() > 0 => Ok(ingest_response),
+            _ => Err(Error::from(ApiError {
+                message: error_message_from_body(&body_bytes),
+                code,
+            })),
+        }
+    }
+}
+
+fn error_message_from_body(error_body_bytes: &[u8]) -> Option<String> {
+    let error_body_text = std::str::from_utf8(error_body_bytes).ok()?;
+    if let Ok(error_payload) = serde_json::from_str::<ErrorResponsePayload>(error_body_text) {
+        Some(error_payload.message)
+    } else {
+        Some(error_body_text.to_string())
+    }
 }
 
 #[derive(Clone)]
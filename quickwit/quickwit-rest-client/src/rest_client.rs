@@ -21,13 +21,18 @@ use std::time::Duration;
 
 use bytes::Bytes;
 use quickwit_cluster::ClusterSnapshot;
-use quickwit_config::{ConfigFormat, SourceConfig};
+use quickwit_config::{ConfigFormat, IndexTemplate, SourceConfig};
 use quickwit_indexing::actors::IndexingServiceCounters;
+use quickwit_indexing::DeadLetterQueuePage;
 pub use quickwit_ingest::CommitType;
 use quickwit_metastore::{IndexMetadata, Split, SplitInfo};
-use quickwit_search::SearchResponseRest;
+use quickwit_proto::metastore::IndexAlias;
+use quickwit_search::{AnalyzeQueryResponse, SearchResponseRest};
 use quickwit_serve::{
-    IndexUpdates, ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString,
+    AnalyzeQueryRequest, ClusterMemberInfo, DeadLetterQueueQueryParams, IndexAliasUpdates,
+    IndexStatsResponse, IndexUpdates, ListSplitsQueryParams, ListSplitsResponse,
+    NdjsonIngestResponse, SearchRequestQueryString, TruncateDeadLetterQueueQueryParams,
+    TruncateDeadLetterQueueResponse,
 };
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::{Client, ClientBuilder, Method, StatusCode, Url};
@@ -41,6 +46,7 @@ use crate::BatchLineReader;
 
 pub const DEFAULT_BASE_URL: &str = "http://127.0.0.1:7280";
 pub const DEFAULT_CONTENT_TYPE: &str = "application/json";
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
 pub const INGEST_CONTENT_LENGTH_LIMIT: usize = 10 * 1024 * 1024; // 10MiB
 pub const DEFAULT_CLIENT_CONNECT_TIMEOUT: Timeout = Timeout::from_secs(5);
 pub const DEFAULT_CLIENT_TIMEOUT: Timeout = Timeout::from_secs(10);
@@ -230,6 +236,31 @@ impl QuickwitClient {
         Ok(search_response)
     }
 
+    pub async fn analyze_query(
+        &self,
+        index_id: &str,
+        query: &str,
+    ) -> Result<AnalyzeQueryResponse, Error> {
+        let path = format!("{index_id}/analyze-query");
+        let analyze_query_request = AnalyzeQueryRequest {
+            query: query.to_string(),
+        };
+        let body = Bytes::from(serde_json::to_vec(&analyze_query_request).unwrap());
+        let response = self
+            .transport
+            .send::<()>(
+                Method::POST,
+                &path,
+                None,
+                None,
+                Some(body),
+                self.search_timeout,
+            )
+            .await?;
+        let analyze_query_response = response.deserialize().await?;
+        Ok(analyze_query_response)
+    }
+
     pub fn indexes(&self) -> IndexClient {
         IndexClient::new(&self.transport, self.timeout)
     }
@@ -238,10 +269,22 @@ impl QuickwitClient {
         SplitClient::new(&self.transport, self.timeout, index_id)
     }
 
+    pub fn templates(&self) -> TemplateClient {
+        TemplateClient::new(&self.transport, self.timeout)
+    }
+
+    pub fn aliases(&self) -> AliasClient {
+        AliasClient::new(&self.transport, self.timeout)
+    }
+
     pub fn sources<'a>(&'a self, index_id: &'a str) -> SourceClient {
         SourceClient::new(&self.transport, self.timeout, index_id)
     }
 
+    pub fn dead_letter_queue<'a>(&'a self, index_id: &'a str) -> DeadLetterQueueClient {
+        DeadLetterQueueClient::new(&self.transport, self.timeout, index_id)
+    }
+
     pub fn cluster(&self) -> ClusterClient {
         ClusterClient::new(&self.transport, self.timeout)
     }
@@ -261,7 +304,7 @@ impl QuickwitClient {
         batch_size_limit_opt: Option<usize>,
         mut on_ingest_event: Option<&mut (dyn FnMut(IngestEvent) + Sync)>,
         last_block_commit: CommitType,
-    ) -> Result<(), Error> {
+    ) -> Result<NdjsonIngestResponse, Error> {
         let ingest_path = if self.ingest_v2 {
             format!("{index_id}/ingest-v2")
         } else {
@@ -277,6 +320,9 @@ impl QuickwitClient {
                 BatchLineReader::from_string(ingest_payload, batch_size_limit)
             }
         };
+        let mut ingest_response = NdjsonIngestResponse::default();
+        let mut num_previous_lines = 0;
+
         while let Some(batch) = batch_reader.next_batch().await? {
             loop {
                 let (query_params, timeout) =
@@ -290,7 +336,7 @@ impl QuickwitClient {
                     .send(
                         Method::POST,
                         &ingest_path,
-                        None,
+                        Some(ndjson_header_map()),
                         query_params,
                         Some(batch.clone()),
                         timeout,
@@ -302,16 +348,23 @@ impl QuickwitClient {
                     }
                     tokio::time::sleep(Duration::from_millis(500)).await;
                 } else {
-                    response.check().await?;
+                    let batch_ingest_response =
+                        response.deserialize_ndjson_ingest_response().await?;
+                    merge_ndjson_ingest_responses(
+                        &mut ingest_response,
+                        batch_ingest_response,
+                        num_previous_lines,
+                    );
                     break;
                 }
             }
+            num_previous_lines += count_lines(&batch);
+
             if let Some(event_fn) = &mut on_ingest_event {
                 event_fn(IngestEvent::IngestedDocBatch(batch.len()))
             }
         }
-
-        Ok(())
+        Ok(ingest_response)
     }
 }
 
@@ -369,6 +422,31 @@ impl<'a> IndexClient<'a> {
         Ok(index_metadata)
     }
 
+    /// Upgrades the schema of an index to the doc mapping of the given index config.
+    pub async fn upgrade_schema(
+        &self,
+        index_id: &str,
+        index_config: impl ToString,
+        config_format: ConfigFormat,
+    ) -> Result<IndexMetadata, Error> {
+        let header_map = header_from_config_format(config_format);
+        let body = Bytes::from(index_config.to_string());
+        let path = format!("indexes/{index_id}/schema");
+        let response = self
+            .transport
+            .send::<()>(
+                Method::PUT,
+                &path,
+                Some(header_map),
+                None,
+                Some(body),
+                self.timeout,
+            )
+            .await?;
+        let index_metadata = response.deserialize().await?;
+        Ok(index_metadata)
+    }
+
     pub async fn list(&self) -> Result<Vec<IndexMetadata>, Error> {
         let response = self
             .transport
@@ -378,6 +456,28 @@ impl<'a> IndexClient<'a> {
         Ok(indexes_metadatas)
     }
 
+    /// Lists the indexes matching the given index ID patterns, where a pattern prefixed with `-`
+    /// excludes the indexes it matches.
+    pub async fn list_matching(
+        &self,
+        index_id_patterns: &[String],
+    ) -> Result<Vec<IndexMetadata>, Error> {
+        let index_id_patterns = index_id_patterns.join(",");
+        let response = self
+            .transport
+            .send(
+                Method::GET,
+                "indexes",
+                None,
+                Some(&[("index_id_patterns", index_id_patterns)]),
+                None,
+                self.timeout,
+            )
+            .await?;
+        let indexes_metadatas = response.deserialize().await?;
+        Ok(indexes_metadatas)
+    }
+
     pub async fn get(&self, index_id: &str) -> Result<IndexMetadata, Error> {
         let path = format!("indexes/{index_id}");
         let response = self
@@ -388,6 +488,39 @@ impl<'a> IndexClient<'a> {
         Ok(index_metadata)
     }
 
+    /// Freezes an index so that it rejects new documents while remaining searchable.
+    pub async fn freeze(&self, index_id: &str) -> Result<IndexMetadata, Error> {
+        let path = format!("{index_id}/freeze");
+        let response = self
+            .transport
+            .send::<()>(Method::POST, &path, None, None, None, self.timeout)
+            .await?;
+        let index_metadata = response.deserialize().await?;
+        Ok(index_metadata)
+    }
+
+    /// Returns operational statistics about an index.
+    pub async fn stats(&self, index_id: &str) -> Result<IndexStatsResponse, Error> {
+        let path = format!("{index_id}/stats");
+        let response = self
+            .transport
+            .send::<()>(Method::GET, &path, None, None, None, self.timeout)
+            .await?;
+        let index_stats = response.deserialize().await?;
+        Ok(index_stats)
+    }
+
+    /// Unfreezes an index so that it accepts new documents again.
+    pub async fn unfreeze(&self, index_id: &str) -> Result<IndexMetadata, Error> {
+        let path = format!("{index_id}/unfreeze");
+        let response = self
+            .transport
+            .send::<()>(Method::POST, &path, None, None, None, self.timeout)
+            .await?;
+        let index_metadata = response.deserialize().await?;
+        Ok(index_metadata)
+    }
+
     pub async fn clear(&self, index_id: &str) -> Result<(), Error> {
         let path = format!("indexes/{index_id}/clear");
         let response = self
@@ -416,6 +549,155 @@ impl<'a> IndexClient<'a> {
     }
 }
 
+/// Client for index templates APIs.
+pub struct TemplateClient<'a> {
+    transport: &'a Transport,
+    timeout: Timeout,
+}
+
+impl<'a> TemplateClient<'a> {
+    fn new(transport: &'a Transport, timeout: Timeout) -> Self {
+        Self { transport, timeout }
+    }
+
+    pub async fn create(
+        &self,
+        index_template: impl ToString,
+        config_format: ConfigFormat,
+    ) -> Result<IndexTemplate, Error> {
+        let header_map = header_from_config_format(config_format);
+        let body = Bytes::from(index_template.to_string());
+        let response = self
+            .transport
+            .send::<()>(
+                Method::POST,
+                "templates",
+                Some(header_map),
+                None,
+                Some(body),
+                self.timeout,
+            )
+            .await?;
+        let index_template = response.deserialize().await?;
+        Ok(index_template)
+    }
+
+    pub async fn list(&self) -> Result<Vec<IndexTemplate>, Error> {
+        let response = self
+            .transport
+            .send::<()>(Method::GET, "templates", None, None, None, self.timeout)
+            .await?;
+        let index_templates = response.deserialize().await?;
+        Ok(index_templates)
+    }
+
+    pub async fn get(&self, template_id: &str) -> Result<IndexTemplate, Error> {
+        let path = format!("templates/{template_id}");
+        let response = self
+            .transport
+            .send::<()>(Method::GET, &path, None, None, None, self.timeout)
+            .await?;
+        let index_template = response.deserialize().await?;
+        Ok(index_template)
+    }
+
+    pub async fn delete(&self, template_id: &str) -> Result<(), Error> {
+        let path = format!("templates/{template_id}");
+        let response = self
+            .transport
+            .send::<()>(Method::DELETE, &path, None, None, None, self.timeout)
+            .await?;
+        response.check().await?;
+        Ok(())
+    }
+}
+
+/// Client for index aliases APIs.
+pub struct AliasClient<'a> {
+    transport: &'a Transport,
+    timeout: Timeout,
+}
+
+impl<'a> AliasClient<'a> {
+    fn new(transport: &'a Transport, timeout: Timeout) -> Self {
+        Self { transport, timeout }
+    }
+
+    pub async fn create(
+        &self,
+        alias_id: &str,
+        index_ids: Vec<String>,
+    ) -> Result<IndexAlias, Error> {
+        let index_alias = IndexAlias {
+            alias_id: alias_id.to_string(),
+            index_ids,
+        };
+        let body = Bytes::from(serde_json::to_string(&index_alias)?);
+        let response = self
+            .transport
+            .send::<()>(
+                Method::POST,
+                "aliases",
+                None,
+                None,
+                Some(body),
+                self.timeout,
+            )
+            .await?;
+        let index_alias = response.deserialize().await?;
+        Ok(index_alias)
+    }
+
+    pub async fn list(&self) -> Result<Vec<IndexAlias>, Error> {
+        let response = self
+            .transport
+            .send::<()>(Method::GET, "aliases", None, None, None, self.timeout)
+            .await?;
+        let index_aliases = response.deserialize().await?;
+        Ok(index_aliases)
+    }
+
+    /// Resolves an alias into the IDs of the indexes it points to.
+    pub async fn get(&self, alias_id: &str) -> Result<IndexAlias, Error> {
+        let path = format!("aliases/{alias_id}");
+        let response = self
+            .transport
+            .send::<()>(Method::GET, &path, None, None, None, self.timeout)
+            .await?;
+        let index_alias = response.deserialize().await?;
+        Ok(index_alias)
+    }
+
+    pub async fn update(
+        &self,
+        alias_id: &str,
+        index_alias_updates: IndexAliasUpdates,
+    ) -> Result<IndexAlias, Error> {
+        let body = Bytes::from(serde_json::to_string(&index_alias_updates)?);
+        let path = format!("aliases/{alias_id}");
+        let response = self
+            .transport
+            .send::<()>(Method::PUT, &path, None, None, Some(body), self.timeout)
+            .await?;
+        let index_alias = response.deserialize().await?;
+        Ok(index_alias)
+    }
+
+    /// Atomically replaces the index `from_index_id` with `to_index_id` in the alias.
+    pub async fn switch(
+        &self,
+        alias_id: &str,
+        from_index_id: &str,
+        to_index_id: &str,
+    ) -> Result<IndexAlias, Error> {
+        let index_alias_updates = IndexAliasUpdates {
+            add: vec![to_index_id.to_string()],
+            remove: vec![from_index_id.to_string()],
+        };
+        self.update(alias_id, index_alias_updates).await
+    }
+}
+
 /// Client for splits APIs.
 pub struct SplitClient<'a, 'b> {
     transport: &'a Transport,
@@ -576,6 +858,66 @@ impl<'a> SourceClient<'a> {
     }
 }
 
+/// Client for dead letter queue APIs.
+pub struct DeadLetterQueueClient<'a> {
+    transport: &'a Transport,
+    timeout: Timeout,
+    index_id: &'a str,
+}
+
+impl<'a> DeadLetterQueueClient<'a> {
+    fn new(transport: &'a Transport, timeout: Timeout, index_id: &'a str) -> Self {
+        Self {
+            transport,
+            timeout,
+            index_id,
+        }
+    }
+
+    fn dead_letter_queue_url(&self) -> String {
+        format!("{}/dead-letter-queue", self.index_id)
+    }
+
+    /// Fetches a page of entries, oldest first. Pages are numbered from 1.
+    pub async fn get(&self, page: usize, page_size: usize) -> Result<DeadLetterQueuePage, Error> {
+        let path = self.dead_letter_queue_url();
+        let query_params = DeadLetterQueueQueryParams { page, page_size };
+        let response = self
+            .transport
+            .send(
+                Method::GET,
+                &path,
+                None,
+                Some(&query_params),
+                None,
+                self.timeout,
+            )
+            .await?;
+        let dead_letter_queue_page = response.deserialize().await?;
+        Ok(dead_letter_queue_page)
+    }
+
+    /// Removes up to `num_entries` of the oldest entries and returns the number of entries
+    /// actually removed.
+    pub async fn truncate(&self, num_entries: usize) -> Result<usize, Error> {
+        let path = self.dead_letter_queue_url();
+        let query_params = TruncateDeadLetterQueueQueryParams { num_entries };
+        let response = self
+            .transport
+            .send(
+                Method::DELETE,
+                &path,
+                None,
+                Some(&query_params),
+                None,
+                self.timeout,
+            )
+            .await?;
+        let truncate_response: TruncateDeadLetterQueueResponse = response.deserialize().await?;
+        Ok(truncate_response.num_removed_entries)
+    }
+}
+
 /// Client for Cluster APIs.
 pub struct ClusterClient<'a> {
     transport: &'a Transport,
@@ -595,6 +937,22 @@ impl<'a> ClusterClient<'a> {
         let cluster_snapshot = response.deserialize().await?;
         Ok(cluster_snapshot)
     }
+
+    pub async fn members(&self) -> Result<Vec<ClusterMemberInfo>, Error> {
+        let response = self
+            .transport
+            .send::<()>(
+                Method::GET,
+                "cluster/members",
+                None,
+                None,
+                None,
+                self.timeout,
+            )
+            .await?;
+        let members = response.deserialize().await?;
+        Ok(members)
+    }
 }
 
 /// Client for Node-level Stats APIs.
@@ -657,6 +1015,38 @@ impl<'a> NodeHealthClient<'a> {
     }
 }
 
+fn ndjson_header_map() -> HeaderMap {
+    let mut header_map = HeaderMap::new();
+    header_map.insert(CONTENT_TYPE, HeaderValue::from_static(NDJSON_CONTENT_TYPE));
+    header_map
+}
+
+fn count_lines(batch: &[u8]) -> u64 {
+    let num_newlines = batch.iter().filter(|byte| **byte == b'\n').count() as u64;
+
+    if batch.last().is_some_and(|byte| *byte != b'\n') {
+        return num_newlines + 1;
+    }
+    num_newlines
+}
+
+/// Merges the response of an ingest request into the response of the previous requests. Lines
+/// are numbered from the beginning of the ingested source.
+fn merge_ndjson_ingest_responses(
+    ingest_response: &mut NdjsonIngestResponse,
+    batch_ingest_response: NdjsonIngestResponse,
+    num_previous_lines: u64,
+) {
+    ingest_response.num_docs_for_processing += batch_ingest_response.num_docs_for_processing;
+    ingest_response.num_parse_errors += batch_ingest_response.num_parse_errors;
+    ingest_response.num_validation_errors += batch_ingest_response.num_validation_errors;
+
+    for mut doc_error in batch_ingest_response.errors {
+        doc_error.line += num_previous_lines;
+        ingest_response.errors.push(doc_error);
+    }
+}
+
 fn header_from_config_format(config_format: ConfigFormat) -> HeaderMap {
     let mut header_map = HeaderMap::new();
     let content_type_value = format!("application/{}", config_format.as_str());
@@ -672,10 +1062,11 @@ mod test {
     use std::path::PathBuf;
     use std::str::FromStr;
 
-    use quickwit_config::{ConfigFormat, SourceConfig};
-    use quickwit_indexing::mock_split;
+    use quickwit_config::{ConfigFormat, IndexTemplate, SourceConfig};
+    use quickwit_indexing::{mock_split, DeadLetterEntry, DeadLetterQueuePage};
     use quickwit_ingest::CommitType;
-    use quickwit_metastore::IndexMetadata;
+    use quickwit_metastore::{IndexMetadata, IndexState};
+    use quickwit_proto::metastore::IndexAlias;
     use quickwit_search::SearchResponseRest;
     use quickwit_serve::{ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString};
     use reqwest::header::CONTENT_TYPE;
@@ -688,7 +1079,7 @@ mod test {
     };
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    use crate::error::Error;
+    use crate::error::{ApiError, Error};
     use crate::models::IngestSource;
     use crate::rest_client::QuickwitClientBuilder;
 
@@ -719,6 +1110,7 @@ mod test {
             aggregations: None,
             elapsed_time_micros: 100,
             errors: Vec::new(),
+            suggestions: None,
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/search"))
@@ -737,6 +1129,32 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_analyze_query_endpoint() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+        let response_json = json!({
+            "query_ast": {"type": "match_all"},
+            "terms": [],
+            "phrases": [],
+            "wildcards": [],
+        });
+        Mock::given(method("POST"))
+            .and(path("/api/v1/my-index/analyze-query"))
+            .and(body_json(json!({"query": "*"})))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_json(response_json))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let analyze_query_response = qw_client.analyze_query("my-index", "*").await.unwrap();
+        assert_eq!(
+            analyze_query_response.query_ast,
+            json!({"type": "match_all"})
+        );
+        assert!(analyze_query_response.terms.is_empty());
+    }
+
     fn get_ndjson_filepath(ndjson_dataset_filename: &str) -> String {
         format!(
             "{}/resources/tests/{}",
@@ -838,6 +1256,82 @@ mod test {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_ingest_endpoint_reports_doc_errors() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+
+        for valid_doc in ["{\"id\": 1}\n", "{\"id\": 2}\n"] {
+            Mock::given(method("POST"))
+                .and(path("/api/v1/my-index/ingest"))
+                .and(header(CONTENT_TYPE.as_str(), "application/x-ndjson"))
+                .and(body_bytes(valid_doc))
+                .respond_with(
+                    ResponseTemplate::new(StatusCode::OK)
+                        .set_body_json(json!({"num_docs_for_processing": 1})),
+                )
+                .up_to_n_times(1)
+                .mount(&mock_server)
+                .await;
+        }
+        for invalid_doc in ["foo\n", "bar\n"] {
+            Mock::given(method("POST"))
+                .and(path("/api/v1/my-index/ingest"))
+                .and(body_bytes(invalid_doc))
+                .respond_with(
+                    ResponseTemplate::new(StatusCode::BAD_REQUEST).set_body_json(json!({
+                        "num_docs_for_processing": 0,
+                        "num_parse_errors": 1,
+                        "num_validation_errors": 0,
+                        "errors": [{
+                            "line": 1,
+                            "kind": "parse",
+                            "error": "expected value at line 1 column 1",
+                            "document": invalid_doc.trim(),
+                        }],
+                    })),
+                )
+                .up_to_n_times(1)
+                .mount(&mock_server)
+                .await;
+        }
+        // Each line is sent in its own batch.
+        let ingest_source = IngestSource::Str("{\"id\": 1}\nfoo\n{\"id\": 2}\nbar\n".to_string());
+        let ingest_response = qw_client
+            .ingest("my-index", ingest_source, Some(12), None, CommitType::Auto)
+            .await
+            .unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 2);
+        assert_eq!(ingest_response.num_parse_errors, 2);
+        assert_eq!(ingest_response.num_validation_errors, 0);
+        let error_lines: Vec<u64> = ingest_response
+            .errors
+            .iter()
+            .map(|doc_error| doc_error.line)
+            .collect();
+        assert_eq!(error_lines, vec![2, 4]);
+
+        // Regular bad requests are still reported as errors.
+        Mock::given(method("POST"))
+            .and(path("/api/v1/other-index/ingest"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::BAD_REQUEST)
+                    .set_body_json(json!({"message": "invalid commit type"})),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let ingest_source = IngestSource::Str("{\"id\": 1}\n".to_string());
+        let error = qw_client
+            .ingest("other-index", ingest_source, None, None, CommitType::Auto)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::Api(ApiError { message: Some(message), code: StatusCode::BAD_REQUEST }) if message == "invalid commit type")
+        );
+    }
+
     #[tokio::test]
     async fn test_ingest_endpoint_should_return_api_error() {
         let mock_server = MockServer::start().await;
@@ -953,6 +1447,49 @@ mod test {
             .await;
         qw_client.indexes().clear("my-index").await.unwrap_err();
 
+        // POST freeze index
+        let mut frozen_index_metadata = index_metadata.clone();
+        frozen_index_metadata.set_index_state(IndexState::Frozen);
+        Mock::given(method("POST"))
+            .and(path("/api/v1/test-index/freeze"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(frozen_index_metadata.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.indexes().freeze("test-index").await.unwrap(),
+            frozen_index_metadata
+        );
+
+        // POST unfreeze index
+        Mock::given(method("POST"))
+            .and(path("/api/v1/test-index/unfreeze"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(index_metadata.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.indexes().unfreeze("test-index").await.unwrap(),
+            index_metadata
+        );
+
+        // POST freeze index returns an error
+        Mock::given(method("POST"))
+            .and(path("/api/v1/unknown-index/freeze"))
+            .respond_with(ResponseTemplate::new(StatusCode::NOT_FOUND))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        qw_client
+            .indexes()
+            .freeze("unknown-index")
+            .await
+            .unwrap_err();
+
         // DELETE index
         Mock::given(method("DELETE"))
             .and(path("/api/v1/indexes/my-index"))
@@ -981,6 +1518,195 @@ mod test {
             .delete("my-index", true)
             .await
             .unwrap_err();
+
+        // GET indexes matching patterns
+        Mock::given(method("GET"))
+            .and(path("/api/v1/indexes"))
+            .and(query_param("index_id_patterns", "test-*,-test-foo"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(vec![index_metadata.clone()]),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let index_id_patterns = vec!["test-*".to_string(), "-test-foo".to_string()];
+        assert_eq!(
+            qw_client
+                .indexes()
+                .list_matching(&index_id_patterns)
+                .await
+                .unwrap(),
+            vec![index_metadata]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_templates_endpoints() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+        let index_template = IndexTemplate::for_test("test-template", &["test-*"], 100);
+
+        // POST create template
+        Mock::given(method("POST"))
+            .and(path("/api/v1/templates"))
+            .and(header(CONTENT_TYPE.as_str(), "application/yaml"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(index_template.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client
+                .templates()
+                .create("", ConfigFormat::Yaml)
+                .await
+                .unwrap(),
+            index_template
+        );
+
+        // GET templates
+        Mock::given(method("GET"))
+            .and(path("/api/v1/templates"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(vec![index_template.clone()]),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.templates().list().await.unwrap(),
+            vec![index_template.clone()]
+        );
+
+        // GET template
+        Mock::given(method("GET"))
+            .and(path("/api/v1/templates/test-template"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(index_template.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.templates().get("test-template").await.unwrap(),
+            index_template
+        );
+
+        // GET template returns an error
+        Mock::given(method("GET"))
+            .and(path("/api/v1/templates/unknown-template"))
+            .respond_with(ResponseTemplate::new(StatusCode::NOT_FOUND))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        qw_client
+            .templates()
+            .get("unknown-template")
+            .await
+            .unwrap_err();
+
+        // DELETE template
+        Mock::given(method("DELETE"))
+            .and(path("/api/v1/templates/test-template"))
+            .respond_with(ResponseTemplate::new(StatusCode::OK))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        qw_client.templates().delete("test-template").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_aliases_endpoints() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+        let index_alias = IndexAlias {
+            alias_id: "test-alias".to_string(),
+            index_ids: vec!["test-index-1".to_string()],
+        };
+
+        // POST create alias
+        Mock::given(method("POST"))
+            .and(path("/api/v1/aliases"))
+            .and(body_json(json!({
+                "alias_id": "test-alias",
+                "index_ids": ["test-index-1"],
+            })))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_json(index_alias.clone()))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client
+                .aliases()
+                .create("test-alias", vec!["test-index-1".to_string()])
+                .await
+                .unwrap(),
+            index_alias
+        );
+
+        // GET aliases
+        Mock::given(method("GET"))
+            .and(path("/api/v1/aliases"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(vec![index_alias.clone()]),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.aliases().list().await.unwrap(),
+            vec![index_alias.clone()]
+        );
+
+        // GET alias
+        Mock::given(method("GET"))
+            .and(path("/api/v1/aliases/test-alias"))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_json(index_alias.clone()))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client.aliases().get("test-alias").await.unwrap(),
+            index_alias
+        );
+
+        // GET alias returns an error
+        Mock::given(method("GET"))
+            .and(path("/api/v1/aliases/unknown-alias"))
+            .respond_with(ResponseTemplate::new(StatusCode::NOT_FOUND))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        qw_client.aliases().get("unknown-alias").await.unwrap_err();
+
+        // PUT switch alias
+        let switched_index_alias = IndexAlias {
+            alias_id: "test-alias".to_string(),
+            index_ids: vec!["test-index-2".to_string()],
+        };
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/aliases/test-alias"))
+            .and(body_json(json!({
+                "add": ["test-index-2"],
+                "remove": ["test-index-1"],
+            })))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(switched_index_alias.clone()),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client
+                .aliases()
+                .switch("test-alias", "test-index-1", "test-index-2")
+                .await
+                .unwrap(),
+            switched_index_alias
+        );
     }
 
     #[tokio::test]
@@ -1045,6 +1771,59 @@ mod test {
             .unwrap_err();
     }
 
+    #[tokio::test]
+    async fn test_dead_letter_queue_endpoints() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+        let dead_letter_queue_page = DeadLetterQueuePage {
+            entries: vec![DeadLetterEntry::new(
+                "invalid doc".to_string(),
+                br#"{"body": "foo"}"#,
+            )],
+            page: 2,
+            page_size: 10,
+            num_entries: 11,
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/v1/my-index/dead-letter-queue"))
+            .and(query_param("page", "2"))
+            .and(query_param("page_size", "10"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK).set_body_json(&dead_letter_queue_page),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client
+                .dead_letter_queue("my-index")
+                .get(2, 10)
+                .await
+                .unwrap(),
+            dead_letter_queue_page
+        );
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/v1/my-index/dead-letter-queue"))
+            .and(query_param("num_entries", "11"))
+            .respond_with(
+                ResponseTemplate::new(StatusCode::OK)
+                    .set_body_json(json!({"num_removed_entries": 11})),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        assert_eq!(
+            qw_client
+                .dead_letter_queue("my-index")
+                .truncate(11)
+                .await
+                .unwrap(),
+            11
+        );
+    }
+
     #[tokio::test]
     async fn test_sources_endpoints() {
         let mock_server = MockServer::start().await;
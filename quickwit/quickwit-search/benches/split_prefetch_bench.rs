@@ -0,0 +1,223 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Measures the latency of a leaf search spanning many splits stored on a high-latency storage,
+//! with and without prefetching the split footers.
+//!
+//! The split searches are simulated: each one acquires a leaf search permit, loads the footer of
+//! its split from the split footer cache or the storage, then "searches" for a fixed duration.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use quickwit_common::uri::Uri;
+use quickwit_config::SearcherConfig;
+use quickwit_proto::search::SplitIdAndFooterOffsets;
+use quickwit_search::{SearcherContext, SplitPrefetcher};
+use quickwit_storage::{
+    BulkDeleteError, OwnedBytes, PutPayload, RamStorage, SendableAsync, Storage, StorageResult,
+};
+use tokio::io::AsyncRead;
+
+const NUM_SPLITS: usize = 200;
+const FOOTER_NUM_BYTES: usize = 4096;
+const MAX_NUM_CONCURRENT_SPLIT_SEARCHES: usize = 10;
+const STORAGE_LATENCY: Duration = Duration::from_millis(20);
+const SPLIT_SEARCH_DURATION: Duration = Duration::from_millis(5);
+
+/// Storage adding a fixed latency to the reads of the underlying storage, akin to an object
+/// storage.
+struct HighLatencyStorage {
+    underlying: RamStorage,
+}
+
+impl fmt::Debug for HighLatencyStorage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HighLatencyStorage").finish()
+    }
+}
+
+#[async_trait]
+impl Storage for HighLatencyStorage {
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.underlying.check_connectivity().await
+    }
+
+    async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()> {
+        self.underlying.put(path, payload).await
+    }
+
+    async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
+        tokio::time::sleep(STORAGE_LATENCY).await;
+        self.underlying.copy_to(path, output).await
+    }
+
+    async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
+        tokio::time::sleep(STORAGE_LATENCY).await;
+        self.underlying.get_slice(path, range).await
+    }
+
+    async fn get_slice_stream(
+        &self,
+        path: &Path,
+        range: Range<usize>,
+    ) -> StorageResult<Box<dyn AsyncRead + Send + Unpin>> {
+        tokio::time::sleep(STORAGE_LATENCY).await;
+        self.underlying.get_slice_stream(path, range).await
+    }
+
+    async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
+        tokio::time::sleep(STORAGE_LATENCY).await;
+        self.underlying.get_all(path).await
+    }
+
+    async fn delete(&self, path: &Path) -> StorageResult<()> {
+        self.underlying.delete(path).await
+    }
+
+    async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
+        self.underlying.bulk_delete(paths).await
+    }
+
+    async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
+        self.underlying.file_num_bytes(path).await
+    }
+
+    fn uri(&self) -> &Uri {
+        self.underlying.uri()
+    }
+}
+
+async fn make_splits(storage: &dyn Storage) -> Vec<SplitIdAndFooterOffsets> {
+    let mut splits = Vec::with_capacity(NUM_SPLITS);
+
+    for split_ord in 0..NUM_SPLITS {
+        let split_id = format!("split-{split_ord}");
+        let split_path = quickwit_common::split_file_path(&split_id);
+        storage
+            .put(&split_path, Box::new(vec![0u8; FOOTER_NUM_BYTES]))
+            .await
+            .unwrap();
+        splits.push(SplitIdAndFooterOffsets {
+            split_id,
+            split_footer_start: 0,
+            split_footer_end: FOOTER_NUM_BYTES as u64,
+            timestamp_start: None,
+            timestamp_end: None,
+        });
+    }
+    splits
+}
+
+async fn simulated_leaf_search(
+    searcher_context: Arc<SearcherContext>,
+    storage: Arc<dyn Storage>,
+    splits: Vec<SplitIdAndFooterOffsets>,
+    prefetch: bool,
+) {
+    let _prefetch_handle_opt = if prefetch {
+        SplitPrefetcher::new(searcher_context.clone(), storage.clone())
+            .spawn_prefetch(splits[MAX_NUM_CONCURRENT_SPLIT_SEARCHES..].to_vec())
+    } else {
+        None
+    };
+    let mut split_search_tasks = Vec::with_capacity(splits.len());
+
+    for split in splits {
+        let permit = searcher_context
+            .leaf_search_split_semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        let searcher_context = searcher_context.clone();
+        let storage = storage.clone();
+
+        split_search_tasks.push(tokio::spawn(async move {
+            if searcher_context
+                .split_footer_cache
+                .get(&split.split_id)
+                .is_none()
+            {
+                let split_path = quickwit_common::split_file_path(&split.split_id);
+                let footer = storage
+                    .get_slice(
+                        &split_path,
+                        split.split_footer_start as usize..split.split_footer_end as usize,
+                    )
+                    .await
+                    .unwrap();
+                searcher_context
+                    .split_footer_cache
+                    .put(split.split_id.clone(), footer);
+            }
+            tokio::time::sleep(SPLIT_SEARCH_DURATION).await;
+            drop(permit);
+        }));
+    }
+    futures::future::try_join_all(split_search_tasks)
+        .await
+        .unwrap();
+}
+
+fn split_prefetch_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let storage: Arc<dyn Storage> = Arc::new(HighLatencyStorage {
+        underlying: RamStorage::default(),
+    });
+    let splits = runtime.block_on(make_splits(&*storage));
+
+    let mut group = c.benchmark_group("leaf_search_200_splits");
+    group.sample_size(10);
+
+    for prefetch in [false, true] {
+        group.bench_with_input(
+            BenchmarkId::new("prefetch", prefetch),
+            &prefetch,
+            |b, &prefetch| {
+                b.to_async(&runtime).iter(|| {
+                    // A new context has an empty split footer cache.
+                    let searcher_config = SearcherConfig {
+                        max_num_concurrent_split_searches: MAX_NUM_CONCURRENT_SPLIT_SEARCHES,
+                        ..Default::default()
+                    };
+                    let searcher_context = Arc::new(SearcherContext::new(searcher_config, None));
+                    simulated_leaf_search(
+                        searcher_context,
+                        storage.clone(),
+                        splits.clone(),
+                        prefetch,
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, split_prefetch_benchmark);
+criterion_main!(benches);
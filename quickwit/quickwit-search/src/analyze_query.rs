@@ -0,0 +1,443 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use quickwit_config::build_doc_mapper;
+use quickwit_doc_mapper::DocMapper;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
+use quickwit_proto::search::ListTermsRequest;
+use quickwit_query::query_ast::{
+    query_ast_from_user_text, FullTextMode, FullTextQuery, PhrasePrefixQuery, QueryAst,
+    QueryAstVisitor, WildcardQuery,
+};
+use quickwit_query::tokenizers::TokenizerManager;
+use quickwit_query::InvalidQuery;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tantivy::schema::Schema;
+use tantivy::Term;
+use tracing::instrument;
+
+use crate::list_terms::root_list_terms;
+use crate::{ClusterClient, SearchError};
+
+/// Maximum number of terms counted when expanding a wildcard query.
+const MAX_COUNTED_WILDCARD_EXPANSIONS: u64 = 10_000;
+
+/// Describes how a query is parsed and which terms it looks up, without executing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AnalyzeQueryResponse {
+    /// Query AST obtained by parsing the query against the default search fields of the index.
+    #[schema(value_type = Object)]
+    pub query_ast: JsonValue,
+    /// Terms searched in the inverted index, sorted by field and value.
+    pub terms: Vec<AnalyzedTerm>,
+    /// Tokens of the phrase queries along with their positions.
+    pub phrases: Vec<AnalyzedPhrase>,
+    /// Wildcard queries along with the number of distinct terms they expand to.
+    pub wildcards: Vec<AnalyzedWildcard>,
+}
+
+/// A term searched in the inverted index.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+pub struct AnalyzedTerm {
+    pub field: String,
+    pub value: String,
+    /// Whether the positions of the term are needed to evaluate the query.
+    pub need_positions: bool,
+}
+
+/// The tokens emitted for a phrase query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AnalyzedPhrase {
+    pub field: String,
+    pub phrase: String,
+    pub tokens: Vec<AnalyzedToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AnalyzedToken {
+    pub position: usize,
+    pub value: String,
+}
+
+/// A wildcard query and the number of distinct terms of the index matching its prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AnalyzedWildcard {
+    pub field: String,
+    pub pattern: String,
+    /// Normalized prefix of the wildcard query.
+    pub prefix: String,
+    /// Number of distinct terms matching the prefix, capped at 10,000.
+    pub num_expanded_terms: u64,
+    #[serde(skip)]
+    prefix_key: Vec<u8>,
+}
+
+/// Parses a user query against the doc mapping of an index and lists the terms it searches.
+/// The number of terms matching each wildcard query is counted by listing the terms of the
+/// published splits of the index.
+///
+/// This is a read-only operation.
+#[instrument(skip(metastore, cluster_client))]
+pub async fn root_analyze_query(
+    index_id: &str,
+    query: &str,
+    mut metastore: MetastoreServiceClient,
+    cluster_client: &ClusterClient,
+) -> crate::Result<AnalyzeQueryResponse> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let index_config = index_metadata.into_index_config();
+
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(|err| {
+            SearchError::Internal(format!("failed to build doc mapper. cause: {err}"))
+        })?;
+    let mut analyze_query_response = analyze_query(&*doc_mapper, query)?;
+
+    for wildcard in &mut analyze_query_response.wildcards {
+        let list_terms_request = ListTermsRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            field: wildcard.field.clone(),
+            start_timestamp: None,
+            end_timestamp: None,
+            max_hits: Some(MAX_COUNTED_WILDCARD_EXPANSIONS),
+            start_key: Some(wildcard.prefix_key.clone()),
+            end_key: prefix_end_key(&wildcard.prefix_key),
+        };
+        let list_terms_response =
+            root_list_terms(&list_terms_request, metastore.clone(), cluster_client).await?;
+        wildcard.num_expanded_terms = list_terms_response.num_hits;
+    }
+    Ok(analyze_query_response)
+}
+
+/// Parses a user query against the doc mapper and lists the terms, phrases, and wildcards of the
+/// resulting query. The number of expanded terms of the wildcards is left to zero.
+fn analyze_query(doc_mapper: &dyn DocMapper, query: &str) -> crate::Result<AnalyzeQueryResponse> {
+    let query_ast = query_ast_from_user_text(query, None)
+        .parse_user_query(doc_mapper.default_search_fields())
+        // We convert the error to return a 400 to the user (and not a 500).
+        .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+
+    let schema = doc_mapper.schema();
+    let (tantivy_query, _warmup_info) = doc_mapper.query(schema.clone(), &query_ast, true)?;
+
+    let mut terms = Vec::new();
+    tantivy_query.query_terms(&mut |term, need_positions| {
+        terms.push(AnalyzedTerm {
+            field: schema.get_field_name(term.field()).to_string(),
+            value: term_value_to_string(term),
+            need_positions,
+        });
+    });
+    terms.sort();
+    terms.dedup();
+
+    let mut visitor = AnalyzeQueryVisitor {
+        schema: &schema,
+        tokenizer_manager: doc_mapper.tokenizer_manager(),
+        phrases: Vec::new(),
+        wildcards: Vec::new(),
+    };
+    visitor
+        .visit(&query_ast)
+        .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+
+    Ok(AnalyzeQueryResponse {
+        query_ast: serde_json::to_value(&query_ast)?,
+        terms,
+        phrases: visitor.phrases,
+        wildcards: visitor.wildcards,
+    })
+}
+
+struct AnalyzeQueryVisitor<'a> {
+    schema: &'a Schema,
+    tokenizer_manager: &'a TokenizerManager,
+    phrases: Vec<AnalyzedPhrase>,
+    wildcards: Vec<AnalyzedWildcard>,
+}
+
+impl AnalyzeQueryVisitor<'_> {
+    fn add_phrase(&mut self, field: &str, phrase: &str, terms: Vec<(usize, Term)>) {
+        let tokens = terms
+            .into_iter()
+            .map(|(position, term)| AnalyzedToken {
+                position,
+                value: term_value_to_string(&term),
+            })
+            .collect();
+        self.phrases.push(AnalyzedPhrase {
+            field: field.to_string(),
+            phrase: phrase.to_string(),
+            tokens,
+        });
+    }
+}
+
+impl<'a> QueryAstVisitor<'a> for AnalyzeQueryVisitor<'_> {
+    type Err = InvalidQuery;
+
+    fn visit_full_text(&mut self, full_text_query: &'a FullTextQuery) -> Result<(), Self::Err> {
+        if matches!(
+            full_text_query.params.mode,
+            FullTextMode::Phrase { .. } | FullTextMode::PhraseFallbackToIntersection
+        ) {
+            let (_, terms) = full_text_query.get_terms(self.schema, self.tokenizer_manager)?;
+            // Unquoted single words are parsed as phrases: we only report actual phrases.
+            if terms.len() > 1 {
+                self.add_phrase(&full_text_query.field, &full_text_query.text, terms);
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_phrase_prefix(
+        &mut self,
+        phrase_prefix: &'a PhrasePrefixQuery,
+    ) -> Result<(), Self::Err> {
+        let (_, terms) = phrase_prefix.get_terms(self.schema, self.tokenizer_manager)?;
+        self.add_phrase(&phrase_prefix.field, &phrase_prefix.phrase, terms);
+        Ok(())
+    }
+
+    fn visit_wildcard(&mut self, wildcard_query: &'a WildcardQuery) -> Result<(), Self::Err> {
+        let (field, prefix_term) =
+            wildcard_query.extract_prefix_term(self.schema, self.tokenizer_manager)?;
+        self.wildcards.push(AnalyzedWildcard {
+            field: self.schema.get_field_name(field).to_string(),
+            pattern: wildcard_query.value.clone(),
+            prefix: term_value_to_string(&prefix_term),
+            num_expanded_terms: 0,
+            prefix_key: prefix_term.serialized_value_bytes().to_vec(),
+        });
+        Ok(())
+    }
+}
+
+fn term_value_to_string(term: &Term) -> String {
+    let value = term.value();
+
+    if let Some(text) = value.as_str() {
+        return text.to_string();
+    }
+    format!("{value:?}")
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, or `None` if there
+/// is no such key.
+fn prefix_end_key(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end_key = prefix.to_vec();
+
+    while let Some(last_byte) = end_key.pop() {
+        if last_byte != u8::MAX {
+            end_key.push(last_byte + 1);
+            return Some(end_key);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_doc_mapper::DefaultDocMapper;
+    use quickwit_indexing::TestSandbox;
+    use serde_json::json;
+
+    use super::*;
+    use crate::SingleNodeSearcher;
+
+    fn doc_mapper_for_test() -> DefaultDocMapper {
+        let doc_mapper_json = r#"{
+            "default_search_fields": ["body"],
+            "field_mappings": [
+                {"name": "title", "type": "text", "tokenizer": "raw"},
+                {"name": "body", "type": "text", "record": "position"},
+                {"name": "body_no_positions", "type": "text", "record": "basic"},
+                {"name": "count", "type": "u64"},
+                {"name": "attributes", "type": "json"}
+            ]
+        }"#;
+        serde_json::from_str(doc_mapper_json).unwrap()
+    }
+
+    fn analyzed_term(field: &str, value: &str, need_positions: bool) -> AnalyzedTerm {
+        AnalyzedTerm {
+            field: field.to_string(),
+            value: value.to_string(),
+            need_positions,
+        }
+    }
+
+    #[test]
+    fn test_analyze_query_boolean_operators() {
+        let doc_mapper = doc_mapper_for_test();
+        let response = analyze_query(&doc_mapper, "Foo AND bar OR baz").unwrap();
+        assert_eq!(
+            response.terms,
+            [
+                analyzed_term("body", "bar", false),
+                analyzed_term("body", "baz", false),
+                analyzed_term("body", "foo", false),
+            ]
+        );
+        assert!(response.phrases.is_empty());
+        assert!(response.wildcards.is_empty());
+        assert_eq!(response.query_ast["type"], "bool");
+    }
+
+    #[test]
+    fn test_analyze_query_field_and_tokenizer() {
+        let doc_mapper = doc_mapper_for_test();
+        let response = analyze_query(&doc_mapper, "title:Hello -body:World").unwrap();
+        assert_eq!(
+            response.terms,
+            [
+                analyzed_term("body", "world", false),
+                analyzed_term("title", "Hello", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_query_phrase() {
+        let doc_mapper = doc_mapper_for_test();
+        let response = analyze_query(&doc_mapper, r#"body:"Hello, big world""#).unwrap();
+        assert_eq!(
+            response.terms,
+            [
+                analyzed_term("body", "big", true),
+                analyzed_term("body", "hello", true),
+                analyzed_term("body", "world", true),
+            ]
+        );
+        assert_eq!(response.phrases.len(), 1);
+
+        let phrase = &response.phrases[0];
+        assert_eq!(phrase.field, "body");
+        assert_eq!(phrase.phrase, "Hello, big world");
+
+        let positions_and_values: Vec<(usize, &str)> = phrase
+            .tokens
+            .iter()
+            .map(|token| (token.position, token.value.as_str()))
+            .collect();
+        assert_eq!(
+            positions_and_values,
+            [(0, "hello"), (1, "big"), (2, "world")]
+        );
+    }
+
+    #[test]
+    fn test_analyze_query_phrase_without_positions() {
+        let doc_mapper = doc_mapper_for_test();
+        let error = analyze_query(&doc_mapper, r#"body_no_positions:"hello world""#).unwrap_err();
+        assert!(matches!(error, SearchError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_analyze_query_wildcard() {
+        let doc_mapper = doc_mapper_for_test();
+        let response = analyze_query(&doc_mapper, "body:Qui* OR attributes.host:web*").unwrap();
+        assert!(response.terms.is_empty());
+        assert_eq!(response.wildcards.len(), 2);
+
+        let wildcard = &response.wildcards[0];
+        assert_eq!(wildcard.field, "body");
+        assert_eq!(wildcard.pattern, "Qui*");
+        assert_eq!(wildcard.prefix, "qui");
+        assert_eq!(wildcard.prefix_key, b"qui");
+
+        let wildcard = &response.wildcards[1];
+        assert_eq!(wildcard.field, "attributes");
+        assert_eq!(wildcard.pattern, "web*");
+    }
+
+    #[test]
+    fn test_analyze_query_invalid_queries() {
+        let doc_mapper = doc_mapper_for_test();
+
+        let error = analyze_query(&doc_mapper, "foo AND (bar").unwrap_err();
+        assert!(matches!(error, SearchError::InvalidQuery(_)));
+
+        let error = analyze_query(&doc_mapper, "unknown_field:foo").unwrap_err();
+        assert!(matches!(error, SearchError::InvalidQuery(_)));
+
+        let error = analyze_query(&doc_mapper, "count:foo").unwrap_err();
+        assert!(matches!(error, SearchError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_analyze_query_match_all() {
+        let doc_mapper = doc_mapper_for_test();
+        let response = analyze_query(&doc_mapper, "*").unwrap();
+        assert!(response.terms.is_empty());
+        assert_eq!(response.query_ast, json!({"type": "match_all"}));
+    }
+
+    #[test]
+    fn test_prefix_end_key() {
+        assert_eq!(prefix_end_key(b"abc"), Some(b"abd".to_vec()));
+        assert_eq!(prefix_end_key(&[b'a', u8::MAX]), Some(b"b".to_vec()));
+        assert_eq!(prefix_end_key(&[u8::MAX, u8::MAX]), None);
+        assert_eq!(prefix_end_key(b""), None);
+    }
+
+    #[tokio::test]
+    async fn test_root_analyze_query_counts_wildcard_expansions() {
+        let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+        "#;
+        let index_id = "analyze-query";
+        let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"])
+            .await
+            .unwrap();
+        let docs = vec![
+            json!({"body": "quick quickwit quiet"}),
+            json!({"body": "quickly quiz"}),
+        ];
+        test_sandbox.add_documents(docs).await.unwrap();
+
+        let single_node_searcher =
+            SingleNodeSearcher::new(test_sandbox.metastore(), test_sandbox.storage_resolver());
+        let response = single_node_searcher
+            .analyze_query(index_id, "quick* OR qui*")
+            .await
+            .unwrap();
+        assert_eq!(response.wildcards.len(), 2);
+        assert_eq!(response.wildcards[0].num_expanded_terms, 3);
+        assert_eq!(response.wildcards[1].num_expanded_terms, 5);
+
+        let error = single_node_searcher
+            .analyze_query("does-not-exist", "foo")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SearchError::IndexesNotFound { .. }));
+
+        test_sandbox.assert_quit().await;
+    }
+}
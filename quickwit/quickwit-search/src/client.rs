@@ -25,13 +25,14 @@ use std::time::Duration;
 use bytesize::ByteSize;
 use futures::{StreamExt, TryStreamExt};
 use http::Uri;
+use quickwit_common::tower::GrpcClientInterceptor;
 use quickwit_proto::search::{
     GetKvRequest, LeafSearchStreamResponse, PutKvRequest, ReportSplitsRequest,
 };
+use quickwit_proto::tonic;
 use quickwit_proto::tonic::codegen::InterceptedService;
 use quickwit_proto::tonic::transport::{Channel, Endpoint};
 use quickwit_proto::tonic::Request;
-use quickwit_proto::{tonic, SpanContextInterceptor};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tower::timeout::Timeout;
 use tracing::{info_span, warn, Instrument};
@@ -45,7 +46,7 @@ enum SearchServiceClientImpl {
     Local(Arc<dyn SearchService>),
     Grpc(
         quickwit_proto::search::search_service_client::SearchServiceClient<
-            InterceptedService<Timeout<Channel>, SpanContextInterceptor>,
+            InterceptedService<Timeout<Channel>, GrpcClientInterceptor>,
         >,
     ),
 }
@@ -76,7 +77,7 @@ impl SearchServiceClient {
     /// Create a search service client instance given a gRPC client and gRPC address.
     pub fn from_grpc_client(
         client: quickwit_proto::search::search_service_client::SearchServiceClient<
-            InterceptedService<Timeout<Channel>, SpanContextInterceptor>,
+            InterceptedService<Timeout<Channel>, GrpcClientInterceptor>,
         >,
         grpc_addr: SocketAddr,
     ) -> Self {
@@ -317,6 +318,7 @@ impl SearchServiceClient {
 pub fn create_search_client_from_grpc_addr(
     grpc_addr: SocketAddr,
     max_message_size: ByteSize,
+    grpc_client_interceptor: GrpcClientInterceptor,
 ) -> SearchServiceClient {
     let uri = Uri::builder()
         .scheme("http")
@@ -326,7 +328,12 @@ pub fn create_search_client_from_grpc_addr(
         .expect("The URI should be well-formed.");
     let channel = Endpoint::from(uri).connect_lazy();
     let timeout_channel = Timeout::new(channel, Duration::from_secs(5));
-    create_search_client_from_channel(grpc_addr, timeout_channel, max_message_size)
+    create_search_client_from_channel(
+        grpc_addr,
+        timeout_channel,
+        max_message_size,
+        grpc_client_interceptor,
+    )
 }
 
 /// Creates a [`SearchServiceClient`] from a pre-established connection (channel).
@@ -334,11 +341,12 @@ pub fn create_search_client_from_channel(
     grpc_addr: SocketAddr,
     channel: Timeout<Channel>,
     max_message_size: ByteSize,
+    grpc_client_interceptor: GrpcClientInterceptor,
 ) -> SearchServiceClient {
     let client =
         quickwit_proto::search::search_service_client::SearchServiceClient::with_interceptor(
             channel,
-            SpanContextInterceptor,
+            grpc_client_interceptor,
         )
         .max_decoding_message_size(max_message_size.0 as usize)
         .max_encoding_message_size(max_message_size.0 as usize);
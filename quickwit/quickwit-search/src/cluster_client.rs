@@ -36,6 +36,7 @@ use tracing::{debug, error, info, warn};
 use crate::retry::search::LeafSearchRetryPolicy;
 use crate::retry::search_stream::{LeafSearchStreamRetryPolicy, SuccessfulSplitIds};
 use crate::retry::{retry_client, DefaultRetryPolicy, RetryPolicy};
+use crate::suggestion::merge_term_suggestions;
 use crate::{SearchError, SearchJobPlacer, SearchServiceClient};
 
 /// Maximum number of put requests emitted to perform a replicated given PUT KV.
@@ -93,6 +94,11 @@ impl ClusterClient {
         mut client: SearchServiceClient,
     ) -> crate::Result<LeafSearchResponse> {
         let mut response_res = client.leaf_search(request.clone()).await;
+        let search_router = self.search_job_placer.search_router();
+
+        if let Ok(response) = &response_res {
+            search_router.record_leaf_search(&request, response, client.grpc_addr());
+        }
         let retry_policy = LeafSearchRetryPolicy {};
         if let Some(retry_request) = retry_policy.retry_request(request, &response_res) {
             assert!(!retry_request.leaf_requests.is_empty());
@@ -106,7 +112,15 @@ impl ClusterClient {
                 "Leaf search response error: `{:?}`. Retry once to execute {:?} with {:?}",
                 response_res, retry_request, client
             );
-            let retry_result = client.leaf_search(retry_request).await;
+            let retry_result = client.leaf_search(retry_request.clone()).await;
+
+            if let Ok(retry_response) = &retry_result {
+                search_router.record_leaf_search(
+                    &retry_request,
+                    retry_response,
+                    client.grpc_addr(),
+                );
+            }
             response_res = merge_leaf_search_results(response_res, retry_result);
         }
         response_res
@@ -301,6 +315,12 @@ fn merge_leaf_search_response(
             + right_response.num_attempted_splits,
         failed_splits: right_response.failed_splits,
         partial_hits: left_response.partial_hits,
+        suggestions: merge_term_suggestions(
+            left_response
+                .suggestions
+                .into_iter()
+                .chain(right_response.suggestions),
+        ),
     })
 }
 
@@ -25,8 +25,8 @@ use itertools::Itertools;
 use quickwit_common::binary_heap::{SortKeyMapper, TopK};
 use quickwit_doc_mapper::WarmupInfo;
 use quickwit_proto::search::{
-    LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortOrder, SortValue,
-    SplitSearchError,
+    Hit, LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortOrder, SortValue,
+    SplitSearchError, TermSuggestion,
 };
 use quickwit_proto::types::SplitId;
 use serde::Deserialize;
@@ -39,6 +39,7 @@ use tantivy::fastfield::Column;
 use tantivy::{DateTime, DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
 
 use crate::find_trace_ids_collector::{FindTraceIdsCollector, FindTraceIdsSegmentCollector, Span};
+use crate::suggestion::merge_term_suggestions;
 use crate::top_k_collector::{specialized_top_k_segment_collector, QuickwitSegmentTopKCollector};
 use crate::GlobalDocAddress;
 
@@ -592,6 +593,7 @@ impl SegmentCollector for QuickwitSegmentCollector {
             partial_hits,
             failed_splits: Vec::new(),
             num_attempted_splits: 1,
+            suggestions: Vec::new(),
         })
     }
 }
@@ -926,6 +928,11 @@ fn merge_leaf_responses(
         .flat_map(|leaf_response| leaf_response.failed_splits.iter())
         .cloned()
         .collect_vec();
+    let suggestions = merge_term_suggestions(
+        leaf_responses
+            .iter_mut()
+            .flat_map(|leaf_response| std::mem::take(&mut leaf_response.suggestions)),
+    );
     let all_partial_hits: Vec<PartialHit> = leaf_responses
         .into_iter()
         .flat_map(|leaf_response| leaf_response.partial_hits)
@@ -942,6 +949,7 @@ fn merge_leaf_responses(
         partial_hits: top_k_partial_hits,
         failed_splits,
         num_attempted_splits,
+        suggestions,
     })
 }
 
@@ -963,6 +971,25 @@ fn top_k_partial_hits(
     top_k_hits.finalize()
 }
 
+/// Merges the hits of several search responses into the `max_hits` best hits following the first
+/// `start_offset` ones, sorted as specified by `search_request`.
+pub(crate) fn merge_hits(
+    search_request: &SearchRequest,
+    hits: impl Iterator<Item = Hit>,
+) -> Vec<Hit> {
+    let (order1, order2) = sort_by_from_request(search_request).sort_orders();
+    let sort_key_mapper = HitSortingMapper { order1, order2 };
+    let start_offset = search_request.start_offset as usize;
+    let num_hits = start_offset + search_request.max_hits as usize;
+    let mut top_k_hits = TopK::new(num_hits, sort_key_mapper);
+
+    top_k_hits.add_entries(hits);
+
+    let mut hits = top_k_hits.finalize();
+    hits.drain(0..start_offset.min(hits.len()));
+    hits
+}
+
 pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair {
     let to_sort_by_component = |field_name: &str, order| {
         if field_name == "_score" {
@@ -1142,6 +1169,16 @@ impl SortKeyMapper<PartialHit> for HitSortingMapper {
     }
 }
 
+impl SortKeyMapper<Hit> for HitSortingMapper {
+    type Key = PartialHitSortingKey;
+    fn get_sort_key(&self, hit: &Hit) -> PartialHitSortingKey {
+        match &hit.partial_hit {
+            Some(partial_hit) => self.get_sort_key(partial_hit),
+            None => self.get_sort_key(&PartialHit::default()),
+        }
+    }
+}
+
 impl SortKeyMapper<SegmentPartialHit> for HitSortingMapper {
     type Key = SegmentPartialHitSortingKey;
     fn get_sort_key(&self, partial_hit: &SegmentPartialHit) -> SegmentPartialHitSortingKey {
@@ -1163,6 +1200,7 @@ pub(crate) struct IncrementalCollector {
     num_hits: u64,
     failed_splits: Vec<SplitSearchError>,
     num_attempted_splits: u64,
+    suggestions: Vec<TermSuggestion>,
     start_offset: usize,
 }
 
@@ -1183,6 +1221,7 @@ impl IncrementalCollector {
             num_hits: 0,
             failed_splits: Vec::new(),
             num_attempted_splits: 0,
+            suggestions: Vec::new(),
         }
     }
 
@@ -1194,12 +1233,14 @@ impl IncrementalCollector {
             failed_splits,
             num_attempted_splits,
             intermediate_aggregation_result,
+            suggestions,
         } = leaf_response;
 
         self.num_hits += num_hits;
         self.top_k_hits.add_entries(partial_hits.into_iter());
         self.failed_splits.extend(failed_splits);
         self.num_attempted_splits += num_attempted_splits;
+        self.suggestions.extend(suggestions);
         if let Some(intermediate_aggregation_result) = intermediate_aggregation_result {
             self.incremental_aggregation
                 .add(intermediate_aggregation_result)?;
@@ -1243,6 +1284,7 @@ impl IncrementalCollector {
             failed_splits: self.failed_splits,
             num_attempted_splits: self.num_attempted_splits,
             intermediate_aggregation_result,
+            suggestions: merge_term_suggestions(self.suggestions),
         })
     }
 }
@@ -1800,6 +1842,7 @@ mod tests {
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
                 intermediate_aggregation_result: None,
+                suggestions: Vec::new(),
             }],
         );
 
@@ -1816,7 +1859,8 @@ mod tests {
                 }],
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new()
             }
         );
 
@@ -1854,6 +1898,7 @@ mod tests {
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1871,6 +1916,7 @@ mod tests {
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
                 },
             ],
         );
@@ -1901,7 +1947,8 @@ mod tests {
                     retryable_error: true,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new()
             }
         );
 
@@ -1940,6 +1987,7 @@ mod tests {
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1957,6 +2005,7 @@ mod tests {
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
                 },
             ],
         );
@@ -1987,7 +2036,8 @@ mod tests {
                     retryable_error: true,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new()
             }
         );
         // TODO would be nice to test aggregation too.
@@ -0,0 +1,450 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::try_join;
+use quickwit_config::build_doc_mapper;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
+use quickwit_proto::search::SearchRequest;
+use quickwit_proto::types::IndexId;
+use quickwit_query::find_field_or_hit_dynamic;
+use quickwit_query::query_ast::{FieldPresenceQuery, QueryAst};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tantivy::schema::FieldType;
+use tracing::instrument;
+use ttl_cache::TtlCache;
+
+use crate::service::SearcherContext;
+use crate::{root_search, ClusterClient, SearchError};
+
+/// Number of distinct values above which a field is considered to have a high cardinality.
+const HIGH_CARDINALITY_THRESHOLD: usize = 1_000;
+
+/// Number of values reported in the histogram of a high cardinality field.
+const NUM_TOP_VALUES_HIGH_CARDINALITY: usize = 20;
+
+/// Field stats are costly to compute, so we cache them for a few minutes.
+const FIELD_STATS_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Maximum number of (index, field) pairs held by the field stats cache.
+const FIELD_STATS_CACHE_CAPACITY: usize = 1_000;
+
+const VALUES_AGG_NAME: &str = "values";
+const STATS_AGG_NAME: &str = "stats";
+
+/// Statistics about the distribution of the values of a fast field across the published splits
+/// of an index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FieldStatsResponse {
+    pub field: String,
+    /// Number of documents with at least one value for the field.
+    pub count: u64,
+    /// Number of documents without any value for the field.
+    pub null_count: u64,
+    /// Number of distinct values of the field. For high cardinality fields, this is a lower
+    /// bound.
+    pub distinct_values: u64,
+    /// Whether the field has more than 1,000 distinct values, in which case only the top 20
+    /// values are reported in the histogram.
+    pub is_high_cardinality: bool,
+    /// Minimum value of the field. Only reported for numeric fields.
+    #[schema(value_type = Object)]
+    pub min: Option<JsonValue>,
+    /// Maximum value of the field. Only reported for numeric fields.
+    #[schema(value_type = Object)]
+    pub max: Option<JsonValue>,
+    /// Number of documents per value, sorted by decreasing count.
+    pub histogram: Vec<FieldValueCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FieldValueCount {
+    #[schema(value_type = Object)]
+    pub value: JsonValue,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NumericType {
+    Integer,
+    Float,
+}
+
+/// A cache of recently computed field stats, keyed by index ID and field.
+#[derive(Clone)]
+pub(crate) struct FieldStatsCache {
+    ttl_cache: Arc<Mutex<TtlCache<(IndexId, String), FieldStatsResponse>>>,
+}
+
+impl Default for FieldStatsCache {
+    fn default() -> Self {
+        FieldStatsCache {
+            ttl_cache: Arc::new(Mutex::new(TtlCache::new(FIELD_STATS_CACHE_CAPACITY))),
+        }
+    }
+}
+
+impl FieldStatsCache {
+    pub fn get(&self, index_id: &str, field: &str) -> Option<FieldStatsResponse> {
+        let cache_lock = self.ttl_cache.lock().unwrap();
+        cache_lock
+            .get(&(index_id.to_string(), field.to_string()))
+            .cloned()
+    }
+
+    pub fn put(&self, index_id: IndexId, field: String, field_stats: FieldStatsResponse) {
+        let mut cache_lock = self.ttl_cache.lock().unwrap();
+        cache_lock.insert((index_id, field), field_stats, FIELD_STATS_CACHE_TTL);
+    }
+}
+
+/// Computes statistics about the values of a fast field by running a terms aggregation, and a
+/// stats aggregation for numeric fields, over all the published splits of an index. The splits
+/// are searched in parallel by the leaf searchers of the cluster.
+#[instrument(skip(searcher_context, metastore, cluster_client))]
+pub async fn root_field_stats(
+    searcher_context: &SearcherContext,
+    index_id: &str,
+    field: &str,
+    mut metastore: MetastoreServiceClient,
+    cluster_client: &ClusterClient,
+) -> crate::Result<FieldStatsResponse> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let index_config = index_metadata.into_index_config();
+
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(|err| {
+            SearchError::Internal(format!("failed to build doc mapper. cause: {err}"))
+        })?;
+    let schema = doc_mapper.schema();
+    let (_field, field_entry, json_path) = find_field_or_hit_dynamic(field, &schema)
+        .map_err(|err| SearchError::InvalidArgument(err.to_string()))?;
+
+    if !field_entry.is_fast() {
+        return Err(SearchError::InvalidArgument(format!(
+            "field `{field}` is not a fast field"
+        )));
+    }
+    let numeric_type_opt = if json_path.is_empty() {
+        numeric_type(field_entry.field_type())
+    } else {
+        None
+    };
+    let mut aggregations = json!({
+        VALUES_AGG_NAME: {
+            "terms": {
+                "field": field,
+                "size": HIGH_CARDINALITY_THRESHOLD + 1,
+            }
+        }
+    });
+    if numeric_type_opt.is_some() {
+        aggregations[STATS_AGG_NAME] = json!({"stats": {"field": field}});
+    }
+    let num_docs_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: serialize_query_ast(&QueryAst::MatchAll)?,
+        max_hits: 0,
+        ..Default::default()
+    };
+    let field_presence_query_ast: QueryAst = FieldPresenceQuery {
+        field: field.to_string(),
+    }
+    .into();
+    let field_values_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: serialize_query_ast(&field_presence_query_ast)?,
+        max_hits: 0,
+        aggregation_request: Some(aggregations.to_string()),
+        ..Default::default()
+    };
+    let (num_docs_response, field_values_response) = try_join(
+        root_search(
+            searcher_context,
+            num_docs_request,
+            metastore.clone(),
+            cluster_client,
+        ),
+        root_search(
+            searcher_context,
+            field_values_request,
+            metastore,
+            cluster_client,
+        ),
+    )
+    .await?;
+
+    let aggregation_results: JsonValue = match field_values_response.aggregation.as_deref() {
+        Some(aggregation_json) => serde_json::from_str(aggregation_json).map_err(|err| {
+            SearchError::Internal(format!("failed to parse aggregation results: {err}"))
+        })?,
+        None => JsonValue::Null,
+    };
+    let mut histogram: Vec<FieldValueCount> = aggregation_results[VALUES_AGG_NAME]["buckets"]
+        .as_array()
+        .map(|buckets| buckets.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|bucket| FieldValueCount {
+            value: bucket_value(bucket, numeric_type_opt),
+            count: bucket["doc_count"].as_u64().unwrap_or_default(),
+        })
+        .collect();
+    let distinct_values = histogram.len() as u64;
+    let is_high_cardinality = histogram.len() > HIGH_CARDINALITY_THRESHOLD;
+
+    if is_high_cardinality {
+        histogram.truncate(NUM_TOP_VALUES_HIGH_CARDINALITY);
+    }
+    let stats = &aggregation_results[STATS_AGG_NAME];
+    let min = numeric_type_opt.and_then(|numeric_type| to_json_number(&stats["min"], numeric_type));
+    let max = numeric_type_opt.and_then(|numeric_type| to_json_number(&stats["max"], numeric_type));
+
+    let count = field_values_response.num_hits;
+    let null_count = num_docs_response.num_hits.saturating_sub(count);
+
+    let field_stats = FieldStatsResponse {
+        field: field.to_string(),
+        count,
+        null_count,
+        distinct_values,
+        is_high_cardinality,
+        min,
+        max,
+        histogram,
+    };
+    Ok(field_stats)
+}
+
+fn serialize_query_ast(query_ast: &QueryAst) -> crate::Result<String> {
+    serde_json::to_string(query_ast)
+        .map_err(|err| SearchError::Internal(format!("failed to serialize query AST: {err}")))
+}
+
+fn numeric_type(field_type: &FieldType) -> Option<NumericType> {
+    match field_type {
+        FieldType::U64(_) | FieldType::I64(_) => Some(NumericType::Integer),
+        FieldType::F64(_) => Some(NumericType::Float),
+        _ => None,
+    }
+}
+
+/// Aggregations return numbers as floats. This converts them back to integers for integer
+/// fields.
+fn to_json_number(value: &JsonValue, numeric_type: NumericType) -> Option<JsonValue> {
+    let value_f64 = value.as_f64()?;
+
+    match numeric_type {
+        NumericType::Integer if value_f64.fract() == 0.0 => {
+            if value_f64 >= 0.0 {
+                Some(json!(value_f64 as u64))
+            } else {
+                Some(json!(value_f64 as i64))
+            }
+        }
+        _ => Some(json!(value_f64)),
+    }
+}
+
+fn bucket_value(bucket: &JsonValue, numeric_type_opt: Option<NumericType>) -> JsonValue {
+    if let Some(key_as_string) = bucket.get("key_as_string") {
+        return key_as_string.clone();
+    }
+    let key = &bucket["key"];
+
+    numeric_type_opt
+        .and_then(|numeric_type| to_json_number(key, numeric_type))
+        .unwrap_or_else(|| key.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_indexing::TestSandbox;
+
+    use super::*;
+    use crate::SingleNodeSearcher;
+
+    fn field_value_count(value: JsonValue, count: u64) -> FieldValueCount {
+        FieldValueCount { value, count }
+    }
+
+    #[test]
+    fn test_to_json_number() {
+        assert_eq!(
+            to_json_number(&json!(200.0), NumericType::Integer),
+            Some(json!(200))
+        );
+        assert_eq!(
+            to_json_number(&json!(-3.0), NumericType::Integer),
+            Some(json!(-3))
+        );
+        assert_eq!(
+            to_json_number(&json!(1.5), NumericType::Float),
+            Some(json!(1.5))
+        );
+        assert_eq!(to_json_number(&JsonValue::Null, NumericType::Float), None);
+    }
+
+    #[test]
+    fn test_field_stats_cache() {
+        let cache = FieldStatsCache::default();
+        assert!(cache.get("index", "field").is_none());
+
+        let field_stats = FieldStatsResponse {
+            field: "field".to_string(),
+            count: 1,
+            null_count: 0,
+            distinct_values: 1,
+            is_high_cardinality: false,
+            min: None,
+            max: None,
+            histogram: vec![field_value_count(json!("foo"), 1)],
+        };
+        cache.put(
+            "index".to_string(),
+            "field".to_string(),
+            field_stats.clone(),
+        );
+        assert_eq!(cache.get("index", "field").unwrap(), field_stats);
+        assert!(cache.get("index", "other-field").is_none());
+        assert!(cache.get("other-index", "field").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_root_field_stats() {
+        let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: status_code
+                type: u64
+                fast: true
+              - name: level
+                type: text
+                tokenizer: raw
+                fast: true
+              - name: latency
+                type: f64
+                fast: true
+              - name: user_id
+                type: u64
+                fast: true
+              - name: body
+                type: text
+        "#;
+        let index_id = "field-stats";
+        let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"])
+            .await
+            .unwrap();
+        let mut docs = Vec::new();
+
+        for (status_code, level, num_docs) in
+            [(200, "INFO", 3), (404, "WARN", 2), (500, "ERROR", 1)]
+        {
+            for _ in 0..num_docs {
+                docs.push(json!({"status_code": status_code, "level": level, "latency": 1.5}));
+            }
+        }
+        docs.push(json!({"latency": 0.5}));
+
+        for user_id in 0..1_100 {
+            docs.push(json!({"user_id": user_id}));
+        }
+        test_sandbox.add_documents(docs).await.unwrap();
+
+        let single_node_searcher =
+            SingleNodeSearcher::new(test_sandbox.metastore(), test_sandbox.storage_resolver());
+        let field_stats = single_node_searcher
+            .field_stats(index_id, "status_code")
+            .await
+            .unwrap();
+        let expected_field_stats = FieldStatsResponse {
+            field: "status_code".to_string(),
+            count: 6,
+            null_count: 1_101,
+            distinct_values: 3,
+            is_high_cardinality: false,
+            min: Some(json!(200)),
+            max: Some(json!(500)),
+            histogram: vec![
+                field_value_count(json!(200), 3),
+                field_value_count(json!(404), 2),
+                field_value_count(json!(500), 1),
+            ],
+        };
+        assert_eq!(field_stats, expected_field_stats);
+
+        let field_stats = single_node_searcher
+            .field_stats(index_id, "level")
+            .await
+            .unwrap();
+        assert_eq!(field_stats.count, 6);
+        assert_eq!(field_stats.distinct_values, 3);
+        assert!(field_stats.min.is_none());
+        assert!(field_stats.max.is_none());
+        assert_eq!(
+            field_stats.histogram,
+            [
+                field_value_count(json!("INFO"), 3),
+                field_value_count(json!("WARN"), 2),
+                field_value_count(json!("ERROR"), 1),
+            ]
+        );
+
+        let field_stats = single_node_searcher
+            .field_stats(index_id, "latency")
+            .await
+            .unwrap();
+        assert_eq!(field_stats.count, 7);
+        assert_eq!(field_stats.min, Some(json!(0.5)));
+        assert_eq!(field_stats.max, Some(json!(1.5)));
+
+        let field_stats = single_node_searcher
+            .field_stats(index_id, "user_id")
+            .await
+            .unwrap();
+        assert_eq!(field_stats.count, 1_100);
+        assert_eq!(field_stats.null_count, 7);
+        assert!(field_stats.is_high_cardinality);
+        assert_eq!(field_stats.distinct_values, 1_001);
+        assert_eq!(field_stats.histogram.len(), 20);
+        assert_eq!(field_stats.min, Some(json!(0)));
+        assert_eq!(field_stats.max, Some(json!(1_099)));
+
+        let error = single_node_searcher
+            .field_stats(index_id, "body")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, SearchError::InvalidArgument(message) if message == "field `body` is not a fast field")
+        );
+        let error = single_node_searcher
+            .field_stats("does-not-exist", "status_code")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SearchError::IndexesNotFound { .. }));
+
+        test_sandbox.assert_quit().await;
+    }
+}
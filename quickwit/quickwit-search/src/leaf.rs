@@ -19,7 +19,6 @@
 
 use std::collections::{HashMap, HashSet};
 use std::ops::Bound;
-use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
@@ -48,10 +47,11 @@ use tracing::*;
 
 use crate::collector::{make_collector_for_split, make_merge_collector, IncrementalCollector};
 use crate::service::{deserialize_doc_mapper, SearcherContext};
+use crate::split_prefetcher::SplitPrefetcher;
 use crate::{QuickwitAggregations, SearchError};
 
 #[instrument(skip_all)]
-async fn get_split_footer_from_cache_or_fetch(
+pub(crate) async fn get_split_footer_from_cache_or_fetch(
     index_storage: Arc<dyn Storage>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
     footer_cache: &MemorySizedCache<String>,
@@ -62,7 +62,7 @@ async fn get_split_footer_from_cache_or_fetch(
             return Ok(footer_data);
         }
     }
-    let split_file = PathBuf::from(format!("{}.split", split_and_footer_offsets.split_id));
+    let split_file = quickwit_common::split_file_path(&split_and_footer_offsets.split_id);
     let footer_data_opt = index_storage
         .get_slice(
             &split_file,
@@ -78,7 +78,9 @@ async fn get_split_footer_from_cache_or_fetch(
             )
         })?;
 
-    footer_cache.put(
+    // The split prefetcher and the leaf search may fetch the same footer concurrently. We keep
+    // the entry inserted first rather than evicting and reinserting it.
+    footer_cache.put_if_absent(
         split_and_footer_offsets.split_id.to_owned(),
         footer_data_opt.clone(),
     );
@@ -94,7 +96,7 @@ pub(crate) async fn open_split_bundle(
     index_storage: Arc<dyn Storage>,
     split_and_footer_offsets: &SplitIdAndFooterOffsets,
 ) -> anyhow::Result<(FileSlice, BundleStorage)> {
-    let split_file = PathBuf::from(format!("{}.split", split_and_footer_offsets.split_id));
+    let split_file = quickwit_common::split_file_path(&split_and_footer_offsets.split_id);
     let footer_data = get_split_footer_from_cache_or_fetch(
         index_storage.clone(),
         split_and_footer_offsets,
@@ -206,7 +208,7 @@ pub(crate) async fn warmup(searcher: &Searcher, warmup_info: &WarmupInfo) -> any
     Ok(())
 }
 
-async fn warm_up_term_dict_fields(
+pub(crate) async fn warm_up_term_dict_fields(
     searcher: &Searcher,
     term_dict_fields: &HashSet<Field>,
 ) -> anyhow::Result<()> {
@@ -378,8 +380,13 @@ async fn leaf_search_single_split(
     warmup_info.simplify();
 
     warmup(&searcher, &warmup_info).await?;
+    // The searcher and the query are moved into the search closure, so we keep a copy around in
+    // case we need to generate suggestions afterwards.
+    let suggestion_searcher_and_query_opt = search_request
+        .suggest_corrections
+        .then(|| (searcher.clone(), query.box_clone()));
     let span = info_span!("tantivy_search");
-    let leaf_search_response = crate::search_thread_pool()
+    let mut leaf_search_response = crate::search_thread_pool()
         .run_cpu_intensive(move || {
             let _span_guard = span.enter();
             searcher.search(&query, &quickwit_collector)
@@ -389,6 +396,25 @@ async fn leaf_search_single_split(
             crate::SearchError::Internal(format!("leaf search panicked. split={split_id}"))
         })??;
 
+    if let Some((searcher, query)) = suggestion_searcher_and_query_opt {
+        if leaf_search_response.num_hits == 0 {
+            match searcher_context
+                .suggestion_service
+                .suggest(&searcher, query.as_ref())
+                .await
+            {
+                Ok(suggestions) => leaf_search_response.suggestions = suggestions,
+                Err(error) => {
+                    warn!(
+                        split_id = %split.split_id,
+                        error = ?error,
+                        "failed to generate search suggestions"
+                    );
+                }
+            }
+        }
+    }
+
     searcher_context
         .leaf_search_cache
         .put(split, search_request, leaf_search_response.clone());
@@ -1016,6 +1042,17 @@ pub async fn leaf_search(
         || (request.aggregation_request.is_some()
             && !matches!(split_filter, CanSplitDoBetter::FindTraceIdsAggregation(_)));
 
+    // The first splits are searched right away, so their footers are fetched by the split
+    // searches themselves.
+    let num_available_permits = searcher_context
+        .leaf_search_split_semaphore
+        .available_permits();
+    let _prefetch_handle_opt = if splits.len() > num_available_permits {
+        SplitPrefetcher::new(searcher_context.clone(), index_storage.clone())
+            .spawn_prefetch(splits[num_available_permits..].to_vec())
+    } else {
+        None
+    };
     let split_filter = Arc::new(Mutex::new(split_filter));
 
     let mut leaf_search_single_split_futures: Vec<_> = Vec::with_capacity(splits.len());
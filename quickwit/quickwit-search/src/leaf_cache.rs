@@ -51,7 +51,7 @@ impl LeafSearchCache {
         LeafSearchCache {
             content: MemorySizedCache::with_capacity_in_bytes(
                 capacity,
-                &quickwit_storage::STORAGE_METRICS.partial_request_cache,
+                &quickwit_storage::CACHE_METRICS.partial_request_cache,
             ),
         }
     }
@@ -240,6 +240,7 @@ mod tests {
         let result = LeafSearchResponse {
             failed_splits: Vec::new(),
             intermediate_aggregation_result: None,
+            suggestions: Vec::new(),
             num_attempted_splits: 0,
             num_hits: 1234,
             partial_hits: vec![PartialHit {
@@ -326,6 +327,7 @@ mod tests {
         let result = LeafSearchResponse {
             failed_splits: Vec::new(),
             intermediate_aggregation_result: None,
+            suggestions: Vec::new(),
             num_attempted_splits: 0,
             num_hits: 1234,
             partial_hits: vec![PartialHit {
@@ -22,11 +22,13 @@
 #![allow(clippy::bool_assert_comparison)]
 #![deny(clippy::disallowed_methods)]
 
+mod analyze_query;
 mod client;
 mod cluster_client;
 mod collector;
 mod error;
 mod fetch_docs;
+mod field_stats;
 mod filters;
 mod find_trace_ids_collector;
 mod leaf;
@@ -34,13 +36,17 @@ mod leaf_cache;
 mod list_fields;
 mod list_fields_cache;
 mod list_terms;
+mod multi_index_search;
 mod retry;
 mod root;
 mod scroll_context;
 mod search_job_placer;
 mod search_response_rest;
+mod search_router;
 mod search_stream;
 mod service;
+mod split_prefetcher;
+mod suggestion;
 pub(crate) mod top_k_collector;
 
 mod metrics;
@@ -77,20 +83,29 @@ use quickwit_storage::StorageResolver;
 pub use service::SearcherContext;
 use tantivy::DocAddress;
 
+pub use crate::analyze_query::{
+    root_analyze_query, AnalyzeQueryResponse, AnalyzedPhrase, AnalyzedTerm, AnalyzedToken,
+    AnalyzedWildcard,
+};
 pub use crate::client::{
     create_search_client_from_channel, create_search_client_from_grpc_addr, SearchServiceClient,
 };
 pub use crate::cluster_client::ClusterClient;
 pub use crate::error::{parse_grpc_error, SearchError};
 use crate::fetch_docs::fetch_docs;
+pub use crate::field_stats::{root_field_stats, FieldStatsResponse, FieldValueCount};
+pub use crate::multi_index_search::MultiIndexSearchCoordinator;
 pub use crate::root::{
     check_all_index_metadata_found, jobs_to_leaf_request, root_search, IndexMetasForLeafSearch,
     SearchJob,
 };
 pub use crate::search_job_placer::{Job, SearchJobPlacer};
 pub use crate::search_response_rest::SearchResponseRest;
+pub use crate::search_router::{AffinityMap, IndexSearchRouter};
 pub use crate::search_stream::root_search_stream;
 pub use crate::service::{MockSearchService, SearchService, SearchServiceImpl};
+pub use crate::split_prefetcher::{PrefetchHandle, SplitPrefetcher};
+pub use crate::suggestion::SearchSuggestionService;
 
 /// A pool of searcher clients identified by their gRPC socket address.
 pub type SearcherPool = Pool<SocketAddr, SearchServiceClient>;
@@ -277,28 +292,88 @@ pub async fn single_node_search(
     metastore: MetastoreServiceClient,
     storage_resolver: StorageResolver,
 ) -> crate::Result<SearchResponse> {
-    let socket_addr = SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 7280u16);
-    let searcher_pool = SearcherPool::default();
-    let search_job_placer = SearchJobPlacer::new(searcher_pool.clone());
-    let cluster_client = ClusterClient::new(search_job_placer);
-    let searcher_config = SearcherConfig::default();
-    let searcher_context = Arc::new(SearcherContext::new(searcher_config, None));
-    let search_service = Arc::new(SearchServiceImpl::new(
-        metastore.clone(),
-        storage_resolver,
-        cluster_client.clone(),
-        searcher_context.clone(),
-    ));
-    let search_service_client =
-        SearchServiceClient::from_service(search_service.clone(), socket_addr);
-    searcher_pool.insert(socket_addr, search_service_client);
-    root_search(
-        &searcher_context,
-        search_request,
-        metastore,
-        &cluster_client,
-    )
-    .await
+    SingleNodeSearcher::new(metastore, storage_resolver)
+        .search(search_request)
+        .await
+}
+
+/// Runs searches on the current node without going through the REST or gRPC APIs.
+///
+/// Contrary to [`single_node_search`], the searcher context, and therefore its caches, is shared
+/// by all the searches performed with the same `SingleNodeSearcher`.
+#[derive(Clone)]
+pub struct SingleNodeSearcher {
+    searcher_context: Arc<SearcherContext>,
+    metastore: MetastoreServiceClient,
+    cluster_client: ClusterClient,
+}
+
+impl SingleNodeSearcher {
+    /// Creates a new single node searcher with the default searcher config.
+    pub fn new(metastore: MetastoreServiceClient, storage_resolver: StorageResolver) -> Self {
+        let socket_addr = SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 7280u16);
+        let searcher_pool = SearcherPool::default();
+        let search_job_placer = SearchJobPlacer::new(searcher_pool.clone());
+        let cluster_client = ClusterClient::new(search_job_placer);
+        let searcher_config = SearcherConfig::default();
+        let searcher_context = Arc::new(SearcherContext::new(searcher_config, None));
+        let search_service = Arc::new(SearchServiceImpl::new(
+            metastore.clone(),
+            storage_resolver,
+            cluster_client.clone(),
+            searcher_context.clone(),
+        ));
+        let search_service_client = SearchServiceClient::from_service(search_service, socket_addr);
+        searcher_pool.insert(socket_addr, search_service_client);
+        Self {
+            searcher_context,
+            metastore,
+            cluster_client,
+        }
+    }
+
+    /// Performs a search on the current node.
+    pub async fn search(&self, search_request: SearchRequest) -> crate::Result<SearchResponse> {
+        root_search(
+            &self.searcher_context,
+            search_request,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await
+    }
+
+    /// Analyzes a query against an index of the current node. See [`root_analyze_query`].
+    pub async fn analyze_query(
+        &self,
+        index_id: &str,
+        query: &str,
+    ) -> crate::Result<AnalyzeQueryResponse> {
+        root_analyze_query(
+            index_id,
+            query,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await
+    }
+
+    /// Computes the stats of a fast field of an index of the current node. See
+    /// [`root_field_stats`].
+    pub async fn field_stats(
+        &self,
+        index_id: &str,
+        field: &str,
+    ) -> crate::Result<FieldStatsResponse> {
+        root_field_stats(
+            &self.searcher_context,
+            index_id,
+            field,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await
+    }
 }
 
 /// Creates a tantivy Term from a &str.
@@ -35,7 +35,7 @@ impl ListFieldsCache {
         ListFieldsCache {
             content: MemorySizedCache::with_capacity_in_bytes(
                 capacity,
-                &quickwit_storage::STORAGE_METRICS.partial_request_cache,
+                &quickwit_storage::CACHE_METRICS.partial_request_cache,
             ),
         }
     }
@@ -17,38 +17,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-// See https://prometheus.io/docs/practices/naming/
-
 use once_cell::sync::Lazy;
-use quickwit_common::metrics::{
-    exponential_buckets, new_counter, new_histogram, Histogram, IntCounter,
-};
-
-pub struct SearchMetrics {
-    pub leaf_searches_splits_total: IntCounter,
-    pub leaf_search_split_duration_secs: Histogram,
-}
-
-impl Default for SearchMetrics {
-    fn default() -> Self {
-        SearchMetrics {
-            leaf_searches_splits_total: new_counter(
-                "leaf_searches_splits_total",
-                "Number of leaf searches (count of splits) started.",
-                "search",
-                &[],
-            ),
-            leaf_search_split_duration_secs: new_histogram(
-                "leaf_search_split_duration_secs",
-                "Number of seconds required to run a leaf search over a single split. The timer \
-                 starts after the semaphore is obtained.",
-                "search",
-                exponential_buckets(0.005, 2.0, 10).unwrap(),
-            ),
-        }
-    }
-}
+use quickwit_common::metrics::QuickwitMetrics;
+pub use quickwit_common::metrics::SearchMetrics;
 
 /// `SEARCH_METRICS` exposes a bunch a set of storage/cache related metrics through a prometheus
 /// endpoint.
-pub static SEARCH_METRICS: Lazy<SearchMetrics> = Lazy::new(SearchMetrics::default);
+pub static SEARCH_METRICS: Lazy<&'static SearchMetrics> =
+    Lazy::new(|| &QuickwitMetrics::global().search);
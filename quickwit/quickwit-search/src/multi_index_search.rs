@@ -0,0 +1,423 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::sync::Arc;
+
+use itertools::Itertools;
+use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::{IndexSearchFailure, SearchRequest, SearchResponse};
+use quickwit_proto::types::IndexId;
+use tokio::task::JoinSet;
+use tracing::{instrument, warn};
+
+use crate::cluster_client::ClusterClient;
+use crate::collector::merge_hits;
+use crate::root::{check_all_index_metadata_found, root_search};
+use crate::service::SearcherContext;
+use crate::SearchError;
+
+/// Searches several indexes by running one root search per index in parallel and merging their
+/// responses.
+///
+/// Contrary to a regular root search over the same indexes, an index that fails to be searched
+/// does not fail the whole request: the response holds the hits of the other indexes and reports
+/// the failing index in `partial_failures`. Aggregations and scrolls are not supported.
+#[derive(Clone)]
+pub struct MultiIndexSearchCoordinator {
+    searcher_context: Arc<SearcherContext>,
+    metastore: MetastoreServiceClient,
+    cluster_client: ClusterClient,
+}
+
+impl MultiIndexSearchCoordinator {
+    pub fn new(
+        searcher_context: Arc<SearcherContext>,
+        metastore: MetastoreServiceClient,
+        cluster_client: ClusterClient,
+    ) -> Self {
+        Self {
+            searcher_context,
+            metastore,
+            cluster_client,
+        }
+    }
+
+    /// Searches the indexes matching the index ID patterns of `search_request`.
+    #[instrument(skip_all)]
+    pub async fn search(&self, search_request: SearchRequest) -> crate::Result<SearchResponse> {
+        let start_instant = tokio::time::Instant::now();
+
+        if search_request.aggregation_request.is_some() {
+            return Err(SearchError::InvalidArgument(
+                "aggregations are not supported by multi-index searches".to_string(),
+            ));
+        }
+        if search_request.scroll_ttl_secs.is_some() {
+            return Err(SearchError::InvalidArgument(
+                "scrolls are not supported by multi-index searches".to_string(),
+            ));
+        }
+        let list_indexes_metadata_request = ListIndexesMetadataRequest {
+            index_id_patterns: search_request.index_id_patterns.clone(),
+        };
+        let indexes_metadata: Vec<IndexMetadata> = self
+            .metastore
+            .clone()
+            .list_indexes_metadata(list_indexes_metadata_request)
+            .await?
+            .deserialize_indexes_metadata()
+            .await?;
+        check_all_index_metadata_found(&indexes_metadata, &search_request.index_id_patterns)?;
+
+        let mut search_tasks = JoinSet::new();
+
+        for index_metadata in indexes_metadata {
+            let index_id: IndexId = index_metadata.index_id().to_string();
+            // Each index must return enough hits to fill the requested page on its own.
+            let mut index_search_request = search_request.clone();
+            index_search_request.index_id_patterns = vec![index_id.clone()];
+            index_search_request.start_offset = 0;
+            index_search_request.max_hits = search_request.start_offset + search_request.max_hits;
+
+            let searcher_context = self.searcher_context.clone();
+            let metastore = self.metastore.clone();
+            let cluster_client = self.cluster_client.clone();
+
+            search_tasks.spawn(async move {
+                let search_result = root_search(
+                    &searcher_context,
+                    index_search_request,
+                    metastore,
+                    &cluster_client,
+                )
+                .await;
+                (index_id, search_result)
+            });
+        }
+        let mut search_responses = Vec::new();
+        let mut partial_failures = Vec::new();
+        let mut first_error_opt: Option<SearchError> = None;
+
+        while let Some(join_result) = search_tasks.join_next().await {
+            let (index_id, search_result) = join_result.map_err(|join_error| {
+                SearchError::Internal(format!("index search task failed: {join_error}"))
+            })?;
+            match search_result {
+                Ok(search_response) => search_responses.push(search_response),
+                Err(search_error) => {
+                    warn!(index_id=%index_id, error=%search_error, "failed to search index");
+                    partial_failures.push(IndexSearchFailure {
+                        index_id,
+                        error: search_error.to_string(),
+                    });
+                    first_error_opt.get_or_insert(search_error);
+                }
+            }
+        }
+        if search_responses.is_empty() {
+            if let Some(first_error) = first_error_opt {
+                return Err(first_error);
+            }
+        }
+        partial_failures.sort_by(|left, right| left.index_id.cmp(&right.index_id));
+
+        let num_hits: u64 = search_responses
+            .iter()
+            .map(|search_response| search_response.num_hits)
+            .sum();
+        let errors = search_responses
+            .iter_mut()
+            .flat_map(|search_response| std::mem::take(&mut search_response.errors))
+            .collect();
+        // Suggestions are only meaningful if none of the indexes matched the query.
+        let suggestions = if num_hits == 0 {
+            search_responses
+                .iter_mut()
+                .flat_map(|search_response| std::mem::take(&mut search_response.suggestions))
+                .unique()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let hits = merge_hits(
+            &search_request,
+            search_responses
+                .into_iter()
+                .flat_map(|search_response| search_response.hits),
+        );
+        Ok(SearchResponse {
+            num_hits,
+            hits,
+            elapsed_time_micros: start_instant.elapsed().as_micros() as u64,
+            errors,
+            aggregation: None,
+            scroll_id: None,
+            suggestions,
+            partial_failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_common::ServiceStream;
+    use quickwit_indexing::MockSplitBuilder;
+    use quickwit_metastore::{ListSplitsRequestExt, ListSplitsResponseExt};
+    use quickwit_proto::metastore::{
+        ListIndexesMetadataResponse, ListSplitsResponse, MetastoreError, MockMetastoreService,
+    };
+    use quickwit_proto::search::{
+        FetchDocsResponse, LeafHit, LeafSearchRequest, LeafSearchResponse, PartialHit, SortValue,
+    };
+    use quickwit_query::query_ast::qast_json_helper;
+
+    use super::*;
+    use crate::{searcher_pool_for_test, MockSearchService, SearchJobPlacer};
+
+    const INDEX_IDS: [&str; 3] = ["test-index-1", "test-index-2", "test-index-3"];
+
+    /// Returns the sort value of the only hit of each split.
+    fn split_sort_value(split_id: &str) -> u64 {
+        match split_id {
+            "index-1-split-1" => 3,
+            "index-1-split-2" => 9,
+            "index-2-split-1" => 7,
+            "index-3-split-1" => 5,
+            _ => panic!("unexpected split `{split_id}`"),
+        }
+    }
+
+    /// Builds a metastore serving the indexes `test-index-{1,2,3}`. Listing the splits of the
+    /// indexes in `failing_index_ids` fails.
+    fn mock_metastore(failing_index_ids: &'static [&'static str]) -> MetastoreServiceClient {
+        let indexes_metadata: Vec<IndexMetadata> = INDEX_IDS
+            .iter()
+            .map(|index_id| IndexMetadata::for_test(index_id, &format!("ram:///{index_id}")))
+            .collect();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_indexes_metadata().returning(
+            move |list_indexes_metadata_request| {
+                let index_id_patterns = list_indexes_metadata_request.index_id_patterns;
+                let matching_indexes_metadata = indexes_metadata
+                    .iter()
+                    .filter(|index_metadata| {
+                        index_id_patterns.iter().any(|index_id_pattern| {
+                            index_id_pattern == "test-index-*"
+                                || index_id_pattern == index_metadata.index_id()
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                Ok(ListIndexesMetadataResponse::for_test(
+                    matching_indexes_metadata,
+                ))
+            },
+        );
+        mock_metastore
+            .expect_list_splits()
+            .returning(move |list_splits_request| {
+                let list_splits_query =
+                    list_splits_request.deserialize_list_splits_query().unwrap();
+                let index_uid = list_splits_query.index_uids[0].clone();
+                let index_id = index_uid.index_id.as_str();
+
+                if failing_index_ids.contains(&index_id) {
+                    return Err(MetastoreError::Unavailable(format!(
+                        "failed to list splits of index `{index_id}`"
+                    )));
+                }
+                let split_ids: &[&str] = match index_id {
+                    "test-index-1" => &["index-1-split-1", "index-1-split-2"],
+                    "test-index-2" => &["index-2-split-1"],
+                    "test-index-3" => &["index-3-split-1"],
+                    _ => unreachable!(),
+                };
+                let splits = split_ids
+                    .iter()
+                    .map(|split_id| {
+                        MockSplitBuilder::new(split_id)
+                            .with_index_uid(&index_uid)
+                            .build()
+                    })
+                    .collect();
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        MetastoreServiceClient::from_mock(mock_metastore)
+    }
+
+    fn mock_search_service() -> MockSearchService {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_leaf_search().returning(
+            |leaf_search_request: LeafSearchRequest| {
+                let partial_hits: Vec<PartialHit> = leaf_search_request
+                    .leaf_requests
+                    .iter()
+                    .flat_map(|leaf_request| leaf_request.split_offsets.iter())
+                    .map(|split_offsets| PartialHit {
+                        sort_value: Some(
+                            SortValue::U64(split_sort_value(&split_offsets.split_id)).into(),
+                        ),
+                        sort_value2: None,
+                        split_id: split_offsets.split_id.clone(),
+                        segment_ord: 0,
+                        doc_id: 0,
+                    })
+                    .collect();
+                Ok(LeafSearchResponse {
+                    num_hits: partial_hits.len() as u64,
+                    partial_hits,
+                    num_attempted_splits: 1,
+                    ..Default::default()
+                })
+            },
+        );
+        mock_search_service
+            .expect_fetch_docs()
+            .returning(|fetch_docs_request| {
+                let hits = fetch_docs_request
+                    .partial_hits
+                    .into_iter()
+                    .map(|partial_hit| LeafHit {
+                        leaf_json: format!(r#"{{"split_id": "{}"}}"#, partial_hit.split_id),
+                        partial_hit: Some(partial_hit),
+                        leaf_snippet_json: None,
+                    })
+                    .collect();
+                Ok(FetchDocsResponse { hits })
+            });
+        mock_search_service
+    }
+
+    fn multi_index_search_coordinator(
+        failing_index_ids: &'static [&'static str],
+    ) -> MultiIndexSearchCoordinator {
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service())]);
+        let cluster_client = ClusterClient::new(SearchJobPlacer::new(searcher_pool));
+        MultiIndexSearchCoordinator::new(
+            Arc::new(SearcherContext::for_test()),
+            mock_metastore(failing_index_ids),
+            cluster_client,
+        )
+    }
+
+    fn search_request(max_hits: u64, start_offset: u64) -> SearchRequest {
+        SearchRequest {
+            index_id_patterns: vec!["test-index-*".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits,
+            start_offset,
+            ..Default::default()
+        }
+    }
+
+    fn hit_split_ids(search_response: &SearchResponse) -> Vec<&str> {
+        search_response
+            .hits
+            .iter()
+            .map(|hit| hit.partial_hit.as_ref().unwrap().split_id.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_multi_index_search_merges_hits() {
+        let coordinator = multi_index_search_coordinator(&[]);
+        let search_response = coordinator.search(search_request(10, 0)).await.unwrap();
+
+        assert_eq!(search_response.num_hits, 4);
+        assert_eq!(
+            hit_split_ids(&search_response),
+            [
+                "index-1-split-2",
+                "index-2-split-1",
+                "index-3-split-1",
+                "index-1-split-1"
+            ]
+        );
+        let hit_index_ids: Vec<&str> = search_response
+            .hits
+            .iter()
+            .map(|hit| hit.index_id.as_str())
+            .collect();
+        assert_eq!(
+            hit_index_ids,
+            [
+                "test-index-1",
+                "test-index-2",
+                "test-index-3",
+                "test-index-1"
+            ]
+        );
+        assert!(search_response.partial_failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_multi_index_search_applies_offset_after_merge() {
+        let coordinator = multi_index_search_coordinator(&[]);
+        let search_response = coordinator.search(search_request(2, 1)).await.unwrap();
+
+        assert_eq!(search_response.num_hits, 4);
+        assert_eq!(
+            hit_split_ids(&search_response),
+            ["index-2-split-1", "index-3-split-1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_index_search_reports_partial_failures() {
+        let coordinator = multi_index_search_coordinator(&["test-index-2"]);
+        let search_response = coordinator.search(search_request(10, 0)).await.unwrap();
+
+        assert_eq!(search_response.num_hits, 3);
+        assert_eq!(
+            hit_split_ids(&search_response),
+            ["index-1-split-2", "index-3-split-1", "index-1-split-1"]
+        );
+        assert_eq!(search_response.partial_failures.len(), 1);
+
+        let partial_failure = &search_response.partial_failures[0];
+        assert_eq!(partial_failure.index_id, "test-index-2");
+        assert!(partial_failure
+            .error
+            .contains("failed to list splits of index `test-index-2`"));
+    }
+
+    #[tokio::test]
+    async fn test_multi_index_search_all_indexes_failing() {
+        let coordinator = multi_index_search_coordinator(&INDEX_IDS);
+        let search_error = coordinator.search(search_request(10, 0)).await.unwrap_err();
+        assert!(search_error
+            .to_string()
+            .contains("failed to list splits of index"));
+    }
+
+    #[tokio::test]
+    async fn test_multi_index_search_rejects_aggregations() {
+        let coordinator = multi_index_search_coordinator(&[]);
+        let mut search_request = search_request(10, 0);
+        let aggregation_request = r#"{"count": {"value_count": {"field": "body"}}}"#;
+        search_request.aggregation_request = Some(aggregation_request.to_string());
+
+        let search_error = coordinator.search(search_request).await.unwrap_err();
+        assert!(matches!(search_error, SearchError::InvalidArgument(_)));
+    }
+}
@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -28,7 +29,7 @@ use quickwit_common::shared_consts::{DELETION_GRACE_PERIOD, SCROLL_BATCH_LEN};
 use quickwit_common::uri::Uri;
 use quickwit_config::build_doc_mapper;
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
-use quickwit_doc_mapper::DYNAMIC_FIELD_NAME;
+use quickwit_doc_mapper::{DocMapper, DYNAMIC_FIELD_NAME};
 use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt, SplitMetadata};
 use quickwit_proto::metastore::{
     ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
@@ -53,6 +54,7 @@ use tracing::{debug, error, info, info_span, instrument};
 use crate::cluster_client::ClusterClient;
 use crate::collector::{make_merge_collector, QuickwitAggregations};
 use crate::find_trace_ids_collector::Span;
+use crate::metrics::SEARCH_METRICS;
 use crate::scroll_context::{ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_job_placer::{group_by, group_jobs_by_index_id, Job};
 use crate::service::SearcherContext;
@@ -71,6 +73,8 @@ const SORT_DOC_FIELD_NAMES: &[&str] = &["_shard_doc", "_doc"];
 pub struct SearchJob {
     /// The index UID.
     pub index_uid: IndexUid,
+    /// The schema version the split was indexed with.
+    pub schema_version: u32,
     cost: usize,
     /// The split ID and footer offsets of the split.
     pub offsets: SplitIdAndFooterOffsets,
@@ -82,6 +86,7 @@ impl SearchJob {
         use std::str::FromStr;
         SearchJob {
             index_uid: IndexUid::from_str("test-index:00000000000000000000000000").unwrap(),
+            schema_version: 0,
             cost,
             offsets: SplitIdAndFooterOffsets {
                 split_id: split_id.to_string(),
@@ -101,6 +106,7 @@ impl<'a> From<&'a SplitMetadata> for SearchJob {
     fn from(split_metadata: &'a SplitMetadata) -> Self {
         SearchJob {
             index_uid: split_metadata.index_uid.clone(),
+            schema_version: split_metadata.schema_version,
             cost: compute_split_cost(split_metadata),
             offsets: extract_split_and_footer_offsets(split_metadata),
         }
@@ -119,6 +125,7 @@ impl Job for SearchJob {
 
 pub struct FetchDocsJob {
     index_uid: IndexUid,
+    schema_version: u32,
     offsets: SplitIdAndFooterOffsets,
     pub partial_hits: Vec<PartialHit>,
 }
@@ -146,6 +153,19 @@ pub struct IndexMetasForLeafSearch {
     pub index_uri: Uri,
     /// Doc mapper json string.
     pub doc_mapper_str: String,
+    /// Doc mapper json strings of the previous schema versions of the index, used to search the
+    /// splits indexed before the last schema upgrades.
+    #[serde(default)]
+    pub previous_doc_mapper_strs: HashMap<u32, String>,
+}
+
+impl IndexMetasForLeafSearch {
+    /// Returns the doc mapper json string to search the splits of the given schema version with.
+    pub fn doc_mapper_str_for_schema_version(&self, schema_version: u32) -> &str {
+        self.previous_doc_mapper_strs
+            .get(&schema_version)
+            .unwrap_or(&self.doc_mapper_str)
+    }
 }
 
 pub(crate) type IndexesMetasForLeafSearch = HashMap<IndexUid, IndexMetasForLeafSearch>;
@@ -239,11 +259,25 @@ fn validate_request_and_build_metadata(
         // Validates the query by effectively building it against the current schema.
         doc_mapper.query(doc_mapper.schema(), &query_ast_resolved_for_index, true)?;
 
+        // The query is resolved and validated against the current doc mapping: the doc mappers of
+        // the previous schema versions are only used to search the splits they indexed.
+        let mut previous_doc_mapper_strs = HashMap::new();
+        for (schema_version, doc_mapping) in &index_metadata.doc_mapping_history {
+            let previous_doc_mapper =
+                build_doc_mapper(doc_mapping, &index_metadata.index_config.search_settings)
+                    .map_err(|err| {
+                        SearchError::Internal(format!(
+                            "failed to build doc mapper of schema version {schema_version}. \
+                             cause: {err}"
+                        ))
+                    })?;
+            previous_doc_mapper_strs
+                .insert(*schema_version, serialize_doc_mapper(&previous_doc_mapper)?);
+        }
         let index_metadata_for_leaf_search = IndexMetasForLeafSearch {
             index_uri: index_metadata.index_uri().clone(),
-            doc_mapper_str: serde_json::to_string(&doc_mapper).map_err(|err| {
-                SearchError::Internal(format!("failed to serialize doc mapper. cause: {err}"))
-            })?,
+            doc_mapper_str: serialize_doc_mapper(&doc_mapper)?,
+            previous_doc_mapper_strs,
         };
         indexes_meta_for_leaf_search.insert(
             index_metadata.index_uid.clone(),
@@ -265,6 +299,12 @@ fn validate_request_and_build_metadata(
     })
 }
 
+fn serialize_doc_mapper(doc_mapper: &Arc<dyn DocMapper>) -> crate::Result<String> {
+    serde_json::to_string(doc_mapper).map_err(|err| {
+        SearchError::Internal(format!("failed to serialize doc mapper. cause: {err}"))
+    })
+}
+
 /// Validate sort field types.
 fn validate_sort_field_types(
     schema: &Schema,
@@ -354,6 +394,8 @@ fn simplify_search_request_for_scroll_api(req: &SearchRequest) -> crate::Result<
         // request is simplified after initial query, and we cache the hit count, so we don't need
         // to recompute it afterward.
         count_hits: quickwit_proto::search::CountHits::Underestimate as i32,
+        // Suggestions are only returned with the first page of results.
+        suggest_corrections: false,
     })
 }
 
@@ -620,6 +662,7 @@ fn get_count_from_metadata(split_metadatas: &[SplitMetadata]) -> Vec<LeafSearchR
             failed_splits: Vec::new(),
             num_attempted_splits: 1,
             intermediate_aggregation_result: None,
+            suggestions: Vec::new(),
         })
         .collect()
 }
@@ -893,6 +936,18 @@ async fn root_search_aux(
         aggregation_result_json_opt = None;
     }
 
+    // Leaves only generate suggestions if they were requested, but a split without hits
+    // can produce suggestions while other splits have hits.
+    let suggestions: Vec<String> = if first_phase_result.num_hits == 0 {
+        first_phase_result
+            .suggestions
+            .into_iter()
+            .map(|suggestion| suggestion.term)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     Ok(SearchResponse {
         aggregation: aggregation_result_json_opt,
         num_hits: first_phase_result.num_hits,
@@ -902,6 +957,8 @@ async fn root_search_aux(
         scroll_id: scroll_key_and_start_offset_opt
             .as_ref()
             .map(ToString::to_string),
+        suggestions,
+        partial_failures: Vec::new(),
     })
 }
 
@@ -1046,6 +1103,12 @@ pub async fn root_search(
         .iter()
         .map(|index_metadata| index_metadata.index_uid.clone())
         .collect_vec();
+    for index_metadata in &indexes_metadata {
+        SEARCH_METRICS
+            .root_search_requests_total
+            .with_label_values([index_metadata.index_id()])
+            .inc();
+    }
     let request_metadata = validate_request_and_build_metadata(&indexes_metadata, &search_request)?;
     search_request.query_ast = serde_json::to_string(&request_metadata.query_ast_resolved)?;
 
@@ -1086,7 +1149,14 @@ pub async fn root_search(
     )
     .await?;
 
-    search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
+    let elapsed = start_instant.elapsed();
+    for index_metadata in &indexes_metadata {
+        SEARCH_METRICS
+            .root_search_request_duration_secs
+            .with_label_values([index_metadata.index_id()])
+            .observe(elapsed.as_secs_f64());
+    }
+    search_response.elapsed_time_micros = elapsed.as_micros() as u64;
     Ok(search_response)
 }
 
@@ -1344,19 +1414,22 @@ async fn assign_client_fetch_docs_jobs(
     split_metadatas: &[SplitMetadata],
     client_pool: &SearchJobPlacer,
 ) -> crate::Result<impl Iterator<Item = (SearchServiceClient, Vec<FetchDocsJob>)>> {
-    let index_uids_and_split_offsets_map: HashMap<String, (IndexUid, SplitIdAndFooterOffsets)> =
-        split_metadatas
-            .iter()
-            .map(|metadata| {
+    let index_uids_and_split_offsets_map: HashMap<
+        String,
+        (IndexUid, u32, SplitIdAndFooterOffsets),
+    > = split_metadatas
+        .iter()
+        .map(|metadata| {
+            (
+                metadata.split_id().to_string(),
                 (
-                    metadata.split_id().to_string(),
-                    (
-                        metadata.index_uid.clone(),
-                        extract_split_and_footer_offsets(metadata),
-                    ),
-                )
-            })
-            .collect();
+                    metadata.index_uid.clone(),
+                    metadata.schema_version,
+                    extract_split_and_footer_offsets(metadata),
+                ),
+            )
+        })
+        .collect();
 
     // Group the partial hits per split
     let mut partial_hits_map: HashMap<String, Vec<PartialHit>> = HashMap::new();
@@ -1369,7 +1442,7 @@ async fn assign_client_fetch_docs_jobs(
 
     let mut fetch_docs_req_jobs: Vec<FetchDocsJob> = Vec::new();
     for (split_id, partial_hits) in partial_hits_map {
-        let (index_uid, offsets) = index_uids_and_split_offsets_map
+        let (index_uid, schema_version, offsets) = index_uids_and_split_offsets_map
             .get(&split_id)
             .ok_or_else(|| {
                 crate::SearchError::Internal(format!(
@@ -1379,6 +1452,7 @@ async fn assign_client_fetch_docs_jobs(
             .clone();
         let fetch_docs_job = FetchDocsJob {
             index_uid: index_uid.clone(),
+            schema_version,
             offsets,
             partial_hits,
         };
@@ -1424,34 +1498,47 @@ pub fn jobs_to_leaf_request(
                 "received job for an unknown index {index_uid}. it should never happen"
             ))
         })?;
-        let doc_mapper_ord = *added_doc_mappers
-            .entry(&search_index_meta.doc_mapper_str)
-            .or_insert_with(|| {
-                let ord = leaf_search_request.doc_mappers.len();
-                leaf_search_request
-                    .doc_mappers
-                    .push(search_index_meta.doc_mapper_str.to_string());
-                ord as u32
-            });
         let index_uri_ord = leaf_search_request.index_uris.len() as u32;
         leaf_search_request
             .index_uris
             .push(search_index_meta.index_uri.to_string());
 
-        let leaf_search_request_ref = LeafRequestRef {
-            split_offsets: job_group.into_iter().map(|job| job.offsets).collect(),
-            doc_mapper_ord,
-            index_uri_ord,
-        };
-        leaf_search_request
-            .leaf_requests
-            .push(leaf_search_request_ref);
-        Ok(())
+        // Splits indexed with different schema versions are searched with their own doc mapper.
+        group_by(
+            job_group,
+            |job| &job.schema_version,
+            |schema_version_job_group| {
+                let schema_version = schema_version_job_group[0].schema_version;
+                let doc_mapper_str =
+                    search_index_meta.doc_mapper_str_for_schema_version(schema_version);
+                let doc_mapper_ord =
+                    *added_doc_mappers.entry(doc_mapper_str).or_insert_with(|| {
+                        let ord = leaf_search_request.doc_mappers.len();
+                        leaf_search_request
+                            .doc_mappers
+                            .push(doc_mapper_str.to_string());
+                        ord as u32
+                    });
+                let leaf_search_request_ref = LeafRequestRef {
+                    split_offsets: schema_version_job_group
+                        .into_iter()
+                        .map(|job| job.offsets)
+                        .collect(),
+                    doc_mapper_ord,
+                    index_uri_ord,
+                };
+                leaf_search_request
+                    .leaf_requests
+                    .push(leaf_search_request_ref);
+                Ok(())
+            },
+        )
     })?;
     Ok(leaf_search_request)
 }
 
-/// Builds a list of [`FetchDocsRequest`], one per index, from a list of [`FetchDocsJob`].
+/// Builds a list of [`FetchDocsRequest`], one per index and schema version, from a list of
+/// [`FetchDocsJob`].
 pub fn jobs_to_fetch_docs_requests(
     snippet_request_opt: Option<SnippetRequest>,
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
@@ -1462,8 +1549,8 @@ pub fn jobs_to_fetch_docs_requests(
     group_by(
         jobs,
         |job| &job.index_uid,
-        |fetch_docs_jobs| {
-            let index_uid = &fetch_docs_jobs[0].index_uid;
+        |index_fetch_docs_jobs| {
+            let index_uid = &index_fetch_docs_jobs[0].index_uid;
 
             let index_meta = indexes_metas_for_leaf_search
                 .get(index_uid)
@@ -1472,24 +1559,33 @@ pub fn jobs_to_fetch_docs_requests(
                         "received search job for an unknown index {index_uid}"
                     ))
                 })?;
-            let partial_hits: Vec<PartialHit> = fetch_docs_jobs
-                .iter()
-                .flat_map(|fetch_doc_job| fetch_doc_job.partial_hits.iter().cloned())
-                .collect();
-            let split_offsets: Vec<SplitIdAndFooterOffsets> = fetch_docs_jobs
-                .into_iter()
-                .map(|fetch_doc_job| fetch_doc_job.into())
-                .collect();
-            let fetch_docs_req = FetchDocsRequest {
-                partial_hits,
-                split_offsets,
-                index_uri: index_meta.index_uri.to_string(),
-                snippet_request: snippet_request_opt.clone(),
-                doc_mapper: index_meta.doc_mapper_str.clone(),
-            };
-            fetch_docs_requests.push(fetch_docs_req);
-
-            Ok(())
+            // Group jobs by schema version.
+            group_by(
+                index_fetch_docs_jobs,
+                |job| &job.schema_version,
+                |fetch_docs_jobs| {
+                    let schema_version = fetch_docs_jobs[0].schema_version;
+                    let partial_hits: Vec<PartialHit> = fetch_docs_jobs
+                        .iter()
+                        .flat_map(|fetch_doc_job| fetch_doc_job.partial_hits.iter().cloned())
+                        .collect();
+                    let split_offsets: Vec<SplitIdAndFooterOffsets> = fetch_docs_jobs
+                        .into_iter()
+                        .map(|fetch_doc_job| fetch_doc_job.into())
+                        .collect();
+                    let fetch_docs_req = FetchDocsRequest {
+                        partial_hits,
+                        split_offsets,
+                        index_uri: index_meta.index_uri.to_string(),
+                        snippet_request: snippet_request_opt.clone(),
+                        doc_mapper: index_meta
+                            .doc_mapper_str_for_schema_version(schema_version)
+                            .to_string(),
+                    };
+                    fetch_docs_requests.push(fetch_docs_req);
+                    Ok(())
+                },
+            )
         },
     )?;
     Ok(fetch_docs_requests)
@@ -1516,6 +1612,7 @@ mod tests {
     use tantivy::schema::{FAST, STORED, TEXT};
 
     use super::*;
+    use crate::service::deserialize_doc_mapper;
     use crate::{searcher_pool_for_test, MockSearchService};
 
     #[track_caller]
@@ -1601,6 +1698,7 @@ mod tests {
             index_id: index_id.to_string(),
             index_uri,
             doc_mapping,
+            schema_version: 0,
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
@@ -1664,6 +1762,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_request_and_build_metadatas_previous_doc_mappers_use_search_settings() {
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            ..Default::default()
+        };
+        let mut index_metadata =
+            index_metadata_for_multi_indexes_test("test-index", "ram:///test-index");
+        let previous_doc_mapping = index_metadata.index_config.doc_mapping.clone();
+        index_metadata
+            .doc_mapping_history
+            .insert(0, previous_doc_mapping);
+        index_metadata.index_config.schema_version = 1;
+
+        let request_metadata =
+            validate_request_and_build_metadata(&[index_metadata.clone()], &search_request)
+                .unwrap();
+        let index_meta_for_leaf_search = request_metadata
+            .indexes_meta_for_leaf_search
+            .get(&index_metadata.index_uid)
+            .unwrap();
+        let previous_doc_mapper =
+            deserialize_doc_mapper(index_meta_for_leaf_search.doc_mapper_str_for_schema_version(0))
+                .unwrap();
+        assert_eq!(previous_doc_mapper.default_search_fields(), ["body"]);
+    }
+
     #[test]
     fn test_validate_request_and_build_metadatas_fail_with_different_timestamps() {
         let search_request = quickwit_proto::search::SearchRequest {
@@ -1773,6 +1900,7 @@ mod tests {
             index_id: index_id.to_string(),
             index_uri,
             doc_mapping,
+            schema_version: 0,
             indexing_settings,
             search_settings,
             retention_policy_opt: Default::default(),
@@ -2435,6 +2563,78 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_root_search_records_per_index_metrics() {
+        let index_id = "test-index-root-search-metrics";
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            ..Default::default()
+        };
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata = IndexMetadata::for_test(index_id, "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(move |_index_ids_query| {
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    index_metadata.clone()
+                ]))
+            });
+        mock_metastore
+            .expect_list_splits()
+            .returning(move |_list_splits_request| {
+                let splits = vec![MockSplitBuilder::new("split1")
+                    .with_index_uid(&index_uid)
+                    .build()];
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_leaf_search().returning(
+            |_leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                Ok(quickwit_proto::search::LeafSearchResponse {
+                    num_hits: 1,
+                    partial_hits: vec![mock_partial_hit("split1", 1, 1)],
+                    failed_splits: Vec::new(),
+                    num_attempted_splits: 1,
+                    ..Default::default()
+                })
+            },
+        );
+        mock_search_service.expect_fetch_docs().returning(
+            |fetch_docs_req: quickwit_proto::search::FetchDocsRequest| {
+                Ok(quickwit_proto::search::FetchDocsResponse {
+                    hits: get_doc_for_fetch_req(fetch_docs_req),
+                })
+            },
+        );
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+
+        root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            &cluster_client,
+        )
+        .await
+        .unwrap();
+
+        let num_requests = SEARCH_METRICS
+            .root_search_requests_total
+            .with_label_values([index_id])
+            .get();
+        assert_eq!(num_requests, 1);
+        let num_timed_requests = SEARCH_METRICS
+            .root_search_request_duration_secs
+            .with_label_values([index_id])
+            .get_sample_count();
+        assert_eq!(num_timed_requests, 1);
+    }
+
     #[tokio::test]
     async fn test_root_search_multiple_splits() -> anyhow::Result<()> {
         let search_request = quickwit_proto::search::SearchRequest {
@@ -4472,4 +4672,42 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_root_search_runs_on_searching_runtime() {
+        use quickwit_common::runtimes::QuickwitRuntimes;
+
+        use crate::{SearchService, SearchServiceImpl, SearcherPool};
+
+        // Starts the test runtimes, which the search service then spawns root searches on.
+        QuickwitRuntimes::searching();
+
+        let search_thread_name = Arc::new(RwLock::new(None));
+        let search_thread_name_clone = search_thread_name.clone();
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_indexes_metadata().returning(
+            move |_list_indexes_metadata_request| {
+                let thread_name = std::thread::current().name().map(str::to_string);
+                *search_thread_name_clone.write().unwrap() = thread_name;
+                Ok(ListIndexesMetadataResponse::for_test(Vec::new()))
+            },
+        );
+        let search_service = SearchServiceImpl::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            quickwit_storage::StorageResolver::unconfigured(),
+            ClusterClient::new(SearchJobPlacer::new(SearcherPool::default())),
+            Arc::new(SearcherContext::for_test()),
+        );
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            ..Default::default()
+        };
+        // No index matches the request: we only care about where the search ran.
+        let _ = search_service.root_search(search_request).await;
+
+        let search_thread_name = search_thread_name.read().unwrap().clone().unwrap();
+        assert!(search_thread_name.starts_with("searching-"));
+    }
 }
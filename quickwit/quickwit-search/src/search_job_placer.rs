@@ -29,7 +29,7 @@ use quickwit_common::pubsub::EventSubscriber;
 use quickwit_common::rendezvous_hasher::{node_affinity, sort_by_rendez_vous_hash};
 use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
 
-use crate::{SearchJob, SearchServiceClient, SearcherPool};
+use crate::{IndexSearchRouter, SearchJob, SearchServiceClient, SearcherPool};
 
 /// Job.
 /// The unit in which distributed search is performed.
@@ -61,6 +61,8 @@ pub trait Job {
 pub struct SearchJobPlacer {
     /// Search clients pool.
     searcher_pool: SearcherPool,
+    /// Routes the jobs to the nodes owning their split.
+    search_router: IndexSearchRouter,
 }
 
 #[async_trait]
@@ -86,6 +88,14 @@ impl EventSubscriber<ReportSplitsRequest> for SearchJobPlacer {
                 .push(report_split);
         }
         for (node_addr, report_splits) in splits_per_node {
+            // The node receiving the report warms up its caches with the splits, so it becomes
+            // their owner.
+            self.search_router.affinity_map().record(
+                report_splits
+                    .iter()
+                    .map(|report_split| report_split.split_id.as_str()),
+                node_addr,
+            );
             if let Some(search_client) = nodes.get_mut(&node_addr) {
                 let report_splits_req = ReportSplitsRequest { report_splits };
                 let _ = search_client.report_splits(report_splits_req).await;
@@ -103,7 +113,16 @@ impl fmt::Debug for SearchJobPlacer {
 impl SearchJobPlacer {
     /// Returns an [`SearchJobPlacer`] from a search service client pool.
     pub fn new(searcher_pool: SearcherPool) -> Self {
-        Self { searcher_pool }
+        let search_router = IndexSearchRouter::new(searcher_pool.clone());
+        Self {
+            searcher_pool,
+            search_router,
+        }
+    }
+
+    /// Returns the router tracking the nodes owning the splits.
+    pub fn search_router(&self) -> &IndexSearchRouter {
+        &self.search_router
     }
 }
 
@@ -177,10 +196,26 @@ impl SearchJobPlacer {
         let mut job_assignments: HashMap<SocketAddr, (SearchServiceClient, Vec<J>)> =
             HashMap::with_capacity(num_nodes);
 
+        let num_jobs = jobs.len();
+        let mut num_routed_to_affinity_node = 0;
+
         for job in jobs {
             sort_by_rendez_vous_hash(&mut candidate_nodes, job.split_id());
-            // Select the least loaded node.
-            let chosen_node_idx = if candidate_nodes.len() >= 2 {
+
+            let affinity_node_idx_opt =
+                self.search_router
+                    .owner_node(job.split_id())
+                    .and_then(|owner_addr| {
+                        candidate_nodes
+                            .iter()
+                            .position(|candidate_node| candidate_node.grpc_addr == owner_addr)
+                    });
+            let chosen_node_idx = if let Some(affinity_node_idx) = affinity_node_idx_opt {
+                // Select the node owning the split.
+                num_routed_to_affinity_node += 1;
+                affinity_node_idx
+            } else if candidate_nodes.len() >= 2 {
+                // Select the least loaded node.
                 usize::from(candidate_nodes[0].load > candidate_nodes[1].load)
             } else {
                 0
@@ -194,6 +229,8 @@ impl SearchJobPlacer {
                 .1
                 .push(job);
         }
+        self.search_router
+            .observe_routing(num_jobs, num_routed_to_affinity_node);
         Ok(job_assignments.into_values())
     }
 
@@ -273,6 +310,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use quickwit_proto::search::ReportSplitsResponse;
+
     use super::*;
     use crate::{searcher_pool_for_test, MockSearchService, SearchJob};
 
@@ -411,4 +450,77 @@ mod tests {
             assert_eq!(assigned_jobs, expected_assigned_jobs);
         }
     }
+
+    #[tokio::test]
+    async fn test_search_job_placer_routes_to_affinity_node() {
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", MockSearchService::new()),
+            ("127.0.0.1:1002", MockSearchService::new()),
+        ]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+
+        let searcher_addr_1: SocketAddr = ([127, 0, 0, 1], 1001).into();
+        let searcher_addr_2: SocketAddr = ([127, 0, 0, 1], 1002).into();
+        let unavailable_searcher_addr: SocketAddr = ([127, 0, 0, 1], 1003).into();
+
+        // Without affinity, `split5` and `split6` are respectively assigned to the first and
+        // second searchers (see `test_search_job_placer`).
+        let affinity_map = search_job_placer.search_router().affinity_map();
+        affinity_map.record(["split5"], searcher_addr_2);
+        affinity_map.record(["split6"], unavailable_searcher_addr);
+
+        let jobs = vec![
+            SearchJob::for_test("split5", 5),
+            SearchJob::for_test("split6", 6),
+        ];
+        let mut assigned_jobs: Vec<(SocketAddr, Vec<SearchJob>)> = search_job_placer
+            .assign_jobs(jobs.clone(), &HashSet::default())
+            .await
+            .unwrap()
+            .map(|(client, jobs)| (client.grpc_addr(), jobs))
+            .collect();
+        assigned_jobs.sort_unstable_by_key(|(node_uid, _)| *node_uid);
+
+        let expected_assigned_jobs = vec![(
+            searcher_addr_2,
+            vec![
+                SearchJob::for_test("split6", 6),
+                SearchJob::for_test("split5", 5),
+            ],
+        )];
+        assert_eq!(assigned_jobs, expected_assigned_jobs);
+
+        // The affinity node is ignored when it is excluded.
+        let excluded_addrs = HashSet::from_iter([searcher_addr_2]);
+        let assigned_jobs: Vec<(SocketAddr, Vec<SearchJob>)> = search_job_placer
+            .assign_jobs(jobs, &excluded_addrs)
+            .await
+            .unwrap()
+            .map(|(client, jobs)| (client.grpc_addr(), jobs))
+            .collect();
+        assert_eq!(assigned_jobs.len(), 1);
+        assert_eq!(assigned_jobs[0].0, searcher_addr_1);
+    }
+
+    #[tokio::test]
+    async fn test_search_job_placer_records_reported_splits_affinity() {
+        let searcher_addr: SocketAddr = ([127, 0, 0, 1], 1001).into();
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_report_splits()
+            .returning(|_| ReportSplitsResponse {});
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let mut search_job_placer = SearchJobPlacer::new(searcher_pool);
+
+        let report_splits_request = ReportSplitsRequest {
+            report_splits: vec![ReportSplit {
+                split_id: "split1".to_string(),
+                storage_uri: "ram:///indexes/test-index".to_string(),
+            }],
+        };
+        search_job_placer.handle_event(report_splits_request).await;
+
+        let affinity_map = search_job_placer.search_router().affinity_map();
+        assert_eq!(affinity_map.get("split1"), Some(searcher_addr));
+    }
 }
@@ -47,6 +47,10 @@ pub struct SearchResponseRest {
     #[schema(value_type = Object)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aggregations: Option<JsonValue>,
+    /// Spelling suggestions, only set if they were requested and the query matched no
+    /// documents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestions: Option<Vec<String>>,
 }
 
 impl TryFrom<SearchResponse> for SearchResponseRest {
@@ -90,6 +94,12 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
             None
         };
 
+        let suggestions_opt = if !search_response.suggestions.is_empty() {
+            Some(search_response.suggestions)
+        } else {
+            None
+        };
+
         Ok(SearchResponseRest {
             num_hits: search_response.num_hits,
             hits: documents,
@@ -97,6 +107,7 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
             elapsed_time_micros: search_response.elapsed_time_micros,
             errors: search_response.errors,
             aggregations: aggregations_opt,
+            suggestions: suggestions_opt,
         })
     }
 }
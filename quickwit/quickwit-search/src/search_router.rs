@@ -0,0 +1,281 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use quickwit_proto::search::{LeafSearchRequest, LeafSearchResponse};
+use quickwit_proto::types::SplitId;
+
+use crate::metrics::SEARCH_METRICS;
+use crate::SearcherPool;
+
+/// Maximum number of splits tracked by an [`AffinityMap`]. Once reached, the map is cleared and
+/// the searches fall back to rendez-vous hashing until it is populated again.
+const MAX_NUM_TRACKED_SPLITS: usize = 1_000_000;
+
+/// In-memory map recording, for each split, the searcher node that owns it, i.e. the node that
+/// most likely holds the split in its caches. Nodes are identified by their gRPC address, like in
+/// the [`SearcherPool`].
+#[derive(Clone, Default)]
+pub struct AffinityMap {
+    inner: Arc<RwLock<HashMap<SplitId, SocketAddr>>>,
+}
+
+impl AffinityMap {
+    /// Returns the node owning the split, if any.
+    pub fn get(&self, split_id: &str) -> Option<SocketAddr> {
+        self.inner
+            .read()
+            .expect("lock should not be poisoned")
+            .get(split_id)
+            .copied()
+    }
+
+    /// Records that the splits are owned by the node `node_addr`.
+    pub fn record<'a>(&self, split_ids: impl IntoIterator<Item = &'a str>, node_addr: SocketAddr) {
+        let mut inner = self.inner.write().expect("lock should not be poisoned");
+
+        for split_id in split_ids {
+            if let Some(owner_addr) = inner.get_mut(split_id) {
+                *owner_addr = node_addr;
+                continue;
+            }
+            if inner.len() >= MAX_NUM_TRACKED_SPLITS {
+                inner.clear();
+            }
+            inner.insert(split_id.to_string(), node_addr);
+        }
+    }
+
+    /// Forgets the node owning the split, if it is still `node_addr`.
+    fn forget(&self, split_id: &str, node_addr: SocketAddr) {
+        let mut inner = self.inner.write().expect("lock should not be poisoned");
+
+        if inner.get(split_id) == Some(&node_addr) {
+            inner.remove(split_id);
+        }
+    }
+
+    /// Returns the number of tracked splits.
+    pub fn len(&self) -> usize {
+        self.inner
+            .read()
+            .expect("lock should not be poisoned")
+            .len()
+    }
+
+    /// Returns whether the map tracks no split.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Routes the per-split search jobs to the searcher node owning the split.
+///
+/// Split ownership is recorded when the newly published splits are reported to the searchers
+/// for them to warm up their caches, and every time a split is successfully searched on a node.
+/// The membership of the [`SearcherPool`] is kept up to date by the cluster gossip, so the
+/// owners that left the cluster are ignored and forgotten, and the jobs targeting their splits
+/// fall back to the other available nodes.
+#[derive(Clone, Default)]
+pub struct IndexSearchRouter {
+    searcher_pool: SearcherPool,
+    affinity_map: AffinityMap,
+}
+
+impl fmt::Debug for IndexSearchRouter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IndexSearchRouter")
+            .field("num_tracked_splits", &self.affinity_map.len())
+            .finish()
+    }
+}
+
+impl IndexSearchRouter {
+    /// Creates a router for the nodes of the searcher pool.
+    pub fn new(searcher_pool: SearcherPool) -> Self {
+        Self {
+            searcher_pool,
+            affinity_map: AffinityMap::default(),
+        }
+    }
+
+    /// Returns the affinity map of the router.
+    pub fn affinity_map(&self) -> &AffinityMap {
+        &self.affinity_map
+    }
+
+    /// Returns the node owning the split if it is still part of the searcher pool.
+    pub fn owner_node(&self, split_id: &str) -> Option<SocketAddr> {
+        let owner_addr = self.affinity_map.get(split_id)?;
+
+        if self.searcher_pool.contains_key(&owner_addr) {
+            Some(owner_addr)
+        } else {
+            self.affinity_map.forget(split_id, owner_addr);
+            None
+        }
+    }
+
+    /// Records the splits successfully searched on the node `node_addr` by a leaf search.
+    pub fn record_leaf_search(
+        &self,
+        leaf_search_request: &LeafSearchRequest,
+        leaf_search_response: &LeafSearchResponse,
+        node_addr: SocketAddr,
+    ) {
+        let failed_split_ids: HashSet<&str> = leaf_search_response
+            .failed_splits
+            .iter()
+            .map(|split_search_error| split_search_error.split_id.as_str())
+            .collect();
+        let searched_split_ids = leaf_search_request
+            .leaf_requests
+            .iter()
+            .flat_map(|leaf_request| leaf_request.split_offsets.iter())
+            .map(|split_offsets| split_offsets.split_id.as_str())
+            .filter(|split_id| !failed_split_ids.contains(split_id));
+        self.affinity_map.record(searched_split_ids, node_addr);
+    }
+
+    /// Updates the routing metrics after `num_routed_jobs` jobs were routed, among which
+    /// `num_routed_to_affinity_node` to the node owning their split.
+    pub(crate) fn observe_routing(
+        &self,
+        num_routed_jobs: usize,
+        num_routed_to_affinity_node: usize,
+    ) {
+        if num_routed_jobs == 0 {
+            return;
+        }
+        SEARCH_METRICS
+            .routed_search_jobs_total
+            .inc_by(num_routed_jobs as u64);
+        SEARCH_METRICS
+            .routed_to_affinity_node_total
+            .inc_by(num_routed_to_affinity_node as u64);
+
+        let routed_to_affinity_node_fraction = SEARCH_METRICS.routed_to_affinity_node_total.get()
+            as f64
+            / SEARCH_METRICS.routed_search_jobs_total.get() as f64;
+        SEARCH_METRICS
+            .routed_to_affinity_node_fraction
+            .set(routed_to_affinity_node_fraction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::search::{
+        LeafRequestRef, SearchRequest, SplitIdAndFooterOffsets, SplitSearchError,
+    };
+
+    use super::*;
+    use crate::{searcher_pool_for_test, MockSearchService};
+
+    #[test]
+    fn test_affinity_map() {
+        let affinity_map = AffinityMap::default();
+        assert!(affinity_map.is_empty());
+
+        let node_addr_1: SocketAddr = ([127, 0, 0, 1], 1001).into();
+        let node_addr_2: SocketAddr = ([127, 0, 0, 1], 1002).into();
+
+        affinity_map.record(["split-1", "split-2"], node_addr_1);
+        assert_eq!(affinity_map.len(), 2);
+        assert_eq!(affinity_map.get("split-1"), Some(node_addr_1));
+        assert_eq!(affinity_map.get("split-2"), Some(node_addr_1));
+        assert_eq!(affinity_map.get("split-3"), None);
+
+        affinity_map.record(["split-2"], node_addr_2);
+        assert_eq!(affinity_map.len(), 2);
+        assert_eq!(affinity_map.get("split-2"), Some(node_addr_2));
+
+        affinity_map.forget("split-2", node_addr_1);
+        assert_eq!(affinity_map.get("split-2"), Some(node_addr_2));
+
+        affinity_map.forget("split-2", node_addr_2);
+        assert_eq!(affinity_map.get("split-2"), None);
+    }
+
+    #[test]
+    fn test_index_search_router_owner_node() {
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", MockSearchService::new())]);
+        let search_router = IndexSearchRouter::new(searcher_pool);
+
+        let available_node_addr: SocketAddr = ([127, 0, 0, 1], 1001).into();
+        let unavailable_node_addr: SocketAddr = ([127, 0, 0, 1], 1002).into();
+
+        search_router
+            .affinity_map()
+            .record(["split-1"], available_node_addr);
+        search_router
+            .affinity_map()
+            .record(["split-2"], unavailable_node_addr);
+
+        assert_eq!(
+            search_router.owner_node("split-1"),
+            Some(available_node_addr)
+        );
+        assert_eq!(search_router.owner_node("split-2"), None);
+        assert_eq!(search_router.owner_node("split-3"), None);
+
+        // The owners that left the cluster are forgotten.
+        assert_eq!(search_router.affinity_map().get("split-2"), None);
+    }
+
+    #[test]
+    fn test_index_search_router_record_leaf_search() {
+        let search_router = IndexSearchRouter::default();
+        let node_addr: SocketAddr = ([127, 0, 0, 1], 1001).into();
+
+        let leaf_search_request = LeafSearchRequest {
+            search_request: Some(SearchRequest::default()),
+            leaf_requests: vec![LeafRequestRef {
+                doc_mapper_ord: 0,
+                index_uri_ord: 0,
+                split_offsets: vec![
+                    SplitIdAndFooterOffsets {
+                        split_id: "split-1".to_string(),
+                        ..Default::default()
+                    },
+                    SplitIdAndFooterOffsets {
+                        split_id: "split-2".to_string(),
+                        ..Default::default()
+                    },
+                ],
+            }],
+            ..Default::default()
+        };
+        let leaf_search_response = LeafSearchResponse {
+            failed_splits: vec![SplitSearchError {
+                error: "split search error".to_string(),
+                split_id: "split-2".to_string(),
+                retryable_error: true,
+            }],
+            ..Default::default()
+        };
+        search_router.record_leaf_search(&leaf_search_request, &leaf_search_response, node_addr);
+
+        assert_eq!(search_router.affinity_map().get("split-1"), Some(node_addr));
+        assert_eq!(search_router.affinity_map().get("split-2"), None);
+    }
+}
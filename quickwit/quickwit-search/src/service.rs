@@ -24,6 +24,8 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use quickwit_common::memory_pressure::MemoryPressureMonitor;
+use quickwit_common::runtimes::{AbortOnDropJoinHandle, QuickwitRuntimes};
 use quickwit_common::uri::Uri;
 use quickwit_config::SearcherConfig;
 use quickwit_doc_mapper::DocMapper;
@@ -35,13 +37,18 @@ use quickwit_proto::search::{
     ListTermsRequest, ListTermsResponse, PutKvRequest, ReportSplitsRequest, ReportSplitsResponse,
     ScrollRequest, SearchRequest, SearchResponse, SearchStreamRequest, SnippetRequest,
 };
+use quickwit_proto::types::IndexId;
 use quickwit_storage::{
-    MemorySizedCache, QuickwitCache, SplitCache, StorageCache, StorageResolver,
+    AdaptiveCacheCapacityConfig, AdaptiveMemorySizedCache, QuickwitCache, SplitCache, StorageCache,
+    StorageResolver,
 };
 use tantivy::aggregation::AggregationLimits;
 use tokio::sync::Semaphore;
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::Instrument;
 
+use crate::analyze_query::{root_analyze_query, AnalyzeQueryResponse};
+use crate::field_stats::{root_field_stats, FieldStatsCache, FieldStatsResponse};
 use crate::leaf::multi_leaf_search;
 use crate::leaf_cache::LeafSearchCache;
 use crate::list_fields::{leaf_list_fields, root_list_fields};
@@ -50,8 +57,12 @@ use crate::list_terms::{leaf_list_terms, root_list_terms};
 use crate::root::fetch_docs_phase;
 use crate::scroll_context::{MiniKV, ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_stream::{leaf_search_stream, root_search_stream};
+use crate::suggestion::SearchSuggestionService;
 use crate::{fetch_docs, root_search, ClusterClient, SearchError};
 
+/// Fraction of the memory available on the host the split footer cache may use.
+const SPLIT_FOOTER_CACHE_TARGET_MEMORY_FRACTION: f64 = 0.1;
+
 #[derive(Clone)]
 /// The search service implementation.
 pub struct SearchServiceImpl {
@@ -60,6 +71,7 @@ pub struct SearchServiceImpl {
     cluster_client: ClusterClient,
     searcher_context: Arc<SearcherContext>,
     search_after_cache: MiniKV,
+    field_stats_cache: FieldStatsCache,
 }
 
 /// Trait representing a search service.
@@ -149,6 +161,22 @@ pub trait SearchService: 'static + Send + Sync {
         &self,
         list_fields: LeafListFieldsRequest,
     ) -> crate::Result<ListFieldsResponse>;
+
+    /// Describes how a query is parsed against an index and which terms it searches, without
+    /// executing it.
+    async fn root_analyze_query(
+        &self,
+        index_id: IndexId,
+        query: String,
+    ) -> crate::Result<AnalyzeQueryResponse>;
+
+    /// Computes statistics about the distribution of the values of a fast field of an index.
+    /// Results are cached for a few minutes.
+    async fn root_field_stats(
+        &self,
+        index_id: IndexId,
+        field: String,
+    ) -> crate::Result<FieldStatsResponse>;
 }
 
 impl SearchServiceImpl {
@@ -165,6 +193,7 @@ impl SearchServiceImpl {
             cluster_client,
             searcher_context,
             search_after_cache: MiniKV::default(),
+            field_stats_cache: FieldStatsCache::default(),
         }
     }
 }
@@ -179,13 +208,29 @@ pub fn deserialize_doc_mapper(doc_mapper_str: &str) -> crate::Result<Arc<dyn Doc
 #[async_trait]
 impl SearchService for SearchServiceImpl {
     async fn root_search(&self, search_request: SearchRequest) -> crate::Result<SearchResponse> {
-        let search_result = root_search(
-            &self.searcher_context,
-            search_request,
-            self.metastore.clone(),
-            &self.cluster_client,
-        )
-        .await?;
+        let searcher_context = self.searcher_context.clone();
+        let metastore = self.metastore.clone();
+        let cluster_client = self.cluster_client.clone();
+        // Root searches are run on the searching runtime so that they are not slowed down by
+        // CPU-heavy indexing tasks. The task is aborted if the request is cancelled.
+        let join_handle = QuickwitRuntimes::searching_or_current().spawn(
+            async move {
+                root_search(
+                    &searcher_context,
+                    search_request,
+                    metastore,
+                    &cluster_client,
+                )
+                .await
+            }
+            .in_current_span(),
+        );
+        let search_result =
+            AbortOnDropJoinHandle::new(join_handle)
+                .await
+                .map_err(|join_error| {
+                    SearchError::Internal(format!("root search task failed: {join_error}"))
+                })??;
         Ok(search_result)
     }
 
@@ -352,6 +397,41 @@ impl SearchService for SearchServiceImpl {
         )
         .await
     }
+
+    async fn root_analyze_query(
+        &self,
+        index_id: IndexId,
+        query: String,
+    ) -> crate::Result<AnalyzeQueryResponse> {
+        root_analyze_query(
+            &index_id,
+            &query,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await
+    }
+
+    async fn root_field_stats(
+        &self,
+        index_id: IndexId,
+        field: String,
+    ) -> crate::Result<FieldStatsResponse> {
+        if let Some(field_stats) = self.field_stats_cache.get(&index_id, &field) {
+            return Ok(field_stats);
+        }
+        let field_stats = root_field_stats(
+            &self.searcher_context,
+            &index_id,
+            &field,
+            self.metastore.clone(),
+            &self.cluster_client,
+        )
+        .await?;
+        self.field_stats_cache
+            .put(index_id, field, field_stats.clone());
+        Ok(field_stats)
+    }
 }
 
 pub(crate) async fn scroll(
@@ -424,6 +504,8 @@ pub(crate) async fn scroll(
         scroll_id: Some(next_scroll_id.to_string()),
         errors: Vec::new(),
         aggregation: None,
+        suggestions: Vec::new(),
+        partial_failures: Vec::new(),
     })
 }
 /// [`SearcherContext`] provides a common set of variables
@@ -437,7 +519,7 @@ pub struct SearcherContext {
     /// Counting semaphore to limit concurrent leaf search split requests.
     pub leaf_search_split_semaphore: Arc<Semaphore>,
     /// Split footer cache.
-    pub split_footer_cache: MemorySizedCache<String>,
+    pub split_footer_cache: AdaptiveMemorySizedCache<String>,
     /// Counting semaphore to limit concurrent split stream requests.
     pub split_stream_semaphore: Semaphore,
     /// Recent sub-query cache.
@@ -446,6 +528,8 @@ pub struct SearcherContext {
     pub split_cache_opt: Option<Arc<SplitCache>>,
     /// List fields cache. Caches the list fields response for a given split.
     pub list_fields_cache: ListFieldsCache,
+    /// Generates "did you mean" suggestions for queries matching no documents.
+    pub suggestion_service: SearchSuggestionService,
 }
 
 impl std::fmt::Debug for SearcherContext {
@@ -471,9 +555,17 @@ impl SearcherContext {
     /// Creates a new searcher context, given a searcher config, and an optional `SplitCache`.
     pub fn new(searcher_config: SearcherConfig, split_cache_opt: Option<Arc<SplitCache>>) -> Self {
         let capacity_in_bytes = searcher_config.split_footer_cache_capacity.as_u64() as usize;
-        let global_split_footer_cache = MemorySizedCache::with_capacity_in_bytes(
-            capacity_in_bytes,
-            &quickwit_storage::STORAGE_METRICS.split_footer_cache,
+        // The configured capacity is an upper bound: the split footer cache shrinks when the
+        // host runs low on memory.
+        let split_footer_cache_capacity_config = AdaptiveCacheCapacityConfig {
+            target_memory_fraction: SPLIT_FOOTER_CACHE_TARGET_MEMORY_FRACTION,
+            min_capacity_in_bytes: capacity_in_bytes / 10,
+            max_capacity_in_bytes: capacity_in_bytes,
+        };
+        let global_split_footer_cache = AdaptiveMemorySizedCache::new(
+            split_footer_cache_capacity_config,
+            MemoryPressureMonitor::default(),
+            &quickwit_storage::CACHE_METRICS.split_footer_cache,
         );
         let leaf_search_split_semaphore = Arc::new(Semaphore::new(
             searcher_config.max_num_concurrent_split_searches,
@@ -496,6 +588,7 @@ impl SearcherContext {
             leaf_search_cache,
             list_fields_cache,
             split_cache_opt,
+            suggestion_service: SearchSuggestionService::default(),
         }
     }
 
@@ -0,0 +1,170 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use quickwit_proto::search::SplitIdAndFooterOffsets;
+use quickwit_storage::Storage;
+use tokio::task::JoinHandle;
+use tracing::{debug, Instrument};
+
+use crate::leaf::get_split_footer_from_cache_or_fetch;
+use crate::service::SearcherContext;
+
+/// Fetches the footers (hotcache and Tantivy footer) of the splits about to be searched into the
+/// split footer cache, ahead of the leaf search tasks.
+///
+/// A leaf search can only run `max_num_concurrent_split_searches` split searches at once, so the
+/// splits at the end of the queue usually wait for a permit before fetching their footer. The
+/// prefetcher fetches their footers in the meantime, removing that round trip to the storage from
+/// the critical path of the search.
+pub struct SplitPrefetcher {
+    searcher_context: Arc<SearcherContext>,
+    index_storage: Arc<dyn Storage>,
+}
+
+impl SplitPrefetcher {
+    /// Creates a prefetcher for the splits of the index stored in `index_storage`.
+    pub fn new(searcher_context: Arc<SearcherContext>, index_storage: Arc<dyn Storage>) -> Self {
+        Self {
+            searcher_context,
+            index_storage,
+        }
+    }
+
+    /// Spawns a task prefetching the footers of `splits`, in order, with at most
+    /// `prefetch_concurrency` concurrent requests. Returns `None` if prefetching is disabled.
+    ///
+    /// The task is aborted when the returned handle is dropped. Failures are ignored: the leaf
+    /// search fetches the footers missing from the cache and reports the errors.
+    pub fn spawn_prefetch(self, splits: Vec<SplitIdAndFooterOffsets>) -> Option<PrefetchHandle> {
+        let prefetch_concurrency = self.searcher_context.searcher_config.prefetch_concurrency;
+
+        if prefetch_concurrency == 0 || splits.is_empty() {
+            return None;
+        }
+        let prefetch_future = async move {
+            let split_footer_cache = &self.searcher_context.split_footer_cache;
+            let index_storage = &self.index_storage;
+
+            futures::stream::iter(splits)
+                .map(|split| async move {
+                    if let Err(error) = get_split_footer_from_cache_or_fetch(
+                        index_storage.clone(),
+                        &split,
+                        split_footer_cache,
+                    )
+                    .await
+                    {
+                        debug!(split_id=%split.split_id, error=?error, "failed to prefetch split footer");
+                    }
+                })
+                .buffer_unordered(prefetch_concurrency)
+                .collect::<()>()
+                .await;
+        };
+        let join_handle = tokio::spawn(prefetch_future.in_current_span());
+        Some(PrefetchHandle { join_handle })
+    }
+}
+
+/// Aborts the prefetch task on drop.
+pub struct PrefetchHandle {
+    join_handle: JoinHandle<()>,
+}
+
+impl PrefetchHandle {
+    /// Waits for the prefetch task to complete.
+    pub async fn join(mut self) {
+        let _ = (&mut self.join_handle).await;
+    }
+}
+
+impl Drop for PrefetchHandle {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_storage::RamStorage;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_split_prefetcher() {
+        let searcher_context = Arc::new(SearcherContext::for_test());
+        let index_storage = Arc::new(RamStorage::default());
+        let mut splits = Vec::new();
+
+        for split_id in ["split-1", "split-2", "split-3"] {
+            let split_path = quickwit_common::split_file_path(split_id);
+            let payload = format!("{split_id}-payload-footer").into_bytes();
+            index_storage
+                .put(&split_path, Box::new(payload.clone()))
+                .await
+                .unwrap();
+            splits.push(SplitIdAndFooterOffsets {
+                split_id: split_id.to_string(),
+                split_footer_start: payload.len() as u64 - 6,
+                split_footer_end: payload.len() as u64,
+                timestamp_start: None,
+                timestamp_end: None,
+            });
+        }
+        // The footer of missing splits cannot be prefetched.
+        splits.push(SplitIdAndFooterOffsets {
+            split_id: "split-4".to_string(),
+            split_footer_start: 0,
+            split_footer_end: 6,
+            timestamp_start: None,
+            timestamp_end: None,
+        });
+
+        let prefetch_handle = SplitPrefetcher::new(searcher_context.clone(), index_storage)
+            .spawn_prefetch(splits)
+            .unwrap();
+        prefetch_handle.join().await;
+
+        for split_id in ["split-1", "split-2", "split-3"] {
+            let footer = searcher_context.split_footer_cache.get(split_id).unwrap();
+            assert_eq!(footer.as_slice(), b"footer");
+        }
+        assert!(searcher_context.split_footer_cache.get("split-4").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_split_prefetcher_disabled() {
+        let mut searcher_context = SearcherContext::for_test();
+        searcher_context.searcher_config.prefetch_concurrency = 0;
+        let splits = vec![SplitIdAndFooterOffsets {
+            split_id: "split-1".to_string(),
+            split_footer_start: 0,
+            split_footer_end: 6,
+            timestamp_start: None,
+            timestamp_end: None,
+        }];
+        let prefetch_handle_opt =
+            SplitPrefetcher::new(Arc::new(searcher_context), Arc::new(RamStorage::default()))
+                .spawn_prefetch(splits);
+        assert!(prefetch_handle_opt.is_none());
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Context;
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use quickwit_proto::search::TermSuggestion;
+use tantivy::query::Query;
+use tantivy::schema::Field;
+use tantivy::{Searcher, Term};
+use tantivy_fst::Automaton;
+
+use crate::leaf::warm_up_term_dict_fields;
+
+/// Maximum Levenshtein distance between a query term and a suggested term.
+const MAX_SUGGESTION_DISTANCE: u8 = 2;
+
+/// Maximum number of suggestions returned for a search request.
+pub(crate) const MAX_NUM_SUGGESTIONS: usize = 5;
+
+/// Wraps a Levenshtein DFA so that it can be used to search a term dictionary.
+///
+/// The term dictionary only explores the branches for which the automaton can still reach a
+/// matching state, so the scan is bounded by the automaton rather than by the dictionary size.
+struct DfaWrapper(DFA);
+
+impl Automaton for DfaWrapper {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        *state != SINK_STATE
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+/// Generates "did you mean" suggestions for queries that do not match any document.
+///
+/// Suggestions are the terms of the split term dictionaries within a Levenshtein distance of
+/// [`MAX_SUGGESTION_DISTANCE`] of the text terms of the query.
+pub struct SearchSuggestionService {
+    // Building the automaton builder is expensive (it precomputes the parametric automaton), so
+    // we build it once and reuse it for every request.
+    automaton_builder: LevenshteinAutomatonBuilder,
+}
+
+impl Default for SearchSuggestionService {
+    fn default() -> Self {
+        SearchSuggestionService {
+            automaton_builder: LevenshteinAutomatonBuilder::new(MAX_SUGGESTION_DISTANCE, true),
+        }
+    }
+}
+
+impl SearchSuggestionService {
+    /// Returns the best suggestions for the text terms of `query` found in the term dictionaries
+    /// of the searcher's segments.
+    pub async fn suggest(
+        &self,
+        searcher: &Searcher,
+        query: &dyn Query,
+    ) -> anyhow::Result<Vec<TermSuggestion>> {
+        let query_terms = extract_text_terms(query);
+        if query_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let fields: HashSet<Field> = query_terms.iter().map(|(field, _)| *field).collect();
+        warm_up_term_dict_fields(searcher, &fields).await?;
+        self.suggest_from_term_dictionaries(searcher, &query_terms)
+    }
+
+    /// Scans the (already warmed up) term dictionaries. This does not perform any IO.
+    fn suggest_from_term_dictionaries(
+        &self,
+        searcher: &Searcher,
+        query_terms: &[(Field, String)],
+    ) -> anyhow::Result<Vec<TermSuggestion>> {
+        let mut suggestions = Vec::new();
+        for (field, query_text) in query_terms {
+            let dfa = self.automaton_builder.build_dfa(query_text);
+            for segment_reader in searcher.segment_readers() {
+                let inverted_index = segment_reader.inverted_index(*field)?;
+                let segment_dfa = self.automaton_builder.build_dfa(query_text);
+                let mut stream = inverted_index
+                    .terms()
+                    .search(DfaWrapper(segment_dfa))
+                    .into_stream()
+                    .context("failed to create stream over term dictionary")?;
+                while stream.advance() {
+                    let Ok(term_text) = std::str::from_utf8(stream.key()) else {
+                        continue;
+                    };
+                    if term_text == query_text {
+                        continue;
+                    }
+                    let Distance::Exact(distance) = dfa.eval(term_text) else {
+                        continue;
+                    };
+                    suggestions.push(TermSuggestion {
+                        term: term_text.to_string(),
+                        distance: distance as u32,
+                        doc_freq: stream.value().doc_freq as u64,
+                    });
+                }
+            }
+        }
+        Ok(merge_term_suggestions(suggestions))
+    }
+}
+
+/// Returns the `(field, text)` pairs of the string terms of the query.
+fn extract_text_terms(query: &dyn Query) -> Vec<(Field, String)> {
+    let mut query_terms: Vec<(Field, String)> = Vec::new();
+    query.query_terms(&mut |term: &Term, _need_position: bool| {
+        if let Some(text) = term.value().as_str() {
+            let query_term = (term.field(), text.to_string());
+            if !query_terms.contains(&query_term) {
+                query_terms.push(query_term);
+            }
+        }
+    });
+    query_terms
+}
+
+/// Merges suggestions coming from different segments, splits or query terms, and keeps the
+/// [`MAX_NUM_SUGGESTIONS`] best ones.
+///
+/// Suggestions are ranked by increasing distance, then by decreasing document frequency.
+pub(crate) fn merge_term_suggestions(
+    suggestions: impl IntoIterator<Item = TermSuggestion>,
+) -> Vec<TermSuggestion> {
+    let mut merged_suggestions: HashMap<String, TermSuggestion> = HashMap::new();
+    for suggestion in suggestions {
+        match merged_suggestions.entry(suggestion.term.clone()) {
+            Entry::Occupied(mut entry) => {
+                let merged_suggestion = entry.get_mut();
+                merged_suggestion.distance = merged_suggestion.distance.min(suggestion.distance);
+                merged_suggestion.doc_freq += suggestion.doc_freq;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(suggestion);
+            }
+        }
+    }
+    let mut sorted_suggestions: Vec<TermSuggestion> = merged_suggestions.into_values().collect();
+    sorted_suggestions.sort_by(|left, right| {
+        left.distance
+            .cmp(&right.distance)
+            .then_with(|| right.doc_freq.cmp(&left.doc_freq))
+            .then_with(|| left.term.cmp(&right.term))
+    });
+    sorted_suggestions.truncate(MAX_NUM_SUGGESTIONS);
+    sorted_suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::query::TermQuery;
+    use tantivy::schema::{IndexRecordOption, Schema, TEXT};
+    use tantivy::{doc, Index};
+
+    use super::*;
+
+    fn suggestion(term: &str, distance: u32, doc_freq: u64) -> TermSuggestion {
+        TermSuggestion {
+            term: term.to_string(),
+            distance,
+            doc_freq,
+        }
+    }
+
+    #[test]
+    fn test_merge_term_suggestions() {
+        let merged_suggestions = merge_term_suggestions([
+            suggestion("hallo", 1, 3),
+            suggestion("help", 2, 10),
+            suggestion("hello", 1, 2),
+            suggestion("hello", 2, 2),
+            suggestion("hellos", 1, 1),
+            suggestion("jello", 2, 1),
+            suggestion("yellow", 2, 1),
+            suggestion("cello", 2, 1),
+        ]);
+        assert_eq!(
+            merged_suggestions,
+            vec![
+                suggestion("hello", 1, 4),
+                suggestion("hallo", 1, 3),
+                suggestion("hellos", 1, 1),
+                suggestion("help", 2, 10),
+                suggestion("cello", 2, 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_suggestion_service() {
+        let mut schema_builder = Schema::builder();
+        let body_field = schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_with_num_threads(1, 15_000_000).unwrap();
+        index_writer
+            .add_document(doc!(body_field => "hello world"))
+            .unwrap();
+        index_writer
+            .add_document(doc!(body_field => "hello"))
+            .unwrap();
+        index_writer
+            .add_document(doc!(body_field => "help"))
+            .unwrap();
+        index_writer
+            .add_document(doc!(body_field => "quickwit"))
+            .unwrap();
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+
+        let suggestion_service = SearchSuggestionService::default();
+        let query = TermQuery::new(
+            Term::from_field_text(body_field, "helo"),
+            IndexRecordOption::Basic,
+        );
+        let suggestions = suggestion_service.suggest(&searcher, &query).await.unwrap();
+        assert_eq!(
+            suggestions,
+            vec![suggestion("hello", 1, 2), suggestion("help", 1, 1)]
+        );
+
+        let query = TermQuery::new(
+            Term::from_field_text(body_field, "elasticsearch"),
+            IndexRecordOption::Basic,
+        );
+        let suggestions = suggestion_service.suggest(&searcher, &query).await.unwrap();
+        assert!(suggestions.is_empty());
+    }
+}
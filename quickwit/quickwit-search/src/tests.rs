@@ -87,6 +87,152 @@ async fn test_single_node_simple() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_node_search_on_frozen_index() -> anyhow::Result<()> {
+    use quickwit_config::{RetentionPolicy, SearchSettings};
+    use quickwit_metastore::{IndexState, UpdateIndexRequestExt};
+    use quickwit_proto::metastore::{MetastoreService, UpdateIndexRequest};
+
+    let index_id = "single-node-frozen-index";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    test_sandbox
+        .add_documents(vec![json!({"body": "frozen beagle"})])
+        .await?;
+
+    let update_index_request = UpdateIndexRequest::try_from_updates(
+        test_sandbox.index_uid(),
+        &SearchSettings {
+            default_search_fields: vec!["body".to_string()],
+        },
+        &None::<RetentionPolicy>,
+        None,
+        Some(IndexState::Frozen),
+        None,
+    )?;
+    test_sandbox
+        .metastore()
+        .update_index(update_index_request)
+        .await?;
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("beagle", &["body"]),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 1);
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_after_schema_upgrade() -> anyhow::Result<()> {
+    use quickwit_config::{ConfigFormat, DocMapping, RetentionPolicy, SearchSettings};
+    use quickwit_metastore::UpdateIndexRequestExt;
+    use quickwit_proto::metastore::{MetastoreService, UpdateIndexRequest};
+
+    let index_id = "single-node-schema-upgrade";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: count
+                type: i64
+                fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    test_sandbox
+        .add_documents(vec![json!({"body": "first beagle", "count": 1})])
+        .await?;
+
+    let new_doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+              - name: count
+                type: f64
+                fast: true
+              - name: severity
+                type: text
+        "#;
+    let new_doc_mapping: DocMapping = ConfigFormat::Yaml.parse(new_doc_mapping_yaml.as_bytes())?;
+    let update_index_request = UpdateIndexRequest::try_from_updates(
+        test_sandbox.index_uid(),
+        &SearchSettings {
+            default_search_fields: vec!["body".to_string()],
+        },
+        &None::<RetentionPolicy>,
+        Some(&new_doc_mapping),
+        None,
+        None,
+    )?;
+    test_sandbox
+        .metastore()
+        .update_index(update_index_request)
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"body": "second beagle", "count": 2.5, "severity": "info"}),
+        ])
+        .await?;
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("beagle", &["body"]),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 2);
+    let bodies: BTreeSet<String> = single_node_result
+        .hits
+        .iter()
+        .map(|hit| {
+            let hit_json: JsonValue = serde_json::from_str(&hit.json).unwrap();
+            hit_json["body"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        bodies,
+        BTreeSet::from(["first beagle".to_string(), "second beagle".to_string()])
+    );
+
+    // The field added by the upgrade does not exist in the splits indexed before.
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("severity:info", &["body"]),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 1);
+    let hit_json: JsonValue = serde_json::from_str(&single_node_result.hits[0].json)?;
+    assert_eq!(hit_json["body"], "second beagle");
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_node_termset() -> anyhow::Result<()> {
     let index_id = "single-node-termset-1";
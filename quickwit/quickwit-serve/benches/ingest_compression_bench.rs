@@ -0,0 +1,106 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::io::{Read, Write};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+const NUM_LOG_LINES: usize = 10_000;
+
+const LEVELS: [&str; 4] = ["INFO", "INFO", "WARN", "ERROR"];
+
+const SERVICES: [&str; 5] = ["api-gateway", "auth", "billing", "checkout", "search"];
+
+const MESSAGES: [&str; 5] = [
+    "request completed",
+    "cache miss, fetching from upstream",
+    "connection reset by peer",
+    "slow query detected",
+    "user session refreshed",
+];
+
+/// Generates an NDJSON payload resembling the application logs typically sent to the ingest API.
+fn generate_log_payload() -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    for i in 0..NUM_LOG_LINES {
+        writeln!(
+            payload,
+            r#"{{"timestamp":"2024-05-01T12:{:02}:{:02}.{:03}Z","level":"{}","service":"{}","trace_id":"{:032x}","http.status_code":{},"duration_ms":{},"message":"{}"}}"#,
+            (i / 60) % 60,
+            i % 60,
+            (i * 7) % 1_000,
+            LEVELS[i % LEVELS.len()],
+            SERVICES[(i / 3) % SERVICES.len()],
+            (i as u128).wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c834),
+            [200, 200, 201, 404, 500][i % 5],
+            (i * 13) % 2_000,
+            MESSAGES[(i / 7) % MESSAGES.len()],
+        )
+        .unwrap();
+    }
+    payload
+}
+
+fn gzip_compress(payload: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(payload).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn gzip_decompress(compressed: &[u8]) -> Vec<u8> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(compressed)
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    decompressed
+}
+
+pub fn ingest_compression_benchmark(c: &mut Criterion) {
+    let payload = generate_log_payload();
+    let gzip_payload = gzip_compress(&payload);
+    let zstd_payload = zstd::encode_all(payload.as_slice(), 0).unwrap();
+
+    // Criterion only reports timings, so the bandwidth reduction is printed once upfront.
+    for (encoding, compressed_payload) in [("gzip", &gzip_payload), ("zstd", &zstd_payload)] {
+        println!(
+            "{encoding}: {} bytes -> {} bytes ({:.1}% bandwidth reduction)",
+            payload.len(),
+            compressed_payload.len(),
+            100.0 * (1.0 - compressed_payload.len() as f64 / payload.len() as f64)
+        );
+    }
+    let mut group = c.benchmark_group("ingest-compression");
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("gzip-compress", |b| b.iter(|| gzip_compress(&payload)));
+    group.bench_function("gzip-decompress", |b| {
+        b.iter(|| gzip_decompress(&gzip_payload))
+    });
+    group.bench_function("zstd-compress", |b| {
+        b.iter(|| zstd::encode_all(payload.as_slice(), 0).unwrap())
+    });
+    group.bench_function("zstd-decompress", |b| {
+        b.iter(|| zstd::decode_all(zstd_payload.as_slice()).unwrap())
+    });
+}
+
+criterion_group!(benches, ingest_compression_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,411 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use itertools::Itertools;
+use quickwit_proto::metastore::{
+    CreateIndexAliasRequest, EntityKind, IndexAlias, ListIndexAliasesRequest, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceClient, UpdateIndexAliasRequest,
+};
+use quickwit_proto::types::IndexId;
+use serde::{Deserialize, Serialize};
+use warp::reject::Rejection;
+use warp::{Filter, Reply};
+
+use crate::format::extract_format_from_qs;
+use crate::rest_api_response::into_rest_api_response;
+use crate::with_arg;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        create_index_alias,
+        get_index_alias,
+        update_index_alias,
+        list_index_aliases,
+    ),
+    components(schemas(IndexAlias, IndexAliasUpdates))
+)]
+pub(crate) struct IndexAliasApi;
+
+/// The body of the index alias update request. The indexes are removed from the alias before the
+/// new ones are added, and the whole update is applied atomically.
+#[derive(Debug, Default, Eq, PartialEq, Deserialize, Serialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IndexAliasUpdates {
+    /// IDs of the indexes to add to the alias.
+    #[serde(default)]
+    pub add: Vec<IndexId>,
+    /// IDs of the indexes to remove from the alias. The update fails if any of them is not part
+    /// of the alias.
+    #[serde(default)]
+    pub remove: Vec<IndexId>,
+}
+
+pub(crate) fn index_alias_api_handlers(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    create_index_alias_handler(metastore.clone())
+        .or(get_index_alias_handler(metastore.clone()))
+        .or(update_index_alias_handler(metastore.clone()))
+        .or(list_index_aliases_handler(metastore.clone()))
+}
+
+fn create_index_alias_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("aliases")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .then(create_index_alias)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Aliases",
+    path = "/aliases",
+    request_body = IndexAlias,
+    responses(
+        (status = 200, description = "The index alias was successfully created.", body = IndexAlias),
+        (status = 404, description = "One of the indexes was not found.")
+    ),
+)]
+/// Creates an index alias resolving to the indexes identified by `index_ids`.
+async fn create_index_alias(
+    index_alias: IndexAlias,
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexAlias> {
+    let create_index_alias_request = CreateIndexAliasRequest {
+        alias_id: index_alias.alias_id.clone(),
+        index_ids: index_alias.index_ids.clone(),
+    };
+    metastore
+        .create_index_alias(create_index_alias_request)
+        .await?;
+    let index_ids = index_alias
+        .index_ids
+        .into_iter()
+        .sorted_unstable()
+        .dedup()
+        .collect();
+    let created_index_alias = IndexAlias {
+        alias_id: index_alias.alias_id,
+        index_ids,
+    };
+    Ok(created_index_alias)
+}
+
+fn get_index_alias_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("aliases" / String)
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(get_index_alias)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Aliases",
+    path = "/aliases/{alias_id}",
+    responses(
+        (status = 200, description = "The index alias was successfully resolved.", body = IndexAlias),
+        (status = 404, description = "The index alias was not found.")
+    ),
+    params(
+        ("alias_id" = String, Path, description = "The ID of the index alias to resolve."),
+    )
+)]
+/// Resolves the index alias identified by `alias_id` into the IDs of the indexes it points to.
+async fn get_index_alias(
+    alias_id: String,
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexAlias> {
+    let list_index_aliases_request = ListIndexAliasesRequest {
+        alias_ids: vec![alias_id.clone()],
+    };
+    let index_alias = metastore
+        .list_index_aliases(list_index_aliases_request)
+        .await?
+        .index_aliases
+        .into_iter()
+        .next()
+        .ok_or(MetastoreError::NotFound(EntityKind::IndexAlias {
+            alias_id,
+        }))?;
+    Ok(index_alias)
+}
+
+fn update_index_alias_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("aliases" / String)
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .then(update_index_alias)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Aliases",
+    path = "/aliases/{alias_id}",
+    request_body = IndexAliasUpdates,
+    responses(
+        (status = 200, description = "The index alias was successfully updated.", body = IndexAlias),
+        (status = 404, description = "The index alias or one of the indexes was not found."),
+        (status = 400, description = "One of the indexes to remove is not part of the alias.")
+    ),
+    params(
+        ("alias_id" = String, Path, description = "The ID of the index alias to update."),
+    )
+)]
+/// Atomically adds and removes indexes from the index alias identified by `alias_id`.
+async fn update_index_alias(
+    alias_id: String,
+    index_alias_updates: IndexAliasUpdates,
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexAlias> {
+    let update_index_alias_request = UpdateIndexAliasRequest {
+        alias_id: alias_id.clone(),
+        add_index_ids: index_alias_updates.add,
+        remove_index_ids: index_alias_updates.remove,
+    };
+    let index_alias = metastore
+        .update_index_alias(update_index_alias_request)
+        .await?
+        .index_alias
+        .ok_or_else(|| MetastoreError::Internal {
+            message: format!("failed to update index alias `{alias_id}`"),
+            cause: "the metastore did not return the updated alias".to_string(),
+        })?;
+    Ok(index_alias)
+}
+
+fn list_index_aliases_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("aliases")
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(list_index_aliases)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Aliases",
+    path = "/aliases",
+    responses(
+        (status = 200, description = "The index aliases were successfully retrieved.", body = [IndexAlias]),
+    ),
+)]
+/// Retrieves all the index aliases stored in the metastore.
+async fn list_index_aliases(
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<Vec<IndexAlias>> {
+    let list_index_aliases_request = ListIndexAliasesRequest::default();
+    let index_aliases = metastore
+        .list_index_aliases(list_index_aliases_request)
+        .await?
+        .index_aliases;
+    Ok(index_aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::metastore::{
+        EmptyResponse, ListIndexAliasesResponse, MockMetastoreService, UpdateIndexAliasResponse,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_index_alias() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_create_index_alias()
+            .times(2)
+            .returning(|request| {
+                assert_eq!(request.alias_id, "test-alias");
+
+                let missing_index_ids: Vec<IndexId> = request
+                    .index_ids
+                    .into_iter()
+                    .filter(|index_id| !index_id.starts_with("test-index"))
+                    .collect();
+                if !missing_index_ids.is_empty() {
+                    return Err(MetastoreError::NotFound(EntityKind::Indexes {
+                        index_ids: missing_index_ids,
+                    }));
+                }
+                Ok(EmptyResponse {})
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let create_index_alias_handler = create_index_alias_handler(metastore);
+
+        let response = warp::test::request()
+            .path("/aliases")
+            .method("POST")
+            .json(&json!({
+                "alias_id": "test-alias",
+                "index_ids": ["test-index-foo", "test-index-bar"],
+            }))
+            .reply(&create_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let index_alias: IndexAlias = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(index_alias.alias_id, "test-alias");
+        assert_eq!(index_alias.index_ids, ["test-index-bar", "test-index-foo"]);
+
+        let response = warp::test::request()
+            .path("/aliases")
+            .method("POST")
+            .json(&json!({
+                "alias_id": "test-alias",
+                "index_ids": ["test-index-foo", "missing-index"],
+            }))
+            .reply(&create_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_get_index_alias() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_index_aliases()
+            .returning(|request| {
+                assert_eq!(request.alias_ids.len(), 1);
+
+                let index_aliases = request
+                    .alias_ids
+                    .into_iter()
+                    .filter(|alias_id| alias_id == "test-alias-foo")
+                    .map(|alias_id| IndexAlias {
+                        alias_id,
+                        index_ids: vec!["test-index-foo".to_string()],
+                    })
+                    .collect();
+                Ok(ListIndexAliasesResponse { index_aliases })
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let get_index_alias_handler = get_index_alias_handler(metastore);
+
+        let response = warp::test::request()
+            .path("/aliases/test-alias-foo")
+            .method("GET")
+            .reply(&get_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let index_alias: IndexAlias = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(index_alias.index_ids, ["test-index-foo"]);
+
+        let response = warp::test::request()
+            .path("/aliases/test-alias-bar")
+            .method("GET")
+            .reply(&get_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_update_index_alias() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_update_index_alias()
+            .return_once(|request| {
+                assert_eq!(request.alias_id, "test-alias");
+                assert_eq!(request.add_index_ids, ["test-index-bar"]);
+                assert_eq!(request.remove_index_ids, ["test-index-foo"]);
+
+                let index_alias = IndexAlias {
+                    alias_id: request.alias_id,
+                    index_ids: request.add_index_ids,
+                };
+                Ok(UpdateIndexAliasResponse {
+                    index_alias: Some(index_alias),
+                })
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let update_index_alias_handler = update_index_alias_handler(metastore);
+
+        let response = warp::test::request()
+            .path("/aliases/test-alias")
+            .method("PUT")
+            .json(&json!({
+                "add": ["test-index-bar"],
+                "remove": ["test-index-foo"],
+            }))
+            .reply(&update_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let index_alias: IndexAlias = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(index_alias.index_ids, ["test-index-bar"]);
+
+        let response = warp::test::request()
+            .path("/aliases/test-alias")
+            .method("PUT")
+            .json(&json!({
+                "replace": ["test-index-bar"],
+            }))
+            .reply(&update_index_alias_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_list_index_aliases() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_index_aliases()
+            .return_once(|request| {
+                assert!(request.alias_ids.is_empty());
+
+                let index_aliases = vec![IndexAlias {
+                    alias_id: "test-alias".to_string(),
+                    index_ids: vec!["test-index-foo".to_string()],
+                }];
+                Ok(ListIndexAliasesResponse { index_aliases })
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let list_index_aliases_handler = list_index_aliases_handler(metastore);
+
+        let response = warp::test::request()
+            .path("/aliases")
+            .method("GET")
+            .reply(&list_index_aliases_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let index_aliases: Vec<IndexAlias> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(index_aliases.len(), 1);
+        assert_eq!(index_aliases[0].alias_id, "test-alias");
+    }
+}
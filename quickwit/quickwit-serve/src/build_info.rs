@@ -101,6 +101,8 @@ pub struct RuntimeInfo {
     pub num_cpus: usize,
     pub num_threads_blocking: usize,
     pub num_threads_non_blocking: usize,
+    pub num_threads_indexing: usize,
+    pub num_threads_searching: usize,
 }
 
 impl RuntimeInfo {
@@ -115,6 +117,8 @@ impl RuntimeInfo {
                 num_cpus,
                 num_threads_blocking: runtimes_config.num_threads_blocking,
                 num_threads_non_blocking: runtimes_config.num_threads_non_blocking,
+                num_threads_indexing: runtimes_config.num_threads_indexing,
+                num_threads_searching: runtimes_config.num_threads_searching,
             }
         })
     }
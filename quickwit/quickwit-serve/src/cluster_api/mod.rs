@@ -19,4 +19,4 @@
 
 mod rest_handler;
 
-pub use rest_handler::{cluster_handler, ClusterApi};
+pub use rest_handler::{cluster_handler, cluster_members_handler, ClusterApi, ClusterMemberInfo};
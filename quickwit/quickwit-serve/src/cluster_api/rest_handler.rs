@@ -17,21 +17,89 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::net::SocketAddr;
 
-use quickwit_cluster::{Cluster, ClusterSnapshot, NodeIdSchema};
+use itertools::Itertools;
+use quickwit_cluster::{Cluster, ClusterSnapshot, MemberStatus, MembershipEntry, NodeIdSchema};
+use quickwit_metastore::{
+    ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitState,
+};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, ListSplitsRequest, MetastoreResult, MetastoreService,
+    MetastoreServiceClient,
+};
+use serde::{Deserialize, Serialize};
 use warp::{Filter, Rejection};
 
 use crate::format::extract_format_from_qs;
 use crate::rest_api_response::into_rest_api_response;
+use crate::with_arg;
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
-    paths(get_cluster),
-    components(schemas(ClusterSnapshot, NodeIdSchema,))
+    paths(get_cluster, get_cluster_members),
+    components(schemas(ClusterSnapshot, NodeIdSchema, ClusterMemberInfo, MemberStatus,))
 )]
 pub struct ClusterApi;
 
+/// A member of the cluster as returned by the cluster members API.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ClusterMemberInfo {
+    #[schema(example = "node-1")]
+    /// The unique identifier of the node in the cluster.
+    pub node_id: String,
+
+    #[schema(example = "1683736537")]
+    /// A numeric identifier incremented every time the node leaves and rejoins the cluster.
+    pub generation_id: u64,
+
+    #[schema(example = "127.0.0.1:7281", value_type = String)]
+    /// The address peers should use to communicate with the node via gRPC.
+    pub grpc_advertise_addr: SocketAddr,
+
+    #[schema(example = json!(["indexer", "searcher"]))]
+    /// The services enabled on the node.
+    pub roles: Vec<String>,
+
+    /// The liveness status of the node.
+    pub status: MemberStatus,
+
+    /// Whether the node is ready to handle requests.
+    pub is_ready: bool,
+
+    #[schema(example = "1683736537")]
+    /// The last time (Unix timestamp, in seconds) the node was observed live.
+    pub last_seen_timestamp: Option<i64>,
+
+    /// The number of published splits produced by the node.
+    pub num_splits: usize,
+}
+
+impl ClusterMemberInfo {
+    fn new(entry: MembershipEntry, num_splits: usize) -> Self {
+        let roles = entry
+            .member
+            .enabled_services
+            .iter()
+            .map(|service| service.as_str().to_string())
+            .sorted()
+            .collect();
+        Self {
+            node_id: entry.member.node_id.to_string(),
+            generation_id: entry.member.generation_id.as_u64(),
+            grpc_advertise_addr: entry.member.grpc_advertise_addr,
+            roles,
+            status: entry.status,
+            is_ready: entry.member.is_ready,
+            last_seen_timestamp: entry.last_seen.map(|last_seen| last_seen.unix_timestamp()),
+            num_splits,
+        }
+    }
+}
+
 /// Cluster handler.
 pub fn cluster_handler(
     cluster: Cluster,
@@ -45,6 +113,20 @@ pub fn cluster_handler(
         .map(into_rest_api_response)
 }
 
+/// Cluster members handler.
+pub fn cluster_members_handler(
+    cluster: Cluster,
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("cluster" / "members")
+        .and(warp::get())
+        .and(with_arg(cluster))
+        .and(with_arg(metastore))
+        .then(get_cluster_members)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
 #[utoipa::path(
     get,
     tag = "Cluster Info",
@@ -59,3 +141,171 @@ async fn get_cluster(cluster: Cluster) -> Result<ClusterSnapshot, Infallible> {
     let snapshot = cluster.snapshot().await;
     Ok(snapshot)
 }
+
+#[utoipa::path(
+    get,
+    tag = "Cluster Info",
+    path = "/cluster/members",
+    responses(
+        (status = 200, description = "Successfully fetched cluster members.", body = [ClusterMemberInfo])
+    )
+)]
+
+/// Get the live and dead members of the cluster.
+async fn get_cluster_members(
+    cluster: Cluster,
+    metastore: MetastoreServiceClient,
+) -> MetastoreResult<Vec<ClusterMemberInfo>> {
+    let num_splits_per_node = count_published_splits_per_node(metastore).await?;
+    let members = cluster
+        .members()
+        .await
+        .into_iter()
+        .map(|entry| {
+            let num_splits = num_splits_per_node
+                .get(entry.member.node_id.as_str())
+                .copied()
+                .unwrap_or(0);
+            ClusterMemberInfo::new(entry, num_splits)
+        })
+        .collect();
+    Ok(members)
+}
+
+async fn count_published_splits_per_node(
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<HashMap<String, usize>> {
+    let index_uids: Vec<_> = metastore
+        .list_indexes_metadata(ListIndexesMetadataRequest::all())
+        .await?
+        .deserialize_indexes_metadata()
+        .await?
+        .into_iter()
+        .map(|index_metadata| index_metadata.index_uid)
+        .collect();
+
+    if index_uids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let query =
+        ListSplitsQuery::try_from_index_uids(index_uids)?.with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits_metadata = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+    let num_splits_per_node = splits_metadata
+        .into_iter()
+        .map(|split_metadata| split_metadata.node_id)
+        .counts();
+    Ok(num_splits_per_node)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use quickwit_cluster::{create_cluster_for_test, ChannelTransport};
+    use quickwit_common::ServiceStream;
+    use quickwit_indexing::MockSplitBuilder;
+    use quickwit_metastore::{IndexMetadata, ListSplitsResponseExt};
+    use quickwit_proto::metastore::{
+        ListIndexesMetadataResponse, ListSplitsResponse, MockMetastoreService,
+    };
+    use serde_json::Value as JsonValue;
+
+    use super::*;
+    use crate::recover_fn;
+
+    #[tokio::test]
+    async fn test_rest_cluster_members() {
+        let transport = ChannelTransport::default();
+        let node_1 = create_cluster_for_test(Vec::new(), &["indexer"], &transport, true)
+            .await
+            .unwrap();
+        let peer_seeds = vec![node_1.gossip_listen_addr.to_string()];
+        let node_2 = create_cluster_for_test(peer_seeds.clone(), &["indexer"], &transport, true)
+            .await
+            .unwrap();
+        let node_3 = create_cluster_for_test(peer_seeds, &["searcher"], &transport, true)
+            .await
+            .unwrap();
+        node_1
+            .wait_for_ready_members(|members| members.len() == 3, Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        let node_1_id = node_1.self_node_id().to_string();
+        let node_2_id = node_2.self_node_id().to_string();
+        let node_3_id = node_3.self_node_id().to_string();
+
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        let mut split_1 = MockSplitBuilder::new("split-1")
+            .with_index_uid(&index_uid)
+            .build();
+        split_1.split_metadata.node_id = node_1_id.clone();
+        let mut split_2 = MockSplitBuilder::new("split-2")
+            .with_index_uid(&index_uid)
+            .build();
+        split_2.split_metadata.node_id = node_1_id.clone();
+        let mut split_3 = MockSplitBuilder::new("split-3")
+            .with_index_uid(&index_uid)
+            .build();
+        split_3.split_metadata.node_id = node_2_id.clone();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .return_once(move |_| Ok(ListIndexesMetadataResponse::for_test(vec![index_metadata])));
+        mock_metastore
+            .expect_list_splits()
+            .withf(move |list_splits_request| {
+                let list_splits_query =
+                    list_splits_request.deserialize_list_splits_query().unwrap();
+                list_splits_query.index_uids == [index_uid.clone()]
+                    && list_splits_query.split_states == [SplitState::Published]
+            })
+            .return_once(move |_| {
+                let splits = vec![split_1, split_2, split_3];
+                let response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(response)]))
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = cluster_members_handler(node_1.clone(), metastore).recover(recover_fn);
+
+        let resp = warp::test::request()
+            .path("/cluster/members")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let members: Vec<ClusterMemberInfo> = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(members.len(), 3);
+
+        let num_splits_per_node: HashMap<&str, usize> = members
+            .iter()
+            .map(|member| (member.node_id.as_str(), member.num_splits))
+            .collect();
+        assert_eq!(num_splits_per_node[node_1_id.as_str()], 2);
+        assert_eq!(num_splits_per_node[node_2_id.as_str()], 1);
+        assert_eq!(num_splits_per_node[node_3_id.as_str()], 0);
+
+        let node_3_member = members
+            .iter()
+            .find(|member| member.node_id == node_3_id)
+            .unwrap();
+        assert_eq!(node_3_member.roles, ["searcher"]);
+        assert_eq!(node_3_member.status, MemberStatus::Alive);
+        assert!(node_3_member.is_ready);
+        assert!(node_3_member.last_seen_timestamp.is_some());
+
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json[0]["status"], "alive");
+
+        node_1.shutdown().await;
+        node_2.shutdown().await;
+        node_3.shutdown().await;
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_config::IndexingErrorPolicy;
+use quickwit_index_management::IndexService;
+use quickwit_indexing::{DeadLetterEntry, DeadLetterQueue, DeadLetterQueuePage};
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService};
+use quickwit_proto::types::IndexId;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use warp::{Filter, Rejection};
+
+use crate::error::QuickwitError;
+use crate::format::extract_format_from_qs;
+use crate::rest_api_response::into_rest_api_response;
+use crate::with_arg;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(get_dead_letter_queue, truncate_dead_letter_queue),
+    components(schemas(DeadLetterEntry, DeadLetterQueuePage, TruncateDeadLetterQueueResponse))
+)]
+pub struct DeadLetterQueueApi;
+
+/// Dead letter queue API handlers.
+pub fn dead_letter_queue_api_handlers(
+    index_service: IndexService,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    get_dead_letter_queue_handler(index_service.clone())
+        .or(truncate_dead_letter_queue_handler(index_service))
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, utoipa::IntoParams, utoipa::ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterQueueQueryParams {
+    /// Page number, starting at 1.
+    #[serde(default = "DeadLetterQueueQueryParams::default_page")]
+    pub page: usize,
+    /// Maximum number of entries per page.
+    #[serde(default = "DeadLetterQueueQueryParams::default_page_size")]
+    pub page_size: usize,
+}
+
+impl DeadLetterQueueQueryParams {
+    fn default_page() -> usize {
+        1
+    }
+
+    fn default_page_size() -> usize {
+        100
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, utoipa::IntoParams, utoipa::ToSchema)]
+#[into_params(parameter_in = Query)]
+pub struct TruncateDeadLetterQueueQueryParams {
+    /// Number of entries to remove, oldest first.
+    pub num_entries: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct TruncateDeadLetterQueueResponse {
+    /// Number of entries actually removed.
+    pub num_removed_entries: usize,
+}
+
+async fn open_dead_letter_queue(
+    index_id: &IndexId,
+    index_service: &IndexService,
+) -> Result<DeadLetterQueue, QuickwitError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_metadata = index_service
+        .metastore()
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+    let IndexingErrorPolicy::DeadLetterQueue(dlq_config) = index_metadata
+        .index_config
+        .indexing_settings
+        .on_document_error
+    else {
+        return Err(QuickwitError::BadRequest(format!(
+            "dead letter queue is not enabled for index `{index_id}`"
+        )));
+    };
+    let storage = index_service
+        .storage_resolver()
+        .resolve(&dlq_config.uri)
+        .await?;
+    Ok(DeadLetterQueue::new(storage))
+}
+
+fn get_dead_letter_queue_handler(
+    index_service: IndexService,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(String / "dead-letter-queue")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(with_arg(index_service))
+        .then(get_dead_letter_queue)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Dead Letter Queue",
+    path = "/{index_id}/dead-letter-queue",
+    responses(
+        (status = 200, description = "Successfully fetched dead letter queue entries.", body = DeadLetterQueuePage)
+    ),
+    params(
+        DeadLetterQueueQueryParams,
+        ("index_id" = String, Path, description = "The index ID to retrieve dead letter queue entries for."),
+    )
+)]
+/// Get Dead Letter Queue Entries
+///
+/// Returns a page of the documents rejected while indexing, oldest first.
+async fn get_dead_letter_queue(
+    index_id: IndexId,
+    query_params: DeadLetterQueueQueryParams,
+    index_service: IndexService,
+) -> Result<DeadLetterQueuePage, QuickwitError> {
+    info!(index_id = %index_id, page = query_params.page, page_size = query_params.page_size, "get-dead-letter-queue");
+    if query_params.page == 0 || query_params.page_size == 0 {
+        return Err(QuickwitError::BadRequest(
+            "`page` and `page_size` must be strictly positive".to_string(),
+        ));
+    }
+    let dead_letter_queue = open_dead_letter_queue(&index_id, &index_service).await?;
+    let page = dead_letter_queue
+        .read_page(query_params.page, query_params.page_size)
+        .await?;
+    Ok(page)
+}
+
+fn truncate_dead_letter_queue_handler(
+    index_service: IndexService,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(String / "dead-letter-queue")
+        .and(warp::delete())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(with_arg(index_service))
+        .then(truncate_dead_letter_queue)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Dead Letter Queue",
+    path = "/{index_id}/dead-letter-queue",
+    responses(
+        (status = 200, description = "Successfully truncated the dead letter queue.", body = TruncateDeadLetterQueueResponse)
+    ),
+    params(
+        TruncateDeadLetterQueueQueryParams,
+        ("index_id" = String, Path, description = "The index ID whose dead letter queue to truncate."),
+    )
+)]
+/// Truncate Dead Letter Queue
+///
+/// Removes the oldest entries of the dead letter queue, typically after they have been
+/// reingested. Entries are removed by whole batches, so fewer entries than requested may be
+/// removed.
+async fn truncate_dead_letter_queue(
+    index_id: IndexId,
+    query_params: TruncateDeadLetterQueueQueryParams,
+    index_service: IndexService,
+) -> Result<TruncateDeadLetterQueueResponse, QuickwitError> {
+    info!(index_id = %index_id, num_entries = query_params.num_entries, "truncate-dead-letter-queue");
+    let dead_letter_queue = open_dead_letter_queue(&index_id, &index_service).await?;
+    let num_removed_entries = dead_letter_queue.truncate(query_params.num_entries).await?;
+    Ok(TruncateDeadLetterQueueResponse {
+        num_removed_entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_common::uri::Uri;
+    use quickwit_config::DlqConfig;
+    use quickwit_metastore::IndexMetadata;
+    use quickwit_proto::metastore::{
+        IndexMetadataResponse, MetastoreServiceClient, MockMetastoreService,
+    };
+    use quickwit_storage::StorageResolver;
+    use serde_json::Value as JsonValue;
+    use warp::Filter;
+
+    use super::*;
+    use crate::rest::recover_fn;
+
+    #[tokio::test]
+    async fn test_dead_letter_queue_api() {
+        let dead_letter_queue_uri = Uri::for_test("ram:///dlq/test-index");
+        let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///indexes/test-index");
+        index_metadata
+            .index_config
+            .indexing_settings
+            .on_document_error = IndexingErrorPolicy::DeadLetterQueue(DlqConfig {
+            uri: dead_letter_queue_uri.clone(),
+        });
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().returning(move |_| {
+            Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+        });
+        let storage_resolver = StorageResolver::for_test();
+        let dead_letter_queue = DeadLetterQueue::new(
+            storage_resolver
+                .resolve(&dead_letter_queue_uri)
+                .await
+                .unwrap(),
+        );
+        let entries: Vec<DeadLetterEntry> = (0..3)
+            .map(|doc_id| {
+                let raw_doc = format!(r#"{{"doc_id": {doc_id}}}"#);
+                DeadLetterEntry::new("invalid doc".to_string(), raw_doc.as_bytes())
+            })
+            .collect();
+        dead_letter_queue.append(&entries).await.unwrap();
+
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            storage_resolver,
+        );
+        let dead_letter_queue_handler =
+            dead_letter_queue_api_handlers(index_service).recover(recover_fn);
+
+        let resp = warp::test::request()
+            .path("/test-index/dead-letter-queue?page=2&page_size=2")
+            .reply(&dead_letter_queue_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: DeadLetterQueuePage = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(page.num_entries, 3);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].document, serde_json::json!({"doc_id": 2}));
+
+        let resp = warp::test::request()
+            .path("/test-index/dead-letter-queue?page=0")
+            .reply(&dead_letter_queue_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .method("DELETE")
+            .path("/test-index/dead-letter-queue?num_entries=3")
+            .reply(&dead_letter_queue_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"num_removed_entries": 3}));
+        assert_eq!(dead_letter_queue.num_entries().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_queue_api_disabled() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().return_once(|_| {
+            Ok(
+                IndexMetadataResponse::try_from_index_metadata(&IndexMetadata::for_test(
+                    "test-index",
+                    "ram:///indexes/test-index",
+                ))
+                .unwrap(),
+            )
+        });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::for_test(),
+        );
+        let dead_letter_queue_handler =
+            dead_letter_queue_api_handlers(index_service).recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/test-index/dead-letter-queue")
+            .reply(&dead_letter_queue_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+}
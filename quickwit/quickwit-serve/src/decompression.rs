@@ -115,3 +115,55 @@ impl Body {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_decompress_body() {
+        let payload = Bytes::from_static(b"{\"id\": 1}\n{\"id\": 2}");
+
+        let decompressed = decompress_body(None, payload.clone()).await.unwrap();
+        assert_eq!(decompressed, payload);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&payload).unwrap();
+        let gzip_payload = Bytes::from(encoder.finish().unwrap());
+
+        for encoding in ["gzip", "x-gzip"] {
+            let decompressed = decompress_body(Some(encoding.to_string()), gzip_payload.clone())
+                .await
+                .unwrap();
+            assert_eq!(decompressed, payload);
+        }
+        let zstd_payload = Bytes::from(zstd::encode_all(payload.as_ref(), 0).unwrap());
+        let decompressed = decompress_body(Some("zstd".to_string()), zstd_payload)
+            .await
+            .unwrap();
+        assert_eq!(decompressed, payload);
+    }
+
+    #[tokio::test]
+    async fn test_decompress_body_rejects_invalid_payloads() {
+        let rejection = decompress_body(Some("gzip".to_string()), Bytes::from_static(b"foo"))
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<CorruptedData>().is_some());
+
+        let rejection = decompress_body(Some("zstd".to_string()), Bytes::from_static(b"foo"))
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<CorruptedData>().is_some());
+
+        let rejection = decompress_body(Some("br".to_string()), Bytes::from_static(b"foo"))
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<UnsupportedEncoding>().is_some());
+    }
+}
@@ -114,6 +114,7 @@ async fn get_node_debug_infos(
                 ready_node.grpc_advertise_addr(),
                 ready_node.channel(),
                 DeveloperApiServer::MAX_GRPC_MESSAGE_SIZE,
+                cluster.grpc_client_interceptor(),
             );
             let roles = target_roles.iter().map(|role| role.to_string()).collect();
             let request = GetDebugInfoRequest { roles };
@@ -29,25 +29,38 @@ use quickwit_proto::ingest::router::IngestRouterServiceClient;
 use quickwit_proto::types::IndexId;
 use warp::{Filter, Rejection};
 
-use super::bulk_v2::{elastic_bulk_ingest_v2, ElasticBulkResponse};
+use super::bulk_v2::{
+    elastic_bulk_ingest_v2, ElasticBulkAction, ElasticBulkItem, ElasticBulkResponse,
+};
 use crate::elasticsearch_api::filter::{elastic_bulk_filter, elastic_index_bulk_filter};
 use crate::elasticsearch_api::make_elastic_api_response;
 use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
 use crate::format::extract_format_from_qs;
-use crate::ingest_api::lines;
+use crate::ingest_api::{lines, FrozenIndexes};
 use crate::{with_arg, Body};
 
 /// POST `_elastic/_bulk`
 pub fn es_compat_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_bulk_filter()
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
-        .then(|body, bulk_options, ingest_service, ingest_router| {
-            elastic_ingest_bulk(None, body, bulk_options, ingest_service, ingest_router)
-        })
+        .and(with_arg(frozen_indexes))
+        .then(
+            |body, bulk_options, ingest_service, ingest_router, frozen_indexes| {
+                elastic_ingest_bulk(
+                    None,
+                    body,
+                    bulk_options,
+                    ingest_service,
+                    ingest_router,
+                    frozen_indexes,
+                )
+            },
+        )
         .and(extract_format_from_qs())
         .map(make_elastic_api_response)
 }
@@ -56,18 +69,21 @@ pub fn es_compat_bulk_handler(
 pub fn es_compat_index_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_index_bulk_filter()
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
+        .and(with_arg(frozen_indexes))
         .then(
-            |index_id, body, bulk_options, ingest_service, ingest_router| {
+            |index_id, body, bulk_options, ingest_service, ingest_router, frozen_indexes| {
                 elastic_ingest_bulk(
                     Some(index_id),
                     body,
                     bulk_options,
                     ingest_service,
                     ingest_router,
+                    frozen_indexes,
                 )
             },
         )
@@ -81,9 +97,17 @@ async fn elastic_ingest_bulk(
     bulk_options: ElasticBulkOptions,
     mut ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
     if enable_ingest_v2() || bulk_options.enable_ingest_v2 {
-        return elastic_bulk_ingest_v2(default_index_id, body, bulk_options, ingest_router).await;
+        return elastic_bulk_ingest_v2(
+            default_index_id,
+            body,
+            bulk_options,
+            ingest_router,
+            frozen_indexes,
+        )
+        .await;
     }
     if disable_ingest_v1() {
         return Err(ElasticsearchError::new(
@@ -94,6 +118,8 @@ async fn elastic_ingest_bulk(
     }
     let now = Instant::now();
     let mut doc_batch_builders = HashMap::new();
+    let mut actions: Vec<ElasticBulkAction> = Vec::new();
+    let mut errors = false;
     let mut lines = lines(&body.content).enumerate();
 
     while let Some((line_number, line)) = lines.next() {
@@ -104,6 +130,16 @@ async fn elastic_ingest_bulk(
                 None,
             )
         })?;
+        if !action.is_supported() {
+            if action.has_source() {
+                lines.next();
+            }
+            let not_supported_action =
+                ElasticBulkAction::not_supported(&action, default_index_id.as_ref());
+            actions.push(not_supported_action);
+            errors = true;
+            continue;
+        }
         let (_, source) = lines.next().ok_or_else(|| {
             ElasticsearchError::new(
                 StatusCode::BAD_REQUEST,
@@ -114,8 +150,9 @@ async fn elastic_ingest_bulk(
         // when ingesting on /my-index/_bulk, if _index: is set to something else than my-index,
         // ES honors it and create the doc in the requested index. That is, `my-index` is a default
         // value in case _index: is missing, but not a constraint on each sub-action.
-        let index_id = action
-            .into_index_id()
+        let meta = action.clone().into_meta();
+        let index_id = meta
+            .index_id
             .or_else(|| default_index_id.clone())
             .ok_or_else(|| {
                 ElasticsearchError::new(
@@ -126,10 +163,24 @@ async fn elastic_ingest_bulk(
             })?;
         let doc_batch_builder = doc_batch_builders
             .entry(index_id.clone())
-            .or_insert(DocBatchBuilder::new(index_id));
+            .or_insert(DocBatchBuilder::new(index_id.clone()));
 
         doc_batch_builder.ingest_doc(source);
+
+        // Ingest v1 either accepts or rejects the whole request, so the items are only reported
+        // once the request has succeeded.
+        let item = ElasticBulkItem {
+            index_id,
+            es_doc_id: meta.es_doc_id,
+            status: StatusCode::CREATED,
+            error: None,
+        };
+        actions.push(ElasticBulkAction::new(&action, item));
     }
+    frozen_indexes
+        .check_not_frozen(doc_batch_builders.keys())
+        .await?;
+
     let doc_batches = doc_batch_builders
         .into_values()
         .map(|builder| builder.build())
@@ -142,11 +193,10 @@ async fn elastic_ingest_bulk(
     ingest_service.ingest(ingest_request).await?;
 
     let took_millis = now.elapsed().as_millis() as u64;
-    let errors = false;
     let bulk_response = ElasticBulkResponse {
         took_millis,
         errors,
-        actions: Vec::new(),
+        actions,
     };
     Ok(bulk_response)
 }
@@ -162,14 +212,13 @@ mod tests {
     use quickwit_ingest::{FetchRequest, IngestServiceClient, SuggestTruncateRequest};
     use quickwit_metastore::metastore_for_test;
     use quickwit_proto::ingest::router::IngestRouterServiceClient;
-    use quickwit_proto::metastore::MetastoreServiceClient;
     use quickwit_search::MockSearchService;
     use quickwit_storage::StorageResolver;
 
-    use crate::elasticsearch_api::bulk_v2::ElasticBulkResponse;
+    use crate::elasticsearch_api::bulk_v2::{ElasticBulkAction, ElasticBulkResponse};
     use crate::elasticsearch_api::elastic_api_handlers;
-    use crate::elasticsearch_api::model::ElasticsearchError;
-    use crate::ingest_api::setup_ingest_service;
+    use crate::elasticsearch_api::model::{ElasticsearchError, ErrorCauseException};
+    use crate::ingest_api::{setup_frozen_index, setup_ingest_service};
 
     #[tokio::test]
     async fn test_bulk_api_returns_404_if_index_id_does_not_exist() {
@@ -185,7 +234,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -217,7 +266,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -253,7 +302,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = "
@@ -286,7 +335,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -308,6 +357,92 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_es_compat_bulk_api_with_filebeat_payload() {
+        let config = Arc::new(NodeConfig::for_test());
+        let search_service = Arc::new(MockSearchService::new());
+        let (universe, _temp_dir, ingest_service, ingest_service_mailbox) =
+            setup_ingest_service(&["filebeat-7", "filebeat-8"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let index_service =
+            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            search_service,
+            ingest_service,
+            ingest_router,
+            metastore_for_test(),
+            index_service,
+        );
+        // Filebeat 8.x sends `create` actions, while Filebeat 7.x sends `index` actions.
+        let payload = r#"{"create":{"_index":"filebeat-8"}}
+{"@timestamp":"2024-03-04T10:25:41.372Z","log":{"offset":0,"file":{"path":"/var/log/nginx/access.log"}},"message":"127.0.0.1 - - [04/Mar/2024:10:25:40 +0000] \"GET / HTTP/1.1\" 200 615 \"-\" \"curl/8.4.0\"","input":{"type":"filestream"},"ecs":{"version":"8.0.0"},"host":{"name":"web-1"},"agent":{"type":"filebeat","version":"8.12.2","name":"web-1","id":"9c0a5a33-3c1a-4b1c-9b5e-0d5f9b7d1c2e","ephemeral_id":"2f7f6f0e-8c9a-4f51-a5f1-5b1e1d9d8a6b"}}
+{"create":{"_index":"filebeat-8"}}
+{"@timestamp":"2024-03-04T10:25:42.013Z","log":{"offset":92,"file":{"path":"/var/log/nginx/access.log"}},"message":"127.0.0.1 - - [04/Mar/2024:10:25:41 +0000] \"GET /favicon.ico HTTP/1.1\" 404 153 \"-\" \"curl/8.4.0\"","input":{"type":"filestream"},"ecs":{"version":"8.0.0"},"host":{"name":"web-1"},"agent":{"type":"filebeat","version":"8.12.2","name":"web-1","id":"9c0a5a33-3c1a-4b1c-9b5e-0d5f9b7d1c2e","ephemeral_id":"2f7f6f0e-8c9a-4f51-a5f1-5b1e1d9d8a6b"}}
+{"index":{"_index":"filebeat-7"}}
+{"@timestamp":"2024-03-04T10:25:43.500Z","log":{"offset":0,"file":{"path":"/var/log/syslog"}},"message":"Mar  4 10:25:43 web-1 systemd[1]: Started Session 42 of user ubuntu.","input":{"type":"log"},"ecs":{"version":"1.12.0"},"host":{"name":"web-1"},"agent":{"type":"filebeat","version":"7.17.18","hostname":"web-1","id":"4b8e2f6c-0d6e-4a5b-8d3e-1f2c3b4a5d6e","ephemeral_id":"7a6b5c4d-3e2f-1a0b-9c8d-7e6f5a4b3c2d"}}
+{"update":{"_index":"filebeat-7","_id":"1"}}
+{"doc":{"message":"updated"}}
+{"delete":{"_index":"filebeat-7","_id":"2"}}
+"#;
+        let resp = warp::test::request()
+            .path("/_es_compat/bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&elastic_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert!(bulk_response.errors);
+        assert_eq!(bulk_response.actions.len(), 5);
+
+        let ElasticBulkAction::Create(item) = &bulk_response.actions[0] else {
+            panic!("expected `create` action");
+        };
+        assert_eq!(item.index_id, "filebeat-8");
+        assert_eq!(item.status, StatusCode::CREATED);
+        assert!(item.error.is_none());
+
+        let ElasticBulkAction::Index(item) = &bulk_response.actions[2] else {
+            panic!("expected `index` action");
+        };
+        assert_eq!(item.index_id, "filebeat-7");
+        assert_eq!(item.status, StatusCode::CREATED);
+
+        let ElasticBulkAction::Update(item) = &bulk_response.actions[3] else {
+            panic!("expected `update` action");
+        };
+        assert_eq!(item.index_id, "filebeat-7");
+        assert_eq!(item.es_doc_id.as_deref(), Some("1"));
+        assert_eq!(item.status, StatusCode::BAD_REQUEST);
+        let error = item.error.as_ref().unwrap();
+        assert_eq!(error.exception, ErrorCauseException::NotSupported);
+        assert_eq!(error.reason, "`update` action is not supported");
+
+        let ElasticBulkAction::Delete(item) = &bulk_response.actions[4] else {
+            panic!("expected `delete` action");
+        };
+        assert_eq!(item.es_doc_id.as_deref(), Some("2"));
+        assert_eq!(item.status, StatusCode::BAD_REQUEST);
+
+        for (index_id, expected_num_docs) in [("filebeat-7", 1), ("filebeat-8", 2)] {
+            let num_docs = ingest_service_mailbox
+                .ask_for_res(FetchRequest {
+                    index_id: index_id.to_string(),
+                    start_after: None,
+                    num_bytes_limit: None,
+                })
+                .await
+                .unwrap()
+                .doc_batch
+                .unwrap()
+                .num_docs();
+            assert_eq!(num_docs, expected_num_docs);
+        }
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_bulk_api_blocks_when_refresh_wait_for_is_specified() {
         let config = Arc::new(NodeConfig::for_test());
@@ -322,7 +457,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -409,7 +544,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -494,7 +629,7 @@ mod tests {
             search_service,
             ingest_service,
             ingest_router,
-            MetastoreServiceClient::mocked(),
+            metastore_for_test(),
             index_service,
         );
         let payload = r#"
@@ -514,4 +649,41 @@ mod tests {
             "Malformed action/metadata line [#0]. Details: `expected value at line 1 column 57`"
         );
     }
+
+    #[tokio::test]
+    async fn test_bulk_api_returns_423_if_index_is_frozen() {
+        let config = Arc::new(NodeConfig::for_test());
+        let search_service = Arc::new(MockSearchService::new());
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let metastore = setup_frozen_index("my-index-2").await;
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let elastic_api_handlers = elastic_api_handlers(
+            config,
+            search_service,
+            ingest_service,
+            ingest_router,
+            metastore,
+            index_service,
+        );
+        let payload = r#"
+            { "create" : { "_index" : "my-index-1", "_id" : "1"} }
+            {"id": 1, "message": "push"}
+            { "create" : { "_index" : "my-index-2", "_id" : "1" } }
+            {"id": 1, "message": "push"}"#;
+        let resp = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&elastic_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 423);
+        let es_error: ElasticsearchError = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            es_error.error.reason.unwrap(),
+            "index `my-index-2` is frozen"
+        );
+        universe.assert_quit().await;
+    }
 }
@@ -33,7 +33,7 @@ use tracing::warn;
 
 use super::model::ErrorCauseException;
 use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
-use crate::ingest_api::lines;
+use crate::ingest_api::{lines, FrozenIndexes};
 use crate::Body;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -51,6 +51,44 @@ pub(crate) enum ElasticBulkAction {
     Create(ElasticBulkItem),
     #[serde(rename = "index")]
     Index(ElasticBulkItem),
+    #[serde(rename = "update")]
+    Update(ElasticBulkItem),
+    #[serde(rename = "delete")]
+    Delete(ElasticBulkItem),
+}
+
+impl ElasticBulkAction {
+    /// Wraps the item into a response entry of the same type as the requested action.
+    pub(crate) fn new(action: &BulkAction, item: ElasticBulkItem) -> Self {
+        match action {
+            BulkAction::Create(_) => ElasticBulkAction::Create(item),
+            BulkAction::Index(_) => ElasticBulkAction::Index(item),
+            BulkAction::Update(_) => ElasticBulkAction::Update(item),
+            BulkAction::Delete(_) => ElasticBulkAction::Delete(item),
+        }
+    }
+
+    /// Builds the response entry of an action that Quickwit does not support, such as `update`
+    /// or `delete`. The action is ignored and the rest of the bulk request is processed.
+    pub(crate) fn not_supported(action: &BulkAction, default_index_id: Option<&IndexId>) -> Self {
+        let meta = action.clone().into_meta();
+        let index_id = meta
+            .index_id
+            .or_else(|| default_index_id.cloned())
+            .unwrap_or_default();
+        let error = ElasticBulkError {
+            index_id: Some(index_id.clone()),
+            exception: ErrorCauseException::NotSupported,
+            reason: format!("`{}` action is not supported", action.name()),
+        };
+        let item = ElasticBulkItem {
+            index_id,
+            es_doc_id: meta.es_doc_id,
+            status: StatusCode::BAD_REQUEST,
+            error: Some(error),
+        };
+        ElasticBulkAction::new(action, item)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,11 +116,13 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     body: Body,
     bulk_options: ElasticBulkOptions,
     mut ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
     let now = Instant::now();
     let mut ingest_request_builder = IngestRequestV2Builder::default();
     let mut lines = lines(&body.content).enumerate();
     let mut per_subrequest_id_es_doc_ids: HashMap<u32, Vec<Option<String>>> = HashMap::new();
+    let mut not_supported_actions: Vec<ElasticBulkAction> = Vec::new();
 
     while let Some((line_no, line)) = lines.next() {
         let action = serde_json::from_slice::<BulkAction>(line).map_err(|error| {
@@ -92,6 +132,15 @@ pub(crate) async fn elastic_bulk_ingest_v2(
                 Some(ErrorCauseException::IllegalArgument),
             )
         })?;
+        if !action.is_supported() {
+            if action.has_source() {
+                lines.next();
+            }
+            let not_supported_action =
+                ElasticBulkAction::not_supported(&action, default_index_id.as_ref());
+            not_supported_actions.push(not_supported_action);
+            continue;
+        }
         let (_, source) = lines.next().ok_or_else(|| {
             ElasticsearchError::new(
                 StatusCode::BAD_REQUEST,
@@ -129,11 +178,22 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     let ingest_request_opt = ingest_request_builder.build(INGEST_V2_SOURCE_ID, commit_type);
 
     let Some(ingest_request) = ingest_request_opt else {
-        return Ok(ElasticBulkResponse::default());
+        let bulk_response = ElasticBulkResponse {
+            took_millis: now.elapsed().as_millis() as u64,
+            errors: !not_supported_actions.is_empty(),
+            actions: not_supported_actions,
+        };
+        return Ok(bulk_response);
     };
+    let index_ids = ingest_request
+        .subrequests
+        .iter()
+        .map(|subrequest| &subrequest.index_id);
+    frozen_indexes.check_not_frozen(index_ids).await?;
+
     let ingest_response_v2 = ingest_router.ingest(ingest_request).await?;
-    let errors = !ingest_response_v2.failures.is_empty();
-    let mut actions: Vec<ElasticBulkAction> = Vec::new();
+    let errors = !ingest_response_v2.failures.is_empty() || !not_supported_actions.is_empty();
+    let mut actions: Vec<ElasticBulkAction> = not_supported_actions;
 
     for success in ingest_response_v2.successes {
         let es_doc_ids = per_subrequest_id_es_doc_ids
@@ -224,10 +284,12 @@ pub(crate) async fn elastic_bulk_ingest_v2(
 
 #[cfg(test)]
 mod tests {
+    use quickwit_metastore::metastore_for_test;
     use quickwit_proto::ingest::router::{
         IngestFailure, IngestFailureReason, IngestResponseV2, IngestSuccess,
         MockIngestRouterService,
     };
+    use quickwit_proto::metastore::MetastoreServiceClient;
     use quickwit_proto::types::{IndexUid, Position, ShardId};
     use warp::{Filter, Rejection, Reply};
 
@@ -237,15 +299,24 @@ mod tests {
     use crate::elasticsearch_api::make_elastic_api_response;
     use crate::elasticsearch_api::model::ElasticsearchError;
     use crate::format::extract_format_from_qs;
+    use crate::ingest_api::setup_frozen_index;
     use crate::with_arg;
 
     fn es_compat_bulk_handler_v2(
         ingest_router: IngestRouterServiceClient,
+    ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+        es_compat_bulk_handler_v2_with_metastore(ingest_router, metastore_for_test())
+    }
+
+    fn es_compat_bulk_handler_v2_with_metastore(
+        ingest_router: IngestRouterServiceClient,
+        metastore: MetastoreServiceClient,
     ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
         elastic_bulk_filter()
             .and(with_arg(ingest_router))
-            .then(|body, bulk_options, ingest_router| {
-                elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router)
+            .and(with_arg(FrozenIndexes::new(metastore)))
+            .then(|body, bulk_options, ingest_router, frozen_indexes| {
+                elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router, frozen_indexes)
             })
             .and(extract_format_from_qs())
             .map(make_elastic_api_response)
@@ -324,6 +395,7 @@ mod tests {
             .map(|action| match action {
                 ElasticBulkAction::Create(item) => item,
                 ElasticBulkAction::Index(item) => item,
+                action => panic!("unexpected action `{action:?}`"),
             })
             .collect::<Vec<_>>();
         assert_eq!(items.len(), 3);
@@ -474,6 +546,74 @@ mod tests {
         assert_eq!(reason, "Validation Failed: 1: index is missing;");
     }
 
+    #[tokio::test]
+    async fn test_bulk_api_ignores_not_supported_actions() {
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|ingest_request| {
+                assert_eq!(ingest_request.subrequests.len(), 1);
+
+                let subrequest_0 = &ingest_request.subrequests[0];
+                assert_eq!(subrequest_0.index_id, "my-index-1");
+                assert_eq!(subrequest_0.doc_batch.as_ref().unwrap().num_docs(), 1);
+
+                Ok(IngestResponseV2 {
+                    successes: vec![IngestSuccess {
+                        subrequest_id: 0,
+                        index_uid: Some(IndexUid::for_test("my-index-1", 0)),
+                        source_id: INGEST_V2_SOURCE_ID.to_string(),
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: Some(Position::offset(0u64)),
+                    }],
+                    failures: Vec::new(),
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let handler = es_compat_bulk_handler_v2(ingest_router);
+
+        let payload = r#"
+            {"delete": {"_index": "my-index-1", "_id" : "1"}}
+            {"update": {"_index": "my-index-1", "_id" : "2"}}
+            {"doc": {"message": "my-updated-message"}}
+            {"index": {"_index": "my-index-1", "_id" : "3"}}
+            {"ts": 1, "message": "my-message-1"}
+        "#;
+        let response = warp::test::request()
+            .path("/_es_compat/bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(response.body()).unwrap();
+        assert!(bulk_response.errors);
+        assert_eq!(bulk_response.actions.len(), 3);
+
+        let ElasticBulkAction::Delete(item) = &bulk_response.actions[0] else {
+            panic!("expected `delete` action");
+        };
+        assert_eq!(item.es_doc_id.as_deref(), Some("1"));
+        assert_eq!(item.status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            item.error.as_ref().unwrap().exception,
+            ErrorCauseException::NotSupported
+        );
+        let ElasticBulkAction::Update(item) = &bulk_response.actions[1] else {
+            panic!("expected `update` action");
+        };
+        assert_eq!(item.es_doc_id.as_deref(), Some("2"));
+        assert_eq!(item.status, StatusCode::BAD_REQUEST);
+
+        let ElasticBulkAction::Index(item) = &bulk_response.actions[2] else {
+            panic!("expected `index` action");
+        };
+        assert_eq!(item.es_doc_id.as_deref(), Some("3"));
+        assert_eq!(item.status, StatusCode::CREATED);
+    }
+
     #[tokio::test]
     async fn test_bulk_api_index_not_found() {
         let mut mock_ingest_router = MockIngestRouterService::new();
@@ -538,4 +678,32 @@ mod tests {
         assert!(bulk_response.errors);
         assert_eq!(bulk_response.actions.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_bulk_api_returns_423_if_index_is_frozen() {
+        let metastore = setup_frozen_index("my-index-2").await;
+        // The request is rejected before reaching the router.
+        let ingest_router = IngestRouterServiceClient::from_mock(MockIngestRouterService::new());
+        let handler = es_compat_bulk_handler_v2_with_metastore(ingest_router, metastore);
+
+        let payload = r#"
+            {"create": {"_index": "my-index-1", "_id" : "1"}}
+            {"ts": 1, "message": "my-message-1"}
+            {"create": {"_index": "my-index-2", "_id" : "1"}}
+            {"ts": 1, "message": "my-message-1"}
+        "#;
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 423);
+
+        let es_error: ElasticsearchError = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            es_error.error.reason.unwrap(),
+            "index `my-index-2` is frozen"
+        );
+    }
 }
@@ -73,7 +73,10 @@ pub(crate) fn elasticsearch_filter(
 )]
 pub(crate) fn elastic_bulk_filter(
 ) -> impl Filter<Extract = (Body, ElasticBulkOptions), Error = Rejection> + Clone {
+    // `_es_compat/bulk` is an alias of `_elastic/_bulk`.
     warp::path!("_elastic" / "_bulk")
+        .or(warp::path!("_es_compat" / "bulk"))
+        .unify()
         .and(warp::post().or(warp::put()).unify())
         .and(warp::body::content_length_limit(
             CONTENT_LENGTH_LIMIT.as_u64(),
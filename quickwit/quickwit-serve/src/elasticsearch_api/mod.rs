@@ -45,6 +45,7 @@ use serde::{Deserialize, Serialize};
 use warp::{Filter, Rejection};
 
 use crate::elasticsearch_api::model::ElasticsearchError;
+use crate::ingest_api::FrozenIndexes;
 use crate::rest_api_response::RestApiResponse;
 use crate::{BodyFormat, BuildInfo};
 
@@ -60,6 +61,7 @@ pub fn elastic_api_handlers(
     metastore: MetastoreServiceClient,
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let frozen_indexes = FrozenIndexes::new(metastore.clone());
     es_compat_cluster_info_handler(node_config, BuildInfo::get())
         .or(es_compat_search_handler(search_service.clone()))
         .or(es_compat_index_search_handler(search_service.clone()))
@@ -72,8 +74,13 @@ pub fn elastic_api_handlers(
         .or(es_compat_bulk_handler(
             ingest_service.clone(),
             ingest_router.clone(),
+            frozen_indexes.clone(),
+        ))
+        .or(es_compat_index_bulk_handler(
+            ingest_service,
+            ingest_router,
+            frozen_indexes,
         ))
-        .or(es_compat_index_bulk_handler(ingest_service, ingest_router))
         .or(es_compat_index_stats_handler(metastore.clone()))
         .or(es_compat_delete_index_handler(index_service))
         .or(es_compat_stats_handler(metastore.clone()))
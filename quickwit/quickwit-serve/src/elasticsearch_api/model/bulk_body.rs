@@ -25,22 +25,40 @@ use serde::Deserialize;
 pub enum BulkAction {
     Create(BulkActionMeta),
     Index(BulkActionMeta),
+    Update(BulkActionMeta),
+    Delete(BulkActionMeta),
 }
 
 impl BulkAction {
-    pub fn into_index_id(self) -> Option<IndexId> {
+    pub fn into_meta(self) -> BulkActionMeta {
         match self {
-            BulkAction::Index(meta) => meta.index_id,
-            BulkAction::Create(meta) => meta.index_id,
+            BulkAction::Create(meta) => meta,
+            BulkAction::Index(meta) => meta,
+            BulkAction::Update(meta) => meta,
+            BulkAction::Delete(meta) => meta,
         }
     }
 
-    pub fn into_meta(self) -> BulkActionMeta {
+    pub fn name(&self) -> &'static str {
         match self {
-            BulkAction::Create(meta) => meta,
-            BulkAction::Index(meta) => meta,
+            BulkAction::Create(_) => "create",
+            BulkAction::Index(_) => "index",
+            BulkAction::Update(_) => "update",
+            BulkAction::Delete(_) => "delete",
         }
     }
+
+    /// Returns whether the action line is followed by a source line. In the bulk format, only
+    /// `delete` actions stand alone.
+    pub fn has_source(&self) -> bool {
+        !matches!(self, BulkAction::Delete(_))
+    }
+
+    /// Returns whether Quickwit supports the action. Quickwit indexes are append-only, so
+    /// documents cannot be updated or deleted individually.
+    pub fn is_supported(&self) -> bool {
+        matches!(self, BulkAction::Create(_) | BulkAction::Index(_))
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -113,6 +131,36 @@ mod tests {
                     "_id": "2"
                 }
             }"#;
+            let bulk_action = serde_json::from_str::<BulkAction>(bulk_action_json).unwrap();
+            assert_eq!(
+                bulk_action,
+                BulkAction::Delete(BulkActionMeta {
+                    index_id: Some("test".to_string()),
+                    es_doc_id: Some("2".to_string()),
+                })
+            );
+            assert!(!bulk_action.has_source());
+            assert!(!bulk_action.is_supported());
+        }
+        {
+            let bulk_action_json = r#"{
+                "update": {
+                    "_index": "test",
+                    "_id": "2"
+                }
+            }"#;
+            let bulk_action = serde_json::from_str::<BulkAction>(bulk_action_json).unwrap();
+            assert_eq!(bulk_action.name(), "update");
+            assert!(bulk_action.has_source());
+            assert!(!bulk_action.is_supported());
+        }
+        {
+            let bulk_action_json = r#"{
+                "upsert": {
+                    "_index": "test",
+                    "_id": "2"
+                }
+            }"#;
             serde_json::from_str::<BulkAction>(bulk_action_json).unwrap_err();
         }
     }
@@ -134,7 +134,7 @@ impl From<IndexServiceError> for ElasticsearchError {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ErrorCauseException {
     #[serde(rename = "action_request_validation_exception")]
     ActionRequestValidation,
@@ -142,6 +142,8 @@ pub enum ErrorCauseException {
     IllegalArgument,
     #[serde(rename = "index_not_found_exception")]
     IndexNotFound,
+    #[serde(rename = "not_supported_exception")]
+    NotSupported,
     #[serde(rename = "timeout_exception")]
     Timeout,
 }
@@ -152,6 +154,7 @@ impl ErrorCauseException {
             Self::ActionRequestValidation => "action_request_validation_exception",
             Self::IllegalArgument => "illegal_argument_exception",
             Self::IndexNotFound => "index_not_found_exception",
+            Self::NotSupported => "not_supported_exception",
             Self::Timeout => "timeout_exception",
         }
     }
@@ -321,6 +321,7 @@ fn build_request_for_es_api(
             scroll_ttl_secs,
             search_after,
             count_hits,
+            suggest_corrections: false,
         },
         has_doc_id_field,
     ))
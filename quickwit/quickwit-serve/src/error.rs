@@ -0,0 +1,207 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use hyper::StatusCode;
+use quickwit_index_management::IndexServiceError;
+use quickwit_metastore::MetastoreResolverError;
+use quickwit_proto::metastore::MetastoreError;
+use quickwit_proto::{tonic, ServiceError, ServiceErrorCode};
+use quickwit_storage::{StorageError, StorageErrorKind, StorageResolverError};
+use thiserror::Error;
+
+/// Top-level error of the REST and gRPC layers.
+///
+/// Handlers interact with several subsystems that each return their own error type. Converting
+/// them into a `QuickwitError` lets handlers use `?` uniformly while preserving the status code
+/// associated with each error.
+#[derive(Debug, Error)]
+pub enum QuickwitError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error(transparent)]
+    IndexService(#[from] IndexServiceError),
+    #[error(transparent)]
+    Metastore(#[from] MetastoreError),
+    #[error(transparent)]
+    MetastoreResolver(#[from] MetastoreResolverError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    StorageResolver(#[from] StorageResolverError),
+    #[error("internal error: {0:#}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl QuickwitError {
+    /// Returns the HTTP status code to respond with for this error.
+    pub fn http_status_code(&self) -> StatusCode {
+        self.error_code().http_status_code()
+    }
+
+    /// Converts the error into a gRPC status.
+    pub fn grpc_status(&self) -> tonic::Status {
+        tonic::Status::new(self.error_code().grpc_status_code(), self.to_string())
+    }
+}
+
+fn storage_error_code(storage_error_kind: StorageErrorKind) -> ServiceErrorCode {
+    match storage_error_kind {
+        StorageErrorKind::NotFound => ServiceErrorCode::NotFound,
+        StorageErrorKind::Unauthorized | StorageErrorKind::PermissionDenied => {
+            ServiceErrorCode::Forbidden
+        }
+        StorageErrorKind::Timeout => ServiceErrorCode::Timeout,
+        StorageErrorKind::QuotaExceeded => ServiceErrorCode::TooManyRequests,
+        StorageErrorKind::Service
+        | StorageErrorKind::Internal
+        | StorageErrorKind::Io
+        | StorageErrorKind::Unsupported => ServiceErrorCode::Internal,
+    }
+}
+
+impl ServiceError for QuickwitError {
+    fn error_code(&self) -> ServiceErrorCode {
+        match self {
+            Self::BadRequest(_) => ServiceErrorCode::BadRequest,
+            Self::IndexService(error) => error.error_code(),
+            Self::Metastore(error) => error.error_code(),
+            Self::MetastoreResolver(error) => match error {
+                MetastoreResolverError::InvalidConfig(_)
+                | MetastoreResolverError::InvalidUri(_)
+                | MetastoreResolverError::UnsupportedBackend(_) => ServiceErrorCode::BadRequest,
+                MetastoreResolverError::ConnectionRefused(_) => ServiceErrorCode::Unavailable,
+                MetastoreResolverError::AuthenticationFailed(_) => {
+                    ServiceErrorCode::Unauthenticated
+                }
+                MetastoreResolverError::Timeout { .. } => ServiceErrorCode::Timeout,
+                MetastoreResolverError::SchemaVersionMismatch { .. }
+                | MetastoreResolverError::IncompatibleSchemaVersion { .. } => {
+                    ServiceErrorCode::Internal
+                }
+                MetastoreResolverError::InsufficientPermissions(_) => ServiceErrorCode::Forbidden,
+                MetastoreResolverError::Initialization(error) => error.error_code(),
+            },
+            Self::Storage(error) => storage_error_code(error.kind()),
+            Self::StorageResolver(error) => match error {
+                StorageResolverError::InvalidConfig(_)
+                | StorageResolverError::InvalidUri(_)
+                | StorageResolverError::UnsupportedBackend(_) => ServiceErrorCode::BadRequest,
+                StorageResolverError::FailedToOpenStorage { kind, .. } => storage_error_code(*kind),
+            },
+            Self::Internal(_) => ServiceErrorCode::Internal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use quickwit_common::uri::Uri;
+    use quickwit_proto::metastore::EntityKind;
+
+    use super::*;
+
+    #[test]
+    fn test_quickwit_error_from_storage_error() {
+        let storage_error = StorageErrorKind::NotFound.with_error(anyhow::anyhow!("missing"));
+        let error = QuickwitError::from(storage_error);
+        assert!(matches!(error, QuickwitError::Storage(_)));
+        assert_eq!(error.http_status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(error.grpc_status().code(), tonic::Code::NotFound);
+
+        let storage_error = StorageErrorKind::Unauthorized.with_error(anyhow::anyhow!("denied"));
+        let error = QuickwitError::from(storage_error);
+        assert_eq!(error.http_status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(error.grpc_status().code(), tonic::Code::PermissionDenied);
+
+        let storage_error =
+            StorageErrorKind::PermissionDenied.with_error(anyhow::anyhow!("access denied"));
+        let error = QuickwitError::from(storage_error);
+        assert_eq!(error.http_status_code(), StatusCode::FORBIDDEN);
+
+        let storage_error =
+            StorageErrorKind::QuotaExceeded.with_error(anyhow::anyhow!("quota exceeded"));
+        let error = QuickwitError::from(storage_error);
+        assert_eq!(error.http_status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error.grpc_status().code(), tonic::Code::ResourceExhausted);
+
+        let storage_error = StorageErrorKind::Io.with_error(anyhow::anyhow!("disk full"));
+        let error = QuickwitError::from(storage_error);
+        assert_eq!(error.http_status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.grpc_status().code(), tonic::Code::Internal);
+    }
+
+    #[test]
+    fn test_quickwit_error_from_metastore_error() {
+        let metastore_error = MetastoreError::NotFound(EntityKind::Index {
+            index_id: "test-index".to_string(),
+        });
+        let error = QuickwitError::from(metastore_error);
+        assert!(matches!(error, QuickwitError::Metastore(_)));
+        assert_eq!(error.http_status_code(), StatusCode::NOT_FOUND);
+
+        let grpc_status = error.grpc_status();
+        assert_eq!(grpc_status.code(), tonic::Code::NotFound);
+        assert_eq!(grpc_status.message(), error.to_string());
+    }
+
+    #[test]
+    fn test_quickwit_error_from_metastore_resolver_error() {
+        let uri = Uri::for_test("postgresql://localhost:5432/metastore");
+
+        let error = QuickwitError::from(MetastoreResolverError::ConnectionRefused(uri.clone()));
+        assert!(matches!(error, QuickwitError::MetastoreResolver(_)));
+        assert_eq!(error.http_status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.grpc_status().code(), tonic::Code::Unavailable);
+
+        let error = QuickwitError::from(MetastoreResolverError::AuthenticationFailed(
+            "invalid password".to_string(),
+        ));
+        assert_eq!(error.http_status_code(), StatusCode::UNAUTHORIZED);
+        assert_eq!(error.grpc_status().code(), tonic::Code::Unauthenticated);
+
+        let error = QuickwitError::from(MetastoreResolverError::Timeout {
+            uri,
+            elapsed: Duration::from_secs(10),
+        });
+        assert_eq!(error.http_status_code(), StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(error.grpc_status().code(), tonic::Code::DeadlineExceeded);
+
+        let error = QuickwitError::from(MetastoreResolverError::InvalidUri(
+            "missing host".to_string(),
+        ));
+        assert_eq!(error.http_status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(error.grpc_status().code(), tonic::Code::InvalidArgument);
+
+        let error = QuickwitError::from(MetastoreResolverError::Initialization(
+            MetastoreError::Unavailable("metastore is down".to_string()),
+        ));
+        assert_eq!(error.http_status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_quickwit_error_from_anyhow_error() {
+        let error = QuickwitError::from(anyhow::anyhow!("something went wrong"));
+        assert!(matches!(error, QuickwitError::Internal(_)));
+        assert_eq!(error.to_string(), "internal error: something went wrong");
+        assert_eq!(error.http_status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.grpc_status().code(), tonic::Code::Internal);
+    }
+}
@@ -23,7 +23,7 @@ use std::sync::Arc;
 
 use bytesize::ByteSize;
 use quickwit_cluster::cluster_grpc_server;
-use quickwit_common::tower::BoxFutureInfaillible;
+use quickwit_common::tower::{AuthTokenValidator, BoxFutureInfaillible, GrpcTracingLayer};
 use quickwit_config::service::QuickwitService;
 use quickwit_proto::developer::DeveloperServiceClient;
 use quickwit_proto::indexing::IndexingServiceClient;
@@ -31,11 +31,13 @@ use quickwit_proto::jaeger::storage::v1::span_reader_plugin_server::SpanReaderPl
 use quickwit_proto::opentelemetry::proto::collector::logs::v1::logs_service_server::LogsServiceServer;
 use quickwit_proto::opentelemetry::proto::collector::trace::v1::trace_service_server::TraceServiceServer;
 use quickwit_proto::search::search_service_server::SearchServiceServer;
-use quickwit_proto::tonic::codegen::CompressionEncoding;
+use quickwit_proto::tonic::codegen::{CompressionEncoding, InterceptedService};
 use quickwit_proto::tonic::transport::Server;
+use tonic_health::server::{Health, HealthServer};
 use tracing::*;
 
 use crate::developer_api::DeveloperApiServer;
+use crate::grpc_health::{grpc_service_name, GrpcHealthReporter};
 use crate::search_api::GrpcSearchAdapter;
 use crate::{QuickwitServices, INDEXING_GRPC_SERVER_METRICS_LAYER};
 
@@ -44,18 +46,34 @@ pub(crate) async fn start_grpc_server(
     grpc_listen_addr: SocketAddr,
     max_message_size: ByteSize,
     services: Arc<QuickwitServices>,
+    grpc_health_reporter: GrpcHealthReporter,
+    grpc_health_service: HealthServer<impl Health>,
     readiness_trigger: BoxFutureInfaillible<()>,
     shutdown_signal: BoxFutureInfaillible<()>,
 ) -> anyhow::Result<()> {
     let mut enabled_grpc_services = BTreeSet::new();
-    let mut server = Server::builder();
+    let mut server = Server::builder().layer(GrpcTracingLayer);
 
-    let cluster_grpc_service = cluster_grpc_server(services.cluster.clone());
+    // The services called by the other nodes of the cluster require the auth token derived from the
+    // cluster key. The health, reflection, Jaeger, and OTLP services remain open to external
+    // clients.
+    let auth_token_validator = AuthTokenValidator::new(&services.node_config.cluster_key);
+
+    if auth_token_validator.is_enabled() {
+        info!("gRPC authentication is enabled");
+    }
+    let cluster_grpc_service = with_auth(
+        cluster_grpc_server(services.cluster.clone()),
+        &auth_token_validator,
+    );
 
     // Mount gRPC metastore service if `QuickwitService::Metastore` is enabled on node.
     let metastore_grpc_service = if let Some(metastore_server) = &services.metastore_server_opt {
         enabled_grpc_services.insert("metastore");
-        Some(metastore_server.as_grpc_service(max_message_size))
+        Some(with_auth(
+            metastore_server.as_grpc_service(max_message_size),
+            &auth_token_validator,
+        ))
     } else {
         None
     };
@@ -69,7 +87,10 @@ pub(crate) async fn start_grpc_server(
             let indexing_service = IndexingServiceClient::tower()
                 .stack_layer(INDEXING_GRPC_SERVER_METRICS_LAYER.clone())
                 .build_from_mailbox(indexing_service);
-            Some(indexing_service.as_grpc_service(max_message_size))
+            Some(with_auth(
+                indexing_service.as_grpc_service(max_message_size),
+                &auth_token_validator,
+            ))
         } else {
             None
         }
@@ -82,7 +103,10 @@ pub(crate) async fn start_grpc_server(
         .is_service_enabled(QuickwitService::Indexer)
     {
         enabled_grpc_services.insert("ingest-api");
-        Some(services.ingest_service.as_grpc_service(max_message_size))
+        Some(with_auth(
+            services.ingest_service.as_grpc_service(max_message_size),
+            &auth_token_validator,
+        ))
     } else {
         None
     };
@@ -95,14 +119,17 @@ pub(crate) async fn start_grpc_server(
         let ingest_router_service = services
             .ingest_router_service
             .as_grpc_service(max_message_size);
-        Some(ingest_router_service)
+        Some(with_auth(ingest_router_service, &auth_token_validator))
     } else {
         None
     };
 
     let ingester_grpc_service = if let Some(ingester_service) = services.ingester_service() {
         enabled_grpc_services.insert("ingester");
-        Some(ingester_service.as_grpc_service(max_message_size))
+        Some(with_auth(
+            ingester_service.as_grpc_service(max_message_size),
+            &auth_token_validator,
+        ))
     } else {
         None
     };
@@ -113,11 +140,10 @@ pub(crate) async fn start_grpc_server(
         .is_service_enabled(QuickwitService::ControlPlane)
     {
         enabled_grpc_services.insert("control-plane");
-        Some(
-            services
-                .control_plane_client
-                .as_grpc_service(max_message_size),
-        )
+        let control_plane_service = services
+            .control_plane_client
+            .as_grpc_service(max_message_size);
+        Some(with_auth(control_plane_service, &auth_token_validator))
     } else {
         None
     };
@@ -148,11 +174,10 @@ pub(crate) async fn start_grpc_server(
         enabled_grpc_services.insert("search");
         let search_service = services.search_service.clone();
         let grpc_search_service = GrpcSearchAdapter::from(search_service);
-        Some(
-            SearchServiceServer::new(grpc_search_service)
-                .max_decoding_message_size(max_message_size.0 as usize)
-                .max_encoding_message_size(max_message_size.0 as usize),
-        )
+        let search_service = SearchServiceServer::new(grpc_search_service)
+            .max_decoding_message_size(max_message_size.0 as usize)
+            .max_encoding_message_size(max_message_size.0 as usize);
+        Some(with_auth(search_service, &auth_token_validator))
     } else {
         None
     };
@@ -169,10 +194,52 @@ pub(crate) async fn start_grpc_server(
 
         let developer_service = DeveloperApiServer::from_services(&services);
 
-        DeveloperServiceClient::new(developer_service)
-            .as_grpc_service(DeveloperApiServer::MAX_GRPC_MESSAGE_SIZE)
+        let developer_grpc_service = DeveloperServiceClient::new(developer_service)
+            .as_grpc_service(DeveloperApiServer::MAX_GRPC_MESSAGE_SIZE);
+        with_auth(developer_grpc_service, &auth_token_validator)
+    };
+    // Mount gRPC reflection service if enabled.
+    let reflection_grpc_service = if services.node_config.grpc_config.enable_reflection {
+        enabled_grpc_services.insert("reflection");
+
+        let reflection_service = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(quickwit_proto::GRPC_FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_reflection::pb::FILE_DESCRIPTOR_SET)
+            .build()?;
+        Some(reflection_service)
+    } else {
+        None
     };
+    // The services that are not mounted remain unknown to the health service.
+    let mut enabled_grpc_service_names = vec![
+        grpc_service_name(&cluster_grpc_service),
+        grpc_service_name(&developer_grpc_service),
+    ];
+    enabled_grpc_service_names.extend(
+        [
+            control_plane_grpc_service.as_ref().map(grpc_service_name),
+            indexing_grpc_service.as_ref().map(grpc_service_name),
+            ingest_api_grpc_service.as_ref().map(grpc_service_name),
+            ingest_router_grpc_service.as_ref().map(grpc_service_name),
+            ingester_grpc_service.as_ref().map(grpc_service_name),
+            jaeger_grpc_service.as_ref().map(grpc_service_name),
+            metastore_grpc_service.as_ref().map(grpc_service_name),
+            otlp_log_grpc_service.as_ref().map(grpc_service_name),
+            otlp_trace_grpc_service.as_ref().map(grpc_service_name),
+            reflection_grpc_service.as_ref().map(grpc_service_name),
+            search_grpc_service.as_ref().map(grpc_service_name),
+        ]
+        .into_iter()
+        .flatten(),
+    );
+    grpc_health_reporter
+        .register_services(enabled_grpc_service_names)
+        .await;
+    enabled_grpc_services.insert("health");
+
     let server_router = server
+        .add_service(grpc_health_service)
         .add_service(cluster_grpc_service)
         .add_service(developer_grpc_service)
         .add_optional_service(control_plane_grpc_service)
@@ -184,6 +251,7 @@ pub(crate) async fn start_grpc_server(
         .add_optional_service(metastore_grpc_service)
         .add_optional_service(otlp_log_grpc_service)
         .add_optional_service(otlp_trace_grpc_service)
+        .add_optional_service(reflection_grpc_service)
         .add_optional_service(search_grpc_service);
 
     info!(
@@ -196,3 +264,104 @@ pub(crate) async fn start_grpc_server(
     serve_res?;
     Ok(())
 }
+
+/// Wraps a gRPC service so that it rejects the requests that do not carry the auth token derived
+/// from the cluster key.
+fn with_auth<S>(
+    service: S,
+    auth_token_validator: &AuthTokenValidator,
+) -> InterceptedService<S, AuthTokenValidator> {
+    InterceptedService::new(service, auth_token_validator.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_common::tower::GrpcClientInterceptor;
+    use quickwit_proto::cluster::cluster_service_grpc_client::ClusterServiceGrpcClient;
+    use quickwit_proto::cluster::{
+        ClusterService, ClusterServiceClient, FetchClusterStateRequest, FetchClusterStateResponse,
+        MockClusterService,
+    };
+    use quickwit_proto::tonic::transport::Endpoint;
+    use quickwit_proto::tonic::Code;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_grpc_auth_token() {
+        let mut mock_cluster_service = MockClusterService::new();
+        mock_cluster_service
+            .expect_fetch_cluster_state()
+            .once()
+            .returning(|request| {
+                Ok(FetchClusterStateResponse {
+                    cluster_id: request.cluster_id,
+                    node_states: Vec::new(),
+                })
+            });
+        let cluster_grpc_service =
+            ClusterServiceClient::from_mock(mock_cluster_service).as_grpc_service(ByteSize::mib(1));
+        let auth_token_validator = AuthTokenValidator::new("test-cluster-key");
+
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(with_auth(cluster_grpc_service, &auth_token_validator))
+                .serve_with_incoming(futures::stream::iter(vec![Ok::<_, std::io::Error>(
+                    server_stream,
+                )]))
+                .await
+        });
+        let mut client_stream_opt = Some(client_stream);
+        let channel = Endpoint::try_from("http://test.server")
+            .unwrap()
+            .connect_with_connector(tower::service_fn(move |_: hyper::Uri| {
+                let client_stream_opt = client_stream_opt.take();
+                async move {
+                    client_stream_opt.ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::Other, "client already taken")
+                    })
+                }
+            }))
+            .await
+            .unwrap();
+
+        // Requests without the auth token are rejected.
+        let status = ClusterServiceGrpcClient::new(channel.clone())
+            .fetch_cluster_state(FetchClusterStateRequest {
+                cluster_id: "test-cluster".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+
+        // Requests with an auth token derived from another cluster key are rejected.
+        let addr: SocketAddr = "127.0.0.1:7281".parse().unwrap();
+        ClusterServiceClient::from_channel(
+            addr,
+            channel.clone(),
+            ByteSize::mib(1),
+            GrpcClientInterceptor::new("other-cluster-key"),
+        )
+        .fetch_cluster_state(FetchClusterStateRequest {
+            cluster_id: "test-cluster".to_string(),
+        })
+        .await
+        .unwrap_err();
+
+        // Clients built with the cluster key inject the auth token.
+        let mut cluster_client = ClusterServiceClient::from_channel(
+            addr,
+            channel,
+            ByteSize::mib(1),
+            GrpcClientInterceptor::new("test-cluster-key"),
+        );
+        let response = cluster_client
+            .fetch_cluster_state(FetchClusterStateRequest {
+                cluster_id: "test-cluster".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.cluster_id, "test-cluster");
+    }
+}
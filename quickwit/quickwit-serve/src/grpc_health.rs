@@ -0,0 +1,242 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::sync::{Arc, Mutex};
+
+use quickwit_proto::tonic::server::NamedService;
+use tonic_health::server::{health_reporter, Health, HealthReporter, HealthServer};
+use tonic_health::ServingStatus;
+use tracing::info;
+
+/// Name under which the overall health of the node is reported.
+const NODE_SERVICE_NAME: &str = "";
+
+#[derive(Debug, Default)]
+struct GrpcHealthState {
+    enabled_service_names: Vec<&'static str>,
+    is_serving: bool,
+    is_draining: bool,
+}
+
+/// Reports the health of the gRPC services of the node according to the gRPC health checking
+/// protocol (`grpc.health.v1.Health`), which Kubernetes probes and Envoy rely on.
+///
+/// The enabled services and the node as a whole (empty service name) are `NOT_SERVING` until the
+/// node startup completes, then `SERVING` as long as the node is ready, and `NOT_SERVING` again
+/// once the node starts shutting down. The services that the node does not run are unknown to
+/// the health service, for which the protocol mandates a `NOT_FOUND` error on `Check` and a
+/// `SERVICE_UNKNOWN` status on `Watch`.
+#[derive(Clone)]
+pub(crate) struct GrpcHealthReporter {
+    health_reporter: HealthReporter,
+    state: Arc<Mutex<GrpcHealthState>>,
+}
+
+impl GrpcHealthReporter {
+    pub fn new() -> (Self, HealthServer<impl Health>) {
+        let (health_reporter, health_server) = health_reporter();
+        let grpc_health_reporter = Self {
+            health_reporter,
+            state: Arc::default(),
+        };
+        (grpc_health_reporter, health_server)
+    }
+
+    /// Registers the gRPC services mounted on the server. Must be called before updating the
+    /// health of the node.
+    pub async fn register_services(&self, enabled_service_names: Vec<&'static str>) {
+        let mut health_reporter = self.health_reporter.clone();
+
+        for service_name in enabled_service_names
+            .iter()
+            .copied()
+            .chain([NODE_SERVICE_NAME])
+        {
+            health_reporter
+                .set_service_status(service_name, ServingStatus::NotServing)
+                .await;
+        }
+        self.state.lock().unwrap().enabled_service_names = enabled_service_names;
+    }
+
+    /// Reports the services as `SERVING` or `NOT_SERVING` depending on the node readiness. This
+    /// is a no-op once the node is draining.
+    pub async fn set_serving(&self, is_serving: bool) {
+        let service_names = {
+            let mut state = self.state.lock().unwrap();
+
+            if state.is_draining || state.is_serving == is_serving {
+                return;
+            }
+            state.is_serving = is_serving;
+            state.enabled_service_names.clone()
+        };
+        let status = if is_serving {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        self.set_status(&service_names, status).await;
+    }
+
+    /// Reports the services as `NOT_SERVING` for good so that load balancers stop routing
+    /// requests to the node while it shuts down.
+    pub async fn set_draining(&self) {
+        let service_names = {
+            let mut state = self.state.lock().unwrap();
+
+            if state.is_draining {
+                return;
+            }
+            state.is_draining = true;
+            state.is_serving = false;
+            state.enabled_service_names.clone()
+        };
+        info!("reporting gRPC services as not serving");
+        self.set_status(&service_names, ServingStatus::NotServing)
+            .await;
+    }
+
+    async fn set_status(&self, service_names: &[&'static str], status: ServingStatus) {
+        let mut health_reporter = self.health_reporter.clone();
+
+        for service_name in service_names.iter().copied().chain([NODE_SERVICE_NAME]) {
+            health_reporter
+                .set_service_status(service_name, status)
+                .await;
+        }
+    }
+}
+
+/// Returns the fully qualified name of a gRPC service, for instance
+/// `quickwit.search.SearchService`.
+pub(crate) fn grpc_service_name<S: NamedService>(_service: &S) -> &'static str {
+    S::NAME
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::tonic::transport::{Endpoint, Server};
+    use quickwit_proto::tonic::Code;
+    use tonic_health::pb::health_check_response::ServingStatus as ProtoServingStatus;
+    use tonic_health::pb::health_client::HealthClient;
+    use tonic_health::pb::HealthCheckRequest;
+
+    use super::*;
+
+    async fn check(
+        health_client: &mut HealthClient<quickwit_proto::tonic::transport::Channel>,
+        service_name: &str,
+    ) -> Result<ProtoServingStatus, Code> {
+        let request = HealthCheckRequest {
+            service: service_name.to_string(),
+        };
+        health_client
+            .check(request)
+            .await
+            .map(|response| response.into_inner().status())
+            .map_err(|status| status.code())
+    }
+
+    #[tokio::test]
+    async fn test_grpc_health_reporter_status_transitions() {
+        let (grpc_health_reporter, health_server) = GrpcHealthReporter::new();
+        grpc_health_reporter
+            .register_services(vec!["quickwit.search.SearchService"])
+            .await;
+
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(health_server)
+                .serve_with_incoming(futures::stream::iter(vec![Ok::<_, std::io::Error>(
+                    server_stream,
+                )]))
+                .await
+        });
+        let mut client_stream_opt = Some(client_stream);
+        let channel = Endpoint::try_from("http://test.server")
+            .unwrap()
+            .connect_with_connector(tower::service_fn(move |_: hyper::Uri| {
+                let client_stream_opt = client_stream_opt.take();
+                async move {
+                    client_stream_opt.ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::Other, "client already taken")
+                    })
+                }
+            }))
+            .await
+            .unwrap();
+        let mut health_client = HealthClient::new(channel);
+
+        // Startup.
+        assert_eq!(
+            check(&mut health_client, "").await.unwrap(),
+            ProtoServingStatus::NotServing
+        );
+        assert_eq!(
+            check(&mut health_client, "quickwit.search.SearchService")
+                .await
+                .unwrap(),
+            ProtoServingStatus::NotServing
+        );
+        assert_eq!(
+            check(&mut health_client, "quickwit.indexing.IndexingService")
+                .await
+                .unwrap_err(),
+            Code::NotFound
+        );
+
+        // Startup completed.
+        grpc_health_reporter.set_serving(true).await;
+        assert_eq!(
+            check(&mut health_client, "").await.unwrap(),
+            ProtoServingStatus::Serving
+        );
+        assert_eq!(
+            check(&mut health_client, "quickwit.search.SearchService")
+                .await
+                .unwrap(),
+            ProtoServingStatus::Serving
+        );
+
+        // Metastore unavailable.
+        grpc_health_reporter.set_serving(false).await;
+        assert_eq!(
+            check(&mut health_client, "quickwit.search.SearchService")
+                .await
+                .unwrap(),
+            ProtoServingStatus::NotServing
+        );
+        grpc_health_reporter.set_serving(true).await;
+
+        // Shutdown.
+        grpc_health_reporter.set_draining().await;
+        assert_eq!(
+            check(&mut health_client, "").await.unwrap(),
+            ProtoServingStatus::NotServing
+        );
+        grpc_health_reporter.set_serving(true).await;
+        assert_eq!(
+            check(&mut health_client, "quickwit.search.SearchService")
+                .await
+                .unwrap(),
+            ProtoServingStatus::NotServing
+        );
+    }
+}
@@ -17,10 +17,19 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use quickwit_actors::{Healthz, Mailbox};
 use quickwit_cluster::Cluster;
+use quickwit_common::uri::Uri;
+use quickwit_indexing::models::ListStalledPipelines;
 use quickwit_indexing::IndexingService;
 use quickwit_janitor::JanitorService;
+use quickwit_proto::metastore::{MetastoreService, MetastoreServiceClient};
+use quickwit_storage::StorageResolver;
+use serde::Serialize;
 use tracing::error;
 use warp::hyper::StatusCode;
 use warp::reply::with_status;
@@ -28,17 +37,49 @@ use warp::{Filter, Rejection};
 
 use crate::with_arg;
 
+/// Maximum amount of time the runtime may take to poll a freshly spawned task before the node is
+/// considered deadlocked.
+const RUNTIME_RESPONSIVENESS_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(utoipa::OpenApi)]
 #[openapi(paths(get_liveness, get_readiness))]
 pub struct HealthCheckApi;
 
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(get_live_probe, get_ready_probe, get_startup_probe),
+    components(schemas(ProbeResponse))
+)]
+pub struct HealthzApi;
+
+/// Dependencies checked by the Kubernetes-style `/healthz/*` probes.
+#[derive(Clone)]
+pub(crate) struct ProbeTargets {
+    pub metastore: MetastoreServiceClient,
+    pub storage_resolver: StorageResolver,
+    pub default_index_root_uri: Uri,
+    /// Set once the node has completed its startup sequence.
+    pub startup_completed: Arc<AtomicBool>,
+}
+
 /// Health check handlers.
 pub(crate) fn health_check_handlers(
     cluster: Cluster,
     indexer_service_opt: Option<Mailbox<IndexingService>>,
     janitor_service_opt: Option<Mailbox<JanitorService>>,
+    probe_targets: ProbeTargets,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    liveness_handler(indexer_service_opt, janitor_service_opt).or(readiness_handler(cluster))
+    liveness_handler(indexer_service_opt.clone(), janitor_service_opt.clone())
+        .or(readiness_handler(cluster))
+        .or(live_probe_handler(
+            indexer_service_opt.clone(),
+            janitor_service_opt,
+        ))
+        .or(ready_probe_handler(
+            indexer_service_opt,
+            probe_targets.clone(),
+        ))
+        .or(startup_probe_handler(probe_targets))
 }
 
 fn liveness_handler(
@@ -61,6 +102,37 @@ fn readiness_handler(
         .then(get_readiness)
 }
 
+fn live_probe_handler(
+    indexer_service_opt: Option<Mailbox<IndexingService>>,
+    janitor_service_opt: Option<Mailbox<JanitorService>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("healthz" / "live")
+        .and(warp::get())
+        .and(with_arg(indexer_service_opt))
+        .and(with_arg(janitor_service_opt))
+        .then(get_live_probe)
+}
+
+fn ready_probe_handler(
+    indexer_service_opt: Option<Mailbox<IndexingService>>,
+    probe_targets: ProbeTargets,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("healthz" / "ready")
+        .and(warp::get())
+        .and(with_arg(indexer_service_opt))
+        .and(with_arg(probe_targets))
+        .then(get_ready_probe)
+}
+
+fn startup_probe_handler(
+    probe_targets: ProbeTargets,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("healthz" / "startup")
+        .and(warp::get())
+        .and(with_arg(probe_targets))
+        .then(get_startup_probe)
+}
+
 #[utoipa::path(
     get,
     tag = "Node Health",
@@ -117,10 +189,159 @@ async fn get_readiness(cluster: Cluster) -> impl warp::Reply {
     with_status(warp::reply::json(&is_ready), status_code)
 }
 
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+struct ProbeResponse {
+    /// `live`, `ready`, or `started` when the probe succeeds, prefixed with `not_` otherwise.
+    status: &'static str,
+    /// Why the probe failed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reasons: Vec<String>,
+}
+
+fn into_probe_reply(
+    reasons: Vec<String>,
+    success_status: &'static str,
+    failure_status: &'static str,
+) -> impl warp::Reply {
+    let (status, status_code) = if reasons.is_empty() {
+        (success_status, StatusCode::OK)
+    } else {
+        (failure_status, StatusCode::SERVICE_UNAVAILABLE)
+    };
+    let probe_response = ProbeResponse { status, reasons };
+    with_status(warp::reply::json(&probe_response), status_code)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Node Health",
+    path = "/live",
+    responses(
+        (status = 200, description = "The process is alive.", body = ProbeResponse),
+        (status = 503, description = "The process is deadlocked or one of its services is unhealthy.", body = ProbeResponse),
+    ),
+)]
+/// Kubernetes Liveness Probe
+///
+/// Checks that the async runtime still polls new tasks and that the indexer and janitor services
+/// are healthy.
+async fn get_live_probe(
+    indexer_service_opt: Option<Mailbox<IndexingService>>,
+    janitor_service_opt: Option<Mailbox<JanitorService>>,
+) -> impl warp::Reply {
+    let mut reasons = Vec::new();
+
+    let runtime_probe =
+        tokio::time::timeout(RUNTIME_RESPONSIVENESS_TIMEOUT, tokio::spawn(async {}));
+    if !matches!(runtime_probe.await, Ok(Ok(()))) {
+        reasons.push("async runtime is unresponsive".to_string());
+    }
+    if let Some(indexer_service) = indexer_service_opt {
+        if !indexer_service.ask(Healthz).await.unwrap_or(false) {
+            reasons.push("indexer service is unhealthy".to_string());
+        }
+    }
+    if let Some(janitor_service) = janitor_service_opt {
+        if !janitor_service.ask(Healthz).await.unwrap_or(false) {
+            reasons.push("janitor service is unhealthy".to_string());
+        }
+    }
+    into_probe_reply(reasons, "live", "not_live")
+}
+
+#[utoipa::path(
+    get,
+    tag = "Node Health",
+    path = "/ready",
+    responses(
+        (status = 200, description = "The node is ready to serve traffic.", body = ProbeResponse),
+        (status = 503, description = "The node is not ready to serve traffic.", body = ProbeResponse),
+    ),
+)]
+/// Kubernetes Readiness Probe
+///
+/// Checks that the metastore and the default index storage are reachable and that all the
+/// indexing pipelines of the node are running.
+async fn get_ready_probe(
+    indexer_service_opt: Option<Mailbox<IndexingService>>,
+    mut probe_targets: ProbeTargets,
+) -> impl warp::Reply {
+    let mut reasons = Vec::new();
+
+    if let Err(error) = probe_targets.metastore.check_connectivity().await {
+        reasons.push(format!("metastore is unreachable: {error}"));
+    }
+    let storage_connectivity_res = match probe_targets
+        .storage_resolver
+        .resolve(&probe_targets.default_index_root_uri)
+        .await
+    {
+        Ok(storage) => storage.check_connectivity().await,
+        Err(error) => Err(error.into()),
+    };
+    if let Err(error) = storage_connectivity_res {
+        reasons.push(format!(
+            "storage `{}` is unreachable: {error}",
+            probe_targets.default_index_root_uri
+        ));
+    }
+    if let Some(indexer_service) = indexer_service_opt {
+        match indexer_service.ask(ListStalledPipelines).await {
+            Ok(stalled_pipeline_ids) => {
+                for pipeline_id in stalled_pipeline_ids {
+                    reasons.push(format!(
+                        "source `{}` of index `{}` is not running",
+                        pipeline_id.source_id, pipeline_id.index_uid.index_id
+                    ));
+                }
+            }
+            Err(error) => {
+                reasons.push(format!("indexer service is unavailable: {error}"));
+            }
+        }
+    }
+    into_probe_reply(reasons, "ready", "not_ready")
+}
+
+#[utoipa::path(
+    get,
+    tag = "Node Health",
+    path = "/startup",
+    responses(
+        (status = 200, description = "The node has started.", body = ProbeResponse),
+        (status = 503, description = "The node is still starting.", body = ProbeResponse),
+    ),
+)]
+/// Kubernetes Startup Probe
+///
+/// Succeeds once the node has reported itself ready for the first time. The searcher split cache
+/// is loaded from disk before the servers start, so it is warm by then.
+async fn get_startup_probe(probe_targets: ProbeTargets) -> impl warp::Reply {
+    let mut reasons = Vec::new();
+
+    if !probe_targets.startup_completed.load(Ordering::Relaxed) {
+        reasons.push("node is still starting".to_string());
+    }
+    into_probe_reply(reasons, "started", "not_started")
+}
+
 #[cfg(test)]
 mod tests {
 
     use quickwit_cluster::{create_cluster_for_test, ChannelTransport};
+    use quickwit_proto::metastore::{MetastoreError, MockMetastoreService};
+    use serde_json::Value as JsonValue;
+
+    use super::*;
+
+    fn probe_targets_for_test(mock_metastore: MockMetastoreService) -> ProbeTargets {
+        ProbeTargets {
+            metastore: MetastoreServiceClient::from_mock(mock_metastore),
+            storage_resolver: StorageResolver::for_test(),
+            default_index_root_uri: Uri::for_test("ram:///indexes"),
+            startup_completed: Arc::new(AtomicBool::new(false)),
+        }
+    }
 
     #[tokio::test]
     async fn test_rest_search_api_health_checks() {
@@ -128,7 +349,9 @@ mod tests {
         let cluster = create_cluster_for_test(Vec::new(), &[], &transport, false)
             .await
             .unwrap();
-        let health_check_handler = super::health_check_handlers(cluster.clone(), None, None);
+        let probe_targets = probe_targets_for_test(MockMetastoreService::new());
+        let health_check_handler =
+            super::health_check_handlers(cluster.clone(), None, None, probe_targets);
         let resp = warp::test::request()
             .path("/health/livez")
             .reply(&health_check_handler)
@@ -146,4 +369,70 @@ mod tests {
             .await;
         assert_eq!(resp.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_healthz_probes() {
+        let transport = ChannelTransport::default();
+        let cluster = create_cluster_for_test(Vec::new(), &[], &transport, false)
+            .await
+            .unwrap();
+        let mut mock_metastore = MockMetastoreService::new();
+        let mut num_connectivity_checks = 0;
+        mock_metastore
+            .expect_check_connectivity()
+            .times(2)
+            .returning(move || {
+                num_connectivity_checks += 1;
+                if num_connectivity_checks == 1 {
+                    return Ok(());
+                }
+                Err(MetastoreError::Unavailable("connection refused".to_string()).into())
+            });
+        let probe_targets = probe_targets_for_test(mock_metastore);
+        let startup_completed = probe_targets.startup_completed.clone();
+        let health_check_handler = health_check_handlers(cluster, None, None, probe_targets);
+
+        let resp = warp::test::request()
+            .path("/healthz/live")
+            .reply(&health_check_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"status": "live"}));
+
+        let resp = warp::test::request()
+            .path("/healthz/startup")
+            .reply(&health_check_handler)
+            .await;
+        assert_eq!(resp.status(), 503);
+        startup_completed.store(true, Ordering::Relaxed);
+
+        let resp = warp::test::request()
+            .path("/healthz/startup")
+            .reply(&health_check_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/healthz/ready")
+            .reply(&health_check_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"status": "ready"}));
+
+        let resp = warp::test::request()
+            .path("/healthz/ready")
+            .reply(&health_check_handler)
+            .await;
+        assert_eq!(resp.status(), 503);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["status"], "not_ready");
+        let reasons = resp_json["reasons"].as_array().unwrap();
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0]
+            .as_str()
+            .unwrap()
+            .starts_with("metastore is unreachable"));
+    }
 }
@@ -0,0 +1,342 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Periodically samples the Prometheus counters of the node so that the index stats endpoint can
+//! report rates, such as the indexing throughput or the search QPS, over a sliding window.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use quickwit_common::metrics::{CacheMetrics, QuickwitMetrics};
+use quickwit_proto::types::IndexId;
+
+const SAMPLING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Length of the window over which the rates are computed.
+const SAMPLING_WINDOW: Duration = Duration::from_secs(60);
+
+static INDEX_METRICS_SAMPLER: Lazy<IndexMetricsSampler> = Lazy::new(IndexMetricsSampler::default);
+
+/// Value of the per-index counters at a given point in time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct IndexCounters {
+    num_valid_docs_processed: u64,
+    num_search_requests: u64,
+    num_timed_search_requests: u64,
+    search_duration_secs: f64,
+}
+
+#[derive(Clone, Debug)]
+struct MetricsSnapshot {
+    taken_at: Instant,
+    index_counters: HashMap<IndexId, IndexCounters>,
+    num_cache_hits: u64,
+    num_cache_misses: u64,
+}
+
+impl MetricsSnapshot {
+    fn take() -> Self {
+        let metrics = QuickwitMetrics::global();
+        let mut index_counters: HashMap<IndexId, IndexCounters> = HashMap::new();
+
+        for (labels, value) in metrics.indexer.processed_docs_total.collect_values() {
+            if labels.get("docs_processed_status").map(String::as_str) != Some("valid") {
+                continue;
+            }
+            if let Some(index_id) = labels.get("index") {
+                index_counters
+                    .entry(index_id.clone())
+                    .or_default()
+                    .num_valid_docs_processed += value;
+            }
+        }
+        for (labels, value) in metrics.search.root_search_requests_total.collect_values() {
+            if let Some(index_id) = labels.get("index") {
+                index_counters
+                    .entry(index_id.clone())
+                    .or_default()
+                    .num_search_requests += value;
+            }
+        }
+        for (labels, sum, count) in metrics
+            .search
+            .root_search_request_duration_secs
+            .collect_sums_and_counts()
+        {
+            if let Some(index_id) = labels.get("index") {
+                let counters = index_counters.entry(index_id.clone()).or_default();
+                counters.num_timed_search_requests += count;
+                counters.search_duration_secs += sum;
+            }
+        }
+        // The caches are shared by all the indexes, so the hit rate is a node-level figure.
+        let search_caches: [&CacheMetrics; 6] = [
+            &metrics.cache.shortlived_cache,
+            &metrics.cache.partial_request_cache,
+            &metrics.cache.fd_cache_metrics,
+            &metrics.cache.fast_field_cache,
+            &metrics.cache.split_footer_cache,
+            &metrics.cache.searcher_split_cache,
+        ];
+        let num_cache_hits = search_caches
+            .iter()
+            .map(|cache| cache.hits_num_items.get())
+            .sum();
+        let num_cache_misses = search_caches
+            .iter()
+            .map(|cache| cache.misses_num_items.get())
+            .sum();
+
+        MetricsSnapshot {
+            taken_at: Instant::now(),
+            index_counters,
+            num_cache_hits,
+            num_cache_misses,
+        }
+    }
+
+    fn counters(&self, index_id: &str) -> IndexCounters {
+        self.index_counters
+            .get(index_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Rates of an index computed over the sampling window.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct IndexRates {
+    pub indexing_throughput_docs_per_sec: f64,
+    pub search_qps: f64,
+    /// `None` if no search request completed during the window.
+    pub average_search_latency_ms: Option<f64>,
+    /// `None` if the search caches were not accessed during the window.
+    pub cache_hit_rate: Option<f64>,
+}
+
+#[derive(Default)]
+struct IndexMetricsSampler {
+    snapshots: Mutex<VecDeque<MetricsSnapshot>>,
+}
+
+impl IndexMetricsSampler {
+    fn record_snapshot(&self, snapshot: MetricsSnapshot) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        // We keep the most recent snapshot taken before the start of the window, so that the rates
+        // always cover the entire window once the sampler has been running long enough.
+        while snapshots.len() >= 2
+            && snapshot.taken_at.duration_since(snapshots[1].taken_at) >= SAMPLING_WINDOW
+        {
+            snapshots.pop_front();
+        }
+        snapshots.push_back(snapshot);
+    }
+
+    fn index_rates(&self, index_id: &str, current_snapshot: &MetricsSnapshot) -> IndexRates {
+        let snapshots = self.snapshots.lock().unwrap();
+        let Some(baseline_snapshot) = snapshots.front() else {
+            return IndexRates::default();
+        };
+        let elapsed_secs = current_snapshot
+            .taken_at
+            .saturating_duration_since(baseline_snapshot.taken_at)
+            .as_secs_f64();
+
+        if elapsed_secs == 0.0 {
+            return IndexRates::default();
+        }
+        let baseline_counters = baseline_snapshot.counters(index_id);
+        let current_counters = current_snapshot.counters(index_id);
+
+        let num_valid_docs_processed = current_counters
+            .num_valid_docs_processed
+            .saturating_sub(baseline_counters.num_valid_docs_processed);
+        let num_search_requests = current_counters
+            .num_search_requests
+            .saturating_sub(baseline_counters.num_search_requests);
+        let num_timed_search_requests = current_counters
+            .num_timed_search_requests
+            .saturating_sub(baseline_counters.num_timed_search_requests);
+        let search_duration_secs = (current_counters.search_duration_secs
+            - baseline_counters.search_duration_secs)
+            .max(0.0);
+        let average_search_latency_ms = (num_timed_search_requests > 0)
+            .then(|| search_duration_secs * 1_000.0 / num_timed_search_requests as f64);
+
+        let num_cache_hits = current_snapshot
+            .num_cache_hits
+            .saturating_sub(baseline_snapshot.num_cache_hits);
+        let num_cache_misses = current_snapshot
+            .num_cache_misses
+            .saturating_sub(baseline_snapshot.num_cache_misses);
+        let num_cache_lookups = num_cache_hits + num_cache_misses;
+        let cache_hit_rate =
+            (num_cache_lookups > 0).then(|| num_cache_hits as f64 / num_cache_lookups as f64);
+
+        IndexRates {
+            indexing_throughput_docs_per_sec: num_valid_docs_processed as f64 / elapsed_secs,
+            search_qps: num_search_requests as f64 / elapsed_secs,
+            average_search_latency_ms,
+            cache_hit_rate,
+        }
+    }
+}
+
+/// Samples the metrics of the node every few seconds, for the lifetime of the process.
+pub(crate) async fn index_metrics_sampling_loop() {
+    let mut interval = tokio::time::interval(SAMPLING_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        INDEX_METRICS_SAMPLER.record_snapshot(MetricsSnapshot::take());
+    }
+}
+
+/// Returns the rates of an index observed by this node over the sampling window. The rates are
+/// all zero until the sampling loop has recorded its first snapshot.
+pub(crate) fn index_rates(index_id: &str) -> IndexRates {
+    INDEX_METRICS_SAMPLER.index_rates(index_id, &MetricsSnapshot::take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_for_test(
+        taken_at: Instant,
+        index_counters: IndexCounters,
+        num_cache_hits: u64,
+        num_cache_misses: u64,
+    ) -> MetricsSnapshot {
+        MetricsSnapshot {
+            taken_at,
+            index_counters: HashMap::from_iter([("test-index".to_string(), index_counters)]),
+            num_cache_hits,
+            num_cache_misses,
+        }
+    }
+
+    #[test]
+    fn test_index_metrics_sampler_without_snapshots() {
+        let sampler = IndexMetricsSampler::default();
+        let current_snapshot = snapshot_for_test(Instant::now(), IndexCounters::default(), 0, 0);
+        let index_rates = sampler.index_rates("test-index", &current_snapshot);
+        assert_eq!(index_rates, IndexRates::default());
+    }
+
+    #[test]
+    fn test_index_metrics_sampler_index_rates() {
+        let sampler = IndexMetricsSampler::default();
+        let start = Instant::now();
+        let baseline_counters = IndexCounters {
+            num_valid_docs_processed: 100,
+            num_search_requests: 10,
+            num_timed_search_requests: 10,
+            search_duration_secs: 1.0,
+        };
+        sampler.record_snapshot(snapshot_for_test(start, baseline_counters, 10, 10));
+
+        let current_counters = IndexCounters {
+            num_valid_docs_processed: 1_100,
+            num_search_requests: 30,
+            num_timed_search_requests: 20,
+            search_duration_secs: 3.0,
+        };
+        let current_snapshot =
+            snapshot_for_test(start + Duration::from_secs(10), current_counters, 40, 20);
+        let index_rates = sampler.index_rates("test-index", &current_snapshot);
+        assert_eq!(index_rates.indexing_throughput_docs_per_sec, 100.0);
+        assert_eq!(index_rates.search_qps, 2.0);
+        assert_eq!(index_rates.average_search_latency_ms, Some(200.0));
+        assert_eq!(index_rates.cache_hit_rate, Some(0.75));
+
+        let unknown_index_rates = sampler.index_rates("unknown-index", &current_snapshot);
+        assert_eq!(unknown_index_rates.indexing_throughput_docs_per_sec, 0.0);
+        assert_eq!(unknown_index_rates.search_qps, 0.0);
+        assert_eq!(unknown_index_rates.average_search_latency_ms, None);
+    }
+
+    #[test]
+    fn test_index_metrics_sampler_prunes_snapshots_outside_window() {
+        let sampler = IndexMetricsSampler::default();
+        let start = Instant::now();
+
+        for i in 0..20 {
+            let index_counters = IndexCounters {
+                num_valid_docs_processed: i * 50,
+                ..Default::default()
+            };
+            let taken_at = start + SAMPLING_INTERVAL * i as u32;
+            sampler.record_snapshot(snapshot_for_test(taken_at, index_counters, 0, 0));
+        }
+        let snapshots = sampler.snapshots.lock().unwrap();
+        let last_snapshot_taken_at = snapshots.back().unwrap().taken_at;
+        assert!(last_snapshot_taken_at - snapshots[0].taken_at >= SAMPLING_WINDOW);
+        assert!(last_snapshot_taken_at - snapshots[1].taken_at < SAMPLING_WINDOW);
+        drop(snapshots);
+
+        let current_snapshot = snapshot_for_test(
+            start + SAMPLING_INTERVAL * 20,
+            IndexCounters {
+                num_valid_docs_processed: 1_000,
+                ..Default::default()
+            },
+            0,
+            0,
+        );
+        let index_rates = sampler.index_rates("test-index", &current_snapshot);
+        assert_eq!(index_rates.indexing_throughput_docs_per_sec, 10.0);
+        assert_eq!(index_rates.cache_hit_rate, None);
+    }
+
+    #[test]
+    fn test_metrics_snapshot_take() {
+        let metrics = QuickwitMetrics::global();
+        let index_id = "test-index-metrics-snapshot";
+        metrics
+            .indexer
+            .processed_docs_total
+            .with_label_values([index_id, "valid"])
+            .inc_by(5);
+        metrics
+            .indexer
+            .processed_docs_total
+            .with_label_values([index_id, "json_parse_error"])
+            .inc();
+        metrics
+            .search
+            .root_search_requests_total
+            .with_label_values([index_id])
+            .inc_by(2);
+        metrics
+            .search
+            .root_search_request_duration_secs
+            .with_label_values([index_id])
+            .observe(0.5);
+
+        let snapshot = MetricsSnapshot::take();
+        let index_counters = snapshot.counters(index_id);
+        assert_eq!(index_counters.num_valid_docs_processed, 5);
+        assert_eq!(index_counters.num_search_requests, 2);
+        assert_eq!(index_counters.num_timed_search_requests, 1);
+        assert_eq!(index_counters.search_duration_secs, 0.5);
+    }
+}
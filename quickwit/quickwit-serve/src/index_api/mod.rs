@@ -17,8 +17,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod index_metrics_sampler;
 mod rest_handler;
 
+pub(crate) use self::index_metrics_sampler::index_metrics_sampling_loop;
 pub use self::rest_handler::{
-    index_management_handlers, IndexApi, IndexUpdates, ListSplitsQueryParams, ListSplitsResponse,
+    index_management_handlers, IndexApi, IndexStatsResponse, IndexUpdates, ListSplitsQueryParams,
+    ListSplitsResponse,
 };
@@ -17,21 +17,24 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use quickwit_common::uri::Uri;
 use quickwit_config::{
-    load_source_config_from_user_config, validate_index_id_pattern, ConfigFormat, NodeConfig,
-    RetentionPolicy, SearchSettings, SourceConfig, SourceParams, CLI_SOURCE_ID,
-    INGEST_API_SOURCE_ID,
+    build_doc_mapper, load_source_config_from_user_config, validate_index_id_pattern, ConfigFormat,
+    IndexingSettings, NodeConfig, RetentionPolicy, SearchSettings, SourceConfig, SourceParams,
+    CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
+};
+use quickwit_doc_mapper::{
+    analyze_field_text, analyze_text, analyze_text_with_tokenizer, TokenizerConfig,
 };
-use quickwit_doc_mapper::{analyze_text, TokenizerConfig};
 use quickwit_index_management::{IndexService, IndexServiceError};
 use quickwit_metastore::{
-    IndexMetadata, IndexMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsQuery,
-    ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, Split, SplitInfo, SplitState,
-    UpdateIndexRequestExt,
+    IndexMetadata, IndexMetadataResponseExt, IndexState, ListIndexesMetadataResponseExt,
+    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceExt, MetastoreServiceStreamSplitsExt,
+    Split, SplitInfo, SplitState, UpdateIndexRequestExt, ValidatedIndexId,
 };
 use quickwit_proto::metastore::{
     DeleteSourceRequest, EntityKind, IndexMetadataRequest, ListIndexesMetadataRequest,
@@ -40,12 +43,15 @@ use quickwit_proto::metastore::{
     UpdateIndexRequest,
 };
 use quickwit_proto::types::{IndexId, IndexUid, SourceId};
+use quickwit_query::create_default_quickwit_tokenizer_manager;
 use quickwit_query::query_ast::{query_ast_from_user_text, QueryAst};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use tantivy::tokenizer::Token;
 use tracing::{info, warn};
 use warp::{Filter, Rejection};
 
+use super::index_metrics_sampler::index_rates;
 use crate::format::{extract_config_format, extract_format_from_qs};
 use crate::rest_api_response::into_rest_api_response;
 use crate::simple_list::{from_simple_list, to_simple_list};
@@ -56,18 +62,36 @@ use crate::with_arg;
     paths(
         create_index,
         update_index,
+        freeze_index,
+        unfreeze_index,
+        upgrade_index_schema,
         clear_index,
         delete_index,
         list_indexes_metadata,
         list_splits,
         describe_index,
+        get_index_stats,
+        get_index_fields,
         mark_splits_for_deletion,
         create_source,
         reset_source_checkpoint,
         toggle_source,
         delete_source,
+        analyze_field_text_request,
+        analyze_text_request,
     ),
-    components(schemas(ToggleSource, SplitsForDeletion, IndexStats, IndexUpdates))
+    components(schemas(
+        ToggleSource,
+        SplitsForDeletion,
+        IndexStats,
+        IndexStatsResponse,
+        IndexFieldsResponse,
+        IndexUpdates,
+        AnalyzeFieldTextRequest,
+        AnalyzeTextRequest,
+        AnalyzeTextResponse,
+        AnalyzedTextToken,
+    ))
 )]
 pub struct IndexApi;
 
@@ -89,13 +113,24 @@ pub fn index_management_handlers(
     // Indexes handlers.
     get_index_metadata_handler(index_service.metastore())
         .or(list_indexes_metadata_handler(index_service.metastore()))
-        .or(create_index_handler(index_service.clone(), node_config))
+        .or(create_index_handler(
+            index_service.clone(),
+            node_config.clone(),
+        ))
         .or(update_index_handler(index_service.metastore()))
+        .or(freeze_index_handler(index_service.metastore()))
+        .or(unfreeze_index_handler(index_service.metastore()))
+        .or(upgrade_index_schema_handler(
+            index_service.metastore(),
+            node_config,
+        ))
         .or(clear_index_handler(index_service.clone()))
         .or(delete_index_handler(index_service.clone()))
         // Splits handlers
         .or(list_splits_handler(index_service.metastore()))
         .or(describe_index_handler(index_service.metastore()))
+        .or(index_stats_handler(index_service.metastore()))
+        .or(index_fields_handler(index_service.metastore()))
         .or(mark_splits_for_deletion_handler(index_service.metastore()))
         // Sources handlers.
         .or(reset_source_checkpoint_handler(index_service.metastore()))
@@ -105,6 +140,8 @@ pub fn index_management_handlers(
         .or(delete_source_handler(index_service.metastore()))
         // Tokenizer handlers.
         .or(analyze_request_handler())
+        .or(analyze_field_text_handler(index_service.metastore()))
+        .or(analyze_text_handler())
         // Parse query into query AST handler.
         .or(parse_query_request_handler())
 }
@@ -117,7 +154,8 @@ fn json_body<T: DeserializeOwned + Send>(
 fn get_index_metadata_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String)
+    warp::path!("indexes" / ValidatedIndexId)
+        .map(ValidatedIndexId::into_string)
         .and(warp::get())
         .and(with_arg(metastore))
         .then(get_index_metadata)
@@ -172,6 +210,9 @@ struct IndexStats {
     pub size_published_splits: u64,
     pub num_published_docs: u64,
     pub size_published_docs_uncompressed: u64,
+    /// Total size of the published split files, as recorded in the metastore when the splits
+    /// were staged.
+    pub index_size_bytes: u64,
     pub timestamp_field_name: Option<String>,
     pub min_timestamp: Option<i64>,
     pub max_timestamp: Option<i64>,
@@ -213,6 +254,7 @@ async fn describe_index(
     let mut total_num_docs = 0;
     let mut total_num_bytes = 0;
     let mut total_uncompressed_num_bytes = 0;
+    let mut index_size_bytes = 0;
     let mut min_timestamp: Option<i64> = None;
     let mut max_timestamp: Option<i64> = None;
 
@@ -220,6 +262,7 @@ async fn describe_index(
         total_num_docs += split.split_metadata.num_docs as u64;
         total_num_bytes += split.split_metadata.footer_offsets.end;
         total_uncompressed_num_bytes += split.split_metadata.uncompressed_docs_size_in_bytes;
+        index_size_bytes += split.split_metadata.size_in_bytes;
 
         if let Some(time_range) = &split.split_metadata.time_range {
             min_timestamp = min_timestamp
@@ -239,6 +282,7 @@ async fn describe_index(
         size_published_splits: total_num_bytes,
         num_published_docs: total_num_docs,
         size_published_docs_uncompressed: total_uncompressed_num_bytes,
+        index_size_bytes,
         timestamp_field_name: index_config.doc_mapping.timestamp_field,
         min_timestamp,
         max_timestamp,
@@ -250,7 +294,8 @@ async fn describe_index(
 fn describe_index_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String / "describe")
+    warp::path!("indexes" / ValidatedIndexId / "describe")
+        .map(ValidatedIndexId::into_string)
         .and(warp::get())
         .and(with_arg(metastore))
         .then(describe_index)
@@ -258,6 +303,163 @@ fn describe_index_handler(
         .map(into_rest_api_response)
 }
 
+/// Operational statistics about an index.
+///
+/// The rates are computed from the metrics of the node serving the request over the last 60
+/// seconds, so they only account for the indexing pipelines and the root searches running on that
+/// node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IndexStatsResponse {
+    /// Number of documents in the published splits.
+    pub documents_total: u64,
+    /// Number of splits, whatever their state.
+    pub splits_total: u64,
+    #[schema(value_type = Object)]
+    pub splits_by_state: HashMap<SplitState, u64>,
+    /// Total size of the published split files.
+    pub size_bytes: u64,
+    pub oldest_document_ts: Option<i64>,
+    pub newest_document_ts: Option<i64>,
+    pub indexing_throughput_docs_per_sec: f64,
+    pub search_qps: f64,
+    /// Average duration of the successful root search requests.
+    pub average_search_latency_ms: Option<f64>,
+    /// Ratio of hits over lookups in the search caches of the node, which are shared by all the
+    /// indexes.
+    pub cache_hit_rate: Option<f64>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "Indexes",
+    path = "/{index_id}/stats",
+    responses(
+        (status = 200, description = "Successfully fetched the stats of the index.", body = IndexStatsResponse)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to fetch the stats of."),
+    )
+)]
+/// Returns operational statistics about an index.
+async fn get_index_stats(
+    index_id: IndexId,
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexStatsResponse> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_uid = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    let query = ListSplitsQuery::for_index(index_uid);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits()
+        .await?;
+
+    let mut splits_by_state: HashMap<SplitState, u64> = HashMap::new();
+    let mut documents_total = 0;
+    let mut size_bytes = 0;
+    let mut oldest_document_ts: Option<i64> = None;
+    let mut newest_document_ts: Option<i64> = None;
+
+    for split in &splits {
+        *splits_by_state.entry(split.split_state).or_default() += 1;
+
+        if split.split_state != SplitState::Published {
+            continue;
+        }
+        documents_total += split.split_metadata.num_docs as u64;
+        size_bytes += split.split_metadata.size_in_bytes;
+
+        if let Some(time_range) = &split.split_metadata.time_range {
+            oldest_document_ts = oldest_document_ts
+                .min(Some(*time_range.start()))
+                .or(Some(*time_range.start()));
+            newest_document_ts = newest_document_ts
+                .max(Some(*time_range.end()))
+                .or(Some(*time_range.end()));
+        }
+    }
+    let index_rates = index_rates(&index_id);
+
+    let index_stats = IndexStatsResponse {
+        documents_total,
+        splits_total: splits.len() as u64,
+        splits_by_state,
+        size_bytes,
+        oldest_document_ts,
+        newest_document_ts,
+        indexing_throughput_docs_per_sec: index_rates.indexing_throughput_docs_per_sec,
+        search_qps: index_rates.search_qps,
+        average_search_latency_ms: index_rates.average_search_latency_ms,
+        cache_hit_rate: index_rates.cache_hit_rate,
+    };
+    Ok(index_stats)
+}
+
+fn index_stats_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(ValidatedIndexId / "stats")
+        .map(ValidatedIndexId::into_string)
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(get_index_stats)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+/// Names of the fields present in the published splits of an index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IndexFieldsResponse {
+    /// Union of the field names of the published splits, sorted alphabetically.
+    pub field_names: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "Indexes",
+    path = "/{index_id}/fields",
+    responses(
+        (status = 200, description = "Successfully fetched the fields of the index.", body = IndexFieldsResponse)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to fetch the fields of."),
+    )
+)]
+/// Returns the names of the fields present in the published splits of an index.
+async fn get_index_fields(
+    index_id: IndexId,
+    mut metastore: MetastoreServiceClient,
+) -> MetastoreResult<IndexFieldsResponse> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_uid = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    let field_names = metastore.list_field_names(index_uid).await?;
+    let index_fields = IndexFieldsResponse {
+        field_names: field_names.into_iter().collect(),
+    };
+    Ok(index_fields)
+}
+
+fn index_fields_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(ValidatedIndexId / "fields")
+        .map(ValidatedIndexId::into_string)
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(get_index_fields)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
 /// This struct represents the QueryString passed to
 /// the rest API to filter splits.
 #[derive(Debug, Clone, Deserialize, Serialize, utoipa::IntoParams, utoipa::ToSchema, Default)]
@@ -366,7 +568,8 @@ async fn list_splits(
 fn list_splits_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String / "splits")
+    warp::path!("indexes" / ValidatedIndexId / "splits")
+        .map(ValidatedIndexId::into_string)
         .and(warp::get())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
         .and(with_arg(metastore))
@@ -422,7 +625,8 @@ async fn mark_splits_for_deletion(
 fn mark_splits_for_deletion_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String / "splits" / "mark-for-deletion")
+    warp::path!("indexes" / ValidatedIndexId / "splits" / "mark-for-deletion")
+        .map(ValidatedIndexId::into_string)
         .and(warp::put())
         .and(json_body())
         .and(with_arg(metastore))
@@ -528,19 +732,24 @@ async fn create_index(
 }
 
 /// The body of the index update request. All fields will be replaced in the
-/// existing configuration.
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Default, utoipa::ToSchema)]
+/// existing configuration, except the indexing settings, which are left unchanged when omitted.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)] // Remove when adding new fields to allow to ensure forward compatibility
 pub struct IndexUpdates {
     pub search_settings: SearchSettings,
     #[serde(rename = "retention_policy")]
     pub retention_policy_opt: Option<RetentionPolicy>,
+    #[serde(default)]
+    #[serde(rename = "indexing_settings")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexing_settings_opt: Option<IndexingSettings>,
 }
 
 fn update_index_handler(
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String)
+    warp::path!("indexes" / ValidatedIndexId)
+        .map(ValidatedIndexId::into_string)
         .and(warp::put())
         .and(json_body())
         .and(with_arg(metastore))
@@ -566,7 +775,8 @@ fn update_index_handler(
 ///
 /// This endpoint has PUT semantics, which means that all the updatable fields of the index
 /// configuration are replaced by the values specified in the request. In particular, omitting an
-/// optional field like `retention_policy` will delete the associated configuration.
+/// optional field like `retention_policy` will delete the associated configuration. The indexing
+/// settings are the exception: they are left unchanged when omitted.
 async fn update_index(
     index_id: IndexId,
     request: IndexUpdates,
@@ -574,16 +784,198 @@ async fn update_index(
 ) -> Result<IndexMetadata, IndexServiceError> {
     info!(index_id = %index_id, "update-index");
     let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
-    let index_uid: IndexUid = metastore
+    let index_metadata = metastore
         .index_metadata(index_metadata_request)
         .await?
-        .deserialize_index_metadata()?
-        .index_uid;
+        .deserialize_index_metadata()?;
+    let index_uid: IndexUid = index_metadata.index_uid;
+
+    let mut updated_index_config = index_metadata.index_config;
+    updated_index_config.search_settings = request.search_settings.clone();
+    updated_index_config.retention_policy_opt = request.retention_policy_opt.clone();
+    if let Some(indexing_settings) = &request.indexing_settings_opt {
+        updated_index_config.indexing_settings = indexing_settings.clone();
+    }
+    updated_index_config
+        .validate()
+        .map_err(IndexServiceError::InvalidConfig)?;
 
     let update_request = UpdateIndexRequest::try_from_updates(
         index_uid,
         &request.search_settings,
         &request.retention_policy_opt,
+        None,
+        None,
+        request.indexing_settings_opt.as_ref(),
+    )?;
+    let update_resp = metastore.update_index(update_request).await?;
+    Ok(update_resp.deserialize_index_metadata()?)
+}
+
+fn freeze_index_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(ValidatedIndexId / "freeze")
+        .map(ValidatedIndexId::into_string)
+        .and(warp::post())
+        .and(with_arg(metastore))
+        .then(freeze_index)
+        .map(log_failure("failed to freeze index"))
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Indexes",
+    path = "/{index_id}/freeze",
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully froze the index.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to freeze."),
+    )
+)]
+/// Freezes an index.
+///
+/// A frozen index rejects the ingest requests with a `423 Locked` status code but can still be
+/// searched. Freezing an index that is already frozen is a no-op.
+async fn freeze_index(
+    index_id: IndexId,
+    metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    info!(index_id = %index_id, "freeze-index");
+    set_index_state(index_id, IndexState::Frozen, metastore).await
+}
+
+fn unfreeze_index_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(ValidatedIndexId / "unfreeze")
+        .map(ValidatedIndexId::into_string)
+        .and(warp::post())
+        .and(with_arg(metastore))
+        .then(unfreeze_index)
+        .map(log_failure("failed to unfreeze index"))
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Indexes",
+    path = "/{index_id}/unfreeze",
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully unfroze the index.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to unfreeze."),
+    )
+)]
+/// Unfreezes an index so that it accepts new documents again.
+async fn unfreeze_index(
+    index_id: IndexId,
+    metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    info!(index_id = %index_id, "unfreeze-index");
+    set_index_state(index_id, IndexState::Active, metastore).await
+}
+
+async fn set_index_state(
+    index_id: IndexId,
+    index_state: IndexState,
+    mut metastore: MetastoreServiceClient,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id);
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+
+    let update_request = UpdateIndexRequest::try_from_updates(
+        index_metadata.index_uid,
+        &index_metadata.index_config.search_settings,
+        &index_metadata.index_config.retention_policy_opt,
+        None,
+        Some(index_state),
+        None,
+    )?;
+    let update_resp = metastore.update_index(update_request).await?;
+    Ok(update_resp.deserialize_index_metadata()?)
+}
+
+fn upgrade_index_schema_handler(
+    metastore: MetastoreServiceClient,
+    node_config: Arc<NodeConfig>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / ValidatedIndexId / "schema")
+        .map(ValidatedIndexId::into_string)
+        .and(warp::put())
+        .and(extract_config_format())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::filters::body::bytes())
+        .and(with_arg(metastore))
+        .and(with_arg(node_config))
+        .then(upgrade_index_schema)
+        .map(log_failure("failed to upgrade index schema"))
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Indexes",
+    path = "/indexes/{index_id}/schema",
+    request_body = VersionedIndexConfig,
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully upgraded the index schema.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to upgrade."),
+    )
+)]
+/// Upgrades the schema of an existing index.
+///
+/// Only the doc mapping of the index config passed in the request body is taken into account. The
+/// new doc mapping must be backward compatible with the current one: fields can be added, but
+/// cannot be removed and their types can only be widened. The splits indexed before the upgrade
+/// keep being searched with the doc mapping they were indexed with.
+async fn upgrade_index_schema(
+    index_id: IndexId,
+    config_format: ConfigFormat,
+    index_config_bytes: Bytes,
+    mut metastore: MetastoreServiceClient,
+    node_config: Arc<NodeConfig>,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let index_config = quickwit_config::load_index_config_from_user_config(
+        config_format,
+        &index_config_bytes,
+        &node_config.default_index_root_uri,
+    )
+    .map_err(IndexServiceError::InvalidConfig)?;
+    if index_config.index_id != index_id {
+        return Err(IndexServiceError::InvalidIdentifier(format!(
+            "index ID `{}` of the new index config does not match index ID `{index_id}`",
+            index_config.index_id
+        )));
+    }
+    info!(index_id = %index_id, "upgrade-index-schema");
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.to_string());
+    let index_metadata = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?;
+
+    let update_request = UpdateIndexRequest::try_from_updates(
+        index_metadata.index_uid,
+        &index_metadata.index_config.search_settings,
+        &index_metadata.index_config.retention_policy_opt,
+        Some(&index_config.doc_mapping),
+        None,
+        None,
     )?;
     let update_resp = metastore.update_index(update_request).await?;
     Ok(update_resp.deserialize_index_metadata()?)
@@ -592,7 +984,8 @@ async fn update_index(
 fn clear_index_handler(
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String / "clear")
+    warp::path!("indexes" / ValidatedIndexId / "clear")
+        .map(ValidatedIndexId::into_string)
         .and(warp::put())
         .and(with_arg(index_service))
         .then(clear_index)
@@ -631,7 +1024,8 @@ struct DeleteIndexQueryParam {
 fn delete_index_handler(
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String)
+    warp::path!("indexes" / ValidatedIndexId)
+        .map(ValidatedIndexId::into_string)
         .and(warp::delete())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
         .and(with_arg(index_service))
@@ -668,7 +1062,8 @@ async fn delete_index(
 fn create_source_handler(
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    warp::path!("indexes" / String / "sources")
+    warp::path!("indexes" / ValidatedIndexId / "sources")
+        .map(ValidatedIndexId::into_string)
         .and(warp::post())
         .and(extract_config_format())
         .and(warp::body::content_length_limit(1024 * 1024))
@@ -951,59 +1346,200 @@ async fn analyze_request(request: AnalyzeRequest) -> Result<serde_json::Value, I
     Ok(json_value)
 }
 
-#[derive(Debug, Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
-struct ParseQueryRequest {
-    /// Query text. The query language is that of tantivy.
-    pub query: String,
-    // Fields to search on.
-    #[param(rename = "search_field")]
-    #[serde(default)]
-    #[serde(rename(deserialize = "search_field"))]
-    #[serde(deserialize_with = "from_simple_list")]
-    pub search_fields: Option<Vec<String>>,
+/// A token produced by the analysis of a text, mirroring the tokens returned by the
+/// Elasticsearch `_analyze` API.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub struct AnalyzedTextToken {
+    /// The token text, after all the filters of the tokenizer chain were applied.
+    pub token: String,
+    /// Start byte offset of the token in the original text.
+    pub start: usize,
+    /// End byte offset of the token in the original text.
+    pub end: usize,
+    /// Position of the token in the token stream.
+    pub position: usize,
 }
 
-fn parse_query_request_filter(
-) -> impl Filter<Extract = (ParseQueryRequest,), Error = Rejection> + Clone {
-    warp::path!("parse-query")
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub struct AnalyzeTextResponse {
+    pub tokens: Vec<AnalyzedTextToken>,
+}
+
+impl AnalyzeTextResponse {
+    fn from_tokens(tokens: Vec<Token>) -> Self {
+        let tokens = tokens
+            .into_iter()
+            .map(|token| AnalyzedTextToken {
+                token: token.text,
+                start: token.offset_from,
+                end: token.offset_to,
+                position: token.position,
+            })
+            .collect();
+        Self { tokens }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+struct AnalyzeFieldTextRequest {
+    /// The field whose tokenizer is used to analyze the text.
+    pub field: String,
+    /// The text to analyze.
+    pub text: String,
+}
+
+fn analyze_field_text_filter(
+) -> impl Filter<Extract = (IndexId, AnalyzeFieldTextRequest), Error = Rejection> + Clone {
+    warp::path!(ValidatedIndexId / "analyze-text")
+        .map(ValidatedIndexId::into_string)
         .and(warp::post())
-        .and(warp::body::json())
+        .and(json_body())
 }
 
-fn parse_query_request_handler(
+fn analyze_field_text_handler(
+    metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    parse_query_request_filter()
-        .then(parse_query_request)
+    analyze_field_text_filter()
+        .and(with_arg(metastore))
+        .then(analyze_field_text_request)
         .and(extract_format_from_qs())
         .map(into_rest_api_response)
 }
 
-/// Analyzes text with given tokenizer config and returns the list of tokens.
+/// Analyzes a text with the tokenizer configured for a field of an index.
 #[utoipa::path(
     post,
-    tag = "parse_query",
-    path = "/parse_query",
-    request_body = ParseQueryRequest,
+    tag = "analyze",
+    path = "/{index_id}/analyze-text",
+    request_body = AnalyzeFieldTextRequest,
     responses(
-        (status = 200, description = "Successfully parsed query into AST.")
+        (status = 200, description = "Successfully analyzed text.", body = AnalyzeTextResponse)
     ),
+    params(
+        ("index_id" = String, Path, description = "The index ID of the field to analyze the text with."),
+    )
 )]
-async fn parse_query_request(request: ParseQueryRequest) -> Result<QueryAst, IndexServiceError> {
-    let query_ast = query_ast_from_user_text(&request.query, request.search_fields)
-        .parse_user_query(&[])
-        .map_err(|err| IndexServiceError::Internal(err.to_string()))?;
-    Ok(query_ast)
+async fn analyze_field_text_request(
+    index_id: IndexId,
+    request: AnalyzeFieldTextRequest,
+    mut metastore: MetastoreServiceClient,
+) -> Result<AnalyzeTextResponse, IndexServiceError> {
+    info!(index_id = %index_id, field = %request.field, "analyze-text");
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id);
+    let index_config = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .into_index_config();
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(|err| IndexServiceError::Internal(format!("failed to build doc mapper: {err}")))?;
+    let tokens = analyze_field_text(&request.text, &request.field, &*doc_mapper)
+        .map_err(IndexServiceError::InvalidConfig)?;
+    Ok(AnalyzeTextResponse::from_tokens(tokens))
 }
 
-#[cfg(test)]
-mod tests {
-    use std::ops::{Bound, RangeInclusive};
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+struct AnalyzeTextRequest {
+    /// The name of the built-in tokenizer to use: `raw`, `default`, `en_stem`, etc.
+    pub analyzer: String,
+    /// The text to analyze.
+    pub text: String,
+}
 
-    use assert_json_diff::assert_json_include;
-    use quickwit_common::uri::Uri;
-    use quickwit_common::ServiceStream;
-    use quickwit_config::{SourceParams, VecSourceParams};
-    use quickwit_indexing::{mock_split, MockSplitBuilder};
+fn analyze_text_filter() -> impl Filter<Extract = (AnalyzeTextRequest,), Error = Rejection> + Clone
+{
+    warp::path!("analyze-text")
+        .and(warp::post())
+        .and(json_body())
+}
+
+fn analyze_text_handler() -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    analyze_text_filter()
+        .then(analyze_text_request)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+/// Analyzes a text with one of the built-in tokenizers.
+#[utoipa::path(
+    post,
+    tag = "analyze",
+    path = "/analyze-text",
+    request_body = AnalyzeTextRequest,
+    responses(
+        (status = 200, description = "Successfully analyzed text.", body = AnalyzeTextResponse)
+    ),
+)]
+async fn analyze_text_request(
+    request: AnalyzeTextRequest,
+) -> Result<AnalyzeTextResponse, IndexServiceError> {
+    let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+    let tokens = analyze_text_with_tokenizer(
+        &request.text,
+        &request.analyzer,
+        tokenizer_manager.tantivy_manager(),
+    )
+    .map_err(IndexServiceError::InvalidConfig)?;
+    Ok(AnalyzeTextResponse::from_tokens(tokens))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
+struct ParseQueryRequest {
+    /// Query text. The query language is that of tantivy.
+    pub query: String,
+    // Fields to search on.
+    #[param(rename = "search_field")]
+    #[serde(default)]
+    #[serde(rename(deserialize = "search_field"))]
+    #[serde(deserialize_with = "from_simple_list")]
+    pub search_fields: Option<Vec<String>>,
+}
+
+fn parse_query_request_filter(
+) -> impl Filter<Extract = (ParseQueryRequest,), Error = Rejection> + Clone {
+    warp::path!("parse-query")
+        .and(warp::post())
+        .and(warp::body::json())
+}
+
+fn parse_query_request_handler(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    parse_query_request_filter()
+        .then(parse_query_request)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+/// Analyzes text with given tokenizer config and returns the list of tokens.
+#[utoipa::path(
+    post,
+    tag = "parse_query",
+    path = "/parse_query",
+    request_body = ParseQueryRequest,
+    responses(
+        (status = 200, description = "Successfully parsed query into AST.")
+    ),
+)]
+async fn parse_query_request(request: ParseQueryRequest) -> Result<QueryAst, IndexServiceError> {
+    let query_ast = query_ast_from_user_text(&request.query, request.search_fields)
+        .parse_user_query(&[])
+        .map_err(|err| IndexServiceError::Internal(err.to_string()))?;
+    Ok(query_ast)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::ops::{Bound, RangeInclusive};
+
+    use assert_json_diff::assert_json_include;
+    use bytesize::ByteSize;
+    use quickwit_common::uri::Uri;
+    use quickwit_common::ServiceStream;
+    use quickwit_config::{SourceParams, VecSourceParams};
+    use quickwit_indexing::{mock_split, MockSplitBuilder};
     use quickwit_metastore::{metastore_for_test, IndexMetadata, ListSplitsResponseExt};
     use quickwit_proto::metastore::{
         EmptyResponse, IndexMetadataResponse, ListIndexesMetadataResponse, ListSplitsResponse,
@@ -1065,6 +1601,30 @@ mod tests {
         assert_eq!(resp.status(), 404);
     }
 
+    #[tokio::test]
+    async fn test_get_invalid_index_id() {
+        // The request is rejected before reaching the metastore.
+        let mock_metastore = MockMetastoreService::new();
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        for path in [
+            "/indexes/ab",
+            "/indexes/-test-index",
+            "/indexes/test%20index",
+        ] {
+            let resp = warp::test::request()
+                .path(path)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 404);
+        }
+    }
+
     #[tokio::test]
     async fn test_get_splits() {
         let mut mock_metastore = MockMetastoreService::new();
@@ -1153,13 +1713,15 @@ mod tests {
             .return_once(move |_| {
                 Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
             });
-        let split_1 = MockSplitBuilder::new("split_1")
+        let mut split_1 = MockSplitBuilder::new("split_1")
             .with_index_uid(&index_uid)
             .build();
+        split_1.split_metadata.size_in_bytes = 1_000;
         let split_1_time_range = split_1.split_metadata.time_range.clone().unwrap();
         let mut split_2 = MockSplitBuilder::new("split_2")
             .with_index_uid(&index_uid)
             .build();
+        split_2.split_metadata.size_in_bytes = 2_000;
         split_2.split_metadata.time_range = Some(RangeInclusive::new(
             split_1_time_range.start() - 10,
             split_1_time_range.end() + 10,
@@ -1197,6 +1759,7 @@ mod tests {
             "size_published_splits": 1600,
             "num_published_docs": 20,
             "size_published_docs_uncompressed": 512,
+            "index_size_bytes": 3000,
             "timestamp_field_name": "timestamp",
             "min_timestamp": split_1_time_range.start() - 10,
             "max_timestamp": split_1_time_range.end() + 10,
@@ -1206,6 +1769,146 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_index_stats() {
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata =
+            IndexMetadata::for_test("quickwit-demo-index", "ram:///indexes/quickwit-demo-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_index_metadata()
+            .return_once(move |_| {
+                Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+            });
+        let mut split_1 = MockSplitBuilder::new("split_1")
+            .with_index_uid(&index_uid)
+            .build();
+        split_1.split_metadata.size_in_bytes = 1_000;
+        split_1.split_metadata.time_range = Some(100..=200);
+        let mut split_2 = MockSplitBuilder::new("split_2")
+            .with_index_uid(&index_uid)
+            .build();
+        split_2.split_metadata.size_in_bytes = 2_000;
+        split_2.split_metadata.time_range = Some(150..=300);
+        let mut split_3 = MockSplitBuilder::new("split_3")
+            .with_index_uid(&index_uid)
+            .build();
+        split_3.split_state = SplitState::Staged;
+        split_3.split_metadata.size_in_bytes = 4_000;
+        split_3.split_metadata.time_range = Some(0..=1_000);
+        mock_metastore
+            .expect_list_splits()
+            .withf(move |list_split_request| -> bool {
+                let list_split_query = list_split_request.deserialize_list_splits_query().unwrap();
+                list_split_query.index_uids.contains(&index_uid)
+            })
+            .return_once(move |_| {
+                let splits = vec![split_1, split_2, split_3];
+                let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits)]))
+            });
+
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/stats")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let index_stats: IndexStatsResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(index_stats.documents_total, 20);
+        assert_eq!(index_stats.splits_total, 3);
+        assert_eq!(
+            index_stats.splits_by_state,
+            HashMap::from_iter([(SplitState::Published, 2), (SplitState::Staged, 1)])
+        );
+        assert_eq!(index_stats.size_bytes, 3_000);
+        assert_eq!(index_stats.oldest_document_ts, Some(100));
+        assert_eq!(index_stats.newest_document_ts, Some(300));
+    }
+
+    #[tokio::test]
+    async fn test_get_index_stats_not_found() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().return_once(|_| {
+            Err(MetastoreError::NotFound(EntityKind::Index {
+                index_id: "unknown-index".to_string(),
+            }))
+        });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/unknown-index/stats")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_get_index_fields() {
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata =
+            IndexMetadata::for_test("quickwit-demo-index", "ram:///indexes/quickwit-demo-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_index_metadata()
+            .return_once(move |_| {
+                Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+            });
+        let mut split_1 = MockSplitBuilder::new("split_1")
+            .with_index_uid(&index_uid)
+            .build();
+        split_1.split_metadata.field_names =
+            BTreeSet::from_iter(["body".to_string(), "timestamp".to_string()]);
+        let mut split_2 = MockSplitBuilder::new("split_2")
+            .with_index_uid(&index_uid)
+            .build();
+        split_2.split_metadata.field_names =
+            BTreeSet::from_iter(["attributes.host".to_string(), "body".to_string()]);
+        mock_metastore
+            .expect_list_splits()
+            .withf(move |list_split_request| -> bool {
+                let list_split_query = list_split_request.deserialize_list_splits_query().unwrap();
+                list_split_query.index_uids.contains(&index_uid)
+                    && list_split_query.split_states == vec![SplitState::Published]
+            })
+            .return_once(move |_| {
+                let splits = vec![split_1, split_2];
+                let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits)]))
+            });
+
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/fields")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let index_fields: IndexFieldsResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            index_fields.field_names,
+            vec!["attributes.host", "body", "timestamp"]
+        );
+    }
+
     #[tokio::test]
     async fn test_get_all_splits() {
         let mut mock_metastore = MockMetastoreService::new();
@@ -1513,6 +2216,58 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_freeze_and_unfreeze_index() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config));
+
+        let resp = warp::test::request()
+            .path("/indexes")
+            .method("POST")
+            .json(&true)
+            .body(r#"{"version": "0.7", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/hdfs-logs/freeze")
+            .method("POST")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["index_state"], "frozen");
+
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["index_state"], "frozen");
+
+        let resp = warp::test::request()
+            .path("/hdfs-logs/unfreeze")
+            .method("POST")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json.get("index_state").is_none());
+
+        let resp = warp::test::request()
+            .path("/unknown-index/freeze")
+            .method("POST")
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+
     #[tokio::test]
     async fn test_create_delete_index_and_source() {
         let mut metastore = metastore_for_test();
@@ -1841,6 +2596,213 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_update_index_retention_policy() {
+        let mut metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        for (index_id, timestamp_field) in [("hdfs-logs", r#""timestamp""#), ("no-ts", "null")] {
+            let resp = warp::test::request()
+                .path("/indexes")
+                .method("POST")
+                .json(&true)
+                .body(format!(r#"{{"version": "0.8", "index_id": "{index_id}", "doc_mapping": {{"timestamp_field": {timestamp_field}, "field_mappings":[{{"name": "timestamp", "type": "datetime", "fast": true, "indexed": true}}]}}}}"#))
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"search_settings":{}, "retention_policy":{"period": "30 days", "max_size": "500 GB"}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            // The retention policy must be valid.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"search_settings":{}, "retention_policy":{"period": "30 days", "max_size": "0 B"}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+        }
+        {
+            // A retention policy requires a timestamp field.
+            let resp = warp::test::request()
+                .path("/indexes/no-ts")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"search_settings":{}, "retention_policy":{"period": "30 days"}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+            let body = std::str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("retention policy requires a timestamp field"));
+        }
+        let index_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id("hdfs-logs".to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        let retention_policy = index_metadata.index_config.retention_policy_opt.unwrap();
+        assert_eq!(retention_policy.retention_period, "30 days");
+        assert_eq!(retention_policy.max_size, Some(ByteSize::gb(500)));
+    }
+
+    #[tokio::test]
+    async fn test_update_index_indexing_settings() {
+        let mut metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        {
+            let resp = warp::test::request()
+                .path("/indexes")
+                .method("POST")
+                .json(&true)
+                .body(r#"{"version": "0.8", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"search_settings":{}, "indexing_settings":{"split_num_docs_target": 2000000, "split_num_bytes_target": "5 GB"}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            // The split targets must not be too small.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(
+                    r#"{"search_settings":{}, "indexing_settings":{"split_num_docs_target": 10}}"#,
+                )
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+            let body = std::str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("split_num_docs_target"));
+        }
+        {
+            // Omitting the indexing settings leaves them unchanged.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"search_settings":{}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        let index_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id("hdfs-logs".to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        let indexing_settings = index_metadata.index_config.indexing_settings;
+        assert_eq!(indexing_settings.split_num_docs_target, 2_000_000);
+        assert_eq!(
+            indexing_settings.split_num_bytes_target,
+            Some(ByteSize::gb(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_index_schema() {
+        let mut metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        {
+            let resp = warp::test::request()
+                .path("/indexes")
+                .method("POST")
+                .json(&true)
+                .body(r#"{"version": "0.8", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+        {
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/schema")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.8", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}, {"name": "body", "type": "text"}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+            let expected_response_json = serde_json::json!({
+                "index_config": {
+                    "schema_version": 1
+                }
+            });
+            assert_json_include!(actual: resp_json, expected: expected_response_json);
+        }
+        {
+            // Removing a field is not allowed.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/schema")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.8", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "body", "type": "text"}]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+            let body = std::str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("field `timestamp` cannot be removed from the doc mapping"));
+        }
+        {
+            // The index ID must match.
+            let resp = warp::test::request()
+                .path("/indexes/hdfs-logs/schema")
+                .method("PUT")
+                .json(&true)
+                .body(r#"{"version": "0.8", "index_id": "other-logs", "doc_mapping": {"field_mappings":[]}}"#)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 400);
+        }
+        let index_metadata = metastore
+            .index_metadata(IndexMetadataRequest::for_index_id("hdfs-logs".to_string()))
+            .await
+            .unwrap()
+            .deserialize_index_metadata()
+            .unwrap();
+        assert_eq!(index_metadata.index_config.schema_version, 1);
+        assert_eq!(index_metadata.doc_mapping_history.len(), 1);
+        assert_eq!(
+            index_metadata.index_config.doc_mapping.field_mappings.len(),
+            2
+        );
+    }
+
     #[tokio::test]
     async fn test_create_source_with_bad_config() {
         let metastore = metastore_for_test();
@@ -2106,6 +3068,111 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_analyze_field_text_request() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().returning(|_| {
+            Ok(
+                IndexMetadataResponse::try_from_index_metadata(&IndexMetadata::for_test(
+                    "test-index",
+                    "ram:///indexes/test-index",
+                ))
+                .unwrap(),
+            )
+        });
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/test-index/analyze-text")
+            .method("POST")
+            .body(r#"{"field": "body", "text": "The quick brown fox"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let actual_response_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "tokens": [
+                {"token": "the", "start": 0, "end": 3, "position": 0},
+                {"token": "quick", "start": 4, "end": 9, "position": 1},
+                {"token": "brown", "start": 10, "end": 15, "position": 2},
+                {"token": "fox", "start": 16, "end": 19, "position": 3}
+            ]
+        });
+        assert_eq!(actual_response_json, expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/test-index/analyze-text")
+            .method("POST")
+            .body(r#"{"field": "owner", "text": "The quick brown fox"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let actual_response_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "tokens": [
+                {"token": "The quick brown fox", "start": 0, "end": 19, "position": 0}
+            ]
+        });
+        assert_eq!(actual_response_json, expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/test-index/analyze-text")
+            .method("POST")
+            .body(r#"{"field": "response_time", "text": "3.14"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let body = std::str::from_utf8(resp.body()).unwrap();
+        assert!(body.contains("field `response_time` is not a text field"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_text_request() {
+        let index_service = IndexService::new(
+            MetastoreServiceClient::from_mock(MockMetastoreService::new()),
+            StorageResolver::unconfigured(),
+        );
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(NodeConfig::for_test()))
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/analyze-text")
+            .method("POST")
+            .body(r#"{"analyzer": "en_stem", "text": "Jumping foxes"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let actual_response_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let expected_response_json = serde_json::json!({
+            "tokens": [
+                {"token": "jump", "start": 0, "end": 7, "position": 0},
+                {"token": "fox", "start": 8, "end": 13, "position": 1}
+            ]
+        });
+        assert_eq!(actual_response_json, expected_response_json);
+
+        let resp = warp::test::request()
+            .path("/analyze-text")
+            .method("POST")
+            .body(r#"{"analyzer": "unknown", "text": "Jumping foxes"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/analyze-text")
+            .method("POST")
+            .body(r#"{"analyzer": "raw", "text": "Jumping foxes", "field": "body"}"#)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
     #[tokio::test]
     async fn test_parse_query_request() {
         let index_service = IndexService::new(
@@ -0,0 +1,355 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use quickwit_ingest::{IngestRequest, IngestResponse, IngestServiceError};
+use quickwit_metastore::{IndexMetadataResponseExt, IndexState};
+use quickwit_proto::ingest::router::{
+    IngestFailure, IngestFailureReason, IngestRequestV2, IngestResponseV2,
+};
+use quickwit_proto::ingest::IngestV2Error;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
+use quickwit_proto::types::IndexId;
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+/// How long the state of an index is cached before being fetched again from the metastore. A
+/// frozen index starts rejecting documents at most this long after being frozen.
+const FROZEN_INDEXES_CACHE_TTL: Duration = Duration::from_secs(3);
+
+/// Keeps track of the frozen indexes so that the ingest endpoints can reject the documents sent to
+/// them without hitting the metastore for every request.
+///
+/// Indexes that cannot be fetched from the metastore are considered active: ingesting into them
+/// fails later with the usual error.
+#[derive(Clone)]
+pub(crate) struct FrozenIndexes {
+    metastore: MetastoreServiceClient,
+    index_states: Arc<Mutex<HashMap<IndexId, (IndexState, Instant)>>>,
+}
+
+impl FrozenIndexes {
+    pub fn new(metastore: MetastoreServiceClient) -> Self {
+        Self {
+            metastore,
+            index_states: Default::default(),
+        }
+    }
+
+    /// Returns an error if any of the indexes is frozen.
+    pub async fn check_not_frozen<'a>(
+        &self,
+        index_ids: impl IntoIterator<Item = &'a IndexId>,
+    ) -> Result<(), IngestServiceError> {
+        for index_id in index_ids {
+            if self.is_frozen(index_id).await {
+                return Err(IngestServiceError::IndexFrozen {
+                    index_id: index_id.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn is_frozen(&self, index_id: &IndexId) -> bool {
+        self.index_state(index_id).await == IndexState::Frozen
+    }
+
+    async fn index_state(&self, index_id: &IndexId) -> IndexState {
+        let now = Instant::now();
+
+        if let Some((index_state, fetched_at)) = self.index_states.lock().unwrap().get(index_id) {
+            if now.duration_since(*fetched_at) < FROZEN_INDEXES_CACHE_TTL {
+                return *index_state;
+            }
+        }
+        let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.clone());
+        let index_state = match self.metastore.index_metadata(index_metadata_request).await {
+            Ok(response) => response
+                .deserialize_index_metadata()
+                .map(|index_metadata| index_metadata.index_state)
+                .unwrap_or_default(),
+            Err(_) => IndexState::Active,
+        };
+        let mut index_states = self.index_states.lock().unwrap();
+        // Drop the expired entries so that the map does not grow with deleted indexes.
+        index_states.retain(|_, (_, fetched_at)| {
+            now.duration_since(*fetched_at) < FROZEN_INDEXES_CACHE_TTL
+        });
+        index_states.insert(index_id.clone(), (index_state, now));
+        index_state
+    }
+}
+
+/// Rejects the documents sent to frozen indexes before they reach the ingest API service (v1) or
+/// the ingest router (v2), so that every ingest path (REST, Elasticsearch bulk, gRPC, OTLP...) is
+/// covered.
+#[derive(Clone)]
+pub(crate) struct FrozenIndexesLayer {
+    frozen_indexes: FrozenIndexes,
+}
+
+impl FrozenIndexesLayer {
+    pub fn new(frozen_indexes: FrozenIndexes) -> Self {
+        Self { frozen_indexes }
+    }
+}
+
+impl<S> Layer<S> for FrozenIndexesLayer {
+    type Service = FrozenIndexesService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        FrozenIndexesService {
+            service,
+            frozen_indexes: self.frozen_indexes.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct FrozenIndexesService<S> {
+    service: S,
+    frozen_indexes: FrozenIndexes,
+}
+
+impl<S> FrozenIndexesService<S>
+where S: Clone
+{
+    /// Takes the inner service, which was polled ready, and leaves a clone in its place.
+    fn take_ready_service(&mut self) -> S {
+        let service_clone = self.service.clone();
+        std::mem::replace(&mut self.service, service_clone)
+    }
+}
+
+impl<S> Service<IngestRequest> for FrozenIndexesService<S>
+where
+    S: Service<IngestRequest, Response = IngestResponse, Error = IngestServiceError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = IngestResponse;
+    type Error = IngestServiceError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: IngestRequest) -> Self::Future {
+        let mut service = self.take_ready_service();
+        let frozen_indexes = self.frozen_indexes.clone();
+
+        Box::pin(async move {
+            frozen_indexes
+                .check_not_frozen(
+                    request
+                        .doc_batches
+                        .iter()
+                        .map(|doc_batch| &doc_batch.index_id),
+                )
+                .await?;
+            service.call(request).await
+        })
+    }
+}
+
+impl<S> Service<IngestRequestV2> for FrozenIndexesService<S>
+where
+    S: Service<IngestRequestV2, Response = IngestResponseV2, Error = IngestV2Error>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = IngestResponseV2;
+    type Error = IngestV2Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: IngestRequestV2) -> Self::Future {
+        let mut service = self.take_ready_service();
+        let frozen_indexes = self.frozen_indexes.clone();
+
+        Box::pin(async move {
+            let mut subrequests = Vec::with_capacity(request.subrequests.len());
+            let mut frozen_index_failures = Vec::new();
+
+            for subrequest in request.subrequests {
+                if frozen_indexes.is_frozen(&subrequest.index_id).await {
+                    let failure = IngestFailure {
+                        subrequest_id: subrequest.subrequest_id,
+                        index_id: subrequest.index_id,
+                        source_id: subrequest.source_id,
+                        reason: IngestFailureReason::IndexFrozen as i32,
+                    };
+                    frozen_index_failures.push(failure);
+                } else {
+                    subrequests.push(subrequest);
+                }
+            }
+            if subrequests.is_empty() {
+                let response = IngestResponseV2 {
+                    successes: Vec::new(),
+                    failures: frozen_index_failures,
+                };
+                return Ok(response);
+            }
+            request.subrequests = subrequests;
+            let mut response = service.call(request).await?;
+            response.failures.extend(frozen_index_failures);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::IndexConfig;
+    use quickwit_metastore::{metastore_for_test, CreateIndexRequestExt, UpdateIndexRequestExt};
+    use quickwit_proto::ingest::router::{
+        IngestRouterService, IngestRouterServiceClient, IngestSubrequest, IngestSuccess,
+        MockIngestRouterService,
+    };
+    use quickwit_proto::metastore::{CreateIndexRequest, UpdateIndexRequest};
+    use quickwit_proto::types::IndexUid;
+
+    use super::*;
+    use crate::ingest_api::setup_frozen_index;
+
+    #[tokio::test]
+    async fn test_frozen_indexes() {
+        tokio::time::pause();
+
+        let mut metastore = metastore_for_test();
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+
+        let frozen_indexes = FrozenIndexes::new(metastore.clone());
+        let index_ids = ["test-index".to_string(), "unknown-index".to_string()];
+        frozen_indexes.check_not_frozen(&index_ids).await.unwrap();
+
+        let update_index_request = UpdateIndexRequest::try_from_updates(
+            index_uid,
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            None,
+            Some(IndexState::Frozen),
+            None,
+        )
+        .unwrap();
+        metastore.update_index(update_index_request).await.unwrap();
+
+        // The cached state is still valid.
+        frozen_indexes.check_not_frozen(&index_ids).await.unwrap();
+
+        tokio::time::advance(FROZEN_INDEXES_CACHE_TTL).await;
+
+        let error = frozen_indexes
+            .check_not_frozen(&index_ids)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, IngestServiceError::IndexFrozen { index_id } if index_id == "test-index")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frozen_indexes_layer_rejects_subrequests_to_frozen_indexes() {
+        let metastore = setup_frozen_index("frozen-index").await;
+
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|request| {
+                assert_eq!(request.subrequests.len(), 1);
+                assert_eq!(request.subrequests[0].index_id, "active-index");
+
+                let response = IngestResponseV2 {
+                    successes: vec![IngestSuccess {
+                        subrequest_id: 1,
+                        index_uid: Some(IndexUid::for_test("active-index", 0)),
+                        ..Default::default()
+                    }],
+                    failures: Vec::new(),
+                };
+                Ok(response)
+            });
+        let mut ingest_router = IngestRouterServiceClient::tower()
+            .stack_ingest_layer(FrozenIndexesLayer::new(FrozenIndexes::new(metastore)))
+            .build_from_mock(mock_ingest_router);
+
+        let request = IngestRequestV2 {
+            subrequests: vec![
+                IngestSubrequest {
+                    subrequest_id: 0,
+                    index_id: "frozen-index".to_string(),
+                    ..Default::default()
+                },
+                IngestSubrequest {
+                    subrequest_id: 1,
+                    index_id: "active-index".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let response = ingest_router.ingest(request).await.unwrap();
+        assert_eq!(response.successes.len(), 1);
+        assert_eq!(response.successes[0].subrequest_id, 1);
+
+        assert_eq!(response.failures.len(), 1);
+        assert_eq!(response.failures[0].subrequest_id, 0);
+        assert_eq!(response.failures[0].index_id, "frozen-index");
+        assert_eq!(
+            response.failures[0].reason(),
+            IngestFailureReason::IndexFrozen
+        );
+
+        // The inner service is not called when all the subrequests target frozen indexes.
+        let request = IngestRequestV2 {
+            subrequests: vec![IngestSubrequest {
+                subrequest_id: 0,
+                index_id: "frozen-index".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let response = ingest_router.ingest(request).await.unwrap();
+        assert!(response.successes.is_empty());
+        assert_eq!(response.failures.len(), 1);
+    }
+}
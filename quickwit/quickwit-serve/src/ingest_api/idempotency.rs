@@ -0,0 +1,408 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use hyper::StatusCode;
+use quickwit_storage::{MemorySizedCache, OwnedBytes};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use warp::{Filter, Rejection};
+
+use crate::rest_api_response::RestApiResponse;
+
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+const IDEMPOTENCY_CACHE_CAPACITY: ByteSize = ByteSize::mib(64);
+
+/// Size of the header prepended to the body of the cached responses: the expiration time of the
+/// entry in milliseconds (8 bytes) followed by the HTTP status code (2 bytes).
+const ENTRY_HEADER_LEN: usize = 10;
+
+/// Extracts the optional `Idempotency-Key` header of a request. Blank keys are ignored.
+pub(crate) fn extract_idempotency_key(
+) -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(IDEMPOTENCY_KEY_HEADER).map(|key_opt: Option<String>| {
+        key_opt
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+    })
+}
+
+/// Caches the responses to the ingest requests carrying an `Idempotency-Key` header so that the
+/// clients retrying a request with the same key get the original response back instead of
+/// ingesting the same documents twice.
+///
+/// Entries expire `ttl` after their insertion. Server errors, rate-limited responses, and
+/// responses to requests sent to frozen indexes are not cached because retrying them is precisely
+/// what the client is expected to do.
+///
+/// The key is reserved while the first request carrying it is being processed: the concurrent
+/// requests with the same key wait for its response instead of ingesting the documents again.
+pub(crate) struct IdempotencyCache {
+    responses: MemorySizedCache<String>,
+    pending_requests: PendingRequests,
+    ttl: Duration,
+    start_time: Instant,
+}
+
+/// Maps the key of each request being processed to the channel its cached response is sent on.
+type PendingRequests = Mutex<HashMap<String, broadcast::Sender<OwnedBytes>>>;
+
+/// Releases the reservation of a key once its request completes, or when the request is
+/// cancelled.
+struct PendingRequest<'a> {
+    pending_requests: &'a PendingRequests,
+    cache_key_opt: Option<String>,
+}
+
+impl PendingRequest<'_> {
+    /// Releases the key and forwards the cached response, if any, to the waiting requests.
+    fn complete(mut self, entry_opt: Option<OwnedBytes>) {
+        let Some(cache_key) = self.cache_key_opt.take() else {
+            return;
+        };
+        let sender_opt = self.pending_requests.lock().unwrap().remove(&cache_key);
+
+        if let (Some(sender), Some(entry)) = (sender_opt, entry_opt) {
+            // Sending fails if nobody is waiting, which is fine.
+            let _ = sender.send(entry);
+        }
+    }
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        // Dropping the sender notifies the waiting requests that they must be retried.
+        if let Some(cache_key) = self.cache_key_opt.take() {
+            self.pending_requests.lock().unwrap().remove(&cache_key);
+        }
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            responses: MemorySizedCache::with_capacity_in_bytes(
+                IDEMPOTENCY_CACHE_CAPACITY.as_u64() as usize,
+                &quickwit_storage::CACHE_METRICS.idempotency_cache,
+            ),
+            pending_requests: Default::default(),
+            ttl,
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Returns the cached response to the request sent to `index_id` with `idempotency_key`, or
+    /// processes the request with `ingest` and records its response.
+    ///
+    /// If a request with the same key is already being processed, waits for its response. When
+    /// that response is not cached, or the request is cancelled, one of the waiting requests is
+    /// processed in turn.
+    pub async fn get_or_ingest<F, Fut>(
+        &self,
+        index_id: &str,
+        idempotency_key: &str,
+        ingest: F,
+    ) -> RestApiResponse
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = RestApiResponse>,
+    {
+        let cache_key = cache_key(index_id, idempotency_key);
+
+        loop {
+            let receiver_opt = {
+                let mut pending_requests = self.pending_requests.lock().unwrap();
+
+                // The lookup is performed while holding the lock so that a request completing
+                // concurrently is either visible in the cache or still pending.
+                if let Some(cached_response) = self.get(&cache_key) {
+                    return cached_response;
+                }
+                if let Some(sender) = pending_requests.get(&cache_key) {
+                    Some(sender.subscribe())
+                } else {
+                    let (sender, _receiver) = broadcast::channel(1);
+                    pending_requests.insert(cache_key.clone(), sender);
+                    None
+                }
+            };
+            if let Some(mut receiver) = receiver_opt {
+                match receiver.recv().await {
+                    Ok(entry) => {
+                        if let Some(cached_response) = self.decode_entry(&entry) {
+                            return cached_response;
+                        }
+                        continue;
+                    }
+                    // The pending request did not produce a cacheable response, so we try again.
+                    Err(_) => continue,
+                }
+            }
+            let pending_request = PendingRequest {
+                pending_requests: &self.pending_requests,
+                cache_key_opt: Some(cache_key.clone()),
+            };
+            let response = ingest().await;
+            let entry_opt = self.put(cache_key, &response);
+            pending_request.complete(entry_opt);
+            return response;
+        }
+    }
+
+    /// Returns the cached response stored under `cache_key`, if it has not expired yet.
+    fn get(&self, cache_key: &str) -> Option<RestApiResponse> {
+        let entry = self.responses.get(cache_key)?;
+        self.decode_entry(&entry)
+    }
+
+    fn decode_entry(&self, entry: &[u8]) -> Option<RestApiResponse> {
+        if entry.len() < ENTRY_HEADER_LEN {
+            return None;
+        }
+        let (header, body) = entry.split_at(ENTRY_HEADER_LEN);
+        let expires_at_millis = u64::from_le_bytes(header[..8].try_into().unwrap());
+
+        if self.elapsed_millis() >= expires_at_millis {
+            return None;
+        }
+        let status_code = StatusCode::from_u16(u16::from_le_bytes([header[8], header[9]])).ok()?;
+        let response = RestApiResponse::from_body(body.to_vec(), status_code);
+        Some(response)
+    }
+
+    /// Records the response stored under `cache_key` and returns the cache entry, unless the
+    /// response must not be cached.
+    fn put(&self, cache_key: String, response: &RestApiResponse) -> Option<OwnedBytes> {
+        let status_code = response.status_code();
+
+        if status_code.is_server_error()
+            || status_code == StatusCode::TOO_MANY_REQUESTS
+            || status_code == StatusCode::LOCKED
+        {
+            return None;
+        }
+        let body = response.body()?;
+        let expires_at_millis = self.elapsed_millis() + self.ttl.as_millis() as u64;
+
+        let mut entry = Vec::with_capacity(ENTRY_HEADER_LEN + body.len());
+        entry.extend_from_slice(&expires_at_millis.to_le_bytes());
+        entry.extend_from_slice(&status_code.as_u16().to_le_bytes());
+        entry.extend_from_slice(body);
+
+        let entry = OwnedBytes::new(entry);
+        self.responses.put(cache_key, entry.clone());
+        Some(entry)
+    }
+
+    fn elapsed_millis(&self) -> u64 {
+        self.start_time.elapsed().as_millis() as u64
+    }
+}
+
+/// The same key sent to two different indexes identifies two different requests.
+fn cache_key(index_id: &str, idempotency_key: &str) -> String {
+    format!("{index_id}/{idempotency_key}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn response(status_code: StatusCode) -> RestApiResponse {
+        RestApiResponse::from_body(b"{}".to_vec(), status_code)
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache() {
+        tokio::time::pause();
+
+        let idempotency_cache = IdempotencyCache::new(Duration::from_secs(60));
+        let num_ingests = AtomicUsize::new(0);
+        let ingest = || {
+            num_ingests.fetch_add(1, Ordering::Relaxed);
+            async { response(StatusCode::BAD_REQUEST) }
+        };
+        let response = idempotency_cache
+            .get_or_ingest("my-index", "my-key", ingest)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 1);
+
+        let cached_response = idempotency_cache
+            .get_or_ingest("my-index", "my-key", ingest)
+            .await;
+        assert_eq!(cached_response.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(cached_response.body().unwrap(), b"{}");
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 1);
+
+        idempotency_cache
+            .get_or_ingest("my-other-index", "my-key", ingest)
+            .await;
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 2);
+
+        idempotency_cache
+            .get_or_ingest("my-index", "my-other-key", ingest)
+            .await;
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 3);
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        idempotency_cache
+            .get_or_ingest("my-index", "my-key", ingest)
+            .await;
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 4);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache_ignores_retryable_responses() {
+        let idempotency_cache = IdempotencyCache::new(Duration::from_secs(60));
+
+        for status_code in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::LOCKED,
+        ] {
+            idempotency_cache
+                .get_or_ingest(
+                    "my-index",
+                    "my-key",
+                    || async move { response(status_code) },
+                )
+                .await;
+            assert!(idempotency_cache
+                .get(&cache_key("my-index", "my-key"))
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache_deduplicates_concurrent_requests() {
+        let idempotency_cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60)));
+        let num_ingests = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let first_request_handle = {
+            let idempotency_cache = idempotency_cache.clone();
+            let num_ingests = num_ingests.clone();
+            tokio::spawn(async move {
+                idempotency_cache
+                    .get_or_ingest("my-index", "my-key", || async move {
+                        num_ingests.fetch_add(1, Ordering::Relaxed);
+                        release_rx.await.unwrap();
+                        response(StatusCode::OK)
+                    })
+                    .await
+            })
+        };
+        while idempotency_cache
+            .pending_requests
+            .lock()
+            .unwrap()
+            .is_empty()
+        {
+            tokio::task::yield_now().await;
+        }
+        let second_request_handle = {
+            let idempotency_cache = idempotency_cache.clone();
+            let num_ingests = num_ingests.clone();
+            tokio::spawn(async move {
+                idempotency_cache
+                    .get_or_ingest("my-index", "my-key", || async move {
+                        num_ingests.fetch_add(1, Ordering::Relaxed);
+                        response(StatusCode::OK)
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+        release_tx.send(()).unwrap();
+
+        let first_response = first_request_handle.await.unwrap();
+        let second_response = second_request_handle.await.unwrap();
+        assert_eq!(first_response.status_code(), StatusCode::OK);
+        assert_eq!(second_response.status_code(), StatusCode::OK);
+        assert_eq!(second_response.body().unwrap(), b"{}");
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 1);
+        assert!(idempotency_cache
+            .pending_requests
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache_retries_after_uncacheable_response() {
+        let idempotency_cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60)));
+        let num_ingests = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let first_request_handle = {
+            let idempotency_cache = idempotency_cache.clone();
+            let num_ingests = num_ingests.clone();
+            tokio::spawn(async move {
+                idempotency_cache
+                    .get_or_ingest("my-index", "my-key", || async move {
+                        num_ingests.fetch_add(1, Ordering::Relaxed);
+                        release_rx.await.unwrap();
+                        response(StatusCode::SERVICE_UNAVAILABLE)
+                    })
+                    .await
+            })
+        };
+        while idempotency_cache
+            .pending_requests
+            .lock()
+            .unwrap()
+            .is_empty()
+        {
+            tokio::task::yield_now().await;
+        }
+        let second_request_handle = {
+            let idempotency_cache = idempotency_cache.clone();
+            let num_ingests = num_ingests.clone();
+            tokio::spawn(async move {
+                idempotency_cache
+                    .get_or_ingest("my-index", "my-key", || async move {
+                        num_ingests.fetch_add(1, Ordering::Relaxed);
+                        response(StatusCode::OK)
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+        release_tx.send(()).unwrap();
+
+        let first_response = first_request_handle.await.unwrap();
+        let second_response = second_request_handle.await.unwrap();
+        assert_eq!(
+            first_response.status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(second_response.status_code(), StatusCode::OK);
+        assert_eq!(num_ingests.load(Ordering::Relaxed), 2);
+    }
+}
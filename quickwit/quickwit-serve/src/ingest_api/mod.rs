@@ -17,9 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod frozen_indexes;
+mod idempotency;
+mod ndjson;
 mod rest_handler;
 
+pub(crate) use frozen_indexes::{FrozenIndexes, FrozenIndexesLayer};
+pub use ndjson::{NdjsonDocError, NdjsonDocErrorKind, NdjsonIngestResponse};
 #[cfg(test)]
-pub(crate) use rest_handler::tests::setup_ingest_service;
+pub(crate) use rest_handler::tests::{setup_frozen_index, setup_ingest_service};
 pub(crate) use rest_handler::{ingest_api_handlers, lines};
 pub use rest_handler::{IngestApi, IngestApiSchemas};
@@ -0,0 +1,227 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use bytes::Bytes;
+use hyper::StatusCode;
+use quickwit_ingest::IngestServiceError;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::format::BodyFormat;
+use crate::rest_api_response::{into_rest_api_response, RestApiError, RestApiResponse};
+
+/// Maximum number of document errors reported in a response. The number of parse and
+/// validation errors are always reported in full.
+const MAX_NUM_REPORTED_DOC_ERRORS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NdjsonDocErrorKind {
+    /// The line is not valid JSON.
+    Parse,
+    /// The line is valid JSON but not a JSON object.
+    Validation,
+}
+
+/// Error reported for a document of an NDJSON ingest request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NdjsonDocError {
+    /// Line of the document in the request body, starting at 1.
+    pub line: u64,
+    pub kind: NdjsonDocErrorKind,
+    pub error: String,
+    /// The rejected document, or the raw line as a string if it could not be parsed.
+    #[schema(value_type = Object)]
+    pub document: JsonValue,
+}
+
+/// Response of an ingest request whose body is sent with the `application/x-ndjson` content
+/// type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NdjsonIngestResponse {
+    #[serde(default)]
+    pub num_docs_for_processing: u64,
+    #[serde(default)]
+    pub num_parse_errors: u64,
+    #[serde(default)]
+    pub num_validation_errors: u64,
+    /// The first document errors of the request.
+    #[serde(default)]
+    pub errors: Vec<NdjsonDocError>,
+}
+
+impl NdjsonIngestResponse {
+    pub fn num_errors(&self) -> u64 {
+        self.num_parse_errors + self.num_validation_errors
+    }
+
+    fn record_error(&mut self, line: u64, kind: NdjsonDocErrorKind, error: String, doc: &[u8]) {
+        match kind {
+            NdjsonDocErrorKind::Parse => self.num_parse_errors += 1,
+            NdjsonDocErrorKind::Validation => self.num_validation_errors += 1,
+        }
+        if self.errors.len() >= MAX_NUM_REPORTED_DOC_ERRORS {
+            return;
+        }
+        let document = serde_json::from_slice(doc)
+            .unwrap_or_else(|_| JsonValue::String(String::from_utf8_lossy(doc).into_owned()));
+        let doc_error = NdjsonDocError {
+            line,
+            kind,
+            error,
+            document,
+        };
+        self.errors.push(doc_error);
+    }
+}
+
+/// Checks the documents of an NDJSON body one line at a time, passes the valid ones to
+/// `add_doc`, and records the errors of the others. The returned response counts the valid
+/// documents in `num_docs_for_processing`.
+pub(crate) fn check_ndjson_docs<'a>(
+    body: &'a Bytes,
+    mut add_doc: impl FnMut(&'a [u8]),
+) -> NdjsonIngestResponse {
+    let mut response = NdjsonIngestResponse::default();
+
+    for (line_idx, doc) in body.split(|byte| *byte == b'\n').enumerate() {
+        if doc.iter().all(|byte| byte.is_ascii_whitespace()) {
+            continue;
+        }
+        let line = line_idx as u64 + 1;
+
+        if let Err(error) = serde_json::from_slice::<IgnoredAny>(doc) {
+            response.record_error(line, NdjsonDocErrorKind::Parse, error.to_string(), doc);
+            continue;
+        }
+        let is_json_object = doc
+            .iter()
+            .find(|byte| !byte.is_ascii_whitespace())
+            .is_some_and(|byte| *byte == b'{');
+
+        if !is_json_object {
+            let error = "document must be a JSON object".to_string();
+            response.record_error(line, NdjsonDocErrorKind::Validation, error, doc);
+            continue;
+        }
+        add_doc(doc);
+        response.num_docs_for_processing += 1;
+    }
+    response
+}
+
+/// Makes the REST API response of an NDJSON ingest request. The request succeeds as long as at
+/// least one document was queued and fails with a `400 Bad Request` if all documents were
+/// rejected.
+pub(crate) fn into_ndjson_rest_api_response(
+    result: Result<NdjsonIngestResponse, IngestServiceError>,
+) -> RestApiResponse {
+    match result {
+        Ok(response) if response.num_docs_for_processing == 0 && response.num_errors() > 0 => {
+            RestApiResponse::new::<_, RestApiError>(
+                &Ok(response),
+                StatusCode::BAD_REQUEST,
+                BodyFormat::default(),
+            )
+        }
+        _ => into_rest_api_response(result, BodyFormat::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use warp::Reply;
+
+    use super::*;
+
+    #[test]
+    fn test_check_ndjson_docs() {
+        let body = Bytes::from(
+            "{\"id\": 1}\n\n[1, 2]\n{\"id\": \n   \n  {\"id\": 2}  \n\"foo\"\n{\"id\": 3}",
+        );
+        let mut docs = Vec::new();
+        let response = check_ndjson_docs(&body, |doc| docs.push(doc));
+
+        assert_eq!(
+            docs,
+            vec![
+                &b"{\"id\": 1}"[..],
+                &b"  {\"id\": 2}  "[..],
+                &b"{\"id\": 3}"[..]
+            ]
+        );
+        assert_eq!(response.num_docs_for_processing, 3);
+        assert_eq!(response.num_parse_errors, 1);
+        assert_eq!(response.num_validation_errors, 2);
+        assert_eq!(response.num_errors(), 3);
+        assert_eq!(response.errors.len(), 3);
+
+        assert_eq!(response.errors[0].line, 3);
+        assert_eq!(response.errors[0].kind, NdjsonDocErrorKind::Validation);
+        assert_eq!(response.errors[0].error, "document must be a JSON object");
+        assert_eq!(response.errors[0].document, json!([1, 2]));
+
+        assert_eq!(response.errors[1].line, 4);
+        assert_eq!(response.errors[1].kind, NdjsonDocErrorKind::Parse);
+        assert_eq!(response.errors[1].document, json!("{\"id\": "));
+
+        assert_eq!(response.errors[2].line, 7);
+        assert_eq!(response.errors[2].kind, NdjsonDocErrorKind::Validation);
+        assert_eq!(response.errors[2].document, json!("foo"));
+    }
+
+    #[test]
+    fn test_check_ndjson_docs_caps_reported_errors() {
+        let body = Bytes::from("foo\n".repeat(MAX_NUM_REPORTED_DOC_ERRORS + 10));
+        let response = check_ndjson_docs(&body, |_| panic!("no document should be valid"));
+        assert_eq!(response.num_docs_for_processing, 0);
+        assert_eq!(
+            response.num_parse_errors,
+            MAX_NUM_REPORTED_DOC_ERRORS as u64 + 10
+        );
+        assert_eq!(response.errors.len(), MAX_NUM_REPORTED_DOC_ERRORS);
+    }
+
+    #[test]
+    fn test_into_ndjson_rest_api_response() {
+        let response = NdjsonIngestResponse {
+            num_docs_for_processing: 1,
+            num_parse_errors: 1,
+            ..Default::default()
+        };
+        let rest_response = into_ndjson_rest_api_response(Ok(response)).into_response();
+        assert_eq!(rest_response.status(), StatusCode::OK);
+
+        let response = NdjsonIngestResponse {
+            num_parse_errors: 1,
+            ..Default::default()
+        };
+        let rest_response = into_ndjson_rest_api_response(Ok(response)).into_response();
+        assert_eq!(rest_response.status(), StatusCode::BAD_REQUEST);
+
+        let rest_response =
+            into_ndjson_rest_api_response(Ok(NdjsonIngestResponse::default())).into_response();
+        assert_eq!(rest_response.status(), StatusCode::OK);
+
+        let rest_response =
+            into_ndjson_rest_api_response(Err(IngestServiceError::RateLimited)).into_response();
+        assert_eq!(rest_response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -17,6 +17,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
+
 use bytes::{Buf, Bytes};
 use quickwit_config::{disable_ingest_v1, IngestApiConfig, INGEST_V2_SOURCE_ID};
 use quickwit_ingest::{
@@ -27,15 +29,24 @@ use quickwit_proto::ingest::router::{
     IngestFailureReason, IngestRequestV2, IngestResponseV2, IngestRouterService,
     IngestRouterServiceClient, IngestSubrequest,
 };
+use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_proto::types::IndexId;
 use serde::Deserialize;
 use warp::{Filter, Rejection};
 
+use super::frozen_indexes::FrozenIndexes;
+use super::idempotency::{extract_idempotency_key, IdempotencyCache};
+use super::ndjson::{
+    check_ndjson_docs, into_ndjson_rest_api_response, NdjsonDocError, NdjsonDocErrorKind,
+    NdjsonIngestResponse,
+};
 use crate::decompression::get_body_bytes;
 use crate::format::extract_format_from_qs;
-use crate::rest_api_response::into_rest_api_response;
+use crate::rest_api_response::{into_rest_api_response, RestApiResponse};
 use crate::{with_arg, Body, BodyFormat};
 
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
 #[derive(utoipa::OpenApi)]
 #[openapi(paths(ingest, tail_endpoint,))]
 pub struct IngestApi;
@@ -46,6 +57,9 @@ pub struct IngestApi;
     quickwit_ingest::FetchResponse,
     quickwit_ingest::IngestResponse,
     quickwit_ingest::CommitType,
+    NdjsonIngestResponse,
+    NdjsonDocError,
+    NdjsonDocErrorKind,
 )))]
 pub struct IngestApiSchemas;
 
@@ -59,16 +73,30 @@ struct IngestOptions {
 pub(crate) fn ingest_api_handlers(
     ingest_router: IngestRouterServiceClient,
     ingest_service: IngestServiceClient,
+    metastore: MetastoreServiceClient,
     config: IngestApiConfig,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    ingest_handler(ingest_service.clone(), config.clone())
-        .or(tail_handler(ingest_service))
-        .or(ingest_v2_handler(ingest_router, config))
+    let frozen_indexes = FrozenIndexes::new(metastore);
+    ingest_handler(
+        ingest_service.clone(),
+        frozen_indexes.clone(),
+        config.clone(),
+    )
+    .or(tail_handler(ingest_service))
+    .or(ingest_v2_handler(ingest_router, frozen_indexes, config))
+}
+
+/// Returns whether the request body is sent with the `application/x-ndjson` content type, in
+/// which case the documents are checked one by one and the errors are reported per document.
+fn extract_is_ndjson() -> impl Filter<Extract = (bool,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("content-type").map(|content_type_opt: Option<String>| {
+        content_type_opt.is_some_and(|content_type| content_type.starts_with(NDJSON_CONTENT_TYPE))
+    })
 }
 
 fn ingest_filter(
     config: IngestApiConfig,
-) -> impl Filter<Extract = (String, Body, IngestOptions), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (String, Body, IngestOptions, bool), Error = Rejection> + Clone {
     warp::path!(String / "ingest")
         .and(warp::post())
         .and(warp::body::content_length_limit(
@@ -78,21 +106,63 @@ fn ingest_filter(
         .and(serde_qs::warp::query::<IngestOptions>(
             serde_qs::Config::default(),
         ))
+        .and(extract_is_ndjson())
 }
 
 fn ingest_handler(
     ingest_service: IngestServiceClient,
+    frozen_indexes: FrozenIndexes,
     config: IngestApiConfig,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let idempotency_cache = Arc::new(IdempotencyCache::new(config.idempotency_ttl()));
     ingest_filter(config)
+        .and(extract_idempotency_key())
         .and(with_arg(ingest_service))
-        .then(ingest)
-        .map(|result| into_rest_api_response(result, BodyFormat::default()))
+        .and(with_arg(frozen_indexes))
+        .and(with_arg(idempotency_cache))
+        .then(ingest_with_idempotency_key)
+}
+
+/// Replays the cached response if a request with the same `Idempotency-Key` was already
+/// processed, and ingests the documents otherwise.
+async fn ingest_with_idempotency_key(
+    index_id: IndexId,
+    body: Body,
+    ingest_options: IngestOptions,
+    is_ndjson: bool,
+    idempotency_key_opt: Option<String>,
+    ingest_service: IngestServiceClient,
+    frozen_indexes: FrozenIndexes,
+    idempotency_cache: Arc<IdempotencyCache>,
+) -> RestApiResponse {
+    let Some(idempotency_key) = idempotency_key_opt else {
+        return ingest(
+            index_id,
+            body,
+            ingest_options,
+            is_ndjson,
+            ingest_service,
+            frozen_indexes,
+        )
+        .await;
+    };
+    idempotency_cache
+        .get_or_ingest(&index_id, &idempotency_key, || {
+            ingest(
+                index_id.clone(),
+                body,
+                ingest_options,
+                is_ndjson,
+                ingest_service,
+                frozen_indexes,
+            )
+        })
+        .await
 }
 
 fn ingest_v2_filter(
     config: IngestApiConfig,
-) -> impl Filter<Extract = (String, Body, IngestOptions), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (String, Body, IngestOptions, bool), Error = Rejection> + Clone {
     warp::path!(String / "ingest-v2")
         .and(warp::post())
         .and(warp::body::content_length_limit(
@@ -102,30 +172,63 @@ fn ingest_v2_filter(
         .and(serde_qs::warp::query::<IngestOptions>(
             serde_qs::Config::default(),
         ))
+        .and(extract_is_ndjson())
 }
 
 fn ingest_v2_handler(
     ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
     config: IngestApiConfig,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     ingest_v2_filter(config)
         .and(with_arg(ingest_router))
+        .and(with_arg(frozen_indexes))
         .then(ingest_v2)
-        .and(with_arg(BodyFormat::default()))
-        .map(into_rest_api_response)
 }
 
 async fn ingest_v2(
     index_id: IndexId,
     body: Body,
     ingest_options: IngestOptions,
-    mut ingest_router: IngestRouterServiceClient,
-) -> Result<IngestResponse, IngestServiceError> {
+    is_ndjson: bool,
+    ingest_router: IngestRouterServiceClient,
+    frozen_indexes: FrozenIndexes,
+) -> RestApiResponse {
+    if let Err(error) = frozen_indexes.check_not_frozen([&index_id]).await {
+        return into_rest_api_response::<IngestResponse, _>(Err(error), BodyFormat::default());
+    }
     let mut doc_batch_builder = DocBatchV2Builder::default();
 
-    for doc in lines(&body.content) {
-        doc_batch_builder.add_doc(doc);
+    if !is_ndjson {
+        for doc in lines(&body.content) {
+            doc_batch_builder.add_doc(doc);
+        }
+        let ingest_result =
+            ingest_doc_batch_v2(index_id, doc_batch_builder, ingest_options, ingest_router).await;
+        return into_rest_api_response(ingest_result, BodyFormat::default());
+    }
+    let mut ndjson_response =
+        check_ndjson_docs(&body.content, |doc| doc_batch_builder.add_doc(doc));
+
+    if ndjson_response.num_docs_for_processing > 0 {
+        let ingest_result =
+            ingest_doc_batch_v2(index_id, doc_batch_builder, ingest_options, ingest_router).await;
+        match ingest_result {
+            Ok(ingest_response) => {
+                ndjson_response.num_docs_for_processing = ingest_response.num_docs_for_processing
+            }
+            Err(error) => return into_ndjson_rest_api_response(Err(error)),
+        }
     }
+    into_ndjson_rest_api_response(Ok(ndjson_response))
+}
+
+async fn ingest_doc_batch_v2(
+    index_id: IndexId,
+    doc_batch_builder: DocBatchV2Builder,
+    ingest_options: IngestOptions,
+    mut ingest_router: IngestRouterServiceClient,
+) -> Result<IngestResponse, IngestServiceError> {
     let doc_batch_opt = doc_batch_builder.build();
 
     let Some(doc_batch) = doc_batch_opt else {
@@ -185,6 +288,9 @@ fn convert_ingest_response_v2(
         IngestFailureReason::Timeout => {
             IngestServiceError::Internal("request timed out".to_string())
         }
+        IngestFailureReason::IndexFrozen => IngestServiceError::IndexFrozen {
+            index_id: ingest_failure.index_id,
+        },
     })
 }
 
@@ -194,11 +300,14 @@ fn convert_ingest_response_v2(
     path = "/{index_id}/ingest",
     request_body(content = String, description = "Documents to ingest in NDJSON format and limited to 10MB", content_type = "application/json"),
     responses(
-        (status = 200, description = "Successfully ingested documents.", body = IngestResponse)
+        (status = 200, description = "Successfully ingested documents. When the documents are sent with the `application/x-ndjson` content type, the response reports the rejected documents.", body = NdjsonIngestResponse),
+        (status = 400, description = "All the documents sent with the `application/x-ndjson` content type were rejected.", body = NdjsonIngestResponse),
+        (status = 423, description = "The index is frozen.")
     ),
     params(
         ("index_id" = String, Path, description = "The index ID to add docs to."),
         ("commit" = Option<CommitType>, Query, description = "Force or wait for commit at the end of the indexing operation."),
+        ("Idempotency-Key" = Option<String>, Header, description = "Unique key identifying the request. The requests retried with the same key within `ingest_api.idempotency_ttl_secs` get the original response back and do not ingest the documents again."),
     )
 )]
 /// Ingest documents
@@ -206,18 +315,52 @@ async fn ingest(
     index_id: IndexId,
     body: Body,
     ingest_options: IngestOptions,
-    mut ingest_service: IngestServiceClient,
-) -> Result<IngestResponse, IngestServiceError> {
+    is_ndjson: bool,
+    ingest_service: IngestServiceClient,
+    frozen_indexes: FrozenIndexes,
+) -> RestApiResponse {
     if disable_ingest_v1() {
         let message = "ingest v1 is disabled: environment variable `QW_DISABLE_INGEST_V1` is set";
-        return Err(IngestServiceError::Internal(message.to_string()));
+        let error = IngestServiceError::Internal(message.to_string());
+        return into_rest_api_response::<IngestResponse, _>(Err(error), BodyFormat::default());
+    }
+    if let Err(error) = frozen_indexes.check_not_frozen([&index_id]).await {
+        return into_rest_api_response::<IngestResponse, _>(Err(error), BodyFormat::default());
     }
     // The size of the body should be an upper bound of the size of the batch. The removal of the
     // end of line character for each doc compensates the addition of the `DocCommand` header.
     let mut doc_batch_builder = DocBatchBuilder::with_capacity(index_id, body.content.remaining());
-    for line in lines(&body.content) {
-        doc_batch_builder.ingest_doc(line);
+
+    if !is_ndjson {
+        for line in lines(&body.content) {
+            doc_batch_builder.ingest_doc(line);
+        }
+        let ingest_result =
+            ingest_doc_batch(doc_batch_builder, ingest_options, ingest_service).await;
+        return into_rest_api_response(ingest_result, BodyFormat::default());
     }
+    let mut ndjson_response = check_ndjson_docs(&body.content, |doc| {
+        doc_batch_builder.ingest_doc(doc);
+    });
+
+    if ndjson_response.num_docs_for_processing > 0 {
+        let ingest_result =
+            ingest_doc_batch(doc_batch_builder, ingest_options, ingest_service).await;
+        match ingest_result {
+            Ok(ingest_response) => {
+                ndjson_response.num_docs_for_processing = ingest_response.num_docs_for_processing
+            }
+            Err(error) => return into_ndjson_rest_api_response(Err(error)),
+        }
+    }
+    into_ndjson_rest_api_response(Ok(ndjson_response))
+}
+
+async fn ingest_doc_batch(
+    doc_batch_builder: DocBatchBuilder,
+    ingest_options: IngestOptions,
+    mut ingest_service: IngestServiceClient,
+) -> Result<IngestResponse, IngestServiceError> {
     let ingest_req = IngestRequest {
         doc_batches: vec![doc_batch_builder.build()],
         commit: ingest_options.commit_type.into(),
@@ -272,22 +415,31 @@ fn is_empty_or_blank_line(line: &[u8]) -> bool {
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::io::Write;
     use std::str;
     use std::time::Duration;
 
     use bytes::Bytes;
     use bytesize::ByteSize;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
     use quickwit_actors::{Mailbox, Universe};
-    use quickwit_config::IngestApiConfig;
+    use quickwit_config::{IndexConfig, IngestApiConfig};
     use quickwit_ingest::{
         init_ingest_api, CreateQueueIfNotExistsRequest, FetchRequest, FetchResponse,
         IngestApiService, IngestResponse, IngestServiceClient, SuggestTruncateRequest,
         QUEUES_DIR_NAME,
     };
+    use quickwit_metastore::{
+        metastore_for_test, CreateIndexRequestExt, IndexState, UpdateIndexRequestExt,
+    };
     use quickwit_proto::ingest::router::IngestRouterServiceClient;
+    use quickwit_proto::metastore::{
+        CreateIndexRequest, MetastoreService, MetastoreServiceClient, UpdateIndexRequest,
+    };
 
     use super::ingest_api_handlers;
-    use crate::ingest_api::lines;
+    use crate::ingest_api::{lines, NdjsonDocErrorKind, NdjsonIngestResponse};
 
     #[test]
     fn test_process_lines() {
@@ -340,8 +492,12 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -376,8 +532,12 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
         let payload = r#"
             {"id": 1, "message": "push"}
             {"id": 2, "message": "push"}
@@ -395,6 +555,161 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_ingest_api_decompresses_ndjson_payloads() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
+        let payload = b"{\"id\": 1, \"message\": \"push\"}\n{\"id\": 2, \"message\": \"push\"}";
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(payload).unwrap();
+        let gzip_payload = encoder.finish().unwrap();
+        let zstd_payload = zstd::encode_all(&payload[..], 0).unwrap();
+
+        for (encoding, compressed_payload) in [("gzip", gzip_payload), ("zstd", zstd_payload)] {
+            let resp = warp::test::request()
+                .path("/my-index/ingest")
+                .method("POST")
+                .header("content-type", "application/x-ndjson")
+                .header("content-encoding", encoding)
+                .body(compressed_payload)
+                .reply(&ingest_api_handlers)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let ingest_response: NdjsonIngestResponse =
+                serde_json::from_slice(resp.body()).unwrap();
+            assert_eq!(ingest_response.num_docs_for_processing, 2);
+            assert_eq!(ingest_response.num_errors(), 0);
+        }
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        let fetch_response: FetchResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 4);
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_reports_ndjson_doc_errors() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
+        let payload = "{\"id\": 1, \"message\": \"push\"}\n{\"id\": 2,\n[3]";
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "application/x-ndjson")
+            .body(payload)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: NdjsonIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 1);
+        assert_eq!(ingest_response.num_parse_errors, 1);
+        assert_eq!(ingest_response.num_validation_errors, 1);
+        assert_eq!(ingest_response.errors[0].line, 2);
+        assert_eq!(ingest_response.errors[0].kind, NdjsonDocErrorKind::Parse);
+        assert_eq!(ingest_response.errors[1].line, 3);
+        assert_eq!(
+            ingest_response.errors[1].kind,
+            NdjsonDocErrorKind::Validation
+        );
+
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let fetch_response: FetchResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 1);
+
+        // All the documents are rejected.
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "application/x-ndjson")
+            .body("foo\nbar")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let ingest_response: NdjsonIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 0);
+        assert_eq!(ingest_response.num_parse_errors, 2);
+        assert_eq!(ingest_response.errors.len(), 2);
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_replays_response_for_same_idempotency_key() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
+
+        for _ in 0..2 {
+            let resp = warp::test::request()
+                .path("/my-index/ingest")
+                .method("POST")
+                .header("idempotency-key", "d2b3a1c6-0e4f-4a5e-9a3b-6f1e2c7d8a90")
+                .body("{\"id\": 1, \"message\": \"push\"}\n{\"id\": 2, \"message\": \"push\"}")
+                .reply(&ingest_api_handlers)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let ingest_response: IngestResponse = serde_json::from_slice(resp.body()).unwrap();
+            assert_eq!(ingest_response.num_docs_for_processing, 2);
+        }
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        let fetch_response: FetchResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 2);
+
+        // A different key is a different request.
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("idempotency-key", "5c0f8e21-7b6d-4f3a-8e2c-1a9b0d4e6f37")
+            .body(r#"{"id": 3, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        let fetch_response: FetchResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(fetch_response.doc_batch.unwrap().num_docs(), 3);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_return_429_if_above_limits() {
         let config = IngestApiConfig {
@@ -404,8 +719,12 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &config).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            IngestApiConfig::default(),
+        );
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -426,8 +745,12 @@ pub(crate) mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, config.clone());
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore_for_test(),
+            config.clone(),
+        );
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -439,6 +762,64 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    pub(crate) async fn setup_frozen_index(index_id: &str) -> MetastoreServiceClient {
+        let mut metastore = metastore_for_test();
+        let index_uri = format!("ram:///indexes/{index_id}");
+        let index_config = IndexConfig::for_test(index_id, &index_uri);
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+        let update_index_request = UpdateIndexRequest::try_from_updates(
+            index_uid,
+            &index_config.search_settings,
+            &index_config.retention_policy_opt,
+            None,
+            Some(IndexState::Frozen),
+            None,
+        )
+        .unwrap();
+        metastore.update_index(update_index_request).await.unwrap();
+        metastore
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_returns_423_if_index_is_frozen() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let metastore = setup_frozen_index("my-index").await;
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let ingest_api_handlers = ingest_api_handlers(
+            ingest_router,
+            ingest_service,
+            metastore,
+            IngestApiConfig::default(),
+        );
+        for path in ["/my-index/ingest", "/my-index/ingest-v2"] {
+            let resp = warp::test::request()
+                .path(path)
+                .method("POST")
+                .body(r#"{"id": 1, "message": "push"}"#)
+                .reply(&ingest_api_handlers)
+                .await;
+            assert_eq!(resp.status(), 423);
+            let body = str::from_utf8(resp.body()).unwrap();
+            assert!(body.contains("index `my-index` is frozen"));
+        }
+        // Tailing a frozen index still works.
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_blocks_when_wait_is_specified() {
         let (universe, _temp_dir, ingest_service_client, ingest_service_mailbox) =
@@ -447,6 +828,7 @@ pub(crate) mod tests {
         let ingest_api_handlers = ingest_api_handlers(
             ingest_router,
             ingest_service_client,
+            metastore_for_test(),
             IngestApiConfig::default(),
         );
         let handle = tokio::spawn(async move {
@@ -496,6 +878,7 @@ pub(crate) mod tests {
         let ingest_api_handlers = ingest_api_handlers(
             ingest_router,
             ingest_service_client,
+            metastore_for_test(),
             IngestApiConfig::default(),
         );
         let handle = tokio::spawn(async move {
@@ -460,6 +460,8 @@ mod tests {
                     errors: Vec::new(),
                     aggregation: None,
                     scroll_id: None,
+                    suggestions: Vec::new(),
+                    partial_failures: Vec::new(),
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
@@ -491,6 +493,8 @@ mod tests {
                     errors: Vec::new(),
                     aggregation: None,
                     scroll_id: None,
+                    suggestions: Vec::new(),
+                    partial_failures: Vec::new(),
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
@@ -19,20 +19,25 @@
 
 #![recursion_limit = "256"]
 
+mod alias_api;
 mod build_info;
 mod cluster_api;
+mod dead_letter_queue_api;
 mod decompression;
 mod delete_task_api;
 mod developer_api;
 mod elasticsearch_api;
+mod error;
 mod format;
 mod grpc;
+mod grpc_health;
 mod health_check_api;
 mod index_api;
 mod indexing_api;
 mod ingest_api;
 mod jaeger_api;
 mod load_shield;
+mod log_level_api;
 mod metrics;
 mod metrics_api;
 mod node_info_handler;
@@ -52,6 +57,7 @@ use std::fs;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -69,11 +75,13 @@ use quickwit_cluster::{
 use quickwit_common::pubsub::{EventBroker, EventSubscriptionHandle};
 use quickwit_common::rate_limiter::RateLimiterSettings;
 use quickwit_common::retry::RetryParams;
-use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::runtimes::{
+    spawn_with_panic_handler, RuntimesConfig, ShutdownCoordinator, ShutdownSignal,
+};
 use quickwit_common::spawn_named_task;
 use quickwit_common::tower::{
-    BalanceChannel, BoxFutureInfaillible, BufferLayer, Change, ConstantRate, EstimateRateLayer,
-    EventListenerLayer, GrpcMetricsLayer, LoadShedLayer, OneTaskPerCallLayer, RateLimitLayer,
+    BalanceChannel, BufferLayer, Change, ConstantRate, EstimateRateLayer, EventListenerLayer,
+    GrpcClientInterceptor, GrpcMetricsLayer, LoadShedLayer, OneTaskPerCallLayer, RateLimitLayer,
     RetryLayer, RetryPolicy, SmaRateEstimator,
 };
 use quickwit_common::uri::Uri;
@@ -93,7 +101,8 @@ use quickwit_ingest::{
 use quickwit_jaeger::JaegerService;
 use quickwit_janitor::{start_janitor_service, JanitorService};
 use quickwit_metastore::{
-    ControlPlaneMetastore, ListIndexesMetadataResponseExt, MetastoreResolver,
+    AuditLog, AuditLogWriter, ControlPlaneMetastore, ListIndexesMetadataResponseExt,
+    MetastoreResolver,
 };
 use quickwit_opentelemetry::otlp::{OtlpGrpcLogsService, OtlpGrpcTracesService};
 use quickwit_proto::control_plane::ControlPlaneServiceClient;
@@ -113,19 +122,35 @@ use quickwit_search::{
     SearchServiceClient, SearcherContext, SearcherPool,
 };
 use quickwit_storage::{SplitCache, StorageResolver};
+use tokio::runtime::Handle;
 use tokio::sync::oneshot;
 use tower::timeout::Timeout;
 use tower::ServiceBuilder;
 use tracing::{debug, error, info, warn};
 use warp::{Filter, Rejection};
 
+pub use crate::alias_api::IndexAliasUpdates;
 pub use crate::build_info::{BuildInfo, RuntimeInfo};
-pub use crate::index_api::{IndexUpdates, ListSplitsQueryParams, ListSplitsResponse};
+pub use crate::cluster_api::ClusterMemberInfo;
+pub use crate::dead_letter_queue_api::{
+    DeadLetterQueueQueryParams, TruncateDeadLetterQueueQueryParams, TruncateDeadLetterQueueResponse,
+};
+pub use crate::error::QuickwitError;
+use crate::grpc_health::GrpcHealthReporter;
+use crate::index_api::index_metrics_sampling_loop;
+pub use crate::index_api::{
+    IndexStatsResponse, IndexUpdates, ListSplitsQueryParams, ListSplitsResponse,
+};
+use crate::ingest_api::{FrozenIndexes, FrozenIndexesLayer};
+pub use crate::ingest_api::{NdjsonDocError, NdjsonDocErrorKind, NdjsonIngestResponse};
+pub use crate::log_level_api::{LogLevelResponse, UpdateLogLevelRequest, UpdateLogLevelResponse};
 pub use crate::metrics::SERVE_METRICS;
 use crate::rate_modulator::RateModulator;
 #[cfg(test)]
 use crate::rest::recover_fn;
-pub use crate::search_api::{search_request_from_api_request, SearchRequestQueryString, SortBy};
+pub use crate::search_api::{
+    search_request_from_api_request, AnalyzeQueryRequest, SearchRequestQueryString, SortBy,
+};
 
 const READINESS_REPORTING_INTERVAL: Duration = if cfg!(any(test, feature = "testsuite")) {
     Duration::from_millis(25)
@@ -144,9 +169,11 @@ pub fn do_nothing_env_filter_reload_fn() -> EnvFilterReloadFn {
 }
 
 fn get_metastore_client_max_concurrency() -> usize {
-    quickwit_common::get_from_env(
+    quickwit_common::get_from_env_with_validation(
         METASTORE_CLIENT_MAX_CONCURRENCY_ENV_KEY,
         DEFAULT_METASTORE_CLIENT_MAX_CONCURRENCY,
+        |max_concurrency| *max_concurrency > 0,
+        "max concurrency must be strictly positive",
     )
 }
 
@@ -197,6 +224,9 @@ struct QuickwitServices {
 
     pub env_filter_reload_fn: EnvFilterReloadFn,
 
+    /// Set once the node has reported itself ready for the first time.
+    pub startup_completed: Arc<AtomicBool>,
+
     /// The control plane listens to various events.
     /// We must maintain a reference to the subscription handles to continue receiving
     /// notifications. Otherwise, the subscriptions are dropped.
@@ -256,6 +286,7 @@ async fn start_ingest_client_if_needed(
     node_config: &NodeConfig,
     universe: &Universe,
     cluster: &Cluster,
+    frozen_indexes: FrozenIndexes,
 ) -> anyhow::Result<IngestServiceClient> {
     if node_config.is_service_enabled(QuickwitService::Indexer) {
         let ingest_api_service = start_ingest_api_service(
@@ -273,9 +304,12 @@ async fn start_ingest_client_if_needed(
         let memory_capacity = ingest_api_service.ask(GetMemoryCapacity).await?;
         let min_rate = ConstantRate::new(ByteSize::mib(1).as_u64(), Duration::from_millis(100));
         let rate_modulator = RateModulator::new(rate_estimator.clone(), memory_capacity, min_rate);
+        // The requests forwarded to remote indexers are checked by their local ingest API service,
+        // so the frozen indexes layer is only stacked on top of the local one.
         let ingest_service = IngestServiceClient::tower()
             .stack_ingest_layer(
                 ServiceBuilder::new()
+                    .layer(FrozenIndexesLayer::new(frozen_indexes))
                     .layer(EstimateRateLayer::<IngestRequest, _>::new(rate_estimator))
                     .layer(BufferLayer::new(100))
                     .layer(RateLimitLayer::new(rate_modulator))
@@ -288,6 +322,7 @@ async fn start_ingest_client_if_needed(
         let ingest_service = IngestServiceClient::from_balance_channel(
             balance_channel,
             node_config.grpc_config.max_message_size,
+            GrpcClientInterceptor::new(&node_config.cluster_key),
         );
         Ok(ingest_service)
     }
@@ -367,7 +402,11 @@ async fn start_control_plane_if_needed(
         let control_plane_server_opt = None;
         let control_plane_client = ControlPlaneServiceClient::tower()
             .stack_layer(CP_GRPC_CLIENT_METRICS_LAYER.clone())
-            .build_from_balance_channel(balance_channel, node_config.grpc_config.max_message_size);
+            .build_from_balance_channel(
+                balance_channel,
+                node_config.grpc_config.max_message_size,
+                GrpcClientInterceptor::new(&node_config.cluster_key),
+            );
         Ok((control_plane_server_opt, control_plane_client))
     }
 }
@@ -398,9 +437,13 @@ pub async fn serve_quickwit(
     runtimes_config: RuntimesConfig,
     metastore_resolver: MetastoreResolver,
     storage_resolver: StorageResolver,
-    shutdown_signal: BoxFutureInfaillible<()>,
+    shutdown_coordinator: ShutdownCoordinator,
     env_filter_reload_fn: EnvFilterReloadFn,
 ) -> anyhow::Result<HashMap<String, ActorExitStatus>> {
+    // We subscribe before starting anything so that a shutdown initiated during startup is not
+    // missed.
+    let mut shutdown_signal_rx = shutdown_coordinator.subscribe();
+
     let cluster = start_cluster_service(&node_config)
         .await
         .context("failed to start cluster service")?;
@@ -423,6 +466,23 @@ pub async fn serve_quickwit(
                         node_config.metastore_uri
                     )
                 })?;
+            // Refuse to serve a metastore whose schema this version of Quickwit cannot handle.
+            metastore_resolver
+                .version_check(&node_config.metastore_uri)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to check schema version of metastore `{}`",
+                        node_config.metastore_uri
+                    )
+                })?;
+            let metastore = if let Some(audit_log_config) = &node_config.audit_log_config {
+                let audit_log_writer = AuditLogWriter::open(audit_log_config)
+                    .context("failed to open metastore audit log")?;
+                MetastoreServiceClient::new(AuditLog::new(metastore, audit_log_writer))
+            } else {
+                metastore
+            };
             let max_in_flight_requests = if node_config.metastore_uri.protocol().is_database() {
                 node_config
                     .metastore_configs
@@ -479,7 +539,11 @@ pub async fn serve_quickwit(
                 .into_inner();
             MetastoreServiceClient::tower()
                 .stack_layer(shared_layers)
-                .build_from_balance_channel(balance_channel, grpc_config.max_message_size)
+                .build_from_balance_channel(
+                    balance_channel,
+                    grpc_config.max_message_size,
+                    GrpcClientInterceptor::new(&node_config.cluster_key),
+                )
         };
     // Instantiate a control plane server if the `control-plane` role is enabled on the node.
     // Otherwise, instantiate a control plane client.
@@ -501,16 +565,20 @@ pub async fn serve_quickwit(
         metastore_client.clone(),
     ));
 
+    // Shared by the ingest services v1 and v2 to reject the documents sent to frozen indexes.
+    let frozen_indexes = FrozenIndexes::new(metastore_client.clone());
+
     // Setup ingest service v1.
-    let ingest_service = start_ingest_client_if_needed(&node_config, &universe, &cluster)
-        .await
-        .context("failed to start ingest v1 service")?;
+    let ingest_service =
+        start_ingest_client_if_needed(&node_config, &universe, &cluster, frozen_indexes.clone())
+            .await
+            .context("failed to start ingest v1 service")?;
 
     let indexing_service_opt = if node_config.is_service_enabled(QuickwitService::Indexer) {
         let indexing_service = start_indexing_service(
             &universe,
             &node_config,
-            runtimes_config.num_threads_blocking,
+            runtimes_config.num_threads_indexing,
             cluster.clone(),
             metastore_through_control_plane.clone(),
             ingester_pool.clone(),
@@ -539,6 +607,7 @@ pub async fn serve_quickwit(
         &event_broker,
         control_plane_client.clone(),
         ingester_pool,
+        frozen_indexes,
     )
     .await
     .context("failed to start ingest v2 service")?;
@@ -594,6 +663,7 @@ pub async fn serve_quickwit(
                 split_cache_root_directory,
                 storage_resolver.clone(),
                 split_cache_limits,
+                node_config.searcher_config.direct_download,
             )
             .context("failed to load searcher split cache")?;
             Some(split_cache)
@@ -684,6 +754,7 @@ pub async fn serve_quickwit(
 
     let grpc_listen_addr = node_config.grpc_listen_addr;
     let rest_listen_addr = node_config.rest_config.listen_addr;
+    let startup_completed = Arc::new(AtomicBool::new(false));
     let quickwit_services: Arc<QuickwitServices> = Arc::new(QuickwitServices {
         node_config: Arc::new(node_config),
         cluster: cluster.clone(),
@@ -705,8 +776,10 @@ pub async fn serve_quickwit(
         otlp_traces_service_opt,
         search_service,
         env_filter_reload_fn,
+        startup_completed: startup_completed.clone(),
     });
     // Setup and start gRPC server.
+    let (grpc_health_reporter, grpc_health_service) = GrpcHealthReporter::new();
     let (grpc_readiness_trigger_tx, grpc_readiness_signal_rx) = oneshot::channel::<()>();
     let grpc_readiness_trigger = Box::pin(async move {
         if grpc_readiness_trigger_tx.send(()).is_err() {
@@ -723,6 +796,8 @@ pub async fn serve_quickwit(
         grpc_listen_addr,
         grpc_config.max_message_size,
         quickwit_services.clone(),
+        grpc_health_reporter.clone(),
+        grpc_health_service,
         grpc_readiness_trigger,
         grpc_shutdown_signal,
     );
@@ -739,6 +814,14 @@ pub async fn serve_quickwit(
             debug!("REST server shutdown trigger sender was dropped");
         }
     });
+    // Samples the metrics of the node to compute the rates reported by the index stats endpoint.
+    spawn_with_panic_handler(
+        &Handle::current(),
+        "index_metrics_sampling",
+        index_metrics_sampling_loop,
+        true,
+    );
+
     let rest_server = rest::start_rest_server(
         rest_listen_addr,
         quickwit_services,
@@ -750,26 +833,60 @@ pub async fn serve_quickwit(
     // Thus readiness task is started once gRPC and REST servers are started.
     spawn_named_task(
         node_readiness_reporting_task(
-            cluster,
+            cluster.clone(),
             metastore_through_control_plane,
             ingester_opt.clone(),
             grpc_readiness_signal_rx,
             rest_readiness_signal_rx,
+            startup_completed,
+            grpc_health_reporter.clone(),
         ),
         "node_readiness_reporting",
     );
 
+    // Each component torn down below is registered with the coordinator so that the shutdown
+    // initiator can wait for all of them. The components are stopped in order by a single task, so
+    // only the first receiver, registered for the actors at startup, is polled.
+    let _ingester_shutdown_signal_rx_opt = ingester_opt
+        .as_ref()
+        .map(|_| shutdown_coordinator.subscribe());
+    let _grpc_server_shutdown_signal_rx = shutdown_coordinator.subscribe();
+    let _rest_server_shutdown_signal_rx = shutdown_coordinator.subscribe();
+
+    let shutdown_coordinator_clone = shutdown_coordinator.clone();
     let shutdown_handle = tokio::spawn(async move {
-        shutdown_signal.await;
+        let shutdown_coordinator = shutdown_coordinator_clone;
+        // The coordinator is kept alive by this task, so the channel cannot be closed before the
+        // signal is sent.
+        let ShutdownSignal { deadline } = shutdown_signal_rx
+            .recv()
+            .await
+            .expect("shutdown coordinator should not be dropped before the signal is sent");
+
+        // Load balancers relying on the gRPC health checking protocol stop routing requests to
+        // the node while it drains.
+        grpc_health_reporter.set_draining().await;
+        // Peers display the node as draining while it shuts down.
+        cluster.set_self_node_draining().await;
 
         // We must decommission the ingester first before terminating the indexing pipelines that
         // may consume from it. We also need to keep the gRPC server running while doing so.
         if let Some(ingester) = ingester_opt {
-            if let Err(error) = wait_for_ingester_decommission(ingester).await {
-                error!("failed to decommission ingester gracefully: {:?}", error);
+            match tokio::time::timeout_at(deadline, wait_for_ingester_decommission(ingester)).await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    error!("failed to decommission ingester gracefully: {:?}", error);
+                }
+                Err(_) => {
+                    warn!("ingester did not decommission before the shutdown deadline");
+                }
             }
+            shutdown_coordinator.component_done("ingester");
         }
+        // Stops the indexing pipelines, the janitor, the control plane, and the other actors.
         let actor_exit_statuses = universe.quit().await;
+        shutdown_coordinator.component_done("actors");
 
         if grpc_shutdown_trigger_tx.send(()).is_err() {
             debug!("gRPC server shutdown signal receiver was dropped");
@@ -788,9 +905,13 @@ pub async fn serve_quickwit(
     if let Err(grpc_err) = grpc_res {
         error!("gRPC server failed: {:?}", grpc_err);
     }
+    shutdown_coordinator.component_done("grpc_server");
+
     if let Err(rest_err) = rest_res {
         error!("REST server failed: {:?}", rest_err);
     }
+    shutdown_coordinator.component_done("rest_server");
+
     let actor_exit_statuses = shutdown_handle
         .await
         .context("failed to gracefully shutdown services")?;
@@ -818,6 +939,7 @@ async fn setup_ingest_v2(
     event_broker: &EventBroker,
     control_plane: ControlPlaneServiceClient,
     ingester_pool: IngesterPool,
+    frozen_indexes: FrozenIndexes,
 ) -> anyhow::Result<(IngestRouter, IngestRouterServiceClient, Option<Ingester>)> {
     // Instantiate ingest router.
     let self_node_id: NodeId = cluster.self_node_id().into();
@@ -840,6 +962,7 @@ async fn setup_ingest_v2(
 
     let ingest_router_service = IngestRouterServiceClient::tower()
         .stack_layer(INGEST_GRPC_SERVER_METRICS_LAYER.clone())
+        .stack_ingest_layer(FrozenIndexesLayer::new(frozen_indexes))
         .build(ingest_router.clone());
 
     // We compute the burst limit as something a bit larger than the content length limit, because
@@ -882,8 +1005,10 @@ async fn setup_ingest_v2(
     // Setup ingester pool change stream.
     let ingester_opt_clone = ingester_opt.clone();
     let max_message_size = node_config.grpc_config.max_message_size;
+    let grpc_client_interceptor = GrpcClientInterceptor::new(&node_config.cluster_key);
     let ingester_change_stream = cluster.change_stream().filter_map(move |cluster_change| {
         let ingester_opt_clone_clone = ingester_opt_clone.clone();
+        let grpc_client_interceptor = grpc_client_interceptor.clone();
         Box::pin(async move {
             match cluster_change {
                 ClusterChange::Add(node) if node.is_indexer() => {
@@ -915,6 +1040,7 @@ async fn setup_ingest_v2(
                                 node.grpc_advertise_addr(),
                                 node.channel(),
                                 max_message_size,
+                                grpc_client_interceptor,
                             );
                         Some(Change::Insert(node_id, ingester_service))
                     }
@@ -955,8 +1081,10 @@ async fn setup_searcher(
     .await?;
     let search_service_clone = search_service.clone();
     let max_message_size = node_config.grpc_config.max_message_size;
+    let grpc_client_interceptor = GrpcClientInterceptor::new(&node_config.cluster_key);
     let searcher_change_stream = cluster_change_stream.filter_map(move |cluster_change| {
         let search_service_clone = search_service_clone.clone();
+        let grpc_client_interceptor = grpc_client_interceptor.clone();
         Box::pin(async move {
             match cluster_change {
                 ClusterChange::Add(node) if node.is_searcher() => {
@@ -979,6 +1107,7 @@ async fn setup_searcher(
                             grpc_addr,
                             timeout_channel,
                             max_message_size,
+                            grpc_client_interceptor,
                         );
                         Some(Change::Insert(grpc_addr, search_client))
                     }
@@ -1056,8 +1185,10 @@ fn setup_indexer_pool(
     indexing_service_opt: Option<Mailbox<IndexingService>>,
 ) {
     let max_message_size = node_config.grpc_config.max_message_size;
+    let grpc_client_interceptor = GrpcClientInterceptor::new(&node_config.cluster_key);
     let indexer_change_stream = cluster_change_stream.filter_map(move |cluster_change| {
         let indexing_service_clone_opt = indexing_service_opt.clone();
+        let grpc_client_interceptor = grpc_client_interceptor.clone();
         Box::pin(async move {
             match &cluster_change {
                 ClusterChange::Add(node) if node.is_indexer() => {
@@ -1109,6 +1240,7 @@ fn setup_indexer_pool(
                                 node.grpc_advertise_addr(),
                                 node.channel(),
                                 max_message_size,
+                                grpc_client_interceptor,
                             );
                         let change = Change::Insert(
                             node_id.clone(),
@@ -1166,6 +1298,8 @@ async fn node_readiness_reporting_task(
     ingester_opt: Option<impl IngesterService>,
     grpc_readiness_signal_rx: oneshot::Receiver<()>,
     rest_readiness_signal_rx: oneshot::Receiver<()>,
+    startup_completed: Arc<AtomicBool>,
+    grpc_health_reporter: GrpcHealthReporter,
 ) {
     if grpc_readiness_signal_rx.await.is_err() {
         // the gRPC server failed.
@@ -1202,6 +1336,11 @@ async fn node_readiness_reporting_task(
             }
         };
         cluster.set_self_node_readiness(node_ready).await;
+        grpc_health_reporter.set_serving(node_ready).await;
+
+        if node_ready && !startup_completed.swap(true, Ordering::Relaxed) {
+            info!("node startup completed");
+        }
     }
 }
 
@@ -1339,27 +1478,34 @@ mod tests {
             });
         let (grpc_readiness_trigger_tx, grpc_readiness_signal_rx) = oneshot::channel();
         let (rest_readiness_trigger_tx, rest_readiness_signal_rx) = oneshot::channel();
+        let startup_completed = Arc::new(AtomicBool::new(false));
         tokio::spawn(node_readiness_reporting_task(
             cluster.clone(),
             MetastoreServiceClient::from_mock(mock_metastore),
             Some(mock_ingester),
             grpc_readiness_signal_rx,
             rest_readiness_signal_rx,
+            startup_completed.clone(),
+            GrpcHealthReporter::new().0,
         ));
         assert!(!cluster.is_self_node_ready().await);
 
         grpc_readiness_trigger_tx.send(()).unwrap();
         rest_readiness_trigger_tx.send(()).unwrap();
         assert!(!cluster.is_self_node_ready().await);
+        assert!(!startup_completed.load(Ordering::Relaxed));
 
         metastore_readiness_tx.send(true).unwrap();
         ingester_status_tx.send(IngesterStatus::Ready).unwrap();
         tokio::time::sleep(Duration::from_millis(25)).await;
         assert!(cluster.is_self_node_ready().await);
+        assert!(startup_completed.load(Ordering::Relaxed));
 
         metastore_readiness_tx.send(false).unwrap();
         tokio::time::sleep(Duration::from_millis(25)).await;
         assert!(!cluster.is_self_node_ready().await);
+        // Startup only completes once.
+        assert!(startup_completed.load(Ordering::Relaxed));
     }
 
     #[tokio::test]
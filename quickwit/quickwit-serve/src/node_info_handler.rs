@@ -20,21 +20,28 @@
 use std::sync::Arc;
 
 use quickwit_config::NodeConfig;
+use quickwit_storage::{StorageResolver, StorageStats};
 use serde_json::json;
 use warp::{Filter, Rejection};
 
+use crate::error::QuickwitError;
+use crate::format::extract_format_from_qs;
+use crate::rest_api_response::into_rest_api_response;
 use crate::{with_arg, BuildInfo, RuntimeInfo};
 
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(node_version_handler, node_config_handler,))]
+#[openapi(paths(node_version_handler, node_config_handler, node_storage_stats_handler,))]
 pub struct NodeInfoApi;
 
 pub fn node_info_handler(
     build_info: &'static BuildInfo,
     runtime_info: &'static RuntimeInfo,
     config: Arc<NodeConfig>,
+    storage_resolver: StorageResolver,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    node_version_handler(build_info, runtime_info).or(node_config_handler(config))
+    node_version_handler(build_info, runtime_info)
+        .or(node_config_handler(config.clone()))
+        .or(node_storage_stats_handler(config, storage_resolver))
 }
 
 #[utoipa::path(get, tag = "Node Info", path = "/version")]
@@ -76,10 +83,36 @@ async fn get_config(config: Arc<NodeConfig>) -> impl warp::Reply {
     warp::reply::json(&config)
 }
 
+#[utoipa::path(get, tag = "Node Info", path = "/node/storage-stats")]
+fn node_storage_stats_handler(
+    config: Arc<NodeConfig>,
+    storage_resolver: StorageResolver,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("node" / "storage-stats")
+        .and(warp::get())
+        .and(with_arg(config))
+        .and(with_arg(storage_resolver))
+        .then(get_storage_stats)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+/// Returns the statistics of the storage backend of the node's default index root URI.
+async fn get_storage_stats(
+    config: Arc<NodeConfig>,
+    storage_resolver: StorageResolver,
+) -> Result<StorageStats, QuickwitError> {
+    let storage = storage_resolver
+        .resolve(&config.default_index_root_uri)
+        .await?;
+    Ok(storage.stats())
+}
+
 #[cfg(test)]
 mod tests {
     use assert_json_diff::assert_json_include;
     use quickwit_common::uri::Uri;
+    use quickwit_storage::StorageResolver;
     use serde_json::Value as JsonValue;
 
     use super::*;
@@ -91,8 +124,13 @@ mod tests {
         let runtime_info = RuntimeInfo::get();
         let mut config = NodeConfig::for_test();
         config.metastore_uri = Uri::for_test("postgresql://username:password@db");
-        let handler = node_info_handler(build_info, runtime_info, Arc::new(config.clone()))
-            .recover(recover_fn);
+        let handler = node_info_handler(
+            build_info,
+            runtime_info,
+            Arc::new(config.clone()),
+            StorageResolver::for_test(),
+        )
+        .recover(recover_fn);
         let resp = warp::test::request().path("/version").reply(&handler).await;
         assert_eq!(resp.status(), 200);
         let info_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
@@ -121,4 +159,24 @@ mod tests {
         });
         assert_json_include!(actual: resp_json, expected: expected_response_json);
     }
+
+    #[tokio::test]
+    async fn test_rest_node_storage_stats() {
+        let mut config = NodeConfig::for_test();
+        config.default_index_root_uri = Uri::for_test("ram:///indexes");
+        let handler = node_info_handler(
+            BuildInfo::get(),
+            RuntimeInfo::get(),
+            Arc::new(config),
+            StorageResolver::for_test(),
+        )
+        .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/node/storage-stats")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let storage_stats: StorageStats = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(storage_stats, StorageStats::untracked("ram"));
+    }
 }
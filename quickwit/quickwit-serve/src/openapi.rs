@@ -27,15 +27,18 @@ use quickwit_metastore::MetastoreApiSchemas;
 use utoipa::openapi::Tag;
 use utoipa::OpenApi;
 
+use crate::alias_api::IndexAliasApi;
 use crate::cluster_api::ClusterApi;
+use crate::dead_letter_queue_api::DeadLetterQueueApi;
 use crate::delete_task_api::DeleteTaskApi;
 use crate::developer_api::DeveloperApi;
 use crate::elasticsearch_api::ElasticCompatibleApi;
-use crate::health_check_api::HealthCheckApi;
+use crate::health_check_api::{HealthCheckApi, HealthzApi};
 use crate::index_api::IndexApi;
 use crate::indexing_api::IndexingApi;
 use crate::ingest_api::{IngestApi, IngestApiSchemas};
 use crate::jaeger_api::JaegerApi;
+use crate::log_level_api::LogLevelApi;
 use crate::metrics_api::MetricsApi;
 use crate::node_info_handler::NodeInfoApi;
 use crate::search_api::SearchApi;
@@ -68,8 +71,10 @@ pub fn build_docs() -> utoipa::openapi::OpenApi {
     let tags = vec![
         Tag::new("Search"),
         Tag::new("Indexes"),
+        Tag::new("Aliases"),
         Tag::new("Ingest"),
         Tag::new("Delete Tasks"),
+        Tag::new("Dead Letter Queue"),
         Tag::new("Node Health"),
         Tag::new("Sources"),
         Tag::new("Get Metrics"),
@@ -84,15 +89,19 @@ pub fn build_docs() -> utoipa::openapi::OpenApi {
 
     // Routing
     docs_base.merge_components_and_paths(ClusterApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(DeadLetterQueueApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(DeleteTaskApi::openapi().with_path_prefix("/api/v1"));
     docs_base
         .merge_components_and_paths(DeveloperApi::openapi().with_path_prefix("/api/developer"));
     docs_base
         .merge_components_and_paths(ElasticCompatibleApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(HealthCheckApi::openapi().with_path_prefix("/health"));
+    docs_base.merge_components_and_paths(HealthzApi::openapi().with_path_prefix("/healthz"));
     docs_base.merge_components_and_paths(IndexApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(IndexingApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(LogLevelApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(IndexTemplateApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(IndexAliasApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(IngestApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(JaegerApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(MetricsApi::openapi().with_path_prefix("/metrics"));
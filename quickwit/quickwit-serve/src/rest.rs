@@ -20,40 +20,49 @@
 use std::fmt::Formatter;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use hyper::http::HeaderValue;
-use hyper::{http, Method, StatusCode};
+use hyper::http::{header, HeaderValue};
+use hyper::{http, StatusCode};
 use quickwit_common::tower::BoxFutureInfaillible;
+use quickwit_config::CorsConfig;
 use tower::make::Shared;
 use tower::ServiceBuilder;
 use tower_http::compression::predicate::{DefaultPredicate, Predicate, SizeAbove};
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use warp::filters::log::Info;
 use warp::{redirect, Filter, Rejection, Reply};
 
-use crate::cluster_api::cluster_handler;
+use crate::alias_api::index_alias_api_handlers;
+use crate::cluster_api::{cluster_handler, cluster_members_handler};
+use crate::dead_letter_queue_api::dead_letter_queue_api_handlers;
 use crate::decompression::{CorruptedData, UnsupportedEncoding};
 use crate::delete_task_api::delete_task_api_handlers;
 use crate::developer_api::developer_api_routes;
 use crate::elasticsearch_api::elastic_api_handlers;
-use crate::health_check_api::health_check_handlers;
+use crate::health_check_api::{health_check_handlers, ProbeTargets};
 use crate::index_api::index_management_handlers;
 use crate::indexing_api::indexing_get_handler;
 use crate::ingest_api::ingest_api_handlers;
 use crate::jaeger_api::jaeger_api_handlers;
+use crate::log_level_api::log_level_api_handlers;
 use crate::metrics_api::metrics_handler;
 use crate::node_info_handler::node_info_handler;
 use crate::otlp_api::otlp_ingest_api_handlers;
 use crate::rest_api_response::{RestApiError, RestApiResponse};
-use crate::search_api::{search_get_handler, search_post_handler, search_stream_handler};
+use crate::search_api::{
+    analyze_query_handler, field_stats_handler, search_get_handler, search_post_handler,
+    search_stream_handler,
+};
 use crate::template_api::index_template_api_handlers;
 use crate::ui_handler::ui_handler;
 use crate::{BodyFormat, BuildInfo, QuickwitServices, RuntimeInfo};
 
 /// The minimum size a response body must be in order to
-/// be automatically compressed with gzip.
+/// be automatically compressed with gzip or zstd, depending on the `Accept-Encoding` header of the
+/// request.
 const MINIMUM_RESPONSE_COMPRESSION_SIZE: u16 = 10 << 10;
 
 #[derive(Debug)]
@@ -113,11 +122,18 @@ pub(crate) async fn start_rest_server(
         .and(warp::get())
         .map(|| warp::reply::json(&crate::openapi::build_docs()));
 
-    // `/health/*` routes.
+    // `/health/*` and `/healthz/*` routes.
+    let probe_targets = ProbeTargets {
+        metastore: quickwit_services.metastore_client.clone(),
+        storage_resolver: quickwit_services.index_manager.storage_resolver(),
+        default_index_root_uri: quickwit_services.node_config.default_index_root_uri.clone(),
+        startup_completed: quickwit_services.startup_completed.clone(),
+    };
     let health_check_routes = health_check_handlers(
         quickwit_services.cluster.clone(),
         quickwit_services.indexing_service_opt.clone(),
         quickwit_services.janitor_service_opt.clone(),
+        probe_targets,
     );
 
     // `/metrics` route.
@@ -160,12 +176,13 @@ pub(crate) async fn start_rest_server(
     let warp_service = warp::service(rest_routes);
     let compression_predicate =
         DefaultPredicate::new().and(SizeAbove::new(MINIMUM_RESPONSE_COMPRESSION_SIZE));
-    let cors = build_cors(&quickwit_services.node_config.rest_config.cors_allow_origins);
+    let cors = build_cors(&quickwit_services.node_config.rest_config.cors);
 
     let service = ServiceBuilder::new()
         .layer(
             CompressionLayer::new()
                 .gzip(true)
+                .zstd(true)
                 .compress_when(compression_predicate),
         )
         .layer(cors)
@@ -200,10 +217,22 @@ fn api_v1_routes(
     let api_v1_root_url = warp::path!("api" / "v1" / ..);
     api_v1_root_url.and(
         cluster_handler(quickwit_services.cluster.clone())
+            .or(cluster_members_handler(
+                quickwit_services.cluster.clone(),
+                quickwit_services.metastore_client.clone(),
+            ))
             .or(node_info_handler(
                 BuildInfo::get(),
                 RuntimeInfo::get(),
                 quickwit_services.node_config.clone(),
+                quickwit_services.index_manager.storage_resolver(),
+            ))
+            .or(log_level_api_handlers(
+                quickwit_services
+                    .node_config
+                    .rest_config
+                    .admin_api_key
+                    .clone(),
             ))
             .or(indexing_get_handler(
                 quickwit_services.indexing_service_opt.clone(),
@@ -215,9 +244,16 @@ fn api_v1_routes(
             .or(search_stream_handler(
                 quickwit_services.search_service.clone(),
             ))
+            .or(analyze_query_handler(
+                quickwit_services.search_service.clone(),
+            ))
+            .or(field_stats_handler(
+                quickwit_services.search_service.clone(),
+            ))
             .or(ingest_api_handlers(
                 quickwit_services.ingest_router_service.clone(),
                 quickwit_services.ingest_service.clone(),
+                quickwit_services.metastore_client.clone(),
                 quickwit_services.node_config.ingest_api_config.clone(),
             ))
             .or(otlp_ingest_api_handlers(
@@ -231,6 +267,9 @@ fn api_v1_routes(
             .or(delete_task_api_handlers(
                 quickwit_services.metastore_client.clone(),
             ))
+            .or(dead_letter_queue_api_handlers(
+                quickwit_services.index_manager.clone(),
+            ))
             .or(jaeger_api_handlers(
                 quickwit_services.jaeger_service_opt.clone(),
             ))
@@ -244,6 +283,9 @@ fn api_v1_routes(
             ))
             .or(index_template_api_handlers(
                 quickwit_services.metastore_client.clone(),
+            ))
+            .or(index_alias_api_handlers(
+                quickwit_services.metastore_client.clone(),
             )),
     )
 }
@@ -362,30 +404,49 @@ fn get_status_with_error(rejection: Rejection) -> RestApiError {
     }
 }
 
-fn build_cors(cors_origins: &[String]) -> CorsLayer {
-    let mut cors = CorsLayer::new().allow_methods([
-        Method::GET,
-        Method::POST,
-        Method::PUT,
-        Method::DELETE,
-        Method::OPTIONS,
-    ]);
-    if !cors_origins.is_empty() {
-        let allow_any = cors_origins.iter().any(|origin| origin.as_str() == "*");
-
-        if allow_any {
-            info!("CORS is enabled, all origins will be allowed");
-            cors = cors.allow_origin(tower_http::cors::Any);
-        } else {
-            info!(origins = ?cors_origins, "CORS is enabled, the following origins will be allowed");
-            let origins = cors_origins
-                .iter()
-                .map(|origin| origin.parse::<HeaderValue>().unwrap())
-                .collect::<Vec<_>>();
-            cors = cors.allow_origin(origins);
-        };
+fn build_cors(cors_config: &CorsConfig) -> CorsLayer {
+    let mut cors = CorsLayer::new().allow_methods(cors_config.allowed_methods.clone());
+
+    if let Some(max_age_secs) = cors_config.max_age_secs {
+        cors = cors.max_age(Duration::from_secs(max_age_secs));
     }
+    let cors_origins = &cors_config.allowed_origins;
 
+    if cors_origins.is_empty() {
+        return cors;
+    }
+    if cors_config.allows_any_origin() {
+        if cors_config.allow_credentials {
+            // Browsers reject credentialed responses carrying `Access-Control-Allow-Origin: *`.
+            warn!(
+                "CORS credentials cannot be allowed along with any origin (`*`), \
+                 `cors_allow_credentials` will be ignored"
+            );
+        }
+        info!("CORS is enabled, all origins will be allowed");
+        cors = cors.allow_origin(tower_http::cors::Any);
+    } else {
+        info!(origins = ?cors_origins, "CORS is enabled, the following origins will be allowed");
+        let origins = cors_origins
+            .iter()
+            .map(|origin| {
+                origin
+                    .parse::<HeaderValue>()
+                    .expect("CORS origins should have been validated")
+            })
+            .collect::<Vec<_>>();
+        cors = cors
+            .allow_origin(origins)
+            .allow_credentials(cors_config.allow_credentials);
+    }
+    // The allowed origin is echoed back only when several origins are allowed, in which case
+    // responses vary on the `Origin` header of the request.
+    if !cors_config.vary_on_origin || cors_origins.len() == 1 {
+        cors = cors.vary([
+            header::ACCESS_CONTROL_REQUEST_METHOD,
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+        ]);
+    }
     cors
 }
 
@@ -396,7 +457,7 @@ mod tests {
     use std::task::{Context, Poll};
 
     use http::HeaderName;
-    use hyper::{Request, Response, StatusCode};
+    use hyper::{Method, Request, Response, StatusCode};
     use quickwit_cluster::{create_cluster_for_test, ChannelTransport};
     use quickwit_config::NodeConfig;
     use quickwit_index_management::IndexService;
@@ -420,7 +481,7 @@ mod tests {
     async fn test_cors() {
         // No cors enabled
         {
-            let cors = build_cors(&[]);
+            let cors = build_cors(&CorsConfig::default());
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -451,7 +512,7 @@ mod tests {
 
         // Wildcard cors enabled
         {
-            let cors = build_cors(&["*".to_string()]);
+            let cors = build_cors(&cors_config(&["*"]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -488,7 +549,7 @@ mod tests {
 
         // Specific origin cors enabled
         {
-            let cors = build_cors(&["https://quickwit.io".to_string()]);
+            let cors = build_cors(&cors_config(&["https://quickwit.io"]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -539,10 +600,10 @@ mod tests {
 
         // Specific multiple-origin cors enabled
         {
-            let cors = build_cors(&[
-                "https://quickwit.io".to_string(),
-                "http://localhost:3000".to_string(),
-            ]);
+            let cors = build_cors(&cors_config(&[
+                "https://quickwit.io",
+                "http://localhost:3000",
+            ]));
 
             let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
 
@@ -595,6 +656,91 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_cors_credentials_max_age_and_vary() {
+        // Credentials are allowed for specific origins only.
+        {
+            let cors_config = CorsConfig {
+                allow_credentials: true,
+                max_age_secs: Some(3600),
+                ..cors_config(&["https://quickwit.io"])
+            };
+            let cors = build_cors(&cors_config);
+            let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
+
+            let resp = layer
+                .call(cors_request("https://quickwit.io"))
+                .await
+                .unwrap();
+            let headers = resp.headers();
+            assert_eq!(
+                headers.get("Access-Control-Allow-Credentials"),
+                Some(&HeaderValue::from_static("true"))
+            );
+            assert_eq!(
+                headers.get("Access-Control-Max-Age"),
+                Some(&HeaderValue::from_static("3600"))
+            );
+            let vary_headers = headers.get_all("Vary").iter().collect::<Vec<_>>();
+            assert!(!vary_headers
+                .iter()
+                .any(|value| value.to_str().unwrap().contains("origin")));
+        }
+
+        // Credentials are ignored for wildcard origins, which would otherwise make the layer
+        // panic.
+        {
+            let cors_config = CorsConfig {
+                allow_credentials: true,
+                ..cors_config(&["*"])
+            };
+            let cors = build_cors(&cors_config);
+            let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
+
+            let resp = layer
+                .call(cors_request("https://quickwit.io"))
+                .await
+                .unwrap();
+            let headers = resp.headers();
+            assert_eq!(
+                headers.get("Access-Control-Allow-Origin"),
+                Some(&HeaderValue::from_static("*"))
+            );
+            assert_eq!(headers.get("Access-Control-Allow-Credentials"), None);
+        }
+
+        // Responses vary on the origin when several origins are allowed.
+        for vary_on_origin in [true, false] {
+            let cors_config = CorsConfig {
+                vary_on_origin,
+                ..cors_config(&["https://quickwit.io", "http://localhost:3000"])
+            };
+            let cors = build_cors(&cors_config);
+            let mut layer = ServiceBuilder::new().layer(cors).service(HelloWorld);
+
+            let resp = layer
+                .call(cors_request("http://localhost:3000"))
+                .await
+                .unwrap();
+            let varies_on_origin = resp
+                .headers()
+                .get_all("Vary")
+                .iter()
+                .any(|value| value.to_str().unwrap().contains("origin"));
+            assert_eq!(varies_on_origin, vary_on_origin);
+        }
+    }
+
+    fn cors_config(allowed_origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: allowed_origins
+                .iter()
+                .map(|origin| origin.to_string())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
     fn cors_request(origin: &'static str) -> Request<()> {
         let mut request = Request::new(());
         (*request.method_mut()) = Method::OPTIONS;
@@ -668,6 +814,7 @@ mod tests {
             search_service: Arc::new(MockSearchService::new()),
             jaeger_service_opt: None,
             env_filter_reload_fn: crate::do_nothing_env_filter_reload_fn(),
+            startup_completed: Default::default(),
         };
 
         let handler = api_v1_routes(Arc::new(quickwit_services))
@@ -70,6 +70,23 @@ impl RestApiResponse {
         let inner = body_format.result_to_vec(result);
         RestApiResponse { status_code, inner }
     }
+
+    /// Makes a response from an already serialized body.
+    pub(crate) fn from_body(body: Vec<u8>, status_code: StatusCode) -> Self {
+        RestApiResponse {
+            status_code,
+            inner: Ok(body),
+        }
+    }
+
+    pub(crate) fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    /// Returns the serialized body of the response, or `None` if the serialization failed.
+    pub(crate) fn body(&self) -> Option<&[u8]> {
+        self.inner.as_deref().ok()
+    }
 }
 
 impl Reply for RestApiResponse {
@@ -27,7 +27,7 @@ use quickwit_proto::search::{
     LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest, ListFieldsResponse,
     ReportSplitsRequest, ReportSplitsResponse,
 };
-use quickwit_proto::{set_parent_span_from_request_metadata, tonic, GrpcServiceError};
+use quickwit_proto::{tonic, GrpcServiceError};
 use quickwit_search::SearchService;
 use tracing::instrument;
 
@@ -47,7 +47,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::SearchRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::SearchResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let search_request = request.into_inner();
         let search_result = self.0.root_search(search_request).await;
         convert_to_grpc_result(search_result)
@@ -58,7 +57,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::LeafSearchRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::LeafSearchResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
         let leaf_search_result = self.0.leaf_search(leaf_search_request).await;
         convert_to_grpc_result(leaf_search_result)
@@ -69,7 +67,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::FetchDocsRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::FetchDocsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let fetch_docs_request = request.into_inner();
         let fetch_docs_result = self.0.fetch_docs(fetch_docs_request).await;
         convert_to_grpc_result(fetch_docs_result)
@@ -87,7 +84,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<LeafSearchStreamRequest>,
     ) -> Result<tonic::Response<Self::LeafSearchStreamStream>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
         let leaf_search_result = self
             .0
@@ -103,7 +99,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::ListTermsRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::ListTermsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let search_request = request.into_inner();
         let search_result = self.0.root_list_terms(search_request).await;
         convert_to_grpc_result(search_result)
@@ -114,7 +109,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::LeafListTermsRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::LeafListTermsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let leaf_search_request = request.into_inner();
         let leaf_search_result = self.0.leaf_list_terms(leaf_search_request).await;
         convert_to_grpc_result(leaf_search_result)
@@ -134,7 +128,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<quickwit_proto::search::PutKvRequest>,
     ) -> Result<tonic::Response<quickwit_proto::search::PutKvResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let put_request = request.into_inner();
         self.0.put_kv(put_request).await;
         Ok(tonic::Response::new(
@@ -147,7 +140,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<GetKvRequest>,
     ) -> Result<tonic::Response<GetKvResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let get_search_after_context_request = request.into_inner();
         let payload = self.0.get_kv(get_search_after_context_request).await;
         let get_response = GetKvResponse { payload };
@@ -159,7 +151,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<ReportSplitsRequest>,
     ) -> Result<tonic::Response<ReportSplitsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let get_search_after_context_request = request.into_inner();
         self.0.report_splits(get_search_after_context_request).await;
         Ok(tonic::Response::new(ReportSplitsResponse {}))
@@ -170,7 +161,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<ListFieldsRequest>,
     ) -> Result<tonic::Response<ListFieldsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let resp = self.0.root_list_fields(request.into_inner()).await;
         convert_to_grpc_result(resp)
     }
@@ -179,7 +169,6 @@ impl grpc::SearchService for GrpcSearchAdapter {
         &self,
         request: tonic::Request<LeafListFieldsRequest>,
     ) -> Result<tonic::Response<ListFieldsResponse>, tonic::Status> {
-        set_parent_span_from_request_metadata(request.metadata());
         let resp = self.0.leaf_list_fields(request.into_inner()).await;
         convert_to_grpc_result(resp)
     }
@@ -21,11 +21,12 @@ mod grpc_adapter;
 mod rest_handler;
 
 pub use self::grpc_adapter::GrpcSearchAdapter;
-pub(crate) use self::rest_handler::{extract_index_id_patterns, extract_index_id_patterns_default};
 pub use self::rest_handler::{
-    search_get_handler, search_post_handler, search_request_from_api_request,
-    search_stream_handler, SearchApi, SearchRequestQueryString, SortBy,
+    analyze_query_handler, field_stats_handler, search_get_handler, search_post_handler,
+    search_request_from_api_request, search_stream_handler, AnalyzeQueryRequest, SearchApi,
+    SearchRequestQueryString, SortBy,
 };
+pub(crate) use self::rest_handler::{extract_index_id_patterns, extract_index_id_patterns_default};
 
 #[cfg(test)]
 mod tests {
@@ -34,6 +35,7 @@ mod tests {
 
     use bytesize::ByteSize;
     use futures::TryStreamExt;
+    use quickwit_common::tower::GrpcClientInterceptor;
     use quickwit_common::ServiceStream;
     use quickwit_indexing::MockSplitBuilder;
     use quickwit_metastore::{IndexMetadata, IndexMetadataResponseExt, ListSplitsResponseExt};
@@ -131,7 +133,11 @@ mod tests {
         let searcher_pool = SearcherPool::default();
         searcher_pool.insert(
             grpc_addr,
-            create_search_client_from_grpc_addr(grpc_addr, ByteSize::mib(1)),
+            create_search_client_from_grpc_addr(
+                grpc_addr,
+                ByteSize::mib(1),
+                GrpcClientInterceptor::default(),
+            ),
         );
         let search_job_placer = SearchJobPlacer::new(searcher_pool);
         let cluster_client = ClusterClient::new(search_job_placer.clone());
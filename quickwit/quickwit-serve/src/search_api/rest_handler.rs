@@ -24,12 +24,16 @@ use futures::stream::StreamExt;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use percent_encoding::percent_decode_str;
+use quickwit_common::is_false;
 use quickwit_config::validate_index_id_pattern;
 use quickwit_proto::search::{CountHits, OutputFormat, SortField, SortOrder};
 use quickwit_proto::types::IndexId;
 use quickwit_proto::ServiceError;
 use quickwit_query::query_ast::query_ast_from_user_text;
-use quickwit_search::{SearchError, SearchResponseRest, SearchService};
+use quickwit_search::{
+    AnalyzeQueryResponse, AnalyzedPhrase, AnalyzedTerm, AnalyzedToken, AnalyzedWildcard,
+    FieldStatsResponse, FieldValueCount, SearchError, SearchResponseRest, SearchService,
+};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 use tracing::info;
@@ -43,9 +47,23 @@ use crate::{with_arg, BodyFormat};
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
-    paths(search_get_handler, search_post_handler, search_stream_handler,),
+    paths(
+        search_get_handler,
+        search_post_handler,
+        search_stream_handler,
+        analyze_query_handler,
+        field_stats_handler,
+    ),
     components(schemas(
+        AnalyzeQueryRequest,
+        AnalyzeQueryResponse,
+        AnalyzedPhrase,
+        AnalyzedTerm,
+        AnalyzedToken,
+        AnalyzedWildcard,
         BodyFormat,
+        FieldStatsResponse,
+        FieldValueCount,
         OutputFormat,
         SearchRequestQueryString,
         SearchResponseRest,
@@ -232,6 +250,11 @@ pub struct SearchRequestQueryString {
     #[serde(with = "count_hits_from_bool")]
     #[serde(default = "count_hits_from_bool::default")]
     pub count_all: CountHits,
+    /// If set and the query matches no documents, the response contains spelling
+    /// suggestions for the query terms.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub suggest_corrections: bool,
 }
 
 mod count_hits_from_bool {
@@ -286,6 +309,7 @@ pub fn search_request_from_api_request(
         scroll_ttl_secs: None,
         search_after: None,
         count_hits: search_request.count_all.into(),
+        suggest_corrections: search_request.suggest_corrections,
     };
     Ok(search_request)
 }
@@ -398,6 +422,113 @@ pub fn search_stream_handler(
         .then(search_stream)
 }
 
+/// Body of an analyze query request.
+#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnalyzeQueryRequest {
+    /// Query text. The query language is that of tantivy.
+    pub query: String,
+}
+
+fn analyze_query_filter(
+) -> impl Filter<Extract = (IndexId, AnalyzeQueryRequest), Error = Rejection> + Clone {
+    warp::path!(String / "analyze-query")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+}
+
+async fn analyze_query(
+    index_id: IndexId,
+    analyze_query_request: AnalyzeQueryRequest,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(index_id = %index_id, request =? analyze_query_request, "analyze-query");
+    let result = search_service
+        .root_analyze_query(index_id, analyze_query_request.query)
+        .await;
+    into_rest_api_response(result, BodyFormat::default())
+}
+
+#[utoipa::path(
+    post,
+    tag = "Search",
+    path = "/{index_id}/analyze-query",
+    request_body = AnalyzeQueryRequest,
+    responses(
+        (status = 200, description = "Successfully analyzed query.", body = AnalyzeQueryResponse)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to analyze the query against."),
+    )
+)]
+/// Analyze Query
+///
+/// Parses the query against the doc mapping of the index and returns the query AST, the terms
+/// searched, the token positions of the phrases, and the number of terms each wildcard expands
+/// to. The query is not executed.
+pub fn analyze_query_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    analyze_query_filter()
+        .and(with_arg(search_service))
+        .then(analyze_query)
+}
+
+/// Query string of a field stats request.
+#[derive(Debug, Eq, PartialEq, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+struct FieldStatsQueryString {
+    /// Fast field to compute the stats of.
+    pub field: String,
+}
+
+fn field_stats_filter(
+) -> impl Filter<Extract = (IndexId, FieldStatsQueryString), Error = Rejection> + Clone {
+    warp::path!(String / "field-stats")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+async fn field_stats(
+    index_id: IndexId,
+    field_stats_query_string: FieldStatsQueryString,
+    search_service: Arc<dyn SearchService>,
+) -> impl warp::Reply {
+    info!(index_id = %index_id, field = %field_stats_query_string.field, "field-stats");
+    let result = search_service
+        .root_field_stats(index_id, field_stats_query_string.field)
+        .await;
+    into_rest_api_response(result, BodyFormat::default())
+}
+
+#[utoipa::path(
+    get,
+    tag = "Search",
+    path = "/{index_id}/field-stats",
+    responses(
+        (status = 200, description = "Successfully computed field stats.", body = FieldStatsResponse)
+    ),
+    params(
+        FieldStatsQueryString,
+        ("index_id" = String, Path, description = "The index ID of the field."),
+    )
+)]
+/// Field Stats
+///
+/// Returns the number of documents with and without a value, the number of distinct values, the
+/// min and max values, and the value histogram of a fast field, computed over all the published
+/// splits of the index. For fields with more than 1,000 distinct values, only the top 20 values
+/// are returned. Results are cached for 5 minutes.
+pub fn field_stats_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    field_stats_filter()
+        .and(with_arg(search_service))
+        .then(field_stats)
+}
+
 /// This struct represents the search stream query passed to
 /// the REST API.
 #[derive(Deserialize, Debug, Eq, PartialEq, utoipa::IntoParams)]
@@ -537,7 +668,9 @@ mod tests {
         let mock_search_service_in_arc = Arc::new(mock_search_service);
         search_get_handler(mock_search_service_in_arc.clone())
             .or(search_post_handler(mock_search_service_in_arc.clone()))
-            .or(search_stream_handler(mock_search_service_in_arc))
+            .or(search_stream_handler(mock_search_service_in_arc.clone()))
+            .or(analyze_query_handler(mock_search_service_in_arc.clone()))
+            .or(field_stats_handler(mock_search_service_in_arc))
             .recover(recover_fn)
     }
 
@@ -573,6 +706,7 @@ mod tests {
             elapsed_time_micros: 0u64,
             errors: Vec::new(),
             aggregations: None,
+            suggestions: None,
         };
         let search_response_json: JsonValue = serde_json::to_value(search_response)?;
         let expected_search_response_json: JsonValue = json!({
@@ -738,6 +872,21 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_route_suggest_corrections() {
+        let rest_search_api_filter = search_get_filter();
+        let (_indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=helo&suggest_corrections=true")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        assert!(req.suggest_corrections);
+
+        let search_request =
+            search_request_from_api_request(vec!["quickwit-demo-index".to_string()], req).unwrap();
+        assert!(search_request.suggest_corrections);
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_route_simple_default_num_hits_default_offset() {
         let rest_search_api_filter = search_get_filter();
@@ -1062,6 +1211,151 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_analyze_query_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_analyze_query()
+            .with(
+                predicate::eq("my-index".to_string()),
+                predicate::eq("foo AND bar*".to_string()),
+            )
+            .return_once(|_, _| {
+                Ok(AnalyzeQueryResponse {
+                    query_ast: json!({"type": "match_all"}),
+                    terms: vec![AnalyzedTerm {
+                        field: "body".to_string(),
+                        value: "foo".to_string(),
+                        need_positions: false,
+                    }],
+                    phrases: Vec::new(),
+                    wildcards: Vec::new(),
+                })
+            });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/my-index/analyze-query")
+            .json(&json!({"query": "foo AND bar*"}))
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let response_json: JsonValue = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            response_json,
+            json!({
+                "query_ast": {"type": "match_all"},
+                "terms": [{"field": "body", "value": "foo", "need_positions": false}],
+                "phrases": [],
+                "wildcards": [],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rest_analyze_query_api_errors() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_analyze_query()
+            .return_once(|_, _| Err(SearchError::InvalidQuery("invalid query".to_string())));
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/my-index/analyze-query")
+            .json(&json!({"query": "foo AND (bar"}))
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+        let body = String::from_utf8_lossy(response.body());
+        assert!(body.contains("invalid query"));
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/my-index/analyze-query")
+            .json(&json!({"query": "foo", "max_hits": 10}))
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_rest_field_stats_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_field_stats()
+            .with(
+                predicate::eq("my-index".to_string()),
+                predicate::eq("status_code".to_string()),
+            )
+            .return_once(|_, _| {
+                Ok(FieldStatsResponse {
+                    field: "status_code".to_string(),
+                    count: 3,
+                    null_count: 1,
+                    distinct_values: 2,
+                    is_high_cardinality: false,
+                    min: Some(json!(200)),
+                    max: Some(json!(503)),
+                    histogram: vec![
+                        FieldValueCount {
+                            value: json!(200),
+                            count: 2,
+                        },
+                        FieldValueCount {
+                            value: json!(503),
+                            count: 1,
+                        },
+                    ],
+                })
+            });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .path("/my-index/field-stats?field=status_code")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let response_json: JsonValue = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            response_json,
+            json!({
+                "field": "status_code",
+                "count": 3,
+                "null_count": 1,
+                "distinct_values": 2,
+                "is_high_cardinality": false,
+                "min": 200,
+                "max": 503,
+                "histogram": [{"value": 200, "count": 2}, {"value": 503, "count": 1}],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rest_field_stats_api_errors() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_field_stats()
+            .return_once(|_, _| {
+                Err(SearchError::InvalidArgument(
+                    "field `body` is not a fast field".to_string(),
+                ))
+            });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let response = warp::test::request()
+            .path("/my-index/field-stats?field=body")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+        let body = String::from_utf8_lossy(response.body());
+        assert!(body.contains("field `body` is not a fast field"));
+
+        let response = warp::test::request()
+            .path("/my-index/field-stats")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+    }
+
     #[tokio::test]
     async fn test_rest_search_stream_api() {
         let mut mock_search_service = MockSearchService::new();
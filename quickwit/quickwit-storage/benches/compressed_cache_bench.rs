@@ -0,0 +1,120 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use quickwit_storage::{
+    CompressedBytes, CompressionCodec, MemorySizedCache, OwnedBytes, CACHE_METRICS,
+};
+
+const NUM_ENTRIES: usize = 100;
+const ENTRY_NUM_BYTES: usize = 64 * 1024;
+
+/// Generates a moderately compressible payload, akin to a split footer or a doc store block.
+fn generate_payload(seed: usize) -> Vec<u8> {
+    (0..ENTRY_NUM_BYTES)
+        .map(|i| ((i / 16 + seed) % 64) as u8 + b' ')
+        .collect()
+}
+
+fn compressed_cache_benchmark(c: &mut Criterion) {
+    let payloads: Vec<Vec<u8>> = (0..NUM_ENTRIES).map(generate_payload).collect();
+
+    let raw_num_bytes: usize = payloads.iter().map(Vec::len).sum();
+    println!("uncompressed cache entries: {raw_num_bytes} bytes");
+
+    for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+        let compressed_num_bytes: usize = payloads
+            .iter()
+            .map(|payload| {
+                CompressedBytes::compress(payload, codec)
+                    .unwrap()
+                    .compressed_len()
+            })
+            .sum();
+        println!("{codec:?} compressed cache entries: {compressed_num_bytes} bytes");
+    }
+
+    let mut group = c.benchmark_group("memory_sized_cache_get");
+    group.throughput(Throughput::Bytes(ENTRY_NUM_BYTES as u64));
+
+    group.bench_function("uncompressed", |b| {
+        let cache =
+            MemorySizedCache::<usize>::with_infinite_capacity(&CACHE_METRICS.shortlived_cache);
+        for (key, payload) in payloads.iter().enumerate() {
+            cache.put(key, OwnedBytes::new(payload.clone()));
+        }
+        b.iter(|| {
+            for key in 0..NUM_ENTRIES {
+                black_box(cache.get(&key));
+            }
+        })
+    });
+    for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+        // Measures the latency of the first access, which pays for the decompression.
+        group.bench_with_input(
+            BenchmarkId::new("compressed_first_access", format!("{codec:?}")),
+            &codec,
+            |b, &codec| {
+                b.iter_batched(
+                    || {
+                        let cache = MemorySizedCache::<usize>::with_infinite_capacity(
+                            &CACHE_METRICS.shortlived_cache,
+                        );
+                        for (key, payload) in payloads.iter().enumerate() {
+                            let compressed_bytes =
+                                CompressedBytes::compress(payload, codec).unwrap();
+                            cache.put_compressed(key, compressed_bytes);
+                        }
+                        cache
+                    },
+                    |cache| {
+                        for key in 0..NUM_ENTRIES {
+                            black_box(cache.get(&key));
+                        }
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+        // Measures the latency of the subsequent accesses, once the entries are decompressed.
+        group.bench_with_input(
+            BenchmarkId::new("compressed_warm_access", format!("{codec:?}")),
+            &codec,
+            |b, &codec| {
+                let cache = MemorySizedCache::<usize>::with_infinite_capacity(
+                    &CACHE_METRICS.shortlived_cache,
+                );
+                for (key, payload) in payloads.iter().enumerate() {
+                    let compressed_bytes = CompressedBytes::compress(payload, codec).unwrap();
+                    cache.put_compressed(key, compressed_bytes);
+                    cache.get(&key);
+                }
+                b.iter(|| {
+                    for key in 0..NUM_ENTRIES {
+                        black_box(cache.get(&key));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, compressed_cache_benchmark);
+criterion_main!(benches);
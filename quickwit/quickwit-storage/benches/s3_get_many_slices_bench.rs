@@ -0,0 +1,97 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Compares reading 20 small non-adjacent ranges of a split with one request per range against
+//! `get_many_slices`, which coalesces them.
+//!
+//! The benchmark runs against the S3-compatible object storage configured via the usual AWS
+//! environment variables and `QW_S3_ENDPOINT`, for instance LocalStack:
+//! `QW_S3_ENDPOINT=http://localhost:4566 cargo bench --bench s3_get_many_slices_bench`.
+//! The target bucket can be overridden with `QW_S3_BENCH_URI`.
+
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use quickwit_common::uri::Uri;
+use quickwit_config::S3StorageConfig;
+use quickwit_storage::{S3CompatibleObjectStorage, Storage};
+
+const DEFAULT_BENCH_URI: &str = "s3://quickwit-integration-tests/bench-get-many-slices";
+const SPLIT_NUM_BYTES: usize = 16 * 1024 * 1024;
+const NUM_RANGES: usize = 20;
+const RANGE_NUM_BYTES: usize = 4 * 1024;
+const RANGE_STRIDE_NUM_BYTES: usize = 64 * 1024;
+
+/// Returns ranges akin to the ones read when opening a split: small and scattered across the
+/// file.
+fn split_ranges() -> Vec<Range<usize>> {
+    (0..NUM_RANGES)
+        .map(|range_idx| {
+            let start = SPLIT_NUM_BYTES / 2 + range_idx * RANGE_STRIDE_NUM_BYTES;
+            start..start + RANGE_NUM_BYTES
+        })
+        .collect()
+}
+
+fn s3_get_many_slices_benchmark(c: &mut Criterion) {
+    // The S3 client holds a connection pool tied to the runtime it was created on.
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let bench_uri = std::env::var("QW_S3_BENCH_URI").unwrap_or(DEFAULT_BENCH_URI.to_string());
+    let storage_uri = Uri::from_str(&bench_uri).unwrap();
+    let storage = runtime.block_on(async {
+        let s3_storage_config = S3StorageConfig {
+            force_path_style_access: true,
+            ..Default::default()
+        };
+        S3CompatibleObjectStorage::from_uri(&s3_storage_config, &storage_uri)
+            .await
+            .unwrap()
+    });
+    let split_path = Path::new("bench.split");
+    let split_payload: Vec<u8> = (0..SPLIT_NUM_BYTES).map(|i| (i % 251) as u8).collect();
+    runtime
+        .block_on(storage.put(split_path, Box::new(split_payload)))
+        .unwrap();
+    let ranges = split_ranges();
+
+    let mut group = c.benchmark_group("s3_read_20_small_ranges");
+    group.bench_function("get_slice_per_range", |b| {
+        b.to_async(&runtime).iter(|| {
+            let get_slice_futures = ranges
+                .iter()
+                .map(|range| storage.get_slice(split_path, range.clone()));
+            futures::future::try_join_all(get_slice_futures)
+        })
+    });
+    group.bench_function("get_many_slices", |b| {
+        b.to_async(&runtime)
+            .iter(|| storage.get_many_slices(split_path, &ranges))
+    });
+    group.finish();
+
+    runtime.block_on(storage.delete(split_path)).unwrap();
+}
+
+criterion_group!(benches, s3_get_many_slices_benchmark);
+criterion_main!(benches);
@@ -38,7 +38,8 @@ use tracing::error;
 
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, OwnedBytes, Storage, StorageError, StorageResult, VersionedComponent,
+    BulkDeleteError, OwnedBytes, Storage, StorageError, StorageResult, StorageStats,
+    VersionedComponent,
 };
 
 /// BundleStorage bundles together multiple files into a single file.
@@ -220,6 +221,10 @@ impl Storage for BundleStorage {
         Err(unsupported_operation(&[path]))
     }
 
+    async fn copy(&self, src: &Path, dst: &Path) -> crate::StorageResult<()> {
+        Err(unsupported_operation(&[src, dst]))
+    }
+
     async fn copy_to(
         &self,
         path: &Path,
@@ -251,6 +256,25 @@ impl Storage for BundleStorage {
             .await
     }
 
+    async fn get_many_slices(
+        &self,
+        path: &Path,
+        ranges: &[Range<usize>],
+    ) -> crate::StorageResult<Vec<OwnedBytes>> {
+        let file_offsets = self.metadata.get(path).ok_or_else(|| {
+            crate::StorageErrorKind::NotFound
+                .with_error(anyhow::anyhow!("missing file `{}`", path.display()))
+        })?;
+        let file_start = file_offsets.start as usize;
+        let new_ranges: Vec<Range<usize>> = ranges
+            .iter()
+            .map(|range| file_start + range.start..file_start + range.end)
+            .collect();
+        self.storage
+            .get_many_slices(&self.bundle_filepath, &new_ranges)
+            .await
+    }
+
     async fn get_slice_stream(
         &self,
         path: &Path,
@@ -299,6 +323,10 @@ impl Storage for BundleStorage {
     fn uri(&self) -> &Uri {
         self.storage.uri()
     }
+
+    fn stats(&self) -> StorageStats {
+        self.storage.stats()
+    }
 }
 
 impl HasLen for BundleStorage {
@@ -0,0 +1,252 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use quickwit_common::memory_pressure::MemoryPressureMonitor;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::cache::slice_address::SliceAddress;
+use crate::metrics::CacheMetrics;
+use crate::MemorySizedCache;
+
+/// Interval at which the capacity of an [`AdaptiveMemorySizedCache`] is adjusted.
+const CAPACITY_ADJUSTMENT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Bounds the capacity of an [`AdaptiveMemorySizedCache`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveCacheCapacityConfig {
+    /// Fraction of the memory available on the host the cache may use.
+    pub target_memory_fraction: f64,
+    /// The capacity never goes below this limit, regardless of the memory pressure.
+    pub min_capacity_in_bytes: usize,
+    /// The capacity never goes above this limit, regardless of the memory available. This is
+    /// also the capacity used when the available memory cannot be determined.
+    pub max_capacity_in_bytes: usize,
+}
+
+impl AdaptiveCacheCapacityConfig {
+    /// Computes the capacity of a cache currently holding `cache_num_bytes` bytes.
+    ///
+    /// The memory held by the cache is accounted as available: otherwise, the cache would shrink
+    /// as it fills up.
+    fn capacity_in_bytes(
+        &self,
+        available_memory_bytes_opt: Option<u64>,
+        cache_num_bytes: u64,
+    ) -> usize {
+        let Some(available_memory_bytes) = available_memory_bytes_opt else {
+            return self.max_capacity_in_bytes;
+        };
+        let memory_budget_bytes = (available_memory_bytes + cache_num_bytes) as f64;
+        let target_capacity_in_bytes = (self.target_memory_fraction * memory_budget_bytes) as usize;
+        target_capacity_in_bytes.clamp(self.min_capacity_in_bytes, self.max_capacity_in_bytes)
+    }
+}
+
+/// A [`MemorySizedCache`] whose capacity follows the memory available on the host.
+///
+/// Every 10 seconds, a background task sets the capacity of the cache to
+/// `target_memory_fraction * available_memory`, within the configured bounds. When the
+/// available memory drops, the cache evicts its least recently used items right away to free up
+/// memory. When memory is abundant again, the cache is allowed to grow back.
+///
+/// The background task stops when the cache is dropped. When the cache is created outside of a
+/// tokio runtime, no task is spawned and the capacity stays at its initial value.
+pub struct AdaptiveMemorySizedCache<K: Hash + Eq = SliceAddress> {
+    cache: Arc<MemorySizedCache<K>>,
+    adjustment_task_handle_opt: Option<JoinHandle<()>>,
+}
+
+impl<K> AdaptiveMemorySizedCache<K>
+where K: Hash + Eq + Send + 'static
+{
+    /// Creates an adaptive cache and spawns the task adjusting its capacity on the current
+    /// runtime, if any.
+    pub fn new(
+        capacity_config: AdaptiveCacheCapacityConfig,
+        memory_pressure_monitor: MemoryPressureMonitor,
+        cache_counters: &'static CacheMetrics,
+    ) -> Self {
+        let initial_capacity_in_bytes =
+            capacity_config.capacity_in_bytes(memory_pressure_monitor.available_memory_bytes(), 0);
+        let cache = Arc::new(MemorySizedCache::with_capacity_in_bytes(
+            initial_capacity_in_bytes,
+            cache_counters,
+        ));
+        let adjustment_task_handle_opt = Handle::try_current().ok().map(|runtime_handle| {
+            runtime_handle.spawn(adjust_capacity_loop(
+                Arc::downgrade(&cache),
+                capacity_config,
+                memory_pressure_monitor,
+            ))
+        });
+        Self {
+            cache,
+            adjustment_task_handle_opt,
+        }
+    }
+}
+
+impl<K: Hash + Eq> Deref for AdaptiveMemorySizedCache<K> {
+    type Target = MemorySizedCache<K>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cache
+    }
+}
+
+impl<K: Hash + Eq> Drop for AdaptiveMemorySizedCache<K> {
+    fn drop(&mut self) {
+        if let Some(adjustment_task_handle) = &self.adjustment_task_handle_opt {
+            adjustment_task_handle.abort();
+        }
+    }
+}
+
+async fn adjust_capacity_loop<K: Hash + Eq>(
+    cache_weak: Weak<MemorySizedCache<K>>,
+    capacity_config: AdaptiveCacheCapacityConfig,
+    memory_pressure_monitor: MemoryPressureMonitor,
+) {
+    let mut interval = tokio::time::interval(CAPACITY_ADJUSTMENT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let Some(cache) = cache_weak.upgrade() else {
+            return;
+        };
+        let available_memory_bytes_opt = memory_pressure_monitor.available_memory_bytes();
+        let new_capacity_in_bytes =
+            capacity_config.capacity_in_bytes(available_memory_bytes_opt, cache.num_bytes());
+
+        if cache.capacity_in_bytes() != Some(new_capacity_in_bytes) {
+            debug!(
+                available_memory_bytes=?available_memory_bytes_opt,
+                capacity_in_bytes=new_capacity_in_bytes,
+                "adjusting cache capacity"
+            );
+            cache.set_capacity_in_bytes(new_capacity_in_bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use quickwit_common::memory_pressure::MemoryReporter;
+
+    use super::*;
+    use crate::metrics::CACHE_METRICS_FOR_TESTS;
+    use crate::OwnedBytes;
+
+    #[derive(Clone, Default)]
+    struct MockMemoryReporter {
+        available_memory_bytes: Arc<AtomicU64>,
+    }
+
+    impl MockMemoryReporter {
+        fn set_available_memory_bytes(&self, available_memory_bytes: u64) {
+            self.available_memory_bytes
+                .store(available_memory_bytes, Ordering::Relaxed);
+        }
+    }
+
+    impl MemoryReporter for MockMemoryReporter {
+        fn available_memory_bytes(&self) -> Option<u64> {
+            Some(self.available_memory_bytes.load(Ordering::Relaxed))
+        }
+    }
+
+    #[test]
+    fn test_adaptive_cache_capacity_config() {
+        let capacity_config = AdaptiveCacheCapacityConfig {
+            target_memory_fraction: 0.5,
+            min_capacity_in_bytes: 10,
+            max_capacity_in_bytes: 100,
+        };
+        assert_eq!(capacity_config.capacity_in_bytes(None, 0), 100);
+        assert_eq!(capacity_config.capacity_in_bytes(Some(0), 0), 10);
+        assert_eq!(capacity_config.capacity_in_bytes(Some(60), 0), 30);
+        assert_eq!(capacity_config.capacity_in_bytes(Some(60), 40), 50);
+        assert_eq!(capacity_config.capacity_in_bytes(Some(1_000), 0), 100);
+    }
+
+    #[test]
+    fn test_adaptive_memory_sized_cache_without_runtime() {
+        let memory_reporter = MockMemoryReporter::default();
+        memory_reporter.set_available_memory_bytes(1_000);
+
+        let capacity_config = AdaptiveCacheCapacityConfig {
+            target_memory_fraction: 0.5,
+            min_capacity_in_bytes: 0,
+            max_capacity_in_bytes: 100,
+        };
+        let cache = AdaptiveMemorySizedCache::<String>::new(
+            capacity_config,
+            MemoryPressureMonitor::new(memory_reporter),
+            &CACHE_METRICS_FOR_TESTS,
+        );
+        assert!(cache.adjustment_task_handle_opt.is_none());
+        assert_eq!(cache.capacity_in_bytes(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_memory_sized_cache() {
+        tokio::time::pause();
+        let memory_reporter = MockMemoryReporter::default();
+        memory_reporter.set_available_memory_bytes(1_000);
+
+        let capacity_config = AdaptiveCacheCapacityConfig {
+            target_memory_fraction: 0.5,
+            min_capacity_in_bytes: 0,
+            max_capacity_in_bytes: 100,
+        };
+        let cache = AdaptiveMemorySizedCache::<String>::new(
+            capacity_config,
+            MemoryPressureMonitor::new(memory_reporter.clone()),
+            &CACHE_METRICS_FOR_TESTS,
+        );
+        assert_eq!(cache.capacity_in_bytes(), Some(100));
+
+        for key in ["1", "2", "3"] {
+            cache.put(key.to_string(), OwnedBytes::new(vec![0u8; 30]));
+        }
+        assert_eq!(cache.num_bytes(), 90);
+
+        // The cache shrinks and evicts its least recently used item under memory pressure.
+        memory_reporter.set_available_memory_bytes(40);
+        tokio::time::sleep(CAPACITY_ADJUSTMENT_INTERVAL + Duration::from_millis(1)).await;
+        assert_eq!(cache.capacity_in_bytes(), Some(65));
+        assert_eq!(cache.num_bytes(), 60);
+        assert!(cache.get(&"1".to_string()).is_none());
+        assert!(cache.get(&"3".to_string()).is_some());
+
+        // The cache grows back up to its maximum capacity when memory is abundant.
+        memory_reporter.set_available_memory_bytes(10_000);
+        tokio::time::sleep(CAPACITY_ADJUSTMENT_INTERVAL).await;
+        assert_eq!(cache.capacity_in_bytes(), Some(100));
+    }
+}
@@ -18,6 +18,8 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::future::Future;
 use std::hash::Hash;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
@@ -25,15 +27,16 @@ use std::sync::Mutex;
 use std::time::Duration;
 
 use lru::LruCache;
+use tokio::sync::broadcast;
 use tokio::time::Instant;
 use tracing::{error, warn};
 
 use crate::cache::slice_address::{SliceAddress, SliceAddressKey, SliceAddressRef};
 use crate::cache::stored_item::StoredItem;
 use crate::metrics::CacheMetrics;
-use crate::OwnedBytes;
+use crate::{CompressedBytes, OwnedBytes, StorageResult};
 
-/// We do not evict anything that has been accessed in the last 60s.
+/// By default, we do not evict anything that has been accessed in the last 60s.
 ///
 /// The goal is to behave better on scan access patterns, without being as aggressive as
 /// using a MRU strategy.
@@ -47,7 +50,10 @@ use crate::OwnedBytes;
 /// On the other hand, for very large queries involving enough data to saturate the cache,
 /// we are facing a scanning pattern. If variations of this  query is repeated over and over
 /// a regular LRU eviction policy would yield a hit rate of 0.
-const MIN_TIME_SINCE_LAST_ACCESS: Duration = Duration::from_secs(60);
+///
+/// Caches with small and hot working sets can lower this duration with
+/// [`MemorySizedCache::with_capacity_in_bytes_and_no_evict_duration`].
+const DEFAULT_NO_EVICT_DURATION: Duration = Duration::from_secs(60);
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Capacity {
@@ -69,6 +75,8 @@ struct NeedMutMemorySizedCache<K: Hash + Eq> {
     num_items: usize,
     num_bytes: u64,
     capacity: Capacity,
+    // Items accessed more recently than this are never evicted.
+    no_evict_duration: Duration,
     cache_counters: &'static CacheMetrics,
 }
 
@@ -85,7 +93,11 @@ impl<K: Hash + Eq> Drop for NeedMutMemorySizedCache<K> {
 
 impl<K: Hash + Eq> NeedMutMemorySizedCache<K> {
     /// Creates a new NeedMutSliceCache with the given capacity.
-    fn with_capacity(capacity: Capacity, cache_counters: &'static CacheMetrics) -> Self {
+    fn with_capacity(
+        capacity: Capacity,
+        no_evict_duration: Duration,
+        cache_counters: &'static CacheMetrics,
+    ) -> Self {
         NeedMutMemorySizedCache {
             // The limit will be decided by the amount of memory in the cache,
             // not the number of items in the cache.
@@ -94,6 +106,7 @@ impl<K: Hash + Eq> NeedMutMemorySizedCache<K> {
             num_items: 0,
             num_bytes: 0,
             capacity,
+            no_evict_duration,
             cache_counters,
         }
     }
@@ -132,34 +145,74 @@ impl<K: Hash + Eq> NeedMutMemorySizedCache<K> {
     /// This may fail silently if the owned_bytes slice is larger than the cache
     /// capacity.
     fn put(&mut self, key: K, bytes: OwnedBytes) {
-        if self.capacity.exceeds_capacity(bytes.len()) {
+        let now = Instant::now();
+        self.put_item(key, StoredItem::new(bytes, now), now);
+    }
+
+    /// Attempt to put the given amount of data in the cache, unless the key is already present,
+    /// in which case the existing entry is left untouched.
+    ///
+    /// Returns whether the data was inserted.
+    fn put_if_absent(&mut self, key: K, bytes: OwnedBytes) -> bool {
+        if self.lru_cache.contains(&key) {
+            return false;
+        }
+        let now = Instant::now();
+        self.put_item(key, StoredItem::new(bytes, now), now)
+    }
+
+    /// Attempt to put the given compressed data in the cache. The capacity is accounted for
+    /// using the compressed size of the data.
+    fn put_compressed(&mut self, key: K, bytes: CompressedBytes) {
+        let now = Instant::now();
+        self.put_item(key, StoredItem::new_compressed(bytes, now), now);
+    }
+
+    /// Updates the capacity of the cache, evicting the least recently used items until the
+    /// cache fits in its new capacity. Unlike regular evictions, recently accessed items are
+    /// evicted too.
+    fn set_capacity(&mut self, capacity: Capacity) {
+        self.capacity = capacity;
+
+        while self.capacity.exceeds_capacity(self.num_bytes as usize) {
+            let Some((_, item)) = self.lru_cache.pop_lru() else {
+                break;
+            };
+            self.drop_item(item.len() as u64);
+        }
+    }
+
+    /// Returns whether the item was inserted.
+    fn put_item(&mut self, key: K, item: StoredItem, now: Instant) -> bool {
+        let num_bytes = item.len();
+
+        if self.capacity.exceeds_capacity(num_bytes) {
             // The value does not fit in the cache. We simply don't store it.
             if self.capacity != Capacity::InBytes(0) {
                 warn!(
                     capacity_in_bytes = ?self.capacity,
-                    len = bytes.len(),
+                    len = num_bytes,
                     "Downloaded a byte slice larger than the cache capacity."
                 );
             }
-            return;
+            return false;
         }
         if let Some(previous_data) = self.lru_cache.pop(&key) {
             self.drop_item(previous_data.len() as u64);
         }
 
-        let now = Instant::now();
         while self
             .capacity
-            .exceeds_capacity(self.num_bytes as usize + bytes.len())
+            .exceeds_capacity(self.num_bytes as usize + num_bytes)
         {
             if let Some((_, candidate_for_eviction)) = self.lru_cache.peek_lru() {
                 let time_since_last_access =
                     now.duration_since(candidate_for_eviction.last_access_time());
-                if time_since_last_access < MIN_TIME_SINCE_LAST_ACCESS {
+                if time_since_last_access < self.no_evict_duration {
                     // It is not worth doing an eviction.
                     // TODO: It is sub-optimal that we might have needlessly evicted items in this
                     // loop before just returning.
-                    return;
+                    return false;
                 }
             }
             if let Some((_, bytes)) = self.lru_cache.pop_lru() {
@@ -170,43 +223,142 @@ impl<K: Hash + Eq> NeedMutMemorySizedCache<K> {
                      capacity is insufficient. This case is guarded against and should never \
                      happen."
                 );
-                return;
+                return false;
             }
         }
-        self.record_item(bytes.len() as u64);
-        self.lru_cache.put(key, StoredItem::new(bytes, now));
+        self.record_item(num_bytes as u64);
+        self.lru_cache.put(key, item);
+        true
+    }
+}
+
+/// Keys being loaded by [`MemorySizedCache::get_or_insert_async`], associated with the channel
+/// used to share the result of the load with the concurrent callers.
+type InFlightLoads<K> = Mutex<HashMap<K, broadcast::Sender<StorageResult<OwnedBytes>>>>;
+
+/// Removes the in-flight entry of a key once its load completes, or when the load is cancelled.
+struct InFlightLoad<'a, K: Hash + Eq> {
+    in_flight_loads: &'a InFlightLoads<K>,
+    key_opt: Option<&'a K>,
+}
+
+impl<K: Hash + Eq> InFlightLoad<'_, K> {
+    /// Removes the in-flight entry and forwards the result to the callers waiting for it.
+    fn complete(mut self, result: &StorageResult<OwnedBytes>) {
+        let Some(key) = self.key_opt.take() else {
+            return;
+        };
+        let sender_opt = self.in_flight_loads.lock().unwrap().remove(key);
+
+        if let Some(sender) = sender_opt {
+            // Sending fails if nobody is waiting, which is fine.
+            let _ = sender.send(result.clone());
+        }
+    }
+}
+
+impl<K: Hash + Eq> Drop for InFlightLoad<'_, K> {
+    fn drop(&mut self) {
+        // Dropping the sender notifies the waiting callers that the load was cancelled.
+        if let Some(key) = self.key_opt.take() {
+            self.in_flight_loads.lock().unwrap().remove(key);
+        }
     }
 }
 
 /// A simple in-resident memory slice cache.
 pub struct MemorySizedCache<K: Hash + Eq = SliceAddress> {
     inner: Mutex<NeedMutMemorySizedCache<K>>,
+    in_flight_loads: InFlightLoads<K>,
 }
 
 impl<K: Hash + Eq> MemorySizedCache<K> {
     /// Creates an slice cache with the given capacity.
+    ///
+    /// Items accessed in the last 60s are not evicted.
     pub fn with_capacity_in_bytes(
         capacity_in_bytes: usize,
         cache_counters: &'static CacheMetrics,
+    ) -> Self {
+        Self::with_capacity_in_bytes_and_no_evict_duration(
+            capacity_in_bytes,
+            DEFAULT_NO_EVICT_DURATION,
+            cache_counters,
+        )
+    }
+
+    /// Creates an slice cache with the given capacity, which does not evict items accessed more
+    /// recently than `no_evict_duration`.
+    ///
+    /// `Duration::ZERO` makes the cache a regular LRU cache, while `Duration::MAX` prevents any
+    /// eviction: new items are dismissed once the cache is full.
+    pub fn with_capacity_in_bytes_and_no_evict_duration(
+        capacity_in_bytes: usize,
+        no_evict_duration: Duration,
+        cache_counters: &'static CacheMetrics,
     ) -> Self {
         MemorySizedCache {
             inner: Mutex::new(NeedMutMemorySizedCache::with_capacity(
                 Capacity::InBytes(capacity_in_bytes),
+                no_evict_duration,
                 cache_counters,
             )),
+            in_flight_loads: Default::default(),
         }
     }
 
     /// Creates a slice cache that never removes any entry.
     pub fn with_infinite_capacity(cache_counters: &'static CacheMetrics) -> Self {
+        Self::with_infinite_capacity_and_no_evict_duration(
+            DEFAULT_NO_EVICT_DURATION,
+            cache_counters,
+        )
+    }
+
+    /// Creates a slice cache that never removes any entry until it is given a capacity with
+    /// [`MemorySizedCache::set_capacity_in_bytes`]. From then on, items accessed more recently than
+    /// `no_evict_duration` are not evicted.
+    pub fn with_infinite_capacity_and_no_evict_duration(
+        no_evict_duration: Duration,
+        cache_counters: &'static CacheMetrics,
+    ) -> Self {
         MemorySizedCache {
             inner: Mutex::new(NeedMutMemorySizedCache::with_capacity(
                 Capacity::Unlimited,
+                no_evict_duration,
                 cache_counters,
             )),
+            in_flight_loads: Default::default(),
         }
     }
 
+    /// Updates the capacity of the cache, evicting the least recently used items if the cache
+    /// no longer fits.
+    pub fn set_capacity_in_bytes(&self, capacity_in_bytes: usize) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_capacity(Capacity::InBytes(capacity_in_bytes));
+    }
+
+    /// Returns the capacity of the cache, or `None` if the capacity is unlimited.
+    pub fn capacity_in_bytes(&self) -> Option<usize> {
+        match self.inner.lock().unwrap().capacity {
+            Capacity::Unlimited => None,
+            Capacity::InBytes(capacity_in_bytes) => Some(capacity_in_bytes),
+        }
+    }
+
+    /// Returns the duration during which an accessed item cannot be evicted.
+    pub fn no_evict_duration(&self) -> Duration {
+        self.inner.lock().unwrap().no_evict_duration
+    }
+
+    /// Returns the number of bytes currently held by the cache.
+    pub fn num_bytes(&self) -> u64 {
+        self.inner.lock().unwrap().num_bytes
+    }
+
     /// If available, returns the cached view of the slice.
     pub fn get<Q>(&self, cache_key: &Q) -> Option<OwnedBytes>
     where
@@ -222,6 +374,78 @@ impl<K: Hash + Eq> MemorySizedCache<K> {
     pub fn put(&self, val: K, bytes: OwnedBytes) {
         self.inner.lock().unwrap().put(val, bytes);
     }
+
+    /// Attempt to put the given amount of data in the cache, unless the key is already present.
+    ///
+    /// Unlike [`MemorySizedCache::put`], an existing entry is kept as is, which avoids a
+    /// spurious eviction and re-insertion when several tasks populate the same key concurrently.
+    /// Returns whether the data was inserted.
+    pub fn put_if_absent(&self, val: K, bytes: OwnedBytes) -> bool {
+        self.inner.lock().unwrap().put_if_absent(val, bytes)
+    }
+
+    /// Returns the cached value of `key` or, on a cache miss, calls `loader` to fetch it and puts
+    /// it in the cache.
+    ///
+    /// Concurrent calls missing the same key are deduplicated: only one of them calls its
+    /// `loader`, and the others wait for its result. Errors are forwarded to all the waiting
+    /// callers, but they are not cached.
+    pub async fn get_or_insert_async<F, Fut>(&self, key: K, loader: F) -> StorageResult<OwnedBytes>
+    where
+        K: Clone,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = StorageResult<OwnedBytes>>,
+    {
+        let mut loader_opt = Some(loader);
+        loop {
+            let receiver_opt = {
+                let mut in_flight_loads = self.in_flight_loads.lock().unwrap();
+
+                // The lookup is performed while holding the lock so that a load completing
+                // concurrently is either visible in the cache or still registered as in flight.
+                if let Some(bytes) = self.get(&key) {
+                    return Ok(bytes);
+                }
+                if let Some(sender) = in_flight_loads.get(&key) {
+                    Some(sender.subscribe())
+                } else {
+                    let (sender, _receiver) = broadcast::channel(1);
+                    in_flight_loads.insert(key.clone(), sender);
+                    None
+                }
+            };
+            if let Some(mut receiver) = receiver_opt {
+                match receiver.recv().await {
+                    Ok(result) => return result,
+                    // The load was cancelled before completing, so we try again.
+                    Err(_) => continue,
+                }
+            }
+            let in_flight_load = InFlightLoad {
+                in_flight_loads: &self.in_flight_loads,
+                key_opt: Some(&key),
+            };
+            let loader = loader_opt
+                .take()
+                .expect("the loader should be called at most once");
+            let result = loader().await;
+
+            if let Ok(bytes) = &result {
+                self.put(key.clone(), bytes.clone());
+            }
+            in_flight_load.complete(&result);
+            return result;
+        }
+    }
+
+    /// Attempt to put the given compressed data in the cache.
+    ///
+    /// The data is accounted for using its compressed size and is transparently decompressed
+    /// the first time it is accessed via [`MemorySizedCache::get`].
+    /// This may fail silently if the compressed data is larger than the cache capacity.
+    pub fn put_compressed(&self, val: K, bytes: CompressedBytes) {
+        self.inner.lock().unwrap().put_compressed(val, bytes);
+    }
 }
 
 impl MemorySizedCache<SliceAddress> {
@@ -243,8 +467,14 @@ impl MemorySizedCache<SliceAddress> {
 #[cfg(test)]
 mod tests {
 
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tokio::sync::Notify;
+
     use super::*;
     use crate::metrics::CACHE_METRICS_FOR_TESTS;
+    use crate::{CompressionCodec, StorageErrorKind};
 
     #[tokio::test]
     async fn test_cache_edge_condition() {
@@ -268,7 +498,7 @@ mod tests {
             // Eviction should not happen, because all items in cache are too young.
             assert!(cache.get(&"5".to_string()).is_none());
         }
-        tokio::time::advance(super::MIN_TIME_SINCE_LAST_ACCESS.mul_f32(1.1f32)).await;
+        tokio::time::advance(super::DEFAULT_NO_EVICT_DURATION.mul_f32(1.1f32)).await;
         {
             let data = OwnedBytes::new(&b"fghij"[..]);
             cache.put("5".to_string(), data);
@@ -277,7 +507,7 @@ mod tests {
             assert!(cache.get(&"2".to_string()).is_none());
             assert!(cache.get(&"3".to_string()).is_none());
         }
-        tokio::time::advance(super::MIN_TIME_SINCE_LAST_ACCESS.mul_f32(1.1f32)).await;
+        tokio::time::advance(super::DEFAULT_NO_EVICT_DURATION.mul_f32(1.1f32)).await;
         {
             let data = OwnedBytes::new(&b"klmnop"[..]);
             cache.put("6".to_string(), data);
@@ -288,6 +518,98 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_cache_set_capacity_in_bytes() {
+        tokio::time::pause();
+        let cache =
+            MemorySizedCache::<String>::with_capacity_in_bytes(10, &CACHE_METRICS_FOR_TESTS);
+        cache.put("1".to_string(), OwnedBytes::new(&b"abc"[..]));
+        cache.put("2".to_string(), OwnedBytes::new(&b"def"[..]));
+        cache.put("3".to_string(), OwnedBytes::new(&b"ghi"[..]));
+        assert_eq!(cache.num_bytes(), 9);
+
+        // Shrinking the capacity evicts the least recently used items, even the fresh ones.
+        cache.set_capacity_in_bytes(4);
+        assert_eq!(cache.capacity_in_bytes(), Some(4));
+        assert_eq!(cache.num_bytes(), 3);
+        assert!(cache.get(&"1".to_string()).is_none());
+        assert!(cache.get(&"2".to_string()).is_none());
+        assert_eq!(cache.get(&"3".to_string()).unwrap(), &b"ghi"[..]);
+
+        cache.set_capacity_in_bytes(10);
+        cache.put("4".to_string(), OwnedBytes::new(&b"jkl"[..]));
+        assert_eq!(cache.num_bytes(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_cache_zero_no_evict_duration() {
+        tokio::time::pause();
+        let cache = MemorySizedCache::<String>::with_capacity_in_bytes_and_no_evict_duration(
+            5,
+            Duration::ZERO,
+            &CACHE_METRICS_FOR_TESTS,
+        );
+        assert_eq!(cache.no_evict_duration(), Duration::ZERO);
+        cache.put("3".to_string(), OwnedBytes::new(&b"abc"[..]));
+        cache.put("2".to_string(), OwnedBytes::new(&b"de"[..]));
+
+        // Fresh items are evicted right away.
+        cache.put("4".to_string(), OwnedBytes::new(&b"fghi"[..]));
+        assert_eq!(cache.get(&"4".to_string()).unwrap(), &b"fghi"[..]);
+        assert!(cache.get(&"3".to_string()).is_none());
+        assert!(cache.get(&"2".to_string()).is_none());
+        assert_eq!(cache.num_bytes(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_cache_max_no_evict_duration() {
+        tokio::time::pause();
+        let cache = MemorySizedCache::<String>::with_capacity_in_bytes_and_no_evict_duration(
+            5,
+            Duration::MAX,
+            &CACHE_METRICS_FOR_TESTS,
+        );
+        cache.put("3".to_string(), OwnedBytes::new(&b"abc"[..]));
+        cache.put("2".to_string(), OwnedBytes::new(&b"de"[..]));
+
+        tokio::time::advance(DEFAULT_NO_EVICT_DURATION.mul_f32(10f32)).await;
+
+        // Items are never evicted, so the new item is dismissed.
+        cache.put("4".to_string(), OwnedBytes::new(&b"fghi"[..]));
+        assert!(cache.get(&"4".to_string()).is_none());
+        assert_eq!(cache.get(&"3".to_string()).unwrap(), &b"abc"[..]);
+        assert_eq!(cache.get(&"2".to_string()).unwrap(), &b"de"[..]);
+        assert_eq!(cache.num_bytes(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_cache_infinite_capacity_no_evict_duration() {
+        tokio::time::pause();
+        let cache = MemorySizedCache::<String>::with_infinite_capacity_and_no_evict_duration(
+            Duration::ZERO,
+            &CACHE_METRICS_FOR_TESTS,
+        );
+        assert_eq!(cache.capacity_in_bytes(), None);
+        assert_eq!(cache.no_evict_duration(), Duration::ZERO);
+        cache.put("3".to_string(), OwnedBytes::new(&b"abc"[..]));
+        cache.put("2".to_string(), OwnedBytes::new(&b"de"[..]));
+
+        // Once the cache is bounded, fresh items are evicted right away.
+        cache.set_capacity_in_bytes(5);
+        cache.put("4".to_string(), OwnedBytes::new(&b"fghi"[..]));
+        assert_eq!(cache.get(&"4".to_string()).unwrap(), &b"fghi"[..]);
+        assert_eq!(cache.num_bytes(), 4);
+    }
+
+    #[test]
+    fn test_cache_default_no_evict_duration() {
+        let cache = MemorySizedCache::<String>::with_capacity_in_bytes(5, &CACHE_METRICS_FOR_TESTS);
+        assert_eq!(cache.no_evict_duration(), DEFAULT_NO_EVICT_DURATION);
+
+        let cache = MemorySizedCache::<String>::with_infinite_capacity(&CACHE_METRICS_FOR_TESTS);
+        assert_eq!(cache.no_evict_duration(), DEFAULT_NO_EVICT_DURATION);
+    }
+
     #[test]
     fn test_cache_edge_unlimited_capacity() {
         let cache = MemorySizedCache::with_infinite_capacity(&CACHE_METRICS_FOR_TESTS);
@@ -312,4 +634,211 @@ mod tests {
         cache.put("hello.seg", data);
         assert_eq!(cache.get(&"hello.seg").unwrap(), &b"werwer"[..]);
     }
+
+    #[test]
+    fn test_cache_put_if_absent() {
+        let cache = MemorySizedCache::with_capacity_in_bytes(10_000, &CACHE_METRICS_FOR_TESTS);
+        assert!(cache.put_if_absent("hello.seg", OwnedBytes::new(&b"werwer"[..])));
+        assert_eq!(cache.get(&"hello.seg").unwrap(), &b"werwer"[..]);
+
+        // The original value survives.
+        assert!(!cache.put_if_absent("hello.seg", OwnedBytes::new(&b"foobarbaz"[..])));
+        assert_eq!(cache.get(&"hello.seg").unwrap(), &b"werwer"[..]);
+        assert_eq!(cache.num_bytes(), 6);
+
+        // `put` still replaces the existing value.
+        cache.put("hello.seg", OwnedBytes::new(&b"foobarbaz"[..]));
+        assert_eq!(cache.get(&"hello.seg").unwrap(), &b"foobarbaz"[..]);
+        assert_eq!(cache.num_bytes(), 9);
+    }
+
+    #[test]
+    fn test_cache_put_if_absent_too_large() {
+        let cache = MemorySizedCache::with_capacity_in_bytes(5, &CACHE_METRICS_FOR_TESTS);
+        assert!(!cache.put_if_absent("hello.seg", OwnedBytes::new(&b"werwer"[..])));
+        assert!(cache.get(&"hello.seg").is_none());
+    }
+
+    #[test]
+    fn test_cache_compressed() {
+        let cache = MemorySizedCache::with_capacity_in_bytes(1_000, &CACHE_METRICS_FOR_TESTS);
+        let payload = b"werwer".repeat(1_000);
+
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed_bytes = CompressedBytes::compress(&payload, codec).unwrap();
+            let compressed_len = compressed_bytes.compressed_len();
+            assert!(compressed_len < 1_000);
+
+            // The uncompressed payload would not fit in the cache.
+            cache.put_compressed("hello.seg", compressed_bytes);
+            assert_eq!(cache.inner.lock().unwrap().num_bytes, compressed_len as u64);
+            assert_eq!(cache.get(&"hello.seg").unwrap(), &payload[..]);
+            assert_eq!(cache.get(&"hello.seg").unwrap(), &payload[..]);
+            assert_eq!(cache.inner.lock().unwrap().num_bytes, compressed_len as u64);
+        }
+    }
+
+    async fn load_when_released(
+        cache: Arc<MemorySizedCache<String>>,
+        release_load: Arc<Notify>,
+        num_loads: Arc<AtomicUsize>,
+        result: StorageResult<OwnedBytes>,
+    ) -> StorageResult<OwnedBytes> {
+        cache
+            .get_or_insert_async("hello.seg".to_string(), || async move {
+                num_loads.fetch_add(1, Ordering::Relaxed);
+                release_load.notified().await;
+                result
+            })
+            .await
+    }
+
+    /// Waits until `num_waiters` callers are waiting on the in-flight load of `hello.seg`.
+    async fn wait_for_in_flight_load_waiters(cache: &MemorySizedCache<String>, num_waiters: usize) {
+        loop {
+            let num_receivers_opt = cache
+                .in_flight_loads
+                .lock()
+                .unwrap()
+                .get("hello.seg")
+                .map(broadcast::Sender::receiver_count);
+            if num_receivers_opt == Some(num_waiters) {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_insert_async() {
+        let cache =
+            MemorySizedCache::<String>::with_capacity_in_bytes(1_000, &CACHE_METRICS_FOR_TESTS);
+        let bytes = cache
+            .get_or_insert_async("hello.seg".to_string(), || async {
+                Ok(OwnedBytes::new(&b"werwer"[..]))
+            })
+            .await
+            .unwrap();
+        assert_eq!(bytes, &b"werwer"[..]);
+        assert_eq!(cache.get(&"hello.seg".to_string()).unwrap(), &b"werwer"[..]);
+
+        // The loader is not called on a cache hit.
+        let bytes = cache
+            .get_or_insert_async(
+                "hello.seg".to_string(),
+                || -> futures::future::Ready<StorageResult<OwnedBytes>> {
+                    panic!("the value should be cached");
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(bytes, &b"werwer"[..]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_insert_async_deduplicates_concurrent_loads() {
+        let cache = Arc::new(MemorySizedCache::<String>::with_capacity_in_bytes(
+            1_000,
+            &CACHE_METRICS_FOR_TESTS,
+        ));
+        let release_load = Arc::new(Notify::new());
+        let num_loads = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                tokio::spawn(load_when_released(
+                    cache.clone(),
+                    release_load.clone(),
+                    num_loads.clone(),
+                    Ok(OwnedBytes::new(&b"werwer"[..])),
+                ))
+            })
+            .collect();
+        // One caller holds the load while the other one waits on it.
+        wait_for_in_flight_load_waiters(&cache, 1).await;
+        release_load.notify_one();
+
+        for handle in handles {
+            let bytes = handle.await.unwrap().unwrap();
+            assert_eq!(bytes, &b"werwer"[..]);
+        }
+        assert_eq!(num_loads.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.num_bytes(), 6);
+        assert!(cache.in_flight_loads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_insert_async_forwards_errors() {
+        let cache = Arc::new(MemorySizedCache::<String>::with_capacity_in_bytes(
+            1_000,
+            &CACHE_METRICS_FOR_TESTS,
+        ));
+        let release_load = Arc::new(Notify::new());
+        let num_loads = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                tokio::spawn(load_when_released(
+                    cache.clone(),
+                    release_load.clone(),
+                    num_loads.clone(),
+                    Err(StorageErrorKind::Io.with_error(anyhow::anyhow!("read failed"))),
+                ))
+            })
+            .collect();
+        // One caller holds the load while the other one waits on it.
+        wait_for_in_flight_load_waiters(&cache, 1).await;
+        release_load.notify_one();
+
+        for handle in handles {
+            let error = handle.await.unwrap().unwrap_err();
+            assert_eq!(error.kind(), StorageErrorKind::Io);
+        }
+        assert_eq!(num_loads.load(Ordering::Relaxed), 1);
+        assert!(cache.in_flight_loads.lock().unwrap().is_empty());
+
+        // The error is not cached.
+        assert!(cache.get(&"hello.seg".to_string()).is_none());
+        let bytes = cache
+            .get_or_insert_async("hello.seg".to_string(), || async {
+                Ok(OwnedBytes::new(&b"werwer"[..]))
+            })
+            .await
+            .unwrap();
+        assert_eq!(bytes, &b"werwer"[..]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_get_or_insert_async_cancelled_load() {
+        let cache = Arc::new(MemorySizedCache::<String>::with_capacity_in_bytes(
+            1_000,
+            &CACHE_METRICS_FOR_TESTS,
+        ));
+        let cache_clone = cache.clone();
+        let cancelled_handle = tokio::spawn(async move {
+            cache_clone
+                .get_or_insert_async("hello.seg".to_string(), || {
+                    futures::future::pending::<StorageResult<OwnedBytes>>()
+                })
+                .await
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(cache.in_flight_loads.lock().unwrap().len(), 1);
+
+        let cache_clone = cache.clone();
+        let waiting_handle = tokio::spawn(async move {
+            cache_clone
+                .get_or_insert_async("hello.seg".to_string(), || async {
+                    Ok(OwnedBytes::new(&b"werwer"[..]))
+                })
+                .await
+        });
+        tokio::task::yield_now().await;
+        cancelled_handle.abort();
+
+        // The waiting caller takes over the load.
+        let bytes = waiting_handle.await.unwrap().unwrap();
+        assert_eq!(bytes, &b"werwer"[..]);
+        assert!(cache.in_flight_loads.lock().unwrap().is_empty());
+    }
 }
@@ -47,7 +47,7 @@ impl QuickwitCache {
     pub fn new(fast_field_cache_capacity: usize) -> Self {
         let mut quickwit_cache = QuickwitCache::empty();
         let fast_field_cache_counters: &'static CacheMetrics =
-            &crate::STORAGE_METRICS.fast_field_cache;
+            &crate::CACHE_METRICS.fast_field_cache;
         quickwit_cache.add_route(
             ".fast",
             Arc::new(SimpleCache::with_capacity_in_bytes(
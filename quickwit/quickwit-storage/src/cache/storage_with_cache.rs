@@ -21,14 +21,19 @@ use std::fmt;
 use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use quickwit_common::uri::Uri;
+use reqwest::Url;
 use tokio::io::AsyncRead;
 
 use crate::cache::StorageCache;
 use crate::storage::SendableAsync;
-use crate::{BulkDeleteError, OwnedBytes, Storage, StorageResult};
+use crate::{
+    BulkDeleteError, ObjectMetadata, ObjectMetadataStream, OwnedBytes, Storage, StorageResult,
+    StorageStats,
+};
 
 /// Use with care, StorageWithCache is read-only.
 pub struct StorageWithCache {
@@ -56,6 +61,10 @@ impl Storage for StorageWithCache {
         unimplemented!("StorageWithCache is readonly. Failed to put {:?}", path)
     }
 
+    async fn copy(&self, _src: &Path, dst: &Path) -> crate::StorageResult<()> {
+        unimplemented!("StorageWithCache is readonly. Failed to copy to {:?}", dst)
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         self.storage.copy_to(path, output).await
     }
@@ -109,9 +118,25 @@ impl Storage for StorageWithCache {
         self.storage.file_num_bytes(path).await
     }
 
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        self.storage.head(path).await
+    }
+
+    async fn presign_download_url(&self, path: &Path, expiry: Duration) -> StorageResult<Url> {
+        self.storage.presign_download_url(path, expiry).await
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        self.storage.list_objects(prefix).await
+    }
+
     fn uri(&self) -> &Uri {
         self.storage.uri()
     }
+
+    fn stats(&self) -> StorageStats {
+        self.storage.stats()
+    }
 }
 
 #[cfg(test)]
@@ -20,18 +20,32 @@
 use tantivy::directory::OwnedBytes;
 use tokio::time::Instant;
 
+use crate::CompressedBytes;
+
+enum Payload {
+    Raw(OwnedBytes),
+    Compressed(CompressedBytes),
+}
+
 /// It is a bit overkill to put this in its own module, but I
 /// wanted to ensure that no one would access payload without updating `last_access_time`.
 pub(super) struct StoredItem {
     last_access_time: Instant,
-    payload: OwnedBytes,
+    payload: Payload,
 }
 
 impl StoredItem {
     pub fn new(payload: OwnedBytes, now: Instant) -> Self {
         StoredItem {
             last_access_time: now,
-            payload,
+            payload: Payload::Raw(payload),
+        }
+    }
+
+    pub fn new_compressed(payload: CompressedBytes, now: Instant) -> Self {
+        StoredItem {
+            last_access_time: now,
+            payload: Payload::Compressed(payload),
         }
     }
 }
@@ -39,11 +53,19 @@ impl StoredItem {
 impl StoredItem {
     pub fn payload(&mut self) -> OwnedBytes {
         self.last_access_time = Instant::now();
-        self.payload.clone()
+        match &self.payload {
+            Payload::Raw(payload) => payload.clone(),
+            Payload::Compressed(payload) => payload.decompress().clone(),
+        }
     }
 
+    /// Returns the number of bytes accounted for in the cache, i.e. the compressed size for
+    /// compressed items whether or not they have already been decompressed.
     pub fn len(&self) -> usize {
-        self.payload.len()
+        match &self.payload {
+            Payload::Raw(payload) => payload.len(),
+            Payload::Compressed(payload) => payload.compressed_len(),
+        }
     }
 
     pub fn last_access_time(&self) -> Instant {
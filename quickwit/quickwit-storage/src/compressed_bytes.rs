@@ -0,0 +1,161 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::{fmt, io};
+
+use once_cell::sync::OnceCell;
+use tantivy::directory::OwnedBytes;
+
+/// ZSTD level used when compressing bytes. Favors compression speed over compression ratio.
+const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
+/// Codec used to compress a [`CompressedBytes`] payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionCodec {
+    /// LZ4 offers faster decompression, for latency-sensitive workloads.
+    Lz4,
+    /// ZSTD offers a better compression ratio.
+    Zstd,
+}
+
+/// Compressed bytes that are transparently decompressed on first access.
+///
+/// The decompressed bytes are kept once computed, so subsequent accesses are free.
+pub struct CompressedBytes {
+    compressed: OwnedBytes,
+    codec: CompressionCodec,
+    decompressed: OnceCell<OwnedBytes>,
+}
+
+impl fmt::Debug for CompressedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CompressedBytes")
+            .field("codec", &self.codec)
+            .field("compressed_len", &self.compressed.len())
+            .field("is_decompressed", &self.decompressed.get().is_some())
+            .finish()
+    }
+}
+
+impl CompressedBytes {
+    /// Creates a new `CompressedBytes` from bytes compressed with `codec`.
+    pub fn new(compressed: OwnedBytes, codec: CompressionCodec) -> Self {
+        Self {
+            compressed,
+            codec,
+            decompressed: OnceCell::new(),
+        }
+    }
+
+    /// Compresses `bytes` with `codec`.
+    pub fn compress(bytes: &[u8], codec: CompressionCodec) -> io::Result<Self> {
+        let compressed = match codec {
+            CompressionCodec::Lz4 => lz4_flex::compress_prepend_size(bytes),
+            CompressionCodec::Zstd => zstd::bulk::compress(bytes, ZSTD_COMPRESSION_LEVEL)?,
+        };
+        let compressed_bytes = Self {
+            compressed: OwnedBytes::new(compressed),
+            codec,
+            // We already have the decompressed bytes at hand, but we do not keep them: the whole
+            // point is to save memory until the bytes are actually accessed.
+            decompressed: OnceCell::new(),
+        };
+        Ok(compressed_bytes)
+    }
+
+    /// Returns the codec used to compress the bytes.
+    pub fn codec(&self) -> CompressionCodec {
+        self.codec
+    }
+
+    /// Returns the compressed bytes.
+    pub fn compressed(&self) -> &OwnedBytes {
+        &self.compressed
+    }
+
+    /// Returns the number of compressed bytes.
+    pub fn compressed_len(&self) -> usize {
+        self.compressed.len()
+    }
+
+    /// Returns the decompressed bytes, decompressing them on first access.
+    pub fn try_decompress(&self) -> io::Result<&OwnedBytes> {
+        self.decompressed.get_or_try_init(|| {
+            let decompressed = match self.codec {
+                CompressionCodec::Lz4 => lz4_flex::decompress_size_prepended(&self.compressed)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?,
+                CompressionCodec::Zstd => zstd::stream::decode_all(self.compressed.as_slice())?,
+            };
+            Ok(OwnedBytes::new(decompressed))
+        })
+    }
+
+    /// Returns the decompressed bytes, decompressing them on first access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes are corrupted. Use [`CompressedBytes::try_decompress`] for bytes that
+    /// do not originate from [`CompressedBytes::compress`].
+    pub fn decompress(&self) -> &OwnedBytes {
+        self.try_decompress()
+            .expect("compressed bytes should not be corrupted")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_bytes_roundtrip() {
+        let payload = b"quickwit quickwit quickwit quickwit quickwit".repeat(100);
+
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed_bytes = CompressedBytes::compress(&payload, codec).unwrap();
+            assert_eq!(compressed_bytes.codec(), codec);
+            assert!(compressed_bytes.compressed_len() < payload.len());
+
+            let decompressed = compressed_bytes.decompress();
+            assert_eq!(decompressed.as_slice(), &payload[..]);
+
+            // The decompressed bytes are only computed once.
+            let decompressed_ptr = decompressed.as_slice().as_ptr();
+            assert_eq!(
+                compressed_bytes.decompress().as_slice().as_ptr(),
+                decompressed_ptr
+            );
+        }
+    }
+
+    #[test]
+    fn test_compressed_bytes_empty() {
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed_bytes = CompressedBytes::compress(b"", codec).unwrap();
+            assert!(compressed_bytes.decompress().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_compressed_bytes_corrupted() {
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed_bytes = CompressedBytes::new(OwnedBytes::new(b"qw".to_vec()), codec);
+            compressed_bytes.try_decompress().unwrap_err();
+        }
+    }
+}
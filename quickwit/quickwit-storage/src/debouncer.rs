@@ -22,17 +22,21 @@ use std::hash::Hash;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use fnv::FnvHashMap;
 use futures::future::{BoxFuture, WeakShared};
 use futures::{Future, FutureExt};
 use quickwit_common::uri::Uri;
+use reqwest::Url;
 use tantivy::directory::OwnedBytes;
 use tokio::io::AsyncRead;
 
 use crate::storage::SendableAsync;
-use crate::{BulkDeleteError, Storage, StorageResult};
+use crate::{
+    BulkDeleteError, ObjectMetadata, ObjectMetadataStream, Storage, StorageResult, StorageStats,
+};
 
 /// The AsyncDebouncer debounces inflight Futures, so that concurrent async request to the same data
 /// source can be deduplicated.
@@ -150,6 +154,10 @@ impl<T: Storage> Storage for DebouncedStorage<T> {
         self.underlying.put(path, payload).await
     }
 
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        self.underlying.copy(src, dst).await
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         self.underlying.copy_to(path, output).await
     }
@@ -196,9 +204,25 @@ impl<T: Storage> Storage for DebouncedStorage<T> {
         self.underlying.uri()
     }
 
+    fn stats(&self) -> StorageStats {
+        self.underlying.stats()
+    }
+
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
         self.underlying.file_num_bytes(path).await
     }
+
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        self.underlying.head(path).await
+    }
+
+    async fn presign_download_url(&self, path: &Path, expiry: Duration) -> StorageResult<Url> {
+        self.underlying.presign_download_url(path, expiry).await
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        self.underlying.list_objects(prefix).await
+    }
 }
 
 #[cfg(test)]
@@ -33,6 +33,11 @@ pub enum StorageErrorKind {
     NotFound,
     /// The request credentials do not allow for this operation.
     Unauthorized,
+    /// The storage backend denies this operation, typically because the IAM policy or the access
+    /// control list attached to the credentials does not grant the required permissions.
+    PermissionDenied,
+    /// The storage quota of the bucket or the account is exhausted.
+    QuotaExceeded,
     /// A third-party service forbids this operation, or is misconfigured.
     Service,
     /// Any generic internal error.
@@ -41,6 +46,8 @@ pub enum StorageErrorKind {
     Timeout,
     /// Io error.
     Io,
+    /// The storage backend does not support this operation.
+    Unsupported,
 }
 
 /// Generic Storage Resolver Error.
@@ -73,9 +80,31 @@ pub enum StorageResolverError {
 impl StorageErrorKind {
     /// Creates a StorageError.
     pub fn with_error(self, source: impl Into<anyhow::Error>) -> StorageError {
+        let mut source: anyhow::Error = source.into();
+
+        if let Some(hint) = self.troubleshooting_hint() {
+            let message = format!("{source}: {hint}");
+            source = source.context(message);
+        }
         StorageError {
             kind: self,
-            source: Arc::new(source.into()),
+            source: Arc::new(source),
+        }
+    }
+
+    /// Returns a hint helping operators fix the errors of this kind, which is appended to their
+    /// messages.
+    fn troubleshooting_hint(&self) -> Option<&'static str> {
+        match self {
+            StorageErrorKind::PermissionDenied => Some(
+                "check that the IAM policy attached to the storage credentials grants the \
+                 permissions required by Quickwit on the bucket (for Amazon S3, see \
+                 https://quickwit.io/docs/guides/aws-setup)",
+            ),
+            StorageErrorKind::QuotaExceeded => Some(
+                "free up some space or raise the storage quota of the bucket before retrying",
+            ),
+            _ => None,
         }
     }
 }
@@ -84,6 +113,7 @@ impl From<StorageError> for io::Error {
     fn from(storage_err: StorageError) -> Self {
         let io_error_kind = match storage_err.kind() {
             StorageErrorKind::NotFound => io::ErrorKind::NotFound,
+            StorageErrorKind::PermissionDenied => io::ErrorKind::PermissionDenied,
             _ => io::ErrorKind::Other,
         };
         // TODO: This is swallowing the context of the source error.
@@ -124,6 +154,7 @@ impl From<io::Error> for StorageError {
     fn from(err: io::Error) -> StorageError {
         match err.kind() {
             io::ErrorKind::NotFound => StorageErrorKind::NotFound.with_error(err),
+            io::ErrorKind::PermissionDenied => StorageErrorKind::PermissionDenied.with_error(err),
             _ => StorageErrorKind::Io.with_error(err),
         }
     }
@@ -201,3 +232,41 @@ impl fmt::Display for BulkDeleteError {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_error_troubleshooting_hint() {
+        let storage_error = StorageErrorKind::PermissionDenied.with_error(anyhow::anyhow!(
+            "access denied on `s3://quickwit-indexes/hdfs-logs/split.split`"
+        ));
+        let storage_error_message = storage_error.to_string();
+        assert!(storage_error_message
+            .contains("access denied on `s3://quickwit-indexes/hdfs-logs/split.split`: check"));
+        assert!(storage_error_message.contains("https://quickwit.io/docs/guides/aws-setup"));
+
+        let storage_error =
+            StorageErrorKind::QuotaExceeded.with_error(anyhow::anyhow!("bucket quota exceeded"));
+        assert!(storage_error
+            .to_string()
+            .contains("bucket quota exceeded: free up some space"));
+
+        let storage_error = StorageErrorKind::Io.with_error(anyhow::anyhow!("disk full"));
+        assert_eq!(
+            storage_error.to_string(),
+            "storage error(kind=Io, source=disk full)"
+        );
+    }
+
+    #[test]
+    fn test_storage_error_from_io_error() {
+        let io_error = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
+        let storage_error = StorageError::from(io_error);
+        assert_eq!(storage_error.kind(), StorageErrorKind::PermissionDenied);
+
+        let io_error = io::Error::from(storage_error);
+        assert_eq!(io_error.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
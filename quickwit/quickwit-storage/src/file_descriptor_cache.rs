@@ -47,8 +47,7 @@ struct SplitFileInner {
 }
 
 fn get_split_file_path(root_path: &Path, split_id: Ulid) -> PathBuf {
-    let split_filename = quickwit_common::split_file(split_id);
-    root_path.join(split_filename)
+    root_path.join(quickwit_common::split_file_path(split_id))
 }
 
 impl FileDescriptorCache {
@@ -93,7 +92,7 @@ impl FileDescriptorCache {
         Self::new(
             NonZeroU32::new(max_fd_limit).unwrap(),
             fd_cache_capacity,
-            crate::STORAGE_METRICS.fd_cache_metrics.clone(),
+            crate::CACHE_METRICS.fd_cache_metrics.clone(),
         )
     }
 
@@ -31,6 +31,7 @@
 //!
 //! - The `BundleStorage` bundles together multiple files into a single file.
 mod cache;
+mod compressed_bytes;
 mod debouncer;
 mod file_descriptor_cache;
 mod metrics;
@@ -38,9 +39,9 @@ mod storage;
 pub use debouncer::AsyncDebouncer;
 pub(crate) use debouncer::DebouncedStorage;
 
-pub use self::metrics::STORAGE_METRICS;
+pub use self::metrics::{CACHE_METRICS, STORAGE_METRICS};
 pub use self::payload::PutPayload;
-pub use self::storage::Storage;
+pub use self::storage::{ObjectMetadata, ObjectMetadataStream, SendableAsync, Storage};
 
 mod bundle_storage;
 mod error;
@@ -49,6 +50,7 @@ mod local_file_storage;
 mod object_storage;
 #[cfg(feature = "gcs")]
 mod opendal_storage;
+mod owned_bytes_ext;
 mod payload;
 mod prefix_storage;
 mod ram_storage;
@@ -56,6 +58,7 @@ mod split;
 mod split_cache;
 mod storage_factory;
 mod storage_resolver;
+mod storage_stats;
 mod versioned_component;
 
 use quickwit_common::uri::Uri;
@@ -67,8 +70,10 @@ pub use self::bundle_storage::{BundleStorage, BundleStorageFileOffsets};
 #[cfg(any(test, feature = "testsuite"))]
 pub use self::cache::MockStorageCache;
 pub use self::cache::{
-    wrap_storage_with_cache, ByteRangeCache, MemorySizedCache, QuickwitCache, StorageCache,
+    wrap_storage_with_cache, AdaptiveCacheCapacityConfig, AdaptiveMemorySizedCache, ByteRangeCache,
+    MemorySizedCache, QuickwitCache, StorageCache,
 };
+pub use self::compressed_bytes::{CompressedBytes, CompressionCodec};
 pub use self::local_file_storage::{LocalFileStorage, LocalFileStorageFactory};
 #[cfg(feature = "azure")]
 pub use self::object_storage::{AzureBlobStorage, AzureBlobStorageFactory};
@@ -79,18 +84,20 @@ pub use self::object_storage::{
 pub use self::opendal_storage::new_emulated_google_cloud_storage;
 #[cfg(feature = "gcs")]
 pub use self::opendal_storage::GoogleCloudStorageFactory;
+pub use self::owned_bytes_ext::{OwnedBytesExt, OwnedBytesReader};
 pub use self::ram_storage::{RamStorage, RamStorageBuilder};
-pub use self::split::{SplitPayload, SplitPayloadBuilder};
+pub use self::split::{FilePayload, SplitPayload, SplitPayloadBuilder};
 #[cfg(any(test, feature = "testsuite"))]
 pub use self::storage::MockStorage;
 #[cfg(any(test, feature = "testsuite"))]
 pub use self::storage_factory::MockStorageFactory;
 pub use self::storage_factory::{StorageFactory, UnsupportedStorage};
 pub use self::storage_resolver::StorageResolver;
+pub use self::storage_stats::StorageStats;
 #[cfg(feature = "integration-testsuite")]
 pub use self::test_suite::{
-    storage_test_multi_part_upload, storage_test_single_part_upload, storage_test_suite,
-    test_write_and_bulk_delete,
+    storage_test_multi_part_upload, storage_test_presign_download_url,
+    storage_test_single_part_upload, storage_test_suite, test_write_and_bulk_delete,
 };
 pub use crate::error::{
     BulkDeleteError, DeleteFailure, StorageError, StorageErrorKind, StorageResolverError,
@@ -209,6 +216,29 @@ pub(crate) mod test_suite {
         Ok(())
     }
 
+    async fn test_write_and_get_many_slices(storage: &mut dyn Storage) -> anyhow::Result<()> {
+        let test_path = Path::new("write_and_read_many_slices");
+        let payload_bytes: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        storage
+            .put(test_path, Box::new(payload_bytes.clone()))
+            .await?;
+        let ranges = [
+            90_000..90_010,
+            3..6,
+            10..10,
+            4..20,
+            50_000..60_000,
+            99_990..100_000,
+        ];
+        let slices = storage.get_many_slices(test_path, &ranges).await?;
+        assert_eq!(slices.len(), ranges.len());
+
+        for (range, slice) in ranges.iter().zip(slices.iter()) {
+            assert_eq!(slice.as_slice(), &payload_bytes[range.clone()]);
+        }
+        Ok(())
+    }
+
     async fn test_write_get_all(storage: &mut dyn Storage) -> anyhow::Result<()> {
         let test_path = Path::new("write_and_read_all");
         storage
@@ -234,6 +264,28 @@ pub(crate) mod test_suite {
         Ok(())
     }
 
+    async fn test_write_and_copy(storage: &mut dyn Storage) -> anyhow::Result<()> {
+        let src_path = Path::new("write_and_copy_src");
+        let dst_path = Path::new("foo/write_and_copy_dst");
+        let payload_bytes = b"abcdefghijklmnopqrstuvwxyz";
+        storage
+            .put(src_path, Box::new(payload_bytes.to_vec()))
+            .await?;
+        storage.copy(src_path, dst_path).await?;
+        assert_eq!(&storage.get_all(dst_path).await?[..], payload_bytes);
+        assert_eq!(&storage.get_all(src_path).await?[..], payload_bytes);
+
+        let err = storage
+            .copy(Path::new("missingfile"), dst_path)
+            .await
+            .map_err(|err| err.kind());
+        assert!(matches!(err, Err(StorageErrorKind::NotFound)));
+
+        storage.delete(src_path).await?;
+        storage.delete(dst_path).await?;
+        Ok(())
+    }
+
     async fn test_write_and_delete(storage: &mut dyn Storage) -> anyhow::Result<()> {
         let test_path = Path::new("write_and_delete");
         let payload_bytes = b"abcdefghijklmnopqrstuvwxyz";
@@ -281,6 +333,25 @@ pub(crate) mod test_suite {
         Ok(())
     }
 
+    async fn test_head(storage: &mut dyn Storage) -> anyhow::Result<()> {
+        let test_path = Path::new("foo/write_for_head");
+        let payload_bytes = b"abcdefghijklmnopqrstuvwxyz";
+        storage
+            .put(test_path, Box::new(payload_bytes.to_vec()))
+            .await?;
+        let object_metadata = storage.head(test_path).await?;
+        assert_eq!(object_metadata.path, test_path);
+        assert_eq!(object_metadata.size_bytes, 26);
+        storage.delete(test_path).await?;
+
+        let err = storage
+            .head(Path::new("missing_file_for_head"))
+            .await
+            .map_err(|err| err.kind());
+        assert!(matches!(err, Err(StorageErrorKind::NotFound)));
+        Ok(())
+    }
+
     async fn test_exists(storage: &mut dyn Storage) -> anyhow::Result<()> {
         let test_path = Path::new("exists");
         assert!(!storage.exists(test_path).await.unwrap());
@@ -332,10 +403,16 @@ pub(crate) mod test_suite {
         test_write_and_get_slice_stream(storage)
             .await
             .context("write_and_get_slice_stream")?;
+        test_write_and_get_many_slices(storage)
+            .await
+            .context("write_and_get_many_slices")?;
         test_write_get_all(storage)
             .await
             .context("write_and_get_all")?;
         test_write_and_cp(storage).await.context("write_and_cp")?;
+        test_write_and_copy(storage)
+            .await
+            .context("write_and_copy")?;
         test_write_and_delete(storage)
             .await
             .context("write_and_delete")?;
@@ -347,6 +424,7 @@ pub(crate) mod test_suite {
             .await
             .context("write_and_delete_with_separator")?;
         test_file_size(storage).await.context("file_size")?;
+        test_head(storage).await.context("head")?;
         test_delete_missing_file(storage)
             .await
             .context("delete_missing_file")?;
@@ -382,6 +460,29 @@ pub(crate) mod test_suite {
         Ok(())
     }
 
+    /// Generic presigned download URL test. Checks that the URL returned by
+    /// `Storage::presign_download_url` can be fetched without credentials.
+    #[cfg(feature = "integration-testsuite")]
+    pub async fn storage_test_presign_download_url(
+        storage: &mut dyn Storage,
+    ) -> anyhow::Result<()> {
+        use std::time::Duration;
+
+        let test_path = Path::new("presigned_download.txt");
+        let data = b"hello, happy presigned tax payer!";
+        storage.put(test_path, Box::new(data.to_vec())).await?;
+
+        let url = storage
+            .presign_download_url(test_path, Duration::from_secs(60))
+            .await?;
+        let response = reqwest::get(url).await?.error_for_status()?;
+        let downloaded_bytes = response.bytes().await?;
+        assert_eq!(downloaded_bytes.as_ref(), data);
+
+        storage.delete(test_path).await?;
+        Ok(())
+    }
+
     /// Generic multi-part upload test.
     #[cfg(feature = "integration-testsuite")]
     pub async fn storage_test_multi_part_upload(storage: &mut dyn Storage) -> anyhow::Result<()> {
@@ -25,8 +25,9 @@ use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::future::{BoxFuture, FutureExt};
-use futures::StreamExt;
+use futures::{stream, StreamExt};
 use quickwit_common::ignore_error_kind;
 use quickwit_common::uri::Uri;
 use quickwit_config::StorageBackend;
@@ -34,11 +35,16 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tracing::warn;
 
 use crate::storage::SendableAsync;
+use crate::storage_stats::{track_operation, StorageOperation};
 use crate::{
-    BulkDeleteError, DebouncedStorage, DeleteFailure, OwnedBytes, Storage, StorageError,
-    StorageErrorKind, StorageFactory, StorageResolverError, StorageResult,
+    BulkDeleteError, DebouncedStorage, DeleteFailure, ObjectMetadata, ObjectMetadataStream,
+    OwnedBytes, Storage, StorageError, StorageErrorKind, StorageFactory, StorageResolverError,
+    StorageResult, StorageStats,
 };
 
+/// Backend label of the local file storage in the storage metrics.
+const BACKEND: &str = "file";
+
 /// File system compatible storage implementation.
 #[derive(Clone)]
 pub struct LocalFileStorage {
@@ -165,6 +171,88 @@ fn delete_all_dirs_if_empty<'a>(
     .boxed()
 }
 
+/// Walks recursively through the directories of a [`LocalFileStorage`] and yields the files
+/// whose path relative to the root of the storage starts with `prefix`.
+struct LocalFileLister {
+    root: PathBuf,
+    prefix: String,
+    pending_dirs: Vec<PathBuf>,
+    read_dir_opt: Option<tokio::fs::ReadDir>,
+}
+
+impl LocalFileLister {
+    fn new(root: PathBuf, prefix: &Path) -> Self {
+        // FIXME: This may not work on Windows.
+        let prefix = prefix.to_string_lossy().to_string();
+        let pending_dirs = vec![root.clone()];
+        Self {
+            root,
+            prefix,
+            pending_dirs,
+            read_dir_opt: None,
+        }
+    }
+
+    /// Returns whether the directory identified by `relative_dir_path` may contain files
+    /// matching the prefix.
+    fn should_visit_dir(&self, relative_dir_path: &str) -> bool {
+        relative_dir_path.starts_with(&self.prefix)
+            || self
+                .prefix
+                .strip_prefix(relative_dir_path)
+                .map(|prefix_suffix| prefix_suffix.starts_with('/'))
+                .unwrap_or(false)
+    }
+
+    async fn next_object(mut self) -> anyhow::Result<Option<(ObjectMetadata, Self)>> {
+        loop {
+            if let Some(read_dir) = &mut self.read_dir_opt {
+                let Some(dir_entry) = read_dir.next_entry().await? else {
+                    self.read_dir_opt = None;
+                    continue;
+                };
+                let entry_path = dir_entry.path();
+                let relative_path = entry_path.strip_prefix(&self.root)?.to_path_buf();
+                let relative_path_str = relative_path.to_string_lossy();
+                let file_type = dir_entry.file_type().await?;
+
+                if file_type.is_dir() {
+                    if self.should_visit_dir(&relative_path_str) {
+                        self.pending_dirs.push(entry_path);
+                    }
+                    continue;
+                }
+                if !file_type.is_file() || !relative_path_str.starts_with(&self.prefix) {
+                    continue;
+                }
+                let metadata = match dir_entry.metadata().await {
+                    Ok(metadata) => metadata,
+                    // The file may have been deleted by a concurrent task.
+                    Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                    Err(error) => return Err(error.into()),
+                };
+                let object_metadata = ObjectMetadata {
+                    path: relative_path,
+                    size_bytes: metadata.len(),
+                    last_modified: DateTime::<Utc>::from(metadata.modified()?),
+                    etag: None,
+                };
+                return Ok(Some((object_metadata, self)));
+            }
+            let Some(dir_path) = self.pending_dirs.pop() else {
+                return Ok(None);
+            };
+            match tokio::fs::read_dir(&dir_path).await {
+                Ok(read_dir) => self.read_dir_opt = Some(read_dir),
+                // The directory may have been deleted by a concurrent task, or the root of the
+                // storage may not exist yet.
+                Err(error) if error.kind() == ErrorKind::NotFound => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl Storage for LocalFileStorage {
     async fn check_connectivity(&self) -> anyhow::Result<()> {
@@ -180,57 +268,98 @@ impl Storage for LocalFileStorage {
         path: &Path,
         payload: Box<dyn crate::PutPayload>,
     ) -> crate::StorageResult<()> {
-        let full_path = self.full_path(path)?;
-        let parent_dir = full_path.parent().ok_or_else(|| {
-            let err = anyhow::anyhow!("no parent directory for {full_path:?}");
+        let num_bytes = payload.len();
+        let put_fut = async {
+            let full_path = self.full_path(path)?;
+            let parent_dir = full_path.parent().ok_or_else(|| {
+                let err = anyhow::anyhow!("no parent directory for {full_path:?}");
+                StorageErrorKind::Internal.with_error(err)
+            })?;
+
+            tokio::fs::create_dir_all(parent_dir).await?;
+            let mut reader = payload.byte_stream().await?.into_async_read();
+            let named_temp_file = tempfile::NamedTempFile::new_in(parent_dir)?;
+            let (temp_std_file, temp_filepath) = named_temp_file.into_parts();
+            let mut temp_tokio_file = tokio::fs::File::from_std(temp_std_file);
+            tokio::io::copy(&mut reader, &mut temp_tokio_file).await?;
+            temp_tokio_file.flush().await?;
+            temp_tokio_file.sync_data().await?;
+            temp_filepath
+                .persist(&full_path)
+                .map_err(|err| StorageErrorKind::Io.with_error(err))?;
+            // We also need to sync the parent directory to ensure it
+            // the file move has been persisted on all file systems.
+            tokio::fs::File::open(parent_dir).await?.sync_data().await?;
+            Ok(())
+        };
+        track_operation(BACKEND, StorageOperation::Write, |_| num_bytes, put_fut).await
+    }
+
+    async fn copy(&self, src: &Path, dst: &Path) -> crate::StorageResult<()> {
+        let src_full_path = self.full_path(src)?;
+        let dst_full_path = self.full_path(dst)?;
+        let parent_dir = dst_full_path.parent().ok_or_else(|| {
+            let err = anyhow::anyhow!("no parent directory for {dst_full_path:?}");
             StorageErrorKind::Internal.with_error(err)
         })?;
-
         tokio::fs::create_dir_all(parent_dir).await?;
-        let mut reader = payload.byte_stream().await?.into_async_read();
-        let named_temp_file = tempfile::NamedTempFile::new_in(parent_dir)?;
-        let (temp_std_file, temp_filepath) = named_temp_file.into_parts();
-        let mut temp_tokio_file = tokio::fs::File::from_std(temp_std_file);
-        tokio::io::copy(&mut reader, &mut temp_tokio_file).await?;
-        temp_tokio_file.flush().await?;
-        temp_tokio_file.sync_data().await?;
+        // We copy to a temporary file first so that `dst` is never observed partially written.
+        let temp_filepath = tempfile::NamedTempFile::new_in(parent_dir)?.into_temp_path();
+        tokio::fs::copy(&src_full_path, &temp_filepath).await?;
         temp_filepath
-            .persist(&full_path)
+            .persist(&dst_full_path)
             .map_err(|err| StorageErrorKind::Io.with_error(err))?;
-        // We also need to sync the parent directory to ensure it
-        // the file move has been persisted on all file systems.
         tokio::fs::File::open(parent_dir).await?.sync_data().await?;
         Ok(())
     }
 
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
-        let full_path = self.full_path(path)?;
-        let mut file = tokio::fs::File::open(&full_path).await?;
-        tokio::io::copy(&mut file, output).await?;
+        let copy_to_fut = async {
+            let full_path = self.full_path(path)?;
+            let mut file = tokio::fs::File::open(&full_path).await?;
+            let num_bytes = tokio::io::copy(&mut file, output).await?;
+            Ok(num_bytes)
+        };
+        track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |num_bytes| *num_bytes,
+            copy_to_fut,
+        )
+        .await?;
         Ok(())
     }
 
     #[tracing::instrument(skip(self), level = "debug")]
     async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
-        let full_path = self.full_path(path)?;
-        tokio::task::spawn_blocking(move || {
-            use std::io::{Read, Seek};
-            // we run these io in a spawn_blocking so there is no scheduling delay between each
-            // step, as there would be if using tokio async File.
-            let mut file = std::fs::File::open(full_path)?;
-            file.seek(SeekFrom::Start(range.start as u64))?;
-            let mut content_bytes: Vec<u8> = Vec::with_capacity(range.len());
-            #[allow(clippy::uninit_vec)]
-            unsafe {
-                content_bytes.set_len(range.len());
-            }
-            file.read_exact(&mut content_bytes)?;
-            Ok(OwnedBytes::new(content_bytes))
-        })
+        let get_slice_fut = async {
+            let full_path = self.full_path(path)?;
+            tokio::task::spawn_blocking(move || {
+                use std::io::{Read, Seek};
+                // we run these io in a spawn_blocking so there is no scheduling delay between each
+                // step, as there would be if using tokio async File.
+                let mut file = std::fs::File::open(full_path)?;
+                file.seek(SeekFrom::Start(range.start as u64))?;
+                let mut content_bytes: Vec<u8> = Vec::with_capacity(range.len());
+                #[allow(clippy::uninit_vec)]
+                unsafe {
+                    content_bytes.set_len(range.len());
+                }
+                file.read_exact(&mut content_bytes)?;
+                Ok(OwnedBytes::new(content_bytes))
+            })
+            .await
+            .map_err(|_| {
+                StorageErrorKind::Internal.with_error(anyhow::anyhow!("reading file panicked"))
+            })?
+        };
+        track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |bytes: &OwnedBytes| bytes.len() as u64,
+            get_slice_fut,
+        )
         .await
-        .map_err(|_| {
-            StorageErrorKind::Internal.with_error(anyhow::anyhow!("reading file panicked"))
-        })?
     }
 
     #[tracing::instrument(skip(self), level = "debug")]
@@ -246,13 +375,16 @@ impl Storage for LocalFileStorage {
     }
 
     async fn delete(&self, path: &Path) -> StorageResult<()> {
-        self.delete_single_file(path).await?;
-        if let Some(parent) = path.parent() {
-            if let Err(error) = delete_all_dirs_if_empty(&self.root, parent).await {
-                warn!(error=?error, path=%path.display(), "failed to delete directory");
+        let delete_fut = async {
+            self.delete_single_file(path).await?;
+            if let Some(parent) = path.parent() {
+                if let Err(error) = delete_all_dirs_if_empty(&self.root, parent).await {
+                    warn!(error=?error, path=%path.display(), "failed to delete directory");
+                }
             }
-        }
-        Ok(())
+            Ok(())
+        };
+        track_operation(BACKEND, StorageOperation::Delete, |_| 0, delete_fut).await
     }
 
     /// Deletes the files identified by `paths` concurrently, with a maximum of `10` syscalls at a
@@ -310,21 +442,44 @@ impl Storage for LocalFileStorage {
     }
 
     async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
-        let full_path = self.full_path(path)?;
-        let content_bytes = tokio::fs::read(full_path).await.map_err(|err| {
-            StorageError::from(err).add_context(format!(
-                "failed to read file {}/{}",
-                self.uri(),
-                path.to_string_lossy()
-            ))
-        })?;
-        Ok(OwnedBytes::new(content_bytes))
+        let get_all_fut = async {
+            let full_path = self.full_path(path)?;
+            let content_bytes = tokio::fs::read(full_path).await.map_err(|err| {
+                StorageError::from(err).add_context(format!(
+                    "failed to read file {}/{}",
+                    self.uri(),
+                    path.to_string_lossy()
+                ))
+            })?;
+            Ok(OwnedBytes::new(content_bytes))
+        };
+        track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |bytes: &OwnedBytes| bytes.len() as u64,
+            get_all_fut,
+        )
+        .await
     }
 
     fn uri(&self) -> &Uri {
         &self.uri
     }
 
+    fn stats(&self) -> StorageStats {
+        StorageStats::for_backend(BACKEND)
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        if let Err(error) = ensure_valid_relative_path(prefix) {
+            return Box::new(stream::iter([Err(error.into())]));
+        }
+        let local_file_lister = LocalFileLister::new(self.root.clone(), prefix);
+        let object_metadata_stream =
+            stream::try_unfold(local_file_lister, LocalFileLister::next_object);
+        Box::new(object_metadata_stream.boxed())
+    }
+
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
         let full_path = self.full_path(path)?;
         match tokio::fs::metadata(full_path).await {
@@ -347,6 +502,29 @@ impl Storage for LocalFileStorage {
             }
         }
     }
+
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        let full_path = self.full_path(path)?;
+        match tokio::fs::metadata(full_path).await {
+            Ok(metadata) if metadata.is_file() => {
+                let object_metadata = ObjectMetadata {
+                    path: path.to_path_buf(),
+                    size_bytes: metadata.len(),
+                    last_modified: DateTime::<Utc>::from(metadata.modified()?),
+                    etag: None,
+                };
+                Ok(object_metadata)
+            }
+            Ok(_) => Err(StorageErrorKind::NotFound.with_error(anyhow::anyhow!(
+                "file `{}` is not a regular file",
+                path.display()
+            ))),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                Err(StorageErrorKind::NotFound.with_error(err))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 /// A File storage resolver
@@ -370,6 +548,8 @@ mod tests {
 
     use std::str::FromStr;
 
+    use futures::TryStreamExt;
+
     use super::*;
     use crate::test_suite::storage_test_suite;
 
@@ -382,6 +562,36 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_local_file_storage_stats() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let uri = Uri::from_str(&format!("{}", temp_dir.path().display())).unwrap();
+        let local_file_storage = LocalFileStorage::from_uri(&uri).unwrap();
+
+        // Storage metrics are global and other tests may run concurrently, so we only check
+        // that the stats increase.
+        let stats_before = local_file_storage.stats();
+        assert_eq!(stats_before.backend_type, "file");
+
+        let path = Path::new("foo");
+        local_file_storage
+            .put(path, Box::new(b"hello".to_vec()))
+            .await
+            .unwrap();
+        local_file_storage.get_slice(path, 1..3).await.unwrap();
+        local_file_storage
+            .get_all(Path::new("bar"))
+            .await
+            .unwrap_err();
+
+        let stats_after = local_file_storage.stats();
+        assert!(stats_after.bytes_written_total >= stats_before.bytes_written_total + 5);
+        assert!(stats_after.bytes_read_total >= stats_before.bytes_read_total + 2);
+        assert!(stats_after.operations_succeeded >= stats_before.operations_succeeded + 2);
+        assert!(stats_after.operations_failed >= stats_before.operations_failed + 1);
+        assert!(stats_after.connection_pool_active.is_none());
+    }
+
     #[tokio::test]
     async fn test_local_file_storage_forbids_double_dot() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -401,6 +611,79 @@ mod tests {
         assert_eq!(exist_error.kind(), StorageErrorKind::Unauthorized);
     }
 
+    #[tokio::test]
+    async fn test_local_file_storage_list_objects() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let uri = Uri::from_str(&format!("{}", temp_dir.path().display())).unwrap();
+        let local_file_storage = LocalFileStorage::from_uri(&uri).unwrap();
+
+        let object_metadata_stream = local_file_storage.list_objects(Path::new("")).await;
+        let objects_metadata: Vec<ObjectMetadata> =
+            object_metadata_stream.try_collect().await.unwrap();
+        assert!(objects_metadata.is_empty());
+
+        let before_put = Utc::now() - chrono::Duration::seconds(1);
+
+        for (path, payload) in [
+            ("foo/split-1.split", &b"split-1"[..]),
+            ("foo/split-2.split", &b"split-22"[..]),
+            ("foo-bar/split-3.split", &b"split-333"[..]),
+            ("bar/baz/split-4.split", &b"split-4444"[..]),
+        ] {
+            local_file_storage
+                .put(Path::new(path), Box::new(payload.to_vec()))
+                .await
+                .unwrap();
+        }
+        let mut objects_metadata: Vec<ObjectMetadata> = local_file_storage
+            .list_objects(Path::new(""))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        objects_metadata.sort_by(|left, right| left.path.cmp(&right.path));
+        assert_eq!(objects_metadata.len(), 4);
+
+        assert_eq!(objects_metadata[0].path, Path::new("bar/baz/split-4.split"));
+        assert_eq!(objects_metadata[0].size_bytes, 10);
+        assert_eq!(objects_metadata[1].path, Path::new("foo-bar/split-3.split"));
+        assert_eq!(objects_metadata[1].size_bytes, 9);
+        assert_eq!(objects_metadata[2].path, Path::new("foo/split-1.split"));
+        assert_eq!(objects_metadata[2].size_bytes, 7);
+        assert_eq!(objects_metadata[3].path, Path::new("foo/split-2.split"));
+        assert_eq!(objects_metadata[3].size_bytes, 8);
+
+        for object_metadata in &objects_metadata {
+            assert!(object_metadata.last_modified >= before_put);
+        }
+        let mut objects_metadata: Vec<ObjectMetadata> = local_file_storage
+            .list_objects(Path::new("foo/"))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        objects_metadata.sort_by(|left, right| left.path.cmp(&right.path));
+        assert_eq!(objects_metadata.len(), 2);
+        assert_eq!(objects_metadata[0].path, Path::new("foo/split-1.split"));
+        assert_eq!(objects_metadata[1].path, Path::new("foo/split-2.split"));
+
+        let objects_metadata: Vec<ObjectMetadata> = local_file_storage
+            .list_objects(Path::new("foo"))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(objects_metadata.len(), 3);
+
+        let list_error = local_file_storage
+            .list_objects(Path::new("../foo"))
+            .await
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        assert!(list_error.to_string().contains("forbidden"));
+    }
+
     #[tokio::test]
     async fn test_local_file_storage_factory() -> anyhow::Result<()> {
         let temp_dir = tempfile::tempdir()?;
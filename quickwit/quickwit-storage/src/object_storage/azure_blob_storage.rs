@@ -22,6 +22,7 @@ use std::num::NonZeroU32;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fmt, io};
 
 use async_trait::async_trait;
@@ -30,6 +31,7 @@ use azure_core::{Pageable, StatusCode};
 use azure_storage::prelude::*;
 use azure_storage::Error as AzureError;
 use azure_storage_blobs::blob::operations::GetBlobResponse;
+use azure_storage_blobs::blob::CopyStatus;
 use azure_storage_blobs::prelude::*;
 use bytes::Bytes;
 use futures::io::{Error as FutureError, ErrorKind as FutureErrorKind};
@@ -51,8 +53,9 @@ use tracing::{instrument, warn};
 use crate::debouncer::DebouncedStorage;
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, DeleteFailure, MultiPartPolicy, PutPayload, Storage, StorageError,
-    StorageErrorKind, StorageFactory, StorageResolverError, StorageResult, STORAGE_METRICS,
+    BulkDeleteError, DeleteFailure, MultiPartPolicy, ObjectMetadata, PutPayload, Storage,
+    StorageError, StorageErrorKind, StorageFactory, StorageResolverError, StorageResult,
+    STORAGE_METRICS,
 };
 
 /// Azure object storage resolver.
@@ -334,6 +337,45 @@ impl Storage for AzureBlobStorage {
         Ok(())
     }
 
+    /// Copies the blob server-side with a `Copy Blob` request. Copies within a storage account are
+    /// usually completed synchronously, otherwise we poll the destination blob until the copy
+    /// completes.
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        let src_url = self
+            .container_client
+            .blob_client(self.blob_name(src))
+            .url()
+            .map_err(AzureErrorWrapper::from)?;
+        let dst_blob_client = self.container_client.blob_client(self.blob_name(dst));
+        let mut copy_status = dst_blob_client
+            .copy(src_url)
+            .into_future()
+            .await
+            .map_err(AzureErrorWrapper::from)?
+            .copy_status;
+        while copy_status == CopyStatus::Pending {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            copy_status = dst_blob_client
+                .get_properties()
+                .into_future()
+                .await
+                .map_err(AzureErrorWrapper::from)?
+                .blob
+                .properties
+                .copy_status
+                .unwrap_or(CopyStatus::Success);
+        }
+        if copy_status != CopyStatus::Success {
+            let error = anyhow::anyhow!(
+                "failed to copy blob `{}` to `{}`: copy status is `{copy_status:?}`",
+                src.display(),
+                dst.display()
+            );
+            return Err(StorageErrorKind::Service.with_error(error));
+        }
+        Ok(())
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         let name = self.blob_name(path);
         let mut output_stream = self.container_client.blob_client(name).get().into_stream();
@@ -495,6 +537,36 @@ impl Storage for AzureBlobStorage {
         }
     }
 
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        let name = self.blob_name(path);
+        let properties = self
+            .container_client
+            .blob_client(name)
+            .get_properties()
+            .into_future()
+            .await
+            .map_err(|err| StorageError::from(AzureErrorWrapper::from(err)))?
+            .blob
+            .properties;
+        let last_modified = chrono::DateTime::from_timestamp(
+            properties.last_modified.unix_timestamp(),
+            properties.last_modified.nanosecond(),
+        )
+        .ok_or_else(|| {
+            StorageErrorKind::Internal.with_error(anyhow::anyhow!(
+                "blob `{}` has no valid last modified date",
+                path.display()
+            ))
+        })?;
+        let object_metadata = ObjectMetadata {
+            path: path.to_path_buf(),
+            size_bytes: properties.content_length,
+            last_modified,
+            etag: Some(properties.etag.to_string()),
+        };
+        Ok(object_metadata)
+    }
+
     fn uri(&self) -> &Uri {
         &self.uri
     }
@@ -600,6 +672,7 @@ impl From<AzureErrorWrapper> for StorageError {
         match err.inner.kind() {
             ErrorKind::HttpResponse { status, .. } => match status {
                 StatusCode::NotFound => StorageErrorKind::NotFound.with_error(err),
+                StatusCode::Forbidden => StorageErrorKind::PermissionDenied.with_error(err),
                 _ => StorageErrorKind::Service.with_error(err),
             },
             ErrorKind::Io => StorageErrorKind::Io.with_error(err),
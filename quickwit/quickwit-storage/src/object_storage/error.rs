@@ -20,11 +20,13 @@
 use aws_sdk_s3::error::{DisplayErrorContext, SdkError};
 use aws_sdk_s3::operation::abort_multipart_upload::AbortMultipartUploadError;
 use aws_sdk_s3::operation::complete_multipart_upload::CompleteMultipartUploadError;
+use aws_sdk_s3::operation::copy_object::CopyObjectError;
 use aws_sdk_s3::operation::create_multipart_upload::CreateMultipartUploadError;
 use aws_sdk_s3::operation::delete_object::DeleteObjectError;
 use aws_sdk_s3::operation::delete_objects::DeleteObjectsError;
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::operation::head_object::HeadObjectError;
+use aws_sdk_s3::operation::list_objects_v2::ListObjectsV2Error;
 use aws_sdk_s3::operation::put_object::PutObjectError;
 use aws_sdk_s3::operation::upload_part::UploadPartError;
 
@@ -46,13 +48,15 @@ where E: std::error::Error + ToStorageErrorKind + Send + Sync + 'static
                 }
             }
             SdkError::ResponseError(response_error) => {
-                match response_error.raw().status().as_u16() {
-                    404 /* NOT_FOUND */ => StorageErrorKind::NotFound,
-                    403 /* UNAUTHORIZED */ => StorageErrorKind::Unauthorized,
-                    _ => StorageErrorKind::Internal,
-                }
+                storage_error_kind_from_status_code(response_error.raw().status().as_u16())
+                    .unwrap_or(StorageErrorKind::Internal)
+            }
+            // S3 reports access denied and quota errors with generic error codes, so we rely on
+            // the status code of the response instead.
+            SdkError::ServiceError(service_error) => {
+                storage_error_kind_from_status_code(service_error.raw().status().as_u16())
+                    .unwrap_or_else(|| service_error.err().to_storage_error_kind())
             }
-            SdkError::ServiceError(service_error) => service_error.err().to_storage_error_kind(),
             SdkError::TimeoutError(_) => StorageErrorKind::Timeout,
             _ => StorageErrorKind::Internal,
         };
@@ -61,6 +65,18 @@ where E: std::error::Error + ToStorageErrorKind + Send + Sync + 'static
     }
 }
 
+/// Maps the status code of an S3 error response to a storage error kind. S3-compatible object
+/// stores such as MinIO or Ceph return `507 Insufficient Storage` when the quota of a bucket is
+/// exhausted.
+fn storage_error_kind_from_status_code(status_code: u16) -> Option<StorageErrorKind> {
+    match status_code {
+        403 /* FORBIDDEN */ => Some(StorageErrorKind::PermissionDenied),
+        404 /* NOT_FOUND */ => Some(StorageErrorKind::NotFound),
+        507 /* INSUFFICIENT_STORAGE */ => Some(StorageErrorKind::QuotaExceeded),
+        _ => None,
+    }
+}
+
 pub trait ToStorageErrorKind {
     fn to_storage_error_kind(&self) -> StorageErrorKind;
 }
@@ -120,6 +136,12 @@ impl ToStorageErrorKind for PutObjectError {
     }
 }
 
+impl ToStorageErrorKind for CopyObjectError {
+    fn to_storage_error_kind(&self) -> StorageErrorKind {
+        StorageErrorKind::Service
+    }
+}
+
 impl ToStorageErrorKind for HeadObjectError {
     fn to_storage_error_kind(&self) -> StorageErrorKind {
         match self {
@@ -128,3 +150,35 @@ impl ToStorageErrorKind for HeadObjectError {
         }
     }
 }
+
+impl ToStorageErrorKind for ListObjectsV2Error {
+    fn to_storage_error_kind(&self) -> StorageErrorKind {
+        match self {
+            ListObjectsV2Error::NoSuchBucket(_) => StorageErrorKind::NotFound,
+            _ => StorageErrorKind::Service,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_error_kind_from_status_code() {
+        assert_eq!(
+            storage_error_kind_from_status_code(403),
+            Some(StorageErrorKind::PermissionDenied)
+        );
+        assert_eq!(
+            storage_error_kind_from_status_code(404),
+            Some(StorageErrorKind::NotFound)
+        );
+        assert_eq!(
+            storage_error_kind_from_status_code(507),
+            Some(StorageErrorKind::QuotaExceeded)
+        );
+        assert_eq!(storage_error_kind_from_status_code(500), None);
+        assert_eq!(storage_error_kind_from_status_code(503), None);
+    }
+}
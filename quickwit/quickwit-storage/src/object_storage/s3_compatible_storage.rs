@@ -22,6 +22,7 @@ use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use std::{fmt, io};
 
 use anyhow::{anyhow, Context as AnyhhowContext};
@@ -31,37 +32,72 @@ use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
 use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
 use aws_sdk_s3::operation::delete_objects::DeleteObjectsOutput;
 use aws_sdk_s3::operation::get_object::{GetObjectError, GetObjectOutput};
+use aws_sdk_s3::operation::list_objects_v2::ListObjectsV2Output;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::builders::ObjectIdentifierBuilder;
-use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart, Delete, ObjectIdentifier};
+use aws_sdk_s3::types::{
+    CompletedMultipartUpload, CompletedPart, Delete, Object, ObjectIdentifier,
+};
 use aws_sdk_s3::Client as S3Client;
 use base64::prelude::{Engine, BASE64_STANDARD};
-use futures::{stream, StreamExt};
+use chrono::DateTime;
+use futures::{stream, StreamExt, TryStreamExt};
 use once_cell::sync::{Lazy, OnceCell};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use quickwit_aws::get_aws_config;
 use quickwit_aws::retry::{aws_retry, AwsRetryable};
 use quickwit_common::retry::{Retry, RetryParams};
 use quickwit_common::uri::Uri;
-use quickwit_common::{chunk_range, into_u64_range};
+use quickwit_common::{chunk_range, coalesce_ranges_with_max_len, into_u64_range};
 use quickwit_config::S3StorageConfig;
 use regex::Regex;
+use reqwest::Url;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, ReadBuf};
 use tokio::sync::Semaphore;
 use tracing::{info, instrument, warn};
 
 use crate::object_storage::MultiPartPolicy;
 use crate::storage::SendableAsync;
+use crate::storage_stats::{track_operation, StorageOperation};
 use crate::{
-    BulkDeleteError, DeleteFailure, OwnedBytes, Storage, StorageError, StorageErrorKind,
-    StorageResolverError, StorageResult, STORAGE_METRICS,
+    BulkDeleteError, DeleteFailure, ObjectMetadata, ObjectMetadataStream, OwnedBytes, Storage,
+    StorageError, StorageErrorKind, StorageResolverError, StorageResult, StorageStats,
+    STORAGE_METRICS,
 };
 
+/// Backend label of the S3 compatible object storages in the storage metrics.
+const BACKEND: &str = "s3";
+
+/// Maximum number of concurrent requests to the object store.
+static MAX_CONCURRENT_REQUESTS: Lazy<usize> = Lazy::new(|| {
+    quickwit_common::get_from_env_with_validation(
+        "QW_S3_MAX_CONCURRENCY",
+        10_000usize,
+        |max_concurrency| *max_concurrency > 0,
+        "max concurrency must be strictly positive",
+    )
+});
+
 /// Semaphore to limit the number of concurent requests to the object store. Some object stores
 /// (R2, SeaweedFs...) return errors when too many concurrent requests are emitted.
-static REQUEST_SEMAPHORE: Lazy<Semaphore> = Lazy::new(|| {
-    let num_permits: usize = quickwit_common::get_from_env("QW_S3_MAX_CONCURRENCY", 10_000usize);
-    Semaphore::new(num_permits)
-});
+static REQUEST_SEMAPHORE: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(*MAX_CONCURRENT_REQUESTS));
+
+/// Maximum gap between two ranges read with a single request by `get_many_slices`. Downloading a
+/// few extra bytes is cheaper than issuing another request, which costs tens of milliseconds.
+const MAX_COALESCING_GAP_NUM_BYTES: usize = 256 * 1024;
+
+/// Maximum length of a coalesced range read by `get_many_slices`.
+const MAX_COALESCED_RANGE_NUM_BYTES: usize = 8 * 1024 * 1024;
+
+/// Characters percent-encoded in the `x-amz-copy-source` header of `CopyObject` requests. S3
+/// expects the source key to be URL-encoded but keeps `/` as the delimiter.
+const COPY_SOURCE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
 
 /// Wrap the async read handle together with a permit to keep the permit alive
 /// until the handle is dropped
@@ -705,6 +741,77 @@ impl S3CompatibleObjectStorage {
     }
 }
 
+/// Walks through the pages of a `ListObjectsV2` request, following the continuation tokens
+/// returned by S3 until the listing is exhausted.
+struct ListObjectsPager {
+    s3_client: S3Client,
+    bucket: String,
+    key_prefix: String,
+    storage_prefix: PathBuf,
+    retry_params: RetryParams,
+    continuation_token: Option<String>,
+    is_exhausted: bool,
+}
+
+impl ListObjectsPager {
+    async fn next_page(mut self) -> anyhow::Result<Option<(Vec<ObjectMetadata>, Self)>> {
+        if self.is_exhausted {
+            return Ok(None);
+        }
+        let list_objects_output = self.list_objects_v2().await?;
+        let objects_metadata = list_objects_output
+            .contents()
+            .iter()
+            .map(|object| self.object_metadata(object))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.continuation_token = list_objects_output
+            .next_continuation_token()
+            .map(|continuation_token| continuation_token.to_string());
+        self.is_exhausted = !list_objects_output.is_truncated().unwrap_or(false)
+            || self.continuation_token.is_none();
+        Ok(Some((objects_metadata, self)))
+    }
+
+    async fn list_objects_v2(&self) -> StorageResult<ListObjectsV2Output> {
+        let _permit = REQUEST_SEMAPHORE.acquire().await;
+        let list_objects_output = aws_retry(&self.retry_params, || async {
+            self.s3_client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&self.key_prefix)
+                .set_continuation_token(self.continuation_token.clone())
+                .send()
+                .await
+        })
+        .await?;
+        Ok(list_objects_output)
+    }
+
+    fn object_metadata(&self, object: &Object) -> anyhow::Result<ObjectMetadata> {
+        let key = object
+            .key()
+            .context("S3 returned an object without a key")?;
+        // FIXME: This may not work on Windows.
+        let path = Path::new(key)
+            .strip_prefix(&self.storage_prefix)
+            .with_context(|| format!("object key `{key}` does not start with the storage prefix"))?
+            .to_path_buf();
+        let last_modified = object
+            .last_modified()
+            .and_then(|last_modified| {
+                DateTime::from_timestamp(last_modified.secs(), last_modified.subsec_nanos())
+            })
+            .with_context(|| format!("object `{key}` has no valid last modified date"))?;
+        let object_metadata = ObjectMetadata {
+            path,
+            size_bytes: object.size().unwrap_or(0) as u64,
+            last_modified,
+            etag: object.e_tag().map(|etag| etag.to_string()),
+        };
+        Ok(object_metadata)
+    }
+}
+
 async fn download_all(byte_stream: ByteStream, output: &mut Vec<u8>) -> io::Result<()> {
     output.clear();
     let mut body_stream_reader = BufReader::new(byte_stream.into_async_read());
@@ -741,27 +848,99 @@ impl Storage for S3CompatibleObjectStorage {
         let key = self.key(path);
         let total_len = payload.len();
         let part_num_bytes = self.multipart_policy.part_num_bytes(total_len);
-        if self.disable_multipart_upload || part_num_bytes >= total_len {
-            self.put_single_part(&key, payload, total_len).await?;
-        } else {
-            self.put_multipart(&key, payload, part_num_bytes, total_len)
-                .await?;
-        }
+        let put_fut = async {
+            if self.disable_multipart_upload || part_num_bytes >= total_len {
+                self.put_single_part(&key, payload, total_len).await?;
+            } else {
+                self.put_multipart(&key, payload, part_num_bytes, total_len)
+                    .await?;
+            }
+            Ok(())
+        };
+        track_operation(BACKEND, StorageOperation::Write, |_| total_len, put_fut).await
+    }
+
+    /// Copies the object server-side with a single `CopyObject` request, which does not support
+    /// objects larger than 5GiB.
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        let _permit = REQUEST_SEMAPHORE.acquire().await;
+        let bucket = self.bucket.clone();
+        let copy_source = utf8_percent_encode(
+            &format!("{}/{}", self.bucket, self.key(src)),
+            COPY_SOURCE_ENCODE_SET,
+        )
+        .to_string();
+        let dst_key = self.key(dst);
+        aws_retry(&self.retry_params, || async {
+            self.s3_client
+                .copy_object()
+                .bucket(&bucket)
+                .copy_source(&copy_source)
+                .key(&dst_key)
+                .send()
+                .await
+        })
+        .await
+        .map_err(|error| {
+            StorageError::from(error).add_context(format!(
+                "failed to copy object `{}` to `{}` in storage `{}`",
+                src.display(),
+                dst.display(),
+                self.uri
+            ))
+        })?;
         Ok(())
     }
 
+    /// Presigns a `GetObject` request. Signing happens locally and does not issue any request to
+    /// the object storage, so a URL is returned even if the object does not exist.
+    async fn presign_download_url(&self, path: &Path, expiry: Duration) -> StorageResult<Url> {
+        let presigning_config = PresigningConfig::expires_in(expiry).map_err(|error| {
+            StorageErrorKind::Internal
+                .with_error(anyhow!(error))
+                .add_context(format!("invalid presigned URL expiry `{expiry:?}`"))
+        })?;
+        let presigned_request = self
+            .s3_client
+            .get_object()
+            .bucket(self.bucket.clone())
+            .key(self.key(path))
+            .presigned(presigning_config)
+            .await
+            .map_err(|error| {
+                StorageError::from(error).add_context(format!(
+                    "failed to presign download URL for `{}` in storage `{}`",
+                    path.display(),
+                    self.uri
+                ))
+            })?;
+        let url = Url::parse(presigned_request.uri())
+            .map_err(|error| StorageErrorKind::Internal.with_error(anyhow!(error)))?;
+        Ok(url)
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         let _permit = REQUEST_SEMAPHORE.acquire().await;
-        let get_object_output = aws_retry(&self.retry_params, || {
-            self.create_get_object_request(path, None)
-        })
+        let copy_to_fut = async {
+            let get_object_output = aws_retry(&self.retry_params, || {
+                self.create_get_object_request(path, None)
+            })
+            .await?;
+            let mut body_read = BufReader::new(get_object_output.body.into_async_read());
+            let num_bytes_copied = tokio::io::copy_buf(&mut body_read, output).await?;
+            STORAGE_METRICS
+                .object_storage_download_num_bytes
+                .inc_by(num_bytes_copied);
+            output.flush().await?;
+            Ok(num_bytes_copied)
+        };
+        track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |num_bytes_copied| *num_bytes_copied,
+            copy_to_fut,
+        )
         .await?;
-        let mut body_read = BufReader::new(get_object_output.body.into_async_read());
-        let num_bytes_copied = tokio::io::copy_buf(&mut body_read, output).await?;
-        STORAGE_METRICS
-            .object_storage_download_num_bytes
-            .inc_by(num_bytes_copied);
-        output.flush().await?;
         Ok(())
     }
 
@@ -769,21 +948,24 @@ impl Storage for S3CompatibleObjectStorage {
         let _permit = REQUEST_SEMAPHORE.acquire().await;
         let bucket = self.bucket.clone();
         let key = self.key(path);
-        let delete_res = aws_retry(&self.retry_params, || async {
-            self.s3_client
-                .delete_object()
-                .bucket(&bucket)
-                .key(&key)
-                .send()
-                .await
-        })
-        .await;
+        let delete_fut = async {
+            let delete_res = aws_retry(&self.retry_params, || async {
+                self.s3_client
+                    .delete_object()
+                    .bucket(&bucket)
+                    .key(&key)
+                    .send()
+                    .await
+            })
+            .await;
 
-        match delete_res {
-            Ok(_) => Ok(()),
-            Err(error) if error.code() == Some("NoSuchKey") => Ok(()),
-            Err(error) => Err(error.into()),
-        }
+            match delete_res {
+                Ok(_) => Ok(()),
+                Err(error) if error.code() == Some("NoSuchKey") => Ok(()),
+                Err(error) => Err(error.into()),
+            }
+        };
+        track_operation(BACKEND, StorageOperation::Delete, |_| 0, delete_fut).await
     }
 
     async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
@@ -797,17 +979,74 @@ impl Storage for S3CompatibleObjectStorage {
     #[instrument(level = "debug", skip(self, range), fields(range.start = range.start, range.end = range.end))]
     async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
         let _permit = REQUEST_SEMAPHORE.acquire().await;
-        self.get_to_vec(path, Some(range.clone()))
-            .await
-            .map(OwnedBytes::new)
-            .map_err(|err| {
-                err.add_context(format!(
-                    "failed to fetch slice {:?} for object: {}/{}",
-                    range,
-                    self.uri,
-                    path.display(),
-                ))
+        let get_slice_fut = async {
+            self.get_to_vec(path, Some(range.clone()))
+                .await
+                .map(OwnedBytes::new)
+                .map_err(|err| {
+                    err.add_context(format!(
+                        "failed to fetch slice {:?} for object: {}/{}",
+                        range,
+                        self.uri,
+                        path.display(),
+                    ))
+                })
+        };
+        track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |bytes: &OwnedBytes| bytes.len() as u64,
+            get_slice_fut,
+        )
+        .await
+    }
+
+    #[instrument(level = "debug", skip(self, ranges), fields(num_ranges = ranges.len(), num_requests))]
+    async fn get_many_slices(
+        &self,
+        path: &Path,
+        ranges: &[Range<usize>],
+    ) -> StorageResult<Vec<OwnedBytes>> {
+        let coalesced_ranges = coalesce_ranges_with_max_len(
+            ranges,
+            MAX_COALESCING_GAP_NUM_BYTES,
+            MAX_COALESCED_RANGE_NUM_BYTES,
+        );
+        tracing::Span::current().record("num_requests", coalesced_ranges.len());
+
+        let get_coalesced_slice_futures = coalesced_ranges.iter().map(|coalesced_range| async {
+            let _permit = REQUEST_SEMAPHORE.acquire().await;
+            self.get_to_vec(path, Some(coalesced_range.clone()))
+                .await
+                .map(OwnedBytes::new)
+                .map_err(|err| {
+                    err.add_context(format!(
+                        "failed to fetch slice {:?} for object: {}/{}",
+                        coalesced_range,
+                        self.uri,
+                        path.display(),
+                    ))
+                })
+        });
+        let coalesced_slices = futures::future::try_join_all(get_coalesced_slice_futures).await?;
+
+        let slices = ranges
+            .iter()
+            .map(|range| {
+                if range.is_empty() {
+                    return OwnedBytes::empty();
+                }
+                // The coalesced ranges are disjoint and sorted, so the one containing `range` is
+                // the last one starting before it.
+                let coalesced_range_idx = coalesced_ranges
+                    .partition_point(|coalesced_range| coalesced_range.start <= range.start)
+                    - 1;
+                let coalesced_range_start = coalesced_ranges[coalesced_range_idx].start;
+                coalesced_slices[coalesced_range_idx]
+                    .slice(range.start - coalesced_range_start..range.end - coalesced_range_start)
             })
+            .collect();
+        Ok(slices)
     }
 
     #[instrument(level = "debug", skip(self, range), fields(range.start = range.start, range.end = range.end))]
@@ -830,17 +1069,25 @@ impl Storage for S3CompatibleObjectStorage {
     #[instrument(level = "debug", skip(self), fields(num_bytes_fetched))]
     async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
         let _permit = REQUEST_SEMAPHORE.acquire().await;
-        let bytes = self
-            .get_to_vec(path, None)
-            .await
-            .map(OwnedBytes::new)
-            .map_err(|err| {
-                err.add_context(format!(
-                    "failed to fetch object: {}/{}",
-                    self.uri,
-                    path.display()
-                ))
-            })?;
+        let get_all_fut = async {
+            self.get_to_vec(path, None)
+                .await
+                .map(OwnedBytes::new)
+                .map_err(|err| {
+                    err.add_context(format!(
+                        "failed to fetch object: {}/{}",
+                        self.uri,
+                        path.display()
+                    ))
+                })
+        };
+        let bytes = track_operation(
+            BACKEND,
+            StorageOperation::Read,
+            |bytes: &OwnedBytes| bytes.len() as u64,
+            get_all_fut,
+        )
+        .await?;
         tracing::Span::current().record("num_bytes_fetched", bytes.len());
         Ok(bytes)
     }
@@ -862,9 +1109,65 @@ impl Storage for S3CompatibleObjectStorage {
         Ok(head_object_output.content_length().unwrap_or(0) as u64)
     }
 
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        let _permit = REQUEST_SEMAPHORE.acquire().await;
+        let bucket = self.bucket.clone();
+        let key = self.key(path);
+        let head_object_output = aws_retry(&self.retry_params, || async {
+            self.s3_client
+                .head_object()
+                .bucket(&bucket)
+                .key(&key)
+                .send()
+                .await
+        })
+        .await?;
+
+        let last_modified = head_object_output
+            .last_modified()
+            .and_then(|last_modified| {
+                DateTime::from_timestamp(last_modified.secs(), last_modified.subsec_nanos())
+            })
+            .with_context(|| format!("object `{key}` has no valid last modified date"))
+            .map_err(|error| StorageErrorKind::Internal.with_error(error))?;
+        let object_metadata = ObjectMetadata {
+            path: path.to_path_buf(),
+            size_bytes: head_object_output.content_length().unwrap_or(0) as u64,
+            last_modified,
+            etag: head_object_output.e_tag().map(|etag| etag.to_string()),
+        };
+        Ok(object_metadata)
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        let list_objects_pager = ListObjectsPager {
+            s3_client: self.s3_client.clone(),
+            bucket: self.bucket.clone(),
+            key_prefix: self.key(prefix),
+            storage_prefix: self.prefix.clone(),
+            retry_params: self.retry_params,
+            continuation_token: None,
+            is_exhausted: false,
+        };
+        let object_metadata_stream =
+            stream::try_unfold(list_objects_pager, ListObjectsPager::next_page)
+                .map_ok(|objects_metadata| stream::iter(objects_metadata.into_iter().map(Ok)))
+                .try_flatten();
+        Box::new(object_metadata_stream.boxed())
+    }
+
     fn uri(&self) -> &Uri {
         &self.uri
     }
+
+    fn stats(&self) -> StorageStats {
+        let connection_pool_active =
+            MAX_CONCURRENT_REQUESTS.saturating_sub(REQUEST_SEMAPHORE.available_permits());
+        StorageStats {
+            connection_pool_active: Some(connection_pool_active),
+            ..StorageStats::for_backend(BACKEND)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1169,4 +1472,264 @@ mod tests {
         let delete_objects_error = bulk_delete_error.error.unwrap();
         assert!(delete_objects_error.to_string().contains("MalformedXML"));
     }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_get_many_slices() {
+        let payload: Vec<u8> = (0..64u8).collect();
+        let client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(206)
+                .body(SdkBody::from(payload[2..60].to_vec()))
+                .unwrap(),
+        )]);
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::v2024_03_28())
+            .region(Some(Region::new("Foo")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_client = S3Client::from_conf(config);
+        let uri = Uri::for_test("s3://bucket/indexes");
+        let bucket = "bucket".to_string();
+        let prefix = PathBuf::new();
+
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client,
+            uri,
+            bucket,
+            prefix,
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+        };
+        let ranges = [40..60, 2..10, 5..5, 20..30];
+        let slices = s3_storage
+            .get_many_slices(Path::new("split.split"), &ranges)
+            .await
+            .unwrap();
+        assert_eq!(slices.len(), 4);
+
+        for (range, slice) in ranges.iter().zip(slices.iter()) {
+            assert_eq!(slice.as_slice(), &payload[range.clone()]);
+        }
+        let requests = client.actual_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers().get("range").unwrap(), "bytes=2-59");
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_copy() {
+        let copy_object_result = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <CopyObjectResult>
+                <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+                <ETag>"9b2cf535f27731c974343645a3985328"</ETag>
+            </CopyObjectResult>"#;
+        let client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .body(SdkBody::from_body_0_4(Body::empty()))
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from(copy_object_result))
+                .unwrap(),
+        )]);
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::v2024_03_28())
+            .region(Some(Region::new("Foo")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_client = S3Client::from_conf(config);
+        let uri = Uri::for_test("s3://bucket/indexes");
+        let bucket = "bucket".to_string();
+        let prefix = PathBuf::from("indexes");
+
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client,
+            uri,
+            bucket,
+            prefix,
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+        };
+        s3_storage
+            .copy(Path::new("my index/src.split"), Path::new("dst.split"))
+            .await
+            .unwrap();
+
+        // The object is copied server-side: no download, no upload.
+        let requests = client.actual_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 1);
+        let request_uri = requests[0].uri().to_string();
+        assert!(request_uri.contains("indexes/dst.split"));
+        assert!(request_uri.ends_with("CopyObject"));
+        assert_eq!(
+            requests[0].headers().get("x-amz-copy-source").unwrap(),
+            "bucket/indexes/my%20index/src.split"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_list_objects() {
+        let first_page = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Name>bucket</Name>
+                <Prefix>indexes/</Prefix>
+                <KeyCount>2</KeyCount>
+                <MaxKeys>2</MaxKeys>
+                <IsTruncated>true</IsTruncated>
+                <NextContinuationToken>next-page</NextContinuationToken>
+                <Contents>
+                    <Key>indexes/foo/split-1.split</Key>
+                    <LastModified>2024-01-01T12:00:00.000Z</LastModified>
+                    <Size>1024</Size>
+                </Contents>
+                <Contents>
+                    <Key>indexes/foo/split-2.split</Key>
+                    <LastModified>2024-01-02T12:00:00.000Z</LastModified>
+                    <Size>2048</Size>
+                </Contents>
+            </ListBucketResult>"#;
+        let second_page = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+                <Name>bucket</Name>
+                <Prefix>indexes/</Prefix>
+                <KeyCount>1</KeyCount>
+                <MaxKeys>2</MaxKeys>
+                <IsTruncated>false</IsTruncated>
+                <Contents>
+                    <Key>indexes/bar/split-3.split</Key>
+                    <LastModified>2024-01-03T12:00:00.000Z</LastModified>
+                    <Size>4096</Size>
+                </Contents>
+            </ListBucketResult>"#;
+        let client = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                http::Request::builder()
+                    .body(SdkBody::from_body_0_4(Body::empty()))
+                    .unwrap(),
+                http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from(first_page))
+                    .unwrap(),
+            ),
+            ReplayEvent::new(
+                http::Request::builder()
+                    .body(SdkBody::from_body_0_4(Body::empty()))
+                    .unwrap(),
+                http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from(second_page))
+                    .unwrap(),
+            ),
+        ]);
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::v2024_03_28())
+            .region(Some(Region::new("Foo")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_client = S3Client::from_conf(config);
+        let uri = Uri::for_test("s3://bucket/indexes");
+        let bucket = "bucket".to_string();
+        let prefix = PathBuf::from("indexes");
+
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client,
+            uri,
+            bucket,
+            prefix,
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+        };
+        let objects_metadata: Vec<ObjectMetadata> = s3_storage
+            .list_objects(Path::new(""))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(objects_metadata.len(), 3);
+
+        assert_eq!(objects_metadata[0].path, Path::new("foo/split-1.split"));
+        assert_eq!(objects_metadata[0].size_bytes, 1024);
+        assert_eq!(
+            objects_metadata[0].last_modified,
+            DateTime::from_timestamp(1_704_110_400, 0).unwrap()
+        );
+        assert_eq!(objects_metadata[1].path, Path::new("foo/split-2.split"));
+        assert_eq!(objects_metadata[1].size_bytes, 2048);
+        assert_eq!(objects_metadata[2].path, Path::new("bar/split-3.split"));
+        assert_eq!(objects_metadata[2].size_bytes, 4096);
+
+        let requests = client.actual_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 2);
+
+        let first_request_uri = requests[0].uri().to_string();
+        assert!(first_request_uri.contains("list-type=2"));
+        assert!(first_request_uri.contains("prefix=indexes%2F"));
+        assert!(!first_request_uri.contains("continuation-token"));
+
+        let second_request_uri = requests[1].uri().to_string();
+        assert!(second_request_uri.contains("continuation-token=next-page"));
+    }
+
+    #[tokio::test]
+    async fn test_s3_compatible_storage_presign_download_url() {
+        let client = StaticReplayClient::new(Vec::new());
+        let credentials = Credentials::new("mock_key", "mock_secret", None, None, "mock_provider");
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::v2024_03_28())
+            .region(Some(Region::new("eu-west-1")))
+            .http_client(client.clone())
+            .credentials_provider(credentials)
+            .build();
+        let s3_client = S3Client::from_conf(config);
+        let uri = Uri::for_test("s3://bucket/indexes");
+        let bucket = "bucket".to_string();
+        let prefix = PathBuf::from("indexes");
+
+        let s3_storage = S3CompatibleObjectStorage {
+            s3_client,
+            uri,
+            bucket,
+            prefix,
+            multipart_policy: MultiPartPolicy::default(),
+            retry_params: RetryParams::for_test(),
+            disable_multi_object_delete: false,
+            disable_multipart_upload: false,
+        };
+        let url = s3_storage
+            .presign_download_url(Path::new("foo.split"), Duration::from_secs(900))
+            .await
+            .unwrap();
+        assert_eq!(url.scheme(), "https");
+        assert_eq!(url.host_str(), Some("bucket.s3.eu-west-1.amazonaws.com"));
+        assert_eq!(url.path(), "/indexes/foo.split");
+
+        let query_params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(query_params["X-Amz-Algorithm"], "AWS4-HMAC-SHA256");
+        assert_eq!(query_params["X-Amz-Expires"], "900");
+        assert!(query_params["X-Amz-Credential"].starts_with("mock_key/"));
+        assert!(query_params.contains_key("X-Amz-Signature"));
+
+        // Presigning is performed locally.
+        assert_eq!(client.actual_requests().count(), 0);
+
+        let error = s3_storage
+            .presign_download_url(Path::new("foo.split"), Duration::from_secs(8 * 24 * 3_600))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), StorageErrorKind::Internal);
+    }
 }
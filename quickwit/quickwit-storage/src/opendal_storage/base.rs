@@ -20,17 +20,19 @@
 use std::fmt;
 use std::ops::Range;
 use std::path::Path;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytesize::ByteSize;
 use opendal::Operator;
 use quickwit_common::uri::Uri;
+use reqwest::Url;
 use tokio::io::{AsyncRead, AsyncWriteExt};
 
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, OwnedBytes, PutPayload, Storage, StorageError, StorageErrorKind,
-    StorageResolverError, StorageResult,
+    BulkDeleteError, ObjectMetadata, OwnedBytes, PutPayload, Storage, StorageError,
+    StorageErrorKind, StorageResolverError, StorageResult,
 };
 
 /// OpenDAL based storage implementation.
@@ -91,6 +93,37 @@ impl Storage for OpendalStorage {
         Ok(())
     }
 
+    /// Copies the object server-side (GCS `rewrite`) when the underlying service supports it.
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        if !self.op.info().full_capability().copy {
+            let payload = self.get_all(src).await?;
+            return self.put(dst, Box::new(payload.to_vec())).await;
+        }
+        let src = src.as_os_str().to_string_lossy();
+        let dst = dst.as_os_str().to_string_lossy();
+        self.op.copy(&src, &dst).await?;
+
+        Ok(())
+    }
+
+    /// Presigns a read request. On Google Cloud Storage, this requires service account
+    /// credentials.
+    async fn presign_download_url(&self, path: &Path, expiry: Duration) -> StorageResult<Url> {
+        if !self.op.info().full_capability().presign_read {
+            return Err(StorageErrorKind::Unsupported.with_error(anyhow::anyhow!(
+                "failed to presign download URL for `{}`: storage `{}` does not support presigned \
+                 URLs",
+                path.display(),
+                self.uri
+            )));
+        }
+        let path = path.as_os_str().to_string_lossy();
+        let presigned_request = self.op.presign_read(&path, expiry).await?;
+        let url = Url::parse(&presigned_request.uri().to_string())
+            .map_err(|error| StorageErrorKind::Internal.with_error(anyhow::anyhow!(error)))?;
+        Ok(url)
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         let path = path.as_os_str().to_string_lossy();
         let mut storage_reader = self.op.reader(&path).await?;
@@ -194,6 +227,19 @@ impl Storage for OpendalStorage {
         Ok(meta.content_length())
     }
 
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        let path_str = path.as_os_str().to_string_lossy();
+        let meta = self.op.stat(&path_str).await?;
+        let object_metadata = ObjectMetadata {
+            path: path.to_path_buf(),
+            size_bytes: meta.content_length(),
+            // Not all the services supported by OpenDAL return the last modification date.
+            last_modified: meta.last_modified().unwrap_or(chrono::DateTime::UNIX_EPOCH),
+            etag: meta.etag().map(|etag| etag.to_string()),
+        };
+        Ok(object_metadata)
+    }
+
     fn uri(&self) -> &Uri {
         &self.uri
     }
@@ -203,7 +249,11 @@ impl From<opendal::Error> for StorageError {
     fn from(err: opendal::Error) -> Self {
         match err.kind() {
             opendal::ErrorKind::NotFound => StorageErrorKind::NotFound.with_error(err),
-            opendal::ErrorKind::PermissionDenied => StorageErrorKind::Unauthorized.with_error(err),
+            opendal::ErrorKind::PermissionDenied => {
+                StorageErrorKind::PermissionDenied.with_error(err)
+            }
+            // Google Cloud Storage responds with `429 Too Many Requests` when a quota is exceeded.
+            opendal::ErrorKind::RateLimited => StorageErrorKind::QuotaExceeded.with_error(err),
             opendal::ErrorKind::ConfigInvalid => StorageErrorKind::Service.with_error(err),
             _ => StorageErrorKind::Io.with_error(err),
         }
@@ -215,3 +265,28 @@ impl From<opendal::Error> for StorageResolverError {
         StorageResolverError::InvalidConfig(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_error_from_opendal_error() {
+        let opendal_error = opendal::Error::new(opendal::ErrorKind::NotFound, "not found");
+        let storage_error = StorageError::from(opendal_error);
+        assert_eq!(storage_error.kind(), StorageErrorKind::NotFound);
+
+        let opendal_error =
+            opendal::Error::new(opendal::ErrorKind::PermissionDenied, "permission denied");
+        let storage_error = StorageError::from(opendal_error);
+        assert_eq!(storage_error.kind(), StorageErrorKind::PermissionDenied);
+
+        let opendal_error = opendal::Error::new(opendal::ErrorKind::RateLimited, "quota exceeded");
+        let storage_error = StorageError::from(opendal_error);
+        assert_eq!(storage_error.kind(), StorageErrorKind::QuotaExceeded);
+
+        let opendal_error = opendal::Error::new(opendal::ErrorKind::Unexpected, "unexpected");
+        let storage_error = StorageError::from(opendal_error);
+        assert_eq!(storage_error.kind(), StorageErrorKind::Io);
+    }
+}
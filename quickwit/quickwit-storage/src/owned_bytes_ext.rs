@@ -0,0 +1,236 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::{Cursor, SeekFrom};
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tantivy::directory::OwnedBytes;
+use tokio::io::{AsyncRead, AsyncSeek, BufReader, ReadBuf};
+
+/// Zero-copy slicing and reading helpers for [`OwnedBytes`].
+///
+/// `OwnedBytes` already exposes a zero-copy `slice` method that panics on out-of-bounds ranges.
+/// `try_split_at` and `try_slice` are its non-panicking counterparts. None of the methods below
+/// copy the underlying data: the values they return share the buffer of `self`.
+pub trait OwnedBytesExt {
+    /// Splits the bytes into two non-overlapping parts, `[0, mid)` and `[mid, len)`.
+    ///
+    /// Returns `None` if `mid` is greater than the length of the bytes.
+    fn try_split_at(&self, mid: usize) -> Option<(OwnedBytes, OwnedBytes)>;
+
+    /// Returns the sub-range `range` of the bytes.
+    ///
+    /// Returns `None` if the range is out of bounds or if its start is greater than its end.
+    fn try_slice(&self, range: Range<usize>) -> Option<OwnedBytes>;
+
+    /// Returns a reader implementing [`AsyncRead`] and [`AsyncSeek`] over the bytes, without
+    /// copying them.
+    fn async_reader(&self) -> OwnedBytesReader;
+
+    /// Returns a buffered version of [`OwnedBytesExt::async_reader`].
+    fn async_buf_reader(&self) -> BufReader<OwnedBytesReader> {
+        BufReader::new(self.async_reader())
+    }
+}
+
+impl OwnedBytesExt for OwnedBytes {
+    fn try_split_at(&self, mid: usize) -> Option<(OwnedBytes, OwnedBytes)> {
+        if mid > self.len() {
+            return None;
+        }
+        record_slices_created(2);
+        Some(self.clone().split(mid))
+    }
+
+    fn try_slice(&self, range: Range<usize>) -> Option<OwnedBytes> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        record_slices_created(1);
+        Some(self.slice(range))
+    }
+
+    fn async_reader(&self) -> OwnedBytesReader {
+        OwnedBytesReader {
+            cursor: Cursor::new(self.clone()),
+        }
+    }
+}
+
+/// An [`AsyncRead`] and [`AsyncSeek`] adapter over [`OwnedBytes`].
+///
+/// The reader holds a reference to the buffer of the original `OwnedBytes`, so creating it does
+/// not copy any data.
+#[derive(Clone)]
+pub struct OwnedBytesReader {
+    cursor: Cursor<OwnedBytes>,
+}
+
+impl OwnedBytesReader {
+    /// Returns the current position of the reader.
+    pub fn position(&self) -> u64 {
+        self.cursor.position()
+    }
+}
+
+impl AsyncRead for OwnedBytesReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.cursor).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for OwnedBytesReader {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.cursor).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.cursor).poll_complete(cx)
+    }
+}
+
+/// Keeps track of the number of slices created in debug builds to help detect excessive slicing.
+#[inline]
+fn record_slices_created(_num_slices: u64) {
+    #[cfg(debug_assertions)]
+    crate::STORAGE_METRICS.slices_created.inc_by(_num_slices);
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt};
+
+    use super::*;
+
+    #[test]
+    fn test_owned_bytes_try_split_at() {
+        let bytes = OwnedBytes::new(b"abcdef".to_vec());
+        let start_ptr = bytes.as_slice().as_ptr();
+
+        let (left, right) = bytes.try_split_at(2).unwrap();
+        assert_eq!(left.as_slice(), b"ab");
+        assert_eq!(right.as_slice(), b"cdef");
+        assert_eq!(left.as_slice().as_ptr(), start_ptr);
+        assert_eq!(right.as_slice().as_ptr(), start_ptr.wrapping_add(2));
+
+        let (left, right) = bytes.try_split_at(0).unwrap();
+        assert!(left.is_empty());
+        assert_eq!(right.as_slice(), b"abcdef");
+
+        let (left, right) = bytes.try_split_at(6).unwrap();
+        assert_eq!(left.as_slice(), b"abcdef");
+        assert!(right.is_empty());
+
+        assert!(bytes.try_split_at(7).is_none());
+        assert_eq!(bytes.as_slice(), b"abcdef");
+    }
+
+    #[test]
+    fn test_owned_bytes_try_slice() {
+        let bytes = OwnedBytes::new(b"abcdef".to_vec());
+        let start_ptr = bytes.as_slice().as_ptr();
+
+        let slice = bytes.try_slice(1..4).unwrap();
+        assert_eq!(slice.as_slice(), b"bcd");
+        assert_eq!(slice.as_slice().as_ptr(), start_ptr.wrapping_add(1));
+
+        assert!(bytes.try_slice(6..6).unwrap().is_empty());
+        assert!(bytes.try_slice(4..7).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed_range = 4..2;
+        assert!(bytes.try_slice(reversed_range).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_owned_bytes_async_reader() {
+        let bytes = OwnedBytes::new(b"hello world".to_vec());
+        let mut reader = bytes.async_reader();
+
+        let mut buffer = [0u8; 5];
+        reader.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello");
+        assert_eq!(reader.position(), 5);
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b" world");
+
+        // Reading past the end returns 0 bytes.
+        assert_eq!(reader.read(&mut buffer).await.unwrap(), 0);
+
+        // Repeated reads from the start yield the same data.
+        for _ in 0..2 {
+            reader.seek(SeekFrom::Start(0)).await.unwrap();
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content).await.unwrap();
+            assert_eq!(content, b"hello world");
+        }
+        // The reader does not consume the original bytes.
+        assert_eq!(bytes.as_slice(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_owned_bytes_async_reader_seek() {
+        let bytes = OwnedBytes::new(b"hello world".to_vec());
+        let mut reader = bytes.async_reader();
+
+        assert_eq!(reader.seek(SeekFrom::Start(6)).await.unwrap(), 6);
+        let mut buffer = [0u8; 3];
+        reader.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"wor");
+
+        assert_eq!(reader.seek(SeekFrom::Current(-5)).await.unwrap(), 4);
+        reader.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"o w");
+
+        assert_eq!(reader.seek(SeekFrom::End(-2)).await.unwrap(), 9);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"ld");
+
+        reader.seek(SeekFrom::Current(-20)).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_owned_bytes_async_buf_reader() {
+        let bytes = OwnedBytes::new(b"foo\nbar\n".to_vec());
+        let mut lines = bytes.async_buf_reader().lines();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "foo");
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "bar");
+        assert!(lines.next_line().await.unwrap().is_none());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_owned_bytes_slices_created_metric() {
+        let bytes = OwnedBytes::new(b"abcdef".to_vec());
+        let num_slices_before = crate::STORAGE_METRICS.slices_created.get();
+        bytes.try_split_at(3).unwrap();
+        bytes.try_slice(0..3).unwrap();
+        bytes.try_slice(0..42);
+        // Other tests may slice concurrently.
+        assert!(crate::STORAGE_METRICS.slices_created.get() >= num_slices_before + 3);
+    }
+}
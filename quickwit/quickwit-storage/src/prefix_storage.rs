@@ -21,13 +21,18 @@ use std::fmt;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
 use quickwit_common::uri::Uri;
+use reqwest::Url;
 use tokio::io::AsyncRead;
 
 use crate::storage::SendableAsync;
-use crate::{BulkDeleteError, OwnedBytes, Storage};
+use crate::{
+    BulkDeleteError, ObjectMetadata, ObjectMetadataStream, OwnedBytes, Storage, StorageStats,
+};
 
 /// This storage acts as a proxy to another storage that simply modifies each API call
 /// by preceding each path with a given a prefix.
@@ -60,6 +65,12 @@ impl Storage for PrefixStorage {
         self.storage.put(&self.prefix.join(path), payload).await
     }
 
+    async fn copy(&self, src: &Path, dst: &Path) -> crate::StorageResult<()> {
+        self.storage
+            .copy(&self.prefix.join(src), &self.prefix.join(dst))
+            .await
+    }
+
     async fn copy_to(
         &self,
         path: &Path,
@@ -76,6 +87,16 @@ impl Storage for PrefixStorage {
         self.storage.get_slice(&self.prefix.join(path), range).await
     }
 
+    async fn get_many_slices(
+        &self,
+        path: &Path,
+        ranges: &[Range<usize>],
+    ) -> crate::StorageResult<Vec<OwnedBytes>> {
+        self.storage
+            .get_many_slices(&self.prefix.join(path), ranges)
+            .await
+    }
+
     async fn get_all(&self, path: &Path) -> crate::StorageResult<OwnedBytes> {
         self.storage.get_all(&self.prefix.join(path)).await
     }
@@ -112,13 +133,57 @@ impl Storage for PrefixStorage {
         self.storage.exists(&self.prefix.join(path)).await
     }
 
+    async fn presign_download_url(
+        &self,
+        path: &Path,
+        expiry: Duration,
+    ) -> crate::StorageResult<Url> {
+        self.storage
+            .presign_download_url(&self.prefix.join(path), expiry)
+            .await
+    }
+
     fn uri(&self) -> &Uri {
         &self.uri
     }
 
+    fn stats(&self) -> StorageStats {
+        self.storage.stats()
+    }
+
     async fn file_num_bytes(&self, path: &Path) -> crate::StorageResult<u64> {
         self.storage.file_num_bytes(&self.prefix.join(path)).await
     }
+
+    async fn head(&self, path: &Path) -> crate::StorageResult<ObjectMetadata> {
+        let object_metadata = self.storage.head(&self.prefix.join(path)).await?;
+        Ok(ObjectMetadata {
+            path: path.to_path_buf(),
+            ..object_metadata
+        })
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        let storage_prefix = self.prefix.clone();
+        let object_metadata_stream = self
+            .storage
+            .list_objects(&self.prefix.join(prefix))
+            .await
+            .try_filter_map(move |object_metadata| {
+                // The underlying storage may return objects of a sibling prefix sharing the same
+                // leading characters, e.g. `data-archive/` for the prefix `data`.
+                let object_metadata_opt = object_metadata
+                    .path
+                    .strip_prefix(&storage_prefix)
+                    .ok()
+                    .map(|path| ObjectMetadata {
+                        path: path.to_path_buf(),
+                        ..object_metadata
+                    });
+                futures::future::ready(Ok(object_metadata_opt))
+            });
+        Box::new(object_metadata_stream.boxed())
+    }
 }
 
 /// Creates a [`PrefixStorage`] using an underlying storage and a prefix.
@@ -191,7 +256,7 @@ mod tests {
     use std::collections::HashMap;
 
     use super::*;
-    use crate::DeleteFailure;
+    use crate::{DeleteFailure, RamStorage};
 
     #[test]
     fn test_strip_prefix_from_error() {
@@ -259,4 +324,31 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_prefix_storage_list_objects() {
+        let ram_storage = RamStorage::builder()
+            .put("data/foo/split-1.split", b"split-1")
+            .put("data/bar/split-2.split", b"split-22")
+            .put("data-archive/foo/split-3.split", b"split-333")
+            .build();
+        let prefix_storage = add_prefix_to_storage(
+            Arc::new(ram_storage),
+            PathBuf::from("data"),
+            Uri::for_test("ram:///data"),
+        );
+        let mut objects_metadata: Vec<ObjectMetadata> = prefix_storage
+            .list_objects(Path::new(""))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        objects_metadata.sort_by(|left, right| left.path.cmp(&right.path));
+        assert_eq!(objects_metadata.len(), 2);
+
+        assert_eq!(objects_metadata[0].path, Path::new("bar/split-2.split"));
+        assert_eq!(objects_metadata[0].size_bytes, 8);
+        assert_eq!(objects_metadata[1].path, Path::new("foo/split-1.split"));
+        assert_eq!(objects_metadata[1].size_bytes, 7);
+    }
 }
@@ -25,6 +25,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream;
 use quickwit_common::uri::{Protocol, Uri};
 use quickwit_config::StorageBackend;
 use tokio::io::{AsyncRead, AsyncWriteExt};
@@ -33,17 +35,32 @@ use tokio::sync::RwLock;
 use crate::prefix_storage::add_prefix_to_storage;
 use crate::storage::SendableAsync;
 use crate::{
-    BulkDeleteError, OwnedBytes, Storage, StorageErrorKind, StorageFactory, StorageResolverError,
-    StorageResult,
+    BulkDeleteError, ObjectMetadata, ObjectMetadataStream, OwnedBytes, Storage, StorageErrorKind,
+    StorageFactory, StorageResolverError, StorageResult,
 };
 
+#[derive(Clone)]
+struct RamFile {
+    payload: OwnedBytes,
+    last_modified: DateTime<Utc>,
+}
+
+impl RamFile {
+    fn new(payload: OwnedBytes) -> Self {
+        Self {
+            payload,
+            last_modified: Utc::now(),
+        }
+    }
+}
+
 /// In Ram implementation of quickwit's storage.
 ///
 /// This implementation is mostly useful in unit tests.
 #[derive(Clone)]
 pub struct RamStorage {
     uri: Uri,
-    files: Arc<RwLock<HashMap<PathBuf, OwnedBytes>>>,
+    files: Arc<RwLock<HashMap<PathBuf, RamFile>>>,
 }
 
 impl fmt::Debug for RamStorage {
@@ -71,11 +88,18 @@ impl RamStorage {
     }
 
     async fn put_data(&self, path: &Path, payload: OwnedBytes) {
-        self.files.write().await.insert(path.to_path_buf(), payload);
+        self.files
+            .write()
+            .await
+            .insert(path.to_path_buf(), RamFile::new(payload));
     }
 
     async fn get_data(&self, path: &Path) -> Option<OwnedBytes> {
-        self.files.read().await.get(path).cloned()
+        self.files
+            .read()
+            .await
+            .get(path)
+            .map(|ram_file| ram_file.payload.clone())
     }
 
     /// Returns the list of files that are present in the RamStorage.
@@ -100,6 +124,15 @@ impl Storage for RamStorage {
         Ok(())
     }
 
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        let payload_bytes = self.get_data(src).await.ok_or_else(|| {
+            StorageErrorKind::NotFound
+                .with_error(anyhow::anyhow!("failed to find src_path {:?}", src))
+        })?;
+        self.put_data(dst, payload_bytes).await;
+        Ok(())
+    }
+
     async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
         let payload_bytes = self.get_data(path).await.ok_or_else(|| {
             StorageErrorKind::NotFound
@@ -153,26 +186,65 @@ impl Storage for RamStorage {
     }
 
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
-        if let Some(file_bytes) = self.files.read().await.get(path) {
-            Ok(file_bytes.len() as u64)
+        if let Some(ram_file) = self.files.read().await.get(path) {
+            Ok(ram_file.payload.len() as u64)
         } else {
             let err = anyhow::anyhow!("missing file `{}`", path.display());
             Err(StorageErrorKind::NotFound.with_error(err))
         }
     }
+
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        if let Some(ram_file) = self.files.read().await.get(path) {
+            let object_metadata = ObjectMetadata {
+                path: path.to_path_buf(),
+                size_bytes: ram_file.payload.len() as u64,
+                last_modified: ram_file.last_modified,
+                etag: None,
+            };
+            Ok(object_metadata)
+        } else {
+            let err = anyhow::anyhow!("missing file `{}`", path.display());
+            Err(StorageErrorKind::NotFound.with_error(err))
+        }
+    }
+
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        // FIXME: This may not work on Windows.
+        let prefix = prefix.to_string_lossy();
+        let objects_metadata: Vec<anyhow::Result<ObjectMetadata>> = self
+            .files
+            .read()
+            .await
+            .iter()
+            .filter(|(path, _)| path.to_string_lossy().starts_with(prefix.as_ref()))
+            .map(|(path, ram_file)| {
+                let object_metadata = ObjectMetadata {
+                    path: path.clone(),
+                    size_bytes: ram_file.payload.len() as u64,
+                    last_modified: ram_file.last_modified,
+                    etag: None,
+                };
+                Ok(object_metadata)
+            })
+            .collect();
+        Box::new(stream::iter(objects_metadata))
+    }
 }
 
 /// Builder to create a prepopulated [`RamStorage`]. This is mostly useful for tests.
 #[derive(Default)]
 pub struct RamStorageBuilder {
-    files: HashMap<PathBuf, OwnedBytes>,
+    files: HashMap<PathBuf, RamFile>,
 }
 
 impl RamStorageBuilder {
     /// Adds a new file into the [`RamStorageBuilder`].
     pub fn put(mut self, path: &str, payload: &[u8]) -> Self {
-        self.files
-            .insert(PathBuf::from(path), OwnedBytes::new(payload.to_vec()));
+        self.files.insert(
+            PathBuf::from(path),
+            RamFile::new(OwnedBytes::new(payload.to_vec())),
+        );
         self
     }
 
@@ -222,6 +294,8 @@ impl StorageFactory for RamStorageFactory {
 #[cfg(test)]
 mod tests {
 
+    use futures::TryStreamExt;
+
     use super::*;
     use crate::test_suite::storage_test_suite;
 
@@ -266,4 +340,36 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_ram_storage_list_objects() {
+        let before_build = Utc::now();
+        let storage = RamStorage::builder()
+            .put("foo/split-1.split", b"split-1")
+            .put("foo/split-2.split", b"split-22")
+            .put("bar/split-3.split", b"split-333")
+            .build();
+        let mut objects_metadata: Vec<ObjectMetadata> = storage
+            .list_objects(Path::new("foo/"))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        objects_metadata.sort_by(|left, right| left.path.cmp(&right.path));
+        assert_eq!(objects_metadata.len(), 2);
+
+        assert_eq!(objects_metadata[0].path, Path::new("foo/split-1.split"));
+        assert_eq!(objects_metadata[0].size_bytes, 7);
+        assert!(objects_metadata[0].last_modified >= before_build);
+        assert_eq!(objects_metadata[1].path, Path::new("foo/split-2.split"));
+        assert_eq!(objects_metadata[1].size_bytes, 8);
+
+        let objects_metadata: Vec<ObjectMetadata> = storage
+            .list_objects(Path::new(""))
+            .await
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(objects_metadata.len(), 3);
+    }
 }
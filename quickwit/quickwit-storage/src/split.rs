@@ -105,12 +105,25 @@ impl PutPayload for SplitPayload {
     }
 }
 
+/// Payload streaming a local file to the storage.
 #[derive(Clone)]
-struct FilePayload {
+pub struct FilePayload {
     len: u64,
     path: PathBuf,
 }
 
+impl FilePayload {
+    /// Creates a payload for the local file at `path`. The file must not be modified until the
+    /// payload has been uploaded.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(Self {
+            len: metadata.len(),
+            path: path.to_path_buf(),
+        })
+    }
+}
+
 #[async_trait]
 impl PutPayload for FilePayload {
     fn len(&self) -> u64 {
@@ -172,7 +185,6 @@ impl SplitPayloadBuilder {
 
     /// Adds the file to the bundle file.
     pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
-        let file = std::fs::metadata(path)?;
         let file_name = path
             .file_name()
             .and_then(std::ffi::OsStr::to_str)
@@ -184,11 +196,7 @@ impl SplitPayloadBuilder {
                 )
             })?;
 
-        let file_payload = FilePayload {
-            path: path.to_owned(),
-            len: file.len(),
-        };
-
+        let file_payload = FilePayload::open(path)?;
         self.add_payload(file_name, Box::new(file_payload));
 
         Ok(())
@@ -22,35 +22,87 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
-use quickwit_common::split_file;
+use quickwit_common::split_file_path;
+use reqwest::Url;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use crate::split_cache::split_table::{CandidateSplit, DownloadOpportunity};
-use crate::{SplitCache, StorageResolver};
+use crate::{SplitCache, StorageErrorKind, StorageResolver};
+
+/// Presigned URLs only need to remain valid for the duration of a single split download.
+const PRESIGNED_URL_EXPIRY: Duration = Duration::from_secs(15 * 60);
 
 async fn download_split(
     root_path: &Path,
     candidate_split: &CandidateSplit,
     storage_resolver: StorageResolver,
+    direct_download: bool,
 ) -> anyhow::Result<u64> {
     let CandidateSplit {
         split_ulid,
         storage_uri,
         living_token: _,
     } = candidate_split;
-    let split_filename = split_file(*split_ulid);
-    let target_filepath = root_path.join(&split_filename);
+    let split_filepath = split_file_path(*split_ulid);
+    let target_filepath = root_path.join(&split_filepath);
     let storage = storage_resolver.resolve(storage_uri).await?;
+
+    if direct_download {
+        match storage
+            .presign_download_url(&split_filepath, PRESIGNED_URL_EXPIRY)
+            .await
+        {
+            Ok(url) => return download_split_from_url(url, &target_filepath).await,
+            Err(storage_error) if storage_error.kind() == StorageErrorKind::Unsupported => {}
+            Err(storage_error) => return Err(storage_error.into()),
+        }
+    }
     let num_bytes = storage
-        .copy_to_file(Path::new(&split_filename), &target_filepath)
+        .copy_to_file(&split_filepath, &target_filepath)
         .await?;
     Ok(num_bytes)
 }
 
+async fn download_split_from_url(url: Url, target_filepath: &Path) -> anyhow::Result<u64> {
+    // Files with the `.temp` extension are removed when the split cache starts, so an interrupted
+    // download does not leak.
+    let temp_filepath = target_filepath.with_extension("temp");
+
+    let download_res = async {
+        // Presigned URLs act as credentials, so we make sure they do not end up in the logs.
+        let mut response = reqwest::get(url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.without_url())?;
+        let mut temp_file = tokio::fs::File::create(&temp_filepath).await?;
+        let mut num_bytes = 0;
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| error.without_url())?
+        {
+            temp_file.write_all(&chunk).await?;
+            num_bytes += chunk.len() as u64;
+        }
+        temp_file.flush().await?;
+        tokio::fs::rename(&temp_filepath, target_filepath).await?;
+        anyhow::Ok(num_bytes)
+    }
+    .await;
+
+    if download_res.is_err() {
+        let _ = tokio::fs::remove_file(&temp_filepath).await;
+    }
+    download_res
+}
+
 async fn perform_eviction_and_download(
     download_opportunity: DownloadOpportunity,
     split_cache: Arc<SplitCache>,
     storage_resolver: StorageResolver,
+    direct_download: bool,
     _download_permit: OwnedSemaphorePermit,
 ) -> anyhow::Result<()> {
     let DownloadOpportunity {
@@ -64,8 +116,13 @@ async fn perform_eviction_and_download(
         split_cache_clone.evict(&splits_to_delete[..]);
     })
     .await;
-    let num_bytes =
-        download_split(&split_cache.root_path, &split_to_download, storage_resolver).await?;
+    let num_bytes = download_split(
+        &split_cache.root_path,
+        &split_to_download,
+        storage_resolver,
+        direct_download,
+    )
+    .await?;
     let mut shared_split_table_lock = split_cache.split_table.lock().unwrap();
     shared_split_table_lock.register_as_downloaded(split_ulid, num_bytes);
     Ok(())
@@ -75,6 +132,7 @@ pub(crate) fn spawn_download_task(
     split_cache: Arc<SplitCache>,
     storage_resolver: StorageResolver,
     num_concurrent_downloads: NonZeroU32,
+    direct_download: bool,
 ) {
     let semaphore = Arc::new(Semaphore::new(num_concurrent_downloads.get() as usize));
     tokio::task::spawn(async move {
@@ -91,6 +149,7 @@ pub(crate) fn spawn_download_task(
                     download_opportunity,
                     split_cache_clone,
                     storage_resolver.clone(),
+                    direct_download,
                     download_permit,
                 ));
             } else {
@@ -100,3 +159,39 @@ pub(crate) fn spawn_download_task(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use quickwit_common::uri::Uri;
+    use ulid::Ulid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_download_split_falls_back_without_presigned_url_support() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_resolver = StorageResolver::for_test();
+        let storage_uri = Uri::for_test("ram:///indexes/test-index");
+        let storage = storage_resolver.resolve(&storage_uri).await.unwrap();
+
+        let split_ulid = Ulid::new();
+        storage
+            .put(&split_file_path(split_ulid), Box::new(b"split".to_vec()))
+            .await
+            .unwrap();
+
+        let candidate_split = CandidateSplit {
+            storage_uri,
+            split_ulid,
+            living_token: Arc::new(()),
+        };
+        let num_bytes = download_split(temp_dir.path(), &candidate_split, storage_resolver, true)
+            .await
+            .unwrap();
+        assert_eq!(num_bytes, 5);
+
+        let split_content =
+            std::fs::read(temp_dir.path().join(split_file_path(split_ulid))).unwrap();
+        assert_eq!(split_content, b"split");
+    }
+}
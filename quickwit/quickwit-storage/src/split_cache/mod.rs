@@ -29,7 +29,7 @@ use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
-use quickwit_common::split_file;
+use quickwit_common::split_file_path;
 use quickwit_common::uri::Uri;
 use quickwit_config::SplitCacheLimits;
 use quickwit_proto::search::ReportSplit;
@@ -58,10 +58,14 @@ pub struct SplitCache {
 impl SplitCache {
     /// Creates a new SplitCache and spawns the task that will continuously search for
     /// download opportunities.
+    ///
+    /// If `direct_download` is set, splits are downloaded via presigned URLs when the storage
+    /// supports them.
     pub fn with_root_path(
         root_path: PathBuf,
         storage_resolver: crate::StorageResolver,
         limits: SplitCacheLimits,
+        direct_download: bool,
     ) -> io::Result<Arc<SplitCache>> {
         std::fs::create_dir_all(&root_path)?;
         let mut existing_splits: BTreeMap<Ulid, u64> = Default::default();
@@ -118,6 +122,7 @@ impl SplitCache {
             split_cache.clone(),
             storage_resolver,
             limits.num_concurrent_downloads,
+            direct_download,
         );
 
         Ok(split_cache)
@@ -182,7 +187,7 @@ impl SplitCache {
 #[instrument]
 fn delete_evicted_splits(root_path: &Path, splits_to_delete: &[Ulid]) {
     for &split_to_delete in splits_to_delete {
-        let split_file_path = root_path.join(split_file(split_to_delete));
+        let split_file_path = root_path.join(split_file_path(split_to_delete));
         if let Err(_io_err) = std::fs::remove_file(&split_file_path) {
             // This is an pretty critical error. The split size is not tracked anymore at this
             // point.
@@ -222,7 +227,7 @@ impl SplitCacheBackingStorage {
     }
 
     fn record_hit_metrics(&self, result_opt: Option<&OwnedBytes>) {
-        let split_metrics = &crate::STORAGE_METRICS.searcher_split_cache;
+        let split_metrics = &crate::CACHE_METRICS.searcher_split_cache;
         if let Some(result) = result_opt {
             split_metrics.hits_num_items.inc();
             split_metrics.hits_num_bytes.inc_by(result.len() as u64);
@@ -21,20 +21,43 @@ use std::fmt;
 use std::io::{self, ErrorKind};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
 use quickwit_common::uri::Uri;
+use reqwest::Url;
 use tempfile::TempPath;
 use tokio::fs::File;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tracing::error;
 
-use crate::{BulkDeleteError, OwnedBytes, PutPayload, StorageErrorKind, StorageResult};
+use crate::{
+    BulkDeleteError, OwnedBytes, PutPayload, StorageErrorKind, StorageResult, StorageStats,
+};
 
 /// This trait is only used to make it build trait object with `AsyncWrite + Send + Unpin`.
 pub trait SendableAsync: AsyncWrite + Send + Unpin {}
 impl<W: AsyncWrite + Send + Unpin> SendableAsync for W {}
 
+/// Metadata of an object stored in a [`Storage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMetadata {
+    /// Path of the object, relative to the root of the storage.
+    pub path: PathBuf,
+    /// Size of the object in bytes.
+    pub size_bytes: u64,
+    /// Last time the object was modified.
+    pub last_modified: DateTime<Utc>,
+    /// Entity tag of the object, if the storage provides one.
+    pub etag: Option<String>,
+}
+
+/// Stream of object metadata returned by [`Storage::list_objects`].
+pub type ObjectMetadataStream =
+    Box<dyn Stream<Item = anyhow::Result<ObjectMetadata>> + Send + Unpin>;
+
 /// Storage meant to receive and serve quickwit's split.
 ///
 /// Object storage are the primary target implementation of this trait,
@@ -55,6 +78,16 @@ pub trait Storage: fmt::Debug + Send + Sync + 'static {
     /// Saves a file into the storage.
     async fn put(&self, path: &Path, payload: Box<dyn PutPayload>) -> StorageResult<()>;
 
+    /// Copies the file at `src` to `dst` within the same storage, overwriting `dst` if it already
+    /// exists.
+    ///
+    /// The default implementation downloads the file in memory and uploads it back. Object
+    /// storages override this method to perform the copy server-side.
+    async fn copy(&self, src: &Path, dst: &Path) -> StorageResult<()> {
+        let payload = self.get_all(src).await?;
+        self.put(dst, Box::new(payload.to_vec())).await
+    }
+
     /// Copies the file associated to `Path` into an `AsyncWrite`.
     /// This function is required to call `.flush()` before it successfully returns.
     ///
@@ -103,6 +136,21 @@ pub trait Storage: fmt::Debug + Send + Sync + 'static {
     /// Downloads a slice of a file from the storage, and returns an in memory buffer
     async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes>;
 
+    /// Downloads several slices of a file from the storage, and returns them in the order of the
+    /// requested ranges.
+    ///
+    /// Object storages override this method to coalesce nearby ranges and issue fewer requests.
+    async fn get_many_slices(
+        &self,
+        path: &Path,
+        ranges: &[Range<usize>],
+    ) -> StorageResult<Vec<OwnedBytes>> {
+        let get_slice_futures = ranges
+            .iter()
+            .map(|range| self.get_slice(path, range.clone()));
+        futures::future::try_join_all(get_slice_futures).await
+    }
+
     /// Open a stream handle on the file from the storage
     async fn get_slice_stream(
         &self,
@@ -138,8 +186,59 @@ pub trait Storage: fmt::Debug + Send + Sync + 'static {
     /// Returns a file size.
     async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64>;
 
+    /// Returns the metadata of a file without downloading its content, or a
+    /// [`StorageErrorKind::NotFound`] error if the file does not exist.
+    ///
+    /// The default implementation relies on [`Storage::file_num_bytes`]. It does not know the last
+    /// modification date of the file and returns the Unix epoch instead.
+    async fn head(&self, path: &Path) -> StorageResult<ObjectMetadata> {
+        let size_bytes = self.file_num_bytes(path).await?;
+        let object_metadata = ObjectMetadata {
+            path: path.to_path_buf(),
+            size_bytes,
+            last_modified: DateTime::UNIX_EPOCH,
+            etag: None,
+        };
+        Ok(object_metadata)
+    }
+
+    /// Lists the objects whose path starts with `prefix`. Use an empty path to list all the
+    /// objects of the storage.
+    ///
+    /// Listing is paginated under the hood when the underlying storage requires it, so this
+    /// method can be used on very large storages. The order of the objects is not specified.
+    async fn list_objects(&self, prefix: &Path) -> ObjectMetadataStream {
+        let error = anyhow::anyhow!(
+            "failed to list objects with prefix `{}`: storage `{}` does not support listing",
+            prefix.display(),
+            self.uri()
+        );
+        Box::new(futures::stream::iter([Err(error)]))
+    }
+
+    /// Returns a URL from which the file at `path` can be downloaded directly, without
+    /// credentials, until `expiry` elapses.
+    ///
+    /// The default implementation returns an [`StorageErrorKind::Unsupported`] error. Object
+    /// storages able to sign requests override this method.
+    async fn presign_download_url(&self, path: &Path, expiry: Duration) -> StorageResult<Url> {
+        let _ = expiry;
+        Err(StorageErrorKind::Unsupported.with_error(anyhow::anyhow!(
+            "failed to presign download URL for `{}`: storage `{}` does not support presigned URLs",
+            path.display(),
+            self.uri()
+        )))
+    }
+
     /// Returns an URI identifying the storage
     fn uri(&self) -> &Uri;
+
+    /// Returns statistics about the operations performed against the storage backend.
+    ///
+    /// Backends that do not track their operations return empty statistics.
+    fn stats(&self) -> StorageStats {
+        StorageStats::untracked(self.uri().protocol().as_str())
+    }
 }
 
 async fn default_copy_to_file<S: Storage + ?Sized>(
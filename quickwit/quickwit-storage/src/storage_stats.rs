@@ -0,0 +1,210 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+use std::future::Future;
+use std::time::Instant;
+
+use quickwit_common::metrics::histogram_quantile;
+use serde::{Deserialize, Serialize};
+
+use crate::{StorageResult, STORAGE_METRICS};
+
+/// Kind of operation performed against a storage, used to label the storage metrics.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum StorageOperation {
+    Read,
+    Write,
+    Delete,
+}
+
+impl StorageOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Runs `storage_operation_fut` and records its duration and outcome in the storage metrics of
+/// `backend`. On success, the number of bytes returned by `num_bytes_fn` is added to the read or
+/// written bytes counters, depending on the operation.
+pub(crate) async fn track_operation<T, Fut>(
+    backend: &str,
+    operation: StorageOperation,
+    num_bytes_fn: impl FnOnce(&T) -> u64,
+    storage_operation_fut: Fut,
+) -> StorageResult<T>
+where
+    Fut: Future<Output = StorageResult<T>>,
+{
+    let start = Instant::now();
+    let storage_operation_res = storage_operation_fut.await;
+    let elapsed = start.elapsed();
+
+    STORAGE_METRICS
+        .storage_operation_duration_seconds
+        .with_label_values([backend, operation.as_str()])
+        .observe(elapsed.as_secs_f64());
+
+    let status = if storage_operation_res.is_ok() {
+        "success"
+    } else {
+        "error"
+    };
+    STORAGE_METRICS
+        .storage_operations_total
+        .with_label_values([backend, operation.as_str(), status])
+        .inc();
+
+    if let Ok(output) = &storage_operation_res {
+        let num_bytes = num_bytes_fn(output);
+
+        match operation {
+            StorageOperation::Read => STORAGE_METRICS
+                .storage_read_num_bytes
+                .with_label_values([backend])
+                .inc_by(num_bytes),
+            StorageOperation::Write => STORAGE_METRICS
+                .storage_write_num_bytes
+                .with_label_values([backend])
+                .inc_by(num_bytes),
+            StorageOperation::Delete => {}
+        }
+    }
+    storage_operation_res
+}
+
+/// Statistics about the operations performed against a storage backend since the process
+/// started.
+///
+/// Statistics are aggregated per backend type: two storages of the same type (for instance, two
+/// S3 buckets) report the same statistics.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageStats {
+    /// Type of the storage backend, for instance `s3` or `file`.
+    pub backend_type: String,
+    pub bytes_read_total: u64,
+    pub bytes_written_total: u64,
+    pub operations_succeeded: u64,
+    pub operations_failed: u64,
+    /// Estimated 99th percentile of the read operations latency, in milliseconds.
+    pub p99_read_latency_ms: f64,
+    /// Estimated 99th percentile of the write operations latency, in milliseconds.
+    pub p99_write_latency_ms: f64,
+    /// Number of connections to the backend currently in use, for the backends that maintain a
+    /// connection pool.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_pool_active: Option<usize>,
+}
+
+impl StorageStats {
+    /// Returns empty statistics for a backend that does not track its operations.
+    pub fn untracked(backend_type: impl Into<String>) -> Self {
+        Self {
+            backend_type: backend_type.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Builds the statistics of `backend` from the storage metrics.
+    pub(crate) fn for_backend(backend: &str) -> Self {
+        let operations = [
+            StorageOperation::Read,
+            StorageOperation::Write,
+            StorageOperation::Delete,
+        ];
+        let count_operations = |status: &str| -> u64 {
+            operations
+                .iter()
+                .map(|operation| {
+                    STORAGE_METRICS
+                        .storage_operations_total
+                        .with_label_values([backend, operation.as_str(), status])
+                        .get()
+                })
+                .sum()
+        };
+        let p99_latency_ms = |operation: StorageOperation| -> f64 {
+            let histogram = STORAGE_METRICS
+                .storage_operation_duration_seconds
+                .with_label_values([backend, operation.as_str()]);
+            histogram_quantile(&histogram, 0.99) * 1_000.0
+        };
+        Self {
+            backend_type: backend.to_string(),
+            bytes_read_total: STORAGE_METRICS
+                .storage_read_num_bytes
+                .with_label_values([backend])
+                .get(),
+            bytes_written_total: STORAGE_METRICS
+                .storage_write_num_bytes
+                .with_label_values([backend])
+                .get(),
+            operations_succeeded: count_operations("success"),
+            operations_failed: count_operations("error"),
+            p99_read_latency_ms: p99_latency_ms(StorageOperation::Read),
+            p99_write_latency_ms: p99_latency_ms(StorageOperation::Write),
+            connection_pool_active: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StorageErrorKind, StorageResult};
+
+    #[tokio::test]
+    async fn test_track_operation() {
+        let backend = "test-track-operation";
+        let stats = StorageStats::for_backend(backend);
+        assert_eq!(stats, StorageStats::untracked(backend));
+
+        track_operation(
+            backend,
+            StorageOperation::Read,
+            |bytes: &Vec<u8>| bytes.len() as u64,
+            async { Ok(vec![0u8; 10]) },
+        )
+        .await
+        .unwrap();
+
+        track_operation(backend, StorageOperation::Write, |_| 5, async { Ok(()) })
+            .await
+            .unwrap();
+
+        let storage_operation_res: StorageResult<()> =
+            track_operation(backend, StorageOperation::Write, |_| 5, async {
+                Err(StorageErrorKind::Io.with_error(anyhow::anyhow!("disk full")))
+            })
+            .await;
+        storage_operation_res.unwrap_err();
+
+        let stats = StorageStats::for_backend(backend);
+        assert_eq!(stats.backend_type, backend);
+        assert_eq!(stats.bytes_read_total, 10);
+        assert_eq!(stats.bytes_written_total, 5);
+        assert_eq!(stats.operations_succeeded, 2);
+        assert_eq!(stats.operations_failed, 1);
+        assert!(stats.p99_read_latency_ms > 0.0);
+        assert!(stats.connection_pool_active.is_none());
+    }
+}
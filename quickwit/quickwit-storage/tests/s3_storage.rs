@@ -76,6 +76,11 @@ pub mod s3_storage_test_suite {
             .context("test single-part upload failed")
             .unwrap();
 
+        quickwit_storage::storage_test_presign_download_url(&mut object_storage)
+            .await
+            .context("test presigned download URL failed")
+            .unwrap();
+
         object_storage.set_policy(MultiPartPolicy {
             target_part_num_bytes: 5 * 1_024 * 1_024, //< the minimum on S3 is 5MB.
             max_num_parts: 10_000,
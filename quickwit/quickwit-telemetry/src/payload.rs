@@ -125,6 +125,8 @@ pub enum QuickwitFeature {
     Otlp,
     PostgresqMetastore,
     AwsLambda,
+    SqliteMetastore,
+    ConsulMetastore,
 }
 
 fn hashed_host_username() -> String {